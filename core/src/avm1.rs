@@ -19,7 +19,7 @@ pub mod function;
 pub mod property_decl;
 
 pub mod activation;
-mod callable_value;
+pub mod callable_value;
 pub mod debug;
 pub mod error;
 mod fscommand;
@@ -615,6 +615,16 @@ pub fn start_drag<'gc>(
     *activation.context.drag_object = Some(drag_object);
 }
 
+/// Stops dragging the current drag object, if any.
+/// Runs via the `stopDrag` method or `EndDrag` AVM1 action.
+///
+/// The dragged object's `_droptarget` is resolved one last time, so that it
+/// names the object that it was dropped onto.
+pub fn stop_drag<'gc>(activation: &mut Activation<'_, 'gc, '_>) {
+    crate::player::Player::update_drop_target(&mut activation.context);
+    *activation.context.drag_object = None;
+}
+
 pub struct DisplayObjectIter<'gc> {
     clip: Option<DisplayObject<'gc>>,
 }
@@ -5,7 +5,8 @@ use crate::avm1::object::{Object, TObject};
 use crate::avm1::property::Attribute;
 use crate::avm1::scope::Scope;
 use crate::avm1::{
-    fscommand, globals, scope, skip_actions, start_drag, ArrayObject, ScriptObject, Value,
+    fscommand, globals, scope, skip_actions, start_drag, stop_drag, ArrayObject, ScriptObject,
+    Value,
 };
 use crate::backend::navigator::{NavigationMethod, RequestOptions};
 use crate::context::UpdateContext;
@@ -907,6 +908,14 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         let name = name.as_ref();
         let swf_version = self.swf_version();
         let scope = Scope::new_closure_scope(self.scope_cell(), self.context.gc_context);
+        let name_scope = if name.is_empty() {
+            None
+        } else {
+            Some(Scope::new_function_name_scope(
+                scope,
+                self.context.gc_context,
+            ))
+        };
         let constant_pool = self.constant_pool();
         let func = Avm1Function::from_df1(
             self.context.gc_context,
@@ -914,7 +923,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
             actions,
             name,
             params,
-            scope,
+            name_scope.unwrap_or(scope),
             constant_pool,
             self.target_clip_or_root()?,
         );
@@ -931,6 +940,9 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
             prototype,
         );
         if let Some(name) = name {
+            if let Some(name_scope) = name_scope {
+                Self::define_function_name(self.context.gc_context, name_scope, name, func_obj);
+            }
             self.define_local(name, func_obj.into())?;
         } else {
             self.context.avm1.push(func_obj.into());
@@ -949,13 +961,21 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
             .to_unbounded_subslice(action_func.actions)
             .unwrap();
         let scope = Scope::new_closure_scope(self.scope_cell(), self.context.gc_context);
+        let name_scope = if action_func.name.is_empty() {
+            None
+        } else {
+            Some(Scope::new_function_name_scope(
+                scope,
+                self.context.gc_context,
+            ))
+        };
         let constant_pool = self.constant_pool();
         let func = Avm1Function::from_df2(
             self.context.gc_context,
             swf_version,
             func_data,
             action_func,
-            scope,
+            name_scope.unwrap_or(scope),
             constant_pool,
             self.base_clip(),
         );
@@ -970,17 +990,34 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
             Some(self.context.avm1.prototypes.function),
             prototype,
         );
-        if action_func.name.is_empty() {
-            self.context.avm1.push(func_obj.into());
-        } else {
+        if let Some(name_scope) = name_scope {
             let name = action_func.name.to_str_lossy(self.encoding());
             let name = AvmString::new_utf8(self.context.gc_context, name);
+            Self::define_function_name(self.context.gc_context, name_scope, name, func_obj);
             self.define_local(name, func_obj.into())?;
+        } else {
+            self.context.avm1.push(func_obj.into());
         }
 
         Ok(FrameControl::Continue)
     }
 
+    /// Define a named function on its function name scope, so that it can
+    /// always refer to itself by name.
+    fn define_function_name(
+        gc_context: MutationContext<'gc, '_>,
+        name_scope: GcCell<'gc, Scope<'gc>>,
+        name: AvmString<'gc>,
+        function: Object<'gc>,
+    ) {
+        name_scope.read().locals().define_value(
+            gc_context,
+            name,
+            function.into(),
+            Attribute::DONT_ENUM | Attribute::DONT_DELETE | Attribute::READ_ONLY,
+        );
+    }
+
     fn action_define_local(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
         // If the property does not exist on the local object's prototype chain, it is created on the local object.
         // Otherwise, the property is set (including calling virtual setters).
@@ -1046,7 +1083,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
     }
 
     fn action_end_drag(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
-        *self.context.drag_object = None;
+        stop_drag(self);
         Ok(FrameControl::Continue)
     }
 
@@ -1222,6 +1259,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
                             self.context.player.clone().unwrap(),
                             level,
                             fetch,
+                            None,
                             url,
                             None,
                             None,
@@ -1321,6 +1359,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
                         self.context.player.clone().unwrap(),
                         clip_target,
                         fetch,
+                        None,
                         url.to_string(),
                         None,
                         None,
@@ -1344,6 +1383,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
                         self.context.player.clone().unwrap(),
                         level,
                         fetch,
+                        None,
                         url.to_string(),
                         None,
                         None,
@@ -1951,14 +1991,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
 
         self.set_target_clip(new_target_clip);
 
-        let scope = self.scope_cell();
-        let clip_obj = self.target_clip_or_root()?.object().coerce_to_object(self);
-
-        self.set_scope(Scope::new_target_scope(
-            scope,
-            clip_obj,
-            self.context.gc_context,
-        ));
+        self.retarget_scope()?;
         Ok(FrameControl::Continue)
     }
 
@@ -1996,14 +2029,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
             }
         };
 
-        let scope = self.scope_cell();
-        let clip_obj = self.target_clip_or_root()?.object().coerce_to_object(self);
-
-        self.set_scope(Scope::new_target_scope(
-            scope,
-            clip_obj,
-            self.context.gc_context,
-        ));
+        self.retarget_scope()?;
         Ok(FrameControl::Continue)
     }
 
@@ -2019,18 +2045,20 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         let target = self.context.avm1.pop();
         let start_clip = self.target_clip_or_root()?;
         let display_object = self.resolve_target_display_object(start_clip, target, true)?;
+        // The arguments are always popped, even if the target is invalid.
+        let lock_center = self.context.avm1.pop();
+        let constrain = self.context.avm1.pop().as_bool(self.swf_version());
+        let args = if constrain {
+            let y2 = self.context.avm1.pop();
+            let x2 = self.context.avm1.pop();
+            let y1 = self.context.avm1.pop();
+            let x1 = self.context.avm1.pop();
+            vec![lock_center, x1, y1, x2, y2]
+        } else {
+            vec![lock_center]
+        };
         if let Some(display_object) = display_object {
-            let lock_center = self.context.avm1.pop();
-            let constrain = self.context.avm1.pop().as_bool(self.swf_version());
-            if constrain {
-                let y2 = self.context.avm1.pop();
-                let x2 = self.context.avm1.pop();
-                let y1 = self.context.avm1.pop();
-                let x1 = self.context.avm1.pop();
-                start_drag(display_object, self, &[lock_center, x1, y1, x2, y2]);
-            } else {
-                start_drag(display_object, self, &[lock_center]);
-            };
+            start_drag(display_object, self, &args);
         } else {
             avm_warn!(self, "StartDrag: Invalid target");
         }
@@ -2288,7 +2316,17 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
                 let with_scope =
                     Scope::new_with_scope(self.scope_cell(), object, self.context.gc_context);
                 let mut new_activation = self.with_new_scope("[With]", with_scope);
-                if let ReturnType::Explicit(value) = new_activation.run_actions(code)? {
+                let result = new_activation.run_actions(code)?;
+                let target_clip = new_activation.target_clip();
+
+                // The with block is part of this frame's code, so a `tellTarget`
+                // inside of it still applies once the block ends.
+                if !DisplayObject::option_ptr_eq(target_clip, self.target_clip()) {
+                    self.set_target_clip(target_clip);
+                    self.retarget_scope()?;
+                }
+
+                if let ReturnType::Explicit(value) = result {
                     Ok(FrameControl::Return(ReturnType::Explicit(value)))
                 } else {
                     Ok(FrameControl::Continue)
@@ -2923,6 +2961,20 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         self.target_clip
     }
 
+    /// Replaces the timeline scopes in the scope chain with the current target
+    /// clip, after the target was changed by `SetTarget`.
+    fn retarget_scope(&mut self) -> Result<(), Error<'gc>> {
+        let scope = self.scope_cell();
+        let clip_obj = self.target_clip_or_root()?.object().coerce_to_object(self);
+
+        self.set_scope(Scope::new_target_scope(
+            scope,
+            clip_obj,
+            self.context.gc_context,
+        ));
+        Ok(())
+    }
+
     /// Changes the target clip.
     pub fn set_target_clip(&mut self, value: Option<DisplayObject<'gc>>) {
         // The target should revert to `None` if the clip is removed.
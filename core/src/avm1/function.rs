@@ -197,6 +197,24 @@ pub enum Executable<'gc> {
     /// ActionScript data defined by a previous `DefineFunction` or
     /// `DefineFunction2` action.
     Action(Gc<'gc, Avm1Function<'gc>>),
+
+    /// Another function, always called with a captured `this`.
+    ///
+    /// These are created internally, such as for `asfunction:` links, and by
+    /// delegates that forward events to a particular object.
+    Bound(Gc<'gc, BoundFunction<'gc>>),
+}
+
+/// A function that is always called with the same `this`, no matter how it is
+/// called.
+#[derive(Debug, Collect)]
+#[collect(no_drop)]
+pub struct BoundFunction<'gc> {
+    /// The function that is called.
+    function: Object<'gc>,
+
+    /// The `this` that the function is called with.
+    this: Object<'gc>,
 }
 
 unsafe impl<'gc> Collect for Executable<'gc> {
@@ -204,6 +222,7 @@ unsafe impl<'gc> Collect for Executable<'gc> {
         match self {
             Self::Native(_) => {}
             Self::Action(af) => af.trace(cc),
+            Self::Bound(bf) => bf.trace(cc),
         }
     }
 }
@@ -216,6 +235,7 @@ impl fmt::Debug for Executable<'_> {
                 .field(&format!("{:p}", nf))
                 .finish(),
             Executable::Action(af) => f.debug_tuple("Executable::Action").field(&af).finish(),
+            Executable::Bound(bf) => f.debug_tuple("Executable::Bound").field(&bf).finish(),
         }
     }
 }
@@ -246,6 +266,13 @@ impl<'gc> Executable<'gc> {
     ) -> Result<Value<'gc>, Error<'gc>> {
         match self {
             Executable::Native(nf) => nf(activation, this, args),
+            Executable::Bound(bf) => {
+                let name = match name {
+                    ExecutionName::Static(name) => name.into(),
+                    ExecutionName::Dynamic(name) => name,
+                };
+                bf.function.call(name, activation, bf.this, args)
+            }
             Executable::Action(af) => {
                 let child_scope = GcCell::allocate(
                     activation.context.gc_context,
@@ -522,6 +549,18 @@ impl<'gc> FunctionObject<'gc> {
             prototype,
         )
     }
+
+    /// Construct a function that calls `function` with `this`, ignoring the
+    /// `this` that it is called with.
+    pub fn bound(
+        gc_context: MutationContext<'gc, '_>,
+        function: Object<'gc>,
+        this: Object<'gc>,
+        fn_proto: Option<Object<'gc>>,
+    ) -> Object<'gc> {
+        let bound = Gc::allocate(gc_context, BoundFunction { function, this });
+        Self::bare_function(gc_context, Some(Executable::Bound(bound)), None, fn_proto).into()
+    }
 }
 
 impl<'gc> TObject<'gc> for FunctionObject<'gc> {
@@ -12,6 +12,7 @@ use std::str;
 
 mod array;
 pub(crate) mod as_broadcaster;
+mod as_native;
 mod bevel_filter;
 mod bitmap_data;
 mod bitmap_filter;
@@ -36,20 +37,26 @@ pub mod gradient_bevel_filter;
 pub mod gradient_glow_filter;
 mod key;
 mod load_vars;
+mod local_connection;
 mod math;
 mod matrix;
+mod microphone;
 pub(crate) mod mouse;
 pub(crate) mod movie_clip;
 mod movie_clip_loader;
+pub(crate) mod netconnection;
+mod netstream;
 pub(crate) mod number;
 mod object;
 mod point;
+mod print_job;
 mod rectangle;
 mod selection;
 pub(crate) mod shared_object;
 mod sound;
 mod stage;
 pub(crate) mod string;
+mod style_sheet;
 pub(crate) mod system;
 pub(crate) mod system_capabilities;
 pub(crate) mod system_ime;
@@ -59,6 +66,7 @@ mod text_format;
 mod transform;
 mod video;
 mod xml;
+mod xml_socket;
 
 const GLOBAL_DECLS: &[Declaration] = declare_properties! {
     "isFinite" => method(is_finite; DONT_ENUM);
@@ -67,6 +75,8 @@ const GLOBAL_DECLS: &[Declaration] = declare_properties! {
     "parseFloat" => method(parse_float; DONT_ENUM);
     "random" => method(random; DONT_ENUM);
     "ASSetPropFlags" => method(object::as_set_prop_flags; DONT_ENUM);
+    "ASnative" => method(as_native::as_native; DONT_ENUM);
+    "ASconstructor" => method(as_native::as_constructor; DONT_ENUM);
     "clearInterval" => method(clear_interval; DONT_ENUM);
     "setInterval" => method(set_interval; DONT_ENUM);
     "clearTimeout" => method(clear_timeout; DONT_ENUM);
@@ -562,6 +572,7 @@ pub struct SystemPrototypes<'gc> {
     pub bitmap_data_constructor: Object<'gc>,
     pub video: Object<'gc>,
     pub video_constructor: Object<'gc>,
+    pub microphone: Object<'gc>,
 }
 
 /// Initialize default global scope and builtins for an AVM1 instance.
@@ -585,6 +596,7 @@ pub fn create_globals<'gc>(
 
     let text_field_proto = text_field::create_proto(gc_context, object_proto, function_proto);
     let text_format_proto = text_format::create_proto(gc_context, object_proto, function_proto);
+    let style_sheet_proto = style_sheet::create_proto(gc_context, object_proto, function_proto);
 
     let array_proto = array::create_proto(gc_context, object_proto, function_proto);
 
@@ -600,6 +612,8 @@ pub fn create_globals<'gc>(
     let number_proto = number::create_proto(gc_context, object_proto, function_proto);
     let boolean_proto = boolean::create_proto(gc_context, object_proto, function_proto);
     let load_vars_proto = load_vars::create_proto(gc_context, object_proto, function_proto);
+    let local_connection_proto =
+        local_connection::create_proto(gc_context, object_proto, function_proto);
     let matrix_proto = matrix::create_proto(gc_context, object_proto, function_proto);
     let point_proto = point::create_proto(gc_context, object_proto, function_proto);
     let rectangle_proto = rectangle::create_proto(gc_context, object_proto, function_proto);
@@ -630,6 +644,12 @@ pub fn create_globals<'gc>(
     let date_proto = date::create_proto(gc_context, object_proto, function_proto);
 
     let video_proto = video::create_proto(gc_context, object_proto, function_proto);
+    let microphone_proto = microphone::create_proto(gc_context, object_proto, function_proto);
+    let xml_socket_proto = xml_socket::create_proto(gc_context, object_proto, function_proto);
+    let net_connection_proto =
+        netconnection::create_proto(gc_context, object_proto, function_proto);
+    let net_stream_proto = netstream::create_proto(gc_context, object_proto, function_proto);
+    let print_job_proto = print_job::create_proto(gc_context, object_proto, function_proto);
 
     //TODO: These need to be constructors and should also set `.prototype` on each one
     let object = object::create_object_object(gc_context, object_proto, function_proto);
@@ -673,6 +693,41 @@ pub fn create_globals<'gc>(
         Some(function_proto),
         load_vars_proto,
     );
+    let xml_socket = FunctionObject::constructor(
+        gc_context,
+        Executable::Native(xml_socket::constructor),
+        constructor_to_fn!(xml_socket::constructor),
+        Some(function_proto),
+        xml_socket_proto,
+    );
+    let net_connection = FunctionObject::constructor(
+        gc_context,
+        Executable::Native(netconnection::constructor),
+        constructor_to_fn!(netconnection::constructor),
+        Some(function_proto),
+        net_connection_proto,
+    );
+    let net_stream = FunctionObject::constructor(
+        gc_context,
+        Executable::Native(netstream::constructor),
+        constructor_to_fn!(netstream::constructor),
+        Some(function_proto),
+        net_stream_proto,
+    );
+    let print_job = FunctionObject::constructor(
+        gc_context,
+        Executable::Native(print_job::constructor),
+        constructor_to_fn!(print_job::constructor),
+        Some(function_proto),
+        print_job_proto,
+    );
+    let local_connection = FunctionObject::constructor(
+        gc_context,
+        Executable::Native(local_connection::constructor),
+        constructor_to_fn!(local_connection::constructor),
+        Some(function_proto),
+        local_connection_proto,
+    );
     let movie_clip = FunctionObject::constructor(
         gc_context,
         Executable::Native(movie_clip::constructor),
@@ -688,12 +743,20 @@ pub fn create_globals<'gc>(
         Some(function_proto),
         sound_proto,
     );
-    let text_field = FunctionObject::constructor(
+    let text_field =
+        text_field::create_text_field_object(gc_context, text_field_proto, function_proto);
+    let style_sheet = FunctionObject::constructor(
         gc_context,
-        Executable::Native(text_field::constructor),
-        constructor_to_fn!(text_field::constructor),
+        Executable::Native(style_sheet::constructor),
+        constructor_to_fn!(style_sheet::constructor),
         Some(function_proto),
-        text_field_proto,
+        style_sheet_proto,
+    );
+    text_field.define_value(
+        gc_context,
+        "StyleSheet",
+        style_sheet.into(),
+        Attribute::DONT_ENUM,
     );
     let text_format = FunctionObject::constructor(
         gc_context,
@@ -990,6 +1053,12 @@ pub fn create_globals<'gc>(
         load_vars.into(),
         Attribute::DONT_ENUM,
     );
+    globals.define_value(
+        gc_context,
+        "LocalConnection",
+        local_connection.into(),
+        Attribute::DONT_ENUM,
+    );
     globals.define_value(
         gc_context,
         "MovieClip",
@@ -1002,7 +1071,31 @@ pub fn create_globals<'gc>(
         movie_clip_loader.into(),
         Attribute::DONT_ENUM,
     );
+    globals.define_value(
+        gc_context,
+        "NetConnection",
+        net_connection.into(),
+        Attribute::DONT_ENUM,
+    );
+    globals.define_value(
+        gc_context,
+        "NetStream",
+        net_stream.into(),
+        Attribute::DONT_ENUM,
+    );
+    globals.define_value(
+        gc_context,
+        "PrintJob",
+        print_job.into(),
+        Attribute::DONT_ENUM,
+    );
     globals.define_value(gc_context, "Sound", sound.into(), Attribute::DONT_ENUM);
+    globals.define_value(
+        gc_context,
+        "Microphone",
+        microphone::create_microphone_object(gc_context, microphone_proto, function_proto).into(),
+        Attribute::DONT_ENUM,
+    );
     globals.define_value(
         gc_context,
         "TextField",
@@ -1017,6 +1110,12 @@ pub fn create_globals<'gc>(
     );
     globals.define_value(gc_context, "XMLNode", xmlnode.into(), Attribute::DONT_ENUM);
     globals.define_value(gc_context, "XML", xml.into(), Attribute::DONT_ENUM);
+    globals.define_value(
+        gc_context,
+        "XMLSocket",
+        xml_socket.into(),
+        Attribute::DONT_ENUM,
+    );
     globals.define_value(gc_context, "String", string.into(), Attribute::DONT_ENUM);
     globals.define_value(gc_context, "Number", number.into(), Attribute::DONT_ENUM);
     globals.define_value(gc_context, "Boolean", boolean.into(), Attribute::DONT_ENUM);
@@ -1200,6 +1299,7 @@ pub fn create_globals<'gc>(
             bitmap_data_constructor: bitmap_data,
             video: video_proto,
             video_constructor: video,
+            microphone: microphone_proto,
         },
         globals.into(),
         broadcaster_functions,
@@ -411,7 +411,7 @@ pub fn to_string<'gc>(
     join(activation, this, &[])
 }
 
-fn sort<'gc>(
+pub fn sort<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
     args: &[Value<'gc>],
@@ -455,7 +455,7 @@ fn sort<'gc>(
     sort_with_function(activation, this, compare_fn, flags)
 }
 
-fn sort_on<'gc>(
+pub fn sort_on<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
     args: &[Value<'gc>],
@@ -0,0 +1,195 @@
+//! `ASnative` and `ASconstructor` functions
+//!
+//! Flash identifies each of its built-in functions by a pair of numbers, and
+//! these undocumented functions let SWFs fetch a built-in by its numbers.
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::function::{Executable, FunctionObject, NativeFunction};
+use crate::avm1::globals::{self, array, boolean, math, number, object, string};
+use crate::avm1::{Object, ScriptObject, Value};
+
+/// The built-in function with the given numbers.
+///
+/// This is a fixed table rather than a lookup of the globals, so that natives
+/// are found even after a SWF has replaced or deleted the global they are
+/// normally reached through.
+fn native(category: i32, index: i32) -> Option<NativeFunction> {
+    let function: NativeFunction = match (category, index) {
+        (1, 0) => object::as_set_prop_flags,
+        (9, 0) => globals::update_after_event,
+
+        (100, 0) => globals::escape,
+        (100, 1) => globals::unescape,
+        (100, 2) => globals::parse_int,
+        (100, 3) => globals::parse_float,
+        (100, 4) => trace,
+
+        (101, 0) => object::watch,
+        (101, 1) => object::unwatch,
+        (101, 2) => object::add_property,
+        (101, 3) => object::value_of,
+        (101, 4) => object::to_string,
+        (101, 5) => object::has_own_property,
+        (101, 6) => object::is_prototype_of,
+        (101, 7) => object::is_property_enumerable,
+        (101, 8) => object::register_class,
+        (101, 9) => object::constructor,
+
+        (106, 0) => number::value_of,
+        (106, 1) => number::to_string,
+        (106, 2) => number::number,
+
+        (107, 0) => boolean::value_of,
+        (107, 1) => boolean::to_string,
+        (107, 2) => boolean::constructor,
+
+        (200, 0) => math::abs,
+        (200, 1) => math::min,
+        (200, 2) => math::max,
+        (200, 3) => math::sin,
+        (200, 4) => math::cos,
+        (200, 5) => math::atan2,
+        (200, 6) => math::tan,
+        (200, 7) => math::exp,
+        (200, 8) => math::log,
+        (200, 9) => math::sqrt,
+        (200, 10) => math::round,
+        (200, 11) => math::random,
+        (200, 12) => math::floor,
+        (200, 13) => math::ceil,
+        (200, 14) => math::atan,
+        (200, 15) => math::asin,
+        (200, 16) => math::acos,
+        (200, 17) => math::pow,
+        (200, 18) => globals::is_nan,
+        (200, 19) => globals::is_finite,
+
+        (250, 0) => globals::set_interval,
+        (250, 1) => globals::clear_interval,
+
+        (251, 0) => string::string,
+        (251, 1) => string::to_string_value_of,
+        (251, 2) => string::to_string_value_of,
+        (251, 3) => string::to_upper_case,
+        (251, 4) => string::to_lower_case,
+        (251, 5) => string::char_at,
+        (251, 6) => string::char_code_at,
+        (251, 7) => string::concat,
+        (251, 8) => string::index_of,
+        (251, 9) => string::last_index_of,
+        (251, 10) => string::slice,
+        (251, 11) => string::substring,
+        (251, 12) => string::split,
+        (251, 13) => string::substr,
+        (251, 14) => string::from_char_code,
+
+        (252, 0) => array::constructor,
+        (252, 1) => array::push,
+        (252, 2) => array::pop,
+        (252, 3) => array::concat,
+        (252, 4) => array::shift,
+        (252, 5) => array::unshift,
+        (252, 6) => array::slice,
+        (252, 7) => array::join,
+        (252, 8) => array::splice,
+        (252, 9) => array::to_string,
+        (252, 10) => array::sort,
+        (252, 11) => array::reverse,
+        (252, 12) => array::sort_on,
+
+        _ => return None,
+    };
+    Some(function)
+}
+
+/// Look up the built-in function with the numbers given in `args`, and create
+/// a new function object that runs it.
+fn native_function<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+    prototype: Option<Object<'gc>>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let category = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_i32(activation)?;
+    let index = args
+        .get(1)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_i32(activation)?;
+
+    let executable = match native(category, index) {
+        Some(function) => Executable::Native(function),
+        None => {
+            avm_warn!(
+                activation,
+                "ASnative({}, {}) is not implemented",
+                category,
+                index
+            );
+            return Ok(Value::Undefined);
+        }
+    };
+
+    let fn_proto = activation.context.avm1.prototypes().function;
+    let function = match prototype {
+        Some(prototype) => FunctionObject::function(
+            activation.context.gc_context,
+            executable,
+            Some(fn_proto),
+            prototype,
+        ),
+        None => FunctionObject::bare_function(
+            activation.context.gc_context,
+            Some(executable),
+            None,
+            Some(fn_proto),
+        )
+        .into(),
+    };
+    Ok(function.into())
+}
+
+/// Implements `ASnative`
+pub fn as_native<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    native_function(activation, args, None)
+}
+
+/// Implements `ASconstructor`
+///
+/// This is like `ASnative`, but the function is given a new `prototype`, so
+/// that it can be used as the constructor of a class.
+pub fn as_constructor<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let prototype = ScriptObject::object(
+        activation.context.gc_context,
+        Some(activation.context.avm1.prototypes().object),
+    )
+    .into();
+    native_function(activation, args, Some(prototype))
+}
+
+/// The native version of the `Trace` action.
+fn trace<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = args.get(0).cloned().unwrap_or(Value::Undefined);
+    // Like the action, this always prints "undefined" for `undefined`.
+    let out = if value == Value::Undefined {
+        "undefined".into()
+    } else {
+        value.coerce_to_string(activation)?
+    };
+    activation.context.log.avm_trace(&out.to_utf8_lossy());
+    Ok(Value::Undefined)
+}
@@ -3,14 +3,23 @@
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::function::{Executable, FunctionObject};
+use crate::avm1::globals::bitmap_filter::object_to_filter;
+use crate::avm1::globals::color_transform::object_to_color_transform;
+use crate::avm1::globals::display_object;
+use crate::avm1::globals::matrix::object_to_matrix;
 use crate::avm1::object::bitmap_data::BitmapDataObject;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::{Object, TObject, Value};
-use crate::bitmap::bitmap_data::{BitmapData, ChannelOptions, Color};
+use crate::bitmap::bitmap_data::{
+    render_display_object, BitmapData, ChannelOptions, Color, ThresholdOperation,
+};
 use crate::bitmap::is_size_valid;
 use crate::character::Character;
-use crate::display_object::TDisplayObject;
+use crate::color_transform::ColorTransform;
+use crate::matrix::Matrix;
+use crate::transform::Transform;
 use gc_arena::{GcCell, MutationContext};
+use swf::BlendMode;
 
 const PROTO_DECLS: &[Declaration] = declare_properties! {
     "height" => property(height);
@@ -484,22 +493,139 @@ pub fn noise<'gc>(
 }
 
 pub fn apply_filter<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
-    _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("BitmapData.applyFilter - not yet implemented");
+    if let Some(bitmap_data) = this.as_bitmap_data_object() {
+        if !bitmap_data.disposed() {
+            let source_bitmap = args
+                .get(0)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+            let src_rect =
+                rectangle_from_value(activation, args.get(1).unwrap_or(&Value::Undefined))?;
+            let dest_point =
+                point_from_value(activation, args.get(2).unwrap_or(&Value::Undefined))?;
+            let filter = args
+                .get(3)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+            let filter = match object_to_filter(filter) {
+                Some(filter) => filter,
+                None => return Ok((-1).into()),
+            };
+
+            if let Some(src_bitmap) = source_bitmap.as_bitmap_data_object() {
+                if !src_bitmap.disposed() {
+                    // dealing with object aliasing...
+                    let src_bitmap_data_cell = src_bitmap.bitmap_data();
+                    let read;
+                    let source: Option<&BitmapData> =
+                        if GcCell::ptr_eq(src_bitmap_data_cell, bitmap_data.bitmap_data()) {
+                            None
+                        } else {
+                            read = src_bitmap_data_cell.read();
+                            Some(&read)
+                        };
+
+                    bitmap_data
+                        .bitmap_data()
+                        .write(activation.context.gc_context)
+                        .apply_filter(source, src_rect, dest_point, &filter);
+                    return Ok(0.into());
+                }
+            }
+
+            return Ok((-1).into());
+        }
+    }
+
     Ok((-1).into())
 }
 
 pub fn draw<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let Some(bitmap_data) = this.as_bitmap_data_object() {
         if !bitmap_data.disposed() {
-            log::warn!("BitmapData.draw - not yet implemented");
+            let source = args
+                .get(0)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+            let matrix = match args.get(1) {
+                Some(Value::Object(matrix)) => object_to_matrix(*matrix, activation)?,
+                _ => Matrix::default(),
+            };
+            let color_transform = match args.get(2) {
+                Some(Value::Object(color_transform)) => {
+                    object_to_color_transform(*color_transform, activation)?
+                }
+                _ => ColorTransform::default(),
+            };
+            let blend_mode = match args.get(3) {
+                Some(blend_mode) => display_object::value_to_blend_mode(activation, blend_mode)?,
+                None => BlendMode::Normal,
+            };
+            let clip_rect = match args.get(4) {
+                Some(clip_rect @ Value::Object(_)) => {
+                    Some(rectangle_from_value(activation, clip_rect)?)
+                }
+                _ => None,
+            };
+            let smoothing = args
+                .get(5)
+                .unwrap_or(&false.into())
+                .as_bool(activation.swf_version());
+
+            if let Some(src_bitmap) = source.as_bitmap_data_object() {
+                if !src_bitmap.disposed() {
+                    // dealing with object aliasing...
+                    let src_bitmap_data_cell = src_bitmap.bitmap_data();
+                    let read;
+                    let source: Option<&BitmapData> =
+                        if GcCell::ptr_eq(src_bitmap_data_cell, bitmap_data.bitmap_data()) {
+                            None
+                        } else {
+                            read = src_bitmap_data_cell.read();
+                            Some(&read)
+                        };
+
+                    bitmap_data
+                        .bitmap_data()
+                        .write(activation.context.gc_context)
+                        .draw_bitmap_data(
+                            source,
+                            matrix,
+                            &color_transform,
+                            blend_mode,
+                            clip_rect,
+                            smoothing,
+                        );
+                }
+            } else if let Some(source) = source.as_display_object() {
+                let (width, height) = {
+                    let bitmap_data = bitmap_data.bitmap_data().read();
+                    (bitmap_data.width(), bitmap_data.height())
+                };
+                let transform = Transform {
+                    matrix,
+                    color_transform,
+                };
+                match render_display_object(&mut activation.context, source, transform, width, height)
+                {
+                    Some(pixels) => bitmap_data
+                        .bitmap_data()
+                        .write(activation.context.gc_context)
+                        .draw_pixels(&pixels, blend_mode, clip_rect),
+                    None => log::warn!(
+                        "BitmapData.draw - drawing display objects is not supported by this renderer"
+                    ),
+                }
+            }
+
             return Ok(Value::Undefined);
         }
     }
@@ -678,14 +804,62 @@ pub fn perlin_noise<'gc>(
 }
 
 pub fn hit_test<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let Some(bitmap_data) = this.as_bitmap_data_object() {
         if !bitmap_data.disposed() {
-            log::warn!("BitmapData.hitTest - not yet implemented");
-            return Ok(Value::Undefined);
+            let (first_x, first_y) =
+                point_from_value(activation, args.get(0).unwrap_or(&Value::Undefined))?;
+            let first_threshold = args
+                .get(1)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_i32(activation)?
+                .clamp(0, 255) as u8;
+            let second_object = args
+                .get(2)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+
+            // The second object can be a `BitmapData`, a `Rectangle`, or a `Point`,
+            // all in the same coordinate space as `firstPoint`.
+            let result = if let Some(other) = second_object.as_bitmap_data_object() {
+                if other.disposed() {
+                    return Ok(Value::Undefined);
+                }
+                let (second_x, second_y) =
+                    point_from_value(activation, args.get(3).unwrap_or(&Value::Undefined))?;
+                let second_threshold = args
+                    .get(4)
+                    .unwrap_or(&1.into())
+                    .coerce_to_i32(activation)?
+                    .clamp(0, 255) as u8;
+                let other = other.bitmap_data();
+                let other = other.read();
+                bitmap_data.bitmap_data().read().hit_test_bitmap(
+                    first_threshold,
+                    &other,
+                    (second_x - first_x, second_y - first_y),
+                    second_threshold,
+                )
+            } else if second_object.has_property(activation, "width".into())
+                && second_object.has_property(activation, "height".into())
+            {
+                let (x, y, width, height) =
+                    rectangle_from_value(activation, &second_object.into())?;
+                bitmap_data
+                    .bitmap_data()
+                    .read()
+                    .hit_test_rectangle(first_threshold, (x - first_x, y - first_y, width, height))
+            } else {
+                let (x, y) = point_from_value(activation, &second_object.into())?;
+                bitmap_data
+                    .bitmap_data()
+                    .read()
+                    .hit_test_point(first_threshold, (x - first_x, y - first_y))
+            };
+            return Ok(result.into());
         }
     }
 
@@ -1007,13 +1181,56 @@ pub fn palette_map<'gc>(
 }
 
 pub fn pixel_dissolve<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let Some(bitmap_data) = this.as_bitmap_data_object() {
         if !bitmap_data.disposed() {
-            log::warn!("BitmapData.pixelDissolve - not yet implemented");
+            let source_bitmap = args
+                .get(0)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+            let (src_min_x, src_min_y, src_width, src_height) =
+                rectangle_from_value(activation, args.get(1).unwrap_or(&Value::Undefined))?;
+            let (dest_x, dest_y) =
+                point_from_value(activation, args.get(2).unwrap_or(&Value::Undefined))?;
+            let seed = args.get(3).unwrap_or(&0.into()).coerce_to_i32(activation)?;
+            let num_pixels = match args.get(4) {
+                Some(num_pixels) => num_pixels.coerce_to_i32(activation)?,
+                // Defaults to 1/30th of the source rectangle.
+                None => src_width.saturating_mul(src_height) / 30,
+            };
+            let fill_color = args.get(5).unwrap_or(&0.into()).coerce_to_i32(activation)?;
+
+            if let Some(src_bitmap) = source_bitmap.as_bitmap_data_object() {
+                if !src_bitmap.disposed() {
+                    // dealing with object aliasing...
+                    let src_bitmap_data_cell = src_bitmap.bitmap_data();
+                    let read;
+                    let source: Option<&BitmapData> =
+                        if GcCell::ptr_eq(src_bitmap_data_cell, bitmap_data.bitmap_data()) {
+                            None
+                        } else {
+                            read = src_bitmap_data_cell.read();
+                            Some(&read)
+                        };
+
+                    let next_seed = bitmap_data
+                        .bitmap_data()
+                        .write(activation.context.gc_context)
+                        .pixel_dissolve(
+                            source,
+                            (src_min_x, src_min_y, src_width, src_height),
+                            (dest_x, dest_y),
+                            seed,
+                            num_pixels,
+                            fill_color.into(),
+                        );
+                    return Ok(next_seed.into());
+                }
+            }
+
             return Ok(Value::Undefined);
         }
     }
@@ -1050,20 +1267,104 @@ pub fn scroll<'gc>(
 }
 
 pub fn threshold<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let Some(bitmap_data) = this.as_bitmap_data_object() {
         if !bitmap_data.disposed() {
-            log::warn!("BitmapData.threshold - not yet implemented");
-            return Ok(Value::Undefined);
+            let source_bitmap = args
+                .get(0)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+            let (src_min_x, src_min_y, src_width, src_height) =
+                rectangle_from_value(activation, args.get(1).unwrap_or(&Value::Undefined))?;
+            let (dest_x, dest_y) =
+                point_from_value(activation, args.get(2).unwrap_or(&Value::Undefined))?;
+            let operation = args
+                .get(3)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_string(activation)?;
+            let operation = match ThresholdOperation::from_operator(&operation.to_string()) {
+                Some(operation) => operation,
+                // Invalid operations do nothing.
+                None => return Ok(0.into()),
+            };
+            let threshold = args
+                .get(4)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_u32(activation)?;
+            let color = args.get(5).unwrap_or(&0.into()).coerce_to_i32(activation)?;
+            let mask = args
+                .get(6)
+                .unwrap_or(&0xFFFFFFFFu32.into())
+                .coerce_to_u32(activation)?;
+            let copy_source = args
+                .get(7)
+                .unwrap_or(&false.into())
+                .as_bool(activation.swf_version());
+
+            if let Some(src_bitmap) = source_bitmap.as_bitmap_data_object() {
+                if !src_bitmap.disposed() {
+                    // dealing with object aliasing...
+                    let src_bitmap_data_cell = src_bitmap.bitmap_data();
+                    let read;
+                    let source: Option<&BitmapData> =
+                        if GcCell::ptr_eq(src_bitmap_data_cell, bitmap_data.bitmap_data()) {
+                            None
+                        } else {
+                            read = src_bitmap_data_cell.read();
+                            Some(&read)
+                        };
+
+                    let num_changed = bitmap_data
+                        .bitmap_data()
+                        .write(activation.context.gc_context)
+                        .threshold(
+                            source,
+                            (src_min_x, src_min_y, src_width, src_height),
+                            (dest_x, dest_y),
+                            operation,
+                            threshold,
+                            color.into(),
+                            mask,
+                            copy_source,
+                        );
+                    return Ok(num_changed.into());
+                }
+            }
+
+            return Ok(0.into());
         }
     }
 
     Ok((-1).into())
 }
 
+/// Reads the `x`, `y`, `width` and `height` of a rectangle object.
+fn rectangle_from_value<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: &Value<'gc>,
+) -> Result<(i32, i32, i32, i32), Error<'gc>> {
+    let rect = value.coerce_to_object(activation);
+    let x = rect.get("x", activation)?.coerce_to_f64(activation)? as i32;
+    let y = rect.get("y", activation)?.coerce_to_f64(activation)? as i32;
+    let width = rect.get("width", activation)?.coerce_to_f64(activation)? as i32;
+    let height = rect.get("height", activation)?.coerce_to_f64(activation)? as i32;
+    Ok((x, y, width, height))
+}
+
+/// Reads the `x` and `y` of a point object.
+fn point_from_value<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: &Value<'gc>,
+) -> Result<(i32, i32), Error<'gc>> {
+    let point = value.coerce_to_object(activation);
+    let x = point.get("x", activation)?.coerce_to_f64(activation)? as i32;
+    let y = point.get("y", activation)?.coerce_to_f64(activation)? as i32;
+    Ok((x, y))
+}
+
 pub fn compare<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
@@ -2,8 +2,24 @@
 
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
+use crate::avm1::object::bevel_filter::{BevelFilterObject, BevelFilterType};
+use crate::avm1::object::bitmap_data::BitmapDataObject;
+use crate::avm1::object::blur_filter::BlurFilterObject;
+use crate::avm1::object::color_matrix_filter::ColorMatrixFilterObject;
+use crate::avm1::object::convolution_filter::ConvolutionFilterObject;
+use crate::avm1::object::displacement_map_filter::{
+    DisplacementMapFilterMode, DisplacementMapFilterObject,
+};
+use crate::avm1::object::drop_shadow_filter::DropShadowFilterObject;
+use crate::avm1::object::glow_filter::GlowFilterObject;
+use crate::avm1::object::gradient_bevel_filter::GradientBevelFilterObject;
+use crate::avm1::object::gradient_glow_filter::GradientGlowFilterObject;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::{Object, ScriptObject, TObject, Value};
+use crate::bitmap::filters::{
+    BevelFilter, BevelType, BitmapFilter, BlurFilter, ColorMatrixFilter, ConvolutionFilter,
+    DisplacementMapFilter, DisplacementMode, GradientFilter, ShadowFilter,
+};
 use gc_arena::MutationContext;
 
 const PROTO_DECLS: &[Declaration] = declare_properties! {
@@ -272,6 +288,340 @@ pub fn clone<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Read the parameters of a filter object, such as a `BlurFilter`.
+pub fn object_to_filter(object: Object<'_>) -> Option<BitmapFilter> {
+    if let Some(filter) = object.as_blur_filter_object() {
+        return Some(BitmapFilter::Blur(BlurFilter {
+            blur_x: filter.blur_x(),
+            blur_y: filter.blur_y(),
+            quality: filter.quality(),
+        }));
+    }
+
+    if let Some(filter) = object.as_glow_filter_object() {
+        return Some(BitmapFilter::Glow(ShadowFilter {
+            color: filter.color() as u32,
+            alpha: filter.alpha(),
+            blur: BlurFilter {
+                blur_x: filter.blur_x(),
+                blur_y: filter.blur_y(),
+                quality: filter.quality(),
+            },
+            strength: filter.strength(),
+            distance: 0.0,
+            angle: 0.0,
+            inner: filter.inner(),
+            knockout: filter.knockout(),
+            hide_object: false,
+        }));
+    }
+
+    if let Some(filter) = object.as_drop_shadow_filter_object() {
+        return Some(BitmapFilter::DropShadow(ShadowFilter {
+            color: filter.color(),
+            alpha: filter.alpha(),
+            blur: BlurFilter {
+                blur_x: filter.blur_x(),
+                blur_y: filter.blur_y(),
+                quality: filter.quality(),
+            },
+            strength: filter.strength(),
+            distance: filter.distance(),
+            angle: filter.angle(),
+            inner: filter.inner(),
+            knockout: filter.knockout(),
+            hide_object: filter.hide_object(),
+        }));
+    }
+
+    if let Some(filter) = object.as_bevel_filter_object() {
+        return Some(BitmapFilter::Bevel(BevelFilter {
+            highlight_color: filter.highlight_color(),
+            highlight_alpha: filter.highlight_alpha(),
+            shadow_color: filter.shadow_color(),
+            shadow_alpha: filter.shadow_alpha(),
+            blur: BlurFilter {
+                blur_x: filter.blur_x(),
+                blur_y: filter.blur_y(),
+                quality: filter.quality(),
+            },
+            strength: filter.strength(),
+            distance: filter.distance(),
+            angle: filter.angle(),
+            bevel_type: to_bevel_type(filter.get_type()),
+            knockout: filter.knockout(),
+        }));
+    }
+
+    if let Some(filter) = object.as_gradient_glow_filter_object() {
+        return Some(BitmapFilter::GradientGlow(GradientFilter {
+            colors: filter.colors(),
+            alphas: filter.alphas(),
+            ratios: filter.ratios(),
+            blur: BlurFilter {
+                blur_x: filter.blur_x(),
+                blur_y: filter.blur_y(),
+                quality: filter.quality(),
+            },
+            strength: filter.strength(),
+            distance: filter.distance(),
+            angle: filter.angle(),
+            bevel_type: to_bevel_type(filter.get_type()),
+            knockout: filter.knockout(),
+        }));
+    }
+
+    if let Some(filter) = object.as_gradient_bevel_filter_object() {
+        return Some(BitmapFilter::GradientBevel(GradientFilter {
+            colors: filter.colors(),
+            alphas: filter.alphas(),
+            ratios: filter.ratios(),
+            blur: BlurFilter {
+                blur_x: filter.blur_x(),
+                blur_y: filter.blur_y(),
+                quality: filter.quality(),
+            },
+            strength: filter.strength(),
+            distance: filter.distance(),
+            angle: filter.angle(),
+            bevel_type: to_bevel_type(filter.get_type()),
+            knockout: filter.knockout(),
+        }));
+    }
+
+    if let Some(filter) = object.as_color_matrix_filter_object() {
+        return Some(BitmapFilter::ColorMatrix(ColorMatrixFilter {
+            matrix: filter.matrix(),
+        }));
+    }
+
+    if let Some(filter) = object.as_convolution_filter_object() {
+        return Some(BitmapFilter::Convolution(ConvolutionFilter {
+            matrix_x: filter.matrix_x(),
+            matrix_y: filter.matrix_y(),
+            matrix: filter.matrix(),
+            divisor: filter.divisor(),
+            bias: filter.bias(),
+            preserve_alpha: filter.preserve_alpha(),
+            clamp: filter.clamp(),
+            color: filter.color(),
+            alpha: filter.alpha(),
+        }));
+    }
+
+    if let Some(filter) = object.as_displacement_map_filter_object() {
+        let map = filter
+            .map_bitmap()
+            .and_then(|map| map.as_bitmap_data_object());
+        let (map, map_width, map_height) = match map {
+            Some(map) if !map.disposed() => {
+                let map = map.bitmap_data();
+                let map = map.read();
+                (map.pixels().to_vec(), map.width(), map.height())
+            }
+            _ => (Vec::new(), 0, 0),
+        };
+        return Some(BitmapFilter::DisplacementMap(DisplacementMapFilter {
+            map,
+            map_width,
+            map_height,
+            map_point: filter.map_point(),
+            component_x: filter.component_x(),
+            component_y: filter.component_y(),
+            scale_x: filter.scale_x(),
+            scale_y: filter.scale_y(),
+            mode: match filter.mode() {
+                DisplacementMapFilterMode::Wrap => DisplacementMode::Wrap,
+                DisplacementMapFilterMode::Clamp => DisplacementMode::Clamp,
+                DisplacementMapFilterMode::Ignore => DisplacementMode::Ignore,
+                DisplacementMapFilterMode::Color => DisplacementMode::Color,
+            },
+            color: filter.color(),
+            alpha: filter.alpha(),
+        }));
+    }
+
+    None
+}
+
+fn to_bevel_type(bevel_type: BevelFilterType) -> BevelType {
+    match bevel_type {
+        BevelFilterType::Inner => BevelType::Inner,
+        BevelFilterType::Outer => BevelType::Outer,
+        BevelFilterType::Full => BevelType::Full,
+    }
+}
+
+/// Create a filter object, such as a `BlurFilter`, with the given parameters.
+pub fn filter_to_object<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    filter: &BitmapFilter,
+) -> Object<'gc> {
+    let gc_context = activation.context.gc_context;
+    let prototypes = activation.context.avm1.prototypes();
+    match filter {
+        BitmapFilter::Blur(blur) => {
+            let object = BlurFilterObject::empty_object(gc_context, Some(prototypes.blur_filter));
+            object.set_blur_x(gc_context, blur.blur_x);
+            object.set_blur_y(gc_context, blur.blur_y);
+            object.set_quality(gc_context, blur.quality);
+            object.into()
+        }
+        BitmapFilter::Glow(glow) => {
+            let object = GlowFilterObject::empty_object(gc_context, Some(prototypes.glow_filter));
+            object.set_color(gc_context, glow.color as i32);
+            object.set_alpha(gc_context, glow.alpha);
+            object.set_blur_x(gc_context, glow.blur.blur_x);
+            object.set_blur_y(gc_context, glow.blur.blur_y);
+            object.set_quality(gc_context, glow.blur.quality);
+            object.set_strength(gc_context, glow.strength);
+            object.set_inner(gc_context, glow.inner);
+            object.set_knockout(gc_context, glow.knockout);
+            object.into()
+        }
+        BitmapFilter::DropShadow(shadow) => {
+            let object = DropShadowFilterObject::empty_object(
+                gc_context,
+                Some(prototypes.drop_shadow_filter),
+            );
+            object.set_color(gc_context, shadow.color);
+            object.set_alpha(gc_context, shadow.alpha);
+            object.set_blur_x(gc_context, shadow.blur.blur_x);
+            object.set_blur_y(gc_context, shadow.blur.blur_y);
+            object.set_quality(gc_context, shadow.blur.quality);
+            object.set_strength(gc_context, shadow.strength);
+            object.set_distance(gc_context, shadow.distance);
+            object.set_angle(gc_context, shadow.angle);
+            object.set_inner(gc_context, shadow.inner);
+            object.set_knockout(gc_context, shadow.knockout);
+            object.set_hide_object(gc_context, shadow.hide_object);
+            object.into()
+        }
+        BitmapFilter::Bevel(bevel) => {
+            let object = BevelFilterObject::empty_object(gc_context, Some(prototypes.bevel_filter));
+            object.set_highlight_color(gc_context, bevel.highlight_color);
+            object.set_highlight_alpha(gc_context, bevel.highlight_alpha);
+            object.set_shadow_color(gc_context, bevel.shadow_color);
+            object.set_shadow_alpha(gc_context, bevel.shadow_alpha);
+            object.set_blur_x(gc_context, bevel.blur.blur_x);
+            object.set_blur_y(gc_context, bevel.blur.blur_y);
+            object.set_quality(gc_context, bevel.blur.quality);
+            object.set_strength(gc_context, bevel.strength);
+            object.set_distance(gc_context, bevel.distance);
+            object.set_angle(gc_context, bevel.angle);
+            object.set_type(gc_context, from_bevel_type(bevel.bevel_type));
+            object.set_knockout(gc_context, bevel.knockout);
+            object.into()
+        }
+        BitmapFilter::GradientGlow(glow) => {
+            let object = GradientGlowFilterObject::empty_object(
+                gc_context,
+                Some(prototypes.gradient_glow_filter),
+            );
+            object.set_colors(gc_context, glow.colors.clone());
+            object.set_alphas(gc_context, glow.alphas.clone());
+            object.set_ratios(gc_context, glow.ratios.clone());
+            object.set_blur_x(gc_context, glow.blur.blur_x);
+            object.set_blur_y(gc_context, glow.blur.blur_y);
+            object.set_quality(gc_context, glow.blur.quality);
+            object.set_strength(gc_context, glow.strength);
+            object.set_distance(gc_context, glow.distance);
+            object.set_angle(gc_context, glow.angle);
+            object.set_type(gc_context, from_bevel_type(glow.bevel_type));
+            object.set_knockout(gc_context, glow.knockout);
+            object.into()
+        }
+        BitmapFilter::GradientBevel(bevel) => {
+            let object = GradientBevelFilterObject::empty_object(
+                gc_context,
+                Some(prototypes.gradient_bevel_filter),
+            );
+            object.set_colors(gc_context, bevel.colors.clone());
+            object.set_alphas(gc_context, bevel.alphas.clone());
+            object.set_ratios(gc_context, bevel.ratios.clone());
+            object.set_blur_x(gc_context, bevel.blur.blur_x);
+            object.set_blur_y(gc_context, bevel.blur.blur_y);
+            object.set_quality(gc_context, bevel.blur.quality);
+            object.set_strength(gc_context, bevel.strength);
+            object.set_distance(gc_context, bevel.distance);
+            object.set_angle(gc_context, bevel.angle);
+            object.set_type(gc_context, from_bevel_type(bevel.bevel_type));
+            object.set_knockout(gc_context, bevel.knockout);
+            object.into()
+        }
+        BitmapFilter::ColorMatrix(color_matrix) => {
+            let object = ColorMatrixFilterObject::empty_object(
+                gc_context,
+                Some(prototypes.color_matrix_filter),
+            );
+            object.set_matrix(gc_context, color_matrix.matrix);
+            object.into()
+        }
+        BitmapFilter::Convolution(convolution) => {
+            let object = ConvolutionFilterObject::empty_object(
+                gc_context,
+                Some(prototypes.convolution_filter),
+            );
+            object.set_matrix_x(gc_context, convolution.matrix_x);
+            object.set_matrix_y(gc_context, convolution.matrix_y);
+            object.set_matrix(gc_context, convolution.matrix.clone());
+            object.set_divisor(gc_context, convolution.divisor);
+            object.set_bias(gc_context, convolution.bias);
+            object.set_preserve_alpha(gc_context, convolution.preserve_alpha);
+            object.set_clamp(gc_context, convolution.clamp);
+            object.set_color(gc_context, convolution.color);
+            object.set_alpha(gc_context, convolution.alpha);
+            object.into()
+        }
+        BitmapFilter::DisplacementMap(displacement) => {
+            let object = DisplacementMapFilterObject::empty_object(
+                gc_context,
+                Some(prototypes.displacement_map_filter),
+            );
+            // The map is copied when the filter is read, so this is a copy of it too.
+            let map_bitmap = if displacement.map.is_empty() {
+                None
+            } else {
+                let map_bitmap =
+                    BitmapDataObject::empty_object(gc_context, Some(prototypes.bitmap_data));
+                map_bitmap.bitmap_data().write(gc_context).set_pixels(
+                    displacement.map_width,
+                    displacement.map_height,
+                    true,
+                    displacement.map.clone(),
+                );
+                Some(map_bitmap.into())
+            };
+            object.set_map_bitmap(gc_context, map_bitmap);
+            object.set_map_point(gc_context, displacement.map_point);
+            object.set_component_x(gc_context, displacement.component_x);
+            object.set_component_y(gc_context, displacement.component_y);
+            object.set_scale_x(gc_context, displacement.scale_x);
+            object.set_scale_y(gc_context, displacement.scale_y);
+            object.set_mode(
+                gc_context,
+                match displacement.mode {
+                    DisplacementMode::Wrap => DisplacementMapFilterMode::Wrap,
+                    DisplacementMode::Clamp => DisplacementMapFilterMode::Clamp,
+                    DisplacementMode::Ignore => DisplacementMapFilterMode::Ignore,
+                    DisplacementMode::Color => DisplacementMapFilterMode::Color,
+                },
+            );
+            object.set_color(gc_context, displacement.color);
+            object.set_alpha(gc_context, displacement.alpha);
+            object.into()
+        }
+    }
+}
+
+fn from_bevel_type(bevel_type: BevelType) -> BevelFilterType {
+    match bevel_type {
+        BevelType::Inner => BevelFilterType::Inner,
+        BevelType::Outer => BevelFilterType::Outer,
+        BevelType::Full => BevelFilterType::Full,
+    }
+}
+
 pub fn create_proto<'gc>(
     gc_context: MutationContext<'gc, '_>,
     proto: Object<'gc>,
@@ -37,6 +37,9 @@ macro_rules! button_setter {
 const PROTO_DECLS: &[Declaration] = declare_properties! {
     "enabled" => property(button_getter!(enabled), button_setter!(set_enabled); DONT_ENUM | DONT_DELETE);
     "useHandCursor" => property(button_getter!(use_hand_cursor), button_setter!(set_use_hand_cursor); DONT_ENUM | DONT_DELETE);
+    "filters" => property(button_getter!(filters), button_setter!(set_filters); DONT_ENUM | DONT_DELETE);
+    "cacheAsBitmap" => property(button_getter!(cache_as_bitmap), button_setter!(set_cache_as_bitmap); DONT_ENUM | DONT_DELETE);
+    "blendMode" => property(button_getter!(blend_mode), button_setter!(set_blend_mode); DONT_ENUM | DONT_DELETE);
 };
 
 pub fn create_proto<'gc>(
@@ -94,3 +97,50 @@ fn set_use_hand_cursor<'gc>(
     this.set_use_hand_cursor(&mut activation.context, use_hand_cursor);
     Ok(())
 }
+
+fn filters<'gc>(
+    this: Avm1Button<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    display_object::filters(this.into(), activation)
+}
+
+fn set_filters<'gc>(
+    this: Avm1Button<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    display_object::set_filters(this.into(), activation, value)
+}
+
+fn blend_mode<'gc>(
+    this: Avm1Button<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    display_object::blend_mode(this.into(), activation)
+}
+
+fn set_blend_mode<'gc>(
+    this: Avm1Button<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    display_object::set_blend_mode(this.into(), activation, value)
+}
+
+fn cache_as_bitmap<'gc>(
+    this: Avm1Button<'gc>,
+    _activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.cache_as_bitmap().into())
+}
+
+fn set_cache_as_bitmap<'gc>(
+    this: Avm1Button<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    let cache_as_bitmap = value.as_bool(activation.swf_version());
+    this.set_cache_as_bitmap(activation.context.gc_context, cache_as_bitmap);
+    Ok(())
+}
@@ -190,8 +190,9 @@ fn set_transform<'gc>(
     if let Some(target) = target(activation, this)? {
         target.set_transformed_by_script(activation.context.gc_context, true);
 
-        let mut base = target.base_mut(activation.context.gc_context);
-        let color_transform = base.color_transform_mut();
+        // Reading the properties can run getters, which may touch the target,
+        // so the new transform is built up before being applied.
+        let mut color_transform = *target.base().color_transform();
         let transform = args
             .get(0)
             .unwrap_or(&Value::Undefined)
@@ -204,6 +205,7 @@ fn set_transform<'gc>(
         set_color_add(activation, transform, "gb", &mut color_transform.g_add)?;
         set_color_add(activation, transform, "bb", &mut color_transform.b_add)?;
         set_color_add(activation, transform, "ab", &mut color_transform.a_add)?;
+        target.set_color_transform(activation.context.gc_context, &color_transform);
     }
 
     Ok(Value::Undefined)
@@ -5,7 +5,7 @@ use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::Object;
 use crate::avm1::{ScriptObject, Value};
 use crate::context_menu;
-use crate::display_object::TDisplayObject;
+use crate::display_object::{DisplayObject, TDisplayObject};
 use gc_arena::MutationContext;
 
 const PROTO_DECLS: &[Declaration] = declare_properties! {
@@ -149,6 +149,7 @@ pub fn create_proto<'gc>(
 
 pub fn make_context_menu_state<'gc>(
     menu: Option<Object<'gc>>,
+    target: DisplayObject<'gc>,
     activation: &mut Activation<'_, 'gc, '_>,
 ) -> context_menu::ContextMenuState<'gc> {
     let mut result = context_menu::ContextMenuState::new();
@@ -267,6 +268,7 @@ pub fn make_context_menu_state<'gc>(
                                 checked: false,
                             },
                             context_menu::ContextMenuCallback::Avm1 {
+                                target,
                                 item,
                                 callback: on_select,
                             },
@@ -4,8 +4,11 @@ use crate::avm1::function::{Executable, FunctionObject};
 use crate::avm1::object::date_object::DateObject;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::{Object, TObject, Value};
+use crate::backend::locale::local_to_utc;
 use crate::string::AvmString;
-use chrono::{DateTime, Datelike, Duration, LocalResult, TimeZone, Timelike, Utc};
+use chrono::{
+    DateTime, Datelike, Duration, LocalResult, NaiveDate, Offset, TimeZone, Timelike, Utc,
+};
 use gc_arena::{Collect, MutationContext};
 use num_traits::ToPrimitive;
 
@@ -555,14 +558,10 @@ impl<'builder, 'activation_a, 'gc, 'gc_context, T: TimeZone>
                 + Duration::seconds(second)
                 + Duration::milliseconds(millisecond);
 
-            if let LocalResult::Single(Some(result)) = current
-                .timezone()
-                .ymd_opt(year, (month + 1) as u32, 1)
-                .and_hms_opt(0, 0, 0)
-                .map(|date| date.checked_add_signed(duration))
-            {
-                return Some(result.with_timezone(&Utc));
-            }
+            let date = NaiveDate::from_ymd_opt(year, (month + 1) as u32, 1)?
+                .and_hms(0, 0, 0)
+                .checked_add_signed(duration)?;
+            return local_to_utc(&current.timezone(), &date);
         }
 
         None
@@ -598,7 +597,7 @@ fn constructor<'gc>(
             // We need a starting value to adjust from.
             this.set_date_time(
                 activation.context.gc_context,
-                Some(timezone.ymd(0, 1, 1).and_hms(0, 0, 0).into()),
+                Some(timezone.ymd(0, 1, 1).and_hms(0, 0, 0).with_timezone(&Utc)),
             );
 
             DateAdjustment::new(activation, &timezone)
@@ -693,7 +692,7 @@ fn get_timezone_offset<'gc>(
         return Ok(f64::NAN.into());
     };
 
-    let seconds = date.offset().utc_minus_local() as f32;
+    let seconds = date.offset().fix().utc_minus_local() as f32;
     let minutes = seconds / 60.0;
     Ok(minutes.into())
 }
@@ -884,9 +883,10 @@ fn set_time<'gc>(
         .coerce_to_f64(activation)?;
 
     if new_time.is_finite() {
-        let time = Utc.timestamp_millis(new_time as i64);
-        this.set_date_time(activation.context.gc_context, Some(time));
-        return Ok((time.timestamp_millis() as f64).into());
+        if let LocalResult::Single(time) = Utc.timestamp_millis_opt(new_time as i64) {
+            this.set_date_time(activation.context.gc_context, Some(time));
+            return Ok((time.timestamp_millis() as f64).into());
+        }
     }
 
     this.set_date_time(activation.context.gc_context, None);
@@ -2,12 +2,14 @@
 
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
+use crate::avm1::globals::bitmap_filter::{filter_to_object, object_to_filter};
 use crate::avm1::property::Attribute;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
-use crate::avm1::{Object, ScriptObject, TObject, Value};
+use crate::avm1::{ArrayObject, Object, ScriptObject, TObject, Value};
 use crate::display_object::{DisplayObject, Lists, TDisplayObject, TDisplayObjectContainer};
 use crate::string::AvmString;
 use gc_arena::MutationContext;
+use swf::BlendMode;
 
 /// Depths used/returned by ActionScript are offset by this amount from depths used inside the SWF/by the VM.
 /// The depth of objects placed on the timeline in the Flash IDE start from 0 in the SWF,
@@ -168,3 +170,76 @@ pub fn remove_display_object<'gc>(
         }
     }
 }
+
+/// Returns copies of the filters of a display object, for its `filters` property.
+pub fn filters<'gc>(
+    this: DisplayObject<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let filters: Vec<Value<'gc>> = this
+        .filters()
+        .iter()
+        .map(|filter| filter_to_object(activation, filter).into())
+        .collect();
+    Ok(ArrayObject::new(
+        activation.context.gc_context,
+        activation.context.avm1.prototypes().array,
+        filters,
+    )
+    .into())
+}
+
+/// Sets the filters of a display object from an array of filter objects, for
+/// its `filters` property.
+///
+/// Entries that aren't filter objects are ignored.
+pub fn set_filters<'gc>(
+    this: DisplayObject<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    let mut filters = Vec::new();
+    if let Value::Object(array) = value {
+        for i in 0..array.length(activation)? {
+            if let Value::Object(filter) = array.get_element(activation, i) {
+                filters.extend(object_to_filter(filter));
+            }
+        }
+    }
+    this.set_filters(activation.context.gc_context, filters);
+    Ok(())
+}
+
+/// Read a blend mode, which is either a name such as `"multiply"` or its number.
+pub fn value_to_blend_mode<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: &Value<'gc>,
+) -> Result<BlendMode, Error<'gc>> {
+    if let Value::Number(number) = value {
+        return Ok(BlendMode::from_u8(*number as u8).unwrap_or(BlendMode::Normal));
+    }
+
+    let name = value.coerce_to_string(activation)?.to_string();
+    Ok(name.parse().unwrap_or(BlendMode::Normal))
+}
+
+/// Returns the name of the blend mode of a display object, for its
+/// `blendMode` property.
+pub fn blend_mode<'gc>(
+    this: DisplayObject<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(AvmString::new_utf8(activation.context.gc_context, this.blend_mode().to_string()).into())
+}
+
+/// Sets the blend mode of a display object from its name or number, for its
+/// `blendMode` property.
+pub fn set_blend_mode<'gc>(
+    this: DisplayObject<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    let blend_mode = value_to_blend_mode(activation, &value)?;
+    this.set_blend_mode(activation.context.gc_context, blend_mode);
+    Ok(())
+}
@@ -27,6 +27,7 @@ const OBJECT_DECLS: &[Declaration] = declare_properties! {
     "TAB" => int(KeyCode::Tab as i32; DONT_ENUM | DONT_DELETE | READ_ONLY);
     "UP" => int(KeyCode::Up as i32; DONT_ENUM | DONT_DELETE | READ_ONLY);
     "isDown" => method(is_down; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "isToggled" => method(is_toggled; DONT_ENUM | DONT_DELETE | READ_ONLY);
     "getAscii" => method(get_ascii; DONT_ENUM | DONT_DELETE | READ_ONLY);
     "getCode" => method(get_code; DONT_ENUM | DONT_DELETE | READ_ONLY);
 };
@@ -47,6 +48,24 @@ pub fn is_down<'gc>(
     }
 }
 
+pub fn is_toggled<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    // Only lock keys can be toggled.
+    match KeyCode::from_u8(
+        args.get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_i32(activation)? as u8,
+    ) {
+        Some(key @ (KeyCode::CapsLock | KeyCode::NumLock | KeyCode::ScrollLock)) => {
+            Ok(activation.context.ui.is_key_toggled(key).into())
+        }
+        _ => Ok(false.into()),
+    }
+}
+
 pub fn get_ascii<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     _this: Object<'gc>,
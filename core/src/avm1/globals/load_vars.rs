@@ -1,11 +1,10 @@
 //! AVM1 LoadVars object
-//! TODO: bytesLoaded, bytesTotal, contentType, addRequestHeader
 
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::property::Attribute;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
-use crate::avm1::{Object, ScriptObject, TObject, Value};
+use crate::avm1::{ArrayObject, Object, ScriptObject, TObject, Value};
 use crate::avm_warn;
 use crate::backend::navigator::{NavigationMethod, RequestOptions};
 use crate::string::AvmString;
@@ -47,13 +46,73 @@ pub fn create_proto<'gc>(
 
 fn add_request_header<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
-    _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    this: Object<'gc>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm_warn!(activation, "LoadVars.addRequestHeader: Unimplemented");
+    // Accepts either a name and a value, or an array of alternating names and values.
+    let new_headers = match args {
+        [Value::Object(array), ..] => {
+            let mut values = Vec::new();
+            for i in 0..array.length(activation)? {
+                values.push(array.get_element(activation, i));
+            }
+            values
+        }
+        [name, value, ..] => vec![*name, *value],
+        _ => return Ok(Value::Undefined),
+    };
+
+    // Headers are kept on an undocumented property, as in Flash Player.
+    let headers = match this.get("_customHeaders", activation)? {
+        Value::Object(headers) => headers,
+        _ => {
+            let headers: Object<'gc> = ArrayObject::empty(activation).into();
+            this.define_value(
+                activation.context.gc_context,
+                "_customHeaders",
+                headers.into(),
+                Attribute::DONT_ENUM,
+            );
+            headers
+        }
+    };
+
+    let mut length = headers.length(activation)?;
+    for pair in new_headers.chunks_exact(2) {
+        for value in pair {
+            let value = value.coerce_to_string(activation)?;
+            headers.set_element(activation, length, value.into())?;
+            length += 1;
+        }
+    }
+
     Ok(Value::Undefined)
 }
 
+/// Collects the headers added to `object` by `addRequestHeader`.
+fn custom_headers<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<Vec<(String, String)>, Error<'gc>> {
+    let headers = match object.get("_customHeaders", activation)? {
+        Value::Object(headers) => headers,
+        _ => return Ok(Vec::new()),
+    };
+
+    let mut result = Vec::new();
+    for i in (0..headers.length(activation)? - 1).step_by(2) {
+        let name = headers
+            .get_element(activation, i)
+            .coerce_to_string(activation)?;
+        let value = headers
+            .get_element(activation, i + 1)
+            .coerce_to_string(activation)?;
+        result.push((name.to_string(), value.to_string()));
+    }
+
+    Ok(result)
+}
+
 fn decode<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
@@ -152,7 +211,7 @@ fn send<'gc>(
     };
 
     let method_name = args
-        .get(1)
+        .get(2)
         .unwrap_or(&Value::Undefined)
         .coerce_to_string(activation)?;
     let method = NavigationMethod::from_method_str(&method_name).unwrap_or(NavigationMethod::Post);
@@ -248,7 +307,19 @@ fn spawn_load_var_fetch<'gc>(
 ) -> Result<Value<'gc>, Error<'gc>> {
     let (url, request_options) = if let Some((send_object, method)) = send_object {
         // Send properties from `send_object`.
-        activation.object_into_request_options(send_object, url, Some(method))
+        let (url, request_options) =
+            activation.object_into_request_options(send_object, url, Some(method));
+        let content_type = send_object
+            .get("contentType", activation)?
+            .coerce_to_string(activation)?
+            .to_string();
+        let headers = custom_headers(activation, send_object)?;
+        (
+            url,
+            request_options
+                .with_content_type(content_type)
+                .with_headers(headers),
+        )
     } else {
         // Not sending any parameters.
         (url.to_utf8_lossy(), RequestOptions::get())
@@ -273,6 +344,17 @@ fn spawn_load_var_fetch<'gc>(
         loader_object.set("_bytesLoaded", 0.into(), activation)?;
     }
 
+    if !loader_object.has_property(activation, "_bytesTotal".into()) {
+        loader_object.define_value(
+            activation.context.gc_context,
+            "_bytesTotal",
+            Value::Undefined,
+            Attribute::DONT_DELETE | Attribute::DONT_ENUM,
+        );
+    } else {
+        loader_object.set("_bytesTotal", Value::Undefined, activation)?;
+    }
+
     if !loader_object.has_property(activation, "loaded".into()) {
         loader_object.define_value(
             activation.context.gc_context,
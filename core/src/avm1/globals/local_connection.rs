@@ -0,0 +1,139 @@
+//! LocalConnection object
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::globals::shared_object::serialize_value;
+use crate::avm1::property_decl::{define_properties_on, Declaration};
+use crate::avm1::{Object, ScriptObject, Value};
+use crate::avm_warn;
+use crate::display_object::TDisplayObject;
+use crate::local_connection::{movie_domain, LocalConnectionObject};
+use crate::string::AvmString;
+use flash_lso::types::Value as AmfValue;
+use gc_arena::MutationContext;
+
+const PROTO_DECLS: &[Declaration] = declare_properties! {
+    "connect" => method(connect; DONT_ENUM | DONT_DELETE);
+    "send" => method(send; DONT_ENUM | DONT_DELETE);
+    "close" => method(close; DONT_ENUM | DONT_DELETE);
+    "domain" => method(domain; DONT_ENUM | DONT_DELETE);
+};
+
+/// Method names that can't be called through a `LocalConnection`.
+const RESERVED_METHODS: &[&str] = &[
+    "send",
+    "connect",
+    "close",
+    "allowDomain",
+    "allowInsecureDomain",
+    "domain",
+];
+
+/// Implements `LocalConnection`
+pub fn constructor<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.into())
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let object = ScriptObject::object(gc_context, Some(proto));
+    define_properties_on(PROTO_DECLS, gc_context, object, fn_proto);
+    object.into()
+}
+
+/// The domain of the movie that is running the current code.
+fn current_domain(activation: &mut Activation<'_, '_, '_>) -> String {
+    activation
+        .base_clip()
+        .movie()
+        .map(|movie| movie_domain(&movie))
+        .unwrap_or_else(|| "localhost".to_string())
+}
+
+fn connect<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let name = match args.get(0) {
+        Some(Value::String(name)) if !name.is_empty() => name.to_string(),
+        _ => return Ok(false.into()),
+    };
+
+    let domain = current_domain(activation);
+    Ok(activation
+        .context
+        .local_connections
+        .connect(&domain, &name, LocalConnectionObject::Avm1(this))
+        .into())
+}
+
+fn send<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let (connection_name, method_name) = match args {
+        [Value::String(connection_name), Value::String(method_name), ..]
+            if !connection_name.is_empty() && !method_name.is_empty() =>
+        {
+            (connection_name.to_string(), method_name.to_string())
+        }
+        _ => return Ok(false.into()),
+    };
+
+    if RESERVED_METHODS.contains(&method_name.as_str()) {
+        avm_warn!(
+            activation,
+            "LocalConnection.send: Can't call reserved method {}",
+            method_name
+        );
+        return Ok(false.into());
+    }
+
+    // Functions can't be sent, and are passed as `undefined`.
+    let message_args = args[2..]
+        .iter()
+        .map(|arg| serialize_value(activation, *arg).unwrap_or(AmfValue::Undefined))
+        .collect();
+
+    let domain = current_domain(activation);
+    activation.context.local_connections.send(
+        LocalConnectionObject::Avm1(this),
+        &domain,
+        &connection_name,
+        &method_name,
+        message_args,
+    );
+
+    Ok(true.into())
+}
+
+fn close<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    activation
+        .context
+        .local_connections
+        .close(LocalConnectionObject::Avm1(this));
+
+    Ok(Value::Undefined)
+}
+
+fn domain<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let domain = current_domain(activation);
+    Ok(AvmString::new_utf8(activation.context.gc_context, domain).into())
+}
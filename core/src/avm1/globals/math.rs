@@ -8,17 +8,38 @@ use rand::Rng;
 use std::f64::consts;
 
 macro_rules! wrap_std {
-    ($std:path) => {
-        |activation, _this, args| {
-            if let Some(input) = args.get(0) {
-                Ok($std(input.coerce_to_f64(activation)?).into())
-            } else {
-                Ok(f64::NAN.into())
+    ($($name:ident => $std:path),* $(,)?) => {
+        $(
+            pub fn $name<'gc>(
+                activation: &mut Activation<'_, 'gc, '_>,
+                _this: Object<'gc>,
+                args: &[Value<'gc>],
+            ) -> Result<Value<'gc>, Error<'gc>> {
+                if let Some(input) = args.get(0) {
+                    Ok($std(input.coerce_to_f64(activation)?).into())
+                } else {
+                    Ok(f64::NAN.into())
+                }
             }
-        }
+        )*
     };
 }
 
+wrap_std! {
+    abs => f64::abs,
+    acos => f64::acos,
+    asin => f64::asin,
+    atan => f64::atan,
+    ceil => f64::ceil,
+    cos => f64::cos,
+    exp => f64::exp,
+    floor => f64::floor,
+    sin => f64::sin,
+    sqrt => f64::sqrt,
+    tan => f64::tan,
+    log => f64::ln,
+}
+
 const OBJECT_DECLS: &[Declaration] = declare_properties! {
     "E" => float(consts::E; DONT_ENUM | DONT_DELETE | READ_ONLY);
     "LN10" => float(consts::LN_10; DONT_ENUM | DONT_DELETE | READ_ONLY);
@@ -28,18 +49,18 @@ const OBJECT_DECLS: &[Declaration] = declare_properties! {
     "PI" => float(consts::PI; DONT_ENUM | DONT_DELETE | READ_ONLY);
     "SQRT1_2" => float(consts::FRAC_1_SQRT_2; DONT_ENUM | DONT_DELETE | READ_ONLY);
     "SQRT2" => float(consts::SQRT_2; DONT_ENUM | DONT_DELETE | READ_ONLY);
-    "abs" => method(wrap_std!(f64::abs); DONT_ENUM | DONT_DELETE | READ_ONLY);
-    "acos" => method(wrap_std!(f64::acos); DONT_ENUM | DONT_DELETE | READ_ONLY);
-    "asin" => method(wrap_std!(f64::asin); DONT_ENUM | DONT_DELETE | READ_ONLY);
-    "atan" => method(wrap_std!(f64::atan); DONT_ENUM | DONT_DELETE | READ_ONLY);
-    "ceil" => method(wrap_std!(f64::ceil); DONT_ENUM | DONT_DELETE | READ_ONLY);
-    "cos" => method(wrap_std!(f64::cos); DONT_ENUM | DONT_DELETE | READ_ONLY);
-    "exp" => method(wrap_std!(f64::exp); DONT_ENUM | DONT_DELETE | READ_ONLY);
-    "floor" => method(wrap_std!(f64::floor); DONT_ENUM | DONT_DELETE | READ_ONLY);
-    "sin" => method(wrap_std!(f64::sin); DONT_ENUM | DONT_DELETE | READ_ONLY);
-    "sqrt" => method(wrap_std!(f64::sqrt); DONT_ENUM | DONT_DELETE | READ_ONLY);
-    "tan" => method(wrap_std!(f64::tan); DONT_ENUM | DONT_DELETE | READ_ONLY);
-    "log" => method(wrap_std!(f64::ln); DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "abs" => method(abs; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "acos" => method(acos; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "asin" => method(asin; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "atan" => method(atan; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "ceil" => method(ceil; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "cos" => method(cos; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "exp" => method(exp; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "floor" => method(floor; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "sin" => method(sin; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "sqrt" => method(sqrt; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "tan" => method(tan; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "log" => method(log; DONT_ENUM | DONT_DELETE | READ_ONLY);
     "atan2" => method(atan2; DONT_ENUM | DONT_DELETE | READ_ONLY);
     "pow" => method(pow; DONT_ENUM | DONT_DELETE | READ_ONLY);
     "max" => method(max; DONT_ENUM | DONT_DELETE | READ_ONLY);
@@ -48,7 +69,7 @@ const OBJECT_DECLS: &[Declaration] = declare_properties! {
     "round" => method(round; DONT_ENUM | DONT_DELETE | READ_ONLY);
 };
 
-fn atan2<'gc>(
+pub fn atan2<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     _this: Object<'gc>,
     args: &[Value<'gc>],
@@ -66,7 +87,7 @@ fn atan2<'gc>(
     Ok(f64::NAN.into())
 }
 
-fn pow<'gc>(
+pub fn pow<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     _this: Object<'gc>,
     args: &[Value<'gc>],
@@ -83,7 +104,7 @@ fn pow<'gc>(
     Ok(f64::NAN.into())
 }
 
-fn round<'gc>(
+pub fn round<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     _this: Object<'gc>,
     args: &[Value<'gc>],
@@ -98,7 +119,7 @@ fn round<'gc>(
     Ok(f64::NAN.into())
 }
 
-fn max<'gc>(
+pub fn max<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     _this: Object<'gc>,
     args: &[Value<'gc>],
@@ -123,7 +144,7 @@ fn max<'gc>(
     Ok(result.into())
 }
 
-fn min<'gc>(
+pub fn min<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     _this: Object<'gc>,
     args: &[Value<'gc>],
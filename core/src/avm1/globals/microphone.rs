@@ -0,0 +1,242 @@
+//! Microphone object
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::function::{Executable, FunctionObject};
+use crate::avm1::property_decl::{define_properties_on, Declaration};
+use crate::avm1::{ArrayObject, Object, ScriptObject, TObject, Value};
+use crate::microphone::Microphone;
+use gc_arena::MutationContext;
+
+const PROTO_DECLS: &[Declaration] = declare_properties! {
+    "activityLevel" => property(activity_level; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "gain" => property(gain; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "index" => property(index; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "muted" => property(muted; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "name" => property(name; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "rate" => property(rate; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "silenceLevel" => property(silence_level; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "silenceTimeout" => property(silence_timeout; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "useEchoSuppression" => property(use_echo_suppression; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "setGain" => method(set_gain; DONT_ENUM | DONT_DELETE);
+    "setRate" => method(set_rate; DONT_ENUM | DONT_DELETE);
+    "setSilenceLevel" => method(set_silence_level; DONT_ENUM | DONT_DELETE);
+    "setUseEchoSuppression" => method(set_use_echo_suppression; DONT_ENUM | DONT_DELETE);
+};
+
+const OBJECT_DECLS: &[Declaration] = declare_properties! {
+    "get" => method(get; DONT_ENUM | DONT_DELETE);
+    "names" => property(names; DONT_ENUM | DONT_DELETE | READ_ONLY);
+};
+
+/// Implements `Microphone`
+pub fn constructor<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let object = ScriptObject::object(gc_context, Some(proto));
+    define_properties_on(PROTO_DECLS, gc_context, object, fn_proto);
+    object.into()
+}
+
+pub fn create_microphone_object<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    microphone_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let microphone = FunctionObject::constructor(
+        gc_context,
+        Executable::Native(constructor),
+        constructor_to_fn!(constructor),
+        Some(fn_proto),
+        microphone_proto,
+    );
+    let object = microphone.as_script_object().unwrap();
+    define_properties_on(OBJECT_DECLS, gc_context, object, fn_proto);
+    microphone
+}
+
+/// Implements `Microphone.get`
+fn get<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let index = match args.get(0) {
+        None | Some(Value::Undefined) | Some(Value::Null) => 0,
+        Some(index) => index.coerce_to_i32(activation)?,
+    };
+    if index > 0 {
+        return Ok(Value::Null);
+    }
+
+    let microphone = match activation.context.microphone.avm1_object() {
+        Some(microphone) => microphone,
+        None => {
+            let microphone: Object<'gc> = ScriptObject::object(
+                activation.context.gc_context,
+                Some(activation.context.avm1.prototypes().microphone),
+            )
+            .into();
+            activation.context.microphone.set_avm1_object(microphone);
+            microphone
+        }
+    };
+
+    Microphone::start(&mut activation.context);
+
+    Ok(microphone.into())
+}
+
+fn names<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(ArrayObject::new(
+        activation.context.gc_context,
+        activation.context.avm1.prototypes().array,
+        [Microphone::NAME.into()],
+    )
+    .into())
+}
+
+fn activity_level<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(activation.context.microphone.activity_level().into())
+}
+
+fn gain<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(activation.context.microphone.gain().into())
+}
+
+fn index<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(0.into())
+}
+
+fn muted<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(activation.context.microphone.muted().into())
+}
+
+fn name<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Microphone::NAME.into())
+}
+
+fn rate<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(activation.context.microphone.rate().into())
+}
+
+fn silence_level<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(activation.context.microphone.silence_level().into())
+}
+
+fn silence_timeout<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(activation.context.microphone.silence_timeout().into())
+}
+
+fn use_echo_suppression<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(activation.context.microphone.use_echo_suppression().into())
+}
+
+fn set_gain<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(gain) = args.get(0) {
+        let gain = gain.coerce_to_f64(activation)?;
+        activation.context.microphone.set_gain(gain);
+    }
+    Ok(Value::Undefined)
+}
+
+fn set_rate<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(rate) = args.get(0) {
+        let rate = rate.coerce_to_u32(activation)?;
+        Microphone::set_rate(&mut activation.context, rate);
+    }
+    Ok(Value::Undefined)
+}
+
+fn set_silence_level<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(silence_level) = args.get(0) {
+        let silence_level = silence_level.coerce_to_f64(activation)?;
+        let silence_timeout = match args.get(1) {
+            Some(timeout) => Some(timeout.coerce_to_i32(activation)?),
+            None => None,
+        };
+        activation
+            .context
+            .microphone
+            .set_silence_level(silence_level, silence_timeout);
+    }
+    Ok(Value::Undefined)
+}
+
+fn set_use_echo_suppression<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let use_echo_suppression = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .as_bool(activation.swf_version());
+    activation
+        .context
+        .microphone
+        .set_use_echo_suppression(use_echo_suppression);
+    Ok(Value::Undefined)
+}
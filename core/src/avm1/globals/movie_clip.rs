@@ -98,10 +98,16 @@ const PROTO_DECLS: &[Declaration] = declare_properties! {
     "curveTo" => method(mc_method!(curve_to); DONT_ENUM | DONT_DELETE | READ_ONLY);
     "endFill" => method(mc_method!(end_fill); DONT_ENUM | DONT_DELETE | READ_ONLY);
     "lineStyle" => method(mc_method!(line_style); DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "lineGradientStyle" => method(mc_method!(line_gradient_style); DONT_ENUM | DONT_DELETE | READ_ONLY);
     "clear" => method(mc_method!(clear); DONT_ENUM | DONT_DELETE | READ_ONLY);
     "attachBitmap" => method(mc_method!(attach_bitmap); DONT_ENUM | DONT_DELETE | READ_ONLY);
     "removeMovieClip" => method(remove_movie_clip; DONT_ENUM | DONT_DELETE | READ_ONLY);
     "transform" => property(mc_getter!(transform), mc_setter!(set_transform); DONT_DELETE | DONT_ENUM);
+    "filters" => property(mc_getter!(filters), mc_setter!(set_filters); DONT_DELETE | DONT_ENUM);
+    "cacheAsBitmap" => property(mc_getter!(cache_as_bitmap), mc_setter!(set_cache_as_bitmap); DONT_DELETE | DONT_ENUM);
+    "blendMode" => property(mc_getter!(blend_mode), mc_setter!(set_blend_mode); DONT_DELETE | DONT_ENUM);
+    "scrollRect" => property(mc_getter!(scroll_rect), mc_setter!(set_scroll_rect); DONT_DELETE | DONT_ENUM);
+    "scale9Grid" => property(mc_getter!(scale_9_grid), mc_setter!(set_scale_9_grid); DONT_DELETE | DONT_ENUM);
     "enabled" => property(mc_getter!(enabled), mc_setter!(set_enabled); DONT_DELETE | DONT_ENUM);
     "focusEnabled" => property(mc_getter!(focus_enabled), mc_setter!(set_focus_enabled); DONT_DELETE | DONT_ENUM);
     "_lockroot" => property(mc_getter!(lock_root), mc_setter!(set_lock_root); DONT_DELETE | DONT_ENUM);
@@ -262,10 +268,10 @@ fn line_style<'gc>(
             .and_then(|v| v.coerce_to_string(activation).ok())
             .as_deref()
         {
-            Some(v) if v == b"normal" => (true, true),
+            Some(v) if v == b"none" => (false, false),
             Some(v) if v == b"vertical" => (true, false),
             Some(v) if v == b"horizontal" => (false, true),
-            _ => (false, false),
+            _ => (true, true),
         };
         let cap_style = match args
             .get(5)
@@ -420,6 +426,35 @@ fn begin_gradient_fill<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
+    let style = gradient_style("beginGradientFill", activation, args)?;
+    movie_clip
+        .as_drawing(activation.context.gc_context)
+        .unwrap()
+        .set_fill_style(style);
+    Ok(Value::Undefined)
+}
+
+fn line_gradient_style<'gc>(
+    movie_clip: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let style = gradient_style("lineGradientStyle", activation, args)?;
+    movie_clip
+        .as_drawing(activation.context.gc_context)
+        .unwrap()
+        .set_line_fill_style(style);
+    Ok(Value::Undefined)
+}
+
+/// Reads the gradient given to `beginGradientFill` or `lineGradientStyle`.
+///
+/// Returns `None` if the arguments don't describe a valid gradient.
+fn gradient_style<'gc>(
+    method_name: &str,
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Option<FillStyle>, Error<'gc>> {
     if let (Some(method), Some(colors), Some(alphas), Some(ratios), Some(matrix)) = (
         args.get(0),
         args.get(1),
@@ -438,9 +473,10 @@ fn begin_gradient_fill<'gc>(
         if colors_length != alphas_length || colors_length != ratios_length {
             avm_warn!(
                 activation,
-                "beginGradientFill() received different sized arrays for colors, alphas and ratios"
+                "{}() received different sized arrays for colors, alphas and ratios",
+                method_name
             );
-            return Ok(Value::Undefined);
+            return Ok(None);
         }
         let records: Result<Vec<_>, Error<'gc>> = (0..colors_length)
             .map(|i| {
@@ -501,22 +537,16 @@ fn begin_gradient_fill<'gc>(
         } else {
             avm_warn!(
                 activation,
-                "beginGradientFill() received invalid fill type {:?}",
+                "{}() received invalid fill type {:?}",
+                method_name,
                 method
             );
-            return Ok(Value::Undefined);
+            return Ok(None);
         };
-        movie_clip
-            .as_drawing(activation.context.gc_context)
-            .unwrap()
-            .set_fill_style(Some(style));
+        Ok(Some(style))
     } else {
-        movie_clip
-            .as_drawing(activation.context.gc_context)
-            .unwrap()
-            .set_fill_style(None);
+        Ok(None)
     }
-    Ok(Value::Undefined)
 }
 
 fn move_to<'gc>(
@@ -1108,7 +1138,7 @@ fn stop_drag<'gc>(
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     // It doesn't matter which clip we call this on; it simply stops any active drag.
-    *activation.context.drag_object = None;
+    crate::avm1::stop_drag(activation);
     Ok(Value::Undefined)
 }
 
@@ -1206,6 +1236,26 @@ fn get_bounds<'gc>(
     movie_clip: MovieClip<'gc>,
     activation: &mut Activation<'_, 'gc, '_>,
     args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    bounds_object(movie_clip, activation, args, movie_clip.bounds())
+}
+
+fn get_rect<'gc>(
+    movie_clip: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    // Like `getBounds`, but ignoring strokes. Always equal to or smaller than `getBounds`.
+    bounds_object(movie_clip, activation, args, movie_clip.rect_bounds())
+}
+
+/// Creates the object returned by `getBounds` and `getRect`, with the given
+/// bounds transformed into the coordinate space of the target in `args`.
+fn bounds_object<'gc>(
+    movie_clip: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+    bounds: BoundingBox,
 ) -> Result<Value<'gc>, Error<'gc>> {
     let target = match args.get(0) {
         Some(Value::String(s)) if s.is_empty() => None,
@@ -1218,20 +1268,7 @@ fn get_bounds<'gc>(
     };
 
     if let Some(target) = target {
-        let bounds = movie_clip.bounds();
-        let out_bounds = if DisplayObject::ptr_eq(movie_clip.into(), target) {
-            // Getting the clips bounds in its own coordinate space; no AABB transform needed.
-            bounds
-        } else {
-            // Transform AABB to target space.
-            // Calculate the matrix to transform into the target coordinate space, and transform the above AABB.
-            // Note that this doesn't produce as tight of an AABB as if we had used `bounds_with_transform` with
-            // the final matrix, but this matches Flash's behavior.
-            let to_global_matrix = movie_clip.local_to_global_matrix();
-            let to_target_matrix = target.global_to_local_matrix();
-            let bounds_transform = to_target_matrix * to_global_matrix;
-            bounds.transform(&bounds_transform)
-        };
+        let out_bounds = movie_clip.bounds_in_space(bounds, target);
 
         let out = ScriptObject::object(
             activation.context.gc_context,
@@ -1247,16 +1284,6 @@ fn get_bounds<'gc>(
     }
 }
 
-fn get_rect<'gc>(
-    movie_clip: MovieClip<'gc>,
-    activation: &mut Activation<'_, 'gc, '_>,
-    args: &[Value<'gc>],
-) -> Result<Value<'gc>, Error<'gc>> {
-    // TODO: This should get the bounds ignoring strokes. Always equal to or smaller than getBounds.
-    // Just defer to getBounds for now. Will have to store edge_bounds vs. shape_bounds in Graphic.
-    get_bounds(movie_clip, activation, args)
-}
-
 #[allow(unused_must_use)] //can't use errors yet
 pub fn get_url<'gc>(
     _movie_clip: MovieClip<'gc>,
@@ -1348,6 +1375,7 @@ fn load_movie<'gc>(
         activation.context.player.clone().unwrap(),
         DisplayObject::MovieClip(target),
         fetch,
+        None,
         url.to_string(),
         None,
         None,
@@ -1392,6 +1420,145 @@ fn unload_movie<'gc>(
     Ok(Value::Undefined)
 }
 
+fn filters<'gc>(
+    this: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    display_object::filters(this.into(), activation)
+}
+
+fn set_filters<'gc>(
+    this: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    display_object::set_filters(this.into(), activation, value)
+}
+
+fn blend_mode<'gc>(
+    this: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    display_object::blend_mode(this.into(), activation)
+}
+
+fn set_blend_mode<'gc>(
+    this: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    display_object::set_blend_mode(this.into(), activation, value)
+}
+
+fn cache_as_bitmap<'gc>(
+    this: MovieClip<'gc>,
+    _activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.cache_as_bitmap().into())
+}
+
+fn set_cache_as_bitmap<'gc>(
+    this: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    let cache_as_bitmap = value.as_bool(activation.swf_version());
+    this.set_cache_as_bitmap(activation.context.gc_context, cache_as_bitmap);
+    Ok(())
+}
+
+fn scroll_rect<'gc>(
+    this: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(scroll_rect) = this.scroll_rect() {
+        let constructor = activation.context.avm1.prototypes.rectangle_constructor;
+        let result = constructor.construct(
+            activation,
+            &[
+                scroll_rect.x_min.to_pixels().into(),
+                scroll_rect.y_min.to_pixels().into(),
+                scroll_rect.width().to_pixels().into(),
+                scroll_rect.height().to_pixels().into(),
+            ],
+        )?;
+        Ok(result)
+    } else {
+        Ok(Value::Undefined)
+    }
+}
+
+fn set_scroll_rect<'gc>(
+    this: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    // Setting anything other than a rectangle removes the `scrollRect`.
+    let scroll_rect = if let Value::Object(rect) = value {
+        let x = rect.get("x", activation)?.coerce_to_f64(activation)?;
+        let y = rect.get("y", activation)?.coerce_to_f64(activation)?;
+        let width = rect.get("width", activation)?.coerce_to_f64(activation)?;
+        let height = rect.get("height", activation)?.coerce_to_f64(activation)?;
+        Some(BoundingBox {
+            x_min: Twips::from_pixels(x),
+            y_min: Twips::from_pixels(y),
+            x_max: Twips::from_pixels(x + width),
+            y_max: Twips::from_pixels(y + height),
+            valid: true,
+        })
+    } else {
+        None
+    };
+    this.set_scroll_rect(activation.context.gc_context, scroll_rect);
+    Ok(())
+}
+
+fn scale_9_grid<'gc>(
+    this: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(grid) = this.scaling_grid() {
+        let constructor = activation.context.avm1.prototypes.rectangle_constructor;
+        let result = constructor.construct(
+            activation,
+            &[
+                grid.x_min.to_pixels().into(),
+                grid.y_min.to_pixels().into(),
+                grid.width().to_pixels().into(),
+                grid.height().to_pixels().into(),
+            ],
+        )?;
+        Ok(result)
+    } else {
+        Ok(Value::Undefined)
+    }
+}
+
+fn set_scale_9_grid<'gc>(
+    this: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    // Setting anything other than a rectangle removes the `scale9Grid`.
+    let grid = if let Value::Object(rect) = value {
+        let x = rect.get("x", activation)?.coerce_to_f64(activation)?;
+        let y = rect.get("y", activation)?.coerce_to_f64(activation)?;
+        let width = rect.get("width", activation)?.coerce_to_f64(activation)?;
+        let height = rect.get("height", activation)?.coerce_to_f64(activation)?;
+        Some(BoundingBox {
+            x_min: Twips::from_pixels(x),
+            y_min: Twips::from_pixels(y),
+            x_max: Twips::from_pixels(x + width),
+            y_max: Twips::from_pixels(y + height),
+            valid: true,
+        })
+    } else {
+        None
+    };
+    this.set_scaling_grid(activation.context.gc_context, grid);
+    Ok(())
+}
+
 fn transform<'gc>(
     this: MovieClip<'gc>,
     activation: &mut Activation<'_, 'gc, '_>,
@@ -8,7 +8,7 @@ use crate::avm1::object::TObject;
 use crate::avm1::property::Attribute;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::{ArrayObject, Object, Value};
-use crate::backend::navigator::RequestOptions;
+use crate::backend::navigator::{FetchProgress, RequestOptions};
 use crate::display_object::{DisplayObject, TDisplayObject};
 use gc_arena::MutationContext;
 
@@ -46,26 +46,24 @@ pub fn load_clip<'gc>(
     let url = url_val.coerce_to_string(activation)?;
     let target = args.get(1).cloned().unwrap_or(Value::Undefined);
 
-    if let Value::Object(target) = target {
-        if let Some(mc) = target
-            .as_display_object()
-            .and_then(|dobj| dobj.as_movie_clip())
-        {
-            let fetch = activation
-                .context
-                .navigator
-                .fetch(&url.to_utf8_lossy(), RequestOptions::get());
-            let process = activation.context.load_manager.load_movie_into_clip(
-                activation.context.player.clone().unwrap(),
-                DisplayObject::MovieClip(mc),
-                fetch,
-                url.to_string(),
-                None,
-                Some(this),
-            );
-
-            activation.context.navigator.spawn_future(process);
-        }
+    if let Some(mc) = resolve_target(activation, target)? {
+        let progress = FetchProgress::new();
+        let fetch = activation.context.navigator.fetch_with_progress(
+            &url.to_utf8_lossy(),
+            RequestOptions::get(),
+            progress.clone(),
+        );
+        let process = activation.context.load_manager.load_movie_into_clip(
+            activation.context.player.clone().unwrap(),
+            mc,
+            fetch,
+            Some(progress),
+            url.to_string(),
+            None,
+            Some(this),
+        );
+
+        activation.context.navigator.spawn_future(process);
 
         Ok(true.into())
     } else {
@@ -80,16 +78,15 @@ pub fn unload_clip<'gc>(
 ) -> Result<Value<'gc>, Error<'gc>> {
     let target = args.get(0).cloned().unwrap_or(Value::Undefined);
 
-    if let Value::Object(target) = target {
-        if let Some(mut mc) = target
-            .as_display_object()
-            .and_then(|dobj| dobj.as_movie_clip())
-        {
-            mc.unload(&mut activation.context);
-            mc.replace_with_movie(activation.context.gc_context, None);
+    if let Some(target) = resolve_target(activation, target)? {
+        // A load that is still in progress won't replace the clip anymore.
+        activation.context.load_manager.cancel_movie_loads(target);
 
-            return Ok(true.into());
-        }
+        let mut mc = target.as_movie_clip().unwrap();
+        mc.unload(&mut activation.context);
+        mc.replace_with_movie(activation.context.gc_context, None);
+
+        return Ok(true.into());
     }
 
     Ok(false.into())
@@ -102,36 +99,59 @@ pub fn get_progress<'gc>(
 ) -> Result<Value<'gc>, Error<'gc>> {
     let target = args.get(0).cloned().unwrap_or(Value::Undefined);
 
-    if let Value::Object(target) = target {
-        if let Some(mc) = target
-            .as_display_object()
-            .and_then(|dobj| dobj.as_movie_clip())
-        {
-            let ret_obj = ScriptObject::object(activation.context.gc_context, None);
-            ret_obj.define_value(
-                activation.context.gc_context,
-                "bytesLoaded",
-                mc.movie()
-                    .map(|mv| (mv.uncompressed_len()).into())
-                    .unwrap_or(Value::Undefined),
-                Attribute::empty(),
-            );
-            ret_obj.define_value(
-                activation.context.gc_context,
-                "bytesTotal",
-                mc.movie()
-                    .map(|mv| (mv.uncompressed_len()).into())
-                    .unwrap_or(Value::Undefined),
-                Attribute::empty(),
-            );
-
-            return Ok(ret_obj.into());
-        }
+    if let Some(mc) = resolve_target(activation, target)? {
+        // Clips that are being loaded into report the progress of the load,
+        // rather than the size of the movie they are about to lose.
+        let (bytes_loaded, bytes_total) =
+            match activation.context.load_manager.movie_load_progress(mc) {
+                Some((bytes_loaded, bytes_total)) => (bytes_loaded.into(), bytes_total.into()),
+                None => mc
+                    .movie()
+                    .map_or((Value::Undefined, Value::Undefined), |mv| {
+                        (mv.compressed_len().into(), mv.compressed_len().into())
+                    }),
+            };
+
+        let ret_obj = ScriptObject::object(
+            activation.context.gc_context,
+            Some(activation.context.avm1.prototypes().object),
+        );
+        ret_obj.define_value(
+            activation.context.gc_context,
+            "bytesLoaded",
+            bytes_loaded,
+            Attribute::empty(),
+        );
+        ret_obj.define_value(
+            activation.context.gc_context,
+            "bytesTotal",
+            bytes_total,
+            Attribute::empty(),
+        );
+
+        return Ok(ret_obj.into());
     }
 
     Ok(Value::Undefined)
 }
 
+/// Resolve the target of a `MovieClipLoader` or `PrintJob` method, which is
+/// either a movie clip, its path, or the number of a level.
+pub fn resolve_target<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    target: Value<'gc>,
+) -> Result<Option<DisplayObject<'gc>>, Error<'gc>> {
+    let target = match target {
+        Value::Number(level_id) => Some(activation.resolve_level(level_id as i32)),
+        Value::Object(_) | Value::String(_) => {
+            let start = activation.target_clip_or_root()?;
+            activation.resolve_target_display_object(start, target, false)?
+        }
+        _ => None,
+    };
+    Ok(target.filter(|target| target.as_movie_clip().is_some()))
+}
+
 pub fn create_proto<'gc>(
     gc_context: MutationContext<'gc, '_>,
     proto: Object<'gc>,
@@ -0,0 +1,122 @@
+//! NetConnection object
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::globals::shared_object::serialize_value;
+use crate::avm1::property::Attribute;
+use crate::avm1::property_decl::{define_properties_on, Declaration};
+use crate::avm1::{Object, ScriptObject, TObject, Value};
+use crate::avm_warn;
+use crate::streams::net_connection;
+use flash_lso::types::Value as AmfValue;
+use gc_arena::MutationContext;
+
+const PROTO_DECLS: &[Declaration] = declare_properties! {
+    "connect" => method(connect; DONT_ENUM | DONT_DELETE);
+    "close" => method(close; DONT_ENUM | DONT_DELETE);
+};
+
+/// Implements `NetConnection`
+pub fn constructor<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    set_connected(activation.context.gc_context, this, false);
+
+    Ok(this.into())
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let object = ScriptObject::object(gc_context, Some(proto));
+    define_properties_on(PROTO_DECLS, gc_context, object, fn_proto);
+    object.into()
+}
+
+/// Update the `isConnected` property of a `NetConnection`.
+pub fn set_connected<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    this: Object<'gc>,
+    connected: bool,
+) {
+    this.define_value(
+        gc_context,
+        "isConnected",
+        connected.into(),
+        Attribute::DONT_ENUM | Attribute::DONT_DELETE,
+    );
+}
+
+fn connect<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    // A `null` command "connects" for progressive downloads. Anything else
+    // has to be the URL of an RTMP server.
+    let command = args.get(0).unwrap_or(&Value::Undefined);
+    if !matches!(command, Value::Null | Value::Undefined) {
+        let url = command
+            .coerce_to_string(activation)?
+            .to_utf8_lossy()
+            .into_owned();
+        let arguments: Vec<AmfValue> = args[1..]
+            .iter()
+            .map(|arg| serialize_value(activation, *arg).unwrap_or(AmfValue::Undefined))
+            .collect();
+        set_connected(activation.context.gc_context, this, false);
+        // The outcome is reported to `onStatus` once the server responds.
+        if net_connection::connect(&mut activation.context, this, &url, &arguments) {
+            return Ok(true.into());
+        }
+        avm_warn!(
+            activation,
+            "NetConnection.connect: Can't connect to {}",
+            url
+        );
+        return report_connect(activation, this, false);
+    }
+
+    net_connection::close(&mut activation.context, this);
+    report_connect(activation, this, true)
+}
+
+/// Call `onStatus` with the outcome of a connection attempt that didn't
+/// involve a server.
+fn report_connect<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    success: bool,
+) -> Result<Value<'gc>, Error<'gc>> {
+    set_connected(activation.context.gc_context, this, success);
+
+    let info = ScriptObject::object(
+        activation.context.gc_context,
+        Some(activation.context.avm1.prototypes().object),
+    );
+    let (code, level) = if success {
+        ("NetConnection.Connect.Success", "status")
+    } else {
+        ("NetConnection.Connect.Failed", "error")
+    };
+    info.set("code", code.into(), activation)?;
+    info.set("level", level.into(), activation)?;
+    this.call_method("onStatus".into(), &[info.into()], activation)?;
+
+    Ok(success.into())
+}
+
+fn close<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    net_connection::close(&mut activation.context, this);
+    set_connected(activation.context.gc_context, this, false);
+
+    Ok(Value::Undefined)
+}
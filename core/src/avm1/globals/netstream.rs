@@ -0,0 +1,190 @@
+//! NetStream object
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::object::net_stream_object::NetStreamObject;
+use crate::avm1::property_decl::{define_properties_on, Declaration};
+use crate::avm1::{Object, TObject, Value};
+use crate::avm_warn;
+use crate::streams::{net_connection, NetStream};
+use gc_arena::MutationContext;
+
+const PROTO_DECLS: &[Declaration] = declare_properties! {
+    "play" => method(play; DONT_ENUM | DONT_DELETE);
+    "pause" => method(pause; DONT_ENUM | DONT_DELETE);
+    "seek" => method(seek; DONT_ENUM | DONT_DELETE);
+    "close" => method(close; DONT_ENUM | DONT_DELETE);
+    "setBufferTime" => method(set_buffer_time; DONT_ENUM | DONT_DELETE);
+    "bufferTime" => property(buffer_time; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "bufferLength" => property(buffer_length; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "time" => property(time; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "currentFps" => property(current_fps; DONT_ENUM | DONT_DELETE | READ_ONLY);
+};
+
+/// Implements `NetStream`
+pub fn constructor<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(net_stream) = net_stream(this) {
+        net_stream.set_avm_object(activation.context.gc_context, this);
+        // The `NetConnection` argument only matters for streaming from a server.
+        if let Some(Value::Object(connection)) = args.get(0) {
+            net_connection::attach_stream(&mut activation.context, *connection, net_stream);
+        }
+    } else {
+        log::error!("Tried to construct a NetStream on a non-NetStreamObject");
+    }
+
+    Ok(this.into())
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let net_stream = NetStreamObject::empty_net_stream(gc_context, Some(proto));
+    let object = net_stream.as_script_object().unwrap();
+    define_properties_on(PROTO_DECLS, gc_context, object, fn_proto);
+    net_stream.into()
+}
+
+fn net_stream(this: Object<'_>) -> Option<NetStream<'_>> {
+    this.as_net_stream_object()
+        .map(|object| object.net_stream())
+}
+
+fn play<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(net_stream) = net_stream(this) {
+        let url = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_string(activation)?;
+        let url = url.to_utf8_lossy();
+        if !net_connection::play(&mut activation.context, net_stream, &url) {
+            net_stream.play(&mut activation.context, &url);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+fn pause<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(net_stream) = net_stream(this) {
+        // Without an argument, playback is toggled.
+        let paused = match args.get(0) {
+            Some(Value::Undefined) | None => net_stream.is_playing(),
+            Some(value) => value.as_bool(activation.swf_version()),
+        };
+        if paused {
+            net_stream.pause(&mut activation.context);
+        } else {
+            net_stream.resume(&mut activation.context);
+        }
+        net_connection::pause(&mut activation.context, net_stream, paused);
+    }
+
+    Ok(Value::Undefined)
+}
+
+fn seek<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(net_stream) = net_stream(this) {
+        let offset = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_f64(activation)?;
+        if offset.is_finite() {
+            let offset = offset.max(0.0);
+            if !net_connection::seek(&mut activation.context, net_stream, offset) {
+                net_stream.seek(&mut activation.context, offset);
+            }
+        } else {
+            avm_warn!(activation, "NetStream.seek: Invalid offset {}", offset);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+fn close<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(net_stream) = net_stream(this) {
+        net_connection::close_stream(&mut activation.context, net_stream);
+        net_stream.close(&mut activation.context);
+    }
+
+    Ok(Value::Undefined)
+}
+
+fn set_buffer_time<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(net_stream) = net_stream(this) {
+        let buffer_time = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_f64(activation)?;
+        if buffer_time.is_finite() {
+            net_stream.set_buffer_time(activation.context.gc_context, buffer_time);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+fn buffer_time<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(net_stream(this).map_or(Value::Undefined, |net_stream| {
+        net_stream.buffer_time().into()
+    }))
+}
+
+fn buffer_length<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(net_stream(this).map_or(Value::Undefined, |net_stream| {
+        net_stream.buffer_length().into()
+    }))
+}
+
+fn time<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(net_stream(this).map_or(Value::Undefined, |net_stream| net_stream.time().into()))
+}
+
+fn current_fps<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(net_stream(this).map_or(Value::Undefined, |net_stream| {
+        net_stream.current_fps().into()
+    }))
+}
@@ -89,7 +89,7 @@ pub fn create_proto<'gc>(
     number_proto
 }
 
-fn to_string<'gc>(
+pub fn to_string<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
     args: &[Value<'gc>],
@@ -163,7 +163,7 @@ fn to_string<'gc>(
     }
 }
 
-fn value_of<'gc>(
+pub fn value_of<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
     _args: &[Value<'gc>],
@@ -108,7 +108,7 @@ pub fn has_own_property<'gc>(
 }
 
 /// Implements `Object.prototype.toString`
-fn to_string<'gc>(
+pub fn to_string<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
     _: Object<'gc>,
     _: &[Value<'gc>],
@@ -117,7 +117,7 @@ fn to_string<'gc>(
 }
 
 /// Implements `Object.prototype.isPropertyEnumerable`
-fn is_property_enumerable<'gc>(
+pub fn is_property_enumerable<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
     args: &[Value<'gc>],
@@ -132,7 +132,7 @@ fn is_property_enumerable<'gc>(
 }
 
 /// Implements `Object.prototype.isPrototypeOf`
-fn is_prototype_of<'gc>(
+pub fn is_prototype_of<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
     args: &[Value<'gc>],
@@ -147,7 +147,7 @@ fn is_prototype_of<'gc>(
 }
 
 /// Implements `Object.prototype.valueOf`
-fn value_of<'gc>(
+pub fn value_of<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
     _: &[Value<'gc>],
@@ -193,7 +193,7 @@ pub fn register_class<'gc>(
 }
 
 /// Implements `Object.prototype.watch`
-fn watch<'gc>(
+pub fn watch<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
     args: &[Value<'gc>],
@@ -218,7 +218,7 @@ fn watch<'gc>(
 }
 
 /// Implements `Object.prototype.unmwatch`
-fn unwatch<'gc>(
+pub fn unwatch<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
     args: &[Value<'gc>],
@@ -272,16 +272,17 @@ pub fn as_set_prop_flags<'gc>(
         return Ok(Value::Undefined);
     };
 
-    let set_flags = args.get(2).unwrap_or(&0.into()).coerce_to_f64(activation)? as u8;
+    let set_flags = args.get(2).unwrap_or(&0.into()).coerce_to_i32(activation)? as u16;
     let set_attributes = Attribute::from_bits_truncate(set_flags);
 
-    let clear_flags = args.get(3).unwrap_or(&0.into()).coerce_to_f64(activation)? as u8;
+    let clear_flags = args.get(3).unwrap_or(&0.into()).coerce_to_i32(activation)? as u16;
     let clear_attributes = Attribute::from_bits_truncate(clear_flags);
 
     if set_attributes.bits() != set_flags || clear_attributes.bits() != clear_flags {
         avm_warn!(
             activation,
-            "ASSetPropFlags: Unimplemented support for flags > 7"
+            "ASSetPropFlags: Unimplemented support for flags {:#x}",
+            (set_flags & !set_attributes.bits()) | (clear_flags & !clear_attributes.bits())
         );
     }
 
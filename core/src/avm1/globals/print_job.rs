@@ -0,0 +1,274 @@
+//! PrintJob object
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::globals::movie_clip_loader::resolve_target;
+use crate::avm1::object::print_job_object::PrintJobObject;
+use crate::avm1::property_decl::{define_properties_on, Declaration};
+use crate::avm1::{Object, TObject, Value};
+use crate::backend::render::{Bitmap, Color};
+use crate::bounding_box::BoundingBox;
+use crate::context::RenderContext;
+use crate::display_object::{DisplayObject, TDisplayObject, MAX_BITMAP_CACHE_SIZE};
+use crate::matrix::Matrix;
+use crate::transform::{Transform, TransformStack};
+use gc_arena::MutationContext;
+use swf::Twips;
+
+const PROTO_DECLS: &[Declaration] = declare_properties! {
+    "start" => method(start; DONT_ENUM | DONT_DELETE);
+    "addPage" => method(add_page; DONT_ENUM | DONT_DELETE);
+    "send" => method(send; DONT_ENUM | DONT_DELETE);
+};
+
+/// The size of a US Letter page in points, which is what we report as the
+/// paper size, since we don't know the printer's settings.
+const PAPER_SIZE: (i32, i32) = (612, 792);
+
+/// The margins that are subtracted from the paper size to get the printable
+/// area of a page.
+const PAGE_MARGIN: i32 = 18;
+
+/// Implements `PrintJob`
+pub fn constructor<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.into())
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let print_job = PrintJobObject::empty_print_job(gc_context, Some(proto));
+    let object = print_job.as_script_object().unwrap();
+    define_properties_on(PROTO_DECLS, gc_context, object, fn_proto);
+    print_job.into()
+}
+
+fn start<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let print_job = match this.as_print_job_object() {
+        Some(print_job) => print_job,
+        None => return Ok(Value::Undefined),
+    };
+
+    // Only one job can be started at a time.
+    if print_job.started() {
+        return Ok(false.into());
+    }
+    print_job.set_started(activation.context.gc_context, true);
+
+    let (paper_width, paper_height) = PAPER_SIZE;
+    this.set("paperWidth", paper_width.into(), activation)?;
+    this.set("paperHeight", paper_height.into(), activation)?;
+    this.set(
+        "pageWidth",
+        (paper_width - 2 * PAGE_MARGIN).into(),
+        activation,
+    )?;
+    this.set(
+        "pageHeight",
+        (paper_height - 2 * PAGE_MARGIN).into(),
+        activation,
+    )?;
+    this.set("orientation", "portrait".into(), activation)?;
+    Ok(true.into())
+}
+
+fn add_page<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let print_job = match this.as_print_job_object() {
+        Some(print_job) if print_job.started() => print_job,
+        _ => return Ok(false.into()),
+    };
+
+    let target = args.get(0).cloned().unwrap_or(Value::Undefined);
+    let target = match resolve_target(activation, target)? {
+        Some(target) => target,
+        None => return Ok(false.into()),
+    };
+
+    let area = match args.get(1) {
+        Some(Value::Object(area)) => print_area(activation, *area)?,
+        _ => None,
+    };
+
+    let frame = match args.get(3) {
+        None | Some(Value::Undefined | Value::Null) => None,
+        Some(frame) => match frame_number(activation, target, *frame)? {
+            Some(frame) => Some(frame),
+            None => return Ok(false.into()),
+        },
+    };
+
+    let page = match (frame, target.as_movie_clip()) {
+        (Some(frame), Some(clip)) if frame != clip.current_frame() => {
+            // Show the frame just long enough to render it, without running
+            // any of the actions that the gotos would queue.
+            let current_frame = clip.current_frame();
+            let queued_actions = activation.context.action_queue.lengths();
+            clip.run_goto(&mut activation.context, frame, true);
+            let page = render_page(activation, target, area);
+            clip.run_goto(&mut activation.context, current_frame, true);
+            activation.context.action_queue.truncate(&queued_actions);
+            page
+        }
+        _ => render_page(activation, target, area),
+    };
+
+    match page {
+        Some(page) => {
+            print_job.add_page(activation.context.gc_context, page);
+            Ok(true.into())
+        }
+        None => {
+            avm_warn!(
+                activation,
+                "PrintJob.addPage: the renderer can't render pages to print"
+            );
+            Ok(false.into())
+        }
+    }
+}
+
+fn send<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(print_job) = this.as_print_job_object() {
+        if print_job.started() {
+            let pages = print_job.take_pages(activation.context.gc_context);
+            print_job.set_started(activation.context.gc_context, false);
+            if !pages.is_empty() {
+                activation.context.ui.print(pages);
+            }
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+/// Read a `printArea` object, with the `xMin`, `xMax`, `yMin` and `yMax`
+/// bounds in the target's coordinate space.
+fn print_area<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    area: Object<'gc>,
+) -> Result<Option<BoundingBox>, Error<'gc>> {
+    let mut bound = |name: &'static str| -> Result<f64, Error<'gc>> {
+        area.get(name, activation)?.coerce_to_f64(activation)
+    };
+    let x_min = bound("xMin")?;
+    let x_max = bound("xMax")?;
+    let y_min = bound("yMin")?;
+    let y_max = bound("yMax")?;
+    if !(x_min < x_max && y_min < y_max) {
+        return Ok(None);
+    }
+
+    Ok(Some(BoundingBox {
+        x_min: Twips::from_pixels(x_min),
+        y_min: Twips::from_pixels(y_min),
+        x_max: Twips::from_pixels(x_max),
+        y_max: Twips::from_pixels(y_max),
+        valid: true,
+    }))
+}
+
+/// Resolve the `frameNum` argument of `addPage`, which is either a 1-based
+/// frame number or a frame label of the target clip.
+fn frame_number<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    target: DisplayObject<'gc>,
+    frame: Value<'gc>,
+) -> Result<Option<u16>, Error<'gc>> {
+    let clip = match target.as_movie_clip() {
+        Some(clip) => clip,
+        None => return Ok(None),
+    };
+    let frame = match frame {
+        Value::Number(frame) => Some(frame),
+        frame => {
+            let label = frame.coerce_to_string(activation)?;
+            match clip.frame_label_to_number(&label) {
+                Some(frame) => Some(frame.into()),
+                None => label.to_string().parse().ok(),
+            }
+        }
+    };
+    Ok(frame
+        .filter(|frame| *frame >= 1.0)
+        .map(|frame| (frame as u16).min(clip.total_frames())))
+}
+
+/// Render the given area of a clip to a bitmap, on a white background.
+///
+/// If no area is given, the bounds of the clip are used, or the stage if it
+/// has none. Pages are scaled down to fit within the largest offscreen image
+/// that we render.
+fn render_page<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    target: DisplayObject<'gc>,
+    area: Option<BoundingBox>,
+) -> Option<Bitmap> {
+    let area = area.unwrap_or_else(|| {
+        let bounds = target.bounds_with_transform(&Matrix::IDENTITY);
+        if bounds.valid {
+            bounds
+        } else {
+            let (width, height) = activation.context.stage.stage_size();
+            BoundingBox {
+                x_min: Twips::ZERO,
+                y_min: Twips::ZERO,
+                x_max: Twips::from_pixels(width.into()),
+                y_max: Twips::from_pixels(height.into()),
+                valid: true,
+            }
+        }
+    });
+
+    let area_width = (area.x_max - area.x_min).to_pixels();
+    let area_height = (area.y_max - area.y_min).to_pixels();
+    let scale = (f64::from(MAX_BITMAP_CACHE_SIZE) / area_width.max(area_height)).min(1.0);
+    let size = |length: f64| ((length * scale).ceil() as u32).clamp(1, MAX_BITMAP_CACHE_SIZE);
+    let (width, height) = (size(area_width), size(area_height));
+
+    let context = &mut activation.context;
+    let ui = &mut *context.ui;
+    let library = &*context.library;
+    let stage = context.stage;
+    let matrix = Matrix::scale(scale as f32, scale as f32)
+        * Matrix::translate(Twips::ZERO - area.x_min, Twips::ZERO - area.y_min);
+    context.renderer.render_offscreen(
+        width,
+        height,
+        Color::from_rgb(0xFFFFFF, 255),
+        &mut |renderer| {
+            let mut transform_stack = TransformStack::new();
+            transform_stack.push(&Transform {
+                matrix,
+                ..Default::default()
+            });
+            let mut render_context = RenderContext {
+                renderer,
+                ui: &mut *ui,
+                library,
+                transform_stack: &mut transform_stack,
+                stage,
+                clip_depth_stack: vec![],
+                allow_mask: true,
+                scaling_grid: None,
+            };
+            target.render_self(&mut render_context);
+        },
+    )
+}
@@ -125,8 +125,11 @@ pub fn set_focus<'gc>(
             tracker.set(None, &mut activation.context);
             Ok(true.into())
         }
-        Some(Value::Object(obj)) => {
-            if let Some(display_object) = obj.as_display_object() {
+        Some(value @ (Value::Object(_) | Value::String(_))) => {
+            // Text fields can also be given by their path.
+            let start = activation.target_clip_or_root()?;
+            let target = activation.resolve_target_display_object(start, *value, false)?;
+            if let Some(display_object) = target {
                 if display_object.is_focusable() {
                     tracker.set(Some(display_object), &mut activation.context);
                 }
@@ -1,5 +1,3 @@
-use std::borrow::Cow;
-
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::function::{Executable, FunctionObject};
@@ -56,7 +54,7 @@ pub fn get_disk_usage<'gc>(
 }
 
 /// Serialize a Value to an AmfValue
-fn serialize_value<'gc>(
+pub fn serialize_value<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     elem: Value<'gc>,
 ) -> Option<AmfValue> {
@@ -114,7 +112,10 @@ fn recursive_serialize<'gc>(
 }
 
 /// Deserialize a AmfValue to a Value
-fn deserialize_value<'gc>(activation: &mut Activation<'_, 'gc, '_>, val: &AmfValue) -> Value<'gc> {
+pub fn deserialize_value<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    val: &AmfValue,
+) -> Value<'gc> {
     match val {
         AmfValue::Null => Value::Null,
         AmfValue::Undefined => Value::Undefined,
@@ -299,39 +300,31 @@ fn deserialize_array_json<'gc>(
     }
 }
 
-pub fn get_local<'gc>(
-    activation: &mut Activation<'_, 'gc, '_>,
-    _this: Object<'gc>,
-    args: &[Value<'gc>],
-) -> Result<Value<'gc>, Error<'gc>> {
+/// Determines the name that a local shared object is stored under, such as
+/// `foo.com/folder/game.swf/SOName`.
+///
+/// Returns `None` if the object can't be accessed from the movie at `movie_url`.
+pub(crate) fn local_shared_object_name(
+    movie_url: Option<&str>,
+    name: &str,
+    local_path: Option<&str>,
+    secure: bool,
+) -> Option<String> {
     // TODO: It appears that Flash does some kind of escaping here:
     // the name "foo\uD800" correspond to a file named "fooE#FB#FB#D.sol".
 
-    let name = args
-        .get(0)
-        .unwrap_or(&Value::Undefined)
-        .coerce_to_string(activation)?;
-    let name = name.to_utf8_lossy();
-
     const INVALID_CHARS: &str = "~%&\\;:\"',<>?# ";
     if name.contains(|c| INVALID_CHARS.contains(c)) {
         log::error!("SharedObject::get_local: Invalid character in name");
-        return Ok(Value::Null);
+        return None;
     }
 
-    let movie = if let Some(movie) = activation.base_clip().movie() {
-        movie
-    } else {
-        log::error!("SharedObject::get_local: Movie was None");
-        return Ok(Value::Null);
-    };
-
-    let mut movie_url = if let Some(url) = movie.url() {
+    let mut movie_url = if let Some(url) = movie_url {
         if let Ok(url) = url::Url::parse(url) {
             url
         } else {
             log::error!("SharedObject::get_local: Unable to parse movie URL");
-            return Ok(Value::Null);
+            return None;
         }
     } else {
         // No URL (loading local data). Use a dummy URL to allow SharedObjects to work.
@@ -340,17 +333,12 @@ pub fn get_local<'gc>(
     movie_url.set_query(None);
     movie_url.set_fragment(None);
 
-    let secure = args
-        .get(2)
-        .unwrap_or(&Value::Undefined)
-        .as_bool(activation.swf_version());
-
     // Secure parameter disallows using the shared object from non-HTTPS.
     if secure && movie_url.scheme() != "https" {
         log::warn!(
             "SharedObject.get_local: Tried to load a secure shared object from non-HTTPS origin"
         );
-        return Ok(Value::Null);
+        return None;
     }
 
     // Shared objects are sandboxed per-domain.
@@ -370,33 +358,18 @@ pub fn get_local<'gc>(
         movie_url.host_str().unwrap_or_default()
     };
 
-    let local_path = if let Some(Value::String(local_path)) = args.get(1) {
+    let local_path = if let Some(local_path) = local_path {
         // Empty local path always fails.
         if local_path.is_empty() {
-            return Ok(Value::Null);
+            return None;
         }
 
         // Remove leading/trailing slashes.
-        let mut local_path = local_path.to_utf8_lossy();
-        if local_path.ends_with('/') {
-            match &mut local_path {
-                Cow::Owned(p) => {
-                    p.pop();
-                }
-                Cow::Borrowed(p) => *p = &p[..p.len() - 1],
-            }
-        }
-        if local_path.starts_with('/') {
-            match &mut local_path {
-                Cow::Owned(p) => {
-                    p.remove(0);
-                }
-                Cow::Borrowed(p) => *p = &p[1..],
-            }
-        }
+        let local_path = local_path.strip_suffix('/').unwrap_or(local_path);
+        let local_path = local_path.strip_prefix('/').unwrap_or(local_path);
 
         // Verify that local_path is a prefix of the SWF path.
-        if movie_path.starts_with(local_path.as_ref())
+        if movie_path.starts_with(local_path)
             && (local_path.is_empty()
                 || movie_path.len() == local_path.len()
                 || movie_path[local_path.len()..].starts_with('/'))
@@ -404,10 +377,10 @@ pub fn get_local<'gc>(
             local_path
         } else {
             log::warn!("SharedObject.get_local: localPath parameter does not match SWF path");
-            return Ok(Value::Null);
+            return None;
         }
     } else {
-        Cow::Borrowed(movie_path)
+        movie_path
     };
 
     // Final SO path: foo.com/folder/game.swf/SOName
@@ -420,9 +393,49 @@ pub fn get_local<'gc>(
     // so let's disallow them altogether.
     if full_name.split('/').any(|s| s.starts_with('.')) {
         log::error!("SharedObject.get_local: Invalid path with .. segments");
-        return Ok(Value::Null);
+        return None;
     }
 
+    Some(full_name)
+}
+
+pub fn get_local<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let name = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?;
+
+    let movie = if let Some(movie) = activation.base_clip().movie() {
+        movie
+    } else {
+        log::error!("SharedObject::get_local: Movie was None");
+        return Ok(Value::Null);
+    };
+
+    let local_path = match args.get(1) {
+        Some(Value::String(local_path)) => Some(local_path.to_utf8_lossy()),
+        _ => None,
+    };
+
+    let secure = args
+        .get(2)
+        .unwrap_or(&Value::Undefined)
+        .as_bool(activation.swf_version());
+
+    let full_name = match local_shared_object_name(
+        movie.url(),
+        &name.to_utf8_lossy(),
+        local_path.as_deref(),
+        secure,
+    ) {
+        Some(full_name) => full_name,
+        None => return Ok(Value::Null),
+    };
+
     // Check if this is referencing an existing shared object
     if let Some(so) = activation.context.shared_objects.get(&full_name) {
         return Ok((*so).into());
@@ -566,40 +579,85 @@ pub fn connect<'gc>(
     Ok(Value::Undefined)
 }
 
-pub fn flush<'gc>(
+/// Serialize the `data` of a shared object into the contents of a `.sol` file.
+fn serialize_lso<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
-    _args: &[Value<'gc>],
-) -> Result<Value<'gc>, Error<'gc>> {
+    name: &str,
+) -> Result<Vec<u8>, Error<'gc>> {
     let data = this.get("data", activation)?.coerce_to_object(activation);
 
-    let this_obj = this.as_shared_object().unwrap();
-    let name = this_obj.get_name();
-
     let mut elements = Vec::new();
     recursive_serialize(activation, data, &mut elements);
     let mut lso = Lso::new(
         elements,
-        &name
-            .split('/')
-            .last()
-            .map(|e| e.to_string())
-            .unwrap_or_else(|| "<unknown>".to_string()),
+        name.split('/').last().unwrap_or("<unknown>"),
         AMFVersion::AMF0,
     );
 
-    let bytes = flash_lso::write::write_to_bytes(&mut lso).unwrap_or_default();
+    Ok(flash_lso::write::write_to_bytes(&mut lso).unwrap_or_default())
+}
+
+/// Write a shared object to storage, if it fits within the storage's size limit.
+pub fn write_to_storage<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    min_disk_space: usize,
+) -> Result<bool, Error<'gc>> {
+    let name = match this.as_shared_object() {
+        Some(shared_object) => shared_object.get_name(),
+        None => return Ok(false),
+    };
 
-    Ok(activation.context.storage.put(&name, &bytes).into())
+    let bytes = serialize_lso(activation, this, &name)?;
+    let fits = activation
+        .context
+        .storage
+        .size_limit(&name)
+        .map_or(true, |limit| bytes.len().max(min_disk_space) <= limit);
+    Ok(fits && activation.context.storage.put(&name, &bytes))
+}
+
+pub fn flush<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let min_disk_space = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_i32(activation)?
+        .max(0) as usize;
+
+    let success = write_to_storage(activation, this, min_disk_space)?;
+
+    let info = ScriptObject::object(
+        activation.context.gc_context,
+        Some(activation.context.avm1.prototypes.object),
+    );
+    let (code, level) = if success {
+        ("SharedObject.Flush.Success", "status")
+    } else {
+        ("SharedObject.Flush.Failed", "error")
+    };
+    info.set("code", code.into(), activation)?;
+    info.set("level", level.into(), activation)?;
+    this.call_method("onStatus".into(), &[info.into()], activation)?;
+
+    Ok(success.into())
 }
 
 pub fn get_size<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
-    _this: Object<'gc>,
+    this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm_warn!(activation, "SharedObject.getSize() not implemented");
-    Ok(Value::Undefined)
+    let name = match this.as_shared_object() {
+        Some(shared_object) => shared_object.get_name(),
+        None => return Ok(Value::Undefined),
+    };
+
+    Ok(serialize_lso(activation, this, &name)?.len().into())
 }
 
 pub fn send<'gc>(
@@ -621,11 +679,11 @@ pub fn set_fps<'gc>(
 }
 
 pub fn on_status<'gc>(
-    activation: &mut Activation<'_, 'gc, '_>,
+    _activation: &mut Activation<'_, 'gc, '_>,
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm_warn!(activation, "SharedObject.onStatus() not implemented");
+    // Does nothing unless overridden by content.
     Ok(Value::Undefined)
 }
 
@@ -1,11 +1,12 @@
 //! AVM1 Sound object
-//! TODO: Sound position, transform, loadSound
+//! TODO: Sound position, transform
 
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::{Object, ScriptObject, SoundObject, TObject, Value};
 use crate::avm_warn;
+use crate::backend::navigator::RequestOptions;
 use crate::character::Character;
 use crate::display_object::{SoundTransform, TDisplayObject};
 use gc_arena::MutationContext;
@@ -238,11 +239,42 @@ fn id3<'gc>(
 
 fn load_sound<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
-    _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    this: Object<'gc>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if activation.swf_version() >= 6 {
-        avm_warn!(activation, "Sound.loadSound: Unimplemented");
+        if let Some(sound_object) = this.as_sound_object() {
+            let url = args
+                .get(0)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_string(activation)?;
+            let is_streaming = args
+                .get(1)
+                .unwrap_or(&Value::Undefined)
+                .as_bool(activation.swf_version());
+            if is_streaming {
+                // There's no streaming audio source yet, so streaming sounds
+                // only start playing once they have been fully downloaded.
+                avm_warn!(
+                    activation,
+                    "Sound.loadSound: Streaming is unimplemented, the sound will play once loaded"
+                );
+            }
+
+            let fetch = activation
+                .context
+                .navigator
+                .fetch(&url.to_utf8_lossy(), RequestOptions::get());
+            let process = activation.context.load_manager.load_sound_avm1(
+                activation.context.player.clone().unwrap(),
+                sound_object,
+                fetch,
+                is_streaming,
+            );
+            activation.context.navigator.spawn_future(process);
+        } else {
+            avm_warn!(activation, "Sound.loadSound: this is not a Sound");
+        }
     }
     Ok(Value::Undefined)
 }
@@ -256,7 +288,13 @@ fn position<'gc>(
     // Return undefined for player <6 if we ever add player version emulation.
     if let Some(sound_object) = this.as_sound_object() {
         if sound_object.sound().is_some() {
-            return Ok(sound_object.position().into());
+            // Ask the audio backend for the exact position of a playing sound, rather than
+            // the position recorded at the start of the frame.
+            let position = sound_object
+                .sound_instance()
+                .and_then(|instance| activation.context.audio.get_sound_position(instance))
+                .map_or_else(|| sound_object.position(), |pos| pos.round() as u32);
+            return Ok(position.into());
         }
     } else {
         avm_warn!(activation, "Sound.position: this is not a Sound");
@@ -6,6 +6,7 @@ use crate::avm1::object::value_object::ValueObject;
 use crate::avm1::property::Attribute;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::{ArrayObject, Object, TObject, Value};
+use crate::ecma_conversions::f64_to_wrapping_u16;
 use crate::string::{utils as string_utils, AvmString, WString};
 use gc_arena::MutationContext;
 
@@ -17,6 +18,7 @@ const PROTO_DECLS: &[Declaration] = declare_properties! {
     "concat" => method(concat; DONT_DELETE | DONT_ENUM);
     "indexOf" => method(index_of; DONT_DELETE | DONT_ENUM);
     "lastIndexOf" => method(last_index_of; DONT_DELETE | DONT_ENUM);
+    "localeCompare" => method(locale_compare; DONT_DELETE | DONT_ENUM);
     "slice" => method(slice; DONT_DELETE | DONT_ENUM);
     "split" => method(split; DONT_DELETE | DONT_ENUM);
     "substr" => method(substr; DONT_DELETE | DONT_ENUM);
@@ -99,7 +101,7 @@ pub fn create_proto<'gc>(
     string_proto
 }
 
-fn char_at<'gc>(
+pub fn char_at<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
     args: &[Value<'gc>],
@@ -121,7 +123,7 @@ fn char_at<'gc>(
     Ok(ret.into())
 }
 
-fn char_code_at<'gc>(
+pub fn char_code_at<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
     args: &[Value<'gc>],
@@ -132,6 +134,8 @@ fn char_code_at<'gc>(
         .get(0)
         .unwrap_or(&Value::Undefined)
         .coerce_to_i32(activation)?;
+    // Like in Flash, this returns UTF-16 code units, so each half of a
+    // surrogate pair is returned on its own.
     let ret = if i >= 0 {
         this.get(i as usize).map(f64::from).unwrap_or(f64::NAN)
     } else {
@@ -140,7 +144,7 @@ fn char_code_at<'gc>(
     Ok(ret.into())
 }
 
-fn concat<'gc>(
+pub fn concat<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
     args: &[Value<'gc>],
@@ -156,14 +160,28 @@ fn concat<'gc>(
     Ok(AvmString::new(activation.context.gc_context, ret).into())
 }
 
-fn from_char_code<'gc>(
+pub fn from_char_code<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     _this: Object<'gc>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     let mut out = WString::with_capacity(args.len(), false);
     for arg in args {
-        let i = arg.coerce_to_u16(activation)?;
+        let code = arg.coerce_to_f64(activation)?.trunc();
+        if let Some(c) = Some(code)
+            .filter(|code| (f64::from(0x10000)..=f64::from(0x10FFFF)).contains(code))
+            .and_then(|code| char::from_u32(code as u32))
+        {
+            // Characters outside of the Basic Multilingual Plane are stored
+            // as surrogate pairs.
+            for unit in c.encode_utf16(&mut [0; 2]) {
+                out.push(*unit);
+            }
+            continue;
+        }
+
+        // Anything else is a single code unit, including lone surrogates.
+        let i = f64_to_wrapping_u16(code);
         if i == 0 {
             // Stop at a null-terminator.
             break;
@@ -173,7 +191,7 @@ fn from_char_code<'gc>(
     Ok(AvmString::new(activation.context.gc_context, out).into())
 }
 
-fn index_of<'gc>(
+pub fn index_of<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
     args: &[Value<'gc>],
@@ -195,7 +213,7 @@ fn index_of<'gc>(
         .unwrap_or_else(|| Ok((-1).into())) // Out of range or not found
 }
 
-fn last_index_of<'gc>(
+pub fn last_index_of<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
     args: &[Value<'gc>],
@@ -221,7 +239,30 @@ fn last_index_of<'gc>(
         .unwrap_or_else(|| Ok((-1).into())) // Not found
 }
 
-fn slice<'gc>(
+/// Compares strings by their code units, as there's no locale-specific
+/// collation. Returns the difference of the first unequal units, or of the
+/// lengths if one string is a prefix of the other.
+fn locale_compare<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = Value::from(this).coerce_to_string(activation)?;
+    let other = match args.get(0) {
+        None => return Ok(Value::Undefined),
+        Some(other) => other.coerce_to_string(activation)?,
+    };
+
+    let difference = this
+        .iter()
+        .zip(other.iter())
+        .map(|(a, b)| i32::from(a) - i32::from(b))
+        .find(|difference| *difference != 0)
+        .unwrap_or_else(|| this.len() as i32 - other.len() as i32);
+    Ok(difference.into())
+}
+
+pub fn slice<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
     args: &[Value<'gc>],
@@ -251,7 +292,7 @@ fn slice<'gc>(
     }
 }
 
-fn split<'gc>(
+pub fn split<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
     args: &[Value<'gc>],
@@ -289,7 +330,7 @@ fn split<'gc>(
     }
 }
 
-fn substr<'gc>(
+pub fn substr<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
     args: &[Value<'gc>],
@@ -311,7 +352,7 @@ fn substr<'gc>(
         None | Some(Value::Undefined) => this.len() as i32,
         Some(n) => n.coerce_to_i32(activation)?,
     };
-    let end_index = string_wrapping_index((start_index as i32) + len, this.len());
+    let end_index = string_wrapping_index((start_index as i32).saturating_add(len), this.len());
 
     if start_index < end_index {
         let ret = WString::from(&this[start_index..end_index]);
@@ -321,7 +362,7 @@ fn substr<'gc>(
     }
 }
 
-fn substring<'gc>(
+pub fn substring<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
     args: &[Value<'gc>],
@@ -347,7 +388,7 @@ fn substring<'gc>(
     Ok(AvmString::new(activation.context.gc_context, ret).into())
 }
 
-fn to_lower_case<'gc>(
+pub fn to_lower_case<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
     _args: &[Value<'gc>],
@@ -381,7 +422,7 @@ pub fn to_string_value_of<'gc>(
     Ok(Value::Undefined)
 }
 
-fn to_upper_case<'gc>(
+pub fn to_upper_case<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
     _args: &[Value<'gc>],
@@ -418,3 +459,118 @@ fn string_wrapping_index(i: i32, len: usize) -> usize {
         (i as usize).min(len)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm1::object::value_object::ValueObject;
+    use crate::avm1::test_utils::with_avm;
+    use crate::string::WStr;
+
+    fn setup<'gc>(activation: &mut Activation<'_, 'gc, '_>) -> Object<'gc> {
+        ValueObject::boxed(activation, "abcdef".into())
+    }
+
+    test_method!(test_substr, "substr", setup,
+        [6, 19] => {
+            [] => Value::Undefined,
+            [2] => "cdef",
+            [-2] => "ef",
+            [1, 3] => "bcd",
+            [-3, 2] => "de",
+            [0, -2] => "abcd",
+            [2, -1] => "",
+            [1, -7] => "",
+            [2, i32::MAX] => "cdef"
+        }
+    );
+
+    test_method!(test_slice, "slice", setup,
+        [6, 19] => {
+            [] => Value::Undefined,
+            [2] => "cdef",
+            [1, -1] => "bcde",
+            [-3, -1] => "de",
+            [4, 2] => ""
+        }
+    );
+
+    test_method!(test_locale_compare, "localeCompare", setup,
+        [6, 19] => {
+            ["abcdef"] => 0,
+            ["abd"] => -1,
+            ["abc"] => 3,
+            ["abcdefgh"] => -2
+        }
+    );
+
+    test_method!(test_char_code_at, "charCodeAt", setup,
+        [6, 19] => {
+            [0] => 97,
+            [-1] => f64::NAN,
+            [6] => f64::NAN
+        }
+    );
+
+    fn setup_surrogates<'gc>(activation: &mut Activation<'_, 'gc, '_>) -> Object<'gc> {
+        // "a😀", followed by a lone high surrogate.
+        let units = WStr::from_units(&[0x61_u16, 0xD83D, 0xDE00, 0xD83D][..]);
+        let string = AvmString::new(activation.context.gc_context, units);
+        ValueObject::boxed(activation, string.into())
+    }
+
+    test_method!(test_char_code_at_surrogates, "charCodeAt", setup_surrogates,
+        [6, 19] => {
+            [1] => 0xD83D,
+            [2] => 0xDE00,
+            [3] => 0xD83D,
+            [4] => f64::NAN
+        }
+    );
+
+    fn setup_number<'gc>(activation: &mut Activation<'_, 'gc, '_>) -> Object<'gc> {
+        ValueObject::boxed(activation, 12345.into())
+    }
+
+    test_method!(test_number_this, "charAt", setup_number,
+        [6, 19] => {
+            [0] => "1",
+            [4] => "5"
+        }
+    );
+
+    test_method!(test_number_this_index_of, "indexOf", setup_number,
+        [6, 19] => {
+            ["34"] => 2
+        }
+    );
+
+    fn from_char_code_units(args: &[f64]) -> Vec<u16> {
+        let mut units = Vec::new();
+        with_avm(19, |activation, root| -> Result<(), Error> {
+            let args: Vec<Value> = args.iter().map(|&arg| arg.into()).collect();
+            let string = from_char_code(activation, root, &args)?.coerce_to_string(activation)?;
+            units = string.iter().collect();
+            Ok(())
+        });
+        units
+    }
+
+    #[test]
+    fn from_char_code_surrogates() {
+        // Code points above 0xFFFF become surrogate pairs.
+        assert_eq!(
+            from_char_code_units(&[97.0, 128512.0]),
+            [97, 0xD83D, 0xDE00]
+        );
+        assert_eq!(from_char_code_units(&[1114111.0]), [0xDBFF, 0xDFFF]);
+
+        // Surrogates are kept as they are, whether they are paired or not.
+        assert_eq!(from_char_code_units(&[55357.0, 56832.0]), [0xD83D, 0xDE00]);
+        assert_eq!(from_char_code_units(&[56832.0, 97.0]), [0xDE00, 97]);
+
+        // Larger values are truncated to 16 bits.
+        assert_eq!(from_char_code_units(&[1114177.0]), [0x41]);
+        assert_eq!(from_char_code_units(&[97.0, 0.0, 98.0]), [97]);
+    }
+}
@@ -0,0 +1,234 @@
+//! TextField.StyleSheet object
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::object::style_sheet_object::StyleSheetObject;
+use crate::avm1::object::text_format_object::TextFormatObject;
+use crate::avm1::property_decl::{define_properties_on, Declaration};
+use crate::avm1::{ArrayObject, AvmString, Object, ScriptObject, TObject, Value};
+use crate::backend::navigator::RequestOptions;
+use crate::html::{apply_style, Style, StyleSheet, TextFormat};
+use gc_arena::MutationContext;
+
+const PROTO_DECLS: &[Declaration] = declare_properties! {
+    "getStyle" => method(get_style; DONT_ENUM | DONT_DELETE);
+    "setStyle" => method(set_style; DONT_ENUM | DONT_DELETE);
+    "getStyleNames" => method(get_style_names; DONT_ENUM | DONT_DELETE);
+    "clear" => method(clear; DONT_ENUM | DONT_DELETE);
+    "parseCSS" => method(parse_css; DONT_ENUM | DONT_DELETE);
+    "transform" => method(transform; DONT_ENUM | DONT_DELETE);
+    "load" => method(load; DONT_ENUM | DONT_DELETE);
+    "onData" => method(on_data; DONT_ENUM | DONT_DELETE);
+};
+
+/// Implements `TextField.StyleSheet`
+pub fn constructor<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.into())
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let style_sheet = StyleSheetObject::empty_style_sheet(gc_context, Some(proto));
+    let object = style_sheet.as_script_object().unwrap();
+    define_properties_on(PROTO_DECLS, gc_context, object, fn_proto);
+    style_sheet.into()
+}
+
+/// Convert a style to an object with a string property per declaration.
+fn style_to_object<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    style: &Style,
+) -> Result<Object<'gc>, Error<'gc>> {
+    let object = ScriptObject::object(
+        activation.context.gc_context,
+        Some(activation.context.avm1.prototypes.object),
+    );
+    for (name, value) in style {
+        let name = AvmString::new_utf8(activation.context.gc_context, name);
+        let value = AvmString::new_utf8(activation.context.gc_context, value);
+        object.set(name, value.into(), activation)?;
+    }
+    Ok(object.into())
+}
+
+/// Convert the enumerable properties of an object to a style.
+fn object_to_style<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<Style, Error<'gc>> {
+    let mut style = Style::new();
+    for name in object.get_keys(activation) {
+        let value = object.get(name, activation)?.coerce_to_string(activation)?;
+        style.push((name.to_string(), value.to_string()));
+    }
+    Ok(style)
+}
+
+fn get_style<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(style_sheet) = this.as_style_sheet_object() {
+        let name = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_string(activation)?
+            .to_string();
+        let style = style_sheet.style_sheet().style(&name).cloned();
+        if let Some(style) = style {
+            return Ok(style_to_object(activation, &style)?.into());
+        }
+    }
+
+    Ok(Value::Null)
+}
+
+fn set_style<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(style_sheet) = this.as_style_sheet_object() {
+        let name = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_string(activation)?
+            .to_string();
+        let style = match args.get(1) {
+            Some(Value::Object(object)) => Some(object_to_style(activation, *object)?),
+            _ => None,
+        };
+        style_sheet
+            .style_sheet_mut(activation.context.gc_context)
+            .set_style(&name, style);
+    }
+
+    Ok(Value::Undefined)
+}
+
+fn get_style_names<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let style_sheet = match this.as_style_sheet_object() {
+        Some(style_sheet) => style_sheet.style_sheet(),
+        None => return Ok(Value::Undefined),
+    };
+
+    let gc_context = activation.context.gc_context;
+    Ok(ArrayObject::new(
+        gc_context,
+        activation.context.avm1.prototypes().array,
+        style_sheet
+            .selectors()
+            .map(|selector| AvmString::new_utf8(gc_context, selector).into()),
+    )
+    .into())
+}
+
+fn clear<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(style_sheet) = this.as_style_sheet_object() {
+        style_sheet
+            .style_sheet_mut(activation.context.gc_context)
+            .clear();
+    }
+
+    Ok(Value::Undefined)
+}
+
+fn parse_css<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let style_sheet = match this.as_style_sheet_object() {
+        Some(style_sheet) => style_sheet,
+        None => return Ok(false.into()),
+    };
+
+    let css = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?
+        .to_string();
+    match StyleSheet::parse(&css) {
+        Some(rules) => {
+            let mut style_sheet = style_sheet.style_sheet_mut(activation.context.gc_context);
+            for (selector, style) in rules {
+                style_sheet.set_style(&selector, Some(style));
+            }
+            Ok(true.into())
+        }
+        None => Ok(false.into()),
+    }
+}
+
+fn transform<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let style = match args.get(0) {
+        Some(Value::Object(object)) => object_to_style(activation, *object)?,
+        _ => return Ok(Value::Null),
+    };
+
+    let mut text_format = TextFormat::default();
+    apply_style(&style, &mut text_format);
+    Ok(TextFormatObject::new(activation, text_format).into())
+}
+
+fn load<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let url = match args.get(0) {
+        Some(val) => val.coerce_to_string(activation)?,
+        None => return Ok(false.into()),
+    };
+
+    let fetch = activation
+        .context
+        .navigator
+        .fetch(&url.to_utf8_lossy(), RequestOptions::get());
+    let process = activation.context.load_manager.load_form_into_load_vars(
+        activation.context.player.clone().unwrap(),
+        this,
+        fetch,
+    );
+    activation.context.navigator.spawn_future(process);
+
+    Ok(true.into())
+}
+
+fn on_data<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    // Default implementation parses the loaded CSS and forwards to onLoad.
+    let success = match args.get(0).unwrap_or(&Value::Undefined) {
+        Value::Undefined | Value::Null => false,
+        val => this
+            .call_method("parseCSS".into(), &[*val], activation)?
+            .as_bool(activation.swf_version()),
+    };
+
+    this.call_method("onLoad".into(), &[success.into()], activation)?;
+
+    Ok(Value::Undefined)
+}
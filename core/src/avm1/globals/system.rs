@@ -5,6 +5,7 @@ use crate::avm1::property::Attribute;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::{Avm1, ScriptObject, TObject, Value};
 use crate::avm_warn;
+use crate::backend::capabilities::{CapabilitiesOverrides, CapabilitiesProvider};
 use bitflags::bitflags;
 use core::fmt;
 use gc_arena::MutationContext;
@@ -57,6 +58,7 @@ impl fmt::Display for SandboxType {
 }
 
 /// The available host operating systems
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum OperatingSystem {
     WindowsXp,
     Windows2k,
@@ -94,6 +96,15 @@ pub enum Manufacturer {
 }
 
 impl Manufacturer {
+    /// The manufacturer of players for an operating system.
+    pub fn for_os(os: OperatingSystem) -> Self {
+        match os {
+            OperatingSystem::Linux => Manufacturer::Linux,
+            OperatingSystem::MacOs => Manufacturer::Macintosh,
+            _ => Manufacturer::Windows,
+        }
+    }
+
     pub fn get_manufacturer_string(&self, version: u8) -> String {
         let os_part = match self {
             Manufacturer::Windows => "Windows",
@@ -120,6 +131,7 @@ impl Manufacturer {
 }
 
 /// The language of the host os
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Language {
     Czech,
     Danish,
@@ -145,6 +157,37 @@ pub enum Language {
 }
 
 impl Language {
+    /// The language for a language code, such as `en-US`.
+    pub fn from_language_code(code: &str) -> Self {
+        let code = code.to_ascii_lowercase();
+        let (language, region) = code
+            .split_once(&['-', '_'][..])
+            .unwrap_or((code.as_str(), ""));
+        match language {
+            "cs" => Language::Czech,
+            "da" => Language::Danish,
+            "nl" => Language::Dutch,
+            "en" => Language::English,
+            "fi" => Language::Finnish,
+            "fr" => Language::French,
+            "de" => Language::German,
+            "hu" => Language::Hungarian,
+            "it" => Language::Italian,
+            "ja" => Language::Japanese,
+            "ko" => Language::Korean,
+            "no" | "nb" | "nn" => Language::Norwegian,
+            "pl" => Language::Polish,
+            "pt" => Language::Portuguese,
+            "ru" => Language::Russian,
+            "zh" if matches!(region, "tw" | "hk" | "mo" | "hant") => Language::TraditionalChinese,
+            "zh" => Language::SimplifiedChinese,
+            "es" => Language::Spanish,
+            "sv" => Language::Swedish,
+            "tr" => Language::Turkish,
+            _ => Language::Unknown,
+        }
+    }
+
     pub fn get_language_code(&self, player_version: u8) -> &str {
         match self {
             Language::Czech => "cs",
@@ -195,6 +238,7 @@ impl fmt::Display for ScreenColor {
     }
 }
 /// The type of the player
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PlayerType {
     StandAlone,
     External,
@@ -284,10 +328,16 @@ pub struct SystemProperties {
     pub cpu_architecture: CpuArchitecture,
     /// The highest supported h264 decoder level
     pub idc_level: String,
+    /// The version string to report instead of the real one
+    pub version_override: Option<String>,
 }
 
 impl SystemProperties {
     pub fn get_version_string(&self, avm: &mut Avm1) -> String {
+        if let Some(version) = &self.version_override {
+            return version.clone();
+        }
+
         format!(
             "{} {},0,0,0",
             self.manufacturer.get_platform_name(),
@@ -295,6 +345,31 @@ impl SystemProperties {
         )
     }
 
+    /// Update the properties that describe the host system from a provider,
+    /// preferring any overridden values.
+    pub fn apply_capabilities(
+        &mut self,
+        provider: &dyn CapabilitiesProvider,
+        overrides: &CapabilitiesOverrides,
+    ) {
+        self.screen_resolution = overrides
+            .screen_resolution
+            .unwrap_or_else(|| provider.screen_resolution());
+        self.dpi = overrides
+            .screen_dpi
+            .unwrap_or_else(|| provider.screen_dpi());
+        self.language = overrides.language.unwrap_or_else(|| provider.language());
+        self.os = overrides.os.unwrap_or_else(|| provider.os());
+        self.manufacturer = Manufacturer::for_os(self.os);
+        self.player_type = overrides
+            .player_type
+            .unwrap_or_else(|| provider.player_type());
+        self.capabilities = overrides
+            .capabilities
+            .unwrap_or_else(|| provider.capabilities());
+        self.version_override = overrides.version.clone();
+    }
+
     pub fn has_capability(&self, cap: SystemCapabilities) -> bool {
         self.capabilities.contains(cap)
     }
@@ -408,6 +483,7 @@ impl Default for SystemProperties {
             sandbox_type: SandboxType::LocalTrusted,
             cpu_architecture: CpuArchitecture::X86,
             idc_level: "5.1".into(),
+            version_override: None,
         }
     }
 }
@@ -1,9 +1,10 @@
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
+use crate::avm1::function::{Executable, FunctionObject};
 use crate::avm1::globals::display_object;
 use crate::avm1::object::text_format_object::TextFormatObject;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
-use crate::avm1::{Object, ScriptObject, TObject, Value};
+use crate::avm1::{ArrayObject, Object, ScriptObject, TObject, Value};
 use crate::avm_error;
 use crate::display_object::{AutoSizeMode, EditText, TDisplayObject, TextSelection};
 use crate::font::round_down_to_pixel;
@@ -59,6 +60,7 @@ const PROTO_DECLS: &[Declaration] = declare_properties! {
     "replaceSel" => method(tf_method!(replace_sel); DONT_ENUM | DONT_DELETE | READ_ONLY);
     "replaceText" => method(tf_method!(replace_text); DONT_ENUM | DONT_DELETE | READ_ONLY);
     "removeTextField" => method(tf_method!(remove_text_field); DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "antiAliasType" => property(tf_getter!(anti_alias_type), tf_setter!(set_anti_alias_type); DONT_DELETE);
     "autoSize" => property(tf_getter!(auto_size), tf_setter!(set_auto_size); DONT_DELETE);
     "background" => property(tf_getter!(background), tf_setter!(set_background); DONT_DELETE);
     "backgroundColor" => property(tf_getter!(background_color), tf_setter!(set_background_color); DONT_DELETE);
@@ -66,25 +68,36 @@ const PROTO_DECLS: &[Declaration] = declare_properties! {
     "borderColor" => property(tf_getter!(border_color), tf_setter!(set_border_color); DONT_DELETE);
     "bottomScroll" => property(tf_getter!(bottom_scroll); DONT_DELETE | READ_ONLY);
     "embedFonts" => property(tf_getter!(embed_fonts), tf_setter!(set_embed_fonts); DONT_DELETE);
+    "filters" => property(tf_getter!(filters), tf_setter!(set_filters); DONT_DELETE);
+    "gridFitType" => property(tf_getter!(grid_fit_type), tf_setter!(set_grid_fit_type); DONT_DELETE);
     "hscroll" => property(tf_getter!(hscroll), tf_setter!(set_hscroll); DONT_DELETE);
     "html" => property(tf_getter!(html), tf_setter!(set_html); DONT_DELETE);
     "htmlText" => property(tf_getter!(html_text), tf_setter!(set_html_text); DONT_DELETE);
     "length" => property(tf_getter!(length); DONT_DELETE | READ_ONLY);
+    "maxChars" => property(tf_getter!(max_chars), tf_setter!(set_max_chars); DONT_DELETE);
     "maxhscroll" => property(tf_getter!(maxhscroll); DONT_DELETE | READ_ONLY);
     "maxscroll" => property(tf_getter!(maxscroll); DONT_DELETE | READ_ONLY);
     "multiline" => property(tf_getter!(multiline), tf_setter!(set_multiline); DONT_DELETE);
     "password" => property(tf_getter!(password), tf_setter!(set_password); DONT_DELETE);
+    "restrict" => property(tf_getter!(restrict), tf_setter!(set_restrict); DONT_DELETE);
     "scroll" => property(tf_getter!(scroll), tf_setter!(set_scroll); DONT_DELETE);
     "selectable" => property(tf_getter!(selectable), tf_setter!(set_selectable); DONT_DELETE);
+    "sharpness" => property(tf_getter!(sharpness), tf_setter!(set_sharpness); DONT_DELETE);
+    "styleSheet" => property(tf_getter!(style_sheet), tf_setter!(set_style_sheet); DONT_DELETE);
     "text" => property(tf_getter!(text), tf_setter!(set_text); DONT_DELETE);
     "textColor" => property(tf_getter!(text_color), tf_setter!(set_text_color); DONT_DELETE);
     "textHeight" => property(tf_getter!(text_height); DONT_DELETE);
     "textWidth" => property(tf_getter!(text_width); DONT_DELETE);
+    "thickness" => property(tf_getter!(thickness), tf_setter!(set_thickness); DONT_DELETE);
     "type" => property(tf_getter!(get_type), tf_setter!(set_type); DONT_DELETE);
     "variable" => property(tf_getter!(variable), tf_setter!(set_variable); DONT_DELETE);
     "wordWrap" => property(tf_getter!(word_wrap), tf_setter!(set_word_wrap); DONT_DELETE);
 };
 
+const OBJECT_DECLS: &[Declaration] = declare_properties! {
+    "getFontList" => method(get_font_list; DONT_ENUM | DONT_DELETE | READ_ONLY);
+};
+
 /// Implements `TextField`
 pub fn constructor<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
@@ -94,6 +107,23 @@ pub fn constructor<'gc>(
     Ok(this.into())
 }
 
+pub fn create_text_field_object<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    text_field_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let text_field = FunctionObject::constructor(
+        gc_context,
+        Executable::Native(constructor),
+        constructor_to_fn!(constructor),
+        Some(fn_proto),
+        text_field_proto,
+    );
+    let object = text_field.as_script_object().unwrap();
+    define_properties_on(OBJECT_DECLS, gc_context, object, fn_proto);
+    text_field
+}
+
 pub fn create_proto<'gc>(
     gc_context: MutationContext<'gc, '_>,
     proto: Object<'gc>,
@@ -123,6 +153,100 @@ pub fn set_password<'gc>(
     Ok(())
 }
 
+fn max_chars<'gc>(
+    this: EditText<'gc>,
+    _activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    // No limit is reported as null.
+    match this.max_chars() {
+        0 => Ok(Value::Null),
+        max_chars => Ok(max_chars.into()),
+    }
+}
+
+fn set_max_chars<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    let max_chars = match value {
+        Value::Undefined | Value::Null => 0,
+        v => v.coerce_to_i32(activation)?,
+    };
+    this.set_max_chars(max_chars, &mut activation.context);
+    Ok(())
+}
+
+fn restrict<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    match this.restrict() {
+        Some(restrict) => Ok(AvmString::new(activation.context.gc_context, restrict).into()),
+        None => Ok(Value::Null),
+    }
+}
+
+fn set_restrict<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    // An empty string allows no characters at all, unlike `null`.
+    let restrict = match value {
+        Value::Undefined | Value::Null => None,
+        v => Some(v.coerce_to_string(activation)?),
+    };
+    this.set_restrict(restrict.as_deref(), &mut activation.context);
+    Ok(())
+}
+
+fn style_sheet<'gc>(
+    this: EditText<'gc>,
+    _activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this
+        .style_sheet_object()
+        .map_or(Value::Undefined, Value::Object))
+}
+
+fn set_style_sheet<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    // The rules are copied, so later changes to the style sheet only apply
+    // once it is assigned again.
+    let style_sheet = match value {
+        Value::Object(object) => object
+            .as_style_sheet_object()
+            .map(|style_sheet| (object, style_sheet.style_sheet())),
+        _ => None,
+    };
+    this.set_style_sheet(&mut activation.context, style_sheet);
+    Ok(())
+}
+
+/// Implements `TextField.getFontList`, which lists the fonts installed on the system.
+fn get_font_list<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let mut names = activation.context.fonts.font_names();
+    names.sort();
+    names.dedup();
+    let gc_context = activation.context.gc_context;
+    Ok(ArrayObject::new(
+        gc_context,
+        activation.context.avm1.prototypes().array,
+        names
+            .into_iter()
+            .map(|name| AvmString::new_utf8(gc_context, name).into()),
+    )
+    .into())
+}
+
 fn get_new_text_format<'gc>(
     text_field: EditText<'gc>,
     activation: &mut Activation<'_, 'gc, '_>,
@@ -590,6 +714,88 @@ pub fn set_type<'gc>(
     Ok(())
 }
 
+pub fn anti_alias_type<'gc>(
+    this: EditText<'gc>,
+    _activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(AvmString::from(this.render_settings().anti_alias_type()).into())
+}
+
+pub fn set_anti_alias_type<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    let value = value.coerce_to_string(activation)?;
+    let mut settings = this.render_settings();
+    if settings.set_anti_alias_type(&value) {
+        this.set_render_settings(activation.context.gc_context, settings);
+    } else {
+        log::warn!("Invalid TextField.antiAliasType: {}", value);
+    }
+    Ok(())
+}
+
+pub fn grid_fit_type<'gc>(
+    this: EditText<'gc>,
+    _activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(AvmString::from(this.render_settings().grid_fit_type()).into())
+}
+
+pub fn set_grid_fit_type<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    let value = value.coerce_to_string(activation)?;
+    let mut settings = this.render_settings();
+    if settings.set_grid_fit_type(&value) {
+        this.set_render_settings(activation.context.gc_context, settings);
+    } else {
+        log::warn!("Invalid TextField.gridFitType: {}", value);
+    }
+    Ok(())
+}
+
+pub fn sharpness<'gc>(
+    this: EditText<'gc>,
+    _activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.render_settings().sharpness.into())
+}
+
+pub fn set_sharpness<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    let sharpness = value.coerce_to_f64(activation)?;
+    let mut settings = this.render_settings();
+    settings.set_sharpness(sharpness);
+    this.set_render_settings(activation.context.gc_context, settings);
+    Ok(())
+}
+
+pub fn thickness<'gc>(
+    this: EditText<'gc>,
+    _activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.render_settings().thickness.into())
+}
+
+pub fn set_thickness<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    let thickness = value.coerce_to_f64(activation)?;
+    let mut settings = this.render_settings();
+    settings.set_thickness(thickness);
+    this.set_render_settings(activation.context.gc_context, settings);
+    Ok(())
+}
+
 pub fn hscroll<'gc>(
     this: EditText<'gc>,
     _activation: &mut Activation<'_, 'gc, '_>,
@@ -646,3 +852,18 @@ pub fn bottom_scroll<'gc>(
 ) -> Result<Value<'gc>, Error<'gc>> {
     Ok(this.bottom_scroll().into())
 }
+
+fn filters<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    display_object::filters(this.into(), activation)
+}
+
+fn set_filters<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    display_object::set_filters(this.into(), activation, value)
+}
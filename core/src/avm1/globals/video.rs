@@ -4,10 +4,40 @@ use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::globals::display_object;
 use crate::avm1::object::Object;
+use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::value::Value;
-use crate::avm1::ScriptObject;
+use crate::avm1::{ScriptObject, TObject};
+use crate::display_object::{TDisplayObject, Video};
 use gc_arena::MutationContext;
 
+macro_rules! video_getter {
+    ($get:expr) => {
+        |activation, this, _args| {
+            if let Some(video) = this.as_display_object().and_then(|dobj| dobj.as_video()) {
+                return $get(video, activation);
+            }
+            Ok(Value::Undefined)
+        }
+    };
+}
+
+macro_rules! video_setter {
+    ($set:expr) => {
+        |activation, this, args| {
+            if let Some(video) = this.as_display_object().and_then(|dobj| dobj.as_video()) {
+                let value = args.get(0).unwrap_or(&Value::Undefined).clone();
+                $set(video, activation, value)?;
+            }
+            Ok(Value::Undefined)
+        }
+    };
+}
+
+const PROTO_DECLS: &[Declaration] = declare_properties! {
+    "smoothing" => property(video_getter!(smoothing), video_setter!(set_smoothing); DONT_DELETE);
+    "attachVideo" => method(attach_video; DONT_ENUM | DONT_DELETE);
+};
+
 /// Implements `Video`
 pub fn constructor<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
@@ -25,6 +55,44 @@ pub fn create_proto<'gc>(
     let object = ScriptObject::object(gc_context, Some(proto));
 
     display_object::define_display_object_proto(gc_context, object, fn_proto);
+    define_properties_on(PROTO_DECLS, gc_context, object, fn_proto);
 
     object.into()
 }
+
+fn smoothing<'gc>(
+    this: Video<'gc>,
+    _activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.smoothing().into())
+}
+
+fn set_smoothing<'gc>(
+    this: Video<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    let smoothing = value.as_bool(activation.swf_version());
+    this.set_smoothing(activation.context.gc_context, smoothing);
+    Ok(())
+}
+
+fn attach_video<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(video) = this.as_display_object().and_then(|dobj| dobj.as_video()) {
+        // Anything other than a `NetStream` detaches the current source.
+        let net_stream = match args.get(0) {
+            Some(Value::Object(source)) => source
+                .as_net_stream_object()
+                .map(|source| source.net_stream()),
+            _ => None,
+        };
+        video.attach_net_stream(activation.context.gc_context, net_stream);
+        *activation.context.needs_render = true;
+    }
+
+    Ok(Value::Undefined)
+}
@@ -3,6 +3,7 @@
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::object::xml_object::XmlObject;
+use crate::avm1::property::Attribute;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::{ArrayObject, Object, TObject, Value};
 use crate::avm_warn;
@@ -55,7 +56,7 @@ const XMLNODE_PROTO_DECLS: &[Declaration] = declare_properties! {
 const XML_PROTO_DECLS: &[Declaration] = declare_properties! {
     "docTypeDecl" => property(xml_doc_type_decl; READ_ONLY);
     "ignoreWhite" => bool(false);
-    "contentType" => string("application/x-www-form-urlencoded"; READ_ONLY);
+    "contentType" => string("application/x-www-form-urlencoded");
     "xmlDecl" => property(xml_xml_decl; READ_ONLY);
     "idMap" => property(xml_id_map; READ_ONLY);
     "status" => property(xml_status; READ_ONLY);
@@ -63,7 +64,10 @@ const XML_PROTO_DECLS: &[Declaration] = declare_properties! {
     "createTextNode" => method(xml_create_text_node);
     "parseXML" => method(xml_parse_xml);
     "load" => method(xml_load);
+    "send" => method(xml_send);
     "sendAndLoad" => method(xml_send_and_load);
+    "getBytesLoaded" => method(xml_get_bytes_loaded);
+    "getBytesTotal" => method(xml_get_bytes_total);
     "onData" => method(xml_on_data);
 };
 
@@ -708,6 +712,46 @@ pub fn xml_parse_xml<'gc>(
     Ok(Value::Undefined)
 }
 
+pub fn xml_send<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let url_val = args.get(0).cloned().unwrap_or(Value::Undefined);
+
+    if let Value::Null | Value::Undefined = url_val {
+        return Ok(false.into());
+    }
+
+    if let Some(node) = this.as_xml_node() {
+        let url = url_val.coerce_to_string(activation)?;
+        if args.get(1).is_some() {
+            // The navigator backend can't open a window with a raw request body.
+            avm_warn!(
+                activation,
+                "XML.send: Displaying the response in a window is unimplemented"
+            );
+        }
+
+        let request_options = xml_request_options(activation, this, node)?;
+        let fetch = activation
+            .context
+            .navigator
+            .fetch(&url.to_utf8_lossy(), request_options);
+        activation
+            .context
+            .navigator
+            .spawn_future(Box::pin(async move {
+                // The response is discarded.
+                fetch.await.map(|_| ())
+            }));
+
+        Ok(true.into())
+    } else {
+        Ok(false.into())
+    }
+}
+
 pub fn xml_send_and_load<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
@@ -752,6 +796,24 @@ pub fn xml_load<'gc>(
     }
 }
 
+pub fn xml_get_bytes_loaded<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    // Forwards to undocumented property on the object.
+    this.get("_bytesLoaded", activation)
+}
+
+pub fn xml_get_bytes_total<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    // Forwards to undocumented property on the object.
+    this.get("_bytesTotal", activation)
+}
+
 pub fn xml_on_data<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
@@ -863,6 +925,25 @@ pub fn xml_status<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Builds a POST request containing the serialized `node`, using the `contentType` of `this`.
+fn xml_request_options<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    node: XmlNode<'gc>,
+) -> Result<RequestOptions, Error<'gc>> {
+    let content_type = this
+        .get("contentType", activation)?
+        .coerce_to_string(activation)?
+        .to_string();
+
+    Ok(RequestOptions::post(Some((
+        node.into_string(&mut is_as2_compatible)
+            .unwrap_or_default()
+            .into_bytes(),
+        content_type,
+    ))))
+}
+
 fn spawn_xml_fetch<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
@@ -872,12 +953,7 @@ fn spawn_xml_fetch<'gc>(
 ) -> Result<Value<'gc>, Error<'gc>> {
     let request_options = if let Some(node) = send_object {
         // Send `node` as string
-        RequestOptions::post(Some((
-            node.into_string(&mut is_as2_compatible)
-                .unwrap_or_default()
-                .into_bytes(),
-            "application/x-www-form-urlencoded".to_string(),
-        )))
+        xml_request_options(activation, this, node)?
     } else {
         // Not sending any parameters.
         RequestOptions::get()
@@ -885,6 +961,20 @@ fn spawn_xml_fetch<'gc>(
 
     this.set("loaded", false.into(), activation)?;
 
+    // Create hidden properties on object.
+    for name in ["_bytesLoaded", "_bytesTotal"] {
+        if !loader_object.has_property(activation, name.into()) {
+            loader_object.define_value(
+                activation.context.gc_context,
+                name,
+                Value::Undefined,
+                Attribute::DONT_DELETE | Attribute::DONT_ENUM,
+            );
+        } else {
+            loader_object.set(name, Value::Undefined, activation)?;
+        }
+    }
+
     let fetch = activation
         .context
         .navigator
@@ -0,0 +1,108 @@
+//! XMLSocket object
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::property_decl::{define_properties_on, Declaration};
+use crate::avm1::{Object, ScriptObject, TObject, Value};
+use crate::avm_warn;
+use crate::socket::Sockets;
+use gc_arena::MutationContext;
+
+const PROTO_DECLS: &[Declaration] = declare_properties! {
+    "connect" => method(connect; DONT_ENUM | DONT_DELETE);
+    "send" => method(send; DONT_ENUM | DONT_DELETE);
+    "close" => method(close; DONT_ENUM | DONT_DELETE);
+    "onData" => method(on_data; DONT_ENUM | DONT_DELETE);
+};
+
+/// Implements `XMLSocket`
+pub fn constructor<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.into())
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let object = ScriptObject::object(gc_context, Some(proto));
+    define_properties_on(PROTO_DECLS, gc_context, object, fn_proto);
+    object.into()
+}
+
+fn connect<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    // A null host connects to the host the movie was loaded from.
+    let host = match args.get(0).unwrap_or(&Value::Undefined) {
+        Value::Null | Value::Undefined => activation
+            .base_clip()
+            .movie()
+            .and_then(|movie| movie.url().and_then(|url| url::Url::parse(url).ok()))
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_else(|| "localhost".to_string()),
+        host => host.coerce_to_string(activation)?.to_string(),
+    };
+
+    let port = args
+        .get(1)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_i32(activation)?;
+    // As in Flash, the well-known ports are off limits.
+    if !(1024..=65535).contains(&port) {
+        avm_warn!(activation, "XMLSocket.connect: Invalid port {}", port);
+        return Ok(false.into());
+    }
+
+    Ok(Sockets::connect(&mut activation.context, this, &host, port as u16).into())
+}
+
+fn send<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    // XML objects are sent serialized, through their `toString`.
+    let message = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?;
+    Sockets::send(
+        &mut activation.context,
+        this,
+        message.to_utf8_lossy().as_bytes(),
+    );
+
+    Ok(Value::Undefined)
+}
+
+fn close<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Sockets::close(&mut activation.context, this);
+
+    Ok(Value::Undefined)
+}
+
+fn on_data<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    // Default implementation parses the message and forwards it to `onXML`.
+    let src = args.get(0).copied().unwrap_or(Value::Undefined);
+    let constructor = activation.context.avm1.prototypes().xml_constructor;
+    let xml = constructor.construct(activation, &[src])?;
+
+    this.call_method("onXML".into(), &[xml], activation)?;
+
+    Ok(Value::Undefined)
+}
@@ -21,6 +21,9 @@ use crate::avm1::object::drop_shadow_filter::DropShadowFilterObject;
 use crate::avm1::object::glow_filter::GlowFilterObject;
 use crate::avm1::object::gradient_bevel_filter::GradientBevelFilterObject;
 use crate::avm1::object::gradient_glow_filter::GradientGlowFilterObject;
+use crate::avm1::object::net_stream_object::NetStreamObject;
+use crate::avm1::object::print_job_object::PrintJobObject;
+use crate::avm1::object::style_sheet_object::StyleSheetObject;
 use crate::avm1::object::text_format_object::TextFormatObject;
 use crate::avm1::object::transform_object::TransformObject;
 use crate::avm1::object::xml_attributes_object::XmlAttributesObject;
@@ -47,10 +50,13 @@ pub mod drop_shadow_filter;
 pub mod glow_filter;
 pub mod gradient_bevel_filter;
 pub mod gradient_glow_filter;
+pub mod net_stream_object;
+pub mod print_job_object;
 pub mod script_object;
 pub mod shared_object;
 pub mod sound_object;
 pub mod stage_object;
+pub mod style_sheet_object;
 pub mod super_object;
 pub mod text_format_object;
 pub mod transform_object;
@@ -91,6 +97,9 @@ pub mod xml_object;
         DateObject(DateObject<'gc>),
         BitmapData(BitmapDataObject<'gc>),
         TextFormatObject(TextFormatObject<'gc>),
+        NetStreamObject(NetStreamObject<'gc>),
+        StyleSheetObject(StyleSheetObject<'gc>),
+        PrintJobObject(PrintJobObject<'gc>),
     }
 )]
 pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy {
@@ -616,6 +625,21 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
         None
     }
 
+    /// Get the underlying `NetStreamObject`, if it exists
+    fn as_net_stream_object(&self) -> Option<NetStreamObject<'gc>> {
+        None
+    }
+
+    /// Get the underlying `StyleSheetObject`, if it exists
+    fn as_style_sheet_object(&self) -> Option<StyleSheetObject<'gc>> {
+        None
+    }
+
+    /// Get the underlying `PrintJobObject`, if it exists
+    fn as_print_job_object(&self) -> Option<PrintJobObject<'gc>> {
+        None
+    }
+
     fn as_ptr(&self) -> *const ObjectPtr;
 
     /// Check if this object is in the prototype chain of the specified test object.
@@ -0,0 +1,75 @@
+//! AVM1 object type to represent PrintJob objects.
+
+use crate::avm1::{Object, ScriptObject, TObject};
+use crate::backend::render::Bitmap;
+use crate::impl_custom_object;
+use gc_arena::{Collect, GcCell, MutationContext};
+use std::fmt;
+
+/// A PrintJobObject that collects the pages of a `PrintJob`.
+#[derive(Clone, Copy, Collect)]
+#[collect(no_drop)]
+pub struct PrintJobObject<'gc>(GcCell<'gc, PrintJobObjectData<'gc>>);
+
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct PrintJobObjectData<'gc> {
+    /// The underlying script object.
+    base: ScriptObject<'gc>,
+
+    /// Whether `start` was called and the job wasn't sent yet.
+    started: bool,
+
+    /// The pages added since the job was started, rendered when they were added.
+    #[collect(require_static)]
+    pages: Vec<Bitmap>,
+}
+
+impl fmt::Debug for PrintJobObject<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let this = self.0.read();
+        f.debug_struct("PrintJobObject")
+            .field("started", &this.started)
+            .field("pages", &this.pages.len())
+            .finish()
+    }
+}
+
+impl<'gc> PrintJobObject<'gc> {
+    pub fn empty_print_job(
+        gc_context: MutationContext<'gc, '_>,
+        proto: Option<Object<'gc>>,
+    ) -> PrintJobObject<'gc> {
+        PrintJobObject(GcCell::allocate(
+            gc_context,
+            PrintJobObjectData {
+                base: ScriptObject::object(gc_context, proto),
+                started: false,
+                pages: Vec::new(),
+            },
+        ))
+    }
+
+    pub fn started(self) -> bool {
+        self.0.read().started
+    }
+
+    pub fn set_started(self, gc_context: MutationContext<'gc, '_>, started: bool) {
+        self.0.write(gc_context).started = started;
+    }
+
+    pub fn add_page(self, gc_context: MutationContext<'gc, '_>, page: Bitmap) {
+        self.0.write(gc_context).pages.push(page);
+    }
+
+    /// Remove all pages from this job, returning them.
+    pub fn take_pages(self, gc_context: MutationContext<'gc, '_>) -> Vec<Bitmap> {
+        std::mem::take(&mut self.0.write(gc_context).pages)
+    }
+}
+
+impl<'gc> TObject<'gc> for PrintJobObject<'gc> {
+    impl_custom_object!(base {
+        bare_object(as_print_job_object -> PrintJobObject::empty_print_job);
+    });
+}
@@ -15,6 +15,10 @@ pub const TYPE_OF_OBJECT: &str = "object";
 pub struct Watcher<'gc> {
     callback: Object<'gc>,
     user_data: Value<'gc>,
+
+    /// Whether the callback is running, in which case setting the property
+    /// doesn't call it again.
+    executing: bool,
 }
 
 impl<'gc> Watcher<'gc> {
@@ -22,6 +26,7 @@ impl<'gc> Watcher<'gc> {
         Self {
             callback,
             user_data,
+            executing: false,
         }
     }
 
@@ -138,7 +143,8 @@ impl<'gc> ScriptObject<'gc> {
         value: Value<'gc>,
         activation: &mut Activation<'_, 'gc, '_>,
     ) -> Result<(), Error<'gc>> {
-        // TODO: Call watchers.
+        let mut value = value;
+        let watcher_result = self.call_watcher(activation, name, &mut value, (*self).into());
         match self
             .0
             .write(activation.context.gc_context)
@@ -148,7 +154,7 @@ impl<'gc> ScriptObject<'gc> {
             Entry::Occupied(mut entry) => entry.get_mut().set_data(value),
             Entry::Vacant(entry) => entry.insert(Property::new_stored(value, Attribute::empty())),
         }
-        Ok(())
+        watcher_result
     }
 }
 
@@ -163,6 +169,7 @@ impl<'gc> TObject<'gc> for ScriptObject<'gc> {
             .read()
             .properties
             .get(name.into(), activation.is_case_sensitive())
+            .filter(|property| property.allow_swf_version(activation.swf_version()))
             .map(|property| property.data())
     }
 
@@ -316,15 +323,23 @@ impl<'gc> TObject<'gc> for ScriptObject<'gc> {
         this: Object<'gc>,
     ) -> Result<(), Error<'gc>> {
         let mut result = Ok(());
-        let watcher = self
+        let watcher = match self
             .0
-            .read()
+            .write(activation.context.gc_context)
             .watchers
-            .get(name, activation.is_case_sensitive())
-            .cloned();
+            .get_mut(name, activation.is_case_sensitive())
+        {
+            Some(watcher) if !watcher.executing => {
+                watcher.executing = true;
+                Some(watcher.clone())
+            }
+            _ => None,
+        };
         if let Some(watcher) = watcher {
-            let old_value = self.get_stored(name, activation)?;
-            match watcher.call(activation, name, old_value, *value, this) {
+            let old_value = self.get_stored(name, activation);
+            let call_result = old_value
+                .and_then(|old_value| watcher.call(activation, name, old_value, *value, this));
+            match call_result {
                 Ok(v) => *value = v,
                 Err(Error::ThrownValue(e)) => {
                     *value = Value::Undefined;
@@ -332,6 +347,16 @@ impl<'gc> TObject<'gc> for ScriptObject<'gc> {
                 }
                 Err(_) => *value = Value::Undefined,
             };
+
+            // The callback may have replaced or removed the watcher.
+            if let Some(watcher) = self
+                .0
+                .write(activation.context.gc_context)
+                .watchers
+                .get_mut(name, activation.is_case_sensitive())
+            {
+                watcher.executing = false;
+            }
         }
 
         result
@@ -421,7 +446,10 @@ impl<'gc> TObject<'gc> for ScriptObject<'gc> {
         self.0
             .read()
             .properties
-            .contains_key(name, activation.is_case_sensitive())
+            .get(name, activation.is_case_sensitive())
+            .map_or(false, |property| {
+                property.allow_swf_version(activation.swf_version())
+            })
     }
 
     fn has_own_virtual(
@@ -433,7 +461,9 @@ impl<'gc> TObject<'gc> for ScriptObject<'gc> {
             .read()
             .properties
             .get(name, activation.is_case_sensitive())
-            .map_or(false, |property| property.is_virtual())
+            .map_or(false, |property| {
+                property.is_virtual() && property.allow_swf_version(activation.swf_version())
+            })
     }
 
     /// Checks if a named property appears when enumerating the object.
@@ -446,7 +476,9 @@ impl<'gc> TObject<'gc> for ScriptObject<'gc> {
             .read()
             .properties
             .get(name, activation.is_case_sensitive())
-            .map_or(false, |property| property.is_enumerable())
+            .map_or(false, |property| {
+                property.is_enumerable() && property.allow_swf_version(activation.swf_version())
+            })
     }
 
     /// Enumerate the object.
@@ -466,8 +498,9 @@ impl<'gc> TObject<'gc> for ScriptObject<'gc> {
         );
 
         // Then our own keys.
+        let swf_version = activation.swf_version();
         out_keys.extend(self.0.read().properties.iter().filter_map(move |(k, p)| {
-            if p.is_enumerable() {
+            if p.is_enumerable() && p.allow_swf_version(swf_version) {
                 Some(k)
             } else {
                 None
@@ -547,6 +580,7 @@ mod tests {
     use crate::avm1::{Avm1, Timers};
     use crate::avm2::Avm2;
     use crate::backend::audio::{AudioManager, NullAudioBackend};
+    use crate::backend::font::NullFontBackend;
     use crate::backend::locale::NullLocaleBackend;
     use crate::backend::log::NullLogBackend;
     use crate::backend::navigator::NullNavigatorBackend;
@@ -559,7 +593,11 @@ mod tests {
     use crate::focus_tracker::FocusTracker;
     use crate::library::Library;
     use crate::loader::LoadManager;
+    use crate::local_connection::LocalConnections;
+    use crate::microphone::Microphone;
     use crate::prelude::*;
+    use crate::socket::Sockets;
+    use crate::streams::StreamManager;
     use crate::tag_utils::SwfMovie;
     use crate::vminterface::Instantiator;
     use gc_arena::rootless_arena;
@@ -595,11 +633,17 @@ mod tests {
                 action_queue: &mut crate::context::ActionQueue::new(),
                 audio: &mut NullAudioBackend::new(),
                 audio_manager: &mut AudioManager::new(),
+                stream_manager: &mut StreamManager::new(),
+                microphone: &mut Microphone::new(),
+                sockets: &mut Sockets::new(),
+                local_connections: &mut LocalConnections::new(),
                 ui: &mut NullUiBackend::new(),
                 library: &mut Library::empty(gc_context),
                 navigator: &mut NullNavigatorBackend::new(),
                 renderer: &mut NullRenderer::new(),
                 locale: &mut NullLocaleBackend::new(),
+                fonts: &mut NullFontBackend::new(),
+                fonts: &mut NullFontBackend::new(),
                 log: &mut NullLogBackend::new(),
                 video: &mut NullVideoBackend::new(),
                 mouse_over_object: None,
@@ -850,4 +894,30 @@ mod tests {
             assert!(!keys.contains(&"virtual_hidden".into()));
         })
     }
+
+    #[test]
+    fn test_version_hidden() {
+        with_object(6, |activation, object| {
+            object.as_script_object().unwrap().define_value(
+                activation.context.gc_context,
+                "swf6",
+                "visible".into(),
+                Attribute::VERSION_6,
+            );
+            object.as_script_object().unwrap().define_value(
+                activation.context.gc_context,
+                "swf7",
+                "hidden".into(),
+                Attribute::VERSION_7,
+            );
+
+            assert_eq!(object.get("swf6", activation).unwrap(), "visible".into());
+            assert_eq!(object.get("swf7", activation).unwrap(), Value::Undefined);
+            assert!(!object.has_own_property(activation, "swf7".into()));
+
+            let keys: Vec<_> = object.get_keys(activation);
+            assert!(keys.contains(&"swf6".into()));
+            assert!(!keys.contains(&"swf7".into()));
+        })
+    }
 }
@@ -0,0 +1,62 @@
+//! AVM1 object type to represent TextField.StyleSheet objects.
+
+use crate::avm1::{Object, ScriptObject, TObject};
+use crate::html::StyleSheet;
+use crate::impl_custom_object;
+use gc_arena::{Collect, GcCell, MutationContext};
+use std::cell::RefMut;
+use std::fmt;
+
+/// A StyleSheetObject that holds the CSS rules of a `TextField.StyleSheet`.
+#[derive(Clone, Copy, Collect)]
+#[collect(no_drop)]
+pub struct StyleSheetObject<'gc>(GcCell<'gc, StyleSheetObjectData<'gc>>);
+
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct StyleSheetObjectData<'gc> {
+    /// The underlying script object.
+    base: ScriptObject<'gc>,
+
+    /// The styles of this style sheet.
+    #[collect(require_static)]
+    style_sheet: StyleSheet,
+}
+
+impl fmt::Debug for StyleSheetObject<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let this = self.0.read();
+        f.debug_struct("StyleSheetObject")
+            .field("style_sheet", &this.style_sheet)
+            .finish()
+    }
+}
+
+impl<'gc> StyleSheetObject<'gc> {
+    pub fn empty_style_sheet(
+        gc_context: MutationContext<'gc, '_>,
+        proto: Option<Object<'gc>>,
+    ) -> StyleSheetObject<'gc> {
+        StyleSheetObject(GcCell::allocate(
+            gc_context,
+            StyleSheetObjectData {
+                base: ScriptObject::object(gc_context, proto),
+                style_sheet: StyleSheet::new(),
+            },
+        ))
+    }
+
+    pub fn style_sheet(self) -> StyleSheet {
+        self.0.read().style_sheet.clone()
+    }
+
+    pub fn style_sheet_mut(&self, gc_context: MutationContext<'gc, '_>) -> RefMut<StyleSheet> {
+        RefMut::map(self.0.write(gc_context), |o| &mut o.style_sheet)
+    }
+}
+
+impl<'gc> TObject<'gc> for StyleSheetObject<'gc> {
+    impl_custom_object!(base {
+        bare_object(as_style_sheet_object -> StyleSheetObject::empty_style_sheet);
+    });
+}
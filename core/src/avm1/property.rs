@@ -10,10 +10,22 @@ bitflags! {
     /// The values are significant and should match the order used by `object::as_set_prop_flags`.
     #[derive(Collect)]
     #[collect(require_static)]
-    pub struct Attribute: u8 {
+    pub struct Attribute: u16 {
         const DONT_ENUM   = 1 << 0;
         const DONT_DELETE = 1 << 1;
         const READ_ONLY   = 1 << 2;
+
+        /// The property is hidden from SWFs older than version 6.
+        const VERSION_6   = 1 << 7;
+
+        /// The property is hidden from SWFs older than version 7.
+        const VERSION_7   = 1 << 10;
+
+        /// The property is hidden from SWFs older than version 8.
+        const VERSION_8   = 1 << 12;
+
+        /// The property is hidden from SWFs older than version 9.
+        const VERSION_9   = 1 << 13;
     }
 }
 
@@ -101,6 +113,21 @@ impl<'gc> Property<'gc> {
     pub fn is_virtual(&self) -> bool {
         self.getter.is_some()
     }
+
+    /// Whether this property is visible to code from a given SWF version.
+    pub fn allow_swf_version(&self, swf_version: u8) -> bool {
+        if self.attributes.contains(Attribute::VERSION_9) {
+            swf_version >= 9
+        } else if self.attributes.contains(Attribute::VERSION_8) {
+            swf_version >= 8
+        } else if self.attributes.contains(Attribute::VERSION_7) {
+            swf_version >= 7
+        } else if self.attributes.contains(Attribute::VERSION_6) {
+            swf_version >= 6
+        } else {
+            true
+        }
+    }
 }
 
 impl fmt::Debug for Property<'_> {
@@ -27,7 +27,7 @@ pub struct Declaration {
     // This should be an `Attribute`, but because of `const` shenanigans
     // we need to store the raw flags.
     // See the comment in the `declare_properties!` macro.
-    pub attributes: u8,
+    pub attributes: u16,
 }
 
 /// All the possible types of a [`Declaration`].
@@ -149,7 +149,7 @@ macro_rules! declare_properties {
                 WARNING: HORRIBLE HACK AHEAD!
 
                 To declare property attributes in a way that is valid in `const` context,
-                we store them as raw `u8`s and do the bitflag management ourselves.
+                we store them as raw `u16`s and do the bitflag management ourselves.
 
                 Here are two better ways that unfortunately don't work.
 
@@ -180,6 +180,18 @@ macro_rules! declare_properties {
     (@__attr READ_ONLY) => {
         (1 << 2)
     };
+    (@__attr VERSION_6) => {
+        (1 << 7)
+    };
+    (@__attr VERSION_7) => {
+        (1 << 10)
+    };
+    (@__attr VERSION_8) => {
+        (1 << 12)
+    };
+    (@__attr VERSION_9) => {
+        (1 << 13)
+    };
     (@__kind property($getter:expr)) => {
         crate::avm1::property_decl::DeclKind::Property {
             getter: $getter,
@@ -26,6 +26,13 @@ pub enum ScopeClass {
     /// Scope represents an object added to the scope chain with `with`.
     /// It is not inherited when closures are defined.
     With,
+
+    /// Scope holding the name of a named function, so that the function can
+    /// call itself even if the variable it was defined as is reassigned.
+    ///
+    /// Values are resolved from it as if they were found on no object, and it
+    /// can't be assigned to.
+    FunctionName,
 }
 
 /// Represents a scope chain for an AVM1 activation.
@@ -111,6 +118,25 @@ impl<'gc> Scope<'gc> {
         })
     }
 
+    /// Construct a scope for the name of a named function, to be used as the
+    /// parent of the function's local scopes.
+    ///
+    /// The function itself should be defined on the returned scope's object
+    /// once it has been created.
+    pub fn new_function_name_scope(
+        parent: GcCell<'gc, Self>,
+        mc: MutationContext<'gc, '_>,
+    ) -> GcCell<'gc, Self> {
+        GcCell::allocate(
+            mc,
+            Scope {
+                parent: Some(parent),
+                class: ScopeClass::FunctionName,
+                values: ScriptObject::object_cell(mc, None),
+            },
+        )
+    }
+
     /// Construct a scope for use with `tellTarget` code where the timeline
     /// scope has been replaced with another given object.
     pub fn new_target_scope(
@@ -238,10 +264,12 @@ impl<'gc> Scope<'gc> {
         this: Object<'gc>,
     ) -> Result<CallableValue<'gc>, Error<'gc>> {
         if self.locals().has_property(activation, name) {
-            return self
-                .locals()
-                .get(name, activation)
-                .map(|v| CallableValue::Callable(self.locals_cell(), v));
+            let value = self.locals().get(name, activation)?;
+            return Ok(if self.class == ScopeClass::FunctionName {
+                CallableValue::UnCallable(value)
+            } else {
+                CallableValue::Callable(self.locals_cell(), value)
+            });
         }
         if let Some(scope) = self.parent() {
             return scope.resolve(name, activation, this);
@@ -274,6 +302,7 @@ impl<'gc> Scope<'gc> {
     /// The traversal stops at Target scopes, which represents the movie clip timeline
     /// the code is executing in.
     /// If the value is not found, it is defined on this Target scope.
+    /// Function name scopes are skipped.
     pub fn set(
         &self,
         name: AvmString<'gc>,
@@ -281,7 +310,10 @@ impl<'gc> Scope<'gc> {
         activation: &mut Activation<'_, 'gc, '_>,
         this: Object<'gc>,
     ) -> Result<(), Error<'gc>> {
-        if self.class == ScopeClass::Target || self.locals().has_property(activation, name) {
+        if self.class == ScopeClass::Target
+            || (self.class != ScopeClass::FunctionName
+                && self.locals().has_property(activation, name))
+        {
             // Value found on this object, so overwrite it.
             // Or we've hit the executing movie clip, so create it here.
             self.locals().set(name, value, activation)
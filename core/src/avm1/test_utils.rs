@@ -4,6 +4,7 @@ use crate::avm1::globals::system::SystemProperties;
 use crate::avm1::{Avm1, Object, Timers, UpdateContext};
 use crate::avm2::Avm2;
 use crate::backend::audio::{AudioManager, NullAudioBackend};
+use crate::backend::font::NullFontBackend;
 use crate::backend::locale::NullLocaleBackend;
 use crate::backend::log::NullLogBackend;
 use crate::backend::navigator::NullNavigatorBackend;
@@ -16,7 +17,11 @@ use crate::display_object::{MovieClip, Stage, TDisplayObject};
 use crate::focus_tracker::FocusTracker;
 use crate::library::Library;
 use crate::loader::LoadManager;
+use crate::local_connection::LocalConnections;
+use crate::microphone::Microphone;
 use crate::prelude::*;
+use crate::socket::Sockets;
+use crate::streams::StreamManager;
 use crate::tag_utils::SwfMovie;
 use crate::vminterface::Instantiator;
 use gc_arena::{rootless_arena, MutationContext};
@@ -56,6 +61,7 @@ where
             navigator: &mut NullNavigatorBackend::new(),
             renderer: &mut NullRenderer::new(),
             locale: &mut NullLocaleBackend::new(),
+            fonts: &mut NullFontBackend::new(),
             log: &mut NullLogBackend::new(),
             video: &mut NullVideoBackend::new(),
             mouse_over_object: None,
@@ -81,6 +87,10 @@ where
             times_get_time_called: 0,
             time_offset: &mut 0,
             audio_manager: &mut AudioManager::new(),
+            stream_manager: &mut StreamManager::new(),
+            microphone: &mut Microphone::new(),
+            sockets: &mut Sockets::new(),
+            local_connections: &mut LocalConnections::new(),
             frame_rate: &mut frame_rate,
         };
         context.stage.replace_at_depth(&mut context, root, 0);
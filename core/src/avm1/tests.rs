@@ -1,6 +1,13 @@
+use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
+use crate::avm1::function::{FunctionObject, NativeFunction};
 use crate::avm1::test_utils::with_avm;
-use crate::avm1::TObject;
+use crate::avm1::{Object, ScriptObject, TObject, Value};
+use crate::tag_utils::{SwfMovie, SwfSlice};
+use std::sync::Arc;
+use swf::avm1::types::{Action, Value as SwfValue};
+use swf::avm1::write::Writer;
+use swf::{Header, Tag};
 
 #[test]
 fn locals_into_form_values() {
@@ -23,3 +30,180 @@ fn locals_into_form_values() {
         Ok(())
     });
 }
+
+fn return_this<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.into())
+}
+
+#[test]
+fn bound_function_keeps_this() {
+    with_avm(8, |activation, root| -> Result<(), Error> {
+        let gc_context = activation.context.gc_context;
+        let fn_proto = activation.context.avm1.prototypes().function;
+        let function = FunctionObject::function(
+            gc_context,
+            return_this as NativeFunction,
+            Some(fn_proto),
+            fn_proto,
+        );
+        let bound = FunctionObject::bound(gc_context, function, root, Some(fn_proto));
+
+        let other = ScriptObject::object(gc_context, None).into();
+        assert_eq!(
+            bound.call("[Bound]".into(), activation, other, &[])?,
+            root.into()
+        );
+        assert_eq!(
+            function.call("[Unbound]".into(), activation, other, &[])?,
+            other.into()
+        );
+        Ok(())
+    });
+}
+
+/// Encodes the given actions into AVM1 bytecode.
+fn encode_actions(actions: &[Action]) -> Vec<u8> {
+    let mut data = Vec::new();
+    let mut writer = Writer::new(&mut data, 8);
+    for action in actions {
+        writer.write_action(action).unwrap();
+    }
+    data
+}
+
+/// Runs the given actions on the timeline of `root`.
+fn run_actions<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    root: Object<'gc>,
+    actions: &[Action],
+) -> Result<(), Error<'gc>> {
+    let code = encode_actions(actions);
+    let mut swf_data = Vec::new();
+    swf::write_swf(
+        &Header::default_with_swf_version(8),
+        &[Tag::DoAction(&code)],
+        &mut swf_data,
+    )
+    .unwrap();
+    let movie = Arc::new(SwfMovie::from_data(&swf_data, None, None).unwrap());
+    let start = movie
+        .data()
+        .windows(code.len())
+        .position(|window| window == code)
+        .unwrap();
+    let code = SwfSlice::from(movie)
+        .to_start_and_end(start, start + code.len())
+        .unwrap();
+    let clip = root.as_display_object().unwrap();
+    activation.run_child_frame_for_action("[Test Actions]", clip, 8, code)?;
+    Ok(())
+}
+
+fn push_str(value: &str) -> Action<'_> {
+    Action::Push(vec![SwfValue::Str(value.into())])
+}
+
+#[test]
+fn named_function_refers_to_itself() {
+    with_avm(8, |activation, root| -> Result<(), Error> {
+        // function f() { return f; }
+        let body = encode_actions(&[push_str("f"), Action::GetVariable, Action::Return]);
+        run_actions(
+            activation,
+            root,
+            &[
+                Action::DefineFunction {
+                    name: "f".into(),
+                    params: vec![],
+                    actions: &body,
+                },
+                // g = f;
+                push_str("g"),
+                push_str("f"),
+                Action::GetVariable,
+                Action::SetVariable,
+                // f = 1;
+                push_str("f"),
+                Action::Push(vec![SwfValue::Int(1)]),
+                Action::SetVariable,
+                // result = g();
+                push_str("result"),
+                Action::Push(vec![SwfValue::Int(0), SwfValue::Str("g".into())]),
+                Action::CallFunction,
+                Action::SetVariable,
+            ],
+        )?;
+
+        assert_eq!(root.get("f", activation)?, 1.into());
+        assert_eq!(root.get("result", activation)?, root.get("g", activation)?);
+        Ok(())
+    });
+}
+
+#[test]
+fn function_name_scope_is_skipped_by_set() {
+    with_avm(8, |activation, root| -> Result<(), Error> {
+        // function f() { f = 2; }
+        let body = encode_actions(&[
+            push_str("f"),
+            Action::Push(vec![SwfValue::Int(2)]),
+            Action::SetVariable,
+        ]);
+        run_actions(
+            activation,
+            root,
+            &[
+                Action::DefineFunction {
+                    name: "f".into(),
+                    params: vec![],
+                    actions: &body,
+                },
+                // f();
+                Action::Push(vec![SwfValue::Int(0), SwfValue::Str("f".into())]),
+                Action::CallFunction,
+                Action::Pop,
+            ],
+        )?;
+
+        assert_eq!(root.get("f", activation)?, 2.into());
+        Ok(())
+    });
+}
+
+#[test]
+fn tell_target_in_with_block_persists() {
+    with_avm(8, |activation, root| -> Result<(), Error> {
+        let child = root.call_method(
+            "createEmptyMovieClip".into(),
+            &["child".into(), 1.into()],
+            activation,
+        )?;
+        let child = child.coerce_to_object(activation);
+
+        // with (_global) { tellTarget ("child") { ... } }
+        let with_body = encode_actions(&[Action::SetTarget("child".into())]);
+        run_actions(
+            activation,
+            root,
+            &[
+                push_str("_global"),
+                Action::GetVariable,
+                Action::With {
+                    actions: &with_body,
+                },
+                // x = 5;
+                push_str("x"),
+                Action::Push(vec![SwfValue::Int(5)]),
+                Action::SetVariable,
+            ],
+        )?;
+
+        assert_eq!(child.get("x", activation)?, 5.into());
+        assert!(!root.has_property(activation, "x".into()));
+        Ok(())
+    });
+}
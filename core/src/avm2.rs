@@ -1,8 +1,10 @@
 //! ActionScript Virtual Machine 2 (AS3) support
 
+use crate::avm2::bytearray::ByteArrayStorage;
+use crate::avm2::e4x::XmlSettings;
 use crate::avm2::globals::{SystemClasses, SystemPrototypes};
 use crate::avm2::method::Method;
-use crate::avm2::object::EventObject;
+use crate::avm2::object::{ByteArrayObject, EventObject};
 use crate::avm2::script::{Script, TranslationUnit};
 use crate::context::UpdateContext;
 use crate::string::AvmString;
@@ -22,10 +24,12 @@ macro_rules! avm_debug {
 }
 
 mod activation;
+mod amf;
 mod array;
 mod bytearray;
 mod class;
 mod domain;
+mod e4x;
 mod events;
 mod function;
 mod globals;
@@ -40,6 +44,8 @@ mod scope;
 mod script;
 mod slot;
 mod string;
+#[cfg(test)]
+pub mod test_utils;
 mod traits;
 mod value;
 mod vector;
@@ -50,7 +56,8 @@ pub use crate::avm2::domain::Domain;
 pub use crate::avm2::events::Event;
 pub use crate::avm2::names::{Namespace, QName};
 pub use crate::avm2::object::{
-    ArrayObject, ClassObject, Object, ScriptObject, SoundChannelObject, StageObject, TObject,
+    ArrayObject, ClassObject, LoaderInfoObject, LoaderStream, Object, ScriptObject,
+    SoundChannelObject, StageObject, TObject,
 };
 pub use crate::avm2::value::Value;
 
@@ -88,6 +95,17 @@ pub struct Avm2<'gc> {
     /// collector does not support weak references.
     broadcast_list: FnvHashMap<AvmString<'gc>, Vec<Object<'gc>>>,
 
+    /// Local shared objects returned by `SharedObject.getLocal`, by their storage name.
+    shared_objects: FnvHashMap<String, Object<'gc>>,
+
+    /// The settings of the `XML` class.
+    xml_settings: XmlSettings,
+
+    /// Classes registered with `registerClassAlias`, by their alias.
+    ///
+    /// Objects of these classes keep their class through AMF serialization.
+    class_aliases: FnvHashMap<AvmString<'gc>, ClassObject<'gc>>,
+
     #[cfg(feature = "avm_debug")]
     pub debug_output: bool,
 }
@@ -103,6 +121,9 @@ impl<'gc> Avm2<'gc> {
             system_prototypes: None,
             system_classes: None,
             broadcast_list: Default::default(),
+            shared_objects: Default::default(),
+            xml_settings: Default::default(),
+            class_aliases: Default::default(),
 
             #[cfg(feature = "avm_debug")]
             debug_output: false,
@@ -129,6 +150,58 @@ impl<'gc> Avm2<'gc> {
         self.system_classes.as_ref().unwrap()
     }
 
+    /// The local shared object stored under `name`, if it has been loaded.
+    pub fn shared_object(&self, name: &str) -> Option<Object<'gc>> {
+        self.shared_objects.get(name).copied()
+    }
+
+    /// The storage name of a local shared object.
+    pub fn shared_object_name(&self, object: Object<'gc>) -> Option<&str> {
+        self.shared_objects
+            .iter()
+            .find(|(_, so)| Object::ptr_eq(**so, object))
+            .map(|(name, _)| name.as_str())
+    }
+
+    pub fn add_shared_object(&mut self, name: String, object: Object<'gc>) {
+        self.shared_objects.insert(name, object);
+    }
+
+    /// The settings of the `XML` class.
+    pub fn xml_settings(&self) -> XmlSettings {
+        self.xml_settings
+    }
+
+    pub fn set_xml_settings(&mut self, xml_settings: XmlSettings) {
+        self.xml_settings = xml_settings;
+    }
+
+    pub fn register_class_alias(&mut self, alias: AvmString<'gc>, class: ClassObject<'gc>) {
+        self.class_aliases.insert(alias, class);
+    }
+
+    /// The class registered under an alias.
+    pub fn class_by_alias(&self, alias: AvmString<'gc>) -> Option<ClassObject<'gc>> {
+        self.class_aliases.get(&alias).copied()
+    }
+
+    /// The alias that a class was registered under, if any.
+    pub fn class_alias(&self, class: ClassObject<'gc>) -> Option<AvmString<'gc>> {
+        self.class_aliases
+            .iter()
+            .find(|(_, c)| Object::ptr_eq(**c, class))
+            .map(|(alias, _)| *alias)
+    }
+
+    /// Write all local shared objects that have been loaded to storage.
+    pub fn flush_shared_objects(context: &mut UpdateContext<'_, 'gc, '_>) {
+        let shared_objects: Vec<_> = context.avm2.shared_objects.values().copied().collect();
+        let mut activation = Activation::from_nothing(context.reborrow());
+        for so in shared_objects {
+            let _ = globals::flash::net::sharedobject::write_to_storage(&mut activation, so, 0);
+        }
+    }
+
     /// Run a script's initializer method.
     pub fn run_script_initializer(
         script: Script<'gc>,
@@ -183,6 +256,212 @@ impl<'gc> Avm2<'gc> {
         dispatch_event(&mut activation, target, event_object)
     }
 
+    /// Dispatch an `activity` event on a `Microphone` or `Camera`.
+    pub fn dispatch_activity_event(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        target: Object<'gc>,
+        activating: bool,
+    ) -> Result<(), Error> {
+        use crate::avm2::events::dispatch_event;
+
+        let mut activation = Activation::from_nothing(context.reborrow());
+
+        let event_class = activation.avm2().classes().activityevent;
+        let event_object = event_class.construct(
+            &mut activation,
+            &[
+                "activity".into(),
+                false.into(),
+                false.into(),
+                activating.into(),
+            ],
+        )?;
+        dispatch_event(&mut activation, target, event_object)?;
+
+        Ok(())
+    }
+
+    /// Dispatch a `status` event with the given code and level.
+    pub fn dispatch_status_event(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        target: Object<'gc>,
+        code: &'static str,
+        level: &'static str,
+    ) -> Result<(), Error> {
+        use crate::avm2::events::dispatch_event;
+
+        let mut activation = Activation::from_nothing(context.reborrow());
+
+        let event_class = activation.avm2().classes().statusevent;
+        let event_object = event_class.construct(
+            &mut activation,
+            &[
+                "status".into(),
+                false.into(),
+                false.into(),
+                code.into(),
+                level.into(),
+            ],
+        )?;
+        dispatch_event(&mut activation, target, event_object)?;
+
+        Ok(())
+    }
+
+    /// Dispatch a `link` event for a clicked `event:` link in a text field.
+    pub fn dispatch_link_event(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        target: Object<'gc>,
+        text: AvmString<'gc>,
+    ) -> Result<(), Error> {
+        use crate::avm2::events::dispatch_event;
+
+        let mut activation = Activation::from_nothing(context.reborrow());
+
+        let event_class = activation.avm2().classes().textevent;
+        let event_object = event_class.construct(
+            &mut activation,
+            &["link".into(), true.into(), false.into(), text.into()],
+        )?;
+        dispatch_event(&mut activation, target, event_object)?;
+
+        Ok(())
+    }
+
+    /// Dispatch a `progress` event on a loader.
+    pub fn dispatch_progress_event(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        target: Object<'gc>,
+        bytes_loaded: usize,
+        bytes_total: usize,
+    ) -> Result<(), Error> {
+        use crate::avm2::events::dispatch_event;
+
+        let mut activation = Activation::from_nothing(context.reborrow());
+
+        let event_class = activation.avm2().classes().progressevent;
+        let event_object = event_class.construct(
+            &mut activation,
+            &[
+                "progress".into(),
+                false.into(),
+                false.into(),
+                bytes_loaded.into(),
+                bytes_total.into(),
+            ],
+        )?;
+        dispatch_event(&mut activation, target, event_object)?;
+
+        Ok(())
+    }
+
+    /// Dispatch an `httpStatus` event on a loader.
+    pub fn dispatch_http_status_event(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        target: Object<'gc>,
+        status: u16,
+    ) -> Result<(), Error> {
+        use crate::avm2::events::dispatch_event;
+
+        let mut activation = Activation::from_nothing(context.reborrow());
+
+        let event_class = activation.avm2().classes().httpstatusevent;
+        let event_object = event_class.construct(
+            &mut activation,
+            &[
+                "httpStatus".into(),
+                false.into(),
+                false.into(),
+                i32::from(status).into(),
+            ],
+        )?;
+        dispatch_event(&mut activation, target, event_object)?;
+
+        Ok(())
+    }
+
+    /// Dispatch an `ioError` event on a loader whose load failed.
+    pub fn dispatch_io_error_event(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        target: Object<'gc>,
+        error_id: i32,
+        text: &str,
+    ) -> Result<(), Error> {
+        use crate::avm2::events::dispatch_event;
+
+        let mut activation = Activation::from_nothing(context.reborrow());
+
+        let text = AvmString::new_utf8(
+            activation.context.gc_context,
+            format!("Error #{}: {}", error_id, text),
+        );
+        let event_class = activation.avm2().classes().ioerrorevent;
+        let event_object = event_class.construct(
+            &mut activation,
+            &[
+                "ioError".into(),
+                false.into(),
+                false.into(),
+                text.into(),
+                error_id.into(),
+            ],
+        )?;
+        dispatch_event(&mut activation, target, event_object)?;
+
+        Ok(())
+    }
+
+    /// Store the response of a completed load in a `URLLoader`.
+    pub fn set_url_loader_data(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        target: Object<'gc>,
+        data: Vec<u8>,
+    ) -> Result<(), Error> {
+        let mut activation = Activation::from_nothing(context.reborrow());
+        globals::flash::net::urlloader::set_loaded_data(&mut activation, target, data)
+    }
+
+    /// Fire a `sampleData` event on a `Sound` that generates its own audio.
+    ///
+    /// `position` is the number of sample frames provided so far. The sample
+    /// frames written to the event's `data` by its handlers are returned.
+    pub fn request_sample_data(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        sound: Object<'gc>,
+        position: u32,
+    ) -> Result<Vec<[i16; 2]>, Error> {
+        use crate::avm2::events::dispatch_event;
+
+        let mut activation = Activation::from_nothing(context.reborrow());
+
+        let data = ByteArrayObject::from_storage(&mut activation, ByteArrayStorage::new())?;
+        let event_class = activation.avm2().classes().sampledataevent;
+        let event_object = event_class.construct(
+            &mut activation,
+            &[
+                "sampleData".into(),
+                false.into(),
+                false.into(),
+                position.into(),
+                data.into(),
+            ],
+        )?;
+        dispatch_event(&mut activation, sound, event_object)?;
+
+        let bytearray = data
+            .as_bytearray()
+            .ok_or("sampleData buffer is not a ByteArray")?;
+        bytearray.set_position(0);
+        let mut samples = Vec::with_capacity(bytearray.len() / 8);
+        while bytearray.bytes_available() >= 8 {
+            let left = bytearray.read_float()?;
+            let right = bytearray.read_float()?;
+            samples.push([left, right].map(|sample| (sample.clamp(-1.0, 1.0) * 32767.0) as i16));
+        }
+
+        Ok(samples)
+    }
+
     /// Add an object to the broadcast list.
     ///
     /// Each broadcastable event contains it's own broadcast list. You must
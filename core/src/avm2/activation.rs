@@ -6,12 +6,14 @@ use crate::avm2::domain::Domain;
 use crate::avm2::method::{BytecodeMethod, Method, ParamConfig};
 use crate::avm2::names::{Multiname, Namespace, QName};
 use crate::avm2::object::{
-    ArrayObject, ByteArrayObject, ClassObject, FunctionObject, NamespaceObject, ScriptObject,
+    xml_nodes, ArrayObject, ByteArrayObject, ClassObject, FunctionObject, NamespaceObject,
+    ScriptObject, XmlListObject,
 };
 use crate::avm2::object::{Object, TObject};
 use crate::avm2::scope::{Scope, ScopeChain, ScopeStack};
 use crate::avm2::script::Script;
 use crate::avm2::value::Value;
+use crate::avm2::vector::VectorStorage;
 use crate::avm2::{value, Avm2, Error};
 use crate::context::UpdateContext;
 use crate::string::{AvmString, WStr, WString};
@@ -137,6 +139,15 @@ pub struct Activation<'a, 'gc: 'a, 'gc_context: 'a> {
     /// and we will not allocate a class for one.
     activation_class: Option<ClassObject<'gc>>,
 
+    /// The default XML namespace, as set by `default xml namespace = ...`.
+    ///
+    /// Unqualified names in E4X lookups and new XML elements are put in this
+    /// namespace. If this is `None`, they are in no namespace.
+    ///
+    /// Methods start out with the namespace of the scope chain they closed
+    /// over, and builtins with the namespace of their caller.
+    default_xml_namespace: Option<AvmString<'gc>>,
+
     pub context: UpdateContext<'a, 'gc, 'gc_context>,
 }
 
@@ -164,6 +175,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
             caller_domain: context.avm2.globals,
             subclass_object: None,
             activation_class: None,
+            default_xml_namespace: None,
             context,
         }
     }
@@ -205,6 +217,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
             caller_domain: domain,
             subclass_object: None,
             activation_class: None,
+            default_xml_namespace: None,
             context,
         })
     }
@@ -426,6 +439,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
             caller_domain: outer.domain(),
             subclass_object,
             activation_class,
+            default_xml_namespace: outer.default_xml_namespace(),
             context,
         };
 
@@ -488,6 +502,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         subclass_object: Option<ClassObject<'gc>>,
         outer: ScopeChain<'gc>,
         caller_domain: Domain<'gc>,
+        default_xml_namespace: Option<AvmString<'gc>>,
     ) -> Result<Self, Error> {
         let local_registers = GcCell::allocate(context.gc_context, RegisterSet::new(0));
 
@@ -503,6 +518,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
             caller_domain,
             subclass_object,
             activation_class: None,
+            default_xml_namespace,
             context,
         })
     }
@@ -586,9 +602,18 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
 
     /// Creates a new ScopeChain by chaining the current state of this
     /// activation's scope stack with the outer scope.
+    ///
+    /// Functions closing over the new ScopeChain inherit the current default
+    /// XML namespace.
     pub fn create_scopechain(&self) -> ScopeChain<'gc> {
         self.outer
             .chain(self.context.gc_context, self.scope_stack.scopes())
+            .with_default_xml_namespace(self.default_xml_namespace)
+    }
+
+    /// Returns the default XML namespace to pass on to called builtins.
+    pub fn caller_default_xml_namespace(&self) -> Option<AvmString<'gc>> {
+        self.default_xml_namespace
     }
 
     /// Returns the domain of the original AS3 caller.
@@ -616,6 +641,12 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         self.context.avm2
     }
 
+    /// Returns the URI of the default XML namespace, which is empty if there
+    /// is none.
+    pub fn default_xml_namespace(&self) -> AvmString<'gc> {
+        self.default_xml_namespace.unwrap_or_default()
+    }
+
     /// Set the return value.
     pub fn set_return_value(&mut self, value: Value<'gc>) {
         self.return_value = Some(value);
@@ -939,6 +970,10 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
                 Op::TypeOf => self.op_type_of(),
                 Op::EscXAttr => self.op_esc_xattr(),
                 Op::EscXElem => self.op_esc_elem(),
+                Op::GetDescendants { index } => self.op_get_descendants(method, index),
+                Op::CheckFilter => self.op_check_filter(),
+                Op::Dxns { index } => self.op_dxns(method, index),
+                Op::DxnsLate => self.op_dxns_late(),
                 Op::LookupSwitch {
                     default_offset,
                     case_offsets,
@@ -1286,6 +1321,16 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
                 }
             }
 
+            if let Some(index) = name_value.as_element_index() {
+                if let Some(vector) = object.as_vector_storage() {
+                    let value = vector.get(index)?;
+                    drop(vector);
+                    self.context.avm2.push(value);
+
+                    return Ok(FrameControl::Continue);
+                }
+            }
+
             (
                 Multiname::from_multiname_late(txunit, abc_multiname, name_value, self)?,
                 object,
@@ -1334,6 +1379,19 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
                 }
             }
 
+            if let Some(index) = name_value.as_element_index() {
+                let value_type = object.as_vector_storage().map(|v| v.value_type());
+                if let Some(value_type) = value_type {
+                    let value = VectorStorage::coerce(value, value_type, self)?;
+                    object
+                        .as_vector_storage_mut(self.context.gc_context)
+                        .unwrap()
+                        .set(index, value, self)?;
+
+                    return Ok(FrameControl::Continue);
+                }
+            }
+
             (
                 Multiname::from_multiname_late(txunit, abc_multiname, name_value, self)?,
                 object,
@@ -1870,7 +1928,17 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         let value2 = self.context.avm2.pop();
         let value1 = self.context.avm2.pop();
 
-        // TODO: Special handling required for `Date` and ECMA-357/E4X `XML`
+        // Adding two ECMA-357/E4X `XML` or `XMLList` objects concatenates them.
+        if let (Value::Object(o1), Value::Object(o2)) = (value1, value2) {
+            if let (Some(nodes1), Some(nodes2)) = (xml_nodes(o1), xml_nodes(o2)) {
+                let list = XmlListObject::new(self, [nodes1, nodes2].concat())?;
+                self.context.avm2.push(list);
+
+                return Ok(FrameControl::Continue);
+            }
+        }
+
+        // TODO: Special handling required for `Date`
         let sum_value = match (value1, value2) {
             (Value::Number(n1), Value::Number(n2)) => Value::Number(n1 + n2),
             (Value::String(s), value2) => Value::String(AvmString::concat(
@@ -2639,7 +2707,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
                             "object"
                         }
                     }
-                    Object::XmlObject(_) => {
+                    Object::XmlObject(_) | Object::XmlListObject(_) => {
                         if is_not_subclass {
                             "xml"
                         } else {
@@ -2713,6 +2781,87 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         Ok(FrameControl::Continue)
     }
 
+    /// Implements `Op::GetDescendants`
+    fn op_get_descendants(
+        &mut self,
+        method: Gc<'gc, BytecodeMethod<'gc>>,
+        index: Index<AbcMultiname>,
+    ) -> Result<FrameControl<'gc>, Error> {
+        let multiname = self.pool_multiname(method, index)?;
+        let object = self.context.avm2.pop().coerce_to_object(self)?;
+
+        if let Some(proxy) = object.as_proxy_object() {
+            let descendants = proxy.get_descendants(&multiname, self)?;
+            self.context.avm2.push(descendants);
+
+            return Ok(FrameControl::Continue);
+        }
+
+        let nodes = match xml_nodes(object) {
+            Some(nodes) => nodes,
+            None => {
+                return Err(format!(
+                    "TypeError: Error #1016: Descendants operator (..) not supported on type {}.",
+                    object
+                        .instance_of_class_definition()
+                        .map(|c| c.read().name().local_name())
+                        .unwrap_or_else(|| "Object".into())
+                )
+                .into())
+            }
+        };
+
+        let default_namespace = self.default_xml_namespace();
+        let mut descendants = Vec::new();
+        for node in nodes {
+            node.descendants(&multiname, default_namespace, &mut descendants);
+        }
+
+        let list = XmlListObject::new(self, descendants)?;
+        self.context.avm2.push(list);
+
+        Ok(FrameControl::Continue)
+    }
+
+    /// Implements `Op::CheckFilter`
+    fn op_check_filter(&mut self) -> Result<FrameControl<'gc>, Error> {
+        let value = self.context.avm2.pop();
+        self.context.avm2.push(value);
+
+        let is_xml = matches!(
+            value,
+            Value::Object(o) if o.as_xml_object().is_some() || o.as_xml_list_object().is_some()
+        );
+        if !is_xml {
+            return Err(format!(
+                "TypeError: Error #1123: Filter operator not supported on type {}.",
+                value.coerce_to_string(self)?
+            )
+            .into());
+        }
+
+        Ok(FrameControl::Continue)
+    }
+
+    /// Implements `Op::Dxns`
+    fn op_dxns(
+        &mut self,
+        method: Gc<'gc, BytecodeMethod<'gc>>,
+        index: Index<String>,
+    ) -> Result<FrameControl<'gc>, Error> {
+        self.default_xml_namespace = Some(self.pool_string(&method, index)?);
+
+        Ok(FrameControl::Continue)
+    }
+
+    /// Implements `Op::DxnsLate`
+    fn op_dxns_late(&mut self) -> Result<FrameControl<'gc>, Error> {
+        let uri = self.context.avm2.pop().coerce_to_string(self)?;
+        self.default_xml_namespace = Some(uri);
+
+        Ok(FrameControl::Continue)
+    }
+
     /// Implements `Op::LookupSwitch`
     fn op_lookup_switch<'b>(
         &mut self,
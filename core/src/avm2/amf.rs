@@ -0,0 +1,1259 @@
+//! AMF serialization of AVM2 values, as done by `ByteArray.writeObject` and
+//! `ByteArray.readObject`.
+//!
+//! AMF3 is (de)serialized straight from and to AVM2 values, so that repeated
+//! strings and objects can be written once and referenced afterwards, and so
+//! that shared references survive a round trip.
+
+use crate::avm2::activation::Activation;
+use crate::avm2::array::ArrayStorage;
+use crate::avm2::bytearray::ByteArrayStorage;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{
+    xml_nodes, ArrayObject, ByteArrayObject, ClassObject, Object, TObject, VectorObject,
+};
+use crate::avm2::traits::TraitKind;
+use crate::avm2::value::Value;
+use crate::avm2::vector::VectorStorage;
+use crate::avm2::Error;
+use crate::string::AvmString;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+// AMF3 type markers.
+const UNDEFINED: u8 = 0x00;
+const NULL: u8 = 0x01;
+const FALSE: u8 = 0x02;
+const TRUE: u8 = 0x03;
+const INTEGER: u8 = 0x04;
+const DOUBLE: u8 = 0x05;
+const STRING: u8 = 0x06;
+const XML_DOCUMENT: u8 = 0x07;
+const DATE: u8 = 0x08;
+const ARRAY: u8 = 0x09;
+const OBJECT: u8 = 0x0A;
+const XML: u8 = 0x0B;
+const BYTE_ARRAY: u8 = 0x0C;
+const VECTOR_INT: u8 = 0x0D;
+const VECTOR_UINT: u8 = 0x0E;
+const VECTOR_DOUBLE: u8 = 0x0F;
+const VECTOR_OBJECT: u8 = 0x10;
+const DICTIONARY: u8 = 0x11;
+
+/// AMF3 integers are 29 bits wide.
+const INTEGER_RANGE: RangeInclusive<i64> = -(1 << 28)..=(1 << 28) - 1;
+
+/// How deeply values may be nested in the data that is read, so that
+/// malicious data can't overflow the native stack.
+const MAX_DEPTH: usize = 128;
+
+/// Whether a value is written at all as the property of an object.
+fn is_serializable(value: Value<'_>) -> bool {
+    !matches!(value, Value::Object(o) if o.as_executable().is_some())
+}
+
+/// The enumerable properties of an object, by name.
+fn dynamic_properties<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<Vec<(Value<'gc>, Value<'gc>)>, Error> {
+    let mut properties = Vec::new();
+    let mut index = 0;
+    while let Some(next) = object.get_next_enumerant(index, activation)? {
+        // An enumerant of 0 also ends the enumeration.
+        if next == 0 {
+            break;
+        }
+        index = next;
+
+        let name = object.get_enumerant_name(index, activation)?;
+        let value = object.get_enumerant_value(index, activation)?;
+        if is_serializable(value) {
+            properties.push((name, value));
+        }
+    }
+
+    Ok(properties)
+}
+
+/// The public variables of a class and its superclasses, which objects of
+/// aliased classes are serialized with.
+fn sealed_members<'gc>(class: ClassObject<'gc>) -> Vec<AvmString<'gc>> {
+    let mut members = Vec::new();
+    let mut class = Some(class);
+    while let Some(current) = class {
+        let definition = current.inner_class_definition();
+        let own_members = definition
+            .read()
+            .instance_traits()
+            .iter()
+            .filter(|t| {
+                t.name().namespace().is_public() && matches!(t.kind(), TraitKind::Slot { .. })
+            })
+            .map(|t| t.name().local_name())
+            .collect::<Vec<_>>();
+        members.splice(0..0, own_members);
+        class = current.superclass_object();
+    }
+
+    members
+}
+
+/// The `flash.utils.Dictionary` class.
+fn dictionary_class<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<ClassObject<'gc>, Error> {
+    let name = QName::new(Namespace::package("flash.utils"), "Dictionary");
+    activation
+        .avm2()
+        .global_domain()
+        .get_defined_value(activation, name)?
+        .coerce_to_object(activation)?
+        .as_class_object()
+        .ok_or_else(|| "Dictionary is not a class".into())
+}
+
+/// Serializes AVM2 values to AMF3.
+///
+/// A writer keeps the reference tables of everything it has written, so a
+/// new writer must be used for every top-level value.
+pub struct Amf3Writer<'gc> {
+    out: Vec<u8>,
+    strings: HashMap<AvmString<'gc>, u32>,
+    objects: HashMap<Object<'gc>, u32>,
+
+    /// The index of the traits of every aliased class whose objects have
+    /// been written, with `None` standing for anonymous objects.
+    traits: HashMap<Option<ClassObject<'gc>>, u32>,
+}
+
+impl<'gc> Amf3Writer<'gc> {
+    pub fn new() -> Self {
+        Self {
+            out: Vec::new(),
+            strings: HashMap::new(),
+            objects: HashMap::new(),
+            traits: HashMap::new(),
+        }
+    }
+
+    /// Serialize a single value into AMF3.
+    pub fn serialize(
+        activation: &mut Activation<'_, 'gc, '_>,
+        value: Value<'gc>,
+    ) -> Result<Vec<u8>, Error> {
+        let mut writer = Self::new();
+        writer.write_value(activation, value)?;
+        Ok(writer.out)
+    }
+
+    /// Write a variable-length 29-bit integer.
+    fn write_u29(&mut self, value: u32) {
+        let value = value & 0x1FFF_FFFF;
+        if value < 0x80 {
+            self.out.push(value as u8);
+        } else if value < 0x4000 {
+            self.out.push((value >> 7) as u8 | 0x80);
+            self.out.push((value & 0x7F) as u8);
+        } else if value < 0x20_0000 {
+            self.out.push((value >> 14) as u8 | 0x80);
+            self.out.push(((value >> 7) & 0x7F) as u8 | 0x80);
+            self.out.push((value & 0x7F) as u8);
+        } else {
+            self.out.push((value >> 22) as u8 | 0x80);
+            self.out.push(((value >> 15) & 0x7F) as u8 | 0x80);
+            self.out.push(((value >> 8) & 0x7F) as u8 | 0x80);
+            self.out.push((value & 0xFF) as u8);
+        }
+    }
+
+    /// Write a string, or a reference to it if it was already written.
+    ///
+    /// The empty string is never sent by reference.
+    fn write_string(&mut self, string: AvmString<'gc>) {
+        if string.is_empty() {
+            self.write_u29(1);
+        } else if let Some(index) = self.strings.get(&string) {
+            self.write_u29(index << 1);
+        } else {
+            self.strings.insert(string, self.strings.len() as u32);
+            self.write_utf8(&string.to_utf8_lossy());
+        }
+    }
+
+    /// Write the length-prefixed UTF-8 bytes of a string.
+    fn write_utf8(&mut self, string: &str) {
+        self.write_u29(((string.len() as u32) << 1) | 1);
+        self.out.extend_from_slice(string.as_bytes());
+    }
+
+    /// Write a reference to an object if it was already written, or add it
+    /// to the reference table otherwise.
+    ///
+    /// Returns whether a reference was written.
+    fn write_object_reference(&mut self, object: Object<'gc>) -> bool {
+        if let Some(index) = self.objects.get(&object) {
+            self.write_u29(index << 1);
+            true
+        } else {
+            self.objects.insert(object, self.objects.len() as u32);
+            false
+        }
+    }
+
+    fn write_double(&mut self, value: f64) {
+        self.out.push(DOUBLE);
+        self.out.extend_from_slice(&value.to_be_bytes());
+    }
+
+    pub fn write_value(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        value: Value<'gc>,
+    ) -> Result<(), Error> {
+        match value {
+            Value::Undefined => self.out.push(UNDEFINED),
+            Value::Null => self.out.push(NULL),
+            Value::Bool(false) => self.out.push(FALSE),
+            Value::Bool(true) => self.out.push(TRUE),
+            Value::Integer(i) if INTEGER_RANGE.contains(&i64::from(i)) => {
+                self.out.push(INTEGER);
+                self.write_u29(i as u32);
+            }
+            Value::Unsigned(u) if INTEGER_RANGE.contains(&i64::from(u)) => {
+                self.out.push(INTEGER);
+                self.write_u29(u);
+            }
+            Value::Integer(i) => self.write_double(i.into()),
+            Value::Unsigned(u) => self.write_double(u.into()),
+            Value::Number(n) => self.write_double(n),
+            Value::String(s) => {
+                self.out.push(STRING);
+                self.write_string(s);
+            }
+            Value::Object(o) => self.write_object(activation, o)?,
+        }
+
+        Ok(())
+    }
+
+    fn write_object(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        object: Object<'gc>,
+    ) -> Result<(), Error> {
+        // Functions can't be serialized.
+        if object.as_executable().is_some() {
+            self.out.push(UNDEFINED);
+            return Ok(());
+        }
+
+        let classes = activation.avm2().classes();
+        let (int_class, uint_class, number_class, object_class) =
+            (classes.int, classes.uint, classes.number, classes.object);
+        let marker = if object.as_xml_object().is_some() || object.as_xml_list_object().is_some() {
+            XML
+        } else if object.as_bytearray().is_some() {
+            BYTE_ARRAY
+        } else if object.as_date_object().is_some() {
+            DATE
+        } else if object.as_array_storage().is_some() {
+            ARRAY
+        } else if object.as_dictionary_object().is_some() {
+            DICTIONARY
+        } else if let Some(vector) = object.as_vector_storage() {
+            let value_type = vector.value_type();
+            if Object::ptr_eq(value_type, int_class) {
+                VECTOR_INT
+            } else if Object::ptr_eq(value_type, uint_class) {
+                VECTOR_UINT
+            } else if Object::ptr_eq(value_type, number_class) {
+                VECTOR_DOUBLE
+            } else {
+                VECTOR_OBJECT
+            }
+        } else {
+            OBJECT
+        };
+
+        self.out.push(marker);
+        if self.write_object_reference(object) {
+            return Ok(());
+        }
+
+        match marker {
+            XML => {
+                let settings = activation.avm2().xml_settings();
+                let markup: Vec<String> = xml_nodes(object)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|node| node.to_xml_string(settings).to_utf8_lossy().into_owned())
+                    .collect();
+                self.write_utf8(&markup.join("\n"));
+            }
+            BYTE_ARRAY => {
+                let bytes = object
+                    .as_bytearray()
+                    .map(|bytes| bytes.bytes().to_vec())
+                    .unwrap_or_default();
+                self.write_u29(((bytes.len() as u32) << 1) | 1);
+                self.out.extend_from_slice(&bytes);
+            }
+            DATE => {
+                let time = object
+                    .as_date_object()
+                    .and_then(|date| date.date_time())
+                    .map(|date_time| date_time.timestamp_millis() as f64)
+                    .unwrap_or(f64::NAN);
+                self.write_u29(1);
+                self.out.extend_from_slice(&time.to_be_bytes());
+            }
+            ARRAY => {
+                let values: Vec<Value<'gc>> = object
+                    .as_array_storage()
+                    .map(|array| {
+                        (0..array.length())
+                            .map(|i| array.get(i).unwrap_or(Value::Undefined))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                self.write_u29(((values.len() as u32) << 1) | 1);
+                // Arrays only have a dense portion, so the associative
+                // portion is always empty.
+                self.write_string("".into());
+                for value in values {
+                    self.write_value(activation, value)?;
+                }
+            }
+            DICTIONARY => {
+                let entries = dynamic_properties(activation, object)?;
+                let weak_keys = object
+                    .as_dictionary_object()
+                    .map(|dictionary| dictionary.has_weak_keys())
+                    .unwrap_or(false);
+                self.write_u29(((entries.len() as u32) << 1) | 1);
+                self.out.push(weak_keys as u8);
+                for (key, value) in entries {
+                    self.write_value(activation, key)?;
+                    self.write_value(activation, value)?;
+                }
+            }
+            VECTOR_INT | VECTOR_UINT | VECTOR_DOUBLE | VECTOR_OBJECT => {
+                let (values, is_fixed, value_type) = match object.as_vector_storage() {
+                    Some(vector) => (
+                        vector.iter().collect::<Vec<_>>(),
+                        vector.is_fixed(),
+                        vector.value_type(),
+                    ),
+                    None => return Ok(()),
+                };
+
+                self.write_u29(((values.len() as u32) << 1) | 1);
+                self.out.push(is_fixed.into());
+                match marker {
+                    VECTOR_INT => {
+                        for value in values {
+                            let value = value.coerce_to_i32(activation)?;
+                            self.out.extend_from_slice(&value.to_be_bytes());
+                        }
+                    }
+                    VECTOR_UINT => {
+                        for value in values {
+                            let value = value.coerce_to_u32(activation)?;
+                            self.out.extend_from_slice(&value.to_be_bytes());
+                        }
+                    }
+                    VECTOR_DOUBLE => {
+                        for value in values {
+                            let value = value.coerce_to_number(activation)?;
+                            self.out.extend_from_slice(&value.to_be_bytes());
+                        }
+                    }
+                    _ => {
+                        let type_name = if Object::ptr_eq(value_type, object_class) {
+                            "".into()
+                        } else {
+                            value_type
+                                .inner_class_definition()
+                                .read()
+                                .name()
+                                .to_qualified_name(activation.context.gc_context)
+                        };
+                        self.write_string(type_name);
+                        for value in values {
+                            self.write_value(activation, value)?;
+                        }
+                    }
+                }
+            }
+            _ => {
+                // Objects of aliased classes are written with their class
+                // and public variables. Other objects are written as
+                // anonymous dynamic objects, so only their enumerable
+                // properties are kept.
+                let class = object
+                    .instance_of()
+                    .filter(|class| activation.avm2().class_alias(*class).is_some());
+                let sealed_members = class.map(sealed_members).unwrap_or_default();
+                let is_dynamic = class.map_or(true, |class| {
+                    !class.inner_class_definition().read().is_sealed()
+                });
+                if let Some(index) = self.traits.get(&class) {
+                    self.write_u29((index << 2) | 0b01);
+                } else {
+                    self.traits.insert(class, self.traits.len() as u32);
+                    self.write_u29(
+                        ((sealed_members.len() as u32) << 4) | ((is_dynamic as u32) << 3) | 0b011,
+                    );
+                    let alias = class
+                        .and_then(|class| activation.avm2().class_alias(class))
+                        .unwrap_or_default();
+                    self.write_string(alias);
+                    for name in &sealed_members {
+                        self.write_string(*name);
+                    }
+                }
+
+                for name in sealed_members {
+                    let value = object.get_property(
+                        object,
+                        &QName::dynamic_name(name).into(),
+                        activation,
+                    )?;
+                    self.write_value(activation, value)?;
+                }
+                if is_dynamic {
+                    for (name, value) in dynamic_properties(activation, object)? {
+                        let name = name.coerce_to_string(activation)?;
+                        if name.is_empty() {
+                            continue;
+                        }
+                        self.write_string(name);
+                        self.write_value(activation, value)?;
+                    }
+                    self.write_string("".into());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'gc> Default for Amf3Writer<'gc> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The traits of a class of serialized objects.
+struct Traits<'gc> {
+    /// The alias of the class, which is empty for anonymous objects.
+    class_name: AvmString<'gc>,
+    is_dynamic: bool,
+    is_externalizable: bool,
+    sealed_members: Vec<AvmString<'gc>>,
+}
+
+/// Deserializes AMF3 into AVM2 values.
+pub struct Amf3Reader<'a, 'gc> {
+    data: &'a [u8],
+    position: usize,
+    strings: Vec<AvmString<'gc>>,
+    objects: Vec<Value<'gc>>,
+    traits: Vec<Traits<'gc>>,
+
+    /// How many values are being read, including the current one.
+    depth: usize,
+}
+
+impl<'a, 'gc> Amf3Reader<'a, 'gc> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            position: 0,
+            strings: Vec::new(),
+            objects: Vec::new(),
+            traits: Vec::new(),
+            depth: 0,
+        }
+    }
+
+    /// The number of bytes read so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let bytes = self
+            .position
+            .checked_add(len)
+            .and_then(|end| self.data.get(self.position..end))
+            .ok_or("EOFError: Error #2030: End of file was encountered.")?;
+        self.position += len;
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, Error> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_f64(&mut self) -> Result<f64, Error> {
+        let bytes = self.read_bytes(8)?;
+        Ok(f64::from_be_bytes(bytes.try_into().unwrap()))
+    }
+
+    /// Read a variable-length 29-bit integer.
+    fn read_u29(&mut self) -> Result<u32, Error> {
+        let mut value = 0;
+        for _ in 0..3 {
+            let byte = self.read_u8()?;
+            value = (value << 7) | u32::from(byte & 0x7F);
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Ok((value << 8) | u32::from(self.read_u8()?))
+    }
+
+    /// Read a header that is either a reference or an inline length.
+    fn read_header(&mut self) -> Result<Result<u32, usize>, Error> {
+        let header = self.read_u29()?;
+        Ok(if header & 1 == 0 {
+            Ok(header >> 1)
+        } else {
+            Err((header >> 1) as usize)
+        })
+    }
+
+    fn read_string(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<AvmString<'gc>, Error> {
+        match self.read_header()? {
+            Ok(index) => self.strings.get(index as usize).copied().ok_or_else(|| {
+                "RangeError: Error #2006: The supplied index is out of bounds.".into()
+            }),
+            Err(0) => Ok("".into()),
+            Err(len) => {
+                let bytes = self.read_bytes(len)?;
+                let string = AvmString::new_utf8(
+                    activation.context.gc_context,
+                    String::from_utf8_lossy(bytes),
+                );
+                self.strings.push(string);
+                Ok(string)
+            }
+        }
+    }
+
+    fn object_reference(&self, index: u32) -> Result<Value<'gc>, Error> {
+        self.objects
+            .get(index as usize)
+            .copied()
+            .ok_or_else(|| "RangeError: Error #2006: The supplied index is out of bounds.".into())
+    }
+
+    /// Reserve a slot in the object reference table, to be filled once the
+    /// object has been read.
+    fn reserve_object(&mut self) -> usize {
+        self.objects.push(Value::Null);
+        self.objects.len() - 1
+    }
+
+    pub fn read_value(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error> {
+        if self.depth >= MAX_DEPTH {
+            return Err("Error: Error #1023: Stack overflow occurred.".into());
+        }
+
+        self.depth += 1;
+        let value = self.read_marked_value(activation);
+        self.depth -= 1;
+        value
+    }
+
+    fn read_marked_value(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error> {
+        let marker = self.read_u8()?;
+        Ok(match marker {
+            UNDEFINED => Value::Undefined,
+            NULL => Value::Null,
+            FALSE => false.into(),
+            TRUE => true.into(),
+            INTEGER => {
+                // Sign-extend the 29-bit integer.
+                let value = self.read_u29()?;
+                (((value << 3) as i32) >> 3).into()
+            }
+            DOUBLE => self.read_f64()?.into(),
+            STRING => self.read_string(activation)?.into(),
+            XML_DOCUMENT | XML => match self.read_header()? {
+                Ok(index) => self.object_reference(index)?,
+                Err(len) => {
+                    let bytes = self.read_bytes(len)?;
+                    let markup = AvmString::new_utf8(
+                        activation.context.gc_context,
+                        String::from_utf8_lossy(bytes),
+                    );
+                    let xml_class = activation.avm2().classes().xml;
+                    let xml: Value<'gc> = xml_class.construct(activation, &[markup.into()])?.into();
+                    self.objects.push(xml);
+                    xml
+                }
+            },
+            DATE => match self.read_header()? {
+                Ok(index) => self.object_reference(index)?,
+                Err(_) => {
+                    let time = self.read_f64()?;
+                    let date_class = activation.avm2().classes().date;
+                    let date: Value<'gc> = date_class.construct(activation, &[time.into()])?.into();
+                    self.objects.push(date);
+                    date
+                }
+            },
+            ARRAY => match self.read_header()? {
+                Ok(index) => self.object_reference(index)?,
+                Err(len) => {
+                    let mut array = ArrayObject::from_storage(activation, ArrayStorage::new(0))?;
+                    self.objects.push(array.into());
+
+                    loop {
+                        let name = self.read_string(activation)?;
+                        if name.is_empty() {
+                            break;
+                        }
+                        let value = self.read_value(activation)?;
+                        array.set_property(
+                            array,
+                            &QName::dynamic_name(name).into(),
+                            value,
+                            activation,
+                        )?;
+                    }
+
+                    for i in 0..len {
+                        let value = self.read_value(activation)?;
+                        if let Some(mut storage) =
+                            array.as_array_storage_mut(activation.context.gc_context)
+                        {
+                            storage.set(i, value);
+                        }
+                    }
+
+                    array.into()
+                }
+            },
+            OBJECT => match self.read_header()? {
+                Ok(index) => self.object_reference(index)?,
+                Err(header) => self.read_object(activation, header as u32)?,
+            },
+            BYTE_ARRAY => match self.read_header()? {
+                Ok(index) => self.object_reference(index)?,
+                Err(len) => {
+                    let bytes = self.read_bytes(len)?.to_vec();
+                    let bytearray: Value<'gc> = ByteArrayObject::from_storage(
+                        activation,
+                        ByteArrayStorage::from_vec(bytes),
+                    )?
+                    .into();
+                    self.objects.push(bytearray);
+                    bytearray
+                }
+            },
+            VECTOR_INT | VECTOR_UINT | VECTOR_DOUBLE | VECTOR_OBJECT => {
+                match self.read_header()? {
+                    Ok(index) => self.object_reference(index)?,
+                    Err(len) => {
+                        let slot = self.reserve_object();
+                        let is_fixed = self.read_u8()? != 0;
+                        let classes = activation.avm2().classes();
+                        let (int_class, uint_class, number_class, object_class) =
+                            (classes.int, classes.uint, classes.number, classes.object);
+
+                        let mut values = Vec::with_capacity(len.min(self.data.len()));
+                        let value_type = match marker {
+                            VECTOR_INT => {
+                                for _ in 0..len {
+                                    let bytes = self.read_bytes(4)?;
+                                    values
+                                        .push(i32::from_be_bytes(bytes.try_into().unwrap()).into());
+                                }
+                                int_class
+                            }
+                            VECTOR_UINT => {
+                                for _ in 0..len {
+                                    let bytes = self.read_bytes(4)?;
+                                    values
+                                        .push(u32::from_be_bytes(bytes.try_into().unwrap()).into());
+                                }
+                                uint_class
+                            }
+                            VECTOR_DOUBLE => {
+                                for _ in 0..len {
+                                    values.push(self.read_f64()?.into());
+                                }
+                                number_class
+                            }
+                            _ => {
+                                // The element type is ignored, as the class
+                                // might not be reachable from here.
+                                let _type_name = self.read_string(activation)?;
+                                for _ in 0..len {
+                                    values.push(self.read_value(activation)?);
+                                }
+                                object_class
+                            }
+                        };
+
+                        let storage = VectorStorage::from_values(values, is_fixed, value_type);
+                        let vector: Value<'gc> =
+                            VectorObject::from_vector(storage, activation)?.into();
+                        self.objects[slot] = vector;
+                        vector
+                    }
+                }
+            }
+            DICTIONARY => match self.read_header()? {
+                Ok(index) => self.object_reference(index)?,
+                Err(len) => {
+                    let weak_keys = self.read_u8()? != 0;
+                    let mut dictionary =
+                        dictionary_class(activation)?.construct(activation, &[weak_keys.into()])?;
+                    self.objects.push(dictionary.into());
+
+                    for _ in 0..len {
+                        let key = self.read_value(activation)?;
+                        let value = self.read_value(activation)?;
+                        match (key, dictionary.as_dictionary_object()) {
+                            (Value::Object(key), Some(object_space)) => object_space
+                                .set_property_by_object(key, value, activation.context.gc_context),
+                            _ => {
+                                let name = key.coerce_to_string(activation)?;
+                                dictionary.set_property(
+                                    dictionary,
+                                    &QName::dynamic_name(name).into(),
+                                    value,
+                                    activation,
+                                )?;
+                            }
+                        }
+                    }
+
+                    dictionary.into()
+                }
+            },
+            _ => return Err(format!("Error: Unknown AMF3 type marker {}", marker).into()),
+        })
+    }
+
+    /// Read an object, given its header without the reference flag.
+    ///
+    /// Objects are read as instances of the class registered under their
+    /// alias, and other objects as plain `Object`s with their sealed members
+    /// as dynamic properties.
+    fn read_object(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        header: u32,
+    ) -> Result<Value<'gc>, Error> {
+        let traits_index = if header & 1 == 0 {
+            let index = (header >> 1) as usize;
+            if index >= self.traits.len() {
+                return Err("RangeError: Error #2006: The supplied index is out of bounds.".into());
+            }
+            index
+        } else {
+            let is_externalizable = header & 0b10 != 0;
+            let is_dynamic = header & 0b100 != 0;
+            let sealed_count = header >> 3;
+            let class_name = self.read_string(activation)?;
+            let mut sealed_members = Vec::new();
+            for _ in 0..sealed_count {
+                sealed_members.push(self.read_string(activation)?);
+            }
+
+            self.traits.push(Traits {
+                class_name,
+                is_dynamic,
+                is_externalizable,
+                sealed_members,
+            });
+            self.traits.len() - 1
+        };
+
+        if self.traits[traits_index].is_externalizable {
+            return Err("ArgumentError: Error #2173: Unable to read object in stream. The class does not implement flash.utils.IExternalizable but is aliased to an externalizable class.".into());
+        }
+
+        let class_name = self.traits[traits_index].class_name;
+        let class = if class_name.is_empty() {
+            None
+        } else {
+            activation.avm2().class_by_alias(class_name)
+        };
+        let class = class.unwrap_or_else(|| activation.avm2().classes().object);
+        let mut object = class.construct(activation, &[])?;
+        self.objects.push(object.into());
+
+        let sealed_members = self.traits[traits_index].sealed_members.clone();
+        for name in sealed_members {
+            let value = self.read_value(activation)?;
+            object.set_property(object, &QName::dynamic_name(name).into(), value, activation)?;
+        }
+
+        if self.traits[traits_index].is_dynamic {
+            loop {
+                let name = self.read_string(activation)?;
+                if name.is_empty() {
+                    break;
+                }
+                let value = self.read_value(activation)?;
+                object.set_property(
+                    object,
+                    &QName::dynamic_name(name).into(),
+                    value,
+                    activation,
+                )?;
+            }
+        }
+
+        Ok(object.into())
+    }
+}
+
+// AMF0 type markers.
+const AMF0_NUMBER: u8 = 0x00;
+const AMF0_BOOLEAN: u8 = 0x01;
+const AMF0_STRING: u8 = 0x02;
+const AMF0_OBJECT: u8 = 0x03;
+const AMF0_NULL: u8 = 0x05;
+const AMF0_UNDEFINED: u8 = 0x06;
+const AMF0_REFERENCE: u8 = 0x07;
+const AMF0_ECMA_ARRAY: u8 = 0x08;
+const AMF0_OBJECT_END: u8 = 0x09;
+const AMF0_DATE: u8 = 0x0B;
+const AMF0_LONG_STRING: u8 = 0x0C;
+const AMF0_XML_DOCUMENT: u8 = 0x0F;
+
+/// Serializes AVM2 values to AMF0.
+///
+/// AMF0 has no representation for byte arrays, vectors or dictionaries,
+/// which are written as plain objects.
+pub struct Amf0Writer<'gc> {
+    out: Vec<u8>,
+    objects: HashMap<Object<'gc>, u16>,
+}
+
+impl<'gc> Amf0Writer<'gc> {
+    /// Serialize a single value into AMF0.
+    pub fn serialize(
+        activation: &mut Activation<'_, 'gc, '_>,
+        value: Value<'gc>,
+    ) -> Result<Vec<u8>, Error> {
+        let mut writer = Self {
+            out: Vec::new(),
+            objects: HashMap::new(),
+        };
+        writer.write_value(activation, value)?;
+        Ok(writer.out)
+    }
+
+    /// Write a string without a type marker.
+    fn write_utf8(&mut self, string: &str) {
+        let bytes = &string.as_bytes()[..string.len().min(u16::MAX.into())];
+        self.out
+            .extend_from_slice(&(bytes.len() as u16).to_be_bytes());
+        self.out.extend_from_slice(bytes);
+    }
+
+    fn write_value(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        value: Value<'gc>,
+    ) -> Result<(), Error> {
+        match value {
+            Value::Undefined => self.out.push(AMF0_UNDEFINED),
+            Value::Null => self.out.push(AMF0_NULL),
+            Value::Bool(b) => {
+                self.out.push(AMF0_BOOLEAN);
+                self.out.push(b.into());
+            }
+            Value::Number(_) | Value::Integer(_) | Value::Unsigned(_) => {
+                self.out.push(AMF0_NUMBER);
+                let number = value.coerce_to_number(activation)?;
+                self.out.extend_from_slice(&number.to_be_bytes());
+            }
+            Value::String(s) => {
+                let s = s.to_utf8_lossy();
+                if let Ok(len) = u16::try_from(s.len()) {
+                    self.out.push(AMF0_STRING);
+                    self.out.extend_from_slice(&len.to_be_bytes());
+                } else {
+                    self.out.push(AMF0_LONG_STRING);
+                    self.out.extend_from_slice(&(s.len() as u32).to_be_bytes());
+                }
+                self.out.extend_from_slice(s.as_bytes());
+            }
+            Value::Object(o) if o.as_executable().is_some() => self.out.push(AMF0_UNDEFINED),
+            Value::Object(o) if o.as_date_object().is_some() => {
+                let time = o
+                    .as_date_object()
+                    .and_then(|date| date.date_time())
+                    .map(|date_time| date_time.timestamp_millis() as f64)
+                    .unwrap_or(f64::NAN);
+                self.out.push(AMF0_DATE);
+                self.out.extend_from_slice(&time.to_be_bytes());
+                // The time zone is reserved, and always zero.
+                self.out.extend_from_slice(&[0, 0]);
+            }
+            Value::Object(o) if o.as_xml_object().is_some() => {
+                let settings = activation.avm2().xml_settings();
+                let markup = o
+                    .as_xml_object()
+                    .map(|xml| {
+                        xml.node()
+                            .to_xml_string(settings)
+                            .to_utf8_lossy()
+                            .into_owned()
+                    })
+                    .unwrap_or_default();
+                self.out.push(AMF0_XML_DOCUMENT);
+                self.out
+                    .extend_from_slice(&(markup.len() as u32).to_be_bytes());
+                self.out.extend_from_slice(markup.as_bytes());
+            }
+            Value::Object(o) => {
+                if let Some(index) = self.objects.get(&o) {
+                    self.out.push(AMF0_REFERENCE);
+                    self.out.extend_from_slice(&index.to_be_bytes());
+                    return Ok(());
+                }
+                if let Ok(index) = u16::try_from(self.objects.len()) {
+                    self.objects.insert(o, index);
+                }
+
+                if let Some(length) = o.as_array_storage().map(|array| array.length()) {
+                    self.out.push(AMF0_ECMA_ARRAY);
+                    self.out.extend_from_slice(&(length as u32).to_be_bytes());
+                } else {
+                    self.out.push(AMF0_OBJECT);
+                }
+
+                for (name, value) in dynamic_properties(activation, o)? {
+                    let name = name.coerce_to_string(activation)?;
+                    self.write_utf8(&name.to_utf8_lossy());
+                    self.write_value(activation, value)?;
+                }
+                self.out.extend_from_slice(&[0, 0, AMF0_OBJECT_END]);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm2::test_utils::with_avm2;
+
+    /// Write a value and read it back.
+    fn round_trip<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        value: Value<'gc>,
+    ) -> Result<Value<'gc>, Error> {
+        let bytes = Amf3Writer::serialize(activation, value)?;
+        let mut reader = Amf3Reader::new(&bytes);
+        let value = reader.read_value(activation)?;
+        assert_eq!(reader.position(), bytes.len());
+        Ok(value)
+    }
+
+    fn get<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        object: Object<'gc>,
+        name: &'static str,
+    ) -> Result<Value<'gc>, Error> {
+        object.get_property(object, &QName::dynamic_name(name).into(), activation)
+    }
+
+    fn set<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        mut object: Object<'gc>,
+        name: &'static str,
+        value: Value<'gc>,
+    ) -> Result<(), Error> {
+        object.set_property(object, &QName::dynamic_name(name).into(), value, activation)
+    }
+
+    fn round_trip_object<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        object: Object<'gc>,
+    ) -> Result<Object<'gc>, Error> {
+        let read = round_trip(activation, object.into())?;
+        let read = read.coerce_to_object(activation)?;
+        assert!(!Object::ptr_eq(read, object));
+        Ok(read)
+    }
+
+    #[test]
+    fn u29_round_trip() {
+        for value in [
+            0,
+            0x7F,
+            0x80,
+            0x3FFF,
+            0x4000,
+            0x1F_FFFF,
+            0x20_0000,
+            0x1FFF_FFFF,
+        ] {
+            let mut writer = Amf3Writer::new();
+            writer.write_u29(value);
+
+            let mut reader = Amf3Reader::new(&writer.out);
+            assert_eq!(reader.read_u29().unwrap(), value);
+            assert_eq!(reader.position(), writer.out.len());
+        }
+    }
+
+    #[test]
+    fn primitives_round_trip() {
+        with_avm2(|activation| {
+            for value in [
+                Value::Undefined,
+                Value::Null,
+                true.into(),
+                false.into(),
+                0.into(),
+                (-1).into(),
+                ((1 << 28) - 1).into(),
+                (-(1 << 28)).into(),
+                (1 << 28).into(),
+                i32::MIN.into(),
+                u32::MAX.into(),
+                0.5.into(),
+                f64::INFINITY.into(),
+                "".into(),
+                "hello".into(),
+            ] {
+                assert_eq!(round_trip(activation, value)?, value);
+            }
+
+            let nan = round_trip(activation, f64::NAN.into())?;
+            assert!(matches!(nan, Value::Number(n) if n.is_nan()));
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn array_round_trip() {
+        with_avm2(|activation| {
+            let object_class = activation.avm2().classes().object;
+            let object = object_class.construct(activation, &[])?;
+            let values = [
+                1.into(),
+                "a".into(),
+                Value::Null,
+                object.into(),
+                object.into(),
+            ];
+            let array = ArrayObject::from_storage(activation, ArrayStorage::from_args(&values))?;
+
+            let read = round_trip_object(activation, array)?;
+            let read = read.as_array_storage().unwrap().clone();
+            assert_eq!(read.length(), 5);
+            assert_eq!(read.get(0), Some(1.into()));
+            assert_eq!(read.get(1), Some("a".into()));
+            assert_eq!(read.get(2), Some(Value::Null));
+
+            // Both references point at the same copy.
+            let copy = read.get(3).unwrap();
+            assert!(matches!(copy, Value::Object(o) if !Object::ptr_eq(o, object)));
+            assert_eq!(read.get(4), Some(copy));
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn object_round_trip() {
+        with_avm2(|activation| {
+            let object_class = activation.avm2().classes().object;
+            let object = object_class.construct(activation, &[])?;
+            set(activation, object, "a", 1.into())?;
+            set(activation, object, "b", "text".into())?;
+            set(activation, object, "self", object.into())?;
+
+            let read = round_trip_object(activation, object)?;
+            assert_eq!(get(activation, read, "a")?, 1.into());
+            assert_eq!(get(activation, read, "b")?, "text".into());
+            assert_eq!(get(activation, read, "self")?, read.into());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn bytearray_round_trip() {
+        with_avm2(|activation| {
+            let bytes = ByteArrayObject::from_storage(
+                activation,
+                ByteArrayStorage::from_vec(vec![0, 1, 2, 255]),
+            )?;
+
+            let read = round_trip_object(activation, bytes)?;
+            assert_eq!(read.as_bytearray().unwrap().bytes(), &[0, 1, 2, 255]);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn date_round_trip() {
+        with_avm2(|activation| {
+            let date_class = activation.avm2().classes().date;
+            let date = date_class.construct(activation, &[1_234_567_890.0.into()])?;
+
+            let read = round_trip_object(activation, date)?;
+            let date_time = read.as_date_object().and_then(|date| date.date_time());
+            assert_eq!(date_time.map(|d| d.timestamp_millis()), Some(1_234_567_890));
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn xml_round_trip() {
+        with_avm2(|activation| {
+            let xml_class = activation.avm2().classes().xml;
+            let xml = xml_class.construct(activation, &["<a x=\"1\"><b>c</b></a>".into()])?;
+
+            let read = round_trip_object(activation, xml)?;
+            let settings = activation.avm2().xml_settings();
+            let markup = read.as_xml_object().unwrap().node().to_xml_string(settings);
+            assert_eq!(markup.to_utf8_lossy(), "<a x=\"1\">\n  <b>c</b>\n</a>");
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn vector_round_trip() {
+        with_avm2(|activation| {
+            let classes = activation.avm2().classes();
+            let (int_class, uint_class, number_class, object_class) =
+                (classes.int, classes.uint, classes.number, classes.object);
+
+            for (values, is_fixed, value_type) in [
+                (vec![1.into(), (-2).into()], true, int_class),
+                (vec![3u32.into(), u32::MAX.into()], false, uint_class),
+                (vec![0.5.into(), (-1.5).into()], false, number_class),
+                (vec!["a".into(), Value::Null], true, object_class),
+            ] {
+                let storage = VectorStorage::from_values(values.clone(), is_fixed, value_type);
+                let vector = VectorObject::from_vector(storage, activation)?;
+
+                let read = round_trip_object(activation, vector)?;
+                let read = read.as_vector_storage().unwrap();
+                assert_eq!(read.iter().collect::<Vec<_>>(), values);
+                assert_eq!(read.is_fixed(), is_fixed);
+                assert!(Object::ptr_eq(read.value_type(), value_type));
+            }
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn dictionary_round_trip() {
+        with_avm2(|activation| {
+            let object_class = activation.avm2().classes().object;
+            let key = object_class.construct(activation, &[])?;
+            let dictionary = dictionary_class(activation)?.construct(activation, &[true.into()])?;
+            set(activation, dictionary, "name", 1.into())?;
+            dictionary
+                .as_dictionary_object()
+                .unwrap()
+                .set_property_by_object(key, 2.into(), activation.context.gc_context);
+
+            let read = round_trip_object(activation, dictionary)?;
+            let read_dictionary = read.as_dictionary_object().unwrap();
+            assert!(read_dictionary.has_weak_keys());
+            assert_eq!(get(activation, read, "name")?, 1.into());
+
+            // The key is a copy of the original one.
+            let keys = dynamic_properties(activation, read)?;
+            assert_eq!(keys.len(), 2);
+            let copied_key = keys
+                .iter()
+                .find_map(|(key, _)| match key {
+                    Value::Object(o) => Some(*o),
+                    _ => None,
+                })
+                .unwrap();
+            assert!(!Object::ptr_eq(copied_key, key));
+            assert_eq!(read_dictionary.get_property_by_object(copied_key), 2.into());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn aliased_class_round_trip() {
+        with_avm2(|activation| {
+            let point_class = activation.avm2().classes().point;
+            let point = point_class.construct(activation, &[1.into(), 2.into()])?;
+
+            // Without an alias, only dynamic properties are written.
+            let read = round_trip_object(activation, point)?;
+            assert!(Object::ptr_eq(
+                read.instance_of().unwrap(),
+                activation.avm2().classes().object
+            ));
+
+            activation
+                .context
+                .avm2
+                .register_class_alias("point".into(), point_class);
+            let read = round_trip_object(activation, point)?;
+            assert!(Object::ptr_eq(read.instance_of().unwrap(), point_class));
+            assert_eq!(get(activation, read, "x")?, 1.into());
+            assert_eq!(get(activation, read, "y")?, 2.into());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn reject_deep_nesting() {
+        with_avm2(|activation| {
+            let nested = |depth: usize| {
+                let mut bytes = Vec::new();
+                for _ in 0..depth {
+                    // An array with a single value and no named properties.
+                    bytes.extend_from_slice(&[ARRAY, 0b11, 0b1]);
+                }
+                bytes.push(NULL);
+                bytes
+            };
+
+            let bytes = nested(MAX_DEPTH - 1);
+            assert!(Amf3Reader::new(&bytes).read_value(activation).is_ok());
+
+            let bytes = nested(100_000);
+            let error = Amf3Reader::new(&bytes).read_value(activation).unwrap_err();
+            assert!(error.to_string().contains("#1023"));
+
+            Ok(())
+        })
+    }
+}
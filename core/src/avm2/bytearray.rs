@@ -192,7 +192,15 @@ impl ByteArrayStorage {
                 compresser.read_to_end(&mut buffer)?;
             }
             #[cfg(feature = "lzma")]
-            CompressionAlgorithm::Lzma => lzma_rs::lzma_compress(&mut &*self.bytes, &mut buffer)?,
+            CompressionAlgorithm::Lzma => {
+                // Flash writes the uncompressed length into the LZMA header.
+                let options = lzma_rs::compress::Options {
+                    unpacked_size: lzma_rs::compress::UnpackedSize::WriteToHeader(Some(
+                        self.bytes.len() as u64,
+                    )),
+                };
+                lzma_rs::lzma_compress_with_options(&mut &*self.bytes, &mut buffer, &options)?
+            }
             #[cfg(not(feature = "lzma"))]
             CompressionAlgorithm::Lzma => {
                 return Err("Ruffle was not compiled with LZMA support".into())
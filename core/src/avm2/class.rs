@@ -244,6 +244,23 @@ impl<'gc> Class<'gc> {
         self.protected_namespace = Some(ns)
     }
 
+    /// Get the namespace that protected traits of this class are stored into.
+    pub fn protected_namespace(&self) -> Option<Namespace<'gc>> {
+        self.protected_namespace
+    }
+
+    /// Determine if this is a system class.
+    pub fn is_system(&self) -> bool {
+        self.is_system
+    }
+
+    /// Treat this class as if it was loaded from a movie, so that it is
+    /// verified when linked.
+    #[cfg(test)]
+    pub fn mark_user_defined(&mut self) {
+        self.is_system = false;
+    }
+
     /// Construct a class from a `TranslationUnit` and its class index.
     ///
     /// The returned class will be allocated, but no traits will be loaded. The
@@ -402,20 +419,33 @@ impl<'gc> Class<'gc> {
                     let read = superclass_def.read();
 
                     for supertrait in read.instance_traits.iter() {
-                        if supertrait.name() == instance_trait.name() {
-                            match (supertrait.kind(), instance_trait.kind()) {
+                        // Protected traits are also bound in the protected
+                        // namespace of every subclass, which is where
+                        // overrides of them get declared.
+                        let is_protected_alias = self.protected_namespace.is_some()
+                            && Some(instance_trait.name().namespace()) == self.protected_namespace
+                            && Some(supertrait.name().namespace()) == read.protected_namespace
+                            && supertrait.name().local_name() == instance_trait.name().local_name();
+
+                        if supertrait.name() == instance_trait.name() || is_protected_alias {
+                            let same_kind = match (supertrait.kind(), instance_trait.kind()) {
                                 //Getter/setter pairs do NOT override one another
                                 (TraitKind::Getter { .. }, TraitKind::Setter { .. }) => continue,
                                 (TraitKind::Setter { .. }, TraitKind::Getter { .. }) => continue,
-                                (_, _) => did_override = true,
-                            }
-
-                            if supertrait.is_final() {
-                                return Err(format!("VerifyError: Trait {} in class {} overrides final trait {} in class {}", instance_trait.name().local_name(), self.name().local_name(), supertrait.name().local_name(), read.name().local_name()).into());
-                            }
-
-                            if !instance_trait.is_override() {
-                                return Err(format!("VerifyError: Trait {} in class {} has same name as trait {} in class {}, but does not override it", instance_trait.name().local_name(), self.name().local_name(), supertrait.name().local_name(), read.name().local_name()).into());
+                                (TraitKind::Method { .. }, TraitKind::Method { .. }) => true,
+                                (TraitKind::Getter { .. }, TraitKind::Getter { .. }) => true,
+                                (TraitKind::Setter { .. }, TraitKind::Setter { .. }) => true,
+                                (_, _) => false,
+                            };
+                            did_override = true;
+
+                            // Final traits can't be overridden, slots can't be
+                            // overridden at all, and methods may only be
+                            // overridden by a method of the same kind that is
+                            // marked as an override.
+                            if supertrait.is_final() || !same_kind || !instance_trait.is_override()
+                            {
+                                return Err(self.illegal_override_error(instance_trait));
                             }
 
                             break;
@@ -432,14 +462,27 @@ impl<'gc> Class<'gc> {
                 }
 
                 if instance_trait.is_override() && !did_override {
-                    return Err(format!("VerifyError: Trait {} in class {} marked as override, does not override any other trait", instance_trait.name().local_name(), self.name().local_name()).into());
+                    return Err(self.illegal_override_error(instance_trait));
                 }
             }
+        } else if let Some(instance_trait) = self.instance_traits.iter().find(|t| t.is_override()) {
+            return Err(self.illegal_override_error(instance_trait));
         }
 
         Ok(())
     }
 
+    /// The VerifyError thrown for a trait of this class that overrides a trait
+    /// it may not override, or that is marked as an override of nothing.
+    fn illegal_override_error(&self, instance_trait: &Trait<'gc>) -> Error {
+        format!(
+            "VerifyError: Error #1053: Illegal override of {} in {}.",
+            instance_trait.name().local_name(),
+            self.name().local_name()
+        )
+        .into()
+    }
+
     pub fn for_activation(
         activation: &mut Activation<'_, 'gc, '_>,
         translation_unit: TranslationUnit<'gc>,
@@ -0,0 +1,1246 @@
+//! E4X (ECMAScript for XML) node trees
+
+use crate::avm2::names::{Multiname, Namespace};
+use crate::avm2::Error;
+use crate::string::{AvmString, WStr, WString};
+use gc_arena::{Collect, GcCell, MutationContext};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::cell::Ref;
+
+/// The settings of the `XML` class that control how XML is parsed and
+/// printed.
+#[derive(Clone, Copy, Collect, Debug)]
+#[collect(require_static)]
+pub struct XmlSettings {
+    pub ignore_comments: bool,
+    pub ignore_processing_instructions: bool,
+    pub ignore_whitespace: bool,
+    pub pretty_printing: bool,
+    pub pretty_indent: i32,
+}
+
+impl Default for XmlSettings {
+    fn default() -> Self {
+        Self {
+            ignore_comments: true,
+            ignore_processing_instructions: true,
+            ignore_whitespace: true,
+            pretty_printing: true,
+            pretty_indent: 2,
+        }
+    }
+}
+
+/// A namespace declared on an element by an `xmlns` attribute.
+#[derive(Clone, Copy, Collect, Debug, PartialEq)]
+#[collect(no_drop)]
+pub struct NamespaceDeclaration<'gc> {
+    /// The prefix of the namespace, or `None` for the default namespace.
+    pub prefix: Option<AvmString<'gc>>,
+
+    /// The URI of the namespace.
+    pub uri: AvmString<'gc>,
+}
+
+/// A node of an E4X XML tree.
+#[derive(Clone, Copy, Collect, Debug)]
+#[collect(no_drop)]
+pub struct E4XNode<'gc>(GcCell<'gc, E4XNodeData<'gc>>);
+
+#[derive(Clone, Collect, Debug)]
+#[collect(no_drop)]
+pub struct E4XNodeData<'gc> {
+    /// The element that contains this node.
+    parent: Option<E4XNode<'gc>>,
+
+    /// The namespace URI of an element or attribute, which is empty for nodes
+    /// in no namespace.
+    namespace: AvmString<'gc>,
+
+    /// The prefix that an element or attribute was written with, if any.
+    prefix: Option<AvmString<'gc>>,
+
+    /// The local name of an element or attribute, or the target of a
+    /// processing instruction.
+    local_name: Option<AvmString<'gc>>,
+
+    kind: E4XNodeKind<'gc>,
+}
+
+#[derive(Clone, Collect, Debug)]
+#[collect(no_drop)]
+pub enum E4XNodeKind<'gc> {
+    Text(AvmString<'gc>),
+    Comment(AvmString<'gc>),
+    ProcessingInstruction(AvmString<'gc>),
+    Attribute(AvmString<'gc>),
+    Element {
+        attributes: Vec<E4XNode<'gc>>,
+        children: Vec<E4XNode<'gc>>,
+        namespaces: Vec<NamespaceDeclaration<'gc>>,
+    },
+}
+
+impl<'gc> E4XNode<'gc> {
+    fn new(
+        mc: MutationContext<'gc, '_>,
+        namespace: AvmString<'gc>,
+        local_name: Option<AvmString<'gc>>,
+        kind: E4XNodeKind<'gc>,
+    ) -> Self {
+        E4XNode(GcCell::allocate(
+            mc,
+            E4XNodeData {
+                parent: None,
+                namespace,
+                prefix: None,
+                local_name,
+                kind,
+            },
+        ))
+    }
+
+    /// Create a text node.
+    pub fn text(mc: MutationContext<'gc, '_>, text: AvmString<'gc>) -> Self {
+        Self::new(mc, "".into(), None, E4XNodeKind::Text(text))
+    }
+
+    /// Create an empty element.
+    pub fn element(
+        mc: MutationContext<'gc, '_>,
+        namespace: AvmString<'gc>,
+        local_name: AvmString<'gc>,
+    ) -> Self {
+        Self::new(
+            mc,
+            namespace,
+            Some(local_name),
+            E4XNodeKind::Element {
+                attributes: Vec::new(),
+                children: Vec::new(),
+                namespaces: Vec::new(),
+            },
+        )
+    }
+
+    /// Create an attribute that doesn't belong to an element yet.
+    pub fn attribute(
+        mc: MutationContext<'gc, '_>,
+        namespace: AvmString<'gc>,
+        local_name: AvmString<'gc>,
+        value: AvmString<'gc>,
+    ) -> Self {
+        Self::new(
+            mc,
+            namespace,
+            Some(local_name),
+            E4XNodeKind::Attribute(value),
+        )
+    }
+
+    /// Parse XML markup into the list of nodes at its top level.
+    ///
+    /// Unprefixed element names that no `xmlns` attribute applies to are put
+    /// in `default_namespace`.
+    pub fn parse(
+        mc: MutationContext<'gc, '_>,
+        markup: &WStr,
+        settings: XmlSettings,
+        default_namespace: AvmString<'gc>,
+    ) -> Result<Vec<Self>, Error> {
+        let markup = markup.to_utf8_lossy();
+        let mut reader = Reader::from_str(&markup);
+        let mut buf = Vec::new();
+        let mut nodes = Vec::new();
+        let mut open_elements: Vec<E4XNode<'gc>> = Vec::new();
+
+        loop {
+            let event = reader.read_event(&mut buf).map_err(|e| {
+                format!(
+                    "TypeError: Error #1090: XML parser failure: element is malformed. ({})",
+                    e
+                )
+            })?;
+
+            let node = match event {
+                Event::Start(start) => {
+                    let element =
+                        Self::from_start_event(mc, &start, &open_elements, default_namespace)?;
+                    Self::add_parsed_node(mc, &mut nodes, &open_elements, element)?;
+                    open_elements.push(element);
+                    continue;
+                }
+                Event::Empty(start) => {
+                    Self::from_start_event(mc, &start, &open_elements, default_namespace)?
+                }
+                Event::End(_) => {
+                    open_elements.pop();
+                    continue;
+                }
+                Event::Text(text) => {
+                    let text = AvmString::new_utf8_bytes(mc, text.unescaped()?)?;
+                    let text = if settings.ignore_whitespace {
+                        AvmString::new(mc, text.trim())
+                    } else {
+                        text
+                    };
+                    if text.is_empty() {
+                        continue;
+                    }
+
+                    Self::text(mc, text)
+                }
+                Event::CData(text) => {
+                    Self::text(mc, AvmString::new_utf8_bytes(mc, text.escaped())?)
+                }
+                Event::Comment(text) if !settings.ignore_comments => Self::new(
+                    mc,
+                    "".into(),
+                    None,
+                    E4XNodeKind::Comment(AvmString::new_utf8_bytes(mc, text.escaped())?),
+                ),
+                Event::PI(text) if !settings.ignore_processing_instructions => {
+                    let text = AvmString::new_utf8_bytes(mc, text.escaped())?;
+                    let (target, value) = match text.find(|c| c == b' ' as u16) {
+                        Some(i) => (&text[..i], text[i + 1..].trim_start()),
+                        None => (&text[..], WStr::empty()),
+                    };
+
+                    Self::new(
+                        mc,
+                        "".into(),
+                        Some(AvmString::new(mc, target)),
+                        E4XNodeKind::ProcessingInstruction(AvmString::new(mc, value)),
+                    )
+                }
+                Event::Eof => break,
+                _ => continue,
+            };
+
+            Self::add_parsed_node(mc, &mut nodes, &open_elements, node)?;
+        }
+
+        if let Some(element) = open_elements.last() {
+            return Err(format!(
+                "TypeError: Error #1085: The element type \"{}\" must be terminated by the matching end-tag \"</{}>\".",
+                element.local_name().unwrap_or_default(),
+                element.local_name().unwrap_or_default()
+            )
+            .into());
+        }
+
+        Ok(nodes)
+    }
+
+    /// Add a parsed node to the innermost open element, or to the top level if
+    /// there is none.
+    fn add_parsed_node(
+        mc: MutationContext<'gc, '_>,
+        nodes: &mut Vec<Self>,
+        open_elements: &[Self],
+        node: Self,
+    ) -> Result<(), Error> {
+        match open_elements.last() {
+            Some(parent) => parent.append_child(mc, node)?,
+            None => nodes.push(node),
+        }
+        Ok(())
+    }
+
+    /// Construct an element from a `quick_xml` `BytesStart` event, resolving
+    /// the namespaces of its name and attributes.
+    fn from_start_event(
+        mc: MutationContext<'gc, '_>,
+        start: &BytesStart<'_>,
+        open_elements: &[Self],
+        default_namespace: AvmString<'gc>,
+    ) -> Result<Self, Error> {
+        let mut namespaces = Vec::new();
+        let mut attributes = Vec::new();
+        for attribute in start.attributes() {
+            let attribute = attribute?;
+            let key = AvmString::new_utf8_bytes(mc, attribute.key)?;
+            let value = AvmString::new_utf8_bytes(mc, attribute.unescaped_value()?)?;
+            if &key == b"xmlns" {
+                namespaces.push(NamespaceDeclaration {
+                    prefix: None,
+                    uri: value,
+                });
+            } else if let Some(prefix) = key.strip_prefix(WStr::from_units(b"xmlns:")) {
+                namespaces.push(NamespaceDeclaration {
+                    prefix: Some(AvmString::new(mc, prefix)),
+                    uri: value,
+                });
+            } else {
+                attributes.push((key, value));
+            }
+        }
+
+        // Prefixes are looked up in the declarations of this element first,
+        // then in those of its ancestors.
+        let resolve = |prefix: Option<&WStr>| -> Option<AvmString<'gc>> {
+            let is_match =
+                |declaration: &NamespaceDeclaration<'gc>| match (declaration.prefix, prefix) {
+                    (Some(a), Some(b)) => &*a == b,
+                    (None, None) => true,
+                    _ => false,
+                };
+            if let Some(declaration) = namespaces.iter().rev().copied().find(|d| is_match(d)) {
+                return Some(declaration.uri);
+            }
+            open_elements.iter().rev().find_map(|element| {
+                element
+                    .namespace_declarations()
+                    .into_iter()
+                    .rev()
+                    .find(|d| is_match(d))
+                    .map(|d| d.uri)
+            })
+        };
+
+        let name = AvmString::new_utf8_bytes(mc, start.name())?;
+        let (prefix, local_name) = split_prefix(mc, name);
+        let namespace = match prefix {
+            Some(prefix) => resolve(Some(prefix.as_wstr())).ok_or_else(|| {
+                format!(
+                    "TypeError: Error #1083: The prefix \"{}\" for element \"{}\" is not bound.",
+                    prefix, local_name
+                )
+            })?,
+            None => resolve(None).unwrap_or(default_namespace),
+        };
+
+        let element = Self::element(mc, namespace, local_name);
+        element.0.write(mc).prefix = prefix;
+
+        for (key, value) in attributes {
+            let (prefix, local_name) = split_prefix(mc, key);
+            // Unprefixed attributes are in no namespace.
+            let namespace = match prefix {
+                Some(prefix) => resolve(Some(prefix.as_wstr())).ok_or_else(|| {
+                    format!(
+                        "TypeError: Error #1083: The prefix \"{}\" for element \"{}\" is not bound.",
+                        prefix, local_name
+                    )
+                })?,
+                None => "".into(),
+            };
+
+            let attribute = Self::attribute(mc, namespace, local_name, value);
+            attribute.0.write(mc).prefix = prefix;
+            element.append_attribute(mc, attribute);
+        }
+
+        if let E4XNodeKind::Element {
+            namespaces: declarations,
+            ..
+        } = &mut element.0.write(mc).kind
+        {
+            *declarations = namespaces;
+        }
+
+        Ok(element)
+    }
+
+    /// The kind of this node.
+    pub fn kind(&self) -> Ref<E4XNodeKind<'gc>> {
+        Ref::map(self.0.read(), |read| &read.kind)
+    }
+
+    pub fn is_element(&self) -> bool {
+        matches!(&*self.kind(), E4XNodeKind::Element { .. })
+    }
+
+    pub fn is_attribute(&self) -> bool {
+        matches!(&*self.kind(), E4XNodeKind::Attribute(_))
+    }
+
+    /// The name of the kind of this node, as returned by `XML.nodeKind`.
+    pub fn kind_name(&self) -> &'static str {
+        match &*self.kind() {
+            E4XNodeKind::Text(_) => "text",
+            E4XNodeKind::Comment(_) => "comment",
+            E4XNodeKind::ProcessingInstruction(_) => "processing-instruction",
+            E4XNodeKind::Attribute(_) => "attribute",
+            E4XNodeKind::Element { .. } => "element",
+        }
+    }
+
+    pub fn parent(&self) -> Option<Self> {
+        self.0.read().parent
+    }
+
+    pub fn local_name(&self) -> Option<AvmString<'gc>> {
+        self.0.read().local_name
+    }
+
+    pub fn set_local_name(&self, mc: MutationContext<'gc, '_>, local_name: AvmString<'gc>) {
+        self.0.write(mc).local_name = Some(local_name);
+    }
+
+    /// The namespace URI of this node, which is empty if it has none.
+    pub fn namespace(&self) -> AvmString<'gc> {
+        self.0.read().namespace
+    }
+
+    pub fn set_namespace(&self, mc: MutationContext<'gc, '_>, namespace: AvmString<'gc>) {
+        let mut write = self.0.write(mc);
+        write.namespace = namespace;
+        write.prefix = None;
+    }
+
+    /// The namespaces declared on this element.
+    pub fn namespace_declarations(&self) -> Vec<NamespaceDeclaration<'gc>> {
+        match &*self.kind() {
+            E4XNodeKind::Element { namespaces, .. } => namespaces.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// The children of this element, or nothing for other kinds of nodes.
+    pub fn children(&self) -> Vec<Self> {
+        match &*self.kind() {
+            E4XNodeKind::Element { children, .. } => children.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// The attributes of this element, or nothing for other kinds of nodes.
+    pub fn attributes(&self) -> Vec<Self> {
+        match &*self.kind() {
+            E4XNodeKind::Element { attributes, .. } => attributes.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// The position of this node among the children of its parent.
+    pub fn child_index(&self) -> Option<usize> {
+        let parent = self.parent()?;
+        let children = parent.children();
+        children
+            .iter()
+            .position(|child| GcCell::ptr_eq(child.0, self.0))
+    }
+
+    pub fn ptr_eq(a: Self, b: Self) -> bool {
+        GcCell::ptr_eq(a.0, b.0)
+    }
+
+    /// Whether this node is `other` or one of its ancestors.
+    pub fn is_ancestor_or_self(&self, other: Self) -> bool {
+        let mut node = Some(other);
+        while let Some(current) = node {
+            if Self::ptr_eq(*self, current) {
+                return true;
+            }
+            node = current.parent();
+        }
+        false
+    }
+
+    /// Check that `child` can be made a child of this element, and remove it
+    /// from its current parent.
+    fn adopt(&self, mc: MutationContext<'gc, '_>, child: Self) -> Result<(), Error> {
+        if child.is_ancestor_or_self(*self) {
+            return Err("Error: Error #1118: Illegal cyclical loop between nodes.".into());
+        }
+
+        if let Some(parent) = child.parent() {
+            if child.is_attribute() {
+                parent.remove_attribute(mc, child);
+            } else {
+                parent.remove_child(mc, child);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Insert a node into the children of this element, moving it out of its
+    /// current parent.
+    ///
+    /// Does nothing if this isn't an element. Fails if the node is this
+    /// element or one of its ancestors.
+    pub fn insert_child(
+        &self,
+        mc: MutationContext<'gc, '_>,
+        index: usize,
+        child: Self,
+    ) -> Result<(), Error> {
+        if !self.is_element() {
+            return Ok(());
+        }
+
+        // Moving a child further along its own parent shifts its new index.
+        let is_own_child = child
+            .parent()
+            .map_or(false, |parent| Self::ptr_eq(parent, *self));
+        let index = match child.child_index() {
+            Some(old_index) if is_own_child && old_index < index => index - 1,
+            _ => index,
+        };
+        self.adopt(mc, child)?;
+
+        if let E4XNodeKind::Element { children, .. } = &mut self.0.write(mc).kind {
+            children.insert(index.min(children.len()), child);
+        }
+        child.0.write(mc).parent = Some(*self);
+
+        Ok(())
+    }
+
+    /// Add a node to the end of the children of this element.
+    pub fn append_child(&self, mc: MutationContext<'gc, '_>, child: Self) -> Result<(), Error> {
+        self.insert_child(mc, usize::MAX, child)
+    }
+
+    /// Remove a node from the children of this element.
+    pub fn remove_child(&self, mc: MutationContext<'gc, '_>, child: Self) {
+        if let E4XNodeKind::Element { children, .. } = &mut self.0.write(mc).kind {
+            let len = children.len();
+            children.retain(|c| !Self::ptr_eq(*c, child));
+            if children.len() == len {
+                return;
+            }
+        } else {
+            return;
+        }
+
+        child.0.write(mc).parent = None;
+    }
+
+    /// Replace the child of this element at an index with a list of nodes.
+    pub fn replace_child(&self, mc: MutationContext<'gc, '_>, index: usize, nodes: &[Self]) {
+        if let E4XNodeKind::Element { children, .. } = &mut self.0.write(mc).kind {
+            if index < children.len() {
+                children.splice(index..=index, nodes.iter().copied());
+            } else {
+                return;
+            }
+        } else {
+            return;
+        }
+
+        for node in nodes {
+            node.0.write(mc).parent = Some(*self);
+        }
+    }
+
+    /// Replace all children of this element, moving them out of their
+    /// current parents.
+    ///
+    /// Fails if one of the nodes is this element or one of its ancestors.
+    pub fn set_children(&self, mc: MutationContext<'gc, '_>, nodes: &[Self]) -> Result<(), Error> {
+        if !self.is_element() {
+            return Ok(());
+        }
+
+        for node in nodes {
+            if node.is_ancestor_or_self(*self) {
+                return Err("Error: Error #1118: Illegal cyclical loop between nodes.".into());
+            }
+        }
+
+        for child in self.children() {
+            child.0.write(mc).parent = None;
+        }
+        for node in nodes {
+            self.adopt(mc, *node)?;
+        }
+
+        if let E4XNodeKind::Element { children, .. } = &mut self.0.write(mc).kind {
+            *children = nodes.to_vec();
+        }
+        for node in nodes {
+            node.0.write(mc).parent = Some(*self);
+        }
+
+        Ok(())
+    }
+
+    fn append_attribute(&self, mc: MutationContext<'gc, '_>, attribute: Self) {
+        if let E4XNodeKind::Element { attributes, .. } = &mut self.0.write(mc).kind {
+            attributes.push(attribute);
+        } else {
+            return;
+        }
+
+        attribute.0.write(mc).parent = Some(*self);
+    }
+
+    /// Set the value of the attributes of this element that match a name,
+    /// creating the attribute if none does.
+    ///
+    /// If several attributes match, only the first one is kept.
+    pub fn set_attribute(
+        &self,
+        mc: MutationContext<'gc, '_>,
+        name: &Multiname<'gc>,
+        value: AvmString<'gc>,
+    ) {
+        let matching: Vec<_> = self
+            .attributes()
+            .into_iter()
+            .filter(|attribute| attribute.matches_name(name, "".into()))
+            .collect();
+
+        if let Some((first, rest)) = matching.split_first() {
+            first.0.write(mc).kind = E4XNodeKind::Attribute(value);
+            for attribute in rest {
+                self.remove_attribute(mc, *attribute);
+            }
+        } else if let Some(local_name) = name.local_name() {
+            let namespace = name_namespace(name).unwrap_or_default();
+            self.append_attribute(mc, Self::attribute(mc, namespace, local_name, value));
+        }
+    }
+
+    /// Remove an attribute from this element.
+    pub fn remove_attribute(&self, mc: MutationContext<'gc, '_>, attribute: Self) {
+        if let E4XNodeKind::Element { attributes, .. } = &mut self.0.write(mc).kind {
+            let len = attributes.len();
+            attributes.retain(|a| !Self::ptr_eq(*a, attribute));
+            if attributes.len() == len {
+                return;
+            }
+        } else {
+            return;
+        }
+
+        attribute.0.write(mc).parent = None;
+    }
+
+    /// Copy this node and all of its descendants. The copy has no parent.
+    pub fn deep_copy(&self, mc: MutationContext<'gc, '_>) -> Self {
+        let read = self.0.read();
+        let kind = match &read.kind {
+            E4XNodeKind::Element {
+                attributes,
+                children,
+                namespaces,
+            } => E4XNodeKind::Element {
+                attributes: attributes.iter().map(|a| a.deep_copy(mc)).collect(),
+                children: children.iter().map(|c| c.deep_copy(mc)).collect(),
+                namespaces: namespaces.clone(),
+            },
+            kind => kind.clone(),
+        };
+        let copy = E4XNode(GcCell::allocate(
+            mc,
+            E4XNodeData {
+                parent: None,
+                namespace: read.namespace,
+                prefix: read.prefix,
+                local_name: read.local_name,
+                kind,
+            },
+        ));
+        drop(read);
+
+        for node in copy.attributes().into_iter().chain(copy.children()) {
+            node.0.write(mc).parent = Some(copy);
+        }
+
+        copy
+    }
+
+    /// Whether this node is matched by a property name of an E4X object.
+    ///
+    /// Attribute names only match attributes, and other names only match
+    /// child nodes. The any name (`*`) matches every node of its kind, and
+    /// other names only match elements with the same local name in one of
+    /// the namespaces of the name. The public namespace stands for
+    /// `default_namespace` when matching elements, and for no namespace when
+    /// matching attributes.
+    pub fn matches_name(&self, name: &Multiname<'gc>, default_namespace: AvmString<'gc>) -> bool {
+        let read = self.0.read();
+        let is_attribute = matches!(read.kind, E4XNodeKind::Attribute(_));
+        if is_attribute != name.is_attribute() {
+            return false;
+        }
+
+        let local_name = match name.local_name() {
+            Some(local_name) if &local_name != b"*" => local_name,
+            _ => return true,
+        };
+
+        if !is_attribute && !matches!(read.kind, E4XNodeKind::Element { .. }) {
+            return false;
+        }
+
+        if read.local_name != Some(local_name) {
+            return false;
+        }
+
+        name.namespace_set().any(|ns| match ns {
+            Namespace::Any => true,
+            Namespace::Namespace(uri) => *uri == read.namespace,
+            ns if ns.is_public() => {
+                if is_attribute {
+                    read.namespace.is_empty()
+                } else {
+                    read.namespace == default_namespace
+                }
+            }
+            _ => false,
+        })
+    }
+
+    /// The children or attributes of this element that match a name.
+    pub fn children_by_name(
+        &self,
+        name: &Multiname<'gc>,
+        default_namespace: AvmString<'gc>,
+    ) -> Vec<Self> {
+        let nodes = if name.is_attribute() {
+            self.attributes()
+        } else {
+            self.children()
+        };
+
+        nodes
+            .into_iter()
+            .filter(|node| node.matches_name(name, default_namespace))
+            .collect()
+    }
+
+    /// Collect the descendants of this element that match a name, in document
+    /// order.
+    pub fn descendants(
+        &self,
+        name: &Multiname<'gc>,
+        default_namespace: AvmString<'gc>,
+        out: &mut Vec<Self>,
+    ) {
+        if name.is_attribute() {
+            out.extend(self.children_by_name(name, default_namespace));
+        }
+
+        for child in self.children() {
+            if !name.is_attribute() && child.matches_name(name, default_namespace) {
+                out.push(child);
+            }
+
+            child.descendants(name, default_namespace, out);
+        }
+    }
+
+    /// Whether this node has simple content, i.e. it is a text or attribute
+    /// node, or an element without child elements.
+    pub fn has_simple_content(&self) -> bool {
+        match &*self.kind() {
+            E4XNodeKind::Text(_) | E4XNodeKind::Attribute(_) => true,
+            E4XNodeKind::Comment(_) | E4XNodeKind::ProcessingInstruction(_) => false,
+            E4XNodeKind::Element { children, .. } => !children.iter().any(|c| c.is_element()),
+        }
+    }
+
+    /// Convert this node to a string, as done by `XML.toString`.
+    ///
+    /// Nodes with simple content yield their text, and other nodes their
+    /// markup.
+    pub fn xml_to_string(
+        &self,
+        mc: MutationContext<'gc, '_>,
+        settings: XmlSettings,
+    ) -> AvmString<'gc> {
+        match &*self.kind() {
+            E4XNodeKind::Text(text) | E4XNodeKind::Attribute(text) => return *text,
+            E4XNodeKind::Element { children, .. } if self.has_simple_content() => {
+                let mut out = WString::new();
+                for child in children {
+                    if let E4XNodeKind::Text(text) = &*child.kind() {
+                        out.push_str(text);
+                    }
+                }
+                return AvmString::new(mc, out);
+            }
+            _ => {}
+        }
+
+        AvmString::new(mc, self.to_xml_string(settings))
+    }
+
+    /// Print the markup of this node, as done by `XML.toXMLString`.
+    pub fn to_xml_string(&self, settings: XmlSettings) -> WString {
+        let mut out = WString::new();
+        let mut ancestor_namespaces = Vec::new();
+        let mut parent = self.parent();
+        while let Some(element) = parent {
+            ancestor_namespaces.extend(element.namespace_declarations());
+            parent = element.parent();
+        }
+        self.write_xml(&mut out, settings, 0, &ancestor_namespaces);
+        out
+    }
+
+    fn write_xml(
+        &self,
+        out: &mut WString,
+        settings: XmlSettings,
+        indent: usize,
+        ancestor_namespaces: &[NamespaceDeclaration<'gc>],
+    ) {
+        if settings.pretty_printing {
+            for _ in 0..indent {
+                out.push_byte(b' ');
+            }
+        }
+
+        let read = self.0.read();
+        let (attributes, children, namespaces) = match &read.kind {
+            E4XNodeKind::Text(text) => {
+                if settings.pretty_printing {
+                    out.push_str(&escape_element_value(text.trim()));
+                } else {
+                    out.push_str(&escape_element_value(text));
+                }
+                return;
+            }
+            E4XNodeKind::Attribute(value) => {
+                out.push_str(&escape_attribute_value(value));
+                return;
+            }
+            E4XNodeKind::Comment(text) => {
+                out.push_utf8("<!--");
+                out.push_str(text);
+                out.push_utf8("-->");
+                return;
+            }
+            E4XNodeKind::ProcessingInstruction(text) => {
+                out.push_utf8("<?");
+                out.push_str(&read.local_name.unwrap_or_default());
+                if !text.is_empty() {
+                    out.push_byte(b' ');
+                    out.push_str(text);
+                }
+                out.push_utf8("?>");
+                return;
+            }
+            E4XNodeKind::Element {
+                attributes,
+                children,
+                namespaces,
+            } => (attributes, children, namespaces),
+        };
+
+        let mut in_scope: Vec<NamespaceDeclaration<'gc>> = ancestor_namespaces.to_vec();
+        let mut declarations = namespaces.clone();
+        in_scope.extend(namespaces.iter().copied());
+
+        // Elements that were created in a namespace without declaring it
+        // declare it as the default namespace.
+        let is_declared = |in_scope: &[NamespaceDeclaration<'gc>],
+                           prefix: Option<AvmString<'gc>>| {
+            in_scope
+                .iter()
+                .rev()
+                .find(|d| d.prefix == prefix)
+                .map(|d| d.uri)
+                .unwrap_or_default()
+                == read.namespace
+        };
+        if !is_declared(&in_scope, read.prefix) {
+            let declaration = NamespaceDeclaration {
+                prefix: read.prefix,
+                uri: read.namespace,
+            };
+            declarations.push(declaration);
+            in_scope.push(declaration);
+        }
+
+        out.push_byte(b'<');
+        write_qualified_name(out, read.prefix, read.local_name);
+        for declaration in declarations {
+            out.push_utf8(" xmlns");
+            if let Some(prefix) = declaration.prefix {
+                out.push_byte(b':');
+                out.push_str(&prefix);
+            }
+            out.push_utf8("=\"");
+            out.push_str(&escape_attribute_value(&declaration.uri));
+            out.push_byte(b'"');
+        }
+        for attribute in attributes {
+            let attribute = attribute.0.read();
+            out.push_byte(b' ');
+            write_qualified_name(out, attribute.prefix, attribute.local_name);
+            out.push_utf8("=\"");
+            if let E4XNodeKind::Attribute(value) = &attribute.kind {
+                out.push_str(&escape_attribute_value(value));
+            }
+            out.push_byte(b'"');
+        }
+
+        if children.is_empty() {
+            out.push_utf8("/>");
+            return;
+        }
+        out.push_byte(b'>');
+
+        let indent_children = settings.pretty_printing
+            && (children.len() > 1 || children.iter().any(|child| !child.is_text()));
+        for child in children {
+            if indent_children {
+                out.push_byte(b'\n');
+                child.write_xml(
+                    out,
+                    settings,
+                    indent + settings.pretty_indent.max(0) as usize,
+                    &in_scope,
+                );
+            } else {
+                child.write_xml(
+                    out,
+                    XmlSettings {
+                        pretty_printing: false,
+                        ..settings
+                    },
+                    0,
+                    &in_scope,
+                );
+            }
+        }
+        if indent_children {
+            out.push_byte(b'\n');
+            for _ in 0..indent {
+                out.push_byte(b' ');
+            }
+        }
+
+        out.push_utf8("</");
+        write_qualified_name(out, read.prefix, read.local_name);
+        out.push_byte(b'>');
+    }
+
+    fn is_text(&self) -> bool {
+        matches!(&*self.kind(), E4XNodeKind::Text(_))
+    }
+}
+
+/// The URI of the XML namespace that a name is qualified with, if any.
+///
+/// This is the namespace that new elements and attributes created by
+/// assigning to the name are put in. Unqualified names are looked up in
+/// several namespaces at once, and don't have one.
+pub fn name_namespace<'gc>(name: &Multiname<'gc>) -> Option<AvmString<'gc>> {
+    let mut namespaces = name.namespace_set();
+    match (namespaces.next(), namespaces.next()) {
+        (Some(Namespace::Namespace(uri)), None) => Some(*uri),
+        _ => None,
+    }
+}
+
+/// Split a qualified XML name into its prefix and local name.
+fn split_prefix<'gc>(
+    mc: MutationContext<'gc, '_>,
+    name: AvmString<'gc>,
+) -> (Option<AvmString<'gc>>, AvmString<'gc>) {
+    match name.find(b':') {
+        Some(i) => (
+            Some(AvmString::new(mc, &name[..i])),
+            AvmString::new(mc, &name[i + 1..]),
+        ),
+        None => (None, name),
+    }
+}
+
+fn write_qualified_name(
+    out: &mut WString,
+    prefix: Option<AvmString<'_>>,
+    local_name: Option<AvmString<'_>>,
+) {
+    if let Some(prefix) = prefix {
+        out.push_str(&prefix);
+        out.push_byte(b':');
+    }
+    out.push_str(&local_name.unwrap_or_default());
+}
+
+/// Escape the text of an element, as defined by `EscapeElementValue` in
+/// ECMA-357 (10.2.1.1).
+pub fn escape_element_value(s: &WStr) -> WString {
+    let mut r = WString::with_capacity(s.len(), s.is_wide());
+    for c in s.iter() {
+        let escape: &[u8] = match u8::try_from(c) {
+            Ok(b'<') => b"&lt;",
+            Ok(b'>') => b"&gt;",
+            Ok(b'&') => b"&amp;",
+            _ => {
+                r.push(c);
+                continue;
+            }
+        };
+
+        r.push_str(WStr::from_units(escape));
+    }
+    r
+}
+
+/// Escape the value of an attribute, as defined by `EscapeAttributeValue` in
+/// ECMA-357 (10.2.1.2).
+pub fn escape_attribute_value(s: &WStr) -> WString {
+    let mut r = WString::with_capacity(s.len(), s.is_wide());
+    for c in s.iter() {
+        let escape: &[u8] = match u8::try_from(c) {
+            Ok(b'"') => b"&quot;",
+            Ok(b'<') => b"&lt;",
+            Ok(b'&') => b"&amp;",
+            Ok(b'\x0A') => b"&#xA;",
+            Ok(b'\x0D') => b"&#xD;",
+            Ok(b'\x09') => b"&#x9;",
+            _ => {
+                r.push(c);
+                continue;
+            }
+        };
+
+        r.push_str(WStr::from_units(escape));
+    }
+    r
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gc_arena::rootless_arena;
+
+    fn parse<'gc>(mc: MutationContext<'gc, '_>, markup: &str) -> Vec<E4XNode<'gc>> {
+        E4XNode::parse(
+            mc,
+            &WString::from_utf8(markup),
+            XmlSettings::default(),
+            "".into(),
+        )
+        .expect("Parsed markup")
+    }
+
+    fn markup(node: E4XNode<'_>) -> String {
+        let settings = XmlSettings {
+            pretty_printing: false,
+            ..Default::default()
+        };
+        node.to_xml_string(settings).to_utf8_lossy().into_owned()
+    }
+
+    /// Tests parsing elements, attributes and text into a tree.
+    #[test]
+    fn parse_nested_elements() {
+        rootless_arena(|mc| {
+            let nodes = parse(mc, r#"<a x="1"><b>text</b><c/></a>"#);
+            assert_eq!(nodes.len(), 1);
+
+            let a = nodes[0];
+            assert_eq!(a.kind_name(), "element");
+            assert_eq!(a.local_name(), Some("a".into()));
+            assert!(a.parent().is_none());
+
+            let attributes = a.attributes();
+            assert_eq!(attributes.len(), 1);
+            assert_eq!(attributes[0].local_name(), Some("x".into()));
+            assert!(matches!(&*attributes[0].kind(), E4XNodeKind::Attribute(v) if v == b"1"));
+
+            let children = a.children();
+            assert_eq!(children.len(), 2);
+            assert_eq!(children[0].local_name(), Some("b".into()));
+            assert_eq!(children[1].local_name(), Some("c".into()));
+            assert!(E4XNode::ptr_eq(children[0].parent().unwrap(), a));
+            assert_eq!(children[1].child_index(), Some(1));
+
+            let text = children[0].children();
+            assert_eq!(text.len(), 1);
+            assert_eq!(text[0].kind_name(), "text");
+        })
+    }
+
+    /// Tests that unterminated elements and unbound prefixes are rejected.
+    #[test]
+    fn parse_malformed_markup() {
+        rootless_arena(|mc| {
+            let settings = XmlSettings::default();
+            let error = E4XNode::parse(mc, &WString::from_utf8("<a><b></b>"), settings, "".into())
+                .unwrap_err();
+            assert!(error.to_string().contains("#1085"));
+
+            let error =
+                E4XNode::parse(mc, &WString::from_utf8("<p:a/>"), settings, "".into()).unwrap_err();
+            assert!(error.to_string().contains("#1083"));
+        })
+    }
+
+    /// Tests that printed markup matches the markup that was parsed.
+    #[test]
+    fn serialize_round_trip() {
+        rootless_arena(|mc| {
+            for source in [
+                r#"<a x="1" y="&quot;"><b>a &amp; b</b><c/></a>"#,
+                r#"<p:a xmlns:p="urn:p"><p:b p:x="1"/></p:a>"#,
+                r#"<a xmlns="urn:a"><b/></a>"#,
+            ] {
+                let nodes = parse(mc, source);
+                assert_eq!(markup(nodes[0]), source);
+            }
+        })
+    }
+
+    /// Tests indentation when pretty printing.
+    #[test]
+    fn serialize_pretty() {
+        rootless_arena(|mc| {
+            let nodes = parse(mc, "<a><b>text</b><c/></a>");
+            let printed = nodes[0].to_xml_string(XmlSettings::default());
+            assert_eq!(printed.to_utf8_lossy(), "<a>\n  <b>text</b>\n  <c/>\n</a>");
+        })
+    }
+
+    /// Tests that appending a node moves it out of its old parent.
+    #[test]
+    fn append_child_moves_node() {
+        rootless_arena(|mc| {
+            let a = parse(mc, "<a><b/><c/></a>")[0];
+            let d = parse(mc, "<d/>")[0];
+            let b = a.children()[0];
+
+            d.append_child(mc, b).unwrap();
+            assert_eq!(markup(a), "<a><c/></a>");
+            assert_eq!(markup(d), "<d><b/></d>");
+            assert!(E4XNode::ptr_eq(b.parent().unwrap(), d));
+
+            // Moving a child within its own parent.
+            let c = a.children()[0];
+            a.insert_child(mc, 0, b).unwrap();
+            a.append_child(mc, b).unwrap();
+            assert_eq!(markup(a), "<a><c/><b/></a>");
+            a.insert_child(mc, 0, b).unwrap();
+            assert_eq!(markup(a), "<a><b/><c/></a>");
+            assert_eq!(c.child_index(), Some(1));
+            assert_eq!(markup(d), "<d/>");
+        })
+    }
+
+    /// Tests that a node can't be made a child of itself or its descendants.
+    #[test]
+    fn append_child_rejects_cycles() {
+        rootless_arena(|mc| {
+            let a = parse(mc, "<a><b><c/></b></a>")[0];
+            let b = a.children()[0];
+            let c = b.children()[0];
+
+            for (parent, child) in [(a, a), (b, a), (c, a), (c, b)] {
+                let error = parent.append_child(mc, child).unwrap_err();
+                assert!(error.to_string().contains("#1118"));
+            }
+            let error = c.set_children(mc, &[a]).unwrap_err();
+            assert!(error.to_string().contains("#1118"));
+
+            // The tree is left untouched.
+            assert_eq!(markup(a), "<a><b><c/></b></a>");
+        })
+    }
+
+    /// Tests that deleted children no longer have a parent.
+    #[test]
+    fn remove_child_clears_parent() {
+        rootless_arena(|mc| {
+            let a = parse(mc, "<a><b/></a>")[0];
+            let b = a.children()[0];
+            a.remove_child(mc, b);
+            assert!(b.parent().is_none());
+            assert!(a.children().is_empty());
+        })
+    }
+
+    /// Tests that a closure nested two levels deep creates XML elements in the
+    /// default namespace set by the function it was created in.
+    #[test]
+    fn default_namespace_in_nested_closure() {
+        use crate::avm2::object::{xml_nodes, FunctionObject, TObject};
+        use crate::avm2::scope::ScopeChain;
+        use crate::avm2::script::TranslationUnit;
+        use crate::avm2::test_utils::with_avm2;
+        use std::rc::Rc;
+        use swf::avm2::types::{
+            AbcFile, ConstantPool, Index, Method as AbcMethod, MethodBody,
+            Multiname as AbcMultiname, Namespace as AbcNamespace,
+        };
+
+        fn method(needs_dxns: bool) -> AbcMethod {
+            AbcMethod {
+                name: Index::new(0),
+                params: vec![],
+                return_type: Index::new(0),
+                needs_arguments_object: false,
+                needs_activation: false,
+                needs_rest: false,
+                needs_dxns,
+            }
+        }
+
+        fn body(method: u32, code: Vec<u8>) -> MethodBody {
+            MethodBody {
+                method: Index::new(method),
+                max_stack: 3,
+                num_locals: 1,
+                init_scope_depth: 0,
+                max_scope_depth: 0,
+                code,
+                exceptions: vec![],
+                traits: vec![],
+            }
+        }
+
+        // function outer() {
+        //     default xml namespace = "http://example.com/";
+        //     return (function () {
+        //         return (function () { return new XML("<a/>"); })();
+        //     })();
+        // }
+        let abc = AbcFile {
+            major_version: 46,
+            minor_version: 16,
+            constant_pool: ConstantPool {
+                ints: vec![],
+                uints: vec![],
+                doubles: vec![],
+                strings: vec!["XML".into(), "<a/>".into(), "http://example.com/".into()],
+                namespaces: vec![AbcNamespace::Package(Index::new(0))],
+                namespace_sets: vec![],
+                multinames: vec![AbcMultiname::QName {
+                    namespace: Index::new(1),
+                    name: Index::new(1),
+                }],
+            },
+            methods: vec![method(true), method(false), method(false)],
+            metadata: vec![],
+            instances: vec![],
+            classes: vec![],
+            scripts: vec![],
+            method_bodies: vec![
+                // dxns, newfunction, pushnull, call, returnvalue
+                body(0, vec![0x06, 3, 0x40, 1, 0x20, 0x41, 0, 0x48]),
+                // newfunction, pushnull, call, returnvalue
+                body(1, vec![0x40, 2, 0x20, 0x41, 0, 0x48]),
+                // findpropstrict, pushstring, constructprop, returnvalue
+                body(2, vec![0x5d, 1, 0x2c, 2, 0x4a, 1, 1, 0x48]),
+            ],
+        };
+
+        with_avm2(|activation| {
+            let domain = activation.avm2().global_domain();
+            let unit =
+                TranslationUnit::from_abc(Rc::new(abc), domain, activation.context.gc_context);
+            let method = unit.load_method(0, true, activation)?;
+            let outer = FunctionObject::from_function(activation, method, ScopeChain::new(domain))?;
+
+            let xml = outer
+                .call(None, &[], activation)?
+                .coerce_to_object(activation)?;
+            let nodes = xml_nodes(xml).unwrap();
+            assert_eq!(nodes[0].local_name(), Some("a".into()));
+            assert_eq!(nodes[0].namespace(), AvmString::from("http://example.com/"));
+
+            Ok(())
+        });
+    }
+}
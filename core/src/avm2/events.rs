@@ -170,6 +170,27 @@ impl<'gc> Event<'gc> {
     pub fn set_current_target(&mut self, current_target: Object<'gc>) {
         self.current_target = Some(current_target)
     }
+
+    /// Determine if this event has already been dispatched to a target.
+    ///
+    /// Events that have been dispatched cannot be dispatched again; they must
+    /// be cloned first.
+    pub fn is_dispatched(&self) -> bool {
+        self.target.is_some()
+    }
+
+    /// Construct an undispatched copy of this event.
+    ///
+    /// The copy shares this event's type and flags, but none of the state
+    /// that is set up during dispatch.
+    pub fn duplicate(&self) -> Self {
+        let mut event = Self::new(self.event_type);
+
+        event.set_bubbles(self.bubbles);
+        event.set_cancelable(self.cancelable);
+
+        event
+    }
 }
 
 /// A set of handlers organized by event type, priority, and order added.
@@ -192,17 +213,6 @@ impl<'gc> DispatchList<'gc> {
         self.0.get(&event.into())
     }
 
-    /// Get all of the event handlers for a given event type, for mutation.
-    ///
-    /// If the event type does not exist, it will be added to the dispatch
-    /// list.
-    fn get_event_mut(
-        &mut self,
-        event: impl Into<AvmString<'gc>>,
-    ) -> &mut BTreeMap<i32, Vec<EventHandler<'gc>>> {
-        self.0.entry(event.into()).or_insert_with(BTreeMap::new)
-    }
-
     /// Get a single priority level of event handlers for a given event type,
     /// for mutation.
     fn get_event_priority_mut(
@@ -247,18 +257,28 @@ impl<'gc> DispatchList<'gc> {
     /// Remove an event handler from this dispatch list.
     ///
     /// Any listener that has the same handler and capture-phase flag will be
-    /// removed from any priority in the list.
+    /// removed from any priority in the list. Priority levels and event types
+    /// left without listeners are dropped from the list.
     pub fn remove_event_listener(
         &mut self,
         event: impl Into<AvmString<'gc>>,
         handler: Object<'gc>,
         use_capture: bool,
     ) {
+        let event = event.into();
         let old_handler = EventHandler::new(handler, use_capture);
 
-        for (_prio, set) in self.get_event_mut(event).iter_mut() {
-            if let Some(pos) = set.iter().position(|h| *h == old_handler) {
-                set.remove(pos);
+        if let Some(event_sheaf) = self.0.get_mut(&event) {
+            for (_prio, set) in event_sheaf.iter_mut() {
+                if let Some(pos) = set.iter().position(|h| *h == old_handler) {
+                    set.remove(pos);
+                }
+            }
+
+            event_sheaf.retain(|_prio, set| !set.is_empty());
+
+            if event_sheaf.is_empty() {
+                self.0.remove(&event);
             }
         }
     }
@@ -281,17 +301,20 @@ impl<'gc> DispatchList<'gc> {
     /// Event handlers will be yielded in the order they are intended to be
     /// executed.
     ///
+    /// Handlers with a higher priority are yielded first; handlers of the same
+    /// priority are yielded in the order they were added.
+    ///
     /// `use_capture` indicates if you want handlers that execute during the
     /// capture phase, or handlers that execute during the bubble and target
     /// phases.
     pub fn iter_event_handlers<'a>(
-        &'a mut self,
+        &'a self,
         event: impl Into<AvmString<'gc>>,
         use_capture: bool,
     ) -> impl 'a + Iterator<Item = Object<'gc>> {
-        self.get_event_mut(event)
-            .iter()
-            .rev()
+        self.get_event(event)
+            .into_iter()
+            .flat_map(|sheaf| sheaf.iter().rev())
             .flat_map(|(_p, v)| v.iter())
             .filter(move |eh| eh.use_capture == use_capture)
             .map(|eh| eh.handler)
@@ -401,8 +424,9 @@ pub fn dispatch_event_to_target<'gc>(
 
     drop(evtmut);
 
+    // Listeners added or removed by a handler don't affect the current target.
     let handlers: Vec<Object<'gc>> = dispatch_list
-        .as_dispatch_mut(activation.context.gc_context)
+        .as_dispatch()
         .ok_or_else(|| Error::from("Internal dispatch list is missing during dispatch!"))?
         .iter_event_handlers(name, use_capture)
         .collect();
@@ -424,11 +448,28 @@ pub fn dispatch_event_to_target<'gc>(
     Ok(())
 }
 
+/// Dispatch an event through the capture, target, and bubble phases.
+///
+/// Events that have already been dispatched are cloned with their `clone`
+/// method first, so that handlers of the earlier dispatch keep seeing the
+/// state they were called with.
 pub fn dispatch_event<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
-    event: Object<'gc>,
+    mut event: Object<'gc>,
 ) -> Result<bool, Error> {
+    if event.as_event().unwrap().is_dispatched() {
+        event = event
+            .get_property(event, &QName::dynamic_name("clone").into(), activation)?
+            .coerce_to_object(activation)?
+            .call(Some(event), &[], activation)?
+            .coerce_to_object(activation)?;
+
+        if event.as_event().is_none() {
+            return Err("Cloned events must be subclasses of Event.".into());
+        }
+    }
+
     let target = this
         .get_property(
             this,
@@ -489,3 +530,168 @@ pub fn dispatch_event<'gc>(
 
     Ok(was_not_cancelled)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm2::globals::flash::display::displayobjectcontainer::add_child;
+    use crate::avm2::globals::flash::events::eventdispatcher::add_event_listener;
+    use crate::avm2::method::{Method, NativeMethodImpl};
+    use crate::avm2::object::{FunctionObject, ObjectPtr};
+    use crate::avm2::test_utils::with_avm2;
+    use std::cell::RefCell;
+
+    thread_local! {
+        /// The phase and current target of every call to `record`.
+        static CALLS: RefCell<Vec<(EventPhase, *const ObjectPtr)>> = RefCell::new(Vec::new());
+    }
+
+    fn record<'gc>(
+        _activation: &mut Activation<'_, 'gc, '_>,
+        _this: Option<Object<'gc>>,
+        args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, Error> {
+        if let Some(Value::Object(event)) = args.get(0) {
+            let event = event.as_event().unwrap();
+            let call = (event.phase(), event.current_target().unwrap().as_ptr());
+            CALLS.with(|calls| calls.borrow_mut().push(call));
+        }
+        Ok(Value::Undefined)
+    }
+
+    fn stop_propagation<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        _this: Option<Object<'gc>>,
+        args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, Error> {
+        if let Some(Value::Object(event)) = args.get(0) {
+            event
+                .as_event_mut(activation.context.gc_context)
+                .unwrap()
+                .stop_propagation();
+        }
+        Ok(Value::Undefined)
+    }
+
+    fn take_calls() -> Vec<(EventPhase, *const ObjectPtr)> {
+        CALLS.with(|calls| calls.borrow_mut().drain(..).collect())
+    }
+
+    fn listen<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        target: Object<'gc>,
+        listener: NativeMethodImpl,
+        use_capture: bool,
+    ) -> Result<(), Error> {
+        let method =
+            Method::from_builtin(listener, "<test listener>", activation.context.gc_context);
+        let scope = activation.create_scopechain();
+        let function = FunctionObject::from_method(activation, method, scope, None, None);
+        add_event_listener(
+            activation,
+            Some(target),
+            &["test".into(), function.into(), use_capture.into()],
+        )?;
+        Ok(())
+    }
+
+    /// Build a root sprite containing a child sprite containing a leaf
+    /// sprite, all listening to events in both the capture and bubble
+    /// phases.
+    fn hierarchy<'gc>(activation: &mut Activation<'_, 'gc, '_>) -> Result<[Object<'gc>; 3], Error> {
+        let sprite_class = activation.avm2().classes().sprite;
+        let root = sprite_class.construct(activation, &[])?;
+        let child = sprite_class.construct(activation, &[])?;
+        let leaf = sprite_class.construct(activation, &[])?;
+        add_child(activation, Some(root), &[child.into()])?;
+        add_child(activation, Some(child), &[leaf.into()])?;
+
+        for target in [root, child, leaf] {
+            listen(activation, target, record, true)?;
+            listen(activation, target, record, false)?;
+        }
+
+        Ok([root, child, leaf])
+    }
+
+    fn event<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        bubbles: bool,
+    ) -> Result<Object<'gc>, Error> {
+        let event_class = activation.avm2().classes().event;
+        event_class.construct(activation, &["test".into(), bubbles.into()])
+    }
+
+    /// Tests that bubbling events are captured by the ancestors of their
+    /// target, fired on the target, and bubble back up.
+    #[test]
+    fn dispatch_capture_target_bubble() {
+        with_avm2(|activation| {
+            let [root, child, leaf] = hierarchy(activation)?;
+            let event = event(activation, true)?;
+
+            assert!(dispatch_event(activation, leaf, event)?);
+            assert_eq!(
+                take_calls(),
+                vec![
+                    (EventPhase::Capturing, root.as_ptr()),
+                    (EventPhase::Capturing, child.as_ptr()),
+                    (EventPhase::AtTarget, leaf.as_ptr()),
+                    (EventPhase::Bubbling, child.as_ptr()),
+                    (EventPhase::Bubbling, root.as_ptr()),
+                ]
+            );
+            assert!(Object::ptr_eq(
+                event.as_event().unwrap().target().unwrap(),
+                leaf
+            ));
+
+            Ok(())
+        })
+    }
+
+    /// Tests that events that don't bubble stop at their target.
+    #[test]
+    fn dispatch_without_bubbling() {
+        with_avm2(|activation| {
+            let [root, child, leaf] = hierarchy(activation)?;
+            let event = event(activation, false)?;
+
+            dispatch_event(activation, leaf, event)?;
+            assert_eq!(
+                take_calls(),
+                vec![
+                    (EventPhase::Capturing, root.as_ptr()),
+                    (EventPhase::Capturing, child.as_ptr()),
+                    (EventPhase::AtTarget, leaf.as_ptr()),
+                ]
+            );
+
+            Ok(())
+        })
+    }
+
+    /// Tests that stopping propagation still runs the other listeners of the
+    /// current target, but no further targets.
+    #[test]
+    fn dispatch_stop_propagation() {
+        with_avm2(|activation| {
+            let [root, child, leaf] = hierarchy(activation)?;
+            listen(activation, child, stop_propagation, true)?;
+            listen(activation, child, record, true)?;
+            let event = event(activation, true)?;
+
+            dispatch_event(activation, leaf, event)?;
+            assert_eq!(
+                take_calls(),
+                vec![
+                    (EventPhase::Capturing, root.as_ptr()),
+                    (EventPhase::Capturing, child.as_ptr()),
+                    (EventPhase::Capturing, child.as_ptr()),
+                ]
+            );
+
+            Ok(())
+        })
+    }
+}
@@ -112,6 +112,7 @@ impl<'gc> Executable<'gc> {
                 let method = bm.method.method;
                 let receiver = bm.bound_receiver.or(unbound_receiver);
                 let caller_domain = activation.caller_domain();
+                let default_xml_namespace = activation.caller_default_xml_namespace();
                 let subclass_object = bm.bound_superclass;
                 let mut activation = Activation::from_builtin(
                     activation.context.reborrow(),
@@ -119,6 +120,7 @@ impl<'gc> Executable<'gc> {
                     subclass_object,
                     bm.scope,
                     caller_domain,
+                    default_xml_namespace,
                 )?;
 
                 if arguments.len() > bm.method.signature.len() && !bm.method.is_variadic {
@@ -19,7 +19,7 @@ mod array;
 mod boolean;
 mod class;
 mod date;
-mod flash;
+pub mod flash;
 mod function;
 mod global_scope;
 mod int;
@@ -31,7 +31,7 @@ mod qname;
 mod regexp;
 mod string;
 mod r#uint;
-mod vector;
+pub mod vector;
 mod xml;
 mod xml_list;
 
@@ -109,6 +109,13 @@ pub struct SystemPrototypes<'gc> {
     pub application_domain: Object<'gc>,
     pub event: Object<'gc>,
     pub fullscreenevent: Object<'gc>,
+    pub sampledataevent: Object<'gc>,
+    pub activityevent: Object<'gc>,
+    pub statusevent: Object<'gc>,
+    pub textevent: Object<'gc>,
+    pub progressevent: Object<'gc>,
+    pub ioerrorevent: Object<'gc>,
+    pub httpstatusevent: Object<'gc>,
     pub video: Object<'gc>,
     pub xml: Object<'gc>,
     pub xml_list: Object<'gc>,
@@ -118,6 +125,10 @@ pub struct SystemPrototypes<'gc> {
     pub rectangle: Object<'gc>,
     pub textfield: Object<'gc>,
     pub textformat: Object<'gc>,
+    pub textlinemetrics: Object<'gc>,
+    pub fontdescription: Object<'gc>,
+    pub groupelement: Object<'gc>,
+    pub textline: Object<'gc>,
     pub graphics: Object<'gc>,
     pub loaderinfo: Object<'gc>,
     pub bytearray: Object<'gc>,
@@ -128,11 +139,13 @@ pub struct SystemPrototypes<'gc> {
     pub vector: Object<'gc>,
     pub soundtransform: Object<'gc>,
     pub soundchannel: Object<'gc>,
+    pub microphone: Object<'gc>,
     pub bitmap: Object<'gc>,
     pub bitmapdata: Object<'gc>,
     pub date: Object<'gc>,
     pub qname: Object<'gc>,
     pub sharedobject: Object<'gc>,
+    pub urlvariables: Object<'gc>,
 }
 
 impl<'gc> SystemPrototypes<'gc> {
@@ -167,6 +180,13 @@ impl<'gc> SystemPrototypes<'gc> {
             application_domain: empty,
             event: empty,
             fullscreenevent: empty,
+            sampledataevent: empty,
+            activityevent: empty,
+            statusevent: empty,
+            textevent: empty,
+            progressevent: empty,
+            ioerrorevent: empty,
+            httpstatusevent: empty,
             video: empty,
             xml: empty,
             xml_list: empty,
@@ -176,6 +196,10 @@ impl<'gc> SystemPrototypes<'gc> {
             rectangle: empty,
             textfield: empty,
             textformat: empty,
+            textlinemetrics: empty,
+            fontdescription: empty,
+            groupelement: empty,
+            textline: empty,
             graphics: empty,
             loaderinfo: empty,
             bytearray: empty,
@@ -186,11 +210,13 @@ impl<'gc> SystemPrototypes<'gc> {
             vector: empty,
             soundtransform: empty,
             soundchannel: empty,
+            microphone: empty,
             bitmap: empty,
             bitmapdata: empty,
             date: empty,
             qname: empty,
             sharedobject: empty,
+            urlvariables: empty,
         }
     }
 }
@@ -216,6 +242,13 @@ pub struct SystemClasses<'gc> {
     pub application_domain: ClassObject<'gc>,
     pub event: ClassObject<'gc>,
     pub fullscreenevent: ClassObject<'gc>,
+    pub sampledataevent: ClassObject<'gc>,
+    pub activityevent: ClassObject<'gc>,
+    pub statusevent: ClassObject<'gc>,
+    pub textevent: ClassObject<'gc>,
+    pub progressevent: ClassObject<'gc>,
+    pub ioerrorevent: ClassObject<'gc>,
+    pub httpstatusevent: ClassObject<'gc>,
     pub video: ClassObject<'gc>,
     pub xml: ClassObject<'gc>,
     pub xml_list: ClassObject<'gc>,
@@ -225,6 +258,10 @@ pub struct SystemClasses<'gc> {
     pub rectangle: ClassObject<'gc>,
     pub textfield: ClassObject<'gc>,
     pub textformat: ClassObject<'gc>,
+    pub textlinemetrics: ClassObject<'gc>,
+    pub fontdescription: ClassObject<'gc>,
+    pub groupelement: ClassObject<'gc>,
+    pub textline: ClassObject<'gc>,
     pub graphics: ClassObject<'gc>,
     pub loaderinfo: ClassObject<'gc>,
     pub bytearray: ClassObject<'gc>,
@@ -235,11 +272,13 @@ pub struct SystemClasses<'gc> {
     pub vector: ClassObject<'gc>,
     pub soundtransform: ClassObject<'gc>,
     pub soundchannel: ClassObject<'gc>,
+    pub microphone: ClassObject<'gc>,
     pub bitmap: ClassObject<'gc>,
     pub bitmapdata: ClassObject<'gc>,
     pub date: ClassObject<'gc>,
     pub qname: ClassObject<'gc>,
     pub sharedobject: ClassObject<'gc>,
+    pub urlvariables: ClassObject<'gc>,
 }
 
 impl<'gc> SystemClasses<'gc> {
@@ -270,6 +309,13 @@ impl<'gc> SystemClasses<'gc> {
             application_domain: object,
             event: object,
             fullscreenevent: object,
+            sampledataevent: object,
+            activityevent: object,
+            statusevent: object,
+            textevent: object,
+            progressevent: object,
+            ioerrorevent: object,
+            httpstatusevent: object,
             video: object,
             xml: object,
             xml_list: object,
@@ -279,6 +325,10 @@ impl<'gc> SystemClasses<'gc> {
             rectangle: object,
             textfield: object,
             textformat: object,
+            textlinemetrics: object,
+            fontdescription: object,
+            groupelement: object,
+            textline: object,
             graphics: object,
             loaderinfo: object,
             bytearray: object,
@@ -289,11 +339,13 @@ impl<'gc> SystemClasses<'gc> {
             vector: object,
             soundtransform: object,
             soundchannel: object,
+            microphone: object,
             bitmap: object,
             bitmapdata: object,
             date: object,
             qname: object,
             sharedobject: object,
+            urlvariables: object,
         }
     }
 }
@@ -602,22 +654,59 @@ pub fn load_player_globals<'gc>(
         flash::events::keyboardevent::create_class(mc),
         script,
     )?;
-    class(
+    avm2_system_class!(
+        progressevent,
         activation,
         flash::events::progressevent::create_class(mc),
-        script,
-    )?;
-    class(
+        script
+    );
+    avm2_system_class!(
+        httpstatusevent,
+        activation,
+        flash::events::httpstatusevent::create_class(mc),
+        script
+    );
+    avm2_system_class!(
+        activityevent,
         activation,
         flash::events::activityevent::create_class(mc),
-        script,
-    )?;
+        script
+    );
+    avm2_system_class!(
+        statusevent,
+        activation,
+        flash::events::statusevent::create_class(mc),
+        script
+    );
     avm2_system_class!(
         fullscreenevent,
         activation,
         flash::events::fullscreenevent::create_class(mc),
         script
     );
+    avm2_system_class!(
+        sampledataevent,
+        activation,
+        flash::events::sampledataevent::create_class(mc),
+        script
+    );
+    avm2_system_class!(
+        textevent,
+        activation,
+        flash::events::textevent::create_class(mc),
+        script
+    );
+    class(
+        activation,
+        flash::events::errorevent::create_class(mc),
+        script,
+    )?;
+    avm2_system_class!(
+        ioerrorevent,
+        activation,
+        flash::events::ioerrorevent::create_class(mc),
+        script
+    );
     // package `flash.utils`
     avm2_system_class!(
         bytearray,
@@ -723,6 +812,7 @@ pub fn load_player_globals<'gc>(
         flash::display::sprite::create_class(mc),
         script
     );
+    class(activation, flash::display::loader::create_class(mc), script)?;
     avm2_system_class!(
         movieclip,
         activation,
@@ -861,6 +951,12 @@ pub fn load_player_globals<'gc>(
         flash::media::soundchannel::create_class(mc),
         script
     );
+    avm2_system_class!(
+        microphone,
+        activation,
+        flash::media::microphone::create_class(mc),
+        script
+    );
 
     // package `flash.net`
     avm2_system_class!(
@@ -875,6 +971,45 @@ pub fn load_player_globals<'gc>(
         flash::net::object_encoding::create_class(mc),
         script,
     )?;
+    class(activation, flash::net::urlloader::create_class(mc), script)?;
+    class(
+        activation,
+        flash::net::urlloaderdataformat::create_class(mc),
+        script,
+    )?;
+    class(activation, flash::net::urlrequest::create_class(mc), script)?;
+    class(
+        activation,
+        flash::net::urlrequestheader::create_class(mc),
+        script,
+    )?;
+    class(
+        activation,
+        flash::net::urlrequestmethod::create_class(mc),
+        script,
+    )?;
+    avm2_system_class!(
+        urlvariables,
+        activation,
+        flash::net::urlvariables::create_class(mc),
+        script
+    );
+
+    function(
+        activation,
+        "flash.net",
+        "registerClassAlias",
+        flash::net::register_class_alias,
+        script,
+    )?;
+
+    function(
+        activation,
+        "flash.net",
+        "getClassByAlias",
+        flash::net::get_class_by_alias,
+        script,
+    )?;
 
     // package `flash.text`
     avm2_system_class!(
@@ -889,6 +1024,12 @@ pub fn load_player_globals<'gc>(
         flash::text::textformat::create_class(mc),
         script
     );
+    avm2_system_class!(
+        textlinemetrics,
+        activation,
+        flash::text::textlinemetrics::create_class(mc),
+        script
+    );
     class(
         activation,
         flash::text::textfieldautosize::create_class(mc),
@@ -906,6 +1047,76 @@ pub fn load_player_globals<'gc>(
     )?;
     class(activation, flash::text::font::create_class(mc), script)?;
 
+    // package `flash.text.engine`
+    class(
+        activation,
+        flash::text::engine::contentelement::create_class(mc),
+        script,
+    )?;
+    class(
+        activation,
+        flash::text::engine::elementformat::create_class(mc),
+        script,
+    )?;
+    avm2_system_class!(
+        fontdescription,
+        activation,
+        flash::text::engine::fontdescription::create_class(mc),
+        script
+    );
+    class(
+        activation,
+        flash::text::engine::fontlookup::create_class(mc),
+        script,
+    )?;
+    class(
+        activation,
+        flash::text::engine::fontposture::create_class(mc),
+        script,
+    )?;
+    class(
+        activation,
+        flash::text::engine::fontweight::create_class(mc),
+        script,
+    )?;
+    avm2_system_class!(
+        groupelement,
+        activation,
+        flash::text::engine::groupelement::create_class(mc),
+        script
+    );
+    class(
+        activation,
+        flash::text::engine::textbaseline::create_class(mc),
+        script,
+    )?;
+    class(
+        activation,
+        flash::text::engine::textblock::create_class(mc),
+        script,
+    )?;
+    class(
+        activation,
+        flash::text::engine::textelement::create_class(mc),
+        script,
+    )?;
+    avm2_system_class!(
+        textline,
+        activation,
+        flash::text::engine::textline::create_class(mc),
+        script
+    );
+    class(
+        activation,
+        flash::text::engine::textlinecreationresult::create_class(mc),
+        script,
+    )?;
+    class(
+        activation,
+        flash::text::engine::textlinevalidity::create_class(mc),
+        script,
+    )?;
+
     // package `flash.crypto`
     function(
         activation,
@@ -7,8 +7,12 @@ use crate::avm2::names::{Namespace, QName};
 use crate::avm2::object::{date_allocator, DateObject, Object, TObject};
 use crate::avm2::value::Value;
 use crate::avm2::Error;
+use crate::backend::locale::{local_to_utc, LocalTimezone};
 use crate::string::{utils as string_utils, AvmString, WStr};
-use chrono::{DateTime, Datelike, Duration, FixedOffset, LocalResult, TimeZone, Timelike, Utc};
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, LocalResult, NaiveDate, Offset, TimeZone, Timelike,
+    Utc,
+};
 use gc_arena::{GcCell, MutationContext};
 use num_traits::ToPrimitive;
 
@@ -161,16 +165,10 @@ impl<'builder, 'activation_a, 'gc, 'gc_context, T: TimeZone>
             + Duration::seconds(second)
             + Duration::milliseconds(millisecond);
 
-        if let LocalResult::Single(Some(result)) = current
-            .timezone()
-            .ymd_opt(year, (month + 1) as u32, 1)
-            .and_hms_opt(0, 0, 0)
-            .map(|date| date.checked_add_signed(duration))
-        {
-            Some(result.with_timezone(&Utc))
-        } else {
-            None
-        }
+        let date = NaiveDate::from_ymd_opt(year, (month + 1) as u32, 1)?
+            .and_hms(0, 0, 0)
+            .checked_add_signed(duration)?;
+        local_to_utc(&current.timezone(), &date)
     }
 
     fn apply(&mut self, object: DateObject<'gc>) -> f64 {
@@ -206,7 +204,7 @@ pub fn instance_init<'gc>(
                     // We need a starting value to adjust from.
                     date.set_date_time(
                         activation.context.gc_context,
-                        Some(timezone.ymd(0, 1, 1).and_hms(0, 0, 0).into()),
+                        Some(timezone.ymd(0, 1, 1).and_hms(0, 0, 0).with_timezone(&Utc)),
                     );
 
                     DateAdjustment::new(activation, &timezone)
@@ -279,13 +277,13 @@ pub fn set_time<'gc>(
             .unwrap_or(&Value::Undefined)
             .coerce_to_number(activation)?;
         if new_time.is_finite() {
-            let time = Utc.timestamp_millis(new_time as i64);
-            this.set_date_time(activation.context.gc_context, Some(time));
-            return Ok((time.timestamp_millis() as f64).into());
-        } else {
-            this.set_date_time(activation.context.gc_context, None);
-            return Ok(f64::NAN.into());
+            if let LocalResult::Single(time) = Utc.timestamp_millis_opt(new_time as i64) {
+                this.set_date_time(activation.context.gc_context, Some(time));
+                return Ok((time.timestamp_millis() as f64).into());
+            }
         }
+        this.set_date_time(activation.context.gc_context, None);
+        return Ok(f64::NAN.into());
     }
 
     Ok(Value::Undefined)
@@ -833,7 +831,7 @@ pub fn timezone_offset<'gc>(
             .date_time()
             .map(|date| date.with_timezone(&activation.context.locale.get_timezone()))
         {
-            let offset = date.offset().utc_minus_local() as f64;
+            let offset = date.offset().fix().utc_minus_local() as f64;
             return Ok((offset / 60.0).into());
         } else {
             return Ok(f64::NAN.into());
@@ -1081,21 +1079,22 @@ fn parse_mon(item: &WStr) -> Option<usize> {
     MONTHS.iter().position(|&x| x == item)
 }
 
-/// Parses HH:MM:SS. The output is always (hours, minutes, seconds), or None if format was invalid.
+/// Parses HH:MM:SS or HH:MM. The output is always (hours, minutes, seconds), or None if format was invalid.
 fn parse_hms(item: &WStr) -> Option<(u32, u32, u32)> {
     let mut iter = item.split(b':');
     let hours = iter.next()?;
-    if hours.len() != 2 {
+    if hours.is_empty() || hours.len() > 2 {
         return None;
     }
     let minutes = iter.next()?;
     if minutes.len() != 2 {
         return None;
     }
-    let seconds = iter.next()?;
-    if seconds.len() != 2 {
-        return None;
-    }
+    let seconds = match iter.next() {
+        Some(seconds) if seconds.len() == 2 => seconds.parse::<u32>().ok()?,
+        Some(_) => return None,
+        None => 0,
+    };
     if iter.next().is_some() {
         // the iterator should have been empty
         return None;
@@ -1103,10 +1102,35 @@ fn parse_hms(item: &WStr) -> Option<(u32, u32, u32)> {
     Some((
         hours.parse::<u32>().ok()?,
         minutes.parse::<u32>().ok()?,
-        seconds.parse::<u32>().ok()?,
+        seconds,
     ))
 }
 
+/// Parses a timezone: GMT or UTC, optionally followed by -HHMM/+HHMM.
+/// The output is the offset in seconds, or None if format was invalid.
+fn parse_timezone(item: &WStr) -> Option<i32> {
+    if !item.starts_with(WStr::from_units(b"GMT")) && !item.starts_with(WStr::from_units(b"UTC")) {
+        return None;
+    }
+    let (_, offset) = item.split_at(3);
+    if offset.is_empty() {
+        return Some(0);
+    }
+    if offset.len() != 5 {
+        return None;
+    }
+    let (sign, offset) = offset.split_at(1);
+    let (hours, minutes) = offset.split_at(2);
+    let offset = (hours.parse::<i32>().ok()? * 60 * 60) + minutes.parse::<i32>().ok()? * 60;
+    if sign == WStr::from_units(b"-") {
+        Some(-offset)
+    } else if sign == WStr::from_units(b"+") {
+        Some(offset)
+    } else {
+        None
+    }
+}
+
 /// Implements the `parse` class method.
 // False
 #[allow(clippy::question_mark)]
@@ -1124,6 +1148,7 @@ pub fn parse<'gc>(
     let timezone = activation.context.locale.get_timezone();
     let mut final_time = DateAdjustment::new(activation, &timezone);
     let mut new_timezone = None;
+    let mut pm = None;
     // The Date parser is flash is super flexible, so we need to go through each item individually and parse it to match Flash.
     // NOTE: DateTime::parse_from_str is not flexible enough for this, so we need to parse manually.
     for item in date_str
@@ -1159,41 +1184,22 @@ pub fn parse<'gc>(
         } else if let Some(month) = parse_mon(item) {
             // Parse abbreviated month name (Jan, Feb, etc...)
             final_time.month = Some(Some(month as f64));
-        } else if item.starts_with(WStr::from_units(b"GMT"))
-            || item.starts_with(WStr::from_units(b"UTC"))
-        {
-            // Parse GMT-HHMM/GMT+HHMM or UTC-HHMM/UTC+HHMM
-
-            if new_timezone.is_some() || item.len() != 8 {
+        } else if item == WStr::from_units(b"AM") || item == WStr::from_units(b"PM") {
+            // Parse AM/PM, which adjusts the hours once everything is parsed.
+            if pm.is_some() {
                 return Ok(f64::NAN.into());
             }
-            let (other, tzn) = item.split_at(4);
-            if tzn.len() != 4 {
+            pm = Some(item == WStr::from_units(b"PM"));
+        } else if let Some(offset) = parse_timezone(item) {
+            // Parse GMT-HHMM/GMT+HHMM or UTC-HHMM/UTC+HHMM, or just GMT/UTC
+
+            if new_timezone.is_some() {
                 return Ok(f64::NAN.into());
             }
-            let (hours, minutes) = tzn.split_at(2);
-            let hours = if let Ok(hours) = hours.parse::<u32>() {
-                hours
-            } else {
-                return Ok(f64::NAN.into());
-            };
-            let minutes = if let Ok(minutes) = minutes.parse::<u32>() {
-                minutes
-            } else {
-                return Ok(f64::NAN.into());
-            };
-            let sign = other.at(3);
             // NOTE: In real flash, invalid (out of bounds) timezones were allowed, but there isn't a way to construct these using FixedOffset.
             // Since it is insanely rare to ever parse a date with an invalid timezone, for now we just return an error.
-            new_timezone = Some(if sign == b'-' as u16 {
-                FixedOffset::west_opt(((hours * 60 * 60) + minutes * 60) as i32)
-                    .ok_or("Error: Invalid timezone")?
-            } else if sign == b'+' as u16 {
-                FixedOffset::east_opt(((hours * 60 * 60) + minutes * 60) as i32)
-                    .ok_or("Error: Invalid timezone")?
-            } else {
-                return Ok(f64::NAN.into());
-            });
+            let offset = FixedOffset::east_opt(offset).ok_or("Error: Invalid timezone")?;
+            new_timezone = Some(LocalTimezone::new(offset));
         } else if let Ok(mut num) = item.parse::<u32>() {
             // Parse either a day or a year
 
@@ -1222,9 +1228,17 @@ pub fn parse<'gc>(
     if final_time.year.is_none() || final_time.month.is_none() || final_time.day.is_none() {
         return Ok(f64::NAN.into());
     }
+    if let (Some(pm), Some(Some(hour))) = (pm, final_time.hour) {
+        // 12 AM is midnight, and 12 PM is noon. Hours that are already on a
+        // 24-hour clock are left alone.
+        if hour <= 12.0 {
+            let hour = hour % 12.0 + if pm { 12.0 } else { 0.0 };
+            final_time.hour = Some(Some(hour));
+        }
+    }
     if let Some(timestamp) = final_time.calculate(
         new_timezone
-            .unwrap_or(timezone)
+            .unwrap_or_else(|| timezone.clone())
             .ymd(0, 1, 1)
             .and_hms(0, 0, 0),
     ) {
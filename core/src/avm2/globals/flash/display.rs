@@ -12,6 +12,7 @@ pub mod ibitmapdrawable;
 pub mod interactiveobject;
 pub mod jointstyle;
 pub mod linescalemode;
+pub mod loader;
 pub mod loaderinfo;
 pub mod movieclip;
 pub mod pixelsnapping;
@@ -2,15 +2,24 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::geom::rectangle::create_rectangle;
 use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::names::{Namespace, QName};
-use crate::avm2::object::{bitmapdata_allocator, Object, TObject};
+use crate::avm2::object::{bitmapdata_allocator, BitmapDataObject, Object, TObject};
 use crate::avm2::value::Value;
 use crate::avm2::Error;
-use crate::bitmap::bitmap_data::BitmapData;
+use crate::bitmap::bitmap_data::{
+    render_display_object, BitmapData, ChannelOptions, Color, ThresholdOperation,
+};
+use crate::bitmap::color_transform_params::ColorTransformParams;
 use crate::bitmap::is_size_valid;
 use crate::character::Character;
+use crate::color_transform::ColorTransform;
+use crate::matrix::Matrix;
+use crate::string::AvmString;
+use crate::transform::Transform;
 use gc_arena::{GcCell, MutationContext};
+use swf::{BlendMode, Fixed8, Twips};
 
 /// Implements `flash.display.BitmapData`'s instance constructor.
 pub fn instance_init<'gc>(
@@ -21,6 +30,11 @@ pub fn instance_init<'gc>(
     if let Some(this) = this {
         activation.super_init(this, &[])?;
 
+        if this.as_bitmap_data().is_some() {
+            // Bitmaps created by native code, such as `clone`, already have their pixels.
+            return Ok(Value::Undefined);
+        }
+
         let name = this.instance_of_class_definition().map(|c| c.read().name());
         let character = this
             .instance_of()
@@ -120,13 +134,197 @@ pub fn class_init<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Returns the pixels of `this`, or throws if the `BitmapData` has been disposed.
+fn bitmap_data_of<'gc>(
+    this: Option<Object<'gc>>,
+) -> Result<Option<GcCell<'gc, BitmapData<'gc>>>, Error> {
+    match this.and_then(|this| this.as_bitmap_data()) {
+        Some(bitmap_data) if bitmap_data.read().disposed() => {
+            Err("ArgumentError: Error #2015: Invalid BitmapData.".into())
+        }
+        bitmap_data => Ok(bitmap_data),
+    }
+}
+
+/// Returns the pixels of a `BitmapData` parameter, throwing if it is null,
+/// not a `BitmapData`, or disposed.
+fn bitmap_data_arg<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+    index: usize,
+    name: &str,
+) -> Result<GcCell<'gc, BitmapData<'gc>>, Error> {
+    let object = object_arg(activation, args, index, name)?;
+    match object.as_bitmap_data() {
+        Some(bitmap_data) if bitmap_data.read().disposed() => {
+            Err("ArgumentError: Error #2015: Invalid BitmapData.".into())
+        }
+        Some(bitmap_data) => Ok(bitmap_data),
+        None => Err(format!(
+            "TypeError: Error #1034: Type Coercion failed: cannot convert parameter {} to flash.display.BitmapData.",
+            name
+        )
+        .into()),
+    }
+}
+
+/// Returns an object parameter, throwing if it is null.
+fn object_arg<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+    index: usize,
+    name: &str,
+) -> Result<Object<'gc>, Error> {
+    match args.get(index).unwrap_or(&Value::Undefined) {
+        Value::Undefined | Value::Null => Err(format!(
+            "TypeError: Error #2007: Parameter {} must be non-null.",
+            name
+        )
+        .into()),
+        value => value.coerce_to_object(activation),
+    }
+}
+
+/// Reads a public property of an object as a number.
+fn get_number<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    object: Object<'gc>,
+    name: &'static str,
+) -> Result<f64, Error> {
+    object
+        .get_property(
+            object,
+            &QName::new(Namespace::public(), name).into(),
+            activation,
+        )?
+        .coerce_to_number(activation)
+}
+
+/// Reads the `x`, `y`, `width` and `height` of a `Rectangle` parameter.
+fn rectangle_arg<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+    index: usize,
+    name: &str,
+) -> Result<(i32, i32, i32, i32), Error> {
+    let rect = object_arg(activation, args, index, name)?;
+    rectangle_from_object(activation, rect)
+}
+
+fn rectangle_from_object<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    rect: Object<'gc>,
+) -> Result<(i32, i32, i32, i32), Error> {
+    Ok((
+        get_number(activation, rect, "x")? as i32,
+        get_number(activation, rect, "y")? as i32,
+        get_number(activation, rect, "width")? as i32,
+        get_number(activation, rect, "height")? as i32,
+    ))
+}
+
+/// Reads the `x` and `y` of a `Point` parameter, defaulting to the origin
+/// if the parameter is optional and null.
+fn point_arg<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+    index: usize,
+    name: &str,
+    optional: bool,
+) -> Result<(i32, i32), Error> {
+    if optional && matches!(args.get(index), None | Some(Value::Undefined | Value::Null)) {
+        return Ok((0, 0));
+    }
+    let point = object_arg(activation, args, index, name)?;
+    Ok((
+        get_number(activation, point, "x")? as i32,
+        get_number(activation, point, "y")? as i32,
+    ))
+}
+
+/// Reads the properties of a `Matrix` object.
+fn matrix_from_object<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    matrix: Object<'gc>,
+) -> Result<Matrix, Error> {
+    Ok(Matrix {
+        a: get_number(activation, matrix, "a")? as f32,
+        b: get_number(activation, matrix, "b")? as f32,
+        c: get_number(activation, matrix, "c")? as f32,
+        d: get_number(activation, matrix, "d")? as f32,
+        tx: Twips::from_pixels(get_number(activation, matrix, "tx")?),
+        ty: Twips::from_pixels(get_number(activation, matrix, "ty")?),
+    })
+}
+
+/// Reads the properties of a `ColorTransform` object.
+fn color_transform_from_object<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    color_transform: Object<'gc>,
+) -> Result<ColorTransform, Error> {
+    let params = color_transform_params_from_object(activation, color_transform)?;
+    Ok(ColorTransform {
+        r_mult: Fixed8::from_f64(params.red_multiplier),
+        g_mult: Fixed8::from_f64(params.green_multiplier),
+        b_mult: Fixed8::from_f64(params.blue_multiplier),
+        a_mult: Fixed8::from_f64(params.alpha_multiplier),
+        r_add: params.red_offset as i16,
+        g_add: params.green_offset as i16,
+        b_add: params.blue_offset as i16,
+        a_add: params.alpha_offset as i16,
+    })
+}
+
+fn color_transform_params_from_object<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    color_transform: Object<'gc>,
+) -> Result<ColorTransformParams, Error> {
+    Ok(ColorTransformParams {
+        red_multiplier: get_number(activation, color_transform, "redMultiplier")?,
+        green_multiplier: get_number(activation, color_transform, "greenMultiplier")?,
+        blue_multiplier: get_number(activation, color_transform, "blueMultiplier")?,
+        alpha_multiplier: get_number(activation, color_transform, "alphaMultiplier")?,
+        red_offset: get_number(activation, color_transform, "redOffset")?,
+        green_offset: get_number(activation, color_transform, "greenOffset")?,
+        blue_offset: get_number(activation, color_transform, "blueOffset")?,
+        alpha_offset: get_number(activation, color_transform, "alphaOffset")?,
+    })
+}
+
+/// Runs `f` on the pixels of `target` and `source`.
+///
+/// `source` is passed as `None` if it is the same `BitmapData` as `target`.
+fn with_source<'gc, R>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    target: GcCell<'gc, BitmapData<'gc>>,
+    source: GcCell<'gc, BitmapData<'gc>>,
+    f: impl FnOnce(&mut BitmapData<'gc>, Option<&BitmapData<'gc>>) -> R,
+) -> R {
+    let mut target_write = target.write(activation.context.gc_context);
+    if GcCell::ptr_eq(target, source) {
+        f(&mut target_write, None)
+    } else {
+        f(&mut target_write, Some(&source.read()))
+    }
+}
+
+/// Wraps new pixels in a `BitmapData` object.
+fn new_bitmap_data<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    bitmap_data: BitmapData<'gc>,
+) -> Result<Value<'gc>, Error> {
+    let bitmap_data = GcCell::allocate(activation.context.gc_context, bitmap_data);
+    let class = activation.avm2().classes().bitmapdata;
+    Ok(BitmapDataObject::from_bitmap_data(activation, bitmap_data, class)?.into())
+}
+
 /// Implements `BitmapData.width`'s getter.
 pub fn width<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
     this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    if let Some(bitmap_data) = this.and_then(|t| t.as_bitmap_data()) {
+    if let Some(bitmap_data) = bitmap_data_of(this)? {
         return Ok((bitmap_data.read().width() as i32).into());
     }
 
@@ -139,7 +337,7 @@ pub fn height<'gc>(
     this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    if let Some(bitmap_data) = this.and_then(|t| t.as_bitmap_data()) {
+    if let Some(bitmap_data) = bitmap_data_of(this)? {
         return Ok((bitmap_data.read().height() as i32).into());
     }
 
@@ -152,20 +350,37 @@ pub fn transparent<'gc>(
     this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    if let Some(bitmap_data) = this.and_then(|t| t.as_bitmap_data()) {
+    if let Some(bitmap_data) = bitmap_data_of(this)? {
         return Ok(bitmap_data.read().transparency().into());
     }
 
     Ok(Value::Undefined)
 }
 
+/// Implements `BitmapData.rect`'s getter.
+pub fn rect<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = bitmap_data_of(this)? {
+        let (width, height) = {
+            let bitmap_data = bitmap_data.read();
+            (bitmap_data.width(), bitmap_data.height())
+        };
+        return create_rectangle(activation, (0.0, 0.0, width.into(), height.into()));
+    }
+
+    Ok(Value::Undefined)
+}
+
 /// Implements `BitmapData.getPixel`.
 pub fn get_pixel<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Option<Object<'gc>>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    if let Some(bitmap_data) = this.and_then(|t| t.as_bitmap_data()) {
+    if let Some(bitmap_data) = bitmap_data_of(this)? {
         let x = args
             .get(0)
             .unwrap_or(&Value::Undefined)
@@ -180,6 +395,821 @@ pub fn get_pixel<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Implements `BitmapData.getPixel32`.
+pub fn get_pixel32<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = bitmap_data_of(this)? {
+        let x = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_i32(activation)?;
+        let y = args
+            .get(1)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_i32(activation)?;
+        let color: i32 = bitmap_data.read().get_pixel32(x, y).into();
+        return Ok((color as u32).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.setPixel`.
+pub fn set_pixel<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = bitmap_data_of(this)? {
+        let x = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation)?;
+        let y = args
+            .get(1)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation)?;
+        let color = args
+            .get(2)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_i32(activation)?;
+        bitmap_data
+            .write(activation.context.gc_context)
+            .set_pixel(x, y, color.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.setPixel32`.
+pub fn set_pixel32<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = bitmap_data_of(this)? {
+        let x = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_i32(activation)?;
+        let y = args
+            .get(1)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_i32(activation)?;
+        let color = args
+            .get(2)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_i32(activation)?;
+        bitmap_data
+            .write(activation.context.gc_context)
+            .set_pixel32(x, y, color.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.fillRect`.
+pub fn fill_rect<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = bitmap_data_of(this)? {
+        let (x, y, width, height) = rectangle_arg(activation, args, 0, "rect")?;
+        let color = args
+            .get(1)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_i32(activation)?;
+
+        let mut bitmap_data = bitmap_data.write(activation.context.gc_context);
+        let clip = |min: i32, len: i32, max: u32| {
+            let start = i64::from(min).clamp(0, max.into());
+            let end = (i64::from(min) + i64::from(len)).clamp(start, max.into());
+            (start as u32, (end - start) as u32)
+        };
+        let (x, width) = clip(x, width, bitmap_data.width());
+        let (y, height) = clip(y, height, bitmap_data.height());
+        bitmap_data.fill_rect(x, y, width, height, color.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.floodFill`.
+pub fn flood_fill<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = bitmap_data_of(this)? {
+        let x = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation)?;
+        let y = args
+            .get(1)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation)?;
+        let color = args
+            .get(2)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_i32(activation)?;
+
+        let mut bitmap_data = bitmap_data.write(activation.context.gc_context);
+        let color = Color::from(color).to_premultiplied_alpha(bitmap_data.transparency());
+        bitmap_data.flood_fill(x, y, color);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.noise`.
+pub fn noise<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = bitmap_data_of(this)? {
+        let seed = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_i32(activation)?;
+        let low = args.get(1).unwrap_or(&0.into()).coerce_to_u32(activation)? as u8;
+        let high = args
+            .get(2)
+            .unwrap_or(&0xFF.into())
+            .coerce_to_u32(activation)? as u8;
+        let channel_options = ChannelOptions::from_bits_truncate(
+            args.get(3).unwrap_or(&7.into()).coerce_to_u32(activation)? as u8,
+        );
+        let gray_scale = args.get(4).unwrap_or(&false.into()).coerce_to_boolean();
+
+        bitmap_data.write(activation.context.gc_context).noise(
+            seed,
+            low,
+            high.max(low),
+            channel_options,
+            gray_scale,
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.copyChannel`.
+pub fn copy_channel<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = bitmap_data_of(this)? {
+        let source = bitmap_data_arg(activation, args, 0, "sourceBitmapData")?;
+        let (src_x, src_y, src_width, src_height) =
+            rectangle_arg(activation, args, 1, "sourceRect")?;
+        let (dest_x, dest_y) = point_arg(activation, args, 2, "destPoint", false)?;
+        let source_channel = args
+            .get(3)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_i32(activation)?;
+        let dest_channel = args
+            .get(4)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_i32(activation)?;
+
+        with_source(activation, bitmap_data, source, |target, source| {
+            let copy;
+            let source = match source {
+                Some(source) => source,
+                None => {
+                    copy = target.clone();
+                    &copy
+                }
+            };
+            let dest_point = (
+                (dest_x.max(0) as u32).min(target.width()),
+                (dest_y.max(0) as u32).min(target.height()),
+            );
+            let src_min = (src_x.max(0) as u32, src_y.max(0) as u32);
+            let src_rect = (
+                src_min.0,
+                src_min.1,
+                src_min.0.saturating_add(src_width.max(0) as u32),
+                src_min.1.saturating_add(src_height.max(0) as u32),
+            );
+            target.copy_channel(dest_point, src_rect, source, source_channel, dest_channel);
+        });
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.colorTransform`.
+pub fn color_transform<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = bitmap_data_of(this)? {
+        let (x, y, width, height) = rectangle_arg(activation, args, 0, "rect")?;
+        let color_transform = object_arg(activation, args, 1, "colorTransform")?;
+        let params = color_transform_params_from_object(activation, color_transform)?;
+
+        let end = |min: i32, len: i32| (i64::from(min) + i64::from(len)).clamp(0, u32::MAX.into());
+        bitmap_data
+            .write(activation.context.gc_context)
+            .color_transform(
+                x.max(0) as u32,
+                y.max(0) as u32,
+                end(x, width) as u32,
+                end(y, height) as u32,
+                &params,
+            );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.getColorBoundsRect`.
+pub fn get_color_bounds_rect<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = bitmap_data_of(this)? {
+        let mask = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_i32(activation)?;
+        let color = args
+            .get(1)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_i32(activation)?;
+        let find_color = args.get(2).unwrap_or(&true.into()).coerce_to_boolean();
+
+        let (x, y, width, height) = bitmap_data
+            .read()
+            .color_bounds_rect(find_color, mask, color);
+        return create_rectangle(
+            activation,
+            (x.into(), y.into(), width.into(), height.into()),
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.copyPixels`.
+pub fn copy_pixels<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = bitmap_data_of(this)? {
+        let source = bitmap_data_arg(activation, args, 0, "sourceBitmapData")?;
+        let src_rect = rectangle_arg(activation, args, 1, "sourceRect")?;
+        let dest_point = point_arg(activation, args, 2, "destPoint", false)?;
+        let alpha_bitmap = match args.get(3) {
+            None | Some(Value::Undefined | Value::Null) => None,
+            Some(_) => Some(bitmap_data_arg(activation, args, 3, "alphaBitmapData")?),
+        };
+        let alpha_point = point_arg(activation, args, 4, "alphaPoint", true)?;
+        let merge_alpha = args.get(5).unwrap_or(&false.into()).coerce_to_boolean();
+
+        // Bitmaps that are also the target are copied before it is written to.
+        let source_clone: BitmapData;
+        let source_read;
+        let source: &BitmapData = if GcCell::ptr_eq(source, bitmap_data) {
+            source_clone = source.read().clone();
+            &source_clone
+        } else {
+            source_read = source.read();
+            &source_read
+        };
+        let alpha_clone: BitmapData;
+        let alpha_read;
+        let alpha_source = match alpha_bitmap {
+            Some(alpha_bitmap) if GcCell::ptr_eq(alpha_bitmap, bitmap_data) => {
+                alpha_clone = alpha_bitmap.read().clone();
+                Some((&alpha_clone, alpha_point, merge_alpha))
+            }
+            Some(alpha_bitmap) => {
+                alpha_read = alpha_bitmap.read();
+                Some((&*alpha_read, alpha_point, merge_alpha))
+            }
+            None => None,
+        };
+
+        bitmap_data
+            .write(activation.context.gc_context)
+            .copy_pixels(source, src_rect, dest_point, alpha_source);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.merge`.
+pub fn merge<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = bitmap_data_of(this)? {
+        let source = bitmap_data_arg(activation, args, 0, "sourceBitmapData")?;
+        let src_rect = rectangle_arg(activation, args, 1, "sourceRect")?;
+        let dest_point = point_arg(activation, args, 2, "destPoint", false)?;
+        let mut mults = [0; 4];
+        for (i, mult) in mults.iter_mut().enumerate() {
+            *mult = args
+                .get(3 + i)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_i32(activation)?;
+        }
+        let [red_mult, green_mult, blue_mult, alpha_mult] = mults;
+
+        with_source(activation, bitmap_data, source, |target, source| {
+            let copy;
+            let source = match source {
+                Some(source) => source,
+                None => {
+                    copy = target.clone();
+                    &copy
+                }
+            };
+            target.merge(
+                source,
+                src_rect,
+                dest_point,
+                (red_mult, green_mult, blue_mult, alpha_mult),
+            );
+        });
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.paletteMap`.
+pub fn palette_map<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = bitmap_data_of(this)? {
+        let source = bitmap_data_arg(activation, args, 0, "sourceBitmapData")?;
+        let src_rect = rectangle_arg(activation, args, 1, "sourceRect")?;
+        let dest_point = point_arg(activation, args, 2, "destPoint", false)?;
+
+        let mut get_channel = |index: usize, shift: usize| -> Result<[u32; 256], Error> {
+            let mut array = [0_u32; 256];
+            match args.get(index).unwrap_or(&Value::Null) {
+                Value::Undefined | Value::Null => {
+                    // Channels without an array are copied unchanged.
+                    for (i, item) in array.iter_mut().enumerate() {
+                        *item = (i << shift) as u32;
+                    }
+                }
+                value => {
+                    let object = value.coerce_to_object(activation)?;
+                    for (i, item) in array.iter_mut().enumerate() {
+                        let name =
+                            AvmString::new_utf8(activation.context.gc_context, i.to_string());
+                        *item = object
+                            .get_property(
+                                object,
+                                &QName::new(Namespace::public(), name).into(),
+                                activation,
+                            )?
+                            .coerce_to_u32(activation)?;
+                    }
+                }
+            }
+            Ok(array)
+        };
+        let red_array = get_channel(3, 16)?;
+        let green_array = get_channel(4, 8)?;
+        let blue_array = get_channel(5, 0)?;
+        let alpha_array = get_channel(6, 24)?;
+
+        with_source(activation, bitmap_data, source, |target, source| {
+            target.palette_map(
+                source,
+                src_rect,
+                dest_point,
+                (red_array, green_array, blue_array, alpha_array),
+            )
+        });
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.perlinNoise`.
+pub fn perlin_noise<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = bitmap_data_of(this)? {
+        let base_x = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_number(activation)?;
+        let base_y = args
+            .get(1)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_number(activation)?;
+        let num_octaves = args
+            .get(2)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation)? as usize;
+        let seed = args
+            .get(3)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_i32(activation)? as i64;
+        let stitch = args.get(4).unwrap_or(&false.into()).coerce_to_boolean();
+        let fractal_noise = args.get(5).unwrap_or(&false.into()).coerce_to_boolean();
+        let channel_options = ChannelOptions::from_bits_truncate(
+            args.get(6).unwrap_or(&7.into()).coerce_to_u32(activation)? as u8,
+        );
+        let grayscale = args.get(7).unwrap_or(&false.into()).coerce_to_boolean();
+
+        let offsets = match args.get(8) {
+            None | Some(Value::Undefined | Value::Null) => None,
+            Some(value) => Some(value.coerce_to_object(activation)?),
+        };
+        let mut octave_offsets = Vec::with_capacity(num_octaves);
+        for i in 0..num_octaves {
+            let offset = match offsets {
+                Some(offsets) => {
+                    let name = AvmString::new_utf8(activation.context.gc_context, i.to_string());
+                    offsets.get_property(
+                        offsets,
+                        &QName::new(Namespace::public(), name).into(),
+                        activation,
+                    )?
+                }
+                None => Value::Undefined,
+            };
+            octave_offsets.push(match offset {
+                Value::Object(point) => (
+                    get_number(activation, point, "x")?,
+                    get_number(activation, point, "y")?,
+                ),
+                _ => (0.0, 0.0),
+            });
+        }
+
+        bitmap_data
+            .write(activation.context.gc_context)
+            .perlin_noise(
+                (base_x, base_y),
+                num_octaves,
+                seed,
+                stitch,
+                fractal_noise,
+                channel_options,
+                grayscale,
+                octave_offsets,
+            );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.pixelDissolve`.
+pub fn pixel_dissolve<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = bitmap_data_of(this)? {
+        let source = bitmap_data_arg(activation, args, 0, "sourceBitmapData")?;
+        let src_rect = rectangle_arg(activation, args, 1, "sourceRect")?;
+        let dest_point = point_arg(activation, args, 2, "destPoint", false)?;
+        let seed = args.get(3).unwrap_or(&0.into()).coerce_to_i32(activation)?;
+        let num_pixels = args.get(4).unwrap_or(&0.into()).coerce_to_i32(activation)?;
+        let fill_color = args.get(5).unwrap_or(&0.into()).coerce_to_i32(activation)?;
+
+        let next_seed = with_source(activation, bitmap_data, source, |target, source| {
+            target.pixel_dissolve(
+                source,
+                src_rect,
+                dest_point,
+                seed,
+                num_pixels,
+                fill_color.into(),
+            )
+        });
+        return Ok(next_seed.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.scroll`.
+pub fn scroll<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = bitmap_data_of(this)? {
+        let x = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_i32(activation)?;
+        let y = args
+            .get(1)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_i32(activation)?;
+        bitmap_data
+            .write(activation.context.gc_context)
+            .scroll(x, y);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.threshold`.
+pub fn threshold<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = bitmap_data_of(this)? {
+        let source = bitmap_data_arg(activation, args, 0, "sourceBitmapData")?;
+        let src_rect = rectangle_arg(activation, args, 1, "sourceRect")?;
+        let dest_point = point_arg(activation, args, 2, "destPoint", false)?;
+        let operation = args
+            .get(3)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_string(activation)?;
+        let operation = match ThresholdOperation::from_operator(&operation.to_string()) {
+            Some(operation) => operation,
+            None => {
+                return Err("ArgumentError: Error #2004: One of the parameters is invalid.".into())
+            }
+        };
+        let threshold = args
+            .get(4)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation)?;
+        let color = args.get(5).unwrap_or(&0.into()).coerce_to_i32(activation)?;
+        let mask = args
+            .get(6)
+            .unwrap_or(&0xFFFFFFFFu32.into())
+            .coerce_to_u32(activation)?;
+        let copy_source = args.get(7).unwrap_or(&false.into()).coerce_to_boolean();
+
+        let num_changed = with_source(activation, bitmap_data, source, |target, source| {
+            target.threshold(
+                source,
+                src_rect,
+                dest_point,
+                operation,
+                threshold,
+                color.into(),
+                mask,
+                copy_source,
+            )
+        });
+        return Ok(num_changed.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.compare`.
+pub fn compare<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    const EQUIVALENT: i32 = 0;
+    const DIFFERENT_WIDTHS: i32 = -3;
+    const DIFFERENT_HEIGHTS: i32 = -4;
+
+    if let Some(bitmap_data) = bitmap_data_of(this)? {
+        let other = bitmap_data_arg(activation, args, 0, "otherBitmapData")?;
+        let difference = {
+            let bitmap_data = bitmap_data.read();
+            let other = other.read();
+            if bitmap_data.width() != other.width() {
+                return Ok(DIFFERENT_WIDTHS.into());
+            }
+            if bitmap_data.height() != other.height() {
+                return Ok(DIFFERENT_HEIGHTS.into());
+            }
+            BitmapData::compare(&bitmap_data, &other)
+        };
+        return match difference {
+            Some(difference) => new_bitmap_data(activation, difference),
+            None => Ok(EQUIVALENT.into()),
+        };
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.hitTest`.
+pub fn hit_test<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = bitmap_data_of(this)? {
+        let (first_x, first_y) = point_arg(activation, args, 0, "firstPoint", false)?;
+        let first_threshold = args
+            .get(1)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation)?
+            .min(255) as u8;
+        let second_object = object_arg(activation, args, 2, "secondObject")?;
+
+        // The second object can be a `BitmapData`, a `Rectangle`, or a `Point`,
+        // all in the same coordinate space as `firstPoint`.
+        let rectangle_class = activation.avm2().classes().rectangle;
+        let point_class = activation.avm2().classes().point;
+        let result = if second_object.as_bitmap_data().is_some() {
+            let other = bitmap_data_arg(activation, args, 2, "secondObject")?;
+            let (second_x, second_y) =
+                point_arg(activation, args, 3, "secondBitmapDataPoint", false)?;
+            let second_threshold = args
+                .get(4)
+                .unwrap_or(&1.into())
+                .coerce_to_u32(activation)?
+                .min(255) as u8;
+            let offset = (
+                second_x.saturating_sub(first_x),
+                second_y.saturating_sub(first_y),
+            );
+            if GcCell::ptr_eq(bitmap_data, other) {
+                let bitmap_data = bitmap_data.read();
+                bitmap_data.hit_test_bitmap(first_threshold, &bitmap_data, offset, second_threshold)
+            } else {
+                bitmap_data.read().hit_test_bitmap(
+                    first_threshold,
+                    &other.read(),
+                    offset,
+                    second_threshold,
+                )
+            }
+        } else if second_object.is_of_type(rectangle_class, activation)? {
+            let (x, y, width, height) = rectangle_from_object(activation, second_object)?;
+            bitmap_data.read().hit_test_rectangle(
+                first_threshold,
+                (
+                    x.saturating_sub(first_x),
+                    y.saturating_sub(first_y),
+                    width,
+                    height,
+                ),
+            )
+        } else if second_object.is_of_type(point_class, activation)? {
+            let x = get_number(activation, second_object, "x")? as i32;
+            let y = get_number(activation, second_object, "y")? as i32;
+            bitmap_data.read().hit_test_point(
+                first_threshold,
+                (x.saturating_sub(first_x), y.saturating_sub(first_y)),
+            )
+        } else {
+            return Err(
+                "TypeError: Error #2005: Parameter 2 is of the incorrect type. Should be type Point, Rectangle or BitmapData."
+                    .into(),
+            );
+        };
+        return Ok(result.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.draw`.
+pub fn draw<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = bitmap_data_of(this)? {
+        let source = object_arg(activation, args, 0, "source")?;
+        let matrix = match args.get(1) {
+            Some(Value::Object(matrix)) => matrix_from_object(activation, *matrix)?,
+            _ => Matrix::default(),
+        };
+        let color_transform = match args.get(2) {
+            Some(Value::Object(color_transform)) => {
+                color_transform_from_object(activation, *color_transform)?
+            }
+            _ => ColorTransform::default(),
+        };
+        let blend_mode = match args.get(3) {
+            None | Some(Value::Undefined | Value::Null) => BlendMode::Normal,
+            Some(blend_mode) => blend_mode
+                .coerce_to_string(activation)?
+                .to_string()
+                .parse()
+                .unwrap_or(BlendMode::Normal),
+        };
+        let clip_rect = match args.get(4) {
+            Some(Value::Object(clip_rect)) => Some(rectangle_from_object(activation, *clip_rect)?),
+            _ => None,
+        };
+        let smoothing = args.get(5).unwrap_or(&false.into()).coerce_to_boolean();
+
+        if let Some(source) = source.as_bitmap_data() {
+            if source.read().disposed() {
+                return Err("ArgumentError: Error #2015: Invalid BitmapData.".into());
+            }
+            with_source(activation, bitmap_data, source, |target, source| {
+                target.draw_bitmap_data(
+                    source,
+                    matrix,
+                    &color_transform,
+                    blend_mode,
+                    clip_rect,
+                    smoothing,
+                )
+            });
+        } else if let Some(source) = source.as_display_object() {
+            let (width, height) = {
+                let bitmap_data = bitmap_data.read();
+                (bitmap_data.width(), bitmap_data.height())
+            };
+            let transform = Transform {
+                matrix,
+                color_transform,
+            };
+            match render_display_object(&mut activation.context, source, transform, width, height) {
+                Some(pixels) => bitmap_data
+                    .write(activation.context.gc_context)
+                    .draw_pixels(&pixels, blend_mode, clip_rect),
+                None => log::warn!(
+                    "BitmapData.draw - drawing display objects is not supported by this renderer"
+                ),
+            }
+        } else {
+            return Err(
+                "TypeError: Error #1034: Type Coercion failed: cannot convert parameter source to flash.display.IBitmapDrawable."
+                    .into(),
+            );
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.clone`.
+pub fn clone<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = bitmap_data_of(this)? {
+        let mut clone = BitmapData::default();
+        {
+            let bitmap_data = bitmap_data.read();
+            clone.set_pixels(
+                bitmap_data.width(),
+                bitmap_data.height(),
+                bitmap_data.transparency(),
+                bitmap_data.pixels().to_vec(),
+            );
+        }
+        return new_bitmap_data(activation, clone);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.dispose`.
+pub fn dispose<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    // Disposing twice is allowed.
+    if let Some(bitmap_data) = this.and_then(|this| this.as_bitmap_data()) {
+        bitmap_data.write(activation.context.gc_context).dispose();
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.lock` and `BitmapData.unlock`.
+///
+/// Changes are always uploaded lazily when the bitmap is next rendered,
+/// so there's nothing to do here.
+pub fn lock<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    bitmap_data_of(this)?;
+
+    Ok(Value::Undefined)
+}
+
 /// Construct `BitmapData`'s class.
 pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
     let class = Class::new(
@@ -205,11 +1235,179 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         ("width", Some(width), None),
         ("height", Some(height), None),
         ("transparent", Some(transparent), None),
+        ("rect", Some(rect), None),
     ];
     write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
 
-    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[("getPixel", get_pixel)];
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("getPixel", get_pixel),
+        ("getPixel32", get_pixel32),
+        ("setPixel", set_pixel),
+        ("setPixel32", set_pixel32),
+        ("fillRect", fill_rect),
+        ("floodFill", flood_fill),
+        ("noise", noise),
+        ("copyChannel", copy_channel),
+        ("colorTransform", color_transform),
+        ("getColorBoundsRect", get_color_bounds_rect),
+        ("copyPixels", copy_pixels),
+        ("merge", merge),
+        ("paletteMap", palette_map),
+        ("perlinNoise", perlin_noise),
+        ("pixelDissolve", pixel_dissolve),
+        ("scroll", scroll),
+        ("threshold", threshold),
+        ("compare", compare),
+        ("hitTest", hit_test),
+        ("draw", draw),
+        ("clone", clone),
+        ("dispose", dispose),
+        ("lock", lock),
+        ("unlock", lock),
+    ];
     write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
 
     class
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm2::globals::flash::geom::point::create_point;
+    use crate::avm2::test_utils::with_avm2;
+
+    fn new_bitmap<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        width: i32,
+        height: i32,
+        fill_color: u32,
+    ) -> Result<Object<'gc>, Error> {
+        let class = activation.avm2().classes().bitmapdata;
+        class.construct(
+            activation,
+            &[width.into(), height.into(), true.into(), fill_color.into()],
+        )
+    }
+
+    #[test]
+    fn threshold_clips_to_both_bitmaps() {
+        with_avm2(|activation| {
+            let source = new_bitmap(activation, 4, 4, 0xFF00FF00)?;
+            let target = new_bitmap(activation, 2, 2, 0)?;
+            // Only the source's top left pixel lands on the target, at (1, 1).
+            let rect = create_rectangle(activation, (-1.0, -1.0, 1e10, 1e10))?;
+            let point = create_point(activation, (0.0, 0.0))?;
+
+            let changed = threshold(
+                activation,
+                Some(target),
+                &[
+                    source.into(),
+                    rect,
+                    point,
+                    "==".into(),
+                    0xFF00FF00u32.into(),
+                    0xFFFF0000u32.into(),
+                ],
+            )?;
+            assert_eq!(changed.coerce_to_u32(activation)?, 1);
+
+            let pixel = get_pixel32(activation, Some(target), &[1.into(), 1.into()])?;
+            assert_eq!(pixel.coerce_to_u32(activation)?, 0xFFFF0000);
+            let pixel = get_pixel32(activation, Some(target), &[0.into(), 0.into()])?;
+            assert_eq!(pixel.coerce_to_u32(activation)?, 0);
+
+            let result = threshold(
+                activation,
+                Some(target),
+                &[source.into(), rect, point, "=>".into(), 0.into()],
+            );
+            assert!(result.is_err());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn clone_and_compare() {
+        with_avm2(|activation| {
+            let bitmap = new_bitmap(activation, 3, 3, 0xFF000000)?;
+            let copy = clone(activation, Some(bitmap), &[])?.coerce_to_object(activation)?;
+            let result = compare(activation, Some(bitmap), &[copy.into()])?;
+            assert_eq!(result.coerce_to_i32(activation)?, 0);
+
+            set_pixel32(
+                activation,
+                Some(copy),
+                &[1.into(), 1.into(), 0xFFFFFFFFu32.into()],
+            )?;
+            let difference =
+                compare(activation, Some(bitmap), &[copy.into()])?.coerce_to_object(activation)?;
+            assert!(difference.as_bitmap_data().is_some());
+
+            let wider = new_bitmap(activation, 4, 3, 0)?;
+            let result = compare(activation, Some(bitmap), &[wider.into()])?;
+            assert_eq!(result.coerce_to_i32(activation)?, -3);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn hit_test_point_rectangle_and_bitmap() {
+        with_avm2(|activation| {
+            let bitmap = new_bitmap(activation, 4, 4, 0)?;
+            fill_rect(
+                activation,
+                Some(bitmap),
+                &[
+                    create_rectangle(activation, (2.0, 2.0, 100.0, 100.0))?,
+                    0xFFFFFFFFu32.into(),
+                ],
+            )?;
+            let origin = create_point(activation, (10.0, 10.0))?;
+
+            let point = create_point(activation, (12.0, 12.0))?;
+            let hit = hit_test(activation, Some(bitmap), &[origin, 1.into(), point])?;
+            assert!(hit.coerce_to_boolean());
+
+            let rect = create_rectangle(activation, (-1e10, -1e10, 1e10, 1e10))?;
+            let hit = hit_test(activation, Some(bitmap), &[origin, 1.into(), rect])?;
+            assert!(!hit.coerce_to_boolean());
+
+            let other = new_bitmap(activation, 2, 2, 0xFFFFFFFF)?;
+            let far = create_point(activation, (1e10, 1e10))?;
+            let hit = hit_test(
+                activation,
+                Some(bitmap),
+                &[origin, 1.into(), other.into(), far],
+            )?;
+            assert!(!hit.coerce_to_boolean());
+            let near = create_point(activation, (13.0, 13.0))?;
+            let hit = hit_test(
+                activation,
+                Some(bitmap),
+                &[origin, 1.into(), other.into(), near],
+            )?;
+            assert!(hit.coerce_to_boolean());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn disposed_bitmap_throws() {
+        with_avm2(|activation| {
+            let bitmap = new_bitmap(activation, 2, 2, 0)?;
+            let other = new_bitmap(activation, 2, 2, 0)?;
+            dispose(activation, Some(bitmap), &[])?;
+            dispose(activation, Some(bitmap), &[])?;
+
+            assert!(width(activation, Some(bitmap), &[]).is_err());
+            let rect = create_rectangle(activation, (0.0, 0.0, 2.0, 2.0))?;
+            let point = create_point(activation, (0.0, 0.0))?;
+            let result = copy_pixels(activation, Some(other), &[bitmap.into(), rect, point]);
+            assert!(result.is_err());
+            let result = copy_pixels(activation, Some(other), &[Value::Null, rect, point]);
+            assert!(result.is_err());
+            Ok(())
+        });
+    }
+}
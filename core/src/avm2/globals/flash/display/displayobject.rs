@@ -2,16 +2,20 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::class::Class;
+use crate::avm2::globals::flash::geom::point::create_point;
+use crate::avm2::globals::flash::geom::rectangle::create_rectangle;
 use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::names::{Namespace, QName};
 use crate::avm2::object::{stage_allocator, LoaderInfoObject, Object, TObject};
 use crate::avm2::value::Value;
 use crate::avm2::Error;
+use crate::bounding_box::BoundingBox;
 use crate::display_object::{DisplayObject, HitTestOptions, TDisplayObject};
+use crate::string::AvmString;
 use crate::types::{Degrees, Percent};
 use crate::vminterface::Instantiator;
 use gc_arena::{GcCell, MutationContext};
-use swf::Twips;
+use swf::{BlendMode, Twips};
 
 /// Implements `flash.display.DisplayObject`'s instance constructor.
 pub fn instance_init<'gc>(
@@ -465,6 +469,111 @@ pub fn set_visible<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Implements `blendMode`'s getter.
+pub fn blend_mode<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        let blend_mode = dobj.blend_mode().to_string();
+        return Ok(AvmString::new_utf8(activation.context.gc_context, blend_mode).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `blendMode`'s setter.
+pub fn set_blend_mode<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        let name = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_string(activation)?;
+        let blend_mode: BlendMode = match name.to_string().parse() {
+            Ok(blend_mode) => blend_mode,
+            Err(()) => {
+                return Err(
+                    "ArgumentError: Error #2008: Parameter blendMode must be one of the accepted values."
+                        .into(),
+                )
+            }
+        };
+
+        dobj.set_blend_mode(activation.context.gc_context, blend_mode);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `scale9Grid`'s getter.
+pub fn scale_nine_grid<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        if let Some(grid) = dobj.scaling_grid() {
+            return create_rectangle(
+                activation,
+                (
+                    grid.x_min.to_pixels(),
+                    grid.y_min.to_pixels(),
+                    grid.width().to_pixels(),
+                    grid.height().to_pixels(),
+                ),
+            );
+        }
+        return Ok(Value::Null);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `scale9Grid`'s setter.
+pub fn set_scale_nine_grid<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        let grid = match args.get(0).cloned().unwrap_or(Value::Undefined) {
+            Value::Null | Value::Undefined => None,
+            rect => {
+                let rect = rect.coerce_to_object(activation)?;
+                let mut get = |name| -> Result<f64, Error> {
+                    rect.get_property(
+                        rect,
+                        &QName::new(Namespace::public(), name).into(),
+                        activation,
+                    )?
+                    .coerce_to_number(activation)
+                };
+                let x = get("x")?;
+                let y = get("y")?;
+                let width = get("width")?;
+                let height = get("height")?;
+                Some(BoundingBox {
+                    x_min: Twips::from_pixels(x),
+                    y_min: Twips::from_pixels(y),
+                    x_max: Twips::from_pixels(x + width),
+                    y_max: Twips::from_pixels(y + height),
+                    valid: true,
+                })
+            }
+        };
+
+        dobj.set_scaling_grid(activation.context.gc_context, grid);
+    }
+
+    Ok(Value::Undefined)
+}
+
 /// Implements `mouseX`.
 pub fn mouse_x<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
@@ -584,6 +693,122 @@ pub fn loader_info<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Implements `getBounds`.
+pub fn get_bounds<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        return bounds_rectangle(activation, dobj, args, dobj.bounds());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `getRect`.
+pub fn get_rect<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        return bounds_rectangle(activation, dobj, args, dobj.rect_bounds());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Creates the `Rectangle` returned by `getBounds` and `getRect`, with the
+/// given bounds transformed into the coordinate space given in `args`.
+fn bounds_rectangle<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    dobj: DisplayObject<'gc>,
+    args: &[Value<'gc>],
+    bounds: BoundingBox,
+) -> Result<Value<'gc>, Error> {
+    let target = match args.get(0).cloned().unwrap_or(Value::Undefined) {
+        Value::Null | Value::Undefined => dobj,
+        target => target
+            .coerce_to_object(activation)?
+            .as_display_object()
+            .unwrap_or(dobj),
+    };
+
+    let out_bounds = dobj.bounds_in_space(bounds, target);
+    if !out_bounds.valid {
+        return create_rectangle(activation, (0.0, 0.0, 0.0, 0.0));
+    }
+
+    create_rectangle(
+        activation,
+        (
+            out_bounds.x_min.to_pixels(),
+            out_bounds.y_min.to_pixels(),
+            out_bounds.width().to_pixels(),
+            out_bounds.height().to_pixels(),
+        ),
+    )
+}
+
+/// Reads the coordinates of a `Point` given to `localToGlobal` or `globalToLocal`.
+fn point_coordinates<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<(Twips, Twips), Error> {
+    let point = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_object(activation)?;
+    let x = point
+        .get_property(
+            point,
+            &QName::new(Namespace::public(), "x").into(),
+            activation,
+        )?
+        .coerce_to_number(activation)?;
+    let y = point
+        .get_property(
+            point,
+            &QName::new(Namespace::public(), "y").into(),
+            activation,
+        )?
+        .coerce_to_number(activation)?;
+
+    Ok((Twips::from_pixels(x), Twips::from_pixels(y)))
+}
+
+/// Implements `localToGlobal`.
+pub fn local_to_global<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        let point = point_coordinates(activation, args)?;
+        let (x, y) = dobj.local_to_global(point);
+        return create_point(activation, (x.to_pixels(), y.to_pixels()));
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `globalToLocal`.
+pub fn global_to_local<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        let point = point_coordinates(activation, args)?;
+        let (x, y) = dobj.global_to_local(point);
+        return create_point(activation, (x.to_pixels(), y.to_pixels()));
+    }
+
+    Ok(Value::Undefined)
+}
+
 /// Construct `DisplayObject`'s class.
 pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
     let class = Class::new(
@@ -623,6 +848,12 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         ("root", Some(root), None),
         ("stage", Some(stage), None),
         ("visible", Some(visible), Some(set_visible)),
+        ("blendMode", Some(blend_mode), Some(set_blend_mode)),
+        (
+            "scale9Grid",
+            Some(scale_nine_grid),
+            Some(set_scale_nine_grid),
+        ),
         ("mouseX", Some(mouse_x), None),
         ("mouseY", Some(mouse_y), None),
         ("loaderInfo", Some(loader_info), None),
@@ -632,6 +863,10 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
     const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
         ("hitTestPoint", hit_test_point),
         ("hitTestObject", hit_test_object),
+        ("getBounds", get_bounds),
+        ("getRect", get_rect),
+        ("localToGlobal", local_to_global),
+        ("globalToLocal", global_to_local),
     ];
     write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
 
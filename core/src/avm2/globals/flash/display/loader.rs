@@ -0,0 +1,275 @@
+//! `flash.display.Loader` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::net::urlrequest::url_and_request_options;
+use crate::avm2::globals::NS_RUFFLE_INTERNAL;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{LoaderInfoObject, LoaderStream, Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::backend::navigator::{FetchProgress, OwnedFuture};
+use crate::display_object::{DisplayObject, MovieClip, TDisplayObject, TDisplayObjectContainer};
+use crate::loader::Error as LoaderError;
+use crate::tag_utils::SwfMovie;
+use gc_arena::{GcCell, MutationContext};
+use std::sync::Arc;
+
+/// Implements `flash.display.Loader`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        if this.as_display_object().is_none() {
+            let class_object = this
+                .instance_of()
+                .ok_or("Attempted to construct Loader on a bare object")?;
+            let movie = Arc::new(SwfMovie::empty(activation.context.swf.version()));
+            let new_do =
+                MovieClip::new_with_avm2(movie, this, class_object, activation.context.gc_context);
+
+            this.init_display_object(activation.context.gc_context, new_do.into());
+        }
+
+        let content_loader_info = LoaderInfoObject::not_yet_loaded(activation)?;
+        this.set_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "contentLoaderInfo").into(),
+            content_loader_info.into(),
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.display.Loader`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `contentLoaderInfo`.
+pub fn content_loader_info<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return this.get_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "contentLoaderInfo").into(),
+            activation,
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `content`.
+///
+/// The content is only available once the load has completed.
+pub fn content<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let loader_info = content_loader_info(activation, this, &[])?;
+    if let Value::Object(loader_info) = loader_info {
+        if let Some(LoaderStream::Swf(_, root)) = loader_info.as_loader_stream().as_deref() {
+            if let Value::Object(content) = root.object2() {
+                return Ok(content.into());
+            }
+        }
+    }
+
+    Ok(Value::Null)
+}
+
+/// Replace the content of a loader with an empty clip, and start loading a
+/// movie into it.
+fn start_load<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    fetch: OwnedFuture<Vec<u8>, LoaderError>,
+    progress: Option<FetchProgress>,
+    url: String,
+) -> Result<(), Error> {
+    unload(activation, Some(this), &[])?;
+
+    let loader_info =
+        content_loader_info(activation, Some(this), &[])?.coerce_to_object(activation)?;
+    let loader = match this.as_display_object() {
+        Some(loader) => loader,
+        None => return Ok(()),
+    };
+
+    let movie = Arc::new(SwfMovie::empty(activation.context.swf.version()));
+    let content: DisplayObject<'gc> = MovieClip::new(movie, activation.context.gc_context).into();
+    if let Some(mut container) = loader.as_container() {
+        container.insert_at_index(&mut activation.context, content, 0);
+    }
+
+    let process = activation.context.load_manager.load_movie_into_avm2_loader(
+        activation.context.player.clone().unwrap(),
+        content,
+        fetch,
+        progress,
+        url,
+        loader_info,
+    );
+    activation.context.navigator.spawn_future(process);
+
+    Ok(())
+}
+
+/// Implements `Loader.load`.
+pub fn load<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let request = match args.get(0) {
+            Some(Value::Object(request)) => *request,
+            _ => return Err("TypeError: Error #2007: Parameter request must be non-null.".into()),
+        };
+
+        let (url, request_options) = url_and_request_options(activation, request)?;
+        let progress = FetchProgress::new();
+        let fetch = activation.context.navigator.fetch_with_progress(
+            &url,
+            request_options,
+            progress.clone(),
+        );
+        start_load(activation, this, fetch, Some(progress), url)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Loader.loadBytes`.
+///
+/// The bytes are loaded asynchronously, as if they had been fetched from the
+/// loading movie's URL.
+pub fn load_bytes<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let bytes = match args.get(0) {
+            Some(Value::Object(bytes)) => bytes
+                .as_bytearray()
+                .map(|bytes| bytes.bytes().to_vec())
+                .ok_or("TypeError: Error #1034: Type Coercion failed: cannot convert to flash.utils.ByteArray.")?,
+            _ => return Err("TypeError: Error #2007: Parameter bytes must be non-null.".into()),
+        };
+
+        let url = activation.context.swf.url().unwrap_or_default().to_string();
+        let fetch: OwnedFuture<Vec<u8>, LoaderError> = Box::pin(async move { Ok(bytes) });
+        start_load(activation, this, fetch, None, url)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Loader.unload`.
+pub fn unload<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        close(activation, Some(this), &[])?;
+
+        if let Some(mut container) = this
+            .as_display_object()
+            .and_then(|dobj| dobj.as_container())
+        {
+            container.clear(&mut activation.context);
+        }
+
+        if let Value::Object(loader_info) = content_loader_info(activation, Some(this), &[])? {
+            if let Some(loader_info) = loader_info.as_loader_info_object() {
+                loader_info.set_loader_stream(activation.context.gc_context, None);
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Loader.close`.
+pub fn close<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(container) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|dobj| dobj.as_container())
+    {
+        for child in container.iter_render_list() {
+            activation.context.load_manager.cancel_movie_loads(child);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `Loader`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.display"), "Loader"),
+        Some(
+            QName::new(
+                Namespace::package("flash.display"),
+                "DisplayObjectContainer",
+            )
+            .into(),
+        ),
+        Method::from_builtin(instance_init, "<Loader instance initializer>", mc),
+        Method::from_builtin(class_init, "<Loader class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[
+        ("content", Some(content), None),
+        ("contentLoaderInfo", Some(content_loader_info), None),
+    ];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("load", load),
+        ("loadBytes", load_bytes),
+        ("unload", unload),
+        ("close", close),
+    ];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "contentLoaderInfo"),
+        QName::new(Namespace::package("flash.display"), "LoaderInfo").into(),
+        None,
+    ));
+
+    class
+}
@@ -9,10 +9,13 @@ use crate::avm2::object::{Object, StageObject, TObject};
 use crate::avm2::traits::Trait;
 use crate::avm2::value::Value;
 use crate::avm2::Error;
+use crate::bounding_box::BoundingBox;
 use crate::display_object::{MovieClip, SoundTransform, TDisplayObject};
+use crate::player::{DragObject, Player};
 use crate::tag_utils::SwfMovie;
 use gc_arena::{GcCell, MutationContext};
 use std::sync::Arc;
+use swf::Twips;
 
 /// Implements `flash.display.Sprite`'s instance constructor.
 pub fn instance_init<'gc>(
@@ -115,6 +118,114 @@ pub fn set_sound_transform<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Implements `dropTarget`'s getter
+pub fn drop_target<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mc) = this
+        .and_then(|o| o.as_display_object())
+        .and_then(|dobj| dobj.as_movie_clip())
+    {
+        return Ok(mc
+            .drop_target()
+            .map(|drop_target| drop_target.object2())
+            .unwrap_or(Value::Null));
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `startDrag`.
+pub fn start_drag<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(display_object) = this.and_then(|o| o.as_display_object()) {
+        let lock_center = args
+            .get(0)
+            .unwrap_or(&Value::Bool(false))
+            .coerce_to_boolean();
+
+        let offset = if lock_center {
+            // The object's origin point is locked to the mouse.
+            Default::default()
+        } else {
+            // The object moves relative to current mouse position.
+            let (object_x, object_y) = display_object.local_to_global(Default::default());
+            let (mouse_x, mouse_y) = *activation.context.mouse_position;
+            (object_x - mouse_x, object_y - mouse_y)
+        };
+
+        let constraint = match args.get(1).unwrap_or(&Value::Null) {
+            Value::Null | Value::Undefined => Default::default(),
+            bounds => {
+                let bounds = bounds.coerce_to_object(activation)?;
+                let mut get = |name| -> Result<Twips, Error> {
+                    let value = bounds
+                        .get_property(
+                            bounds,
+                            &QName::new(Namespace::public(), name).into(),
+                            activation,
+                        )?
+                        .coerce_to_number(activation)?;
+                    Ok(Twips::from_pixels(if value.is_finite() {
+                        value
+                    } else {
+                        0.0
+                    }))
+                };
+                let x = get("x")?;
+                let y = get("y")?;
+                let width = get("width")?;
+                let height = get("height")?;
+
+                // Normalize the bounds.
+                let (x_min, x_max) = if width.get() < 0 {
+                    (x + width, x)
+                } else {
+                    (x, x + width)
+                };
+                let (y_min, y_max) = if height.get() < 0 {
+                    (y + height, y)
+                } else {
+                    (y, y + height)
+                };
+                BoundingBox {
+                    valid: true,
+                    x_min,
+                    y_min,
+                    x_max,
+                    y_max,
+                }
+            }
+        };
+
+        *activation.context.drag_object = Some(DragObject {
+            display_object,
+            offset,
+            constraint,
+        });
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `stopDrag`.
+pub fn stop_drag<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    // It doesn't matter which sprite we call this on; it simply stops any active drag.
+    Player::update_drop_target(&mut activation.context);
+    *activation.context.drag_object = None;
+
+    Ok(Value::Undefined)
+}
+
 /// Construct `Sprite`'s class.
 pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
     let class = Class::new(
@@ -146,9 +257,14 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
             Some(sound_transform),
             Some(set_sound_transform),
         ),
+        ("dropTarget", Some(drop_target), None),
     ];
     write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
 
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] =
+        &[("startDrag", start_drag), ("stopDrag", stop_drag)];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
     // Slot for lazy-initialized Graphics object.
     write.define_instance_trait(Trait::from_slot(
         QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "graphics"),
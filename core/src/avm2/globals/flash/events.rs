@@ -1,10 +1,16 @@
 //! `flash.events` namespace
 
 pub mod activityevent;
+pub mod errorevent;
 pub mod event;
 pub mod eventdispatcher;
 pub mod fullscreenevent;
+pub mod httpstatusevent;
 pub mod ieventdispatcher;
+pub mod ioerrorevent;
 pub mod keyboardevent;
 pub mod mouseevent;
 pub mod progressevent;
+pub mod sampledataevent;
+pub mod statusevent;
+pub mod textevent;
@@ -1,8 +1,9 @@
 use crate::avm2::activation::Activation;
 use crate::avm2::class::{Class, ClassAttributes};
-use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::method::Method;
 use crate::avm2::names::{Namespace, QName};
-use crate::avm2::object::Object;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
 use crate::avm2::value::Value;
 use crate::avm2::Error;
 use gc_arena::{GcCell, MutationContext};
@@ -13,8 +14,16 @@ pub fn instance_init<'gc>(
     this: Option<Object<'gc>>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    if let Some(this) = this {
-        activation.super_init(this, args)?; // Event uses the first three parameters
+    if let Some(mut this) = this {
+        activation.super_init(this, args.get(0..3).unwrap_or(args))?;
+
+        let activating = args.get(3).cloned().unwrap_or_else(|| false.into());
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "activating").into(),
+            activating,
+            activation,
+        )?;
     }
     Ok(Value::Undefined)
 }
@@ -28,26 +37,6 @@ pub fn class_init<'gc>(
     Ok(Value::Undefined)
 }
 
-pub fn activating<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
-    _this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
-) -> Result<Value<'gc>, Error> {
-    log::warn!("ActivityEvent.activating - not implemented");
-
-    Ok(Value::Undefined)
-}
-
-pub fn set_activating<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
-    _this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
-) -> Result<Value<'gc>, Error> {
-    log::warn!("ActivityEvent.set_activating - not implemented");
-
-    Ok(Value::Undefined)
-}
-
 /// Construct `ActivityEvent`'s class.
 pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
     let class = Class::new(
@@ -60,12 +49,11 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
 
     let mut write = class.write(mc);
 
-    const PUBLIC_INSTANCE_PROPERTIES: &[(
-        &str,
-        Option<NativeMethodImpl>,
-        Option<NativeMethodImpl>,
-    )] = &[("activating", Some(activating), Some(set_activating))];
-    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "activating"),
+        QName::new(Namespace::public(), "Boolean").into(),
+        None,
+    ));
 
     write.set_attributes(ClassAttributes::SEALED);
 
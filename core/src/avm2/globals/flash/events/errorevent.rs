@@ -0,0 +1,64 @@
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.events.ErrorEvent`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, args.get(0..4).unwrap_or(args))?;
+
+        let error_id = args.get(4).cloned().unwrap_or_else(|| 0.into());
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "errorID").into(),
+            error_id,
+            activation,
+        )?;
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.events.ErrorEvent`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `ErrorEvent`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.events"), "ErrorEvent"),
+        Some(QName::new(Namespace::package("flash.events"), "TextEvent").into()),
+        Method::from_builtin(instance_init, "<ErrorEvent instance initializer>", mc),
+        Method::from_builtin(class_init, "<ErrorEvent class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "errorID"),
+        QName::new(Namespace::public(), "int").into(),
+        None,
+    ));
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    const CONSTANTS: &[(&str, &str)] = &[("ERROR", "error")];
+    write.define_public_constant_string_class_traits(CONSTANTS);
+
+    class
+}
@@ -77,8 +77,9 @@ pub fn add_event_listener<'gc>(
             .cloned()
             .unwrap_or(Value::Integer(0))
             .coerce_to_i32(activation)?;
+        // `useWeakReference` is ignored: the garbage collector has no weak
+        // references, so every listener is held strongly.
 
-        //TODO: If we ever get weak GC references, we should respect `useWeakReference`.
         dispatch_list
             .as_dispatch_mut(activation.context.gc_context)
             .ok_or_else(|| Error::from("Internal properties should have what I put in them"))?
@@ -150,7 +151,7 @@ pub fn has_event_listener<'gc>(
             .coerce_to_string(activation)?;
 
         return Ok(dispatch_list
-            .as_dispatch_mut(activation.context.gc_context)
+            .as_dispatch()
             .ok_or_else(|| Error::from("Internal properties should have what I put in them"))?
             .has_event_listener(event_type)
             .into());
@@ -180,7 +181,7 @@ pub fn will_trigger<'gc>(
             .coerce_to_string(activation)?;
 
         if dispatch_list
-            .as_dispatch_mut(activation.context.gc_context)
+            .as_dispatch()
             .ok_or_else(|| Error::from("Internal properties should have what I put in them"))?
             .has_event_listener(event_type)
         {
@@ -0,0 +1,75 @@
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.events.SampleDataEvent`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, args.get(0..3).unwrap_or(args))?;
+
+        let position = args.get(3).cloned().unwrap_or_else(|| 0.into());
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "position").into(),
+            position,
+            activation,
+        )?;
+
+        let data = args.get(4).cloned().unwrap_or(Value::Null);
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "data").into(),
+            data,
+            activation,
+        )?;
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.events.SampleDataEvent`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `SampleDataEvent`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.events"), "SampleDataEvent"),
+        Some(QName::new(Namespace::package("flash.events"), "Event").into()),
+        Method::from_builtin(instance_init, "<SampleDataEvent instance initializer>", mc),
+        Method::from_builtin(class_init, "<SampleDataEvent class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    const PUBLIC_INSTANCE_SLOTS: &[(&str, Option<f64>)] = &[("position", None)];
+    write.define_public_slot_number_instance_traits(PUBLIC_INSTANCE_SLOTS);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "data"),
+        QName::new(Namespace::package("flash.utils"), "ByteArray").into(),
+        None,
+    ));
+
+    const CONSTANTS: &[(&str, &str)] = &[("SAMPLE_DATA", "sampleData")];
+    write.define_public_constant_string_class_traits(CONSTANTS);
+
+    class
+}
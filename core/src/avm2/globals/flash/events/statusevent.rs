@@ -0,0 +1,77 @@
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.events.StatusEvent`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, args.get(0..3).unwrap_or(args))?;
+
+        let code = args.get(3).cloned().unwrap_or_else(|| "".into());
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "code").into(),
+            code,
+            activation,
+        )?;
+
+        let level = args.get(4).cloned().unwrap_or_else(|| "".into());
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "level").into(),
+            level,
+            activation,
+        )?;
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.events.StatusEvent`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `StatusEvent`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.events"), "StatusEvent"),
+        Some(QName::new(Namespace::package("flash.events"), "Event").into()),
+        Method::from_builtin(instance_init, "<StatusEvent instance initializer>", mc),
+        Method::from_builtin(class_init, "<StatusEvent class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "code"),
+        QName::new(Namespace::public(), "String").into(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "level"),
+        QName::new(Namespace::public(), "String").into(),
+        None,
+    ));
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    const CONSTANTS: &[(&str, &str)] = &[("STATUS", "status")];
+    write.define_public_constant_string_class_traits(CONSTANTS);
+
+    class
+}
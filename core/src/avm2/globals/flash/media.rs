@@ -1,5 +1,6 @@
 //! `flash.media` namespace
 
+pub mod microphone;
 pub mod sound;
 pub mod soundchannel;
 pub mod soundmixer;
@@ -0,0 +1,301 @@
+//! `flash.media.Microphone` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::array::ArrayStorage;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{ArrayObject, Object, TObject};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::microphone::Microphone;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.media.Microphone`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.media.Microphone`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `Microphone.getMicrophone`
+pub fn get_microphone<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let index = args
+        .get(0)
+        .cloned()
+        .unwrap_or_else(|| (-1).into())
+        .coerce_to_i32(activation)?;
+    if index > 0 {
+        return Ok(Value::Null);
+    }
+
+    let microphone = match activation.context.microphone.avm2_object() {
+        Some(microphone) => microphone,
+        None => {
+            let microphone_class = activation.avm2().classes().microphone;
+            let microphone = microphone_class.construct(activation, &[])?;
+            activation.context.microphone.set_avm2_object(microphone);
+            microphone
+        }
+    };
+
+    Microphone::start(&mut activation.context);
+
+    Ok(microphone.into())
+}
+
+/// Implements `Microphone.names`
+pub fn names<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let storage = ArrayStorage::from_args(&[Microphone::NAME.into()]);
+    Ok(ArrayObject::from_storage(activation, storage)?.into())
+}
+
+/// Implements `Microphone.isSupported`
+pub fn is_supported<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(true.into())
+}
+
+/// Implements `Microphone.activityLevel`
+pub fn activity_level<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(activation.context.microphone.activity_level().into())
+}
+
+/// Implements `Microphone.gain`'s getter
+pub fn gain<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(activation.context.microphone.gain().into())
+}
+
+/// Implements `Microphone.gain`'s setter
+pub fn set_gain<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let gain = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_number(activation)?;
+    activation.context.microphone.set_gain(gain);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Microphone.rate`'s getter
+pub fn rate<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(activation.context.microphone.rate().into())
+}
+
+/// Implements `Microphone.rate`'s setter
+pub fn set_rate<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let rate = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_u32(activation)?;
+    Microphone::set_rate(&mut activation.context, rate);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Microphone.index`
+pub fn index<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(0.into())
+}
+
+/// Implements `Microphone.muted`
+pub fn muted<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(activation.context.microphone.muted().into())
+}
+
+/// Implements `Microphone.name`
+pub fn name<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Microphone::NAME.into())
+}
+
+/// Implements `Microphone.silenceLevel`
+pub fn silence_level<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(activation.context.microphone.silence_level().into())
+}
+
+/// Implements `Microphone.silenceTimeout`
+pub fn silence_timeout<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(activation.context.microphone.silence_timeout().into())
+}
+
+/// Implements `Microphone.useEchoSuppression`
+pub fn use_echo_suppression<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(activation.context.microphone.use_echo_suppression().into())
+}
+
+/// Implements `Microphone.setSilenceLevel`
+pub fn set_silence_level<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let silence_level = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_number(activation)?;
+    let silence_timeout = args
+        .get(1)
+        .cloned()
+        .unwrap_or_else(|| (-1).into())
+        .coerce_to_i32(activation)?;
+    activation.context.microphone.set_silence_level(
+        silence_level,
+        Some(silence_timeout).filter(|timeout| *timeout >= 0),
+    );
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Microphone.setUseEchoSuppression`
+pub fn set_use_echo_suppression<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let use_echo_suppression = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_boolean();
+    activation
+        .context
+        .microphone
+        .set_use_echo_suppression(use_echo_suppression);
+
+    Ok(Value::Undefined)
+}
+
+/// Stubs `Microphone.setLoopBack`
+pub fn set_loop_back<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    log::warn!("Microphone.setLoopBack - not implemented");
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `Microphone`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.media"), "Microphone"),
+        Some(QName::new(Namespace::package("flash.events"), "EventDispatcher").into()),
+        Method::from_builtin(instance_init, "<Microphone instance initializer>", mc),
+        Method::from_builtin(class_init, "<Microphone class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED | ClassAttributes::FINAL);
+
+    const PUBLIC_CLASS_PROPERTIES: &[(&str, Option<NativeMethodImpl>, Option<NativeMethodImpl>)] =
+        &[
+            ("names", Some(names), None),
+            ("isSupported", Some(is_supported), None),
+        ];
+    write.define_public_builtin_class_properties(mc, PUBLIC_CLASS_PROPERTIES);
+
+    const PUBLIC_CLASS_METHODS: &[(&str, NativeMethodImpl)] = &[("getMicrophone", get_microphone)];
+    write.define_public_builtin_class_methods(mc, PUBLIC_CLASS_METHODS);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[
+        ("activityLevel", Some(activity_level), None),
+        ("gain", Some(gain), Some(set_gain)),
+        ("index", Some(index), None),
+        ("muted", Some(muted), None),
+        ("name", Some(name), None),
+        ("rate", Some(rate), Some(set_rate)),
+        ("silenceLevel", Some(silence_level), None),
+        ("silenceTimeout", Some(silence_timeout), None),
+        ("useEchoSuppression", Some(use_echo_suppression), None),
+    ];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("setSilenceLevel", set_silence_level),
+        ("setUseEchoSuppression", set_use_echo_suppression),
+        ("setLoopBack", set_loop_back),
+    ];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    class
+}
@@ -114,7 +114,19 @@ pub fn play<'gc>(
     this: Option<Object<'gc>>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    if let Some(sound) = this.and_then(|this| this.as_sound()) {
+    let this = match this {
+        Some(this) => this,
+        None => return Ok(Value::Null),
+    };
+
+    let sound_transform = args
+        .get(2)
+        .cloned()
+        .unwrap_or(Value::Null)
+        .coerce_to_object(activation)
+        .ok();
+
+    let instance = if let Some(sound) = this.as_sound() {
         let position = args
             .get(0)
             .cloned()
@@ -125,12 +137,6 @@ pub fn play<'gc>(
             .cloned()
             .unwrap_or_else(|| 0.into())
             .coerce_to_i32(activation)?;
-        let sound_transform = args
-            .get(2)
-            .cloned()
-            .unwrap_or(Value::Null)
-            .coerce_to_object(activation)
-            .ok();
 
         if let Some(duration) = activation.context.audio.get_sound_duration(sound) {
             if position > duration {
@@ -152,35 +158,99 @@ pub fn play<'gc>(
             envelope: None,
         };
 
-        if let Some(instance) = activation
+        activation
             .context
             .start_sound(sound, &sound_info, None, None)
-        {
-            if let Some(sound_transform) = sound_transform {
-                let st = SoundTransform::from_avm2_object(activation, sound_transform)?;
-                activation.context.set_local_sound_transform(instance, st);
-            }
+    } else {
+        // A sound with no data of its own asks its `sampleData` listeners to
+        // generate samples while it plays.
+        activation.context.start_dynamic_sound(this)
+    };
+
+    if let Some(instance) = instance {
+        if let Some(sound_transform) = sound_transform {
+            let st = SoundTransform::from_avm2_object(activation, sound_transform)?;
+            activation.context.set_local_sound_transform(instance, st);
+        }
 
-            let sound_channel = SoundChannelObject::from_sound_instance(activation, instance)?;
+        let sound_channel = SoundChannelObject::from_sound_instance(activation, instance)?;
 
-            activation
-                .context
-                .attach_avm2_sound_channel(instance, sound_channel);
+        activation
+            .context
+            .attach_avm2_sound_channel(instance, sound_channel);
 
-            return Ok(sound_channel.into());
-        }
+        return Ok(sound_channel.into());
     }
 
     Ok(Value::Null)
 }
 
-/// Stubs `Sound.extract`
+/// Implements `Sound.extract`
 pub fn extract<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
-    _this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    Err("Sound.extract is a stub.".into())
+    let this = match this.and_then(|this| this.as_sound_object()) {
+        Some(this) => this,
+        None => return Ok(0.into()),
+    };
+    let sound = match Object::from(this).as_sound() {
+        Some(sound) => sound,
+        None => return Ok(0.into()),
+    };
+
+    let target = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_object(activation)?;
+    let length = args
+        .get(1)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_number(activation)?;
+    let start_position = args
+        .get(2)
+        .cloned()
+        .unwrap_or_else(|| (-1).into())
+        .coerce_to_number(activation)?;
+
+    let position = if start_position < 0.0 {
+        this.extract_position()
+    } else {
+        start_position as u32
+    };
+    let num_sample_frames = if length > 0.0 { length as u32 } else { 0 };
+
+    let samples = match activation
+        .context
+        .audio
+        .extract_sound(sound, position, num_sample_frames)
+    {
+        Ok(samples) => samples,
+        Err(e) => {
+            log::warn!("Sound.extract: {}", e);
+            return Ok(0.into());
+        }
+    };
+
+    let mut bytearray = target
+        .as_bytearray_mut(activation.context.gc_context)
+        .ok_or("Sound.extract: target is not a ByteArray")?;
+    for sample in &samples {
+        for channel in sample {
+            bytearray.write_float(f32::from(*channel) / 32768.0)?;
+        }
+    }
+    drop(bytearray);
+
+    this.set_extract_position(
+        activation.context.gc_context,
+        position + samples.len() as u32,
+    );
+
+    Ok((samples.len() as u32).into())
 }
 
 /// Stubs `Sound.close`
@@ -52,12 +52,18 @@ pub fn right_peak<'gc>(
 
 /// Impl `SoundChannel.position`
 pub fn position<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc, '_>,
     this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
     if let Some(instance) = this.and_then(|this| this.as_sound_channel()) {
-        return Ok(instance.position().into());
+        // Ask the audio backend for the exact position of a playing sound, rather than
+        // the position recorded at the start of the frame.
+        let position = instance
+            .instance()
+            .and_then(|sound| activation.context.audio.get_sound_position(sound))
+            .unwrap_or_else(|| instance.position());
+        return Ok(position.into());
     }
     Ok(Value::Undefined)
 }
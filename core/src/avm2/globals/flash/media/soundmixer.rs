@@ -116,13 +116,126 @@ pub fn are_sounds_inaccessible<'gc>(
     Err("SoundMixer.areSoundsInaccessible is a stub".into())
 }
 
-/// Stub `SoundMixer.computeSpectrum`
+/// The number of values that `SoundMixer.computeSpectrum` writes for each channel.
+const SPECTRUM_LEN: usize = 256;
+
+/// Implements `SoundMixer.computeSpectrum`
 pub fn compute_spectrum<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc, '_>,
     _this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    Err("SoundMixer.computeSpectrum is a stub".into())
+    let output_array = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_object(activation)?;
+    let fft_mode = args
+        .get(1)
+        .cloned()
+        .unwrap_or_else(|| false.into())
+        .coerce_to_boolean();
+    let stretch_factor = args
+        .get(2)
+        .cloned()
+        .unwrap_or_else(|| 0.into())
+        .coerce_to_i32(activation)?;
+
+    // A stretch factor of 1 samples at 22KHz, 2 at 11KHz, and so on.
+    let step = stretch_factor.clamp(0, 16) as usize + 1;
+    // The FFT needs twice as many samples as it produces frequencies.
+    let num_samples = if fft_mode {
+        SPECTRUM_LEN * 2
+    } else {
+        SPECTRUM_LEN
+    };
+    let recent_output = activation
+        .context
+        .audio
+        .get_recent_output(num_samples * step);
+
+    // Pad the start with silence if not enough audio has been played yet.
+    let mut channels = [vec![0.0; num_samples], vec![0.0; num_samples]];
+    let frames = recent_output.iter().rev().step_by(step).take(num_samples);
+    for (i, frame) in frames.enumerate() {
+        channels[0][num_samples - 1 - i] = frame[0];
+        channels[1][num_samples - 1 - i] = frame[1];
+    }
+
+    if fft_mode {
+        for channel in &mut channels {
+            *channel = frequency_spectrum(channel);
+        }
+    }
+
+    let mut bytearray = output_array
+        .as_bytearray_mut(activation.context.gc_context)
+        .ok_or("SoundMixer.computeSpectrum: outputArray is not a ByteArray")?;
+    bytearray.clear();
+    for channel in &channels {
+        for value in channel {
+            bytearray.write_float(*value)?;
+        }
+    }
+    bytearray.set_position(0);
+
+    Ok(Value::Undefined)
+}
+
+/// Computes the magnitudes of the lower half of the frequencies in `samples`, whose length
+/// must be a power of two, using a radix-2 FFT.
+fn frequency_spectrum(samples: &[f32]) -> Vec<f32> {
+    let len = samples.len();
+    let num_bits = len.trailing_zeros();
+
+    // Copy the samples in bit-reversed order.
+    let mut values: Vec<(f32, f32)> = (0..len)
+        .map(|i| (samples[i.reverse_bits() >> (usize::BITS - num_bits)], 0.0))
+        .collect();
+
+    let mut size = 2;
+    while size <= len {
+        let angle = -2.0 * std::f32::consts::PI / size as f32;
+        for start in (0..len).step_by(size) {
+            for k in 0..size / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let (even_re, even_im) = values[start + k];
+                let (odd_re, odd_im) = values[start + k + size / 2];
+                let re = odd_re * cos - odd_im * sin;
+                let im = odd_re * sin + odd_im * cos;
+                values[start + k] = (even_re + re, even_im + im);
+                values[start + k + size / 2] = (even_re - re, even_im - im);
+            }
+        }
+        size *= 2;
+    }
+
+    // Scale so that a full-scale sine wave has a magnitude of 1.
+    let scale = 2.0 / len as f32;
+    values[..len / 2]
+        .iter()
+        .map(|(re, im)| ((re * re + im * im).sqrt() * scale).min(1.0))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sine_spectrum() {
+        let samples: Vec<f32> = (0..512)
+            .map(|i| (2.0 * std::f32::consts::PI * 32.0 * i as f32 / 512.0).sin())
+            .collect();
+        let spectrum = frequency_spectrum(&samples);
+        assert_eq!(spectrum.len(), 256);
+        assert!((spectrum[32] - 1.0).abs() < 0.001);
+        for (i, magnitude) in spectrum.iter().enumerate() {
+            if i != 32 {
+                assert!(*magnitude < 0.001);
+            }
+        }
+    }
 }
 
 /// Construct `SoundMixer`'s class.
@@ -2,11 +2,12 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::class::{Class, ClassAttributes};
-use crate::avm2::method::Method;
+use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::names::{Namespace, QName};
-use crate::avm2::object::Object;
+use crate::avm2::object::{Object, TObject};
 use crate::avm2::value::Value;
 use crate::avm2::Error;
+use crate::display_object::TDisplayObject;
 use gc_arena::{GcCell, MutationContext};
 
 /// Implements `flash.media.Video`'s instance constructor.
@@ -31,6 +32,39 @@ pub fn class_init<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Implements `Video.smoothing`'s getter.
+pub fn smoothing<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(video) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|dobj| dobj.as_video())
+    {
+        return Ok(video.smoothing().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Video.smoothing`'s setter.
+pub fn set_smoothing<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(video) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|dobj| dobj.as_video())
+    {
+        let smoothing = args.get(0).unwrap_or(&Value::Undefined).coerce_to_boolean();
+        video.set_smoothing(activation.context.gc_context, smoothing);
+    }
+
+    Ok(Value::Undefined)
+}
+
 /// Construct `Video`'s class.
 pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
     let class = Class::new(
@@ -45,5 +79,12 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
 
     write.set_attributes(ClassAttributes::SEALED);
 
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[("smoothing", Some(smoothing), Some(set_smoothing))];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
     class
 }
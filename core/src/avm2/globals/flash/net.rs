@@ -1,4 +1,59 @@
 //! `flash.net` namespace
 
+use crate::avm2::object::TObject;
+use crate::avm2::{Activation, Error, Object, Value};
+
 pub mod object_encoding;
 pub mod sharedobject;
+pub mod urlloader;
+pub mod urlloaderdataformat;
+pub mod urlrequest;
+pub mod urlrequestheader;
+pub mod urlrequestmethod;
+pub mod urlvariables;
+
+/// Implements `flash.net.registerClassAlias`
+pub fn register_class_alias<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let alias = match args.get(0).cloned().unwrap_or(Value::Undefined) {
+        Value::Undefined | Value::Null => {
+            return Err("TypeError: Error #2007: Parameter aliasName must be non-null.".into())
+        }
+        alias => alias.coerce_to_string(activation)?,
+    };
+    let class = match args.get(1).cloned().unwrap_or(Value::Undefined) {
+        Value::Object(object) => object.as_class_object(),
+        _ => None,
+    }
+    .ok_or("TypeError: Error #2007: Parameter classObject must be non-null.")?;
+
+    activation.context.avm2.register_class_alias(alias, class);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.net.getClassByAlias`
+pub fn get_class_by_alias<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let alias = match args.get(0).cloned().unwrap_or(Value::Undefined) {
+        Value::Undefined | Value::Null => {
+            return Err("TypeError: Error #2007: Parameter aliasName must be non-null.".into())
+        }
+        alias => alias.coerce_to_string(activation)?,
+    };
+
+    match activation.avm2().class_by_alias(alias) {
+        Some(class) => Ok(class.into()),
+        None => Err(format!(
+            "ReferenceError: Error #1014: Class {} could not be found.",
+            alias
+        )
+        .into()),
+    }
+}
@@ -1,10 +1,15 @@
 //! `flash.net.SharedObject` builtin/prototype
 
+use crate::avm1::globals::shared_object::local_shared_object_name;
+use crate::avm2::bytearray::ByteArrayStorage;
 use crate::avm2::class::{Class, ClassAttributes};
 use crate::avm2::method::{Method, NativeMethodImpl};
-use crate::avm2::object::TObject;
+use crate::avm2::object::{ByteArrayObject, TObject};
 use crate::avm2::traits::Trait;
-use crate::avm2::{Activation, Error, Namespace, Object, QName, Value};
+use crate::avm2::{Activation, ArrayObject, ArrayStorage, Error, Namespace, Object, QName, Value};
+use crate::string::AvmString;
+use flash_lso::types::Value as AmfValue;
+use flash_lso::types::{AMFVersion, Element, Lso};
 use gc_arena::{GcCell, MutationContext};
 
 fn instance_init<'gc>(
@@ -40,24 +45,300 @@ fn class_init<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Serialize a Value to an AMF3 value.
+///
+/// Returns `None` for values that aren't stored, such as functions.
+fn serialize_value<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<Option<AmfValue>, Error> {
+    // AMF3 integers are 29 bits wide.
+    const INTEGER_RANGE: std::ops::RangeInclusive<i64> = -(1 << 28)..=(1 << 28) - 1;
+
+    Ok(match value {
+        Value::Undefined => Some(AmfValue::Undefined),
+        Value::Null => Some(AmfValue::Null),
+        Value::Bool(b) => Some(AmfValue::Bool(b)),
+        Value::Number(f) => Some(AmfValue::Number(f)),
+        Value::Integer(i) if INTEGER_RANGE.contains(&(i as i64)) => Some(AmfValue::Integer(i)),
+        Value::Unsigned(u) if INTEGER_RANGE.contains(&(u as i64)) => {
+            Some(AmfValue::Integer(u as i32))
+        }
+        Value::Integer(_) | Value::Unsigned(_) => {
+            Some(AmfValue::Number(value.coerce_to_number(activation)?))
+        }
+        Value::String(s) => Some(AmfValue::String(s.to_string())),
+        Value::Object(o) => {
+            if o.as_executable().is_some() {
+                None
+            } else if let Some(bytes) = o.as_bytearray() {
+                Some(AmfValue::ByteArray(bytes.bytes().to_vec()))
+            } else if let Some(date) = o.as_date_object() {
+                date.date_time()
+                    .map(|date_time| AmfValue::Date(date_time.timestamp_millis() as f64, None))
+            } else if let Some(length) = o.as_array_storage().map(|array| array.length()) {
+                let mut values = Vec::new();
+                for i in 0..length {
+                    let value = o
+                        .as_array_storage()
+                        .and_then(|array| array.get(i))
+                        .unwrap_or(Value::Undefined);
+                    if let Some(value) = serialize_value(activation, value)? {
+                        values.push(Element::new(i.to_string(), value));
+                    }
+                }
+                Some(AmfValue::ECMAArray(vec![], values, length as u32))
+            } else {
+                Some(AmfValue::Object(serialize_object(activation, o)?, None))
+            }
+        }
+    })
+}
+
+/// Serialize the enumerable properties of an object.
+fn serialize_object<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<Vec<Element>, Error> {
+    let mut elements = Vec::new();
+    let mut index = 0;
+    while let Some(next) = object.get_next_enumerant(index, activation)? {
+        // An enumerant of 0 also ends the enumeration.
+        if next == 0 {
+            break;
+        }
+        index = next;
+
+        let name = object
+            .get_enumerant_name(index, activation)?
+            .coerce_to_string(activation)?;
+        let value = object.get_enumerant_value(index, activation)?;
+        if let Some(value) = serialize_value(activation, value)? {
+            elements.push(Element::new(name.to_utf8_lossy(), value));
+        }
+    }
+    Ok(elements)
+}
+
+/// Deserialize an AMF value to a Value.
+fn deserialize_value<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: &AmfValue,
+) -> Result<Value<'gc>, Error> {
+    Ok(match value {
+        AmfValue::Null => Value::Null,
+        AmfValue::Undefined => Value::Undefined,
+        AmfValue::Bool(b) => (*b).into(),
+        AmfValue::Number(f) => (*f).into(),
+        AmfValue::Integer(i) => (*i).into(),
+        AmfValue::String(s) => AvmString::new_utf8(activation.context.gc_context, s).into(),
+        AmfValue::ByteArray(bytes) => {
+            ByteArrayObject::from_storage(activation, ByteArrayStorage::from_vec(bytes.clone()))?
+                .into()
+        }
+        AmfValue::Date(time, _) => {
+            let date_class = activation.avm2().classes().date;
+            date_class.construct(activation, &[(*time).into()])?.into()
+        }
+        AmfValue::ECMAArray(_, elements, length) => {
+            let array = ArrayObject::from_storage(activation, ArrayStorage::new(*length as usize))?;
+            deserialize_elements(activation, array, elements)?;
+            array.into()
+        }
+        AmfValue::Object(elements, _) => {
+            let object = activation
+                .avm2()
+                .classes()
+                .object
+                .construct(activation, &[])?;
+            deserialize_elements(activation, object, elements)?;
+            object.into()
+        }
+        _ => Value::Undefined,
+    })
+}
+
+/// Deserialize AMF elements into properties of `object`.
+fn deserialize_elements<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    mut object: Object<'gc>,
+    elements: &[Element],
+) -> Result<(), Error> {
+    for element in elements {
+        let value = deserialize_value(activation, element.value())?;
+        let name = AvmString::new_utf8(activation.context.gc_context, &element.name);
+        object.set_property(object, &QName::dynamic_name(name).into(), value, activation)?;
+    }
+    Ok(())
+}
+
+fn data<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+) -> Result<Object<'gc>, Error> {
+    this.get_property(
+        this,
+        &QName::new(Namespace::public(), "data").into(),
+        activation,
+    )?
+    .coerce_to_object(activation)
+}
+
+/// Serialize the `data` of a shared object into the contents of a `.sol` file.
+fn serialize_lso<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    name: &str,
+) -> Result<Vec<u8>, Error> {
+    let data = data(activation, this)?;
+    let elements = serialize_object(activation, data)?;
+    let mut lso = Lso::new(
+        elements,
+        name.split('/').last().unwrap_or("<unknown>"),
+        AMFVersion::AMF3,
+    );
+    Ok(flash_lso::write::write_to_bytes(&mut lso).unwrap_or_default())
+}
+
 fn get_local<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     _this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    log::warn!("SharedObject.getLocal not implemented");
+    let name = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+    let local_path = match args.get(1) {
+        Some(Value::String(local_path)) => Some(local_path.to_utf8_lossy().into_owned()),
+        _ => None,
+    };
+    let secure = args
+        .get(2)
+        .cloned()
+        .unwrap_or(Value::Bool(false))
+        .coerce_to_boolean();
+
+    let full_name = match local_shared_object_name(
+        activation.context.swf.url(),
+        &name.to_utf8_lossy(),
+        local_path.as_deref(),
+        secure,
+    ) {
+        Some(full_name) => full_name,
+        None => return Err("Error #2134: Cannot create SharedObject.".into()),
+    };
+
+    // Check if this is referencing an existing shared object
+    if let Some(so) = activation.context.avm2.shared_object(&full_name) {
+        return Ok(so.into());
+    }
+
     let class = activation.context.avm2.classes().sharedobject;
     let new_shared_object = class.construct(activation, &[])?;
 
+    // Load the data object from storage if it existed prior
+    if let Some(saved) = activation.context.storage.get(&full_name) {
+        if let Ok(lso) = flash_lso::read::Reader::default().parse(&saved) {
+            let data = data(activation, new_shared_object)?;
+            deserialize_elements(activation, data, &lso.body)?;
+        }
+    }
+
+    activation
+        .context
+        .avm2
+        .add_shared_object(full_name, new_shared_object);
+
     Ok(new_shared_object.into())
 }
 
+/// Write a shared object to storage, if it fits within the storage's size limit.
+pub fn write_to_storage<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    min_disk_space: usize,
+) -> Result<bool, Error> {
+    let name = match activation.context.avm2.shared_object_name(this) {
+        Some(name) => name.to_string(),
+        None => return Ok(false),
+    };
+
+    let bytes = serialize_lso(activation, this, &name)?;
+    let fits = activation
+        .context
+        .storage
+        .size_limit(&name)
+        .map_or(true, |limit| bytes.len().max(min_disk_space) <= limit);
+    Ok(fits && activation.context.storage.put(&name, &bytes))
+}
+
 fn flush<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
-    _this: Option<Object<'gc>>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let min_disk_space = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Integer(0))
+            .coerce_to_i32(activation)?
+            .max(0) as usize;
+
+        if !write_to_storage(activation, this, min_disk_space)? {
+            return Err("Error #2130: Unable to flush SharedObject.".into());
+        }
+    }
+
+    Ok("flushed".into())
+}
+
+fn clear<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        let data = activation
+            .context
+            .avm2
+            .classes()
+            .object
+            .construct(activation, &[])?;
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "data").into(),
+            data.into(),
+            activation,
+        )?;
+
+        if let Some(name) = activation.context.avm2.shared_object_name(this) {
+            let name = name.to_string();
+            activation.context.storage.remove_key(&name);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+fn size<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    log::warn!("SharedObject.flush not implemented");
+    if let Some(this) = this {
+        let name = activation
+            .context
+            .avm2
+            .shared_object_name(this)
+            .unwrap_or_default()
+            .to_string();
+        let bytes = serialize_lso(activation, this, &name)?;
+        return Ok(bytes.len().into());
+    }
+
     Ok(Value::Undefined)
 }
 
@@ -80,10 +361,18 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         None,
     ));
 
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[("size", Some(size), None)];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
     const PUBLIC_CLASS_METHODS: &[(&str, NativeMethodImpl)] = &[("getLocal", get_local)];
     write.define_public_builtin_class_methods(mc, PUBLIC_CLASS_METHODS);
 
-    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[("flush", flush)];
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] =
+        &[("flush", flush), ("clear", clear)];
     write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
     class
 }
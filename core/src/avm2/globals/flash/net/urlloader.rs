@@ -0,0 +1,272 @@
+//! `flash.net.URLLoader` builtin
+
+use crate::avm2::activation::Activation;
+use crate::avm2::bytearray::ByteArrayStorage;
+use crate::avm2::class::Class;
+use crate::avm2::globals::flash::net::urlrequest::url_and_request_options;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Multiname, Namespace, QName};
+use crate::avm2::object::{ByteArrayObject, Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::string::AvmString;
+use encoding_rs::UTF_8;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.net.URLLoader`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "dataFormat").into(),
+            "text".into(),
+            activation,
+        )?;
+        set_progress(activation, this, 0, 0)?;
+
+        if let Some(Value::Object(request)) = args.get(0) {
+            load(activation, Some(this), &[(*request).into()])?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.net.URLLoader`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Update the `bytesLoaded` and `bytesTotal` of a `URLLoader`.
+pub fn set_progress<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    mut this: Object<'gc>,
+    bytes_loaded: usize,
+    bytes_total: usize,
+) -> Result<(), Error> {
+    this.set_property(
+        this,
+        &QName::new(Namespace::public(), "bytesLoaded").into(),
+        bytes_loaded.into(),
+        activation,
+    )?;
+    this.set_property(
+        this,
+        &QName::new(Namespace::public(), "bytesTotal").into(),
+        bytes_total.into(),
+        activation,
+    )
+}
+
+/// Store the response of a completed load in a `URLLoader`'s `data`,
+/// converted according to its `dataFormat`.
+pub fn set_loaded_data<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    mut this: Object<'gc>,
+    data: Vec<u8>,
+) -> Result<(), Error> {
+    let length = data.len();
+    let data_format = this
+        .get_property(
+            this,
+            &QName::new(Namespace::public(), "dataFormat").into(),
+            activation,
+        )?
+        .coerce_to_string(activation)?;
+
+    let value: Value<'gc> = if &data_format == b"binary" {
+        ByteArrayObject::from_storage(activation, ByteArrayStorage::from_vec(data))?.into()
+    } else {
+        let text = AvmString::new_utf8(activation.context.gc_context, UTF_8.decode(&data).0);
+        if &data_format == b"variables" {
+            let urlvariables = activation.avm2().classes().urlvariables;
+            urlvariables.construct(activation, &[text.into()])?.into()
+        } else {
+            text.into()
+        }
+    };
+
+    this.set_property(
+        this,
+        &QName::new(Namespace::public(), "data").into(),
+        value,
+        activation,
+    )?;
+    set_progress(activation, this, length, length)
+}
+
+/// Implements `URLLoader.load`.
+pub fn load<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let request = match args.get(0) {
+            Some(Value::Object(request)) => *request,
+            _ => return Err("TypeError: Error #2007: Parameter request must be non-null.".into()),
+        };
+
+        let (url, request_options) = url_and_request_options(activation, request)?;
+
+        // Starting a new load stops the one in progress.
+        activation.context.load_manager.cancel_url_loads(this);
+        set_progress(activation, this, 0, 0)?;
+
+        let fetch = activation.context.navigator.fetch(&url, request_options);
+        let process = activation.context.load_manager.load_data_into_url_loader(
+            activation.context.player.clone().unwrap(),
+            this,
+            fetch,
+            url,
+        );
+        activation.context.navigator.spawn_future(process);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `URLLoader.close`.
+pub fn close<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.context.load_manager.cancel_url_loads(this);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `URLLoader`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.net"), "URLLoader"),
+        Some(QName::new(Namespace::package("flash.events"), "EventDispatcher").into()),
+        Method::from_builtin(instance_init, "<URLLoader instance initializer>", mc),
+        Method::from_builtin(class_init, "<URLLoader class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "data"),
+        Multiname::any(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "dataFormat"),
+        QName::new(Namespace::public(), "String").into(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "bytesLoaded"),
+        QName::new(Namespace::public(), "uint").into(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "bytesTotal"),
+        QName::new(Namespace::public(), "uint").into(),
+        None,
+    ));
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] =
+        &[("load", load), ("close", close)];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    class
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{close, load};
+    use crate::avm2::activation::Activation;
+    use crate::avm2::names::{Namespace, QName};
+    use crate::avm2::object::{Object, TObject};
+    use crate::avm2::test_utils::with_avm2;
+    use crate::avm2::value::Value;
+    use crate::avm2::Error;
+    use std::sync::Weak;
+
+    fn construct<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        name: &'static str,
+        args: &[Value<'gc>],
+    ) -> Result<Object<'gc>, Error> {
+        activation
+            .avm2()
+            .global_domain()
+            .get_defined_value(
+                activation,
+                QName::new(Namespace::package("flash.net"), name),
+            )?
+            .coerce_to_object(activation)?
+            .construct(activation, args)
+    }
+
+    /// Tests that loading again replaces the load in progress, and that
+    /// closing the loader stops it.
+    #[test]
+    fn load_replaces_load_in_progress() {
+        with_avm2(|activation| {
+            // The player is never upgraded, as the loads are never run.
+            activation.context.player = Some(Weak::new());
+
+            let loader = construct(activation, "URLLoader", &[])?;
+            let first = construct(activation, "URLRequest", &["first.txt".into()])?;
+            let second = construct(activation, "URLRequest", &["second.txt".into()])?;
+
+            load(activation, Some(loader), &[first.into()])?;
+            assert_eq!(activation.context.load_manager.url_load_count(loader), 1);
+
+            load(activation, Some(loader), &[second.into()])?;
+            assert_eq!(activation.context.load_manager.url_load_count(loader), 1);
+
+            close(activation, Some(loader), &[])?;
+            assert_eq!(activation.context.load_manager.url_load_count(loader), 0);
+
+            Ok(())
+        });
+    }
+
+    /// Tests that loads of different loaders don't replace each other.
+    #[test]
+    fn loads_of_other_loaders_are_kept() {
+        with_avm2(|activation| {
+            activation.context.player = Some(Weak::new());
+
+            let first_loader = construct(activation, "URLLoader", &[])?;
+            let second_loader = construct(activation, "URLLoader", &[])?;
+            let request = construct(activation, "URLRequest", &["data.txt".into()])?;
+
+            load(activation, Some(first_loader), &[request.into()])?;
+            load(activation, Some(second_loader), &[request.into()])?;
+            assert_eq!(
+                activation.context.load_manager.url_load_count(first_loader),
+                1
+            );
+            assert_eq!(
+                activation
+                    .context
+                    .load_manager
+                    .url_load_count(second_loader),
+                1
+            );
+
+            Ok(())
+        });
+    }
+}
@@ -0,0 +1,178 @@
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Multiname, Namespace, QName};
+use crate::avm2::object::{ArrayObject, Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::backend::navigator::RequestOptions;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.net.URLRequest`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        let url = args.get(0).cloned().unwrap_or(Value::Null);
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "url").into(),
+            url,
+            activation,
+        )?;
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "method").into(),
+            "GET".into(),
+            activation,
+        )?;
+
+        let request_headers = ArrayObject::empty(activation)?;
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "requestHeaders").into(),
+            request_headers.into(),
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.net.URLRequest`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Read a public property of a request object.
+fn request_property<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    request: Object<'gc>,
+    name: &'static str,
+) -> Result<Value<'gc>, Error> {
+    request.get_property(
+        request,
+        &QName::new(Namespace::public(), name).into(),
+        activation,
+    )
+}
+
+/// Build the URL to fetch and the options to fetch it with from a
+/// `URLRequest`.
+///
+/// The request's `data` is appended to the query string of `GET` requests,
+/// and sent as the body of `POST` requests.
+pub fn url_and_request_options<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    request: Object<'gc>,
+) -> Result<(String, RequestOptions), Error> {
+    let mut url = match request_property(activation, request, "url")? {
+        Value::Undefined | Value::Null => {
+            return Err("TypeError: Error #2007: Parameter url must be non-null.".into())
+        }
+        url => url.coerce_to_string(activation)?.to_string(),
+    };
+
+    let is_post = request_property(activation, request, "method")?
+        .coerce_to_string(activation)?
+        .to_string()
+        .eq_ignore_ascii_case("POST");
+
+    let data = match request_property(activation, request, "data")? {
+        Value::Undefined | Value::Null => None,
+        Value::Object(object) if object.as_bytearray().is_some() => object
+            .as_bytearray()
+            .map(|bytearray| bytearray.bytes().to_vec()),
+        data => Some(data.coerce_to_string(activation)?.to_string().into_bytes()),
+    };
+
+    let mut headers = Vec::new();
+    if let Value::Object(request_headers) = request_property(activation, request, "requestHeaders")?
+    {
+        let length = request_headers
+            .as_array_storage()
+            .map(|storage| storage.length())
+            .unwrap_or(0);
+        for i in 0..length {
+            let header = request_headers
+                .as_array_storage()
+                .and_then(|storage| storage.get(i));
+            if let Some(Value::Object(header)) = header {
+                let name = request_property(activation, header, "name")?
+                    .coerce_to_string(activation)?
+                    .to_string();
+                let value = request_property(activation, header, "value")?
+                    .coerce_to_string(activation)?
+                    .to_string();
+                headers.push((name, value));
+            }
+        }
+    }
+
+    let options = if is_post {
+        let content_type = match request_property(activation, request, "contentType")? {
+            Value::Undefined | Value::Null => "application/x-www-form-urlencoded".to_string(),
+            content_type => content_type.coerce_to_string(activation)?.to_string(),
+        };
+        RequestOptions::post(data.map(|data| (data, content_type)))
+    } else {
+        if let Some(data) = data {
+            let query = String::from_utf8_lossy(&data);
+            if !query.is_empty() {
+                url.push(if url.contains('?') { '&' } else { '?' });
+                url.push_str(&query);
+            }
+        }
+        RequestOptions::get()
+    };
+
+    Ok((url, options.with_headers(headers)))
+}
+
+/// Construct `URLRequest`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.net"), "URLRequest"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<URLRequest instance initializer>", mc),
+        Method::from_builtin(class_init, "<URLRequest class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::FINAL | ClassAttributes::SEALED);
+
+    const PUBLIC_SLOTS: &[(&str, &str)] = &[
+        ("url", "String"),
+        ("method", "String"),
+        ("contentType", "String"),
+        ("requestHeaders", "Array"),
+    ];
+    for (name, type_name) in PUBLIC_SLOTS {
+        write.define_instance_trait(Trait::from_slot(
+            QName::new(Namespace::public(), *name),
+            QName::new(Namespace::public(), *type_name).into(),
+            None,
+        ));
+    }
+
+    // `data` can hold any value, such as a `String`, `ByteArray` or
+    // `URLVariables`.
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "data"),
+        Multiname::any(),
+        None,
+    ));
+
+    class
+}
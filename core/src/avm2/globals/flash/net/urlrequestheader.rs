@@ -0,0 +1,75 @@
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.net.URLRequestHeader`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        let name = args.get(0).cloned().unwrap_or_else(|| "".into());
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "name").into(),
+            name,
+            activation,
+        )?;
+
+        let value = args.get(1).cloned().unwrap_or_else(|| "".into());
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "value").into(),
+            value,
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.net.URLRequestHeader`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `URLRequestHeader`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.net"), "URLRequestHeader"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<URLRequestHeader instance initializer>", mc),
+        Method::from_builtin(class_init, "<URLRequestHeader class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::FINAL | ClassAttributes::SEALED);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "name"),
+        QName::new(Namespace::public(), "String").into(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "value"),
+        QName::new(Namespace::public(), "String").into(),
+        None,
+    ));
+
+    class
+}
@@ -0,0 +1,168 @@
+//! `flash.net.URLVariables` builtin
+
+use crate::avm2::activation::Activation;
+use crate::avm2::array::ArrayStorage;
+use crate::avm2::class::Class;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{ArrayObject, Object, TObject};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::string::AvmString;
+use gc_arena::{GcCell, MutationContext};
+use url::form_urlencoded;
+
+/// Implements `flash.net.URLVariables`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+
+        if let Some(source) = args.get(0) {
+            if !matches!(source, Value::Undefined | Value::Null) {
+                decode(activation, Some(this), &[source.clone()])?;
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.net.URLVariables`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `URLVariables.decode`.
+///
+/// Variables that appear more than once are collected into an array.
+pub fn decode<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        let source = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_string(activation)?
+            .to_string();
+
+        for pair in source.split('&').filter(|pair| !pair.is_empty()) {
+            if !pair.contains('=') {
+                return Err("Error: Error #2101: The String passed to URLVariables.decode() must be a URL-encoded query string containing name/value pairs.".into());
+            }
+
+            for (name, value) in form_urlencoded::parse(pair.as_bytes()) {
+                let name =
+                    QName::dynamic_name(AvmString::new_utf8(activation.context.gc_context, name));
+                let value: Value<'gc> =
+                    AvmString::new_utf8(activation.context.gc_context, value).into();
+
+                let value = if this.has_own_property(name)? {
+                    let existing = this.get_property(this, &name.into(), activation)?;
+                    match existing {
+                        Value::Object(array) if array.as_array_storage().is_some() => {
+                            if let Some(mut storage) =
+                                array.as_array_storage_mut(activation.context.gc_context)
+                            {
+                                storage.push(value);
+                            }
+                            continue;
+                        }
+                        existing => {
+                            let mut storage = ArrayStorage::new(0);
+                            storage.push(existing);
+                            storage.push(value);
+                            ArrayObject::from_storage(activation, storage)?.into()
+                        }
+                    }
+                } else {
+                    value
+                };
+
+                this.set_property(this, &name.into(), value, activation)?;
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `URLVariables.toString`.
+pub fn to_string<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let mut pairs = Vec::new();
+        let mut index = 0;
+        while let Some(next) = this.get_next_enumerant(index, activation)? {
+            if next == 0 {
+                break;
+            }
+            index = next;
+
+            let name = this
+                .get_enumerant_name(index, activation)?
+                .coerce_to_string(activation)?
+                .to_string();
+            let value = this.get_enumerant_value(index, activation)?;
+
+            // Arrays send one variable per item.
+            let values = match value {
+                Value::Object(array) if array.as_array_storage().is_some() => array
+                    .as_array_storage()
+                    .map(|storage| {
+                        (0..storage.length())
+                            .map(|i| storage.get(i).unwrap_or(Value::Undefined))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                value => vec![value],
+            };
+            for value in values {
+                let value = value.coerce_to_string(activation)?.to_string();
+                pairs.push((name.clone(), value));
+            }
+        }
+
+        // Flash escapes spaces as `%20` rather than `+`.
+        let query = form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(pairs)
+            .finish()
+            .replace('+', "%20");
+
+        return Ok(AvmString::new_utf8(activation.context.gc_context, query).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `URLVariables`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.net"), "URLVariables"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<URLVariables instance initializer>", mc),
+        Method::from_builtin(class_init, "<URLVariables class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] =
+        &[("decode", decode), ("toString", to_string)];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    class
+}
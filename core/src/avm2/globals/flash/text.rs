@@ -1,8 +1,10 @@
 //! `flash.text` namespace
 
+pub mod engine;
 pub mod font;
 pub mod textfield;
 pub mod textfieldautosize;
 pub mod textfieldtype;
 pub mod textformat;
 pub mod textformatalign;
+pub mod textlinemetrics;
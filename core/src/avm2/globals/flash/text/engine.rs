@@ -0,0 +1,15 @@
+//! `flash.text.engine` namespace
+
+pub mod contentelement;
+pub mod elementformat;
+pub mod fontdescription;
+pub mod fontlookup;
+pub mod fontposture;
+pub mod fontweight;
+pub mod groupelement;
+pub mod textbaseline;
+pub mod textblock;
+pub mod textelement;
+pub mod textline;
+pub mod textlinecreationresult;
+pub mod textlinevalidity;
@@ -0,0 +1,348 @@
+//! `flash.text.engine.ContentElement` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::NS_RUFFLE_INTERNAL;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::string::{AvmString, WString};
+use gc_arena::{GcCell, MutationContext};
+use std::ops::Range;
+
+/// A part of a `TextBlock`'s text that was provided by a single element.
+pub struct ContentRun<'gc> {
+    /// The range of the text that the element covers.
+    pub range: Range<usize>,
+
+    /// The `ElementFormat` the element's text is displayed with.
+    pub element_format: Option<Object<'gc>>,
+}
+
+/// The name of one of the private slots of the text engine classes.
+pub(super) fn internal_name<'gc>(name: &'static str) -> QName<'gc> {
+    QName::new(Namespace::Private(NS_RUFFLE_INTERNAL.into()), name)
+}
+
+/// Collect the text of a content element and all of its descendants.
+///
+/// If `text_block` is provided, each element is also associated with that
+/// `TextBlock` and records where its text begins within it.
+pub fn flatten_content<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    mut element: Object<'gc>,
+    text_block: Option<Value<'gc>>,
+    text: &mut WString,
+    runs: &mut Vec<ContentRun<'gc>>,
+) -> Result<(), Error> {
+    if let Some(text_block) = text_block {
+        element.set_property(
+            element,
+            &internal_name("textBlock").into(),
+            text_block,
+            activation,
+        )?;
+        element.set_property(
+            element,
+            &internal_name("textBlockBeginIndex").into(),
+            text.len().into(),
+            activation,
+        )?;
+    }
+
+    let group_element_class = activation.avm2().classes().groupelement;
+    if element.is_of_type(group_element_class, activation)? {
+        let elements =
+            element.get_property(element, &internal_name("elements").into(), activation)?;
+        let children: Vec<Value<'gc>> = match elements {
+            Value::Object(elements) => elements
+                .as_array_storage()
+                .map(|storage| storage.iter().flatten().collect())
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+
+        for child in children {
+            if let Value::Object(child) = child {
+                flatten_content(activation, child, text_block, text, runs)?;
+            }
+        }
+    } else {
+        let start = text.len();
+        if let Value::String(element_text) =
+            element.get_property(element, &internal_name("text").into(), activation)?
+        {
+            text.push_str(&element_text);
+        }
+
+        let element_format = match element.get_property(
+            element,
+            &internal_name("elementFormat").into(),
+            activation,
+        )? {
+            Value::Object(element_format) => Some(element_format),
+            _ => None,
+        };
+        runs.push(ContentRun {
+            range: start..text.len(),
+            element_format,
+        });
+    }
+
+    Ok(())
+}
+
+/// Implements `flash.text.engine.ContentElement`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        let element_format = args.get(0).cloned().unwrap_or(Value::Null);
+        this.set_property(
+            this,
+            &internal_name("elementFormat").into(),
+            element_format,
+            activation,
+        )?;
+
+        let event_mirror = args.get(1).cloned().unwrap_or(Value::Null);
+        this.set_property(
+            this,
+            &internal_name("eventMirror").into(),
+            event_mirror,
+            activation,
+        )?;
+
+        let text_rotation = match args.get(2) {
+            Some(Value::Undefined) | None => "auto".into(),
+            Some(text_rotation) => text_rotation.coerce_to_string(activation)?.into(),
+        };
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "textRotation").into(),
+            text_rotation,
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.text.engine.ContentElement`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `ContentElement.elementFormat`.
+pub fn element_format<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return this.get_property(this, &internal_name("elementFormat").into(), activation);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ContentElement.elementFormat`'s setter.
+pub fn set_element_format<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        let element_format = args.get(0).cloned().unwrap_or(Value::Null);
+        this.set_property(
+            this,
+            &internal_name("elementFormat").into(),
+            element_format,
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ContentElement.eventMirror`.
+pub fn event_mirror<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return this.get_property(this, &internal_name("eventMirror").into(), activation);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ContentElement.eventMirror`'s setter.
+pub fn set_event_mirror<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        let event_mirror = args.get(0).cloned().unwrap_or(Value::Null);
+        this.set_property(
+            this,
+            &internal_name("eventMirror").into(),
+            event_mirror,
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ContentElement.groupElement`.
+pub fn group_element<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return this.get_property(this, &internal_name("groupElement").into(), activation);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ContentElement.text` and `ContentElement.rawText`.
+pub fn text<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let mut text = WString::new();
+        flatten_content(activation, this, None, &mut text, &mut Vec::new())?;
+
+        return Ok(AvmString::new(activation.context.gc_context, text).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ContentElement.textBlock`.
+pub fn text_block<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return this.get_property(this, &internal_name("textBlock").into(), activation);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ContentElement.textBlockBeginIndex`.
+pub fn text_block_begin_index<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return this.get_property(
+            this,
+            &internal_name("textBlockBeginIndex").into(),
+            activation,
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `ContentElement`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.text.engine"), "ContentElement"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<ContentElement instance initializer>", mc),
+        Method::from_builtin(class_init, "<ContentElement class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[
+        (
+            "elementFormat",
+            Some(element_format),
+            Some(set_element_format),
+        ),
+        ("eventMirror", Some(event_mirror), Some(set_event_mirror)),
+        ("groupElement", Some(group_element), None),
+        ("rawText", Some(text), None),
+        ("text", Some(text), None),
+        ("textBlock", Some(text_block), None),
+        ("textBlockBeginIndex", Some(text_block_begin_index), None),
+    ];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "textRotation"),
+        QName::new(Namespace::public(), "String").into(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "userData"),
+        QName::new(Namespace::public(), "Object").into(),
+        None,
+    ));
+
+    const PRIVATE_INSTANCE_SLOTS: &[(&str, &str, &str, &str)] = &[
+        (
+            NS_RUFFLE_INTERNAL,
+            "elementFormat",
+            "flash.text.engine",
+            "ElementFormat",
+        ),
+        (
+            NS_RUFFLE_INTERNAL,
+            "eventMirror",
+            "flash.events",
+            "EventDispatcher",
+        ),
+        (
+            NS_RUFFLE_INTERNAL,
+            "groupElement",
+            "flash.text.engine",
+            "GroupElement",
+        ),
+        (NS_RUFFLE_INTERNAL, "text", "", "String"),
+        (
+            NS_RUFFLE_INTERNAL,
+            "textBlock",
+            "flash.text.engine",
+            "TextBlock",
+        ),
+        (NS_RUFFLE_INTERNAL, "textBlockBeginIndex", "", "int"),
+    ];
+    write.define_private_slot_instance_traits(PRIVATE_INSTANCE_SLOTS);
+
+    const CONSTANTS: &[(&str, u32)] = &[("GRAPHIC_ELEMENT", 0xFDEF)];
+    write.define_public_constant_uint_class_traits(CONSTANTS);
+
+    class
+}
@@ -0,0 +1,211 @@
+//! `flash.text.engine.ElementFormat` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::html::TextFormat;
+use gc_arena::{GcCell, MutationContext};
+
+/// The properties of an `ElementFormat` and their types, in constructor
+/// argument order.
+const PROPERTIES: &[(&str, &str, &str)] = &[
+    ("fontDescription", "flash.text.engine", "FontDescription"),
+    ("fontSize", "", "Number"),
+    ("color", "", "uint"),
+    ("alpha", "", "Number"),
+    ("textRotation", "", "String"),
+    ("dominantBaseline", "", "String"),
+    ("alignmentBaseline", "", "String"),
+    ("baselineShift", "", "Number"),
+    ("kerning", "", "String"),
+    ("trackingRight", "", "Number"),
+    ("trackingLeft", "", "Number"),
+    ("locale", "", "String"),
+    ("breakOpportunity", "", "String"),
+    ("digitCase", "", "String"),
+    ("digitWidth", "", "String"),
+    ("ligatureLevel", "", "String"),
+    ("typographicCase", "", "String"),
+];
+
+/// Implements `flash.text.engine.ElementFormat`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        let font_description = match args.get(0) {
+            Some(Value::Object(font_description)) => Value::Object(*font_description),
+            _ => {
+                let font_description_class = activation.avm2().classes().fontdescription;
+                font_description_class.construct(activation, &[])?.into()
+            }
+        };
+        let defaults: [Value<'gc>; 17] = [
+            font_description,
+            12.0.into(),
+            0u32.into(),
+            1.0.into(),
+            "auto".into(),
+            "roman".into(),
+            "useDominantBaseline".into(),
+            0.0.into(),
+            "on".into(),
+            0.0.into(),
+            0.0.into(),
+            "en".into(),
+            "auto".into(),
+            "default".into(),
+            "default".into(),
+            "common".into(),
+            "default".into(),
+        ];
+        for (i, ((name, _, type_name), default)) in PROPERTIES.iter().zip(defaults).enumerate() {
+            let value = match args.get(i) {
+                Some(Value::Undefined) | None => default,
+                // `fontDescription` was already resolved above.
+                Some(_) if i == 0 => default,
+                Some(value) => match *type_name {
+                    "Number" => value.coerce_to_number(activation)?.into(),
+                    "uint" => value.coerce_to_u32(activation)?.into(),
+                    _ => value.coerce_to_string(activation)?.into(),
+                },
+            };
+            this.set_property(
+                this,
+                &QName::new(Namespace::public(), *name).into(),
+                value,
+                activation,
+            )?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.text.engine.ElementFormat`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `ElementFormat.clone`.
+pub fn clone<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let class = this
+            .instance_of()
+            .ok_or("Attempted to clone a bare ElementFormat")?;
+
+        let mut args = Vec::with_capacity(PROPERTIES.len());
+        for (name, _, _) in PROPERTIES {
+            args.push(this.get_property(
+                this,
+                &QName::new(Namespace::public(), *name).into(),
+                activation,
+            )?);
+        }
+
+        return Ok(class.construct(activation, &args)?.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Convert an `ElementFormat` into the `TextFormat` used to lay out text.
+///
+/// The returned flag is `true` if the text should be rendered with device
+/// fonts rather than fonts embedded in the movie.
+pub fn element_format_to_text_format<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    element_format: Object<'gc>,
+) -> Result<(TextFormat, bool), Error> {
+    let get = |activation: &mut Activation<'_, 'gc, '_>, object: Object<'gc>, name| {
+        object.get_property(
+            object,
+            &QName::new(Namespace::public(), name).into(),
+            activation,
+        )
+    };
+
+    let size = get(activation, element_format, "fontSize")?.coerce_to_number(activation)?;
+    let color = get(activation, element_format, "color")?.coerce_to_u32(activation)?;
+    let kerning = get(activation, element_format, "kerning")?.coerce_to_string(activation)?;
+    let tracking = get(activation, element_format, "trackingRight")?
+        .coerce_to_number(activation)?
+        + get(activation, element_format, "trackingLeft")?.coerce_to_number(activation)?;
+
+    let mut text_format = TextFormat {
+        size: Some(size),
+        color: Some(swf::Color::from_rgb(color, 0xFF)),
+        kerning: Some(&kerning != b"off"),
+        letter_spacing: Some(tracking),
+        ..Default::default()
+    };
+
+    let mut is_device_font = true;
+    if let Value::Object(font_description) = get(activation, element_format, "fontDescription")? {
+        let font_name =
+            get(activation, font_description, "fontName")?.coerce_to_string(activation)?;
+        let font_weight =
+            get(activation, font_description, "fontWeight")?.coerce_to_string(activation)?;
+        let font_posture =
+            get(activation, font_description, "fontPosture")?.coerce_to_string(activation)?;
+        let font_lookup =
+            get(activation, font_description, "fontLookup")?.coerce_to_string(activation)?;
+
+        text_format.font = Some(font_name.as_wstr().into());
+        text_format.bold = Some(&font_weight == b"bold");
+        text_format.italic = Some(&font_posture == b"italic");
+        is_device_font = &font_lookup != b"embeddedCFF";
+    }
+
+    Ok((text_format, is_device_font))
+}
+
+/// Construct `ElementFormat`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.text.engine"), "ElementFormat"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<ElementFormat instance initializer>", mc),
+        Method::from_builtin(class_init, "<ElementFormat class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::FINAL | ClassAttributes::SEALED);
+
+    for &(name, type_ns, type_name) in PROPERTIES {
+        write.define_instance_trait(Trait::from_slot(
+            QName::new(Namespace::public(), name),
+            QName::new(Namespace::package(type_ns), type_name).into(),
+            None,
+        ));
+    }
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "locked"),
+        QName::new(Namespace::public(), "Boolean").into(),
+        None,
+    ));
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[("clone", clone)];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    class
+}
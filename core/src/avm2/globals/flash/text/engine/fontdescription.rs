@@ -0,0 +1,123 @@
+//! `flash.text.engine.FontDescription` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// The properties of a `FontDescription`, in constructor argument order.
+const PROPERTIES: &[&str] = &[
+    "fontName",
+    "fontWeight",
+    "fontPosture",
+    "fontLookup",
+    "renderingMode",
+    "cffHinting",
+];
+
+/// Implements `flash.text.engine.FontDescription`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        let defaults: [Value<'gc>; 6] = [
+            "_serif".into(),
+            "normal".into(),
+            "normal".into(),
+            "device".into(),
+            "cff".into(),
+            "horizontalStem".into(),
+        ];
+        for (i, (name, default)) in PROPERTIES.iter().zip(defaults).enumerate() {
+            let value = match args.get(i) {
+                Some(Value::Undefined) | None => default,
+                Some(value) => value.coerce_to_string(activation)?.into(),
+            };
+            this.set_property(
+                this,
+                &QName::new(Namespace::public(), *name).into(),
+                value,
+                activation,
+            )?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.text.engine.FontDescription`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `FontDescription.clone`.
+pub fn clone<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let class = this
+            .instance_of()
+            .ok_or("Attempted to clone a bare FontDescription")?;
+
+        let mut args = Vec::with_capacity(PROPERTIES.len());
+        for name in PROPERTIES {
+            args.push(this.get_property(
+                this,
+                &QName::new(Namespace::public(), *name).into(),
+                activation,
+            )?);
+        }
+
+        return Ok(class.construct(activation, &args)?.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `FontDescription`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.text.engine"), "FontDescription"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<FontDescription instance initializer>", mc),
+        Method::from_builtin(class_init, "<FontDescription class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::FINAL | ClassAttributes::SEALED);
+
+    for name in PROPERTIES {
+        write.define_instance_trait(Trait::from_slot(
+            QName::new(Namespace::public(), *name),
+            QName::new(Namespace::public(), "String").into(),
+            None,
+        ));
+    }
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "locked"),
+        QName::new(Namespace::public(), "Boolean").into(),
+        None,
+    ));
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[("clone", clone)];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    class
+}
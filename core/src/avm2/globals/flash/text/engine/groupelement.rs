@@ -0,0 +1,206 @@
+//! `flash.text.engine.GroupElement` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::array::ArrayStorage;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::text::engine::contentelement::internal_name;
+use crate::avm2::globals::NS_RUFFLE_INTERNAL;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{ArrayObject, Object, TObject};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Get the elements of a `GroupElement`.
+fn elements<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+) -> Result<Vec<Object<'gc>>, Error> {
+    let elements = this.get_property(this, &internal_name("elements").into(), activation)?;
+    let elements = match elements {
+        Value::Object(elements) => elements
+            .as_array_storage()
+            .map(|storage| {
+                storage
+                    .iter()
+                    .filter_map(|element| match element {
+                        Some(Value::Object(element)) => Some(element),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    };
+
+    Ok(elements)
+}
+
+/// Implements `flash.text.engine.GroupElement`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, args.get(1..).unwrap_or_default())?;
+
+        set_elements(activation, Some(this), args.get(0..1).unwrap_or_default())?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.text.engine.GroupElement`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `GroupElement.elementCount`.
+pub fn element_count<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return Ok(elements(activation, this)?.len().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `GroupElement.getElementAt`.
+pub fn get_element_at<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let index = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation)?;
+
+        let elements = elements(activation, this)?;
+        return match usize::try_from(index).ok().and_then(|i| elements.get(i)) {
+            Some(element) => Ok((*element).into()),
+            None => Err("RangeError: The supplied index is out of bounds.".into()),
+        };
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `GroupElement.getElementIndex`.
+pub fn get_element_index<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(Value::Object(element)) = args.get(0) {
+            let index = elements(activation, this)?
+                .iter()
+                .position(|e| Object::ptr_eq(*e, *element));
+            return Ok(index.map_or(-1, |i| i as i32).into());
+        }
+
+        return Ok((-1).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `GroupElement.setElements`.
+pub fn set_elements<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        for mut old_element in elements(activation, this)? {
+            old_element.set_property(
+                old_element,
+                &internal_name("groupElement").into(),
+                Value::Null,
+                activation,
+            )?;
+        }
+
+        let new_elements: Vec<Value<'gc>> = match args.get(0) {
+            Some(Value::Object(new_elements)) => {
+                if let Some(vector) = new_elements.as_vector_storage() {
+                    vector.iter().collect()
+                } else if let Some(array) = new_elements.as_array_storage() {
+                    array.iter().flatten().collect()
+                } else {
+                    Vec::new()
+                }
+            }
+            _ => Vec::new(),
+        };
+
+        for element in &new_elements {
+            if let Value::Object(mut element) = *element {
+                element.set_property(
+                    element,
+                    &internal_name("groupElement").into(),
+                    this.into(),
+                    activation,
+                )?;
+            }
+        }
+
+        let elements =
+            ArrayObject::from_storage(activation, ArrayStorage::from_args(&new_elements))?;
+        this.set_property(
+            this,
+            &internal_name("elements").into(),
+            elements.into(),
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `GroupElement`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.text.engine"), "GroupElement"),
+        Some(QName::new(Namespace::package("flash.text.engine"), "ContentElement").into()),
+        Method::from_builtin(instance_init, "<GroupElement instance initializer>", mc),
+        Method::from_builtin(class_init, "<GroupElement class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::FINAL | ClassAttributes::SEALED);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[("elementCount", Some(element_count), None)];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("getElementAt", get_element_at),
+        ("getElementIndex", get_element_index),
+        ("setElements", set_elements),
+    ];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    const PRIVATE_INSTANCE_SLOTS: &[(&str, &str, &str, &str)] =
+        &[(NS_RUFFLE_INTERNAL, "elements", "", "Array")];
+    write.define_private_slot_instance_traits(PRIVATE_INSTANCE_SLOTS);
+
+    class
+}
@@ -0,0 +1,787 @@
+//! `flash.text.engine.TextBlock` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::text::engine::contentelement::{flatten_content, internal_name};
+use crate::avm2::globals::flash::text::engine::textline::create_text_line;
+use crate::avm2::globals::NS_RUFFLE_INTERNAL;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::string::WString;
+use gc_arena::{GcCell, MutationContext};
+
+/// The configuration properties of a `TextBlock` and their types, in
+/// constructor argument order after `content`.
+const PROPERTIES: &[(&str, &str, &str)] = &[
+    ("tabStops", "__AS3__.vec", "Vector"),
+    ("textJustifier", "flash.text.engine", "TextJustifier"),
+    ("lineRotation", "", "String"),
+    ("baselineZero", "", "String"),
+    ("bidiLevel", "", "int"),
+    ("applyNonLinearFontScaling", "", "Boolean"),
+    (
+        "baselineFontDescription",
+        "flash.text.engine",
+        "FontDescription",
+    ),
+    ("baselineFontSize", "", "Number"),
+];
+
+/// Get an object stored in one of the private slots of a text engine object.
+fn internal_object<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    object: Object<'gc>,
+    name: &'static str,
+) -> Result<Option<Object<'gc>>, Error> {
+    match object.get_property(object, &internal_name(name).into(), activation)? {
+        Value::Object(value) => Ok(Some(value)),
+        _ => Ok(None),
+    }
+}
+
+fn set_internal<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    mut object: Object<'gc>,
+    name: &'static str,
+    value: Value<'gc>,
+) -> Result<(), Error> {
+    object.set_property(object, &internal_name(name).into(), value, activation)?;
+    Ok(())
+}
+
+/// Implements `flash.text.engine.TextBlock`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        set_internal(activation, this, "textLineCreationResult", Value::Null)?;
+        set_content(activation, Some(this), args.get(0..1).unwrap_or_default())?;
+
+        let defaults: [Value<'gc>; 8] = [
+            Value::Null,
+            Value::Null,
+            "rotate0".into(),
+            "roman".into(),
+            0.into(),
+            true.into(),
+            Value::Null,
+            12.0.into(),
+        ];
+        for (i, ((name, _, _), default)) in PROPERTIES.iter().zip(defaults).enumerate() {
+            let value = match args.get(i + 1) {
+                Some(Value::Undefined) | None => default,
+                Some(value) => *value,
+            };
+            this.set_property(
+                this,
+                &QName::new(Namespace::public(), *name).into(),
+                value,
+                activation,
+            )?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.text.engine.TextBlock`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `TextBlock.content`.
+pub fn content<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return this.get_property(this, &internal_name("content").into(), activation);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `TextBlock.content`'s setter.
+///
+/// Any lines that were already created from the old content are invalidated.
+pub fn set_content<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let content = match args.get(0) {
+            Some(Value::Object(content)) => Some(*content),
+            _ => None,
+        };
+        set_internal(
+            activation,
+            this,
+            "content",
+            content.map_or(Value::Null, Value::Object),
+        )?;
+
+        if let Some(content) = content {
+            flatten_content(
+                activation,
+                content,
+                Some(this.into()),
+                &mut WString::new(),
+                &mut Vec::new(),
+            )?;
+        }
+
+        let first_line = internal_object(activation, this, "firstLine")?;
+        let mut line = first_line;
+        while let Some(current) = line {
+            set_internal(activation, current, "validity", "invalid".into())?;
+            line = internal_object(activation, current, "nextLine")?;
+        }
+        set_internal(
+            activation,
+            this,
+            "firstInvalidLine",
+            first_line.map_or(Value::Null, Value::Object),
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `TextBlock.firstInvalidLine`.
+pub fn first_invalid_line<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return this.get_property(this, &internal_name("firstInvalidLine").into(), activation);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `TextBlock.firstLine`.
+pub fn first_line<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return this.get_property(this, &internal_name("firstLine").into(), activation);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `TextBlock.lastLine`.
+pub fn last_line<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return this.get_property(this, &internal_name("lastLine").into(), activation);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `TextBlock.textLineCreationResult`.
+pub fn text_line_creation_result<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return this.get_property(
+            this,
+            &internal_name("textLineCreationResult").into(),
+            activation,
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `TextBlock.createTextLine`.
+pub fn create_text_line_method<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let previous_line = match args.get(0) {
+            Some(Value::Object(previous_line)) => Some(*previous_line),
+            _ => None,
+        };
+        let width = match args.get(1) {
+            Some(Value::Undefined) | None => 1000000.0,
+            Some(width) => width.coerce_to_number(activation)?,
+        };
+        if !(0.0..=1000000.0).contains(&width) {
+            return Err("ArgumentError: Error #2004: One of the parameters is invalid.".into());
+        }
+
+        let content = match internal_object(activation, this, "content")? {
+            Some(content) => content,
+            None => {
+                set_internal(
+                    activation,
+                    this,
+                    "textLineCreationResult",
+                    "complete".into(),
+                )?;
+                return Ok(Value::Null);
+            }
+        };
+
+        let begin = if let Some(previous_line) = previous_line {
+            let previous_begin = previous_line
+                .get_property(
+                    previous_line,
+                    &internal_name("textBlockBeginIndex").into(),
+                    activation,
+                )?
+                .coerce_to_u32(activation)?;
+            let previous_length = previous_line
+                .get_property(
+                    previous_line,
+                    &internal_name("rawTextLength").into(),
+                    activation,
+                )?
+                .coerce_to_u32(activation)?;
+            (previous_begin + previous_length) as usize
+        } else {
+            0
+        };
+
+        let mut text = WString::new();
+        let mut runs = Vec::new();
+        flatten_content(activation, content, Some(this.into()), &mut text, &mut runs)?;
+
+        if begin >= text.len() {
+            set_internal(
+                activation,
+                this,
+                "textLineCreationResult",
+                "complete".into(),
+            )?;
+            return Ok(Value::Null);
+        }
+
+        let line = create_text_line(activation, this, &text, &runs, begin, width)?;
+
+        if let Some(previous_line) = previous_line {
+            set_internal(activation, previous_line, "nextLine", line.into())?;
+            set_internal(activation, line, "previousLine", previous_line.into())?;
+        } else {
+            set_internal(activation, this, "firstLine", line.into())?;
+            set_internal(activation, this, "firstInvalidLine", Value::Null)?;
+        }
+
+        let last_line = internal_object(activation, this, "lastLine")?;
+        let is_last = match (last_line, previous_line) {
+            (None, _) | (_, None) => true,
+            (Some(last_line), Some(previous_line)) => Object::ptr_eq(last_line, previous_line),
+        };
+        if is_last {
+            set_internal(activation, this, "lastLine", line.into())?;
+        }
+
+        set_internal(activation, this, "textLineCreationResult", "success".into())?;
+
+        return Ok(line.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `TextBlock.getTextLineAtCharIndex`.
+pub fn get_text_line_at_char_index<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let char_index = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation)?;
+
+        let mut line = internal_object(activation, this, "firstLine")?;
+        while let Some(current) = line {
+            let begin = current
+                .get_property(
+                    current,
+                    &internal_name("textBlockBeginIndex").into(),
+                    activation,
+                )?
+                .coerce_to_i32(activation)?;
+            let length = current
+                .get_property(current, &internal_name("rawTextLength").into(), activation)?
+                .coerce_to_i32(activation)?;
+            if char_index >= begin && char_index < begin + length {
+                return Ok(current.into());
+            }
+
+            line = internal_object(activation, current, "nextLine")?;
+        }
+
+        return Ok(Value::Null);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `TextBlock.releaseLines`.
+pub fn release_lines<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let (first_line, last_line) = match (args.get(0), args.get(1)) {
+            (Some(Value::Object(first_line)), Some(Value::Object(last_line))) => {
+                (*first_line, *last_line)
+            }
+            _ => return Err("TypeError: Error #2007: Parameter must be non-null.".into()),
+        };
+
+        let before = internal_object(activation, first_line, "previousLine")?;
+        let after = internal_object(activation, last_line, "nextLine")?;
+
+        let mut line = Some(first_line);
+        while let Some(current) = line {
+            line = internal_object(activation, current, "nextLine")?;
+            set_internal(activation, current, "textBlock", Value::Null)?;
+            set_internal(activation, current, "previousLine", Value::Null)?;
+            set_internal(activation, current, "nextLine", Value::Null)?;
+
+            if Object::ptr_eq(current, last_line) {
+                break;
+            }
+        }
+
+        match before {
+            Some(before) => set_internal(
+                activation,
+                before,
+                "nextLine",
+                after.map_or(Value::Null, Value::Object),
+            )?,
+            None => set_internal(
+                activation,
+                this,
+                "firstLine",
+                after.map_or(Value::Null, Value::Object),
+            )?,
+        }
+        match after {
+            Some(after) => set_internal(
+                activation,
+                after,
+                "previousLine",
+                before.map_or(Value::Null, Value::Object),
+            )?,
+            None => set_internal(
+                activation,
+                this,
+                "lastLine",
+                before.map_or(Value::Null, Value::Object),
+            )?,
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `TextBlock.releaseLineCreationData`.
+pub fn release_line_creation_data<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `TextBlock`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.text.engine"), "TextBlock"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<TextBlock instance initializer>", mc),
+        Method::from_builtin(class_init, "<TextBlock class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::FINAL | ClassAttributes::SEALED);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[
+        ("content", Some(content), Some(set_content)),
+        ("firstInvalidLine", Some(first_invalid_line), None),
+        ("firstLine", Some(first_line), None),
+        ("lastLine", Some(last_line), None),
+        (
+            "textLineCreationResult",
+            Some(text_line_creation_result),
+            None,
+        ),
+    ];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("createTextLine", create_text_line_method),
+        ("getTextLineAtCharIndex", get_text_line_at_char_index),
+        ("releaseLineCreationData", release_line_creation_data),
+        ("releaseLines", release_lines),
+    ];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    for &(name, type_ns, type_name) in PROPERTIES {
+        write.define_instance_trait(Trait::from_slot(
+            QName::new(Namespace::public(), name),
+            QName::new(Namespace::package(type_ns), type_name).into(),
+            None,
+        ));
+    }
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "userData"),
+        QName::new(Namespace::public(), "Object").into(),
+        None,
+    ));
+
+    const PRIVATE_INSTANCE_SLOTS: &[(&str, &str, &str, &str)] = &[
+        (
+            NS_RUFFLE_INTERNAL,
+            "content",
+            "flash.text.engine",
+            "ContentElement",
+        ),
+        (
+            NS_RUFFLE_INTERNAL,
+            "firstInvalidLine",
+            "flash.text.engine",
+            "TextLine",
+        ),
+        (
+            NS_RUFFLE_INTERNAL,
+            "firstLine",
+            "flash.text.engine",
+            "TextLine",
+        ),
+        (
+            NS_RUFFLE_INTERNAL,
+            "lastLine",
+            "flash.text.engine",
+            "TextLine",
+        ),
+        (NS_RUFFLE_INTERNAL, "textLineCreationResult", "", "String"),
+    ];
+    write.define_private_slot_instance_traits(PRIVATE_INSTANCE_SLOTS);
+
+    class
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::avm2::activation::Activation;
+    use crate::avm2::names::{Namespace, QName};
+    use crate::avm2::object::{Object, TObject};
+    use crate::avm2::test_utils::with_avm2;
+    use crate::avm2::value::Value;
+    use crate::avm2::Error;
+    use crate::player::{Player, DEVICE_FONT_TAG};
+
+    const TEXT: &str = "The quick brown fox jumps over the lazy dog";
+
+    fn construct<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        name: &'static str,
+        args: &[Value<'gc>],
+    ) -> Result<Object<'gc>, Error> {
+        activation
+            .avm2()
+            .global_domain()
+            .get_defined_value(
+                activation,
+                QName::new(Namespace::package("flash.text.engine"), name),
+            )?
+            .coerce_to_object(activation)?
+            .construct(activation, args)
+    }
+
+    fn get<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        object: Object<'gc>,
+        name: &'static str,
+    ) -> Result<Value<'gc>, Error> {
+        object.get_property(
+            object,
+            &QName::new(Namespace::public(), name).into(),
+            activation,
+        )
+    }
+
+    fn call<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        object: Object<'gc>,
+        name: &'static str,
+        args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, Error> {
+        object.call_property(
+            &QName::new(Namespace::public(), name).into(),
+            args,
+            activation,
+        )
+    }
+
+    /// Load the device font, and create a text block with `text` in it.
+    fn text_block<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        text: &'static str,
+    ) -> Result<Object<'gc>, Error> {
+        let device_font = Player::load_device_font(
+            activation.context.gc_context,
+            DEVICE_FONT_TAG,
+            activation.context.renderer,
+        )
+        .unwrap();
+        activation
+            .context
+            .library
+            .set_device_font(Some(device_font));
+
+        let element_format = construct(activation, "ElementFormat", &[])?;
+        let element = construct(
+            activation,
+            "TextElement",
+            &[text.into(), element_format.into()],
+        )?;
+        construct(activation, "TextBlock", &[element.into()])
+    }
+
+    /// Create lines of `width` until the text block runs out of text.
+    fn create_lines<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        text_block: Object<'gc>,
+        width: f64,
+    ) -> Result<Vec<Object<'gc>>, Error> {
+        let mut lines: Vec<Object<'gc>> = Vec::new();
+        loop {
+            let previous_line = lines.last().map_or(Value::Null, |line| (*line).into());
+            match call(
+                activation,
+                text_block,
+                "createTextLine",
+                &[previous_line, width.into()],
+            )? {
+                Value::Object(line) => lines.push(line),
+                _ => return Ok(lines),
+            }
+        }
+    }
+
+    fn is<'gc>(value: Value<'gc>, object: Object<'gc>) -> bool {
+        matches!(value, Value::Object(value) if Object::ptr_eq(value, object))
+    }
+
+    /// Tests that narrow lines break between words, and that together they
+    /// cover all of the text in order.
+    #[test]
+    fn lines_break_between_words() {
+        with_avm2(|activation| {
+            let text_block = text_block(activation, TEXT)?;
+            let lines = create_lines(activation, text_block, 80.0)?;
+            assert!(lines.len() > 1, "The text should not fit on one line");
+
+            let mut begin = 0;
+            for line in &lines {
+                let line_begin = get(activation, *line, "textBlockBeginIndex")?
+                    .coerce_to_u32(activation)? as usize;
+                let length =
+                    get(activation, *line, "rawTextLength")?.coerce_to_u32(activation)? as usize;
+                assert_eq!(line_begin, begin);
+                assert!(length > 0);
+
+                let text = &TEXT[begin..begin + length];
+                if text.trim_end().contains(' ') {
+                    let width =
+                        get(activation, *line, "textWidth")?.coerce_to_number(activation)?;
+                    assert!(width <= 80.0, "{:?} is {} wide", text, width);
+                }
+                if begin + length < TEXT.len() {
+                    assert!(
+                        text.ends_with(' '),
+                        "{:?} should end at a word boundary",
+                        text
+                    );
+                }
+                begin += length;
+            }
+            assert_eq!(begin, TEXT.len());
+
+            let result = get(activation, text_block, "textLineCreationResult")?
+                .coerce_to_string(activation)?;
+            assert_eq!(&result, b"complete");
+
+            Ok(())
+        });
+    }
+
+    /// Tests that a line wide enough for the whole text takes all of it.
+    #[test]
+    fn wide_line_takes_all_text() {
+        with_avm2(|activation| {
+            let text_block = text_block(activation, TEXT)?;
+            let lines = create_lines(activation, text_block, 1000.0)?;
+            assert_eq!(lines.len(), 1);
+
+            let length =
+                get(activation, lines[0], "rawTextLength")?.coerce_to_u32(activation)? as usize;
+            assert_eq!(length, TEXT.len());
+
+            let width = get(activation, lines[0], "textWidth")?.coerce_to_number(activation)?;
+            assert!(width > 0.0 && width <= 1000.0);
+
+            Ok(())
+        });
+    }
+
+    /// Tests that the lines of a text block are linked to each other and to
+    /// the block, and that a line can be found by the text it contains.
+    #[test]
+    fn lines_are_linked() {
+        with_avm2(|activation| {
+            let text_block = text_block(activation, TEXT)?;
+            let lines = create_lines(activation, text_block, 80.0)?;
+            let first = lines[0];
+            let last = *lines.last().unwrap();
+
+            assert!(is(get(activation, text_block, "firstLine")?, first));
+            assert!(is(get(activation, text_block, "lastLine")?, last));
+            assert_eq!(get(activation, first, "previousLine")?, Value::Null);
+            assert_eq!(get(activation, last, "nextLine")?, Value::Null);
+            for pair in lines.windows(2) {
+                assert!(is(get(activation, pair[0], "nextLine")?, pair[1]));
+                assert!(is(get(activation, pair[1], "previousLine")?, pair[0]));
+                assert!(is(get(activation, pair[1], "textBlock")?, text_block));
+            }
+
+            let last_char = (TEXT.len() - 1).into();
+            let line = call(
+                activation,
+                text_block,
+                "getTextLineAtCharIndex",
+                &[last_char],
+            )?;
+            assert!(is(line, last));
+
+            Ok(())
+        });
+    }
+
+    /// Tests that replacing the content invalidates the existing lines.
+    #[test]
+    fn new_content_invalidates_lines() {
+        with_avm2(|activation| {
+            let text_block = text_block(activation, TEXT)?;
+            let lines = create_lines(activation, text_block, 80.0)?;
+            assert_eq!(
+                get(activation, text_block, "firstInvalidLine")?,
+                Value::Null
+            );
+
+            let element_format = construct(activation, "ElementFormat", &[])?;
+            let element = construct(
+                activation,
+                "TextElement",
+                &["Hello".into(), element_format.into()],
+            )?;
+            let mut text_block_object = text_block;
+            text_block_object.set_property(
+                text_block,
+                &QName::new(Namespace::public(), "content").into(),
+                element.into(),
+                activation,
+            )?;
+
+            assert!(is(
+                get(activation, text_block, "firstInvalidLine")?,
+                lines[0]
+            ));
+            for line in &lines {
+                let validity = get(activation, *line, "validity")?.coerce_to_string(activation)?;
+                assert_eq!(&validity, b"invalid");
+            }
+
+            Ok(())
+        });
+    }
+
+    /// Tests that released lines are unlinked from the text block and from
+    /// the lines that remain.
+    #[test]
+    fn release_lines() {
+        with_avm2(|activation| {
+            let text_block = text_block(activation, TEXT)?;
+            let lines = create_lines(activation, text_block, 80.0)?;
+            assert!(lines.len() > 2);
+
+            call(
+                activation,
+                text_block,
+                "releaseLines",
+                &[lines[0].into(), lines[0].into()],
+            )?;
+
+            assert!(is(get(activation, text_block, "firstLine")?, lines[1]));
+            assert_eq!(get(activation, lines[1], "previousLine")?, Value::Null);
+            assert_eq!(get(activation, lines[0], "textBlock")?, Value::Null);
+            assert_eq!(get(activation, lines[0], "nextLine")?, Value::Null);
+
+            Ok(())
+        });
+    }
+
+    /// Tests that lines can't be narrower than nothing or wider than Flash
+    /// allows.
+    #[test]
+    fn invalid_width() {
+        with_avm2(|activation| {
+            let text_block = text_block(activation, TEXT)?;
+            for width in [-1.0, 1000001.0] {
+                let error = call(
+                    activation,
+                    text_block,
+                    "createTextLine",
+                    &[Value::Null, width.into()],
+                )
+                .unwrap_err();
+                assert!(error.to_string().contains("Error #2004"), "{}", error);
+            }
+
+            Ok(())
+        });
+    }
+}
@@ -0,0 +1,126 @@
+//! `flash.text.engine.TextElement` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::text::engine::contentelement::internal_name;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::string::{AvmString, WString};
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.text.engine.TextElement`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, args.get(1..).unwrap_or_default())?;
+
+        set_text(activation, Some(this), args.get(0..1).unwrap_or_default())?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.text.engine.TextElement`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `TextElement.text`'s setter.
+pub fn set_text<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        let text = match args.get(0) {
+            Some(Value::Undefined) | Some(Value::Null) | None => Value::Null,
+            Some(text) => text.coerce_to_string(activation)?.into(),
+        };
+        this.set_property(this, &internal_name("text").into(), text, activation)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `TextElement.replaceText`.
+pub fn replace_text<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        let text = match this.get_property(this, &internal_name("text").into(), activation)? {
+            Value::String(text) => text,
+            _ => "".into(),
+        };
+        let begin_index = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation)?;
+        let end_index = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation)?;
+        if begin_index < 0 || end_index < begin_index || end_index as usize > text.len() {
+            return Err("RangeError: The supplied index is out of bounds.".into());
+        }
+        let new_text = match args.get(2) {
+            Some(Value::Undefined) | Some(Value::Null) | None => "".into(),
+            Some(new_text) => new_text.coerce_to_string(activation)?,
+        };
+
+        let mut replaced = WString::new();
+        replaced.push_str(&text[..begin_index as usize]);
+        replaced.push_str(&new_text);
+        replaced.push_str(&text[end_index as usize..]);
+        let replaced = AvmString::new(activation.context.gc_context, replaced);
+
+        this.set_property(
+            this,
+            &internal_name("text").into(),
+            replaced.into(),
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `TextElement`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.text.engine"), "TextElement"),
+        Some(QName::new(Namespace::package("flash.text.engine"), "ContentElement").into()),
+        Method::from_builtin(instance_init, "<TextElement instance initializer>", mc),
+        Method::from_builtin(class_init, "<TextElement class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::FINAL | ClassAttributes::SEALED);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[("text", None, Some(set_text))];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[("replaceText", replace_text)];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    class
+}
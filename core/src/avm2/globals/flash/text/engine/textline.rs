@@ -0,0 +1,437 @@
+//! `flash.text.engine.TextLine` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::text::engine::contentelement::{internal_name, ContentRun};
+use crate::avm2::globals::flash::text::engine::elementformat::element_format_to_text_format;
+use crate::avm2::globals::NS_RUFFLE_INTERNAL;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, StageObject, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::display_object::{
+    AutoSizeMode, EditText, MovieClip, TDisplayObject, TDisplayObjectContainer,
+};
+use crate::string::WStr;
+use gc_arena::{GcCell, MutationContext};
+
+/// Create a `TextLine` containing as much of `text` after `begin` as fits in
+/// `width`.
+///
+/// The line's origin is on the baseline of its text, at the start of the
+/// line.
+pub fn create_text_line<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    text_block: Object<'gc>,
+    text: &WStr,
+    runs: &[ContentRun<'gc>],
+    begin: usize,
+    width: f64,
+) -> Result<Object<'gc>, Error> {
+    let movie = activation.context.swf.clone();
+
+    // The text is laid out by a text field, which breaks lines the same way
+    // that the text engine does.
+    let edit_text = EditText::new(&mut activation.context, movie.clone(), 0.0, 0.0, width, 0.0);
+    edit_text.set_selectable(false, &mut activation.context);
+    edit_text.set_word_wrap(true, &mut activation.context);
+    edit_text.set_autosize(AutoSizeMode::Left, &mut activation.context);
+    edit_text.set_text(&text[begin..], &mut activation.context)?;
+
+    let mut is_device_font = true;
+    for run in runs {
+        if run.range.end <= begin {
+            continue;
+        }
+
+        if let Some(element_format) = run.element_format {
+            let (text_format, run_is_device_font) =
+                element_format_to_text_format(activation, element_format)?;
+            let from = run.range.start.saturating_sub(begin);
+            let to = run.range.end - begin;
+            edit_text.set_text_format(from, to, text_format, &mut activation.context);
+            is_device_font &= run_is_device_font;
+        }
+    }
+    edit_text.set_is_device_font(&mut activation.context, is_device_font);
+
+    let length = edit_text
+        .line_range(0)
+        .map_or(0, |range| range.end)
+        .max(1)
+        .min(text.len() - begin);
+    edit_text.replace_text(
+        length,
+        text.len() - begin,
+        WStr::empty(),
+        &mut activation.context,
+    );
+    edit_text.set_word_wrap(false, &mut activation.context);
+
+    let metrics = edit_text.line_metrics(0).unwrap_or_default();
+    let baseline = edit_text.line_baseline(0).unwrap_or_default();
+    edit_text.set_x(activation.context.gc_context, -metrics.x);
+    edit_text.set_y(activation.context.gc_context, -baseline);
+
+    let mut line = MovieClip::new(movie, activation.context.gc_context);
+    let text_line_class = activation.avm2().classes().textline;
+    let mut this: Object<'gc> =
+        StageObject::for_display_object_childless(activation, line.into(), text_line_class)?.into();
+    line.set_object2(activation.context.gc_context, this);
+    line.insert_at_index(&mut activation.context, edit_text.into(), 0);
+
+    let slots: [(&'static str, Value<'gc>); 8] = [
+        ("textBlock", text_block.into()),
+        ("textBlockBeginIndex", begin.into()),
+        ("rawTextLength", length.into()),
+        ("specifiedWidth", width.into()),
+        ("ascent", metrics.ascent.into()),
+        ("descent", metrics.descent.into()),
+        ("textWidth", metrics.width.into()),
+        ("validity", "valid".into()),
+    ];
+    for (name, value) in slots {
+        this.set_property(this, &internal_name(name).into(), value, activation)?;
+    }
+
+    Ok(this)
+}
+
+/// Implements `flash.text.engine.TextLine`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if this.as_display_object().is_none() {
+            return Err(
+                "ArgumentError: Error #2012: TextLine class cannot be instantiated.".into(),
+            );
+        }
+
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.text.engine.TextLine`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+fn internal_property<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    name: &'static str,
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return this.get_property(this, &internal_name(name).into(), activation);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `TextLine.ascent`.
+pub fn ascent<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    internal_property(activation, this, "ascent")
+}
+
+/// Implements `TextLine.descent`.
+pub fn descent<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    internal_property(activation, this, "descent")
+}
+
+/// Implements `TextLine.textHeight`.
+pub fn text_height<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let ascent = internal_property(activation, this, "ascent")?.coerce_to_number(activation)?;
+    let descent = internal_property(activation, this, "descent")?.coerce_to_number(activation)?;
+
+    Ok((ascent + descent).into())
+}
+
+/// Implements `TextLine.textWidth` and `TextLine.unjustifiedTextWidth`.
+pub fn text_width<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    internal_property(activation, this, "textWidth")
+}
+
+/// Implements `TextLine.rawTextLength` and `TextLine.atomCount`.
+pub fn raw_text_length<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    internal_property(activation, this, "rawTextLength")
+}
+
+/// Implements `TextLine.specifiedWidth`.
+pub fn specified_width<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    internal_property(activation, this, "specifiedWidth")
+}
+
+/// Implements `TextLine.textBlock`.
+pub fn text_block<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    internal_property(activation, this, "textBlock")
+}
+
+/// Implements `TextLine.textBlockBeginIndex`.
+pub fn text_block_begin_index<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    internal_property(activation, this, "textBlockBeginIndex")
+}
+
+/// Implements `TextLine.nextLine`.
+pub fn next_line<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    internal_property(activation, this, "nextLine")
+}
+
+/// Implements `TextLine.previousLine`.
+pub fn previous_line<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    internal_property(activation, this, "previousLine")
+}
+
+/// Implements `TextLine.validity`.
+pub fn validity<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    internal_property(activation, this, "validity")
+}
+
+/// Implements `TextLine.validity`'s setter.
+pub fn set_validity<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        let validity = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_string(activation)?;
+        if &validity == b"valid" {
+            return Err("ArgumentError: Error #2004: One of the parameters is invalid.".into());
+        }
+
+        this.set_property(
+            this,
+            &internal_name("validity").into(),
+            validity.into(),
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `TextLine.hasGraphicElement`.
+pub fn has_graphic_element<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(false.into())
+}
+
+/// Implements `TextLine.mirrorRegions`.
+pub fn mirror_regions<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Null)
+}
+
+/// Implements `TextLine.getBaselinePosition`.
+pub fn get_baseline_position<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let baseline = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+
+    let position = if &baseline == b"ascent" || &baseline == b"ideographicTop" {
+        -internal_property(activation, this, "ascent")?.coerce_to_number(activation)?
+    } else if &baseline == b"descent" || &baseline == b"ideographicBottom" {
+        internal_property(activation, this, "descent")?.coerce_to_number(activation)?
+    } else if &baseline == b"ideographicCenter" {
+        let ascent = internal_property(activation, this, "ascent")?.coerce_to_number(activation)?;
+        let descent =
+            internal_property(activation, this, "descent")?.coerce_to_number(activation)?;
+        (descent - ascent) / 2.0
+    } else {
+        0.0
+    };
+
+    Ok(position.into())
+}
+
+/// Implements `TextLine.getAtomIndexAtCharIndex`.
+pub fn get_atom_index_at_char_index<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let char_index = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_i32(activation)?;
+    let begin =
+        internal_property(activation, this, "textBlockBeginIndex")?.coerce_to_i32(activation)?;
+    let length = internal_property(activation, this, "rawTextLength")?.coerce_to_i32(activation)?;
+
+    if char_index >= begin && char_index < begin + length {
+        Ok((char_index - begin).into())
+    } else {
+        Ok((-1).into())
+    }
+}
+
+/// Implements `TextLine.flushAtomData`.
+pub fn flush_atom_data<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `TextLine`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.text.engine"), "TextLine"),
+        Some(
+            QName::new(
+                Namespace::package("flash.display"),
+                "DisplayObjectContainer",
+            )
+            .into(),
+        ),
+        Method::from_builtin(instance_init, "<TextLine instance initializer>", mc),
+        Method::from_builtin(class_init, "<TextLine class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::FINAL | ClassAttributes::SEALED);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[
+        ("ascent", Some(ascent), None),
+        ("atomCount", Some(raw_text_length), None),
+        ("descent", Some(descent), None),
+        ("hasGraphicElement", Some(has_graphic_element), None),
+        ("mirrorRegions", Some(mirror_regions), None),
+        ("nextLine", Some(next_line), None),
+        ("previousLine", Some(previous_line), None),
+        ("rawTextLength", Some(raw_text_length), None),
+        ("specifiedWidth", Some(specified_width), None),
+        ("textBlock", Some(text_block), None),
+        ("textBlockBeginIndex", Some(text_block_begin_index), None),
+        ("textHeight", Some(text_height), None),
+        ("textWidth", Some(text_width), None),
+        ("unjustifiedTextWidth", Some(text_width), None),
+        ("validity", Some(validity), Some(set_validity)),
+    ];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("flushAtomData", flush_atom_data),
+        ("getAtomIndexAtCharIndex", get_atom_index_at_char_index),
+        ("getBaselinePosition", get_baseline_position),
+    ];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "userData"),
+        QName::new(Namespace::public(), "Object").into(),
+        None,
+    ));
+
+    const PRIVATE_INSTANCE_SLOTS: &[(&str, &str, &str, &str)] = &[
+        (NS_RUFFLE_INTERNAL, "ascent", "", "Number"),
+        (NS_RUFFLE_INTERNAL, "descent", "", "Number"),
+        (
+            NS_RUFFLE_INTERNAL,
+            "nextLine",
+            "flash.text.engine",
+            "TextLine",
+        ),
+        (
+            NS_RUFFLE_INTERNAL,
+            "previousLine",
+            "flash.text.engine",
+            "TextLine",
+        ),
+        (NS_RUFFLE_INTERNAL, "rawTextLength", "", "int"),
+        (NS_RUFFLE_INTERNAL, "specifiedWidth", "", "Number"),
+        (
+            NS_RUFFLE_INTERNAL,
+            "textBlock",
+            "flash.text.engine",
+            "TextBlock",
+        ),
+        (NS_RUFFLE_INTERNAL, "textBlockBeginIndex", "", "int"),
+        (NS_RUFFLE_INTERNAL, "textWidth", "", "Number"),
+        (NS_RUFFLE_INTERNAL, "validity", "", "String"),
+    ];
+    write.define_private_slot_instance_traits(PRIVATE_INSTANCE_SLOTS);
+
+    const CONSTANTS: &[(&str, i32)] = &[("MAX_LINE_WIDTH", 1000000)];
+    write.define_public_constant_int_class_traits(CONSTANTS);
+
+    class
+}
@@ -1,13 +1,15 @@
 //! `flash.text.Font` builtin/prototype
 
 use crate::avm2::activation::Activation;
+use crate::avm2::array::ArrayStorage;
 use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::NS_RUFFLE_INTERNAL;
 use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::names::{Namespace, QName};
-use crate::avm2::object::{Object, TObject};
+use crate::avm2::object::{ArrayObject, Object, TObject};
 use crate::avm2::value::Value;
 use crate::avm2::Error;
-use crate::character::Character;
+use crate::font::{Font, FontType};
 use crate::string::AvmString;
 use gc_arena::{GcCell, MutationContext};
 
@@ -33,65 +35,89 @@ pub fn class_init<'gc>(
     Ok(Value::Undefined)
 }
 
-/// Implements `Font.fontName`
-pub fn font_name<'gc>(
+/// The font of an embedded font class that the given object is an instance
+/// of.
+fn symbol_font<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
-) -> Result<Value<'gc>, Error> {
-    if let Some((movie, character_id)) = this.and_then(|this| this.instance_of()).and_then(|this| {
+) -> Option<Font<'gc>> {
+    let (movie, character_id) = this.and_then(|this| this.instance_of()).and_then(|this| {
         activation
             .context
             .library
             .avm2_class_registry()
             .class_symbol(this)
-    }) {
-        if let Some(Character::Font(font)) = activation
-            .context
-            .library
-            .library_for_movie_mut(movie)
-            .character_by_id(character_id)
-        {
-            return Ok(AvmString::new_utf8(
-                activation.context.gc_context,
-                font.descriptor().class(),
-            )
-            .into());
-        }
+    })?;
+
+    activation
+        .context
+        .library
+        .library_for_movie_mut(movie)
+        .get_font(character_id)
+}
+
+/// Get one of the properties of a `Font` created by `Font.enumerateFonts`.
+fn enumerated_property<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    name: &'static str,
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return this.get_property(
+            this,
+            &QName::new(Namespace::Private(NS_RUFFLE_INTERNAL.into()), name).into(),
+            activation,
+        );
     }
 
     Ok(Value::Undefined)
 }
 
+/// The `fontStyle` of a font with the given style.
+fn style_name(is_bold: bool, is_italic: bool) -> &'static str {
+    match (is_bold, is_italic) {
+        (false, false) => "regular",
+        (false, true) => "italic",
+        (true, false) => "bold",
+        (true, true) => "boldItalic",
+    }
+}
+
+/// The `fontType` of a font.
+fn type_name(font_type: FontType) -> &'static str {
+    match font_type {
+        FontType::Embedded => "embedded",
+        FontType::EmbeddedCFF => "embeddedCFF",
+        FontType::Device => "device",
+    }
+}
+
+/// Implements `Font.fontName`
+pub fn font_name<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(font) = symbol_font(activation, this) {
+        return Ok(
+            AvmString::new_utf8(activation.context.gc_context, font.descriptor().class()).into(),
+        );
+    }
+
+    enumerated_property(activation, this, "fontName")
+}
+
 /// Implements `Font.fontStyle`
 pub fn font_style<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    if let Some((movie, character_id)) = this.and_then(|this| this.instance_of()).and_then(|this| {
-        activation
-            .context
-            .library
-            .avm2_class_registry()
-            .class_symbol(this)
-    }) {
-        if let Some(Character::Font(font)) = activation
-            .context
-            .library
-            .library_for_movie_mut(movie)
-            .character_by_id(character_id)
-        {
-            return match (font.descriptor().bold(), font.descriptor().italic()) {
-                (false, false) => Ok("regular".into()),
-                (false, true) => Ok("italic".into()),
-                (true, false) => Ok("bold".into()),
-                (true, true) => Ok("boldItalic".into()),
-            };
-        }
+    if let Some(font) = symbol_font(activation, this) {
+        return Ok(style_name(font.descriptor().bold(), font.descriptor().italic()).into());
     }
 
-    Ok(Value::Undefined)
+    enumerated_property(activation, this, "fontStyle")
 }
 
 /// Implements `Font.fontType`
@@ -100,25 +126,11 @@ pub fn font_type<'gc>(
     this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    if let Some((movie, character_id)) = this.and_then(|this| this.instance_of()).and_then(|this| {
-        activation
-            .context
-            .library
-            .avm2_class_registry()
-            .class_symbol(this)
-    }) {
-        if let Some(Character::Font(_)) = activation
-            .context
-            .library
-            .library_for_movie_mut(movie)
-            .character_by_id(character_id)
-        {
-            //TODO: How do we distinguish between CFF and non-CFF embedded fonts?
-            return Ok("embedded".into());
-        }
+    if let Some(font) = symbol_font(activation, this) {
+        return Ok(type_name(font.font_type()).into());
     }
 
-    Ok(Value::Undefined)
+    enumerated_property(activation, this, "fontType")
 }
 
 /// Implements `Font.hasGlyphs`
@@ -127,48 +139,151 @@ pub fn has_glyphs<'gc>(
     this: Option<Object<'gc>>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    if let Some((movie, character_id)) = this.and_then(|this| this.instance_of()).and_then(|this| {
-        activation
-            .context
-            .library
-            .avm2_class_registry()
-            .class_symbol(this)
-    }) {
-        let my_str = args
-            .get(0)
-            .cloned()
-            .unwrap_or(Value::Undefined)
-            .coerce_to_string(activation)?;
-
-        if let Some(Character::Font(font)) = activation
-            .context
-            .library
-            .library_for_movie_mut(movie)
-            .character_by_id(character_id)
-        {
-            return Ok(font.has_glyphs_for_str(&my_str).into());
+    let font = match symbol_font(activation, this) {
+        Some(font) => Some(font),
+        None => {
+            let name = enumerated_property(activation, this, "fontName")?;
+            if matches!(name, Value::Undefined | Value::Null) {
+                return Ok(Value::Undefined);
+            }
+            let name = name
+                .coerce_to_string(activation)?
+                .to_utf8_lossy()
+                .into_owned();
+            let style =
+                enumerated_property(activation, this, "fontStyle")?.coerce_to_string(activation)?;
+            let is_bold = &style == b"bold" || &style == b"boldItalic";
+            let is_italic = &style == b"italic" || &style == b"boldItalic";
+            let font_type =
+                enumerated_property(activation, this, "fontType")?.coerce_to_string(activation)?;
+
+            let context = &mut activation.context;
+            if &font_type == b"device" {
+                context.library.system_font(
+                    context.gc_context,
+                    context.fonts,
+                    &name,
+                    is_bold,
+                    is_italic,
+                )
+            } else {
+                context.library.registered_font(&name, is_bold, is_italic)
+            }
         }
-    }
+    };
 
-    Ok(Value::Undefined)
+    let my_str = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+
+    match font {
+        Some(font) => {
+            font.load_device_glyphs(
+                &my_str,
+                activation.context.renderer,
+                activation.context.fonts,
+            );
+            Ok(font.has_glyphs_for_str(&my_str).into())
+        }
+        None => Ok(false.into()),
+    }
 }
 
-/// Stub `Font.enumerateFonts`
+/// Implements `Font.enumerateFonts`
 pub fn enumerate_fonts<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
-    _this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    Err("Font.enumerateFonts is a stub".into())
+    let enumerate_device_fonts = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_boolean();
+
+    let mut fonts: Vec<(String, &'static str, &'static str)> = activation
+        .context
+        .library
+        .registered_fonts()
+        .map(|font| {
+            let descriptor = font.descriptor();
+            (
+                descriptor.class().to_string(),
+                style_name(descriptor.bold(), descriptor.italic()),
+                type_name(font.font_type()),
+            )
+        })
+        .collect();
+    if enumerate_device_fonts {
+        fonts.extend(
+            activation
+                .context
+                .fonts
+                .font_names()
+                .into_iter()
+                .map(|name| (name, "regular", type_name(FontType::Device))),
+        );
+    }
+    fonts.sort();
+
+    let class = this
+        .and_then(|this| this.as_class_object())
+        .ok_or("Font.enumerateFonts called without the Font class")?;
+    let mut font_objects = Vec::with_capacity(fonts.len());
+    for (name, style, font_type) in fonts {
+        let mut font_object = class.construct(activation, &[])?;
+        let name = AvmString::new_utf8(activation.context.gc_context, name);
+        for (property, value) in [
+            ("fontName", Value::from(name)),
+            ("fontStyle", style.into()),
+            ("fontType", font_type.into()),
+        ] {
+            font_object.set_property(
+                font_object,
+                &QName::new(Namespace::Private(NS_RUFFLE_INTERNAL.into()), property).into(),
+                value,
+                activation,
+            )?;
+        }
+        font_objects.push(font_object.into());
+    }
+
+    Ok(ArrayObject::from_storage(activation, ArrayStorage::from_args(&font_objects))?.into())
 }
 
-/// Stub `Font.registerFont`
+/// Implements `Font.registerFont`
 pub fn register_font<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc, '_>,
     _this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    Err("Font.registerFont is a stub".into())
+    let font = match args.get(0) {
+        Some(Value::Object(class)) => class.as_class_object().and_then(|class| {
+            let (movie, character_id) = activation
+                .context
+                .library
+                .avm2_class_registry()
+                .class_symbol(class)?;
+            activation
+                .context
+                .library
+                .library_for_movie_mut(movie)
+                .get_font(character_id)
+        }),
+        _ => None,
+    };
+
+    match font {
+        Some(font) => {
+            activation.context.library.register_font(font);
+            Ok(Value::Undefined)
+        }
+        None => Err(
+            "ArgumentError: Error #1508: The value specified for argument font is invalid.".into(),
+        ),
+    }
 }
 
 /// Construct `Font`'s class.
@@ -199,6 +314,13 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
     const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[("hasGlyphs", has_glyphs)];
     write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
 
+    const PRIVATE_INSTANCE_SLOTS: &[(&str, &str, &str, &str)] = &[
+        (NS_RUFFLE_INTERNAL, "fontName", "", "String"),
+        (NS_RUFFLE_INTERNAL, "fontStyle", "", "String"),
+        (NS_RUFFLE_INTERNAL, "fontType", "", "String"),
+    ];
+    write.define_private_slot_instance_traits(PRIVATE_INSTANCE_SLOTS);
+
     const PUBLIC_CLASS_METHODS: &[(&str, NativeMethodImpl)] = &[
         ("enumerateFonts", enumerate_fonts),
         ("registerFont", register_font),
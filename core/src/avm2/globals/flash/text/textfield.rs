@@ -2,6 +2,7 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::text::textlinemetrics::create_text_line_metrics;
 use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::names::{Namespace, QName};
 use crate::avm2::object::{Object, TObject, TextFormatObject};
@@ -604,6 +605,168 @@ pub fn set_type<'gc>(
     Ok(Value::Undefined)
 }
 
+pub fn anti_alias_type<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        return Ok(this.render_settings().anti_alias_type().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_anti_alias_type<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        let anti_alias_type = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_string(activation)?;
+
+        let mut settings = this.render_settings();
+        if !settings.set_anti_alias_type(&anti_alias_type) {
+            return Err(
+                "ArgumentError: Error #2008: Parameter antiAliasType must be one of the accepted values."
+                    .into(),
+            );
+        }
+        this.set_render_settings(activation.context.gc_context, settings);
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn grid_fit_type<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        return Ok(this.render_settings().grid_fit_type().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_grid_fit_type<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        let grid_fit_type = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_string(activation)?;
+
+        let mut settings = this.render_settings();
+        if !settings.set_grid_fit_type(&grid_fit_type) {
+            return Err(
+                "ArgumentError: Error #2008: Parameter gridFitType must be one of the accepted values."
+                    .into(),
+            );
+        }
+        this.set_render_settings(activation.context.gc_context, settings);
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn sharpness<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        return Ok(this.render_settings().sharpness.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_sharpness<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        let sharpness = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_number(activation)?;
+
+        let mut settings = this.render_settings();
+        settings.set_sharpness(sharpness);
+        this.set_render_settings(activation.context.gc_context, settings);
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn thickness<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        return Ok(this.render_settings().thickness.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_thickness<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        let thickness = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_number(activation)?;
+
+        let mut settings = this.render_settings();
+        settings.set_thickness(thickness);
+        this.set_render_settings(activation.context.gc_context, settings);
+    }
+
+    Ok(Value::Undefined)
+}
+
 pub fn word_wrap<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
     this: Option<Object<'gc>>,
@@ -850,6 +1013,255 @@ pub fn set_text_format<'gc>(
     Ok(Value::Undefined)
 }
 
+pub fn scroll_h<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        return Ok(this.hscroll().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_scroll_h<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        let hscroll = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation)? as f64;
+        let clamped = hscroll.clamp(0.0, this.maxhscroll());
+        this.set_hscroll(clamped, &mut activation.context);
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn max_scroll_h<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        return Ok(this.maxhscroll().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn scroll_v<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        return Ok(this.scroll().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_scroll_v<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        let scroll = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation)?;
+        this.set_scroll(scroll as f64, &mut activation.context);
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn max_scroll_v<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        return Ok(this.maxscroll().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn bottom_scroll_v<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        return Ok(this.bottom_scroll().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn num_lines<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        return Ok(this.num_lines().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Coerce the line index argument of a line method, checking that the line
+/// exists.
+fn line_index<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: EditText<'gc>,
+    args: &[Value<'gc>],
+) -> Result<usize, Error> {
+    let line = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_i32(activation)?;
+
+    if line < 0 || line as usize >= this.num_lines() {
+        return Err("RangeError: The supplied index is out of bounds.".into());
+    }
+
+    Ok(line as usize)
+}
+
+pub fn get_line_metrics<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        let line = line_index(activation, this, args)?;
+        if let Some(metrics) = this.line_metrics(line) {
+            return create_text_line_metrics(activation, metrics);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn get_line_offset<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        let line = line_index(activation, this, args)?;
+        if let Some(range) = this.line_range(line) {
+            return Ok(range.start.into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn get_line_length<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        let line = line_index(activation, this, args)?;
+        if let Some(range) = this.line_range(line) {
+            return Ok(range.len().into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn get_line_text<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        let line = line_index(activation, this, args)?;
+        if let Some(range) = this.line_range(line) {
+            let text = this.text();
+            let line_text = text.slice(range).unwrap_or_default();
+            return Ok(AvmString::new(activation.context.gc_context, line_text).into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn get_line_index_of_char<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        let index = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation)?;
+
+        let line = usize::try_from(index)
+            .ok()
+            .and_then(|index| this.line_index_of_char(index));
+        return Ok(line.map_or(-1, |line| line as i32).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
 /// Construct `TextField`'s class.
 pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
     let class = Class::new(
@@ -869,6 +1281,11 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         Option<NativeMethodImpl>,
         Option<NativeMethodImpl>,
     )] = &[
+        (
+            "antiAliasType",
+            Some(anti_alias_type),
+            Some(set_anti_alias_type),
+        ),
         ("autoSize", Some(autosize), Some(set_autosize)),
         (
             "backgroundColor",
@@ -876,6 +1293,7 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
             Some(set_background_color),
         ),
         ("border", Some(border), Some(set_border)),
+        ("bottomScrollV", Some(bottom_scroll_v), None),
         ("borderColor", Some(border_color), Some(set_border_color)),
         (
             "defaultTextFormat",
@@ -888,14 +1306,22 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
             Some(set_display_as_password),
         ),
         ("embedFonts", Some(embed_fonts), Some(set_embed_fonts)),
+        ("gridFitType", Some(grid_fit_type), Some(set_grid_fit_type)),
         ("htmlText", Some(html_text), Some(set_html_text)),
         ("length", Some(length), None),
+        ("maxScrollH", Some(max_scroll_h), None),
+        ("maxScrollV", Some(max_scroll_v), None),
         ("multiline", Some(multiline), Some(set_multiline)),
+        ("numLines", Some(num_lines), None),
+        ("scrollH", Some(scroll_h), Some(set_scroll_h)),
+        ("scrollV", Some(scroll_v), Some(set_scroll_v)),
         ("selectable", Some(selectable), Some(set_selectable)),
+        ("sharpness", Some(sharpness), Some(set_sharpness)),
         ("text", Some(text), Some(set_text)),
         ("textColor", Some(text_color), Some(set_text_color)),
         ("textHeight", Some(text_height), None),
         ("textWidth", Some(text_width), None),
+        ("thickness", Some(thickness), Some(set_thickness)),
         ("type", Some(get_type), Some(set_type)),
         ("wordWrap", Some(word_wrap), Some(set_word_wrap)),
     ];
@@ -903,6 +1329,11 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
 
     const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
         ("appendText", append_text),
+        ("getLineIndexOfChar", get_line_index_of_char),
+        ("getLineLength", get_line_length),
+        ("getLineMetrics", get_line_metrics),
+        ("getLineOffset", get_line_offset),
+        ("getLineText", get_line_text),
         ("getTextFormat", get_text_format),
         ("replaceSelectedText", replace_selected_text),
         ("replaceText", replace_text),
@@ -0,0 +1,90 @@
+//! `flash.text.TextLineMetrics` builtin/prototype
+
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::Method;
+use crate::avm2::{Activation, Error, Namespace, Object, QName, TObject, Value};
+use crate::display_object::LineMetrics;
+use gc_arena::{GcCell, MutationContext};
+
+/// The properties of a `TextLineMetrics`, in constructor argument order.
+const PROPERTIES: &[&str] = &["x", "width", "height", "ascent", "descent", "leading"];
+
+pub fn create_text_line_metrics<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    metrics: LineMetrics,
+) -> Result<Value<'gc>, Error> {
+    let text_line_metrics_class = activation.context.avm2.classes().textlinemetrics;
+
+    let args = [
+        Value::Number(metrics.x),
+        Value::Number(metrics.width),
+        Value::Number(metrics.height),
+        Value::Number(metrics.ascent),
+        Value::Number(metrics.descent),
+        Value::Number(metrics.leading),
+    ];
+    let new_text_line_metrics = text_line_metrics_class.construct(activation, &args)?;
+
+    Ok(new_text_line_metrics.into())
+}
+
+/// Implements `flash.text.TextLineMetrics`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        for (i, name) in PROPERTIES.iter().enumerate() {
+            let value = args
+                .get(i)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_number(activation)?;
+            this.set_property(
+                this,
+                &QName::new(Namespace::public(), *name).into(),
+                value.into(),
+                activation,
+            )?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.text.TextLineMetrics`'s class initializer.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `TextLineMetrics`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.text"), "TextLineMetrics"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<TextLineMetrics instance initializer>", mc),
+        Method::from_builtin(class_init, "<TextLineMetrics class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+    write.set_attributes(ClassAttributes::SEALED);
+
+    const PUBLIC_INSTANCE_NUMBER_SLOTS: &[(&str, Option<f64>)] = &[
+        ("x", None),
+        ("width", None),
+        ("height", None),
+        ("ascent", None),
+        ("descent", None),
+        ("leading", None),
+    ];
+    write.define_public_slot_number_instance_traits(PUBLIC_INSTANCE_NUMBER_SLOTS);
+
+    class
+}
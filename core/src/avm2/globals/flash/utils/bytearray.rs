@@ -1,4 +1,5 @@
 use crate::avm2::activation::Activation;
+use crate::avm2::amf::{Amf0Writer, Amf3Reader, Amf3Writer};
 use crate::avm2::array::ArrayStorage;
 use crate::avm2::bytearray::{ByteArrayStorage, CompressionAlgorithm, Endian, ObjectEncoding};
 use crate::avm2::class::{Class, ClassAttributes};
@@ -12,7 +13,6 @@ use crate::string::AvmString;
 use encoding_rs::Encoding;
 use encoding_rs::UTF_8;
 use flash_lso::amf0::read::AMF0Decoder;
-use flash_lso::amf3::read::AMF3Decoder;
 use flash_lso::types::Value as AmfValue;
 use gc_arena::{GcCell, MutationContext};
 
@@ -792,9 +792,12 @@ pub fn uncompress<'gc>(
                 .get(0)
                 .unwrap_or(&"zlib".into())
                 .coerce_to_string(activation)?;
-            let buffer = bytearray.decompress(algorithm.parse()?)?;
+            let buffer = bytearray
+                .decompress(algorithm.parse()?)
+                .map_err(|_| "IOError: Error #2058: There was an error decompressing the data.")?;
             bytearray.clear();
             bytearray.write_bytes(&buffer)?;
+            bytearray.set_position(0);
         }
     }
 
@@ -824,9 +827,12 @@ pub fn inflate<'gc>(
 ) -> Result<Value<'gc>, Error> {
     if let Some(this) = this {
         if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
-            let buffer = bytearray.decompress(CompressionAlgorithm::Deflate)?;
+            let buffer = bytearray
+                .decompress(CompressionAlgorithm::Deflate)
+                .map_err(|_| "IOError: Error #2058: There was an error decompressing the data.")?;
             bytearray.clear();
             bytearray.write_bytes(&buffer)?;
+            bytearray.set_position(0);
         }
     }
 
@@ -840,25 +846,34 @@ pub fn read_object<'gc>(
 ) -> Result<Value<'gc>, Error> {
     if let Some(this) = this {
         if let Some(bytearray) = this.as_bytearray() {
-            let bytes = bytearray.read_at(bytearray.bytes_available(), bytearray.position())?;
-            let (bytes_left, value) = match bytearray.object_encoding() {
+            let position = bytearray.position();
+            let bytes = bytearray
+                .read_at(bytearray.bytes_available(), position)?
+                .to_vec();
+            let encoding = bytearray.object_encoding();
+            drop(bytearray);
+
+            let (bytes_read, value) = match encoding {
                 ObjectEncoding::Amf0 => {
                     let mut decoder = AMF0Decoder::default();
                     let (extra, amf) = decoder
-                        .parse_single_element(bytes)
+                        .parse_single_element(&bytes)
                         .map_err(|_| "Error: Invalid object")?;
-                    (extra.len(), deserialize_value(activation, &amf)?)
+                    (
+                        bytes.len() - extra.len(),
+                        deserialize_value(activation, &amf)?,
+                    )
                 }
                 ObjectEncoding::Amf3 => {
-                    let mut decoder = AMF3Decoder::default();
-                    let (extra, amf) = decoder
-                        .parse_single_element(bytes)
-                        .map_err(|_| "Error: Invalid object")?;
-                    (extra.len(), deserialize_value(activation, &amf)?)
+                    let mut reader = Amf3Reader::new(&bytes);
+                    let value = reader.read_value(activation)?;
+                    (reader.position(), value)
                 }
             };
 
-            bytearray.set_position(bytearray.len() - bytes_left);
+            if let Some(bytearray) = this.as_bytearray() {
+                bytearray.set_position(position + bytes_read);
+            }
             return Ok(value);
         }
     }
@@ -866,6 +881,31 @@ pub fn read_object<'gc>(
     Ok(Value::Undefined)
 }
 
+pub fn write_object<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let encoding = match this.as_bytearray() {
+            Some(bytearray) => bytearray.object_encoding(),
+            None => return Ok(Value::Undefined),
+        };
+
+        let value = args.get(0).cloned().unwrap_or(Value::Undefined);
+        let bytes = match encoding {
+            ObjectEncoding::Amf0 => Amf0Writer::serialize(activation, value)?,
+            ObjectEncoding::Amf3 => Amf3Writer::serialize(activation, value)?,
+        };
+
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            bytearray.write_bytes(&bytes)?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
 pub fn object_encoding<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
     this: Option<Object<'gc>>,
@@ -902,6 +942,26 @@ pub fn set_object_encoding<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Implements `ByteArray.shareable`.
+///
+/// Byte arrays are never shared between workers, as workers aren't supported.
+pub fn shareable<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(false.into())
+}
+
+pub fn set_shareable<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    log::warn!("ByteArray.shareable - not implemented");
+    Ok(Value::Undefined)
+}
+
 pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
     let class = Class::new(
         QName::new(Namespace::package("flash.utils"), "ByteArray"),
@@ -948,6 +1008,7 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         ("writeUTFBytes", write_utf_bytes),
         ("readUTFBytes", read_utf_bytes),
         ("readObject", read_object),
+        ("writeObject", write_object),
     ];
     write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
 
@@ -965,6 +1026,7 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
             Some(object_encoding),
             Some(set_object_encoding),
         ),
+        ("shareable", Some(shareable), Some(set_shareable)),
     ];
     write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
 
@@ -4,19 +4,35 @@ use crate::avm2::activation::Activation;
 use crate::avm2::class::Class;
 use crate::avm2::method::Method;
 use crate::avm2::names::{Namespace, QName};
-use crate::avm2::object::{dictionary_allocator, Object};
+use crate::avm2::object::{dictionary_allocator, Object, TObject};
 use crate::avm2::value::Value;
 use crate::avm2::Error;
 use gc_arena::{GcCell, MutationContext};
 
 /// Implements `flash.utils.Dictionary`'s instance constructor.
+///
+/// TODO: `weakKeys` is only stored, so that it survives serialization. Keys
+/// are never collected, as our garbage collector has no weak references.
 pub fn instance_init<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
     if let Some(this) = this {
         activation.super_init(this, &[])?;
+
+        if let Some(dictionary) = this.as_dictionary_object() {
+            let weak_keys = args
+                .get(0)
+                .cloned()
+                .unwrap_or(Value::Bool(false))
+                .coerce_to_boolean();
+
+            if weak_keys {
+                log::warn!("Dictionary: weakKeys is not yet implemented, keys are held strongly");
+            }
+            dictionary.set_weak_keys(activation.context.gc_context, weak_keys);
+        }
     }
 
     Ok(Value::Undefined)
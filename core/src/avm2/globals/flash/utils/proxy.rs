@@ -8,6 +8,16 @@ use crate::avm2::value::Value;
 use crate::avm2::Error;
 use gc_arena::{GcCell, MutationContext};
 
+/// Construct the error thrown when a `flash_proxy` method that subclasses are
+/// meant to override is called on `Proxy` itself.
+fn not_overridden(method: &str) -> Error {
+    format!(
+        "IllegalOperationError: Error #2088: The Proxy class does not implement {}. It must be overridden by a subclass.",
+        method
+    )
+    .into()
+}
+
 /// Implements `flash.utils.Proxy`'s instance constructor.
 pub fn instance_init<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
@@ -32,7 +42,7 @@ pub fn get_property<'gc>(
     _this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    Err("getproperty is not implemented for this Proxy".into())
+    Err(not_overridden("getProperty"))
 }
 
 /// Implements `Proxy.setProperty`
@@ -41,7 +51,7 @@ pub fn set_property<'gc>(
     _this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    Err("setproperty is not implemented for this Proxy".into())
+    Err(not_overridden("setProperty"))
 }
 
 /// Implements `Proxy.deleteProperty`
@@ -50,7 +60,7 @@ pub fn delete_property<'gc>(
     _this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    Err("deleteproperty is not implemented for this Proxy".into())
+    Err(not_overridden("deleteProperty"))
 }
 
 /// Implements `Proxy.callProperty`
@@ -59,7 +69,7 @@ pub fn call_property<'gc>(
     _this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    Err("callproperty is not implemented for this Proxy".into())
+    Err(not_overridden("callProperty"))
 }
 
 /// Implements `Proxy.hasProperty`
@@ -68,16 +78,19 @@ pub fn has_property<'gc>(
     _this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    Err("hasproperty is not implemented for this Proxy".into())
+    Err(not_overridden("hasProperty"))
 }
 
 /// Implements `Proxy.isAttribute`
+///
+/// Our `QName`s do not record whether they named an attribute, so no name is
+/// ever considered one.
 pub fn is_attribute<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
     _this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    Err("isattribute is not implemented for this Proxy".into())
+    Ok(false.into())
 }
 
 /// Implements `Proxy.getDescendants`
@@ -86,7 +99,7 @@ pub fn get_descendants<'gc>(
     _this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    Err("getdescendants is not implemented for this Proxy".into())
+    Err(not_overridden("getDescendants"))
 }
 
 /// Implements `Proxy.nextNameIndex`
@@ -95,7 +108,7 @@ pub fn next_name_index<'gc>(
     _this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    Err("hasnext/nextNameIndex is not implemented for this Proxy".into())
+    Err(not_overridden("nextNameIndex"))
 }
 
 /// Implements `Proxy.nextName`
@@ -104,7 +117,7 @@ pub fn next_name<'gc>(
     _this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    Err("nextname is not implemented for this Proxy".into())
+    Err(not_overridden("nextName"))
 }
 
 /// Implements `Proxy.nextValue`
@@ -113,7 +126,7 @@ pub fn next_value<'gc>(
     _this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    Err("nextvalue is not implemented for this Proxy".into())
+    Err(not_overridden("nextValue"))
 }
 
 pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
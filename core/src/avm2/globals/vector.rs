@@ -9,7 +9,9 @@ use crate::avm2::globals::array::{
 use crate::avm2::globals::NS_VECTOR;
 use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::names::{Namespace, QName};
-use crate::avm2::object::{vector_allocator, FunctionObject, Object, TObject, VectorObject};
+use crate::avm2::object::{
+    vector_allocator, ClassObject, FunctionObject, Object, TObject, VectorObject,
+};
 use crate::avm2::value::Value;
 use crate::avm2::vector::VectorStorage;
 use crate::avm2::Error;
@@ -181,6 +183,61 @@ pub fn specialized_class_init<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Implements calling a specialized `Vector` class as a function.
+///
+/// Arrays and vectors of any type are copied into a new vector of the called
+/// class, with each element coerced to its value type. Vectors that are
+/// already of the called class are returned as-is.
+pub fn convert<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    class: ClassObject<'gc>,
+    value: Value<'gc>,
+) -> Result<Value<'gc>, Error> {
+    let value_type = class
+        .as_class_params()
+        .ok_or("TypeError: Error #1127: Type application attempted on a non-parameterized type.")?
+        .unwrap_or_else(|| activation.avm2().classes().object);
+    let coercion_error = || -> Error {
+        format!(
+            "TypeError: Error #1034: Type Coercion failed: cannot convert {:?} to {:?}.",
+            value,
+            class.inner_class_definition().read().name()
+        )
+        .into()
+    };
+
+    let object = match value {
+        Value::Object(object) => object,
+        _ => return Err(coercion_error()),
+    };
+
+    if object.is_of_type(class, activation)? {
+        return Ok(value);
+    }
+
+    let values: Vec<Value<'gc>> = if let Some(array) = object.as_array_storage() {
+        array
+            .iter()
+            .map(|v| v.unwrap_or(Value::Undefined))
+            .collect()
+    } else if let Some(vector) = object.as_vector_storage() {
+        vector.iter().collect()
+    } else {
+        return Err(coercion_error());
+    };
+
+    let mut storage = Vec::with_capacity(values.len());
+    for value in values {
+        storage.push(VectorStorage::coerce(value, value_type, activation)?);
+    }
+
+    Ok(VectorObject::from_vector(
+        VectorStorage::from_values(storage, false, value_type),
+        activation,
+    )?
+    .into())
+}
+
 /// `Vector.length` getter
 pub fn length<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
@@ -1046,3 +1103,175 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
 
     class
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::avm2::array::ArrayStorage;
+    use crate::avm2::names::{Namespace, QName};
+    use crate::avm2::object::{ArrayObject, Object, TObject};
+    use crate::avm2::test_utils::with_avm2;
+    use crate::avm2::Value;
+
+    /// Tests that fixed vectors can't change length, and that accesses
+    /// outside of any vector are range errors.
+    #[test]
+    fn fixed_length() {
+        with_avm2(|activation| {
+            let int_class = activation.avm2().classes().int;
+            let int_vector_class = activation
+                .avm2()
+                .classes()
+                .vector
+                .apply(activation, &[int_class.into()])?;
+            let mut vector = int_vector_class.construct(activation, &[3.into(), true.into()])?;
+
+            let error = vector
+                .call_property(
+                    &QName::new(Namespace::as3_namespace(), "push").into(),
+                    &[1.into()],
+                    activation,
+                )
+                .unwrap_err();
+            assert!(error.to_string().contains("Error #1126"), "{}", error);
+
+            let error = vector
+                .set_property(
+                    vector,
+                    &QName::new(Namespace::public(), "length").into(),
+                    5.into(),
+                    activation,
+                )
+                .unwrap_err();
+            assert!(error.to_string().contains("Error #1126"), "{}", error);
+
+            let error = vector
+                .set_property(
+                    vector,
+                    &QName::new(Namespace::public(), "3").into(),
+                    1.into(),
+                    activation,
+                )
+                .unwrap_err();
+            assert!(error.to_string().contains("Error #1125"), "{}", error);
+
+            vector.set_property(
+                vector,
+                &QName::new(Namespace::public(), "2").into(),
+                1.into(),
+                activation,
+            )?;
+            assert_eq!(vector.as_vector_storage().unwrap().length(), 3);
+
+            // Unfixing the vector allows it to grow again.
+            vector.set_property(
+                vector,
+                &QName::new(Namespace::public(), "fixed").into(),
+                false.into(),
+                activation,
+            )?;
+            vector.set_property(
+                vector,
+                &QName::new(Namespace::public(), "3").into(),
+                1.into(),
+                activation,
+            )?;
+            assert_eq!(vector.as_vector_storage().unwrap().length(), 4);
+
+            let error = vector
+                .get_property(
+                    vector,
+                    &QName::new(Namespace::public(), "10").into(),
+                    activation,
+                )
+                .unwrap_err();
+            assert!(error.to_string().contains("Error #1125"), "{}", error);
+
+            Ok(())
+        });
+    }
+
+    /// Tests that values stored into typed vectors are coerced to their
+    /// value type.
+    #[test]
+    fn typed_coercion() {
+        with_avm2(|activation| {
+            let int_class = activation.avm2().classes().int;
+            let int_vector_class = activation
+                .avm2()
+                .classes()
+                .vector
+                .apply(activation, &[int_class.into()])?;
+            let mut vector = int_vector_class.construct(activation, &[2.into()])?;
+
+            let first = QName::new(Namespace::public(), "0").into();
+            vector.set_property(vector, &first, 3.7.into(), activation)?;
+            assert_eq!(vector.get_property(vector, &first, activation)?, 3.into());
+            vector.set_property(vector, &first, Value::Undefined, activation)?;
+            assert_eq!(vector.get_property(vector, &first, activation)?, 0.into());
+            vector.set_property(vector, &first, "12".into(), activation)?;
+            assert_eq!(vector.get_property(vector, &first, activation)?, 12.into());
+
+            let sprite_class = activation.avm2().classes().sprite;
+            let sprite_vector_class = activation
+                .avm2()
+                .classes()
+                .vector
+                .apply(activation, &[sprite_class.into()])?;
+            let mut vector = sprite_vector_class.construct(activation, &[1.into()])?;
+            let object = activation
+                .avm2()
+                .classes()
+                .object
+                .construct(activation, &[])?;
+            let error = vector
+                .set_property(vector, &first, object.into(), activation)
+                .unwrap_err();
+            assert!(error.to_string().contains("Error #1034"), "{}", error);
+            vector.set_property(vector, &first, Value::Undefined, activation)?;
+            assert_eq!(
+                vector.get_property(vector, &first, activation)?,
+                Value::Null
+            );
+
+            Ok(())
+        });
+    }
+
+    /// Tests calling a vector class as a function to convert an array.
+    #[test]
+    fn convert_array() {
+        with_avm2(|activation| {
+            let int_class = activation.avm2().classes().int;
+            let int_vector_class = activation
+                .avm2()
+                .classes()
+                .vector
+                .apply(activation, &[int_class.into()])?;
+
+            let array = ArrayObject::from_storage(
+                activation,
+                ArrayStorage::from_args(&[1.5.into(), "2".into(), Value::Null]),
+            )?;
+            let vector = int_vector_class
+                .call(None, &[array.into()], activation)?
+                .coerce_to_object(activation)?;
+            assert!(vector.is_of_type(int_vector_class, activation)?);
+            let values: Vec<Value<'_>> = vector.as_vector_storage().unwrap().iter().collect();
+            assert_eq!(values, vec![1.into(), 2.into(), 0.into()]);
+            assert!(!vector.as_vector_storage().unwrap().is_fixed());
+
+            // Vectors of the called class are returned as they are.
+            let same = int_vector_class
+                .call(None, &[vector.into()], activation)?
+                .coerce_to_object(activation)?;
+            assert!(Object::ptr_eq(same, vector));
+
+            let error = int_vector_class
+                .call(None, &[5.into()], activation)
+                .unwrap_err();
+            assert!(error.to_string().contains("Error #1034"), "{}", error);
+
+            Ok(())
+        });
+    }
+}
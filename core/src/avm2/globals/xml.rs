@@ -2,15 +2,49 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::class::Class;
-use crate::avm2::method::{Method, ParamConfig};
-use crate::avm2::names::{Namespace, QName};
-use crate::avm2::object::{xml_allocator, Object};
+use crate::avm2::e4x::{E4XNode, E4XNodeKind};
+use crate::avm2::method::{Method, NativeMethodImpl, ParamConfig};
+use crate::avm2::names::{Multiname, Namespace, QName};
+use crate::avm2::object::{
+    xml_allocator, xml_nodes, NamespaceObject, Object, QNameObject, TObject, XmlListObject,
+    XmlObject,
+};
 use crate::avm2::value::Value;
 use crate::avm2::Error;
+use crate::string::AvmString;
 use gc_arena::{GcCell, MutationContext};
 
 /// Implements `XML`'s instance initializer.
 pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(xml) = this.and_then(|this| this.as_xml_object()) {
+        let value = args.get(0).cloned().unwrap_or(Value::Undefined);
+        let mc = activation.context.gc_context;
+
+        // E4X objects are copied, and other values are parsed as markup.
+        let nodes = match value {
+            Value::Undefined | Value::Null => Vec::new(),
+            value => value_to_nodes(activation, value)?
+                .iter()
+                .map(|node| node.deep_copy(mc))
+                .collect(),
+        };
+
+        match nodes.as_slice() {
+            [] => {}
+            [node] => xml.set_node(activation.context.gc_context, *node),
+            _ => return Err("TypeError: Error #1088: The markup in the document following the root element must be well-formed.".into()),
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML`'s class initializer
+pub fn class_init<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
     _this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
@@ -18,15 +52,703 @@ pub fn instance_init<'gc>(
     Ok(Value::Undefined)
 }
 
-/// Implements `XML`'s class initializer
-pub fn class_init<'gc>(
+/// The node of the `XML` object a method was called on.
+fn this_node<'gc>(this: Option<Object<'gc>>) -> Option<E4XNode<'gc>> {
+    this.and_then(|this| this.as_xml_object())
+        .map(|xml| xml.node())
+}
+
+/// Convert a value passed to an XML method to XML nodes.
+///
+/// `XML` and `XMLList` objects yield their nodes, and other values are parsed
+/// as markup.
+pub fn value_to_nodes<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<Vec<E4XNode<'gc>>, Error> {
+    if let Value::Object(object) = value {
+        if let Some(nodes) = xml_nodes(object) {
+            return Ok(nodes);
+        }
+    }
+
+    let markup = value.coerce_to_string(activation)?;
+    E4XNode::parse(
+        activation.context.gc_context,
+        &markup,
+        activation.avm2().xml_settings(),
+        activation.default_xml_namespace(),
+    )
+}
+
+/// Convert a name passed to an XML method to a multiname.
+///
+/// `QName` objects name a property in their namespace, and other values name
+/// a public property.
+pub fn name_to_multiname<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    name: Value<'gc>,
+) -> Result<Multiname<'gc>, Error> {
+    if let Value::Object(object) = name {
+        if let Some(qname_object) = object.as_qname_object() {
+            if let Some(qname) = qname_object.qname() {
+                return Ok((*qname).into());
+            }
+        }
+    }
+
+    let local_name = name.coerce_to_string(activation)?;
+    Ok(QName::dynamic_name(local_name).into())
+}
+
+/// Implements `XML.ignoreComments`'s getter.
+pub fn ignore_comments<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(activation.avm2().xml_settings().ignore_comments.into())
+}
+
+/// Implements `XML.ignoreComments`'s setter.
+pub fn set_ignore_comments<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let mut settings = activation.avm2().xml_settings();
+    settings.ignore_comments = args.get(0).unwrap_or(&Value::Undefined).coerce_to_boolean();
+    activation.context.avm2.set_xml_settings(settings);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML.ignoreProcessingInstructions`'s getter.
+pub fn ignore_processing_instructions<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(activation
+        .avm2()
+        .xml_settings()
+        .ignore_processing_instructions
+        .into())
+}
+
+/// Implements `XML.ignoreProcessingInstructions`'s setter.
+pub fn set_ignore_processing_instructions<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let mut settings = activation.avm2().xml_settings();
+    settings.ignore_processing_instructions =
+        args.get(0).unwrap_or(&Value::Undefined).coerce_to_boolean();
+    activation.context.avm2.set_xml_settings(settings);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML.ignoreWhitespace`'s getter.
+pub fn ignore_whitespace<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(activation.avm2().xml_settings().ignore_whitespace.into())
+}
+
+/// Implements `XML.ignoreWhitespace`'s setter.
+pub fn set_ignore_whitespace<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let mut settings = activation.avm2().xml_settings();
+    settings.ignore_whitespace = args.get(0).unwrap_or(&Value::Undefined).coerce_to_boolean();
+    activation.context.avm2.set_xml_settings(settings);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML.prettyPrinting`'s getter.
+pub fn pretty_printing<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(activation.avm2().xml_settings().pretty_printing.into())
+}
+
+/// Implements `XML.prettyPrinting`'s setter.
+pub fn set_pretty_printing<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let mut settings = activation.avm2().xml_settings();
+    settings.pretty_printing = args.get(0).unwrap_or(&Value::Undefined).coerce_to_boolean();
+    activation.context.avm2.set_xml_settings(settings);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML.prettyIndent`'s getter.
+pub fn pretty_indent<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(activation.avm2().xml_settings().pretty_indent.into())
+}
+
+/// Implements `XML.prettyIndent`'s setter.
+pub fn set_pretty_indent<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let mut settings = activation.avm2().xml_settings();
+    settings.pretty_indent = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_i32(activation)?;
+    activation.context.avm2.set_xml_settings(settings);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML.appendChild`
+pub fn append_child<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(node) = this_node(this) {
+        let value = args.get(0).cloned().unwrap_or(Value::Undefined);
+        for child in value_to_nodes(activation, value)? {
+            node.append_child(activation.context.gc_context, child)?;
+        }
+    }
+
+    Ok(this.map(Value::from).unwrap_or(Value::Undefined))
+}
+
+/// Implements `XML.prependChild`
+pub fn prepend_child<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(node) = this_node(this) {
+        let value = args.get(0).cloned().unwrap_or(Value::Undefined);
+        for (i, child) in value_to_nodes(activation, value)?.into_iter().enumerate() {
+            node.insert_child(activation.context.gc_context, i, child)?;
+        }
+    }
+
+    Ok(this.map(Value::from).unwrap_or(Value::Undefined))
+}
+
+/// Find the index of the child of `node` that an argument of
+/// `insertChildAfter` or `insertChildBefore` refers to.
+fn reference_child_index<'gc>(node: E4XNode<'gc>, reference: Value<'gc>) -> Option<usize> {
+    let reference = match reference {
+        Value::Object(object) => xml_nodes(object)?,
+        _ => return None,
+    };
+    let reference = reference.first()?;
+
+    node.children()
+        .iter()
+        .position(|child| E4XNode::ptr_eq(*child, *reference))
+}
+
+/// Implements `XML.insertChildAfter`
+pub fn insert_child_after<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(node) = this_node(this) {
+        let reference = args.get(0).cloned().unwrap_or(Value::Undefined);
+        let index = if matches!(reference, Value::Null) {
+            0
+        } else {
+            match reference_child_index(node, reference) {
+                Some(index) => index + 1,
+                None => return Ok(Value::Undefined),
+            }
+        };
+
+        let value = args.get(1).cloned().unwrap_or(Value::Undefined);
+        for (i, child) in value_to_nodes(activation, value)?.into_iter().enumerate() {
+            node.insert_child(activation.context.gc_context, index + i, child)?;
+        }
+    }
+
+    Ok(this.map(Value::from).unwrap_or(Value::Undefined))
+}
+
+/// Implements `XML.insertChildBefore`
+pub fn insert_child_before<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(node) = this_node(this) {
+        let reference = args.get(0).cloned().unwrap_or(Value::Undefined);
+        let index = if matches!(reference, Value::Null) {
+            node.children().len()
+        } else {
+            match reference_child_index(node, reference) {
+                Some(index) => index,
+                None => return Ok(Value::Undefined),
+            }
+        };
+
+        let value = args.get(1).cloned().unwrap_or(Value::Undefined);
+        for (i, child) in value_to_nodes(activation, value)?.into_iter().enumerate() {
+            node.insert_child(activation.context.gc_context, index + i, child)?;
+        }
+    }
+
+    Ok(this.map(Value::from).unwrap_or(Value::Undefined))
+}
+
+/// Implements `XML.attribute`
+pub fn attribute<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let nodes = match this_node(this) {
+        Some(node) => {
+            let name = args.get(0).cloned().unwrap_or(Value::Undefined);
+            let name = name_to_multiname(activation, name)?.into_attribute();
+            node.children_by_name(&name, activation.default_xml_namespace())
+        }
+        None => Vec::new(),
+    };
+
+    Ok(XmlListObject::new(activation, nodes)?.into())
+}
+
+/// Implements `XML.attributes`
+pub fn attributes<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let nodes = this_node(this)
+        .map(|node| node.attributes())
+        .unwrap_or_default();
+
+    Ok(XmlListObject::new(activation, nodes)?.into())
+}
+
+/// Implements `XML.child`
+pub fn child<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let nodes = match this_node(this) {
+        Some(node) => {
+            let name = args.get(0).cloned().unwrap_or(Value::Undefined);
+            let name = name_to_multiname(activation, name)?;
+            let index = name.local_name().and_then(|n| n.parse::<u32>().ok());
+
+            match index {
+                Some(index) => node
+                    .children()
+                    .get(index as usize)
+                    .copied()
+                    .into_iter()
+                    .collect(),
+                None => node.children_by_name(&name, activation.default_xml_namespace()),
+            }
+        }
+        None => Vec::new(),
+    };
+
+    Ok(XmlListObject::new(activation, nodes)?.into())
+}
+
+/// Implements `XML.childIndex`
+pub fn child_index<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let index = this_node(this)
+        .and_then(|node| node.child_index())
+        .map(|index| index as i32)
+        .unwrap_or(-1);
+
+    Ok(index.into())
+}
+
+/// Implements `XML.children`
+pub fn children<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let nodes = this_node(this)
+        .map(|node| node.children())
+        .unwrap_or_default();
+
+    Ok(XmlListObject::new(activation, nodes)?.into())
+}
+
+/// Implements `XML.comments`
+pub fn comments<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let nodes = this_node(this)
+        .map(|node| node.children())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|node| matches!(&*node.kind(), E4XNodeKind::Comment(_)))
+        .collect();
+
+    Ok(XmlListObject::new(activation, nodes)?.into())
+}
+
+/// Implements `XML.contains`
+pub fn contains<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let value = args.get(0).cloned().unwrap_or(Value::Undefined);
+        return Ok(Value::from(this).abstract_eq(&value, activation)?.into());
+    }
+
+    Ok(false.into())
+}
+
+/// Implements `XML.copy`
+pub fn copy<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(node) = this_node(this) {
+        let copy = node.deep_copy(activation.context.gc_context);
+        return Ok(XmlObject::new(activation, copy)?.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML.descendants`
+pub fn descendants<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let mut nodes = Vec::new();
+    if let Some(node) = this_node(this) {
+        let name = args.get(0).cloned().unwrap_or_else(|| "*".into());
+        let name = name_to_multiname(activation, name)?;
+        node.descendants(&name, activation.default_xml_namespace(), &mut nodes);
+    }
+
+    Ok(XmlListObject::new(activation, nodes)?.into())
+}
+
+/// Implements `XML.elements`
+pub fn elements<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let nodes = match this_node(this) {
+        Some(node) => {
+            let name = args.get(0).cloned().unwrap_or_else(|| "*".into());
+            let name = name_to_multiname(activation, name)?;
+            node.children_by_name(&name, activation.default_xml_namespace())
+                .into_iter()
+                .filter(|node| node.is_element())
+                .collect()
+        }
+        None => Vec::new(),
+    };
+
+    Ok(XmlListObject::new(activation, nodes)?.into())
+}
+
+/// Implements `XML.hasComplexContent`
+pub fn has_complex_content<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let complex = this_node(this)
+        .map(|node| node.is_element() && !node.has_simple_content())
+        .unwrap_or(false);
+
+    Ok(complex.into())
+}
+
+/// Implements `XML.hasSimpleContent`
+pub fn has_simple_content<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let simple = this_node(this)
+        .map(|node| node.has_simple_content())
+        .unwrap_or(false);
+
+    Ok(simple.into())
+}
+
+/// Implements `XML.length`
+pub fn length<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
     _this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
+    Ok(1.into())
+}
+
+/// Implements `XML.localName`
+pub fn local_name<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(this_node(this)
+        .and_then(|node| node.local_name())
+        .map(Value::from)
+        .unwrap_or(Value::Null))
+}
+
+/// The namespace of a node with a URI.
+fn uri_namespace(uri: AvmString<'_>) -> Namespace<'_> {
+    if uri.is_empty() {
+        Namespace::public()
+    } else {
+        Namespace::Namespace(uri)
+    }
+}
+
+/// Implements `XML.name`
+pub fn name<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(node) = this_node(this) {
+        if let Some(local_name) = node.local_name() {
+            let qname = QName::new(uri_namespace(node.namespace()), local_name);
+            return Ok(QNameObject::from_qname(activation, qname)?.into());
+        }
+    }
+
+    Ok(Value::Null)
+}
+
+/// Implements `XML.namespace`
+pub fn namespace<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let node = match this_node(this) {
+        Some(node) => node,
+        None => return Ok(Value::Undefined),
+    };
+
+    let uri = match args.get(0) {
+        Some(prefix) => {
+            let prefix = prefix.coerce_to_string(activation)?;
+            let mut element = Some(node);
+            let mut uri = None;
+            while let (Some(e), None) = (element, uri) {
+                uri = e
+                    .namespace_declarations()
+                    .into_iter()
+                    .find(|decl| decl.prefix.unwrap_or_default() == prefix)
+                    .map(|decl| decl.uri);
+                element = e.parent();
+            }
+
+            match uri {
+                Some(uri) => uri,
+                None => return Ok(Value::Undefined),
+            }
+        }
+        None => node.namespace(),
+    };
+
+    Ok(NamespaceObject::from_namespace(activation, uri_namespace(uri))?.into())
+}
+
+/// Implements `XML.nodeKind`
+pub fn node_kind<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(this_node(this)
+        .map(|node| node.kind_name().into())
+        .unwrap_or(Value::Undefined))
+}
+
+/// Implements `XML.parent`
+pub fn parent<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    match this_node(this).and_then(|node| node.parent()) {
+        Some(parent) => Ok(XmlObject::new(activation, parent)?.into()),
+        None => Ok(Value::Undefined),
+    }
+}
+
+/// Implements `XML.processingInstructions`
+pub fn processing_instructions<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let name = args
+        .get(0)
+        .cloned()
+        .unwrap_or_else(|| "*".into())
+        .coerce_to_string(activation)?;
+    let nodes = this_node(this)
+        .map(|node| node.children())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|node| {
+            matches!(&*node.kind(), E4XNodeKind::ProcessingInstruction(_))
+                && (&name == b"*" || node.local_name() == Some(name))
+        })
+        .collect();
+
+    Ok(XmlListObject::new(activation, nodes)?.into())
+}
+
+/// Implements `XML.setChildren`
+pub fn set_children<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(node) = this_node(this) {
+        let value = args.get(0).cloned().unwrap_or(Value::Undefined);
+        let children = value_to_nodes(activation, value)?;
+        node.set_children(activation.context.gc_context, &children)?;
+    }
+
+    Ok(this.map(Value::from).unwrap_or(Value::Undefined))
+}
+
+/// Implements `XML.setLocalName`
+pub fn set_local_name<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(node) = this_node(this) {
+        let name = args.get(0).cloned().unwrap_or(Value::Undefined);
+        let name = name_to_multiname(activation, name)?;
+        if let Some(local_name) = name.local_name() {
+            node.set_local_name(activation.context.gc_context, local_name);
+        }
+    }
+
     Ok(Value::Undefined)
 }
 
+/// Implements `XML.setName`
+pub fn set_name<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(node) = this_node(this) {
+        let name = args.get(0).cloned().unwrap_or(Value::Undefined);
+        let name = name_to_multiname(activation, name)?;
+        let mc = activation.context.gc_context;
+        if let Some(local_name) = name.local_name() {
+            node.set_local_name(mc, local_name);
+        }
+        if let Some(uri) = crate::avm2::e4x::name_namespace(&name) {
+            node.set_namespace(mc, uri);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML.text`
+pub fn text<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let nodes = this_node(this)
+        .map(|node| node.children())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|node| matches!(&*node.kind(), E4XNodeKind::Text(_)))
+        .collect();
+
+    Ok(XmlListObject::new(activation, nodes)?.into())
+}
+
+/// Implements `XML.toString`
+pub fn to_string<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    match this_node(this) {
+        Some(node) => {
+            let settings = activation.avm2().xml_settings();
+            Ok(node
+                .xml_to_string(activation.context.gc_context, settings)
+                .into())
+        }
+        None => Ok("".into()),
+    }
+}
+
+/// Implements `XML.toXMLString`
+pub fn to_xml_string<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    match this_node(this) {
+        Some(node) => {
+            let string = node.to_xml_string(activation.avm2().xml_settings());
+            Ok(AvmString::new(activation.context.gc_context, string).into())
+        }
+        None => Ok("".into()),
+    }
+}
+
+/// Implements `XML.valueOf`
+pub fn value_of<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(this.map(Value::from).unwrap_or(Value::Undefined))
+}
+
 pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
     let class = Class::new(
         QName::new(Namespace::public(), "XML"),
@@ -49,5 +771,67 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
     let mut write = class.write(mc);
     write.set_instance_allocator(xml_allocator);
 
+    const PUBLIC_CLASS_PROPERTIES: &[(&str, Option<NativeMethodImpl>, Option<NativeMethodImpl>)] =
+        &[
+            (
+                "ignoreComments",
+                Some(ignore_comments),
+                Some(set_ignore_comments),
+            ),
+            (
+                "ignoreProcessingInstructions",
+                Some(ignore_processing_instructions),
+                Some(set_ignore_processing_instructions),
+            ),
+            (
+                "ignoreWhitespace",
+                Some(ignore_whitespace),
+                Some(set_ignore_whitespace),
+            ),
+            (
+                "prettyPrinting",
+                Some(pretty_printing),
+                Some(set_pretty_printing),
+            ),
+            ("prettyIndent", Some(pretty_indent), Some(set_pretty_indent)),
+        ];
+    write.define_public_builtin_class_properties(mc, PUBLIC_CLASS_PROPERTIES);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] =
+        &[("toString", to_string), ("valueOf", value_of)];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    const AS3_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("appendChild", append_child),
+        ("attribute", attribute),
+        ("attributes", attributes),
+        ("child", child),
+        ("childIndex", child_index),
+        ("children", children),
+        ("comments", comments),
+        ("contains", contains),
+        ("copy", copy),
+        ("descendants", descendants),
+        ("elements", elements),
+        ("hasComplexContent", has_complex_content),
+        ("hasSimpleContent", has_simple_content),
+        ("insertChildAfter", insert_child_after),
+        ("insertChildBefore", insert_child_before),
+        ("length", length),
+        ("localName", local_name),
+        ("name", name),
+        ("namespace", namespace),
+        ("nodeKind", node_kind),
+        ("parent", parent),
+        ("prependChild", prepend_child),
+        ("processingInstructions", processing_instructions),
+        ("setChildren", set_children),
+        ("setLocalName", set_local_name),
+        ("setName", set_name),
+        ("text", text),
+        ("toXMLString", to_xml_string),
+    ];
+    write.define_as3_builtin_instance_methods(mc, AS3_INSTANCE_METHODS);
+
     class
 }
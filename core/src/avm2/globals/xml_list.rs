@@ -2,19 +2,30 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::class::Class;
-use crate::avm2::method::{Method, ParamConfig};
+use crate::avm2::e4x::{E4XNode, E4XNodeKind};
+use crate::avm2::globals::xml::{name_to_multiname, value_to_nodes};
+use crate::avm2::method::{Method, NativeMethodImpl, ParamConfig};
 use crate::avm2::names::{Namespace, QName};
-use crate::avm2::object::{xml_allocator, Object};
+use crate::avm2::object::{xml_list_allocator, Object, TObject, XmlListObject, XmlObject};
 use crate::avm2::value::Value;
 use crate::avm2::Error;
+use crate::string::{AvmString, WString};
 use gc_arena::{GcCell, MutationContext};
 
 /// Implements `XMLList`'s instance initializer.
 pub fn instance_init<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
-    _this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
+    if let Some(list) = this.and_then(|this| this.as_xml_list_object()) {
+        let nodes = match args.get(0).cloned().unwrap_or(Value::Undefined) {
+            Value::Undefined | Value::Null => Vec::new(),
+            value => value_to_nodes(activation, value)?,
+        };
+        list.set_children(activation.context.gc_context, nodes);
+    }
+
     Ok(Value::Undefined)
 }
 
@@ -27,6 +38,327 @@ pub fn class_init<'gc>(
     Ok(Value::Undefined)
 }
 
+/// The items of the `XMLList` a method was called on.
+fn this_items<'gc>(this: Option<Object<'gc>>) -> Vec<E4XNode<'gc>> {
+    this.and_then(|this| this.as_xml_list_object())
+        .map(|list| list.children())
+        .unwrap_or_default()
+}
+
+/// Implements `XMLList.attribute`
+pub fn attribute<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let name = args.get(0).cloned().unwrap_or(Value::Undefined);
+    let name = name_to_multiname(activation, name)?.into_attribute();
+    let default_namespace = activation.default_xml_namespace();
+    let nodes = this_items(this)
+        .iter()
+        .flat_map(|item| item.children_by_name(&name, default_namespace))
+        .collect();
+
+    Ok(XmlListObject::new(activation, nodes)?.into())
+}
+
+/// Implements `XMLList.attributes`
+pub fn attributes<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let nodes = this_items(this)
+        .iter()
+        .flat_map(|item| item.attributes())
+        .collect();
+
+    Ok(XmlListObject::new(activation, nodes)?.into())
+}
+
+/// Implements `XMLList.child`
+pub fn child<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let name = args.get(0).cloned().unwrap_or(Value::Undefined);
+    let name = name_to_multiname(activation, name)?;
+    let index = name.local_name().and_then(|n| n.parse::<u32>().ok());
+    let default_namespace = activation.default_xml_namespace();
+
+    let nodes = this_items(this)
+        .iter()
+        .flat_map(|item| match index {
+            Some(index) => item
+                .children()
+                .get(index as usize)
+                .copied()
+                .into_iter()
+                .collect(),
+            None => item.children_by_name(&name, default_namespace),
+        })
+        .collect();
+
+    Ok(XmlListObject::new(activation, nodes)?.into())
+}
+
+/// Implements `XMLList.children`
+pub fn children<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let nodes = this_items(this)
+        .iter()
+        .flat_map(|item| item.children())
+        .collect();
+
+    Ok(XmlListObject::new(activation, nodes)?.into())
+}
+
+/// Implements `XMLList.comments`
+pub fn comments<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let nodes = this_items(this)
+        .iter()
+        .flat_map(|item| item.children())
+        .filter(|node| matches!(&*node.kind(), E4XNodeKind::Comment(_)))
+        .collect();
+
+    Ok(XmlListObject::new(activation, nodes)?.into())
+}
+
+/// Implements `XMLList.contains`
+pub fn contains<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let value = args.get(0).cloned().unwrap_or(Value::Undefined);
+    for item in this_items(this) {
+        let item: Value<'gc> = XmlObject::new(activation, item)?.into();
+        if item.abstract_eq(&value, activation)? {
+            return Ok(true.into());
+        }
+    }
+
+    Ok(false.into())
+}
+
+/// Implements `XMLList.copy`
+pub fn copy<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let mc = activation.context.gc_context;
+    let nodes = this_items(this)
+        .iter()
+        .map(|item| item.deep_copy(mc))
+        .collect();
+
+    Ok(XmlListObject::new(activation, nodes)?.into())
+}
+
+/// Implements `XMLList.descendants`
+pub fn descendants<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let name = args.get(0).cloned().unwrap_or_else(|| "*".into());
+    let name = name_to_multiname(activation, name)?;
+    let default_namespace = activation.default_xml_namespace();
+
+    let mut nodes = Vec::new();
+    for item in this_items(this) {
+        item.descendants(&name, default_namespace, &mut nodes);
+    }
+
+    Ok(XmlListObject::new(activation, nodes)?.into())
+}
+
+/// Implements `XMLList.elements`
+pub fn elements<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let name = args.get(0).cloned().unwrap_or_else(|| "*".into());
+    let name = name_to_multiname(activation, name)?;
+    let default_namespace = activation.default_xml_namespace();
+
+    let nodes = this_items(this)
+        .iter()
+        .flat_map(|item| item.children_by_name(&name, default_namespace))
+        .filter(|node| node.is_element())
+        .collect();
+
+    Ok(XmlListObject::new(activation, nodes)?.into())
+}
+
+/// Whether a list has simple content, i.e. it is empty, or has one item
+/// with simple content, or has no elements.
+fn has_simple_items(items: &[E4XNode<'_>]) -> bool {
+    match items {
+        [item] => item.has_simple_content(),
+        items => !items.iter().any(|item| item.is_element()),
+    }
+}
+
+/// Implements `XMLList.hasComplexContent`
+pub fn has_complex_content<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let items = this_items(this);
+    let complex = match items.as_slice() {
+        [] => false,
+        [item] => item.is_element() && !item.has_simple_content(),
+        items => items.iter().any(|item| item.is_element()),
+    };
+
+    Ok(complex.into())
+}
+
+/// Implements `XMLList.hasSimpleContent`
+pub fn has_simple_content<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(has_simple_items(&this_items(this)).into())
+}
+
+/// Implements `XMLList.length`
+pub fn length<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(this_items(this).len().into())
+}
+
+/// Implements `XMLList.parent`
+///
+/// Yields the parent of the items, if they all have the same one.
+pub fn parent<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let items = this_items(this);
+    let parent = items.first().and_then(|item| item.parent());
+    let same_parent = items.iter().all(|item| match (item.parent(), parent) {
+        (Some(a), Some(b)) => E4XNode::ptr_eq(a, b),
+        _ => false,
+    });
+
+    match parent {
+        Some(parent) if same_parent => Ok(XmlObject::new(activation, parent)?.into()),
+        _ => Ok(Value::Undefined),
+    }
+}
+
+/// Implements `XMLList.processingInstructions`
+pub fn processing_instructions<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let name = args
+        .get(0)
+        .cloned()
+        .unwrap_or_else(|| "*".into())
+        .coerce_to_string(activation)?;
+    let nodes = this_items(this)
+        .iter()
+        .flat_map(|item| item.children())
+        .filter(|node| {
+            matches!(&*node.kind(), E4XNodeKind::ProcessingInstruction(_))
+                && (&name == b"*" || node.local_name() == Some(name))
+        })
+        .collect();
+
+    Ok(XmlListObject::new(activation, nodes)?.into())
+}
+
+/// Implements `XMLList.text`
+pub fn text<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let nodes = this_items(this)
+        .iter()
+        .flat_map(|item| item.children())
+        .filter(|node| matches!(&*node.kind(), E4XNodeKind::Text(_)))
+        .collect();
+
+    Ok(XmlListObject::new(activation, nodes)?.into())
+}
+
+/// Implements `XMLList.toString`
+///
+/// Lists with simple content yield the text of their items, and other lists
+/// the markup of each item on its own line.
+pub fn to_string<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let items = this_items(this);
+    if !has_simple_items(&items) {
+        return to_xml_string(activation, this, args);
+    }
+
+    let settings = activation.avm2().xml_settings();
+    let mc = activation.context.gc_context;
+    let mut out = WString::new();
+    for item in items {
+        if !matches!(
+            &*item.kind(),
+            E4XNodeKind::Comment(_) | E4XNodeKind::ProcessingInstruction(_)
+        ) {
+            out.push_str(&item.xml_to_string(mc, settings));
+        }
+    }
+
+    Ok(AvmString::new(mc, out).into())
+}
+
+/// Implements `XMLList.toXMLString`
+pub fn to_xml_string<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let settings = activation.avm2().xml_settings();
+    let mut out = WString::new();
+    for (i, item) in this_items(this).into_iter().enumerate() {
+        if i > 0 && settings.pretty_printing {
+            out.push_byte(b'\n');
+        }
+        out.push_str(&item.to_xml_string(settings));
+    }
+
+    Ok(AvmString::new(activation.context.gc_context, out).into())
+}
+
+/// Implements `XMLList.valueOf`
+pub fn value_of<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(this.map(Value::from).unwrap_or(Value::Undefined))
+}
+
 pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
     let class = Class::new(
         QName::new(Namespace::public(), "XMLList"),
@@ -47,7 +379,31 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
     );
 
     let mut write = class.write(mc);
-    write.set_instance_allocator(xml_allocator);
+    write.set_instance_allocator(xml_list_allocator);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] =
+        &[("toString", to_string), ("valueOf", value_of)];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    const AS3_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("attribute", attribute),
+        ("attributes", attributes),
+        ("child", child),
+        ("children", children),
+        ("comments", comments),
+        ("contains", contains),
+        ("copy", copy),
+        ("descendants", descendants),
+        ("elements", elements),
+        ("hasComplexContent", has_complex_content),
+        ("hasSimpleContent", has_simple_content),
+        ("length", length),
+        ("parent", parent),
+        ("processingInstructions", processing_instructions),
+        ("text", text),
+        ("toXMLString", to_xml_string),
+    ];
+    write.define_as3_builtin_instance_methods(mc, AS3_INSTANCE_METHODS);
 
     class
 }
@@ -130,6 +130,18 @@ impl<'gc> Namespace<'gc> {
     }
 }
 
+/// Whether an ABC multiname names an XML attribute.
+fn is_attribute(abc_multiname: &AbcMultiname) -> bool {
+    matches!(
+        abc_multiname,
+        AbcMultiname::QNameA { .. }
+            | AbcMultiname::RTQNameA { .. }
+            | AbcMultiname::RTQNameLA
+            | AbcMultiname::MultinameA { .. }
+            | AbcMultiname::MultinameLA { .. }
+    )
+}
+
 /// A `QName`, likely "qualified name", consists of a namespace and name string.
 ///
 /// This is technically interchangeable with `xml::XMLName`, as they both
@@ -288,6 +300,11 @@ pub struct Multiname<'gc> {
     /// The type parameters required to satisfy this multiname. If empty, then
     /// this multiname is satisfied by any type parameters in any amount.
     params: Vec<Multiname<'gc>>,
+
+    /// Whether this multiname names an XML attribute rather than a property.
+    ///
+    /// Only E4X objects treat attribute names differently from other names.
+    is_attribute: bool,
 }
 
 impl<'gc> Multiname<'gc> {
@@ -345,6 +362,7 @@ impl<'gc> Multiname<'gc> {
                 )?,
                 name: Some(name.coerce_to_string(activation)?),
                 params: Vec::new(),
+                is_attribute: is_attribute(abc_multiname),
             }),
             _ => Err("Cannot assemble early-bound multinames using from_multiname_late".into()),
         }
@@ -374,6 +392,7 @@ impl<'gc> Multiname<'gc> {
                     name: translation_unit
                         .pool_string_option(name.0, activation.context.gc_context)?,
                     params: Vec::new(),
+                    is_attribute: is_attribute(abc_multiname),
                 }
             }
             AbcMultiname::RTQName { name } | AbcMultiname::RTQNameA { name } => {
@@ -384,6 +403,7 @@ impl<'gc> Multiname<'gc> {
                     name: translation_unit
                         .pool_string_option(name.0, activation.context.gc_context)?,
                     params: Vec::new(),
+                    is_attribute: is_attribute(abc_multiname),
                 }
             }
             AbcMultiname::RTQNameL | AbcMultiname::RTQNameLA => {
@@ -394,6 +414,7 @@ impl<'gc> Multiname<'gc> {
                     ns: vec![*ns],
                     name: Some(name),
                     params: Vec::new(),
+                    is_attribute: is_attribute(abc_multiname),
                 }
             }
             AbcMultiname::Multiname {
@@ -411,6 +432,7 @@ impl<'gc> Multiname<'gc> {
                 )?,
                 name: translation_unit.pool_string_option(name.0, activation.context.gc_context)?,
                 params: Vec::new(),
+                is_attribute: is_attribute(abc_multiname),
             },
             AbcMultiname::MultinameL { .. } | AbcMultiname::MultinameLA { .. } => {
                 let name = activation.avm2().pop();
@@ -507,7 +529,9 @@ impl<'gc> Multiname<'gc> {
             .get(actual_index)
             .ok_or_else(|| format!("Unknown multiname constant {}", multiname_index.0).into());
 
-        Ok(match abc_multiname? {
+        let abc_multiname = abc_multiname?;
+
+        Ok(match abc_multiname {
             AbcMultiname::QName { namespace, name } | AbcMultiname::QNameA { namespace, name } => {
                 Self {
                     ns: vec![Namespace::from_abc_namespace(
@@ -517,6 +541,7 @@ impl<'gc> Multiname<'gc> {
                     )?],
                     name: translation_unit.pool_string_option(name.0, mc)?,
                     params: Vec::new(),
+                    is_attribute: is_attribute(abc_multiname),
                 }
             }
             AbcMultiname::Multiname {
@@ -530,6 +555,7 @@ impl<'gc> Multiname<'gc> {
                 ns: Self::abc_namespace_set(translation_unit, namespace_set.clone(), mc)?,
                 name: translation_unit.pool_string_option(name.0, mc)?,
                 params: Vec::new(),
+                is_attribute: is_attribute(abc_multiname),
             },
             AbcMultiname::TypeName {
                 base_type,
@@ -568,9 +594,21 @@ impl<'gc> Multiname<'gc> {
             ns: vec![Namespace::Any],
             name: None,
             params: Vec::new(),
+            is_attribute: false,
         }
     }
 
+    /// Turn this multiname into the name of an XML attribute.
+    pub fn into_attribute(mut self) -> Self {
+        self.is_attribute = true;
+        self
+    }
+
+    /// Indicates if this multiname names an XML attribute.
+    pub fn is_attribute(&self) -> bool {
+        self.is_attribute
+    }
+
     pub fn namespace_set(&self) -> impl Iterator<Item = &Namespace<'gc>> {
         self.ns.iter()
     }
@@ -617,6 +655,7 @@ impl<'gc> From<QName<'gc>> for Multiname<'gc> {
             ns: vec![q.ns],
             name: Some(q.name),
             params: Vec::new(),
+            is_attribute: false,
         }
     }
 }
@@ -48,6 +48,7 @@ mod soundchannel_object;
 mod stage_object;
 mod textformat_object;
 mod vector_object;
+mod xml_list_object;
 mod xml_object;
 
 pub use crate::avm2::object::array_object::{array_allocator, ArrayObject};
@@ -74,7 +75,8 @@ pub use crate::avm2::object::soundchannel_object::{soundchannel_allocator, Sound
 pub use crate::avm2::object::stage_object::{stage_allocator, StageObject};
 pub use crate::avm2::object::textformat_object::{textformat_allocator, TextFormatObject};
 pub use crate::avm2::object::vector_object::{vector_allocator, VectorObject};
-pub use crate::avm2::object::xml_object::{xml_allocator, XmlObject};
+pub use crate::avm2::object::xml_list_object::{xml_list_allocator, XmlListObject};
+pub use crate::avm2::object::xml_object::{xml_allocator, xml_nodes, XmlObject};
 
 /// Represents an object that can be directly interacted with by the AVM2
 /// runtime.
@@ -93,6 +95,7 @@ pub use crate::avm2::object::xml_object::{xml_allocator, XmlObject};
         EventObject(EventObject<'gc>),
         DispatchObject(DispatchObject<'gc>),
         XmlObject(XmlObject<'gc>),
+        XmlListObject(XmlListObject<'gc>),
         RegExpObject(RegExpObject<'gc>),
         ByteArrayObject(ByteArrayObject<'gc>),
         LoaderInfoObject(LoaderInfoObject<'gc>),
@@ -553,6 +556,28 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
         };
 
         if let Some(name) = multiname.local_name() {
+            // Protected members are bound in the protected namespace of every
+            // subclass, so a protected name refers to the most derived binding
+            // in this object's class chain.
+            if matching_set
+                .iter()
+                .any(|ns| matches!(ns, Namespace::Protected(_)))
+            {
+                if let Some(class) = self.instance_of() {
+                    let protected_namespaces = class.protected_namespaces();
+                    if multiname
+                        .namespace_set()
+                        .any(|n| protected_namespaces.contains(n))
+                    {
+                        for ns in protected_namespaces.iter() {
+                            if matching_set.contains(ns) {
+                                return Ok(Some(QName::new(*ns, name)));
+                            }
+                        }
+                    }
+                }
+            }
+
             for ns in matching_set.iter() {
                 if multiname.namespace_set().any(|n| n == ns) {
                     return Ok(Some(QName::new(*ns, name)));
@@ -603,6 +628,15 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
         self.has_property(name)
     }
 
+    /// Indicates whether or not an E4X object has an XML child or attribute
+    /// with a given name.
+    ///
+    /// This is used to find `with` scopes that contain XML objects, whose
+    /// children aren't properties. Other objects never have XML properties.
+    fn has_xml_property(self, _name: &Multiname<'gc>) -> bool {
+        false
+    }
+
     /// Indicates whether or not a property exists on an object.
     fn has_property(self, name: QName<'gc>) -> Result<bool, Error> {
         if self.has_own_property(name)? {
@@ -1408,6 +1442,11 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
         None
     }
 
+    /// Unwrap this object as a loader info object.
+    fn as_loader_info_object(&self) -> Option<LoaderInfoObject<'gc>> {
+        None
+    }
+
     /// Unwrap this object's sound handle.
     fn as_sound(self) -> Option<SoundHandle> {
         None
@@ -1418,6 +1457,11 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
     /// This does nothing if the object is not a sound.
     fn set_sound(self, _mc: MutationContext<'gc, '_>, _sound: SoundHandle) {}
 
+    /// Unwrap this object as a sound.
+    fn as_sound_object(self) -> Option<SoundObject<'gc>> {
+        None
+    }
+
     /// Unwrap this object's sound instance handle.
     fn as_sound_channel(self) -> Option<SoundChannelObject<'gc>> {
         None
@@ -1456,6 +1500,11 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
         None
     }
 
+    /// Get this object as a `ProxyObject`, if it is one.
+    fn as_proxy_object(self) -> Option<ProxyObject<'gc>> {
+        None
+    }
+
     /// Unwrap this object as a text format.
     fn as_text_format(&self) -> Option<Ref<TextFormat>> {
         None
@@ -1465,6 +1514,16 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
     fn as_text_format_mut(&self, _mc: MutationContext<'gc, '_>) -> Option<RefMut<TextFormat>> {
         None
     }
+
+    /// Get this object as an `XmlObject`, if it is one.
+    fn as_xml_object(&self) -> Option<XmlObject<'gc>> {
+        None
+    }
+
+    /// Get this object as an `XmlListObject`, if it is one.
+    fn as_xml_list_object(&self) -> Option<XmlListObject<'gc>> {
+        None
+    }
 }
 
 pub enum ObjectPtr {}
@@ -3,6 +3,7 @@
 use crate::avm2::activation::Activation;
 use crate::avm2::class::{Allocator, AllocatorFn, Class};
 use crate::avm2::function::Executable;
+use crate::avm2::globals::vector;
 use crate::avm2::method::Method;
 use crate::avm2::names::{Namespace, QName};
 use crate::avm2::object::function_object::FunctionObject;
@@ -167,8 +168,8 @@ impl<'gc> ClassObject<'gc> {
         if let Some(base_class) = superclass_object.map(|b| b.inner_class_definition()) {
             if base_class.read().is_final() {
                 return Err(format!(
-                    "Base class {:?} is final and cannot be extended",
-                    base_class.read().name().local_name()
+                    "VerifyError: Error #1103: Class {} cannot extend final base class.",
+                    class.read().name().local_name()
                 )
                 .into());
             }
@@ -444,34 +445,43 @@ impl<'gc> ClassObject<'gc> {
         //Otherwise we won't get overrides.
         drop(write);
 
-        let mut class = Some(self);
+        let class_read = class.read();
+        let must_implement = !class_read.is_interface() && !class_read.is_system();
+        drop(class_read);
+
+        for interface in self.all_interfaces() {
+            let iface_static_class = interface.inner_class_definition();
+            let iface_read = iface_static_class.read();
+
+            for interface_trait in iface_read.instance_traits() {
+                let public_name = QName::dynamic_name(interface_trait.name().local_name());
+                let trait_slot = self
+                    .0
+                    .read()
+                    .resolved_instance_traits
+                    .get(public_name)
+                    .cloned();
 
-        while let Some(cls) = class {
-            for interface in cls.interfaces() {
-                let iface_static_class = interface.inner_class_definition();
-                let iface_read = iface_static_class.read();
-
-                for interface_trait in iface_read.instance_traits() {
-                    if !interface_trait.name().namespace().is_public() {
-                        let public_name = QName::dynamic_name(interface_trait.name().local_name());
-                        let trait_slot = self
-                            .0
-                            .read()
+                match trait_slot {
+                    Some(trait_slot) if !interface_trait.name().namespace().is_public() => {
+                        self.0
+                            .write(activation.context.gc_context)
                             .resolved_instance_traits
-                            .get(public_name)
-                            .cloned();
-
-                        if let Some(trait_slot) = trait_slot {
-                            self.0
-                                .write(activation.context.gc_context)
-                                .resolved_instance_traits
-                                .insert(interface_trait.name(), trait_slot);
-                        }
+                            .insert(interface_trait.name(), trait_slot);
+                    }
+                    Some(_) => {}
+                    None if must_implement => {
+                        return Err(format!(
+                            "VerifyError: Error #1044: Interface method {} in namespace {} not implemented by class {}.",
+                            interface_trait.name().local_name(),
+                            iface_read.name().local_name(),
+                            class.read().name().local_name()
+                        )
+                        .into());
                     }
+                    None => {}
                 }
             }
-
-            class = cls.superclass_object();
         }
 
         Ok(())
@@ -544,6 +554,76 @@ impl<'gc> ClassObject<'gc> {
             .cloned()
     }
 
+    /// Look up an instance trait for a `super` access.
+    ///
+    /// This works like `lookup_instance_traits`, except that protected names
+    /// resolved on a subclass instance are mapped back onto the protected
+    /// namespaces of this class and its superclasses, most derived first.
+    fn lookup_super_trait(
+        self,
+        name: QName<'gc>,
+        filter: fn(&Trait<'gc>) -> bool,
+    ) -> Option<(ClassObject<'gc>, Trait<'gc>)> {
+        if let Some(result) = self.lookup_instance_traits(name, filter) {
+            return Some(result);
+        }
+
+        if !matches!(name.namespace(), Namespace::Protected(_)) {
+            return None;
+        }
+
+        self.protected_namespaces()
+            .into_iter()
+            .find_map(|ns| self.lookup_instance_traits(QName::new(ns, name.local_name()), filter))
+    }
+
+    /// List the protected namespaces of this class and its superclasses, most
+    /// derived first.
+    ///
+    /// Protected members are bound in the protected namespace of the class
+    /// that declares them and in that of every subclass, so all of these
+    /// namespaces name the same set of members on an instance of this class.
+    pub fn protected_namespaces(self) -> SmallVec<[Namespace<'gc>; 4]> {
+        let mut namespaces = SmallVec::new();
+        let mut class = Some(self);
+
+        while let Some(cls) = class {
+            if let Some(ns) = cls.inner_class_definition().read().protected_namespace() {
+                namespaces.push(ns);
+            }
+
+            class = cls.superclass_object();
+        }
+
+        namespaces
+    }
+
+    /// List every interface implemented by this class.
+    ///
+    /// This includes interfaces implemented by superclasses, as well as the
+    /// interfaces that those interfaces extend. Each interface is listed once.
+    pub fn all_interfaces(self) -> Vec<ClassObject<'gc>> {
+        let mut interfaces: Vec<ClassObject<'gc>> = Vec::new();
+        let mut pending = Vec::new();
+        let mut class = Some(self);
+
+        while let Some(cls) = class {
+            pending.extend(cls.interfaces());
+            class = cls.superclass_object();
+        }
+
+        while let Some(interface) = pending.pop() {
+            if interfaces.iter().any(|i| Object::ptr_eq(*i, interface)) {
+                continue;
+            }
+
+            pending.extend(interface.interfaces());
+            interfaces.push(interface);
+        }
+
+        interfaces
+    }
+
     /// Determine if we have an instance trait with a given name.
     pub fn has_instance_trait(self, name: QName<'gc>) -> bool {
         self.0.read().resolved_instance_traits.get(name).is_some()
@@ -596,6 +676,14 @@ impl<'gc> ClassObject<'gc> {
         test_class: ClassObject<'gc>,
         activation: &mut Activation<'_, 'gc, '_>,
     ) -> Result<bool, Error> {
+        if test_class.inner_class_definition().read().is_interface() {
+            return Ok(Object::ptr_eq(self, test_class)
+                || self
+                    .all_interfaces()
+                    .iter()
+                    .any(|interface| Object::ptr_eq(*interface, test_class)));
+        }
+
         let mut my_class = Some(self);
 
         while let Some(class) = my_class {
@@ -603,12 +691,6 @@ impl<'gc> ClassObject<'gc> {
                 return Ok(true);
             }
 
-            for interface in class.interfaces() {
-                if Object::ptr_eq(interface, test_class) {
-                    return Ok(true);
-                }
-            }
-
             if let (Some(my_param), Some(test_param)) =
                 (class.as_class_params(), test_class.as_class_params())
             {
@@ -712,7 +794,7 @@ impl<'gc> ClassObject<'gc> {
         let name = name.unwrap();
 
         let lookup_result =
-            self.lookup_instance_traits(name, |t| matches!(t.kind(), TraitKind::Method { .. }));
+            self.lookup_super_trait(name, |t| matches!(t.kind(), TraitKind::Method { .. }));
 
         if let Some((superclass_object, method_trait)) = lookup_result {
             let scope = superclass_object.class_scope();
@@ -773,7 +855,7 @@ impl<'gc> ClassObject<'gc> {
         let name = name.unwrap();
 
         let lookup_result =
-            self.lookup_instance_traits(name, |t| matches!(t.kind(), TraitKind::Getter { .. }));
+            self.lookup_super_trait(name, |t| matches!(t.kind(), TraitKind::Getter { .. }));
 
         if let Some((superclass_object, method_trait)) = lookup_result {
             let scope = superclass_object.class_scope();
@@ -836,7 +918,7 @@ impl<'gc> ClassObject<'gc> {
         let name = name.unwrap();
 
         let lookup_result =
-            self.lookup_instance_traits(name, |t| matches!(t.kind(), TraitKind::Setter { .. }));
+            self.lookup_super_trait(name, |t| matches!(t.kind(), TraitKind::Setter { .. }));
 
         if let Some((superclass_object, method_trait)) = lookup_result {
             let scope = superclass_object.class_scope();
@@ -1133,11 +1215,15 @@ impl<'gc> TObject<'gc> for ClassObject<'gc> {
         arguments: &[Value<'gc>],
         activation: &mut Activation<'_, 'gc, '_>,
     ) -> Result<Value<'gc>, Error> {
-        arguments
-            .get(0)
-            .cloned()
-            .unwrap_or(Value::Undefined)
-            .coerce_to_type(activation, self)
+        let value = arguments.get(0).cloned().unwrap_or(Value::Undefined);
+
+        // Applied generic classes (which can only be `Vector`) convert their
+        // argument instead of merely coercing it.
+        if self.as_class_params().is_some() {
+            return vector::convert(activation, self, value);
+        }
+
+        value.coerce_to_type(activation, self)
     }
 
     fn construct(
@@ -1350,3 +1436,200 @@ impl<'gc> Hash for ClassObject<'gc> {
         self.as_ptr().hash(state);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::avm2::activation::Activation;
+    use crate::avm2::class::{Class, ClassAttributes};
+    use crate::avm2::method::Method;
+    use crate::avm2::names::{Multiname, Namespace, QName};
+    use crate::avm2::object::{ClassObject, Object, TObject};
+    use crate::avm2::test_utils::with_avm2;
+    use crate::avm2::traits::{Trait, TraitAttributes, TraitKind};
+    use crate::avm2::{Error, Value};
+
+    fn noop<'gc>(
+        _activation: &mut Activation<'_, 'gc, '_>,
+        _this: Option<Object<'gc>>,
+        _args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, Error> {
+        Ok(Value::Undefined)
+    }
+
+    fn method<'gc>(activation: &mut Activation<'_, 'gc, '_>, name: QName<'gc>) -> Trait<'gc> {
+        Trait::from_method(
+            name,
+            Method::from_builtin(noop, "<test method>", activation.context.gc_context),
+        )
+    }
+
+    /// Link a class as if it was loaded from a movie. Its protected namespace
+    /// is named after the class.
+    fn user_class<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        name: &'static str,
+        superclass: ClassObject<'gc>,
+        traits: Vec<Trait<'gc>>,
+        interfaces: Vec<Multiname<'gc>>,
+    ) -> Result<ClassObject<'gc>, Error> {
+        let mc = activation.context.gc_context;
+        let class = Class::new(
+            QName::new(Namespace::public(), name),
+            Some(superclass.inner_class_definition().read().name().into()),
+            Method::from_builtin(noop, "<test instance initializer>", mc),
+            Method::from_builtin(noop, "<test class initializer>", mc),
+            mc,
+        );
+
+        let mut write = class.write(mc);
+        write.mark_user_defined();
+        write.set_protected_namespace(Namespace::Protected(name.into()));
+        for instance_trait in traits {
+            write.define_instance_trait(instance_trait);
+        }
+        for interface in interfaces {
+            write.implements(interface);
+        }
+        drop(write);
+
+        ClassObject::from_class(activation, class, Some(superclass))
+    }
+
+    fn is_method(t: &Trait<'_>) -> bool {
+        matches!(t.kind(), TraitKind::Method { .. })
+    }
+
+    /// Tests that a class must implement every method of its interfaces.
+    #[test]
+    fn unimplemented_interface_method() {
+        with_avm2(|activation| {
+            let object_class = activation.avm2().classes().object;
+            let dispatcher_name =
+                QName::new(Namespace::package("flash.events"), "IEventDispatcher");
+            let dispatcher_class = activation
+                .avm2()
+                .global_domain()
+                .get_defined_value(activation, dispatcher_name)?
+                .coerce_to_object(activation)?
+                .as_class_object()
+                .unwrap();
+
+            let error = user_class(
+                activation,
+                "Incomplete",
+                object_class,
+                vec![method(
+                    activation,
+                    QName::new(Namespace::public(), "addEventListener"),
+                )],
+                vec![dispatcher_name.into()],
+            )
+            .unwrap_err();
+            assert!(error.to_string().contains("Error #1044"), "{}", error);
+
+            let traits = [
+                "addEventListener",
+                "dispatchEvent",
+                "hasEventListener",
+                "removeEventListener",
+                "willTrigger",
+            ]
+            .iter()
+            .map(|name| method(activation, QName::new(Namespace::public(), *name)))
+            .collect();
+            let complete = user_class(
+                activation,
+                "Complete",
+                object_class,
+                traits,
+                vec![dispatcher_name.into()],
+            )?;
+            let instance = complete.construct(activation, &[])?;
+            assert!(instance.is_of_type(dispatcher_class, activation)?);
+
+            Ok(())
+        });
+    }
+
+    /// Tests that a protected member declared by both a class and its
+    /// subclass resolves to the subclass on its instances, while `super`
+    /// accesses from the subclass find the superclass's member.
+    #[test]
+    fn protected_member_overridden_in_subclass() {
+        with_avm2(|activation| {
+            let object_class = activation.avm2().classes().object;
+            let base_foo = QName::new(Namespace::Protected("Base".into()), "foo");
+            let derived_foo = QName::new(Namespace::Protected("Derived".into()), "foo");
+
+            let base_trait = method(activation, base_foo);
+            let base = user_class(activation, "Base", object_class, vec![base_trait], vec![])?;
+            let derived_trait = method(activation, derived_foo).with_override();
+            let derived = user_class(activation, "Derived", base, vec![derived_trait], vec![])?;
+
+            let instance = derived.construct(activation, &[])?;
+            assert_eq!(
+                instance.resolve_multiname(&base_foo.into())?,
+                Some(derived_foo)
+            );
+
+            let (class, _) = base.lookup_super_trait(derived_foo, is_method).unwrap();
+            assert!(Object::ptr_eq(class, base));
+            let (class, _) = derived.lookup_super_trait(derived_foo, is_method).unwrap();
+            assert!(Object::ptr_eq(class, derived));
+            assert!(base
+                .lookup_super_trait(QName::new(Namespace::public(), "foo"), is_method)
+                .is_none());
+
+            let unmarked_trait = method(activation, derived_foo);
+            let error =
+                user_class(activation, "Unmarked", base, vec![unmarked_trait], vec![]).unwrap_err();
+            assert!(error.to_string().contains("Error #1053"), "{}", error);
+
+            Ok(())
+        });
+    }
+
+    /// Tests the verification errors for illegal overrides.
+    #[test]
+    fn illegal_overrides() {
+        with_avm2(|activation| {
+            let object_class = activation.avm2().classes().object;
+            let bar = QName::new(Namespace::public(), "bar");
+
+            let mut final_trait = method(activation, bar);
+            final_trait.set_attributes(TraitAttributes::FINAL);
+            let base = user_class(activation, "Base", object_class, vec![final_trait], vec![])?;
+
+            let override_trait = method(activation, bar).with_override();
+            let error =
+                user_class(activation, "Derived", base, vec![override_trait], vec![]).unwrap_err();
+            assert!(error.to_string().contains("Error #1053"), "{}", error);
+
+            let orphan_trait = method(activation, QName::new(Namespace::public(), "baz"));
+            let error = user_class(
+                activation,
+                "Orphan",
+                base,
+                vec![orphan_trait.with_override()],
+                vec![],
+            )
+            .unwrap_err();
+            assert!(error.to_string().contains("Error #1053"), "{}", error);
+
+            let slot_trait = Trait::from_slot(bar, Multiname::any(), None);
+            let error =
+                user_class(activation, "Slotted", base, vec![slot_trait], vec![]).unwrap_err();
+            assert!(error.to_string().contains("Error #1053"), "{}", error);
+
+            let sealed = user_class(activation, "Sealed", object_class, vec![], vec![])?;
+            sealed
+                .inner_class_definition()
+                .write(activation.context.gc_context)
+                .set_attributes(ClassAttributes::FINAL);
+            let error = user_class(activation, "Unsealed", sealed, vec![], vec![]).unwrap_err();
+            assert!(error.to_string().contains("Error #1103"), "{}", error);
+
+            Ok(())
+        });
+    }
+}
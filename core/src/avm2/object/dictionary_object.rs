@@ -1,6 +1,7 @@
 //! Object representation for `flash.utils.Dictionary`
 
 use crate::avm2::activation::Activation;
+use crate::avm2::names::QName;
 use crate::avm2::object::script_object::ScriptObjectData;
 use crate::avm2::object::{ClassObject, Object, ObjectPtr, TObject};
 use crate::avm2::value::Value;
@@ -22,6 +23,7 @@ pub fn dictionary_allocator<'gc>(
         DictionaryObjectData {
             base,
             object_space: Default::default(),
+            weak_keys: false,
         },
     ))
     .into())
@@ -44,6 +46,12 @@ pub struct DictionaryObjectData<'gc> {
 
     /// Object key storage
     object_space: FnvHashMap<Object<'gc>, Value<'gc>>,
+
+    /// Whether or not the dictionary was constructed with `weakKeys`.
+    ///
+    /// TODO: This has no effect on `object_space` yet. `gc_arena` cannot
+    /// hold weak pointers, so the flag is only kept for serialization.
+    weak_keys: bool,
 }
 
 impl<'gc> DictionaryObject<'gc> {
@@ -75,6 +83,31 @@ impl<'gc> DictionaryObject<'gc> {
     pub fn has_property_by_object(self, name: Object<'gc>) -> bool {
         self.0.read().object_space.get(&name).is_some()
     }
+
+    /// Determine if this dictionary was constructed with `weakKeys`.
+    pub fn has_weak_keys(self) -> bool {
+        self.0.read().weak_keys
+    }
+
+    pub fn set_weak_keys(self, mc: MutationContext<'gc, '_>, weak_keys: bool) {
+        self.0.write(mc).weak_keys = weak_keys;
+    }
+
+    /// Retrieve the object key at a given enumeration index, if the index
+    /// falls into the object space.
+    ///
+    /// Enumeration indices start from one, and cover the ordinary enumerable
+    /// properties of the dictionary before its object keys.
+    fn object_key_at(self, index: u32) -> Option<Object<'gc>> {
+        let read = self.0.read();
+        let last_enumerant = read.base.get_last_enumerant();
+        let object_space_index = index.checked_sub(last_enumerant + 1)?;
+
+        read.object_space
+            .keys()
+            .nth(object_space_index as usize)
+            .cloned()
+    }
 }
 
 impl<'gc> TObject<'gc> for DictionaryObject<'gc> {
@@ -102,6 +135,7 @@ impl<'gc> TObject<'gc> for DictionaryObject<'gc> {
             DictionaryObjectData {
                 base,
                 object_space: Default::default(),
+                weak_keys: false,
             },
         ))
         .into())
@@ -132,24 +166,99 @@ impl<'gc> TObject<'gc> for DictionaryObject<'gc> {
         index: u32,
         _activation: &mut Activation<'_, 'gc, '_>,
     ) -> Result<Value<'gc>, Error> {
-        let read = self.0.read();
-        let last_enumerant = read.base.get_last_enumerant();
+        if let Some(key) = self.object_key_at(index) {
+            return Ok(key.into());
+        }
 
-        if index < last_enumerant {
-            Ok(read
-                .base
-                .get_enumerant_name(index)
-                .unwrap_or(Value::Undefined))
-        } else {
-            let object_space_index = index.saturating_sub(last_enumerant);
-
-            Ok(read
-                .object_space
-                .keys()
-                .nth(object_space_index as usize)
-                .cloned()
-                .map(|v| v.into())
-                .unwrap_or(Value::Undefined))
+        Ok(self
+            .0
+            .read()
+            .base
+            .get_enumerant_name(index)
+            .unwrap_or(Value::Undefined))
+    }
+
+    fn get_enumerant_value(
+        self,
+        index: u32,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error> {
+        if let Some(key) = self.object_key_at(index) {
+            return Ok(self.get_property_by_object(key));
         }
+
+        let name = self
+            .get_enumerant_name(index, activation)?
+            .coerce_to_string(activation)?;
+        self.get_property(self.into(), &QName::dynamic_name(name).into(), activation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::avm2::names::{Namespace, QName};
+    use crate::avm2::object::TObject;
+    use crate::avm2::test_utils::with_avm2;
+    use crate::avm2::Value;
+
+    /// Tests that enumerating a dictionary visits its string keys and its
+    /// object keys once each.
+    #[test]
+    fn enumerate_mixed_keys() {
+        with_avm2(|activation| {
+            let name = QName::new(Namespace::package("flash.utils"), "Dictionary");
+            let dictionary_class = activation
+                .avm2()
+                .global_domain()
+                .get_defined_value(activation, name)?
+                .coerce_to_object(activation)?
+                .as_class_object()
+                .unwrap();
+            let object_class = activation.avm2().classes().object;
+            let mut dictionary = dictionary_class.construct(activation, &[])?;
+            let key_a = object_class.construct(activation, &[])?;
+            let key_b = object_class.construct(activation, &[])?;
+
+            for (name, value) in [("a", 1), ("b", 2)] {
+                dictionary.set_property(
+                    dictionary,
+                    &QName::dynamic_name(name).into(),
+                    value.into(),
+                    activation,
+                )?;
+            }
+            let object_space = dictionary.as_dictionary_object().unwrap();
+            let mc = activation.context.gc_context;
+            object_space.set_property_by_object(key_a, 3.into(), mc);
+            object_space.set_property_by_object(key_b, 4.into(), mc);
+
+            let mut entries = Vec::new();
+            let mut index = 0;
+            while let Some(next) = dictionary.get_next_enumerant(index, activation)? {
+                index = next;
+                let name = dictionary.get_enumerant_name(index, activation)?;
+                let value = dictionary.get_enumerant_value(index, activation)?;
+                entries.push((name, value));
+            }
+
+            assert_eq!(entries.len(), 4);
+            for (key, value) in [
+                (Value::from("a"), 1),
+                ("b".into(), 2),
+                (key_a.into(), 3),
+                (key_b.into(), 4),
+            ] {
+                let matching: Vec<_> = entries.iter().filter(|(name, _)| *name == key).collect();
+                assert_eq!(matching.len(), 1, "{:?} is enumerated once", key);
+                assert_eq!(matching[0].1, value.into());
+            }
+
+            // Object keys stay objects instead of being converted to strings.
+            assert!(entries
+                .iter()
+                .any(|(name, _)| matches!(name, Value::Object(_))));
+
+            Ok(())
+        })
     }
 }
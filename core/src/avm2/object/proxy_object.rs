@@ -25,6 +25,32 @@ pub fn proxy_allocator<'gc>(
     .into())
 }
 
+/// Construct the `QName` object that names a property in calls to the
+/// `flash_proxy` methods of a `Proxy`.
+///
+/// Returns `None` if the multiname has no public-like namespace to hand over.
+///
+/// NOTE: This is incorrect behavior.
+/// `QName` should instead store the whole multiname's namespace set, so that
+/// it can be used to index other objects using the same namespace set.
+fn proxy_name<'gc>(
+    multiname: &Multiname<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Option<Object<'gc>>, Error> {
+    if let Some(local_name) = multiname.local_name() {
+        for namespace in multiname.namespace_set() {
+            if namespace.is_any() || namespace.is_public() || namespace.is_namespace() {
+                return Ok(Some(QNameObject::from_qname(
+                    activation,
+                    QName::new(*namespace, local_name),
+                )?));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
 #[derive(Clone, Collect, Debug, Copy)]
 #[collect(no_drop)]
 pub struct ProxyObject<'gc>(GcCell<'gc, ProxyObjectData<'gc>>);
@@ -36,6 +62,31 @@ pub struct ProxyObjectData<'gc> {
     base: ScriptObjectData<'gc>,
 }
 
+impl<'gc> ProxyObject<'gc> {
+    /// Retrieve the descendants of this proxy for the descendants (`..`)
+    /// operator, by calling its `flash_proxy::getDescendants` method.
+    pub fn get_descendants(
+        self,
+        multiname: &Multiname<'gc>,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error> {
+        let qname = match proxy_name(multiname, activation)? {
+            Some(qname) => qname.into(),
+            None => Value::Undefined,
+        };
+
+        Object::from(self).call_property(
+            &QName::new(
+                Namespace::Namespace(NS_FLASH_PROXY.into()),
+                "getDescendants",
+            )
+            .into(),
+            &[qname],
+            activation,
+        )
+    }
+}
+
 impl<'gc> TObject<'gc> for ProxyObject<'gc> {
     fn base(&self) -> Ref<ScriptObjectData<'gc>> {
         Ref::map(self.0.read(), |read| &read.base)
@@ -53,6 +104,10 @@ impl<'gc> TObject<'gc> for ProxyObject<'gc> {
         Ok(Object::from(*self).into())
     }
 
+    fn as_proxy_object(self) -> Option<ProxyObject<'gc>> {
+        Some(self)
+    }
+
     fn derive(&self, activation: &mut Activation<'_, 'gc, '_>) -> Result<Object<'gc>, Error> {
         let base = ScriptObjectData::base_new(Some((*self).into()), None);
 
@@ -69,24 +124,12 @@ impl<'gc> TObject<'gc> for ProxyObject<'gc> {
         multiname: &Multiname<'gc>,
         activation: &mut Activation<'_, 'gc, '_>,
     ) -> Result<Value<'gc>, Error> {
-        // NOTE: This is incorrect behavior.
-        // `QName` should instead store the whole multiname's namespace set,
-        // so that it can be used to index other objects using the same
-        // namespace set.
-        if let Some(local_name) = multiname.local_name() {
-            for namespace in multiname.namespace_set() {
-                if namespace.is_any() || namespace.is_public() || namespace.is_namespace() {
-                    let qname =
-                        QNameObject::from_qname(activation, QName::new(*namespace, local_name))?;
-
-                    return receiver.call_property(
-                        &QName::new(Namespace::Namespace(NS_FLASH_PROXY.into()), "getProperty")
-                            .into(),
-                        &[qname.into()],
-                        activation,
-                    );
-                }
-            }
+        if let Some(qname) = proxy_name(multiname, activation)? {
+            return receiver.call_property(
+                &QName::new(Namespace::Namespace(NS_FLASH_PROXY.into()), "getProperty").into(),
+                &[qname.into()],
+                activation,
+            );
         }
 
         if !self
@@ -107,26 +150,14 @@ impl<'gc> TObject<'gc> for ProxyObject<'gc> {
         value: Value<'gc>,
         activation: &mut Activation<'_, 'gc, '_>,
     ) -> Result<Option<QName<'gc>>, Error> {
-        // NOTE: This is incorrect behavior.
-        // `QName` should instead store the whole multiname's namespace set,
-        // so that it can be used to index other objects using the same
-        // namespace set.
-        if let Some(local_name) = multiname.local_name() {
-            for namespace in multiname.namespace_set() {
-                if namespace.is_any() || namespace.is_public() || namespace.is_namespace() {
-                    let qname =
-                        QNameObject::from_qname(activation, QName::new(*namespace, local_name))?;
-
-                    receiver.call_property(
-                        &QName::new(Namespace::Namespace(NS_FLASH_PROXY.into()), "setProperty")
-                            .into(),
-                        &[qname.into(), value],
-                        activation,
-                    )?;
-
-                    return Ok(None);
-                }
-            }
+        if let Some(qname) = proxy_name(multiname, activation)? {
+            receiver.call_property(
+                &QName::new(Namespace::Namespace(NS_FLASH_PROXY.into()), "setProperty").into(),
+                &[qname.into(), value],
+                activation,
+            )?;
+
+            return Ok(None);
         }
 
         if !self
@@ -149,27 +180,15 @@ impl<'gc> TObject<'gc> for ProxyObject<'gc> {
         arguments: &[Value<'gc>],
         activation: &mut Activation<'_, 'gc, '_>,
     ) -> Result<Value<'gc>, Error> {
-        // NOTE: This is incorrect behavior.
-        // `QName` should instead store the whole multiname's namespace set,
-        // so that it can be used to index other objects using the same
-        // namespace set.
-        if let Some(local_name) = multiname.local_name() {
-            for namespace in multiname.namespace_set() {
-                if namespace.is_any() || namespace.is_public() || namespace.is_namespace() {
-                    let qname =
-                        QNameObject::from_qname(activation, QName::new(*namespace, local_name))?;
-
-                    let mut args = vec![qname.into()];
-                    args.extend_from_slice(arguments);
-
-                    return self.call_property(
-                        &QName::new(Namespace::Namespace(NS_FLASH_PROXY.into()), "callProperty")
-                            .into(),
-                        &args[..],
-                        activation,
-                    );
-                }
-            }
+        if let Some(qname) = proxy_name(multiname, activation)? {
+            let mut args = vec![qname.into()];
+            args.extend_from_slice(arguments);
+
+            return self.call_property(
+                &QName::new(Namespace::Namespace(NS_FLASH_PROXY.into()), "callProperty").into(),
+                &args[..],
+                activation,
+            );
         }
 
         Err(format!(
@@ -184,29 +203,18 @@ impl<'gc> TObject<'gc> for ProxyObject<'gc> {
         activation: &mut Activation<'_, 'gc, '_>,
         multiname: &Multiname<'gc>,
     ) -> Result<bool, Error> {
-        // NOTE: This is incorrect behavior.
-        // `QName` should instead store the whole multiname's namespace set,
-        // so that it can be used to index other objects using the same
-        // namespace set.
-        if let Some(local_name) = multiname.local_name() {
-            for namespace in multiname.namespace_set() {
-                if namespace.is_any() || namespace.is_public() || namespace.is_namespace() {
-                    let qname =
-                        QNameObject::from_qname(activation, QName::new(*namespace, local_name))?;
-
-                    return Ok(self
-                        .call_property(
-                            &QName::new(
-                                Namespace::Namespace(NS_FLASH_PROXY.into()),
-                                "deleteProperty",
-                            )
-                            .into(),
-                            &[qname.into()],
-                            activation,
-                        )?
-                        .coerce_to_boolean());
-                }
-            }
+        if let Some(qname) = proxy_name(multiname, activation)? {
+            return Ok(self
+                .call_property(
+                    &QName::new(
+                        Namespace::Namespace(NS_FLASH_PROXY.into()),
+                        "deleteProperty",
+                    )
+                    .into(),
+                    &[qname.into()],
+                    activation,
+                )?
+                .coerce_to_boolean());
         }
 
         // Unknown properties on a dynamic class delete successfully.
@@ -269,3 +277,169 @@ impl<'gc> TObject<'gc> for ProxyObject<'gc> {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::avm2::activation::Activation;
+    use crate::avm2::class::Class;
+    use crate::avm2::globals::NS_FLASH_PROXY;
+    use crate::avm2::method::{Method, NativeMethodImpl};
+    use crate::avm2::names::{Namespace, QName};
+    use crate::avm2::object::{ClassObject, Object, TObject};
+    use crate::avm2::test_utils::with_avm2;
+    use crate::avm2::{AvmString, Error, Value};
+
+    fn noop<'gc>(
+        _activation: &mut Activation<'_, 'gc, '_>,
+        _this: Option<Object<'gc>>,
+        _args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, Error> {
+        Ok(Value::Undefined)
+    }
+
+    /// The local name of the `QName` passed to a `flash_proxy` method.
+    fn name_arg<'gc>(args: &[Value<'gc>]) -> AvmString<'gc> {
+        let qname = match args.get(0) {
+            Some(Value::Object(o)) => o.as_qname_object().unwrap(),
+            _ => panic!("Expected a QName argument, got {:?}", args),
+        };
+        let local_name = qname.qname().unwrap().local_name();
+        local_name
+    }
+
+    fn get_property<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        _this: Option<Object<'gc>>,
+        args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, Error> {
+        let name = format!("got {}", name_arg(args));
+        Ok(AvmString::new_utf8(activation.context.gc_context, name).into())
+    }
+
+    fn get_descendants<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        _this: Option<Object<'gc>>,
+        args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, Error> {
+        let name = format!("descendants {}", name_arg(args));
+        Ok(AvmString::new_utf8(activation.context.gc_context, name).into())
+    }
+
+    fn next_name_index<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        _this: Option<Object<'gc>>,
+        args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, Error> {
+        let index = args.get(0).unwrap().coerce_to_u32(activation)?;
+        Ok(if index < 2 { index + 1 } else { 0 }.into())
+    }
+
+    fn next_name<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        _this: Option<Object<'gc>>,
+        args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, Error> {
+        let index = args.get(0).unwrap().coerce_to_u32(activation)?;
+        let name = format!("name {}", index);
+        Ok(AvmString::new_utf8(activation.context.gc_context, name).into())
+    }
+
+    /// Construct a subclass of `Proxy` that overrides some of its methods.
+    fn proxy_subclass<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<ClassObject<'gc>, Error> {
+        let mc = activation.context.gc_context;
+        let proxy_class = activation
+            .avm2()
+            .global_domain()
+            .get_defined_value(
+                activation,
+                QName::new(Namespace::package("flash.utils"), "Proxy"),
+            )?
+            .coerce_to_object(activation)?
+            .as_class_object()
+            .unwrap();
+
+        let class = Class::new(
+            QName::new(Namespace::public(), "TestProxy"),
+            Some(QName::new(Namespace::package("flash.utils"), "Proxy").into()),
+            Method::from_builtin(noop, "<TestProxy instance initializer>", mc),
+            Method::from_builtin(noop, "<TestProxy class initializer>", mc),
+            mc,
+        );
+
+        const FLASH_PROXY_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+            ("getProperty", get_property),
+            ("getDescendants", get_descendants),
+            ("nextNameIndex", next_name_index),
+            ("nextName", next_name),
+        ];
+        class.write(mc).define_ns_builtin_instance_methods(
+            mc,
+            NS_FLASH_PROXY,
+            FLASH_PROXY_INSTANCE_METHODS,
+        );
+
+        ClassObject::from_class(activation, class, Some(proxy_class))
+    }
+
+    /// Tests that reading an undefined property calls the subclass's
+    /// `getProperty`.
+    #[test]
+    fn get_property_override() {
+        with_avm2(|activation| {
+            let class = proxy_subclass(activation)?;
+            let proxy = class.construct(activation, &[])?;
+
+            let value = proxy.get_property(
+                proxy,
+                &QName::new(Namespace::public(), "foo").into(),
+                activation,
+            )?;
+            assert_eq!(value, "got foo".into());
+
+            Ok(())
+        });
+    }
+
+    /// Tests that enumerating a proxy calls the subclass's `nextNameIndex`
+    /// and `nextName`.
+    #[test]
+    fn next_name_override() {
+        with_avm2(|activation| {
+            let class = proxy_subclass(activation)?;
+            let proxy = class.construct(activation, &[])?;
+
+            let mut names = Vec::new();
+            let mut index = 0;
+            while let Some(next) = proxy.get_next_enumerant(index, activation)? {
+                if next == 0 {
+                    break;
+                }
+                names.push(proxy.get_enumerant_name(next, activation)?);
+                index = next;
+            }
+            assert_eq!(names, vec!["name 1".into(), "name 2".into()]);
+
+            Ok(())
+        });
+    }
+
+    /// Tests that the descendants operator calls the subclass's
+    /// `getDescendants`.
+    #[test]
+    fn get_descendants_override() {
+        with_avm2(|activation| {
+            let class = proxy_subclass(activation)?;
+            let proxy = class.construct(activation, &[])?;
+
+            let value = proxy
+                .as_proxy_object()
+                .unwrap()
+                .get_descendants(&QName::new(Namespace::public(), "item").into(), activation)?;
+            assert_eq!(value, "descendants item".into());
+
+            Ok(())
+        });
+    }
+}
@@ -20,7 +20,11 @@ pub fn sound_allocator<'gc>(
 
     Ok(SoundObject(GcCell::allocate(
         activation.context.gc_context,
-        SoundObjectData { base, sound: None },
+        SoundObjectData {
+            base,
+            sound: None,
+            extract_position: 0,
+        },
     ))
     .into())
 }
@@ -38,6 +42,10 @@ pub struct SoundObjectData<'gc> {
     /// The sound this object holds.
     #[collect(require_static)]
     sound: Option<SoundHandle>,
+
+    /// The sample frame that the next call to `extract` without a start
+    /// position continues from.
+    extract_position: u32,
 }
 
 impl<'gc> SoundObject<'gc> {
@@ -65,6 +73,7 @@ impl<'gc> SoundObject<'gc> {
             SoundObjectData {
                 base,
                 sound: Some(sound),
+                extract_position: 0,
             },
         ))
         .into();
@@ -74,6 +83,14 @@ impl<'gc> SoundObject<'gc> {
 
         Ok(sound_object)
     }
+
+    pub fn extract_position(self) -> u32 {
+        self.0.read().extract_position
+    }
+
+    pub fn set_extract_position(self, mc: MutationContext<'gc, '_>, position: u32) {
+        self.0.write(mc).extract_position = position;
+    }
 }
 
 impl<'gc> TObject<'gc> for SoundObject<'gc> {
@@ -98,7 +115,11 @@ impl<'gc> TObject<'gc> for SoundObject<'gc> {
 
         Ok(SoundObject(GcCell::allocate(
             activation.context.gc_context,
-            SoundObjectData { base, sound: None },
+            SoundObjectData {
+                base,
+                sound: None,
+                extract_position: 0,
+            },
         ))
         .into())
     }
@@ -107,6 +128,10 @@ impl<'gc> TObject<'gc> for SoundObject<'gc> {
         self.0.read().sound
     }
 
+    fn as_sound_object(self) -> Option<SoundObject<'gc>> {
+        Some(self)
+    }
+
     /// Associate the object with a particular sound handle.
     ///
     /// This does nothing if the object is not a sound.
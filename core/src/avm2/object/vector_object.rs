@@ -109,7 +109,7 @@ impl<'gc> TObject<'gc> for VectorObject<'gc> {
 
         if name.namespace().is_package("") {
             if let Ok(index) = name.local_name().parse::<usize>() {
-                return Ok(read.vector.get(index).unwrap_or(Value::Undefined));
+                return read.vector.get(index);
             }
         }
 
@@ -129,12 +129,8 @@ impl<'gc> TObject<'gc> for VectorObject<'gc> {
     ) -> Result<(), Error> {
         if name.namespace().is_package("") {
             if let Ok(index) = name.local_name().parse::<usize>() {
-                let type_of = self.0.read().vector.value_type();
-                let value = match value.coerce_to_type(activation, type_of)? {
-                    Value::Undefined => self.0.read().vector.default(activation),
-                    Value::Null => self.0.read().vector.default(activation),
-                    v => v,
-                };
+                let value_type = self.0.read().vector.value_type();
+                let value = VectorStorage::coerce(value, value_type, activation)?;
 
                 self.0
                     .write(activation.context.gc_context)
@@ -167,12 +163,8 @@ impl<'gc> TObject<'gc> for VectorObject<'gc> {
     ) -> Result<(), Error> {
         if name.namespace().is_package("") {
             if let Ok(index) = name.local_name().parse::<usize>() {
-                let type_of = self.0.read().vector.value_type();
-                let value = match value.coerce_to_type(activation, type_of)? {
-                    Value::Undefined => self.0.read().vector.default(activation),
-                    Value::Null => self.0.read().vector.default(activation),
-                    v => v,
-                };
+                let value_type = self.0.read().vector.value_type();
+                let value = VectorStorage::coerce(value, value_type, activation)?;
 
                 self.0
                     .write(activation.context.gc_context)
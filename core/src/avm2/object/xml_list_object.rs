@@ -0,0 +1,357 @@
+//! Object representation for XMLList objects
+
+use crate::avm2::activation::Activation;
+use crate::avm2::e4x::E4XNode;
+use crate::avm2::names::{Multiname, QName};
+use crate::avm2::object::script_object::ScriptObjectData;
+use crate::avm2::object::xml_object::{attribute_value, call_e4x_method, list_index, xml_nodes};
+use crate::avm2::object::{ClassObject, Object, ObjectPtr, TObject, XmlObject};
+use crate::avm2::value::{Hint, Value};
+use crate::avm2::Error;
+use gc_arena::{Collect, GcCell, MutationContext};
+use std::cell::{Ref, RefMut};
+
+/// A class instance allocator that allocates XMLList objects.
+pub fn xml_list_allocator<'gc>(
+    class: ClassObject<'gc>,
+    proto: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Object<'gc>, Error> {
+    let base = ScriptObjectData::base_new(Some(proto), Some(class));
+
+    Ok(XmlListObject(GcCell::allocate(
+        activation.context.gc_context,
+        XmlListObjectData {
+            base,
+            children: Vec::new(),
+        },
+    ))
+    .into())
+}
+
+#[derive(Clone, Collect, Debug, Copy)]
+#[collect(no_drop)]
+pub struct XmlListObject<'gc>(GcCell<'gc, XmlListObjectData<'gc>>);
+
+#[derive(Clone, Collect, Debug)]
+#[collect(no_drop)]
+pub struct XmlListObjectData<'gc> {
+    /// Base script object
+    base: ScriptObjectData<'gc>,
+
+    /// The XML nodes in this list.
+    children: Vec<E4XNode<'gc>>,
+}
+
+impl<'gc> XmlListObject<'gc> {
+    /// Construct an `XMLList` containing some nodes.
+    pub fn new(
+        activation: &mut Activation<'_, 'gc, '_>,
+        children: Vec<E4XNode<'gc>>,
+    ) -> Result<Object<'gc>, Error> {
+        let class = activation.avm2().classes().xml_list;
+        let proto = activation.avm2().prototypes().xml_list;
+        let base = ScriptObjectData::base_new(Some(proto), Some(class));
+
+        let mut this: Object<'gc> = XmlListObject(GcCell::allocate(
+            activation.context.gc_context,
+            XmlListObjectData { base, children },
+        ))
+        .into();
+        this.install_instance_traits(activation, class)?;
+
+        Ok(this)
+    }
+
+    pub fn children(self) -> Vec<E4XNode<'gc>> {
+        self.0.read().children.clone()
+    }
+
+    pub fn set_children(self, mc: MutationContext<'gc, '_>, children: Vec<E4XNode<'gc>>) {
+        self.0.write(mc).children = children;
+    }
+
+    /// The only item of this list, if it has exactly one.
+    fn single_item(self) -> Option<E4XNode<'gc>> {
+        match self.0.read().children.as_slice() {
+            [node] => Some(*node),
+            _ => None,
+        }
+    }
+}
+
+impl<'gc> TObject<'gc> for XmlListObject<'gc> {
+    fn base(&self) -> Ref<ScriptObjectData<'gc>> {
+        Ref::map(self.0.read(), |read| &read.base)
+    }
+
+    fn base_mut(&self, mc: MutationContext<'gc, '_>) -> RefMut<ScriptObjectData<'gc>> {
+        RefMut::map(self.0.write(mc), |write| &mut write.base)
+    }
+
+    fn as_ptr(&self) -> *const ObjectPtr {
+        self.0.as_ptr() as *const ObjectPtr
+    }
+
+    fn derive(&self, activation: &mut Activation<'_, 'gc, '_>) -> Result<Object<'gc>, Error> {
+        let this: Object<'gc> = Object::XmlListObject(*self);
+        let base = ScriptObjectData::base_new(Some(this), None);
+
+        Ok(XmlListObject(GcCell::allocate(
+            activation.context.gc_context,
+            XmlListObjectData {
+                base,
+                children: Vec::new(),
+            },
+        ))
+        .into())
+    }
+
+    fn value_of(&self, _mc: MutationContext<'gc, '_>) -> Result<Value<'gc>, Error> {
+        Ok(Value::Object(Object::from(*self)))
+    }
+
+    fn default_hint(&self) -> Hint {
+        Hint::String
+    }
+
+    fn get_property(
+        self,
+        _receiver: Object<'gc>,
+        multiname: &Multiname<'gc>,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error> {
+        if let Some(index) = list_index(multiname) {
+            return match self.0.read().children.get(index).copied() {
+                Some(node) => Ok(XmlObject::new(activation, node)?.into()),
+                None => Ok(Value::Undefined),
+            };
+        }
+
+        let default_namespace = activation.default_xml_namespace();
+        let mut nodes = Vec::new();
+        for child in self.children() {
+            if child.is_element() {
+                nodes.extend(child.children_by_name(multiname, default_namespace));
+            }
+        }
+
+        Ok(XmlListObject::new(activation, nodes)?.into())
+    }
+
+    fn set_property(
+        &mut self,
+        receiver: Object<'gc>,
+        multiname: &Multiname<'gc>,
+        value: Value<'gc>,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
+        if let Some(index) = list_index(multiname) {
+            let nodes = match value {
+                Value::Object(object) => xml_nodes(object),
+                _ => None,
+            };
+            let mc = activation.context.gc_context;
+            let existing = self.0.read().children.get(index).copied();
+
+            match (existing, nodes) {
+                // Replace the item, and the node it stands for in its parent.
+                (Some(old), Some(nodes)) => {
+                    if let (Some(parent), Some(child_index)) = (old.parent(), old.child_index()) {
+                        let copies: Vec<_> = nodes.iter().map(|node| node.deep_copy(mc)).collect();
+                        parent.replace_child(mc, child_index, &copies);
+                        self.0.write(mc).children.splice(index..=index, copies);
+                    } else {
+                        self.0.write(mc).children.splice(index..=index, nodes);
+                    }
+                }
+                (Some(old), None) => {
+                    if old.is_attribute() {
+                        let text = attribute_value(activation, value)?;
+                        let name = old.local_name().unwrap_or_default();
+                        let name = Multiname::from(QName::dynamic_name(name)).into_attribute();
+                        if let Some(parent) = old.parent() {
+                            parent.set_attribute(activation.context.gc_context, &name, text);
+                        }
+                    } else {
+                        let text = value.coerce_to_string(activation)?;
+                        let mc = activation.context.gc_context;
+                        if old.is_element() {
+                            if text.is_empty() {
+                                old.set_children(mc, &[])?;
+                            } else {
+                                old.set_children(mc, &[E4XNode::text(mc, text)])?;
+                            }
+                        } else {
+                            let text = E4XNode::text(mc, text);
+                            if let (Some(parent), Some(child_index)) =
+                                (old.parent(), old.child_index())
+                            {
+                                parent.replace_child(mc, child_index, &[text]);
+                            }
+                            self.0.write(mc).children[index] = text;
+                        }
+                    }
+                }
+                // Assigning past the end of the list appends to it.
+                (None, Some(nodes)) => {
+                    self.0.write(mc).children.extend(nodes);
+                }
+                (None, None) => {
+                    let text = value.coerce_to_string(activation)?;
+                    let mc = activation.context.gc_context;
+                    self.0.write(mc).children.push(E4XNode::text(mc, text));
+                }
+            }
+
+            return Ok(());
+        }
+
+        match self.single_item() {
+            Some(node) => {
+                let mut xml = XmlObject::new(activation, node)?;
+                xml.set_property(receiver, multiname, value, activation)
+            }
+            None => Err(
+                "TypeError: Error #1089: Assignment to lists with more than one item is not supported."
+                    .into(),
+            ),
+        }
+    }
+
+    fn delete_property(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        multiname: &Multiname<'gc>,
+    ) -> Result<bool, Error> {
+        let mc = activation.context.gc_context;
+
+        if let Some(index) = list_index(multiname) {
+            let removed = {
+                let mut write = self.0.write(mc);
+                if index < write.children.len() {
+                    Some(write.children.remove(index))
+                } else {
+                    None
+                }
+            };
+
+            if let Some(node) = removed {
+                if let Some(parent) = node.parent() {
+                    if node.is_attribute() {
+                        parent.remove_attribute(mc, node);
+                    } else {
+                        parent.remove_child(mc, node);
+                    }
+                }
+            }
+
+            return Ok(true);
+        }
+
+        let default_namespace = activation.default_xml_namespace();
+        for child in self.children() {
+            for node in child.children_by_name(multiname, default_namespace) {
+                if node.is_attribute() {
+                    child.remove_attribute(mc, node);
+                } else {
+                    child.remove_child(mc, node);
+                }
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn call_property(
+        self,
+        multiname: &Multiname<'gc>,
+        arguments: &[Value<'gc>],
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error> {
+        if let Some(value) = call_e4x_method(self.into(), multiname, arguments, activation)? {
+            return Ok(value);
+        }
+
+        // A list with one item can be used like that item.
+        if let Some(node) = self.single_item() {
+            let xml = XmlObject::new(activation, node)?;
+            return xml.call_property(multiname, arguments, activation);
+        }
+
+        Err(format!(
+            "TypeError: Error #1006: {} is not a function.",
+            multiname.local_name().unwrap_or_default()
+        )
+        .into())
+    }
+
+    fn has_property_via_in(
+        self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        name: QName<'gc>,
+    ) -> Result<bool, Error> {
+        let name = name.into();
+        if let Some(index) = list_index(&name) {
+            return Ok(index < self.0.read().children.len());
+        }
+
+        let default_namespace = activation.default_xml_namespace();
+        Ok(self
+            .children()
+            .iter()
+            .any(|child| !child.children_by_name(&name, default_namespace).is_empty()))
+    }
+
+    fn has_xml_property(self, name: &Multiname<'gc>) -> bool {
+        self.children()
+            .iter()
+            .any(|child| !child.children_by_name(name, "".into()).is_empty())
+    }
+
+    fn get_next_enumerant(
+        self,
+        last_index: u32,
+        _activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Option<u32>, Error> {
+        Ok(if (last_index as usize) < self.0.read().children.len() {
+            Some(last_index + 1)
+        } else {
+            None
+        })
+    }
+
+    fn get_enumerant_name(
+        self,
+        index: u32,
+        _activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error> {
+        Ok(
+            if index > 0 && (index as usize) <= self.0.read().children.len() {
+                (index - 1).into()
+            } else {
+                Value::Undefined
+            },
+        )
+    }
+
+    fn get_enumerant_value(
+        self,
+        index: u32,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error> {
+        let node = match index.checked_sub(1) {
+            Some(index) => self.0.read().children.get(index as usize).copied(),
+            None => None,
+        };
+
+        match node {
+            Some(node) => Ok(XmlObject::new(activation, node)?.into()),
+            None => Ok(Value::Undefined),
+        }
+    }
+
+    fn as_xml_list_object(&self) -> Option<XmlListObject<'gc>> {
+        Some(*self)
+    }
+}
@@ -1,10 +1,13 @@
 //! Object representation for XML objects
 
 use crate::avm2::activation::Activation;
+use crate::avm2::e4x::E4XNode;
+use crate::avm2::names::{Multiname, QName};
 use crate::avm2::object::script_object::ScriptObjectData;
-use crate::avm2::object::{ClassObject, Object, ObjectPtr, TObject};
-use crate::avm2::value::Value;
+use crate::avm2::object::{ClassObject, Object, ObjectPtr, TObject, XmlListObject};
+use crate::avm2::value::{Hint, Value};
 use crate::avm2::Error;
+use crate::string::{AvmString, WString};
 use gc_arena::{Collect, GcCell, MutationContext};
 use std::cell::{Ref, RefMut};
 
@@ -15,10 +18,11 @@ pub fn xml_allocator<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
 ) -> Result<Object<'gc>, Error> {
     let base = ScriptObjectData::base_new(Some(proto), Some(class));
+    let node = E4XNode::text(activation.context.gc_context, "".into());
 
     Ok(XmlObject(GcCell::allocate(
         activation.context.gc_context,
-        XmlObjectData { base },
+        XmlObjectData { base, node },
     ))
     .into())
 }
@@ -32,6 +36,119 @@ pub struct XmlObject<'gc>(GcCell<'gc, XmlObjectData<'gc>>);
 pub struct XmlObjectData<'gc> {
     /// Base script object
     base: ScriptObjectData<'gc>,
+
+    /// The XML node this object represents.
+    node: E4XNode<'gc>,
+}
+
+impl<'gc> XmlObject<'gc> {
+    /// Wrap an XML node in an `XML` object.
+    pub fn new(
+        activation: &mut Activation<'_, 'gc, '_>,
+        node: E4XNode<'gc>,
+    ) -> Result<Object<'gc>, Error> {
+        let class = activation.avm2().classes().xml;
+        let proto = activation.avm2().prototypes().xml;
+        let base = ScriptObjectData::base_new(Some(proto), Some(class));
+
+        let mut this: Object<'gc> = XmlObject(GcCell::allocate(
+            activation.context.gc_context,
+            XmlObjectData { base, node },
+        ))
+        .into();
+        this.install_instance_traits(activation, class)?;
+
+        Ok(this)
+    }
+
+    pub fn node(self) -> E4XNode<'gc> {
+        self.0.read().node
+    }
+
+    pub fn set_node(self, mc: MutationContext<'gc, '_>, node: E4XNode<'gc>) {
+        self.0.write(mc).node = node;
+    }
+}
+
+/// The nodes of an `XML` object, or the items of an `XMLList`.
+///
+/// Yields `None` for other objects.
+pub fn xml_nodes<'gc>(object: Object<'gc>) -> Option<Vec<E4XNode<'gc>>> {
+    if let Some(xml) = object.as_xml_object() {
+        return Some(vec![xml.node()]);
+    }
+
+    object.as_xml_list_object().map(|list| list.children())
+}
+
+/// The index named by an E4X property name, if it is one.
+pub fn list_index(name: &Multiname<'_>) -> Option<usize> {
+    if name.is_attribute() {
+        return None;
+    }
+
+    name.local_name()?.parse::<u32>().ok().map(|i| i as usize)
+}
+
+/// Convert a value assigned to an XML attribute to a string.
+///
+/// The items of XML lists are separated by spaces.
+pub fn attribute_value<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<AvmString<'gc>, Error> {
+    if let Value::Object(object) = value {
+        if let Some(list) = object.as_xml_list_object() {
+            let settings = activation.avm2().xml_settings();
+            let mut out = WString::new();
+            for (i, node) in list.children().into_iter().enumerate() {
+                if i > 0 {
+                    out.push_byte(b' ');
+                }
+                out.push_str(&node.xml_to_string(activation.context.gc_context, settings));
+            }
+            return Ok(AvmString::new(activation.context.gc_context, out));
+        }
+    }
+
+    value.coerce_to_string(activation)
+}
+
+/// Call a method of an `XML` or `XMLList` object.
+///
+/// The properties of E4X objects are their XML children, so their methods are
+/// looked up in their class and prototype chain instead of with
+/// `get_property`. Yields `None` if there is no such method.
+pub fn call_e4x_method<'gc>(
+    this: Object<'gc>,
+    multiname: &Multiname<'gc>,
+    arguments: &[Value<'gc>],
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Option<Value<'gc>>, Error> {
+    let name = match this.resolve_multiname(multiname)? {
+        Some(name) => name,
+        None => return Ok(None),
+    };
+
+    if let Some(class) = this.instance_of() {
+        if let Some((method, _)) = class.bound_instance_method(activation, this, name)? {
+            return method.call(Some(this), arguments, activation).map(Some);
+        }
+    }
+
+    let mut proto = this.proto();
+    while let Some(p) = proto {
+        if p.has_own_property(name)? {
+            let function = p
+                .get_property_local(this, name, activation)?
+                .coerce_to_object(activation)?;
+            return function.call(Some(this), arguments, activation).map(Some);
+        }
+
+        proto = p.proto();
+    }
+
+    Ok(None)
 }
 
 impl<'gc> TObject<'gc> for XmlObject<'gc> {
@@ -50,10 +167,11 @@ impl<'gc> TObject<'gc> for XmlObject<'gc> {
     fn derive(&self, activation: &mut Activation<'_, 'gc, '_>) -> Result<Object<'gc>, Error> {
         let this: Object<'gc> = Object::XmlObject(*self);
         let base = ScriptObjectData::base_new(Some(this), None);
+        let node = E4XNode::text(activation.context.gc_context, "".into());
 
         Ok(XmlObject(GcCell::allocate(
             activation.context.gc_context,
-            XmlObjectData { base },
+            XmlObjectData { base, node },
         ))
         .into())
     }
@@ -61,4 +179,215 @@ impl<'gc> TObject<'gc> for XmlObject<'gc> {
     fn value_of(&self, _mc: MutationContext<'gc, '_>) -> Result<Value<'gc>, Error> {
         Ok(Value::Object(Object::from(*self)))
     }
+
+    fn default_hint(&self) -> Hint {
+        Hint::String
+    }
+
+    fn get_property(
+        self,
+        _receiver: Object<'gc>,
+        multiname: &Multiname<'gc>,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error> {
+        // An XML object acts like a list that only contains itself.
+        if let Some(index) = list_index(multiname) {
+            return Ok(if index == 0 {
+                self.into()
+            } else {
+                Value::Undefined
+            });
+        }
+
+        let nodes = self
+            .node()
+            .children_by_name(multiname, activation.default_xml_namespace());
+
+        Ok(XmlListObject::new(activation, nodes)?.into())
+    }
+
+    fn set_property(
+        &mut self,
+        _receiver: Object<'gc>,
+        multiname: &Multiname<'gc>,
+        value: Value<'gc>,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
+        let node = self.node();
+
+        if multiname.is_attribute() {
+            let value = attribute_value(activation, value)?;
+            node.set_attribute(activation.context.gc_context, multiname, value);
+            return Ok(());
+        }
+
+        if list_index(multiname).is_some() {
+            return Err("TypeError: Error #1087: Assignment to indexed XML is not allowed.".into());
+        }
+
+        let default_namespace = activation.default_xml_namespace();
+        let matching = node.children_by_name(multiname, default_namespace);
+
+        // Only the first matching child is kept.
+        let mc = activation.context.gc_context;
+        let index = if let Some((first, rest)) = matching.split_first() {
+            for child in rest {
+                node.remove_child(mc, *child);
+            }
+            first.child_index()
+        } else {
+            None
+        };
+
+        let index = match (index, multiname.local_name()) {
+            (Some(index), _) => index,
+            (None, Some(local_name)) if &local_name != b"*" => {
+                let namespace =
+                    crate::avm2::e4x::name_namespace(multiname).unwrap_or(default_namespace);
+                node.append_child(mc, E4XNode::element(mc, namespace, local_name))?;
+                node.children().len() - 1
+            }
+            _ => return Ok(()),
+        };
+
+        let nodes = match value {
+            Value::Object(object) => xml_nodes(object),
+            _ => None,
+        };
+
+        match nodes {
+            Some(nodes) if nodes.iter().any(|node| !node.is_attribute()) => {
+                let copies: Vec<_> = nodes
+                    .iter()
+                    .filter(|node| !node.is_attribute())
+                    .map(|node| node.deep_copy(mc))
+                    .collect();
+                node.replace_child(mc, index, &copies);
+            }
+            _ => {
+                let text = value.coerce_to_string(activation)?;
+                let mc = activation.context.gc_context;
+                if let Some(child) = node.children().get(index) {
+                    if text.is_empty() {
+                        child.set_children(mc, &[])?;
+                    } else {
+                        child.set_children(mc, &[E4XNode::text(mc, text)])?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn delete_property(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        multiname: &Multiname<'gc>,
+    ) -> Result<bool, Error> {
+        let node = self.node();
+        let mc = activation.context.gc_context;
+        for child in node.children_by_name(multiname, activation.default_xml_namespace()) {
+            if child.is_attribute() {
+                node.remove_attribute(mc, child);
+            } else {
+                node.remove_child(mc, child);
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn call_property(
+        self,
+        multiname: &Multiname<'gc>,
+        arguments: &[Value<'gc>],
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error> {
+        if let Some(value) = call_e4x_method(self.into(), multiname, arguments, activation)? {
+            return Ok(value);
+        }
+
+        // XML with simple content can be used like the string it contains.
+        let node = self.node();
+        if node.has_simple_content() {
+            let settings = activation.avm2().xml_settings();
+            let string = node.xml_to_string(activation.context.gc_context, settings);
+            return Value::from(string)
+                .coerce_to_object(activation)?
+                .call_property(multiname, arguments, activation);
+        }
+
+        Err(format!(
+            "TypeError: Error #1006: {} is not a function.",
+            multiname.local_name().unwrap_or_default()
+        )
+        .into())
+    }
+
+    fn has_property_via_in(
+        self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        name: QName<'gc>,
+    ) -> Result<bool, Error> {
+        let name = name.into();
+        if let Some(index) = list_index(&name) {
+            return Ok(index == 0);
+        }
+
+        Ok(!self
+            .node()
+            .children_by_name(&name, activation.default_xml_namespace())
+            .is_empty())
+    }
+
+    fn has_xml_property(self, name: &Multiname<'gc>) -> bool {
+        !self.node().children_by_name(name, "".into()).is_empty()
+    }
+
+    fn get_next_enumerant(
+        self,
+        last_index: u32,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Option<u32>, Error> {
+        // The `XML` prototype doesn't act like an XML object.
+        let is_instance = self
+            .instance_of()
+            .map(|class| Object::ptr_eq(class, activation.avm2().classes().xml))
+            .unwrap_or(false);
+
+        Ok(if is_instance && last_index == 0 {
+            Some(1)
+        } else {
+            None
+        })
+    }
+
+    fn get_enumerant_name(
+        self,
+        index: u32,
+        _activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error> {
+        Ok(if index == 1 {
+            0.into()
+        } else {
+            Value::Undefined
+        })
+    }
+
+    fn get_enumerant_value(
+        self,
+        index: u32,
+        _activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error> {
+        Ok(if index == 1 {
+            self.into()
+        } else {
+            Value::Undefined
+        })
+    }
+
+    fn as_xml_object(&self) -> Option<XmlObject<'gc>> {
+        Some(*self)
+    }
 }
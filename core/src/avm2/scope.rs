@@ -6,6 +6,7 @@ use crate::avm2::names::Multiname;
 use crate::avm2::object::{Object, TObject};
 use crate::avm2::value::Value;
 use crate::avm2::Error;
+use crate::string::AvmString;
 use gc_arena::{Collect, Gc, MutationContext};
 use std::ops::Deref;
 
@@ -64,6 +65,12 @@ impl<'gc> Scope<'gc> {
 pub struct ScopeChain<'gc> {
     scopes: Option<Gc<'gc, Vec<Scope<'gc>>>>,
     domain: Domain<'gc>,
+
+    /// The default XML namespace of the code that created this ScopeChain.
+    ///
+    /// Functions closing over this ScopeChain start out with it, so that
+    /// `default xml namespace` is lexically scoped.
+    default_xml_namespace: Option<AvmString<'gc>>,
 }
 
 impl<'gc> ScopeChain<'gc> {
@@ -72,6 +79,7 @@ impl<'gc> ScopeChain<'gc> {
         Self {
             scopes: None,
             domain,
+            default_xml_namespace: None,
         }
     }
 
@@ -91,6 +99,7 @@ impl<'gc> ScopeChain<'gc> {
                 Self {
                     scopes: Some(Gc::allocate(mc, cloned)),
                     domain: self.domain,
+                    default_xml_namespace: self.default_xml_namespace,
                 }
             }
             None => {
@@ -99,6 +108,7 @@ impl<'gc> ScopeChain<'gc> {
                 Self {
                     scopes: Some(Gc::allocate(mc, new_scopes.to_vec())),
                     domain: self.domain,
+                    default_xml_namespace: self.default_xml_namespace,
                 }
             }
         }
@@ -117,6 +127,21 @@ impl<'gc> ScopeChain<'gc> {
         self.domain
     }
 
+    /// Returns the default XML namespace that functions closing over this
+    /// ScopeChain start out with.
+    pub fn default_xml_namespace(&self) -> Option<AvmString<'gc>> {
+        self.default_xml_namespace
+    }
+
+    /// Returns this ScopeChain with its default XML namespace replaced.
+    pub fn with_default_xml_namespace(
+        mut self,
+        default_xml_namespace: Option<AvmString<'gc>>,
+    ) -> Self {
+        self.default_xml_namespace = default_xml_namespace;
+        self
+    }
+
     pub fn find(
         &self,
         name: &Multiname<'gc>,
@@ -126,6 +151,12 @@ impl<'gc> ScopeChain<'gc> {
         if let Some(scopes) = self.scopes {
             for (depth, scope) in scopes.iter().enumerate().rev() {
                 let values = scope.values();
+                // The children of XML objects in `with` scopes aren't
+                // properties, but are still found by name.
+                if scope.with() && values.has_xml_property(name) {
+                    return Ok(Some(values));
+                }
+
                 if let Some(qname) = values.resolve_multiname(name)? {
                     // We search the dynamic properties if either conditions are met:
                     // 1. Scope is a `with` scope
@@ -198,6 +229,10 @@ impl<'gc> ScopeStack<'gc> {
     pub fn find(&self, name: &Multiname<'gc>, global: bool) -> Result<Option<Object<'gc>>, Error> {
         for (depth, scope) in self.scopes.iter().enumerate().rev() {
             let values = scope.values();
+            if scope.with() && values.has_xml_property(name) {
+                return Ok(Some(values));
+            }
+
             if let Some(qname) = values.resolve_multiname(name)? {
                 // We search the dynamic properties if either conditions are met:
                 // 1. Scope is a `with` scope
@@ -0,0 +1,104 @@
+use crate::avm1::globals::system::SystemProperties;
+use crate::avm1::{Avm1, Timers};
+use crate::avm2::activation::Activation;
+use crate::avm2::{Avm2, Error};
+use crate::backend::audio::{AudioManager, NullAudioBackend};
+use crate::backend::font::NullFontBackend;
+use crate::backend::locale::NullLocaleBackend;
+use crate::backend::log::NullLogBackend;
+use crate::backend::navigator::NullNavigatorBackend;
+use crate::backend::render::NullRenderer;
+use crate::backend::storage::MemoryStorageBackend;
+use crate::backend::ui::NullUiBackend;
+use crate::backend::video::NullVideoBackend;
+use crate::context::{ActionQueue, UpdateContext};
+use crate::display_object::Stage;
+use crate::focus_tracker::FocusTracker;
+use crate::library::Library;
+use crate::loader::LoadManager;
+use crate::local_connection::LocalConnections;
+use crate::microphone::Microphone;
+use crate::prelude::*;
+use crate::socket::Sockets;
+use crate::streams::StreamManager;
+use crate::tag_utils::SwfMovie;
+use gc_arena::{rootless_arena, MutationContext};
+use instant::Instant;
+use rand::{rngs::SmallRng, SeedableRng};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Run a test in an activation with the player globals loaded.
+pub fn with_avm2<F>(test: F)
+where
+    F: for<'gc> FnOnce(&mut Activation<'_, 'gc, '_>) -> Result<(), Error>,
+{
+    fn in_the_arena<'gc, F>(test: F, gc_context: MutationContext<'gc, '_>)
+    where
+        F: FnOnce(&mut Activation<'_, 'gc, '_>) -> Result<(), Error>,
+    {
+        let mut avm1 = Avm1::new(gc_context, 10);
+        let mut avm2 = Avm2::new(gc_context);
+        let swf = Arc::new(SwfMovie::empty(10));
+        let stage = Stage::empty(gc_context, 550, 400);
+        let mut frame_rate = 12.0;
+
+        let mut context = UpdateContext {
+            gc_context,
+            player_version: 32,
+            swf: &swf,
+            stage,
+            rng: &mut SmallRng::from_seed([0u8; 32]),
+            audio: &mut NullAudioBackend::new(),
+            ui: &mut NullUiBackend::new(),
+            action_queue: &mut ActionQueue::new(),
+            library: &mut Library::empty(gc_context),
+            navigator: &mut NullNavigatorBackend::new(),
+            renderer: &mut NullRenderer::new(),
+            locale: &mut NullLocaleBackend::new(),
+            fonts: &mut NullFontBackend::new(),
+            log: &mut NullLogBackend::new(),
+            video: &mut NullVideoBackend::new(),
+            mouse_over_object: None,
+            mouse_down_object: None,
+            mouse_position: &(Twips::ZERO, Twips::ZERO),
+            drag_object: &mut None,
+            player: None,
+            load_manager: &mut LoadManager::new(),
+            system: &mut SystemProperties::default(),
+            instance_counter: &mut 0,
+            storage: &mut MemoryStorageBackend::default(),
+            shared_objects: &mut HashMap::new(),
+            unbound_text_fields: &mut Vec::new(),
+            timers: &mut Timers::new(),
+            current_context_menu: &mut None,
+            needs_render: &mut false,
+            avm1: &mut avm1,
+            avm2: &mut avm2,
+            external_interface: &mut Default::default(),
+            update_start: Instant::now(),
+            max_execution_duration: Duration::from_secs(15),
+            focus_tracker: FocusTracker::new(gc_context),
+            times_get_time_called: 0,
+            time_offset: &mut 0,
+            audio_manager: &mut AudioManager::new(),
+            stream_manager: &mut StreamManager::new(),
+            microphone: &mut Microphone::new(),
+            sockets: &mut Sockets::new(),
+            local_connections: &mut LocalConnections::new(),
+            frame_rate: &mut frame_rate,
+        };
+
+        if let Err(e) = Avm2::load_player_globals(&mut context) {
+            panic!("Could not load the player globals: {}", e);
+        }
+
+        let mut activation = Activation::from_nothing(context);
+        if let Err(e) = test(&mut activation) {
+            panic!("Encountered exception during test: {}", e);
+        }
+    }
+
+    rootless_arena(|gc_context| in_the_arena(test, gc_context))
+}
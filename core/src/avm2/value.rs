@@ -617,9 +617,16 @@ impl<'gc> Value<'gc> {
             }
         }
 
+        // Other values are converted to E4X objects by their constructors.
+        if Object::ptr_eq(class, activation.avm2().classes().xml)
+            || Object::ptr_eq(class, activation.avm2().classes().xml_list)
+        {
+            return Ok(class.construct(activation, &[*self])?.into());
+        }
+
         let static_class = class.inner_class_definition();
         Err(format!(
-            "Cannot coerce {:?} to an {:?}",
+            "TypeError: Error #1034: Type Coercion failed: cannot convert {:?} to {:?}.",
             self,
             static_class.read().name()
         )
@@ -650,6 +657,21 @@ impl<'gc> Value<'gc> {
         }
     }
 
+    /// Get the element index this value names, if it is a primitive number
+    /// representable as a u32 without loss of precision.
+    ///
+    /// Unlike `is_u32`, this does not look inside boxed primitives, so it is
+    /// cheap enough to use on every element access.
+    #[allow(clippy::float_cmp)]
+    pub fn as_element_index(&self) -> Option<usize> {
+        match self {
+            Value::Number(n) if *n == (*n as u32 as f64) => Some(*n as u32 as usize),
+            Value::Integer(i) if *i >= 0 => Some(*i as usize),
+            Value::Unsigned(u) => Some(*u as usize),
+            _ => None,
+        }
+    }
+
     /// Determine if this value is a number representable as an i32 without
     /// loss of precision.
     #[allow(clippy::float_cmp)]
@@ -731,7 +753,21 @@ impl<'gc> Value<'gc> {
             }
             (Value::String(a), Value::String(b)) => Ok(a == b),
             (Value::Bool(a), Value::Bool(b)) => Ok(a == b),
-            (Value::Object(a), Value::Object(b)) => Ok(Object::ptr_eq(*a, *b)),
+            (Value::Object(a), Value::Object(b)) => {
+                if let (Some(a), Some(b)) = (a.as_xml_object(), b.as_xml_object()) {
+                    // XML objects are equal if they hold equal content.
+                    let (a, b) = (a.node(), b.node());
+                    let settings = activation.avm2().xml_settings();
+                    return Ok(if a.has_simple_content() && b.has_simple_content() {
+                        let mc = activation.context.gc_context;
+                        a.xml_to_string(mc, settings) == b.xml_to_string(mc, settings)
+                    } else {
+                        a.to_xml_string(settings) == b.to_xml_string(settings)
+                    });
+                }
+
+                Ok(Object::ptr_eq(*a, *b))
+            }
             (Value::Undefined, Value::Null) => Ok(true),
             (Value::Null, Value::Undefined) => Ok(true),
             (Value::Number(_) | Value::Unsigned(_) | Value::Integer(_), Value::String(_)) => {
@@ -9,6 +9,9 @@ use std::cmp::{max, min};
 use std::ops::{Index, RangeBounds};
 use std::slice::SliceIndex;
 
+/// The error thrown when attempting to change the length of a fixed vector.
+const FIXED_ERROR: &str = "RangeError: Error #1126: Cannot change the length of a fixed Vector.";
+
 /// The vector storage portion of a vector object.
 ///
 /// Vector values are restricted to a single type, decided at the time of the
@@ -94,7 +97,7 @@ impl<'gc> VectorStorage<'gc> {
         activation: &mut Activation<'_, 'gc, '_>,
     ) -> Result<(), Error> {
         if self.is_fixed {
-            return Err("RangeError: Vector is fixed".into());
+            return Err(FIXED_ERROR.into());
         }
 
         self.storage.resize(new_length, self.default(activation));
@@ -104,22 +107,58 @@ impl<'gc> VectorStorage<'gc> {
 
     /// Get the default value for this vector.
     pub fn default(&self, activation: &mut Activation<'_, 'gc, '_>) -> Value<'gc> {
-        if Object::ptr_eq(self.value_type, activation.avm2().classes().int) {
+        Self::default_for(self.value_type, activation)
+    }
+
+    /// Get the default value for vectors of a given value type.
+    fn default_for(
+        value_type: ClassObject<'gc>,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Value<'gc> {
+        if Object::ptr_eq(value_type, activation.avm2().classes().int) {
             Value::Integer(0)
-        } else if Object::ptr_eq(self.value_type, activation.avm2().classes().uint) {
+        } else if Object::ptr_eq(value_type, activation.avm2().classes().uint) {
             Value::Unsigned(0)
-        } else if Object::ptr_eq(self.value_type, activation.avm2().classes().number) {
+        } else if Object::ptr_eq(value_type, activation.avm2().classes().number) {
             Value::Number(0.0)
         } else {
             Value::Null
         }
     }
 
+    /// Construct the error thrown for accesses outside of the vector.
+    fn range_error(&self, pos: usize) -> Error {
+        format!(
+            "RangeError: Error #1125: The index {} is out of range {}.",
+            pos,
+            self.storage.len()
+        )
+        .into()
+    }
+
     /// Get the value type this vector coerces things to.
     pub fn value_type(&self) -> ClassObject<'gc> {
         self.value_type
     }
 
+    /// Coerce a value into one that can be stored in a vector of the given
+    /// value type.
+    ///
+    /// Numeric types are converted, while `undefined` and `null` turn into
+    /// the default value of the type. Objects of the wrong type raise an
+    /// error. This is an associated function so that callers do not have to
+    /// hold a borrow on the vector while reentering the AVM2 runtime.
+    pub fn coerce(
+        value: Value<'gc>,
+        value_type: ClassObject<'gc>,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error> {
+        match value.coerce_to_type(activation, value_type)? {
+            Value::Undefined | Value::Null => Ok(Self::default_for(value_type, activation)),
+            v => Ok(v),
+        }
+    }
+
     /// Check if a vector index is in bounds.
     pub fn is_in_range(&self, pos: usize) -> bool {
         pos < self.storage.len()
@@ -143,7 +182,7 @@ impl<'gc> VectorStorage<'gc> {
         self.storage
             .get(pos)
             .cloned()
-            .ok_or_else(|| format!("RangeError: {} is outside the range of the vector", pos).into())
+            .ok_or_else(|| self.range_error(pos))
     }
 
     /// Store a value into the vector.
@@ -154,7 +193,8 @@ impl<'gc> VectorStorage<'gc> {
     /// in the vector.
     ///
     /// This function yields an error if the position is outside the length of
-    /// the vector.
+    /// the vector. Non-fixed vectors may be appended to by storing a value
+    /// one past their end.
     pub fn set(
         &mut self,
         pos: usize,
@@ -165,10 +205,12 @@ impl<'gc> VectorStorage<'gc> {
             self.storage.resize(pos + 1, self.default(activation));
         }
 
-        self.storage
-            .get_mut(pos)
-            .map(|v| *v = value)
-            .ok_or_else(|| format!("RangeError: {} is outside the range of the vector", pos).into())
+        if let Some(slot) = self.storage.get_mut(pos) {
+            *slot = value;
+            Ok(())
+        } else {
+            Err(self.range_error(pos))
+        }
     }
 
     /// Push a value to the end of the vector.
@@ -181,7 +223,7 @@ impl<'gc> VectorStorage<'gc> {
     /// in the vector.
     pub fn push(&mut self, value: Value<'gc>) -> Result<(), Error> {
         if self.is_fixed {
-            return Err("RangeError: Vector is fixed".into());
+            return Err(FIXED_ERROR.into());
         }
 
         self.storage.push(value);
@@ -194,7 +236,7 @@ impl<'gc> VectorStorage<'gc> {
     /// This function returns an error if the vector is fixed.
     pub fn pop(&mut self, activation: &mut Activation<'_, 'gc, '_>) -> Result<Value<'gc>, Error> {
         if self.is_fixed {
-            return Err("RangeError: Vector is fixed".into());
+            return Err(FIXED_ERROR.into());
         }
 
         match self.storage.pop() {
@@ -219,7 +261,7 @@ impl<'gc> VectorStorage<'gc> {
     /// in the vector.
     pub fn unshift(&mut self, value: Value<'gc>) -> Result<(), Error> {
         if self.is_fixed {
-            return Err("RangeError: Vector is fixed".into());
+            return Err(FIXED_ERROR.into());
         }
 
         self.storage.insert(0, value);
@@ -232,7 +274,7 @@ impl<'gc> VectorStorage<'gc> {
     /// This function returns an error if the vector is fixed.
     pub fn shift(&mut self, activation: &mut Activation<'_, 'gc, '_>) -> Result<Value<'gc>, Error> {
         if self.is_fixed {
-            return Err("RangeError: Vector is fixed".into());
+            return Err(FIXED_ERROR.into());
         }
 
         let unshifted = if self.storage.is_empty() {
@@ -266,7 +308,7 @@ impl<'gc> VectorStorage<'gc> {
     /// the array, backwards.
     pub fn insert(&mut self, position: i32, value: Value<'gc>) -> Result<(), Error> {
         if self.is_fixed {
-            return Err("RangeError: Vector is fixed".into());
+            return Err(FIXED_ERROR.into());
         }
 
         let position = self.clamp_parameter_index(position);
@@ -289,7 +331,7 @@ impl<'gc> VectorStorage<'gc> {
     /// check error.
     pub fn remove(&mut self, position: i32) -> Result<Value<'gc>, Error> {
         if self.is_fixed {
-            return Err("RangeError: Vector is fixed".into());
+            return Err(FIXED_ERROR.into());
         }
 
         let position = if position < 0 {
@@ -299,11 +341,7 @@ impl<'gc> VectorStorage<'gc> {
         };
 
         if position >= self.storage.len() {
-            Err(format!(
-                "RangeError: Index {} extends beyond the end of the vector",
-                position
-            )
-            .into())
+            Err(self.range_error(position))
         } else {
             Ok(self.storage.remove(position))
         }
@@ -336,7 +374,7 @@ impl<'gc> VectorStorage<'gc> {
         R: Clone + SliceIndex<[Value<'gc>], Output = [Value<'gc>]> + RangeBounds<usize>,
     {
         if self.is_fixed && self.storage.index(range.clone()).len() != replace_with.len() {
-            return Err("RangeError: Vector is fixed".into());
+            return Err(FIXED_ERROR.into());
         }
 
         Ok(self.storage.splice(range, replace_with).collect())
@@ -1,4 +1,6 @@
 pub mod audio;
+pub mod capabilities;
+pub mod font;
 pub mod locale;
 pub mod log;
 pub mod navigator;
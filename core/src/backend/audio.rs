@@ -1,14 +1,17 @@
 use crate::{
     avm1::SoundObject,
     avm2::Event as Avm2Event,
+    avm2::Object as Avm2Object,
     avm2::SoundChannelObject,
     display_object::{self, DisplayObject, MovieClip, TDisplayObject},
 };
 use downcast_rs::Downcast;
 use gc_arena::Collect;
 use generational_arena::{Arena, Index};
+use std::sync::Arc;
 
 pub mod decoders;
+pub mod encoders;
 pub mod swf {
     pub use swf::{
         read, AudioCompression, CharacterId, Sound, SoundEnvelope, SoundEnvelopePoint, SoundEvent,
@@ -30,6 +33,32 @@ pub trait AudioBackend: Downcast {
     fn pause(&mut self);
     fn register_sound(&mut self, swf_sound: &swf::Sound) -> Result<SoundHandle, Error>;
 
+    /// Registers the contents of an MP3 file loaded at runtime, such as by
+    /// `Sound.loadSound`.
+    ///
+    /// The sound's format and length are read from the MP3 frame headers, and
+    /// it can then be played like any other registered sound.
+    fn register_mp3(&mut self, data: &[u8]) -> Result<SoundHandle, Error> {
+        let metadata = decoders::mp3_metadata(data)?;
+
+        // SWF MP3 data is prefixed with the number of samples to skip.
+        let mut swf_data = Vec::with_capacity(data.len() + 2);
+        swf_data.extend_from_slice(&[0, 0]);
+        swf_data.extend_from_slice(data);
+
+        self.register_sound(&swf::Sound {
+            id: 0,
+            format: swf::SoundFormat {
+                compression: swf::AudioCompression::Mp3,
+                sample_rate: metadata.sample_rate,
+                is_stereo: metadata.is_stereo,
+                is_16_bit: true,
+            },
+            num_samples: metadata.num_sample_frames,
+            data: &swf_data,
+        })
+    }
+
     /// Used by the web backend to pre-decode sound streams.
     /// Returns the sound handle to be used to add data to the stream.
     /// Other backends return `None`.
@@ -87,6 +116,30 @@ pub trait AudioBackend: Downcast {
     /// Returns `None` if ther sound is not/no longer playing
     fn get_sound_position(&self, instance: SoundInstanceHandle) -> Option<f64>;
 
+    /// Get the position of a stream sound instance in milliseconds, counting only the audio
+    /// that has reached the output device.
+    ///
+    /// This is the clock that timelines with stream sounds are synced to. Returns `None` if the
+    /// backend has no such clock, in which case frames are only timed by `Player::tick`.
+    fn get_stream_sync_position(&self, _instance: SoundInstanceHandle) -> Option<f64> {
+        None
+    }
+
+    /// Returns up to `num_sample_frames` of the audio that was most recently output, oldest
+    /// first, with samples in the range -1.0 to 1.0.
+    ///
+    /// This is used by `SoundMixer.computeSpectrum`. Backends that can't tap their output return
+    /// no samples, which reads as silence.
+    fn get_recent_output(&self, _num_sample_frames: usize) -> Vec<[f32; 2]> {
+        Vec::new()
+    }
+
+    /// Takes the sound instances that have played to the end, including all of their loops,
+    /// since the last call.
+    ///
+    /// Sounds that were stopped with `stop_sound` or `stop_all_sounds` are not included.
+    fn take_completed_sounds(&mut self) -> Vec<SoundInstanceHandle>;
+
     /// Get the duration of a sound in milliseconds.
     /// Returns `None` if sound is not registered.
     fn get_sound_duration(&self, sound: SoundHandle) -> Option<f64>;
@@ -102,6 +155,83 @@ pub trait AudioBackend: Downcast {
     /// Set the volume transform for a sound instance.
     fn set_sound_transform(&mut self, instance: SoundInstanceHandle, transform: SoundTransform);
 
+    /// Starts playing a "dynamic" sound, whose samples are generated at
+    /// runtime by an AVM2 `sampleData` event handler.
+    ///
+    /// The sound plays silence until samples are added with
+    /// `queue_dynamic_samples`, and ends once `end_dynamic_sound` has been
+    /// called and every queued sample has played.
+    fn start_dynamic_sound(&mut self) -> Result<SoundInstanceHandle, Error> {
+        Err("Dynamic sounds are not supported by this audio backend".into())
+    }
+
+    /// Adds 44.1kHz stereo sample frames to the end of a dynamic sound.
+    fn queue_dynamic_samples(&mut self, _instance: SoundInstanceHandle, _samples: &[[i16; 2]]) {}
+
+    /// Lets a dynamic sound end once all of its queued samples have played.
+    fn end_dynamic_sound(&mut self, _instance: SoundInstanceHandle) {}
+
+    /// Get the number of sample frames of a dynamic sound that have yet to be
+    /// played.
+    /// Returns `None` if the sound is not/no longer playing.
+    fn dynamic_sound_queue_length(&self, _instance: SoundInstanceHandle) -> Option<u32> {
+        None
+    }
+
+    /// Decodes all of a registered sound to 44.1kHz stereo sample frames.
+    ///
+    /// This is independent of playback, and is used to give the raw audio
+    /// data to ActionScript with `Sound.extract`.
+    fn decode_sound(&mut self, _sound: SoundHandle) -> Result<Arc<[[i16; 2]]>, Error> {
+        Err("Decoding sounds is not supported by this audio backend".into())
+    }
+
+    /// Get up to `num_sample_frames` decoded sample frames of a registered
+    /// sound, starting at the sample frame `position`.
+    ///
+    /// Fewer frames are returned at the end of the sound.
+    fn extract_sound(
+        &mut self,
+        sound: SoundHandle,
+        position: u32,
+        num_sample_frames: u32,
+    ) -> Result<Vec<[i16; 2]>, Error> {
+        let decoded = self.decode_sound(sound)?;
+        let start = (position as usize).min(decoded.len());
+        let end = start
+            .saturating_add(num_sample_frames as usize)
+            .min(decoded.len());
+        Ok(decoded[start..end].to_vec())
+    }
+
+    /// Starts capturing mono audio from the user's microphone at the given
+    /// sample rate, replacing any capture that is already running.
+    ///
+    /// This may ask the user for permission to use the microphone.
+    fn start_capture(&mut self, _sample_rate: u32) -> Result<(), Error> {
+        Err("Audio capture is not supported by this audio backend".into())
+    }
+
+    /// Stops capturing audio from the microphone.
+    fn stop_capture(&mut self) {}
+
+    /// Takes the samples that have been captured since the last call.
+    fn take_captured_samples(&mut self) -> Vec<i16> {
+        Vec::new()
+    }
+
+    /// The names of the audio output devices that sound can be played on.
+    fn available_output_devices(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Switches sound output to the output device with the given name.
+    ///
+    /// Playing sounds continue from their current positions on the new device.
+    fn set_output_device(&mut self, _name: &str) -> Result<(), Error> {
+        Err("Output device selection is not supported by this audio backend".into())
+    }
+
     // TODO: Eventually remove this/move it to library.
     fn is_loading_complete(&self) -> bool {
         true
@@ -194,6 +324,10 @@ impl AudioBackend for NullAudioBackend {
     fn get_sound_position(&self, _instance: SoundInstanceHandle) -> Option<f64> {
         Some(0.0)
     }
+    fn take_completed_sounds(&mut self) -> Vec<SoundInstanceHandle> {
+        // Sounds never play, so they never complete either.
+        Vec::new()
+    }
     fn get_sound_duration(&self, sound: SoundHandle) -> Option<f64> {
         if let Some(sound) = self.sounds.get(sound) {
             Some(sound.duration)
@@ -242,6 +376,21 @@ pub struct AudioManager<'gc> {
     transforms_dirty: bool,
 }
 
+/// The state of a sound whose samples are generated by an AVM2 `sampleData`
+/// event handler.
+#[derive(Clone, Collect)]
+#[collect(no_drop)]
+struct DynamicSound<'gc> {
+    /// The AVM2 `Sound` object that `sampleData` events are fired on.
+    sample_source: Avm2Object<'gc>,
+
+    /// The number of sample frames provided so far.
+    position: u32,
+
+    /// Whether the last `sampleData` event provided too few samples, ending the sound.
+    ended: bool,
+}
+
 impl<'gc> AudioManager<'gc> {
     /// The maximum number of sound instances that can play at once.
     pub const MAX_SOUNDS: usize = 32;
@@ -249,6 +398,14 @@ impl<'gc> AudioManager<'gc> {
     /// The default timeline stream buffer time in seconds.
     pub const DEFAULT_STREAM_BUFFER_TIME: i32 = 5;
 
+    /// The fewest sample frames a `sampleData` handler can provide without
+    /// ending the sound.
+    pub const MIN_DYNAMIC_SAMPLES: usize = 2048;
+
+    /// More samples are requested for a dynamic sound while it has fewer than
+    /// this many sample frames left to play.
+    const DYNAMIC_SOUND_LOW_WATER_MARK: u32 = 8192;
+
     pub fn new() -> Self {
         Self {
             sounds: Vec::with_capacity(Self::MAX_SOUNDS),
@@ -266,16 +423,32 @@ impl<'gc> AudioManager<'gc> {
         action_queue: &mut crate::context::ActionQueue<'gc>,
         root: DisplayObject<'gc>,
     ) {
+        // Positions are read before taking the completed sounds, so that a sound finishing in
+        // between is still reported as completed.
+        let positions: Vec<_> = self
+            .sounds
+            .iter()
+            .map(|sound| audio.get_sound_position(sound.instance))
+            .collect();
+        let completed_sounds = audio.take_completed_sounds();
+
         // Update the position of sounds, and remove any completed sounds.
+        let mut positions = positions.into_iter();
         self.sounds.retain(|sound| {
-            if let Some(pos) = audio.get_sound_position(sound.instance) {
-                // Sounds still playing; update position.
-                if let Some(avm1_object) = sound.avm1_object {
-                    avm1_object.set_position(gc_context, pos.round() as u32);
-                } else if let Some(avm2_object) = sound.avm2_object {
-                    avm2_object.set_position(gc_context, pos);
+            let position = positions.next().flatten();
+            if !completed_sounds.contains(&sound.instance) {
+                if let Some(pos) = position {
+                    // Sounds still playing; update position.
+                    if let Some(avm1_object) = sound.avm1_object {
+                        avm1_object.set_position(gc_context, pos.round() as u32);
+                    } else if let Some(avm2_object) = sound.avm2_object {
+                        avm2_object.set_position(gc_context, pos);
+                    }
+                    true
+                } else {
+                    // The backend stopped playing the sound before it finished.
+                    false
                 }
-                true
             } else {
                 // Sound ended.
                 let duration = sound
@@ -337,6 +510,8 @@ impl<'gc> AudioManager<'gc> {
                 transform: display_object::SoundTransform::default(),
                 avm1_object,
                 avm2_object: None,
+                dynamic: None,
+                stream_start_frame: None,
             };
             audio.set_sound_transform(handle, self.transform_for_sound(&instance));
             self.sounds.push(instance);
@@ -346,6 +521,84 @@ impl<'gc> AudioManager<'gc> {
         }
     }
 
+    /// Start a sound whose samples are provided by `sampleData` events fired
+    /// on the given AVM2 `Sound` object.
+    pub fn start_dynamic_sound(
+        &mut self,
+        audio: &mut dyn AudioBackend,
+        sample_source: Avm2Object<'gc>,
+    ) -> Option<SoundInstanceHandle> {
+        if self.sounds.len() < Self::MAX_SOUNDS {
+            let handle = audio.start_dynamic_sound().ok()?;
+            let instance = SoundInstance {
+                sound: None,
+                instance: handle,
+                display_object: None,
+                transform: display_object::SoundTransform::default(),
+                avm1_object: None,
+                avm2_object: None,
+                stream_start_frame: None,
+                dynamic: Some(DynamicSound {
+                    sample_source,
+                    position: 0,
+                    ended: false,
+                }),
+            };
+            audio.set_sound_transform(handle, self.transform_for_sound(&instance));
+            self.sounds.push(instance);
+            Some(handle)
+        } else {
+            None
+        }
+    }
+
+    /// Get the dynamic sounds that are running low on samples.
+    ///
+    /// Each is returned with the `Sound` object to fire `sampleData` on, and
+    /// the position of the samples to request.
+    pub fn dynamic_sounds_needing_samples(
+        &self,
+        audio: &dyn AudioBackend,
+    ) -> Vec<(SoundInstanceHandle, Avm2Object<'gc>, u32)> {
+        self.sounds
+            .iter()
+            .filter_map(|sound| {
+                let dynamic = sound.dynamic.as_ref().filter(|dynamic| !dynamic.ended)?;
+                let queue_length = audio.dynamic_sound_queue_length(sound.instance)?;
+                if queue_length < Self::DYNAMIC_SOUND_LOW_WATER_MARK {
+                    Some((sound.instance, dynamic.sample_source, dynamic.position))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Add the samples provided by a `sampleData` handler to a dynamic sound.
+    ///
+    /// Providing fewer than `MIN_DYNAMIC_SAMPLES` samples ends the sound once
+    /// they have played.
+    pub fn queue_dynamic_samples(
+        &mut self,
+        audio: &mut dyn AudioBackend,
+        instance: SoundInstanceHandle,
+        samples: &[[i16; 2]],
+    ) {
+        let dynamic = self
+            .sounds
+            .iter_mut()
+            .find(|other| other.instance == instance)
+            .and_then(|sound| sound.dynamic.as_mut());
+        if let Some(dynamic) = dynamic {
+            audio.queue_dynamic_samples(instance, samples);
+            dynamic.position += samples.len() as u32;
+            if samples.len() < Self::MIN_DYNAMIC_SAMPLES {
+                audio.end_dynamic_sound(instance);
+                dynamic.ended = true;
+            }
+        }
+    }
+
     pub fn attach_avm2_sound_channel(
         &mut self,
         instance: SoundInstanceHandle,
@@ -429,6 +682,8 @@ impl<'gc> AudioManager<'gc> {
                 transform: display_object::SoundTransform::default(),
                 avm1_object: None,
                 avm2_object: None,
+                dynamic: None,
+                stream_start_frame: Some(movie_clip.current_frame()),
             };
             audio.set_sound_transform(handle, self.transform_for_sound(&instance));
             self.sounds.push(instance);
@@ -438,6 +693,22 @@ impl<'gc> AudioManager<'gc> {
         }
     }
 
+    /// Returns how many frames a timeline with a playing stream sound is behind its audio.
+    ///
+    /// This is negative if the timeline is ahead of the audio, and `None` if no stream sound is
+    /// playing or the audio backend can't report how much of it has been heard.
+    pub fn stream_sync_lag(&self, audio: &dyn AudioBackend, frame_rate: f64) -> Option<f64> {
+        self.sounds.iter().find_map(|sound| {
+            let start_frame = sound.stream_start_frame?;
+            let movie_clip = sound.display_object?.as_movie_clip()?;
+            let position = audio.get_stream_sync_position(sound.instance)?;
+            let audio_frames = position * frame_rate / 1000.0;
+            // The clip may have been sent back to before the stream started.
+            let timeline_frames = movie_clip.current_frame().checked_sub(start_frame)?;
+            Some(audio_frames - f64::from(timeline_frames))
+        })
+    }
+
     pub fn global_sound_transform(&self) -> &display_object::SoundTransform {
         &self.global_sound_transform
     }
@@ -561,6 +832,13 @@ pub struct SoundInstance<'gc> {
 
     /// The AVM2 `SoundChannel` object associated with this sound, if any.
     avm2_object: Option<SoundChannelObject<'gc>>,
+
+    /// The state of this sound if its samples are generated at runtime.
+    dynamic: Option<DynamicSound<'gc>>,
+
+    /// The frame of the movie clip that this stream sound started playing on.
+    /// This will be `None` for event sounds.
+    stream_start_frame: Option<u16>,
 }
 
 /// A sound transform for a playing sound, for use by audio backends.
@@ -1,18 +1,27 @@
 //! Audio decoders.
 
-mod adpcm;
+pub mod aac;
+pub(super) mod adpcm;
 #[cfg(any(feature = "minimp3", feature = "symphonia"))]
 mod mp3;
+mod mp3_header;
+#[cfg(feature = "nellymoser")]
 mod nellymoser;
 mod pcm;
+mod speex;
 
+#[cfg(feature = "aac")]
+pub use aac::AacDecoder;
 pub use adpcm::AdpcmDecoder;
 #[cfg(feature = "minimp3")]
 pub use mp3::minimp3::Mp3Decoder;
 #[cfg(all(feature = "symphonia", not(feature = "minimp3")))]
 pub use mp3::symphonia::Mp3Decoder;
+pub use mp3_header::{mp3_metadata, Mp3Metadata};
+#[cfg(feature = "nellymoser")]
 pub use nellymoser::NellymoserDecoder;
 pub use pcm::PcmDecoder;
+pub use speex::SpeexDecoder;
 
 use crate::tag_utils::SwfSlice;
 use std::io::{Cursor, Read};
@@ -59,9 +68,15 @@ pub fn make_decoder<R: 'static + Send + Read>(
         )?),
         #[cfg(any(feature = "minimp3", feature = "symphonia"))]
         AudioCompression::Mp3 => Box::new(Mp3Decoder::new(data)?),
-        AudioCompression::Nellymoser => {
-            Box::new(NellymoserDecoder::new(data, format.sample_rate.into()))
+        #[cfg(feature = "nellymoser")]
+        AudioCompression::Nellymoser
+        | AudioCompression::Nellymoser16Khz
+        | AudioCompression::Nellymoser8Khz => {
+            Box::new(NellymoserDecoder::with_format(data, format))
         }
+        AudioCompression::Speex => Box::new(SpeexDecoder::new(data)?),
+        #[cfg(feature = "aac")]
+        AudioCompression::Aac => Box::new(AacDecoder::new(data)?),
         _ => {
             let msg = format!(
                 "make_decoder: Unhandled audio compression {:?}",
@@ -0,0 +1,365 @@
+//! AAC audio, as found in MP4 files and FLV streams.
+//!
+//! AAC frames are stored with the format described by a separate
+//! `AudioSpecificConfig`. Sounds made out of them are kept as ADTS streams
+//! instead, where each frame starts with a short header repeating the format,
+//! so that the audio data can be decoded on its own like any other sound.
+
+use std::io::Read;
+use swf::{AudioCompression, SoundFormat};
+
+type Error = Box<dyn std::error::Error>;
+
+/// The sample rates that can be referred to by index.
+const SAMPLE_RATES: [u32; 13] = [
+    96000, 88200, 64000, 48000, 44100, 32000, 24000, 22050, 16000, 12000, 11025, 8000, 7350,
+];
+
+/// The size of an ADTS header without a CRC.
+const ADTS_HEADER_LEN: usize = 7;
+
+/// The largest frame, header included, that an ADTS header can describe.
+const MAX_ADTS_FRAME_LEN: usize = (1 << 13) - 1;
+
+/// The format of an AAC stream.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AudioSpecificConfig {
+    /// The MPEG-4 audio object type, such as 2 for AAC-LC.
+    object_type: u8,
+
+    /// The index of the sample rate in `SAMPLE_RATES`.
+    sample_rate_index: u8,
+
+    channel_config: u8,
+}
+
+impl AudioSpecificConfig {
+    /// Parse an `AudioSpecificConfig`, as found in the `esds` box of MP4
+    /// files or in the sequence header of FLV streams.
+    pub fn parse(data: &[u8]) -> Result<Self, Error> {
+        if data.len() < 2 {
+            return Err("AAC audio specific config is truncated".into());
+        }
+
+        let bits = u16::from_be_bytes([data[0], data[1]]);
+        let config = Self {
+            object_type: (bits >> 11) as u8,
+            sample_rate_index: ((bits >> 7) & 0b1111) as u8,
+            channel_config: ((bits >> 3) & 0b1111) as u8,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check that audio of this format can be described by ADTS headers and
+    /// played back.
+    fn validate(&self) -> Result<(), Error> {
+        // ADTS can only signal the object types 1 to 4, and the escape values
+        // for other object types and explicit sample rates.
+        if !(1..=4).contains(&self.object_type) {
+            return Err(format!("Unsupported AAC object type {}", self.object_type).into());
+        }
+        if u16::try_from(self.sample_rate()?).is_err() {
+            return Err(format!("Unsupported AAC sample rate {}", self.sample_rate()?).into());
+        }
+        if !(1..=2).contains(&self.channel_config) {
+            return Err(format!(
+                "Unsupported AAC channel configuration {}",
+                self.channel_config
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    pub fn sample_rate(&self) -> Result<u32, Error> {
+        SAMPLE_RATES
+            .get(usize::from(self.sample_rate_index))
+            .copied()
+            .ok_or_else(|| {
+                format!("Invalid AAC sample rate index {}", self.sample_rate_index).into()
+            })
+    }
+
+    pub fn num_channels(&self) -> u8 {
+        self.channel_config
+    }
+
+    /// The format of sounds made of ADTS frames of this format.
+    pub fn sound_format(&self) -> SoundFormat {
+        SoundFormat {
+            compression: AudioCompression::Aac,
+            // Checked by `validate`.
+            sample_rate: self.sample_rate().unwrap_or_default() as u16,
+            is_stereo: self.channel_config == 2,
+            is_16_bit: true,
+        }
+    }
+
+    /// The two byte `AudioSpecificConfig` of this format.
+    pub fn to_bytes(self) -> [u8; 2] {
+        let bits = (u16::from(self.object_type) << 11)
+            | (u16::from(self.sample_rate_index) << 7)
+            | (u16::from(self.channel_config) << 3);
+        bits.to_be_bytes()
+    }
+
+    /// Append a raw AAC frame to `output` as an ADTS frame.
+    pub fn write_adts_frame(&self, frame: &[u8], output: &mut Vec<u8>) -> Result<(), Error> {
+        let len = ADTS_HEADER_LEN + frame.len();
+        if len > MAX_ADTS_FRAME_LEN {
+            return Err("AAC frame is too large".into());
+        }
+
+        let profile = self.object_type - 1;
+        output.extend_from_slice(&[
+            // Sync word, MPEG-4, no CRC.
+            0xFF,
+            0xF1,
+            (profile << 6) | (self.sample_rate_index << 2) | (self.channel_config >> 2),
+            ((self.channel_config & 0b11) << 6) | (len >> 11) as u8,
+            (len >> 3) as u8,
+            ((len & 0b111) << 5) as u8 | 0b1_1111,
+            // The rest of the buffer fullness, and one raw data block.
+            0b1111_1100,
+        ]);
+        output.extend_from_slice(frame);
+        Ok(())
+    }
+}
+
+/// Read the next ADTS frame from `reader`, returning its format and the raw
+/// AAC frame.
+///
+/// Returns `None` at the end of the stream.
+pub fn read_adts_frame<R: Read>(
+    reader: &mut R,
+    frame: &mut Vec<u8>,
+) -> Result<Option<AudioSpecificConfig>, Error> {
+    let mut header = [0; ADTS_HEADER_LEN];
+    match reader.read_exact(&mut header) {
+        Ok(()) => (),
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+
+    if header[0] != 0xFF || header[1] & 0xF0 != 0xF0 {
+        return Err("Invalid ADTS frame header".into());
+    }
+
+    let has_crc = header[1] & 1 == 0;
+    let config = AudioSpecificConfig {
+        object_type: (header[2] >> 6) + 1,
+        sample_rate_index: (header[2] >> 2) & 0b1111,
+        channel_config: ((header[2] & 1) << 2) | (header[3] >> 6),
+    };
+    let len = (usize::from(header[3] & 0b11) << 11)
+        | (usize::from(header[4]) << 3)
+        | usize::from(header[5] >> 5);
+    let header_len = if has_crc {
+        ADTS_HEADER_LEN + 2
+    } else {
+        ADTS_HEADER_LEN
+    };
+    let data_len = len
+        .checked_sub(header_len)
+        .ok_or("Invalid ADTS frame length")?;
+
+    if has_crc {
+        reader.read_exact(&mut [0; 2])?;
+    }
+    frame.resize(data_len, 0);
+    reader.read_exact(frame)?;
+
+    Ok(Some(config))
+}
+
+#[cfg(feature = "aac")]
+pub use decoder::AacDecoder;
+
+#[cfg(feature = "aac")]
+mod decoder {
+    use super::{read_adts_frame, AudioSpecificConfig, Error};
+    use crate::backend::audio::decoders::{Decoder, SeekableDecoder};
+    use std::io::{Cursor, Read};
+    use symphonia::core::{audio, codecs, errors, formats::Packet};
+
+    /// Decoder for ADTS streams, as created by
+    /// `AudioSpecificConfig::write_adts_frame`.
+    pub struct AacDecoder<R: Read> {
+        reader: R,
+        params: codecs::CodecParameters,
+        decoder: Box<dyn codecs::Decoder>,
+        sample_buf: Option<audio::SampleBuffer<i16>>,
+        cur_sample: usize,
+        sample_rate: u16,
+        num_channels: u8,
+
+        /// The first frame of the stream, read ahead to learn its format.
+        first_frame: Option<Vec<u8>>,
+
+        /// The buffer the next raw AAC frame is read into.
+        frame: Vec<u8>,
+    }
+
+    impl<R: Read> AacDecoder<R> {
+        pub fn new(mut reader: R) -> Result<Self, Error> {
+            let mut first_frame = Vec::new();
+            let config = read_adts_frame(&mut reader, &mut first_frame)?
+                .ok_or("AAC stream contains no frames")?;
+            config.validate()?;
+
+            let channels = match config.num_channels() {
+                1 => audio::Channels::FRONT_LEFT,
+                _ => audio::Channels::FRONT_LEFT | audio::Channels::FRONT_RIGHT,
+            };
+            let mut params = codecs::CodecParameters::new();
+            params
+                .for_codec(codecs::CODEC_TYPE_AAC)
+                .with_sample_rate(config.sample_rate()?)
+                .with_channels(channels)
+                .with_extra_data(Box::new(config.to_bytes()));
+            let decoder = Self::make_decoder(&params)?;
+
+            Ok(Self {
+                reader,
+                params,
+                decoder,
+                sample_buf: None,
+                cur_sample: 0,
+                sample_rate: config.sample_rate()? as u16,
+                num_channels: config.num_channels(),
+                first_frame: Some(first_frame),
+                frame: Vec::new(),
+            })
+        }
+
+        fn make_decoder(
+            params: &codecs::CodecParameters,
+        ) -> Result<Box<dyn codecs::Decoder>, Error> {
+            Ok(symphonia::default::get_codecs().make(params, &Default::default())?)
+        }
+
+        fn next_frame(&mut self) -> bool {
+            self.cur_sample = 0;
+            loop {
+                let frame = match self.first_frame.take() {
+                    Some(frame) => frame,
+                    None => match read_adts_frame(&mut self.reader, &mut self.frame) {
+                        Ok(Some(_)) => std::mem::take(&mut self.frame),
+                        Ok(None) => return false,
+                        Err(e) => {
+                            log::warn!("Got error when reading AAC frame: {}", e);
+                            return false;
+                        }
+                    },
+                };
+
+                let packet = Packet::new_from_slice(0, 0, 0, &frame);
+                self.frame = frame;
+                match self.decoder.decode(&packet) {
+                    Ok(decoded) => {
+                        let sample_buf = self.sample_buf.get_or_insert_with(|| {
+                            audio::SampleBuffer::new(decoded.capacity() as u64, *decoded.spec())
+                        });
+                        sample_buf.copy_interleaved_ref(decoded);
+                        return true;
+                    }
+                    // Decode errors are not fatal.
+                    Err(errors::Error::DecodeError(_)) => (),
+                    Err(_) => return false,
+                }
+            }
+        }
+    }
+
+    impl<R: Read> Iterator for AacDecoder<R> {
+        type Item = [i16; 2];
+
+        #[inline]
+        fn next(&mut self) -> Option<Self::Item> {
+            let buffered = self.sample_buf.as_ref().map_or(0, |buf| buf.len());
+            if self.cur_sample >= buffered && !self.next_frame() {
+                return None;
+            }
+
+            let samples = self.sample_buf.as_ref()?.samples();
+            if self.num_channels == 2 {
+                let frame = [samples[self.cur_sample], samples[self.cur_sample + 1]];
+                self.cur_sample += 2;
+                Some(frame)
+            } else {
+                let sample = samples[self.cur_sample];
+                self.cur_sample += 1;
+                Some([sample, sample])
+            }
+        }
+    }
+
+    impl<R: AsRef<[u8]>> SeekableDecoder for AacDecoder<Cursor<R>> {
+        fn reset(&mut self) {
+            self.reader.set_position(0);
+            self.first_frame = None;
+            self.cur_sample = self.sample_buf.as_ref().map_or(0, |buf| buf.len());
+            match Self::make_decoder(&self.params) {
+                Ok(decoder) => self.decoder = decoder,
+                Err(e) => log::error!("Unable to reset AAC decoder: {}", e),
+            }
+        }
+    }
+
+    impl<R: Read> Decoder for AacDecoder<R> {
+        #[inline]
+        fn num_channels(&self) -> u8 {
+            self.num_channels
+        }
+
+        #[inline]
+        fn sample_rate(&self) -> u16 {
+            self.sample_rate
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adts_round_trip() {
+        // AAC-LC, 44.1kHz, stereo.
+        let config = AudioSpecificConfig::parse(&[0x12, 0x10]).unwrap();
+        assert_eq!(config.sample_rate().unwrap(), 44100);
+        assert_eq!(config.num_channels(), 2);
+        assert_eq!(config.to_bytes(), [0x12, 0x10]);
+
+        let mut stream = Vec::new();
+        config.write_adts_frame(&[1, 2, 3], &mut stream).unwrap();
+        config.write_adts_frame(&[4; 300], &mut stream).unwrap();
+        assert_eq!(&stream[..7], &[0xFF, 0xF1, 0x50, 0x80, 0x01, 0x5F, 0xFC]);
+
+        let mut reader = &stream[..];
+        let mut frame = Vec::new();
+        assert_eq!(
+            read_adts_frame(&mut reader, &mut frame).unwrap(),
+            Some(config)
+        );
+        assert_eq!(frame, [1, 2, 3]);
+        assert_eq!(
+            read_adts_frame(&mut reader, &mut frame).unwrap(),
+            Some(config)
+        );
+        assert_eq!(frame, [4; 300]);
+        assert_eq!(read_adts_frame(&mut reader, &mut frame).unwrap(), None);
+    }
+
+    #[test]
+    fn reject_unsupported_configs() {
+        // HE-AAC.
+        assert!(AudioSpecificConfig::parse(&[0x2B, 0x92]).is_err());
+        // 96kHz doesn't fit into a SWF sound format.
+        assert!(AudioSpecificConfig::parse(&[0x10, 0x10]).is_err());
+        // 5.1 surround.
+        assert!(AudioSpecificConfig::parse(&[0x12, 0x30]).is_err());
+    }
+}
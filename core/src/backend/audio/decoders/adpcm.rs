@@ -2,14 +2,14 @@ use super::{Decoder, SeekableDecoder};
 use bitstream_io::{BigEndian, BitRead, BitReader};
 use std::io::{Cursor, Read};
 
-const INDEX_TABLE: [&[i16]; 4] = [
+pub(crate) const INDEX_TABLE: [&[i16]; 4] = [
     &[-1, 2],
     &[-1, -1, 2, 4],
     &[-1, -1, -1, -1, 2, 4, 6, 8],
     &[-1, -1, -1, -1, -1, -1, -1, -1, 1, 2, 4, 6, 8, 10, 13, 16],
 ];
 
-const STEP_TABLE: [u16; 89] = [
+pub(crate) const STEP_TABLE: [u16; 89] = [
     7, 8, 9, 10, 11, 12, 13, 14, 16, 17, 19, 21, 23, 25, 28, 31, 34, 37, 41, 45, 50, 55, 60, 66,
     73, 80, 88, 97, 107, 118, 130, 143, 157, 173, 190, 209, 230, 253, 279, 307, 337, 371, 408, 449,
     494, 544, 598, 658, 724, 796, 876, 963, 1060, 1166, 1282, 1411, 1552, 1707, 1878, 2066, 2272,
@@ -17,7 +17,7 @@ const STEP_TABLE: [u16; 89] = [
     10442, 11487, 12635, 13899, 15289, 16818, 18500, 20350, 22385, 24623, 27086, 29794, 32767,
 ];
 
-const SAMPLE_DELTA_CALCULATOR: [fn(u16, u32) -> u16; 4] = [
+pub(crate) const SAMPLE_DELTA_CALCULATOR: [fn(u16, u32) -> u16; 4] = [
     // 2 bits
     |step: u16, magnitude: u32| {
         let mut delta = step >> 1;
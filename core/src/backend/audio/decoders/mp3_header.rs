@@ -0,0 +1,183 @@
+//! Scanning of MP3 frame headers, without decoding any audio.
+
+type Error = Box<dyn std::error::Error>;
+
+/// Bitrates in kbps for MPEG-1 layers I, II and III, by bitrate index.
+const MPEG1_BITRATES: [[u16; 15]; 3] = [
+    [
+        0, 32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448,
+    ],
+    [
+        0, 32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384,
+    ],
+    [
+        0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+    ],
+];
+
+/// Bitrates in kbps for MPEG-2 and MPEG-2.5 layers I, II and III, by bitrate index.
+const MPEG2_BITRATES: [[u16; 15]; 3] = [
+    [
+        0, 32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256,
+    ],
+    [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160],
+    [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160],
+];
+
+/// Sample rates of MPEG-1, by sample rate index.
+const MPEG1_SAMPLE_RATES: [u32; 3] = [44100, 48000, 32000];
+
+/// The format and length of an MP3 file, as found in its frame headers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mp3Metadata {
+    /// The sample rate of the first frame.
+    pub sample_rate: u16,
+
+    /// Whether the first frame has two channels.
+    pub is_stereo: bool,
+
+    /// The total number of sample frames in the file.
+    pub num_sample_frames: u32,
+}
+
+/// A single parsed MP3 frame header.
+struct FrameHeader {
+    sample_rate: u32,
+    is_stereo: bool,
+    samples_per_frame: u32,
+
+    /// The length of the frame in bytes, including the header.
+    length: usize,
+}
+
+impl FrameHeader {
+    /// Parse the four bytes of a frame header, returning `None` if they are
+    /// not a valid header.
+    fn parse(header: [u8; 4]) -> Option<Self> {
+        if header[0] != 0xFF || header[1] & 0xE0 != 0xE0 {
+            return None;
+        }
+
+        let version = (header[1] >> 3) & 0b11;
+        let layer = match (header[1] >> 1) & 0b11 {
+            0b11 => 1,
+            0b10 => 2,
+            0b01 => 3,
+            _ => return None,
+        };
+        let bitrate_index = usize::from(header[2] >> 4);
+        let sample_rate_index = usize::from((header[2] >> 2) & 0b11);
+        if bitrate_index == 0 || bitrate_index == 15 || sample_rate_index == 3 {
+            // Free-format bitrates are not supported.
+            return None;
+        }
+
+        let (bitrates, sample_rate) = match version {
+            0b11 => (&MPEG1_BITRATES, MPEG1_SAMPLE_RATES[sample_rate_index]),
+            0b10 => (&MPEG2_BITRATES, MPEG1_SAMPLE_RATES[sample_rate_index] / 2),
+            0b00 => (&MPEG2_BITRATES, MPEG1_SAMPLE_RATES[sample_rate_index] / 4),
+            _ => return None,
+        };
+        let bitrate = u32::from(bitrates[layer - 1][bitrate_index]) * 1000;
+        let is_padded = (header[2] >> 1) & 1 != 0;
+        let is_stereo = header[3] >> 6 != 0b11;
+
+        let samples_per_frame = match (layer, version) {
+            (1, _) => 384,
+            (3, 0b10) | (3, 0b00) => 576,
+            _ => 1152,
+        };
+        let length = if layer == 1 {
+            (12 * bitrate / sample_rate + u32::from(is_padded)) * 4
+        } else {
+            samples_per_frame / 8 * bitrate / sample_rate + u32::from(is_padded)
+        };
+
+        Some(Self {
+            sample_rate,
+            is_stereo,
+            samples_per_frame,
+            length: length as usize,
+        })
+    }
+}
+
+/// Determine the format and length of an MP3 file by walking its frame
+/// headers.
+///
+/// Any ID3v2 tag at the start of the file is skipped, as is any other data
+/// between frames, such as a trailing ID3v1 tag.
+pub fn mp3_metadata(data: &[u8]) -> Result<Mp3Metadata, Error> {
+    let mut pos = id3v2_length(data);
+    let mut metadata: Option<Mp3Metadata> = None;
+
+    while let Some(header) = data.get(pos..pos + 4) {
+        let header = FrameHeader::parse([header[0], header[1], header[2], header[3]]);
+        match header {
+            Some(header) if pos + header.length <= data.len() => {
+                let metadata = metadata.get_or_insert(Mp3Metadata {
+                    sample_rate: header.sample_rate as u16,
+                    is_stereo: header.is_stereo,
+                    num_sample_frames: 0,
+                });
+                metadata.num_sample_frames += header.samples_per_frame;
+                pos += header.length;
+            }
+            _ => pos += 1,
+        }
+    }
+
+    metadata.ok_or_else(|| "No MP3 frames found".into())
+}
+
+/// The length of the ID3v2 tag at the start of `data`, or 0 if there is none.
+fn id3v2_length(data: &[u8]) -> usize {
+    match data {
+        [b'I', b'D', b'3', _, _, flags, size @ ..] if size.len() >= 4 => {
+            // The size is a "synchsafe" integer of four 7-bit bytes, and
+            // excludes the 10-byte header and optional 10-byte footer.
+            let size = size[..4]
+                .iter()
+                .fold(0, |size, byte| (size << 7) | usize::from(byte & 0x7F));
+            let footer = if flags & 0x10 != 0 { 10 } else { 0 };
+            10 + size + footer
+        }
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An MPEG-1 Layer III frame at 128kbps and 44.1kHz, in joint stereo.
+    fn frame(is_padded: bool) -> Vec<u8> {
+        let header = [0xFF, 0xFB, 0x90 | (u8::from(is_padded) << 1), 0x40];
+        let mut frame = vec![0; 417 + usize::from(is_padded)];
+        frame[..4].copy_from_slice(&header);
+        frame
+    }
+
+    #[test]
+    fn count_frames() {
+        let mut data = b"ID3\x04\x00\x00\x00\x00\x00\x05hello".to_vec();
+        data.extend(frame(false));
+        data.extend(frame(true));
+        data.extend(frame(false));
+        data.extend(b"TAG");
+
+        assert_eq!(
+            mp3_metadata(&data).unwrap(),
+            Mp3Metadata {
+                sample_rate: 44100,
+                is_stereo: true,
+                num_sample_frames: 3 * 1152,
+            }
+        );
+    }
+
+    #[test]
+    fn reject_non_mp3() {
+        assert!(mp3_metadata(b"RIFF\x00\x00\x00\x00WAVEfmt ").is_err());
+    }
+}
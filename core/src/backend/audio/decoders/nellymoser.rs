@@ -1,5 +1,6 @@
 use super::{Decoder, SeekableDecoder};
 use std::io::{Cursor, Read};
+use swf::{AudioCompression, SoundFormat};
 
 pub struct NellymoserDecoder<R: Read> {
     decoder: nellymoser_rs::Decoder<R>,
@@ -11,6 +12,19 @@ impl<R: Read> NellymoserDecoder<R> {
             decoder: nellymoser_rs::Decoder::new(reader, sample_rate),
         }
     }
+
+    /// Creates a decoder for Nellymoser audio of the given format.
+    ///
+    /// The 8kHz and 16kHz variants of the codec always use that sample rate,
+    /// whatever the format itself states.
+    pub fn with_format(reader: R, format: &SoundFormat) -> Self {
+        let sample_rate = match format.compression {
+            AudioCompression::Nellymoser8Khz => 8000,
+            AudioCompression::Nellymoser16Khz => 16000,
+            _ => format.sample_rate.into(),
+        };
+        Self::new(reader, sample_rate)
+    }
 }
 
 impl<R: Read> Iterator for NellymoserDecoder<R> {
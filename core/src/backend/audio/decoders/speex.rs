@@ -0,0 +1,475 @@
+use super::{Decoder, SeekableDecoder};
+use std::io::Read;
+use std::ops::Range;
+
+type Error = Box<dyn std::error::Error>;
+
+/// The number of bits in a narrowband frame of each submode, including the
+/// 5-bit header.
+const NARROWBAND_FRAME_BITS: [usize; 9] = [5, 43, 119, 160, 220, 300, 364, 492, 79];
+
+/// The number of bits in the high band of a wideband frame of each submode,
+/// including the 4-bit header.
+const WIDEBAND_FRAME_BITS: [usize; 5] = [4, 36, 112, 192, 352];
+
+/// Speex audio in Flash is always wideband, with 20ms frames.
+const SAMPLE_RATE: u16 = 16000;
+const SAMPLES_PER_FRAME: u32 = 320;
+
+/// Decoder for the wideband Speex audio used by Flash Player 10 voice content.
+///
+/// The bitstream is split into frames here, and every frame is synthesized
+/// by the reference Speex library when the `speex` feature is enabled.
+/// Without it, frames play as silence, so that sounds still have the right
+/// length and stay in sync with their timeline.
+pub struct SpeexDecoder {
+    data: Vec<u8>,
+
+    /// The position of the next frame in the data, in bits.
+    bit_pos: usize,
+
+    #[cfg(feature = "speex")]
+    synthesis: libspeex::Synthesis,
+
+    /// The samples of the current frame.
+    frame: [i16; SAMPLES_PER_FRAME as usize],
+
+    /// The number of samples of the current frame that have yet to be played.
+    samples_left: u32,
+}
+
+impl SpeexDecoder {
+    pub fn new<R: Read>(mut reader: R) -> Result<Self, Error> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        #[cfg(not(feature = "speex"))]
+        log::warn!("Speex audio is not supported in this build and will play as silence");
+        Ok(Self {
+            data,
+            bit_pos: 0,
+            #[cfg(feature = "speex")]
+            synthesis: libspeex::Synthesis::new()?,
+            frame: [0; SAMPLES_PER_FRAME as usize],
+            samples_left: 0,
+        })
+    }
+
+    /// Read `num_bits` bits without consuming them.
+    fn peek(&self, num_bits: usize) -> Option<u32> {
+        if self.bit_pos + num_bits > self.data.len() * 8 {
+            return None;
+        }
+
+        Some(
+            (self.bit_pos..self.bit_pos + num_bits).fold(0, |value, pos| {
+                let bit = (self.data[pos / 8] >> (7 - pos % 8)) & 1;
+                (value << 1) | u32::from(bit)
+            }),
+        )
+    }
+
+    fn read(&mut self, num_bits: usize) -> Option<u32> {
+        let value = self.peek(num_bits)?;
+        self.bit_pos += num_bits;
+        Some(value)
+    }
+
+    fn skip(&mut self, num_bits: usize) -> Option<()> {
+        self.bit_pos += num_bits;
+        if self.bit_pos <= self.data.len() * 8 {
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    /// Skip over the next frame, returning the bits it spans, or `None` at
+    /// the end of the data or when the bitstream is invalid.
+    fn skip_frame(&mut self) -> Option<Range<usize>> {
+        loop {
+            // Each packet is padded to a whole byte with a terminator of a
+            // zero bit followed by ones.
+            let padding = (8 - self.bit_pos % 8) % 8;
+            if padding > 0 && self.peek(padding) == Some((1 << (padding - 1)) - 1) {
+                self.bit_pos += padding;
+            }
+
+            let start = self.bit_pos;
+            if self.read(1)? != 0 {
+                // The narrowband layer always comes first.
+                return None;
+            }
+
+            match self.read(4)? as usize {
+                submode @ 0..=8 => self.skip(NARROWBAND_FRAME_BITS[submode] - 5)?,
+                13 => {
+                    // User in-band data.
+                    let size = self.read(4)? as usize;
+                    self.skip(5 + size * 8)?;
+                    continue;
+                }
+                14 => {
+                    // In-band request for the decoder.
+                    let skip = match self.read(4)? {
+                        0..=1 => 1,
+                        2..=7 => 4,
+                        8..=9 => 8,
+                        10..=11 => 16,
+                        12..=13 => 32,
+                        _ => 64,
+                    };
+                    self.skip(skip)?;
+                    continue;
+                }
+                15 => {
+                    // Terminator.
+                    self.bit_pos += (8 - self.bit_pos % 8) % 8;
+                    continue;
+                }
+                _ => return None,
+            }
+
+            if self.peek(1) == Some(1) {
+                self.bit_pos += 1;
+                let submode = self.read(3)? as usize;
+                self.skip(*WIDEBAND_FRAME_BITS.get(submode)? - 4)?;
+            }
+
+            return Some(start..self.bit_pos);
+        }
+    }
+
+    /// Copy the given bits of the data into whole bytes.
+    #[cfg(feature = "speex")]
+    fn frame_bytes(&self, bits: Range<usize>) -> Vec<u8> {
+        let mut bytes = vec![0; (bits.len() + 7) / 8];
+        for (i, pos) in bits.enumerate() {
+            let bit = (self.data[pos / 8] >> (7 - pos % 8)) & 1;
+            bytes[i / 8] |= bit << (7 - i % 8);
+        }
+        bytes
+    }
+
+    /// Decode the next frame into `self.frame`.
+    fn decode_frame(&mut self) -> Option<()> {
+        let bits = self.skip_frame()?;
+
+        #[cfg(feature = "speex")]
+        {
+            let frame = self.frame_bytes(bits);
+            if let Err(e) = self.synthesis.decode(&frame, &mut self.frame) {
+                log::warn!("Unable to decode Speex frame: {}", e);
+                self.frame = [0; SAMPLES_PER_FRAME as usize];
+            }
+        }
+        #[cfg(not(feature = "speex"))]
+        let _ = bits;
+
+        Some(())
+    }
+}
+
+impl Iterator for SpeexDecoder {
+    type Item = [i16; 2];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.samples_left == 0 {
+            self.decode_frame()?;
+            self.samples_left = SAMPLES_PER_FRAME;
+        }
+
+        let sample = self.frame[(SAMPLES_PER_FRAME - self.samples_left) as usize];
+        self.samples_left -= 1;
+        Some([sample, sample])
+    }
+}
+
+impl Decoder for SpeexDecoder {
+    #[inline]
+    fn num_channels(&self) -> u8 {
+        1
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> u16 {
+        SAMPLE_RATE
+    }
+}
+
+impl SeekableDecoder for SpeexDecoder {
+    #[inline]
+    fn reset(&mut self) {
+        self.bit_pos = 0;
+        self.samples_left = 0;
+        #[cfg(feature = "speex")]
+        self.synthesis.reset();
+    }
+}
+
+/// Bindings to the decoder of the reference Speex library.
+#[cfg(feature = "speex")]
+mod libspeex {
+    use super::{Error, SAMPLES_PER_FRAME};
+    use std::os::raw::{c_char, c_int, c_void};
+
+    /// The state of a bit reader or writer, as laid out in `speex_bits.h`.
+    ///
+    /// It is only ever accessed by the library.
+    #[allow(dead_code)]
+    #[repr(C)]
+    pub struct SpeexBits {
+        chars: *mut c_char,
+        nb_bits: c_int,
+        char_ptr: c_int,
+        bit_ptr: c_int,
+        owner: c_int,
+        overflow: c_int,
+        buf_size: c_int,
+        reserved1: c_int,
+        reserved2: *mut c_void,
+    }
+
+    pub const SPEEX_MODEID_WB: c_int = 1;
+    #[cfg(test)]
+    pub const SPEEX_SET_QUALITY: c_int = 4;
+
+    #[link(name = "speex")]
+    extern "C" {
+        pub fn speex_lib_get_mode(mode: c_int) -> *const c_void;
+
+        pub fn speex_decoder_init(mode: *const c_void) -> *mut c_void;
+        pub fn speex_decoder_destroy(state: *mut c_void);
+        pub fn speex_decode_int(state: *mut c_void, bits: *mut SpeexBits, out: *mut i16) -> c_int;
+
+        pub fn speex_bits_init(bits: *mut SpeexBits);
+        pub fn speex_bits_destroy(bits: *mut SpeexBits);
+        pub fn speex_bits_read_from(bits: *mut SpeexBits, bytes: *const c_char, len: c_int);
+
+        #[cfg(test)]
+        pub fn speex_encoder_init(mode: *const c_void) -> *mut c_void;
+        #[cfg(test)]
+        pub fn speex_encoder_destroy(state: *mut c_void);
+        #[cfg(test)]
+        pub fn speex_encoder_ctl(state: *mut c_void, request: c_int, ptr: *mut c_void) -> c_int;
+        #[cfg(test)]
+        pub fn speex_encode_int(state: *mut c_void, input: *mut i16, bits: *mut SpeexBits)
+            -> c_int;
+        #[cfg(test)]
+        pub fn speex_bits_reset(bits: *mut SpeexBits);
+        #[cfg(test)]
+        pub fn speex_bits_insert_terminator(bits: *mut SpeexBits);
+        #[cfg(test)]
+        pub fn speex_bits_write(bits: *mut SpeexBits, bytes: *mut c_char, max_len: c_int) -> c_int;
+    }
+
+    /// A wideband decoder, which keeps the excitation and filter memory that
+    /// each frame is synthesized from.
+    pub struct Synthesis {
+        state: *mut c_void,
+        bits: SpeexBits,
+    }
+
+    // The decoder state is only ever used through `&mut self`.
+    unsafe impl Send for Synthesis {}
+
+    impl Synthesis {
+        pub fn new() -> Result<Self, Error> {
+            unsafe {
+                let state = speex_decoder_init(speex_lib_get_mode(SPEEX_MODEID_WB));
+                if state.is_null() {
+                    return Err("Unable to create Speex decoder".into());
+                }
+
+                let mut bits = std::mem::MaybeUninit::uninit();
+                speex_bits_init(bits.as_mut_ptr());
+                Ok(Self {
+                    state,
+                    bits: bits.assume_init(),
+                })
+            }
+        }
+
+        /// Decode a single frame, which must start at the first byte of
+        /// `frame`.
+        pub fn decode(
+            &mut self,
+            frame: &[u8],
+            out: &mut [i16; SAMPLES_PER_FRAME as usize],
+        ) -> Result<(), Error> {
+            let result = unsafe {
+                speex_bits_read_from(
+                    &mut self.bits,
+                    frame.as_ptr() as *const c_char,
+                    frame.len() as c_int,
+                );
+                speex_decode_int(self.state, &mut self.bits, out.as_mut_ptr())
+            };
+
+            match result {
+                0 => Ok(()),
+                -1 => Err("Unexpected end of stream".into()),
+                _ => Err("Corrupt stream".into()),
+            }
+        }
+
+        /// Forget the state of previous frames.
+        pub fn reset(&mut self) {
+            unsafe {
+                speex_decoder_destroy(self.state);
+                self.state = speex_decoder_init(speex_lib_get_mode(SPEEX_MODEID_WB));
+            }
+        }
+    }
+
+    impl Drop for Synthesis {
+        fn drop(&mut self) {
+            unsafe {
+                speex_bits_destroy(&mut self.bits);
+                speex_decoder_destroy(self.state);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pack the given `(value, num_bits)` fields into bytes, most significant bit first.
+    fn pack(fields: &[(u32, usize)]) -> Vec<u8> {
+        let mut bytes = vec![];
+        let mut num_bits = 0;
+        for &(value, len) in fields {
+            for i in (0..len).rev() {
+                if num_bits % 8 == 0 {
+                    bytes.push(0);
+                }
+                let bit = ((value >> i) & 1) as u8;
+                *bytes.last_mut().unwrap() |= bit << (7 - num_bits % 8);
+                num_bits += 1;
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn count_frames() {
+        let data = pack(&[
+            // A narrowband-only frame in submode 3.
+            (0, 1),
+            (3, 4),
+            (0, 31),
+            (0, 31),
+            (0, 31),
+            (0, 31),
+            (0, 31),
+            // A narrowband frame in submode 0, with a high band in submode 0.
+            (0, 1),
+            (0, 4),
+            (1, 1),
+            (0, 3),
+            // The terminator.
+            (0, 1),
+            (0b111111, 6),
+        ]);
+        assert_eq!(data.len(), 22);
+
+        let decoder = SpeexDecoder::new(&data[..]).unwrap();
+        assert_eq!(decoder.count(), 2 * SAMPLES_PER_FRAME as usize);
+    }
+
+    /// Encode a sine wave with the reference encoder, padding every frame to
+    /// a whole byte like Flash Player does.
+    #[cfg(feature = "speex")]
+    fn encode_sine(frequency: f64, num_frames: usize) -> (Vec<i16>, Vec<u8>) {
+        use libspeex::*;
+        use std::os::raw::{c_char, c_int, c_void};
+
+        let input: Vec<i16> = (0..num_frames * SAMPLES_PER_FRAME as usize)
+            .map(|i| {
+                let t = i as f64 / f64::from(SAMPLE_RATE);
+                (8000.0 * (2.0 * std::f64::consts::PI * frequency * t).sin()) as i16
+            })
+            .collect();
+
+        let mut data = Vec::new();
+        unsafe {
+            let state = speex_encoder_init(speex_lib_get_mode(SPEEX_MODEID_WB));
+            let mut quality: c_int = 8;
+            speex_encoder_ctl(
+                state,
+                SPEEX_SET_QUALITY,
+                &mut quality as *mut c_int as *mut c_void,
+            );
+
+            let mut bits = std::mem::MaybeUninit::uninit();
+            speex_bits_init(bits.as_mut_ptr());
+            let mut bits = bits.assume_init();
+            let mut frame = [0i16; SAMPLES_PER_FRAME as usize];
+            let mut bytes = [0u8; 256];
+            for samples in input.chunks(SAMPLES_PER_FRAME as usize) {
+                frame.copy_from_slice(samples);
+                speex_bits_reset(&mut bits);
+                speex_encode_int(state, frame.as_mut_ptr(), &mut bits);
+                speex_bits_insert_terminator(&mut bits);
+                let len = speex_bits_write(
+                    &mut bits,
+                    bytes.as_mut_ptr() as *mut c_char,
+                    bytes.len() as c_int,
+                );
+                data.extend_from_slice(&bytes[..len as usize]);
+            }
+            speex_bits_destroy(&mut bits);
+            speex_encoder_destroy(state);
+        }
+
+        (input, data)
+    }
+
+    /// Tests that a sine wave survives encoding with the reference encoder
+    /// and decoding.
+    #[cfg(feature = "speex")]
+    #[test]
+    fn decode_reference_sine() {
+        let (input, data) = encode_sine(440.0, 50);
+
+        let decoder = SpeexDecoder::new(&data[..]).unwrap();
+        let output: Vec<i16> = decoder.map(|[left, _]| left).collect();
+        assert_eq!(output.len(), input.len());
+
+        // Compare the second half, once the codec has settled.
+        let half = input.len() / 2;
+        let rms = |samples: &[i16]| {
+            let sum: f64 = samples.iter().map(|&s| f64::from(s).powi(2)).sum();
+            (sum / samples.len() as f64).sqrt()
+        };
+        let ratio = rms(&output[half..]) / rms(&input[half..]);
+        assert!((0.7..1.3).contains(&ratio), "level ratio {}", ratio);
+
+        let zero_crossings = |samples: &[i16]| {
+            samples
+                .windows(2)
+                .filter(|w| (w[0] < 0) != (w[1] < 0))
+                .count() as f64
+        };
+        let crossings = zero_crossings(&output[half..]) / zero_crossings(&input[half..]);
+        assert!(
+            (0.9..1.1).contains(&crossings),
+            "crossing ratio {}",
+            crossings
+        );
+    }
+
+    /// Tests that decoding restarts from the first frame after a reset.
+    #[cfg(feature = "speex")]
+    #[test]
+    fn reset_restarts_synthesis() {
+        let (_, data) = encode_sine(300.0, 10);
+
+        let mut decoder = SpeexDecoder::new(&data[..]).unwrap();
+        let first: Vec<_> = decoder.by_ref().take(1000).collect();
+        decoder.reset();
+        let second: Vec<_> = decoder.take(1000).collect();
+        assert_eq!(first, second);
+    }
+}
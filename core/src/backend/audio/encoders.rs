@@ -0,0 +1,7 @@
+//! Audio encoders.
+
+mod adpcm;
+mod wav;
+
+pub use adpcm::AdpcmEncoder;
+pub use wav::write_wav;
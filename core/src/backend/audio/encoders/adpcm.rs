@@ -0,0 +1,133 @@
+use crate::backend::audio::decoders::adpcm::{INDEX_TABLE, SAMPLE_DELTA_CALCULATOR, STEP_TABLE};
+use bitstream_io::{BigEndian, BitWrite, BitWriter};
+use std::io::Write;
+
+type Error = Box<dyn std::error::Error>;
+
+#[derive(Clone, Default)]
+struct Channel {
+    sample: i16,
+    step_index: i16,
+}
+
+/// Encodes audio in the ADPCM format used by SWF sounds.
+///
+/// The output can be played back with `AdpcmDecoder`.
+pub struct AdpcmEncoder<W: Write> {
+    inner: BitWriter<W, BigEndian>,
+    bits_per_sample: usize,
+    sample_num: u16,
+    channels: Vec<Channel>,
+}
+
+impl<W: Write> AdpcmEncoder<W> {
+    /// Creates an encoder that writes 2 to 5 bits for each sample.
+    pub fn new(inner: W, is_stereo: bool, bits_per_sample: usize) -> Result<Self, Error> {
+        if !(2..=5).contains(&bits_per_sample) {
+            return Err(format!("Invalid ADPCM bits per sample: {}", bits_per_sample).into());
+        }
+
+        let mut writer = BitWriter::new(inner);
+        writer.write(2, bits_per_sample as u8 - 2)?;
+
+        let num_channels = if is_stereo { 2 } else { 1 };
+
+        Ok(Self {
+            inner: writer,
+            bits_per_sample,
+            sample_num: 0,
+            channels: vec![Default::default(); num_channels],
+        })
+    }
+
+    /// Encodes the next sample frame. Only the left channel is used for mono sounds.
+    pub fn write_frame(&mut self, frame: [i16; 2]) -> Result<(), Error> {
+        if self.sample_num == 0 {
+            // Each packet starts with the current state, so the decoder can't drift.
+            for channel in &self.channels {
+                self.inner.write_signed(16, channel.sample)?;
+                self.inner.write(6, channel.step_index as u16)?;
+            }
+        }
+
+        self.sample_num = (self.sample_num + 1) % 4095;
+
+        for (channel, &sample) in self.channels.iter_mut().zip(frame.iter()) {
+            let step = STEP_TABLE[channel.step_index as usize];
+            let diff = i32::from(sample) - i32::from(channel.sample);
+
+            // The decoder adds `step`, `step / 2`, ... for each magnitude bit that is set,
+            // so pick the bits from most to least significant.
+            let sign_mask = 1 << (self.bits_per_sample - 1);
+            let mut remaining = diff.unsigned_abs();
+            let mut bit_step = u32::from(step);
+            let mut magnitude = 0;
+            let mut bit = sign_mask >> 1;
+            while bit != 0 {
+                if remaining >= bit_step {
+                    magnitude |= bit;
+                    remaining -= bit_step;
+                }
+                bit_step >>= 1;
+                bit >>= 1;
+            }
+
+            // Track the sample exactly as the decoder will.
+            let delta = SAMPLE_DELTA_CALCULATOR[self.bits_per_sample - 2](step, magnitude);
+            channel.sample = if diff < 0 {
+                (channel.sample as i32 - delta as i32).max(i16::MIN.into())
+            } else {
+                (channel.sample as i32 + delta as i32).min(i16::MAX.into())
+            } as i16;
+
+            channel.step_index += INDEX_TABLE[self.bits_per_sample - 2][magnitude as usize];
+            channel.step_index = channel.step_index.clamp(0, STEP_TABLE.len() as i16 - 1);
+
+            // `data` is sign-magnitude, NOT two's complement.
+            let data = if diff < 0 {
+                magnitude | sign_mask
+            } else {
+                magnitude
+            };
+            self.inner.write(self.bits_per_sample as u32, data)?;
+        }
+
+        Ok(())
+    }
+
+    /// Pads the encoded data to a whole byte, and returns the underlying writer.
+    pub fn finish(mut self) -> Result<W, Error> {
+        self.inner.byte_align()?;
+        Ok(self.inner.into_writer())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::audio::decoders::AdpcmDecoder;
+
+    #[test]
+    fn round_trip() {
+        let frames: Vec<[i16; 2]> = (0..10000)
+            .map(|i| {
+                let sample = (f64::from(i) * 0.05).sin() * 10000.0;
+                [sample as i16, -sample as i16]
+            })
+            .collect();
+
+        let mut encoder = AdpcmEncoder::new(vec![], true, 4).unwrap();
+        for &frame in &frames {
+            encoder.write_frame(frame).unwrap();
+        }
+        let data = encoder.finish().unwrap();
+
+        let decoder = AdpcmDecoder::new(&data[..], true, 44100).unwrap();
+        let decoded: Vec<_> = decoder.take(frames.len()).collect();
+        assert_eq!(decoded.len(), frames.len());
+        for (expected, actual) in frames.iter().zip(&decoded).skip(100) {
+            assert!((i32::from(expected[0]) - i32::from(actual[0])).abs() < 1000);
+            assert!((i32::from(expected[1]) - i32::from(actual[1])).abs() < 1000);
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::io::{self, Write};
+
+/// Writes sample frames as a 16-bit PCM WAV file.
+///
+/// `num_channels` must be 1 or 2. Only the left channel of each frame is written for mono
+/// output.
+pub fn write_wav<W: Write>(
+    mut writer: W,
+    frames: &[[i16; 2]],
+    num_channels: u16,
+    sample_rate: u32,
+) -> io::Result<()> {
+    let block_align = num_channels * 2;
+    let data_len = frames.len() as u32 * u32::from(block_align);
+
+    writer.write_all(b"RIFF")?;
+    writer.write_all(&(36 + data_len).to_le_bytes())?;
+    writer.write_all(b"WAVE")?;
+
+    writer.write_all(b"fmt ")?;
+    writer.write_all(&16u32.to_le_bytes())?;
+    // Format 1 is uncompressed PCM.
+    writer.write_all(&1u16.to_le_bytes())?;
+    writer.write_all(&num_channels.to_le_bytes())?;
+    writer.write_all(&sample_rate.to_le_bytes())?;
+    writer.write_all(&(sample_rate * u32::from(block_align)).to_le_bytes())?;
+    writer.write_all(&block_align.to_le_bytes())?;
+    writer.write_all(&16u16.to_le_bytes())?;
+
+    writer.write_all(b"data")?;
+    writer.write_all(&data_len.to_le_bytes())?;
+    for frame in frames {
+        for sample in &frame[..usize::from(num_channels)] {
+            writer.write_all(&sample.to_le_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mono_header() {
+        let mut data = vec![];
+        write_wav(&mut data, &[[1, 2], [-1, -2]], 1, 22050).unwrap();
+        assert_eq!(data.len(), 44 + 4);
+        assert_eq!(&data[0..4], b"RIFF");
+        assert_eq!(&data[4..8], &40u32.to_le_bytes());
+        assert_eq!(&data[22..24], &1u16.to_le_bytes());
+        assert_eq!(&data[24..28], &22050u32.to_le_bytes());
+        assert_eq!(&data[28..32], &44100u32.to_le_bytes());
+        assert_eq!(&data[40..44], &4u32.to_le_bytes());
+        assert_eq!(&data[44..], &[1, 0, 0xff, 0xff]);
+    }
+}
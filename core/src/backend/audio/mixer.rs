@@ -1,10 +1,12 @@
-use super::decoders::{
-    self, AdpcmDecoder, Decoder, NellymoserDecoder, PcmDecoder, SeekableDecoder,
-};
+#[cfg(feature = "nellymoser")]
+use super::decoders::NellymoserDecoder;
+use super::decoders::{self, AdpcmDecoder, Decoder, PcmDecoder, SeekableDecoder, SpeexDecoder};
 use super::{SoundHandle, SoundInstanceHandle, SoundTransform};
 use crate::tag_utils::SwfSlice;
 use generational_arena::Arena;
+use std::collections::VecDeque;
 use std::io::Cursor;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use swf::AudioCompression;
 
@@ -22,13 +24,53 @@ pub struct AudioMixer {
     /// The list of actively playing sound instances.
     sound_instances: Arc<Mutex<Arena<SoundInstance>>>,
 
+    /// The sound instances that have played to the end since they were last taken.
+    completed_sounds: Arc<Mutex<Vec<SoundInstanceHandle>>>,
+
+    /// The size in sample frames of the last buffer that was mixed.
+    ///
+    /// The output device plays this much audio ahead of what has been heard.
+    output_buffer_frames: Arc<AtomicUsize>,
+
+    /// The most recently mixed sample frames, oldest first.
+    output_history: Arc<Mutex<VecDeque<[f32; 2]>>>,
+
     /// The number of channels in the output stream. Must be 1 or 2.
     num_output_channels: u8,
 
     /// The sample rate of the output stream in Hz.
     output_sample_rate: u32,
+
+    /// The interpolation used when converting sounds to the output sample rate.
+    resampling: Resampling,
 }
 
+/// The interpolation used by the mixer when converting a sound to a different sample rate.
+///
+/// Higher quality interpolation reduces the aliasing heard on low sample rate sounds,
+/// at the cost of more work per output sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resampling {
+    /// Linear interpolation between the two nearest sample frames. This is the cheapest option.
+    Linear,
+
+    /// Catmull-Rom cubic interpolation over the four nearest sample frames.
+    Cubic,
+
+    /// Blackman-windowed sinc interpolation, which low-pass filters the sound to avoid aliasing.
+    Sinc,
+}
+
+impl Default for Resampling {
+    fn default() -> Self {
+        Resampling::Linear
+    }
+}
+
+/// The number of source sample frames on either side of the output position that are used by
+/// `Resampling::Sinc`.
+const SINC_HALF_WIDTH: usize = 8;
+
 type Error = Box<dyn std::error::Error>;
 
 /// An audio stream.
@@ -44,6 +86,11 @@ trait Stream: dasp::signal::Signal<Frame = [i16; 2]> + Send {
     /// The sample rate of the underlying audio source of this stream. For example, this will return
     /// 22050 when playing a 22KHz audio file, even if the output rate is 44KHz.
     fn source_sample_rate(&self) -> u16;
+
+    /// Changes the sample rate that this stream is output at.
+    ///
+    /// Streams that resample their source override this; other streams are unaffected.
+    fn set_output_sample_rate(&mut self, _sample_rate: u32) {}
 }
 
 /// A stream that wraps a `Decoder`.
@@ -118,6 +165,12 @@ struct Sound {
     /// `skip_sample_frames` indicates how many sample frames to skip to bypass the delay.
     /// This is `0` unless `format.compression` is `AudioCompression::Mp3`.
     skip_sample_frames: u16,
+
+    /// The fully decoded audio, resampled to 44.1kHz stereo.
+    ///
+    /// This is filled in the first time the sound is decoded by
+    /// `AudioMixer::decode_sound`.
+    decoded: Option<Arc<[[i16; 2]]>>,
 }
 
 /// An actively playing instance of a sound.
@@ -144,16 +197,57 @@ struct SoundInstance {
 
     /// The transform for the right channel of this sound instance.
     right_transform: [f32; 2],
+
+    /// The queue of samples to play, if this sound is generated at runtime.
+    dynamic_queue: Option<Arc<Mutex<DynamicQueue>>>,
+}
+
+/// Samples of a dynamic sound that have yet to be played.
+#[derive(Default)]
+struct DynamicQueue {
+    samples: VecDeque<[i16; 2]>,
+
+    /// Whether no more samples will be added, so that the sound ends once
+    /// the queue runs dry.
+    ended: bool,
 }
 
 impl AudioMixer {
+    /// The number of mixed sample frames that are kept for `AudioMixer::get_recent_output`.
+    const OUTPUT_HISTORY_LEN: usize = 8192;
+
     /// Creates a new `AudioMixer` with the given number of channels and sample rate.
     pub fn new(num_output_channels: u8, output_sample_rate: u32) -> Self {
         Self {
             sounds: Arena::new(),
             sound_instances: Arc::new(Mutex::new(Arena::new())),
+            completed_sounds: Arc::new(Mutex::new(Vec::new())),
+            output_buffer_frames: Arc::new(AtomicUsize::new(0)),
+            output_history: Arc::new(Mutex::new(VecDeque::with_capacity(
+                Self::OUTPUT_HISTORY_LEN,
+            ))),
             num_output_channels,
             output_sample_rate,
+            resampling: Resampling::default(),
+        }
+    }
+
+    /// Sets the interpolation used to resample sounds to the output sample rate.
+    ///
+    /// This only affects sounds that are started afterwards.
+    pub fn set_resampling(&mut self, resampling: Resampling) {
+        self.resampling = resampling;
+    }
+
+    /// Changes the number of channels and sample rate of the output stream.
+    ///
+    /// Playing sounds are resampled to the new rate from their current positions.
+    pub fn set_output_format(&mut self, num_output_channels: u8, output_sample_rate: u32) {
+        self.num_output_channels = num_output_channels;
+        self.output_sample_rate = output_sample_rate;
+        let mut sound_instances = self.sound_instances.lock().unwrap();
+        for (_, instance) in sound_instances.iter_mut() {
+            instance.stream.set_output_sample_rate(output_sample_rate);
         }
     }
 
@@ -161,6 +255,9 @@ impl AudioMixer {
     pub fn proxy(&self) -> AudioMixerProxy {
         AudioMixerProxy {
             sound_instances: Arc::clone(&self.sound_instances),
+            completed_sounds: Arc::clone(&self.completed_sounds),
+            output_buffer_frames: Arc::clone(&self.output_buffer_frames),
+            output_history: Arc::clone(&self.output_history),
             num_output_channels: self.num_output_channels,
         }
     }
@@ -175,9 +272,15 @@ impl AudioMixer {
         T::Signed: dasp::sample::conv::FromSample<i16>,
         T::Float: dasp::sample::conv::FromSample<f32>,
     {
+        self.output_buffer_frames.store(
+            output_buffer.len() / usize::from(self.num_output_channels),
+            Ordering::Relaxed,
+        );
         let mut sound_instances = self.sound_instances.lock().unwrap();
         Self::mix_audio::<T>(
             &mut sound_instances,
+            &self.completed_sounds,
+            &self.output_history,
             self.num_output_channels,
             output_buffer,
         )
@@ -208,9 +311,15 @@ impl AudioMixer {
             AudioCompression::Mp3 => Box::new(decoders::Mp3Decoder::new(data)?),
             #[cfg(all(feature = "symphonia", not(feature = "minimp3")))]
             AudioCompression::Mp3 => Box::new(decoders::Mp3Decoder::new_seekable(data)?),
-            AudioCompression::Nellymoser => {
-                Box::new(NellymoserDecoder::new(data, format.sample_rate.into()))
+            #[cfg(feature = "nellymoser")]
+            AudioCompression::Nellymoser
+            | AudioCompression::Nellymoser16Khz
+            | AudioCompression::Nellymoser8Khz => {
+                Box::new(NellymoserDecoder::with_format(data, format))
             }
+            AudioCompression::Speex => Box::new(SpeexDecoder::new(data)?),
+            #[cfg(feature = "aac")]
+            AudioCompression::Aac => Box::new(decoders::AacDecoder::new(data)?),
             _ => {
                 let msg = format!(
                     "start_stream: Unhandled audio compression {:?}",
@@ -224,18 +333,13 @@ impl AudioMixer {
     }
 
     /// Transforms a `Stream` into a new `Stream` that matches the output sample rate.
-    fn make_resampler(&self, mut stream: impl Stream) -> impl Stream {
-        // TODO: Allow interpolator to be user-configurable?
-        let left = stream.next();
-        let right = stream.next();
-        let interpolator = dasp::interpolate::linear::Linear::new(left, right);
-        let sample_rate = stream.source_sample_rate().into();
-        ConverterStream(dasp::signal::interpolate::Converter::from_hz_to_hz(
-            stream,
-            interpolator,
-            sample_rate,
-            self.output_sample_rate.into(),
-        ))
+    fn make_resampler(&self, stream: impl Stream) -> impl Stream {
+        Self::resample(stream, self.output_sample_rate, self.resampling)
+    }
+
+    /// Transforms a `Stream` into a new `Stream` with the given sample rate.
+    fn resample(stream: impl Stream, sample_rate: u32, resampling: Resampling) -> impl Stream {
+        ResamplerStream::new(stream, sample_rate, resampling)
     }
 
     /// Creates a `Stream` for an "event" that decodes and resamples the audio stream to the
@@ -307,6 +411,8 @@ impl AudioMixer {
     /// and mixing in their output.
     fn mix_audio<'a, T>(
         sound_instances: &mut Arena<SoundInstance>,
+        completed_sounds: &Mutex<Vec<SoundInstanceHandle>>,
+        output_history: &Mutex<VecDeque<[f32; 2]>>,
         num_channels: u8,
         mut output_buffer: &mut [T],
     ) where
@@ -320,17 +426,22 @@ impl AudioMixer {
         };
         use std::ops::DerefMut;
 
+        let mut output_history = output_history.lock().unwrap();
+
         // For each sample, mix the samples from all active sound instances.
         for buf_frame in output_buffer
             .deref_mut()
             .chunks_exact_mut(num_channels.into())
         {
             let mut output_frame = Stereo::<T::Signed>::EQUILIBRIUM;
+            let mut history_frame = [0.0f32; 2];
             for (_, sound) in sound_instances.iter_mut() {
                 if sound.active && !sound.stream.is_exhausted() {
                     let sound_frame = sound.stream.next();
                     let [left_0, left_1] = sound_frame.mul_amp(sound.left_transform);
                     let [right_0, right_1] = sound_frame.mul_amp(sound.right_transform);
+                    history_frame[0] += (f32::from(left_0) + f32::from(left_1)) / 32768.0;
+                    history_frame[1] += (f32::from(right_0) + f32::from(right_1)) / 32768.0;
                     let sound_frame: Stereo<T::Signed> = [
                         Sample::add_amp(left_0, left_1).to_sample(),
                         Sample::add_amp(right_0, right_1).to_sample(),
@@ -341,13 +452,32 @@ impl AudioMixer {
                 }
             }
 
-            for (buf_sample, output_sample) in buf_frame.iter_mut().zip(output_frame.iter()) {
-                *buf_sample = output_sample.to_sample();
+            if output_history.len() >= Self::OUTPUT_HISTORY_LEN {
+                output_history.pop_front();
+            }
+            output_history.push_back(history_frame);
+
+            if let [buf_sample] = buf_frame {
+                // Downmix to mono, so that panned sounds are still heard.
+                let [left, right] = output_frame;
+                let left = left.to_sample::<T>().to_float_sample();
+                let right = right.to_sample::<T>().to_float_sample();
+                *buf_sample = left.add_amp(right).mul_amp(0.5f32.to_sample()).to_sample();
+            } else {
+                for (buf_sample, output_sample) in buf_frame.iter_mut().zip(output_frame.iter()) {
+                    *buf_sample = output_sample.to_sample();
+                }
             }
         }
 
-        // Remove all dead sounds.
-        sound_instances.retain(|_, sound| sound.active);
+        // Remove all dead sounds, noting that they have finished playing.
+        let mut completed_sounds = completed_sounds.lock().unwrap();
+        sound_instances.retain(|handle, sound| {
+            if !sound.active {
+                completed_sounds.push(handle);
+            }
+            sound.active
+        });
     }
 
     /// Registers a sound with the audio mixer.
@@ -365,6 +495,7 @@ impl AudioMixer {
             data: Arc::from(data),
             num_sample_frames: swf_sound.num_samples,
             skip_sample_frames,
+            decoded: None,
         };
         Ok(self.sounds.insert(sound))
     }
@@ -391,6 +522,7 @@ impl AudioMixer {
             active: true,
             left_transform: [1.0, 0.0],
             right_transform: [0.0, 1.0],
+            dynamic_queue: None,
         });
         Ok(handle)
     }
@@ -427,10 +559,106 @@ impl AudioMixer {
             active: true,
             left_transform: [1.0, 0.0],
             right_transform: [0.0, 1.0],
+            dynamic_queue: None,
         });
         Ok(handle)
     }
 
+    /// Starts a sound whose samples are generated at runtime.
+    ///
+    /// The sound plays silence until samples are added to it with
+    /// `AudioMixer::queue_dynamic_samples`.
+    pub fn start_dynamic_sound(&mut self) -> Result<SoundInstanceHandle, Error> {
+        let queue = Arc::new(Mutex::new(DynamicQueue::default()));
+        let stream = DynamicStream {
+            queue: Arc::clone(&queue),
+            position: 0,
+            is_exhausted: false,
+        };
+        let stream = Box::new(self.make_resampler(stream));
+
+        let mut sound_instances = self.sound_instances.lock().unwrap();
+        let handle = sound_instances.insert(SoundInstance {
+            handle: None,
+            stream,
+            active: true,
+            left_transform: [1.0, 0.0],
+            right_transform: [0.0, 1.0],
+            dynamic_queue: Some(queue),
+        });
+        Ok(handle)
+    }
+
+    /// Returns the sample queue of a playing dynamic sound.
+    fn dynamic_queue(&self, instance: SoundInstanceHandle) -> Option<Arc<Mutex<DynamicQueue>>> {
+        let sound_instances = self.sound_instances.lock().unwrap();
+        sound_instances.get(instance)?.dynamic_queue.clone()
+    }
+
+    /// Adds 44.1kHz sample frames to the end of a dynamic sound.
+    pub fn queue_dynamic_samples(&mut self, instance: SoundInstanceHandle, samples: &[[i16; 2]]) {
+        if let Some(queue) = self.dynamic_queue(instance) {
+            queue.lock().unwrap().samples.extend(samples);
+        }
+    }
+
+    /// Lets a dynamic sound end once all of its queued samples have played.
+    pub fn end_dynamic_sound(&mut self, instance: SoundInstanceHandle) {
+        if let Some(queue) = self.dynamic_queue(instance) {
+            queue.lock().unwrap().ended = true;
+        }
+    }
+
+    /// Returns the number of sample frames of a dynamic sound that have yet to
+    /// be played.
+    ///
+    /// Returns `None` if the sound is no longer playing.
+    pub fn dynamic_sound_queue_length(&self, instance: SoundInstanceHandle) -> Option<u32> {
+        let queue = self.dynamic_queue(instance)?;
+        let length = queue.lock().unwrap().samples.len();
+        Some(length as u32)
+    }
+
+    /// Decodes all of a registered sound to 44.1kHz stereo sample frames,
+    /// separately from the mixing of playing sounds.
+    ///
+    /// The result is cached, so only the first call for each sound decodes it.
+    pub fn decode_sound(&mut self, sound_handle: SoundHandle) -> Result<Arc<[[i16; 2]]>, Error> {
+        let sound = self.sounds.get_mut(sound_handle).ok_or("Invalid sound")?;
+        if let Some(decoded) = &sound.decoded {
+            return Ok(Arc::clone(decoded));
+        }
+
+        let data = Cursor::new(ArcAsRef(Arc::clone(&sound.data)));
+        let decoder = Self::make_seekable_decoder(&sound.format, data)?;
+        let resampling = self.resampling;
+        let mut stream = Self::resample(
+            EventSoundStream::new_with_settings(
+                decoder,
+                &swf::SoundInfo {
+                    event: swf::SoundEvent::Start,
+                    in_sample: None,
+                    out_sample: None,
+                    num_loops: 1,
+                    envelope: None,
+                },
+                sound.num_sample_frames,
+                sound.skip_sample_frames,
+            ),
+            44100,
+            resampling,
+        );
+
+        let mut samples = Vec::new();
+        while !stream.is_exhausted() {
+            samples.push(stream.next());
+        }
+
+        let decoded: Arc<[[i16; 2]]> = samples.into();
+        sound.decoded = Some(Arc::clone(&decoded));
+        Ok(decoded)
+    }
+
     /// Stops a playing sound instance.
     pub fn stop_sound(&mut self, sound: SoundInstanceHandle) {
         let mut sound_instances = self.sound_instances.lock().unwrap();
@@ -449,6 +677,12 @@ impl AudioMixer {
         sound_instances.clear();
     }
 
+    /// Takes the sound instances that have played to the end, including all of their loops,
+    /// since the last call.
+    pub fn take_completed_sounds(&mut self) -> Vec<SoundInstanceHandle> {
+        std::mem::take(&mut *self.completed_sounds.lock().unwrap())
+    }
+
     /// Returns the position of a playing sound in milliseconds.
     ///
     ////// Returns `None` if the sound is no longer playing.
@@ -462,6 +696,26 @@ impl AudioMixer {
         })
     }
 
+    /// Returns the position of a playing sound in milliseconds, minus the audio that has been
+    /// mixed but not yet played by the output device.
+    ///
+    /// Returns `None` if the sound is no longer playing.
+    pub fn get_stream_sync_position(&self, instance: SoundInstanceHandle) -> Option<f64> {
+        let position = self.get_sound_position(instance)?;
+        let buffer_frames = self.output_buffer_frames.load(Ordering::Relaxed) as f64;
+        let latency = buffer_frames * 1000.0 / f64::from(self.output_sample_rate);
+        Some((position - latency).max(0.0))
+    }
+
+    /// Returns up to `num_sample_frames` of the most recently mixed audio, oldest first.
+    ///
+    /// Samples are in the range -1.0 to 1.0, before the output is downmixed to mono.
+    pub fn get_recent_output(&self, num_sample_frames: usize) -> Vec<[f32; 2]> {
+        let output_history = self.output_history.lock().unwrap();
+        let start = output_history.len().saturating_sub(num_sample_frames);
+        output_history.range(start..).copied().collect()
+    }
+
     /// Returns the duration of a registered sound in milliseconds.
     ///
     /// Returns `None` if the sound is not registered or invalid.
@@ -507,6 +761,17 @@ pub struct AudioMixerProxy {
     /// The list of actively playing sound instances.
     sound_instances: Arc<Mutex<Arena<SoundInstance>>>,
 
+    /// The sound instances that have played to the end since they were last taken.
+    completed_sounds: Arc<Mutex<Vec<SoundInstanceHandle>>>,
+
+    /// The size in sample frames of the last buffer that was mixed.
+    ///
+    /// The output device plays this much audio ahead of what has been heard.
+    output_buffer_frames: Arc<AtomicUsize>,
+
+    /// The most recently mixed sample frames, oldest first.
+    output_history: Arc<Mutex<VecDeque<[f32; 2]>>>,
+
     /// The number of channels in the output stream. Must be 1 or 2.
     num_output_channels: u8,
 }
@@ -522,9 +787,15 @@ impl AudioMixerProxy {
         T::Signed: dasp::sample::conv::FromSample<i16>,
         T::Float: dasp::sample::conv::FromSample<f32>,
     {
+        self.output_buffer_frames.store(
+            output_buffer.len() / usize::from(self.num_output_channels),
+            Ordering::Relaxed,
+        );
         let mut sound_instances = self.sound_instances.lock().unwrap();
         AudioMixer::mix_audio::<T>(
             &mut sound_instances,
+            &self.completed_sounds,
+            &self.output_history,
             self.num_output_channels,
             output_buffer,
         )
@@ -644,43 +915,210 @@ impl Stream for EventSoundStream {
     }
 }
 
+/// A stream of 44.1kHz samples that are generated at runtime.
+struct DynamicStream {
+    queue: Arc<Mutex<DynamicQueue>>,
+    position: u32,
+    is_exhausted: bool,
+}
+
+impl dasp::signal::Signal for DynamicStream {
+    type Frame = [i16; 2];
+
+    #[inline]
+    fn next(&mut self) -> Self::Frame {
+        let mut queue = self.queue.lock().unwrap();
+        if let Some(frame) = queue.samples.pop_front() {
+            self.position += 1;
+            frame
+        } else {
+            // Play silence while waiting for more samples.
+            self.is_exhausted = queue.ended;
+            [0, 0]
+        }
+    }
+
+    #[inline]
+    fn is_exhausted(&self) -> bool {
+        self.is_exhausted
+    }
+}
+
+impl Stream for DynamicStream {
+    #[inline]
+    fn source_position(&self) -> u32 {
+        self.position
+    }
+
+    #[inline]
+    fn source_sample_rate(&self) -> u16 {
+        44100
+    }
+}
+
 /// A stream that converts a source stream to a different sample rate.
-struct ConverterStream<S, I>(dasp::signal::interpolate::Converter<S, I>)
-where
-    S: Stream,
-    I: dasp::interpolate::Interpolator<Frame = [i16; 2]>;
+struct ResamplerStream<S> {
+    source: S,
 
-impl<S, I> Stream for ConverterStream<S, I>
-where
-    S: Stream,
-    I: dasp::interpolate::Interpolator<Frame = [i16; 2]> + Send,
-{
+    resampling: Resampling,
+
+    /// The source sample frames surrounding the current output position.
+    ///
+    /// The output position lies between `frames[center]` and `frames[center + 1]`, where
+    /// `center` is `frames.len() / 2 - 1`.
+    frames: VecDeque<[f32; 2]>,
+
+    /// The fractional position between the two center sample frames.
+    phase: f64,
+
+    /// The number of source sample frames per output sample frame.
+    step: f64,
+
+    /// The cutoff frequency of the sinc filter, relative to the source Nyquist frequency.
+    cutoff: f64,
+
+    /// The number of sample frames that were read after the source was exhausted.
+    num_tail_frames: usize,
+}
+
+impl<S: Stream> ResamplerStream<S> {
+    fn new(mut source: S, sample_rate: u32, resampling: Resampling) -> Self {
+        let num_taps = match resampling {
+            Resampling::Linear => 2,
+            Resampling::Cubic => 4,
+            Resampling::Sinc => 2 * SINC_HALF_WIDTH,
+        };
+
+        // Pad the frames before the start of the sound with silence.
+        let mut frames = VecDeque::with_capacity(num_taps);
+        frames.resize(num_taps / 2 - 1, [0.0; 2]);
+        let mut stream = Self {
+            source,
+            resampling,
+            frames: VecDeque::new(),
+            phase: 0.0,
+            step: 1.0,
+            cutoff: 1.0,
+            num_tail_frames: 0,
+        };
+        stream.set_output_sample_rate(sample_rate);
+        while frames.len() < num_taps {
+            frames.push_back(stream.next_source_frame());
+        }
+        stream.frames = frames;
+        stream
+    }
+
+    /// Reads the next sample frame from the source stream.
+    fn next_source_frame(&mut self) -> [f32; 2] {
+        let [left, right] = self.source.next();
+        if self.source.is_exhausted() {
+            self.num_tail_frames += 1;
+        }
+        [f32::from(left), f32::from(right)]
+    }
+
+    /// Interpolates a sample frame at the current position.
+    fn interpolate(&self) -> [f32; 2] {
+        let center = self.frames.len() / 2 - 1;
+        let t = self.phase as f32;
+        match self.resampling {
+            Resampling::Linear => {
+                let [a, b] = [self.frames[center], self.frames[center + 1]];
+                [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t]
+            }
+            Resampling::Cubic => {
+                let cubic = |channel: usize| {
+                    let y0 = self.frames[center - 1][channel];
+                    let y1 = self.frames[center][channel];
+                    let y2 = self.frames[center + 1][channel];
+                    let y3 = self.frames[center + 2][channel];
+                    let a = -0.5 * y0 + 1.5 * y1 - 1.5 * y2 + 0.5 * y3;
+                    let b = y0 - 2.5 * y1 + 2.0 * y2 - 0.5 * y3;
+                    let c = -0.5 * y0 + 0.5 * y2;
+                    ((a * t + b) * t + c) * t + y1
+                };
+                [cubic(0), cubic(1)]
+            }
+            Resampling::Sinc => {
+                let half_width = SINC_HALF_WIDTH as f64;
+                let mut sum = [0.0; 2];
+                let mut total_weight = 0.0;
+                for (i, frame) in self.frames.iter().enumerate() {
+                    let x = i as f64 - center as f64 - self.phase;
+                    let window = 0.42
+                        + 0.5 * (std::f64::consts::PI * x / half_width).cos()
+                        + 0.08 * (2.0 * std::f64::consts::PI * x / half_width).cos();
+                    let weight = sinc(self.cutoff * x) * window;
+                    sum[0] += weight * f64::from(frame[0]);
+                    sum[1] += weight * f64::from(frame[1]);
+                    total_weight += weight;
+                }
+                // Normalize so that the filter doesn't change the volume of the sound.
+                [
+                    (sum[0] / total_weight) as f32,
+                    (sum[1] / total_weight) as f32,
+                ]
+            }
+        }
+    }
+}
+
+/// The normalized sinc function, `sin(pi * x) / (pi * x)`.
+fn sinc(x: f64) -> f64 {
+    if x.abs() < f64::EPSILON {
+        1.0
+    } else {
+        let x = std::f64::consts::PI * x;
+        x.sin() / x
+    }
+}
+
+impl<S: Stream> Stream for ResamplerStream<S> {
     #[inline]
     fn source_position(&self) -> u32 {
-        self.0.source().source_position()
+        // The source has been read ahead of the output position by the frames after the center.
+        let num_frames_ahead = self.frames.len() / 2 + 1;
+        self.source
+            .source_position()
+            .saturating_sub(num_frames_ahead as u32)
     }
 
     #[inline]
     fn source_sample_rate(&self) -> u16 {
-        self.0.source().source_sample_rate()
+        self.source.source_sample_rate()
+    }
+
+    fn set_output_sample_rate(&mut self, sample_rate: u32) {
+        let source_sample_rate = f64::from(self.source.source_sample_rate());
+        self.step = source_sample_rate / f64::from(sample_rate);
+        self.cutoff = (f64::from(sample_rate) / source_sample_rate).min(1.0);
     }
 }
 
-impl<S, I> dasp::signal::Signal for ConverterStream<S, I>
-where
-    S: Stream,
-    I: dasp::interpolate::Interpolator<Frame = [i16; 2]> + Send,
-{
+impl<S: Stream> dasp::signal::Signal for ResamplerStream<S> {
     type Frame = [i16; 2];
 
-    #[inline]
     fn next(&mut self) -> [i16; 2] {
-        self.0.next()
+        let [left, right] = self.interpolate();
+        self.phase += self.step;
+        while self.phase >= 1.0 {
+            self.phase -= 1.0;
+            let frame = self.next_source_frame();
+            self.frames.pop_front();
+            self.frames.push_back(frame);
+        }
+        // Cubic and sinc interpolation may overshoot the source samples.
+        [
+            left.clamp(i16::MIN.into(), i16::MAX.into()) as i16,
+            right.clamp(i16::MIN.into(), i16::MAX.into()) as i16,
+        ]
     }
 
     #[inline]
     fn is_exhausted(&self) -> bool {
-        self.0.is_exhausted()
+        // The sound has ended once the output position has moved past its last sample frame.
+        self.num_tail_frames > self.frames.len() / 2
     }
 }
 
@@ -718,6 +1156,11 @@ where
     fn source_sample_rate(&self) -> u16 {
         self.stream.source_sample_rate()
     }
+
+    fn set_output_sample_rate(&mut self, sample_rate: u32) {
+        // The envelope keeps the timing of the original output rate.
+        self.stream.set_output_sample_rate(sample_rate);
+    }
 }
 
 impl<S, E> dasp::signal::Signal for MulAmpStream<S, E>
@@ -879,6 +1322,21 @@ macro_rules! impl_audio_mixer_backend {
             self.$mixer.get_sound_position(instance)
         }
 
+        #[inline]
+        fn get_stream_sync_position(&self, instance: SoundInstanceHandle) -> Option<f64> {
+            self.$mixer.get_stream_sync_position(instance)
+        }
+
+        #[inline]
+        fn get_recent_output(&self, num_sample_frames: usize) -> Vec<[f32; 2]> {
+            self.$mixer.get_recent_output(num_sample_frames)
+        }
+
+        #[inline]
+        fn take_completed_sounds(&mut self) -> Vec<SoundInstanceHandle> {
+            self.$mixer.take_completed_sounds()
+        }
+
         #[inline]
         fn get_sound_duration(&self, sound: SoundHandle) -> Option<f64> {
             self.$mixer.get_sound_duration(sound)
@@ -902,5 +1360,33 @@ macro_rules! impl_audio_mixer_backend {
         ) {
             self.$mixer.set_sound_transform(instance, transform)
         }
+
+        #[inline]
+        fn start_dynamic_sound(&mut self) -> Result<SoundInstanceHandle, Error> {
+            self.$mixer.start_dynamic_sound()
+        }
+
+        #[inline]
+        fn queue_dynamic_samples(&mut self, instance: SoundInstanceHandle, samples: &[[i16; 2]]) {
+            self.$mixer.queue_dynamic_samples(instance, samples)
+        }
+
+        #[inline]
+        fn end_dynamic_sound(&mut self, instance: SoundInstanceHandle) {
+            self.$mixer.end_dynamic_sound(instance)
+        }
+
+        #[inline]
+        fn dynamic_sound_queue_length(&self, instance: SoundInstanceHandle) -> Option<u32> {
+            self.$mixer.dynamic_sound_queue_length(instance)
+        }
+
+        #[inline]
+        fn decode_sound(
+            &mut self,
+            sound: SoundHandle,
+        ) -> Result<std::sync::Arc<[[i16; 2]]>, Error> {
+            self.$mixer.decode_sound(sound)
+        }
     };
 }
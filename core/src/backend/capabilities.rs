@@ -0,0 +1,81 @@
+pub use crate::avm1::globals::system::{Language, OperatingSystem, PlayerType, SystemCapabilities};
+
+/// Describes the system that the player is running on.
+///
+/// Content can read this through `System.capabilities`, and often uses it to
+/// pick a layout or decide which features to use.
+pub trait CapabilitiesProvider {
+    /// The resolution of the screen, in pixels.
+    fn screen_resolution(&self) -> (u32, u32);
+
+    /// The DPI of the screen.
+    fn screen_dpi(&self) -> f32 {
+        72.0
+    }
+
+    /// The language of the user.
+    fn language(&self) -> Language;
+
+    /// The operating system that the player is running on.
+    fn os(&self) -> OperatingSystem;
+
+    /// How the player is being run.
+    fn player_type(&self) -> PlayerType;
+
+    /// The features that the player supports.
+    fn capabilities(&self) -> SystemCapabilities;
+}
+
+/// Values to report instead of the ones given by the `CapabilitiesProvider`.
+///
+/// This lets the player pretend to be a different system, such as for content
+/// that only works on a particular OS or player type.
+#[derive(Default)]
+pub struct CapabilitiesOverrides {
+    pub screen_resolution: Option<(u32, u32)>,
+    pub screen_dpi: Option<f32>,
+    pub language: Option<Language>,
+    pub os: Option<OperatingSystem>,
+    pub player_type: Option<PlayerType>,
+    pub capabilities: Option<SystemCapabilities>,
+
+    /// The version string, such as `WIN 32,0,0,0`.
+    pub version: Option<String>,
+}
+
+/// Capabilities provider that describes a generic desktop system.
+pub struct NullCapabilitiesProvider {}
+
+impl NullCapabilitiesProvider {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl CapabilitiesProvider for NullCapabilitiesProvider {
+    fn screen_resolution(&self) -> (u32, u32) {
+        (0, 0)
+    }
+
+    fn language(&self) -> Language {
+        Language::English
+    }
+
+    fn os(&self) -> OperatingSystem {
+        OperatingSystem::Linux
+    }
+
+    fn player_type(&self) -> PlayerType {
+        PlayerType::StandAlone
+    }
+
+    fn capabilities(&self) -> SystemCapabilities {
+        SystemCapabilities::empty()
+    }
+}
+
+impl Default for NullCapabilitiesProvider {
+    fn default() -> Self {
+        NullCapabilitiesProvider::new()
+    }
+}
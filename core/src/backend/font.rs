@@ -0,0 +1,126 @@
+use std::rc::Rc;
+
+/// The families that the generic `_sans` device font is looked up as.
+pub const SANS_FAMILIES: &[&str] = &[
+    "Arial",
+    "Helvetica",
+    "Liberation Sans",
+    "DejaVu Sans",
+    "Noto Sans",
+];
+
+/// The families that the generic `_serif` device font is looked up as.
+pub const SERIF_FAMILIES: &[&str] = &[
+    "Times New Roman",
+    "Times",
+    "Liberation Serif",
+    "DejaVu Serif",
+    "Noto Serif",
+];
+
+/// The families that the generic `_typewriter` device font is looked up as.
+pub const TYPEWRITER_FAMILIES: &[&str] = &[
+    "Courier New",
+    "Courier",
+    "Liberation Mono",
+    "DejaVu Sans Mono",
+    "Noto Sans Mono",
+];
+
+/// The families searched for Chinese, Japanese and Korean characters that
+/// a device font has no glyphs for.
+pub const CJK_FALLBACK_FAMILIES: &[&str] = &[
+    "Noto Sans CJK JP",
+    "Noto Sans CJK SC",
+    "Source Han Sans",
+    "Microsoft YaHei",
+    "MS Gothic",
+    "Malgun Gothic",
+    "Hiragino Sans",
+    "PingFang SC",
+    "WenQuanYi Micro Hei",
+];
+
+/// The families searched for any other characters that a device font has no
+/// glyphs for.
+pub const FALLBACK_FAMILIES: &[&str] = &[
+    "Arial Unicode MS",
+    "Segoe UI Symbol",
+    "Noto Sans",
+    "Noto Sans Symbols",
+    "DejaVu Sans",
+];
+
+/// Provides the fonts installed on the system.
+///
+/// Text fields that use device fonts instead of fonts embedded in the movie
+/// are rendered with the outlines of these fonts.
+pub trait FontBackend {
+    /// The family names of the installed fonts.
+    fn font_names(&self) -> Vec<String>;
+
+    /// Loads the installed font of the given family that best matches the
+    /// given style, or returns `None` if no font of the family is installed.
+    fn load_font(&mut self, family: &str, is_bold: bool, is_italic: bool) -> Option<FontFile>;
+}
+
+/// A TrueType or OpenType font file.
+#[derive(Clone)]
+pub struct FontFile {
+    /// The contents of the file.
+    pub data: Rc<[u8]>,
+
+    /// The index of the font in a font collection, or 0 for a single font.
+    pub index: u32,
+}
+
+impl std::fmt::Debug for FontFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FontFile")
+            .field("len", &self.data.len())
+            .field("index", &self.index)
+            .finish()
+    }
+}
+
+/// Returns the families to look up for a device font name, in order.
+///
+/// The generic `_sans`, `_serif` and `_typewriter` names are looked up as
+/// common families of their kind. Any other name falls back to the sans
+/// families if the font isn't installed.
+pub fn device_font_families(name: &str) -> Vec<&str> {
+    match name {
+        "_sans" => SANS_FAMILIES.to_vec(),
+        "_serif" => SERIF_FAMILIES.to_vec(),
+        "_typewriter" => TYPEWRITER_FAMILIES.to_vec(),
+        _ => std::iter::once(name)
+            .chain(SANS_FAMILIES.iter().copied())
+            .collect(),
+    }
+}
+
+/// Font backend for when the fonts of the system aren't available, so that
+/// device text is rendered with the font embedded in the player.
+pub struct NullFontBackend {}
+
+impl NullFontBackend {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl FontBackend for NullFontBackend {
+    fn font_names(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn load_font(&mut self, _family: &str, _is_bold: bool, _is_italic: bool) -> Option<FontFile> {
+        None
+    }
+}
+
+impl Default for NullFontBackend {
+    fn default() -> Self {
+        NullFontBackend::new()
+    }
+}
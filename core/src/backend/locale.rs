@@ -1,9 +1,136 @@
-use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+use chrono::{
+    DateTime, Duration, FixedOffset, LocalResult, NaiveDate, NaiveDateTime, Offset, TimeZone, Utc,
+};
+use std::fmt;
+use std::rc::Rc;
 
 pub trait LocaleBackend {
     fn get_current_date_time(&self) -> DateTime<Utc>;
 
-    fn get_timezone(&self) -> FixedOffset;
+    /// The timezone that local times are in.
+    fn get_timezone(&self) -> LocalTimezone;
+}
+
+/// Provides the offset of local time from UTC.
+///
+/// The offset may differ between dates, such as when the timezone observes
+/// daylight saving time. Every `chrono` timezone is a provider, so a frontend
+/// can simply use `chrono::Local`.
+pub trait TimezoneProvider {
+    /// The offset of local time at the given UTC time.
+    fn offset_from_utc(&self, utc: &NaiveDateTime) -> FixedOffset;
+
+    /// The offsets that the given local time may have.
+    ///
+    /// This is ambiguous for local times that are repeated when DST ends,
+    /// and has no result for local times that are skipped when DST starts.
+    fn offset_from_local(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset>;
+}
+
+impl<T: TimeZone> TimezoneProvider for T {
+    fn offset_from_utc(&self, utc: &NaiveDateTime) -> FixedOffset {
+        self.offset_from_utc_datetime(utc).fix()
+    }
+
+    fn offset_from_local(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+        self.offset_from_local_datetime(local)
+            .map(|offset| offset.fix())
+    }
+}
+
+/// The local timezone of the player, as given by a `TimezoneProvider`.
+#[derive(Clone)]
+pub struct LocalTimezone(Rc<dyn TimezoneProvider>);
+
+impl LocalTimezone {
+    pub fn new(provider: impl TimezoneProvider + 'static) -> Self {
+        Self(Rc::new(provider))
+    }
+
+    fn with_offset(&self, offset: FixedOffset) -> LocalOffset {
+        LocalOffset {
+            timezone: self.clone(),
+            offset,
+        }
+    }
+}
+
+impl fmt::Debug for LocalTimezone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("LocalTimezone")
+    }
+}
+
+impl TimeZone for LocalTimezone {
+    type Offset = LocalOffset;
+
+    fn from_offset(offset: &LocalOffset) -> Self {
+        offset.timezone.clone()
+    }
+
+    fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<LocalOffset> {
+        self.offset_from_local_datetime(&local.and_hms(0, 0, 0))
+    }
+
+    fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<LocalOffset> {
+        self.0
+            .offset_from_local(local)
+            .map(|offset| self.with_offset(offset))
+    }
+
+    fn offset_from_utc_date(&self, utc: &NaiveDate) -> LocalOffset {
+        self.offset_from_utc_datetime(&utc.and_hms(0, 0, 0))
+    }
+
+    fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> LocalOffset {
+        self.with_offset(self.0.offset_from_utc(utc))
+    }
+}
+
+/// The offset of a `LocalTimezone` at some time.
+#[derive(Clone)]
+pub struct LocalOffset {
+    timezone: LocalTimezone,
+    offset: FixedOffset,
+}
+
+impl Offset for LocalOffset {
+    fn fix(&self) -> FixedOffset {
+        self.offset
+    }
+}
+
+impl fmt::Debug for LocalOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.offset, f)
+    }
+}
+
+impl fmt::Display for LocalOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.offset, f)
+    }
+}
+
+/// Convert a local time in the given timezone to UTC, the way Flash does.
+///
+/// Local times that are skipped when DST starts are moved forward by the
+/// length of the gap, and local times that are repeated when DST ends are
+/// taken to be the earlier of the two.
+pub fn local_to_utc<T: TimeZone>(timezone: &T, local: &NaiveDateTime) -> Option<DateTime<Utc>> {
+    match timezone.from_local_datetime(local) {
+        LocalResult::Single(date) | LocalResult::Ambiguous(date, _) => {
+            Some(date.with_timezone(&Utc))
+        }
+        LocalResult::None => {
+            // Use the offset from before the transition.
+            let before = local.checked_sub_signed(Duration::days(1))?;
+            let offset = timezone.offset_from_utc_datetime(&before).fix();
+            let utc =
+                local.checked_sub_signed(Duration::seconds(offset.local_minus_utc().into()))?;
+            Some(DateTime::from_utc(utc, Utc))
+        }
+    }
 }
 
 /// Locale backend that mostly does nothing.
@@ -21,11 +148,14 @@ impl NullLocaleBackend {
 
 impl LocaleBackend for NullLocaleBackend {
     fn get_current_date_time(&self) -> DateTime<Utc> {
-        self.get_timezone().ymd(2001, 2, 3).and_hms(4, 5, 6).into()
+        self.get_timezone()
+            .ymd(2001, 2, 3)
+            .and_hms(4, 5, 6)
+            .with_timezone(&Utc)
     }
 
-    fn get_timezone(&self) -> FixedOffset {
-        FixedOffset::east(20700)
+    fn get_timezone(&self) -> LocalTimezone {
+        LocalTimezone::new(FixedOffset::east(20700))
     }
 }
 
@@ -34,3 +164,84 @@ impl Default for NullLocaleBackend {
         NullLocaleBackend::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A timezone at UTC-5, with DST starting at 2021-03-14 02:00 and ending
+    /// at 2021-11-07 02:00 local time.
+    struct DstTimezone;
+
+    impl DstTimezone {
+        fn is_dst(utc: &NaiveDateTime) -> bool {
+            let start = NaiveDate::from_ymd(2021, 3, 14).and_hms(7, 0, 0);
+            let end = NaiveDate::from_ymd(2021, 11, 7).and_hms(6, 0, 0);
+            (start..end).contains(utc)
+        }
+    }
+
+    impl TimezoneProvider for DstTimezone {
+        fn offset_from_utc(&self, utc: &NaiveDateTime) -> FixedOffset {
+            if Self::is_dst(utc) {
+                FixedOffset::west(4 * 3600)
+            } else {
+                FixedOffset::west(5 * 3600)
+            }
+        }
+
+        fn offset_from_local(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+            let offsets: Vec<_> = [FixedOffset::west(5 * 3600), FixedOffset::west(4 * 3600)]
+                .into_iter()
+                .filter(|offset| {
+                    let utc = *local - Duration::seconds(offset.local_minus_utc().into());
+                    self.offset_from_utc(&utc) == *offset
+                })
+                .collect();
+            match offsets[..] {
+                [offset] => LocalResult::Single(offset),
+                // Local times with the DST offset happen earlier.
+                [standard, dst] => LocalResult::Ambiguous(dst, standard),
+                _ => LocalResult::None,
+            }
+        }
+    }
+
+    fn local(month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd(2021, month, day).and_hms(hour, minute, 0)
+    }
+
+    #[test]
+    fn offset_follows_dst() {
+        let timezone = LocalTimezone::new(DstTimezone);
+        let winter = Utc.ymd(2021, 1, 1).and_hms(12, 0, 0);
+        let summer = Utc.ymd(2021, 7, 1).and_hms(12, 0, 0);
+        assert_eq!(
+            winter.with_timezone(&timezone).offset().fix(),
+            FixedOffset::west(5 * 3600)
+        );
+        assert_eq!(
+            summer.with_timezone(&timezone).offset().fix(),
+            FixedOffset::west(4 * 3600)
+        );
+        assert_eq!(
+            summer.with_timezone(&timezone).naive_local(),
+            local(7, 1, 8, 0)
+        );
+    }
+
+    #[test]
+    fn local_to_utc_transitions() {
+        let timezone = LocalTimezone::new(DstTimezone);
+        let utc = |local| local_to_utc(&timezone, &local).unwrap().naive_utc();
+
+        assert_eq!(utc(local(1, 1, 12, 0)), local(1, 1, 17, 0));
+        assert_eq!(utc(local(7, 1, 12, 0)), local(7, 1, 16, 0));
+
+        // 02:30 doesn't exist when DST starts, so it's moved forward to 03:30.
+        assert_eq!(utc(local(3, 14, 2, 30)), local(3, 14, 7, 30));
+
+        // 01:30 happens twice when DST ends, and the earlier one is used.
+        assert_eq!(utc(local(11, 7, 1, 30)), local(11, 7, 5, 30));
+    }
+}
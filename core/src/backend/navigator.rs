@@ -2,6 +2,7 @@
 
 use crate::loader::Error;
 use crate::string::WStr;
+use generational_arena::Index;
 use indexmap::IndexMap;
 use std::borrow::Cow;
 use std::collections::VecDeque;
@@ -10,7 +11,9 @@ use std::future::Future;
 use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::ptr::null;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
 use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
 use std::time::Duration;
 use swf::avm1::types::SendVarsMethod;
@@ -110,6 +113,9 @@ pub struct RequestOptions {
     ///
     /// The body consists of data and a mime type.
     body: Option<(Vec<u8>, String)>,
+
+    /// Additional HTTP headers to be sent with the request.
+    headers: Vec<(String, String)>,
 }
 
 impl RequestOptions {
@@ -118,6 +124,7 @@ impl RequestOptions {
         Self {
             method: NavigationMethod::Get,
             body: None,
+            headers: Vec::new(),
         }
     }
 
@@ -126,6 +133,7 @@ impl RequestOptions {
         Self {
             method: NavigationMethod::Post,
             body,
+            headers: Vec::new(),
         }
     }
 
@@ -138,12 +146,72 @@ impl RequestOptions {
     pub fn body(&self) -> &Option<(Vec<u8>, String)> {
         &self.body
     }
+
+    /// Replace the mime type of this request's body, if it exists.
+    pub fn with_content_type(mut self, content_type: String) -> Self {
+        if let Some((_, mime)) = &mut self.body {
+            *mime = content_type;
+        }
+        self
+    }
+
+    /// Add HTTP headers to be sent with this request.
+    pub fn with_headers(mut self, headers: impl IntoIterator<Item = (String, String)>) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
+    /// Retrieve the additional HTTP headers of this request.
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
 }
 
 /// Type alias for pinned, boxed, and owned futures that output a falliable
 /// result of type `Result<T, E>`.
 pub type OwnedFuture<T, E> = Pin<Box<dyn Future<Output = Result<T, E>> + 'static>>;
 
+/// How much of a response has arrived so far.
+///
+/// This is shared between the navigator carrying out a fetch, which updates
+/// it as data comes in, and whoever is waiting on the fetch.
+#[derive(Clone, Debug, Default)]
+pub struct FetchProgress {
+    loaded: Arc<AtomicUsize>,
+
+    /// The expected length of the response, or 0 if it isn't known yet.
+    total: Arc<AtomicUsize>,
+}
+
+impl FetchProgress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of bytes received so far.
+    pub fn loaded(&self) -> usize {
+        self.loaded.load(Ordering::Relaxed)
+    }
+
+    /// The expected length of the response, if the server announced it.
+    pub fn total(&self) -> Option<usize> {
+        match self.total.load(Ordering::Relaxed) {
+            0 => None,
+            total => Some(total),
+        }
+    }
+
+    /// Record that `len` more bytes have been received.
+    pub fn add_loaded(&self, len: usize) {
+        self.loaded.fetch_add(len, Ordering::Relaxed);
+    }
+
+    /// Record the expected length of the response.
+    pub fn set_total(&self, total: usize) {
+        self.total.store(total, Ordering::Relaxed);
+    }
+}
+
 /// A backend interacting with a browser environment.
 pub trait NavigatorBackend {
     /// Cause a browser navigation to a given URL.
@@ -178,6 +246,20 @@ pub trait NavigatorBackend {
     /// Fetch data at a given URL and return it some time in the future.
     fn fetch(&self, url: &str, request_options: RequestOptions) -> OwnedFuture<Vec<u8>, Error>;
 
+    /// Fetch data at a given URL, recording in `progress` how much of it has
+    /// arrived while the fetch is underway.
+    ///
+    /// Backends that only receive responses in one piece may leave `progress`
+    /// untouched, which is what the default implementation does.
+    fn fetch_with_progress(
+        &self,
+        url: &str,
+        request_options: RequestOptions,
+        _progress: FetchProgress,
+    ) -> OwnedFuture<Vec<u8>, Error> {
+        self.fetch(url, request_options)
+    }
+
     /// Get the amount of time since the SWF was launched.
     /// Used by the `getTimer` ActionScript call.
     fn time_since_launch(&mut self) -> Duration;
@@ -207,6 +289,44 @@ pub trait NavigatorBackend {
     /// Changing http -> https for example. This function may alter any part of the
     /// URL (generally only if configured to do so by the user).
     fn pre_process_url(&self, url: Url) -> Url;
+
+    /// Open a socket connection to the given host and port, as used by `XMLSocket`.
+    ///
+    /// Connecting happens in the background. Whether it succeeded, and any
+    /// data received afterwards, is reported by `take_socket_events`.
+    ///
+    /// Returns `None` if this backend can't open sockets.
+    fn connect_socket(&mut self, _host: &str, _port: u16) -> Option<SocketHandle> {
+        None
+    }
+
+    /// Send data over a connected socket.
+    fn send_socket(&mut self, _handle: SocketHandle, _data: Vec<u8>) {}
+
+    /// Close a socket. No further events will be reported for it.
+    fn close_socket(&mut self, _handle: SocketHandle) {}
+
+    /// Take the events that have occurred on open sockets since the last call.
+    fn take_socket_events(&mut self) -> Vec<SocketEvent> {
+        Vec::new()
+    }
+}
+
+pub type SocketHandle = Index;
+
+/// Something that happened on a socket opened with `NavigatorBackend::connect_socket`.
+#[derive(Debug)]
+pub enum SocketEvent {
+    /// The connection attempt finished, successfully or not.
+    ///
+    /// A socket that failed to connect is closed.
+    Connected(SocketHandle, bool),
+
+    /// Data was received.
+    Data(SocketHandle, Vec<u8>),
+
+    /// The connection was closed by the other end, or because of an error.
+    Closed(SocketHandle),
 }
 
 /// A null implementation of an event loop that only supports blocking.
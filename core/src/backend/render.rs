@@ -1,3 +1,6 @@
+use crate::backend::video::{AlphaMode, ColorMatrix};
+use crate::bitmap::filters::{self, BitmapFilter};
+use crate::display_object::StageQuality;
 use crate::matrix::Matrix;
 use crate::shape_utils::DistilledShape;
 pub use crate::{library::MovieLibrary, transform::Transform, Color};
@@ -36,7 +39,30 @@ pub trait RenderBackend: Downcast {
         swf_tag: &swf::DefineBitsLossless,
     ) -> Result<BitmapInfo, Error>;
 
+    /// Sets the quality of the following frames.
+    ///
+    /// This is called before every frame, and backends may trade anti-aliasing
+    /// for speed according to `StageQuality::sample_count`.
+    fn set_quality(&mut self, _quality: StageQuality) {}
+
     fn begin_frame(&mut self, clear: Color);
+
+    /// Whether the backend keeps the last frame it rendered, so that a frame
+    /// can be started with `begin_partial_frame`.
+    fn supports_partial_frames(&self) -> bool {
+        false
+    }
+
+    /// Begin a frame that only clears and draws inside of the given
+    /// rectangles of the viewport, keeping the rest of the last frame.
+    ///
+    /// This is only called if `supports_partial_frames` returns true. The
+    /// same drawing commands as for a full frame follow, and anything they
+    /// draw outside of the rectangles is discarded.
+    fn begin_partial_frame(&mut self, clear: Color, _damage: &[ViewportRect]) {
+        self.begin_frame(clear);
+    }
+
     fn render_bitmap(&mut self, bitmap: BitmapHandle, transform: &Transform, smoothing: bool);
     fn render_shape(&mut self, shape: ShapeHandle, transform: &Transform);
     fn draw_rect(&mut self, color: Color, matrix: &Matrix);
@@ -46,6 +72,14 @@ pub trait RenderBackend: Downcast {
     fn deactivate_mask(&mut self);
     fn pop_mask(&mut self);
 
+    /// Begin rendering a display object with the given blend mode.
+    ///
+    /// Everything drawn until the matching `pop_blend_mode` is rendered into
+    /// a transparent layer of its own, which is then composited onto
+    /// whatever was drawn before it using the blend mode.
+    fn push_blend_mode(&mut self, blend_mode: swf::BlendMode);
+    fn pop_blend_mode(&mut self);
+
     fn get_bitmap_pixels(&mut self, bitmap: BitmapHandle) -> Option<Bitmap>;
     fn register_bitmap_raw(
         &mut self,
@@ -53,13 +87,81 @@ pub trait RenderBackend: Downcast {
         height: u32,
         rgba: Vec<u8>,
     ) -> Result<BitmapHandle, Error>;
+
+    /// Replace the image of a bitmap registered with `register_bitmap_raw`.
+    ///
+    /// The new image may be a different size, in which case the texture is
+    /// recreated under the same handle. Implementations should reject `rgba`
+    /// that isn't `width` by `height` pixels with `check_rgba_len`.
     fn update_texture(
         &mut self,
         bitmap: BitmapHandle,
         width: u32,
         height: u32,
-        rgba: Vec<u8>,
+        rgba: &[u8],
     ) -> Result<BitmapHandle, Error>;
+
+    /// Register a new bitmap from planar YUV data, or update an existing one.
+    ///
+    /// Backends that are able to convert YUV to RGB in a shader should
+    /// override this. By default, the conversion happens on the CPU, and the
+    /// result is passed to `register_bitmap_raw` or `update_texture`.
+    fn register_bitmap_yuv(
+        &mut self,
+        bitmap: Option<BitmapHandle>,
+        width: u32,
+        height: u32,
+        planes: &YuvPlanes,
+    ) -> Result<BitmapHandle, Error> {
+        let rgba = planes.to_rgba(width as usize, height as usize);
+        match bitmap {
+            Some(bitmap) => self.update_texture(bitmap, width, height, &rgba),
+            None => self.register_bitmap_raw(width, height, rgba),
+        }
+    }
+
+    /// The alpha mode that decoded video frames should be in when passed to
+    /// `register_bitmap_raw` or `update_texture`.
+    ///
+    /// Video frames are converted to this mode before being uploaded.
+    fn video_alpha_mode(&self) -> AlphaMode {
+        AlphaMode::Premultiplied
+    }
+
+    /// Render an image of the given size offscreen instead of to the screen,
+    /// such as for `BitmapData.draw`, filters, bitmap caching and printing.
+    ///
+    /// `draw` issues the drawing commands, and is called between the begin
+    /// and end of the offscreen frame. This may be called while a frame is
+    /// being rendered, which is left as it was. Returns the image in
+    /// un-multiplied RGBA, or `None` if this backend can't render offscreen.
+    /// The image may be smaller than requested if the backend can't create
+    /// one that large.
+    fn render_offscreen(
+        &mut self,
+        _width: u32,
+        _height: u32,
+        _clear: Color,
+        _draw: &mut dyn FnMut(&mut dyn RenderBackend),
+    ) -> Option<Bitmap> {
+        None
+    }
+
+    /// Run a chain of filters on an image in un-multiplied RGBA, such as one
+    /// returned by `render_offscreen`, returning it in pre-multiplied RGBA.
+    ///
+    /// Backends with filter shaders should override this, using
+    /// `filters::apply_filters_accelerated` to run the rest on the CPU. By
+    /// default, every filter runs on the CPU.
+    fn apply_filters(
+        &mut self,
+        filters: &[BitmapFilter],
+        width: u32,
+        height: u32,
+        rgba: &[u8],
+    ) -> Vec<u8> {
+        filters::apply_filters_rgba(filters, width, height, rgba)
+    }
 }
 impl_downcast!(RenderBackend);
 
@@ -72,6 +174,27 @@ pub struct ShapeHandle(pub usize);
 #[collect(no_drop)]
 pub struct BitmapHandle(pub usize);
 
+/// A rectangle of the viewport in pixels, with exclusive maximums.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ViewportRect {
+    pub x_min: u32,
+    pub y_min: u32,
+    pub x_max: u32,
+    pub y_max: u32,
+}
+
+impl ViewportRect {
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        x >= self.x_min && x < self.x_max && y >= self.y_min && y < self.y_max
+    }
+
+    /// The number of pixels inside of the rectangle.
+    pub fn area(&self) -> u64 {
+        u64::from(self.x_max.saturating_sub(self.x_min))
+            * u64::from(self.y_max.saturating_sub(self.y_min))
+    }
+}
+
 /// Info returned by the `register_bitmap` methods.
 #[derive(Copy, Clone, Debug)]
 pub struct BitmapInfo {
@@ -80,6 +203,25 @@ pub struct BitmapInfo {
     pub height: u16,
 }
 
+/// Planar YUV 4:2:0 image data, such as a decoded frame of video.
+///
+/// The planes may be wider than the image they hold, in which case the
+/// excess on the right of every row is ignored.
+#[derive(Clone, Debug)]
+pub struct YuvPlanes {
+    pub y: Vec<u8>,
+    pub u: Vec<u8>,
+    pub v: Vec<u8>,
+
+    /// The distance between the starts of two rows of the luma plane.
+    pub y_stride: usize,
+
+    /// The distance between the starts of two rows of the chroma planes.
+    pub chroma_stride: usize,
+
+    pub color_matrix: ColorMatrix,
+}
+
 /// An object that returns a bitmap given an ID.
 ///
 /// This is used by render backends to get the bitmap used in a bitmap fill.
@@ -176,6 +318,8 @@ impl RenderBackend for NullRenderer {
     fn activate_mask(&mut self) {}
     fn deactivate_mask(&mut self) {}
     fn pop_mask(&mut self) {}
+    fn push_blend_mode(&mut self, _blend_mode: swf::BlendMode) {}
+    fn pop_blend_mode(&mut self) {}
 
     fn get_bitmap_pixels(&mut self, _bitmap: BitmapHandle) -> Option<Bitmap> {
         None
@@ -194,7 +338,7 @@ impl RenderBackend for NullRenderer {
         _bitmap: BitmapHandle,
         _width: u32,
         _height: u32,
-        _rgba: Vec<u8>,
+        _rgba: &[u8],
     ) -> Result<BitmapHandle, Error> {
         Ok(BitmapHandle(0))
     }
@@ -219,6 +363,19 @@ pub struct Bitmap {
     pub data: BitmapFormat,
 }
 
+impl Bitmap {
+    /// The pixels of the bitmap in RGBA, with RGB bitmaps made opaque.
+    pub fn into_rgba(self) -> Vec<u8> {
+        match self.data {
+            BitmapFormat::Rgb(x) => x
+                .chunks_exact(3)
+                .flat_map(|chunk| [chunk[0], chunk[1], chunk[2], 0xFF])
+                .collect(),
+            BitmapFormat::Rgba(x) => x,
+        }
+    }
+}
+
 /// Decoded bitmap data from an SWF tag.
 /// The image data will have pre-multiplied alpha.
 #[derive(Debug, Clone)]
@@ -588,6 +745,33 @@ pub fn decode_gif(data: &[u8]) -> Result<Bitmap, Error> {
     })
 }
 
+/// Check that `rgba` holds exactly the pixels of a `width` by `height` image,
+/// as backends expect of the data given to `update_texture`.
+pub fn check_rgba_len(width: u32, height: u32, rgba: &[u8]) -> Result<(), Error> {
+    let expected_len = width as usize * height as usize * 4;
+    if rgba.len() != expected_len {
+        return Err(format!(
+            "update_texture: Expected {} bytes of RGBA for a {}x{} image, got {}",
+            expected_len,
+            width,
+            height,
+            rgba.len()
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Converts standard RGBA to RGBA premultiplied alpha.
+pub fn premultiply_alpha_rgba(rgba: &mut [u8]) {
+    rgba.chunks_exact_mut(4).for_each(|rgba| {
+        let a = u16::from(rgba[3]);
+        rgba[0] = ((u16::from(rgba[0]) * a + 127) / 255) as u8;
+        rgba[1] = ((u16::from(rgba[1]) * a + 127) / 255) as u8;
+        rgba[2] = ((u16::from(rgba[2]) * a + 127) / 255) as u8;
+    })
+}
+
 /// Images in SWFs are stored with premultiplied alpha.
 /// Converts RGBA premultiplied alpha to standard RBGA.
 pub fn unmultiply_alpha_rgba(rgba: &mut [u8]) {
@@ -10,6 +10,11 @@ pub trait StorageBackend {
     }
 
     fn remove_key(&mut self, name: &str);
+
+    /// The maximum number of bytes that may be stored under `name`, if limited.
+    fn size_limit(&self, _name: &str) -> Option<usize> {
+        None
+    }
 }
 
 #[derive(Default)]
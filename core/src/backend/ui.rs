@@ -1,3 +1,5 @@
+use crate::backend::render::Bitmap;
+use crate::context_menu::ContextMenuItem;
 use crate::events::KeyCode;
 use downcast_rs::Downcast;
 
@@ -6,6 +8,9 @@ pub type Error = Box<dyn std::error::Error>;
 pub trait UiBackend: Downcast {
     fn is_key_down(&self, key: KeyCode) -> bool;
 
+    /// Whether a lock key, such as Caps Lock or Num Lock, is toggled on.
+    fn is_key_toggled(&self, key: KeyCode) -> bool;
+
     fn last_key_code(&self) -> KeyCode;
 
     fn last_key_char(&self) -> Option<char>;
@@ -17,9 +22,17 @@ pub trait UiBackend: Downcast {
     /// Changes the mouse cursor image.
     fn set_mouse_cursor(&mut self, cursor: MouseCursor);
 
+    /// Get the text content of the clipboard, or an empty string if it has none.
+    fn clipboard_content(&mut self) -> String;
+
     /// Set the clipboard to the given content
     fn set_clipboard_content(&mut self, content: String);
 
+    /// Moves the composition window of the input method editor to the given
+    /// position in the window, in pixels, so that it's next to the caret of
+    /// the focused text field.
+    fn set_ime_position(&mut self, x: f64, y: f64);
+
     fn set_fullscreen(&mut self, is_full: bool) -> Result<(), Error>;
 
     /// Displays a warning about unsupported content in Ruffle.
@@ -31,6 +44,22 @@ pub trait UiBackend: Downcast {
     /// by providing a direct .swf link instead.
     fn display_root_movie_download_failed_message(&self);
 
+    /// Displays a context menu with the given items.
+    /// When the user picks an item, the frontend should call
+    /// `Player::run_context_menu_callback` with its index, and
+    /// `Player::clear_custom_menu_items` once the menu is closed.
+    fn display_context_menu(&mut self, _items: &[ContextMenuItem]) {}
+
+    /// Asks the user whether the movie may use their microphone.
+    ///
+    /// Backends that can't ask deny access.
+    fn request_microphone_access(&mut self) -> bool {
+        false
+    }
+
+    /// Print the pages of a `PrintJob`, one bitmap per page.
+    fn print(&mut self, pages: Vec<Bitmap>);
+
     // Unused, but kept in case we need it later
     fn message(&self, message: &str);
 }
@@ -71,6 +100,10 @@ impl UiBackend for NullUiBackend {
         false
     }
 
+    fn is_key_toggled(&self, _key: KeyCode) -> bool {
+        false
+    }
+
     fn last_key_code(&self) -> KeyCode {
         KeyCode::Unknown
     }
@@ -87,8 +120,14 @@ impl UiBackend for NullUiBackend {
 
     fn set_mouse_cursor(&mut self, _cursor: MouseCursor) {}
 
+    fn clipboard_content(&mut self) -> String {
+        String::new()
+    }
+
     fn set_clipboard_content(&mut self, _content: String) {}
 
+    fn set_ime_position(&mut self, _x: f64, _y: f64) {}
+
     fn set_fullscreen(&mut self, _is_full: bool) -> Result<(), Error> {
         Ok(())
     }
@@ -97,6 +136,8 @@ impl UiBackend for NullUiBackend {
 
     fn display_root_movie_download_failed_message(&self) {}
 
+    fn print(&mut self, _pages: Vec<Bitmap>) {}
+
     fn message(&self, _message: &str) {}
 }
 
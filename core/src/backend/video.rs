@@ -1,12 +1,23 @@
 //! Video decoder backends
 
-use crate::backend::render::{BitmapInfo, RenderBackend};
+use crate::backend::render::{
+    premultiply_alpha_rgba, unmultiply_alpha_rgba, BitmapInfo, RenderBackend, YuvPlanes,
+};
 use generational_arena::{Arena, Index};
+use std::time::Duration;
 use swf::{VideoCodec, VideoDeblocking};
 
+mod hardware;
 mod software;
+mod yuv;
 
-pub use crate::backend::video::software::SoftwareVideoBackend;
+pub use crate::backend::video::hardware::{
+    HardwareDecoderProvider, HardwareVideoBackend, HardwareVideoDecoder,
+};
+pub use crate::backend::video::software::{
+    FramePool, SoftwareVideoBackend, VideoDecoder, VideoDecoderFactory,
+};
+pub use crate::backend::video::yuv::ColorMatrix;
 
 pub type VideoStreamHandle = Index;
 
@@ -32,11 +43,112 @@ impl<'a> EncodedFrame<'a> {
     }
 }
 
-/// A decoded frame of video in RGBA format.
-struct DecodedFrame {
-    width: u16,
-    height: u16,
-    rgba: Vec<u8>,
+/// A decoded frame of video.
+#[derive(Clone)]
+pub struct DecodedFrame {
+    pub width: u16,
+    pub height: u16,
+    pub pixels: FramePixels,
+
+    /// How the color channels of `pixels` relate to its alpha channel.
+    pub alpha_mode: AlphaMode,
+}
+
+impl DecodedFrame {
+    /// Convert the pixels of this frame to the given alpha mode.
+    ///
+    /// Opaque frames are left untouched, as they look the same in every
+    /// mode.
+    pub fn convert_alpha(&mut self, alpha_mode: AlphaMode) {
+        if let FramePixels::Rgba(rgba) = &mut self.pixels {
+            match (self.alpha_mode, alpha_mode) {
+                (AlphaMode::Premultiplied, AlphaMode::Straight) => unmultiply_alpha_rgba(rgba),
+                (AlphaMode::Straight, AlphaMode::Premultiplied) => premultiply_alpha_rgba(rgba),
+                _ => return,
+            }
+            self.alpha_mode = alpha_mode;
+        }
+    }
+}
+
+/// How the color channels of a frame relate to its alpha channel.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AlphaMode {
+    /// The frame has no transparency, so the color channels need no
+    /// conversion in either direction.
+    Opaque,
+
+    /// The color channels have already been multiplied by alpha.
+    Premultiplied,
+
+    /// The color channels are independent of alpha.
+    Straight,
+}
+
+/// The pixel data of a decoded frame of video.
+#[derive(Clone)]
+pub enum FramePixels {
+    /// Pixels in RGBA format, with no gaps between rows.
+    Rgba(Vec<u8>),
+
+    /// Planar YUV 4:2:0 pixels, to be converted to RGB by the renderer.
+    Yuv420(YuvPlanes),
+}
+
+/// Statistics about the decoding of a single video stream.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct VideoStreamStats {
+    /// The number of frames decoded so far.
+    pub decoded_frames: u32,
+
+    /// The number of frames that were skipped over while decoding in order,
+    /// because playback moved on before they could be shown.
+    pub skipped_frames: u32,
+
+    /// The number of frames that could not be decoded.
+    pub failed_frames: u32,
+
+    /// The total time spent decoding frames.
+    pub decode_time: Duration,
+
+    /// The dimensions of the most recently decoded frame, if any.
+    pub dimensions: Option<(u16, u16)>,
+}
+
+impl VideoStreamStats {
+    /// Record the decoding of a frame.
+    ///
+    /// `skipped` is the number of frames between this one and the previously
+    /// decoded one that were never decoded.
+    pub fn record_frame(&mut self, skipped: u32, decode_time: Duration, dimensions: (u16, u16)) {
+        self.decoded_frames += 1;
+        self.skipped_frames += skipped;
+        self.decode_time += decode_time;
+        self.dimensions = Some(dimensions);
+    }
+
+    /// Record a frame that could not be decoded.
+    pub fn record_error(&mut self) {
+        self.failed_frames += 1;
+    }
+
+    /// The average time it took to decode a single frame.
+    pub fn average_decode_time(&self) -> Duration {
+        if self.decoded_frames == 0 {
+            Duration::ZERO
+        } else {
+            self.decode_time / self.decoded_frames
+        }
+    }
+}
+
+/// Count the frames that were skipped when decoding `frame_id` right after
+/// `last_frame_id`.
+fn frames_skipped(last_frame_id: Option<u32>, frame_id: u32) -> u32 {
+    match last_frame_id {
+        Some(last) if frame_id > last => frame_id - last - 1,
+        _ => 0,
+    }
 }
 
 /// What dependencies a given video frame has on any previous frames.
@@ -124,6 +236,27 @@ pub trait VideoBackend {
         encoded_frame: EncodedFrame<'_>,
         renderer: &mut dyn RenderBackend,
     ) -> Result<BitmapInfo, Error>;
+
+    /// Prepare a video stream to be decoded from an arbitrary frame.
+    ///
+    /// The nearest keyframe at or before `frame_id` is located using the
+    /// dependencies found while preloading, and the stream's decoder state is
+    /// reset. The ID of that keyframe is returned: every frame from it up to
+    /// and including `frame_id` must then be decoded, in order.
+    fn seek_video_stream(&mut self, stream: VideoStreamHandle, frame_id: u32)
+        -> Result<u32, Error>;
+
+    /// Get decoding statistics for a video stream.
+    ///
+    /// Unregistered streams, and backends that do not keep track of these,
+    /// report empty statistics.
+    fn video_stream_stats(&self, stream: VideoStreamHandle) -> VideoStreamStats;
+
+    /// Free a video stream and its decoder once it is no longer needed.
+    ///
+    /// The handle, and any bitmaps decoded from the stream, must not be used
+    /// afterwards.
+    fn free_video_stream(&mut self, stream: VideoStreamHandle);
 }
 
 pub struct NullVideoBackend {
@@ -179,4 +312,78 @@ impl VideoBackend for NullVideoBackend {
     ) -> Result<BitmapInfo, Error> {
         Err("Video decoding not implemented".into())
     }
+
+    fn seek_video_stream(
+        &mut self,
+        _stream: VideoStreamHandle,
+        frame_id: u32,
+    ) -> Result<u32, Error> {
+        Ok(frame_id)
+    }
+
+    fn video_stream_stats(&self, _stream: VideoStreamHandle) -> VideoStreamStats {
+        VideoStreamStats::default()
+    }
+
+    fn free_video_stream(&mut self, stream: VideoStreamHandle) {
+        self.streams.remove(stream);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgba_frame(rgba: Vec<u8>, alpha_mode: AlphaMode) -> DecodedFrame {
+        DecodedFrame {
+            width: (rgba.len() / 4) as u16,
+            height: 1,
+            pixels: FramePixels::Rgba(rgba),
+            alpha_mode,
+        }
+    }
+
+    fn rgba(frame: &DecodedFrame) -> &[u8] {
+        match &frame.pixels {
+            FramePixels::Rgba(rgba) => rgba,
+            FramePixels::Yuv420(_) => panic!("Expected an RGBA frame"),
+        }
+    }
+
+    #[test]
+    fn premultiplied_to_straight() {
+        let mut frame = rgba_frame(
+            vec![100, 50, 0, 200, 10, 20, 30, 255, 0, 0, 0, 0],
+            AlphaMode::Premultiplied,
+        );
+        frame.convert_alpha(AlphaMode::Straight);
+        assert_eq!(frame.alpha_mode, AlphaMode::Straight);
+        assert_eq!(
+            rgba(&frame),
+            &[127, 63, 0, 200, 10, 20, 30, 255, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn straight_to_premultiplied() {
+        let mut frame = rgba_frame(
+            vec![127, 64, 0, 200, 10, 20, 30, 255, 200, 100, 50, 0],
+            AlphaMode::Straight,
+        );
+        frame.convert_alpha(AlphaMode::Premultiplied);
+        assert_eq!(frame.alpha_mode, AlphaMode::Premultiplied);
+        assert_eq!(
+            rgba(&frame),
+            &[100, 50, 0, 200, 10, 20, 30, 255, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn opaque_frames_are_untouched() {
+        let pixels = vec![255, 127, 0, 128];
+        let mut frame = rgba_frame(pixels.clone(), AlphaMode::Opaque);
+        frame.convert_alpha(AlphaMode::Straight);
+        assert_eq!(frame.alpha_mode, AlphaMode::Opaque);
+        assert_eq!(rgba(&frame), &pixels[..]);
+    }
 }
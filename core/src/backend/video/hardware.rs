@@ -0,0 +1,220 @@
+//! Video backend that offloads decoding to platform decoders when possible.
+
+use crate::backend::render::{BitmapInfo, RenderBackend};
+use crate::backend::video::{
+    frames_skipped, EncodedFrame, Error, FrameDependency, SoftwareVideoBackend, VideoBackend,
+    VideoStreamHandle, VideoStreamStats,
+};
+use generational_arena::Arena;
+use instant::Instant;
+use swf::{VideoCodec, VideoDeblocking};
+
+/// A source of hardware (or otherwise platform-provided) video decoders.
+///
+/// Implementations typically wrap an OS decoding API such as VideoToolbox or
+/// DXVA, or a GPU-based decoder that cooperates with a specific
+/// `RenderBackend`.
+pub trait HardwareDecoderProvider {
+    /// Determine if this provider is able to decode the given codec at the
+    /// given size.
+    ///
+    /// Returning `false` here causes the stream to be decoded in software.
+    fn supports(&self, codec: VideoCodec, size: (u16, u16)) -> bool;
+
+    /// Create a new decoder for a video stream.
+    ///
+    /// This is only called for codecs that `supports` returned `true` for.
+    /// Returning an `Error` here also causes the stream to fall back to the
+    /// software decoder.
+    fn create_decoder(
+        &mut self,
+        codec: VideoCodec,
+        size: (u16, u16),
+        filter: VideoDeblocking,
+    ) -> Result<Box<dyn HardwareVideoDecoder>, Error>;
+}
+
+/// A single platform-provided decoder instance, bound to one video stream.
+pub trait HardwareVideoDecoder {
+    /// Preload a frame.
+    ///
+    /// This follows the same rules as
+    /// `VideoBackend::preload_video_stream_frame`.
+    fn preload_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<FrameDependency, Error>;
+
+    /// Decode a frame and make it available to the renderer.
+    ///
+    /// Unlike software decoders, hardware decoders are responsible for getting
+    /// the decoded image into the renderer themselves, so that they may upload
+    /// planar data directly instead of converting it to RGBA on the CPU.
+    fn decode_frame(
+        &mut self,
+        encoded_frame: EncodedFrame<'_>,
+        renderer: &mut dyn RenderBackend,
+    ) -> Result<BitmapInfo, Error>;
+
+    /// Reset the decoder to start decoding at the keyframe nearest to
+    /// `frame_id`, returning the ID of that keyframe.
+    ///
+    /// This follows the same rules as `VideoBackend::seek_video_stream`.
+    fn seek(&mut self, frame_id: u32) -> Result<u32, Error>;
+}
+
+/// A video stream, decoded either by a platform decoder or in software.
+enum HardwareVideoStream {
+    Hardware {
+        decoder: Box<dyn HardwareVideoDecoder>,
+        last_decoded: Option<u32>,
+        stats: VideoStreamStats,
+    },
+    Software(VideoStreamHandle),
+}
+
+/// Video backend that uses a `HardwareDecoderProvider` for every stream it
+/// can, and falls back to `SoftwareVideoBackend` for the rest.
+pub struct HardwareVideoBackend {
+    provider: Box<dyn HardwareDecoderProvider>,
+    software: SoftwareVideoBackend,
+    streams: Arena<HardwareVideoStream>,
+}
+
+impl HardwareVideoBackend {
+    pub fn new(provider: Box<dyn HardwareDecoderProvider>) -> Self {
+        Self {
+            provider,
+            software: SoftwareVideoBackend::new(),
+            streams: Arena::new(),
+        }
+    }
+}
+
+impl VideoBackend for HardwareVideoBackend {
+    fn register_video_stream(
+        &mut self,
+        num_frames: u32,
+        size: (u16, u16),
+        codec: VideoCodec,
+        filter: VideoDeblocking,
+    ) -> Result<VideoStreamHandle, Error> {
+        let stream = if self.provider.supports(codec, size) {
+            match self.provider.create_decoder(codec, size, filter) {
+                Ok(decoder) => Some(HardwareVideoStream::Hardware {
+                    decoder,
+                    last_decoded: None,
+                    stats: VideoStreamStats::default(),
+                }),
+                Err(e) => {
+                    log::warn!(
+                        "Unable to create hardware decoder for {:?}, falling back to software: {}",
+                        codec,
+                        e
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let stream = match stream {
+            Some(stream) => stream,
+            None => HardwareVideoStream::Software(
+                self.software
+                    .register_video_stream(num_frames, size, codec, filter)?,
+            ),
+        };
+
+        Ok(self.streams.insert(stream))
+    }
+
+    fn preload_video_stream_frame(
+        &mut self,
+        stream: VideoStreamHandle,
+        encoded_frame: EncodedFrame<'_>,
+    ) -> Result<FrameDependency, Error> {
+        match self
+            .streams
+            .get_mut(stream)
+            .ok_or("Unregistered video stream")?
+        {
+            HardwareVideoStream::Hardware { decoder, .. } => decoder.preload_frame(encoded_frame),
+            HardwareVideoStream::Software(stream) => self
+                .software
+                .preload_video_stream_frame(*stream, encoded_frame),
+        }
+    }
+
+    fn decode_video_stream_frame(
+        &mut self,
+        stream: VideoStreamHandle,
+        encoded_frame: EncodedFrame<'_>,
+        renderer: &mut dyn RenderBackend,
+    ) -> Result<BitmapInfo, Error> {
+        match self
+            .streams
+            .get_mut(stream)
+            .ok_or("Unregistered video stream")?
+        {
+            HardwareVideoStream::Hardware {
+                decoder,
+                last_decoded,
+                stats,
+            } => {
+                let frame_id = encoded_frame.frame_id;
+                let start = Instant::now();
+                let bitmap = decoder.decode_frame(encoded_frame, renderer)?;
+                stats.record_frame(
+                    frames_skipped(*last_decoded, frame_id),
+                    start.elapsed(),
+                    (bitmap.width, bitmap.height),
+                );
+                *last_decoded = Some(frame_id);
+                Ok(bitmap)
+            }
+            HardwareVideoStream::Software(stream) => {
+                self.software
+                    .decode_video_stream_frame(*stream, encoded_frame, renderer)
+            }
+        }
+    }
+
+    fn seek_video_stream(
+        &mut self,
+        stream: VideoStreamHandle,
+        frame_id: u32,
+    ) -> Result<u32, Error> {
+        match self
+            .streams
+            .get_mut(stream)
+            .ok_or("Unregistered video stream")?
+        {
+            HardwareVideoStream::Hardware {
+                decoder,
+                last_decoded,
+                ..
+            } => {
+                *last_decoded = None;
+                decoder.seek(frame_id)
+            }
+            HardwareVideoStream::Software(stream) => {
+                self.software.seek_video_stream(*stream, frame_id)
+            }
+        }
+    }
+
+    fn video_stream_stats(&self, stream: VideoStreamHandle) -> VideoStreamStats {
+        match self.streams.get(stream) {
+            Some(HardwareVideoStream::Hardware { stats, .. }) => *stats,
+            Some(HardwareVideoStream::Software(stream)) => {
+                self.software.video_stream_stats(*stream)
+            }
+            None => VideoStreamStats::default(),
+        }
+    }
+
+    fn free_video_stream(&mut self, stream: VideoStreamHandle) {
+        if let Some(HardwareVideoStream::Software(stream)) = self.streams.remove(stream) {
+            self.software.free_video_stream(stream);
+        }
+    }
+}
@@ -2,15 +2,49 @@
 
 use crate::backend::render::{BitmapHandle, BitmapInfo, RenderBackend};
 use crate::backend::video::{
-    DecodedFrame, EncodedFrame, Error, FrameDependency, VideoBackend, VideoStreamHandle,
+    frames_skipped, DecodedFrame, EncodedFrame, Error, FrameDependency, FramePixels, VideoBackend,
+    VideoStreamHandle, VideoStreamStats,
 };
 use generational_arena::Arena;
+use instant::Instant;
+use std::collections::BTreeMap;
+use std::sync::Arc;
 use swf::{VideoCodec, VideoDeblocking};
 
+#[cfg(any(feature = "h263", feature = "vp6"))]
+mod deblock;
+#[cfg(not(target_arch = "wasm32"))]
+mod decode_ahead;
+#[cfg(feature = "h264")]
+mod h264;
+mod jpeg;
+mod pool;
+mod screen;
+mod screen_v2;
+
+pub use pool::FramePool;
+
+/// A function that creates a decoder for a video stream, given the stream's
+/// size and deblocking filter.
+///
+/// Factories must be shareable across threads, as decoders may be created on
+/// a worker thread when decoding ahead.
+pub type VideoDecoderFactory =
+    Arc<dyn Fn((u16, u16), VideoDeblocking) -> Result<Box<dyn VideoDecoder>, Error> + Send + Sync>;
+
 /// Software video backend that proxies to CPU-only codec implementations that
 /// ship with Ruffle.
 pub struct SoftwareVideoBackend {
     streams: Arena<VideoStream>,
+
+    /// Decoders provided by the embedder, which take precedence over the
+    /// built-in ones.
+    external_decoders: Vec<(VideoCodec, VideoDecoderFactory)>,
+
+    /// How many frames to decode ahead of time on a worker thread, or zero to
+    /// decode every frame on demand.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    decode_ahead: usize,
 }
 
 impl Default for SoftwareVideoBackend {
@@ -23,29 +57,109 @@ impl SoftwareVideoBackend {
     pub fn new() -> Self {
         Self {
             streams: Arena::new(),
+            external_decoders: Vec::new(),
+            decode_ahead: 0,
+        }
+    }
+
+    /// Create a backend that decodes up to `frames` frames of each stream in
+    /// advance, on a worker thread per stream.
+    ///
+    /// Decoding ahead is not available on platforms without threads, where
+    /// this behaves like `new`.
+    pub fn with_decode_ahead(frames: usize) -> Self {
+        Self {
+            streams: Arena::new(),
+            external_decoders: Vec::new(),
+            decode_ahead: frames,
         }
     }
+
+    /// Register a decoder for a video codec.
+    ///
+    /// This replaces the built-in decoder for that codec (if there is one) for
+    /// all streams registered afterwards. Registering another decoder for the
+    /// same codec replaces the previous registration.
+    pub fn register_external_decoder(&mut self, codec: VideoCodec, factory: VideoDecoderFactory) {
+        self.external_decoders
+            .retain(|(existing, _)| *existing != codec);
+        self.external_decoders.push((codec, factory));
+    }
+
+    /// Get the factory used to create decoders for the given codec.
+    fn decoder_factory(&self, codec: VideoCodec) -> VideoDecoderFactory {
+        if let Some((_, factory)) = self
+            .external_decoders
+            .iter()
+            .find(|(existing, _)| *existing == codec)
+        {
+            return factory.clone();
+        }
+
+        Arc::new(move |size: (u16, u16), filter: VideoDeblocking| {
+            create_decoder(codec, size, filter)
+        })
+    }
+}
+
+/// Create a new built-in decoder for the given codec.
+#[allow(unreachable_code, unreachable_patterns, unused_variables)]
+fn create_decoder(
+    codec: VideoCodec,
+    size: (u16, u16),
+    filter: VideoDeblocking,
+) -> Result<Box<dyn VideoDecoder>, Error> {
+    let decoder: Box<dyn VideoDecoder> = match codec {
+        #[cfg(feature = "h263")]
+        VideoCodec::H263 => Box::new(h263::H263Decoder::new(filter)),
+        #[cfg(feature = "vp6")]
+        VideoCodec::Vp6 => Box::new(vp6::Vp6Decoder::new(false, size, filter)),
+        #[cfg(feature = "vp6")]
+        VideoCodec::Vp6WithAlpha => Box::new(vp6::Vp6Decoder::new(true, size, filter)),
+        #[cfg(feature = "h264")]
+        VideoCodec::H264 => Box::new(h264::H264Decoder::new()?),
+        VideoCodec::ScreenVideo => Box::new(jpeg::JpegFallbackDecoder::new(Box::new(
+            screen::ScreenVideoDecoder::new(),
+        ))),
+        VideoCodec::Jpeg => Box::new(jpeg::JpegDecoder::new()),
+        VideoCodec::ScreenVideoV2 => Box::new(screen_v2::ScreenVideoV2Decoder::new()),
+        _ => return Err(format!("Unsupported video codec type {:?}", codec).into()),
+    };
+    Ok(decoder)
 }
 
 impl VideoBackend for SoftwareVideoBackend {
-    #[allow(unreachable_code, unused_variables)]
     fn register_video_stream(
         &mut self,
         _num_frames: u32,
         size: (u16, u16),
         codec: VideoCodec,
-        _filter: VideoDeblocking,
+        filter: VideoDeblocking,
     ) -> Result<VideoStreamHandle, Error> {
-        let decoder: Box<dyn VideoDecoder> = match codec {
-            #[cfg(feature = "h263")]
-            VideoCodec::H263 => Box::new(h263::H263Decoder::new()),
-            #[cfg(feature = "vp6")]
-            VideoCodec::Vp6 => Box::new(vp6::Vp6Decoder::new(false, size)),
-            #[cfg(feature = "vp6")]
-            VideoCodec::Vp6WithAlpha => Box::new(vp6::Vp6Decoder::new(true, size)),
-            _ => return Err(format!("Unsupported video codec type {:?}", codec).into()),
+        let factory = self.decoder_factory(codec);
+        let decoder = factory(size, filter)?;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let decode_ahead = if self.decode_ahead > 0 {
+            Some(decode_ahead::DecodeAhead::new(
+                factory.clone(),
+                codec,
+                size,
+                filter,
+                self.decode_ahead,
+            )?)
+        } else {
+            None
         };
-        let stream = VideoStream::new(decoder);
+
+        #[allow(unused_mut)]
+        let mut stream = VideoStream::new(decoder);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            stream.decode_ahead = decode_ahead;
+        }
+
         let stream_handle = self.streams.insert(stream);
         Ok(stream_handle)
     }
@@ -60,7 +174,19 @@ impl VideoBackend for SoftwareVideoBackend {
             .get_mut(stream)
             .ok_or("Unregistered video stream")?;
 
-        stream.decoder.preload_frame(encoded_frame)
+        #[cfg(not(target_arch = "wasm32"))]
+        let data = encoded_frame.data;
+
+        let frame_id = encoded_frame.frame_id;
+        let dependency = stream.decoder.preload_frame(encoded_frame)?;
+        stream.frames.insert(frame_id, dependency);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(decode_ahead) = &mut stream.decode_ahead {
+            decode_ahead.preload(frame_id, data, dependency);
+        }
+
+        Ok(dependency)
     }
 
     fn decode_video_stream_frame(
@@ -74,19 +200,138 @@ impl VideoBackend for SoftwareVideoBackend {
             .get_mut(stream)
             .ok_or("Unregistered video stream")?;
 
-        let frame = stream.decoder.decode_frame(encoded_frame)?;
-        let handle = if let Some(bitmap) = stream.bitmap {
-            renderer.update_texture(bitmap, frame.width.into(), frame.height.into(), frame.rgba)?
+        let frame_id = encoded_frame.frame_id;
+
+        // After a frame failed to decode, the frames depending on it can't
+        // be decoded properly either, so the last good frame stays on screen
+        // until the next keyframe comes along.
+        if stream.resynchronizing {
+            let is_keyframe = stream
+                .frames
+                .get(&frame_id)
+                .map_or(false, |dependency| dependency.is_keyframe());
+            if !is_keyframe {
+                return stream
+                    .last_frame
+                    .ok_or_else(|| "Waiting for a keyframe after a decoding error".into());
+            }
+
+            stream.decoder.reset()?;
+            #[cfg(not(target_arch = "wasm32"))]
+            if let Some(decode_ahead) = &mut stream.decode_ahead {
+                decode_ahead.seek(frame_id)?;
+            }
+            stream.resynchronizing = false;
+        }
+
+        let start = Instant::now();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let result = if let Some(decode_ahead) = &mut stream.decode_ahead {
+            decode_ahead.decode(encoded_frame.frame_id)
         } else {
-            renderer.register_bitmap_raw(frame.width.into(), frame.height.into(), frame.rgba)?
+            stream.decoder.decode_frame(encoded_frame, &mut stream.pool)
+        };
+        #[cfg(target_arch = "wasm32")]
+        let result = stream.decoder.decode_frame(encoded_frame, &mut stream.pool);
+
+        let mut frame = match result {
+            Ok(frame) => frame,
+            Err(e) => {
+                stream.stats.record_error();
+                stream.resynchronizing = true;
+                log::warn!(
+                    "Could not decode video frame {}, skipping to the next keyframe: {}",
+                    frame_id,
+                    e
+                );
+                return stream.last_frame.ok_or(e);
+            }
+        };
+
+        stream.stats.record_frame(
+            frames_skipped(stream.last_decoded, frame_id),
+            start.elapsed(),
+            (frame.width, frame.height),
+        );
+        stream.last_decoded = Some(frame_id);
+
+        frame.convert_alpha(renderer.video_alpha_mode());
+
+        let (width, height) = (frame.width.into(), frame.height.into());
+        let handle = match (&frame.pixels, stream.bitmap) {
+            (FramePixels::Rgba(rgba), Some(bitmap)) => {
+                renderer.update_texture(bitmap, width, height, rgba)?
+            }
+            (FramePixels::Rgba(rgba), None) => {
+                renderer.register_bitmap_raw(width, height, rgba.clone())?
+            }
+            (FramePixels::Yuv420(planes), bitmap) => {
+                renderer.register_bitmap_yuv(bitmap, width, height, planes)?
+            }
         };
         stream.bitmap = Some(handle);
 
-        Ok(BitmapInfo {
+        let info = BitmapInfo {
             handle,
             width: frame.width,
             height: frame.height,
-        })
+        };
+        stream.last_frame = Some(info);
+
+        // The renderer has its own copy of the pixels now, so the buffers can
+        // be used for another frame.
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(decode_ahead) = &stream.decode_ahead {
+            decode_ahead.recycle(frame);
+            return Ok(info);
+        }
+        stream.pool.recycle(frame);
+
+        Ok(info)
+    }
+
+    fn seek_video_stream(
+        &mut self,
+        stream: VideoStreamHandle,
+        frame_id: u32,
+    ) -> Result<u32, Error> {
+        let stream = self
+            .streams
+            .get_mut(stream)
+            .ok_or("Unregistered video stream")?;
+
+        // Fall back to the very first frame if there is no earlier keyframe.
+        let keyframe = stream
+            .frames
+            .range(..=frame_id)
+            .rev()
+            .find(|(_, dependency)| dependency.is_keyframe())
+            .or_else(|| stream.frames.iter().next())
+            .map(|(id, _)| *id)
+            .ok_or("Cannot seek in a video stream with no preloaded frames")?;
+
+        stream.decoder.reset()?;
+        stream.last_decoded = None;
+        stream.resynchronizing = false;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(decode_ahead) = &mut stream.decode_ahead {
+            decode_ahead.seek(keyframe)?;
+        }
+
+        Ok(keyframe)
+    }
+
+    fn video_stream_stats(&self, stream: VideoStreamHandle) -> VideoStreamStats {
+        self.streams
+            .get(stream)
+            .map(|stream| stream.stats)
+            .unwrap_or_default()
+    }
+
+    fn free_video_stream(&mut self, stream: VideoStreamHandle) {
+        self.streams.remove(stream);
     }
 }
 
@@ -94,6 +339,29 @@ impl VideoBackend for SoftwareVideoBackend {
 struct VideoStream {
     bitmap: Option<BitmapHandle>,
     decoder: Box<dyn VideoDecoder>,
+
+    /// The dependencies of every preloaded frame, by frame ID.
+    frames: BTreeMap<u32, FrameDependency>,
+
+    /// The ID of the last decoded frame, since the last seek.
+    last_decoded: Option<u32>,
+
+    /// The last frame that was decoded successfully.
+    last_frame: Option<BitmapInfo>,
+
+    /// Whether a frame failed to decode, and no keyframe was decoded since.
+    resynchronizing: bool,
+
+    stats: VideoStreamStats,
+
+    /// Buffers of frames that have already been uploaded to the renderer.
+    pool: FramePool,
+
+    /// The worker thread decoding this stream, if decoding ahead is enabled.
+    ///
+    /// When present, `decoder` is only used for preloading.
+    #[cfg(not(target_arch = "wasm32"))]
+    decode_ahead: Option<decode_ahead::DecodeAhead>,
 }
 
 impl VideoStream {
@@ -101,13 +369,24 @@ impl VideoStream {
         Self {
             decoder,
             bitmap: None,
+            frames: BTreeMap::new(),
+            last_decoded: None,
+            last_frame: None,
+            resynchronizing: false,
+            stats: VideoStreamStats::default(),
+            pool: FramePool::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            decode_ahead: None,
         }
     }
 }
 
 /// Trait for video decoders.
 /// This should be implemented for each video codec.
-trait VideoDecoder {
+///
+/// Embedders may implement this for codecs that Ruffle does not support, and
+/// register them with `SoftwareVideoBackend::register_external_decoder`.
+pub trait VideoDecoder {
     /// Preload a frame.
     ///
     /// No decoding is intended to happen at this point in time. Instead, the
@@ -130,25 +409,56 @@ trait VideoDecoder {
     /// Frames may be decoded in any order that does not violate the frame
     /// dependencies declared by the output of `preload_video_stream_frame`.
     ///
+    /// Output buffers should be taken from `pool` where possible, so that
+    /// the buffers of earlier frames are reused.
+    ///
     /// The decoded frame should be returned. An `Error` can be returned if
     /// a drawable bitmap can not be produced.
-    fn decode_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<DecodedFrame, Error>;
+    fn decode_frame(
+        &mut self,
+        encoded_frame: EncodedFrame<'_>,
+        pool: &mut FramePool,
+    ) -> Result<DecodedFrame, Error>;
+
+    /// Prepare to decode from a keyframe again, after a seek or after a frame
+    /// failed to decode.
+    ///
+    /// Any state built up from previously decoded pictures should be thrown
+    /// away. Configuration that applies to the whole stream, such as H.264
+    /// parameter sets, must be kept, as the frames carrying it are not
+    /// decoded again.
+    ///
+    /// The default implementation does nothing, which is enough for decoders
+    /// whose keyframes never depend on earlier frames.
+    fn reset(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
 }
 
 #[cfg(feature = "h263")]
 mod h263 {
-    use crate::backend::video::software::VideoDecoder;
-    use crate::backend::video::{DecodedFrame, EncodedFrame, Error, FrameDependency};
+    use crate::backend::render::YuvPlanes;
+    use crate::backend::video::software::deblock::deblock;
+    use crate::backend::video::software::{FramePool, VideoDecoder};
+    use crate::backend::video::{
+        AlphaMode, ColorMatrix, DecodedFrame, EncodedFrame, Error, FrameDependency, FramePixels,
+    };
     use h263_rs::parser::H263Reader;
     use h263_rs::{DecoderOption, H263State, PictureTypeCode};
-    use h263_rs_yuv::bt601::yuv420_to_rgba;
+    use swf::VideoDeblocking;
 
     /// H263 video decoder.
-    pub struct H263Decoder(H263State);
+    pub struct H263Decoder {
+        state: H263State,
+        deblocking: VideoDeblocking,
+    }
 
     impl H263Decoder {
-        pub fn new() -> Self {
-            Self(H263State::new(DecoderOption::SORENSON_SPARK_BITSTREAM))
+        pub fn new(deblocking: VideoDeblocking) -> Self {
+            Self {
+                state: H263State::new(DecoderOption::SORENSON_SPARK_BITSTREAM),
+                deblocking,
+            }
         }
     }
 
@@ -159,7 +469,7 @@ mod h263 {
         ) -> Result<FrameDependency, Error> {
             let mut reader = H263Reader::from_source(encoded_frame.data());
             let picture = self
-                .0
+                .state
                 .parse_picture(&mut reader, None)?
                 .ok_or("Picture in video stream is not a picture")?;
 
@@ -171,13 +481,17 @@ mod h263 {
             }
         }
 
-        fn decode_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<DecodedFrame, Error> {
+        fn decode_frame(
+            &mut self,
+            encoded_frame: EncodedFrame<'_>,
+            pool: &mut FramePool,
+        ) -> Result<DecodedFrame, Error> {
             let mut reader = H263Reader::from_source(encoded_frame.data());
 
-            self.0.decode_next_picture(&mut reader)?;
+            self.state.decode_next_picture(&mut reader)?;
 
             let picture = self
-                .0
+                .state
                 .get_last_picture()
                 .expect("Decoding a picture should let us grab that picture");
 
@@ -187,39 +501,60 @@ mod h263 {
                 .ok_or("H.263 decoder error!")?;
             let chroma_width = picture.chroma_samples_per_row();
             let (y, b, r) = picture.as_yuv();
-            let rgba = yuv420_to_rgba(y, b, r, width.into(), chroma_width);
+            let mut planes = YuvPlanes {
+                y: pool.copy(y),
+                u: pool.copy(b),
+                v: pool.copy(r),
+                y_stride: width.into(),
+                chroma_stride: chroma_width,
+                // Sorenson Spark is limited to standard definition sizes.
+                color_matrix: ColorMatrix::Bt601,
+            };
+            deblock(&mut planes, self.deblocking);
+
             Ok(DecodedFrame {
                 width,
                 height,
-                rgba,
+                pixels: FramePixels::Yuv420(planes),
+                alpha_mode: AlphaMode::Opaque,
             })
         }
+
+        fn reset(&mut self) -> Result<(), Error> {
+            self.state = H263State::new(DecoderOption::SORENSON_SPARK_BITSTREAM);
+            Ok(())
+        }
     }
 
     impl Default for H263Decoder {
         fn default() -> Self {
-            Self::new()
+            Self::new(VideoDeblocking::UseVideoPacketValue)
         }
     }
 }
 
 #[cfg(feature = "vp6")]
 mod vp6 {
-    use crate::backend::video::software::VideoDecoder;
-    use crate::backend::video::{DecodedFrame, EncodedFrame, Error, FrameDependency};
-
-    use h263_rs_yuv::bt601::yuv420_to_rgba;
+    use crate::backend::render::YuvPlanes;
+    use crate::backend::video::software::deblock::deblock;
+    use crate::backend::video::software::{FramePool, VideoDecoder};
+    use crate::backend::video::yuv::yuv420_to_rgba;
+    use crate::backend::video::{
+        AlphaMode, ColorMatrix, DecodedFrame, EncodedFrame, Error, FrameDependency, FramePixels,
+    };
 
     use nihav_codec_support::codecs::{NABufferRef, NAVideoBuffer, NAVideoInfo};
     use nihav_codec_support::codecs::{NABufferType::Video, YUV420_FORMAT};
     use nihav_core::codecs::NADecoderSupport;
     use nihav_duck::codecs::vp6::{VP56Decoder, VP56Parser, VP6BR};
     use nihav_duck::codecs::vpcommon::{BoolCoder, VP_YUVA420_FORMAT};
+    use swf::VideoDeblocking;
 
     /// VP6 video decoder.
     pub struct Vp6Decoder {
         with_alpha: bool,
         bounds: (u16, u16),
+        deblocking: VideoDeblocking,
         decoder: VP56Decoder,
         support: NADecoderSupport,
         bitreader: VP6BR,
@@ -228,7 +563,7 @@ mod vp6 {
     }
 
     impl Vp6Decoder {
-        pub fn new(with_alpha: bool, bounds: (u16, u16)) -> Self {
+        pub fn new(with_alpha: bool, bounds: (u16, u16), deblocking: VideoDeblocking) -> Self {
             // Unfortunately, `init()` cannot be called on the decoder
             // just yet, because `bounds` is only the declared size of
             // the video, to which it will be cropped.
@@ -242,6 +577,7 @@ mod vp6 {
             Self {
                 with_alpha,
                 bounds,
+                deblocking,
                 decoder: VP56Decoder::new(6, with_alpha, true),
                 support: NADecoderSupport::new(),
                 bitreader: VP6BR::new(),
@@ -267,7 +603,11 @@ mod vp6 {
             )
         }
 
-        fn decode_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<DecodedFrame, Error> {
+        fn decode_frame(
+            &mut self,
+            encoded_frame: EncodedFrame<'_>,
+            pool: &mut FramePool,
+        ) -> Result<DecodedFrame, Error> {
             // If this is the first frame, the decoder needs to be initialized.
 
             if !self.init_called {
@@ -341,11 +681,9 @@ mod vp6 {
                 frame
             };
 
-            // Converting it from YUV420 to RGBA.
-
             let yuv = frame.get_data();
 
-            let (mut width, mut height) = frame.get_dimensions(0);
+            let (width, height) = frame.get_dimensions(0);
             let (chroma_width, chroma_height) = frame.get_dimensions(1);
 
             // We assume that there is no padding between rows
@@ -360,33 +698,15 @@ mod vp6 {
                 frame.get_offset(2),
             );
 
-            let mut rgba = yuv420_to_rgba(
-                &yuv[offsets.0..offsets.0 + width * height],
-                &yuv[offsets.1..offsets.1 + chroma_width * chroma_height],
-                &yuv[offsets.2..offsets.2 + chroma_width * chroma_height],
-                width,
-                chroma_width,
-            );
-
-            // Adding in the alpha component, if present.
-
-            if self.with_alpha {
-                debug_assert!(frame.get_stride(3) == frame.get_dimensions(3).0);
-                let alpha_offset = frame.get_offset(3);
-                let alpha = &yuv[alpha_offset..alpha_offset + width * height];
-                for (alpha, rgba) in alpha.iter().zip(rgba.chunks_mut(4)) {
-                    // The SWF spec mandates the `min` to avoid any accidental "invalid"
-                    // premultiplied colors, which would cause strange results after blending.
-                    // And the alpha data is encoded in full range (0-255), unlike the Y
-                    // component of the main color data, so no remapping is needed.
-                    rgba.copy_from_slice(&[
-                        u8::min(rgba[0], *alpha),
-                        u8::min(rgba[1], *alpha),
-                        u8::min(rgba[2], *alpha),
-                        *alpha,
-                    ]);
-                }
-            }
+            let mut planes = YuvPlanes {
+                y: pool.copy(&yuv[offsets.0..offsets.0 + width * height]),
+                u: pool.copy(&yuv[offsets.1..offsets.1 + chroma_width * chroma_height]),
+                v: pool.copy(&yuv[offsets.2..offsets.2 + chroma_width * chroma_height]),
+                y_stride: width,
+                chroma_stride: chroma_width,
+                color_matrix: ColorMatrix::for_size(width, height),
+            };
+            deblock(&mut planes, self.deblocking);
 
             // Cropping the encoded frame (containing whole macroblocks) to the
             // size requested by the the bounds attribute.
@@ -398,12 +718,52 @@ mod vp6 {
                 // Flash Player just produces a black image in this case!
             }
 
-            if width > bounds.0 as usize {
-                // Removing the unwanted pixels on the right edge (most commonly: unused pieces of macroblocks)
-                // by squishing all the rows tightly next to each other.
+            let new_width = usize::min(width, bounds.0 as usize);
+            let new_height = usize::min(height, bounds.1 as usize);
+
+            if !self.with_alpha {
+                // Without alpha, the planes can be handed over as they are,
+                // with the unwanted pixels on the right edge (most commonly:
+                // unused pieces of macroblocks) skipped over by the stride.
+                return Ok(DecodedFrame {
+                    width: new_width as u16,
+                    height: new_height as u16,
+                    pixels: FramePixels::Yuv420(planes),
+                    alpha_mode: AlphaMode::Opaque,
+                });
+            }
+
+            // Converting it from YUV420 to RGBA, and adding in the alpha component.
+
+            let mut rgba = yuv420_to_rgba(
+                &planes.y,
+                &planes.u,
+                &planes.v,
+                width,
+                chroma_width,
+                planes.color_matrix,
+            );
+
+            debug_assert!(frame.get_stride(3) == frame.get_dimensions(3).0);
+            let alpha_offset = frame.get_offset(3);
+            let alpha = &yuv[alpha_offset..alpha_offset + width * height];
+            for (alpha, rgba) in alpha.iter().zip(rgba.chunks_mut(4)) {
+                // The SWF spec mandates the `min` to avoid any accidental "invalid"
+                // premultiplied colors, which would cause strange results after blending.
+                // And the alpha data is encoded in full range (0-255), unlike the Y
+                // component of the main color data, so no remapping is needed.
+                rgba.copy_from_slice(&[
+                    u8::min(rgba[0], *alpha),
+                    u8::min(rgba[1], *alpha),
+                    u8::min(rgba[2], *alpha),
+                    *alpha,
+                ]);
+            }
+
+            if width > new_width {
+                // Removing the unwanted pixels on the right edge by squishing
+                // all the rows tightly next to each other.
                 // Bitmap at the moment does not allow these gaps, so we need to remove them.
-                let new_width = bounds.0 as usize;
-                let new_height = usize::min(height, bounds.1 as usize);
                 // no need to move the first row, nor any rows on the bottom that will end up being cropped entirely
                 for row in 1..new_height {
                     rgba.copy_within(
@@ -411,25 +771,139 @@ mod vp6 {
                         row * new_width * 4,
                     );
                 }
-                width = new_width;
-                height = new_height;
             }
 
             // Cropping the unwanted rows on the bottom, also dropping any unused space at the end left by the squish above
-            height = usize::min(height, bounds.1 as usize);
-            rgba.truncate(width * height * 4);
+            rgba.truncate(new_width * new_height * 4);
 
             Ok(DecodedFrame {
-                width: width as u16,
-                height: height as u16,
-                rgba,
+                width: new_width as u16,
+                height: new_height as u16,
+                pixels: FramePixels::Rgba(rgba),
+                alpha_mode: AlphaMode::Premultiplied,
             })
         }
+
+        fn reset(&mut self) -> Result<(), Error> {
+            *self = Self::new(self.with_alpha, self.bounds, self.deblocking);
+            Ok(())
+        }
     }
 
     impl Default for Vp6Decoder {
         fn default() -> Self {
-            Self::new(false, (0, 0))
+            Self::new(false, (0, 0), VideoDeblocking::UseVideoPacketValue)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::render::NullRenderer;
+    use crate::backend::video::AlphaMode;
+
+    /// A decoder for made-up frames of a keyframe flag and a frame width,
+    /// where a width of zero marks a corrupt frame.
+    struct TestDecoder;
+
+    impl VideoDecoder for TestDecoder {
+        fn preload_frame(
+            &mut self,
+            encoded_frame: EncodedFrame<'_>,
+        ) -> Result<FrameDependency, Error> {
+            Ok(match encoded_frame.data()[0] {
+                1 => FrameDependency::None,
+                _ => FrameDependency::Past,
+            })
         }
+
+        fn decode_frame(
+            &mut self,
+            encoded_frame: EncodedFrame<'_>,
+            pool: &mut FramePool,
+        ) -> Result<DecodedFrame, Error> {
+            let width = u16::from(encoded_frame.data()[1]);
+            if width == 0 {
+                return Err("Corrupt frame".into());
+            }
+
+            Ok(DecodedFrame {
+                width,
+                height: 1,
+                pixels: FramePixels::Rgba(pool.take(usize::from(width) * 4)),
+                alpha_mode: AlphaMode::Opaque,
+            })
+        }
+    }
+
+    #[test]
+    fn resynchronize_after_error() {
+        let mut backend = SoftwareVideoBackend::new();
+        backend.register_external_decoder(
+            VideoCodec::H263,
+            Arc::new(|_, _| Ok(Box::new(TestDecoder) as Box<dyn VideoDecoder>)),
+        );
+        let mut renderer = NullRenderer::new();
+
+        let stream = backend
+            .register_video_stream(5, (1, 1), VideoCodec::H263, VideoDeblocking::None)
+            .unwrap();
+        let frames: [&[u8]; 5] = [&[1, 1], &[0, 2], &[0, 0], &[0, 4], &[1, 5]];
+        for (frame_id, data) in frames.iter().enumerate() {
+            let frame = EncodedFrame {
+                codec: VideoCodec::H263,
+                data,
+                frame_id: frame_id as u32,
+            };
+            backend.preload_video_stream_frame(stream, frame).unwrap();
+        }
+
+        let shown_widths: Vec<_> = frames
+            .iter()
+            .enumerate()
+            .map(|(frame_id, data)| {
+                let frame = EncodedFrame {
+                    codec: VideoCodec::H263,
+                    data,
+                    frame_id: frame_id as u32,
+                };
+                backend
+                    .decode_video_stream_frame(stream, frame, &mut renderer)
+                    .unwrap()
+                    .width
+            })
+            .collect();
+
+        // The corrupt frame and the one depending on it show the last good
+        // frame, until the keyframe after them.
+        assert_eq!(shown_widths, [1, 2, 2, 2, 5]);
+        assert_eq!(backend.video_stream_stats(stream).failed_frames, 1);
+    }
+
+    #[test]
+    fn free_stream() {
+        let mut backend = SoftwareVideoBackend::new();
+        backend.register_external_decoder(
+            VideoCodec::H263,
+            Arc::new(|_, _| Ok(Box::new(TestDecoder) as Box<dyn VideoDecoder>)),
+        );
+        let mut renderer = NullRenderer::new();
+
+        let stream = backend
+            .register_video_stream(1, (1, 1), VideoCodec::H263, VideoDeblocking::None)
+            .unwrap();
+        let frame = || EncodedFrame {
+            codec: VideoCodec::H263,
+            data: &[1, 1],
+            frame_id: 0,
+        };
+        backend.preload_video_stream_frame(stream, frame()).unwrap();
+        backend.free_video_stream(stream);
+
+        assert!(backend.streams.is_empty());
+        assert!(backend
+            .decode_video_stream_frame(stream, frame(), &mut renderer)
+            .is_err());
     }
 }
@@ -0,0 +1,163 @@
+//! Post-processing deblocking filter for block-based video codecs.
+//!
+//! Both Sorenson H.263 and VP6 code pictures as 8x8 blocks, which become
+//! visible at low bitrates. Flash Player hides these edges with a smoothing
+//! pass whose strength is selected by the `VideoDeblocking` setting of the
+//! video stream. This filter only touches small steps across block edges,
+//! so that actual edges in the picture are preserved.
+
+use crate::backend::render::YuvPlanes;
+use swf::VideoDeblocking;
+
+/// The size of a coded block, in samples of each plane.
+const BLOCK_SIZE: usize = 8;
+
+/// The parameters of the filter at a particular strength.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Strength {
+    /// Steps across an edge larger than this are considered part of the
+    /// picture, and are left alone.
+    threshold: i16,
+
+    /// The maximum amount a sample is adjusted by.
+    clamp: i16,
+
+    /// Whether the second sample on each side of the edge is filtered too.
+    wide: bool,
+}
+
+impl Strength {
+    /// Get the filter strength for a deblocking setting, or `None` if no
+    /// filtering should happen.
+    ///
+    /// `UseVideoPacketValue` defers to the bitstream, which already applies
+    /// any in-loop filtering it asks for, so no post-processing is done.
+    fn from_deblocking(deblocking: VideoDeblocking) -> Option<Self> {
+        let (threshold, clamp, wide) = match deblocking {
+            VideoDeblocking::UseVideoPacketValue | VideoDeblocking::None => return None,
+            VideoDeblocking::Level1 => (6, 2, false),
+            VideoDeblocking::Level2 => (10, 3, false),
+            VideoDeblocking::Level3 => (14, 4, true),
+            VideoDeblocking::Level4 => (20, 6, true),
+        };
+        Some(Self {
+            threshold,
+            clamp,
+            wide,
+        })
+    }
+}
+
+/// Filter the block edges of every plane of a decoded picture in place.
+pub fn deblock(planes: &mut YuvPlanes, deblocking: VideoDeblocking) {
+    let strength = match Strength::from_deblocking(deblocking) {
+        Some(strength) => strength,
+        None => return,
+    };
+
+    let y_stride = planes.y_stride;
+    let chroma_stride = planes.chroma_stride;
+    deblock_plane(&mut planes.y, y_stride, strength);
+    deblock_plane(&mut planes.u, chroma_stride, strength);
+    deblock_plane(&mut planes.v, chroma_stride, strength);
+}
+
+/// Filter the block edges of a single plane.
+fn deblock_plane(plane: &mut [u8], stride: usize, strength: Strength) {
+    if stride == 0 {
+        return;
+    }
+    let height = plane.len() / stride;
+
+    // Vertical edges, between horizontally adjacent blocks.
+    for row in 0..height {
+        for x in (BLOCK_SIZE..stride.saturating_sub(1)).step_by(BLOCK_SIZE) {
+            let start = row * stride + x;
+            filter_edge(plane, start, 1, strength);
+        }
+    }
+
+    // Horizontal edges, between vertically adjacent blocks.
+    for y in (BLOCK_SIZE..height).step_by(BLOCK_SIZE) {
+        for x in 0..stride {
+            let start = y * stride + x;
+            filter_edge(plane, start, stride, strength);
+        }
+    }
+}
+
+/// Filter the samples around one edge.
+///
+/// `q0` is the first sample past the edge, and `step` is the distance
+/// between two samples perpendicular to the edge.
+fn filter_edge(plane: &mut [u8], q0: usize, step: usize, strength: Strength) {
+    let p0 = q0 - step;
+    let (p1, q1) = (p0 - step, q0 + step);
+    if q1 >= plane.len() {
+        return;
+    }
+
+    let sample = |index: usize| i16::from(plane[index]);
+    let (p1_value, p0_value) = (sample(p1), sample(p0));
+    let (q0_value, q1_value) = (sample(q0), sample(q1));
+
+    if (q0_value - p0_value).abs() >= strength.threshold
+        || (p1_value - p0_value).abs() >= strength.threshold
+        || (q1_value - q0_value).abs() >= strength.threshold
+    {
+        return;
+    }
+
+    let delta = ((4 * (q0_value - p0_value) + (p1_value - q1_value) + 4) >> 3)
+        .clamp(-strength.clamp, strength.clamp);
+    plane[p0] = (p0_value + delta).clamp(0, 255) as u8;
+    plane[q0] = (q0_value - delta).clamp(0, 255) as u8;
+
+    if strength.wide {
+        let half = delta / 2;
+        plane[p1] = (p1_value + half).clamp(0, 255) as u8;
+        plane[q1] = (q1_value - half).clamp(0, 255) as u8;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::video::ColorMatrix;
+
+    fn planes(y: Vec<u8>, stride: usize) -> YuvPlanes {
+        YuvPlanes {
+            y,
+            u: vec![128; 64],
+            v: vec![128; 64],
+            y_stride: stride,
+            chroma_stride: 8,
+            color_matrix: ColorMatrix::Bt601,
+        }
+    }
+
+    #[test]
+    fn smooths_small_steps() {
+        // Two blocks side by side, differing slightly.
+        let row: Vec<u8> = [[100; 8], [104; 8]].concat();
+        let mut image = planes(row.repeat(8), 16);
+        deblock(&mut image, VideoDeblocking::Level2);
+        assert_eq!(&image.y[6..10], &[100, 102, 102, 104]);
+    }
+
+    #[test]
+    fn preserves_real_edges() {
+        let row: Vec<u8> = [[0; 8], [255; 8]].concat();
+        let mut image = planes(row.repeat(8), 16);
+        deblock(&mut image, VideoDeblocking::Level4);
+        assert_eq!(image.y, row.repeat(8));
+    }
+
+    #[test]
+    fn disabled_filter_does_nothing() {
+        let row: Vec<u8> = [[100; 8], [104; 8]].concat();
+        let mut image = planes(row.repeat(8), 16);
+        deblock(&mut image, VideoDeblocking::None);
+        assert_eq!(image.y, row.repeat(8));
+    }
+}
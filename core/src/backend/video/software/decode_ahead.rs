@@ -0,0 +1,273 @@
+//! Background decoding of upcoming video frames.
+//!
+//! Each stream with decode-ahead enabled gets its own worker thread, which
+//! owns a separate decoder instance and decodes frames in order, a fixed
+//! number of frames ahead of the last frame requested by the player.
+
+use crate::backend::video::software::{FramePool, VideoDecoderFactory};
+use crate::backend::video::{DecodedFrame, EncodedFrame, Error, FrameDependency};
+use std::collections::VecDeque;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use swf::{VideoCodec, VideoDeblocking};
+
+/// A frame that has been preloaded, and can be sent to the worker.
+struct PreloadedFrame {
+    frame_id: u32,
+    data: Arc<[u8]>,
+    dependency: FrameDependency,
+}
+
+/// A message sent to the worker thread.
+enum Command {
+    /// Throw away the current decoder state, as the following frames start
+    /// from a keyframe.
+    Reset { generation: u32 },
+
+    /// Decode the next frame in the stream.
+    Decode {
+        generation: u32,
+        index: usize,
+        frame_id: u32,
+        data: Arc<[u8]>,
+    },
+
+    /// Return the buffers of a frame that has been uploaded to the renderer.
+    Recycle(DecodedFrame),
+}
+
+/// A message sent back from the worker thread.
+struct DecodeResult {
+    generation: u32,
+    index: usize,
+    frame: Result<DecodedFrame, String>,
+}
+
+/// A decode-ahead pipeline for a single video stream.
+pub struct DecodeAhead {
+    commands: Sender<Command>,
+    results: Receiver<DecodeResult>,
+
+    /// All preloaded frames, in the order they were preloaded.
+    frames: Vec<PreloadedFrame>,
+
+    /// How many frames past the last requested one to keep decoding.
+    depth: usize,
+
+    /// Incremented every time the worker is reset, so that stale results
+    /// can be recognized and dropped.
+    generation: u32,
+
+    /// The index of the next frame to be sent to the worker.
+    next_submit: usize,
+
+    /// The index of the last frame that was handed out.
+    last_returned: Option<usize>,
+
+    /// Decoded frames that have been received, but not yet requested.
+    ready: VecDeque<(usize, DecodedFrame)>,
+}
+
+impl DecodeAhead {
+    pub fn new(
+        factory: VideoDecoderFactory,
+        codec: VideoCodec,
+        size: (u16, u16),
+        filter: VideoDeblocking,
+        depth: usize,
+    ) -> Result<Self, Error> {
+        let (commands, worker_commands) = channel();
+        let (worker_results, results) = channel();
+
+        thread::Builder::new()
+            .name("ruffle video decoder".to_string())
+            .spawn(move || {
+                worker(
+                    factory,
+                    codec,
+                    size,
+                    filter,
+                    worker_commands,
+                    worker_results,
+                )
+            })?;
+
+        Ok(Self {
+            commands,
+            results,
+            frames: Vec::new(),
+            depth: depth.max(1),
+            generation: 0,
+            next_submit: 0,
+            last_returned: None,
+            ready: VecDeque::new(),
+        })
+    }
+
+    /// Record a preloaded frame, so that it can be decoded ahead of time.
+    pub fn preload(&mut self, frame_id: u32, data: &[u8], dependency: FrameDependency) {
+        self.frames.push(PreloadedFrame {
+            frame_id,
+            data: data.into(),
+            dependency,
+        });
+    }
+
+    /// Get a decoded frame, waiting for the worker if it isn't ready yet.
+    pub fn decode(&mut self, frame_id: u32) -> Result<DecodedFrame, Error> {
+        let index = self
+            .frames
+            .iter()
+            .position(|frame| frame.frame_id == frame_id)
+            .ok_or("Video frame was not preloaded")?;
+
+        // Frames are decoded strictly in order, so going backwards (or
+        // skipping past a keyframe) means starting over from a keyframe.
+        let keyframe = self.frames[..=index]
+            .iter()
+            .rposition(|frame| frame.dependency.is_keyframe())
+            .unwrap_or(0);
+        let going_backwards = self.last_returned.map_or(false, |last| index <= last);
+        if going_backwards || keyframe > self.next_submit {
+            self.reset(keyframe)?;
+        }
+
+        self.ready.retain(|(ready_index, _)| *ready_index >= index);
+        self.submit_until((index + self.depth).min(self.frames.len()))?;
+
+        let frame = loop {
+            if let Some((ready_index, _)) = self.ready.front() {
+                if *ready_index == index {
+                    break self.ready.pop_front().unwrap().1;
+                }
+            }
+
+            let result = self
+                .results
+                .recv()
+                .map_err(|_| "Video decoder thread has stopped")?;
+            if result.generation != self.generation || result.index < index {
+                continue;
+            }
+
+            let frame = result.frame.map_err(Error::from)?;
+            self.ready.push_back((result.index, frame));
+        };
+
+        self.last_returned = Some(index);
+
+        Ok(frame)
+    }
+
+    /// Restart decoding from the given keyframe.
+    pub fn seek(&mut self, frame_id: u32) -> Result<(), Error> {
+        let index = self
+            .frames
+            .iter()
+            .position(|frame| frame.frame_id == frame_id)
+            .ok_or("Video frame was not preloaded")?;
+        self.reset(index)
+    }
+
+    /// Hand the buffers of a frame back to the worker, to be reused.
+    pub fn recycle(&self, frame: DecodedFrame) {
+        // If the worker has stopped, the next `decode` reports it.
+        let _ = self.send(Command::Recycle(frame));
+    }
+
+    /// Restart decoding from the given frame index.
+    fn reset(&mut self, index: usize) -> Result<(), Error> {
+        self.generation = self.generation.wrapping_add(1);
+        self.next_submit = index;
+        self.last_returned = None;
+        self.ready.clear();
+        self.send(Command::Reset {
+            generation: self.generation,
+        })
+    }
+
+    /// Send every frame up to (but not including) `end` to the worker.
+    fn submit_until(&mut self, end: usize) -> Result<(), Error> {
+        while self.next_submit < end {
+            let frame = &self.frames[self.next_submit];
+            let command = Command::Decode {
+                generation: self.generation,
+                index: self.next_submit,
+                frame_id: frame.frame_id,
+                data: frame.data.clone(),
+            };
+            self.send(command)?;
+            self.next_submit += 1;
+        }
+
+        Ok(())
+    }
+
+    fn send(&self, command: Command) -> Result<(), Error> {
+        self.commands
+            .send(command)
+            .map_err(|_| "Video decoder thread has stopped".into())
+    }
+}
+
+/// The body of a decoder thread.
+///
+/// The decoder is created on this thread and never leaves it, so decoders
+/// are not required to be `Send`. The thread exits once the owning
+/// `DecodeAhead` is dropped.
+fn worker(
+    factory: VideoDecoderFactory,
+    codec: VideoCodec,
+    size: (u16, u16),
+    filter: VideoDeblocking,
+    commands: Receiver<Command>,
+    results: Sender<DecodeResult>,
+) {
+    let mut decoder = match factory(size, filter) {
+        Ok(decoder) => decoder,
+        Err(e) => {
+            log::error!("Unable to create video decoder on worker thread: {}", e);
+            return;
+        }
+    };
+
+    let mut pool = FramePool::new();
+
+    while let Ok(command) = commands.recv() {
+        match command {
+            Command::Reset { .. } => {
+                if let Err(e) = decoder.reset() {
+                    log::error!("Unable to reset video decoder on worker thread: {}", e);
+                    return;
+                }
+            }
+            Command::Decode {
+                generation,
+                index,
+                frame_id,
+                data,
+            } => {
+                let frame = decoder
+                    .decode_frame(
+                        EncodedFrame {
+                            codec,
+                            data: &data,
+                            frame_id,
+                        },
+                        &mut pool,
+                    )
+                    .map_err(|e| e.to_string());
+                let result = DecodeResult {
+                    generation,
+                    index,
+                    frame,
+                };
+                if results.send(result).is_err() {
+                    return;
+                }
+            }
+            Command::Recycle(frame) => pool.recycle(frame),
+        }
+    }
+}
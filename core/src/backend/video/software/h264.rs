@@ -0,0 +1,422 @@
+//! H.264 (AVC) video decoder.
+//!
+//! Frames are expected as FLV `AVCVIDEOPACKET`s: a packet type byte and a
+//! composition time offset, followed by either an `AVCDecoderConfigurationRecord`
+//! or a series of length-prefixed NAL units. These are converted into an
+//! Annex B byte stream for OpenH264.
+
+use crate::backend::render::YuvPlanes;
+use crate::backend::video::software::{FramePool, VideoDecoder};
+use crate::backend::video::{
+    AlphaMode, ColorMatrix, DecodedFrame, EncodedFrame, Error, FrameDependency, FramePixels,
+};
+use openh264::decoder::Decoder;
+
+/// The start code prefixed to every NAL unit in an Annex B stream.
+const START_CODE: [u8; 4] = [0, 0, 0, 1];
+
+/// The NAL unit type of a slice of an IDR (keyframe) picture.
+const NAL_TYPE_IDR: u8 = 5;
+
+/// The type of an `AVCVIDEOPACKET`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum PacketType {
+    SequenceHeader,
+    Nalu,
+    EndOfSequence,
+}
+
+/// Split an `AVCVIDEOPACKET` into its type and payload.
+fn parse_packet(data: &[u8]) -> Result<(PacketType, &[u8]), Error> {
+    if data.len() < 4 {
+        return Err("H.264 video packet is too short".into());
+    }
+
+    // The composition time in bytes 1-3 is only relevant for B-frames, which
+    // OpenH264 reorders itself.
+    let packet_type = match data[0] {
+        0 => PacketType::SequenceHeader,
+        1 => PacketType::Nalu,
+        2 => PacketType::EndOfSequence,
+        other => return Err(format!("Invalid H.264 packet type {}", other).into()),
+    };
+
+    Ok((packet_type, &data[4..]))
+}
+
+/// The parts of an `AVCDecoderConfigurationRecord` needed for decoding.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct DecoderConfiguration {
+    /// The size of the length prefix of each NAL unit, in bytes.
+    nal_length_size: usize,
+
+    /// The sequence and picture parameter sets, as an Annex B stream.
+    parameter_sets: Vec<u8>,
+}
+
+impl DecoderConfiguration {
+    fn parse(data: &[u8]) -> Result<Self, Error> {
+        let truncated = || Error::from("H.264 decoder configuration is truncated");
+
+        if data.len() < 6 {
+            return Err(truncated());
+        }
+        if data[0] != 1 {
+            return Err(format!("Unsupported H.264 configuration version {}", data[0]).into());
+        }
+
+        let nal_length_size = usize::from(data[4] & 0b11) + 1;
+        let mut parameter_sets = Vec::new();
+        let mut position = 5;
+
+        // Sequence parameter sets, then picture parameter sets.
+        for count_mask in [0b1_1111, 0b1111_1111] {
+            let count = data.get(position).ok_or_else(truncated)? & count_mask;
+            position += 1;
+            for _ in 0..count {
+                let length = data.get(position..position + 2).ok_or_else(truncated)?;
+                let length = usize::from(u16::from_be_bytes([length[0], length[1]]));
+                position += 2;
+                let set = data
+                    .get(position..position + length)
+                    .ok_or_else(truncated)?;
+                parameter_sets.extend_from_slice(&START_CODE);
+                parameter_sets.extend_from_slice(set);
+                position += length;
+            }
+        }
+
+        Ok(Self {
+            nal_length_size,
+            parameter_sets,
+        })
+    }
+
+    /// Iterate over the length-prefixed NAL units in `data`.
+    fn nal_units<'a>(&self, mut data: &'a [u8]) -> impl Iterator<Item = &'a [u8]> {
+        let length_size = self.nal_length_size;
+        std::iter::from_fn(move || {
+            if data.len() < length_size {
+                return None;
+            }
+            let (length, rest) = data.split_at(length_size);
+            let length = length
+                .iter()
+                .fold(0usize, |acc, byte| (acc << 8) | usize::from(*byte));
+            if rest.len() < length {
+                log::warn!("H.264 NAL unit is truncated");
+                return None;
+            }
+            let (unit, rest) = rest.split_at(length);
+            data = rest;
+            Some(unit)
+        })
+    }
+}
+
+/// H.264 video decoder.
+pub struct H264Decoder {
+    decoder: Decoder,
+
+    /// The configuration from the last sequence header, if one was seen.
+    configuration: Option<DecoderConfiguration>,
+
+    /// Whether the parameter sets of the current configuration still need
+    /// to be passed to the decoder.
+    needs_parameter_sets: bool,
+
+    /// The last decoded picture, returned for packets that contain none.
+    last_frame: Option<DecodedFrame>,
+}
+
+impl H264Decoder {
+    pub fn new() -> Result<Self, Error> {
+        Ok(Self {
+            decoder: Self::new_decoder()?,
+            configuration: None,
+            needs_parameter_sets: false,
+            last_frame: None,
+        })
+    }
+
+    fn new_decoder() -> Result<Decoder, Error> {
+        Decoder::new().map_err(|e| format!("Unable to create H.264 decoder: {:?}", e).into())
+    }
+}
+
+impl VideoDecoder for H264Decoder {
+    fn preload_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<FrameDependency, Error> {
+        let (packet_type, payload) = parse_packet(encoded_frame.data())?;
+
+        match packet_type {
+            PacketType::SequenceHeader => {
+                self.configuration = Some(DecoderConfiguration::parse(payload)?);
+                Ok(FrameDependency::None)
+            }
+            PacketType::EndOfSequence => Ok(FrameDependency::None),
+            PacketType::Nalu => {
+                let configuration = self
+                    .configuration
+                    .as_ref()
+                    .ok_or("H.264 video data precedes its sequence header")?;
+                let is_keyframe = configuration
+                    .nal_units(payload)
+                    .any(|unit| unit.first().map(|header| header & 0b1_1111) == Some(NAL_TYPE_IDR));
+                Ok(if is_keyframe {
+                    FrameDependency::None
+                } else {
+                    FrameDependency::Past
+                })
+            }
+        }
+    }
+
+    fn decode_frame(
+        &mut self,
+        encoded_frame: EncodedFrame<'_>,
+        pool: &mut FramePool,
+    ) -> Result<DecodedFrame, Error> {
+        let (packet_type, payload) = parse_packet(encoded_frame.data())?;
+
+        let configuration = match packet_type {
+            PacketType::SequenceHeader => {
+                self.configuration = Some(DecoderConfiguration::parse(payload)?);
+                self.needs_parameter_sets = true;
+                None
+            }
+            PacketType::EndOfSequence => None,
+            PacketType::Nalu => self.configuration.as_ref(),
+        };
+
+        let configuration = match configuration {
+            Some(configuration) => configuration,
+            None => {
+                // This packet has no picture of its own.
+                return self
+                    .last_frame
+                    .as_ref()
+                    .map(|frame| pool.copy_frame(frame))
+                    .ok_or_else(|| "H.264 packet contains no picture".into());
+            }
+        };
+
+        let mut stream = Vec::with_capacity(payload.len() + 64);
+        if self.needs_parameter_sets {
+            stream.extend_from_slice(&configuration.parameter_sets);
+        }
+        for unit in configuration.nal_units(payload) {
+            stream.extend_from_slice(&START_CODE);
+            stream.extend_from_slice(unit);
+        }
+
+        let decoded = self
+            .decoder
+            .decode(&stream)
+            .map_err(|e| format!("H.264 decoder error: {:?}", e))?;
+        self.needs_parameter_sets = false;
+
+        let picture = match decoded {
+            Some(picture) => picture,
+            None => {
+                // The decoder is still buffering pictures.
+                return self
+                    .last_frame
+                    .as_ref()
+                    .map(|frame| pool.copy_frame(frame))
+                    .ok_or_else(|| "H.264 decoder has not produced a picture yet".into());
+            }
+        };
+
+        let (width, height) = picture.dimension_rgb();
+        let (y_stride, u_stride, v_stride) = picture.strides_yuv();
+        debug_assert_eq!(u_stride, v_stride);
+        let chroma_height = (height + 1) / 2;
+
+        let frame = DecodedFrame {
+            width: width as u16,
+            height: height as u16,
+            pixels: FramePixels::Yuv420(YuvPlanes {
+                y: pool.copy(&picture.y_with_stride()[..y_stride * height]),
+                u: pool.copy(&picture.u_with_stride()[..u_stride * chroma_height]),
+                v: pool.copy(&picture.v_with_stride()[..v_stride * chroma_height]),
+                y_stride,
+                chroma_stride: u_stride,
+                color_matrix: ColorMatrix::for_size(width, height),
+            }),
+            alpha_mode: AlphaMode::Opaque,
+        };
+        if let Some(last_frame) = self.last_frame.replace(pool.copy_frame(&frame)) {
+            pool.recycle(last_frame);
+        }
+
+        Ok(frame)
+    }
+
+    fn reset(&mut self) -> Result<(), Error> {
+        // The sequence header is usually only sent once, at the start of the
+        // stream, so its parameter sets are passed to the new decoder along
+        // with the next keyframe.
+        self.decoder = Self::new_decoder()?;
+        self.needs_parameter_sets = self.configuration.is_some();
+        self.last_frame = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::render::NullRenderer;
+    use crate::backend::video::{SoftwareVideoBackend, VideoBackend};
+    use openh264::encoder::{Encoder, EncoderConfig};
+    use openh264::formats::YUVBuffer;
+    use swf::{VideoCodec, VideoDeblocking};
+
+    /// Split an Annex B stream into its NAL units.
+    fn split_annex_b(stream: &[u8]) -> Vec<&[u8]> {
+        let mut starts = vec![];
+        let mut i = 0;
+        while i + 3 <= stream.len() {
+            if stream[i..i + 3] == [0, 0, 1] {
+                starts.push(i + 3);
+                i += 3;
+            } else {
+                i += 1;
+            }
+        }
+        starts
+            .iter()
+            .enumerate()
+            .map(|(n, &start)| {
+                let mut end = starts.get(n + 1).map_or(stream.len(), |next| next - 3);
+                // Four byte start codes have an extra leading zero.
+                while end > start && stream[end - 1] == 0 {
+                    end -= 1;
+                }
+                &stream[start..end]
+            })
+            .collect()
+    }
+
+    /// Encode a few frames with OpenH264, and package them as FLV
+    /// `AVCVIDEOPACKET`s: a sequence header, followed by one packet per frame.
+    fn encode_packets(width: u32, height: u32, num_frames: usize) -> Vec<Vec<u8>> {
+        let mut encoder = Encoder::with_config(EncoderConfig::new(width, height)).unwrap();
+        let mut parameter_sets = (vec![], vec![]);
+        let mut frames = vec![];
+        for frame in 0..num_frames {
+            let rgb: Vec<u8> = (0..width * height * 3)
+                .map(|i| (i as usize + frame * 7) as u8)
+                .collect();
+            let mut yuv = YUVBuffer::new(width as usize, height as usize);
+            yuv.read_rgb(&rgb);
+            let stream = encoder.encode(&yuv).unwrap().to_vec();
+
+            let mut packet = vec![1, 0, 0, 0];
+            for unit in split_annex_b(&stream) {
+                match unit[0] & 0b1_1111 {
+                    7 => parameter_sets.0 = unit.to_vec(),
+                    8 => parameter_sets.1 = unit.to_vec(),
+                    _ => {
+                        packet.extend_from_slice(&(unit.len() as u32).to_be_bytes());
+                        packet.extend_from_slice(unit);
+                    }
+                }
+            }
+            frames.push(packet);
+        }
+
+        let (sps, pps) = parameter_sets;
+        let mut header = vec![0, 0, 0, 0, 1, sps[1], sps[2], sps[3], 0xFF, 0xE1];
+        header.extend_from_slice(&(sps.len() as u16).to_be_bytes());
+        header.extend_from_slice(&sps);
+        header.push(1);
+        header.extend_from_slice(&(pps.len() as u16).to_be_bytes());
+        header.extend_from_slice(&pps);
+
+        std::iter::once(header).chain(frames).collect()
+    }
+
+    #[test]
+    fn decode_after_seek() {
+        let packets = encode_packets(32, 32, 4);
+
+        let mut backend = SoftwareVideoBackend::new();
+        let mut renderer = NullRenderer::new();
+        let stream = backend
+            .register_video_stream(
+                packets.len() as u32,
+                (32, 32),
+                VideoCodec::H264,
+                VideoDeblocking::None,
+            )
+            .unwrap();
+        let frame = |frame_id: usize| EncodedFrame {
+            codec: VideoCodec::H264,
+            data: &packets[frame_id],
+            frame_id: frame_id as u32,
+        };
+
+        for frame_id in 0..packets.len() {
+            backend
+                .preload_video_stream_frame(stream, frame(frame_id))
+                .unwrap();
+        }
+        for frame_id in 0..packets.len() {
+            backend
+                .decode_video_stream_frame(stream, frame(frame_id), &mut renderer)
+                .unwrap();
+        }
+
+        // The sequence header isn't decoded again after seeking, so the
+        // decoder has to hold on to its parameter sets.
+        let keyframe = backend.seek_video_stream(stream, 3).unwrap();
+        assert_ne!(keyframe, 0);
+        for frame_id in keyframe as usize..packets.len() {
+            let info = backend
+                .decode_video_stream_frame(stream, frame(frame_id), &mut renderer)
+                .unwrap();
+            assert_eq!((info.width, info.height), (32, 32));
+        }
+        assert_eq!(backend.video_stream_stats(stream).failed_frames, 0);
+    }
+
+    #[test]
+    fn parse_configuration() {
+        let record = [
+            1, 0x64, 0, 0x1F, 0xFF, // version, profile, compatibility, level, length size
+            0xE1, 0, 3, 0x67, 0xAA, 0xBB, // one SPS
+            1, 0, 2, 0x68, 0xCC, // one PPS
+        ];
+        let configuration = DecoderConfiguration::parse(&record).unwrap();
+        assert_eq!(configuration.nal_length_size, 4);
+        assert_eq!(
+            configuration.parameter_sets,
+            [0, 0, 0, 1, 0x67, 0xAA, 0xBB, 0, 0, 0, 1, 0x68, 0xCC]
+        );
+    }
+
+    #[test]
+    fn split_nal_units() {
+        let configuration = DecoderConfiguration {
+            nal_length_size: 2,
+            parameter_sets: vec![],
+        };
+        let data = [0, 2, 0x65, 0x01, 0, 1, 0x41];
+        let units: Vec<_> = configuration.nal_units(&data).collect();
+        assert_eq!(units, [&[0x65, 0x01][..], &[0x41][..]]);
+    }
+
+    #[test]
+    fn parse_packet_types() {
+        assert_eq!(
+            parse_packet(&[0, 0, 0, 0, 1]).unwrap(),
+            (PacketType::SequenceHeader, &[1][..])
+        );
+        assert_eq!(
+            parse_packet(&[1, 0, 0, 0]).unwrap(),
+            (PacketType::Nalu, &[][..])
+        );
+        assert!(parse_packet(&[3, 0, 0, 0]).is_err());
+    }
+}
@@ -0,0 +1,121 @@
+//! Decoder for video streams made of standalone JPEG images.
+//!
+//! The FLV format reserves codec ID 1 for this, and some encoders also put
+//! JPEG images into the frames of Screen Video streams. Either way, every
+//! frame is a complete image, which is decoded the same way as the image of
+//! a `DefineBitsJPEG2` tag.
+
+use crate::backend::render::{
+    decode_define_bits_jpeg, determine_jpeg_tag_format, BitmapFormat, JpegTagFormat,
+};
+use crate::backend::video::software::{FramePool, VideoDecoder};
+use crate::backend::video::{
+    AlphaMode, DecodedFrame, EncodedFrame, Error, FrameDependency, FramePixels,
+};
+
+/// Determine if the data of a video frame is a JPEG image.
+fn is_jpeg(data: &[u8]) -> bool {
+    determine_jpeg_tag_format(data) == JpegTagFormat::Jpeg
+}
+
+/// JPEG video decoder.
+#[derive(Default)]
+pub struct JpegDecoder;
+
+impl JpegDecoder {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl VideoDecoder for JpegDecoder {
+    fn preload_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<FrameDependency, Error> {
+        if !is_jpeg(encoded_frame.data()) {
+            return Err("Video frame is not a JPEG image".into());
+        }
+
+        Ok(FrameDependency::None)
+    }
+
+    fn decode_frame(
+        &mut self,
+        encoded_frame: EncodedFrame<'_>,
+        pool: &mut FramePool,
+    ) -> Result<DecodedFrame, Error> {
+        let bitmap = decode_define_bits_jpeg(encoded_frame.data(), None)?;
+
+        let (rgba, alpha_mode) = match bitmap.data {
+            BitmapFormat::Rgb(rgb) => {
+                let mut rgba = pool.take(rgb.len() / 3 * 4);
+                for (rgb, rgba) in rgb.chunks_exact(3).zip(rgba.chunks_exact_mut(4)) {
+                    rgba.copy_from_slice(&[rgb[0], rgb[1], rgb[2], 0xFF]);
+                }
+                (rgba, AlphaMode::Opaque)
+            }
+            BitmapFormat::Rgba(rgba) => (rgba, AlphaMode::Premultiplied),
+        };
+
+        Ok(DecodedFrame {
+            width: bitmap.width as u16,
+            height: bitmap.height as u16,
+            pixels: FramePixels::Rgba(rgba),
+            alpha_mode,
+        })
+    }
+}
+
+/// A decoder that hands JPEG frames to a `JpegDecoder`, and everything else
+/// to the decoder of the codec that the stream claims to use.
+pub struct JpegFallbackDecoder {
+    inner: Box<dyn VideoDecoder>,
+    jpeg: JpegDecoder,
+}
+
+impl JpegFallbackDecoder {
+    pub fn new(inner: Box<dyn VideoDecoder>) -> Self {
+        Self {
+            inner,
+            jpeg: JpegDecoder::new(),
+        }
+    }
+}
+
+impl VideoDecoder for JpegFallbackDecoder {
+    fn preload_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<FrameDependency, Error> {
+        if is_jpeg(encoded_frame.data()) {
+            self.jpeg.preload_frame(encoded_frame)
+        } else {
+            self.inner.preload_frame(encoded_frame)
+        }
+    }
+
+    fn decode_frame(
+        &mut self,
+        encoded_frame: EncodedFrame<'_>,
+        pool: &mut FramePool,
+    ) -> Result<DecodedFrame, Error> {
+        if is_jpeg(encoded_frame.data()) {
+            self.jpeg.decode_frame(encoded_frame, pool)
+        } else {
+            self.inner.decode_frame(encoded_frame, pool)
+        }
+    }
+
+    fn reset(&mut self) -> Result<(), Error> {
+        self.inner.reset()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_jpeg() {
+        assert!(is_jpeg(&[0xFF, 0xD8, 0xFF, 0xE0]));
+        // Some SWF encoders prepend an empty image to JPEG data.
+        assert!(is_jpeg(&[0xFF, 0xD9, 0xFF, 0xD8, 0xFF, 0xE0]));
+        // A Screen Video frame header.
+        assert!(!is_jpeg(&[0x00, 0x10, 0x00, 0x20]));
+    }
+}
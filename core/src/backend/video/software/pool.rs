@@ -0,0 +1,132 @@
+//! Recycling of decoded frame buffers.
+
+use crate::backend::render::YuvPlanes;
+use crate::backend::video::{DecodedFrame, FramePixels};
+
+/// How many buffers to hold on to at most.
+///
+/// This covers the three planes of a few YUV frames, which is as many as a
+/// decoder that keeps its previous frame around needs at once.
+const MAX_BUFFERS: usize = 8;
+
+/// A pool of pixel buffers left over from earlier frames of a video stream.
+///
+/// Decoders take their output buffers from here, and the backend returns
+/// them once a frame has been uploaded to the renderer. This way, playback
+/// does not allocate new buffers for every frame.
+#[derive(Default)]
+pub struct FramePool {
+    /// The dimensions of the frames that the pooled buffers came from.
+    ///
+    /// When a stream changes size, its old buffers are of no use anymore.
+    dimensions: (u16, u16),
+
+    buffers: Vec<Vec<u8>>,
+}
+
+impl FramePool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get a buffer of exactly `len` bytes.
+    ///
+    /// The contents of the buffer are unspecified, so callers should
+    /// overwrite all of it.
+    pub fn take(&mut self, len: usize) -> Vec<u8> {
+        match self.buffers.iter().position(|buffer| buffer.len() == len) {
+            Some(index) => self.buffers.swap_remove(index),
+            None => vec![0; len],
+        }
+    }
+
+    /// Get a buffer holding a copy of `data`.
+    pub fn copy(&mut self, data: &[u8]) -> Vec<u8> {
+        let mut buffer = self.take(data.len());
+        buffer.copy_from_slice(data);
+        buffer
+    }
+
+    /// Make a copy of a frame, using buffers from this pool.
+    pub fn copy_frame(&mut self, frame: &DecodedFrame) -> DecodedFrame {
+        let pixels = match &frame.pixels {
+            FramePixels::Rgba(rgba) => FramePixels::Rgba(self.copy(rgba)),
+            FramePixels::Yuv420(planes) => FramePixels::Yuv420(YuvPlanes {
+                y: self.copy(&planes.y),
+                u: self.copy(&planes.u),
+                v: self.copy(&planes.v),
+                ..*planes
+            }),
+        };
+
+        DecodedFrame {
+            width: frame.width,
+            height: frame.height,
+            pixels,
+            alpha_mode: frame.alpha_mode,
+        }
+    }
+
+    /// Return the buffers of a frame that is no longer needed.
+    pub fn recycle(&mut self, frame: DecodedFrame) {
+        let dimensions = (frame.width, frame.height);
+        if dimensions != self.dimensions {
+            self.buffers.clear();
+            self.dimensions = dimensions;
+        }
+
+        match frame.pixels {
+            FramePixels::Rgba(rgba) => self.give(rgba),
+            FramePixels::Yuv420(planes) => {
+                self.give(planes.y);
+                self.give(planes.u);
+                self.give(planes.v);
+            }
+        }
+    }
+
+    fn give(&mut self, buffer: Vec<u8>) {
+        if self.buffers.len() < MAX_BUFFERS {
+            self.buffers.push(buffer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::video::AlphaMode;
+
+    fn rgba_frame(width: u16, height: u16) -> DecodedFrame {
+        DecodedFrame {
+            width,
+            height,
+            pixels: FramePixels::Rgba(vec![0; usize::from(width) * usize::from(height) * 4]),
+            alpha_mode: AlphaMode::Opaque,
+        }
+    }
+
+    #[test]
+    fn reuses_buffers() {
+        let mut pool = FramePool::new();
+        let frame = rgba_frame(4, 4);
+        let pointer = match &frame.pixels {
+            FramePixels::Rgba(rgba) => rgba.as_ptr(),
+            FramePixels::Yuv420(_) => unreachable!(),
+        };
+        pool.recycle(frame);
+
+        let buffer = pool.take(4 * 4 * 4);
+        assert_eq!(buffer.as_ptr(), pointer);
+        assert!(pool.buffers.is_empty());
+    }
+
+    #[test]
+    fn drops_buffers_on_resize() {
+        let mut pool = FramePool::new();
+        pool.recycle(rgba_frame(4, 4));
+        pool.recycle(rgba_frame(8, 8));
+        assert_eq!(pool.buffers.len(), 1);
+        assert_eq!(pool.buffers[0].len(), 8 * 8 * 4);
+    }
+}
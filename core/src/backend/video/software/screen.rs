@@ -0,0 +1,304 @@
+//! Screen Video (codec ID 3) decoder.
+//!
+//! The image is split into a grid of blocks, starting from the bottom left
+//! corner. Each block is either a zlib-compressed run of BGR pixels (stored
+//! bottom-up), or empty, meaning that the block did not change since the
+//! previous frame.
+
+use crate::backend::video::software::{FramePool, VideoDecoder};
+use crate::backend::video::{
+    AlphaMode, DecodedFrame, EncodedFrame, Error, FrameDependency, FramePixels,
+};
+use flate2::read::ZlibDecoder;
+use std::io::Read;
+
+/// The parsed header of a Screen Video frame.
+///
+/// This header is shared with Screen Video V2.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) struct FrameHeader {
+    pub block_width: usize,
+    pub image_width: usize,
+    pub block_height: usize,
+    pub image_height: usize,
+}
+
+impl FrameHeader {
+    /// Parse the four-byte header found at the start of every frame.
+    pub fn parse(data: &[u8]) -> Result<Self, Error> {
+        if data.len() < 4 {
+            return Err("Screen Video frame is too short to contain a header".into());
+        }
+
+        let first = u16::from_be_bytes([data[0], data[1]]);
+        let second = u16::from_be_bytes([data[2], data[3]]);
+
+        let header = Self {
+            block_width: (((first >> 12) as usize) + 1) * 16,
+            image_width: (first & 0x0FFF) as usize,
+            block_height: (((second >> 12) as usize) + 1) * 16,
+            image_height: (second & 0x0FFF) as usize,
+        };
+
+        if header.image_width == 0 || header.image_height == 0 {
+            return Err("Screen Video frame has zero size".into());
+        }
+
+        Ok(header)
+    }
+
+    /// The number of block columns and rows in the image.
+    pub fn block_counts(&self) -> (usize, usize) {
+        (
+            (self.image_width + self.block_width - 1) / self.block_width,
+            (self.image_height + self.block_height - 1) / self.block_height,
+        )
+    }
+}
+
+/// Screen Video decoder.
+pub struct ScreenVideoDecoder {
+    /// The last decoded image, which skipped blocks are copied from.
+    last_frame: Option<DecodedFrame>,
+}
+
+impl ScreenVideoDecoder {
+    pub fn new() -> Self {
+        Self { last_frame: None }
+    }
+}
+
+impl VideoDecoder for ScreenVideoDecoder {
+    fn preload_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<FrameDependency, Error> {
+        // A frame is a keyframe exactly when none of its blocks are skipped.
+        let data = encoded_frame.data();
+        let header = FrameHeader::parse(data)?;
+        let (columns, rows) = header.block_counts();
+
+        let mut position = 4;
+        for _ in 0..columns * rows {
+            let size = read_block_size(data, position)?;
+            if size == 0 {
+                return Ok(FrameDependency::Past);
+            }
+            position += 2 + size;
+        }
+
+        Ok(FrameDependency::None)
+    }
+
+    fn decode_frame(
+        &mut self,
+        encoded_frame: EncodedFrame<'_>,
+        pool: &mut FramePool,
+    ) -> Result<DecodedFrame, Error> {
+        let data = encoded_frame.data();
+        let header = FrameHeader::parse(data)?;
+        let (columns, rows) = header.block_counts();
+        let (width, height) = (header.image_width, header.image_height);
+
+        // Decode into a copy of the last image, so that it is kept intact as
+        // the reference for later frames if this one fails to decode.
+        let mut rgba = match &self.last_frame {
+            Some(DecodedFrame {
+                width: last_width,
+                height: last_height,
+                pixels: FramePixels::Rgba(rgba),
+                ..
+            }) if *last_width as usize == width && *last_height as usize == height => rgba.clone(),
+            _ => vec![0; width * height * 4],
+        };
+
+        let mut block = vec![0; header.block_width * header.block_height * 3];
+        let mut position = 4;
+
+        for block_row in 0..rows {
+            for block_column in 0..columns {
+                let size = read_block_size(data, position)?;
+                position += 2;
+
+                if size == 0 {
+                    continue;
+                }
+
+                let compressed = data
+                    .get(position..position + size)
+                    .ok_or("Screen Video block data is truncated")?;
+                position += size;
+
+                // Blocks on the right and top edges may be cut off.
+                let x = block_column * header.block_width;
+                let y = block_row * header.block_height;
+                let block_width = usize::min(header.block_width, width - x);
+                let block_height = usize::min(header.block_height, height - y);
+                let block_len = block_width * block_height * 3;
+
+                ZlibDecoder::new(compressed)
+                    .read_exact(&mut block[..block_len])
+                    .map_err(|e| format!("Screen Video block decompression error: {}", e))?;
+
+                for (row, bgr) in block[..block_len].chunks_exact(block_width * 3).enumerate() {
+                    // Both block rows and pixel rows are stored bottom-up.
+                    let out_row = height - 1 - (y + row);
+                    let out_start = (out_row * width + x) * 4;
+                    let out = &mut rgba[out_start..out_start + block_width * 4];
+                    for (src, dst) in bgr.chunks_exact(3).zip(out.chunks_exact_mut(4)) {
+                        dst.copy_from_slice(&[src[2], src[1], src[0], 0xFF]);
+                    }
+                }
+            }
+        }
+
+        let frame = DecodedFrame {
+            width: width as u16,
+            height: height as u16,
+            pixels: FramePixels::Rgba(rgba),
+            alpha_mode: AlphaMode::Opaque,
+        };
+        let output = pool.copy_frame(&frame);
+        self.last_frame = Some(frame);
+
+        Ok(output)
+    }
+}
+
+impl Default for ScreenVideoDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Read the big-endian size prefix of the block starting at `position`.
+pub(super) fn read_block_size(data: &[u8], position: usize) -> Result<usize, Error> {
+    let bytes = data
+        .get(position..position + 2)
+        .ok_or("Screen Video frame is truncated")?;
+    Ok(u16::from_be_bytes([bytes[0], bytes[1]]) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+    use swf::VideoCodec;
+
+    fn compress(data: &[u8]) -> Vec<u8> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    /// Build a 16x32 frame made of two 16x16 blocks. `None` skips a block.
+    fn build_frame(bottom: Option<[u8; 3]>, top: Option<[u8; 3]>) -> Vec<u8> {
+        let mut data = vec![0x00, 0x10, 0x00, 0x20];
+        for block in [bottom, top] {
+            match block {
+                Some(bgr) => {
+                    let compressed = compress(&bgr.repeat(16 * 16));
+                    data.extend_from_slice(&(compressed.len() as u16).to_be_bytes());
+                    data.extend_from_slice(&compressed);
+                }
+                None => data.extend_from_slice(&[0, 0]),
+            }
+        }
+        data
+    }
+
+    fn rgba(frame: DecodedFrame) -> Vec<u8> {
+        match frame.pixels {
+            FramePixels::Rgba(rgba) => rgba,
+            FramePixels::Yuv420(_) => panic!("Expected an RGBA frame"),
+        }
+    }
+
+    fn encoded(data: &[u8], frame_id: u32) -> EncodedFrame<'_> {
+        EncodedFrame {
+            codec: VideoCodec::ScreenVideo,
+            data,
+            frame_id,
+        }
+    }
+
+    #[test]
+    fn parse_header() {
+        let header = FrameHeader::parse(&[0x10, 0x28, 0x20, 0x1E]).unwrap();
+        assert_eq!(
+            header,
+            FrameHeader {
+                block_width: 32,
+                image_width: 40,
+                block_height: 48,
+                image_height: 30,
+            }
+        );
+        assert_eq!(header.block_counts(), (2, 1));
+    }
+
+    #[test]
+    fn keyframe_detection() {
+        let mut decoder = ScreenVideoDecoder::new();
+        let keyframe = build_frame(Some([0, 0, 255]), Some([255, 0, 0]));
+        let interframe = build_frame(None, Some([0, 255, 0]));
+        assert!(decoder
+            .preload_frame(encoded(&keyframe, 0))
+            .unwrap()
+            .is_keyframe());
+        assert!(!decoder
+            .preload_frame(encoded(&interframe, 1))
+            .unwrap()
+            .is_keyframe());
+    }
+
+    #[test]
+    fn decode_blocks() {
+        let mut decoder = ScreenVideoDecoder::new();
+        let mut pool = FramePool::new();
+        let keyframe = build_frame(Some([0, 0, 255]), Some([255, 0, 0]));
+        let frame = decoder
+            .decode_frame(encoded(&keyframe, 0), &mut pool)
+            .unwrap();
+        assert_eq!((frame.width, frame.height), (16, 32));
+        // The first block is at the bottom of the image.
+        let pixels = rgba(frame);
+        assert_eq!(&pixels[..4], &[0, 0, 255, 255]);
+        assert_eq!(&pixels[pixels.len() - 4..], &[255, 0, 0, 255]);
+
+        let interframe = build_frame(None, Some([0, 255, 0]));
+        let pixels = rgba(
+            decoder
+                .decode_frame(encoded(&interframe, 1), &mut pool)
+                .unwrap(),
+        );
+        assert_eq!(&pixels[..4], &[0, 255, 0, 255]);
+        assert_eq!(&pixels[pixels.len() - 4..], &[255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn malformed_frame_keeps_reference() {
+        let mut decoder = ScreenVideoDecoder::new();
+        let mut pool = FramePool::new();
+        let keyframe = build_frame(Some([0, 0, 255]), Some([255, 0, 0]));
+        decoder
+            .decode_frame(encoded(&keyframe, 0), &mut pool)
+            .unwrap();
+
+        // The bottom block decodes, but the top block is truncated.
+        let mut malformed = build_frame(Some([0, 255, 0]), Some([0, 255, 0]));
+        malformed.truncate(malformed.len() - 4);
+        assert!(decoder
+            .decode_frame(encoded(&malformed, 1), &mut pool)
+            .is_err());
+
+        let interframe = build_frame(None, Some([0, 255, 0]));
+        let pixels = rgba(
+            decoder
+                .decode_frame(encoded(&interframe, 2), &mut pool)
+                .unwrap(),
+        );
+        // The bottom block is still the one from the keyframe.
+        assert_eq!(&pixels[..4], &[0, 255, 0, 255]);
+        assert_eq!(&pixels[pixels.len() - 4..], &[255, 0, 0, 255]);
+    }
+}
@@ -0,0 +1,480 @@
+//! Screen Video V2 (codec ID 6) decoder.
+//!
+//! This builds on the block layout of Screen Video, adding per-block flags
+//! that allow only a range of rows to be updated ("diff blocks"), and a
+//! hybrid pixel format mixing 15-bit colors with indices into a 128-entry
+//! palette.
+//!
+//! Blocks whose zlib streams are primed with the data of another block
+//! (`ZlibPrimeCompressCurrent` and `ZlibPrimeCompressPrevious`) are not
+//! supported. Frames containing them fail to decode, and the last decoded
+//! image is kept as the reference for the frames after them.
+
+use crate::backend::video::software::screen::{read_block_size, FrameHeader};
+use crate::backend::video::software::{FramePool, VideoDecoder};
+use crate::backend::video::{
+    AlphaMode, DecodedFrame, EncodedFrame, Error, FrameDependency, FramePixels,
+};
+use flate2::read::ZlibDecoder;
+use std::io::Read;
+
+/// The number of colors in a Screen Video V2 palette.
+const PALETTE_SIZE: usize = 128;
+
+/// Flags stored after the frame header.
+const HAS_IFRAME_IMAGE: u8 = 0b10;
+const HAS_PALETTE_INFO: u8 = 0b01;
+
+/// Flags stored in the first byte of each non-empty block.
+const COLOR_DEPTH_MASK: u8 = 0b0001_1000;
+const HAS_DIFF_BLOCKS: u8 = 0b0000_0100;
+const ZLIB_PRIME_COMPRESS_CURRENT: u8 = 0b0000_0010;
+const ZLIB_PRIME_COMPRESS_PREVIOUS: u8 = 0b0000_0001;
+
+/// How the pixels of a block are stored.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum ColorDepth {
+    /// Three bytes per pixel, in BGR order.
+    Bgr24,
+
+    /// Either one byte indexing the palette, or two bytes holding a 15-bit
+    /// color, distinguished by the high bit of the first byte.
+    Hybrid,
+}
+
+impl ColorDepth {
+    fn from_flags(flags: u8) -> Result<Self, Error> {
+        match (flags & COLOR_DEPTH_MASK) >> 3 {
+            0 => Ok(ColorDepth::Bgr24),
+            2 => Ok(ColorDepth::Hybrid),
+            depth => Err(format!("Unsupported Screen Video V2 color depth {}", depth).into()),
+        }
+    }
+}
+
+/// The parsed header of a single non-empty image block.
+#[derive(Copy, Clone, Debug)]
+struct BlockHeader {
+    color_depth: ColorDepth,
+
+    /// The range of rows (counted from the bottom of the block) present in
+    /// the block data, if this is a diff block.
+    diff_rows: Option<(usize, usize)>,
+
+    /// Whether the zlib stream is primed with data from another block, of
+    /// this frame or the previous one.
+    primed: bool,
+
+    /// Whether the block refers to the previous frame in any way.
+    references_previous: bool,
+
+    /// The number of header bytes preceding the compressed pixel data.
+    len: usize,
+}
+
+impl BlockHeader {
+    fn parse(data: &[u8]) -> Result<Self, Error> {
+        let flags = *data.first().ok_or("Screen Video V2 block is empty")?;
+        let mut len = 1;
+
+        let diff_rows = if flags & HAS_DIFF_BLOCKS != 0 {
+            let bytes = data
+                .get(len..len + 2)
+                .ok_or("Screen Video V2 diff block header is truncated")?;
+            len += 2;
+            Some((bytes[0] as usize, bytes[1] as usize))
+        } else {
+            None
+        };
+
+        let primed = flags & (ZLIB_PRIME_COMPRESS_CURRENT | ZLIB_PRIME_COMPRESS_PREVIOUS) != 0;
+        if flags & ZLIB_PRIME_COMPRESS_CURRENT != 0 {
+            // The position of the block to prime the zlib stream with.
+            len += 2;
+        }
+
+        Ok(Self {
+            color_depth: ColorDepth::from_flags(flags)?,
+            diff_rows,
+            primed,
+            references_previous: diff_rows.is_some() || flags & ZLIB_PRIME_COMPRESS_PREVIOUS != 0,
+            len,
+        })
+    }
+}
+
+/// Screen Video V2 decoder.
+pub struct ScreenVideoV2Decoder {
+    /// The last decoded image, which skipped and diff blocks are copied from.
+    last_frame: Option<DecodedFrame>,
+
+    /// The palette used by blocks in the hybrid color format.
+    palette: [[u8; 3]; PALETTE_SIZE],
+}
+
+impl ScreenVideoV2Decoder {
+    pub fn new() -> Self {
+        Self {
+            last_frame: None,
+            palette: default_palette(),
+        }
+    }
+
+    /// Replace the current palette with the zlib-compressed BGR colors in
+    /// `data`.
+    fn read_palette(&mut self, data: &[u8]) -> Result<(), Error> {
+        let mut colors = Vec::with_capacity(PALETTE_SIZE * 3);
+        ZlibDecoder::new(data)
+            .read_to_end(&mut colors)
+            .map_err(|e| format!("Screen Video V2 palette decompression error: {}", e))?;
+
+        for (entry, bgr) in self.palette.iter_mut().zip(colors.chunks_exact(3)) {
+            *entry = [bgr[2], bgr[1], bgr[0]];
+        }
+
+        Ok(())
+    }
+
+    /// Convert a block of decompressed pixel data into RGBA rows of the
+    /// output image.
+    ///
+    /// `rows` is the range of block rows (counted from the bottom) that
+    /// `pixels` covers.
+    #[allow(clippy::too_many_arguments)]
+    fn write_block(
+        &self,
+        pixels: &[u8],
+        color_depth: ColorDepth,
+        rgba: &mut [u8],
+        image_size: (usize, usize),
+        position: (usize, usize),
+        block_width: usize,
+        rows: (usize, usize),
+    ) -> Result<(), Error> {
+        let (width, height) = image_size;
+        let (x, y) = position;
+        let mut pixels = pixels.iter();
+
+        for row in rows.0..rows.0 + rows.1 {
+            let out_row = height - 1 - (y + row);
+            let out_start = (out_row * width + x) * 4;
+            for dst in rgba[out_start..out_start + block_width * 4].chunks_exact_mut(4) {
+                let rgb = match color_depth {
+                    ColorDepth::Bgr24 => {
+                        let b = *pixels.next().ok_or("Screen Video V2 block is truncated")?;
+                        let g = *pixels.next().ok_or("Screen Video V2 block is truncated")?;
+                        let r = *pixels.next().ok_or("Screen Video V2 block is truncated")?;
+                        [r, g, b]
+                    }
+                    ColorDepth::Hybrid => {
+                        let first = *pixels.next().ok_or("Screen Video V2 block is truncated")?;
+                        if first & 0x80 != 0 {
+                            let second =
+                                *pixels.next().ok_or("Screen Video V2 block is truncated")?;
+                            let color = u16::from_be_bytes([first & 0x7F, second]);
+                            [
+                                expand_5_bits(color >> 10),
+                                expand_5_bits(color >> 5),
+                                expand_5_bits(color),
+                            ]
+                        } else {
+                            self.palette[first as usize]
+                        }
+                    }
+                };
+                dst.copy_from_slice(&[rgb[0], rgb[1], rgb[2], 0xFF]);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl VideoDecoder for ScreenVideoV2Decoder {
+    fn preload_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<FrameDependency, Error> {
+        // A frame is a keyframe when every block is present in full and none
+        // of them are primed with data from the previous frame.
+        let data = encoded_frame.data();
+        let header = FrameHeader::parse(data)?;
+        let (columns, rows) = header.block_counts();
+        let flags = *data.get(4).ok_or("Screen Video V2 frame is truncated")?;
+
+        let mut position = 5;
+        if flags & HAS_PALETTE_INFO != 0 {
+            position += 2 + read_block_size(data, position)?;
+        }
+
+        for _ in 0..columns * rows {
+            let size = read_block_size(data, position)?;
+            position += 2;
+            if size == 0 {
+                return Ok(FrameDependency::Past);
+            }
+
+            let block = data
+                .get(position..position + size)
+                .ok_or("Screen Video V2 block data is truncated")?;
+            if BlockHeader::parse(block)?.references_previous {
+                return Ok(FrameDependency::Past);
+            }
+            position += size;
+        }
+
+        Ok(FrameDependency::None)
+    }
+
+    fn decode_frame(
+        &mut self,
+        encoded_frame: EncodedFrame<'_>,
+        pool: &mut FramePool,
+    ) -> Result<DecodedFrame, Error> {
+        let data = encoded_frame.data();
+        let header = FrameHeader::parse(data)?;
+        let (columns, rows) = header.block_counts();
+        let (width, height) = (header.image_width, header.image_height);
+        let flags = *data.get(4).ok_or("Screen Video V2 frame is truncated")?;
+
+        let mut position = 5;
+        if flags & HAS_PALETTE_INFO != 0 {
+            let size = read_block_size(data, position)?;
+            position += 2;
+            let palette = data
+                .get(position..position + size)
+                .ok_or("Screen Video V2 palette is truncated")?;
+            self.read_palette(palette)?;
+            position += size;
+        }
+
+        if flags & HAS_IFRAME_IMAGE != 0 {
+            // The keyframe image follows the image blocks, and is only used as
+            // a source for priming zlib streams, which are not supported.
+            log::debug!("Ignoring Screen Video V2 keyframe image");
+        }
+
+        // Decode into a copy of the last image, so that it is kept intact as
+        // the reference for later frames if this one fails to decode.
+        let mut rgba = match &self.last_frame {
+            Some(DecodedFrame {
+                width: last_width,
+                height: last_height,
+                pixels: FramePixels::Rgba(rgba),
+                ..
+            }) if *last_width as usize == width && *last_height as usize == height => rgba.clone(),
+            _ => vec![0; width * height * 4],
+        };
+
+        let mut pixels = Vec::with_capacity(header.block_width * header.block_height * 3);
+
+        for block_row in 0..rows {
+            for block_column in 0..columns {
+                let size = read_block_size(data, position)?;
+                position += 2;
+
+                if size == 0 {
+                    continue;
+                }
+
+                let block = data
+                    .get(position..position + size)
+                    .ok_or("Screen Video V2 block data is truncated")?;
+                position += size;
+
+                let block_header = BlockHeader::parse(block)?;
+                if block_header.primed {
+                    return Err("Primed zlib streams in Screen Video V2 are not supported".into());
+                }
+
+                let x = block_column * header.block_width;
+                let y = block_row * header.block_height;
+                let block_width = usize::min(header.block_width, width - x);
+                let block_height = usize::min(header.block_height, height - y);
+
+                let (row_start, row_count) = block_header.diff_rows.unwrap_or((0, block_height));
+                if row_start + row_count > block_height {
+                    return Err("Screen Video V2 diff block is out of bounds".into());
+                }
+
+                pixels.clear();
+                ZlibDecoder::new(&block[block_header.len..])
+                    .read_to_end(&mut pixels)
+                    .map_err(|e| format!("Screen Video V2 block decompression error: {}", e))?;
+
+                self.write_block(
+                    &pixels,
+                    block_header.color_depth,
+                    &mut rgba,
+                    (width, height),
+                    (x, y),
+                    block_width,
+                    (row_start, row_count),
+                )?;
+            }
+        }
+
+        let frame = DecodedFrame {
+            width: width as u16,
+            height: height as u16,
+            pixels: FramePixels::Rgba(rgba),
+            alpha_mode: AlphaMode::Opaque,
+        };
+        let output = pool.copy_frame(&frame);
+        self.last_frame = Some(frame);
+
+        Ok(output)
+    }
+}
+
+impl Default for ScreenVideoV2Decoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Expand the low 5 bits of `value` to a full 8-bit color component.
+fn expand_5_bits(value: u16) -> u8 {
+    let value = (value & 0x1F) as u8;
+    (value << 3) | (value >> 2)
+}
+
+/// Build the palette used until a frame provides its own.
+///
+/// Flash Player's built-in palette is not documented, so this approximates
+/// it with a gray ramp followed by an evenly spaced color cube.
+fn default_palette() -> [[u8; 3]; PALETTE_SIZE] {
+    let mut palette = [[0; 3]; PALETTE_SIZE];
+    let (grays, cube) = palette.split_at_mut(PALETTE_SIZE - 5 * 5 * 4);
+    for (i, entry) in grays.iter_mut().enumerate() {
+        let value = (i * 255 / (grays.len() - 1)) as u8;
+        *entry = [value, value, value];
+    }
+    for (i, entry) in cube.iter_mut().enumerate() {
+        let r = (i / 20) * 255 / 4;
+        let g = (i / 4 % 5) * 255 / 4;
+        let b = (i % 4) * 255 / 3;
+        *entry = [r as u8, g as u8, b as u8];
+    }
+    palette
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+    use swf::VideoCodec;
+
+    fn compress(data: &[u8]) -> Vec<u8> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn push_block(data: &mut Vec<u8>, header: &[u8], pixels: &[u8]) {
+        let compressed = compress(pixels);
+        let size = header.len() + compressed.len();
+        data.extend_from_slice(&(size as u16).to_be_bytes());
+        data.extend_from_slice(header);
+        data.extend_from_slice(&compressed);
+    }
+
+    fn rgba(frame: DecodedFrame) -> Vec<u8> {
+        match frame.pixels {
+            FramePixels::Rgba(rgba) => rgba,
+            FramePixels::Yuv420(_) => panic!("Expected an RGBA frame"),
+        }
+    }
+
+    fn encoded(data: &[u8], frame_id: u32) -> EncodedFrame<'_> {
+        EncodedFrame {
+            codec: VideoCodec::ScreenVideoV2,
+            data,
+            frame_id,
+        }
+    }
+
+    #[test]
+    fn expand_components() {
+        assert_eq!(expand_5_bits(0), 0);
+        assert_eq!(expand_5_bits(0x1F), 0xFF);
+        assert_eq!(expand_5_bits(0x10), 0x84);
+    }
+
+    #[test]
+    fn decode_palette_and_diff_blocks() {
+        let mut decoder = ScreenVideoV2Decoder::new();
+        let mut pool = FramePool::new();
+
+        // A single 16x16 block, using a custom palette where index 1 is red.
+        let mut keyframe = vec![0x00, 0x10, 0x00, 0x10, HAS_PALETTE_INFO];
+        let palette = compress(&[0, 0, 0, 0, 0, 255]);
+        keyframe.extend_from_slice(&(palette.len() as u16).to_be_bytes());
+        keyframe.extend_from_slice(&palette);
+        push_block(&mut keyframe, &[0b0001_0000], &[1; 16 * 16]);
+
+        assert!(decoder
+            .preload_frame(encoded(&keyframe, 0))
+            .unwrap()
+            .is_keyframe());
+        let pixels = rgba(
+            decoder
+                .decode_frame(encoded(&keyframe, 0), &mut pool)
+                .unwrap(),
+        );
+        assert!(pixels.chunks(4).all(|p| p == [255, 0, 0, 255]));
+
+        // Only update the bottom row with a 15-bit blue.
+        let mut interframe = vec![0x00, 0x10, 0x00, 0x10, 0];
+        push_block(
+            &mut interframe,
+            &[0b0001_0100, 0, 1],
+            &[0x80, 0x1F].repeat(16),
+        );
+
+        assert!(!decoder
+            .preload_frame(encoded(&interframe, 1))
+            .unwrap()
+            .is_keyframe());
+        let pixels = rgba(
+            decoder
+                .decode_frame(encoded(&interframe, 1), &mut pool)
+                .unwrap(),
+        );
+        let (top, bottom) = pixels.split_at(15 * 16 * 4);
+        assert!(top.chunks(4).all(|p| p == [255, 0, 0, 255]));
+        assert!(bottom.chunks(4).all(|p| p == [0, 0, 255, 255]));
+    }
+
+    /// Primed blocks are rejected, without losing the reference image.
+    #[test]
+    fn primed_blocks_are_unsupported() {
+        let mut decoder = ScreenVideoV2Decoder::new();
+        let mut pool = FramePool::new();
+
+        let mut keyframe = vec![0x00, 0x10, 0x00, 0x10, 0];
+        push_block(&mut keyframe, &[0], &[0, 0, 255].repeat(16 * 16));
+        decoder
+            .decode_frame(encoded(&keyframe, 0), &mut pool)
+            .unwrap();
+
+        for header in [
+            &[ZLIB_PRIME_COMPRESS_PREVIOUS][..],
+            &[ZLIB_PRIME_COMPRESS_CURRENT, 0, 0],
+        ] {
+            let mut primed = vec![0x00, 0x10, 0x00, 0x10, 0];
+            push_block(&mut primed, header, &[0, 255, 0].repeat(16 * 16));
+            assert!(decoder
+                .decode_frame(encoded(&primed, 1), &mut pool)
+                .is_err());
+        }
+
+        let mut interframe = vec![0x00, 0x10, 0x00, 0x10, 0];
+        interframe.extend_from_slice(&[0, 0]);
+        let pixels = rgba(
+            decoder
+                .decode_frame(encoded(&interframe, 2), &mut pool)
+                .unwrap(),
+        );
+        assert!(pixels.chunks(4).all(|p| p == [255, 0, 0, 255]));
+    }
+}
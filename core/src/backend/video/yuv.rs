@@ -0,0 +1,410 @@
+//! YUV 4:2:0 to RGBA conversion.
+//!
+//! Chroma planes are upsampled with bilinear interpolation, assuming chroma
+//! samples are centered between each 2x2 group of luma samples. The color
+//! conversion itself uses 16-bit fixed-point math that is carried out
+//! identically by the scalar and the SIMD code paths, so output does not
+//! depend on the platform.
+
+use crate::backend::render::YuvPlanes;
+
+/// The color matrix used to convert YUV to RGB.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ColorMatrix {
+    /// ITU-R BT.601, used by standard definition content.
+    Bt601,
+
+    /// ITU-R BT.709, used by high definition content.
+    Bt709,
+}
+
+impl ColorMatrix {
+    /// Guess the matrix used by a video of the given size.
+    ///
+    /// Video bitstreams supported by Flash do not signal their color matrix,
+    /// so this follows the common convention of treating anything taller than
+    /// PAL resolution as high definition.
+    pub fn for_size(_width: usize, height: usize) -> Self {
+        if height > 576 {
+            ColorMatrix::Bt709
+        } else {
+            ColorMatrix::Bt601
+        }
+    }
+
+    fn coefficients(self) -> Coefficients {
+        // All coefficients are in Q13 fixed point, and assume limited range
+        // input (16-235 for luma, 16-240 for chroma).
+        match self {
+            ColorMatrix::Bt601 => Coefficients {
+                y: 9539,
+                r_v: 13075,
+                g_u: 3209,
+                g_v: 6660,
+                b_u: 16525,
+            },
+            ColorMatrix::Bt709 => Coefficients {
+                y: 9539,
+                r_v: 14686,
+                g_u: 1747,
+                g_v: 4366,
+                b_u: 17305,
+            },
+        }
+    }
+}
+
+/// Fixed-point color conversion coefficients.
+#[derive(Copy, Clone, Debug)]
+struct Coefficients {
+    y: i16,
+    r_v: i16,
+    g_u: i16,
+    g_v: i16,
+    b_u: i16,
+}
+
+/// Convert planar YUV 4:2:0 data into an RGBA buffer.
+///
+/// The height of the image is determined from the length of the luma plane.
+pub fn yuv420_to_rgba(
+    y: &[u8],
+    cb: &[u8],
+    cr: &[u8],
+    width: usize,
+    chroma_width: usize,
+    matrix: ColorMatrix,
+) -> Vec<u8> {
+    if width == 0 || chroma_width == 0 {
+        return Vec::new();
+    }
+
+    let height = y.len() / width;
+    let chroma_height = cb.len().min(cr.len()) / chroma_width;
+    let coefficients = matrix.coefficients();
+
+    let mut rgba = vec![0; width * height * 4];
+    if chroma_height == 0 {
+        return rgba;
+    }
+
+    let mut cb_row = vec![0; width];
+    let mut cr_row = vec![0; width];
+    let mut scratch = vec![0; chroma_width];
+
+    for (row, (luma, out)) in y
+        .chunks_exact(width)
+        .zip(rgba.chunks_exact_mut(width * 4))
+        .enumerate()
+    {
+        upsample_row(
+            cb,
+            chroma_width,
+            chroma_height,
+            row,
+            &mut scratch,
+            &mut cb_row,
+        );
+        upsample_row(
+            cr,
+            chroma_width,
+            chroma_height,
+            row,
+            &mut scratch,
+            &mut cr_row,
+        );
+        convert_row(luma, &cb_row, &cr_row, out, coefficients);
+    }
+
+    rgba
+}
+
+impl YuvPlanes {
+    /// Convert these planes into a tightly packed RGBA image of the given
+    /// size.
+    pub fn to_rgba(&self, width: usize, height: usize) -> Vec<u8> {
+        let mut rgba = yuv420_to_rgba(
+            &self.y,
+            &self.u,
+            &self.v,
+            self.y_stride,
+            self.chroma_stride,
+            self.color_matrix,
+        );
+
+        // Remove the excess columns on the right of every row.
+        let width = width.min(self.y_stride);
+        if width < self.y_stride {
+            for row in 1..height {
+                rgba.copy_within(
+                    row * self.y_stride * 4..(row * self.y_stride + width) * 4,
+                    row * width * 4,
+                );
+            }
+        }
+        rgba.resize(width * height * 4, 0);
+
+        rgba
+    }
+}
+
+/// Produce one full-resolution row of a chroma plane.
+///
+/// `scratch` holds the vertically interpolated chroma row, scaled by 4.
+fn upsample_row(
+    plane: &[u8],
+    chroma_width: usize,
+    chroma_height: usize,
+    row: usize,
+    scratch: &mut [u16],
+    out: &mut [u8],
+) {
+    let near = (row / 2).min(chroma_height - 1);
+    let far = if row % 2 == 0 {
+        near.saturating_sub(1)
+    } else {
+        (near + 1).min(chroma_height - 1)
+    };
+
+    let near = &plane[near * chroma_width..(near + 1) * chroma_width];
+    let far = &plane[far * chroma_width..(far + 1) * chroma_width];
+    for ((scratch, near), far) in scratch.iter_mut().zip(near).zip(far) {
+        *scratch = 3 * u16::from(*near) + u16::from(*far);
+    }
+
+    for (x, out) in out.iter_mut().enumerate() {
+        let near = (x / 2).min(chroma_width - 1);
+        let far = if x % 2 == 0 {
+            near.saturating_sub(1)
+        } else {
+            (near + 1).min(chroma_width - 1)
+        };
+        *out = ((3 * scratch[near] + scratch[far] + 8) >> 4) as u8;
+    }
+}
+
+/// Convert one row of pixels, using SIMD where available.
+fn convert_row(y: &[u8], u: &[u8], v: &[u8], out: &mut [u8], coefficients: Coefficients) {
+    #[allow(unused_mut)]
+    let mut done = 0;
+
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "sse2"
+    ))]
+    {
+        done = sse2::convert_row(y, u, v, out, coefficients);
+    }
+
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        done = neon::convert_row(y, u, v, out, coefficients);
+    }
+
+    convert_row_scalar(
+        &y[done..],
+        &u[done..],
+        &v[done..],
+        &mut out[done * 4..],
+        coefficients,
+    );
+}
+
+/// The high half of a 16-bit signed multiplication, as computed by
+/// `_mm_mulhi_epi16`.
+fn mulhi(a: i16, b: i16) -> i16 {
+    ((i32::from(a) * i32::from(b)) >> 16) as i16
+}
+
+/// Round a Q3 value and clamp it to a color component.
+fn to_component(value: i16) -> u8 {
+    ((value + 4) >> 3).clamp(0, 255) as u8
+}
+
+fn convert_row_scalar(y: &[u8], u: &[u8], v: &[u8], out: &mut [u8], c: Coefficients) {
+    for (((y, u), v), out) in y.iter().zip(u).zip(v).zip(out.chunks_exact_mut(4)) {
+        let y = mulhi((i16::from(*y) - 16) << 6, c.y);
+        let u = (i16::from(*u) - 128) << 6;
+        let v = (i16::from(*v) - 128) << 6;
+
+        let r = y + mulhi(v, c.r_v);
+        let g = y - mulhi(u, c.g_u) - mulhi(v, c.g_v);
+        let b = y + mulhi(u, c.b_u);
+
+        out.copy_from_slice(&[to_component(r), to_component(g), to_component(b), 255]);
+    }
+}
+
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature = "sse2"
+))]
+mod sse2 {
+    use super::Coefficients;
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::*;
+
+    /// Convert as many whole groups of 8 pixels as possible, returning the
+    /// number of pixels converted.
+    pub fn convert_row(y: &[u8], u: &[u8], v: &[u8], out: &mut [u8], c: Coefficients) -> usize {
+        let pixels = y.len().min(u.len()).min(v.len()).min(out.len() / 4) / 8 * 8;
+
+        // SAFETY: SSE2 is statically enabled, and every load and store below
+        // stays within the first `pixels` elements of each slice.
+        unsafe {
+            let zero = _mm_setzero_si128();
+            let luma_offset = _mm_set1_epi16(16);
+            let chroma_offset = _mm_set1_epi16(128);
+            let rounding = _mm_set1_epi16(4);
+            let alpha = _mm_set1_epi8(-1);
+            let c_y = _mm_set1_epi16(c.y);
+            let c_r_v = _mm_set1_epi16(c.r_v);
+            let c_g_u = _mm_set1_epi16(c.g_u);
+            let c_g_v = _mm_set1_epi16(c.g_v);
+            let c_b_u = _mm_set1_epi16(c.b_u);
+
+            for i in (0..pixels).step_by(8) {
+                let load = |plane: &[u8], offset| {
+                    let bytes = _mm_loadl_epi64(plane.as_ptr().add(i) as *const __m128i);
+                    _mm_slli_epi16(_mm_sub_epi16(_mm_unpacklo_epi8(bytes, zero), offset), 6)
+                };
+                let y = _mm_mulhi_epi16(load(y, luma_offset), c_y);
+                let u = load(u, chroma_offset);
+                let v = load(v, chroma_offset);
+
+                let r = _mm_add_epi16(y, _mm_mulhi_epi16(v, c_r_v));
+                let g = _mm_sub_epi16(
+                    _mm_sub_epi16(y, _mm_mulhi_epi16(u, c_g_u)),
+                    _mm_mulhi_epi16(v, c_g_v),
+                );
+                let b = _mm_add_epi16(y, _mm_mulhi_epi16(u, c_b_u));
+
+                let component = |value| {
+                    _mm_packus_epi16(_mm_srai_epi16(_mm_add_epi16(value, rounding), 3), zero)
+                };
+                let rg = _mm_unpacklo_epi8(component(r), component(g));
+                let ba = _mm_unpacklo_epi8(component(b), alpha);
+
+                let dst = out.as_mut_ptr().add(i * 4) as *mut __m128i;
+                _mm_storeu_si128(dst, _mm_unpacklo_epi16(rg, ba));
+                _mm_storeu_si128(dst.add(1), _mm_unpackhi_epi16(rg, ba));
+            }
+        }
+
+        pixels
+    }
+}
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+mod neon {
+    use super::Coefficients;
+    use std::arch::aarch64::*;
+
+    /// The high half of a 16-bit signed multiplication, matching
+    /// `_mm_mulhi_epi16`.
+    #[inline(always)]
+    unsafe fn mulhi(a: int16x8_t, b: int16x8_t) -> int16x8_t {
+        let low = vmull_s16(vget_low_s16(a), vget_low_s16(b));
+        let high = vmull_s16(vget_high_s16(a), vget_high_s16(b));
+        vcombine_s16(vshrn_n_s32::<16>(low), vshrn_n_s32::<16>(high))
+    }
+
+    /// Convert as many whole groups of 8 pixels as possible, returning the
+    /// number of pixels converted.
+    pub fn convert_row(y: &[u8], u: &[u8], v: &[u8], out: &mut [u8], c: Coefficients) -> usize {
+        let pixels = y.len().min(u.len()).min(v.len()).min(out.len() / 4) / 8 * 8;
+
+        // SAFETY: NEON is statically enabled, and every load and store below
+        // stays within the first `pixels` elements of each slice.
+        unsafe {
+            let luma_offset = vdupq_n_s16(16);
+            let chroma_offset = vdupq_n_s16(128);
+            let c_y = vdupq_n_s16(c.y);
+            let c_r_v = vdupq_n_s16(c.r_v);
+            let c_g_u = vdupq_n_s16(c.g_u);
+            let c_g_v = vdupq_n_s16(c.g_v);
+            let c_b_u = vdupq_n_s16(c.b_u);
+
+            for i in (0..pixels).step_by(8) {
+                let load = |plane: &[u8], offset| {
+                    let bytes = vreinterpretq_s16_u16(vmovl_u8(vld1_u8(plane.as_ptr().add(i))));
+                    vshlq_n_s16::<6>(vsubq_s16(bytes, offset))
+                };
+                let y = mulhi(load(y, luma_offset), c_y);
+                let u = load(u, chroma_offset);
+                let v = load(v, chroma_offset);
+
+                let r = vaddq_s16(y, mulhi(v, c_r_v));
+                let g = vsubq_s16(vsubq_s16(y, mulhi(u, c_g_u)), mulhi(v, c_g_v));
+                let b = vaddq_s16(y, mulhi(u, c_b_u));
+
+                let rgba = uint8x8x4_t(
+                    vqrshrun_n_s16::<3>(r),
+                    vqrshrun_n_s16::<3>(g),
+                    vqrshrun_n_s16::<3>(b),
+                    vdup_n_u8(255),
+                );
+                vst4_u8(out.as_mut_ptr().add(i * 4), rgba);
+            }
+        }
+
+        pixels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primary_colors() {
+        // Limited range black, white and a saturated red.
+        let y = [16, 235, 81, 81];
+        let rgba = yuv420_to_rgba(&y, &[128], &[128], 4, 1, ColorMatrix::Bt601);
+        assert_eq!(&rgba[0..4], &[0, 0, 0, 255]);
+        assert_eq!(&rgba[4..8], &[255, 255, 255, 255]);
+
+        let rgba = yuv420_to_rgba(&[81; 4], &[90], &[240], 2, 1, ColorMatrix::Bt601);
+        for pixel in rgba.chunks(4) {
+            assert!(pixel[0] >= 253 && pixel[1] <= 2 && pixel[2] <= 2);
+        }
+    }
+
+    #[test]
+    fn simd_matches_scalar() {
+        let width = 37;
+        let height = 6;
+        let chroma_width = (width + 1) / 2;
+        let y: Vec<u8> = (0..width * height).map(|i| (i * 7) as u8).collect();
+        let u: Vec<u8> = (0..chroma_width * height / 2)
+            .map(|i| (i * 13) as u8)
+            .collect();
+        let v: Vec<u8> = (0..chroma_width * height / 2)
+            .map(|i| (i * 29 + 5) as u8)
+            .collect();
+
+        for matrix in [ColorMatrix::Bt601, ColorMatrix::Bt709] {
+            let rgba = yuv420_to_rgba(&y, &u, &v, width, chroma_width, matrix);
+
+            let mut expected = vec![0; rgba.len()];
+            let mut u_row = vec![0; width];
+            let mut v_row = vec![0; width];
+            let mut scratch = vec![0; chroma_width];
+            for row in 0..height {
+                upsample_row(&u, chroma_width, height / 2, row, &mut scratch, &mut u_row);
+                upsample_row(&v, chroma_width, height / 2, row, &mut scratch, &mut v_row);
+                convert_row_scalar(
+                    &y[row * width..(row + 1) * width],
+                    &u_row,
+                    &v_row,
+                    &mut expected[row * width * 4..(row + 1) * width * 4],
+                    matrix.coefficients(),
+                );
+            }
+
+            assert_eq!(rgba, expected);
+        }
+    }
+}
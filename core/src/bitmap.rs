@@ -1,5 +1,6 @@
 pub mod bitmap_data;
 pub mod color_transform_params;
+pub mod filters;
 pub mod turbulence;
 
 /// Determine if a particular bitmap data size is valid.
@@ -3,9 +3,17 @@ use gc_arena::Collect;
 use crate::avm2::{Object as Avm2Object, Value as Avm2Value};
 use crate::backend::render::{BitmapHandle, RenderBackend};
 use crate::bitmap::color_transform_params::ColorTransformParams;
+use crate::bitmap::filters::{self, BitmapFilter};
 use crate::bitmap::turbulence::Turbulence;
+use crate::color_transform::ColorTransform;
+use crate::context::{RenderContext, UpdateContext};
+use crate::display_object::{DisplayObject, TDisplayObject};
+use crate::matrix::Matrix;
+use crate::transform::{Transform, TransformStack};
 use bitflags::bitflags;
+use std::cell::Cell;
 use std::ops::Range;
+use swf::BlendMode;
 
 /// An implementation of the Lehmer/Park-Miller random number generator
 /// Uses the fixed parameters m = 2,147,483,647 and a = 16,807
@@ -128,16 +136,66 @@ bitflags! {
     }
 }
 
+/// The comparison used by `BitmapData.threshold`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThresholdOperation {
+    LessThan,
+    LessThanOrEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl ThresholdOperation {
+    pub fn from_operator(operation: &str) -> Option<Self> {
+        Some(match operation {
+            "<" => Self::LessThan,
+            "<=" => Self::LessThanOrEqual,
+            ">" => Self::GreaterThan,
+            ">=" => Self::GreaterThanOrEqual,
+            "==" => Self::Equal,
+            "!=" => Self::NotEqual,
+            _ => return None,
+        })
+    }
+
+    fn matches(self, value: u32, threshold: u32) -> bool {
+        match self {
+            Self::LessThan => value < threshold,
+            Self::LessThanOrEqual => value <= threshold,
+            Self::GreaterThan => value > threshold,
+            Self::GreaterThanOrEqual => value >= threshold,
+            Self::Equal => value == threshold,
+            Self::NotEqual => value != threshold,
+        }
+    }
+}
+
 #[derive(Clone, Collect, Default, Debug)]
 #[collect(no_drop)]
 pub struct BitmapData<'gc> {
     /// The pixels in the bitmap, stored as a array of pre-multiplied ARGB colour values
     pixels: Vec<Color>,
-    dirty: bool,
+
+    /// Whether the pixels have changed since they were last uploaded to the bitmap handle.
+    ///
+    /// This is a `Cell` so that the texture can be updated while rendering.
+    #[collect(require_static)]
+    dirty: Cell<bool>,
+
+    /// The number of times the pixels have changed.
+    ///
+    /// Every display object showing this data keeps the generation it last
+    /// rendered, to tell whether it has to be rendered again.
+    generation: u32,
     width: u32,
     height: u32,
     transparency: bool,
 
+    /// Whether `dispose` has been called, freeing the pixels.
+    disposed: bool,
+
     /// The bitmap handle for this data.
     ///
     /// This is lazily initialized; a value of `None` indicates that
@@ -160,14 +218,19 @@ impl<'gc> BitmapData<'gc> {
             Color(fill_color).to_premultiplied_alpha(self.transparency());
             width as usize * height as usize
         ];
-        self.dirty = true;
+        self.mark_changed();
+    }
+
+    pub fn disposed(&self) -> bool {
+        self.disposed
     }
 
     pub fn dispose(&mut self) {
+        self.disposed = true;
         self.width = 0;
         self.height = 0;
         self.pixels.clear();
-        self.dirty = true;
+        self.mark_changed();
     }
 
     pub fn bitmap_handle(&mut self, renderer: &mut dyn RenderBackend) -> Option<BitmapHandle> {
@@ -178,6 +241,10 @@ impl<'gc> BitmapData<'gc> {
                 log::warn!("Failed to register raw bitmap for BitmapData: {:?}", e);
             }
             self.bitmap_handle = bitmap_handle.ok();
+            if self.bitmap_handle.is_some() {
+                // The handle was registered with the current pixels.
+                self.dirty.set(false);
+            }
         }
 
         self.bitmap_handle
@@ -192,11 +259,45 @@ impl<'gc> BitmapData<'gc> {
     }
 
     pub fn dirty(&self) -> bool {
-        self.dirty
+        self.dirty.get()
     }
 
     pub fn set_dirty(&mut self, dirty: bool) {
-        self.dirty = dirty;
+        if dirty {
+            self.mark_changed();
+        } else {
+            self.dirty.set(false);
+        }
+    }
+
+    /// Marks the pixels as changed, so that they are uploaded again and every
+    /// display object showing them is rendered again.
+    fn mark_changed(&mut self) {
+        self.dirty.set(true);
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Uploads the pixels to the bitmap handle, if they have changed since the last upload.
+    ///
+    /// This does nothing if no bitmap handle has been registered for this data.
+    pub fn update_dirty_texture(&self, renderer: &mut dyn RenderBackend) {
+        if let Some(bitmap_handle) = self.bitmap_handle {
+            if self.dirty.get() {
+                if let Err(e) = renderer.update_texture(
+                    bitmap_handle,
+                    self.width(),
+                    self.height(),
+                    &self.pixels_rgba(),
+                ) {
+                    log::warn!("Failed to update texture for BitmapData: {:?}", e);
+                }
+                self.dirty.set(false);
+            }
+        }
     }
 
     pub fn pixels(&self) -> &[Color] {
@@ -208,7 +309,7 @@ impl<'gc> BitmapData<'gc> {
         self.height = height;
         self.transparency = transparency;
         self.pixels = pixels;
-        self.dirty = true;
+        self.mark_changed();
     }
 
     pub fn pixels_rgba(&self) -> Vec<u8> {
@@ -270,7 +371,7 @@ impl<'gc> BitmapData<'gc> {
     pub fn set_pixel32_raw(&mut self, x: u32, y: u32, color: Color) {
         let width = self.width();
         self.pixels[(x + y * width) as usize] = color;
-        self.dirty = true;
+        self.mark_changed();
     }
 
     pub fn set_pixel32(&mut self, x: i32, y: i32, color: Color) {
@@ -520,30 +621,35 @@ impl<'gc> BitmapData<'gc> {
         dest_point: (i32, i32),
         alpha_source: Option<(&Self, (i32, i32), bool)>,
     ) {
-        let (src_min_x, src_min_y, src_width, src_height) = src_rect;
-        let (dest_min_x, dest_min_y) = dest_point;
-
-        for src_y in src_min_y..(src_min_y + src_height) {
-            for src_x in src_min_x..(src_min_x + src_width) {
-                let dest_x = src_x - src_min_x + dest_min_x;
-                let dest_y = src_y - src_min_y + dest_min_y;
-
-                if !source_bitmap.is_point_in_bounds(src_x, src_y)
-                    || !self.is_point_in_bounds(dest_x, dest_y)
-                {
-                    continue;
-                }
-
-                let source_color = source_bitmap
-                    .get_pixel_raw(src_x as u32, src_y as u32)
-                    .unwrap();
-
-                let mut dest_color = self.get_pixel_raw(dest_x as u32, dest_y as u32).unwrap();
+        let ((src_min_x, src_min_y, src_width, src_height), (dest_min_x, dest_min_y)) =
+            match clip_copy(
+                src_rect,
+                dest_point,
+                (source_bitmap.width(), source_bitmap.height()),
+                (self.width(), self.height()),
+            ) {
+                Some(clipped) => clipped,
+                None => return,
+            };
+
+        for y in 0..src_height {
+            for x in 0..src_width {
+                let (src_x, src_y) = (src_min_x + x, src_min_y + y);
+                let (dest_x, dest_y) = (dest_min_x + x, dest_min_y + y);
+
+                let source_color = source_bitmap.get_pixel_raw(src_x, src_y).unwrap();
+
+                let mut dest_color = self.get_pixel_raw(dest_x, dest_y).unwrap();
 
                 if let Some((alpha_bitmap, (alpha_min_x, alpha_min_y), merge_alpha)) = alpha_source
                 {
-                    let alpha_x = src_x - src_min_x + alpha_min_x;
-                    let alpha_y = src_y - src_min_y + alpha_min_y;
+                    // The alpha point lines up with the unclipped source rectangle.
+                    let alpha_offset = |alpha_min: i32, src: u32, src_min: i32| {
+                        i32::try_from(i64::from(alpha_min) + i64::from(src) - i64::from(src_min))
+                            .unwrap_or(-1)
+                    };
+                    let alpha_x = alpha_offset(alpha_min_x, src_x, src_rect.0);
+                    let alpha_y = alpha_offset(alpha_min_y, src_y, src_rect.1);
 
                     if alpha_bitmap.transparency
                         && !alpha_bitmap.is_point_in_bounds(alpha_x, alpha_y)
@@ -593,7 +699,7 @@ impl<'gc> BitmapData<'gc> {
                     };
                 }
 
-                self.set_pixel32_raw(dest_x as u32, dest_y as u32, dest_color);
+                self.set_pixel32_raw(dest_x, dest_y, dest_color);
             }
         }
     }
@@ -605,27 +711,29 @@ impl<'gc> BitmapData<'gc> {
         dest_point: (i32, i32),
         rgba_mult: (i32, i32, i32, i32),
     ) {
-        let (src_min_x, src_min_y, src_width, src_height) = src_rect;
-        let (dest_min_x, dest_min_y) = dest_point;
-
-        for src_y in src_min_y..(src_min_y + src_height) {
-            for src_x in src_min_x..(src_min_x + src_width) {
-                let dest_x = src_x - src_min_x + dest_min_x;
-                let dest_y = src_y - src_min_y + dest_min_y;
-
-                if !self.is_point_in_bounds(dest_x, dest_y)
-                    || !source_bitmap.is_point_in_bounds(src_x, src_y)
-                {
-                    continue;
-                }
+        let ((src_min_x, src_min_y, src_width, src_height), (dest_min_x, dest_min_y)) =
+            match clip_copy(
+                src_rect,
+                dest_point,
+                (source_bitmap.width(), source_bitmap.height()),
+                (self.width(), self.height()),
+            ) {
+                Some(clipped) => clipped,
+                None => return,
+            };
+
+        for y in 0..src_height {
+            for x in 0..src_width {
+                let (src_x, src_y) = (src_min_x + x, src_min_y + y);
+                let (dest_x, dest_y) = (dest_min_x + x, dest_min_y + y);
 
                 let source_color = source_bitmap
-                    .get_pixel_raw(src_x as u32, src_y as u32)
+                    .get_pixel_raw(src_x, src_y)
                     .unwrap()
                     .to_un_multiplied_alpha();
 
                 let dest_color = self
-                    .get_pixel_raw(dest_x as u32, dest_y as u32)
+                    .get_pixel_raw(dest_x, dest_y)
                     .unwrap()
                     .to_un_multiplied_alpha();
 
@@ -650,8 +758,8 @@ impl<'gc> BitmapData<'gc> {
                 let mix_color = Color::argb(alpha as u8, red as u8, green as u8, blue as u8);
 
                 self.set_pixel32_raw(
-                    dest_x as u32,
-                    dest_y as u32,
+                    dest_x,
+                    dest_y,
                     mix_color.to_premultiplied_alpha(self.transparency),
                 );
             }
@@ -670,25 +778,28 @@ impl<'gc> BitmapData<'gc> {
         dest_point: (i32, i32),
         channel_arrays: ([u32; 256], [u32; 256], [u32; 256], [u32; 256]),
     ) {
-        let (src_min_x, src_min_y, src_width, src_height) = src_rect;
-        let (dest_min_x, dest_min_y) = dest_point;
-
-        for src_y in src_min_y..(src_min_y + src_height) {
-            for src_x in src_min_x..(src_min_x + src_width) {
-                let dest_x = src_x - src_min_x + dest_min_x;
-                let dest_y = src_y - src_min_y + dest_min_y;
-
-                if !self.is_point_in_bounds(dest_x, dest_y)
-                    || !source_bitmap
-                        .unwrap_or(self)
-                        .is_point_in_bounds(src_x, src_y)
-                {
-                    continue;
-                }
+        let source_size = source_bitmap
+            .map(|source| (source.width(), source.height()))
+            .unwrap_or((self.width(), self.height()));
+        let ((src_min_x, src_min_y, src_width, src_height), (dest_min_x, dest_min_y)) =
+            match clip_copy(
+                src_rect,
+                dest_point,
+                source_size,
+                (self.width(), self.height()),
+            ) {
+                Some(clipped) => clipped,
+                None => return,
+            };
+
+        for y in 0..src_height {
+            for x in 0..src_width {
+                let (src_x, src_y) = (src_min_x + x, src_min_y + y);
+                let (dest_x, dest_y) = (dest_min_x + x, dest_min_y + y);
 
                 let source_color = source_bitmap
                     .unwrap_or(self)
-                    .get_pixel_raw(src_x as u32, src_y as u32)
+                    .get_pixel_raw(src_x, src_y)
                     .unwrap()
                     .to_un_multiplied_alpha();
 
@@ -700,7 +811,7 @@ impl<'gc> BitmapData<'gc> {
                 let sum = u32::wrapping_add(u32::wrapping_add(r, g), u32::wrapping_add(b, a));
                 let mix_color = Color(sum as i32).to_premultiplied_alpha(true);
 
-                self.set_pixel32_raw(dest_x as u32, dest_y as u32, mix_color);
+                self.set_pixel32_raw(dest_x, dest_y, mix_color);
             }
         }
     }
@@ -894,10 +1005,12 @@ impl<'gc> BitmapData<'gc> {
         if different {
             Some(Self {
                 pixels,
-                dirty: false,
+                dirty: Cell::new(false),
+                generation: 0,
                 width: bitmap.width,
                 height: bitmap.height,
                 transparency: true,
+                disposed: false,
                 bitmap_handle: None,
                 avm2_object: None,
             })
@@ -906,6 +1019,352 @@ impl<'gc> BitmapData<'gc> {
         }
     }
 
+    /// Sets pixels whose masked value passes the test against `threshold` to `color`.
+    /// Pixels that fail the test are copied from the source if `copy_source` is set.
+    /// Returns the number of pixels that passed the test.
+    ///
+    /// If the `source_bitmap` parameter is `None`, `self` is used as the source.
+    #[allow(clippy::too_many_arguments)]
+    pub fn threshold(
+        &mut self,
+        source_bitmap: Option<&Self>,
+        src_rect: (i32, i32, i32, i32),
+        dest_point: (i32, i32),
+        operation: ThresholdOperation,
+        threshold: u32,
+        color: Color,
+        mask: u32,
+        copy_source: bool,
+    ) -> u32 {
+        let threshold = threshold & mask;
+        let color = color.to_premultiplied_alpha(self.transparency);
+        let mut num_changed = 0;
+
+        let source_size = source_bitmap
+            .map(|source| (source.width(), source.height()))
+            .unwrap_or((self.width(), self.height()));
+        let ((src_min_x, src_min_y, src_width, src_height), (dest_min_x, dest_min_y)) =
+            match clip_copy(
+                src_rect,
+                dest_point,
+                source_size,
+                (self.width(), self.height()),
+            ) {
+                Some(clipped) => clipped,
+                None => return 0,
+            };
+
+        for y in 0..src_height {
+            for x in 0..src_width {
+                let source = source_bitmap.unwrap_or(self);
+                let source_color = source.get_pixel_raw(src_min_x + x, src_min_y + y).unwrap();
+
+                let value = u32::from(source_color.to_un_multiplied_alpha()) & mask;
+
+                if operation.matches(value, threshold) {
+                    num_changed += 1;
+                    self.set_pixel32_raw(dest_min_x + x, dest_min_y + y, color);
+                } else if copy_source {
+                    self.set_pixel32_raw(dest_min_x + x, dest_min_y + y, source_color);
+                }
+            }
+        }
+
+        num_changed
+    }
+
+    /// Copies `num_pixels` pixels from the source, in a pseudo-random order.
+    /// Returns the seed to pass to the next call to continue the dissolve.
+    ///
+    /// If the `source_bitmap` parameter is `None`, the pixels are set to `fill_color` instead,
+    /// as Flash does when a bitmap dissolves into itself.
+    ///
+    /// The order covers every pixel of the rectangle exactly once, but doesn't match
+    /// the exact order that Flash uses.
+    pub fn pixel_dissolve(
+        &mut self,
+        source_bitmap: Option<&Self>,
+        src_rect: (i32, i32, i32, i32),
+        dest_point: (i32, i32),
+        seed: i32,
+        num_pixels: i32,
+        fill_color: Color,
+    ) -> i32 {
+        let (src_min_x, src_min_y, src_width, src_height) = src_rect;
+        let (dest_min_x, dest_min_y) = dest_point;
+        if src_width <= 0 || src_height <= 0 {
+            return seed;
+        }
+
+        let num_total = src_width as u64 * src_height as u64;
+        // Stepping by a value coprime with the total visits every pixel once.
+        let mut step = (num_total as f64 * 0.618_033_988_7) as u64 | 1;
+        while gcd(step, num_total) != 1 {
+            step += 2;
+        }
+
+        let fill_color = fill_color.to_premultiplied_alpha(self.transparency);
+        let mut index = (seed as i64).rem_euclid(num_total as i64) as u64;
+        for _ in 0..(num_pixels.max(0) as u64).min(num_total) {
+            let src_x = src_min_x + (index % src_width as u64) as i32;
+            let src_y = src_min_y + (index / src_width as u64) as i32;
+            let dest_x = src_x - src_min_x + dest_min_x;
+            let dest_y = src_y - src_min_y + dest_min_y;
+
+            if self.is_point_in_bounds(dest_x, dest_y) {
+                let color = match source_bitmap {
+                    Some(source) if source.is_point_in_bounds(src_x, src_y) => {
+                        source.get_pixel_raw(src_x as u32, src_y as u32)
+                    }
+                    Some(_) => None,
+                    None => Some(fill_color),
+                };
+                if let Some(color) = color {
+                    self.set_pixel32_raw(dest_x as u32, dest_y as u32, color);
+                }
+            }
+
+            index = (index + step) % num_total;
+        }
+
+        index as i32
+    }
+
+    /// Whether the pixel at the given point has an alpha of at least `alpha_threshold`.
+    pub fn hit_test_point(&self, alpha_threshold: u8, point: (i32, i32)) -> bool {
+        let (x, y) = point;
+        self.is_point_in_bounds(x, y)
+            && self.get_pixel_raw(x as u32, y as u32).unwrap().alpha() >= alpha_threshold
+    }
+
+    /// Whether any pixel in the given rectangle has an alpha of at least `alpha_threshold`.
+    pub fn hit_test_rectangle(&self, alpha_threshold: u8, rect: (i32, i32, i32, i32)) -> bool {
+        let size = (self.width(), self.height());
+        let (min_x, min_y, width, height) = match clip_copy(rect, (rect.0, rect.1), size, size) {
+            Some((rect, _)) => rect,
+            None => return false,
+        };
+        (min_y..min_y + height).any(|y| {
+            (min_x..min_x + width)
+                .any(|x| self.get_pixel_raw(x, y).unwrap().alpha() >= alpha_threshold)
+        })
+    }
+
+    /// Whether this bitmap overlaps `other` at any pixel where both pass their alpha threshold.
+    /// `offset` is the position of `other` relative to this bitmap.
+    pub fn hit_test_bitmap(
+        &self,
+        alpha_threshold: u8,
+        other: &Self,
+        offset: (i32, i32),
+        other_alpha_threshold: u8,
+    ) -> bool {
+        // Clip the whole of `other` to this bitmap, as if copying it onto this one.
+        let other_rect = (0, 0, other.width() as i32, other.height() as i32);
+        let ((other_x, other_y, width, height), (min_x, min_y)) = match clip_copy(
+            other_rect,
+            offset,
+            (other.width(), other.height()),
+            (self.width(), self.height()),
+        ) {
+            Some(clipped) => clipped,
+            None => return false,
+        };
+        (0..height).any(|y| {
+            (0..width).any(|x| {
+                self.get_pixel_raw(min_x + x, min_y + y).unwrap().alpha() >= alpha_threshold
+                    && other
+                        .get_pixel_raw(other_x + x, other_y + y)
+                        .unwrap()
+                        .alpha()
+                        >= other_alpha_threshold
+            })
+        })
+    }
+
+    /// Draws another bitmap onto this one, transformed by `matrix` and `color_transform`.
+    ///
+    /// If `source_bitmap` is `None`, this bitmap is drawn onto itself.
+    /// Only the pixels inside `clip_rect` are changed.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_bitmap_data(
+        &mut self,
+        source_bitmap: Option<&Self>,
+        matrix: Matrix,
+        color_transform: &ColorTransform,
+        blend_mode: BlendMode,
+        clip_rect: Option<(i32, i32, i32, i32)>,
+        smoothing: bool,
+    ) {
+        let mut pixels = source_bitmap.unwrap_or(self).transformed_pixels(
+            matrix,
+            self.width(),
+            self.height(),
+            smoothing,
+        );
+        if !color_transform.is_identity() {
+            for pixel in &mut pixels {
+                *pixel = apply_color_transform(*pixel, color_transform);
+            }
+        }
+        self.draw_pixels(&pixels, blend_mode, clip_rect);
+    }
+
+    /// Composites pre-multiplied `pixels` of the same size as this bitmap onto it.
+    ///
+    /// Only the pixels inside `clip_rect` are changed.
+    pub fn draw_pixels(
+        &mut self,
+        pixels: &[Color],
+        blend_mode: BlendMode,
+        clip_rect: Option<(i32, i32, i32, i32)>,
+    ) {
+        let (min_x, min_y, end_x, end_y) = match clip_rect {
+            Some((x, y, width, height)) => (
+                x.max(0) as u32,
+                y.max(0) as u32,
+                x.saturating_add(width).max(0) as u32,
+                y.saturating_add(height).max(0) as u32,
+            ),
+            None => (0, 0, self.width(), self.height()),
+        };
+
+        let width = self.width();
+        for y in min_y..end_y.min(self.height()) {
+            for x in min_x..end_x.min(width) {
+                let index = (x + y * width) as usize;
+                let (dest, source) = match (self.pixels.get(index), pixels.get(index)) {
+                    (Some(dest), Some(source)) => (*dest, *source),
+                    _ => continue,
+                };
+                let mut color = blend(dest, source, blend_mode);
+                if !self.transparency() {
+                    color = color.with_alpha(255);
+                }
+                self.pixels[index] = color;
+            }
+        }
+        self.mark_changed();
+    }
+
+    /// Resamples this bitmap into a `width` by `height` buffer, transformed by `matrix`.
+    ///
+    /// Pixels outside of the transformed bitmap are transparent.
+    fn transformed_pixels(
+        &self,
+        matrix: Matrix,
+        width: u32,
+        height: u32,
+        smoothing: bool,
+    ) -> Vec<Color> {
+        let mut pixels = vec![Color(0); width as usize * height as usize];
+        if matrix.a * matrix.d - matrix.b * matrix.c == 0.0 || self.pixels.is_empty() {
+            return pixels;
+        }
+
+        let mut inverse = matrix;
+        inverse.invert();
+        let tx = inverse.tx.to_pixels() as f32;
+        let ty = inverse.ty.to_pixels() as f32;
+        let (src_width, src_height) = (self.width() as f32, self.height() as f32);
+
+        for y in 0..height {
+            for x in 0..width {
+                // Sample at the center of each destination pixel.
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                let sx = inverse.a * px + inverse.c * py + tx;
+                let sy = inverse.b * px + inverse.d * py + ty;
+                if sx < 0.0 || sy < 0.0 || sx >= src_width || sy >= src_height {
+                    continue;
+                }
+
+                pixels[(x + y * width) as usize] = if smoothing {
+                    self.sample_bilinear(sx - 0.5, sy - 0.5)
+                } else {
+                    self.get_pixel_raw(sx as u32, sy as u32)
+                        .unwrap_or_else(|| 0.into())
+                };
+            }
+        }
+        pixels
+    }
+
+    /// Bilinearly interpolates the pixels around the given position, clamping at the edges.
+    fn sample_bilinear(&self, x: f32, y: f32) -> Color {
+        let max_x = self.width() as i32 - 1;
+        let max_y = self.height() as i32 - 1;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i32, y0 as i32);
+        let pixel = |x: i32, y: i32| {
+            self.get_pixel_raw(x.clamp(0, max_x) as u32, y.clamp(0, max_y) as u32)
+                .unwrap_or_else(|| 0.into())
+        };
+        let (top_left, top_right) = (pixel(x0, y0), pixel(x0 + 1, y0));
+        let (bottom_left, bottom_right) = (pixel(x0, y0 + 1), pixel(x0 + 1, y0 + 1));
+
+        let channel = |get: fn(&Color) -> u8| {
+            let top = get(&top_left) as f32 * (1.0 - fx) + get(&top_right) as f32 * fx;
+            let bottom = get(&bottom_left) as f32 * (1.0 - fx) + get(&bottom_right) as f32 * fx;
+            (top * (1.0 - fy) + bottom * fy).round() as u8
+        };
+        Color::argb(
+            channel(Color::alpha),
+            channel(Color::red),
+            channel(Color::green),
+            channel(Color::blue),
+        )
+    }
+
+    /// Applies a filter to the `src_rect` area of another bitmap, and writes the result
+    /// to this bitmap at `dest_point`.
+    ///
+    /// If `source_bitmap` is `None`, the filter is applied to this bitmap.
+    pub fn apply_filter(
+        &mut self,
+        source_bitmap: Option<&Self>,
+        src_rect: (i32, i32, i32, i32),
+        dest_point: (i32, i32),
+        filter: &BitmapFilter,
+    ) {
+        let source = source_bitmap.unwrap_or(self);
+        let (src_x, src_y, src_width, src_height) = src_rect;
+        let min_x = src_x.max(0);
+        let min_y = src_y.max(0);
+        let end_x = src_x.saturating_add(src_width).min(source.width() as i32);
+        let end_y = src_y.saturating_add(src_height).min(source.height() as i32);
+        if min_x >= end_x || min_y >= end_y {
+            return;
+        }
+
+        let (width, height) = ((end_x - min_x) as u32, (end_y - min_y) as u32);
+        let mut pixels = Vec::with_capacity(width as usize * height as usize);
+        for y in min_y..end_y {
+            for x in min_x..end_x {
+                pixels.push(
+                    source
+                        .get_pixel_raw(x as u32, y as u32)
+                        .unwrap_or_else(|| 0.into()),
+                );
+            }
+        }
+        let pixels = filters::apply_filter(filter, width, height, &pixels);
+
+        let dest_x = dest_point.0 + (min_x - src_x);
+        let dest_y = dest_point.1 + (min_y - src_y);
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                if self.is_point_in_bounds(dest_x + x, dest_y + y) {
+                    let mut color = pixels[(x + y * width as i32) as usize];
+                    if !self.transparency() {
+                        color = color.with_alpha(255);
+                    }
+                    self.set_pixel32_raw((dest_x + x) as u32, (dest_y + y) as u32, color);
+                }
+            }
+        }
+    }
+
     pub fn object2(&self) -> Avm2Value<'gc> {
         self.avm2_object
             .map(|o| o.into())
@@ -916,3 +1375,270 @@ impl<'gc> BitmapData<'gc> {
         self.avm2_object = Some(object)
     }
 }
+
+/// Clips a copy of `src_rect` from a source of size `source_size` to `dest_point`
+/// in a destination of size `dest_size`.
+///
+/// Returns the part of the source rectangle that lands inside both bitmaps,
+/// along with the point it lands on, or `None` if no pixels would be copied.
+fn clip_copy(
+    src_rect: (i32, i32, i32, i32),
+    dest_point: (i32, i32),
+    source_size: (u32, u32),
+    dest_size: (u32, u32),
+) -> Option<((u32, u32, u32, u32), (u32, u32))> {
+    // Work in i64 so that extreme rectangles can't overflow.
+    let clip_axis = |src_min: i32, len: i32, dest_min: i32, src_len: u32, dest_len: u32| {
+        let (src_min, dest_min) = (i64::from(src_min), i64::from(dest_min));
+        let shift = dest_min - src_min;
+        let min = src_min.max(0).max(-shift);
+        let max = (src_min + i64::from(len))
+            .min(i64::from(src_len))
+            .min(i64::from(dest_len) - shift);
+        if min < max {
+            Some((min as u32, (max - min) as u32, (min + shift) as u32))
+        } else {
+            None
+        }
+    };
+
+    let (src_x, width, dest_x) = clip_axis(
+        src_rect.0,
+        src_rect.2,
+        dest_point.0,
+        source_size.0,
+        dest_size.0,
+    )?;
+    let (src_y, height, dest_y) = clip_axis(
+        src_rect.1,
+        src_rect.3,
+        dest_point.1,
+        source_size.1,
+        dest_size.1,
+    )?;
+    Some(((src_x, src_y, width, height), (dest_x, dest_y)))
+}
+
+/// Render a display object offscreen, ignoring its own transform, and return
+/// the pre-multiplied pixels.
+///
+/// Returns `None` if the renderer can't render offscreen.
+pub fn render_display_object<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    source: DisplayObject<'gc>,
+    transform: Transform,
+    width: u32,
+    height: u32,
+) -> Option<Vec<Color>> {
+    let ui = &mut *context.ui;
+    let library = &*context.library;
+    let stage = context.stage;
+    let bitmap = context.renderer.render_offscreen(
+        width,
+        height,
+        swf::Color::from_rgb(0, 0),
+        &mut |renderer| {
+            let mut transform_stack = TransformStack::new();
+            transform_stack.push(&transform);
+            let mut render_context = RenderContext {
+                renderer,
+                ui: &mut *ui,
+                library,
+                transform_stack: &mut transform_stack,
+                stage,
+                clip_depth_stack: vec![],
+                allow_mask: true,
+                scaling_grid: None,
+            };
+            source.render_self(&mut render_context);
+        },
+    )?;
+    if bitmap.width != width || bitmap.height != height {
+        return None;
+    }
+
+    // The renderer returns un-multiplied pixels.
+    let pixels: Vec<i32> = bitmap.data.into();
+    Some(
+        pixels
+            .into_iter()
+            .map(|p| Color::from(p).to_premultiplied_alpha(true))
+            .collect(),
+    )
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        let t = b;
+        b = a % b;
+        a = t;
+    }
+    a
+}
+
+/// Applies a color transform to a pre-multiplied color.
+fn apply_color_transform(color: Color, color_transform: &ColorTransform) -> Color {
+    let color = color.to_un_multiplied_alpha();
+    let [r_mult, g_mult, b_mult, a_mult] = color_transform.mult_rgba_normalized();
+    let transform =
+        |value: u8, mult: f32, add: i16| (value as f32 * mult + add as f32).clamp(0.0, 255.0) as u8;
+    Color::argb(
+        transform(color.alpha(), a_mult, color_transform.a_add),
+        transform(color.red(), r_mult, color_transform.r_add),
+        transform(color.green(), g_mult, color_transform.g_add),
+        transform(color.blue(), b_mult, color_transform.b_add),
+    )
+    .to_premultiplied_alpha(true)
+}
+
+/// Composites the pre-multiplied `source` color onto `dest` with the given blend mode.
+///
+/// This is also used by the software render backend to composite blend layers.
+pub fn blend(dest: Color, source: Color, blend_mode: BlendMode) -> Color {
+    let to_u8 = |value: f32| (value * 255.0).round().clamp(0.0, 255.0) as u8;
+    let source_alpha = source.alpha() as f32 / 255.0;
+    let dest_alpha = dest.alpha() as f32 / 255.0;
+
+    let scale = |color: Color, factor: f32| {
+        let channel = |value: u8| to_u8(value as f32 / 255.0 * factor);
+        Color::argb(
+            channel(color.alpha()),
+            channel(color.red()),
+            channel(color.green()),
+            channel(color.blue()),
+        )
+    };
+
+    let channel = |source: u8, dest: u8| {
+        let source = source as f32 / 255.0;
+        let dest = dest as f32 / 255.0;
+        let source_color = if source_alpha > 0.0 {
+            source / source_alpha
+        } else {
+            0.0
+        };
+        let dest_color = if dest_alpha > 0.0 {
+            dest / dest_alpha
+        } else {
+            0.0
+        };
+        let mixed = match blend_mode {
+            BlendMode::Multiply => source_color * dest_color,
+            BlendMode::Screen => source_color + dest_color - source_color * dest_color,
+            BlendMode::Lighten => source_color.max(dest_color),
+            BlendMode::Darken => source_color.min(dest_color),
+            BlendMode::Difference => (source_color - dest_color).abs(),
+            BlendMode::Add => (source_color + dest_color).min(1.0),
+            BlendMode::Subtract => (dest_color - source_color).max(0.0),
+            BlendMode::Invert => 1.0 - dest_color,
+            BlendMode::Overlay => hard_light(dest_color, source_color),
+            BlendMode::HardLight => hard_light(source_color, dest_color),
+            _ => source_color,
+        };
+        to_u8(
+            source * (1.0 - dest_alpha)
+                + dest * (1.0 - source_alpha)
+                + source_alpha * dest_alpha * mixed,
+        )
+    };
+
+    match blend_mode {
+        BlendMode::Alpha => scale(dest, source_alpha),
+        BlendMode::Erase => scale(dest, 1.0 - source_alpha),
+        _ => Color::argb(
+            to_u8(source_alpha + dest_alpha * (1.0 - source_alpha)),
+            channel(source.red(), dest.red()),
+            channel(source.green(), dest.green()),
+            channel(source.blue(), dest.blue()),
+        ),
+    }
+}
+
+/// The hard light blend of `top` onto `bottom`, which is also overlay with the arguments swapped.
+fn hard_light(top: f32, bottom: f32) -> f32 {
+    if top <= 0.5 {
+        2.0 * top * bottom
+    } else {
+        1.0 - 2.0 * (1.0 - top) * (1.0 - bottom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bitmap(width: u32, height: u32, fill_color: i32) -> BitmapData<'static> {
+        let mut bitmap = BitmapData::default();
+        bitmap.init_pixels(width, height, true, fill_color);
+        bitmap
+    }
+
+    #[test]
+    fn clip_copy_clips_to_both_bitmaps() {
+        assert_eq!(
+            clip_copy((-2, 1, 10, 10), (3, -1), (8, 8), (6, 6)),
+            Some(((0, 2, 3, 6), (5, 0)))
+        );
+        assert_eq!(clip_copy((0, 0, -5, 5), (0, 0), (8, 8), (8, 8)), None);
+        assert_eq!(clip_copy((0, 0, 5, 5), (8, 0), (8, 8), (8, 8)), None);
+    }
+
+    #[test]
+    fn threshold_handles_extreme_rectangles() {
+        let source = bitmap(4, 4, 0xFF00FF00u32 as i32);
+        let mut dest = bitmap(4, 4, 0);
+        let changed = dest.threshold(
+            Some(&source),
+            (i32::MIN, i32::MIN, i32::MAX, i32::MAX),
+            (0, 0),
+            ThresholdOperation::Equal,
+            0xFF00FF00,
+            Color::argb(255, 255, 0, 0),
+            0xFFFFFFFF,
+            false,
+        );
+        assert_eq!(changed, 0);
+
+        let changed = dest.threshold(
+            Some(&source),
+            (1, 1, i32::MAX, i32::MAX),
+            (i32::MAX, 0),
+            ThresholdOperation::Equal,
+            0xFF00FF00,
+            Color::argb(255, 255, 0, 0),
+            0xFFFFFFFF,
+            false,
+        );
+        assert_eq!(changed, 0);
+
+        let changed = dest.threshold(
+            Some(&source),
+            (0, 0, i32::MAX, i32::MAX),
+            (2, 2),
+            ThresholdOperation::Equal,
+            0xFF00FF00,
+            Color::argb(255, 255, 0, 0),
+            0xFFFFFFFF,
+            false,
+        );
+        assert_eq!(changed, 4);
+        assert_eq!(dest.get_pixel32(3, 3), Color::argb(255, 255, 0, 0));
+        assert_eq!(dest.get_pixel32(1, 1), Color::argb(0, 0, 0, 0));
+    }
+
+    #[test]
+    fn hit_tests_handle_extreme_rectangles() {
+        let mut bitmap = bitmap(4, 4, 0);
+        bitmap.set_pixel32(3, 3, Color::argb(255, 255, 255, 255));
+
+        assert!(!bitmap.hit_test_rectangle(1, (i32::MIN, i32::MIN, i32::MAX, i32::MAX)));
+        assert!(bitmap.hit_test_rectangle(1, (3, 3, i32::MAX, i32::MAX)));
+        assert!(!bitmap.hit_test_rectangle(1, (i32::MAX, 0, i32::MAX, 4)));
+
+        let other = self::bitmap(2, 2, 0xFFFFFFFFu32 as i32);
+        assert!(bitmap.hit_test_bitmap(1, &other, (2, 2), 1));
+        assert!(!bitmap.hit_test_bitmap(1, &other, (0, 0), 1));
+        assert!(!bitmap.hit_test_bitmap(1, &other, (i32::MAX, i32::MAX), 1));
+        assert!(!bitmap.hit_test_bitmap(1, &other, (i32::MIN, i32::MIN), 1));
+    }
+}
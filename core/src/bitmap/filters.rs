@@ -0,0 +1,844 @@
+//! CPU implementations of the bitmap filters, as used by `BitmapData.applyFilter`.
+//!
+//! All filters take and return pre-multiplied pixels. Pixels outside of the
+//! image are treated as transparent.
+
+use crate::bitmap::bitmap_data::Color;
+
+/// A pixel with pre-multiplied red, green, blue and alpha channels in `0.0..=1.0`.
+type Pixel = [f32; 4];
+
+const TRANSPARENT: Pixel = [0.0; 4];
+
+#[derive(Clone, Debug)]
+pub enum BitmapFilter {
+    Blur(BlurFilter),
+
+    /// A glow, which is drawn as a drop shadow with no distance.
+    Glow(ShadowFilter),
+    DropShadow(ShadowFilter),
+    Bevel(BevelFilter),
+    GradientGlow(GradientFilter),
+    GradientBevel(GradientFilter),
+    ColorMatrix(ColorMatrixFilter),
+    Convolution(ConvolutionFilter),
+    DisplacementMap(DisplacementMapFilter),
+}
+
+impl BitmapFilter {
+    /// How far this filter can spread the image, in pixels on each side.
+    pub fn padding(&self) -> (u32, u32) {
+        let blur_padding = |blur: &BlurFilter| {
+            let passes = blur.passes() as f64;
+            (
+                (blur.blur_x / 2.0).ceil() * passes,
+                (blur.blur_y / 2.0).ceil() * passes,
+            )
+        };
+        let offset_padding = |blur: &BlurFilter, distance: f64, angle: f64| {
+            let (x, y) = blur_padding(blur);
+            let (dx, dy) = offset(distance, angle);
+            (x + dx.unsigned_abs() as f64, y + dy.unsigned_abs() as f64)
+        };
+
+        let (x, y) = match self {
+            BitmapFilter::Blur(blur) => blur_padding(blur),
+            BitmapFilter::Glow(shadow) | BitmapFilter::DropShadow(shadow) => {
+                offset_padding(&shadow.blur, shadow.distance, shadow.angle)
+            }
+            BitmapFilter::Bevel(bevel) => offset_padding(&bevel.blur, bevel.distance, bevel.angle),
+            BitmapFilter::GradientGlow(filter) | BitmapFilter::GradientBevel(filter) => {
+                offset_padding(&filter.blur, filter.distance, filter.angle)
+            }
+            BitmapFilter::ColorMatrix(_) => (0.0, 0.0),
+            BitmapFilter::Convolution(convolution) => (
+                (convolution.matrix_x / 2) as f64,
+                (convolution.matrix_y / 2) as f64,
+            ),
+            BitmapFilter::DisplacementMap(displacement) => (
+                (displacement.scale_x.abs() / 2.0).ceil(),
+                (displacement.scale_y.abs() / 2.0).ceil(),
+            ),
+        };
+        (x.clamp(0.0, 4096.0) as u32, y.clamp(0.0, 4096.0) as u32)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BlurFilter {
+    pub blur_x: f64,
+    pub blur_y: f64,
+
+    /// The number of times the box blur is applied.
+    pub quality: i32,
+}
+
+impl BlurFilter {
+    /// The number of times the box blur is applied in each direction.
+    pub fn passes(&self) -> usize {
+        self.quality.clamp(0, 15) as usize
+    }
+
+    /// The width and height of the box blur window, in pixels.
+    pub fn box_size(&self) -> (usize, usize) {
+        (
+            self.blur_x.round().clamp(1.0, 255.0) as usize,
+            self.blur_y.round().clamp(1.0, 255.0) as usize,
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BevelType {
+    Inner,
+    Outer,
+    Full,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ShadowFilter {
+    /// The RGB color of the shadow.
+    pub color: u32,
+    pub alpha: f64,
+    pub blur: BlurFilter,
+    pub strength: f64,
+    pub distance: f64,
+
+    /// The angle of the offset of the shadow, in degrees.
+    pub angle: f64,
+    pub inner: bool,
+    pub knockout: bool,
+
+    /// Whether only the shadow is drawn, without the source image.
+    pub hide_object: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BevelFilter {
+    pub highlight_color: u32,
+    pub highlight_alpha: f64,
+    pub shadow_color: u32,
+    pub shadow_alpha: f64,
+    pub blur: BlurFilter,
+    pub strength: f64,
+    pub distance: f64,
+    pub angle: f64,
+    pub bevel_type: BevelType,
+    pub knockout: bool,
+}
+
+/// A gradient glow or gradient bevel.
+#[derive(Clone, Debug)]
+pub struct GradientFilter {
+    pub colors: Vec<u32>,
+    pub alphas: Vec<f64>,
+    pub ratios: Vec<u8>,
+    pub blur: BlurFilter,
+    pub strength: f64,
+    pub distance: f64,
+    pub angle: f64,
+    pub bevel_type: BevelType,
+    pub knockout: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ColorMatrixFilter {
+    /// A 4x5 matrix in row-major order, applied to un-multiplied RGBA values in `0..=255`.
+    pub matrix: [f64; 4 * 5],
+}
+
+impl ColorMatrixFilter {
+    /// The matrix as a column-major 4x4 matrix and an offset, applied to
+    /// un-multiplied RGBA values in `0.0..=1.0`, as used by shaders.
+    pub fn normalized(&self) -> ([[f32; 4]; 4], [f32; 4]) {
+        let m = self.matrix.map(|value| value as f32);
+        (
+            [0, 1, 2, 3].map(|column| [m[column], m[5 + column], m[10 + column], m[15 + column]]),
+            [m[4], m[9], m[14], m[19]].map(|offset| offset / 255.0),
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ConvolutionFilter {
+    pub matrix_x: u8,
+    pub matrix_y: u8,
+    pub matrix: Vec<f64>,
+    pub divisor: f64,
+    pub bias: f64,
+    pub preserve_alpha: bool,
+
+    /// Whether pixels outside of the image are clamped to the edge, instead of
+    /// using `color` and `alpha`.
+    pub clamp: bool,
+    pub color: u32,
+    pub alpha: f64,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DisplacementMode {
+    Wrap,
+    Clamp,
+    Ignore,
+    Color,
+}
+
+#[derive(Clone, Debug)]
+pub struct DisplacementMapFilter {
+    /// The pre-multiplied pixels of the displacement map.
+    pub map: Vec<Color>,
+    pub map_width: u32,
+    pub map_height: u32,
+
+    /// The position of the map relative to the filtered image.
+    pub map_point: (i32, i32),
+
+    /// The channel used for each axis, as a `BitmapDataChannel` value.
+    pub component_x: i32,
+    pub component_y: i32,
+    pub scale_x: f64,
+    pub scale_y: f64,
+    pub mode: DisplacementMode,
+    pub color: u32,
+    pub alpha: f64,
+}
+
+/// Applies a filter to a `width` by `height` image, returning the filtered image.
+pub fn apply_filter(
+    filter: &BitmapFilter,
+    width: u32,
+    height: u32,
+    pixels: &[Color],
+) -> Vec<Color> {
+    let image = Image {
+        width: width as usize,
+        height: height as usize,
+        pixels: pixels.iter().copied().map(color_to_pixel).collect(),
+    };
+    let pixels = match filter {
+        BitmapFilter::Blur(blur) => blur_image(&image, blur),
+        BitmapFilter::Glow(shadow) | BitmapFilter::DropShadow(shadow) => {
+            shadow_image(&image, shadow)
+        }
+        BitmapFilter::Bevel(bevel) => bevel_image(&image, bevel),
+        BitmapFilter::GradientGlow(glow) => gradient_glow_image(&image, glow),
+        BitmapFilter::GradientBevel(bevel) => gradient_bevel_image(&image, bevel),
+        BitmapFilter::ColorMatrix(color_matrix) => color_matrix_image(&image, color_matrix),
+        BitmapFilter::Convolution(convolution) => convolution_image(&image, convolution),
+        BitmapFilter::DisplacementMap(displacement) => displacement_image(&image, displacement),
+    };
+    pixels.into_iter().map(pixel_to_color).collect()
+}
+
+/// Applies a chain of filters to an image in un-multiplied RGBA, such as one
+/// rendered offscreen by the renderer.
+///
+/// Returns the filtered image in pre-multiplied RGBA, ready to upload as a texture.
+pub fn apply_filters_rgba(
+    filters: &[BitmapFilter],
+    width: u32,
+    height: u32,
+    rgba: &[u8],
+) -> Vec<u8> {
+    apply_filters_accelerated(filters, width, height, rgba, |_| false, |_, _| None)
+}
+
+/// Applies a chain of filters like `apply_filters_rgba`, letting a renderer
+/// run the filters that it has shaders for.
+///
+/// Each run of consecutive filters accepted by `supported` is passed to `run`
+/// with the image in pre-multiplied RGBA. Other filters, and any run that
+/// `run` fails on, are applied on the CPU.
+pub fn apply_filters_accelerated(
+    filters: &[BitmapFilter],
+    width: u32,
+    height: u32,
+    rgba: &[u8],
+    supported: impl Fn(&BitmapFilter) -> bool,
+    mut run: impl FnMut(&[BitmapFilter], &[u8]) -> Option<Vec<u8>>,
+) -> Vec<u8> {
+    let mut pixels: Vec<u8> = rgba
+        .chunks_exact(4)
+        .flat_map(|p| {
+            let color = Color::argb(p[3], p[0], p[1], p[2]).to_premultiplied_alpha(true);
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+
+    let mut filters = filters;
+    while !filters.is_empty() {
+        let count = filters
+            .iter()
+            .take_while(|filter| supported(filter))
+            .count();
+        if count > 0 {
+            if let Some(output) = run(&filters[..count], &pixels) {
+                pixels = output;
+                filters = &filters[count..];
+                continue;
+            }
+        }
+
+        // Run the first filter on the CPU, and try the rest again.
+        let colors: Vec<Color> = pixels
+            .chunks_exact(4)
+            .map(|p| Color::argb(p[3], p[0], p[1], p[2]))
+            .collect();
+        pixels = apply_filter(&filters[0], width, height, &colors)
+            .iter()
+            .flat_map(|p| [p.red(), p.green(), p.blue(), p.alpha()])
+            .collect();
+        filters = &filters[1..];
+    }
+    pixels
+}
+
+struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Pixel>,
+}
+
+impl Image {
+    fn get(&self, x: isize, y: isize) -> Option<Pixel> {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return None;
+        }
+        Some(self.pixels[x as usize + y as usize * self.width])
+    }
+
+    fn positions(&self) -> impl Iterator<Item = (isize, isize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x as isize, y as isize)))
+    }
+}
+
+fn blur_image(image: &Image, blur: &BlurFilter) -> Vec<Pixel> {
+    let mut channels: Vec<Vec<f32>> = (0..4)
+        .map(|channel| image.pixels.iter().map(|p| p[channel]).collect())
+        .collect();
+    for channel in &mut channels {
+        blur_plane(channel, image.width, image.height, blur, 0.0);
+    }
+    (0..image.pixels.len())
+        .map(|i| {
+            [
+                channels[0][i],
+                channels[1][i],
+                channels[2][i],
+                channels[3][i],
+            ]
+        })
+        .collect()
+}
+
+/// Box blurs a single channel in place, `quality` times in each direction.
+///
+/// Values outside of the plane are taken to be `padding`.
+fn blur_plane(plane: &mut [f32], width: usize, height: usize, blur: &BlurFilter, padding: f32) {
+    let passes = blur.passes();
+    let (size_x, size_y) = blur.box_size();
+    let mut line = Vec::new();
+    for _ in 0..passes {
+        if size_x > 1 {
+            for y in 0..height {
+                line.clear();
+                line.extend((0..width).map(|x| plane[x + y * width]));
+                for (x, value) in box_blur_line(&line, size_x, padding).enumerate() {
+                    plane[x + y * width] = value;
+                }
+            }
+        }
+        if size_y > 1 {
+            for x in 0..width {
+                line.clear();
+                line.extend((0..height).map(|y| plane[x + y * width]));
+                for (y, value) in box_blur_line(&line, size_y, padding).enumerate() {
+                    plane[x + y * width] = value;
+                }
+            }
+        }
+    }
+}
+
+/// Averages each value of `line` with its neighbours in a window of `size` values.
+fn box_blur_line(line: &[f32], size: usize, padding: f32) -> impl Iterator<Item = f32> + '_ {
+    let left = (size - 1) / 2;
+    let right = size - 1 - left;
+    let mut prefix = Vec::with_capacity(line.len() + 1);
+    prefix.push(0.0);
+    for value in line {
+        prefix.push(prefix.last().unwrap() + value);
+    }
+
+    (0..line.len()).map(move |i| {
+        let start = i.saturating_sub(left);
+        let end = (i + right + 1).min(line.len());
+        let outside = size - (end - start);
+        (prefix[end] - prefix[start] + padding * outside as f32) / size as f32
+    })
+}
+
+/// The blurred alpha channel of an image, which is inverted for inner effects.
+fn blurred_alpha(image: &Image, blur: &BlurFilter, invert: bool) -> Vec<f32> {
+    let mut alpha: Vec<f32> = image
+        .pixels
+        .iter()
+        .map(|p| if invert { 1.0 - p[3] } else { p[3] })
+        .collect();
+    let padding = if invert { 1.0 } else { 0.0 };
+    blur_plane(&mut alpha, image.width, image.height, blur, padding);
+    alpha
+}
+
+/// The offset of `distance` in the direction of `angle`, in degrees.
+fn offset(distance: f64, angle: f64) -> (isize, isize) {
+    let angle = angle.to_radians();
+    (
+        (distance * angle.cos()).round() as isize,
+        (distance * angle.sin()).round() as isize,
+    )
+}
+
+/// Reads a value from a plane, returning `padding` outside of it.
+fn plane_value(plane: &[f32], image: &Image, x: isize, y: isize, padding: f32) -> f32 {
+    if x < 0 || y < 0 || x as usize >= image.width || y as usize >= image.height {
+        return padding;
+    }
+    plane[x as usize + y as usize * image.width]
+}
+
+fn shadow_image(image: &Image, shadow: &ShadowFilter) -> Vec<Pixel> {
+    let alpha = blurred_alpha(image, &shadow.blur, shadow.inner);
+    let padding = if shadow.inner { 1.0 } else { 0.0 };
+    let (dx, dy) = offset(shadow.distance, shadow.angle);
+    let color = rgb_pixel(shadow.color);
+    let bevel_type = if shadow.inner {
+        BevelType::Inner
+    } else {
+        BevelType::Outer
+    };
+
+    image
+        .positions()
+        .map(|(x, y)| {
+            let source = image.get(x, y).unwrap_or(TRANSPARENT);
+            let amount = plane_value(&alpha, image, x - dx, y - dy, padding);
+            let amount = (amount * shadow.strength as f32).min(1.0) * shadow.alpha as f32;
+            let effect = scale(color, amount);
+            if shadow.hide_object && !shadow.inner {
+                effect
+            } else {
+                composite(effect, source, bevel_type, shadow.knockout)
+            }
+        })
+        .collect()
+}
+
+fn bevel_image(image: &Image, bevel: &BevelFilter) -> Vec<Pixel> {
+    let alpha = blurred_alpha(image, &bevel.blur, false);
+    let (dx, dy) = offset(bevel.distance, bevel.angle);
+    let highlight = scale(
+        rgb_pixel(bevel.highlight_color),
+        bevel.highlight_alpha as f32,
+    );
+    let shadow = scale(rgb_pixel(bevel.shadow_color), bevel.shadow_alpha as f32);
+
+    image
+        .positions()
+        .map(|(x, y)| {
+            let source = image.get(x, y).unwrap_or(TRANSPARENT);
+            let lit = plane_value(&alpha, image, x + dx, y + dy, 0.0);
+            let shaded = plane_value(&alpha, image, x - dx, y - dy, 0.0);
+            let strength = bevel.strength as f32;
+            let effect = add(
+                scale(highlight, ((lit - shaded) * strength).clamp(0.0, 1.0)),
+                scale(shadow, ((shaded - lit) * strength).clamp(0.0, 1.0)),
+            );
+            composite(effect, source, bevel.bevel_type, bevel.knockout)
+        })
+        .collect()
+}
+
+fn gradient_glow_image(image: &Image, glow: &GradientFilter) -> Vec<Pixel> {
+    let inner = glow.bevel_type == BevelType::Inner;
+    let alpha = blurred_alpha(image, &glow.blur, inner);
+    let padding = if inner { 1.0 } else { 0.0 };
+    let (dx, dy) = offset(glow.distance, glow.angle);
+    let gradient = gradient_lut(&glow.colors, &glow.alphas, &glow.ratios);
+
+    image
+        .positions()
+        .map(|(x, y)| {
+            let source = image.get(x, y).unwrap_or(TRANSPARENT);
+            let amount = plane_value(&alpha, image, x - dx, y - dy, padding);
+            let amount = (amount * glow.strength as f32).clamp(0.0, 1.0);
+            let effect = gradient[(amount * 255.0).round() as usize];
+            composite(effect, source, glow.bevel_type, glow.knockout)
+        })
+        .collect()
+}
+
+fn gradient_bevel_image(image: &Image, bevel: &GradientFilter) -> Vec<Pixel> {
+    let alpha = blurred_alpha(image, &bevel.blur, false);
+    let (dx, dy) = offset(bevel.distance, bevel.angle);
+    let gradient = gradient_lut(&bevel.colors, &bevel.alphas, &bevel.ratios);
+
+    image
+        .positions()
+        .map(|(x, y)| {
+            let source = image.get(x, y).unwrap_or(TRANSPARENT);
+            let lit = plane_value(&alpha, image, x + dx, y + dy, 0.0);
+            let shaded = plane_value(&alpha, image, x - dx, y - dy, 0.0);
+            // The shadow side is at the start of the gradient, and the highlight at the end.
+            let amount = ((lit - shaded) * bevel.strength as f32).clamp(-1.0, 1.0);
+            let effect = gradient[((amount + 1.0) * 127.5).round() as usize];
+            composite(effect, source, bevel.bevel_type, bevel.knockout)
+        })
+        .collect()
+}
+
+fn color_matrix_image(image: &Image, filter: &ColorMatrixFilter) -> Vec<Pixel> {
+    let m = filter.matrix.map(|value| value as f32);
+    image
+        .pixels
+        .iter()
+        .map(|&pixel| {
+            let [r, g, b, a] = unmultiply(pixel).map(|value| value * 255.0);
+            let row = |i: usize| {
+                (m[i] * r + m[i + 1] * g + m[i + 2] * b + m[i + 3] * a + m[i + 4]).clamp(0.0, 255.0)
+                    / 255.0
+            };
+            premultiply([row(0), row(5), row(10), row(15)])
+        })
+        .collect()
+}
+
+fn convolution_image(image: &Image, filter: &ConvolutionFilter) -> Vec<Pixel> {
+    let (matrix_x, matrix_y) = (filter.matrix_x as isize, filter.matrix_y as isize);
+    if filter.matrix.len() < (matrix_x * matrix_y) as usize {
+        return image.pixels.clone();
+    }
+
+    let divisor = if filter.divisor == 0.0 {
+        1.0
+    } else {
+        filter.divisor as f32
+    };
+    let bias = filter.bias as f32 / 255.0;
+    let edge_color = unmultiply(scale(rgb_pixel(filter.color), filter.alpha as f32));
+    let max_x = image.width as isize - 1;
+    let max_y = image.height as isize - 1;
+
+    image
+        .positions()
+        .map(|(x, y)| {
+            let mut sum = [0.0; 4];
+            for ky in 0..matrix_y {
+                for kx in 0..matrix_x {
+                    let weight = filter.matrix[(kx + ky * matrix_x) as usize] as f32;
+                    let (sx, sy) = (x + kx - matrix_x / 2, y + ky - matrix_y / 2);
+                    let sample = if filter.clamp {
+                        unmultiply(image.get(sx.clamp(0, max_x), sy.clamp(0, max_y)).unwrap())
+                    } else {
+                        image.get(sx, sy).map(unmultiply).unwrap_or(edge_color)
+                    };
+                    for (sum, sample) in sum.iter_mut().zip(sample) {
+                        *sum += sample * weight;
+                    }
+                }
+            }
+
+            let mut result = sum.map(|value| (value / divisor + bias).clamp(0.0, 1.0));
+            if filter.preserve_alpha {
+                result[3] = image.get(x, y).unwrap()[3];
+            }
+            premultiply(result)
+        })
+        .collect()
+}
+
+fn displacement_image(image: &Image, filter: &DisplacementMapFilter) -> Vec<Pixel> {
+    let component = |color: Color, channel: i32| -> f32 {
+        let color = color.to_un_multiplied_alpha();
+        match channel {
+            1 => color.red() as f32,
+            2 => color.green() as f32,
+            4 => color.blue() as f32,
+            8 => color.alpha() as f32,
+            _ => 128.0,
+        }
+    };
+    let outside_color = scale(rgb_pixel(filter.color), filter.alpha as f32);
+    let (width, height) = (image.width as isize, image.height as isize);
+
+    image
+        .positions()
+        .map(|(x, y)| {
+            let source = image.get(x, y).unwrap_or(TRANSPARENT);
+            let (map_x, map_y) = (
+                x - filter.map_point.0 as isize,
+                y - filter.map_point.1 as isize,
+            );
+            if map_x < 0
+                || map_y < 0
+                || map_x >= filter.map_width as isize
+                || map_y >= filter.map_height as isize
+            {
+                return source;
+            }
+            let map_color = match filter
+                .map
+                .get(map_x as usize + map_y as usize * filter.map_width as usize)
+            {
+                Some(color) => *color,
+                None => return source,
+            };
+
+            let dx =
+                (component(map_color, filter.component_x) - 128.0) * filter.scale_x as f32 / 256.0;
+            let dy =
+                (component(map_color, filter.component_y) - 128.0) * filter.scale_y as f32 / 256.0;
+            let (sx, sy) = (x + dx.round() as isize, y + dy.round() as isize);
+            match filter.mode {
+                DisplacementMode::Wrap => image
+                    .get(sx.rem_euclid(width), sy.rem_euclid(height))
+                    .unwrap_or(TRANSPARENT),
+                DisplacementMode::Clamp => image
+                    .get(sx.clamp(0, width - 1), sy.clamp(0, height - 1))
+                    .unwrap_or(TRANSPARENT),
+                DisplacementMode::Ignore => image.get(sx, sy).unwrap_or(source),
+                DisplacementMode::Color => image.get(sx, sy).unwrap_or(outside_color),
+            }
+        })
+        .collect()
+}
+
+/// Combines a filter effect with the source pixel.
+///
+/// Inner effects are drawn on top of the source, clipped to it. Outer effects
+/// are drawn behind the source, and full effects on top of it. Knocking out
+/// leaves only the effect, in the area it would have been visible in.
+fn composite(effect: Pixel, source: Pixel, bevel_type: BevelType, knockout: bool) -> Pixel {
+    match (bevel_type, knockout) {
+        (BevelType::Inner, false) => add(scale(effect, source[3]), scale(source, 1.0 - effect[3])),
+        (BevelType::Inner, true) => scale(effect, source[3]),
+        (BevelType::Outer, false) => over(source, effect),
+        (BevelType::Outer, true) => scale(effect, 1.0 - source[3]),
+        (BevelType::Full, false) => over(effect, source),
+        (BevelType::Full, true) => effect,
+    }
+}
+
+/// Builds a table of 256 pre-multiplied colors from the stops of a gradient.
+fn gradient_lut(colors: &[u32], alphas: &[f64], ratios: &[u8]) -> Vec<Pixel> {
+    let stops: Vec<(f32, Pixel)> = colors
+        .iter()
+        .zip(alphas)
+        .zip(ratios)
+        .map(|((&color, &alpha), &ratio)| {
+            let [r, g, b, _] = rgb_pixel(color);
+            (ratio as f32, [r, g, b, alpha as f32])
+        })
+        .collect();
+    if stops.is_empty() {
+        return vec![TRANSPARENT; 256];
+    }
+
+    (0..256)
+        .map(|i| {
+            let i = i as f32;
+            let next = stops.iter().position(|(ratio, _)| *ratio >= i);
+            let color = match next {
+                Some(0) => stops[0].1,
+                None => stops[stops.len() - 1].1,
+                Some(next) => {
+                    let (start_ratio, start) = stops[next - 1];
+                    let (end_ratio, end) = stops[next];
+                    let t = if end_ratio > start_ratio {
+                        (i - start_ratio) / (end_ratio - start_ratio)
+                    } else {
+                        1.0
+                    };
+                    [0, 1, 2, 3].map(|c| start[c] + (end[c] - start[c]) * t)
+                }
+            };
+            premultiply(color)
+        })
+        .collect()
+}
+
+/// An opaque pixel of the given RGB color.
+fn rgb_pixel(rgb: u32) -> Pixel {
+    [
+        ((rgb >> 16) & 0xFF) as f32 / 255.0,
+        ((rgb >> 8) & 0xFF) as f32 / 255.0,
+        (rgb & 0xFF) as f32 / 255.0,
+        1.0,
+    ]
+}
+
+fn scale(pixel: Pixel, factor: f32) -> Pixel {
+    pixel.map(|value| value * factor)
+}
+
+fn add(a: Pixel, b: Pixel) -> Pixel {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2], a[3] + b[3]]
+}
+
+/// Composites `top` over `bottom`.
+fn over(top: Pixel, bottom: Pixel) -> Pixel {
+    add(top, scale(bottom, 1.0 - top[3]))
+}
+
+fn premultiply([r, g, b, a]: Pixel) -> Pixel {
+    [r * a, g * a, b * a, a]
+}
+
+fn unmultiply([r, g, b, a]: Pixel) -> Pixel {
+    if a > 0.0 {
+        [r / a, g / a, b / a, a]
+    } else {
+        TRANSPARENT
+    }
+}
+
+fn color_to_pixel(color: Color) -> Pixel {
+    [
+        color.red() as f32 / 255.0,
+        color.green() as f32 / 255.0,
+        color.blue() as f32 / 255.0,
+        color.alpha() as f32 / 255.0,
+    ]
+}
+
+fn pixel_to_color(pixel: Pixel) -> Color {
+    let alpha = pixel[3].clamp(0.0, 1.0);
+    // Pre-multiplied colors can't be brighter than their alpha.
+    let channel = |value: f32| (value.clamp(0.0, alpha) * 255.0).round() as u8;
+    Color::argb(
+        channel(alpha),
+        channel(pixel[0]),
+        channel(pixel[1]),
+        channel(pixel[2]),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opaque_square(size: u32, inset: u32) -> Vec<Color> {
+        (0..size * size)
+            .map(|i| {
+                let (x, y) = (i % size, i / size);
+                let inside =
+                    (inset..size - inset).contains(&x) && (inset..size - inset).contains(&y);
+                if inside {
+                    Color::argb(255, 255, 0, 0)
+                } else {
+                    Color::argb(0, 0, 0, 0)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn blur_spreads_alpha() {
+        let pixels = opaque_square(9, 3);
+        let blur = BitmapFilter::Blur(BlurFilter {
+            blur_x: 3.0,
+            blur_y: 3.0,
+            quality: 1,
+        });
+        let result = apply_filter(&blur, 9, 9, &pixels);
+        // The center is unchanged, and the edge of the square fades out.
+        assert_eq!(result[4 + 4 * 9].alpha(), 255);
+        assert_eq!(result[2 + 4 * 9].alpha(), 85);
+        assert_eq!(result[0].alpha(), 0);
+    }
+
+    #[test]
+    fn color_matrix_swaps_channels() {
+        let pixels = vec![Color::argb(255, 255, 0, 0)];
+        let mut matrix = [0.0; 20];
+        // Red becomes blue, and alpha is kept.
+        matrix[2 * 5] = 1.0;
+        matrix[3 * 5 + 3] = 1.0;
+        let filter = BitmapFilter::ColorMatrix(ColorMatrixFilter { matrix });
+        let result = apply_filter(&filter, 1, 1, &pixels);
+        assert_eq!(result[0], Color::argb(255, 0, 0, 255));
+    }
+
+    #[test]
+    fn glow_is_behind_source() {
+        let pixels = opaque_square(9, 3);
+        let glow = BitmapFilter::Glow(ShadowFilter {
+            color: 0x00FF00,
+            alpha: 1.0,
+            blur: BlurFilter {
+                blur_x: 3.0,
+                blur_y: 3.0,
+                quality: 1,
+            },
+            strength: 1.0,
+            distance: 0.0,
+            angle: 0.0,
+            inner: false,
+            knockout: false,
+            hide_object: false,
+        });
+        let result = apply_filter(&glow, 9, 9, &pixels);
+        assert_eq!(result[4 + 4 * 9], Color::argb(255, 255, 0, 0));
+        assert_eq!(result[2 + 4 * 9], Color::argb(85, 0, 85, 0));
+    }
+
+    #[test]
+    fn accelerated_filters_fall_back_to_cpu() {
+        let blur = BitmapFilter::Blur(BlurFilter {
+            blur_x: 3.0,
+            blur_y: 3.0,
+            quality: 1,
+        });
+        let mut matrix = [0.0; 20];
+        matrix[2 * 5] = 1.0;
+        matrix[3 * 5 + 3] = 1.0;
+        let color_matrix = BitmapFilter::ColorMatrix(ColorMatrixFilter { matrix });
+        let filters = [color_matrix.clone(), blur, color_matrix];
+        let rgba: Vec<u8> = opaque_square(9, 3)
+            .iter()
+            .flat_map(|p| [p.red(), p.green(), p.blue(), p.alpha()])
+            .collect();
+
+        // Only the color matrices are "supported", and the renderer fails on
+        // the second one.
+        let mut runs = vec![];
+        let accelerated = apply_filters_accelerated(
+            &filters,
+            9,
+            9,
+            &rgba,
+            |filter| matches!(filter, BitmapFilter::ColorMatrix(_)),
+            |filters, pixels| {
+                runs.push(filters.len());
+                if runs.len() == 1 {
+                    let colors: Vec<Color> = pixels
+                        .chunks_exact(4)
+                        .map(|p| Color::argb(p[3], p[0], p[1], p[2]))
+                        .collect();
+                    let output = apply_filter(&filters[0], 9, 9, &colors);
+                    Some(
+                        output
+                            .iter()
+                            .flat_map(|p| [p.red(), p.green(), p.blue(), p.alpha()])
+                            .collect(),
+                    )
+                } else {
+                    None
+                }
+            },
+        );
+        assert_eq!(runs, vec![1, 1]);
+        assert_eq!(accelerated, apply_filters_rgba(&filters, 9, 9, &rgba));
+    }
+}
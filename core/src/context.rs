@@ -7,6 +7,7 @@ use crate::avm2::{
 };
 use crate::backend::{
     audio::{AudioBackend, AudioManager, SoundHandle, SoundInstanceHandle},
+    font::FontBackend,
     locale::LocaleBackend,
     log::LogBackend,
     navigator::NavigatorBackend,
@@ -21,8 +22,13 @@ use crate::external::ExternalInterface;
 use crate::focus_tracker::FocusTracker;
 use crate::library::Library;
 use crate::loader::LoadManager;
+use crate::local_connection::LocalConnections;
+use crate::microphone::Microphone;
 use crate::player::Player;
 use crate::prelude::*;
+use crate::shape_utils::ScalingGrid;
+use crate::socket::Sockets;
+use crate::streams::StreamManager;
 use crate::tag_utils::{SwfMovie, SwfSlice};
 use crate::transform::TransformStack;
 use crate::vminterface::AvmType;
@@ -68,6 +74,18 @@ pub struct UpdateContext<'a, 'gc, 'gc_context> {
     /// The audio manager, manging all actively playing sounds.
     pub audio_manager: &'a mut AudioManager<'gc>,
 
+    /// The stream manager, advancing all playing `NetStream`s.
+    pub stream_manager: &'a mut StreamManager<'gc>,
+
+    /// The user's microphone, shared by all `Microphone` objects.
+    pub microphone: &'a mut Microphone<'gc>,
+
+    /// Connections opened by `XMLSocket` objects.
+    pub sockets: &'a mut Sockets<'gc>,
+
+    /// The message bus shared by all `LocalConnection` objects.
+    pub local_connections: &'a mut LocalConnections<'gc>,
+
     /// The navigator backend, used by the AVM to make HTTP requests and visit webpages.
     pub navigator: &'a mut (dyn NavigatorBackend + 'a),
 
@@ -83,6 +101,9 @@ pub struct UpdateContext<'a, 'gc, 'gc_context> {
     /// The locale backend, used for localisation and personalisation
     pub locale: &'a mut dyn LocaleBackend,
 
+    /// The font backend, used to render device text with the fonts of the system
+    pub fonts: &'a mut dyn FontBackend,
+
     /// The logging backend, used for trace output capturing
     pub log: &'a mut dyn LogBackend,
 
@@ -174,6 +195,34 @@ impl<'a, 'gc, 'gc_context> UpdateContext<'a, 'gc, 'gc_context> {
             self.action_queue,
             self.stage.root_clip(),
         );
+        self.fill_dynamic_sounds();
+    }
+
+    /// Fire `sampleData` events on the AVM2 sounds that generate their own
+    /// audio and are running low on samples.
+    fn fill_dynamic_sounds(&mut self) {
+        // A handler that provides few samples at a low frame rate may need to
+        // be asked several times to keep up.
+        const MAX_REQUESTS_PER_UPDATE: usize = 8;
+
+        for _ in 0..MAX_REQUESTS_PER_UPDATE {
+            let requests = self
+                .audio_manager
+                .dynamic_sounds_needing_samples(self.audio);
+            if requests.is_empty() {
+                break;
+            }
+
+            for (instance, sound, position) in requests {
+                let samples = crate::avm2::Avm2::request_sample_data(self, sound, position)
+                    .unwrap_or_else(|e| {
+                        log::error!("Unhandled AVM2 exception in sampleData handler: {}", e);
+                        vec![]
+                    });
+                self.audio_manager
+                    .queue_dynamic_samples(self.audio, instance, &samples);
+            }
+        }
     }
 
     pub fn global_sound_transform(&self) -> &SoundTransform {
@@ -211,6 +260,14 @@ impl<'a, 'gc, 'gc_context> UpdateContext<'a, 'gc, 'gc_context> {
             .start_sound(self.audio, sound, settings, owner, avm1_object)
     }
 
+    pub fn start_dynamic_sound(
+        &mut self,
+        sample_source: crate::avm2::Object<'gc>,
+    ) -> Option<SoundInstanceHandle> {
+        self.audio_manager
+            .start_dynamic_sound(self.audio, sample_source)
+    }
+
     pub fn attach_avm2_sound_channel(
         &mut self,
         instance: SoundInstanceHandle,
@@ -286,9 +343,14 @@ impl<'a, 'gc, 'gc_context> UpdateContext<'a, 'gc, 'gc_context> {
             swf: self.swf,
             audio: self.audio,
             audio_manager: self.audio_manager,
+            stream_manager: self.stream_manager,
+            microphone: self.microphone,
+            sockets: self.sockets,
+            local_connections: self.local_connections,
             navigator: self.navigator,
             renderer: self.renderer,
             locale: self.locale,
+            fonts: self.fonts,
             log: self.log,
             ui: self.ui,
             video: self.video,
@@ -381,6 +443,20 @@ impl<'gc> ActionQueue<'gc> {
         }
     }
 
+    /// The number of actions queued at each priority.
+    ///
+    /// Pass this to `truncate` to discard the actions queued after this point.
+    pub fn lengths(&self) -> Vec<usize> {
+        self.action_queue.iter().map(VecDeque::len).collect()
+    }
+
+    /// Discards the actions queued since `lengths` was called.
+    pub fn truncate(&mut self, lengths: &[usize]) {
+        for (queue, &len) in self.action_queue.iter_mut().zip(lengths) {
+            queue.truncate(len);
+        }
+    }
+
     /// Sorts and drains the actions from the queue.
     pub fn pop_action(&mut self) -> Option<QueuedActions<'gc>> {
         for queue in self.action_queue.iter_mut().rev() {
@@ -423,6 +499,10 @@ pub struct RenderContext<'a, 'gc> {
     /// Whether to allow pushing a new mask. A masker-inside-a-masker does not work in Flash, instead
     /// causing the inner mask to be included as part of the outer mask. Maskee-inside-a-maskee works as one expects.
     pub allow_mask: bool,
+
+    /// The `scale9Grid` of the display object whose children are being rendered, which their
+    /// shapes are moved for.
+    pub scaling_grid: Option<ScalingGrid>,
 }
 
 /// The type of action being run.
@@ -436,10 +516,10 @@ pub enum ActionType<'gc> {
     Initialize { bytecode: SwfSlice },
 
     /// Construct a movie with a custom class or on(construct) events.
-    Construct {
-        constructor: Option<Avm1Object<'gc>>,
-        events: Vec<SwfSlice>,
-    },
+    ///
+    /// The custom class is the one registered for the movie's symbol when
+    /// this action runs.
+    Construct { events: Vec<SwfSlice> },
 
     /// An event handler method, e.g. `onEnterFrame`.
     Method {
@@ -490,12 +570,8 @@ impl fmt::Debug for ActionType<'_> {
                 .debug_struct("ActionType::Initialize")
                 .field("bytecode", bytecode)
                 .finish(),
-            ActionType::Construct {
-                constructor,
-                events,
-            } => f
+            ActionType::Construct { events } => f
                 .debug_struct("ActionType::Construct")
-                .field("constructor", constructor)
                 .field("events", events)
                 .finish(),
             ActionType::Method { object, name, args } => f
@@ -5,6 +5,7 @@
 //! items work even if the movie changed `object.menu` in the meantime.
 
 use crate::avm1;
+use crate::display_object::DisplayObject;
 use gc_arena::Collect;
 #[cfg(feature = "serde")]
 use serde::Serialize;
@@ -55,6 +56,8 @@ pub enum ContextMenuCallback<'gc> {
     Back,
     Print,
     Avm1 {
+        /// The display object whose `menu` holds this item.
+        target: DisplayObject<'gc>,
         item: avm1::Object<'gc>,
         callback: avm1::Object<'gc>,
     },
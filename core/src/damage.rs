@@ -0,0 +1,155 @@
+//! The areas of the stage that changed between frames.
+
+use crate::backend::render::ViewportRect;
+use crate::bounding_box::BoundingBox;
+
+/// The areas of the viewport that changed since the last frame, and have to
+/// be rendered again.
+///
+/// The areas are kept as a few rectangles of whole pixels that don't overlap.
+/// When there would be too many, the two rectangles that are closest to each
+/// other are merged into one rectangle that covers both.
+#[derive(Clone, Debug, Default)]
+pub struct DamageRegion {
+    rects: Vec<ViewportRect>,
+}
+
+impl DamageRegion {
+    /// The most rectangles that the region is made of.
+    const MAX_RECTS: usize = 8;
+
+    /// How far anti-aliasing and pixel snapping may draw past the bounds of
+    /// an object, in pixels.
+    const MARGIN: f64 = 2.0;
+
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    pub fn rects(&self) -> &[ViewportRect] {
+        &self.rects
+    }
+
+    /// The number of pixels inside of the region.
+    pub fn area(&self) -> u64 {
+        self.rects.iter().map(ViewportRect::area).sum()
+    }
+
+    /// Adds the given bounds in the coordinate space of the viewport, clipped
+    /// to a viewport of the given size.
+    pub fn add(&mut self, bounds: &BoundingBox, (width, height): (u32, u32)) {
+        if !bounds.valid {
+            return;
+        }
+        let clamp = |value: f64, max: u32| value.clamp(0.0, f64::from(max)) as u32;
+        let rect = ViewportRect {
+            x_min: clamp((bounds.x_min.to_pixels() - Self::MARGIN).floor(), width),
+            y_min: clamp((bounds.y_min.to_pixels() - Self::MARGIN).floor(), height),
+            x_max: clamp((bounds.x_max.to_pixels() + Self::MARGIN).ceil(), width),
+            y_max: clamp((bounds.y_max.to_pixels() + Self::MARGIN).ceil(), height),
+        };
+        if rect.area() > 0 {
+            self.insert(rect);
+        }
+    }
+
+    fn insert(&mut self, mut rect: ViewportRect) {
+        while let Some(i) = self.rects.iter().position(|other| overlaps(other, &rect)) {
+            rect = union(&self.rects.swap_remove(i), &rect);
+        }
+        self.rects.push(rect);
+
+        if self.rects.len() > Self::MAX_RECTS {
+            // Merge the two rectangles whose union adds the least area.
+            let mut closest = (0, 1, u64::MAX);
+            for i in 0..self.rects.len() {
+                for j in i + 1..self.rects.len() {
+                    let (a, b) = (&self.rects[i], &self.rects[j]);
+                    let added = union(a, b).area() - a.area() - b.area();
+                    if added < closest.2 {
+                        closest = (i, j, added);
+                    }
+                }
+            }
+            let b = self.rects.swap_remove(closest.1);
+            let a = self.rects.swap_remove(closest.0);
+            self.insert(union(&a, &b));
+        }
+    }
+}
+
+fn overlaps(a: &ViewportRect, b: &ViewportRect) -> bool {
+    a.x_min < b.x_max && b.x_min < a.x_max && a.y_min < b.y_max && b.y_min < a.y_max
+}
+
+fn union(a: &ViewportRect, b: &ViewportRect) -> ViewportRect {
+    ViewportRect {
+        x_min: a.x_min.min(b.x_min),
+        y_min: a.y_min.min(b.y_min),
+        x_max: a.x_max.max(b.x_max),
+        y_max: a.y_max.max(b.y_max),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swf::Twips;
+
+    fn bounds(x_min: f64, y_min: f64, x_max: f64, y_max: f64) -> BoundingBox {
+        BoundingBox {
+            x_min: Twips::from_pixels(x_min),
+            y_min: Twips::from_pixels(y_min),
+            x_max: Twips::from_pixels(x_max),
+            y_max: Twips::from_pixels(y_max),
+            valid: true,
+        }
+    }
+
+    #[test]
+    fn merges_overlapping_rects() {
+        let mut damage = DamageRegion::default();
+        damage.add(&bounds(10.0, 10.0, 20.0, 20.0), (100, 100));
+        damage.add(&bounds(50.0, 50.0, 60.5, 60.0), (100, 100));
+        assert_eq!(damage.rects().len(), 2);
+        assert_eq!(
+            damage.rects()[1],
+            ViewportRect {
+                x_min: 48,
+                y_min: 48,
+                x_max: 63,
+                y_max: 62,
+            }
+        );
+
+        damage.add(&bounds(15.0, 15.0, 55.0, 55.0), (100, 100));
+        assert_eq!(
+            damage.rects(),
+            &[ViewportRect {
+                x_min: 8,
+                y_min: 8,
+                x_max: 63,
+                y_max: 62,
+            }]
+        );
+    }
+
+    #[test]
+    fn clipped_to_viewport() {
+        let mut damage = DamageRegion::default();
+        damage.add(&bounds(-50.0, -50.0, -10.0, 10.0), (100, 100));
+        assert!(damage.is_empty());
+        damage.add(&bounds(90.0, -50.0, 150.0, 10.0), (100, 100));
+        assert_eq!(damage.area(), 12 * 12);
+    }
+
+    #[test]
+    fn merges_closest_rects() {
+        let mut damage = DamageRegion::default();
+        for i in 0..=DamageRegion::MAX_RECTS {
+            let x = i as f64 * 20.0;
+            damage.add(&bounds(x, 0.0, x + 4.0, 4.0), (1000, 100));
+        }
+        assert_eq!(damage.rects().len(), DamageRegion::MAX_RECTS);
+    }
+}
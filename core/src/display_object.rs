@@ -6,22 +6,26 @@ use crate::avm2::{
     Namespace as Avm2Namespace, Object as Avm2Object, QName as Avm2QName, TObject as Avm2TObject,
     Value as Avm2Value,
 };
+use crate::backend::render::{BitmapFormat, BitmapHandle};
+use crate::bitmap::filters::BitmapFilter;
 use crate::context::{RenderContext, UpdateContext};
+use crate::damage::DamageRegion;
 use crate::drawing::Drawing;
 use crate::player::NEWEST_PLAYER_VERSION;
 use crate::prelude::*;
+use crate::shape_utils::ScalingGrid;
 use crate::string::{AvmString, WString};
 use crate::tag_utils::SwfMovie;
-use crate::transform::Transform;
+use crate::transform::{Transform, TransformStack};
 use crate::types::{Degrees, Percent};
 use crate::vminterface::{AvmType, Instantiator};
 use bitflags::bitflags;
 use gc_arena::{Collect, MutationContext};
 use ruffle_macros::enum_trait_object;
-use std::cell::{Ref, RefMut};
+use std::cell::{Cell, Ref, RefCell, RefMut};
 use std::fmt::Debug;
 use std::sync::Arc;
-use swf::Fixed8;
+use swf::{BlendMode, Fixed8};
 
 mod avm1_button;
 mod avm2_button;
@@ -44,7 +48,7 @@ pub use crate::display_object::container::{
 pub use avm1_button::{Avm1Button, ButtonState, ButtonTracking};
 pub use avm2_button::Avm2Button;
 pub use bitmap::Bitmap;
-pub use edit_text::{AutoSizeMode, EditText, TextSelection};
+pub use edit_text::{AutoSizeMode, EditText, LineMetrics, TextSelection};
 pub use graphic::Graphic;
 pub use interactive::{InteractiveObject, TInteractiveObject};
 pub use morph_shape::{MorphShape, MorphShapeStatic};
@@ -96,6 +100,70 @@ pub struct DisplayObjectBase<'gc> {
 
     /// Bit flags for various display object properties.
     flags: DisplayObjectFlags,
+
+    /// The filters applied to this display object when it is rendered.
+    #[collect(require_static)]
+    filters: Vec<BitmapFilter>,
+
+    /// The rectangle that this display object is clipped to and scrolled by,
+    /// in its own coordinate space (`scrollRect`).
+    scroll_rect: Option<BoundingBox>,
+
+    /// The grid that the shapes of this display object are sliced into when
+    /// it is scaled, in its own coordinate space (`scale9Grid`).
+    scaling_grid: Option<BoundingBox>,
+
+    /// How this display object is composited onto the objects below it.
+    #[collect(require_static)]
+    blend_mode: BlendMode,
+
+    /// The last image this display object was rasterized to, if it is
+    /// cached as a bitmap or has filters.
+    #[collect(require_static)]
+    bitmap_cache: Cell<Option<BitmapCache>>,
+
+    /// Whether the position, transform or visibility of this object changed
+    /// since a cached ancestor was last rasterized.
+    transform_changed: Cell<bool>,
+
+    /// Whether the contents of this object changed since it, or a cached
+    /// ancestor, was last rasterized.
+    content_changed: Cell<bool>,
+
+    /// Whether anything about this object changed since the stage was last
+    /// rendered, so that the area it covers has to be rendered again.
+    damaged: Cell<bool>,
+
+    /// The area of the viewport this object covered when the stage was last
+    /// rendered.
+    #[collect(require_static)]
+    rendered_bounds: RefCell<BoundingBox>,
+
+    /// The area of the viewport covered by children that were removed from
+    /// this object since the stage was last rendered.
+    #[collect(require_static)]
+    removed_bounds: RefCell<BoundingBox>,
+}
+
+/// The image of a display object rasterized for `cacheAsBitmap` or filters.
+#[derive(Clone, Copy, Debug)]
+struct BitmapCache {
+    handle: BitmapHandle,
+
+    /// The scale, rotation and skew (`a`, `b`, `c` and `d`) of the world
+    /// matrix that the image was rendered with.
+    ///
+    /// The image can be reused for any world matrix with the same values,
+    /// no matter its translation.
+    scale_rotation: [f32; 4],
+
+    /// The offset of the image from the pixel-snapped translation of the
+    /// world matrix, in pixels.
+    offset: (i32, i32),
+
+    /// Whether the image was masked by the alpha channel of another object,
+    /// in which case it can't be reused.
+    alpha_masked: bool,
 }
 
 impl<'gc> Default for DisplayObjectBase<'gc> {
@@ -117,6 +185,16 @@ impl<'gc> Default for DisplayObjectBase<'gc> {
             maskee: None,
             sound_transform: Default::default(),
             flags: DisplayObjectFlags::VISIBLE,
+            filters: Vec::new(),
+            scroll_rect: None,
+            scaling_grid: None,
+            blend_mode: BlendMode::Normal,
+            bitmap_cache: Cell::new(None),
+            transform_changed: Cell::new(false),
+            content_changed: Cell::new(false),
+            damaged: Cell::new(true),
+            rendered_bounds: Default::default(),
+            removed_bounds: Default::default(),
         }
     }
 }
@@ -127,6 +205,7 @@ impl<'gc> DisplayObjectBase<'gc> {
     fn reset_for_movie_load(&mut self) {
         let flags_to_keep = self.flags & DisplayObjectFlags::LOCK_ROOT;
         self.flags = flags_to_keep | DisplayObjectFlags::VISIBLE;
+        self.invalidate_cached_bitmap();
     }
 
     fn id(&self) -> CharacterId {
@@ -158,11 +237,13 @@ impl<'gc> DisplayObjectBase<'gc> {
     }
 
     pub fn matrix_mut(&mut self) -> &mut Matrix {
+        self.mark_transform_changed();
         &mut self.transform.matrix
     }
 
     fn set_matrix(&mut self, matrix: &Matrix) {
         self.transform.matrix = *matrix;
+        self.mark_transform_changed();
         self.flags -= DisplayObjectFlags::SCALE_ROTATION_CACHED;
     }
 
@@ -171,11 +252,13 @@ impl<'gc> DisplayObjectBase<'gc> {
     }
 
     pub fn color_transform_mut(&mut self) -> &mut ColorTransform {
+        self.mark_transform_changed();
         &mut self.transform.color_transform
     }
 
     fn set_color_transform(&mut self, color_transform: &ColorTransform) {
         self.transform.color_transform = *color_transform;
+        self.mark_transform_changed();
     }
 
     fn x(&self) -> f64 {
@@ -184,6 +267,7 @@ impl<'gc> DisplayObjectBase<'gc> {
 
     fn set_x(&mut self, value: f64) {
         self.set_transformed_by_script(true);
+        self.mark_transform_changed();
         self.transform.matrix.tx = Twips::from_pixels(value)
     }
 
@@ -193,6 +277,7 @@ impl<'gc> DisplayObjectBase<'gc> {
 
     fn set_y(&mut self, value: f64) {
         self.set_transformed_by_script(true);
+        self.mark_transform_changed();
         self.transform.matrix.ty = Twips::from_pixels(value)
     }
 
@@ -238,6 +323,7 @@ impl<'gc> DisplayObjectBase<'gc> {
 
     fn set_scale(&mut self, scale_x: f32, scale_y: f32, rotation: f32) {
         self.cache_scale_rotation();
+        self.mark_transform_changed();
         let mut matrix = &mut self.transform.matrix;
         let rotation = rotation.to_radians();
         let cos_x = f32::cos(rotation);
@@ -259,6 +345,7 @@ impl<'gc> DisplayObjectBase<'gc> {
     fn set_rotation(&mut self, degrees: Degrees) {
         self.set_transformed_by_script(true);
         self.cache_scale_rotation();
+        self.mark_transform_changed();
         self.rotation = degrees;
         let cos_x = f64::cos(degrees.into_radians());
         let sin_x = f64::sin(degrees.into_radians());
@@ -279,6 +366,7 @@ impl<'gc> DisplayObjectBase<'gc> {
     fn set_scale_x(&mut self, value: Percent) {
         self.set_transformed_by_script(true);
         self.cache_scale_rotation();
+        self.mark_transform_changed();
         self.scale_x = value;
         let cos = f64::cos(self.rotation.into_radians());
         let sin = f64::sin(self.rotation.into_radians());
@@ -295,6 +383,7 @@ impl<'gc> DisplayObjectBase<'gc> {
     fn set_scale_y(&mut self, value: Percent) {
         self.set_transformed_by_script(true);
         self.cache_scale_rotation();
+        self.mark_transform_changed();
         self.scale_y = value;
         let cos = f64::cos(self.rotation.into_radians() + self.skew);
         let sin = f64::sin(self.rotation.into_radians() + self.skew);
@@ -368,11 +457,73 @@ impl<'gc> DisplayObjectBase<'gc> {
         self.sound_transform = sound_transform;
     }
 
+    pub fn filters(&self) -> &[BitmapFilter] {
+        &self.filters
+    }
+
+    pub fn set_filters(&mut self, filters: Vec<BitmapFilter>) {
+        self.filters = filters;
+        self.invalidate_cached_bitmap();
+    }
+
+    pub fn scroll_rect(&self) -> Option<&BoundingBox> {
+        self.scroll_rect.as_ref()
+    }
+
+    pub fn set_scroll_rect(&mut self, scroll_rect: Option<BoundingBox>) {
+        self.scroll_rect = scroll_rect;
+        self.invalidate_cached_bitmap();
+    }
+
+    pub fn scaling_grid(&self) -> Option<&BoundingBox> {
+        self.scaling_grid.as_ref()
+    }
+
+    pub fn set_scaling_grid(&mut self, scaling_grid: Option<BoundingBox>) {
+        self.scaling_grid = scaling_grid;
+        self.invalidate_cached_bitmap();
+    }
+
+    pub fn blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+
+    pub fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.blend_mode = blend_mode;
+        self.mark_transform_changed();
+    }
+
+    /// The translation applied to the contents of this object by its `scrollRect`.
+    fn scroll_rect_matrix(&self) -> Matrix {
+        match &self.scroll_rect {
+            Some(scroll_rect) => Matrix::translate(
+                Twips::ZERO - scroll_rect.x_min,
+                Twips::ZERO - scroll_rect.y_min,
+            ),
+            None => Matrix::default(),
+        }
+    }
+
+    /// Marks the contents of this object as changed, so that any bitmap
+    /// cached of it or of its ancestors will be rasterized again, and the area
+    /// it covers on the stage is rendered again.
+    pub fn invalidate_cached_bitmap(&self) {
+        self.content_changed.set(true);
+        self.damaged.set(true);
+    }
+
+    /// Marks the position, transform or visibility of this object as changed.
+    fn mark_transform_changed(&self) {
+        self.transform_changed.set(true);
+        self.damaged.set(true);
+    }
+
     fn visible(&self) -> bool {
         self.flags.contains(DisplayObjectFlags::VISIBLE)
     }
 
     fn set_visible(&mut self, value: bool) {
+        self.mark_transform_changed();
         self.flags.set(DisplayObjectFlags::VISIBLE, value);
     }
 
@@ -392,6 +543,15 @@ impl<'gc> DisplayObjectBase<'gc> {
         self.flags.set(DisplayObjectFlags::LOCK_ROOT, value);
     }
 
+    fn cache_as_bitmap(&self) -> bool {
+        self.flags.contains(DisplayObjectFlags::CACHE_AS_BITMAP)
+    }
+
+    fn set_cache_as_bitmap(&mut self, value: bool) {
+        self.invalidate_cached_bitmap();
+        self.flags.set(DisplayObjectFlags::CACHE_AS_BITMAP, value);
+    }
+
     fn transformed_by_script(&self) -> bool {
         self.flags
             .contains(DisplayObjectFlags::TRANSFORMED_BY_SCRIPT)
@@ -435,6 +595,7 @@ impl<'gc> DisplayObjectBase<'gc> {
     }
     fn set_masker(&mut self, node: Option<DisplayObject<'gc>>) {
         self.masker = node;
+        self.damaged.set(true);
     }
 
     fn maskee(&self) -> Option<DisplayObject<'gc>> {
@@ -442,6 +603,7 @@ impl<'gc> DisplayObjectBase<'gc> {
     }
     fn set_maskee(&mut self, node: Option<DisplayObject<'gc>>) {
         self.maskee = node;
+        self.damaged.set(true);
     }
 }
 
@@ -451,7 +613,22 @@ pub fn render_base<'gc>(this: DisplayObject<'gc>, context: &mut RenderContext<'_
     }
     context.transform_stack.push(&*this.base().transform());
 
-    let mask = this.masker();
+    // Objects rendered as part of a mask only add their shape to it, so they
+    // are neither masked, blended, nor cached as bitmaps themselves.
+    let is_mask = !context.allow_mask;
+    let mask = this.masker().filter(|_| !is_mask);
+
+    // A mask that is cached as a bitmap masks an object that is also cached
+    // by its alpha channel, instead of by its shape.
+    let alpha_mask = mask.filter(|m| this.cache_as_bitmap() && m.cache_as_bitmap());
+    let is_cached = !is_mask && (this.cache_as_bitmap() || !this.base().filters().is_empty());
+    let cached = if is_cached {
+        cached_image(this, context, alpha_mask)
+    } else {
+        None
+    };
+    let mask = mask.filter(|_| alpha_mask.is_none() || cached.is_none());
+
     let mut mask_transform = crate::transform::Transform::default();
     if let Some(m) = mask {
         mask_transform.matrix = this.global_to_local_matrix();
@@ -464,7 +641,21 @@ pub fn render_base<'gc>(this: DisplayObject<'gc>, context: &mut RenderContext<'_
         context.allow_mask = true;
         context.renderer.activate_mask();
     }
-    this.render_self(context);
+    let blend_mode = if is_mask {
+        BlendMode::Normal
+    } else {
+        effective_blend_mode(this)
+    };
+    if blend_mode != BlendMode::Normal {
+        context.renderer.push_blend_mode(blend_mode);
+    }
+    match cached {
+        Some((handle, transform)) => context.renderer.render_bitmap(handle, &transform, true),
+        None => render_scrolled(this, context),
+    }
+    if blend_mode != BlendMode::Normal {
+        context.renderer.pop_blend_mode();
+    }
     if let Some(m) = mask {
         context.renderer.deactivate_mask();
         context.allow_mask = false;
@@ -478,6 +669,402 @@ pub fn render_base<'gc>(this: DisplayObject<'gc>, context: &mut RenderContext<'_
     context.transform_stack.pop();
 }
 
+/// The blend mode that a display object is rendered with.
+///
+/// The `alpha` and `erase` blend modes only apply to the contents of a parent
+/// with the `layer` blend mode. Otherwise, the object is drawn normally.
+fn effective_blend_mode(this: DisplayObject<'_>) -> BlendMode {
+    match this.blend_mode() {
+        BlendMode::Alpha | BlendMode::Erase
+            if this.parent().map(|p| p.blend_mode()) != Some(BlendMode::Layer) =>
+        {
+            BlendMode::Normal
+        }
+        blend_mode => blend_mode,
+    }
+}
+
+/// Render the contents of a display object, clipped to and scrolled by its
+/// `scrollRect` if it has one.
+fn render_scrolled<'gc>(this: DisplayObject<'gc>, context: &mut RenderContext<'_, 'gc>) {
+    let scroll_rect = match this.scroll_rect() {
+        Some(scroll_rect) => scroll_rect,
+        None => {
+            this.render_self(context);
+            return;
+        }
+    };
+
+    // Masks can't be clipped while they are being drawn, so the contents of
+    // a mask are only scrolled.
+    if !context.allow_mask {
+        context.transform_stack.push(&Transform {
+            matrix: this.base().scroll_rect_matrix(),
+            color_transform: Default::default(),
+        });
+        this.render_self(context);
+        context.transform_stack.pop();
+        return;
+    }
+
+    let mask = context.transform_stack.transform().matrix
+        * Matrix::create_box(
+            scroll_rect.width().to_pixels() as f32,
+            scroll_rect.height().to_pixels() as f32,
+            0.0,
+            Twips::ZERO,
+            Twips::ZERO,
+        );
+    context.renderer.push_mask();
+    context.renderer.draw_rect(Color::from_rgb(0, 0xff), &mask);
+    context.renderer.activate_mask();
+
+    context.transform_stack.push(&Transform {
+        matrix: this.base().scroll_rect_matrix(),
+        color_transform: Default::default(),
+    });
+    this.render_self(context);
+    context.transform_stack.pop();
+
+    context.renderer.deactivate_mask();
+    context.renderer.draw_rect(Color::from_rgb(0, 0xff), &mask);
+    context.renderer.pop_mask();
+}
+
+/// The `scale9Grid` of a display object, with the bounds that its shapes and
+/// the shapes of its children are scaled to.
+pub fn scaling_grid(this: DisplayObject<'_>) -> Option<ScalingGrid> {
+    let grid = this.scaling_grid()?;
+    let bounds = this.bounds_with_transform(&Matrix::default());
+    Some(ScalingGrid::new(grid, bounds, *this.base().matrix()))
+}
+
+/// Renders the children of a display object, whose shapes are moved for
+/// `scaling_grid`, the `scale9Grid` of that display object.
+pub fn render_in_scaling_grid<'gc>(
+    context: &mut RenderContext<'_, 'gc>,
+    scaling_grid: Option<ScalingGrid>,
+    render_children: impl FnOnce(&mut RenderContext<'_, 'gc>),
+) {
+    let parent_grid = std::mem::replace(&mut context.scaling_grid, scaling_grid);
+    render_children(context);
+    context.scaling_grid = parent_grid;
+}
+
+/// Whether the contents of a display object, or anything about its
+/// descendants, changed since it was last rasterized.
+fn contents_changed(this: DisplayObject<'_>) -> bool {
+    if this.base().content_changed.get() {
+        return true;
+    }
+    this.as_container().map_or(false, |container| {
+        container
+            .iter_render_list()
+            .any(|child| child.base().transform_changed.get() || contents_changed(child))
+    })
+}
+
+/// Mark a display object and its descendants as unchanged, after it was
+/// rasterized.
+fn clear_changes(this: DisplayObject<'_>) {
+    this.base().content_changed.set(false);
+    if let Some(container) = this.as_container() {
+        for child in container.iter_render_list() {
+            child.base().transform_changed.set(false);
+            clear_changes(child);
+        }
+    }
+}
+
+/// Adds the areas of the viewport that changed since the stage was last
+/// rendered, because of `this` or its descendants, to `damage`.
+///
+/// `matrix` transforms the parent of `this` into the viewport, whose size is
+/// `viewport_size`. If `covered` is true, an ancestor changed, and the area it
+/// covers already includes that of `this`. Returns the area that `this`
+/// covers now.
+pub fn collect_damage<'gc>(
+    this: DisplayObject<'gc>,
+    matrix: &Matrix,
+    damage: &mut DamageRegion,
+    viewport_size: (u32, u32),
+    covered: bool,
+) -> BoundingBox {
+    // Bitmaps showing the same `BitmapData` have to see its changes in time
+    // for this frame.
+    if let Some(bitmap) = this.as_bitmap() {
+        bitmap.invalidate_if_bitmap_data_changed();
+    }
+    let base = this.base();
+    let matrix = *matrix * *base.matrix();
+    let damaged = base.damaged.replace(false);
+    // Masks are drawn as part of the objects they mask, even if invisible.
+    let visible = base.visible() || base.maskee().is_some();
+
+    // The contents of an object are moved by its `scrollRect`.
+    let contents_matrix = matrix * base.scroll_rect_matrix();
+    let mut bounds = this.self_bounds().transform(&contents_matrix);
+    let children: Vec<DisplayObject<'gc>> = if let Some(container) = this.as_container() {
+        container.iter_render_list().collect()
+    } else if let Some(button) = this.as_avm2_button() {
+        button
+            .get_state_child(button.state().into())
+            .into_iter()
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let children_covered = covered || damaged || !visible;
+    for child in children {
+        let child_bounds = collect_damage(
+            child,
+            &contents_matrix,
+            damage,
+            viewport_size,
+            children_covered,
+        );
+        bounds.union(&child_bounds);
+    }
+
+    if let Some(scroll_rect) = base.scroll_rect() {
+        bounds = BoundingBox {
+            x_min: Twips::ZERO,
+            y_min: Twips::ZERO,
+            x_max: scroll_rect.width(),
+            y_max: scroll_rect.height(),
+            valid: true,
+        }
+        .transform(&matrix);
+    }
+    // Filters such as blurs draw past the bounds of the object.
+    let (pad_x, pad_y) = base.filters().iter().fold((0, 0), |(x, y), filter| {
+        let (filter_x, filter_y) = filter.padding();
+        (x + filter_x, y + filter_y)
+    });
+    if bounds.valid && (pad_x > 0 || pad_y > 0) {
+        let pad_x = Twips::from_pixels(pad_x.into());
+        let pad_y = Twips::from_pixels(pad_y.into());
+        bounds.x_min -= pad_x;
+        bounds.y_min -= pad_y;
+        bounds.x_max += pad_x;
+        bounds.y_max += pad_y;
+    }
+    if !visible {
+        bounds = BoundingBox::default();
+    }
+
+    let removed_bounds = base.removed_bounds.take();
+    let rendered_bounds = base.rendered_bounds.replace(bounds.clone());
+    if !covered {
+        damage.add(&removed_bounds, viewport_size);
+        if damaged {
+            damage.add(&rendered_bounds, viewport_size);
+            damage.add(&bounds, viewport_size);
+        }
+    }
+    bounds
+}
+
+/// Marks a child that was added to, or moved within, the render list of
+/// `parent`, so that the area it covers on the stage is rendered again.
+fn damage_moved_child<'gc>(parent: DisplayObject<'gc>, child: DisplayObject<'gc>) {
+    parent.base().content_changed.set(true);
+    child.base().damaged.set(true);
+}
+
+/// Marks a child that was removed from the render list of `parent`, so that
+/// the area it covered on the stage is rendered again.
+fn damage_removed_child<'gc>(parent: DisplayObject<'gc>, child: DisplayObject<'gc>) {
+    parent.base().content_changed.set(true);
+    let rendered_bounds = child.base().rendered_bounds.take();
+    parent
+        .base()
+        .removed_bounds
+        .borrow_mut()
+        .union(&rendered_bounds);
+    child.base().damaged.set(true);
+}
+
+/// The largest width or height of the offscreen image that a display object
+/// is cached to.
+pub(crate) const MAX_BITMAP_CACHE_SIZE: u32 = 4096;
+
+/// Get the image of a display object that is cached as a bitmap or has
+/// filters, along with the transform to draw it with.
+///
+/// The object is rasterized to an offscreen image, which is filtered and
+/// kept between frames. As long as neither the object's contents nor its
+/// scale, rotation and skew change, the image is drawn again without
+/// rasterizing the object. Cached images are snapped to whole pixels.
+///
+/// If `alpha_mask` is given, the image is masked by the alpha channel of
+/// that object, and is rasterized again every time.
+///
+/// Returns `None` if the renderer can't render offscreen, in which case the
+/// object should be rendered directly.
+fn cached_image<'gc>(
+    this: DisplayObject<'gc>,
+    context: &mut RenderContext<'_, 'gc>,
+    alpha_mask: Option<DisplayObject<'gc>>,
+) -> Option<(BitmapHandle, Transform)> {
+    let world_transform = context.transform_stack.transform().clone();
+    let mut matrix = world_transform.matrix;
+    let tx = matrix.tx.to_pixels().round() as i32;
+    let ty = matrix.ty.to_pixels().round() as i32;
+    matrix.tx = Twips::from_pixels(tx.into());
+    matrix.ty = Twips::from_pixels(ty.into());
+    let scale_rotation = [matrix.a, matrix.b, matrix.c, matrix.d];
+
+    let previous = this.base().bitmap_cache.get();
+    let cache = match previous {
+        Some(cache)
+            if alpha_mask.is_none()
+                && !cache.alpha_masked
+                && cache.scale_rotation == scale_rotation
+                && !contents_changed(this) =>
+        {
+            cache
+        }
+        cache => rasterize(this, context, &matrix, cache, alpha_mask)?,
+    };
+
+    Some((
+        cache.handle,
+        Transform {
+            matrix: Matrix::translate(
+                Twips::from_pixels((tx + cache.offset.0).into()),
+                Twips::from_pixels((ty + cache.offset.1).into()),
+            ),
+            color_transform: world_transform.color_transform,
+        },
+    ))
+}
+
+/// Rasterize a display object to an offscreen image with the given
+/// pixel-snapped world matrix, and run its filters on the image.
+///
+/// The texture of the previous image is reused, even if the size changed.
+fn rasterize<'gc>(
+    this: DisplayObject<'gc>,
+    context: &mut RenderContext<'_, 'gc>,
+    matrix: &Matrix,
+    previous: Option<BitmapCache>,
+    alpha_mask: Option<DisplayObject<'gc>>,
+) -> Option<BitmapCache> {
+    let filters = this.base().filters().to_vec();
+    let bounds = this.bounds_with_transform(matrix);
+
+    // Filters such as blurs grow the image past the bounds of the object.
+    let (pad_x, pad_y) = filters.iter().fold((0, 0), |(x, y), filter| {
+        let (filter_x, filter_y) = filter.padding();
+        (x + filter_x as i32, y + filter_y as i32)
+    });
+    let x_min = bounds.x_min.to_pixels().floor() as i32 - pad_x;
+    let y_min = bounds.y_min.to_pixels().floor() as i32 - pad_y;
+    let x_max = bounds.x_max.to_pixels().ceil() as i32 + pad_x;
+    let y_max = bounds.y_max.to_pixels().ceil() as i32 + pad_y;
+    let width = (x_max - x_min).max(1) as u32;
+    let height = (y_max - y_min).max(1) as u32;
+    if width > MAX_BITMAP_CACHE_SIZE || height > MAX_BITMAP_CACHE_SIZE {
+        return None;
+    }
+
+    let origin = Matrix::translate(
+        Twips::from_pixels(-x_min as f64),
+        Twips::from_pixels(-y_min as f64),
+    );
+    let mut rgba = render_filtered(this, context, origin * *matrix, width, height)?;
+    if let Some(mask) = alpha_mask {
+        let mask_matrix =
+            origin * *matrix * this.global_to_local_matrix() * mask.local_to_global_matrix();
+        let mask_rgba = render_filtered(mask, context, mask_matrix, width, height)?;
+        // Both images are pre-multiplied, so every channel is scaled.
+        for (pixel, mask_pixel) in rgba.chunks_exact_mut(4).zip(mask_rgba.chunks_exact(4)) {
+            let alpha = u16::from(mask_pixel[3]);
+            for channel in pixel {
+                *channel = (u16::from(*channel) * alpha / 255) as u8;
+            }
+        }
+    }
+
+    let handle = match previous {
+        Some(previous) => context
+            .renderer
+            .update_texture(previous.handle, width, height, &rgba)
+            .ok()?,
+        None => context
+            .renderer
+            .register_bitmap_raw(width, height, rgba)
+            .ok()?,
+    };
+    let cache = BitmapCache {
+        handle,
+        scale_rotation: [matrix.a, matrix.b, matrix.c, matrix.d],
+        offset: (
+            x_min - matrix.tx.to_pixels() as i32,
+            y_min - matrix.ty.to_pixels() as i32,
+        ),
+        alpha_masked: alpha_mask.is_some(),
+    };
+    this.base().bitmap_cache.set(Some(cache));
+    clear_changes(this);
+    Some(cache)
+}
+
+/// Render a display object offscreen to an image of the given size, with
+/// the given matrix, and run its filters on the image.
+///
+/// Returns the image in pre-multiplied RGBA.
+fn render_filtered<'gc>(
+    this: DisplayObject<'gc>,
+    context: &mut RenderContext<'_, 'gc>,
+    matrix: Matrix,
+    width: u32,
+    height: u32,
+) -> Option<Vec<u8>> {
+    // The color transform is applied when the image is drawn, not before filtering.
+    let transform = Transform {
+        matrix,
+        color_transform: Default::default(),
+    };
+    let ui = &mut *context.ui;
+    let library = context.library;
+    let stage = context.stage;
+    let allow_mask = context.allow_mask;
+    let scaling_grid = context.scaling_grid.clone();
+    let bitmap =
+        context
+            .renderer
+            .render_offscreen(width, height, Color::from_rgb(0, 0), &mut |renderer| {
+                let mut transform_stack = TransformStack::new();
+                transform_stack.push(&transform);
+                let mut render_context = RenderContext {
+                    renderer,
+                    ui: &mut *ui,
+                    library,
+                    transform_stack: &mut transform_stack,
+                    stage,
+                    clip_depth_stack: vec![],
+                    allow_mask,
+                    scaling_grid: scaling_grid.clone(),
+                };
+                render_scrolled(this, &mut render_context);
+            });
+    let rgba = match bitmap {
+        Some(bitmap) if bitmap.width == width && bitmap.height == height => match bitmap.data {
+            BitmapFormat::Rgba(rgba) => rgba,
+            BitmapFormat::Rgb(_) => return None,
+        },
+        _ => return None,
+    };
+    let base = this.base();
+    Some(
+        context
+            .renderer
+            .apply_filters(base.filters(), width, height, &rgba),
+    )
+}
+
 #[enum_trait_object(
     #[derive(Clone, Collect, Debug, Copy)]
     #[collect(no_drop)]
@@ -538,6 +1125,18 @@ pub trait TDisplayObject<'gc>:
     /// it to the bounding box. This gives a tighter AABB then if we simply transformed
     /// the overall AABB.
     fn bounds_with_transform(&self, matrix: &Matrix) -> BoundingBox {
+        // Objects with a `scrollRect` are clipped to the size of the rectangle.
+        if let Some(scroll_rect) = self.scroll_rect() {
+            let bounds = BoundingBox {
+                x_min: Twips::ZERO,
+                y_min: Twips::ZERO,
+                x_max: scroll_rect.width(),
+                y_max: scroll_rect.height(),
+                valid: true,
+            };
+            return bounds.transform(matrix);
+        }
+
         let mut bounds = self.self_bounds().transform(matrix);
 
         if let Some(ctr) = self.as_container() {
@@ -550,6 +1149,50 @@ pub trait TDisplayObject<'gc>:
         bounds
     }
 
+    /// The untransformed inherent bounding box of this object, excluding strokes.
+    /// This is never larger than `self_bounds`, and is used by `getRect`.
+    ///
+    /// Leaf DisplayObjects with strokes should override this method.
+    fn self_rect_bounds(&self) -> BoundingBox {
+        self.self_bounds()
+    }
+
+    /// The untransformed bounding box of this object including children, excluding strokes.
+    fn rect_bounds(&self) -> BoundingBox {
+        self.rect_bounds_with_transform(&Matrix::default())
+    }
+
+    /// Gets the bounds of this object and all children, excluding strokes, transformed by a given matrix.
+    /// Like `bounds_with_transform`, this transforms the AABB of each child separately.
+    fn rect_bounds_with_transform(&self, matrix: &Matrix) -> BoundingBox {
+        let mut bounds = self.self_rect_bounds().transform(matrix);
+
+        if let Some(ctr) = self.as_container() {
+            for child in ctr.iter_render_list() {
+                let matrix = *matrix * *child.base().matrix();
+                bounds.union(&child.rect_bounds_with_transform(&matrix));
+            }
+        }
+
+        bounds
+    }
+
+    /// Transforms bounds in this object's coordinate space into the coordinate space of `target`.
+    ///
+    /// This transforms the whole AABB, which doesn't produce as tight of an AABB as
+    /// `bounds_with_transform` would with the final matrix, but this matches Flash's
+    /// `getBounds` and `getRect`.
+    fn bounds_in_space(&self, bounds: BoundingBox, target: DisplayObject<'gc>) -> BoundingBox {
+        if self.as_ptr() == target.as_ptr() {
+            // Already in the target's coordinate space; no AABB transform needed.
+            bounds
+        } else {
+            let to_global_matrix = self.local_to_global_matrix();
+            let to_target_matrix = target.global_to_local_matrix();
+            bounds.transform(&(to_target_matrix * to_global_matrix))
+        }
+    }
+
     fn place_frame(&self) -> u16 {
         self.base().place_frame()
     }
@@ -573,7 +1216,7 @@ pub trait TDisplayObject<'gc>:
     /// Returns the matrix for transforming from this object's local space to global stage space.
     fn local_to_global_matrix(&self) -> Matrix {
         let mut node = self.parent();
-        let mut matrix = *self.base().matrix();
+        let mut matrix = *self.base().matrix() * self.base().scroll_rect_matrix();
         while let Some(display_object) = node {
             // TODO: We don't want to include the stage transform because it includes the scale
             // mode and alignment transform, but the AS APIs expect "global" to be relative to the
@@ -582,7 +1225,8 @@ pub trait TDisplayObject<'gc>:
             if display_object.as_stage().is_some() {
                 break;
             }
-            matrix = *display_object.base().matrix() * matrix;
+            let base = display_object.base();
+            matrix = *base.matrix() * base.scroll_rect_matrix() * matrix;
             node = display_object.parent();
         }
         matrix
@@ -950,6 +1594,82 @@ pub trait TDisplayObject<'gc>:
         self.base_mut(gc_context).set_is_root(value);
     }
 
+    /// The filters applied to this display object when it is rendered.
+    fn filters(&self) -> Vec<BitmapFilter> {
+        self.base().filters().to_vec()
+    }
+
+    /// Sets the filters applied to this display object when it is rendered.
+    fn set_filters(&self, gc_context: MutationContext<'gc, '_>, filters: Vec<BitmapFilter>) {
+        self.base_mut(gc_context).set_filters(filters);
+    }
+
+    /// Whether this display object is rasterized to a bitmap which is reused
+    /// until its contents change.
+    /// Returned by the `cacheAsBitmap` ActionScript property.
+    fn cache_as_bitmap(&self) -> bool {
+        self.base().cache_as_bitmap()
+    }
+
+    /// Sets whether this display object is rasterized to a bitmap which is
+    /// reused until its contents change.
+    /// Set by the `cacheAsBitmap` ActionScript property.
+    fn set_cache_as_bitmap(&self, gc_context: MutationContext<'gc, '_>, value: bool) {
+        self.base_mut(gc_context).set_cache_as_bitmap(value);
+    }
+
+    /// The rectangle that this display object is clipped to and scrolled by.
+    /// Returned by the `scrollRect` ActionScript property.
+    fn scroll_rect(&self) -> Option<BoundingBox> {
+        self.base().scroll_rect().cloned()
+    }
+
+    /// Sets the rectangle that this display object is clipped to and scrolled by.
+    /// Set by the `scrollRect` ActionScript property.
+    fn set_scroll_rect(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        scroll_rect: Option<BoundingBox>,
+    ) {
+        self.base_mut(gc_context).set_scroll_rect(scroll_rect);
+    }
+
+    /// The grid that the shapes of this display object are sliced into when it
+    /// is scaled, so that the corners of the grid keep their size.
+    /// Returned by the `scale9Grid` ActionScript property.
+    fn scaling_grid(&self) -> Option<BoundingBox> {
+        self.base().scaling_grid().cloned()
+    }
+
+    /// Sets the grid that the shapes of this display object are sliced into
+    /// when it is scaled.
+    /// Set by the `scale9Grid` ActionScript property.
+    fn set_scaling_grid(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        scaling_grid: Option<BoundingBox>,
+    ) {
+        self.base_mut(gc_context).set_scaling_grid(scaling_grid);
+    }
+
+    /// How this display object is composited onto the objects below it.
+    /// Returned by the `blendMode` ActionScript property.
+    fn blend_mode(&self) -> BlendMode {
+        self.base().blend_mode()
+    }
+
+    /// Sets how this display object is composited onto the objects below it.
+    /// Set by the `blendMode` ActionScript property.
+    fn set_blend_mode(&self, gc_context: MutationContext<'gc, '_>, blend_mode: BlendMode) {
+        self.base_mut(gc_context).set_blend_mode(blend_mode);
+    }
+
+    /// Marks the contents of this display object as changed, so that any
+    /// bitmap cached of it or of its ancestors will be rasterized again.
+    fn invalidate_cached_bitmap(&self) {
+        self.base().invalidate_cached_bitmap();
+    }
+
     /// The sound transform for sounds played inside this display object.
     fn set_sound_transform(
         &self,
@@ -1238,6 +1958,12 @@ pub trait TDisplayObject<'gc>:
                     self.set_visible(context.gc_context, visible);
                 }
             }
+            if let Some(is_bitmap_cached) = place_object.is_bitmap_cached {
+                self.set_cache_as_bitmap(context.gc_context, is_bitmap_cached);
+            }
+            if let Some(blend_mode) = place_object.blend_mode {
+                self.set_blend_mode(context.gc_context, blend_mode);
+            }
             // TODO: Others will go here eventually.
         }
     }
@@ -1325,7 +2051,7 @@ pub trait TDisplayObject<'gc>:
     }
 
     /// The cursor to use when this object is the hovered element under a mouse.
-    fn mouse_cursor(&self) -> MouseCursor {
+    fn mouse_cursor(&self, _context: &mut UpdateContext<'_, 'gc, '_>) -> MouseCursor {
         MouseCursor::Hand
     }
 
@@ -1490,7 +2216,7 @@ bitflags! {
     /// Bit flags used by `DisplayObject`.
     #[derive(Collect)]
     #[collect(no_drop)]
-    struct DisplayObjectFlags: u8 {
+    struct DisplayObjectFlags: u16 {
         /// Whether this object has been removed from the display list.
         /// Necessary in AVM1 to throw away queued actions from removed movie clips.
         const REMOVED                  = 1 << 0;
@@ -1520,6 +2246,10 @@ bitflags! {
         /// Whether this object has `_lockroot` set to true, in which case
         /// it becomes the _root of itself and of any children
         const LOCK_ROOT                = 1 << 7;
+
+        /// Whether this object is rasterized to a bitmap which is reused
+        /// until its contents change (`cacheAsBitmap`).
+        const CACHE_AS_BITMAP          = 1 << 8;
     }
 }
 
@@ -336,7 +336,10 @@ impl<'gc> TDisplayObject<'gc> for Avm1Button<'gc> {
     }
 
     fn render_self(&self, context: &mut RenderContext<'_, 'gc>) {
-        self.render_children(context);
+        let scaling_grid = super::scaling_grid((*self).into());
+        super::render_in_scaling_grid(context, scaling_grid, |context| {
+            self.render_children(context)
+        });
     }
 
     fn self_bounds(&self) -> BoundingBox {
@@ -383,7 +386,7 @@ impl<'gc> TDisplayObject<'gc> for Avm1Button<'gc> {
         None
     }
 
-    fn mouse_cursor(&self) -> MouseCursor {
+    fn mouse_cursor(&self, _context: &mut UpdateContext<'_, 'gc, '_>) -> MouseCursor {
         if self.use_hand_cursor() {
             MouseCursor::Hand
         } else {
@@ -274,6 +274,8 @@ impl<'gc> Avm2Button<'gc> {
         if let Some(state) = self.get_state_child(state.into()) {
             state.set_parent(context.gc_context, Some(self.into()));
         }
+        drop(button);
+        self.invalidate_cached_bitmap();
     }
 
     /// Get the display object that represents a particular button state.
@@ -624,7 +626,8 @@ impl<'gc> TDisplayObject<'gc> for Avm2Button<'gc> {
         let current_state = self.get_state_child(state.into());
 
         if let Some(state) = current_state {
-            state.render(context);
+            let scaling_grid = super::scaling_grid((*self).into());
+            super::render_in_scaling_grid(context, scaling_grid, |context| state.render(context));
         }
     }
 
@@ -683,7 +686,7 @@ impl<'gc> TDisplayObject<'gc> for Avm2Button<'gc> {
         None
     }
 
-    fn mouse_cursor(&self) -> MouseCursor {
+    fn mouse_cursor(&self, _context: &mut UpdateContext<'_, 'gc, '_>) -> MouseCursor {
         if self.use_hand_cursor() {
             MouseCursor::Hand
         } else {
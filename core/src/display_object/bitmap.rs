@@ -11,7 +11,7 @@ use crate::display_object::{DisplayObjectBase, DisplayObjectPtr, TDisplayObject}
 use crate::prelude::*;
 use crate::vminterface::{AvmType, Instantiator};
 use gc_arena::{Collect, Gc, GcCell, MutationContext};
-use std::cell::{Ref, RefMut};
+use std::cell::{Cell, Ref, RefMut};
 
 /// A Bitmap display object is a raw bitamp on the stage.
 /// This can only be instanitated on the display list in SWFv9 AVM2 files.
@@ -41,6 +41,13 @@ pub struct BitmapData<'gc> {
     /// If this is `None`, then the bitmap does not render anything.
     bitmap_handle: Option<BitmapHandle>,
 
+    /// The generation of the bitmap data when this object last checked it
+    /// for changes.
+    ///
+    /// Many bitmaps can show the same data, so each of them keeps track of
+    /// the changes it has seen.
+    bitmap_data_generation: Cell<u32>,
+
     /// Whether or not bitmap smoothing is enabled.
     smoothing: bool,
 
@@ -88,6 +95,7 @@ impl<'gc> Bitmap<'gc> {
                 static_data: Gc::allocate(context.gc_context, BitmapStatic { id, width, height }),
                 bitmap_data,
                 bitmap_handle,
+                bitmap_data_generation: Cell::new(0),
                 smoothing,
                 avm2_object: None,
                 avm2_bitmapdata_class: None,
@@ -153,6 +161,9 @@ impl<'gc> Bitmap<'gc> {
             let mut write = self.0.write(context.gc_context);
 
             write.bitmap_data = Some(bitmap_data);
+            write
+                .bitmap_data_generation
+                .set(bitmap_data.read().generation());
             if let Some(bitmap_handle) = bitmap_handle {
                 write.bitmap_handle = Some(bitmap_handle);
             }
@@ -162,6 +173,19 @@ impl<'gc> Bitmap<'gc> {
             write.bitmap_data = None;
             write.bitmap_handle = None;
         }
+        self.invalidate_cached_bitmap();
+    }
+
+    /// Marks this bitmap as changed if its bitmap data changed since it was
+    /// last checked, so that it is rendered again.
+    pub fn invalidate_if_bitmap_data_changed(self) {
+        let read = self.0.read();
+        if let Some(bitmap_data) = &read.bitmap_data {
+            let generation = bitmap_data.read().generation();
+            if read.bitmap_data_generation.replace(generation) != generation {
+                read.base.invalidate_cached_bitmap();
+            }
+        }
     }
 
     pub fn avm2_bitmapdata_class(self) -> Option<Avm2ClassObject<'gc>> {
@@ -249,20 +273,9 @@ impl<'gc> TDisplayObject<'gc> for Bitmap<'gc> {
     }
 
     fn run_frame(&self, context: &mut UpdateContext<'_, 'gc, '_>) {
-        if let (Some(bitmap_data), Some(bitmap_handle)) =
-            (&self.0.read().bitmap_data, self.0.read().bitmap_handle)
-        {
-            let bd = bitmap_data.read();
-            if bd.dirty() {
-                let _ = context.renderer.update_texture(
-                    bitmap_handle,
-                    bd.width(),
-                    bd.height(),
-                    bd.pixels_rgba(),
-                );
-                drop(bd);
-                bitmap_data.write(context.gc_context).set_dirty(false);
-            }
+        self.invalidate_if_bitmap_data_changed();
+        if let Some(bitmap_data) = &self.0.read().bitmap_data {
+            bitmap_data.read().update_dirty_texture(context.renderer);
         }
     }
 
@@ -273,11 +286,19 @@ impl<'gc> TDisplayObject<'gc> for Bitmap<'gc> {
         }
 
         let bitmap_data = self.0.read();
+
+        // Upload any changes made to the bitmap data since the frame ran,
+        // so that scripts drawing into it every frame are shown without delay.
+        // The change was already seen when the damage of the stage was collected.
+        if let Some(data) = &bitmap_data.bitmap_data {
+            data.read().update_dirty_texture(context.renderer);
+        }
+
         if let Some(bitmap_handle) = bitmap_data.bitmap_handle {
             context.renderer.render_bitmap(
                 bitmap_handle,
                 context.transform_stack.transform(),
-                bitmap_data.smoothing,
+                bitmap_data.smoothing && context.stage.quality().smooths_bitmaps(),
             );
         }
     }
@@ -303,3 +324,55 @@ struct BitmapStatic {
     width: u16,
     height: u16,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm2::test_utils::with_avm2;
+    use crate::bitmap::bitmap_data::{BitmapData as PixelData, Color};
+    use crate::damage::DamageRegion;
+    use crate::display_object::collect_damage;
+
+    fn damage_of(bitmap: Bitmap<'_>) -> DamageRegion {
+        let mut damage = DamageRegion::default();
+        collect_damage(
+            bitmap.into(),
+            &Matrix::default(),
+            &mut damage,
+            (100, 100),
+            false,
+        );
+        damage
+    }
+
+    #[test]
+    fn shared_bitmap_data_damages_every_bitmap() {
+        with_avm2(|activation| {
+            let context = &mut activation.context;
+            let mut pixels = PixelData::default();
+            pixels.init_pixels(10, 10, true, 0);
+            let bitmap_data = GcCell::allocate(context.gc_context, pixels);
+            let bitmaps = [
+                Bitmap::new_with_bitmap_data(context, 0, None, 10, 10, Some(bitmap_data), true),
+                Bitmap::new_with_bitmap_data(context, 0, None, 10, 10, Some(bitmap_data), true),
+            ];
+
+            // New bitmaps are damaged until they are rendered once.
+            for bitmap in bitmaps {
+                assert!(!damage_of(bitmap).is_empty());
+                assert!(damage_of(bitmap).is_empty());
+            }
+
+            bitmap_data.write(context.gc_context).set_pixel32_raw(
+                0,
+                0,
+                Color::argb(255, 255, 255, 255),
+            );
+            for bitmap in bitmaps {
+                assert!(!damage_of(bitmap).is_empty());
+                assert!(damage_of(bitmap).is_empty());
+            }
+            Ok(())
+        })
+    }
+}
@@ -425,10 +425,16 @@ macro_rules! impl_display_object_container {
                 }
             };
             drop(write);
+            crate::display_object::damage_moved_child((*self).into(), child);
+            if let Some(removed_child) = removed_child {
+                crate::display_object::damage_removed_child((*self).into(), removed_child);
+            }
 
             child.set_parent(context.gc_context, Some(self.into()));
             child.set_place_frame(context.gc_context, 0);
             child.set_depth(context.gc_context, depth);
+            // Sounds playing in the child now inherit the sound transforms of its new ancestors.
+            context.set_sound_transforms_dirty();
 
             if let Some(removed_child) = removed_child {
                 removed_child.unload(context);
@@ -458,6 +464,7 @@ macro_rules! impl_display_object_container {
                 child,
                 depth,
             );
+            crate::display_object::damage_moved_child((*self).into(), child);
         }
 
         fn insert_at_index(
@@ -485,11 +492,13 @@ macro_rules! impl_display_object_container {
 
             child.set_place_frame(context.gc_context, 0);
             child.set_parent(context.gc_context, Some((*self).into()));
+            context.set_sound_transforms_dirty();
 
             self.0
                 .write(context.gc_context)
                 .$field
                 .insert_at_id(child, index);
+            crate::display_object::damage_moved_child((*self).into(), child);
 
             if parent_changed {
                 dispatch_added_event(
@@ -507,10 +516,13 @@ macro_rules! impl_display_object_container {
             index1: usize,
             index2: usize,
         ) {
-            self.0
-                .write(context.gc_context)
-                .$field
-                .swap_at_id(index1, index2);
+            let mut write = self.0.write(context.gc_context);
+            write.$field.swap_at_id(index1, index2);
+            let swapped = [write.$field.get_id(index1), write.$field.get_id(index2)];
+            drop(write);
+            for child in swapped.into_iter().flatten() {
+                crate::display_object::damage_moved_child((*self).into(), child);
+            }
         }
 
         fn remove_child(
@@ -537,6 +549,7 @@ macro_rules! impl_display_object_container {
             drop(write);
 
             if removed_from_depth_list || removed_from_render_list {
+                crate::display_object::damage_removed_child((*self).into(), child);
                 child.unload(context);
 
                 //TODO: This is an awful, *awful* hack to deal with the fact
@@ -544,6 +557,7 @@ macro_rules! impl_display_object_container {
                 //don't.
                 if !matches!(child.object2(), Avm2Value::Undefined) {
                     child.set_parent(context.gc_context, None);
+                    context.set_sound_transforms_dirty();
                 }
             }
 
@@ -577,25 +591,32 @@ macro_rules! impl_display_object_container {
 
                 drop(write);
 
+                crate::display_object::damage_removed_child((*self).into(), removed);
+
                 removed.unload(context);
 
                 if !matches!(removed.object2(), Avm2Value::Undefined) {
                     removed.set_parent(context.gc_context, None);
+                    context.set_sound_transforms_dirty();
                 }
 
                 write = self.0.write(context.gc_context);
             }
+            drop(write);
         }
 
         fn clear(&mut self, context: &mut UpdateContext<'_, 'gc, '_>) {
             use crate::display_object::container::dispatch_removed_event;
             let removed_children: Vec<DisplayObject<'gc>> =
                 self.0.read().$field.iter_render_list().collect();
-            for removed in removed_children {
-                dispatch_removed_event(removed, context);
+            for removed in &removed_children {
+                dispatch_removed_event(*removed, context);
             }
 
-            self.0.write(context.gc_context).$field.clear()
+            self.0.write(context.gc_context).$field.clear();
+            for removed in removed_children {
+                crate::display_object::damage_removed_child((*self).into(), removed);
+            }
         }
 
         fn is_empty(self) -> bool {
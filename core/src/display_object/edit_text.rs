@@ -1,32 +1,42 @@
 //! `EditText` display object and support code.
 
+mod restrict;
+
 use crate::avm1::activation::{Activation as Avm1Activation, ActivationIdentifier};
+use crate::avm1::callable_value::CallableValue;
 use crate::avm1::{
     Avm1, Object as Avm1Object, StageObject as Avm1StageObject, TObject as Avm1TObject,
     Value as Avm1Value,
 };
 use crate::avm2::{
-    Activation as Avm2Activation, Object as Avm2Object, StageObject as Avm2StageObject,
+    Activation as Avm2Activation, Avm2, Object as Avm2Object, StageObject as Avm2StageObject,
 };
+use crate::backend::navigator::RequestOptions;
 use crate::backend::ui::MouseCursor;
 use crate::context::{RenderContext, UpdateContext};
 use crate::display_object::interactive::{
     InteractiveObject, InteractiveObjectBase, TInteractiveObject,
 };
-use crate::display_object::{DisplayObjectBase, DisplayObjectPtr, TDisplayObject};
+use crate::display_object::{DisplayObjectBase, DisplayObjectPtr, MovieClip, TDisplayObject};
 use crate::drawing::Drawing;
-use crate::events::{ButtonKeyCode, ClipEvent, ClipEventResult, KeyCode};
-use crate::font::{round_down_to_pixel, Glyph, TextRenderSettings};
-use crate::html::{BoxBounds, FormatSpans, LayoutBox, LayoutContent, TextFormat};
+use crate::events::{CaretMovement, ClipEvent, ClipEventResult, KeyCode, TextControlCode};
+use crate::font::{grid_fit_matrix, round_down_to_pixel, Glyph, TextGridFit, TextRenderSettings};
+use crate::html::{
+    BoxBounds, FormatSpans, LayoutBox, LayoutContent, LayoutLine, StyleSheet, TextFormat,
+};
 use crate::prelude::*;
 use crate::shape_utils::DrawCommand;
 use crate::string::{utils as string_utils, AvmString, WStr, WString};
 use crate::tag_utils::SwfMovie;
 use crate::transform::Transform;
+use crate::types::Percent;
 use crate::vminterface::{AvmObject, AvmType, Instantiator};
 use crate::xml::XmlDocument;
 use chrono::Utc;
 use gc_arena::{Collect, Gc, GcCell, MutationContext};
+use restrict::TextRestrict;
+use std::ops::Range;
+use std::time::Duration;
 use std::{cell::Ref, cell::RefMut, sync::Arc};
 use swf::Twips;
 
@@ -143,65 +153,74 @@ pub struct EditTextData<'gc> {
     /// Whether or not this EditText has the current keyboard focus
     has_focus: bool,
 
+    /// When and at which text position the text field was last clicked, to
+    /// detect double-clicks.
+    #[collect(require_static)]
+    last_click: Option<(Duration, usize)>,
+
     /// Which rendering engine this text field will use.
     render_settings: TextRenderSettings,
 
     /// How many pixels right the text is offset by. 0-based index.
     hscroll: f64,
 
-    /// Information about the layout's current lines. Used by scroll properties.
-    line_data: Vec<LineData>,
+    /// The lines of the current layout. Used by scroll properties.
+    lines: Vec<LayoutLine>,
+
+    /// The display objects of the images placed by `<img>` tags, in the same
+    /// order as the images of `text_spans`.
+    images: Vec<DisplayObject<'gc>>,
 
     /// How many lines down the text is offset by. 1-based index.
     scroll: usize,
-}
 
-// TODO: would be nicer to compute (and return) this during layout, instead of afterwards
-/// Compute line (index, offset, extent) from the layout data.
-fn get_line_data(layout: &[LayoutBox]) -> Vec<LineData> {
-    // if there are no boxes, there are no lines
-    if layout.is_empty() {
-        return Vec::new();
-    }
+    /// The maximum number of characters that users can type, or 0 if there
+    /// is no limit.
+    max_chars: i32,
 
-    let first_box = &layout[0];
+    /// The characters that users can type.
+    #[collect(require_static)]
+    restrict: TextRestrict,
 
-    let mut index = 1;
-    let mut offset = first_box.bounds().offset_y();
-    let mut extent = first_box.bounds().extent_y();
+    /// The style sheet that formats the HTML text.
+    #[collect(require_static)]
+    style_sheet: Option<StyleSheet>,
 
-    let mut line_data = Vec::new();
+    /// The AVM1 object that `style_sheet` was copied from.
+    style_sheet_object: Option<Avm1Object<'gc>>,
 
-    for layout_box in layout.get(1..).unwrap() {
-        let bounds = layout_box.bounds();
+    /// The HTML text as it was set, while a style sheet is in use.
+    #[collect(require_static)]
+    html_source: Option<WString>,
 
-        // if the top of the new box is lower than the bottom of the old box, it's a new line
-        if bounds.offset_y() > extent {
-            // save old line and reset
-            line_data.push(LineData {
-                index,
-                offset,
-                extent,
-            });
+    /// The link that the mouse is over, while it is styled with `a:hover`.
+    #[collect(require_static)]
+    hovered_link: Option<HoveredLink>,
+}
 
-            index += 1;
-            offset = bounds.offset_y();
-            extent = bounds.extent_y();
-        } else {
-            // otherwise we continue from the previous box
-            offset = offset.min(bounds.offset_y());
-            extent = extent.max(bounds.extent_y());
-        }
-    }
+/// A link that is styled with the `a:hover` style of a style sheet.
+#[derive(Clone, Debug)]
+struct HoveredLink {
+    /// The text that the link covers.
+    range: Range<usize>,
 
-    // save the final line
-    line_data.push(LineData {
-        index,
-        offset,
-        extent,
-    });
+    /// The formats of the link's spans from before `a:hover` was applied.
+    formats: Vec<(Range<usize>, TextFormat)>,
+}
+
+/// The start of the line that a text position is on, ignoring word wrapping.
+fn hard_line_start(text: &WStr, index: usize) -> usize {
+    text[..index.min(text.len())]
+        .rfind(&[b'\r', b'\n'][..])
+        .map_or(0, |newline| newline + 1)
+}
 
-    line_data
+/// The end of the line that a text position is on, ignoring word wrapping.
+fn hard_line_end(text: &WStr, index: usize) -> usize {
+    let index = index.min(text.len());
+    text[index..]
+        .find(&[b'\r', b'\n'][..])
+        .map_or(text.len(), |newline| index + newline)
 }
 
 impl<'gc> EditText<'gc> {
@@ -223,7 +242,7 @@ impl<'gc> EditText<'gc> {
 
         let text = WString::from_utf8(&text.to_str_lossy(encoding));
         let mut text_spans = if is_html {
-            FormatSpans::from_html(&text, default_format, is_multiline)
+            FormatSpans::from_html(&text, default_format, None, is_multiline)
         } else {
             FormatSpans::from_text(text, default_format)
         };
@@ -234,7 +253,7 @@ impl<'gc> EditText<'gc> {
 
         let bounds: BoundingBox = swf_tag.bounds.clone().into();
 
-        let (layout, intrinsic_bounds) = LayoutBox::lower_from_text_spans(
+        let layout = LayoutBox::lower_from_text_spans(
             &text_spans,
             context,
             swf_movie.clone(),
@@ -242,7 +261,6 @@ impl<'gc> EditText<'gc> {
             swf_tag.is_word_wrap,
             swf_tag.is_device_font,
         );
-        let line_data = get_line_data(&layout);
 
         let has_background = swf_tag.has_border;
         let background_color = 0xFFFFFF; // Default is white
@@ -313,8 +331,8 @@ impl<'gc> EditText<'gc> {
                 is_html,
                 drawing: Drawing::new(),
                 object: None,
-                layout,
-                intrinsic_bounds,
+                layout: layout.boxes,
+                intrinsic_bounds: layout.bounds,
                 bounds,
                 autosize: AutoSizeMode::None,
                 variable: variable.map(|s| s.to_string_lossy(encoding)),
@@ -322,10 +340,18 @@ impl<'gc> EditText<'gc> {
                 firing_variable_binding: false,
                 selection: None,
                 has_focus: false,
+                last_click: None,
                 render_settings: Default::default(),
                 hscroll: 0.0,
-                line_data,
+                lines: layout.lines,
+                images: Vec::new(),
                 scroll: 1,
+                max_chars: swf_tag.max_length.unwrap_or_default().into(),
+                restrict: TextRestrict::default(),
+                style_sheet: None,
+                style_sheet_object: None,
+                html_source: None,
+                hovered_link: None,
             },
         ));
 
@@ -360,7 +386,7 @@ impl<'gc> EditText<'gc> {
                 b: 0,
                 a: 0xFF,
             }),
-            max_length: Some(width as u16),
+            max_length: None,
             layout: Some(swf::TextLayout {
                 align: swf::TextAlign::Left,
                 left_margin: Twips::from_pixels(0.0),
@@ -407,14 +433,22 @@ impl<'gc> EditText<'gc> {
         let mut edit_text = self.0.write(context.gc_context);
         let default_format = edit_text.text_spans.default_format().clone();
         edit_text.text_spans = FormatSpans::from_text(text.into(), default_format);
+        edit_text.html_source = None;
+        edit_text.hovered_link = None;
         drop(edit_text);
 
+        self.load_images(context);
         self.relayout(context);
 
         Ok(())
     }
 
     pub fn html_text(self, context: &mut UpdateContext<'_, 'gc, '_>) -> WString {
+        // Styled text can't be raised back to HTML, so the source is kept.
+        if let Some(html_source) = &self.0.read().html_source {
+            return html_source.clone();
+        }
+
         if self.is_html() {
             let html_tree = self.html_tree(context).as_node();
             let html_string_result = html_tree.into_string(&mut |_node| true);
@@ -439,11 +473,21 @@ impl<'gc> EditText<'gc> {
         context: &mut UpdateContext<'_, 'gc, '_>,
     ) -> Result<(), Error> {
         if self.is_html() {
-            let mut write = self.0.write(context.gc_context);
-            let default_format = write.text_spans.default_format().clone();
-            write.text_spans = FormatSpans::from_html(text, default_format, write.is_multiline);
-            drop(write);
+            {
+                let mut write = self.0.write(context.gc_context);
+                let write = &mut *write;
+                let default_format = write.text_spans.default_format().clone();
+                write.text_spans = FormatSpans::from_html(
+                    text,
+                    default_format,
+                    write.style_sheet.as_ref(),
+                    write.is_multiline,
+                );
+                write.html_source = write.style_sheet.as_ref().map(|_| text.into());
+                write.hovered_link = None;
+            }
 
+            self.load_images(context);
             self.relayout(context);
 
             Ok(())
@@ -452,6 +496,140 @@ impl<'gc> EditText<'gc> {
         }
     }
 
+    /// Create the display objects of the images in the text, replacing any
+    /// previous ones.
+    ///
+    /// Images are instantiated from the library if their source is a linkage
+    /// name, and loaded from their URL otherwise.
+    fn load_images(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let old_images = std::mem::take(&mut self.0.write(context.gc_context).images);
+        for image in old_images {
+            context.load_manager.cancel_movie_loads(image);
+            image.unload(context);
+        }
+
+        let movie = self.movie().unwrap();
+        let sources: Vec<WString> = self
+            .0
+            .read()
+            .text_spans
+            .images()
+            .iter()
+            .map(|image| image.src.clone())
+            .collect();
+
+        let mut images = Vec::with_capacity(sources.len());
+        for src in sources {
+            let export_name = AvmString::new(context.gc_context, src.clone());
+            let library = context.library.library_for_movie_mut(movie.clone());
+            let image = if library.character_by_export_name(export_name).is_some() {
+                library.instantiate_by_export_name(export_name, context.gc_context)
+            } else {
+                Err("Image is not in the library".into())
+            };
+
+            let image = match image {
+                Ok(image) => image,
+                Err(_) => {
+                    let clip: DisplayObject<'gc> =
+                        MovieClip::new(movie.clone(), context.gc_context).into();
+                    if let Some(player) = context.player.clone() {
+                        let url = src.to_utf8_lossy().into_owned();
+                        let fetch = context.navigator.fetch(&url, RequestOptions::get());
+                        let process = context
+                            .load_manager
+                            .load_movie_into_clip(player, clip, fetch, None, url, None, None);
+                        context.navigator.spawn_future(process);
+                    }
+                    clip
+                }
+            };
+
+            image.set_parent(context.gc_context, Some(self.into()));
+            image.post_instantiation(context, image, None, Instantiator::Movie, false);
+            images.push(image);
+        }
+
+        self.0.write(context.gc_context).images = images;
+    }
+
+    /// Size the images in the text once their content is known.
+    ///
+    /// Images without a given size take the size of their content, and
+    /// images with one are scaled to fit it.
+    fn update_image_sizes(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let images = self.0.read().images.clone();
+        let mut needs_relayout = false;
+
+        for (index, image) in images.into_iter().enumerate() {
+            let bounds = image.bounds();
+            if !bounds.valid || bounds.width() == Twips::ZERO || bounds.height() == Twips::ZERO {
+                continue;
+            }
+
+            let mut write = self.0.write(context.gc_context);
+            let (width, height) = match write.text_spans.images().get(index) {
+                Some(html_image) => (html_image.width, html_image.height),
+                None => continue,
+            };
+            if width.is_none() || height.is_none() {
+                write
+                    .text_spans
+                    .set_image_size(index, bounds.width(), bounds.height());
+                needs_relayout = true;
+            }
+            drop(write);
+
+            let width = width.unwrap_or_else(|| bounds.width());
+            let height = height.unwrap_or_else(|| bounds.height());
+
+            image.set_scale_x(
+                context.gc_context,
+                Percent::from_unit(width.get() as f64 / bounds.width().get() as f64),
+            );
+            image.set_scale_y(
+                context.gc_context,
+                Percent::from_unit(height.get() as f64 / bounds.height().get() as f64),
+            );
+        }
+
+        if needs_relayout {
+            self.relayout(context);
+        }
+    }
+
+    /// The AVM1 `TextField.StyleSheet` that formats the HTML text.
+    pub fn style_sheet_object(self) -> Option<Avm1Object<'gc>> {
+        self.0.read().style_sheet_object
+    }
+
+    /// Format the HTML text with the styles of a style sheet, or stop using
+    /// one.
+    ///
+    /// Text with a style sheet can't be edited by the user.
+    pub fn set_style_sheet(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        style_sheet: Option<(Avm1Object<'gc>, StyleSheet)>,
+    ) {
+        let html_text = self.html_text(context);
+        let mut write = self.0.write(context.gc_context);
+        if let Some((object, style_sheet)) = style_sheet {
+            write.style_sheet = Some(style_sheet);
+            write.style_sheet_object = Some(object);
+            write.is_editable = false;
+        } else {
+            write.style_sheet = None;
+            write.style_sheet_object = None;
+        }
+        write.html_source = None;
+        drop(write);
+
+        if self.is_html() {
+            let _ = self.set_html_text(&html_text, context);
+        }
+    }
+
     pub fn html_tree(self, context: &mut UpdateContext<'_, 'gc, '_>) -> XmlDocument<'gc> {
         self.0.read().text_spans.raise_to_html(context.gc_context)
     }
@@ -600,6 +778,26 @@ impl<'gc> EditText<'gc> {
         self.0.write(context.gc_context).is_html = is_html;
     }
 
+    /// The maximum number of characters that users can type, or 0 if there
+    /// is no limit.
+    pub fn max_chars(self) -> i32 {
+        self.0.read().max_chars
+    }
+
+    pub fn set_max_chars(self, max_chars: i32, context: &mut UpdateContext<'_, 'gc, '_>) {
+        self.0.write(context.gc_context).max_chars = max_chars.max(0);
+    }
+
+    /// The characters that users can type, or `None` if there is no
+    /// restriction.
+    pub fn restrict(self) -> Option<WString> {
+        self.0.read().restrict.value().map(WString::from)
+    }
+
+    pub fn set_restrict(self, restrict: Option<&WStr>, context: &mut UpdateContext<'_, 'gc, '_>) {
+        self.0.write(context.gc_context).restrict = TextRestrict::new(restrict);
+    }
+
     pub fn replace_text(
         self,
         from: usize,
@@ -702,6 +900,7 @@ impl<'gc> EditText<'gc> {
     fn redraw_border(self, gc_context: MutationContext<'gc, '_>) {
         let mut write = self.0.write(gc_context);
 
+        write.base.base.invalidate_cached_bitmap();
         write.drawing.clear();
 
         if write.has_border || write.has_background {
@@ -762,6 +961,7 @@ impl<'gc> EditText<'gc> {
     /// text-span representation.
     fn relayout(self, context: &mut UpdateContext<'_, 'gc, '_>) {
         let mut edit_text = self.0.write(context.gc_context);
+        edit_text.base.base.invalidate_cached_bitmap();
         let autosize = edit_text.autosize;
         let is_word_wrap = edit_text.is_word_wrap;
         let movie = edit_text.static_data.swf.clone();
@@ -775,7 +975,7 @@ impl<'gc> EditText<'gc> {
             edit_text.text_spans.clear_displayed_text();
         }
 
-        let (new_layout, intrinsic_bounds) = LayoutBox::lower_from_text_spans(
+        let new_layout = LayoutBox::lower_from_text_spans(
             &edit_text.text_spans,
             context,
             movie,
@@ -783,22 +983,29 @@ impl<'gc> EditText<'gc> {
             is_word_wrap,
             edit_text.is_device_font,
         );
+        let intrinsic_bounds = new_layout.bounds;
 
-        edit_text.line_data = get_line_data(&new_layout);
-        edit_text.layout = new_layout;
+        edit_text.layout = new_layout.boxes;
+        edit_text.lines = new_layout.lines;
         edit_text.intrinsic_bounds = intrinsic_bounds;
         // reset scroll
         edit_text.hscroll = 0.0;
         edit_text.scroll = 1;
 
+        // Auto-sized text fields fit their text plus the padding around it.
+        // Word-wrapped text fields keep their width and only grow downwards.
+        let padding = Twips::from_pixels(Self::INTERNAL_PADDING * 2.0);
+        let autosize_width = intrinsic_bounds.width() + padding;
+        let autosize_height = intrinsic_bounds.height() + padding;
+
         match autosize {
             AutoSizeMode::None => {}
             AutoSizeMode::Left => {
                 if !is_word_wrap {
-                    edit_text.bounds.set_width(intrinsic_bounds.width());
+                    edit_text.bounds.set_width(autosize_width);
                 }
 
-                edit_text.bounds.set_height(intrinsic_bounds.height());
+                edit_text.bounds.set_height(autosize_height);
                 edit_text.base.base.set_transformed_by_script(true);
                 drop(edit_text);
                 self.redraw_border(context.gc_context);
@@ -806,25 +1013,23 @@ impl<'gc> EditText<'gc> {
             AutoSizeMode::Center => {
                 if !is_word_wrap {
                     let center = (edit_text.bounds.x_min + edit_text.bounds.x_max) / 2;
-                    edit_text
-                        .bounds
-                        .set_x(center - intrinsic_bounds.width() / 2);
-                    edit_text.bounds.set_width(intrinsic_bounds.width());
+                    edit_text.bounds.set_x(center - autosize_width / 2);
+                    edit_text.bounds.set_width(autosize_width);
                 }
 
-                edit_text.bounds.set_height(intrinsic_bounds.height());
+                edit_text.bounds.set_height(autosize_height);
                 edit_text.base.base.set_transformed_by_script(true);
                 drop(edit_text);
                 self.redraw_border(context.gc_context);
             }
             AutoSizeMode::Right => {
                 if !is_word_wrap {
-                    let new_x = edit_text.bounds.x_max - intrinsic_bounds.width();
+                    let new_x = edit_text.bounds.x_max - autosize_width;
                     edit_text.bounds.set_x(new_x);
-                    edit_text.bounds.set_width(intrinsic_bounds.width());
+                    edit_text.bounds.set_width(autosize_width);
                 }
 
-                edit_text.bounds.set_height(intrinsic_bounds.height());
+                edit_text.bounds.set_height(autosize_height);
                 edit_text.base.base.set_transformed_by_script(true);
                 drop(edit_text);
                 self.redraw_border(context.gc_context);
@@ -870,47 +1075,124 @@ impl<'gc> EditText<'gc> {
     pub fn maxscroll(self) -> usize {
         let edit_text = self.0.read();
 
-        let line_data = &edit_text.line_data;
-
-        if line_data.is_empty() {
-            return 1;
-        }
+        let lines = &edit_text.lines;
+        let last_line = match lines.last() {
+            Some(line) => line,
+            None => return 1,
+        };
 
-        let target = line_data.last().unwrap().extent - edit_text.bounds.height();
+        let target = last_line.bounds().extent_y() - edit_text.bounds.height()
+            + Twips::from_pixels(Self::INTERNAL_PADDING * 2.0);
 
         // minimum line n such that n.offset > max.extent - bounds.height()
-        let max_line = line_data.iter().find(|&&l| target < l.offset);
-        if let Some(line) = max_line {
-            line.index
-        } else {
-            // I don't know how this could happen, so return the limit
-            line_data.last().unwrap().index
-        }
+        lines
+            .iter()
+            .position(|l| target < l.bounds().offset_y())
+            .unwrap_or(lines.len() - 1)
+            + 1
     }
 
     /// The lowest visible line of text
     pub fn bottom_scroll(self) -> usize {
         let edit_text = self.0.read();
 
-        let line_data = &edit_text.line_data;
+        let lines = &edit_text.lines;
 
-        if line_data.is_empty() {
+        if lines.is_empty() {
             return 1;
         }
 
-        let scroll_offset = line_data
-            .get(edit_text.scroll - 1)
-            .map_or(Twips::ZERO, |l| l.offset);
-        let target = edit_text.bounds.height() + scroll_offset;
+        let target = edit_text.bounds.height() - Twips::from_pixels(Self::INTERNAL_PADDING * 2.0)
+            + Self::scroll_offset(&edit_text);
 
         // Line before first line with extent greater than bounds.height() + line "scroll"'s offset
-        let too_far = line_data.iter().find(|&&l| l.extent > target);
-        if let Some(line) = too_far {
-            line.index - 1
-        } else {
+        let too_far = lines.iter().position(|l| l.bounds().extent_y() > target);
+        match too_far {
+            // The line being scrolled to is always visible, even if it's cut off.
+            Some(index) => index.max(edit_text.scroll),
             // all lines are visible
-            line_data.last().unwrap().index
+            None => lines.len(),
+        }
+    }
+
+    /// How many lines of text there are.
+    pub fn num_lines(self) -> usize {
+        self.0.read().lines.len()
+    }
+
+    /// The metrics of a line of text, or `None` if there is no such line.
+    ///
+    /// Lines are indexed from 0.
+    pub fn line_metrics(self, line: usize) -> Option<LineMetrics> {
+        let edit_text = self.0.read();
+        let layout_line = edit_text.lines.get(line)?;
+        let bounds = layout_line.bounds();
+
+        Some(LineMetrics {
+            x: (bounds.offset_x() + edit_text.bounds.x_min).to_pixels() + Self::INTERNAL_PADDING,
+            width: bounds.width().to_pixels(),
+            height: (layout_line.ascent() + layout_line.descent() + layout_line.leading())
+                .to_pixels(),
+            ascent: layout_line.ascent().to_pixels(),
+            descent: layout_line.descent().to_pixels(),
+            leading: layout_line.leading().to_pixels(),
+        })
+    }
+
+    /// The vertical position of a line's baseline within this text field, or
+    /// `None` if there is no such line.
+    ///
+    /// This does not account for scrolling.
+    pub fn line_baseline(self, line: usize) -> Option<f64> {
+        let edit_text = self.0.read();
+        let layout_line = edit_text.lines.get(line)?;
+
+        Some(
+            (edit_text.bounds.y_min + layout_line.bounds().offset_y() + layout_line.ascent())
+                .to_pixels()
+                + Self::INTERNAL_PADDING,
+        )
+    }
+
+    /// The range of text positions that a line covers, or `None` if there is
+    /// no such line.
+    ///
+    /// Lines are indexed from 0. The range includes the newline character
+    /// that ends the line, if any.
+    pub fn line_range(self, line: usize) -> Option<Range<usize>> {
+        let edit_text = self.0.read();
+        let start = edit_text.lines.get(line)?.start();
+        let end = edit_text
+            .lines
+            .get(line + 1)
+            .map_or(edit_text.text_spans.text().len(), |l| l.start());
+
+        Some(start..end)
+    }
+
+    /// The line that a text position is on, or `None` if it is past the end
+    /// of the text.
+    pub fn line_index_of_char(self, position: usize) -> Option<usize> {
+        let edit_text = self.0.read();
+        if position >= edit_text.text_spans.text().len() {
+            return None;
         }
+
+        Some(
+            edit_text
+                .lines
+                .iter()
+                .rposition(|l| l.start() <= position)
+                .unwrap_or_default(),
+        )
+    }
+
+    /// How far down the text is scrolled, based on the current `scroll`.
+    fn scroll_offset(edit_text: &EditTextData<'gc>) -> Twips {
+        edit_text
+            .lines
+            .get(edit_text.scroll.saturating_sub(1))
+            .map_or(Twips::ZERO, |l| l.bounds().offset_y())
     }
 
     /// Render a layout box, plus its children.
@@ -955,6 +1237,7 @@ impl<'gc> EditText<'gc> {
         {
             let baseline_adjustment =
                 font.get_baseline_for_height(params.height()) - params.height();
+            let grid_fit = edit_text.render_settings.effective_grid_fit();
             font.evaluate(
                 text,
                 self.text_transform(color.clone(), baseline_adjustment),
@@ -987,10 +1270,24 @@ impl<'gc> EditText<'gc> {
                         }
                     }
 
-                    // Render glyph.
-                    context
-                        .renderer
-                        .render_shape(glyph.shape_handle, context.transform_stack.transform());
+                    // Render glyph, aligned to the pixel grid when using advanced
+                    // text rendering.
+                    if grid_fit == TextGridFit::None {
+                        context
+                            .renderer
+                            .render_shape(glyph.shape_handle, context.transform_stack.transform());
+                    } else {
+                        let zone = text
+                            .get(pos)
+                            .and_then(|c| char::from_u32(c.into()))
+                            .and_then(|c| font.align_zone_for_char(c));
+                        let mut glyph_transform = context.transform_stack.transform().clone();
+                        glyph_transform.matrix =
+                            grid_fit_matrix(glyph_transform.matrix, grid_fit, zone);
+                        context
+                            .renderer
+                            .render_shape(glyph.shape_handle, &glyph_transform);
+                    }
                     context.transform_stack.pop();
 
                     if let Some((caret_pos, length)) = caret {
@@ -1024,6 +1321,14 @@ impl<'gc> EditText<'gc> {
             drawing.render(context);
         }
 
+        if let LayoutContent::Image { index, offset } = lbox.content() {
+            if let Some(image) = edit_text.images.get(*index) {
+                context.transform_stack.push(&(*offset).into());
+                image.render(context);
+                context.transform_stack.pop();
+            }
+        }
+
         context.transform_stack.pop();
     }
 
@@ -1164,6 +1469,7 @@ impl<'gc> EditText<'gc> {
         gc_context: MutationContext<'gc, '_>,
     ) {
         let mut text = self.0.write(gc_context);
+        text.base.base.invalidate_cached_bitmap();
         if let Some(mut selection) = selection {
             selection.clamp(text.text_spans.text().len());
             text.selection = Some(selection);
@@ -1172,6 +1478,10 @@ impl<'gc> EditText<'gc> {
         }
     }
 
+    pub fn render_settings(self) -> TextRenderSettings {
+        self.0.read().render_settings.clone()
+    }
+
     pub fn set_render_settings(
         self,
         gc_context: MutationContext<'gc, '_>,
@@ -1185,7 +1495,9 @@ impl<'gc> EditText<'gc> {
     }
 
     pub fn set_hscroll(self, hscroll: f64, context: &mut UpdateContext<'_, 'gc, '_>) {
-        self.0.write(context.gc_context).hscroll = hscroll;
+        let mut edit_text = self.0.write(context.gc_context);
+        edit_text.hscroll = hscroll;
+        edit_text.base.base.invalidate_cached_bitmap();
     }
 
     pub fn scroll(self) -> usize {
@@ -1203,6 +1515,7 @@ impl<'gc> EditText<'gc> {
         };
         let clamped = scroll_lines.clamp(1, self.maxscroll());
         self.0.write(context.gc_context).scroll = clamped;
+        self.invalidate_cached_bitmap();
     }
 
     pub fn screen_position_to_index(self, position: (Twips, Twips)) -> Option<usize> {
@@ -1252,6 +1565,175 @@ impl<'gc> EditText<'gc> {
         None
     }
 
+    /// The URL and target of the link at a position on the screen, if any.
+    fn link_at(self, position: (Twips, Twips)) -> Option<(WString, WString)> {
+        let index = self.screen_position_to_index(position)?;
+        let text = self.0.read();
+        let (span_index, _) = text.text_spans.resolve_position_as_span(index)?;
+        let span = text.text_spans.span(span_index)?;
+        if span.url.is_empty() {
+            None
+        } else {
+            Some((span.url.clone(), span.target.clone()))
+        }
+    }
+
+    /// The text covered by the link at a position on the screen, if any.
+    ///
+    /// A link can span several text spans, e.g. if part of it is bold.
+    fn link_range_at(self, position: (Twips, Twips)) -> Option<Range<usize>> {
+        let index = self.screen_position_to_index(position)?;
+        let text = self.0.read();
+        let (span_index, _) = text.text_spans.resolve_position_as_span(index)?;
+        let url = &text.text_spans.span(span_index)?.url;
+        if url.is_empty() {
+            return None;
+        }
+
+        let mut link: Option<Range<usize>> = None;
+        for (start, end, _, span) in text.text_spans.iter_spans() {
+            if span.url == *url {
+                let start = link.map_or(start, |link| link.start);
+                link = Some(start..end);
+            } else if link.as_ref().map_or(false, |link| link.contains(&index)) {
+                break;
+            } else {
+                link = None;
+            }
+        }
+        link.filter(|link| link.contains(&index))
+    }
+
+    /// Style the link at a position on the screen with the `a:hover` style of
+    /// the style sheet, and restore the link that was styled before.
+    ///
+    /// Returns `true` if the text needs to be redrawn.
+    pub fn set_hovered_link(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        position: Option<(Twips, Twips)>,
+    ) -> bool {
+        let link = position.and_then(|position| self.link_range_at(position));
+
+        let mut write = self.0.write(context.gc_context);
+        let write = &mut *write;
+        if write.hovered_link.as_ref().map(|hovered| &hovered.range) == link.as_ref() {
+            return false;
+        }
+
+        let mut hover_format = TextFormat::default();
+        match &write.style_sheet {
+            Some(style_sheet) if style_sheet.style("a:hover").is_some() => {
+                style_sheet.apply("a:hover", &mut hover_format);
+            }
+            _ => return false,
+        }
+
+        if let Some(hovered) = write.hovered_link.take() {
+            for (range, format) in hovered.formats {
+                write
+                    .text_spans
+                    .set_text_format(range.start, range.end, &format);
+            }
+        }
+
+        if let Some(range) = link {
+            let formats = write
+                .text_spans
+                .iter_spans()
+                .filter(|(start, end, _, _)| *start >= range.start && *end <= range.end)
+                .map(|(start, end, _, span)| (start..end, span.get_text_format()))
+                .collect();
+            write
+                .text_spans
+                .set_text_format(range.start, range.end, &hover_format);
+            write.hovered_link = Some(HoveredLink { range, formats });
+        }
+
+        drop(write);
+        self.relayout(context);
+        true
+    }
+
+    /// Follow a link that was clicked in this text field.
+    ///
+    /// `asfunction:function,argument` links call a function on the parent
+    /// clip's timeline instead of navigating.
+    fn open_link(self, context: &mut UpdateContext<'_, 'gc, '_>, url: &WStr, target: &WStr) {
+        const ASFUNCTION: &[u8] = b"asfunction:";
+        const EVENT: &[u8] = b"event:";
+        let has_protocol = |protocol: &[u8]| {
+            url.len() >= protocol.len()
+                && url[..protocol.len()].eq_ignore_case(WStr::from_units(protocol))
+        };
+
+        if has_protocol(EVENT) && context.avm_type() == AvmType::Avm2 {
+            if let Avm2Value::Object(object) = self.object2() {
+                let text = AvmString::new(context.gc_context, &url[EVENT.len()..]);
+                if let Err(e) = Avm2::dispatch_link_event(context, object, text) {
+                    log::error!("Got error when dispatching link event: {}", e);
+                }
+            }
+            return;
+        }
+
+        if !has_protocol(ASFUNCTION) {
+            let target = if target.is_empty() {
+                None
+            } else {
+                Some(target.to_string())
+            };
+            context
+                .navigator
+                .navigate_to_url(url.to_string(), target, None);
+            return;
+        }
+
+        if context.avm_type() != AvmType::Avm1 {
+            return;
+        }
+        let parent = match self.avm1_parent() {
+            Some(parent) => parent,
+            None => return,
+        };
+
+        let call = &url[ASFUNCTION.len()..];
+        let (name, argument) = match call.find(b',') {
+            Some(comma) => (&call[..comma], Some(&call[comma + 1..])),
+            None => (call, None),
+        };
+
+        Avm1::run_with_stack_frame_for_display_object(
+            parent,
+            context.swf.version(),
+            context,
+            |activation| {
+                let gc_context = activation.context.gc_context;
+                let name = AvmString::new(gc_context, name);
+                let (this, function) = match activation.get_variable(name) {
+                    Ok(CallableValue::Callable(this, Avm1Value::Object(function))) => {
+                        (this, function)
+                    }
+                    Ok(CallableValue::UnCallable(Avm1Value::Object(function))) => {
+                        match parent.object() {
+                            Avm1Value::Object(this) => (this, function),
+                            _ => return,
+                        }
+                    }
+                    _ => return,
+                };
+
+                let args: Vec<Avm1Value<'gc>> = argument
+                    .map(|argument| AvmString::new(gc_context, argument).into())
+                    .into_iter()
+                    .collect();
+                if let Err(e) = function.call(name, activation, this, &args) {
+                    avm_error!(activation, "Couldn't call {} from a text link: {}", name, e);
+                }
+            },
+        );
+    }
+
     pub fn text_input(self, character: char, context: &mut UpdateContext<'_, 'gc, '_>) {
         if !self.0.read().is_editable {
             return;
@@ -1259,8 +1741,8 @@ impl<'gc> EditText<'gc> {
 
         if let Some(selection) = self.selection() {
             let mut changed = false;
-            match character as u8 {
-                8 | 127 if !selection.is_caret() => {
+            match character {
+                '\u{8}' | '\u{7f}' if !selection.is_caret() => {
                     // Backspace or delete with multiple characters selected
                     self.replace_text(selection.start(), selection.end(), WStr::empty(), context);
                     self.set_selection(
@@ -1269,7 +1751,7 @@ impl<'gc> EditText<'gc> {
                     );
                     changed = true;
                 }
-                8 => {
+                '\u{8}' => {
                     // Backspace with caret
                     if selection.start() > 0 {
                         // Delete previous character
@@ -1283,7 +1765,7 @@ impl<'gc> EditText<'gc> {
                         changed = true;
                     }
                 }
-                127 => {
+                '\u{7f}' => {
                     // Delete with caret
                     if selection.end() < self.text_length() {
                         // Delete next character
@@ -1294,92 +1776,330 @@ impl<'gc> EditText<'gc> {
                         changed = true;
                     }
                 }
-                code if !(code as char).is_control() => {
-                    self.replace_text(
-                        selection.start(),
-                        selection.end(),
-                        &WString::from_char(character),
-                        context,
-                    );
-                    let new_start = selection.start() + character.len_utf8();
-                    self.set_selection(
-                        Some(TextSelection::for_position(new_start)),
-                        context.gc_context,
-                    );
-                    changed = true;
+                _ if !character.is_control() => {
+                    changed = self.insert_input(std::iter::once(character), selection, context);
                 }
                 _ => {}
             }
 
             if changed {
-                let globals = context.avm1.global_object_cell();
-                let swf_version = context.swf.version();
-                let mut activation = Avm1Activation::from_nothing(
-                    context.reborrow(),
-                    ActivationIdentifier::root("[Propagate Text Binding]"),
-                    swf_version,
-                    globals,
-                    self.into(),
-                );
-                self.propagate_text_binding(&mut activation);
-                self.on_changed(&mut activation);
+                self.on_user_changed(context);
             }
         }
     }
 
-    /// Listens for keyboard text control commands.
+    /// Inserts text committed by an input method editor, replacing the
+    /// selection.
+    pub fn ime_commit(self, input: &str, context: &mut UpdateContext<'_, 'gc, '_>) {
+        if !self.0.read().is_editable {
+            return;
+        }
+
+        if let Some(selection) = self.selection() {
+            let input = input.chars().filter(|c| !c.is_control());
+            if self.insert_input(input, selection, context) {
+                self.on_user_changed(context);
+            }
+        }
+    }
+
+    /// Replaces the selection with text that the user typed or pasted, and
+    /// places the caret after it.
     ///
-    /// TODO: Add explicit text control events (#4452).
-    pub fn handle_text_control_event(
+    /// `restrict` and `maxChars` only apply to this text, so characters that
+    /// aren't allowed are left out and the text is cut off once the text
+    /// field is full. Returns whether any text was inserted.
+    fn insert_input(
+        self,
+        input: impl IntoIterator<Item = char>,
+        selection: TextSelection,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> bool {
+        let max_chars = self.max_chars() as usize;
+        let remaining_length = self.text_length() - (selection.end() - selection.start());
+        let mut available = if max_chars > 0 {
+            max_chars.saturating_sub(remaining_length)
+        } else {
+            usize::MAX
+        };
+
+        let mut text = WString::new();
+        {
+            let edit_text = self.0.read();
+            for character in input {
+                let character = match edit_text.restrict.filter(character) {
+                    Some(character) => character,
+                    None => continue,
+                };
+                if character.len_utf16() > available {
+                    break;
+                }
+                available -= character.len_utf16();
+                text.push_char(character);
+            }
+        }
+        if text.is_empty() {
+            return false;
+        }
+
+        self.replace_text(selection.start(), selection.end(), &text, context);
+        let new_start = selection.start() + text.len();
+        self.set_selection(
+            Some(TextSelection::for_position(new_start)),
+            context.gc_context,
+        );
+        true
+    }
+
+    /// Propagates a change to the text made by the user to the bound
+    /// variable, and notifies `onChanged` listeners.
+    fn on_user_changed(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let globals = context.avm1.global_object_cell();
+        let swf_version = context.swf.version();
+        let mut activation = Avm1Activation::from_nothing(
+            context.reborrow(),
+            ActivationIdentifier::root("[Propagate Text Binding]"),
+            swf_version,
+            globals,
+            self.into(),
+        );
+        self.propagate_text_binding(&mut activation);
+        self.on_changed(&mut activation);
+    }
+
+    /// Performs a keyboard editing command, such as moving the caret or
+    /// copying the selected text.
+    pub fn text_control_input(
         self,
+        control_code: TextControlCode,
         context: &mut UpdateContext<'_, 'gc, '_>,
-        event: ClipEvent,
     ) -> ClipEventResult {
-        if let ClipEvent::KeyPress { key_code } = event {
-            let mut edit_text = self.0.write(context.gc_context);
-            let selection = edit_text.selection;
-            if let Some(mut selection) = selection {
-                let text = edit_text.text_spans.text();
-                let length = text.len();
-                match key_code {
-                    ButtonKeyCode::Left => {
-                        if (context.ui.is_key_down(KeyCode::Shift) || selection.is_caret())
-                            && selection.to > 0
-                        {
-                            selection.to = string_utils::prev_char_boundary(text, selection.to);
-                            if !context.ui.is_key_down(KeyCode::Shift) {
-                                selection.from = selection.to;
-                            }
-                        } else if !context.ui.is_key_down(KeyCode::Shift) {
-                            selection.to = selection.start();
-                            selection.from = selection.to;
-                        }
-                        selection.clamp(length);
-                        edit_text.selection = Some(selection);
-                        return ClipEventResult::Handled;
-                    }
-                    ButtonKeyCode::Right => {
-                        if (context.ui.is_key_down(KeyCode::Shift) || selection.is_caret())
-                            && selection.to < length
-                        {
-                            selection.to = string_utils::next_char_boundary(text, selection.to);
-                            if !context.ui.is_key_down(KeyCode::Shift) {
-                                selection.from = selection.to;
-                            }
-                        } else if !context.ui.is_key_down(KeyCode::Shift) {
-                            selection.to = selection.end();
-                            selection.from = selection.to;
-                        }
-                        selection.clamp(length);
-                        edit_text.selection = Some(selection);
-                        return ClipEventResult::Handled;
-                    }
-                    _ => (),
+        let selection = match self.selection() {
+            Some(selection) => selection,
+            None => return ClipEventResult::NotHandled,
+        };
+        let is_editable = self.is_editable();
+
+        match control_code {
+            TextControlCode::Move { movement, select } => {
+                let to = self.caret_destination(selection, movement, select);
+                let from = if select { selection.from } else { to };
+                self.set_selection(Some(TextSelection::for_range(from, to)), context.gc_context);
+            }
+            TextControlCode::SelectAll => {
+                if !self.is_selectable() {
+                    return ClipEventResult::NotHandled;
                 }
+                self.set_selection(
+                    Some(TextSelection::for_range(0, self.text_length())),
+                    context.gc_context,
+                );
+            }
+            TextControlCode::Copy | TextControlCode::Cut => {
+                // Password fields don't give away their text.
+                if selection.is_caret() || self.is_password() {
+                    return ClipEventResult::NotHandled;
+                }
+                let text = self.text();
+                let selected = &text[selection.start()..selection.end()];
+                context.ui.set_clipboard_content(selected.to_string());
+
+                if control_code == TextControlCode::Cut && is_editable {
+                    self.replace_text(selection.start(), selection.end(), WStr::empty(), context);
+                    self.set_selection(
+                        Some(TextSelection::for_position(selection.start())),
+                        context.gc_context,
+                    );
+                    self.on_user_changed(context);
+                }
+            }
+            TextControlCode::Paste => {
+                if !is_editable {
+                    return ClipEventResult::NotHandled;
+                }
+                let content = context.ui.clipboard_content();
+                // Line breaks are only kept in multiline text fields.
+                let is_multiline = self.is_multiline();
+                let input = content
+                    .chars()
+                    .filter(|&c| c != '\n' || is_multiline)
+                    .filter(|&c| !c.is_control() || c == '\n')
+                    .map(|c| if c == '\n' { '\r' } else { c });
+                if self.insert_input(input, selection, context) {
+                    self.on_user_changed(context);
+                }
+            }
+            TextControlCode::Enter => {
+                if !is_editable || !self.is_multiline() {
+                    return ClipEventResult::NotHandled;
+                }
+                let input = WString::from_char('\r');
+                self.replace_text(selection.start(), selection.end(), &input, context);
+                self.set_selection(
+                    Some(TextSelection::for_position(selection.start() + input.len())),
+                    context.gc_context,
+                );
+                self.on_user_changed(context);
             }
         }
 
-        ClipEventResult::NotHandled
+        ClipEventResult::Handled
+    }
+
+    /// Where the caret moves to from a selection.
+    ///
+    /// Without extending the selection, moving left or right collapses the
+    /// selection to its start or end instead.
+    fn caret_destination(
+        self,
+        selection: TextSelection,
+        movement: CaretMovement,
+        select: bool,
+    ) -> usize {
+        let text = self.text();
+        let caret = selection.to;
+        match movement {
+            CaretMovement::Left if !select && !selection.is_caret() => selection.start(),
+            CaretMovement::Right if !select && !selection.is_caret() => selection.end(),
+            CaretMovement::Left => string_utils::prev_char_boundary(&text, caret),
+            CaretMovement::Right => string_utils::next_char_boundary(&text, caret),
+            CaretMovement::WordLeft => string_utils::prev_word_boundary(&text, caret),
+            CaretMovement::WordRight => string_utils::next_word_boundary(&text, caret),
+            CaretMovement::Up => self
+                .index_on_adjacent_line(caret, false)
+                .unwrap_or_else(|| {
+                    hard_line_start(&text, hard_line_start(&text, caret).saturating_sub(1))
+                }),
+            CaretMovement::Down => self
+                .index_on_adjacent_line(caret, true)
+                .unwrap_or_else(|| (hard_line_end(&text, caret) + 1).min(text.len())),
+            CaretMovement::LineStart => self
+                .line_range_at(caret)
+                .map_or_else(|| hard_line_start(&text, caret), |(start, _)| start),
+            CaretMovement::LineEnd => self
+                .line_range_at(caret)
+                .map_or_else(|| hard_line_end(&text, caret), |(_, end)| end),
+            CaretMovement::DocumentStart => 0,
+            CaretMovement::DocumentEnd => text.len(),
+        }
+    }
+
+    /// The positions that the caret can be placed at in a text layout box,
+    /// as pairs of a text position and the horizontal offset of the caret
+    /// in the layout.
+    fn caret_stops(edit_text: &EditTextData<'gc>, lbox: &LayoutBox<'gc>) -> Vec<(usize, Twips)> {
+        let start = match lbox.content() {
+            LayoutContent::Text { start, .. } => *start,
+            _ => return vec![],
+        };
+        let (text, _tf, font, params, _color) =
+            match lbox.as_renderable_text(edit_text.text_spans.text()) {
+                Some(renderable) => renderable,
+                None => return vec![],
+            };
+
+        let origin_x = lbox.bounds().offset_x();
+        let mut stops = vec![];
+        let mut end_x = Twips::ZERO;
+        font.evaluate(
+            text,
+            Default::default(),
+            params,
+            |pos, _transform, _glyph: &Glyph, advance, x| {
+                stops.push((start + pos, origin_x + x));
+                end_x = x + advance;
+            },
+        );
+        stops.push((start + text.len(), origin_x + end_x));
+        stops
+    }
+
+    /// The horizontal offset of the caret at a text position in the layout,
+    /// and the bounds of the layout box that it's in.
+    ///
+    /// A position between two boxes is in the box that it starts. Returns
+    /// `None` if the position isn't in any text box, such as on an empty
+    /// line.
+    fn caret_layout_position(
+        edit_text: &EditTextData<'gc>,
+        index: usize,
+    ) -> Option<(Twips, BoxBounds<Twips>)> {
+        let text_boxes = || {
+            edit_text
+                .layout
+                .iter()
+                .filter_map(|lbox| match lbox.content() {
+                    LayoutContent::Text { start, end, .. } => Some((*start, *end, lbox)),
+                    _ => None,
+                })
+        };
+        let (_, _, lbox) = text_boxes()
+            .find(|(start, end, _)| *start <= index && index < *end)
+            .or_else(|| text_boxes().filter(|(_, end, _)| *end == index).last())?;
+        let (_, x) = Self::caret_stops(edit_text, lbox)
+            .into_iter()
+            .find(|(stop, _)| *stop >= index)?;
+        Some((x, lbox.bounds()))
+    }
+
+    /// The text boxes on the same line as a vertical position in the layout.
+    fn boxes_on_line<'a>(
+        edit_text: &'a EditTextData<'gc>,
+        y: Twips,
+    ) -> impl Iterator<Item = &'a LayoutBox<'gc>> {
+        edit_text.layout.iter().filter(move |lbox| {
+            lbox.is_text_box() && lbox.bounds().offset_y() <= y && y < lbox.bounds().extent_y()
+        })
+    }
+
+    /// The start and end of the line that a text position is on, including
+    /// lines broken by word wrapping.
+    fn line_range_at(self, index: usize) -> Option<(usize, usize)> {
+        let edit_text = self.0.read();
+        let (_, bounds) = Self::caret_layout_position(&edit_text, index)?;
+        let y = bounds.offset_y() + bounds.height() / 2;
+        Self::boxes_on_line(&edit_text, y)
+            .filter_map(|lbox| match lbox.content() {
+                LayoutContent::Text { start, end, .. } => Some((*start, *end)),
+                _ => None,
+            })
+            .reduce(|(start, end), (box_start, box_end)| (start.min(box_start), end.max(box_end)))
+    }
+
+    /// The text position on the line above or below that is closest to the
+    /// caret at a text position, as moving the caret up or down does.
+    fn index_on_adjacent_line(self, index: usize, down: bool) -> Option<usize> {
+        let edit_text = self.0.read();
+        let (x, bounds) = Self::caret_layout_position(&edit_text, index)?;
+        let y = if down {
+            bounds.extent_y() + bounds.height() / 2
+        } else {
+            bounds.offset_y() - bounds.height() / 2
+        };
+        Self::boxes_on_line(&edit_text, y)
+            .flat_map(|lbox| Self::caret_stops(&edit_text, lbox))
+            .min_by_key(|(_, stop_x)| (*stop_x - x).get().abs())
+            .map(|(stop, _)| stop)
+    }
+
+    /// The position of the bottom of the caret on the stage, which input
+    /// method editors show their composition next to.
+    pub fn caret_screen_position(self) -> Option<(Twips, Twips)> {
+        let edit_text = self.0.read();
+        let caret = edit_text.selection?.to;
+        let scroll_offset = Self::scroll_offset(&edit_text);
+        let (x, y) = match Self::caret_layout_position(&edit_text, caret) {
+            Some((x, bounds)) => (x, bounds.extent_y()),
+            None => (Twips::ZERO, Twips::ZERO),
+        };
+        let local = (
+            edit_text.bounds.x_min
+                + Twips::from_pixels(Self::INTERNAL_PADDING - edit_text.hscroll)
+                + x,
+            edit_text.bounds.y_min + Twips::from_pixels(Self::INTERNAL_PADDING) - scroll_offset + y,
+        );
+        drop(edit_text);
+        Some(self.local_to_global(local))
     }
 
     fn initialize_as_broadcaster(&self, activation: &mut Avm1Activation<'_, 'gc, '_>) {
@@ -1512,8 +2232,13 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
         }
     }
 
-    fn run_frame(&self, _context: &mut UpdateContext) {
-        // Noop
+    fn run_frame(&self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let images = self.0.read().images.clone();
+        for image in images {
+            image.run_frame(context);
+        }
+
+        self.update_image_sizes(context);
     }
 
     fn as_edit_text(&self) -> Option<EditText<'gc>> {
@@ -1547,6 +2272,10 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
         if vm_type == AvmType::Avm1 {
             self.construct_as_avm1_object(context, display_object, run_frame);
         }
+
+        if !self.0.read().text_spans.images().is_empty() {
+            self.load_images(context);
+        }
     }
 
     fn object(&self) -> Avm1Value<'gc> {
@@ -1681,17 +2410,7 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
         );
         context.renderer.activate_mask();
 
-        let scroll_offset = if edit_text.scroll > 1 {
-            let line_data = &edit_text.line_data;
-
-            if let Some(line_data) = line_data.get(edit_text.scroll - 1) {
-                line_data.offset
-            } else {
-                Twips::ZERO
-            }
-        } else {
-            Twips::ZERO
-        };
+        let scroll_offset = Self::scroll_offset(&edit_text);
         // TODO: Where does this come from? How is this different than INTERNAL_PADDING? Does this apply to y as well?
         // If this is actually right, offset the border in `redraw_border` instead of doing an extra push.
         context.transform_stack.push(&Transform {
@@ -1751,6 +2470,12 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
     }
 
     fn unload(&self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let images = std::mem::take(&mut self.0.write(context.gc_context).images);
+        for image in images {
+            context.load_manager.cancel_movie_loads(image);
+            image.unload(context);
+        }
+
         let had_focus = self.0.read().has_focus;
         if had_focus {
             let tracker = context.focus_tracker;
@@ -1791,7 +2516,7 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
     ) -> Option<DisplayObject<'gc>> {
         // The button is hovered if the mouse is over any child nodes.
         if self.visible()
-            && self.is_selectable()
+            && (self.is_selectable() || self.link_at(point).is_some())
             && self.hit_test_shape(context, point, HitTestOptions::MOUSE_PICK)
         {
             Some((*self).into())
@@ -1800,12 +2525,19 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
         }
     }
 
-    fn mouse_cursor(&self) -> MouseCursor {
-        MouseCursor::IBeam
+    fn mouse_cursor(&self, context: &mut UpdateContext<'_, 'gc, '_>) -> MouseCursor {
+        if self.link_at(*context.mouse_position).is_some() {
+            MouseCursor::Hand
+        } else if self.is_selectable() {
+            MouseCursor::IBeam
+        } else {
+            MouseCursor::Arrow
+        }
     }
 
     fn on_focus_changed(&self, gc_context: MutationContext<'gc, '_>, focused: bool) {
         let mut text = self.0.write(gc_context);
+        text.base.base.invalidate_cached_bitmap();
         text.has_focus = focused;
         if !focused {
             text.selection = None;
@@ -1844,17 +2576,53 @@ impl<'gc> TInteractiveObject<'gc> for EditText<'gc> {
         context: &mut UpdateContext<'_, 'gc, '_>,
         _event: ClipEvent,
     ) -> ClipEventResult {
+        if let Some((url, target)) = self.link_at(*context.mouse_position) {
+            self.open_link(context, &url, &target);
+            if !self.is_selectable() {
+                return ClipEventResult::Handled;
+            }
+        }
+
+        // How soon a second click has to follow the first to select a word.
+        const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(500);
+
         let tracker = context.focus_tracker;
+        let had_selection = self.selection();
         tracker.set(Some(self.into()), context);
-        if let Some(position) = self
+        let position = self
             .screen_position_to_index(*context.mouse_position)
-            .map(TextSelection::for_position)
-        {
-            self.0.write(context.gc_context).selection = Some(position);
+            .unwrap_or_else(|| self.text_length());
+
+        let now = context.navigator.time_since_launch();
+        let is_double_click = matches!(
+            self.0.read().last_click,
+            Some((time, last_position))
+                if last_position == position && now.saturating_sub(time) < DOUBLE_CLICK_TIME
+        );
+        let selection = if is_double_click {
+            // Double-clicking selects the word under the mouse.
+            let range = string_utils::word_range_at(&self.text(), position);
+            TextSelection::for_range(range.start, range.end)
         } else {
-            self.0.write(context.gc_context).selection =
-                Some(TextSelection::for_position(self.text_length()));
-        }
+            match had_selection {
+                // Shift-clicking extends the selection to the click.
+                Some(selection) if context.ui.is_key_down(KeyCode::Shift) => {
+                    TextSelection::for_range(selection.from, position)
+                }
+                _ => TextSelection::for_position(position),
+            }
+        };
+
+        let mut edit_text = self.0.write(context.gc_context);
+        edit_text.selection = Some(selection);
+        // A third click starts over instead of being another double-click.
+        edit_text.last_click = if is_double_click {
+            None
+        } else {
+            Some((now, position))
+        };
+        drop(edit_text);
+        self.invalidate_cached_bitmap();
 
         ClipEventResult::Handled
     }
@@ -1901,15 +2669,17 @@ pub struct TextSelection {
     to: usize,
 }
 
-/// Information about the start and end y-coordinates of a given line of text
-#[derive(Copy, Clone, Debug, Collect)]
-#[collect(require_static)]
-pub struct LineData {
-    index: usize,
-    /// How many twips down the highest point of the line is
-    offset: Twips,
-    /// How many twips down the lowest point of the line is
-    extent: Twips,
+/// The metrics of a single line of text, in pixels, as exposed by
+/// `TextField.getLineMetrics`.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct LineMetrics {
+    /// The left edge of the line's text, relative to the text field's origin.
+    pub x: f64,
+    pub width: f64,
+    pub height: f64,
+    pub ascent: f64,
+    pub descent: f64,
+    pub leading: f64,
 }
 
 impl TextSelection {
@@ -1974,3 +2744,87 @@ impl TextSelection {
         self.to == self.from
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm1::error::Error as Avm1Error;
+    use crate::avm1::function::{FunctionObject, NativeFunction};
+    use crate::avm1::test_utils::with_avm;
+
+    /// Stores the first argument of the call, and the object it was called
+    /// on, in `_global`.
+    fn record_call<'gc>(
+        activation: &mut Avm1Activation<'_, 'gc, '_>,
+        this: Avm1Object<'gc>,
+        args: &[Avm1Value<'gc>],
+    ) -> Result<Avm1Value<'gc>, Avm1Error<'gc>> {
+        let globals = activation.context.avm1.global_object_cell();
+        let argument = args.get(0).cloned().unwrap_or(Avm1Value::Undefined);
+        globals.set("calledWith", argument, activation)?;
+        globals.set("calledOn", this.into(), activation)?;
+        Ok(Avm1Value::Undefined)
+    }
+
+    /// Follows `url` as a link in a text field on the root clip.
+    fn open_link_on_root<'gc>(
+        activation: &mut Avm1Activation<'_, 'gc, '_>,
+        root: Avm1Object<'gc>,
+        url: &str,
+    ) {
+        let parent = root.as_display_object().unwrap();
+        let movie = activation.context.swf.clone();
+        let text = EditText::new(&mut activation.context, movie, 0.0, 0.0, 100.0, 20.0);
+        text.set_parent(activation.context.gc_context, Some(parent));
+        text.open_link(
+            &mut activation.context,
+            &WString::from_utf8(url),
+            WStr::empty(),
+        );
+    }
+
+    #[test]
+    fn asfunction_link_calls_function_of_parent() {
+        with_avm(8, |activation, root| {
+            let fn_proto = activation.context.avm1.prototypes().function;
+            let function = FunctionObject::function(
+                activation.context.gc_context,
+                record_call as NativeFunction,
+                Some(fn_proto),
+                fn_proto,
+            );
+            root.set("record", function.into(), activation)?;
+
+            open_link_on_root(activation, root, "asfunction:record,some argument");
+
+            let globals = activation.context.avm1.global_object_cell();
+            assert_eq!(
+                globals.get("calledWith", activation)?,
+                AvmString::new_utf8(activation.context.gc_context, "some argument").into()
+            );
+            assert_eq!(globals.get("calledOn", activation)?, root.into());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn asfunction_link_without_argument() {
+        with_avm(8, |activation, root| {
+            let fn_proto = activation.context.avm1.prototypes().function;
+            let function = FunctionObject::function(
+                activation.context.gc_context,
+                record_call as NativeFunction,
+                Some(fn_proto),
+                fn_proto,
+            );
+            root.set("record", function.into(), activation)?;
+
+            open_link_on_root(activation, root, "ASFUNCTION:record");
+
+            let globals = activation.context.avm1.global_object_cell();
+            assert_eq!(globals.get("calledWith", activation)?, Avm1Value::Undefined);
+            assert_eq!(globals.get("calledOn", activation)?, root.into());
+            Ok(())
+        });
+    }
+}
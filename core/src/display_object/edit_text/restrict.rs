@@ -0,0 +1,164 @@
+//! Filtering of typed characters, as set by the `restrict` property of text
+//! fields.
+
+use crate::string::{WStr, WString};
+
+/// The set of characters that users are allowed to type into a text field.
+#[derive(Clone, Debug, Default)]
+pub struct TextRestrict {
+    /// The string this set was parsed from, or `None` if any character may
+    /// be typed.
+    value: Option<WString>,
+
+    /// Inclusive ranges of characters, and whether they are allowed.
+    ///
+    /// Later ranges take precedence over earlier ones.
+    ranges: Vec<(bool, char, char)>,
+}
+
+impl TextRestrict {
+    /// Parse a restrict string, such as `"A-Z 0-9"` or `"^aeiou"`.
+    ///
+    /// Each `^` switches between listing allowed and disallowed characters,
+    /// `-` forms a range between two characters, and `\` escapes the next
+    /// character.
+    pub fn new(value: Option<&WStr>) -> Self {
+        let value = match value {
+            Some(value) => value,
+            None => return Self::default(),
+        };
+
+        let chars: Vec<char> = value
+            .chars()
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect();
+        let mut ranges = Vec::new();
+        let mut allowed = true;
+        let mut i = 0;
+        while i < chars.len() {
+            let start = match chars[i] {
+                '^' => {
+                    allowed = !allowed;
+                    i += 1;
+                    continue;
+                }
+                '\\' => match chars.get(i + 1) {
+                    Some(c) => {
+                        i += 1;
+                        *c
+                    }
+                    None => break,
+                },
+                c => c,
+            };
+            i += 1;
+
+            let mut end = start;
+            if chars.get(i) == Some(&'-') {
+                let (escaped, next) = match chars.get(i + 1) {
+                    Some('\\') => (true, chars.get(i + 2)),
+                    next => (false, next),
+                };
+                if let Some(next) = next {
+                    end = *next;
+                    i += if escaped { 3 } else { 2 };
+                }
+            }
+            ranges.push((allowed, start, end));
+        }
+
+        Self {
+            value: Some(value.into()),
+            ranges,
+        }
+    }
+
+    /// The string this set was parsed from.
+    pub fn value(&self) -> Option<&WStr> {
+        self.value.as_deref()
+    }
+
+    pub fn is_allowed(&self, c: char) -> bool {
+        if self.value.is_none() {
+            return true;
+        }
+
+        // A set that starts by disallowing characters allows all others.
+        let mut allowed = matches!(self.ranges.first(), Some((false, _, _)));
+        for &(range_allowed, start, end) in &self.ranges {
+            if (start..=end).contains(&c) {
+                allowed = range_allowed;
+            }
+        }
+        allowed
+    }
+
+    /// The character to insert when `c` is typed, if any.
+    ///
+    /// Letters that are only allowed in the other case are converted, as in
+    /// Flash Player.
+    pub fn filter(&self, c: char) -> Option<char> {
+        if self.is_allowed(c) {
+            return Some(c);
+        }
+
+        let other_case = if c.is_lowercase() {
+            c.to_uppercase().next()
+        } else {
+            c.to_lowercase().next()
+        };
+        other_case.filter(|other| *other != c && self.is_allowed(*other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn restrict(value: &str) -> TextRestrict {
+        TextRestrict::new(Some(&WString::from_utf8(value)))
+    }
+
+    #[test]
+    fn unrestricted() {
+        let restrict = TextRestrict::new(None);
+        assert!(restrict.is_allowed('a'));
+        assert!(restrict.is_allowed('\u{263A}'));
+    }
+
+    #[test]
+    fn empty_allows_nothing() {
+        assert!(!restrict("").is_allowed('a'));
+    }
+
+    #[test]
+    fn ranges_and_exclusions() {
+        let restrict = restrict("A-Z^Q 0-9");
+        assert!(restrict.is_allowed('A'));
+        assert!(restrict.is_allowed('Z'));
+        assert!(!restrict.is_allowed('Q'));
+        // Everything after a `^` is excluded, until the next one.
+        assert!(!restrict.is_allowed('5'));
+        assert!(!restrict.is_allowed('-'));
+
+        let restrict = self::restrict("^0-9");
+        assert!(restrict.is_allowed('a'));
+        assert!(!restrict.is_allowed('7'));
+    }
+
+    #[test]
+    fn escapes() {
+        let restrict = restrict("0-9\\-\\^\\\\");
+        assert!(restrict.is_allowed('-'));
+        assert!(restrict.is_allowed('^'));
+        assert!(restrict.is_allowed('\\'));
+        assert!(!restrict.is_allowed('a'));
+    }
+
+    #[test]
+    fn case_conversion() {
+        let restrict = restrict("A-Z");
+        assert_eq!(restrict.filter('q'), Some('Q'));
+        assert_eq!(restrict.filter('1'), None);
+    }
+}
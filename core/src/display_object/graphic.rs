@@ -7,6 +7,9 @@ use crate::context::{RenderContext, UpdateContext};
 use crate::display_object::{DisplayObjectBase, DisplayObjectPtr, TDisplayObject};
 use crate::drawing::Drawing;
 use crate::prelude::*;
+use crate::shape_utils::{
+    scale_legacy_strokes, NineSliceCache, ShapeCacheKey, StrokeScale, StrokeScaling,
+};
 use crate::tag_utils::SwfMovie;
 use crate::vminterface::{AvmType, Instantiator};
 use gc_arena::{Collect, GcCell, MutationContext};
@@ -24,15 +27,20 @@ pub struct GraphicData<'gc> {
     static_data: gc_arena::Gc<'gc, GraphicStatic>,
     avm2_object: Option<Avm2Object<'gc>>,
     drawing: Option<Drawing>,
+
+    /// The shape tessellated for the 9-slice grid of the parent.
+    #[collect(require_static)]
+    nine_slice: NineSliceCache,
 }
 
 impl<'gc> Graphic<'gc> {
     /// Construct a `Graphic` from it's associated `Shape` tag.
     pub fn from_swf_tag(
         context: &mut UpdateContext<'_, 'gc, '_>,
-        swf_shape: swf::Shape,
+        mut swf_shape: swf::Shape,
         movie: Arc<SwfMovie>,
     ) -> Self {
+        scale_legacy_strokes(&mut swf_shape);
         let library = context.library.library_for_movie(movie.clone()).unwrap();
         let static_data = GraphicStatic {
             id: swf_shape.id,
@@ -42,6 +50,7 @@ impl<'gc> Graphic<'gc> {
                     .renderer
                     .register_shape((&swf_shape).into(), library),
             ),
+            stroke_scaling: StrokeScaling::from_shape(&swf_shape),
             shape: swf_shape,
             movie: Some(movie),
         };
@@ -53,6 +62,7 @@ impl<'gc> Graphic<'gc> {
                 static_data: gc_arena::Gc::allocate(context.gc_context, static_data),
                 avm2_object: None,
                 drawing: None,
+                nine_slice: NineSliceCache::default(),
             },
         ))
     }
@@ -81,6 +91,7 @@ impl<'gc> Graphic<'gc> {
                 shape: Vec::new(),
             },
             movie: None,
+            stroke_scaling: StrokeScaling::default(),
         };
         let drawing = Drawing::new();
 
@@ -91,6 +102,7 @@ impl<'gc> Graphic<'gc> {
                 static_data: gc_arena::Gc::allocate(context.gc_context, static_data),
                 avm2_object: Some(avm2_object),
                 drawing: Some(drawing),
+                nine_slice: NineSliceCache::default(),
             },
         ))
     }
@@ -106,7 +118,9 @@ impl<'gc> TDisplayObject<'gc> for Graphic<'gc> {
     }
 
     fn instantiate(&self, gc_context: MutationContext<'gc, '_>) -> DisplayObject<'gc> {
-        Self(GcCell::allocate(gc_context, self.0.read().clone())).into()
+        let mut data = self.0.read().clone();
+        data.nine_slice = NineSliceCache::default();
+        Self(GcCell::allocate(gc_context, data)).into()
     }
 
     fn as_ptr(&self) -> *const DisplayObjectPtr {
@@ -125,6 +139,14 @@ impl<'gc> TDisplayObject<'gc> for Graphic<'gc> {
         }
     }
 
+    fn self_rect_bounds(&self) -> BoundingBox {
+        if let Some(drawing) = &self.0.read().drawing {
+            drawing.self_edge_bounds()
+        } else {
+            self.0.read().static_data.shape.edge_bounds.clone().into()
+        }
+    }
+
     fn construct_frame(&self, context: &mut UpdateContext<'_, 'gc, '_>) {
         if context.avm_type() == AvmType::Avm2 && matches!(self.object2(), Avm2Value::Undefined) {
             let shape_constr = context.avm2.classes().shape;
@@ -151,7 +173,10 @@ impl<'gc> TDisplayObject<'gc> for Graphic<'gc> {
             .library_for_movie_mut(self.movie().unwrap())
             .get_graphic(id)
         {
-            self.0.write(context.gc_context).static_data = new_graphic.0.read().static_data;
+            let mut write = self.0.write(context.gc_context);
+            write.static_data = new_graphic.0.read().static_data;
+            write.base.invalidate_cached_bitmap();
+            write.nine_slice.invalidate();
         } else {
             log::warn!("PlaceObject: expected Graphic at character ID {}", id);
         }
@@ -167,12 +192,46 @@ impl<'gc> TDisplayObject<'gc> for Graphic<'gc> {
             return;
         }
 
-        if let Some(drawing) = &self.0.read().drawing {
-            drawing.render(context);
-        } else if let Some(render_handle) = self.0.read().static_data.render_handle {
-            context
-                .renderer
-                .render_shape(render_handle, context.transform_stack.transform())
+        let nine_slice = context
+            .scaling_grid
+            .as_ref()
+            .and_then(|grid| grid.nine_slice(*self.base().matrix()));
+        let read = self.0.read();
+        if let Some(drawing) = &read.drawing {
+            drawing.render_with_nine_slice(context, nine_slice.as_ref());
+        } else if let Some(render_handle) = read.static_data.render_handle {
+            let static_data = &read.static_data;
+            let transform = context.transform_stack.transform();
+            let library = static_data
+                .movie
+                .clone()
+                .and_then(|movie| context.library.library_for_movie(movie));
+            let render_handle = match (library, &nine_slice) {
+                (Some(library), Some(nine_slice)) => {
+                    read.nine_slice
+                        .handle(context.renderer, library, nine_slice, || {
+                            (&static_data.shape).into()
+                        })
+                }
+                (Some(library), None)
+                    if static_data
+                        .stroke_scaling
+                        .is_scale_dependent(&transform.matrix) =>
+                {
+                    let key = ShapeCacheKey {
+                        id: static_data.id,
+                        ratio: 0,
+                        scale: StrokeScale::from_matrix(&transform.matrix),
+                    };
+                    library
+                        .shape_cache()
+                        .handle(context.renderer, library, key, || {
+                            (&static_data.shape).into()
+                        })
+                }
+                _ => render_handle,
+            };
+            context.renderer.render_shape(render_handle, transform)
         }
     }
 
@@ -180,8 +239,12 @@ impl<'gc> TDisplayObject<'gc> for Graphic<'gc> {
         &self,
         _context: &mut UpdateContext<'_, 'gc, '_>,
         point: (Twips, Twips),
-        _options: HitTestOptions,
+        options: HitTestOptions,
     ) -> bool {
+        if options.contains(HitTestOptions::SKIP_INVISIBLE) && !self.visible() {
+            return false;
+        }
+
         // Transform point to local coordinates and test.
         if self.world_bounds().contains(point) {
             let local_matrix = self.global_to_local_matrix();
@@ -241,6 +304,7 @@ impl<'gc> TDisplayObject<'gc> for Graphic<'gc> {
         if write.drawing.is_none() {
             write.drawing = Some(Drawing::new());
         }
+        write.base.invalidate_cached_bitmap();
 
         Some(RefMut::map(write, |m| m.drawing.as_mut().unwrap()))
     }
@@ -256,4 +320,7 @@ struct GraphicStatic {
     render_handle: Option<ShapeHandle>,
     bounds: BoundingBox,
     movie: Option<Arc<SwfMovie>>,
+
+    /// Whether the strokes of the shape need to be tessellated again at some scales.
+    stroke_scaling: StrokeScaling,
 }
@@ -1,10 +1,11 @@
-use crate::backend::render::ShapeHandle;
+use crate::backend::render::{BitmapSource, RenderBackend, ShapeHandle};
 use crate::context::{RenderContext, UpdateContext};
 use crate::display_object::{DisplayObjectBase, DisplayObjectPtr, TDisplayObject};
 use crate::prelude::*;
+use crate::shape_utils::{ShapeCacheKey, StrokeScale, StrokeScaling};
 use crate::tag_utils::SwfMovie;
 use gc_arena::{Collect, Gc, GcCell, MutationContext};
-use std::cell::{Ref, RefMut};
+use std::cell::{Ref, RefCell, RefMut};
 use std::sync::Arc;
 use swf::{Fixed16, Fixed8, Twips};
 
@@ -40,7 +41,9 @@ impl<'gc> MorphShape<'gc> {
     }
 
     pub fn set_ratio(&mut self, gc_context: MutationContext<'gc, '_>, ratio: u16) {
-        self.0.write(gc_context).ratio = ratio;
+        let mut write = self.0.write(gc_context);
+        write.ratio = ratio;
+        write.base.invalidate_cached_bitmap();
     }
 }
 
@@ -86,37 +89,69 @@ impl<'gc> TDisplayObject<'gc> for MorphShape<'gc> {
     }
 
     fn render_self(&self, context: &mut RenderContext) {
-        if let Some(frame) = self.0.read().static_data.frames.get(&self.ratio()) {
-            context
-                .renderer
-                .render_shape(frame.shape_handle, context.transform_stack.transform());
+        let static_data = self.0.read().static_data;
+        let library = context
+            .library
+            .library_for_movie(Arc::clone(&static_data.movie));
+        let library = match library {
+            Some(library) => library,
+            None => return,
+        };
+        let frame = static_data.frame(context.renderer, library, self.ratio());
+        let transform = context.transform_stack.transform();
+        let shape_handle = if frame.stroke_scaling.is_scale_dependent(&transform.matrix) {
+            let key = ShapeCacheKey {
+                id: static_data.id,
+                ratio: quantize_ratio(self.ratio()),
+                scale: StrokeScale::from_matrix(&transform.matrix),
+            };
+            library
+                .shape_cache()
+                .handle(context.renderer, library, key, || (&frame.shape).into())
         } else {
-            log::warn!("Missing ratio for morph shape");
-        }
+            frame.shape_handle
+        };
+        context.renderer.render_shape(shape_handle, transform);
     }
 
     fn self_bounds(&self) -> BoundingBox {
-        // TODO: Use the bounds of the current ratio.
-        if let Some(frame) = self.0.read().static_data.frames.get(&self.ratio()) {
+        let static_data = self.0.read().static_data;
+        let frames = static_data.frames.borrow();
+        if let Some(frame) = frames.get(&quantize_ratio(self.ratio())) {
             frame.bounds.clone()
         } else {
-            BoundingBox::default()
+            // The frame hasn't been rendered yet; interpolate the bounds of the shape.
+            let (a, b) = lerp_weights(quantize_ratio(self.ratio()));
+            (&lerp_rectangle(
+                &static_data.start.shape_bounds,
+                &static_data.end.shape_bounds,
+                a,
+                b,
+            ))
+                .into()
         }
     }
 
     fn hit_test_shape(
         &self,
-        _context: &mut UpdateContext<'_, 'gc, '_>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
         point: (Twips, Twips),
-        _options: HitTestOptions,
+        options: HitTestOptions,
     ) -> bool {
+        if options.contains(HitTestOptions::SKIP_INVISIBLE) && !self.visible() {
+            return false;
+        }
+
         if self.world_bounds().contains(point) {
-            if let Some(frame) = self.0.read().static_data.frames.get(&self.ratio()) {
+            let static_data = self.0.read().static_data;
+            if let Some(library) = context
+                .library
+                .library_for_movie(Arc::clone(&static_data.movie))
+            {
+                let frame = static_data.frame(context.renderer, library, self.ratio());
                 let local_matrix = self.global_to_local_matrix();
                 let point = local_matrix * point;
                 return crate::shape_utils::shape_hit_test(&frame.shape, point, &local_matrix);
-            } else {
-                log::warn!("Missing ratio for morph shape");
             }
         }
 
@@ -129,6 +164,9 @@ struct Frame {
     shape_handle: ShapeHandle,
     shape: swf::Shape,
     bounds: BoundingBox,
+
+    /// Whether the strokes of the frame need to be tessellated again at some scales.
+    stroke_scaling: StrokeScaling,
 }
 
 /// Static data shared between all instances of a morph shape.
@@ -139,7 +177,11 @@ pub struct MorphShapeStatic {
     id: CharacterId,
     start: swf::MorphShape,
     end: swf::MorphShape,
-    frames: fnv::FnvHashMap<u16, Frame>,
+    has_non_scaling_strokes: bool,
+    has_scaling_strokes: bool,
+
+    /// The interpolated frames by their quantized ratio.
+    frames: RefCell<fnv::FnvHashMap<u16, Frame>>,
     movie: Arc<SwfMovie>,
 }
 
@@ -149,11 +191,23 @@ impl MorphShapeStatic {
         swf_tag: &swf::DefineMorphShape,
         movie: Arc<SwfMovie>,
     ) -> Self {
-        let mut morph_shape = Self {
+        let mut start = swf_tag.start.clone();
+        let mut end = swf_tag.end.clone();
+        if swf_tag.version < 2 {
+            // The line styles of `DefineMorphShape` have no scaling flags, and
+            // always scale.
+            for style in start.line_styles.iter_mut().chain(&mut end.line_styles) {
+                style.allow_scale_x = true;
+                style.allow_scale_y = true;
+            }
+        }
+        let morph_shape = Self {
             id: swf_tag.id,
-            start: swf_tag.start.clone(),
-            end: swf_tag.end.clone(),
-            frames: fnv::FnvHashMap::default(),
+            start,
+            end,
+            has_non_scaling_strokes: swf_tag.has_non_scaling_strokes,
+            has_scaling_strokes: swf_tag.has_scaling_strokes,
+            frames: RefCell::new(fnv::FnvHashMap::default()),
             movie,
         };
         // Pre-register the start and end states.
@@ -162,22 +216,43 @@ impl MorphShapeStatic {
         morph_shape
     }
 
-    pub fn register_ratio(&mut self, context: &mut UpdateContext<'_, '_, '_>, ratio: u16) {
-        if self.frames.contains_key(&ratio) {
-            // Already registered.
-            return;
+    /// Interpolates and tessellates the frame for the given ratio ahead of time.
+    pub fn register_ratio(&self, context: &mut UpdateContext<'_, '_, '_>, ratio: u16) {
+        if let Some(library) = context.library.library_for_movie(Arc::clone(&self.movie)) {
+            self.frame(context.renderer, library, ratio);
         }
+    }
 
-        let library = context
-            .library
-            .library_for_movie(Arc::clone(&self.movie))
-            .unwrap();
+    /// The frame for the given ratio, which is interpolated and tessellated the first time it
+    /// is needed.
+    ///
+    /// Ratios are quantized, so that tweens with many frames share tessellations.
+    fn frame(
+        &self,
+        renderer: &mut dyn RenderBackend,
+        library: &dyn BitmapSource,
+        ratio: u16,
+    ) -> Ref<'_, Frame> {
+        let ratio = quantize_ratio(ratio);
+        if !self.frames.borrow().contains_key(&ratio) {
+            let shape = self.interpolate(ratio);
+            let shape_handle = renderer.register_shape((&shape).into(), library);
+            let frame = Frame {
+                shape_handle,
+                stroke_scaling: StrokeScaling::from_shape(&shape),
+                bounds: (&shape.shape_bounds).into(),
+                shape,
+            };
+            self.frames.borrow_mut().insert(ratio, frame);
+        }
+        Ref::map(self.frames.borrow(), |frames| &frames[&ratio])
+    }
 
+    /// Interpolates the start and end shapes into a shape for the given ratio.
+    fn interpolate(&self, ratio: u16) -> swf::Shape {
         // Interpolate MorphShapes into a Shape.
         use swf::{FillStyle, LineStyle, ShapeRecord, ShapeStyles};
-        // Start shape is ratio 65535, end shape is ratio 0.
-        let b = f32::from(ratio) / 65535.0;
-        let a = 1.0 - b;
+        let (a, b) = lerp_weights(ratio);
         let fill_styles: Vec<FillStyle> = self
             .start
             .fill_styles
@@ -196,7 +271,10 @@ impl MorphShapeStatic {
                 start_cap: start.start_cap,
                 end_cap: start.end_cap,
                 join_style: start.join_style,
-                fill_style: None,
+                fill_style: match (&start.fill_style, &end.fill_style) {
+                    (Some(start), Some(end)) => Some(lerp_fill(start, end, a, b)),
+                    (start, _) => start.clone(),
+                },
                 allow_scale_x: start.allow_scale_x,
                 allow_scale_y: start.allow_scale_y,
                 is_pixel_hinted: start.is_pixel_hinted,
@@ -266,7 +344,6 @@ impl MorphShapeStatic {
                         ));
                     }
                     shape.push(ShapeRecord::StyleChange(style_change));
-                    Self::update_pos(&mut end_x, &mut end_y, s);
                     end = end_iter.next();
                     continue;
                 }
@@ -285,26 +362,17 @@ impl MorphShapeStatic {
             line_styles,
         };
 
-        let bounds = crate::shape_utils::calculate_shape_bounds(&shape[..]);
-        let shape = swf::Shape {
+        swf::Shape {
             version: 4,
             id: 0,
-            shape_bounds: bounds.clone(),
-            edge_bounds: bounds.clone(),
+            shape_bounds: lerp_rectangle(&self.start.shape_bounds, &self.end.shape_bounds, a, b),
+            edge_bounds: lerp_rectangle(&self.start.edge_bounds, &self.end.edge_bounds, a, b),
             has_fill_winding_rule: false,
-            has_non_scaling_strokes: false,
-            has_scaling_strokes: true,
+            has_non_scaling_strokes: self.has_non_scaling_strokes,
+            has_scaling_strokes: self.has_scaling_strokes,
             styles,
             shape,
-        };
-
-        let shape_handle = context.renderer.register_shape((&shape).into(), library);
-        let frame = Frame {
-            shape_handle,
-            shape,
-            bounds: bounds.into(),
-        };
-        self.frames.insert(ratio, frame);
+        }
     }
 
     fn update_pos(x: &mut Twips, y: &mut Twips, record: &swf::ShapeRecord) {
@@ -333,23 +401,54 @@ impl MorphShapeStatic {
     }
 }
 
+/// The number of ratios between each interpolated frame of a morph shape.
+const RATIO_STEP: u32 = 16;
+
+/// Rounds a ratio to the nearest frame that is interpolated.
+///
+/// The start and end ratios are always kept exactly.
+fn quantize_ratio(ratio: u16) -> u16 {
+    let ratio = (u32::from(ratio) + RATIO_STEP / 2) / RATIO_STEP * RATIO_STEP;
+    ratio.min(u16::MAX.into()) as u16
+}
+
 // Interpolation functions
 // These interpolate between two SWF shape structures.
 // a + b should = 1.0
 
-fn lerp_color(start: &Color, end: &Color, a: f32, b: f32) -> Color {
+/// The weights of the start and end shapes at the given ratio.
+fn lerp_weights(ratio: u16) -> (f32, f32) {
+    // Start shape is ratio 0, end shape is ratio 65535.
+    let b = f32::from(ratio) / 65535.0;
+    (1.0 - b, b)
+}
+
+fn lerp_u8(start: u8, end: u8, a: f32, b: f32) -> u8 {
     // f32 -> u8 cast is defined to saturate for out of bounds values,
     // so we don't have to worry about clamping.
+    (a * f32::from(start) + b * f32::from(end)).round() as u8
+}
+
+fn lerp_color(start: &Color, end: &Color, a: f32, b: f32) -> Color {
     Color {
-        r: (a * f32::from(start.r) + b * f32::from(end.r)) as u8,
-        g: (a * f32::from(start.g) + b * f32::from(end.g)) as u8,
-        b: (a * f32::from(start.b) + b * f32::from(end.b)) as u8,
-        a: (a * f32::from(start.a) + b * f32::from(end.a)) as u8,
+        r: lerp_u8(start.r, end.r, a, b),
+        g: lerp_u8(start.g, end.g, a, b),
+        b: lerp_u8(start.b, end.b, a, b),
+        a: lerp_u8(start.a, end.a, a, b),
     }
 }
 
 fn lerp_twips(start: Twips, end: Twips, a: f32, b: f32) -> Twips {
-    Twips::new((start.get() as f32 * a + end.get() as f32 * b) as i32)
+    Twips::new((start.get() as f32 * a + end.get() as f32 * b).round() as i32)
+}
+
+fn lerp_rectangle(start: &swf::Rectangle, end: &swf::Rectangle, a: f32, b: f32) -> swf::Rectangle {
+    swf::Rectangle {
+        x_min: lerp_twips(start.x_min, end.x_min, a, b),
+        x_max: lerp_twips(start.x_max, end.x_max, a, b),
+        y_min: lerp_twips(start.y_min, end.y_min, a, b),
+        y_max: lerp_twips(start.y_max, end.y_max, a, b),
+    }
 }
 
 fn lerp_fill(start: &swf::FillStyle, end: &swf::FillStyle, a: f32, b: f32) -> swf::FillStyle {
@@ -399,7 +498,7 @@ fn lerp_fill(start: &swf::FillStyle, end: &swf::FillStyle, a: f32, b: f32) -> sw
             },
         ) => FillStyle::FocalGradient {
             gradient: lerp_gradient(start, end, a, b),
-            focal_point: *start_focal * Fixed8::from_f32(a) + *end_focal * Fixed8::from_f32(b),
+            focal_point: Fixed8::from_f32(start_focal.to_f32() * a + end_focal.to_f32() * b),
         },
 
         // All other combinations should not occur, because SWF stores the start/end fill as the same type, always.
@@ -512,13 +611,13 @@ fn lerp_edges(
 fn lerp_matrix(start: &swf::Matrix, end: &swf::Matrix, a: f32, b: f32) -> swf::Matrix {
     // TODO: Lerping a matrix element-wise is geometrically wrong,
     // but I doubt Flash is decomposing the matrix into scale-rotate-translate?
-    let af = Fixed16::from_f32(a);
-    let bf = Fixed16::from_f32(b);
+    let lerp =
+        |start: Fixed16, end: Fixed16| Fixed16::from_f32(start.to_f32() * a + end.to_f32() * b);
     swf::Matrix {
-        a: start.a * af + end.a * bf,
-        b: start.b * af + end.b * bf,
-        c: start.c * af + end.c * bf,
-        d: start.d * af + end.d * bf,
+        a: lerp(start.a, end.a),
+        b: lerp(start.b, end.b),
+        c: lerp(start.c, end.c),
+        d: lerp(start.d, end.d),
         tx: lerp_twips(start.tx, end.tx, a, b),
         ty: lerp_twips(start.ty, end.ty, a, b),
     }
@@ -533,7 +632,7 @@ fn lerp_gradient(start: &swf::Gradient, end: &swf::Gradient, a: f32, b: f32) ->
         .iter()
         .zip(end.records.iter())
         .map(|(start, end)| swf::GradientRecord {
-            ratio: (f32::from(start.ratio) * a + f32::from(end.ratio) * b) as u8,
+            ratio: lerp_u8(start.ratio, end.ratio, a, b),
             color: lerp_color(&start.color, &end.color, a, b),
         })
         .collect();
@@ -325,6 +325,10 @@ impl<'gc> MovieClip<'gc> {
                 .0
                 .write(context.gc_context)
                 .define_font_4(context, reader),
+            TagCode::DefineFontAlignZones => self
+                .0
+                .write(context.gc_context)
+                .define_font_align_zones(context, reader),
             TagCode::DefineMorphShape => self.0.write(context.gc_context).define_morph_shape(
                 context,
                 reader,
@@ -337,6 +341,10 @@ impl<'gc> MovieClip<'gc> {
                 morph_shapes,
                 2,
             ),
+            TagCode::DefineScalingGrid => self
+                .0
+                .write(context.gc_context)
+                .define_scaling_grid(context, reader),
             TagCode::DefineShape => self
                 .0
                 .write(context.gc_context)
@@ -954,6 +962,14 @@ impl<'gc> MovieClip<'gc> {
         self.0.read().static_data.total_frames
     }
 
+    /// The AVM1 constructor registered for this clip's symbol by `Object.registerClass`.
+    pub fn registered_avm1_constructor(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Option<Avm1Object<'gc>> {
+        self.0.read().get_registered_avm1_constructor(context)
+    }
+
     pub fn set_avm2_class(
         self,
         gc_context: MutationContext<'gc, '_>,
@@ -1517,12 +1533,11 @@ impl<'gc> MovieClip<'gc> {
                 }
             }
 
+            // The registered class is looked up once this runs, as it may be
+            // registered after the clip is placed.
             context.action_queue.queue_actions(
                 display_object,
-                ActionType::Construct {
-                    constructor: avm1_constructor,
-                    events,
-                },
+                ActionType::Construct { events },
                 false,
             );
         } else if run_frame {
@@ -1854,14 +1869,27 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
     }
 
     fn render_self(&self, context: &mut RenderContext<'_, 'gc>) {
-        self.0.read().drawing.render(context);
-        self.render_children(context);
+        let scaling_grid = super::scaling_grid((*self).into());
+        let nine_slice = scaling_grid
+            .as_ref()
+            .and_then(|grid| grid.nine_slice(Matrix::default()));
+        self.0
+            .read()
+            .drawing
+            .render_with_nine_slice(context, nine_slice.as_ref());
+        super::render_in_scaling_grid(context, scaling_grid, |context| {
+            self.render_children(context)
+        });
     }
 
     fn self_bounds(&self) -> BoundingBox {
         self.0.read().drawing.self_bounds()
     }
 
+    fn self_rect_bounds(&self) -> BoundingBox {
+        self.0.read().drawing.self_edge_bounds()
+    }
+
     fn hit_test_shape(
         &self,
         context: &mut UpdateContext<'_, 'gc, '_>,
@@ -1984,7 +2012,7 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
         None
     }
 
-    fn mouse_cursor(&self) -> MouseCursor {
+    fn mouse_cursor(&self, _context: &mut UpdateContext<'_, 'gc, '_>) -> MouseCursor {
         if self.use_hand_cursor() {
             MouseCursor::Hand
         } else {
@@ -2005,7 +2033,9 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
     }
 
     fn as_drawing(&self, gc_context: MutationContext<'gc, '_>) -> Option<RefMut<'_, Drawing>> {
-        Some(RefMut::map(self.0.write(gc_context), |s| &mut s.drawing))
+        let write = self.0.write(gc_context);
+        write.base.base.invalidate_cached_bitmap();
+        Some(RefMut::map(write, |s| &mut s.drawing))
     }
 
     fn post_instantiation(
@@ -2088,7 +2118,8 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
     }
 
     fn allow_as_mask(&self) -> bool {
-        !self.is_empty()
+        // A clip with only drawn lines still masks by the shape of the lines.
+        !self.is_empty() || !self.0.read().drawing.is_empty()
     }
 
     fn is_focusable(&self) -> bool {
@@ -2532,6 +2563,41 @@ impl<'gc, 'a> MovieClipData<'gc> {
         Ok(())
     }
 
+    fn define_scaling_grid(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<'a>,
+    ) -> DecodeResult {
+        let id = reader.read_u16()?;
+        let splitter_rect = reader.read_rectangle()?;
+        let library = context.library.library_for_movie_mut(self.movie());
+        let scaling_grid = Some(splitter_rect.into());
+        match library.character_by_id(id) {
+            Some(Character::MovieClip(movie_clip)) => {
+                movie_clip.set_scaling_grid(context.gc_context, scaling_grid);
+            }
+            Some(Character::Avm1Button(button)) => {
+                button.set_scaling_grid(context.gc_context, scaling_grid);
+            }
+            Some(Character::Avm2Button(button)) => {
+                button.set_scaling_grid(context.gc_context, scaling_grid);
+            }
+            Some(_) => {
+                log::warn!(
+                    "Tried to apply DefineScalingGrid to non-sprite character ID {}",
+                    id
+                );
+            }
+            None => {
+                log::warn!(
+                    "Tried to apply DefineScalingGrid to unregistered character ID {}",
+                    id
+                );
+            }
+        }
+        Ok(())
+    }
+
     fn csm_text_settings(
         &mut self,
         context: &mut UpdateContext<'_, 'gc, '_>,
@@ -2908,10 +2974,41 @@ impl<'gc, 'a> MovieClipData<'gc> {
     #[inline]
     fn define_font_4(
         &mut self,
-        _context: &mut UpdateContext<'_, 'gc, '_>,
-        _reader: &mut SwfStream<'a>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<'a>,
     ) -> DecodeResult {
-        log::warn!("DefineFont4 tag (TLF text) is not implemented");
+        let font = reader.read_define_font_4()?;
+        match Font::from_font4_tag(context.gc_context, &font, reader.encoding()) {
+            Some(font_object) => context
+                .library
+                .library_for_movie_mut(self.movie())
+                .register_character(font.id, Character::Font(font_object)),
+            None => log::warn!("DefineFont4 tag {} contains no valid font data", font.id),
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn define_font_align_zones(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<'a>,
+    ) -> DecodeResult {
+        if let swf::Tag::DefineFontAlignZones { id, zones, .. } =
+            reader.read_define_font_align_zones()?
+        {
+            match context
+                .library
+                .library_for_movie_mut(self.movie())
+                .character_by_id(id)
+            {
+                Some(Character::Font(font)) => font.set_align_zones(&zones),
+                _ => log::warn!(
+                    "Tried to apply DefineFontAlignZones to non-font character ID {}",
+                    id
+                ),
+            }
+        }
         Ok(())
     }
 
@@ -7,6 +7,7 @@ use crate::avm2::{
 };
 use crate::config::Letterbox;
 use crate::context::{RenderContext, UpdateContext};
+use crate::damage::DamageRegion;
 use crate::display_object::container::{
     ChildContainer, DisplayObjectContainer, TDisplayObjectContainer,
 };
@@ -14,7 +15,7 @@ use crate::display_object::interactive::{
     InteractiveObject, InteractiveObjectBase, TInteractiveObject,
 };
 use crate::display_object::{
-    render_base, DisplayObject, DisplayObjectBase, DisplayObjectPtr, TDisplayObject,
+    collect_damage, render_base, DisplayObject, DisplayObjectBase, DisplayObjectPtr, TDisplayObject,
 };
 use crate::events::{ClipEvent, ClipEventResult};
 use crate::prelude::*;
@@ -134,6 +135,7 @@ impl<'gc> Stage<'gc> {
 
     pub fn set_background_color(self, gc_context: MutationContext<'gc, '_>, color: Option<Color>) {
         self.0.write(gc_context).background_color = color;
+        self.invalidate_cached_bitmap();
     }
 
     pub fn inverse_view_matrix(self) -> Matrix {
@@ -148,7 +150,8 @@ impl<'gc> Stage<'gc> {
     }
 
     pub fn set_letterbox(self, gc_context: MutationContext<'gc, '_>, letterbox: Letterbox) {
-        self.0.write(gc_context).letterbox = letterbox
+        self.0.write(gc_context).letterbox = letterbox;
+        self.invalidate_cached_bitmap();
     }
 
     /// Get the size of the SWF file.
@@ -163,8 +166,7 @@ impl<'gc> Stage<'gc> {
 
     /// Returns the quality setting of the stage.
     ///
-    /// In the Flash Player, the quality setting affects anti-aliasing and smoothing of bitmaps.
-    /// This setting is currently ignored in Ruffle.
+    /// The quality setting affects anti-aliasing and smoothing of bitmaps.
     /// Used by AVM1 `stage.quality` and AVM2 `Stage.quality` properties.
     pub fn quality(self) -> StageQuality {
         self.0.read().quality
@@ -172,8 +174,8 @@ impl<'gc> Stage<'gc> {
 
     /// Sets the quality setting of the stage.
     ///
-    /// In the Flash Player, the quality setting affects anti-aliasing and smoothing of bitmaps.
-    /// This setting is currently ignored in Ruffle.
+    /// The quality setting affects anti-aliasing and smoothing of bitmaps, and
+    /// the whole stage is rendered again with the new setting.
     /// Used by AVM1 `stage.quality` and AVM2 `Stage.quality` properties.
     pub fn set_quality(self, gc_context: MutationContext<'gc, '_>, quality: StageQuality) {
         let mut this = self.0.write(gc_context);
@@ -186,6 +188,8 @@ impl<'gc> Stage<'gc> {
                 | StageQuality::High16x16
                 | StageQuality::High16x16Linear
         );
+        drop(this);
+        self.invalidate_cached_bitmap();
     }
 
     /// Get the size of the stage.
@@ -264,6 +268,25 @@ impl<'gc> Stage<'gc> {
         }
     }
 
+    /// Update the stage display state after the frontend left or entered
+    /// fullscreen on its own, such as when the user presses Escape in a
+    /// browser.
+    ///
+    /// Unlike `set_display_state`, this does not ask the UI backend to change
+    /// the fullscreen state.
+    pub fn display_state_changed(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        display_state: StageDisplayState,
+    ) {
+        if Self::is_fullscreen_state(display_state) == self.is_fullscreen() {
+            return;
+        }
+
+        self.0.write(context.gc_context).display_state = display_state;
+        self.fire_fullscreen_event(context);
+    }
+
     /// Get the stage alignment.
     pub fn align(self) -> StageAlign {
         self.0.read().align
@@ -332,6 +355,15 @@ impl<'gc> Stage<'gc> {
     }
 
     /// Determine if we should letterbox the stage content.
+    /// Draws the stage and its letterbox, without beginning or ending a frame.
+    pub fn render_contents(self, context: &mut RenderContext<'_, 'gc>) {
+        render_base(self.into(), context);
+
+        if self.should_letterbox() {
+            self.draw_letterbox(context);
+        }
+    }
+
     fn should_letterbox(self) -> bool {
         // Only enable letterbox is the default `ShowAll` scale mode.
         // If content changes the scale mode or alignment, it signals that it is size-aware.
@@ -671,14 +703,37 @@ impl<'gc> TDisplayObject<'gc> for Stage<'gc> {
             .background_color()
             .unwrap_or_else(|| Color::from_rgb(0xffffff, 255));
 
-        context.renderer.begin_frame(background_color);
-
-        render_base((*self).into(), context);
-
-        if self.should_letterbox() {
-            self.draw_letterbox(context);
+        // Only the areas that changed since the last frame are rendered again,
+        // unless something about the stage itself changed.
+        let viewport_size = self.viewport_size();
+        let full_frame = self.base().damaged.get();
+        let mut damage = DamageRegion::default();
+        collect_damage(
+            (*self).into(),
+            &Matrix::default(),
+            &mut damage,
+            viewport_size,
+            false,
+        );
+        if !full_frame && damage.is_empty() {
+            // Nothing changed, so the last frame is still up to date.
+            return;
+        }
+        context.renderer.set_quality(self.quality());
+        let viewport_area = u64::from(viewport_size.0) * u64::from(viewport_size.1);
+        if full_frame
+            || !context.renderer.supports_partial_frames()
+            || damage.area() * 4 > viewport_area * 3
+        {
+            context.renderer.begin_frame(background_color);
+        } else {
+            context
+                .renderer
+                .begin_partial_frame(background_color, damage.rects());
         }
 
+        self.render_contents(context);
+
         context.renderer.end_frame();
     }
 
@@ -929,8 +984,8 @@ impl FromWStr for StageAlign {
 
 /// The quality setting of the `Stage`.
 ///
-/// In the Flash Player, this settings affects anti-aliasing and bitmap smoothing.
-/// These settings currently have no effect in Ruffle, but the active setting is still stored.
+/// This setting affects anti-aliasing and bitmap smoothing. Render backends
+/// use as many anti-aliasing samples as they support, up to the amount of the setting.
 /// [StageQuality in the AS3 Reference](https://help.adobe.com/en_US/FlashPlatform/reference/actionscript/3/flash/display/StageQuality.html)
 #[derive(Clone, Collect, Copy, Debug, Eq, PartialEq)]
 #[collect(require_static)]
@@ -983,6 +1038,26 @@ impl StageQuality {
             StageQuality::High16x16 | StageQuality::High16x16Linear => "16X16",
         }
     }
+
+    /// Returns the number of anti-aliasing samples along each axis of a pixel for this
+    /// quality setting, as in the names of the `8X8` and `16X16` settings.
+    pub fn sample_count(self) -> u32 {
+        match self {
+            StageQuality::Low => 1,
+            StageQuality::Medium => 2,
+            StageQuality::High | StageQuality::Best => 4,
+            StageQuality::High8x8 | StageQuality::High8x8Linear => 8,
+            StageQuality::High16x16 | StageQuality::High16x16Linear => 16,
+        }
+    }
+
+    /// Returns whether bitmaps may be smoothed with this quality setting.
+    ///
+    /// Bitmaps are never smoothed at low quality, even if smoothing is
+    /// enabled for them.
+    pub fn smooths_bitmaps(self) -> bool {
+        self != StageQuality::Low
+    }
 }
 
 impl Default for StageQuality {
@@ -1,6 +1,6 @@
 use crate::context::{RenderContext, UpdateContext};
 use crate::display_object::{DisplayObjectBase, DisplayObjectPtr, TDisplayObject};
-use crate::font::TextRenderSettings;
+use crate::font::{grid_fit_matrix, TextGridFit, TextRenderSettings};
 use crate::prelude::*;
 use crate::tag_utils::SwfMovie;
 use crate::transform::Transform;
@@ -111,6 +111,7 @@ impl<'gc> TDisplayObject<'gc> for Text<'gc> {
         let mut font_id = 0;
         let mut height = Twips::ZERO;
         let mut transform: Transform = Default::default();
+        let grid_fit = tf.render_settings.effective_grid_fit();
         for block in &tf.static_data.text_blocks {
             if let Some(x) = block.x_offset {
                 transform.matrix.tx = x;
@@ -134,9 +135,22 @@ impl<'gc> TDisplayObject<'gc> for Text<'gc> {
                 for c in &block.glyphs {
                     if let Some(glyph) = font.get_glyph(c.index as usize) {
                         context.transform_stack.push(&transform);
-                        context
-                            .renderer
-                            .render_shape(glyph.shape_handle, context.transform_stack.transform());
+                        if grid_fit == TextGridFit::None {
+                            context.renderer.render_shape(
+                                glyph.shape_handle,
+                                context.transform_stack.transform(),
+                            );
+                        } else {
+                            let mut glyph_transform = context.transform_stack.transform().clone();
+                            glyph_transform.matrix = grid_fit_matrix(
+                                glyph_transform.matrix,
+                                grid_fit,
+                                font.get_align_zone(c.index as usize),
+                            );
+                            context
+                                .renderer
+                                .render_shape(glyph.shape_handle, &glyph_transform);
+                        }
                         context.transform_stack.pop();
                         transform.matrix.tx += Twips::new(c.advance);
                     }
@@ -10,6 +10,7 @@ use crate::bounding_box::BoundingBox;
 use crate::context::{RenderContext, UpdateContext};
 use crate::display_object::{DisplayObjectBase, DisplayObjectPtr, TDisplayObject};
 use crate::prelude::*;
+use crate::streams::NetStream;
 use crate::tag_utils::{SwfMovie, SwfSlice};
 use crate::vminterface::{AvmObject, AvmType, Instantiator};
 use gc_arena::{Collect, GcCell, MutationContext};
@@ -44,6 +45,12 @@ pub struct VideoData<'gc> {
     #[collect(require_static)]
     decoded_frame: Option<(u32, BitmapInfo)>,
 
+    /// Whether decoded frames are smoothed when scaled.
+    smoothing: bool,
+
+    /// The stream whose frames are shown in place of this video's own.
+    net_stream: Option<NetStream<'gc>>,
+
     /// AVM representation of this video player.
     object: Option<AvmObject<'gc>>,
 
@@ -96,6 +103,7 @@ impl<'gc> Video<'gc> {
         streamdef: DefineVideoStream,
         mc: MutationContext<'gc, '_>,
     ) -> Self {
+        let smoothing = streamdef.is_smoothed;
         let source = GcCell::allocate(
             mc,
             VideoSource::Swf {
@@ -112,12 +120,32 @@ impl<'gc> Video<'gc> {
                 source,
                 stream: VideoStream::Uninstantiated(0),
                 decoded_frame: None,
+                smoothing,
+                net_stream: None,
                 object: None,
                 keyframes: BTreeSet::new(),
             },
         ))
     }
 
+    /// Whether or not decoded frames are smoothed when scaled.
+    pub fn smoothing(self) -> bool {
+        self.0.read().smoothing
+    }
+
+    pub fn set_smoothing(self, mc: MutationContext<'gc, '_>, smoothing: bool) {
+        self.0.write(mc).smoothing = smoothing;
+    }
+
+    /// Show the video frames of a `NetStream`, or stop doing so.
+    pub fn attach_net_stream(
+        self,
+        mc: MutationContext<'gc, '_>,
+        net_stream: Option<NetStream<'gc>>,
+    ) {
+        self.0.write(mc).net_stream = net_stream;
+    }
+
     /// Preload frame data from an SWF.
     ///
     /// This function yields an error if this video player is not playing an
@@ -213,6 +241,23 @@ impl<'gc> Video<'gc> {
             }
         };
 
+        // Restarting from a keyframe also requires the decoder to forget
+        // about any frames it has decoded so far.
+        let restarts_from_keyframe =
+            !is_ordered_seek && last_frame.map_or(true, |lf| sweep_from != lf + 1);
+        let sweep_from = match &read.stream {
+            VideoStream::Instantiated(stream) if restarts_from_keyframe => {
+                match context.video.seek_video_stream(*stream, sweep_from) {
+                    Ok(keyframe) => keyframe,
+                    Err(e) => {
+                        log::error!("Got error when seeking video stream: {}", e);
+                        sweep_from
+                    }
+                }
+            }
+            _ => sweep_from,
+        };
+
         drop(read);
 
         for fr in sweep_from..=frame_id {
@@ -268,7 +313,9 @@ impl<'gc> Video<'gc> {
 
         match res {
             Ok(bitmap) => {
-                self.0.write(context.gc_context).decoded_frame = Some((frame_id, bitmap));
+                let mut write = self.0.write(context.gc_context);
+                write.decoded_frame = Some((frame_id, bitmap));
+                write.base.invalidate_cached_bitmap();
             }
             Err(e) => log::error!("Got error when seeking to video frame {}: {}", frame_id, e),
         }
@@ -440,7 +487,12 @@ impl<'gc> TDisplayObject<'gc> for Video<'gc> {
 
         context.transform_stack.push(&*self.base().transform());
 
-        if let Some((_frame_id, ref bitmap)) = self.0.read().decoded_frame {
+        let read = self.0.read();
+        let bitmap = match read.net_stream {
+            Some(net_stream) => net_stream.decoded_frame(),
+            None => read.decoded_frame.map(|(_frame_id, bitmap)| bitmap),
+        };
+        if let Some(bitmap) = bitmap {
             let mut transform = context.transform_stack.transform().clone();
             let bounds = self.self_bounds();
 
@@ -451,9 +503,10 @@ impl<'gc> TDisplayObject<'gc> for Video<'gc> {
                 bounds.height().to_pixels() as f32 / bitmap.height as f32,
             );
 
+            let smoothing = read.smoothing && context.stage.quality().smooths_bitmaps();
             context
                 .renderer
-                .render_bitmap(bitmap.handle, &transform, false);
+                .render_bitmap(bitmap.handle, &transform, smoothing);
         } else {
             log::warn!("Video has no decoded frame to render.");
         }
@@ -1,8 +1,12 @@
 use crate::backend::render::{BitmapInfo, BitmapSource, ShapeHandle};
 use crate::bounding_box::BoundingBox;
 use crate::context::RenderContext;
-use crate::shape_utils::{DistilledShape, DrawCommand, DrawPath};
+use crate::shape_utils::{
+    DistilledShape, DrawCommand, DrawPath, NineSlice, NineSliceCache, ScaledStrokeCache,
+    StrokeScaling,
+};
 use gc_arena::Collect;
+use std::borrow::Cow;
 use std::cell::Cell;
 use swf::{FillStyle, LineStyle, Twips};
 
@@ -10,6 +14,8 @@ use swf::{FillStyle, LineStyle, Twips};
 #[collect(require_static)]
 pub struct Drawing {
     render_handle: Cell<Option<ShapeHandle>>,
+    scaled_strokes: ScaledStrokeCache,
+    nine_slice: NineSliceCache,
     shape_bounds: BoundingBox,
     edge_bounds: BoundingBox,
     dirty: Cell<bool>,
@@ -32,6 +38,8 @@ impl Drawing {
     pub fn new() -> Self {
         Self {
             render_handle: Cell::new(None),
+            scaled_strokes: ScaledStrokeCache::default(),
+            nine_slice: NineSliceCache::default(),
             shape_bounds: BoundingBox::default(),
             edge_bounds: BoundingBox::default(),
             dirty: Cell::new(false),
@@ -48,6 +56,8 @@ impl Drawing {
     pub fn from_swf_shape(shape: &swf::Shape) -> Self {
         let mut this = Self {
             render_handle: Cell::new(None),
+            scaled_strokes: ScaledStrokeCache::default(),
+            nine_slice: NineSliceCache::default(),
             shape_bounds: shape.shape_bounds.clone().into(),
             edge_bounds: shape.edge_bounds.clone().into(),
             dirty: Cell::new(true),
@@ -141,6 +151,19 @@ impl Drawing {
         self.dirty.set(true);
     }
 
+    /// Sets the fill of the current line, such as a gradient.
+    ///
+    /// Lines drawn after this use the new fill. This does nothing if there is no line style.
+    pub fn set_line_fill_style(&mut self, fill_style: Option<FillStyle>) {
+        if let Some(line) = &self.current_line {
+            let style = LineStyle {
+                fill_style,
+                ..line.style.clone()
+            };
+            self.set_line_style(Some(style));
+        }
+    }
+
     pub fn draw_command(&mut self, command: DrawCommand) {
         let add_to_bounds = if let DrawCommand::MoveTo { .. } = command {
             // Close any pending fills before moving.
@@ -189,75 +212,122 @@ impl Drawing {
     }
 
     pub fn render(&self, context: &mut RenderContext) {
+        self.render_with_nine_slice(context, None);
+    }
+
+    /// Renders the drawing with its points moved for the 9-slice grid of its display object.
+    pub fn render_with_nine_slice(
+        &self,
+        context: &mut RenderContext,
+        nine_slice: Option<&NineSlice>,
+    ) {
         if self.dirty.get() {
             self.dirty.set(false);
-            let mut paths = Vec::new();
-
-            for fill in &self.fills {
-                paths.push(DrawPath::Fill {
-                    style: &fill.style,
-                    commands: fill.commands.to_owned(),
-                })
-            }
-
-            if let Some(fill) = &self.current_fill {
-                paths.push(DrawPath::Fill {
-                    style: &fill.style,
-                    commands: fill.commands.to_owned(),
-                })
-            }
-
-            for line in &self.lines {
-                paths.push(DrawPath::Stroke {
-                    style: &line.style,
-                    commands: line.commands.to_owned(),
-                    is_closed: line.is_closed,
-                })
-            }
-
-            if let Some(line) = &self.current_line {
-                let mut commands = line.commands.to_owned();
-                let is_closed = if self.current_fill.is_some() {
-                    commands.push(DrawCommand::LineTo {
-                        x: self.fill_start.0,
-                        y: self.fill_start.1,
-                    });
-                    true
-                } else {
-                    self.cursor == self.fill_start
-                };
-                paths.push(DrawPath::Stroke {
-                    style: &line.style,
-                    commands,
-                    is_closed,
-                })
-            }
-
-            let shape = DistilledShape {
-                paths,
-                shape_bounds: self.shape_bounds.clone(),
-                edge_bounds: self.edge_bounds.clone(),
-                id: 0,
-            };
+            let shape = self.distilled_shape();
             if let Some(handle) = self.render_handle.get() {
                 context.renderer.replace_shape(shape, self, handle);
             } else {
                 self.render_handle
                     .set(Some(context.renderer.register_shape(shape, self)));
             }
+            self.scaled_strokes.invalidate();
+            self.nine_slice.invalidate();
         }
 
         if let Some(handle) = self.render_handle.get() {
-            context
-                .renderer
-                .render_shape(handle, context.transform_stack.transform());
+            let transform = context.transform_stack.transform();
+            let line_styles = self
+                .lines
+                .iter()
+                .chain(&self.current_line)
+                .map(|line| &line.style);
+            let handle = if let Some(nine_slice) = nine_slice {
+                self.nine_slice
+                    .handle(context.renderer, self, nine_slice, || {
+                        self.distilled_shape()
+                    })
+            } else if StrokeScaling::from_line_styles(line_styles)
+                .is_scale_dependent(&transform.matrix)
+            {
+                self.scaled_strokes
+                    .handle(context.renderer, self, &transform.matrix, || {
+                        self.distilled_shape()
+                    })
+            } else {
+                handle
+            };
+            context.renderer.render_shape(handle, transform);
         }
     }
 
+    fn distilled_shape(&self) -> DistilledShape {
+        let mut paths = Vec::new();
+
+        for fill in &self.fills {
+            paths.push(DrawPath::Fill {
+                style: &fill.style,
+                commands: fill.commands.to_owned(),
+            })
+        }
+
+        if let Some(fill) = &self.current_fill {
+            paths.push(DrawPath::Fill {
+                style: &fill.style,
+                commands: fill.commands.to_owned(),
+            })
+        }
+
+        for line in &self.lines {
+            paths.push(DrawPath::Stroke {
+                style: &line.style,
+                commands: line.commands.to_owned(),
+                is_closed: line.is_closed,
+            })
+        }
+
+        if let Some(line) = &self.current_line {
+            let mut commands = line.commands.to_owned();
+            let is_closed = if self.current_fill.is_some() {
+                commands.push(DrawCommand::LineTo {
+                    x: self.fill_start.0,
+                    y: self.fill_start.1,
+                });
+                true
+            } else {
+                self.cursor == self.fill_start
+            };
+            paths.push(DrawPath::Stroke {
+                style: &line.style,
+                commands,
+                is_closed,
+            })
+        }
+
+        DistilledShape {
+            paths,
+            shape_bounds: self.shape_bounds.clone(),
+            edge_bounds: self.edge_bounds.clone(),
+            id: 0,
+        }
+    }
+
+    /// Whether nothing has been drawn, neither fills nor strokes.
+    pub fn is_empty(&self) -> bool {
+        self.fills.is_empty()
+            && self.lines.is_empty()
+            && self.current_fill.is_none()
+            && self.current_line.is_none()
+    }
+
     pub fn self_bounds(&self) -> BoundingBox {
         self.shape_bounds.clone()
     }
 
+    /// The bounds of the drawing, excluding strokes.
+    pub fn self_edge_bounds(&self) -> BoundingBox {
+        self.edge_bounds.clone()
+    }
+
     pub fn hit_test(&self, point: (Twips, Twips), local_matrix: &crate::matrix::Matrix) -> bool {
         use crate::shape_utils;
         for fill in &self.fills {
@@ -269,7 +339,7 @@ impl Drawing {
         for line in &self.lines {
             if shape_utils::draw_command_stroke_hit_test(
                 &line.commands,
-                line.style.width,
+                &line.style,
                 point,
                 local_matrix,
             ) {
@@ -285,36 +355,22 @@ impl Drawing {
         }
 
         if let Some(line) = &self.current_line {
+            // Stroke auto-closes if part of a fill; also check the closing line segment.
+            let mut commands = Cow::Borrowed(&line.commands);
+            if self.current_fill.is_some() && self.cursor != self.fill_start {
+                commands.to_mut().push(DrawCommand::LineTo {
+                    x: self.fill_start.0,
+                    y: self.fill_start.1,
+                });
+            }
             if shape_utils::draw_command_stroke_hit_test(
-                &line.commands,
-                line.style.width,
+                &commands,
+                &line.style,
                 point,
                 local_matrix,
             ) {
                 return true;
             }
-
-            // Stroke auto-closes if part of a fill; also check the closing line segment.
-            if self.current_fill.is_some()
-                && self.cursor != self.fill_start
-                && shape_utils::draw_command_stroke_hit_test(
-                    &[
-                        DrawCommand::MoveTo {
-                            x: self.cursor.0,
-                            y: self.cursor.1,
-                        },
-                        DrawCommand::LineTo {
-                            x: self.fill_start.0,
-                            y: self.fill_start.1,
-                        },
-                    ],
-                    line.style.width,
-                    point,
-                    local_matrix,
-                )
-            {
-                return true;
-            }
         }
 
         false
@@ -2,14 +2,37 @@ use swf::ClipEventFlag;
 
 #[derive(Debug)]
 pub enum PlayerEvent {
-    KeyDown { key_code: KeyCode },
-    KeyUp { key_code: KeyCode },
-    MouseMove { x: f64, y: f64 },
-    MouseUp { x: f64, y: f64 },
-    MouseDown { x: f64, y: f64 },
+    KeyDown {
+        key_code: KeyCode,
+    },
+    KeyUp {
+        key_code: KeyCode,
+    },
+    MouseMove {
+        x: f64,
+        y: f64,
+    },
+    MouseUp {
+        x: f64,
+        y: f64,
+    },
+    MouseDown {
+        x: f64,
+        y: f64,
+    },
     MouseLeft,
-    MouseWheel { delta: MouseWheelDelta },
-    TextInput { codepoint: char },
+    MouseWheel {
+        delta: MouseWheelDelta,
+    },
+    TextInput {
+        codepoint: char,
+    },
+
+    /// Text committed by an input method editor, such as a composed
+    /// Chinese, Japanese or Korean word.
+    ImeCommit {
+        text: String,
+    },
 }
 
 /// The distance scrolled by the mouse wheel.
@@ -32,6 +55,69 @@ impl MouseWheelDelta {
     }
 }
 
+/// An editing command for the focused text field, such as moving the caret
+/// or copying the selected text.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TextControlCode {
+    /// Moves the caret, or extends the selection to the new caret position
+    /// if `select` is set.
+    Move {
+        movement: CaretMovement,
+        select: bool,
+    },
+    SelectAll,
+    Copy,
+    Cut,
+    Paste,
+
+    /// Inserts a line break into a multiline text field.
+    Enter,
+}
+
+impl TextControlCode {
+    /// Returns the editing command that pressing a key performs, given
+    /// whether the Control and Shift keys are held down.
+    pub fn from_key(key_code: KeyCode, control: bool, shift: bool) -> Option<Self> {
+        let movement = match (key_code, control) {
+            (KeyCode::Left, false) => CaretMovement::Left,
+            (KeyCode::Left, true) => CaretMovement::WordLeft,
+            (KeyCode::Right, false) => CaretMovement::Right,
+            (KeyCode::Right, true) => CaretMovement::WordRight,
+            (KeyCode::Up, _) => CaretMovement::Up,
+            (KeyCode::Down, _) => CaretMovement::Down,
+            (KeyCode::Home, false) => CaretMovement::LineStart,
+            (KeyCode::Home, true) => CaretMovement::DocumentStart,
+            (KeyCode::End, false) => CaretMovement::LineEnd,
+            (KeyCode::End, true) => CaretMovement::DocumentEnd,
+            (KeyCode::A, true) => return Some(Self::SelectAll),
+            (KeyCode::C, true) => return Some(Self::Copy),
+            (KeyCode::X, true) => return Some(Self::Cut),
+            (KeyCode::V, true) => return Some(Self::Paste),
+            (KeyCode::Return, false) => return Some(Self::Enter),
+            _ => return None,
+        };
+        Some(Self::Move {
+            movement,
+            select: shift,
+        })
+    }
+}
+
+/// Where the caret of a text field moves to.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CaretMovement {
+    Left,
+    Right,
+    WordLeft,
+    WordRight,
+    Up,
+    Down,
+    LineStart,
+    LineEnd,
+    DocumentStart,
+    DocumentEnd,
+}
+
 /// Whether this button event was handled by some child.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum ClipEventResult {
@@ -249,6 +335,7 @@ pub enum KeyCode {
     F10 = 121,
     F11 = 122,
     F12 = 123,
+    NumLock = 144,
     ScrollLock = 145,
     Semicolon = 186,
     Equals = 187,
@@ -1,9 +1,11 @@
+use crate::backend::font::{FontBackend, FontFile, CJK_FALLBACK_FAMILIES, FALLBACK_FAMILIES};
 use crate::backend::render::{RenderBackend, ShapeHandle};
 use crate::html::TextSpan;
 use crate::prelude::*;
 use crate::string::WStr;
 use crate::transform::Transform;
 use gc_arena::{Collect, Gc, MutationContext};
+use std::cell::RefCell;
 
 pub use swf::TextGridFit;
 
@@ -14,6 +16,10 @@ pub fn round_down_to_pixel(t: Twips) -> Twips {
 
 type Error = Box<dyn std::error::Error>;
 
+/// The size of the EM square that the glyphs of device fonts are converted
+/// to, which is the same as in DefineFont3 tags.
+const DEVICE_FONT_EM: f32 = 20480.0;
+
 /// Parameters necessary to evaluate a font.
 #[derive(Copy, Clone, Debug, Collect)]
 #[collect(require_static)]
@@ -98,6 +104,32 @@ struct FontData {
 
     /// The identity of the font.
     descriptor: FontDescriptor,
+
+    /// The font file that the glyphs of a device font or a CFF font are
+    /// loaded from, or `None` for fonts defined by their glyph shapes.
+    device: Option<DeviceFont>,
+
+    /// Where the font comes from.
+    font_type: FontType,
+
+    /// The alignment zones of each glyph in the `glyphs` array, set by a
+    /// `DefineFontAlignZones` tag following the font definition.
+    align_zones: RefCell<Vec<GlyphAlignZone>>,
+}
+
+/// A font file, whose glyphs are converted from its outlines as text needs
+/// them.
+#[derive(Debug, Clone)]
+struct DeviceFont {
+    file: FontFile,
+
+    /// The glyphs loaded so far by character, or `None` for characters that
+    /// neither the font nor any fallback font has.
+    glyphs: RefCell<fnv::FnvHashMap<char, Option<Glyph>>>,
+
+    /// The glyphs of shaped text loaded so far by glyph ID, or `None` for
+    /// glyphs without an outline.
+    shaped_glyphs: RefCell<fnv::FnvHashMap<u16, Option<Glyph>>>,
 }
 
 impl<'gc> Font<'gc> {
@@ -150,14 +182,171 @@ impl<'gc> Font<'gc> {
                 descent,
                 leading,
                 descriptor,
+                device: None,
+                font_type: FontType::Embedded,
+                align_zones: RefCell::new(vec![]),
+            },
+        )))
+    }
+
+    /// Creates a font from a `DefineFont4` tag, which embeds an OpenType font
+    /// with CFF outlines.
+    ///
+    /// Returns `None` if the tag contains no font data or the data isn't a
+    /// valid font.
+    pub fn from_font4_tag(
+        gc_context: MutationContext<'gc, '_>,
+        tag: &swf::Font4,
+        encoding: &'static swf::Encoding,
+    ) -> Option<Font<'gc>> {
+        let file = FontFile {
+            data: tag.data?.into(),
+            index: 0,
+        };
+        let descriptor = FontDescriptor::from_parts(
+            &tag.name.to_string_lossy(encoding),
+            tag.is_bold,
+            tag.is_italic,
+        );
+
+        Self::from_file(gc_context, file, descriptor, FontType::EmbeddedCFF)
+    }
+
+    /// Creates a device font from a font file installed on the system.
+    ///
+    /// No glyphs are loaded yet, see `load_device_glyphs`. Returns `None` if
+    /// the file isn't a valid font.
+    pub fn from_font_file(
+        gc_context: MutationContext<'gc, '_>,
+        file: FontFile,
+        descriptor: FontDescriptor,
+    ) -> Option<Font<'gc>> {
+        Self::from_file(gc_context, file, descriptor, FontType::Device)
+    }
+
+    fn from_file(
+        gc_context: MutationContext<'gc, '_>,
+        file: FontFile,
+        descriptor: FontDescriptor,
+        font_type: FontType,
+    ) -> Option<Font<'gc>> {
+        let face = ttf_parser::Face::from_slice(&file.data, file.index).ok()?;
+        let scale = DEVICE_FONT_EM / f32::from(face.units_per_em().max(1));
+
+        Some(Font(Gc::allocate(
+            gc_context,
+            FontData {
+                glyphs: vec![],
+                code_point_to_glyph: fnv::FnvHashMap::default(),
+                scale: DEVICE_FONT_EM,
+                kerning_pairs: fnv::FnvHashMap::default(),
+                ascent: (f32::from(face.ascender()) * scale) as u16,
+                descent: (-f32::from(face.descender()) * scale) as u16,
+                leading: (f32::from(face.line_gap()) * scale) as i16,
+                descriptor,
+                device: Some(DeviceFont {
+                    file,
+                    glyphs: RefCell::new(fnv::FnvHashMap::default()),
+                    shaped_glyphs: RefCell::new(fnv::FnvHashMap::default()),
+                }),
+                font_type,
+                align_zones: RefCell::new(vec![]),
             },
         )))
     }
 
+    /// Loads the glyphs of a device font or a CFF font for the characters of
+    /// the given text. Device fonts search fallback fonts of the system for
+    /// the characters that they don't have.
+    ///
+    /// If the text has to be shaped, the glyphs that shaping produces are
+    /// loaded as well.
+    ///
+    /// Does nothing for fonts defined by their glyph shapes.
+    pub fn load_device_glyphs(
+        &self,
+        text: &WStr,
+        renderer: &mut dyn RenderBackend,
+        fonts: &mut dyn FontBackend,
+    ) {
+        let device = match &self.0.device {
+            Some(device) => device,
+            None => return,
+        };
+        let descriptor = &self.0.descriptor;
+
+        if needs_shaping(text) {
+            if let Some(shaped) = shape_text(&device.file, text, true) {
+                let face = ttf_parser::Face::from_slice(&device.file.data, device.file.index);
+                let mut shaped_glyphs = device.shaped_glyphs.borrow_mut();
+                for shaped_glyph in shaped {
+                    shaped_glyphs.entry(shaped_glyph.id).or_insert_with(|| {
+                        let face = face.as_ref().ok()?;
+                        Some(file_glyph(
+                            face,
+                            ttf_parser::GlyphId(shaped_glyph.id),
+                            0,
+                            &mut *renderer,
+                        ))
+                    });
+                }
+            }
+        }
+
+        let mut glyphs = device.glyphs.borrow_mut();
+
+        // The fallback fonts are only loaded once a character is missing.
+        let mut fallbacks: Option<Vec<(FontFile, bool)>> = None;
+        for c in text.chars() {
+            let c = c.unwrap_or(char::REPLACEMENT_CHARACTER);
+            if glyphs.contains_key(&c) {
+                continue;
+            }
+
+            let glyph = device_glyph(&device.file, c, renderer);
+            if self.0.font_type != FontType::Device {
+                glyphs.insert(c, glyph);
+                continue;
+            }
+
+            let glyph = glyph.or_else(|| {
+                let fallbacks = fallbacks.get_or_insert_with(|| {
+                    let load = |fonts: &mut dyn FontBackend, families: &[&str], cjk: bool| {
+                        families
+                            .iter()
+                            .filter_map(|family| {
+                                fonts.load_font(family, descriptor.bold(), descriptor.italic())
+                            })
+                            .map(|file| (file, cjk))
+                            .collect::<Vec<_>>()
+                    };
+                    let mut fallbacks = load(&mut *fonts, CJK_FALLBACK_FAMILIES, true);
+                    fallbacks.extend(load(&mut *fonts, FALLBACK_FAMILIES, false));
+                    fallbacks
+                });
+
+                // CJK fonts are preferred for CJK characters, so that they
+                // are consistent with each other.
+                let is_cjk = is_cjk(c);
+                fallbacks
+                    .iter()
+                    .filter(|(_, cjk)| *cjk == is_cjk)
+                    .chain(fallbacks.iter().filter(|(_, cjk)| *cjk != is_cjk))
+                    .find_map(|(file, _)| device_glyph(file, c, &mut *renderer))
+            });
+            glyphs.insert(c, glyph);
+        }
+    }
+
     /// Returns whether this font contains glyph shapes.
     /// If not, this font should be rendered as a device font.
     pub fn has_glyphs(&self) -> bool {
-        !self.0.glyphs.is_empty()
+        !self.0.glyphs.is_empty() || self.0.font_type == FontType::EmbeddedCFF
+    }
+
+    /// Returns where this font comes from.
+    pub fn font_type(&self) -> FontType {
+        self.0.font_type
     }
 
     /// Returns a glyph entry by index.
@@ -178,11 +367,24 @@ impl<'gc> Font<'gc> {
         }
     }
 
+    /// Calls `f` with the glyph of the given character, including glyphs
+    /// loaded from the system for device fonts.
+    ///
+    /// Returns `None` if the font has no glyph for the character.
+    fn with_glyph_for_char<R>(&self, c: char, f: impl FnOnce(&Glyph) -> R) -> Option<R> {
+        if let Some(glyph) = self.get_glyph_for_char(c) {
+            return Some(f(glyph));
+        }
+        let device = self.0.device.as_ref()?;
+        let glyphs = device.glyphs.borrow();
+        glyphs.get(&c)?.as_ref().map(f)
+    }
+
     /// Determine if this font contains all the glyphs within a given string.
     pub fn has_glyphs_for_str(&self, target_str: &WStr) -> bool {
         for character in target_str.chars() {
             let c = character.unwrap_or(char::REPLACEMENT_CHARACTER);
-            if self.get_glyph_for_char(c).is_none() {
+            if self.with_glyph_for_char(c, |_| ()).is_none() {
                 return false;
             }
         }
@@ -204,6 +406,38 @@ impl<'gc> Font<'gc> {
             .unwrap_or_default()
     }
 
+    /// Sets the alignment zones of the glyphs of this font from a
+    /// `DefineFontAlignZones` tag, in the same order as the glyphs.
+    pub fn set_align_zones(&self, zones: &[swf::FontAlignZone]) {
+        // Zones are in an EM square of 1024 units, regardless of the version
+        // of the font tag.
+        let scale = self.scale() / 1024.0;
+        let to_glyph_units = |value: i16| f16_to_f32(value as u16) * scale;
+        *self.0.align_zones.borrow_mut() = zones
+            .iter()
+            .map(|zone| GlyphAlignZone {
+                left: to_glyph_units(zone.left),
+                width: to_glyph_units(zone.width),
+                bottom: to_glyph_units(zone.bottom),
+                height: to_glyph_units(zone.height),
+            })
+            .collect();
+    }
+
+    /// Returns the alignment zone of a glyph by index, if the font has
+    /// alignment zones.
+    pub fn get_align_zone(&self, i: usize) -> Option<GlyphAlignZone> {
+        self.0.align_zones.borrow().get(i).copied()
+    }
+
+    /// Returns the alignment zone of the glyph of a character, if the font
+    /// has alignment zones.
+    pub fn align_zone_for_char(&self, c: char) -> Option<GlyphAlignZone> {
+        // TODO: Properly handle UTF-16/out-of-bounds code points.
+        let index = *self.0.code_point_to_glyph.get(&(c as u16))?;
+        self.get_align_zone(index)
+    }
+
     /// Return the leading for this font at a given height.
     pub fn get_leading_for_height(&self, height: Twips) -> Twips {
         let scale = height.get() as f32 / self.scale();
@@ -218,6 +452,14 @@ impl<'gc> Font<'gc> {
         Twips::new((self.0.ascent as f32 * scale) as i32)
     }
 
+    /// Get the distance from the baseline to the bottom of the glyph at a
+    /// given height.
+    pub fn get_descent_for_height(&self, height: Twips) -> Twips {
+        let scale = height.get() as f32 / self.scale();
+
+        Twips::new((self.0.descent as f32 * scale) as i32)
+    }
+
     /// Returns whether this font contains kerning information.
     pub fn has_kerning_info(&self) -> bool {
         !self.0.kerning_pairs.is_empty()
@@ -235,6 +477,9 @@ impl<'gc> Font<'gc> {
     /// of transforms and glyphs which will be consumed by the `glyph_func`
     /// closure. This corresponds to the series of drawing operations necessary
     /// to render the text on a single horizontal line.
+    ///
+    /// Text of device and CFF fonts in scripts that need it is shaped, and
+    /// right-to-left runs within the text are laid out in visual order.
     pub fn evaluate<FGlyph>(
         &self,
         text: &WStr, // TODO: take an `IntoIterator<Item=char>`, to not depend on string representation?
@@ -249,15 +494,42 @@ impl<'gc> Font<'gc> {
 
         transform.matrix.a = scale;
         transform.matrix.d = scale;
+
+        if let Some(device) = &self.0.device {
+            if needs_shaping(text) {
+                if let Some(shaped) = shape_text(&device.file, text, params.kerning) {
+                    let glyphs = device.shaped_glyphs.borrow();
+                    let mut x = Twips::ZERO;
+                    for shaped_glyph in shaped {
+                        let twips_advance =
+                            Twips::new((shaped_glyph.advance as f32 * scale) as i32)
+                                + params.letter_spacing;
+                        if let Some(Some(glyph)) = glyphs.get(&shaped_glyph.id) {
+                            let mut glyph_transform = transform.clone();
+                            glyph_transform.matrix.tx +=
+                                Twips::new((shaped_glyph.offset.0 as f32 * scale) as i32);
+                            glyph_transform.matrix.ty +=
+                                Twips::new((shaped_glyph.offset.1 as f32 * scale) as i32);
+                            glyph_func(shaped_glyph.pos, &glyph_transform, glyph, twips_advance, x);
+                        }
+
+                        transform.matrix.tx += twips_advance;
+                        x += twips_advance;
+                    }
+                    return;
+                }
+            }
+        }
+
         let mut char_indices = text.char_indices().peekable();
         let has_kerning_info = self.has_kerning_info();
         let mut x = Twips::ZERO;
         while let Some((pos, c)) = char_indices.next() {
             let c = c.unwrap_or(char::REPLACEMENT_CHARACTER);
-            if let Some(glyph) = self.get_glyph_for_char(c) {
+            let next_char = char_indices.peek().cloned().unwrap_or((0, Ok('\0'))).1;
+            let twips_advance = self.with_glyph_for_char(c, |glyph| {
                 let mut advance = Twips::new(glyph.advance);
                 if has_kerning_info && params.kerning {
-                    let next_char = char_indices.peek().cloned().unwrap_or((0, Ok('\0'))).1;
                     let next_char = next_char.unwrap_or(char::REPLACEMENT_CHARACTER);
                     advance += self.get_kerning_offset(c, next_char);
                 }
@@ -265,7 +537,9 @@ impl<'gc> Font<'gc> {
                     Twips::new((advance.get() as f32 * scale) as i32) + params.letter_spacing;
 
                 glyph_func(pos, &transform, glyph, twips_advance, x);
-
+                twips_advance
+            });
+            if let Some(twips_advance) = twips_advance {
                 // Step horizontally.
                 transform.matrix.tx += twips_advance;
                 x += twips_advance;
@@ -332,16 +606,14 @@ impl<'gc> Font<'gc> {
 
         let mut line_end = 0;
 
-        for word in text.split(b' ') {
-            let word_start = word.offset_in(text).unwrap();
-            let word_end = word_start + word.len();
-
-            let measure = self.measure(
-                // +1 is fine because ' ' is 1 unit
-                text.slice(word_start..word_end + 1).unwrap_or(word),
-                params,
-                false,
-            );
+        for (word_start, word_end) in line_break_words(text) {
+            // Words are measured together with the space following them.
+            let measure_end = if text.get(word_end) == Some(u16::from(b' ')) {
+                word_end + 1
+            } else {
+                word_end
+            };
+            let measure = self.measure(&text[word_start..measure_end], params, false);
 
             if is_start_of_line && measure.0 > remaining_width {
                 //Failsafe for if we get a word wider than the field.
@@ -393,6 +665,53 @@ impl<'gc> Font<'gc> {
     }
 }
 
+/// Whether a line can be broken on either side of a character, as it can for
+/// ideographs and kana.
+fn is_ideographic(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3040}'..='\u{30FF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{FF00}'..='\u{FFEF}'
+    )
+}
+
+/// Split text into the words that a line may be broken between.
+///
+/// Words are returned as `(start, end)` ranges that do not include the space
+/// following them. A word can also end after a hyphen or on either side of an
+/// ideograph, in which case the next word follows it directly.
+fn line_break_words(text: &WStr) -> Vec<(usize, usize)> {
+    let mut words = Vec::new();
+    let mut word_start = 0;
+
+    for (i, c) in text.char_indices() {
+        match c {
+            Ok(' ') => {
+                words.push((word_start, i));
+                word_start = i + 1;
+            }
+            Ok('-') if word_start < i => {
+                words.push((word_start, i + 1));
+                word_start = i + 1;
+            }
+            Ok(c) if is_ideographic(c) => {
+                if word_start < i {
+                    words.push((word_start, i));
+                }
+                words.push((i, i + 1));
+                word_start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    words.push((word_start, text.len()));
+    words
+}
+
 #[derive(Debug, Clone)]
 pub struct Glyph {
     pub shape_handle: ShapeHandle,
@@ -400,6 +719,368 @@ pub struct Glyph {
     pub advance: i16,
 }
 
+/// The part of a glyph that advanced text rendering aligns to the pixel grid
+/// when grid fitting, in the glyph's EM-square coordinates.
+///
+/// The zone is measured up from the baseline, whereas glyph shapes have
+/// their y-axis pointing down.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GlyphAlignZone {
+    pub left: f32,
+    pub width: f32,
+    pub bottom: f32,
+    pub height: f32,
+}
+
+/// Aligns the matrix that a glyph is rendered with to the pixel grid, as the
+/// advanced text rendering engine does.
+///
+/// The origin of the glyph is snapped to whole pixels, or to thirds of a
+/// pixel horizontally for sub-pixel grid fitting. If the glyph has an
+/// alignment zone and isn't rotated or skewed, it is also scaled so that the
+/// edges of the zone fall on pixel boundaries, which keeps stems and
+/// x-heights crisp at small sizes.
+pub fn grid_fit_matrix(
+    mut matrix: Matrix,
+    grid_fit: TextGridFit,
+    zone: Option<GlyphAlignZone>,
+) -> Matrix {
+    let x_step = match grid_fit {
+        TextGridFit::None => return matrix,
+        TextGridFit::Pixel => Twips::TWIPS_PER_PIXEL,
+        TextGridFit::SubPixel => Twips::TWIPS_PER_PIXEL / 3.0,
+    };
+    let y_step = Twips::TWIPS_PER_PIXEL;
+
+    // Snaps the edges of the zone on an axis, returning the new scale and
+    // translation, or `None` if the zone is empty.
+    let fit_zone = |scale: f32, translation: f64, start: f32, end: f32, step: f64| {
+        if start == end {
+            return None;
+        }
+        let snap = |value: f32| ((f64::from(value * scale) + translation) / step).round() * step;
+        let (snapped_start, snapped_end) = (snap(start), snap(end));
+        if snapped_start == snapped_end {
+            return None;
+        }
+        let scale = ((snapped_end - snapped_start) / f64::from(end - start)) as f32;
+        Some((scale, snapped_start - f64::from(start * scale)))
+    };
+
+    let tx = f64::from(matrix.tx.get());
+    let ty = f64::from(matrix.ty.get());
+    let zone = zone.filter(|_| matrix.b == 0.0 && matrix.c == 0.0);
+    let (a, tx) = zone
+        .filter(|_| grid_fit == TextGridFit::Pixel)
+        .and_then(|zone| fit_zone(matrix.a, tx, zone.left, zone.left + zone.width, x_step))
+        .unwrap_or_else(|| (matrix.a, (tx / x_step).round() * x_step));
+    let (d, ty) = zone
+        .and_then(|zone| {
+            fit_zone(
+                matrix.d,
+                ty,
+                -zone.bottom,
+                -zone.bottom - zone.height,
+                y_step,
+            )
+        })
+        .unwrap_or_else(|| (matrix.d, (ty / y_step).round() * y_step));
+    matrix.a = a;
+    matrix.d = d;
+    matrix.tx = Twips::new(tx.round() as i32);
+    matrix.ty = Twips::new(ty.round() as i32);
+    matrix
+}
+
+/// Converts a half-precision float, as used by `DefineFontAlignZones` tags.
+fn f16_to_f32(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = i32::from((bits >> 10) & 0x1f);
+    let mantissa = f32::from(bits & 0x3ff);
+    sign * match exponent {
+        0 => mantissa * 2f32.powi(-24),
+        0x1f if mantissa == 0.0 => f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => (1.0 + mantissa / 1024.0) * 2f32.powi(exponent - 15),
+    }
+}
+
+/// Converts the glyph of a character in a font file, registering its shape
+/// with the renderer. Returns `None` if the font has no glyph for it.
+fn device_glyph(file: &FontFile, c: char, renderer: &mut dyn RenderBackend) -> Option<Glyph> {
+    let face = ttf_parser::Face::from_slice(&file.data, file.index).ok()?;
+    let id = face.glyph_index(c).filter(|id| id.0 != 0)?;
+
+    Some(file_glyph(&face, id, c as u16, renderer))
+}
+
+/// Converts a glyph of a font file to a `Glyph`.
+fn file_glyph(
+    face: &ttf_parser::Face,
+    id: ttf_parser::GlyphId,
+    code: u16,
+    renderer: &mut dyn RenderBackend,
+) -> Glyph {
+    let scale = DEVICE_FONT_EM / f32::from(face.units_per_em().max(1));
+
+    let mut builder = GlyphBuilder::new(scale);
+    face.outline_glyph(id, &mut builder);
+    let advance = face
+        .glyph_hor_advance(id)
+        .map_or(0, |advance| (f32::from(advance) * scale) as i16);
+    let swf_glyph = swf::Glyph {
+        shape_records: builder.records,
+        code,
+        advance: Some(advance),
+        bounds: None,
+    };
+    Glyph {
+        shape_handle: renderer.register_glyph_shape(&swf_glyph),
+        advance,
+        shape: crate::shape_utils::swf_glyph_to_shape(&swf_glyph),
+    }
+}
+
+/// A glyph of shaped text.
+struct ShapedGlyph {
+    /// The glyph ID in the font file.
+    id: u16,
+
+    /// The position in the text of the character that the glyph belongs to.
+    pos: usize,
+
+    /// The advance of the glyph, in glyph units.
+    advance: i32,
+
+    /// The offset of the glyph from its position on the line, in glyph units.
+    offset: (i32, i32),
+}
+
+/// Whether text contains characters of scripts that are written right to left
+/// or whose glyphs depend on the characters around them.
+fn needs_shaping(text: &WStr) -> bool {
+    text.chars().any(|c| {
+        matches!(
+            c,
+            Ok(
+                '\u{0590}'..='\u{08FF}'
+                | '\u{0900}'..='\u{0DFF}'
+                | '\u{0E00}'..='\u{0FFF}'
+                | '\u{1000}'..='\u{109F}'
+                | '\u{1780}'..='\u{17FF}'
+                | '\u{FB1D}'..='\u{FDFF}'
+                | '\u{FE70}'..='\u{FEFC}',
+            )
+        )
+    })
+}
+
+/// Shapes text with a font file, splitting it into runs of the same direction
+/// that are laid out in visual order.
+///
+/// Returns `None` if the font isn't valid or doesn't contain all the
+/// characters of the text, in which case the text can't be shaped.
+fn shape_text(file: &FontFile, text: &WStr, kerning: bool) -> Option<Vec<ShapedGlyph>> {
+    let face = rustybuzz::Face::from_slice(&file.data, file.index)?;
+    let scale = DEVICE_FONT_EM / f32::from(face.units_per_em().max(1));
+
+    // The position in the text of each UTF-8 byte.
+    let mut utf8 = String::with_capacity(text.len());
+    let mut positions = Vec::with_capacity(text.len());
+    for (pos, c) in text.char_indices() {
+        let c = c.unwrap_or(char::REPLACEMENT_CHARACTER);
+        if !c.is_whitespace() && !c.is_control() && face.glyph_index(c).is_none() {
+            return None;
+        }
+        utf8.push(c);
+        positions.resize(utf8.len(), pos);
+    }
+
+    let features = if kerning {
+        vec![]
+    } else {
+        vec![rustybuzz::Feature::new(
+            rustybuzz::Tag::from_bytes(b"kern"),
+            0,
+            ..,
+        )]
+    };
+
+    let bidi = unicode_bidi::BidiInfo::new(&utf8, None);
+    let mut glyphs = vec![];
+    for paragraph in &bidi.paragraphs {
+        let (levels, runs) = bidi.visual_runs(paragraph, paragraph.range.clone());
+        for run in runs {
+            let mut buffer = rustybuzz::UnicodeBuffer::new();
+            buffer.push_str(&utf8[run.clone()]);
+            buffer.set_direction(if levels[run.start].is_rtl() {
+                rustybuzz::Direction::RightToLeft
+            } else {
+                rustybuzz::Direction::LeftToRight
+            });
+
+            let output = rustybuzz::shape(&face, &features, buffer);
+            for (info, position) in output.glyph_infos().iter().zip(output.glyph_positions()) {
+                glyphs.push(ShapedGlyph {
+                    id: info.glyph_id as u16,
+                    pos: positions[run.start + info.cluster as usize],
+                    advance: (position.x_advance as f32 * scale) as i32,
+                    // The y axis of font files points up.
+                    offset: (
+                        (position.x_offset as f32 * scale) as i32,
+                        (-position.y_offset as f32 * scale) as i32,
+                    ),
+                });
+            }
+        }
+    }
+
+    Some(glyphs)
+}
+
+/// Whether a character is in one of the Chinese, Japanese or Korean blocks.
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{1100}'..='\u{11FF}'
+            | '\u{2E80}'..='\u{9FFF}'
+            | '\u{AC00}'..='\u{D7AF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{FF00}'..='\u{FFEF}'
+            | '\u{20000}'..='\u{2FFFF}'
+    )
+}
+
+/// Converts the outline of a glyph in a font file to the shape records of a
+/// DefineFont3 glyph.
+///
+/// The y axis of font files points up, while it points down in SWF shapes.
+/// Cubic curves of OpenType fonts are approximated with quadratic curves.
+struct GlyphBuilder {
+    records: Vec<swf::ShapeRecord>,
+
+    /// The factor from font units to glyph units.
+    scale: f32,
+
+    /// The start of the current contour, in glyph units.
+    start: (i32, i32),
+
+    /// The current position in glyph units, as rounded in the records.
+    position: (i32, i32),
+
+    /// The current position in font units.
+    last_point: (f32, f32),
+}
+
+impl GlyphBuilder {
+    fn new(scale: f32) -> Self {
+        Self {
+            records: vec![],
+            scale,
+            start: (0, 0),
+            position: (0, 0),
+            last_point: (0.0, 0.0),
+        }
+    }
+
+    fn to_glyph_units(&self, x: f32, y: f32) -> (i32, i32) {
+        (
+            (x * self.scale).round() as i32,
+            (-y * self.scale).round() as i32,
+        )
+    }
+
+    fn line_to_position(&mut self, to: (i32, i32)) {
+        if to != self.position {
+            self.records.push(swf::ShapeRecord::StraightEdge {
+                delta_x: Twips::new(to.0 - self.position.0),
+                delta_y: Twips::new(to.1 - self.position.1),
+            });
+            self.position = to;
+        }
+    }
+}
+
+impl ttf_parser::OutlineBuilder for GlyphBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let to = self.to_glyph_units(x, y);
+        let is_first = self.records.is_empty();
+        self.records.push(swf::ShapeRecord::StyleChange(Box::new(
+            swf::StyleChangeData {
+                move_to: Some((Twips::new(to.0), Twips::new(to.1))),
+                fill_style_0: if is_first { Some(1) } else { None },
+                fill_style_1: None,
+                line_style: None,
+                new_styles: None,
+            },
+        )));
+        self.start = to;
+        self.position = to;
+        self.last_point = (x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let to = self.to_glyph_units(x, y);
+        self.line_to_position(to);
+        self.last_point = (x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let control = self.to_glyph_units(x1, y1);
+        let anchor = self.to_glyph_units(x, y);
+        self.records.push(swf::ShapeRecord::CurvedEdge {
+            control_delta_x: Twips::new(control.0 - self.position.0),
+            control_delta_y: Twips::new(control.1 - self.position.1),
+            anchor_delta_x: Twips::new(anchor.0 - control.0),
+            anchor_delta_y: Twips::new(anchor.1 - control.1),
+        });
+        self.position = anchor;
+        self.last_point = (x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        // Split the cubic curve in half, and approximate each half with a
+        // quadratic curve through its ends.
+        let halfway = |a: (f32, f32), b: (f32, f32)| ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+        let p0 = self.last_point;
+        let (p1, p2, p3) = ((x1, y1), (x2, y2), (x, y));
+        let p01 = halfway(p0, p1);
+        let p12 = halfway(p1, p2);
+        let p23 = halfway(p2, p3);
+        let p012 = halfway(p01, p12);
+        let p123 = halfway(p12, p23);
+        let mid = halfway(p012, p123);
+        let control = |a: (f32, f32), b: (f32, f32), c: (f32, f32), d: (f32, f32)| {
+            (
+                (3.0 * (b.0 + c.0) - a.0 - d.0) / 4.0,
+                (3.0 * (b.1 + c.1) - a.1 - d.1) / 4.0,
+            )
+        };
+        let c0 = control(p0, p01, p012, mid);
+        let c1 = control(mid, p123, p23, p3);
+        self.quad_to(c0.0, c0.1, mid.0, mid.1);
+        self.quad_to(c1.0, c1.1, p3.0, p3.1);
+    }
+
+    fn close(&mut self) {
+        self.line_to_position(self.start);
+    }
+}
+
+/// Where a font comes from, as reported by `flash.text.Font.fontType`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Collect)]
+#[collect(require_static)]
+pub enum FontType {
+    /// A font defined in a movie by its glyph shapes.
+    Embedded,
+
+    /// A font defined in a movie by an OpenType font with CFF outlines.
+    EmbeddedCFF,
+
+    /// A font installed on the system.
+    Device,
+}
+
 /// Structure which identifies a particular font by name and properties.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Collect)]
 #[collect(require_static)]
@@ -452,51 +1133,142 @@ impl FontDescriptor {
     }
 }
 
-/// The text rendering engine that a text field should use.
+/// The text rendering engine that a text field should use, and how it
+/// renders the text.
 /// This is controlled by the "Anti-alias" setting in the Flash IDE.
 /// Using "Anti-alias for readibility" switches to the "Advanced" text
-/// rendering engine.
+/// rendering engine, whose parameters are set via the CSMTextSettings SWF tag
+/// or the `antiAliasType`, `gridFitType`, `sharpness` and `thickness`
+/// properties of text fields.
 #[derive(Debug, PartialEq, Clone, Collect)]
 #[collect(require_static)]
-pub enum TextRenderSettings {
-    /// This text should render with the standard rendering engine.
-    /// Set via "Anti-alias for animation" in the Flash IDE.
-    Default,
-
-    /// This text should render with the advanced rendering engine.
-    /// Set via "Anti-alias for readibility" in the Flash IDE.
-    /// The parameters are set via the CSMTextSettings SWF tag.
-    /// Ruffle does not support this currently, but this also affects
-    /// hit-testing behavior.
-    Advanced {
-        grid_fit: TextGridFit,
-        thickness: f32,
-        sharpness: f32,
-    },
+pub struct TextRenderSettings {
+    /// Whether this text renders with the advanced rendering engine.
+    /// Otherwise, it renders with the standard engine and the remaining
+    /// settings have no effect.
+    /// Advanced rendering also affects hit-testing behavior.
+    pub use_advanced_rendering: bool,
+
+    /// How glyphs are aligned to the pixel grid.
+    pub grid_fit: TextGridFit,
+
+    /// The thickness of the edges of glyphs, from -200 to 200.
+    /// Ruffle does not support this currently, so it is only kept to be read
+    /// back by scripts.
+    pub thickness: f32,
+
+    /// The sharpness of the edges of glyphs, from -400 to 400.
+    /// Ruffle does not support this currently, so it is only kept to be read
+    /// back by scripts.
+    pub sharpness: f32,
 }
 
 impl TextRenderSettings {
     pub fn is_advanced(&self) -> bool {
-        matches!(self, TextRenderSettings::Advanced { .. })
+        self.use_advanced_rendering
+    }
+
+    /// The value of `TextField.antiAliasType`.
+    pub fn anti_alias_type(&self) -> &'static str {
+        if self.use_advanced_rendering {
+            "advanced"
+        } else {
+            "normal"
+        }
+    }
+
+    /// Sets the rendering engine from a value of `TextField.antiAliasType`.
+    ///
+    /// The value is case-sensitive, as in ActionScript 3. Returns `false` if it
+    /// isn't one of the accepted values.
+    pub fn set_anti_alias_type(&mut self, anti_alias_type: &WStr) -> bool {
+        self.use_advanced_rendering = if anti_alias_type == WStr::from_units(b"advanced") {
+            true
+        } else if anti_alias_type == WStr::from_units(b"normal") {
+            false
+        } else {
+            return false;
+        };
+        true
+    }
+
+    /// The value of `TextField.gridFitType`.
+    pub fn grid_fit_type(&self) -> &'static str {
+        match self.grid_fit {
+            TextGridFit::None => "none",
+            TextGridFit::Pixel => "pixel",
+            TextGridFit::SubPixel => "subpixel",
+        }
+    }
+
+    /// Sets the grid fitting from a value of `TextField.gridFitType`.
+    ///
+    /// The value is case-sensitive, as in ActionScript 3. Returns `false` if it
+    /// isn't one of the accepted values.
+    pub fn set_grid_fit_type(&mut self, grid_fit_type: &WStr) -> bool {
+        self.grid_fit = if grid_fit_type == WStr::from_units(b"none") {
+            TextGridFit::None
+        } else if grid_fit_type == WStr::from_units(b"pixel") {
+            TextGridFit::Pixel
+        } else if grid_fit_type == WStr::from_units(b"subpixel") {
+            TextGridFit::SubPixel
+        } else {
+            return false;
+        };
+        true
+    }
+
+    /// Sets the thickness from a value of `TextField.thickness`, which is
+    /// clamped to its range and ignored if it is `NaN`.
+    pub fn set_thickness(&mut self, thickness: f64) {
+        if !thickness.is_nan() {
+            self.thickness = thickness.clamp(-200.0, 200.0) as f32;
+            if self.thickness != 0.0 {
+                log::warn!("TextField.thickness is not yet implemented");
+            }
+        }
+    }
+
+    /// Sets the sharpness from a value of `TextField.sharpness`, which is
+    /// clamped to its range and ignored if it is `NaN`.
+    pub fn set_sharpness(&mut self, sharpness: f64) {
+        if !sharpness.is_nan() {
+            self.sharpness = sharpness.clamp(-400.0, 400.0) as f32;
+            if self.sharpness != 0.0 {
+                log::warn!("TextField.sharpness is not yet implemented");
+            }
+        }
+    }
+
+    /// The grid fitting that glyphs should be rendered with, which is none
+    /// for the standard rendering engine.
+    pub fn effective_grid_fit(&self) -> TextGridFit {
+        if self.use_advanced_rendering {
+            self.grid_fit
+        } else {
+            TextGridFit::None
+        }
     }
 }
 
 impl Default for TextRenderSettings {
     fn default() -> Self {
-        TextRenderSettings::Default
+        Self {
+            use_advanced_rendering: false,
+            grid_fit: TextGridFit::Pixel,
+            thickness: 0.0,
+            sharpness: 0.0,
+        }
     }
 }
 
 impl From<swf::CsmTextSettings> for TextRenderSettings {
     fn from(settings: swf::CsmTextSettings) -> Self {
-        if settings.use_advanced_rendering {
-            TextRenderSettings::Advanced {
-                grid_fit: settings.grid_fit,
-                thickness: settings.thickness,
-                sharpness: settings.sharpness,
-            }
-        } else {
-            TextRenderSettings::Default
+        Self {
+            use_advanced_rendering: settings.use_advanced_rendering,
+            grid_fit: settings.grid_fit,
+            thickness: settings.thickness,
+            sharpness: settings.sharpness,
         }
     }
 }
@@ -504,12 +1276,17 @@ impl From<swf::CsmTextSettings> for TextRenderSettings {
 #[cfg(test)]
 mod tests {
     use crate::backend::render::{NullRenderer, RenderBackend};
-    use crate::font::{EvalParameters, Font};
+    use crate::font::{
+        f16_to_f32, grid_fit_matrix, EvalParameters, Font, GlyphAlignZone, GlyphBuilder,
+        TextGridFit, TextRenderSettings,
+    };
+    use crate::matrix::Matrix;
     use crate::player::{Player, DEVICE_FONT_TAG};
     use crate::string::WStr;
     use gc_arena::{rootless_arena, MutationContext};
     use std::ops::DerefMut;
     use swf::Twips;
+    use ttf_parser::OutlineBuilder;
 
     fn with_device_font<F>(callback: F)
     where
@@ -597,6 +1374,24 @@ mod tests {
         });
     }
 
+    #[test]
+    fn wrap_line_breakpoint_after_hyphen() {
+        with_device_font(|_mc, df| {
+            let params =
+                EvalParameters::from_parts(Twips::from_pixels(12.0), Twips::from_pixels(0.0), true);
+            let string = WStr::from_units(b"abcd-efgh ijkl");
+            let breakpoint = df.wrap_line(
+                string,
+                params,
+                Twips::from_pixels(35.0),
+                Twips::from_pixels(0.0),
+                true,
+            );
+
+            assert_eq!(Some(5), breakpoint);
+        });
+    }
+
     #[test]
     fn wrap_line_breakpoint_no_room() {
         with_device_font(|_mc, df| {
@@ -681,4 +1476,105 @@ mod tests {
             assert_eq!(None, breakpoint5);
         });
     }
+
+    #[test]
+    fn glyph_builder() {
+        let mut builder = GlyphBuilder::new(2.0);
+        builder.move_to(0.0, 0.0);
+        builder.line_to(10.0, 0.0);
+        builder.quad_to(10.0, 10.0, 0.0, 10.0);
+        builder.close();
+
+        let twips = |x, y| (Twips::new(x), Twips::new(y));
+        assert_eq!(builder.records.len(), 4);
+        match &builder.records[0] {
+            swf::ShapeRecord::StyleChange(style_change) => {
+                assert_eq!(style_change.move_to, Some(twips(0, 0)));
+                assert_eq!(style_change.fill_style_0, Some(1));
+            }
+            record => panic!("Expected a style change, got {:?}", record),
+        }
+        assert_eq!(
+            builder.records[1],
+            swf::ShapeRecord::StraightEdge {
+                delta_x: Twips::new(20),
+                delta_y: Twips::ZERO,
+            }
+        );
+        // The y axis is flipped.
+        assert_eq!(
+            builder.records[2],
+            swf::ShapeRecord::CurvedEdge {
+                control_delta_x: Twips::ZERO,
+                control_delta_y: Twips::new(-20),
+                anchor_delta_x: Twips::new(-20),
+                anchor_delta_y: Twips::ZERO,
+            }
+        );
+        assert_eq!(
+            builder.records[3],
+            swf::ShapeRecord::StraightEdge {
+                delta_x: Twips::ZERO,
+                delta_y: Twips::new(20),
+            }
+        );
+    }
+
+    #[test]
+    fn f16_conversion() {
+        assert_eq!(f16_to_f32(0x3c00), 1.0);
+        assert_eq!(f16_to_f32(0xc000), -2.0);
+        assert_eq!(f16_to_f32(0x6400), 1024.0);
+        assert_eq!(f16_to_f32(0x0001), 2f32.powi(-24));
+    }
+
+    #[test]
+    fn grid_fit() {
+        let matrix = Matrix {
+            a: 0.5,
+            d: 0.5,
+            tx: Twips::new(13),
+            ty: Twips::new(227),
+            ..Default::default()
+        };
+        assert_eq!(grid_fit_matrix(matrix, TextGridFit::None, None), matrix);
+
+        // The origin is snapped to whole pixels, or thirds of pixels horizontally.
+        let fitted = grid_fit_matrix(matrix, TextGridFit::Pixel, None);
+        assert_eq!((fitted.tx, fitted.ty), (Twips::new(20), Twips::new(220)));
+        let fitted = grid_fit_matrix(matrix, TextGridFit::SubPixel, None);
+        assert_eq!((fitted.tx, fitted.ty), (Twips::new(13), Twips::new(220)));
+
+        // The top of the alignment zone is snapped to a pixel by scaling the glyph.
+        let zone = GlyphAlignZone {
+            left: 0.0,
+            width: 0.0,
+            bottom: 0.0,
+            height: 990.0,
+        };
+        let fitted = grid_fit_matrix(matrix, TextGridFit::Pixel, Some(zone));
+        assert_eq!(fitted.ty, Twips::new(220));
+        assert!((fitted.d * -990.0 + 220.0 - -260.0).abs() < 0.01);
+        assert_eq!(fitted.a, 0.5);
+    }
+
+    #[test]
+    fn text_render_settings_properties() {
+        let mut settings = TextRenderSettings::default();
+        assert_eq!(settings.anti_alias_type(), "normal");
+        assert!(settings.set_anti_alias_type(WStr::from_units(b"advanced")));
+        assert_eq!(settings.anti_alias_type(), "advanced");
+        assert!(!settings.set_anti_alias_type(WStr::from_units(b"NORMAL")));
+        assert_eq!(settings.anti_alias_type(), "advanced");
+
+        assert!(settings.set_grid_fit_type(WStr::from_units(b"subpixel")));
+        assert_eq!(settings.grid_fit, TextGridFit::SubPixel);
+        assert!(!settings.set_grid_fit_type(WStr::from_units(b"Pixel")));
+        assert_eq!(settings.grid_fit_type(), "subpixel");
+
+        settings.set_thickness(500.0);
+        assert_eq!(settings.thickness, 200.0);
+        settings.set_sharpness(f64::NAN);
+        assert_eq!(settings.sharpness, 0.0);
+    }
 }
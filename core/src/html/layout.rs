@@ -4,7 +4,7 @@ use crate::context::UpdateContext;
 use crate::drawing::Drawing;
 use crate::font::{EvalParameters, Font};
 use crate::html::dimensions::{BoxBounds, Position, Size};
-use crate::html::text_format::{FormatSpans, TextFormat, TextSpan};
+use crate::html::text_format::{FormatSpans, HtmlImage, TextFormat, TextSpan};
 use crate::shape_utils::DrawCommand;
 use crate::string::{utils as string_utils, WStr};
 use crate::tag_utils::SwfMovie;
@@ -87,6 +87,16 @@ pub struct LayoutContext<'a, 'gc> {
 
     /// The total width of the text field being laid out.
     max_bounds: Twips,
+
+    /// The lines laid out so far.
+    lines: Vec<LayoutLine>,
+
+    /// The position in the text that the current line starts at.
+    line_start: usize,
+
+    /// The position in the text that the next line would start at, were the
+    /// current line to end now.
+    text_position: usize,
 }
 
 impl<'a, 'gc> LayoutContext<'a, 'gc> {
@@ -104,6 +114,9 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
             current_line: 0,
             current_line_span: Default::default(),
             max_bounds,
+            lines: Vec::new(),
+            line_start: 0,
+            text_position: 0,
         }
     }
 
@@ -226,17 +239,28 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
             return;
         }
 
+        let alignment = self.effective_alignment();
+        let line_box_count = self.boxes.len() - self.current_line;
         let mut line_bounds = None;
         let mut box_count: i32 = 0;
-        for linebox in self.boxes.get_mut(self.current_line..).unwrap() {
-            let (text, _tf, font, params, _color) =
-                linebox.as_renderable_text(self.text).expect("text");
-
-            //Flash ignores trailing spaces when aligning lines, so should we
-            if self.current_line_span.align != swf::TextAlign::Left {
-                linebox.bounds = linebox
-                    .bounds
-                    .with_size(font.measure(text.trim_end(), params, false).into());
+        for (i, linebox) in self
+            .boxes
+            .get_mut(self.current_line..)
+            .unwrap()
+            .iter_mut()
+            .enumerate()
+        {
+            //Flash ignores trailing spaces when aligning lines, so should we.
+            //The space that a line was wrapped at never counts towards its
+            //width either.
+            if let Some((text, _tf, font, params, _color)) = linebox.as_renderable_text(self.text) {
+                if alignment != swf::TextAlign::Left
+                    || (!final_line_of_para && i + 1 == line_box_count)
+                {
+                    linebox.bounds = linebox
+                        .bounds
+                        .with_size(font.measure(text.trim_end(), params, false).into());
+                }
             }
 
             if let Some(line_bounds) = &mut line_bounds {
@@ -290,7 +314,7 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
             // which is information we don't have yet.
             let font_size_adjustment = self.max_font_size - linebox.bounds.height();
 
-            if linebox.is_text_box() {
+            if linebox.is_text_box() || linebox.is_image() {
                 linebox.bounds += Position::from((
                     left_adjustment + align_adjustment + (interim_adjustment * box_count),
                     font_size_adjustment,
@@ -310,6 +334,27 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
 
         self.current_line = self.boxes.len();
 
+        let (ascent, descent) = self
+            .font
+            .map(|f| {
+                (
+                    f.get_baseline_for_height(self.max_font_size),
+                    f.get_descent_for_height(self.max_font_size),
+                )
+            })
+            .unwrap_or((self.max_font_size, Twips::ZERO));
+        self.lines.push(LayoutLine {
+            start: self.line_start,
+            bounds: BoxBounds::from_position_and_size(
+                Position::from((line_bounds.offset_x(), self.cursor.y())),
+                Size::from((line_bounds.width(), self.max_font_size)),
+            ),
+            ascent,
+            descent,
+            leading: Twips::from_pixels(self.current_line_span.leading),
+        });
+        self.line_start = self.text_position;
+
         if let Some(eb) = &mut self.exterior_bounds {
             *eb += line_bounds;
         } else {
@@ -397,12 +442,27 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
     ) -> Option<Font<'gc>> {
         let library = context.library.library_for_movie_mut(self.movie.clone());
 
-        // If this text field is set to use device fonts, fallback to using our embedded Noto Sans.
+        // If this text field is set to use device fonts, look up the font on the system, and
+        // fallback to using our embedded Noto Sans if it isn't installed.
         // Note that the SWF can still contain a DefineFont tag with no glyphs/layout info in this case (see #451).
-        // In an ideal world, device fonts would search for a matching font on the system and render it in some way.
+        let font_name = span.font.to_utf8_lossy();
         if let Some(font) = library
-            .get_font_by_name(&span.font.to_utf8_lossy(), span.bold, span.italic)
+            .get_font_by_name(&font_name, span.bold, span.italic)
+            .or_else(|| {
+                context
+                    .library
+                    .registered_font(&font_name, span.bold, span.italic)
+            })
             .filter(|f| !is_device_font && f.has_glyphs())
+            .or_else(|| {
+                context.library.system_font(
+                    context.gc_context,
+                    context.fonts,
+                    &font_name,
+                    span.bold,
+                    span.italic,
+                )
+            })
             .or_else(|| context.library.device_font())
         {
             self.font = Some(font);
@@ -447,9 +507,41 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
         new_text.bounds = text_bounds;
 
         self.cursor += Position::from((text_size.width(), Twips::default()));
+        self.text_position = end;
         self.append_box(new_text);
     }
 
+    /// Append an image to the current line of the ongoing layout operation.
+    ///
+    /// Images sit on the line like a large character, so the line grows to
+    /// fit them and the text on it is aligned to their bottom edge.
+    fn append_image(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        index: usize,
+        image: &HtmlImage,
+        is_word_wrap: bool,
+    ) {
+        let width = image.width.unwrap_or_default() + image.hspace * 2;
+        let height = image.height.unwrap_or_default() + image.vspace * 2;
+
+        if is_word_wrap && !self.is_start_of_line() {
+            let (max_width, offset) = self.wrap_dimensions(&self.current_line_span);
+            if offset + width > max_width {
+                self.newline(context);
+            }
+        }
+
+        let mut new_image =
+            LayoutBox::from_image(index, Position::from((image.hspace, image.vspace)));
+        new_image.bounds =
+            BoxBounds::from_position_and_size(self.cursor, Size::from((width, height)));
+
+        self.cursor += Position::from((width, Twips::default()));
+        self.max_font_size = max(self.max_font_size, height);
+        self.append_box(new_image);
+    }
+
     /// Append a bullet to the start of the current line.
     ///
     /// The bullet will always be placed at the start of the current line. It
@@ -534,13 +626,14 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
     }
 
     /// Destroy the layout context, returning the newly constructed layout list.
-    fn end_layout(
-        mut self,
-        context: &mut UpdateContext<'_, 'gc, '_>,
-    ) -> (Vec<LayoutBox<'gc>>, BoxBounds<Twips>) {
+    fn end_layout(mut self, context: &mut UpdateContext<'_, 'gc, '_>) -> Layout<'gc> {
         self.fixup_line(context, !self.has_line_break, true);
 
-        (self.boxes, self.exterior_bounds.unwrap_or_default())
+        Layout {
+            boxes: self.boxes,
+            lines: self.lines,
+            bounds: self.exterior_bounds.unwrap_or_default(),
+        }
     }
 
     fn is_start_of_line(&self) -> bool {
@@ -548,6 +641,70 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
     }
 }
 
+/// The result of laying out a text field's text.
+pub struct Layout<'gc> {
+    /// The boxes making up the laid-out text.
+    pub boxes: Vec<LayoutBox<'gc>>,
+
+    /// The lines of the laid-out text, in order.
+    ///
+    /// There is always at least one line, even if there is no text.
+    pub lines: Vec<LayoutLine>,
+
+    /// The exterior bounds of all laid-out text, including left and right
+    /// margins.
+    pub bounds: BoxBounds<Twips>,
+}
+
+/// A single line of laid-out text.
+///
+/// Lines ended by a newline character include that character, and lines
+/// that were word wrapped include the spaces that they were wrapped at.
+#[derive(Copy, Clone, Debug, Collect)]
+#[collect(require_static)]
+pub struct LayoutLine {
+    /// The position in the text that this line starts at.
+    start: usize,
+
+    /// The bounds of the line's text, excluding leading.
+    ///
+    /// The width does not include trailing spaces that the line was wrapped
+    /// at, and the X offset includes margins, indents and alignment.
+    bounds: BoxBounds<Twips>,
+
+    /// The ascent of the line's font, scaled to the largest size on the line.
+    ascent: Twips,
+
+    /// The descent of the line's font, scaled to the largest size on the
+    /// line.
+    descent: Twips,
+
+    /// The extra space between this line and the next.
+    leading: Twips,
+}
+
+impl LayoutLine {
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    pub fn bounds(&self) -> BoxBounds<Twips> {
+        self.bounds
+    }
+
+    pub fn ascent(&self) -> Twips {
+        self.ascent
+    }
+
+    pub fn descent(&self) -> Twips {
+        self.descent
+    }
+
+    pub fn leading(&self) -> Twips {
+        self.leading
+    }
+}
+
 /// A `LayoutBox` represents a single content box within a fully laid-out
 /// `EditText`.
 ///
@@ -564,7 +721,8 @@ pub struct LayoutBox<'gc> {
 
 /// Represents different content modes of a given `LayoutBox`.
 ///
-/// Currently, a `LayoutBox` can contain `Text`, `Bullet`s, or a `Drawing`.
+/// Currently, a `LayoutBox` can contain `Text`, `Bullet`s, an `Image`, or a
+/// `Drawing`.
 #[derive(Clone, Debug, Collect)]
 #[collect(no_drop)]
 pub enum LayoutContent<'gc> {
@@ -614,6 +772,17 @@ pub enum LayoutContent<'gc> {
         color: swf::Color,
     },
 
+    /// A layout box containing an image from an `<img>` tag.
+    ///
+    /// The box includes the empty space around the image.
+    Image {
+        /// The index of the image within the text's images.
+        index: usize,
+
+        /// Where the image is placed within the box.
+        offset: Position<Twips>,
+    },
+
     /// A layout box containing a drawing.
     ///
     /// The drawing will be rendered with its origin at the position of the
@@ -655,6 +824,14 @@ impl<'gc> LayoutBox<'gc> {
         }
     }
 
+    /// Construct an image.
+    pub fn from_image(index: usize, offset: Position<Twips>) -> Self {
+        Self {
+            bounds: Default::default(),
+            content: LayoutContent::Image { index, offset },
+        }
+    }
+
     /// Construct a drawing.
     pub fn from_drawing(drawing: Drawing) -> Self {
         Self {
@@ -674,11 +851,13 @@ impl<'gc> LayoutBox<'gc> {
         bounds: Twips,
         is_word_wrap: bool,
         is_device_font: bool,
-    ) -> (Vec<LayoutBox<'gc>>, BoxBounds<Twips>) {
+    ) -> Layout<'gc> {
         let mut layout_context = LayoutContext::new(movie, bounds, fs.displayed_text());
+        let mut images = fs.images().iter().enumerate().peekable();
 
         for (span_start, _end, span_text, span) in fs.iter_spans() {
             if let Some(font) = layout_context.resolve_font(context, span, is_device_font) {
+                font.load_device_glyphs(span_text, context.renderer, context.fonts);
                 layout_context.newspan(span);
 
                 let params = EvalParameters::from_span(span);
@@ -693,13 +872,20 @@ impl<'gc> LayoutBox<'gc> {
                         None
                     };
 
+                    let start = span_start + slice_start;
+                    layout_context.text_position = start;
+
                     match delimiter {
                         Some(b'\n' | b'\r') => layout_context.explicit_newline(context),
                         Some(b'\t') => layout_context.tab(),
                         _ => {}
                     }
 
-                    let start = span_start + slice_start;
+                    while let Some((index, image)) =
+                        images.next_if(|(_, image)| image.position <= start)
+                    {
+                        layout_context.append_image(context, index, image, is_word_wrap);
+                    }
 
                     let mut last_breakpoint = 0;
 
@@ -736,11 +922,16 @@ impl<'gc> LayoutBox<'gc> {
                             }
 
                             // This ensures that the space causing the line break
-                            // is included in the line it broke.
-                            let next_breakpoint = string_utils::next_char_boundary(
-                                text,
-                                last_breakpoint + breakpoint,
-                            );
+                            // is included in the line it broke. Lines broken
+                            // after a hyphen or between ideographs have no
+                            // such space.
+                            let next_breakpoint = if text.get(last_breakpoint + breakpoint)
+                                == Some(u16::from(b' '))
+                            {
+                                string_utils::next_char_boundary(text, last_breakpoint + breakpoint)
+                            } else {
+                                last_breakpoint + breakpoint
+                            };
 
                             layout_context.append_text(
                                 &text[last_breakpoint..next_breakpoint],
@@ -776,6 +967,10 @@ impl<'gc> LayoutBox<'gc> {
             }
         }
 
+        for (index, image) in images {
+            layout_context.append_image(context, index, image, is_word_wrap);
+        }
+
         layout_context.end_layout(context)
     }
 
@@ -820,6 +1015,7 @@ impl<'gc> LayoutBox<'gc> {
                 *params,
                 swf::Color::from_rgb(color.to_rgb(), 0xFF),
             )),
+            LayoutContent::Image { .. } => None,
             LayoutContent::Drawing(..) => None,
         }
     }
@@ -829,6 +1025,7 @@ impl<'gc> LayoutBox<'gc> {
         match &self.content {
             LayoutContent::Text { .. } => None,
             LayoutContent::Bullet { .. } => None,
+            LayoutContent::Image { .. } => None,
             LayoutContent::Drawing(drawing) => Some(drawing),
         }
     }
@@ -840,4 +1037,8 @@ impl<'gc> LayoutBox<'gc> {
     pub fn is_bullet(&self) -> bool {
         matches!(&self.content, LayoutContent::Bullet { .. })
     }
+
+    pub fn is_image(&self) -> bool {
+        matches!(&self.content, LayoutContent::Image { .. })
+    }
 }
@@ -0,0 +1,229 @@
+//! CSS style sheets that format HTML text
+
+use crate::html::TextFormat;
+use crate::string::WString;
+
+/// The declarations of a CSS rule.
+///
+/// Property names are converted to camel case (`fontSize` instead of
+/// `font-size`), which is how ActionScript sees them.
+pub type Style = Vec<(String, String)>;
+
+/// A set of styles for HTML text, keyed by their selector.
+///
+/// Selectors are either tag names (`p`), class names (`.headline`), or the
+/// `a:link`, `a:hover` and `a:active` pseudo-classes of links.
+#[derive(Clone, Debug, Default)]
+pub struct StyleSheet {
+    styles: Vec<(String, Style)>,
+}
+
+impl StyleSheet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse the rules of a CSS document.
+    ///
+    /// Returns `None` if the document is malformed.
+    pub fn parse(css: &str) -> Option<Vec<(String, Style)>> {
+        let css = strip_comments(css);
+        let mut rules = Vec::new();
+        let mut rest = css.trim();
+        while !rest.is_empty() {
+            let open = rest.find('{')?;
+            let close = open + rest[open..].find('}')?;
+            let selectors = &rest[..open];
+            let declarations = &rest[open + 1..close];
+            rest = rest[close + 1..].trim_start();
+
+            let mut style = Style::new();
+            for declaration in declarations.split(';') {
+                let declaration = declaration.trim();
+                if declaration.is_empty() {
+                    continue;
+                }
+                let (name, value) = declaration.split_once(':')?;
+                style.push((camel_case(name.trim()), value.trim().to_string()));
+            }
+
+            for selector in selectors.split(',') {
+                let selector = selector.trim();
+                if selector.is_empty() {
+                    return None;
+                }
+                rules.push((selector.to_ascii_lowercase(), style.clone()));
+            }
+        }
+        Some(rules)
+    }
+
+    pub fn style(&self, selector: &str) -> Option<&Style> {
+        let selector = selector.to_ascii_lowercase();
+        self.styles
+            .iter()
+            .find(|(other, _)| *other == selector)
+            .map(|(_, style)| style)
+    }
+
+    /// Replace the style of a selector, or remove it if `style` is `None`.
+    pub fn set_style(&mut self, selector: &str, style: Option<Style>) {
+        let selector = selector.to_ascii_lowercase();
+        let index = self.styles.iter().position(|(other, _)| *other == selector);
+        match (index, style) {
+            (Some(index), Some(style)) => self.styles[index].1 = style,
+            (Some(index), None) => {
+                self.styles.remove(index);
+            }
+            (None, Some(style)) => self.styles.push((selector, style)),
+            (None, None) => {}
+        }
+    }
+
+    /// The selectors of all styles, in the order they were added.
+    pub fn selectors(&self) -> impl Iterator<Item = &str> {
+        self.styles.iter().map(|(selector, _)| selector.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.styles.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.styles.clear();
+    }
+
+    /// Apply the style of a selector to `format`, if there is one.
+    pub fn apply(&self, selector: &str, format: &mut TextFormat) {
+        if let Some(style) = self.style(selector) {
+            apply_style(style, format);
+        }
+    }
+}
+
+/// Apply the properties of a style that have a `TextFormat` equivalent.
+///
+/// Unknown properties and invalid values are ignored.
+pub fn apply_style(style: &Style, format: &mut TextFormat) {
+    for (name, value) in style {
+        let value = value.as_str();
+        match name.as_str() {
+            "color" => {
+                if let Some(color) = parse_color(value) {
+                    format.color = Some(color);
+                }
+            }
+            "fontFamily" => {
+                let family = value.split(',').next().unwrap_or_default().trim();
+                let family = family.trim_matches(|c| c == '"' || c == '\'');
+                let family = match family {
+                    "sans-serif" => "_sans",
+                    "serif" => "_serif",
+                    "mono" | "monospace" => "_typewriter",
+                    family => family,
+                };
+                format.font = Some(WString::from_utf8(family));
+            }
+            "fontSize" => {
+                if let Some(size) = parse_length(value) {
+                    format.size = Some(size);
+                }
+            }
+            "fontStyle" => match value {
+                "italic" => format.italic = Some(true),
+                "normal" => format.italic = Some(false),
+                _ => {}
+            },
+            "fontWeight" => match value {
+                "bold" => format.bold = Some(true),
+                "normal" => format.bold = Some(false),
+                _ => {}
+            },
+            "kerning" => match value {
+                "true" => format.kerning = Some(true),
+                "false" => format.kerning = Some(false),
+                _ => {}
+            },
+            "letterSpacing" => {
+                if let Some(letter_spacing) = parse_length(value) {
+                    format.letter_spacing = Some(letter_spacing);
+                }
+            }
+            "marginLeft" => {
+                if let Some(margin) = parse_length(value) {
+                    format.left_margin = Some(margin);
+                }
+            }
+            "marginRight" => {
+                if let Some(margin) = parse_length(value) {
+                    format.right_margin = Some(margin);
+                }
+            }
+            "textAlign" => match value {
+                "left" => format.align = Some(swf::TextAlign::Left),
+                "center" => format.align = Some(swf::TextAlign::Center),
+                "right" => format.align = Some(swf::TextAlign::Right),
+                "justify" => format.align = Some(swf::TextAlign::Justify),
+                _ => {}
+            },
+            "textDecoration" => match value {
+                "underline" => format.underline = Some(true),
+                "none" => format.underline = Some(false),
+                _ => {}
+            },
+            "textIndent" => {
+                if let Some(indent) = parse_length(value) {
+                    format.indent = Some(indent);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Convert a CSS property name like `font-size` to `fontSize`.
+fn camel_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '-' {
+            upper = true;
+        } else if upper {
+            result.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+fn strip_comments(css: &str) -> String {
+    let mut result = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        result.push_str(&rest[..start]);
+        rest = match rest[start + 2..].find("*/") {
+            Some(end) => &rest[start + 2 + end + 2..],
+            None => "",
+        };
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Parse a length in pixels, such as `12px` or `12`.
+fn parse_length(value: &str) -> Option<f64> {
+    let value = value.strip_suffix("px").unwrap_or(value).trim();
+    value.parse().ok().filter(|length: &f64| length.is_finite())
+}
+
+/// Parse a `#RRGGBB` color.
+fn parse_color(value: &str) -> Option<swf::Color> {
+    let hex = value.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let rgb = u32::from_str_radix(hex, 16).ok()?;
+    Some(swf::Color::from_rgb(rgb, 0))
+}
@@ -1,6 +1,7 @@
 //! Tests for HTML module
 
 use crate::html::dimensions::{BoxBounds, Position, Size};
+use crate::html::style_sheet::StyleSheet;
 use crate::html::text_format::{FormatSpans, TextFormat, TextSpan};
 use crate::string::{WStr, WString};
 use swf::{Rectangle, Twips};
@@ -864,3 +865,147 @@ fn formatspans_replace_text_degenerate() {
     assert_eq!((0, 1), fs.get_span_boundaries(0, 5));
     assert_eq!((1, 2), fs.get_span_boundaries(5, 9));
 }
+
+#[test]
+fn style_sheet_parse() {
+    let rules = StyleSheet::parse(
+        "/* headings */ H1, .title { font-size: 24px; FONT-WEIGHT: bold }\na:link{color:#FF0000;}",
+    )
+    .unwrap();
+
+    assert_eq!(rules.len(), 3);
+    assert_eq!(rules[0].0, "h1");
+    assert_eq!(rules[1].0, ".title");
+    assert_eq!(
+        rules[0].1,
+        vec![
+            ("fontSize".to_string(), "24px".to_string()),
+            ("FONTWEIGHT".to_string(), "bold".to_string()),
+        ]
+    );
+    assert_eq!(rules[2].0, "a:link");
+
+    assert!(StyleSheet::parse("p { color: #000000;").is_none());
+    assert!(StyleSheet::parse("p { color }").is_none());
+}
+
+#[test]
+fn formatspans_from_html_with_style_sheet() {
+    let mut style_sheet = StyleSheet::new();
+    for (selector, style) in
+        StyleSheet::parse("p { font-size: 20; } .red { color: #FF0000; font-weight: bold; }")
+            .unwrap()
+    {
+        style_sheet.set_style(&selector, Some(style));
+    }
+
+    let fs = FormatSpans::from_html(
+        &WString::from_utf8("<p>big <span class=\"red\">red</span></p>"),
+        TextFormat::default(),
+        Some(&style_sheet),
+        false,
+    );
+
+    let spans: Vec<_> = fs.iter_spans().collect();
+    assert_eq!(spans.len(), 2);
+    assert_eq!(spans[0].2, WStr::from_units(b"big "));
+    assert_eq!(spans[0].3.size, 20.0);
+    assert!(!spans[0].3.bold);
+    assert_eq!(spans[1].2, WStr::from_units(b"red"));
+    assert_eq!(spans[1].3.size, 20.0);
+    assert!(spans[1].3.bold);
+    assert_eq!(spans[1].3.color, swf::Color::from_rgb(0xFF0000, 0));
+}
+
+#[test]
+fn textspan_text_format_roundtrip() {
+    let tf = TextFormat {
+        bullet: Some(true),
+        bold: Some(false),
+        leading: Some(4.0),
+        letter_spacing: Some(1.5),
+        kerning: Some(true),
+        tab_stops: Some(vec![20.0, 40.0]),
+        ..Default::default()
+    };
+
+    let span = TextSpan::with_length_and_format(1, tf);
+    let tf = span.get_text_format();
+
+    assert_eq!(tf.bullet, Some(true));
+    assert_eq!(tf.bold, Some(false));
+    assert_eq!(tf.leading, Some(4.0));
+    assert_eq!(tf.letter_spacing, Some(1.5));
+    assert_eq!(tf.kerning, Some(true));
+    assert_eq!(tf.tab_stops, Some(vec![20.0, 40.0]));
+}
+
+#[test]
+fn formatspans_from_html_paragraph_formatting() {
+    let fs = FormatSpans::from_html(
+        &WString::from_utf8(
+            "<textformat indent=\"5\" blockindent=\"10\" leading=\"2\" tabstops=\"30, 60\">\
+             <li><font letterSpacing=\"3\" kerning=\"1\">item</font></li></textformat>",
+        ),
+        TextFormat::default(),
+        None,
+        true,
+    );
+
+    let (_, _, text, span) = fs.iter_spans().next().unwrap();
+    assert_eq!(&text[..4], WStr::from_units(b"item"));
+    assert_eq!(span.indent, 5.0);
+    assert_eq!(span.block_indent, 10.0);
+    assert_eq!(span.leading, 2.0);
+    assert_eq!(span.tab_stops, vec![30.0, 60.0]);
+    assert!(span.bullet);
+    assert_eq!(span.letter_spacing, 3.0);
+    assert!(span.kerning);
+}
+
+#[test]
+fn formatspans_from_html_images() {
+    let fs = FormatSpans::from_html(
+        &WString::from_utf8(
+            "a<img src=\"pic.swf\" id=\"pic\" width=\"20\" height=\"10\" hspace=\"0\"/>b\
+             <IMG SRC=\"linkage\">c",
+        ),
+        TextFormat::default(),
+        None,
+        false,
+    );
+
+    assert_eq!(fs.text(), WStr::from_units(b"abc"));
+
+    let images = fs.images();
+    assert_eq!(images.len(), 2);
+    assert_eq!(images[0].position, 1);
+    assert_eq!(images[0].src, WStr::from_units(b"pic.swf"));
+    assert_eq!(images[0].id.as_deref(), Some(WStr::from_units(b"pic")));
+    assert_eq!(images[0].width, Some(Twips::from_pixels(20.0)));
+    assert_eq!(images[0].height, Some(Twips::from_pixels(10.0)));
+    assert_eq!(images[0].hspace, Twips::ZERO);
+    assert_eq!(images[0].vspace, Twips::from_pixels(8.0));
+    assert_eq!(images[1].position, 2);
+    assert_eq!(images[1].src, WStr::from_units(b"linkage"));
+    assert_eq!(images[1].width, None);
+}
+
+#[test]
+fn formatspans_from_html_quirks() {
+    let fs = FormatSpans::from_html(
+        &WString::from_utf8(
+            "<font size=\"10\">a<font size=\"+4\">b</font></font></b>&#x41;&#65;<li>c</li>",
+        ),
+        TextFormat::default(),
+        None,
+        true,
+    );
+
+    assert_eq!(fs.text(), WStr::from_units(b"abAA\nc\n"));
+
+    let spans: Vec<_> = fs.iter_spans().collect();
+    assert_eq!(spans[0].3.size, 10.0);
+    assert_eq!(spans[1].3.size, 14.0);
+    assert!(spans.last().unwrap().3.bullet);
+}
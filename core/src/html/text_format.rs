@@ -2,15 +2,18 @@
 
 use crate::context::UpdateContext;
 use crate::html::iterators::TextSpanIter;
+use crate::html::StyleSheet;
 use crate::string::{AvmString, Integer, Units, WStr, WString};
 use crate::tag_utils::SwfMovie;
 use crate::xml::{XmlDocument, XmlName, XmlNode};
 use gc_arena::{Collect, MutationContext};
+use quick_xml::events::attributes::Attribute;
 use quick_xml::events::Event;
 use quick_xml::Reader;
 use std::borrow::Cow;
 use std::cmp::{min, Ordering};
 use std::sync::Arc;
+use swf::Twips;
 
 /// Replace HTML entities with their equivalent characters.
 ///
@@ -48,7 +51,7 @@ fn process_html_entity(src: &WStr) -> Option<WString> {
                     result_str.push_byte(b'\xA0');
                 } else if s.len() >= 2 && s.at(0) == b'#' as u16 {
                     // Number entity: &#nnnn; or &#xhhhh;
-                    let (digits, radix) = if src.at(1) == b'x' as u16 {
+                    let (digits, radix) = if s.at(1) == b'x' as u16 || s.at(1) == b'X' as u16 {
                         // Only trailing 4 hex digits are used.
                         let start = usize::max(s.len(), 6) - 4;
                         (&s[start..], 16)
@@ -98,6 +101,18 @@ fn process_html_entity(src: &WStr) -> Option<WString> {
     Some(result_str)
 }
 
+/// Create a case-insensitive lookup of the value of an HTML tag's attributes.
+fn html_attribute(attributes: Vec<Attribute<'_>>) -> impl Fn(&[u8]) -> Option<WString> + '_ {
+    move |name| {
+        attributes.iter().find_map(|attribute| {
+            attribute
+                .key
+                .eq_ignore_ascii_case(name)
+                .then(|| WString::from_buf(attribute.value.to_vec()))
+        })
+    }
+}
+
 /// A set of text formatting options to be applied to some part, or the whole
 /// of, a given text field.
 ///
@@ -508,13 +523,63 @@ impl TextSpan {
             leading: Some(self.leading),
             letter_spacing: Some(self.letter_spacing),
             tab_stops: Some(self.tab_stops.clone()),
-            bullet: Some(self.bold),
+            bullet: Some(self.bullet),
             url: Some(self.url.clone()),
             target: Some(self.target.clone()),
         }
     }
 }
 
+/// An image placed into HTML text with an `<img>` tag.
+#[derive(Clone, Debug)]
+pub struct HtmlImage {
+    /// The position in the text that the image is placed at.
+    pub position: usize,
+
+    /// The URL or linkage name of the image.
+    pub src: WString,
+
+    /// The ID that scripts can look the image up by.
+    pub id: Option<WString>,
+
+    /// The width to display the image at, or `None` until it is known.
+    pub width: Option<Twips>,
+
+    /// The height to display the image at, or `None` until it is known.
+    pub height: Option<Twips>,
+
+    /// The empty space to the left and right of the image.
+    pub hspace: Twips,
+
+    /// The empty space above and below the image.
+    pub vspace: Twips,
+}
+
+impl HtmlImage {
+    /// The default space around images, in pixels.
+    const DEFAULT_SPACE: f64 = 8.0;
+
+    /// Parse an image from the attributes of an `<img>` tag.
+    fn from_attributes(position: usize, attribute: impl Fn(&[u8]) -> Option<WString>) -> Self {
+        let pixels = |name: &[u8]| {
+            attribute(name)
+                .and_then(|v| v.trim().parse::<f64>().ok())
+                .filter(|v| v.is_finite() && *v >= 0.0)
+                .map(Twips::from_pixels)
+        };
+
+        Self {
+            position,
+            src: attribute(b"src").unwrap_or_default(),
+            id: attribute(b"id"),
+            width: pixels(b"width"),
+            height: pixels(b"height"),
+            hspace: pixels(b"hspace").unwrap_or_else(|| Twips::from_pixels(Self::DEFAULT_SPACE)),
+            vspace: pixels(b"vspace").unwrap_or_else(|| Twips::from_pixels(Self::DEFAULT_SPACE)),
+        }
+    }
+}
+
 /// Struct which contains text formatted by `TextSpan`s.
 #[derive(Clone, Debug, Collect)]
 #[collect(require_static)]
@@ -523,6 +588,7 @@ pub struct FormatSpans {
     displayed_text: WString,
     spans: Vec<TextSpan>,
     default_format: TextFormat,
+    images: Vec<HtmlImage>,
 }
 
 impl Default for FormatSpans {
@@ -538,6 +604,7 @@ impl FormatSpans {
             displayed_text: WString::new(),
             spans: vec![TextSpan::default()],
             default_format: TextFormat::default(),
+            images: Vec::new(),
         }
     }
 
@@ -549,6 +616,7 @@ impl FormatSpans {
             displayed_text: WString::new(),
             spans: spans.to_vec(),
             default_format: Default::default(),
+            images: Vec::new(),
         }
     }
 
@@ -559,19 +627,25 @@ impl FormatSpans {
             displayed_text: WString::new(),
             spans: vec![TextSpan::with_length_and_format(len, format.clone())],
             default_format: format,
+            images: Vec::new(),
         }
     }
 
     /// Lower an HTML tree into text-span representation.
     ///
-    /// This is the "legacy" implementation of this process: it only looks for
-    /// a handful of presentational attributes in the HTML tree to generate
-    /// styling. There's also a `lower_from_css` that respects both
-    /// presentational markup and CSS stylesheets.
-    pub fn from_html(html: &WStr, default_format: TextFormat, is_multiline: bool) -> Self {
+    /// Styling comes from a handful of presentational tags and attributes,
+    /// as well as the rules of `style_sheet` that match the tag name, its
+    /// class, or the `a:link` pseudo-class of links.
+    pub fn from_html(
+        html: &WStr,
+        default_format: TextFormat,
+        style_sheet: Option<&StyleSheet>,
+        is_multiline: bool,
+    ) -> Self {
         let mut format_stack = vec![default_format.clone()];
         let mut text = WString::new();
         let mut spans: Vec<TextSpan> = Vec::new();
+        let mut images = Vec::new();
 
         // quick_xml::Reader requires a [u8] slice, but doesn't actually care about Unicode;
         // this means we can pass the raw buffer in the Latin1 case.
@@ -583,10 +657,19 @@ impl FormatSpans {
         };
 
         let mut reader = Reader::from_reader(&raw_bytes[..]);
+        // Flash tolerates closing tags that don't match the open tags.
+        reader.check_end_names(false);
         let mut buf = Vec::new();
         loop {
             match reader.read_event(&mut buf) {
                 Ok(Event::Empty(ref e)) => match &e.name().to_ascii_lowercase()[..] {
+                    b"img" => {
+                        let attributes = e.attributes().with_checks(false).flatten().collect();
+                        images.push(HtmlImage::from_attributes(
+                            text.len(),
+                            html_attribute(attributes),
+                        ));
+                    }
                     b"br" if is_multiline => {
                         text.push_byte(b'\n');
                         if let Some(span) = spans.last_mut() {
@@ -612,16 +695,15 @@ impl FormatSpans {
                             return Default::default();
                         }
                     };
-                    let attribute = move |name| {
-                        attributes.iter().find_map(|attribute| {
-                            attribute
-                                .key
-                                .eq_ignore_ascii_case(name)
-                                .then(|| WString::from_buf(attribute.value.to_vec()))
-                        })
-                    };
+                    let attribute = html_attribute(attributes);
                     let mut format = format_stack.last().unwrap().clone();
                     match &e.name().to_ascii_lowercase()[..] {
+                        b"img" => {
+                            images.push(HtmlImage::from_attributes(text.len(), &attribute));
+
+                            // Skip push to `format_stack`.
+                            continue;
+                        }
                         b"br" => {
                             if is_multiline {
                                 text.push_byte(b'\n');
@@ -670,7 +752,17 @@ impl FormatSpans {
                             }
 
                             if let Some(size) = attribute(b"size") {
-                                format.size = size.parse().ok();
+                                // Sizes starting with a sign are relative to the
+                                // current size.
+                                let size = size.trim();
+                                let relative = size.starts_with(b'+') || size.starts_with(b'-');
+                                format.size = match size.parse::<f64>() {
+                                    Ok(size) if relative => {
+                                        Some((format.size.unwrap_or_default() + size).max(0.0))
+                                    }
+                                    Ok(size) => Some(size),
+                                    Err(_) => None,
+                                };
                             }
 
                             if let Some(color) = attribute(b"color") {
@@ -713,6 +805,14 @@ impl FormatSpans {
                             format.underline = Some(true);
                         }
                         b"li" if is_multiline => {
+                            // List items always start on a new line.
+                            if !text.is_empty() && !text.ends_with(b'\n') {
+                                text.push_byte(b'\n');
+                                if let Some(span) = spans.last_mut() {
+                                    span.span_length += 1;
+                                }
+                            }
+
                             format.bullet = Some(true);
                         }
                         b"textformat" => {
@@ -749,6 +849,16 @@ impl FormatSpans {
                         }
                         _ => {}
                     }
+                    if let Some(style_sheet) = style_sheet {
+                        let name = String::from_utf8_lossy(e.name()).to_ascii_lowercase();
+                        style_sheet.apply(&name, &mut format);
+                        if name == "a" {
+                            style_sheet.apply("a:link", &mut format);
+                        }
+                        if let Some(class) = attribute(b"class") {
+                            style_sheet.apply(&format!(".{}", class), &mut format);
+                        }
+                    }
                     format_stack.push(format);
                 }
                 Ok(Event::Text(e)) if !e.is_empty() => {
@@ -760,7 +870,7 @@ impl FormatSpans {
                 }
                 Ok(Event::End(e)) => {
                     match &e.name().to_ascii_lowercase()[..] {
-                        b"br" | b"sbr" => {
+                        b"br" | b"sbr" | b"img" => {
                             // Skip pop from `format_stack`.
                             continue;
                         }
@@ -772,7 +882,11 @@ impl FormatSpans {
                         }
                         _ => {}
                     }
-                    format_stack.pop();
+
+                    // Stray closing tags must not pop the default format.
+                    if format_stack.len() > 1 {
+                        format_stack.pop();
+                    }
                 }
                 Ok(Event::Eof) => break,
                 Err(e) => {
@@ -790,6 +904,21 @@ impl FormatSpans {
             displayed_text: WString::new(),
             spans,
             default_format,
+            images,
+        }
+    }
+
+    /// The images placed into the text, in order of their position.
+    pub fn images(&self) -> &[HtmlImage] {
+        &self.images
+    }
+
+    /// Set the size of an image once it is known, keeping any size that was
+    /// given explicitly.
+    pub fn set_image_size(&mut self, index: usize, width: Twips, height: Twips) {
+        if let Some(image) = self.images.get_mut(index) {
+            image.width.get_or_insert(width);
+            image.height.get_or_insert(height);
         }
     }
 
@@ -975,12 +1104,17 @@ impl FormatSpans {
             self.spans.remove(0);
         }
 
+        // Spans are kept apart where images are placed, so that layout can
+        // place images between them.
         let mut i = 0;
+        let mut position = 0;
         while i < self.spans.len().saturating_sub(1) {
             let remove_next = {
                 let spans = self.spans.get_mut(i..i + 2).unwrap();
+                let boundary = position + spans[0].span_length;
+                let has_image = self.images.iter().any(|image| image.position == boundary);
 
-                if spans[0].can_merge(&spans[1]) || spans[1].span_length == 0 {
+                if (spans[0].can_merge(&spans[1]) && !has_image) || spans[1].span_length == 0 {
                     spans[0].span_length += spans[1].span_length;
                     true
                 } else {
@@ -991,6 +1125,7 @@ impl FormatSpans {
             if remove_next {
                 self.spans.remove(i + 1);
             } else {
+                position += self.spans[i].span_length;
                 i += 1;
             }
         }
@@ -1107,6 +1242,19 @@ impl FormatSpans {
             new_string.push_str(text);
         }
 
+        // Images within the replaced text move to its start, and images after
+        // it move along with the text that follows them.
+        let replaced_end = to.min(self.text.len()).max(from);
+        for image in self.images.iter_mut() {
+            if image.position <= from {
+                continue;
+            } else if image.position >= replaced_end {
+                image.position = image.position - (replaced_end - from) + with.len();
+            } else {
+                image.position = from;
+            }
+        }
+
         self.text = new_string;
 
         self.normalize();
@@ -1158,7 +1306,7 @@ impl FormatSpans {
             {
                 let new_tf = XmlNode::new_element(mc, "TEXTFORMAT".into(), document);
 
-                if ls.left_margin != 0.0 {
+                if span.left_margin != 0.0 {
                     new_tf.set_attribute_value(
                         mc,
                         XmlName::from_str("LEFTMARGIN"),
@@ -1166,7 +1314,7 @@ impl FormatSpans {
                     );
                 }
 
-                if ls.right_margin != 0.0 {
+                if span.right_margin != 0.0 {
                     new_tf.set_attribute_value(
                         mc,
                         XmlName::from_str("RIGHTMARGIN"),
@@ -1174,7 +1322,7 @@ impl FormatSpans {
                     );
                 }
 
-                if ls.indent != 0.0 {
+                if span.indent != 0.0 {
                     new_tf.set_attribute_value(
                         mc,
                         XmlName::from_str("INDENT"),
@@ -1182,7 +1330,7 @@ impl FormatSpans {
                     );
                 }
 
-                if ls.block_indent != 0.0 {
+                if span.block_indent != 0.0 {
                     new_tf.set_attribute_value(
                         mc,
                         XmlName::from_str("BLOCKINDENT"),
@@ -1190,7 +1338,7 @@ impl FormatSpans {
                     );
                 }
 
-                if ls.leading != 0.0 {
+                if span.leading != 0.0 {
                     new_tf.set_attribute_value(
                         mc,
                         XmlName::from_str("LEADING"),
@@ -1198,7 +1346,7 @@ impl FormatSpans {
                     );
                 }
 
-                if !ls.tab_stops.is_empty() {
+                if !span.tab_stops.is_empty() {
                     let tab_stops = span
                         .tab_stops
                         .iter()
@@ -11,7 +11,7 @@
 
 #[macro_use]
 mod display_object;
-pub use display_object::StageDisplayState;
+pub use display_object::{StageDisplayState, StageQuality};
 
 #[macro_use]
 extern crate smallvec;
@@ -32,6 +32,7 @@ mod character;
 pub mod color_transform;
 pub mod context;
 pub mod context_menu;
+mod damage;
 mod drawing;
 mod ecma_conversions;
 pub mod events;
@@ -40,10 +41,14 @@ mod font;
 mod html;
 mod library;
 pub mod loader;
+pub mod local_connection;
 pub mod matrix;
+pub mod microphone;
 mod player;
 mod prelude;
 pub mod shape_utils;
+pub mod socket;
+pub mod streams;
 pub mod string;
 pub mod tag_utils;
 mod transform;
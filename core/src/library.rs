@@ -1,11 +1,13 @@
 use crate::avm1::function::FunctionObject;
 use crate::avm1::property_map::PropertyMap as Avm1PropertyMap;
 use crate::avm2::{ClassObject as Avm2ClassObject, Domain as Avm2Domain};
+use crate::backend::font::{device_font_families, FontBackend};
 use crate::backend::{audio::SoundHandle, render};
 use crate::character::Character;
 use crate::display_object::{Bitmap, Graphic, MorphShape, TDisplayObject, Text};
 use crate::font::{Font, FontDescriptor};
 use crate::prelude::*;
+use crate::shape_utils::ShapeCache;
 use crate::string::AvmString;
 use crate::tag_utils::SwfMovie;
 use crate::vminterface::AvmType;
@@ -145,6 +147,10 @@ pub struct MovieLibrary<'gc> {
     /// Shared reference to the constructor registry used for this movie.
     /// Should be `None` if this is an AVM2 movie.
     avm1_constructor_registry: Option<Gc<'gc, Avm1ConstructorRegistry<'gc>>>,
+
+    /// The shapes of this movie tessellated with their strokes scaled.
+    #[collect(require_static)]
+    shape_cache: ShapeCache,
 }
 
 impl<'gc> MovieLibrary<'gc> {
@@ -157,6 +163,7 @@ impl<'gc> MovieLibrary<'gc> {
             avm_type,
             avm2_domain: None,
             avm1_constructor_registry: None,
+            shape_cache: ShapeCache::default(),
         }
     }
 
@@ -377,6 +384,12 @@ impl<'gc> MovieLibrary<'gc> {
     pub fn avm2_domain(&self) -> Avm2Domain<'gc> {
         self.avm2_domain.unwrap()
     }
+
+    /// The shapes of this movie tessellated with their strokes scaled, which are
+    /// shared between all instances of the shapes.
+    pub fn shape_cache(&self) -> &ShapeCache {
+        &self.shape_cache
+    }
 }
 
 impl<'gc> render::BitmapSource for MovieLibrary<'gc> {
@@ -399,6 +412,14 @@ pub struct Library<'gc> {
     /// The embedded device font.
     device_font: Option<Font<'gc>>,
 
+    /// The fonts installed on the system that device fonts were looked up
+    /// as, or `None` for device fonts that no family is installed for.
+    system_fonts: HashMap<FontDescriptor, Option<Font<'gc>>>,
+
+    /// The embedded fonts registered with `Font.registerFont`, which are
+    /// available to every movie.
+    registered_fonts: HashMap<FontDescriptor, Font<'gc>>,
+
     constructor_registry_case_insensitive: Gc<'gc, Avm1ConstructorRegistry<'gc>>,
     constructor_registry_case_sensitive: Gc<'gc, Avm1ConstructorRegistry<'gc>>,
 
@@ -414,6 +435,12 @@ unsafe impl<'gc> gc_arena::Collect for Library<'gc> {
             val.trace(cc);
         }
         self.device_font.trace(cc);
+        for font in self.system_fonts.values() {
+            font.trace(cc);
+        }
+        for font in self.registered_fonts.values() {
+            font.trace(cc);
+        }
         self.constructor_registry_case_insensitive.trace(cc);
         self.constructor_registry_case_sensitive.trace(cc);
         self.avm2_class_registry.trace(cc);
@@ -425,6 +452,8 @@ impl<'gc> Library<'gc> {
         Self {
             movie_libraries: PtrWeakKeyHashMap::new(),
             device_font: None,
+            system_fonts: HashMap::new(),
+            registered_fonts: HashMap::new(),
             constructor_registry_case_insensitive: Gc::allocate(
                 gc_context,
                 Avm1ConstructorRegistry::new(false, gc_context),
@@ -456,6 +485,16 @@ impl<'gc> Library<'gc> {
         self.movie_libraries.get_mut(&movie).unwrap()
     }
 
+    /// Evicts the shapes that weren't rendered during the last frame from
+    /// the shape caches of all movies that are full.
+    ///
+    /// This should be called before every frame is rendered.
+    pub fn sweep_shape_caches(&self) {
+        for (_, library) in self.movie_libraries.iter() {
+            library.shape_cache.sweep();
+        }
+    }
+
     /// Returns the device font for use when a font is unavailable.
     pub fn device_font(&self) -> Option<Font<'gc>> {
         self.device_font
@@ -466,6 +505,53 @@ impl<'gc> Library<'gc> {
         self.device_font = font;
     }
 
+    /// Returns the font installed on the system for a device font with the
+    /// given name and style, which is loaded through the font backend the
+    /// first time it is used.
+    ///
+    /// Returns `None` if no such font is installed, in which case the
+    /// embedded device font should be used instead.
+    pub fn system_font(
+        &mut self,
+        gc_context: MutationContext<'gc, '_>,
+        fonts: &mut dyn FontBackend,
+        name: &str,
+        is_bold: bool,
+        is_italic: bool,
+    ) -> Option<Font<'gc>> {
+        let descriptor = FontDescriptor::from_parts(name, is_bold, is_italic);
+        if let Some(font) = self.system_fonts.get(&descriptor) {
+            return *font;
+        }
+
+        let font = device_font_families(descriptor.class())
+            .into_iter()
+            .find_map(|family| {
+                let file = fonts.load_font(family, is_bold, is_italic)?;
+                Font::from_font_file(gc_context, file, descriptor.clone())
+            });
+        self.system_fonts.insert(descriptor, font);
+        font
+    }
+
+    /// Makes an embedded font available to every movie.
+    pub fn register_font(&mut self, font: Font<'gc>) {
+        self.registered_fonts
+            .insert(font.descriptor().clone(), font);
+    }
+
+    /// Find a font registered with `register_font` by its name and style.
+    pub fn registered_font(&self, name: &str, is_bold: bool, is_italic: bool) -> Option<Font<'gc>> {
+        let descriptor = FontDescriptor::from_parts(name, is_bold, is_italic);
+
+        self.registered_fonts.get(&descriptor).copied()
+    }
+
+    /// The fonts registered with `register_font`.
+    pub fn registered_fonts(&self) -> impl Iterator<Item = Font<'gc>> + '_ {
+        self.registered_fonts.values().copied()
+    }
+
     /// Gets the constructor registry to use for the given SWF version.
     /// Because SWFs v6 and v7+ use different case-sensitivity rules, Flash
     /// keeps two separate registries, one case-sensitive, the other not.
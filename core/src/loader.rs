@@ -1,12 +1,16 @@
 //! Management of async loaders
 
 use crate::avm1::activation::{Activation, ActivationIdentifier};
-use crate::avm1::{Avm1, Object, TObject, Value};
-use crate::avm2::{Activation as Avm2Activation, Domain as Avm2Domain};
-use crate::backend::navigator::OwnedFuture;
-use crate::context::{ActionQueue, ActionType};
+use crate::avm1::{Avm1, Object, SoundObject, TObject, Value};
+use crate::avm2::{
+    Activation as Avm2Activation, Avm2, Domain as Avm2Domain, Event as Avm2Event, LoaderStream,
+    Object as Avm2Object, TObject as _,
+};
+use crate::backend::navigator::{FetchProgress, OwnedFuture};
+use crate::context::{ActionQueue, ActionType, UpdateContext};
 use crate::display_object::{DisplayObject, MorphShape, TDisplayObject};
 use crate::player::{Player, NEWEST_PLAYER_VERSION};
+use crate::streams::NetStream;
 use crate::string::AvmString;
 use crate::tag_utils::SwfMovie;
 use crate::vminterface::Instantiator;
@@ -14,8 +18,11 @@ use crate::xml::XmlNode;
 use encoding_rs::UTF_8;
 use gc_arena::{Collect, CollectionContext};
 use generational_arena::{Arena, Index};
+use std::future::Future;
+use std::pin::Pin;
 use std::string::FromUtf8Error;
 use std::sync::{Arc, Mutex, Weak};
+use std::task::{Context, Poll};
 use thiserror::Error;
 use url::form_urlencoded;
 
@@ -41,12 +48,30 @@ pub enum Error {
     #[error("Non-XML loader spawned as XML loader")]
     NotXmlLoader,
 
+    #[error("Non-NetStream loader spawned as NetStream loader")]
+    NotNetStreamLoader,
+
+    #[error("Non-sound loader spawned as sound loader")]
+    NotSoundLoader,
+
+    #[error("Non-URLLoader loader spawned as URLLoader loader")]
+    NotUrlLoader,
+
     #[error("Could not fetch movie {0}")]
     FetchError(String),
 
+    #[error("HTTP status is not ok, got {0}")]
+    HttpNotOk(u16),
+
     #[error("Invalid SWF")]
     InvalidSwf(#[from] crate::tag_utils::Error),
 
+    #[error("Invalid FLV")]
+    InvalidFlv(#[from] crate::streams::flv::Error),
+
+    #[error("Invalid MP4")]
+    InvalidMp4(#[from] crate::streams::mp4::Error),
+
     #[error("Invalid XML encoding")]
     InvalidXmlEncoding(#[from] FromUtf8Error),
 
@@ -62,6 +87,18 @@ pub enum Error {
     Avm1Error(String),
 }
 
+impl Error {
+    /// The HTTP status code to report to content for this failed load.
+    ///
+    /// Failures that didn't come from an HTTP response are reported as 404.
+    pub fn http_status(&self) -> u16 {
+        match self {
+            Error::HttpNotOk(status) => *status,
+            _ => 404,
+        }
+    }
+}
+
 pub type FormLoadHandler<'gc> =
     fn(&mut Activation<'_, 'gc, '_>, Object<'gc>, data: &[u8]) -> Result<(), Error>;
 
@@ -73,6 +110,15 @@ impl From<crate::avm1::error::Error<'_>> for Error {
     }
 }
 
+/// Report an error thrown by an AVM2 event handler that a loader invoked.
+///
+/// Loads carry on regardless, just like they do when AVM1 handlers fail.
+fn log_avm2_error<T>(result: Result<T, crate::avm2::Error>) {
+    if let Err(e) = result {
+        log::error!("Error running AVM2 loader event handler: {}", e);
+    }
+}
+
 /// Holds all in-progress loads for the player.
 pub struct LoadManager<'gc>(Arena<Loader<'gc>>);
 
@@ -141,12 +187,17 @@ impl<'gc> LoadManager<'gc> {
 
     /// Kick off a movie clip load.
     ///
+    /// If `progress` is given, it should be the progress of `fetch`, and
+    /// `onLoadProgress` will be broadcast as the movie arrives.
+    ///
     /// Returns the loader's async process, which you will need to spawn.
+    #[allow(clippy::too_many_arguments)]
     pub fn load_movie_into_clip(
         &mut self,
         player: Weak<Mutex<Player>>,
         target_clip: DisplayObject<'gc>,
         fetch: OwnedFuture<Vec<u8>, Error>,
+        progress: Option<FetchProgress>,
         url: String,
         loader_url: Option<String>,
         target_broadcaster: Option<Object<'gc>>,
@@ -155,14 +206,74 @@ impl<'gc> LoadManager<'gc> {
             self_handle: None,
             target_clip,
             target_broadcaster,
+            target_loader_info: None,
+            loader_status: LoaderStatus::Pending,
+            bytes_loaded: 0,
+            bytes_total: 0,
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        loader.movie_loader(player, fetch, progress, url, loader_url)
+    }
+
+    /// Kick off a movie load on behalf of an AVM2 `Loader`.
+    ///
+    /// The movie is loaded into `target_clip`, and the load's events are
+    /// fired on `loader_info`, the `contentLoaderInfo` of the `Loader`. If
+    /// `progress` is given, it should be the progress of `fetch`.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    pub fn load_movie_into_avm2_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_clip: DisplayObject<'gc>,
+        fetch: OwnedFuture<Vec<u8>, Error>,
+        progress: Option<FetchProgress>,
+        url: String,
+        loader_info: Avm2Object<'gc>,
+    ) -> OwnedFuture<(), Error> {
+        let loader = Loader::Movie {
+            self_handle: None,
+            target_clip,
+            target_broadcaster: None,
+            target_loader_info: Some(loader_info),
             loader_status: LoaderStatus::Pending,
+            bytes_loaded: 0,
+            bytes_total: 0,
         };
         let handle = self.add_loader(loader);
 
         let loader = self.get_loader_mut(handle).unwrap();
         loader.introduce_loader_handle(handle);
 
-        loader.movie_loader(player, fetch, url, loader_url)
+        loader.movie_loader(player, fetch, progress, url, None)
+    }
+
+    /// The number of bytes loaded so far, and the total number of bytes, of
+    /// a movie that is being loaded into `target_clip`. The total is 0 until
+    /// it is known.
+    ///
+    /// Returns `None` if no movie is being loaded into the clip.
+    pub fn movie_load_progress(&self, target_clip: DisplayObject<'gc>) -> Option<(usize, usize)> {
+        self.0.iter().find_map(|(_, loader)| match loader {
+            Loader::Movie {
+                target_clip: clip,
+                bytes_loaded,
+                bytes_total,
+                ..
+            } if DisplayObject::ptr_eq(*clip, target_clip) => Some((*bytes_loaded, *bytes_total)),
+            _ => None,
+        })
+    }
+
+    /// Cancel all loads of movies into `target_clip`.
+    pub fn cancel_movie_loads(&mut self, target_clip: DisplayObject<'gc>) {
+        self.0.retain(|_, loader| {
+            !matches!(loader, Loader::Movie { target_clip: clip, .. } if DisplayObject::ptr_eq(*clip, target_clip))
+        });
     }
 
     /// Indicates that a movie clip has initialized (ran its first frame).
@@ -251,6 +362,89 @@ impl<'gc> LoadManager<'gc> {
 
         loader.xml_loader(player, fetch)
     }
+
+    /// Kick off a media file load into a `NetStream`.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    pub fn load_net_stream(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_stream: NetStream<'gc>,
+        fetch: OwnedFuture<Vec<u8>, Error>,
+    ) -> OwnedFuture<(), Error> {
+        let loader = Loader::NetStream {
+            self_handle: None,
+            target_stream,
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        loader.net_stream_loader(player, fetch)
+    }
+
+    /// Kick off an MP3 load into an AVM1 `Sound` object.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    pub fn load_sound_avm1(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_object: SoundObject<'gc>,
+        fetch: OwnedFuture<Vec<u8>, Error>,
+        play_on_load: bool,
+    ) -> OwnedFuture<(), Error> {
+        let loader = Loader::SoundAvm1 {
+            self_handle: None,
+            target_object,
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        loader.sound_loader_avm1(player, fetch, play_on_load)
+    }
+
+    /// Kick off a data load into an AVM2 `URLLoader`.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    pub fn load_data_into_url_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_object: Avm2Object<'gc>,
+        fetch: OwnedFuture<Vec<u8>, Error>,
+        url: String,
+    ) -> OwnedFuture<(), Error> {
+        let loader = Loader::UrlLoader {
+            self_handle: None,
+            target_object,
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        loader.url_loader(player, fetch, url)
+    }
+
+    /// Cancel all loads into the AVM2 `URLLoader` `target_object`.
+    pub fn cancel_url_loads(&mut self, target_object: Avm2Object<'gc>) {
+        self.0.retain(|_, loader| {
+            !matches!(loader, Loader::UrlLoader { target_object: object, .. } if Avm2Object::ptr_eq(*object, target_object))
+        });
+    }
+
+    /// The number of loads into the AVM2 `URLLoader` `target_object`.
+    #[cfg(test)]
+    pub fn url_load_count(&self, target_object: Avm2Object<'gc>) -> usize {
+        self.0
+            .iter()
+            .filter(|(_, loader)| {
+                matches!(loader, Loader::UrlLoader { target_object: object, .. } if Avm2Object::ptr_eq(*object, target_object))
+            })
+            .count()
+    }
 }
 
 impl<'gc> Default for LoadManager<'gc> {
@@ -295,6 +489,9 @@ pub enum Loader<'gc> {
         /// into.
         target_broadcaster: Option<Object<'gc>>,
 
+        /// The `LoaderInfo` of an AVM2 `Loader` to fire events on.
+        target_loader_info: Option<Avm2Object<'gc>>,
+
         /// Indicates the completion status of this loader.
         ///
         /// This flag exists to prevent a situation in which loading a movie
@@ -304,6 +501,12 @@ pub enum Loader<'gc> {
         /// or an error has occurred (in which case we don't care about the
         /// loader anymore).
         loader_status: LoaderStatus,
+
+        /// The number of bytes of the movie that have been loaded so far.
+        bytes_loaded: usize,
+
+        /// The size of the movie in bytes, or 0 if it isn't known yet.
+        bytes_total: usize,
     },
 
     /// Loader that is loading form data into an AVM1 object scope.
@@ -343,6 +546,36 @@ pub enum Loader<'gc> {
         /// The target node whose contents will be replaced with the parsed XML.
         target_node: XmlNode<'gc>,
     },
+
+    /// Loader that is loading a media file into a `NetStream`.
+    NetStream {
+        /// The handle to refer to this loader instance.
+        #[collect(require_static)]
+        self_handle: Option<Handle>,
+
+        /// The stream that will play the loaded media file.
+        target_stream: NetStream<'gc>,
+    },
+
+    /// Loader that is loading an MP3 file into an AVM1 `Sound` object.
+    SoundAvm1 {
+        /// The handle to refer to this loader instance.
+        #[collect(require_static)]
+        self_handle: Option<Handle>,
+
+        /// The target `Sound` object that the MP3 will be attached to.
+        target_object: SoundObject<'gc>,
+    },
+
+    /// Loader that is loading data into an AVM2 `URLLoader`.
+    UrlLoader {
+        /// The handle to refer to this loader instance.
+        #[collect(require_static)]
+        self_handle: Option<Handle>,
+
+        /// The `URLLoader` that will receive the data.
+        target_object: Avm2Object<'gc>,
+    },
 }
 
 impl<'gc> Loader<'gc> {
@@ -357,6 +590,9 @@ impl<'gc> Loader<'gc> {
             Loader::Form { self_handle, .. } => *self_handle = Some(handle),
             Loader::LoadVars { self_handle, .. } => *self_handle = Some(handle),
             Loader::Xml { self_handle, .. } => *self_handle = Some(handle),
+            Loader::NetStream { self_handle, .. } => *self_handle = Some(handle),
+            Loader::SoundAvm1 { self_handle, .. } => *self_handle = Some(handle),
+            Loader::UrlLoader { self_handle, .. } => *self_handle = Some(handle),
         }
     }
 
@@ -424,6 +660,7 @@ impl<'gc> Loader<'gc> {
         &mut self,
         player: Weak<Mutex<Player>>,
         fetch: OwnedFuture<Vec<u8>, Error>,
+        progress: Option<FetchProgress>,
         mut url: String,
         loader_url: Option<String>,
     ) -> OwnedFuture<(), Error> {
@@ -445,12 +682,14 @@ impl<'gc> Loader<'gc> {
                 .update(|uc| -> Result<(), Error> {
                     url = uc.navigator.resolve_relative_url(&url).into_owned();
 
-                    let (clip, broadcaster) = match uc.load_manager.get_loader(handle) {
+                    let (clip, broadcaster, loader_info) = match uc.load_manager.get_loader(handle)
+                    {
                         Some(Loader::Movie {
                             target_clip,
                             target_broadcaster,
+                            target_loader_info,
                             ..
-                        }) => (*target_clip, *target_broadcaster),
+                        }) => (*target_clip, *target_broadcaster, *target_loader_info),
                         None => return Err(Error::Cancelled),
                         _ => unreachable!(),
                     };
@@ -470,122 +709,176 @@ impl<'gc> Loader<'gc> {
                             NEWEST_PLAYER_VERSION,
                             uc,
                             "broadcastMessage".into(),
-                            &["onLoadStart".into(), Value::Object(broadcaster)],
+                            &["onLoadStart".into(), clip.object()],
                         );
                     }
 
+                    if let Some(loader_info) = loader_info {
+                        log_avm2_error(Avm2::dispatch_event(
+                            uc,
+                            Avm2Event::new("open"),
+                            loader_info,
+                        ));
+                    }
+
                     Ok(())
                 })?;
 
-            let data = (fetch.await).and_then(|data| {
-                Ok((
-                    data.len(),
-                    SwfMovie::from_data(&data, Some(url.clone()), loader_url.clone())?,
-                ))
-            });
-            if let Ok((length, movie)) = data {
-                let movie = Arc::new(movie);
-                if replacing_root_movie {
-                    player.lock().unwrap().set_root_movie(movie);
-                    return Ok(());
-                }
+            let fetch = MovieProgressFetch {
+                fetch,
+                progress,
+                reported: 0,
+                player: player.clone(),
+                handle,
+            };
+            let data = match fetch.await {
+                Ok(data) => SwfMovie::from_data(&data, Some(url.clone()), loader_url.clone())
+                    .map(|movie| (data.len(), movie))
+                    // The file arrived, but isn't a movie.
+                    .map_err(|_| ("LoadNeverCompleted", 200)),
+                Err(e) => Err(("URLNotFound", e.http_status())),
+            };
+            match data {
+                Ok((length, movie)) => {
+                    let movie = Arc::new(movie);
+                    if replacing_root_movie {
+                        player.lock().unwrap().set_root_movie(movie);
+                        return Ok(());
+                    }
 
-                player
-                    .lock()
-                    .expect("Could not lock player!!")
-                    .update(|uc| {
-                        let (clip, broadcaster) = match uc.load_manager.get_loader(handle) {
-                            Some(Loader::Movie {
-                                target_clip,
-                                target_broadcaster,
+                    player
+                        .lock()
+                        .expect("Could not lock player!!")
+                        .update(|uc| {
+                            let (clip, broadcaster, loader_info) =
+                                match uc.load_manager.get_loader(handle) {
+                                    Some(Loader::Movie {
+                                        target_clip,
+                                        target_broadcaster,
+                                        target_loader_info,
+                                        ..
+                                    }) => (*target_clip, *target_broadcaster, *target_loader_info),
+                                    None => return Err(Error::Cancelled),
+                                    _ => unreachable!(),
+                                };
+
+                            if let Some(Loader::Movie {
+                                bytes_loaded,
+                                bytes_total,
                                 ..
-                            }) => (*target_clip, *target_broadcaster),
-                            None => return Err(Error::Cancelled),
-                            _ => unreachable!(),
-                        };
-
-                        let mut activation = Avm2Activation::from_nothing(uc.reborrow());
-                        let parent_domain = activation.avm2().global_domain();
-                        let domain = Avm2Domain::movie_domain(&mut activation, parent_domain);
-                        uc.library
-                            .library_for_movie_mut(movie.clone())
-                            .set_avm2_domain(domain);
-
-                        if let Some(broadcaster) = broadcaster {
-                            Avm1::run_stack_frame_for_method(
-                                clip,
-                                broadcaster,
-                                NEWEST_PLAYER_VERSION,
-                                uc,
-                                "broadcastMessage".into(),
-                                &[
-                                    "onLoadProgress".into(),
-                                    Value::Object(broadcaster),
-                                    length.into(),
-                                    length.into(),
-                                ],
-                            );
-                        }
-
-                        let mut mc = clip
-                            .as_movie_clip()
-                            .expect("Attempted to load movie into not movie clip");
-
-                        mc.replace_with_movie(uc.gc_context, Some(movie.clone()));
-                        mc.post_instantiation(uc, clip, None, Instantiator::Movie, false);
-
-                        let mut morph_shapes = fnv::FnvHashMap::default();
-                        mc.preload(uc, &mut morph_shapes);
-
-                        // Finalize morph shapes.
-                        for (id, static_data) in morph_shapes {
-                            let morph_shape = MorphShape::new(uc.gc_context, static_data);
+                            }) = uc.load_manager.get_loader_mut(handle)
+                            {
+                                *bytes_loaded = length;
+                                *bytes_total = length;
+                            };
+
+                            let mut activation = Avm2Activation::from_nothing(uc.reborrow());
+                            let parent_domain = activation.avm2().global_domain();
+                            let domain = Avm2Domain::movie_domain(&mut activation, parent_domain);
                             uc.library
                                 .library_for_movie_mut(movie.clone())
-                                .register_character(
-                                    id,
-                                    crate::character::Character::MorphShape(morph_shape),
+                                .set_avm2_domain(domain);
+
+                            if let Some(broadcaster) = broadcaster {
+                                Avm1::run_stack_frame_for_method(
+                                    clip,
+                                    broadcaster,
+                                    NEWEST_PLAYER_VERSION,
+                                    uc,
+                                    "broadcastMessage".into(),
+                                    &[
+                                        "onLoadProgress".into(),
+                                        clip.object(),
+                                        length.into(),
+                                        length.into(),
+                                    ],
                                 );
-                        }
-
-                        if let Some(broadcaster) = broadcaster {
-                            Avm1::run_stack_frame_for_method(
-                                clip,
-                                broadcaster,
-                                NEWEST_PLAYER_VERSION,
-                                uc,
-                                "broadcastMessage".into(),
-                                &["onLoadComplete".into(), Value::Object(broadcaster)],
-                            );
-                        }
+                            }
+
+                            let mut mc = clip
+                                .as_movie_clip()
+                                .expect("Attempted to load movie into not movie clip");
+
+                            mc.replace_with_movie(uc.gc_context, Some(movie.clone()));
+                            mc.post_instantiation(uc, clip, None, Instantiator::Movie, false);
+
+                            let mut morph_shapes = fnv::FnvHashMap::default();
+                            mc.preload(uc, &mut morph_shapes);
+
+                            // Finalize morph shapes.
+                            for (id, static_data) in morph_shapes {
+                                let morph_shape = MorphShape::new(uc.gc_context, static_data);
+                                uc.library
+                                    .library_for_movie_mut(movie.clone())
+                                    .register_character(
+                                        id,
+                                        crate::character::Character::MorphShape(morph_shape),
+                                    );
+                            }
+
+                            if let Some(loader_info) = loader_info {
+                                if let Some(loader_info_object) =
+                                    loader_info.as_loader_info_object()
+                                {
+                                    loader_info_object.set_loader_stream(
+                                        uc.gc_context,
+                                        Some(LoaderStream::Swf(movie.clone(), clip)),
+                                    );
+                                }
+
+                                log_avm2_error(Avm2::dispatch_progress_event(
+                                    uc,
+                                    loader_info,
+                                    length,
+                                    length,
+                                ));
+                                log_avm2_error(Avm2::dispatch_event(
+                                    uc,
+                                    Avm2Event::new("init"),
+                                    loader_info,
+                                ));
+                                log_avm2_error(Avm2::dispatch_event(
+                                    uc,
+                                    Avm2Event::new("complete"),
+                                    loader_info,
+                                ));
+                            }
+
+                            if let Some(broadcaster) = broadcaster {
+                                Avm1::run_stack_frame_for_method(
+                                    clip,
+                                    broadcaster,
+                                    NEWEST_PLAYER_VERSION,
+                                    uc,
+                                    "broadcastMessage".into(),
+                                    &["onLoadComplete".into(), clip.object(), 200.into()],
+                                );
+                            }
 
-                        if let Some(Loader::Movie { loader_status, .. }) =
-                            uc.load_manager.get_loader_mut(handle)
-                        {
-                            *loader_status = LoaderStatus::Succeeded;
-                        };
+                            if let Some(Loader::Movie { loader_status, .. }) =
+                                uc.load_manager.get_loader_mut(handle)
+                            {
+                                *loader_status = LoaderStatus::Succeeded;
+                            };
 
-                        Ok(())
-                    })
-            } else {
-                //TODO: Inspect the fetch error.
-                //This requires cooperation from the backend to send abstract
-                //error types we can actually inspect.
-                //This also can get errors from decoding an invalid SWF file,
-                //too. We should distinguish those to player code.
-                player
+                            Ok(())
+                        })
+                }
+                Err((error_code, http_status)) => player
                     .lock()
                     .expect("Could not lock player!!")
                     .update(|uc| -> Result<(), Error> {
-                        let (clip, broadcaster) = match uc.load_manager.get_loader(handle) {
-                            Some(Loader::Movie {
-                                target_clip,
-                                target_broadcaster,
-                                ..
-                            }) => (*target_clip, *target_broadcaster),
-                            None => return Err(Error::Cancelled),
-                            _ => unreachable!(),
-                        };
+                        let (clip, broadcaster, loader_info) =
+                            match uc.load_manager.get_loader(handle) {
+                                Some(Loader::Movie {
+                                    target_clip,
+                                    target_broadcaster,
+                                    target_loader_info,
+                                    ..
+                                }) => (*target_clip, *target_broadcaster, *target_loader_info),
+                                None => return Err(Error::Cancelled),
+                                _ => unreachable!(),
+                            };
 
                         if let Some(broadcaster) = broadcaster {
                             Avm1::run_stack_frame_for_method(
@@ -596,12 +889,33 @@ impl<'gc> Loader<'gc> {
                                 "broadcastMessage".into(),
                                 &[
                                     "onLoadError".into(),
-                                    Value::Object(broadcaster),
-                                    "LoadNeverCompleted".into(),
+                                    clip.object(),
+                                    error_code.into(),
+                                    http_status.into(),
                                 ],
                             );
                         }
 
+                        if let Some(loader_info) = loader_info {
+                            let (error_id, message) = if error_code == "URLNotFound" {
+                                (2035, "URL Not Found.")
+                            } else {
+                                (2124, "Loaded file is an unknown type.")
+                            };
+
+                            log_avm2_error(Avm2::dispatch_http_status_event(
+                                uc,
+                                loader_info,
+                                http_status,
+                            ));
+                            log_avm2_error(Avm2::dispatch_io_error_event(
+                                uc,
+                                loader_info,
+                                error_id,
+                                &format!("{} URL: {}", message, url),
+                            ));
+                        }
+
                         if let Some(Loader::Movie { loader_status, .. }) =
                             uc.load_manager.get_loader_mut(handle)
                         {
@@ -609,7 +923,7 @@ impl<'gc> Loader<'gc> {
                         };
 
                         Ok(())
-                    })
+                    }),
             }
         })
     }
@@ -692,6 +1006,13 @@ impl<'gc> Loader<'gc> {
 
                 match data {
                     Ok(data) => {
+                        let length = data.len();
+                        let _ = that.set("_bytesLoaded", length.into(), &mut activation);
+                        let _ = that.set("_bytesTotal", length.into(), &mut activation);
+
+                        let _ =
+                            that.call_method("onHTTPStatus".into(), &[200.into()], &mut activation);
+
                         // Fire the onData method with the loaded string.
                         let string_data = AvmString::new_utf8(
                             activation.context.gc_context,
@@ -703,12 +1024,13 @@ impl<'gc> Loader<'gc> {
                             &mut activation,
                         );
                     }
-                    Err(_) => {
+                    Err(e) => {
                         // TODO: Log "Error opening URL" trace similar to the Flash Player?
-                        // Simulate 404 HTTP status. This should probably be fired elsewhere
-                        // because a failed local load doesn't fire a 404.
-                        let _ =
-                            that.call_method("onHTTPStatus".into(), &[404.into()], &mut activation);
+                        let _ = that.call_method(
+                            "onHTTPStatus".into(),
+                            &[e.http_status().into()],
+                            &mut activation,
+                        );
 
                         // Fire the onData method with no data to indicate an unsuccessful load.
                         let _ =
@@ -784,85 +1106,399 @@ impl<'gc> Loader<'gc> {
             .expect("Could not upgrade weak reference to player");
 
         Box::pin(async move {
-            let data = fetch.await;
-            if let Ok(data) = data {
-                let xmlstring = String::from_utf8(data)?;
-
-                player.lock().expect("Could not lock player!!").update(
-                    |uc| -> Result<(), Error> {
-                        let (mut node, active_clip) = match uc.load_manager.get_loader(handle) {
-                            Some(Loader::Xml {
-                                target_node,
+            match fetch.await {
+                Ok(data) => {
+                    let length = data.len();
+                    let xmlstring = String::from_utf8(data)?;
+
+                    player.lock().expect("Could not lock player!!").update(
+                        |uc| -> Result<(), Error> {
+                            let (mut node, active_clip) = match uc.load_manager.get_loader(handle) {
+                                Some(Loader::Xml {
+                                    target_node,
+                                    active_clip,
+                                    ..
+                                }) => (*target_node, *active_clip),
+                                None => return Err(Error::Cancelled),
+                                _ => unreachable!(),
+                            };
+
+                            let object = node
+                                .script_object(uc.gc_context, Some(uc.avm1.prototypes().xml_node));
+
+                            {
+                                let mut activation = Activation::from_stub(
+                                    uc.reborrow(),
+                                    ActivationIdentifier::root("[XML Loader]"),
+                                );
+                                let _ = object.set("_bytesLoaded", length.into(), &mut activation);
+                                let _ = object.set("_bytesTotal", length.into(), &mut activation);
+                            }
+
+                            Avm1::run_stack_frame_for_method(
                                 active_clip,
-                                ..
-                            }) => (*target_node, *active_clip),
-                            None => return Err(Error::Cancelled),
-                            _ => unreachable!(),
-                        };
+                                object,
+                                NEWEST_PLAYER_VERSION,
+                                uc,
+                                "onHTTPStatus".into(),
+                                &[200.into()],
+                            );
 
-                        let object =
-                            node.script_object(uc.gc_context, Some(uc.avm1.prototypes().xml_node));
-                        Avm1::run_stack_frame_for_method(
-                            active_clip,
-                            object,
-                            NEWEST_PLAYER_VERSION,
-                            uc,
-                            "onHTTPStatus".into(),
-                            &[200.into()],
-                        );
+                            Avm1::run_stack_frame_for_method(
+                                active_clip,
+                                object,
+                                NEWEST_PLAYER_VERSION,
+                                uc,
+                                "onData".into(),
+                                &[AvmString::new_utf8(uc.gc_context, xmlstring).into()],
+                            );
 
-                        Avm1::run_stack_frame_for_method(
-                            active_clip,
-                            object,
-                            NEWEST_PLAYER_VERSION,
-                            uc,
-                            "onData".into(),
-                            &[AvmString::new_utf8(uc.gc_context, xmlstring).into()],
-                        );
+                            Ok(())
+                        },
+                    )?;
+                }
+                Err(e) => {
+                    let status = e.http_status();
+                    player.lock().expect("Could not lock player!!").update(
+                        |uc| -> Result<(), Error> {
+                            let (mut node, active_clip) = match uc.load_manager.get_loader(handle) {
+                                Some(Loader::Xml {
+                                    target_node,
+                                    active_clip,
+                                    ..
+                                }) => (*target_node, *active_clip),
+                                None => return Err(Error::Cancelled),
+                                _ => unreachable!(),
+                            };
+
+                            let object = node
+                                .script_object(uc.gc_context, Some(uc.avm1.prototypes().xml_node));
 
-                        Ok(())
-                    },
-                )?;
-            } else {
-                player.lock().expect("Could not lock player!!").update(
-                    |uc| -> Result<(), Error> {
-                        let (mut node, active_clip) = match uc.load_manager.get_loader(handle) {
-                            Some(Loader::Xml {
-                                target_node,
+                            Avm1::run_stack_frame_for_method(
                                 active_clip,
-                                ..
-                            }) => (*target_node, *active_clip),
-                            None => return Err(Error::Cancelled),
-                            _ => unreachable!(),
-                        };
+                                object,
+                                NEWEST_PLAYER_VERSION,
+                                uc,
+                                "onHTTPStatus".into(),
+                                &[status.into()],
+                            );
 
-                        let object =
-                            node.script_object(uc.gc_context, Some(uc.avm1.prototypes().xml_node));
+                            Avm1::run_stack_frame_for_method(
+                                active_clip,
+                                object,
+                                NEWEST_PLAYER_VERSION,
+                                uc,
+                                "onData".into(),
+                                &[],
+                            );
 
-                        Avm1::run_stack_frame_for_method(
-                            active_clip,
-                            object,
-                            NEWEST_PLAYER_VERSION,
-                            uc,
-                            "onHTTPStatus".into(),
-                            &[404.into()],
-                        );
+                            Ok(())
+                        },
+                    )?;
+                }
+            }
 
-                        Avm1::run_stack_frame_for_method(
-                            active_clip,
-                            object,
-                            NEWEST_PLAYER_VERSION,
-                            uc,
-                            "onData".into(),
-                            &[],
+            Ok(())
+        })
+    }
+
+    /// Construct a future for the given `NetStream` loader.
+    pub fn net_stream_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        fetch: OwnedFuture<Vec<u8>, Error>,
+    ) -> OwnedFuture<(), Error> {
+        let handle = match self {
+            Loader::NetStream { self_handle, .. } => {
+                self_handle.expect("Loader not self-introduced")
+            }
+            _ => return Box::pin(async { Err(Error::NotNetStreamLoader) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        Box::pin(async move {
+            let data = fetch.await;
+
+            player
+                .lock()
+                .expect("Could not lock player!!")
+                .update(|uc| -> Result<(), Error> {
+                    let stream = match uc.load_manager.get_loader(handle) {
+                        Some(Loader::NetStream { target_stream, .. }) => *target_stream,
+                        None => return Err(Error::Cancelled),
+                        _ => unreachable!(),
+                    };
+
+                    match data {
+                        Ok(data) => stream.load_buffer(uc, &data)?,
+                        Err(e) => {
+                            stream.load_failed(uc);
+                            return Err(e);
+                        }
+                    }
+
+                    Ok(())
+                })
+        })
+    }
+
+    /// Construct a future for the given AVM1 `Sound` loader.
+    ///
+    /// Sounds are always fully downloaded before they can play. If
+    /// `play_on_load` is set, as it is for streaming sounds, the sound starts
+    /// playing as soon as the load completes; otherwise it waits for
+    /// `Sound.start`.
+    pub fn sound_loader_avm1(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        fetch: OwnedFuture<Vec<u8>, Error>,
+        play_on_load: bool,
+    ) -> OwnedFuture<(), Error> {
+        let handle = match self {
+            Loader::SoundAvm1 { self_handle, .. } => {
+                self_handle.expect("Loader not self-introduced")
+            }
+            _ => return Box::pin(async { Err(Error::NotSoundLoader) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        Box::pin(async move {
+            let data = fetch.await;
+
+            player.lock().unwrap().update(|uc| {
+                let sound_object = match uc.load_manager.get_loader(handle) {
+                    Some(&Loader::SoundAvm1 { target_object, .. }) => target_object,
+                    None => return Err(Error::Cancelled),
+                    _ => return Err(Error::NotSoundLoader),
+                };
+
+                let sound = match data {
+                    Ok(data) => uc.audio.register_mp3(&data).map_err(|e| {
+                        log::error!("Sound.loadSound: Invalid MP3 file: {}", e);
+                    }),
+                    Err(e) => {
+                        log::error!("Sound.loadSound: Could not fetch MP3: {}", e);
+                        Err(())
+                    }
+                };
+
+                if let Ok(sound) = sound {
+                    sound_object.set_sound(uc.gc_context, Some(sound));
+                    sound_object.set_duration(
+                        uc.gc_context,
+                        uc.audio.get_sound_duration(sound).map(|d| d.round() as u32),
+                    );
+                    sound_object.set_position(uc.gc_context, 0);
+
+                    if play_on_load {
+                        let sound_instance = uc.start_sound(
+                            sound,
+                            &swf::SoundInfo {
+                                event: swf::SoundEvent::Start,
+                                in_sample: None,
+                                out_sample: None,
+                                num_loops: 1,
+                                envelope: None,
+                            },
+                            sound_object.owner(),
+                            Some(sound_object),
                         );
+                        sound_object.set_sound_instance(uc.gc_context, sound_instance);
+                    }
+                }
 
-                        Ok(())
-                    },
-                )?;
+                let mut activation =
+                    Activation::from_stub(uc.reborrow(), ActivationIdentifier::root("[Loader]"));
+                let _ = Object::from(sound_object).call_method(
+                    "onLoad".into(),
+                    &[sound.is_ok().into()],
+                    &mut activation,
+                );
+
+                Ok(())
+            })
+        })
+    }
+
+    /// Construct a future for the given AVM2 `URLLoader` loader.
+    ///
+    /// The response is handed to the `URLLoader` in whatever format its
+    /// `dataFormat` asks for, followed by the usual progress and completion
+    /// events. Failed loads fire `httpStatus` and `ioError` instead.
+    pub fn url_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        fetch: OwnedFuture<Vec<u8>, Error>,
+        url: String,
+    ) -> OwnedFuture<(), Error> {
+        let handle = match self {
+            Loader::UrlLoader { self_handle, .. } => {
+                self_handle.expect("Loader not self-introduced")
             }
+            _ => return Box::pin(async { Err(Error::NotUrlLoader) }),
+        };
 
-            Ok(())
+        Box::pin(async move {
+            let player = player
+                .upgrade()
+                .expect("Could not upgrade weak reference to player");
+
+            player.lock().unwrap().update(|uc| {
+                let target_object = match uc.load_manager.get_loader(handle) {
+                    Some(&Loader::UrlLoader { target_object, .. }) => target_object,
+                    None => return Err(Error::Cancelled),
+                    _ => return Err(Error::NotUrlLoader),
+                };
+
+                log_avm2_error(Avm2::dispatch_event(
+                    uc,
+                    Avm2Event::new("open"),
+                    target_object,
+                ));
+
+                Ok(())
+            })?;
+
+            let data = fetch.await;
+
+            player.lock().unwrap().update(|uc| {
+                let target_object = match uc.load_manager.get_loader(handle) {
+                    Some(&Loader::UrlLoader { target_object, .. }) => target_object,
+                    None => return Err(Error::Cancelled),
+                    _ => return Err(Error::NotUrlLoader),
+                };
+
+                match data {
+                    Ok(data) => {
+                        let length = data.len();
+
+                        log_avm2_error(Avm2::set_url_loader_data(uc, target_object, data));
+                        log_avm2_error(Avm2::dispatch_progress_event(
+                            uc,
+                            target_object,
+                            length,
+                            length,
+                        ));
+                        log_avm2_error(Avm2::dispatch_http_status_event(uc, target_object, 200));
+                        log_avm2_error(Avm2::dispatch_event(
+                            uc,
+                            Avm2Event::new("complete"),
+                            target_object,
+                        ));
+                    }
+                    Err(e) => {
+                        log_avm2_error(Avm2::dispatch_http_status_event(
+                            uc,
+                            target_object,
+                            e.http_status(),
+                        ));
+                        log_avm2_error(Avm2::dispatch_io_error_event(
+                            uc,
+                            target_object,
+                            2032,
+                            &format!("Stream Error. URL: {}", url),
+                        ));
+                    }
+                }
+
+                Ok(())
+            })
         })
     }
 }
+
+/// A movie fetch that fires progress events on its loader whenever the
+/// navigator reports that more of the movie has arrived.
+struct MovieProgressFetch {
+    fetch: OwnedFuture<Vec<u8>, Error>,
+    progress: Option<FetchProgress>,
+
+    /// The number of bytes we last fired a progress event for.
+    reported: usize,
+
+    player: Arc<Mutex<Player>>,
+    handle: Handle,
+}
+
+impl Future for MovieProgressFetch {
+    type Output = Result<Vec<u8>, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let result = this.fetch.as_mut().poll(cx);
+
+        // Once the fetch is done, the loader reports the whole movie itself.
+        if result.is_pending() {
+            if let Some(progress) = &this.progress {
+                let loaded = progress.loaded();
+                if loaded > this.reported {
+                    this.reported = loaded;
+                    let total = progress.total().unwrap_or(0);
+                    let handle = this.handle;
+                    this.player
+                        .lock()
+                        .expect("Could not lock player!!")
+                        .update(|uc| report_movie_progress(uc, handle, loaded, total));
+                }
+            }
+        }
+
+        result
+    }
+}
+
+/// Record the progress of a movie load, and tell its broadcaster or
+/// `LoaderInfo` about it.
+fn report_movie_progress<'gc>(
+    uc: &mut UpdateContext<'_, 'gc, '_>,
+    handle: Handle,
+    loaded: usize,
+    total: usize,
+) {
+    let (clip, broadcaster, loader_info) = match uc.load_manager.get_loader_mut(handle) {
+        Some(Loader::Movie {
+            target_clip,
+            target_broadcaster,
+            target_loader_info,
+            bytes_loaded,
+            bytes_total,
+            ..
+        }) => {
+            *bytes_loaded = loaded;
+            *bytes_total = total;
+            (*target_clip, *target_broadcaster, *target_loader_info)
+        }
+        _ => return,
+    };
+
+    if let Some(broadcaster) = broadcaster {
+        Avm1::run_stack_frame_for_method(
+            clip,
+            broadcaster,
+            NEWEST_PLAYER_VERSION,
+            uc,
+            "broadcastMessage".into(),
+            &[
+                "onLoadProgress".into(),
+                clip.object(),
+                loaded.into(),
+                total.into(),
+            ],
+        );
+    }
+
+    if let Some(loader_info) = loader_info {
+        log_avm2_error(Avm2::dispatch_progress_event(
+            uc,
+            loader_info,
+            loaded,
+            total,
+        ));
+    }
+}
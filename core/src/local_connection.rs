@@ -0,0 +1,245 @@
+//! Message bus for `LocalConnection`s between movies running in this player.
+//!
+//! Messages are queued when they are sent, and delivered on the next tick, as
+//! in Flash Player. Arguments are carried as AMF values, so that they don't
+//! depend on which VM sent them.
+
+use crate::avm1::activation::{Activation, ActivationIdentifier};
+use crate::avm1::globals::shared_object::deserialize_value;
+use crate::avm1::{Object as Avm1Object, ScriptObject, TObject, Value as Avm1Value};
+use crate::context::UpdateContext;
+use crate::string::AvmString;
+use crate::tag_utils::SwfMovie;
+use flash_lso::types::Value as AmfValue;
+use gc_arena::Collect;
+
+/// An object that sends or receives `LocalConnection` messages.
+#[derive(Clone, Copy, Collect)]
+#[collect(no_drop)]
+pub enum LocalConnectionObject<'gc> {
+    Avm1(Avm1Object<'gc>),
+}
+
+impl<'gc> LocalConnectionObject<'gc> {
+    fn ptr_eq(self, other: Self) -> bool {
+        match (self, other) {
+            (Self::Avm1(a), Self::Avm1(b)) => Avm1Object::ptr_eq(a, b),
+        }
+    }
+}
+
+/// A connection name claimed by a receiving object.
+#[derive(Collect)]
+#[collect(no_drop)]
+struct Connection<'gc> {
+    /// The fully qualified, lowercased name of the connection.
+    name: String,
+
+    /// The domain of the movie that opened the connection.
+    domain: String,
+
+    receiver: LocalConnectionObject<'gc>,
+}
+
+/// A message waiting to be delivered.
+#[derive(Collect)]
+#[collect(no_drop)]
+struct Message<'gc> {
+    sender: LocalConnectionObject<'gc>,
+
+    /// The domain of the movie that sent the message.
+    sender_domain: String,
+
+    /// The fully qualified, lowercased name of the target connection.
+    connection_name: String,
+
+    method_name: String,
+
+    #[collect(require_static)]
+    args: Vec<AmfValue>,
+}
+
+/// All open `LocalConnection`s, and the messages sent between them.
+#[derive(Collect, Default)]
+#[collect(no_drop)]
+pub struct LocalConnections<'gc> {
+    connections: Vec<Connection<'gc>>,
+    messages: Vec<Message<'gc>>,
+}
+
+impl<'gc> LocalConnections<'gc> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start receiving messages sent to `name`.
+    ///
+    /// Returns `false` if the name is already in use, or `receiver` is
+    /// already connected.
+    pub fn connect(
+        &mut self,
+        domain: &str,
+        name: &str,
+        receiver: LocalConnectionObject<'gc>,
+    ) -> bool {
+        let name = qualified_name(domain, name);
+        if self
+            .connections
+            .iter()
+            .any(|connection| connection.name == name || connection.receiver.ptr_eq(receiver))
+        {
+            return false;
+        }
+
+        self.connections.push(Connection {
+            name,
+            domain: domain.to_string(),
+            receiver,
+        });
+        true
+    }
+
+    /// Stop receiving messages. Returns `false` if `receiver` wasn't connected.
+    pub fn close(&mut self, receiver: LocalConnectionObject<'gc>) -> bool {
+        let len = self.connections.len();
+        self.connections
+            .retain(|connection| !connection.receiver.ptr_eq(receiver));
+        self.connections.len() != len
+    }
+
+    /// Queue a call of `method_name` on the receiver of `connection_name`.
+    pub fn send(
+        &mut self,
+        sender: LocalConnectionObject<'gc>,
+        sender_domain: &str,
+        connection_name: &str,
+        method_name: &str,
+        args: Vec<AmfValue>,
+    ) {
+        self.messages.push(Message {
+            sender,
+            sender_domain: sender_domain.to_string(),
+            connection_name: qualified_name(sender_domain, connection_name),
+            method_name: method_name.to_string(),
+            args,
+        });
+    }
+
+    /// Deliver all queued messages, and report the outcome to their senders.
+    pub fn update(context: &mut UpdateContext<'_, 'gc, '_>) {
+        if context.local_connections.messages.is_empty() {
+            return;
+        }
+
+        let messages = std::mem::take(&mut context.local_connections.messages);
+        let version = context.swf.version();
+        let globals = context.avm1.global_object_cell();
+        let level0 = context.stage.root_clip();
+        let mut activation = Activation::from_nothing(
+            context.reborrow(),
+            ActivationIdentifier::root("[LocalConnection]"),
+            version,
+            globals,
+            level0,
+        );
+
+        for message in messages {
+            let connection = activation
+                .context
+                .local_connections
+                .connections
+                .iter()
+                .find(|connection| connection.name == message.connection_name)
+                .map(|connection| (connection.receiver, connection.domain.clone()));
+
+            let delivered = match connection {
+                Some((LocalConnectionObject::Avm1(receiver), domain)) => {
+                    deliver_avm1(&mut activation, receiver, &domain, &message)
+                }
+                None => false,
+            };
+
+            match message.sender {
+                LocalConnectionObject::Avm1(sender) => {
+                    let info = ScriptObject::object(
+                        activation.context.gc_context,
+                        Some(activation.context.avm1.prototypes().object),
+                    );
+                    let level = if delivered { "status" } else { "error" };
+                    let _ = info.set("level", level.into(), &mut activation);
+                    let _ = sender.call_method("onStatus".into(), &[info.into()], &mut activation);
+                }
+            }
+
+            crate::player::Player::run_actions(&mut activation.context);
+        }
+    }
+}
+
+/// Call the method of `message` on an AVM1 receiver.
+///
+/// Returns `false` if the receiver doesn't accept messages from the sender's domain.
+fn deliver_avm1<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    receiver: Avm1Object<'gc>,
+    domain: &str,
+    message: &Message<'gc>,
+) -> bool {
+    // Other domains have to be allowed explicitly by the receiver.
+    if message.sender_domain != domain {
+        let sender_domain =
+            AvmString::new_utf8(activation.context.gc_context, &message.sender_domain);
+        let allowed = receiver
+            .call_method("allowDomain".into(), &[sender_domain.into()], activation)
+            .map(|allowed| allowed.as_bool(activation.swf_version()))
+            .unwrap_or(false);
+        if !allowed {
+            return false;
+        }
+    }
+
+    let args: Vec<Avm1Value<'gc>> = message
+        .args
+        .iter()
+        .map(|arg| deserialize_value(activation, arg))
+        .collect();
+    let method_name = AvmString::new_utf8(activation.context.gc_context, &message.method_name);
+    let _ = receiver.call_method(method_name, &args, activation);
+    true
+}
+
+/// The domain that `LocalConnection` names are qualified with, for the given movie.
+pub fn movie_domain(movie: &SwfMovie) -> String {
+    movie
+        .url()
+        .and_then(|url| url::Url::parse(url).ok())
+        .filter(|url| url.scheme() != "file")
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| "localhost".to_string())
+}
+
+/// Qualifies a connection name with a domain, unless it starts with an
+/// underscore or already has one.
+fn qualified_name(domain: &str, name: &str) -> String {
+    let name = if name.starts_with('_') || name.contains(':') {
+        name.to_string()
+    } else {
+        format!("{}:{}", domain, name)
+    };
+    name.to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn qualify_names() {
+        assert_eq!(qualified_name("example.com", "Game"), "example.com:game");
+        assert_eq!(qualified_name("example.com", "_Game"), "_game");
+        assert_eq!(
+            qualified_name("example.com", "other.com:game"),
+            "other.com:game"
+        );
+    }
+}
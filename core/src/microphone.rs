@@ -0,0 +1,364 @@
+//! Capture of audio input, as done by `Microphone`.
+
+use crate::avm1::property::Attribute;
+use crate::avm1::{Object as Avm1Object, ScriptObject, TObject};
+use crate::avm2::{Avm2, Object as Avm2Object};
+use crate::context::{ActionType, UpdateContext};
+use gc_arena::Collect;
+
+/// The capture rates supported by `Microphone.rate`, in kHz.
+const SUPPORTED_RATES: [u32; 5] = [5, 8, 11, 22, 44];
+
+/// Whether the user allowed the movie to use their microphone.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Access {
+    /// The movie hasn't asked for the microphone yet.
+    NotRequested,
+
+    /// The user will be asked on the next update.
+    Pending,
+
+    Allowed,
+
+    Denied,
+}
+
+/// The state of the user's microphone.
+///
+/// There is only ever one microphone, which is shared by the AVM1 and AVM2
+/// `Microphone` objects.
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct Microphone<'gc> {
+    /// The AVM1 object that receives `onActivity` calls.
+    avm1_object: Option<Avm1Object<'gc>>,
+
+    /// The AVM2 object that `activity` events are dispatched on.
+    avm2_object: Option<Avm2Object<'gc>>,
+
+    /// Whether the user allowed the movie to use the microphone.
+    #[collect(require_static)]
+    access: Access,
+
+    /// Whether audio is being captured.
+    capturing: bool,
+
+    /// Whether the microphone is unavailable to the movie.
+    ///
+    /// This stays set until the user allows access and the microphone
+    /// was opened successfully.
+    muted: bool,
+
+    /// The amplification of the input, from 0 to 100.
+    ///
+    /// The input is unchanged at 50.
+    gain: f64,
+
+    /// The capture rate in kHz.
+    rate: u32,
+
+    /// The activity level that the input has to reach to count as activity.
+    silence_level: f64,
+
+    /// How many milliseconds the input must stay below `silence_level` before
+    /// the microphone becomes inactive.
+    silence_timeout: i32,
+
+    use_echo_suppression: bool,
+
+    /// How loud the input currently is, from 0 to 100, or -1 if nothing has
+    /// been captured yet.
+    activity_level: f64,
+
+    /// Whether the input has been above `silence_level` recently.
+    is_active: bool,
+
+    /// How many milliseconds the input has been below `silence_level`.
+    silent_time: f64,
+}
+
+impl<'gc> Default for Microphone<'gc> {
+    fn default() -> Self {
+        Self {
+            avm1_object: None,
+            avm2_object: None,
+            access: Access::NotRequested,
+            capturing: false,
+            muted: true,
+            gain: 50.0,
+            rate: 8,
+            silence_level: 10.0,
+            silence_timeout: 2000,
+            use_echo_suppression: false,
+            activity_level: -1.0,
+            is_active: false,
+            silent_time: 0.0,
+        }
+    }
+}
+
+impl<'gc> Microphone<'gc> {
+    /// The name of the one microphone that is exposed to content.
+    pub const NAME: &'static str = "Default";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn avm1_object(&self) -> Option<Avm1Object<'gc>> {
+        self.avm1_object
+    }
+
+    pub fn set_avm1_object(&mut self, object: Avm1Object<'gc>) {
+        self.avm1_object = Some(object);
+    }
+
+    pub fn avm2_object(&self) -> Option<Avm2Object<'gc>> {
+        self.avm2_object
+    }
+
+    pub fn set_avm2_object(&mut self, object: Avm2Object<'gc>) {
+        self.avm2_object = Some(object);
+    }
+
+    pub fn muted(&self) -> bool {
+        self.muted
+    }
+
+    pub fn gain(&self) -> f64 {
+        self.gain
+    }
+
+    pub fn set_gain(&mut self, gain: f64) {
+        self.gain = gain.clamp(0.0, 100.0);
+    }
+
+    pub fn rate(&self) -> u32 {
+        self.rate
+    }
+
+    pub fn silence_level(&self) -> f64 {
+        self.silence_level
+    }
+
+    pub fn silence_timeout(&self) -> i32 {
+        self.silence_timeout
+    }
+
+    pub fn set_silence_level(&mut self, silence_level: f64, silence_timeout: Option<i32>) {
+        self.silence_level = silence_level.clamp(0.0, 100.0);
+        if let Some(silence_timeout) = silence_timeout {
+            self.silence_timeout = silence_timeout.max(0);
+        }
+    }
+
+    pub fn use_echo_suppression(&self) -> bool {
+        self.use_echo_suppression
+    }
+
+    pub fn set_use_echo_suppression(&mut self, use_echo_suppression: bool) {
+        self.use_echo_suppression = use_echo_suppression;
+    }
+
+    pub fn activity_level(&self) -> f64 {
+        self.activity_level
+    }
+
+    /// Ask the user for access to the microphone, if this hasn't been done already.
+    ///
+    /// As in Flash, the microphone stays muted until the user answers, which
+    /// happens on the next update. The answer is reported with a `status`
+    /// event.
+    pub fn start(context: &mut UpdateContext<'_, 'gc, '_>) {
+        if context.microphone.access == Access::NotRequested {
+            context.microphone.access = Access::Pending;
+        }
+    }
+
+    /// Change the capture rate, in kHz.
+    ///
+    /// Unsupported rates are rounded up to the next supported one.
+    pub fn set_rate(context: &mut UpdateContext<'_, 'gc, '_>, rate: u32) {
+        let rate = SUPPORTED_RATES
+            .iter()
+            .copied()
+            .find(|supported| *supported >= rate)
+            .unwrap_or(44);
+
+        if context.microphone.rate != rate {
+            context.microphone.rate = rate;
+            if context.microphone.capturing {
+                Self::start_capture(context);
+            }
+        }
+    }
+
+    fn start_capture(context: &mut UpdateContext<'_, 'gc, '_>) {
+        let sample_rate = match context.microphone.rate {
+            5 => 5512,
+            11 => 11025,
+            22 => 22050,
+            44 => 44100,
+            rate => rate * 1000,
+        };
+
+        let microphone = &mut *context.microphone;
+        match context.audio.start_capture(sample_rate) {
+            Ok(()) => {
+                microphone.capturing = true;
+                microphone.muted = false;
+                if microphone.activity_level < 0.0 {
+                    microphone.activity_level = 0.0;
+                }
+            }
+            Err(e) => {
+                log::warn!("Unable to capture audio from the microphone: {}", e);
+                microphone.capturing = false;
+                microphone.muted = true;
+            }
+        }
+    }
+
+    /// Ask the user for access to the microphone, start capturing if it was
+    /// granted, and tell the `Microphone` objects whether it's muted.
+    fn request_access(context: &mut UpdateContext<'_, 'gc, '_>) {
+        if context.ui.request_microphone_access() {
+            context.microphone.access = Access::Allowed;
+            Self::start_capture(context);
+        } else {
+            context.microphone.access = Access::Denied;
+        }
+
+        let code = if context.microphone.muted {
+            "Microphone.Muted"
+        } else {
+            "Microphone.Unmuted"
+        };
+
+        if let Some(object) = context.microphone.avm1_object {
+            let info =
+                ScriptObject::object(context.gc_context, Some(context.avm1.prototypes().object));
+            info.define_value(context.gc_context, "code", code.into(), Attribute::empty());
+            info.define_value(
+                context.gc_context,
+                "level",
+                "status".into(),
+                Attribute::empty(),
+            );
+            context.action_queue.queue_actions(
+                context.stage.root_clip(),
+                ActionType::Method {
+                    object,
+                    name: "onStatus",
+                    args: vec![info.into()],
+                },
+                false,
+            );
+        }
+
+        if let Some(object) = context.microphone.avm2_object {
+            if let Err(e) = Avm2::dispatch_status_event(context, object, code, "status") {
+                log::error!("Unhandled AVM2 exception in status handler: {}", e);
+            }
+        }
+    }
+
+    /// Meter the audio captured in the last `dt` milliseconds, and notify
+    /// the `Microphone` objects when activity starts or stops.
+    pub fn update(context: &mut UpdateContext<'_, 'gc, '_>, dt: f64) {
+        if context.microphone.access == Access::Pending {
+            Self::request_access(context);
+        }
+
+        if !context.microphone.capturing {
+            return;
+        }
+
+        let samples = context.audio.take_captured_samples();
+        let activating = match context.microphone.process_samples(&samples, dt) {
+            Some(activating) => activating,
+            None => return,
+        };
+
+        if let Some(object) = context.microphone.avm1_object {
+            context.action_queue.queue_actions(
+                context.stage.root_clip(),
+                ActionType::Method {
+                    object,
+                    name: "onActivity",
+                    args: vec![activating.into()],
+                },
+                false,
+            );
+        }
+
+        if let Some(object) = context.microphone.avm2_object {
+            if let Err(e) = Avm2::dispatch_activity_event(context, object, activating) {
+                log::error!("Unhandled AVM2 exception in activity handler: {}", e);
+            }
+        }
+    }
+
+    /// Update the activity level with newly captured samples.
+    ///
+    /// Returns whether the microphone has just become active or inactive.
+    fn process_samples(&mut self, samples: &[i16], dt: f64) -> Option<bool> {
+        if !samples.is_empty() {
+            let peak = samples
+                .iter()
+                .map(|sample| i32::from(*sample).abs())
+                .max()
+                .unwrap_or_default();
+            let level = f64::from(peak) / 32768.0 * 100.0 * (self.gain / 50.0);
+            self.activity_level = level.min(100.0);
+        }
+
+        if self.activity_level >= self.silence_level {
+            self.silent_time = 0.0;
+            if !self.is_active {
+                self.is_active = true;
+                return Some(true);
+            }
+        } else {
+            self.silent_time += dt;
+            if self.is_active && self.silent_time >= f64::from(self.silence_timeout) {
+                self.is_active = false;
+                return Some(false);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_muted() {
+        let microphone = Microphone::new();
+        assert!(microphone.muted());
+        assert_eq!(microphone.access, Access::NotRequested);
+        assert_eq!(microphone.activity_level(), -1.0);
+    }
+
+    #[test]
+    fn activity() {
+        let mut microphone = Microphone::new();
+        microphone.set_silence_level(10.0, Some(100));
+
+        assert_eq!(microphone.process_samples(&[0, 100, -200], 50.0), None);
+        assert_eq!(
+            microphone.process_samples(&[0, 16384, -200], 50.0),
+            Some(true)
+        );
+        assert_eq!(microphone.activity_level(), 50.0);
+        assert_eq!(microphone.process_samples(&[0, 100, -200], 50.0), None);
+        assert_eq!(microphone.process_samples(&[], 50.0), Some(false));
+
+        microphone.set_gain(100.0);
+        assert_eq!(microphone.process_samples(&[-16384], 50.0), Some(true));
+        assert_eq!(microphone.activity_level(), 100.0);
+    }
+}
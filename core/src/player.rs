@@ -7,10 +7,12 @@ use crate::avm1::{Avm1, ScriptObject, TObject, Timers, Value};
 use crate::avm2::{Activation as Avm2Activation, Avm2, Domain as Avm2Domain};
 use crate::backend::{
     audio::{AudioBackend, AudioManager},
+    capabilities::{CapabilitiesOverrides, CapabilitiesProvider, NullCapabilitiesProvider},
+    font::{FontBackend, NullFontBackend},
     locale::LocaleBackend,
     log::LogBackend,
     navigator::{NavigatorBackend, RequestOptions},
-    render::RenderBackend,
+    render::{Bitmap, RenderBackend},
     storage::StorageBackend,
     ui::{MouseCursor, UiBackend},
     video::VideoBackend,
@@ -22,13 +24,19 @@ use crate::display_object::{
     EditText, MorphShape, MovieClip, Stage, StageAlign, StageDisplayState, StageQuality,
     StageScaleMode, TInteractiveObject,
 };
-use crate::events::{ButtonKeyCode, ClipEvent, ClipEventResult, KeyCode, PlayerEvent};
+use crate::events::{
+    ButtonKeyCode, ClipEvent, ClipEventResult, KeyCode, PlayerEvent, TextControlCode,
+};
 use crate::external::Value as ExternalValue;
 use crate::external::{ExternalInterface, ExternalInterfaceProvider};
 use crate::focus_tracker::FocusTracker;
 use crate::library::Library;
 use crate::loader::LoadManager;
+use crate::local_connection::LocalConnections;
+use crate::microphone::Microphone;
 use crate::prelude::*;
+use crate::socket::Sockets;
+use crate::streams::StreamManager;
 use crate::string::AvmString;
 use crate::tag_utils::SwfMovie;
 use crate::transform::TransformStack;
@@ -103,6 +111,18 @@ struct GcRootData<'gc> {
 
     /// Manager of active sound instances.
     audio_manager: AudioManager<'gc>,
+
+    /// Streams of external media files that are currently playing.
+    stream_manager: StreamManager<'gc>,
+
+    /// The user's microphone.
+    microphone: Microphone<'gc>,
+
+    /// Connections opened by `XMLSocket` objects.
+    sockets: Sockets<'gc>,
+
+    /// The message bus shared by all `LocalConnection` objects.
+    local_connections: LocalConnections<'gc>,
 }
 
 impl<'gc> GcRootData<'gc> {
@@ -125,6 +145,10 @@ impl<'gc> GcRootData<'gc> {
         &mut Option<ContextMenuState<'gc>>,
         &mut ExternalInterface<'gc>,
         &mut AudioManager<'gc>,
+        &mut StreamManager<'gc>,
+        &mut Microphone<'gc>,
+        &mut Sockets<'gc>,
+        &mut LocalConnections<'gc>,
     ) {
         (
             self.stage,
@@ -140,6 +164,10 @@ impl<'gc> GcRootData<'gc> {
             &mut self.current_context_menu,
             &mut self.external_interface,
             &mut self.audio_manager,
+            &mut self.stream_manager,
+            &mut self.microphone,
+            &mut self.sockets,
+            &mut self.local_connections,
         )
     }
 }
@@ -211,6 +239,15 @@ pub struct Player {
 
     system: SystemProperties,
 
+    /// Describes the host system for `System.capabilities`.
+    capabilities: Box<dyn CapabilitiesProvider>,
+
+    /// Capabilities to report instead of the ones from `capabilities`.
+    capabilities_overrides: CapabilitiesOverrides,
+
+    /// Provides the fonts of the system for device text.
+    fonts: Box<dyn FontBackend>,
+
     /// The current instance ID. Used to generate default `instanceN` names.
     instance_counter: i32,
 
@@ -287,6 +324,10 @@ impl Player {
                         external_interface: ExternalInterface::new(),
                         focus_tracker: FocusTracker::new(gc_context),
                         audio_manager: AudioManager::new(),
+                        stream_manager: StreamManager::new(),
+                        microphone: Microphone::new(),
+                        sockets: Sockets::new(),
+                        local_connections: LocalConnections::new(),
                     },
                 ))
             }),
@@ -309,12 +350,16 @@ impl Player {
             video,
             self_reference: None,
             system: SystemProperties::default(),
+            capabilities: Box::new(NullCapabilitiesProvider::new()),
+            capabilities_overrides: CapabilitiesOverrides::default(),
+            fonts: Box::new(NullFontBackend::new()),
             instance_counter: 0,
             time_til_next_timer: None,
             storage,
             max_execution_duration: Duration::from_secs(max_execution_duration),
             current_frame: None,
         };
+        player.update_capabilities();
 
         player.mutate_with_update_context(|context| {
             // Instantiate an empty root before the main movie loads.
@@ -511,6 +556,18 @@ impl Player {
             self.frame_accumulator += dt;
             let frame_time = 1000.0 / self.frame_rate;
 
+            // While a stream sound is playing, its audio is the clock for the timeline
+            // ("stream sync"). Frames are held back when the timeline gets ahead of the audio,
+            // and run back-to-back when it falls behind.
+            let frame_rate = self.frame_rate;
+            if let Some(lag) = self.mutate_with_update_context(|context| {
+                context
+                    .audio_manager
+                    .stream_sync_lag(context.audio, frame_rate)
+            }) {
+                self.frame_accumulator = lag * frame_time;
+            }
+
             let max_frames_per_tick = self.max_frames_per_tick();
             let mut frame = 0;
 
@@ -548,6 +605,10 @@ impl Player {
             }
 
             self.update_timers(dt);
+            self.update_streams(dt);
+            self.update_microphone(dt);
+            self.update_sockets();
+            self.update_local_connections();
             self.audio.tick();
         }
     }
@@ -588,23 +649,30 @@ impl Player {
                 ActivationIdentifier::root("[ContextMenu]"),
             );
 
-            // TODO: This should use a pointed display object with `.menu`
-            let menu_object = {
-                let dobj = activation.context.stage.root_clip();
-                if let Value::Object(obj) = dobj.object() {
-                    if let Ok(Value::Object(menu)) = obj.get("menu", &mut activation) {
-                        Some(menu)
-                    } else {
-                        None
+            // The menu of the object under the mouse takes precedence over
+            // the menu of the root movie.
+            let point = *activation.context.mouse_position;
+            let stage = activation.context.stage.into();
+            let root_clip = activation.context.stage.root_clip();
+            let (target, menu_object) =
+                match Self::context_menu_owner(&mut activation, stage, point) {
+                    Some((target, menu)) => (target, Some(menu)),
+                    None => {
+                        let menu = match root_clip.object() {
+                            Value::Object(obj) => match obj.get("menu", &mut activation) {
+                                Ok(Value::Object(menu)) => Some(menu),
+                                _ => None,
+                            },
+                            _ => None,
+                        };
+                        (root_clip, menu)
                     }
-                } else {
-                    None
-                }
-            };
+                };
 
             if let Some(menu) = menu_object {
                 if let Ok(Value::Object(on_select)) = menu.get("onSelect", &mut activation) {
                     Self::run_context_menu_custom_callback(
+                        target,
                         menu,
                         on_select,
                         &mut activation.context,
@@ -614,6 +682,7 @@ impl Player {
 
             let menu = crate::avm1::globals::context_menu::make_context_menu_state(
                 menu_object,
+                target,
                 &mut activation,
             );
             let ret = menu.info().clone();
@@ -622,6 +691,44 @@ impl Player {
         })
     }
 
+    /// Prepare the context menu and ask the UI backend to display it.
+    pub fn show_context_menu(&mut self) {
+        let items = self.prepare_context_menu();
+        if !items.is_empty() {
+            self.ui.display_context_menu(&items);
+        }
+    }
+
+    /// Find the topmost object under `point` that has a context menu in its
+    /// `menu` property, or that has an ancestor with one.
+    fn context_menu_owner<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        object: DisplayObject<'gc>,
+        point: (Twips, Twips),
+    ) -> Option<(DisplayObject<'gc>, Object<'gc>)> {
+        let child = object.as_container().and_then(|container| {
+            container.iter_render_list().rev().find(|child| {
+                child.hit_test_shape(
+                    &mut activation.context,
+                    point,
+                    HitTestOptions::SKIP_INVISIBLE,
+                )
+            })
+        });
+        if let Some(owner) =
+            child.and_then(|child| Self::context_menu_owner(activation, child, point))
+        {
+            return Some(owner);
+        }
+
+        if let Value::Object(obj) = object.object() {
+            if let Ok(Value::Object(menu)) = obj.get("menu", activation) {
+                return Some((object, menu));
+            }
+        }
+        None
+    }
+
     pub fn clear_custom_menu_items(&mut self) {
         self.gc_arena.mutate(|gc_context, gc_root| {
             let mut root_data = gc_root.0.write(gc_context);
@@ -634,9 +741,11 @@ impl Player {
             let menu = &context.current_context_menu;
             if let Some(ref menu) = menu {
                 match menu.callback(index) {
-                    ContextMenuCallback::Avm1 { item, callback } => {
-                        Self::run_context_menu_custom_callback(*item, *callback, context)
-                    }
+                    ContextMenuCallback::Avm1 {
+                        target,
+                        item,
+                        callback,
+                    } => Self::run_context_menu_custom_callback(*target, *item, *callback, context),
                     ContextMenuCallback::Play => Self::toggle_play_root_movie(context),
                     ContextMenuCallback::Forward => Self::forward_root_movie(context),
                     ContextMenuCallback::Back => Self::back_root_movie(context),
@@ -649,6 +758,7 @@ impl Player {
     }
 
     fn run_context_menu_custom_callback<'gc>(
+        target: DisplayObject<'gc>,
         item: Object<'gc>,
         callback: Object<'gc>,
         context: &mut UpdateContext<'_, 'gc, '_>,
@@ -669,9 +779,7 @@ impl Player {
         // currently doesn't allow `this` to be a Value (#843).
         let undefined = Value::Undefined.coerce_to_object(&mut activation);
 
-        // TODO: Remember to also change the first arg
-        // when we support contextmenu on non-root-movie
-        let params = vec![root_clip.object(), Value::Object(item)];
+        let params = vec![target.object(), Value::Object(item)];
 
         let _ = callback.call(
             "[Context Menu Callback]".into(),
@@ -692,6 +800,19 @@ impl Player {
         });
     }
 
+    /// Inform the player that the frontend entered or left fullscreen by
+    /// itself.
+    pub fn fullscreen_changed(&mut self, is_fullscreen: bool) {
+        self.mutate_with_update_context(|context| {
+            let display_state = if is_fullscreen {
+                StageDisplayState::FullScreen
+            } else {
+                StageDisplayState::Normal
+            };
+            context.stage.display_state_changed(context, display_state);
+        });
+    }
+
     fn toggle_play_root_movie<'gc>(context: &mut UpdateContext<'_, 'gc, '_>) {
         if let Some(mc) = context.stage.root_clip().as_movie_clip() {
             if mc.playing() {
@@ -780,6 +901,31 @@ impl Player {
         })
     }
 
+    /// Set what describes the host system to content, through
+    /// `System.capabilities`.
+    pub fn set_capabilities_provider(&mut self, capabilities: Box<dyn CapabilitiesProvider>) {
+        self.capabilities = capabilities;
+        self.update_capabilities();
+    }
+
+    /// Set what provides the fonts of the system, which device text is
+    /// rendered with.
+    pub fn set_font_backend(&mut self, fonts: Box<dyn FontBackend>) {
+        self.fonts = fonts;
+    }
+
+    /// Report different capabilities to content than the host system has.
+    pub fn set_capabilities_overrides(&mut self, overrides: CapabilitiesOverrides) {
+        self.capabilities_overrides = overrides;
+        self.update_capabilities();
+    }
+
+    /// Re-read the host system's capabilities, such as after the screen changes.
+    pub fn update_capabilities(&mut self) {
+        self.system
+            .apply_capabilities(&*self.capabilities, &self.capabilities_overrides);
+    }
+
     pub fn set_show_menu(&mut self, show_menu: bool) {
         self.mutate_with_update_context(|context| {
             let stage = context.stage;
@@ -827,11 +973,14 @@ impl Player {
     /// 3. If the incoming event is text input or key input that could be
     ///    related to text input (e.g. pressing a letter key), we dispatch a
     ///    key press event onto the stage.
-    /// 4. If the event from step 3 was not handled, we check if an `EditText`
-    ///    object is in focus and dispatch a text-control event to said object.
+    /// 4. If the event from step 3 was not handled and the key is an editing
+    ///    command, such as moving the caret or copying, we check if an
+    ///    `EditText` object is in focus and dispatch a text-control event to
+    ///    said object.
     /// 5. If the incoming event is text input, and neither step 3 nor step 4
     ///    resulted in an event being handled, we dispatch a text input event
-    ///    to the currently focused `EditText` (if present).
+    ///    to the currently focused `EditText` (if present). Text committed by
+    ///    an input method editor is always dispatched this way.
     /// 6. Regardless of all prior event handling, we dispatch the event
     ///    through the stage normally.
     /// 7. Then, we dispatch the event through AVM1 global listener objects.
@@ -917,25 +1066,22 @@ impl Player {
             _ => None,
         };
 
+        let text_control = match event {
+            PlayerEvent::KeyDown { key_code } => TextControlCode::from_key(
+                key_code,
+                self.ui.is_key_down(KeyCode::Control),
+                self.ui.is_key_down(KeyCode::Shift),
+            ),
+            _ => None,
+        };
+
         let mut key_press_handled = false;
         if let Some(button_event) = button_event {
             self.mutate_with_update_context(|context| {
                 let levels: Vec<_> = context.stage.iter_depth_list().collect();
                 for (_depth, level) in levels {
-                    let state = if let Some(interactive) = level.as_interactive() {
-                        interactive.handle_clip_event(context, button_event)
-                    } else {
-                        ClipEventResult::NotHandled
-                    };
-
-                    if state == ClipEventResult::Handled {
-                        key_press_handled = true;
-                        return;
-                    } else if let Some(text) =
-                        context.focus_tracker.get().and_then(|o| o.as_edit_text())
-                    {
-                        // Text fields listen for arrow key presses, etc.
-                        if text.handle_text_control_event(context, button_event)
+                    if let Some(interactive) = level.as_interactive() {
+                        if interactive.handle_clip_event(context, button_event)
                             == ClipEventResult::Handled
                         {
                             key_press_handled = true;
@@ -946,15 +1092,39 @@ impl Player {
             });
         }
 
+        // Text fields listen for arrow key presses, copying, etc.
+        if let Some(text_control) = text_control.filter(|_| !key_press_handled) {
+            self.mutate_with_update_context(|context| {
+                if let Some(text) = context.focus_tracker.get().and_then(|o| o.as_edit_text()) {
+                    key_press_handled =
+                        text.text_control_input(text_control, context) == ClipEventResult::Handled;
+                }
+            });
+        }
+
         // keyPress events take precedence over text input.
-        if !key_press_handled {
-            if let PlayerEvent::TextInput { codepoint } = event {
+        match &event {
+            PlayerEvent::TextInput { codepoint } if !key_press_handled => {
+                let codepoint = *codepoint;
                 self.mutate_with_update_context(|context| {
                     if let Some(text) = context.focus_tracker.get().and_then(|o| o.as_edit_text()) {
                         text.text_input(codepoint, context);
                     }
                 });
             }
+            PlayerEvent::ImeCommit { text: input } => {
+                self.mutate_with_update_context(|context| {
+                    if let Some(text) = context.focus_tracker.get().and_then(|o| o.as_edit_text()) {
+                        text.ime_commit(input, context);
+                    }
+                });
+            }
+            _ => {}
+        }
+
+        // Input method editors show their composition next to the caret.
+        if !matches!(event, PlayerEvent::MouseMove { .. }) {
+            self.update_ime_position();
         }
 
         // Propagate clip events.
@@ -1016,10 +1186,37 @@ impl Player {
         if self.update_mouse_state(Some(&event)) {
             self.needs_render = true;
         }
+
+        // Dragged objects follow the mouse as it moves, rather than waiting for the next frame.
+        // This keeps custom cursors attached with `startDrag(true)` responsive.
+        if let PlayerEvent::MouseMove { .. } = event {
+            if self.update_drag() {
+                self.needs_render = true;
+            }
+        }
+    }
+
+    /// Moves the composition window of the input method editor to the caret
+    /// of the focused text field, if it is editable.
+    fn update_ime_position(&mut self) {
+        let position = self.mutate_with_update_context(|context| {
+            let text = context.focus_tracker.get()?.as_edit_text()?;
+            if !text.is_editable() {
+                return None;
+            }
+            let caret = text.caret_screen_position()?;
+            let view_matrix = *context.stage.base().matrix();
+            Some(view_matrix * caret)
+        });
+        if let Some((x, y)) = position {
+            self.ui.set_ime_position(x.to_pixels(), y.to_pixels());
+        }
     }
 
     /// Update dragged object, if any.
-    fn update_drag(&mut self) {
+    ///
+    /// Returns `true` if an object was moved.
+    fn update_drag(&mut self) -> bool {
         let mouse_pos = self.mouse_pos;
         self.mutate_with_update_context(|context| {
             if let Some(drag_object) = &mut context.drag_object {
@@ -1027,6 +1224,7 @@ impl Player {
                 if drag_object.display_object.removed() {
                     // Be sure to clear the drag if the object was removed.
                     *context.drag_object = None;
+                    false
                 } else {
                     let mut drag_point = (
                         mouse_pos.0 + drag_object.offset.0,
@@ -1039,27 +1237,42 @@ impl Player {
                     display_object.set_x(context.gc_context, drag_point.0.to_pixels());
                     display_object.set_y(context.gc_context, drag_point.1.to_pixels());
 
-                    // Update _droptarget property of dragged object.
-                    if let Some(movie_clip) = display_object.as_movie_clip() {
-                        // Turn the dragged object invisible so that we don't pick it.
-                        // TODO: This could be handled via adding a `HitTestOptions::SKIP_DRAGGED`.
-                        let was_visible = display_object.visible();
-                        display_object.set_visible(context.gc_context, false);
-                        // Set _droptarget to the object the mouse is hovering over.
-                        let drop_target_object = context
-                            .stage
-                            .iter_depth_list()
-                            .rev()
-                            .filter_map(|(_depth, level)| {
-                                level.mouse_pick(context, *context.mouse_position, false)
-                            })
-                            .next();
-                        movie_clip.set_drop_target(context.gc_context, drop_target_object);
-                        display_object.set_visible(context.gc_context, was_visible);
-                    }
+                    Self::update_drop_target(context);
+                    true
                 }
+            } else {
+                false
             }
-        });
+        })
+    }
+
+    /// Update the `_droptarget` property of the dragged object, if any.
+    ///
+    /// This is done whenever the object moves, and once more when the drag
+    /// stops, so that the drop target reflects where the object was dropped.
+    pub fn update_drop_target<'gc>(context: &mut UpdateContext<'_, 'gc, '_>) {
+        let display_object = match &context.drag_object {
+            Some(drag_object) => drag_object.display_object,
+            None => return,
+        };
+
+        if let Some(movie_clip) = display_object.as_movie_clip() {
+            // Turn the dragged object invisible so that we don't pick it.
+            // TODO: This could be handled via adding a `HitTestOptions::SKIP_DRAGGED`.
+            let was_visible = display_object.visible();
+            display_object.set_visible(context.gc_context, false);
+            // Set _droptarget to the object the mouse is hovering over.
+            let drop_target_object = context
+                .stage
+                .iter_depth_list()
+                .rev()
+                .filter_map(|(_depth, level)| {
+                    level.mouse_pick(context, *context.mouse_position, false)
+                })
+                .next();
+            movie_clip.set_drop_target(context.gc_context, drop_target_object);
+            display_object.set_visible(context.gc_context, was_visible);
+        }
     }
 
     /// Updates the hover state of buttons.
@@ -1122,6 +1335,18 @@ impl Player {
             }
 
             let cur_over_object = context.mouse_over_object;
+
+            // Links in text fields are styled with `a:hover` while the mouse is over them.
+            let mut link_hover_changed = false;
+            if !DisplayObject::option_ptr_eq(cur_over_object, new_over_object) {
+                if let Some(text) = cur_over_object.and_then(|object| object.as_edit_text()) {
+                    link_hover_changed |= text.set_hovered_link(context, None);
+                }
+            }
+            if let Some(text) = new_over_object.and_then(|object| object.as_edit_text()) {
+                link_hover_changed |= text.set_hovered_link(context, Some(*context.mouse_position));
+            }
+
             // Check if a new object has been hovered over.
             if !DisplayObject::option_ptr_eq(cur_over_object, new_over_object) {
                 // If the mouse button is down, the object the user clicked on grabs the focus
@@ -1149,12 +1374,17 @@ impl Player {
                     }
                     // Rolled over the new object.
                     if let Some(new_over_object) = new_over_object {
-                        new_cursor = new_over_object.mouse_cursor();
+                        new_cursor = new_over_object.mouse_cursor(context);
                         events.push((new_over_object, ClipEvent::RollOver));
                     } else {
                         new_cursor = MouseCursor::Arrow;
                     }
                 }
+            } else if !is_mouse_down {
+                // The cursor can change within the same object, e.g. over a link in a text field.
+                if let Some(over_object) = new_over_object {
+                    new_cursor = over_object.mouse_cursor(context);
+                }
             }
             context.mouse_over_object = new_over_object;
 
@@ -1183,7 +1413,7 @@ impl Player {
                         }
                         // The new object is rolled over immediately.
                         if let Some(over_object) = context.mouse_over_object {
-                            new_cursor = over_object.mouse_cursor();
+                            new_cursor = over_object.mouse_cursor(context);
                             events.push((over_object, ClipEvent::RollOver));
                         } else {
                             new_cursor = MouseCursor::Arrow;
@@ -1195,7 +1425,7 @@ impl Player {
 
             // Fire any pending mouse events.
             let needs_render = if events.is_empty() {
-                false
+                link_hover_changed
             } else {
                 for (object, event) in events {
                     if !object.removed() {
@@ -1291,14 +1521,51 @@ impl Player {
                 stage: root_data.stage,
                 clip_depth_stack: vec![],
                 allow_mask: true,
+                scaling_grid: None,
             };
 
+            root_data.library.sweep_shape_caches();
             root_data.stage.render(&mut render_context);
         });
 
         self.needs_render = false;
     }
 
+    /// Renders the stage offscreen at the size of the viewport, such as for
+    /// screenshots.
+    ///
+    /// Unlike `render`, this always draws the whole stage and leaves what is
+    /// on screen as it was. Returns the image in un-multiplied RGBA, or `None`
+    /// if the render backend can't render offscreen.
+    pub fn capture_frame(&mut self) -> Option<Bitmap> {
+        let (renderer, ui, transform_stack) =
+            (&mut self.renderer, &mut self.ui, &mut self.transform_stack);
+
+        self.gc_arena.mutate(|_gc_context, gc_root| {
+            let root_data = gc_root.0.read();
+            let stage = root_data.stage;
+            let library = &root_data.library;
+            let (width, height) = stage.viewport_size();
+            let background_color = stage
+                .background_color()
+                .unwrap_or_else(|| Color::from_rgb(0xffffff, 255));
+            renderer.set_quality(stage.quality());
+            renderer.render_offscreen(width, height, background_color, &mut |renderer| {
+                let mut render_context = RenderContext {
+                    renderer,
+                    ui: ui.deref_mut(),
+                    library,
+                    transform_stack: &mut *transform_stack,
+                    stage,
+                    clip_depth_stack: vec![],
+                    allow_mask: true,
+                    scaling_grid: None,
+                };
+                stage.render_contents(&mut render_context);
+            })
+        })
+    }
+
     /// The current frame of the main timeline, if available.
     /// The first frame is frame 1.
     pub fn current_frame(&self) -> Option<u16> {
@@ -1370,54 +1637,54 @@ impl Player {
                     );
                 }
                 // Change the prototype of a MovieClip and run constructor events.
-                ActionType::Construct {
-                    constructor: Some(constructor),
-                    events,
-                } => {
-                    let version = context.swf.version();
-                    let globals = context.avm1.global_object_cell();
-
-                    let mut activation = Activation::from_nothing(
-                        context.reborrow(),
-                        ActivationIdentifier::root("[Construct]"),
-                        version,
-                        globals,
-                        actions.clip,
-                    );
-                    if let Ok(prototype) = constructor.get("prototype", &mut activation) {
-                        if let Value::Object(object) = actions.clip.object() {
-                            object.define_value(
-                                activation.context.gc_context,
-                                "__proto__",
-                                prototype,
-                                Attribute::empty(),
-                            );
-                            for event in events {
-                                let _ = activation.run_child_frame_for_action(
-                                    "[Actions]",
-                                    actions.clip,
-                                    activation.context.swf.version(),
-                                    event,
+                ActionType::Construct { events } => {
+                    let constructor = actions
+                        .clip
+                        .as_movie_clip()
+                        .and_then(|clip| clip.registered_avm1_constructor(context));
+                    if let Some(constructor) = constructor {
+                        let version = context.swf.version();
+                        let globals = context.avm1.global_object_cell();
+
+                        let mut activation = Activation::from_nothing(
+                            context.reborrow(),
+                            ActivationIdentifier::root("[Construct]"),
+                            version,
+                            globals,
+                            actions.clip,
+                        );
+                        if let Ok(prototype) = constructor.get("prototype", &mut activation) {
+                            if let Value::Object(object) = actions.clip.object() {
+                                object.define_value(
+                                    activation.context.gc_context,
+                                    "__proto__",
+                                    prototype,
+                                    Attribute::empty(),
                                 );
+                                for event in events {
+                                    let _ = activation.run_child_frame_for_action(
+                                        "[Actions]",
+                                        actions.clip,
+                                        activation.context.swf.version(),
+                                        event,
+                                    );
+                                }
+
+                                let _ =
+                                    constructor.construct_on_existing(&mut activation, object, &[]);
                             }
-
-                            let _ = constructor.construct_on_existing(&mut activation, object, &[]);
                         }
-                    }
-                }
-                // Run constructor events without changing the prototype.
-                ActionType::Construct {
-                    constructor: None,
-                    events,
-                } => {
-                    for event in events {
-                        Avm1::run_stack_frame_for_action(
-                            actions.clip,
-                            "[Construct]",
-                            context.swf.version(),
-                            event,
-                            context,
-                        );
+                    } else {
+                        // Run constructor events without changing the prototype.
+                        for event in events {
+                            Avm1::run_stack_frame_for_action(
+                                actions.clip,
+                                "[Construct]",
+                                context.swf.version(),
+                                event,
+                                context,
+                            );
+                        }
                     }
                 }
                 // Event handler method call (e.g. onEnterFrame).
@@ -1496,6 +1763,10 @@ impl Player {
                 current_context_menu,
                 external_interface,
                 audio_manager,
+                stream_manager,
+                microphone,
+                sockets,
+                local_connections,
             ) = root_data.update_context_params();
 
             let mut update_context = UpdateContext {
@@ -1520,6 +1791,7 @@ impl Player {
                 instance_counter: &mut self.instance_counter,
                 storage: self.storage.deref_mut(),
                 locale: self.locale.deref_mut(),
+                fonts: self.fonts.deref_mut(),
                 log: self.log.deref_mut(),
                 video: self.video.deref_mut(),
                 shared_objects,
@@ -1536,6 +1808,10 @@ impl Player {
                 times_get_time_called: 0,
                 time_offset: &mut self.time_offset,
                 audio_manager,
+                stream_manager,
+                microphone,
+                sockets,
+                local_connections,
                 frame_rate: &mut self.frame_rate,
             };
 
@@ -1622,8 +1898,11 @@ impl Player {
                 Activation::from_stub(context.reborrow(), ActivationIdentifier::root("[Flush]"));
             let shared_objects = activation.context.shared_objects.clone();
             for so in shared_objects.values() {
-                let _ = crate::avm1::globals::shared_object::flush(&mut activation, *so, &[]);
+                let _ =
+                    crate::avm1::globals::shared_object::write_to_storage(&mut activation, *so, 0);
             }
+
+            Avm2::flush_shared_objects(&mut activation.context);
         });
     }
 
@@ -1634,6 +1913,26 @@ impl Player {
             self.mutate_with_update_context(|context| Timers::update_timers(context, dt));
     }
 
+    /// Advance the playhead of all playing `NetStream`s.
+    pub fn update_streams(&mut self, dt: f64) {
+        self.mutate_with_update_context(|context| StreamManager::update_streams(context, dt));
+    }
+
+    /// Meter the audio captured from the microphone.
+    pub fn update_microphone(&mut self, dt: f64) {
+        self.mutate_with_update_context(|context| Microphone::update(context, dt));
+    }
+
+    /// Deliver the events of open `XMLSocket` connections.
+    pub fn update_sockets(&mut self) {
+        self.mutate_with_update_context(|context| Sockets::update(context));
+    }
+
+    /// Deliver the messages sent between `LocalConnection` objects.
+    pub fn update_local_connections(&mut self) {
+        self.mutate_with_update_context(|context| LocalConnections::update(context));
+    }
+
     /// Returns whether this player consumes mouse wheel events.
     /// Used by web to prevent scrolling.
     pub fn should_prevent_scrolling(&mut self) -> bool {
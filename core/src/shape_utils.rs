@@ -1,6 +1,8 @@
+use crate::backend::render::{BitmapSource, RenderBackend, ShapeHandle};
 use crate::{bounding_box::BoundingBox, matrix::Matrix};
 use fnv::FnvHashMap;
 use smallvec::SmallVec;
+use std::cell::RefCell;
 use std::num::NonZeroU32;
 use swf::{CharacterId, FillStyle, LineStyle, Shape, ShapeRecord, Twips};
 
@@ -97,6 +99,606 @@ impl<'a> From<&'a swf::Shape> for DistilledShape<'a> {
     }
 }
 
+impl<'a> DistilledShape<'a> {
+    /// Copies the shape, replacing the styles of its strokes in order.
+    ///
+    /// `line_styles` should have a style for every stroke of the shape, such as the styles
+    /// returned by [`DistilledShape::scaled_line_styles`].
+    pub fn with_line_styles<'b>(&self, line_styles: &'b [LineStyle]) -> DistilledShape<'b>
+    where
+        'a: 'b,
+    {
+        let mut line_styles = line_styles.iter();
+        let paths = self
+            .paths
+            .iter()
+            .map(|path| match path {
+                DrawPath::Stroke {
+                    style,
+                    is_closed,
+                    commands,
+                } => DrawPath::Stroke {
+                    style: line_styles.next().unwrap_or(*style),
+                    is_closed: *is_closed,
+                    commands: commands.clone(),
+                },
+                DrawPath::Fill { style, commands } => DrawPath::Fill {
+                    style: *style,
+                    commands: commands.clone(),
+                },
+            })
+            .collect();
+        DistilledShape {
+            paths,
+            shape_bounds: self.shape_bounds.clone(),
+            edge_bounds: self.edge_bounds.clone(),
+            id: self.id,
+        }
+    }
+
+    /// The styles of the strokes of the shape, with the widths that they have when the shape is
+    /// rendered at the given scale.
+    pub fn scaled_line_styles(&self, scale: StrokeScale) -> Vec<LineStyle> {
+        let scales = scale.scales();
+        self.paths
+            .iter()
+            .filter_map(|path| match path {
+                DrawPath::Stroke { style, .. } => Some(LineStyle {
+                    width: Twips::new(scaled_stroke_width(style, scales).round().max(1.0) as i32),
+                    ..(*style).clone()
+                }),
+                DrawPath::Fill { .. } => None,
+            })
+            .collect()
+    }
+}
+
+/// The horizontal and vertical scale of a shape on screen, rounded to steps of about 4%.
+///
+/// The strokes of a shape don't always scale with the shape: Flash renders strokes at least
+/// 1 pixel wide, and strokes may not scale horizontally or vertically at all. Shapes with such
+/// strokes are tessellated again for each `StrokeScale` they're rendered at. The rounding
+/// avoids tessellating a shape that is being tweened on every frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct StrokeScale {
+    x: i16,
+    y: i16,
+}
+
+impl StrokeScale {
+    /// The number of steps for each doubling of the scale.
+    const STEPS: f64 = 16.0;
+
+    pub fn from_matrix(matrix: &Matrix) -> Self {
+        let step = |scale: f64| {
+            if scale > 0.0 && scale.is_finite() {
+                (scale.log2() * Self::STEPS).round().clamp(-4096.0, 4096.0) as i16
+            } else {
+                i16::MIN
+            }
+        };
+        let (x, y) = matrix_scales(matrix);
+        Self {
+            x: step(x),
+            y: step(y),
+        }
+    }
+
+    fn scales(self) -> (f64, f64) {
+        let scale = |step: i16| {
+            if step == i16::MIN {
+                0.0
+            } else {
+                (f64::from(step) / Self::STEPS).exp2()
+            }
+        };
+        (scale(self.x), scale(self.y))
+    }
+}
+
+/// The scales of a shape that its strokes can be rendered at without tessellating it again.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StrokeScaling {
+    /// The smallest scale at which all strokes are wider than the 1 pixel minimum.
+    min_scale: f64,
+
+    /// Whether any stroke doesn't scale with the shape, or is pixel hinted.
+    has_scale_independent_strokes: bool,
+}
+
+impl StrokeScaling {
+    pub fn from_line_styles<'a>(line_styles: impl IntoIterator<Item = &'a LineStyle>) -> Self {
+        line_styles
+            .into_iter()
+            .fold(Self::default(), |scaling, style| Self {
+                min_scale: scaling
+                    .min_scale
+                    .max(20.0 / f64::from(style.width.get().max(1))),
+                has_scale_independent_strokes: scaling.has_scale_independent_strokes
+                    || !style.allow_scale_x
+                    || !style.allow_scale_y
+                    || style.is_pixel_hinted,
+            })
+    }
+
+    /// All the line styles of an SWF shape, including the styles of its later layers.
+    pub fn from_shape(shape: &swf::Shape) -> Self {
+        let new_line_styles = shape.shape.iter().filter_map(|record| match record {
+            ShapeRecord::StyleChange(style_change) => style_change
+                .new_styles
+                .as_ref()
+                .map(|styles| &styles.line_styles),
+            _ => None,
+        });
+        Self::from_line_styles(
+            std::iter::once(&shape.styles.line_styles)
+                .chain(new_line_styles)
+                .flatten(),
+        )
+    }
+
+    /// Whether the strokes of the shape have different widths when rendered with the given
+    /// matrix than when the shape was tessellated.
+    pub fn is_scale_dependent(&self, matrix: &Matrix) -> bool {
+        if self.has_scale_independent_strokes {
+            return true;
+        }
+        let (x, y) = matrix_scales(matrix);
+        average_scale(x, y) < self.min_scale
+    }
+}
+
+/// Marks the strokes of a shape from `DefineShape` 1 to 3 as scaling with the shape.
+///
+/// The line styles of those tags have no scaling flags, and Flash always scales their strokes.
+pub fn scale_legacy_strokes(shape: &mut swf::Shape) {
+    if shape.version >= 4 {
+        return;
+    }
+    let new_line_styles = shape.shape.iter_mut().filter_map(|record| match record {
+        ShapeRecord::StyleChange(style_change) => style_change
+            .new_styles
+            .as_mut()
+            .map(|styles| &mut styles.line_styles),
+        _ => None,
+    });
+    for style in std::iter::once(&mut shape.styles.line_styles)
+        .chain(new_line_styles)
+        .flatten()
+    {
+        style.allow_scale_x = true;
+        style.allow_scale_y = true;
+    }
+}
+
+/// The length of the axes of the given matrix.
+fn matrix_scales(matrix: &Matrix) -> (f64, f64) {
+    let x = f64::from(matrix.a).hypot(matrix.b.into());
+    let y = f64::from(matrix.c).hypot(matrix.d.into());
+    (x, y)
+}
+
+/// The scale of the width of a stroke that scales with a shape with the given scales.
+fn average_scale(x: f64, y: f64) -> f64 {
+    ((x * x + y * y) / 2.0).sqrt()
+}
+
+/// The width of a stroke in twips in the space of a shape that is rendered with the given
+/// horizontal and vertical scales.
+///
+/// This accounts for the stroke scaling flags, pixel hinting, and the minimum width of 1 pixel
+/// that Flash renders strokes with.
+fn scaled_stroke_width(style: &LineStyle, (x, y): (f64, f64)) -> f64 {
+    let scale = average_scale(x, y);
+    let width = f64::from(style.width.get());
+    if !(scale > 0.0 && scale.is_finite()) {
+        return width;
+    }
+    let screen_width = match (style.allow_scale_x, style.allow_scale_y) {
+        (true, true) => width * scale,
+        (false, true) => width * y,
+        (true, false) => width * x,
+        (false, false) => width,
+    };
+    let screen_width = if style.is_pixel_hinted {
+        (screen_width / 20.0).round() * 20.0
+    } else {
+        screen_width
+    };
+    screen_width.max(20.0) / scale
+}
+
+/// The width of a stroke in twips in the space of a shape, for a hit test with the given
+/// matrix from global to local space.
+fn hit_test_stroke_width(style: &LineStyle, local_matrix: &Matrix) -> f64 {
+    let mut matrix = *local_matrix;
+    matrix.invert();
+    scaled_stroke_width(style, matrix_scales(&matrix))
+}
+
+/// The handles of a shape tessellated for the scales its strokes have been rendered at.
+///
+/// Only the last few scales are kept, and the handles of older scales are reused.
+#[derive(Clone, Debug, Default)]
+pub struct ScaledStrokeCache {
+    /// The handles with the scale they were tessellated for, from oldest to newest.
+    /// Handles of a stale shape have no scale.
+    handles: RefCell<Vec<(Option<StrokeScale>, ShapeHandle)>>,
+}
+
+impl ScaledStrokeCache {
+    const MAX_SCALES: usize = 8;
+
+    /// The handle of the shape with its strokes scaled for the given matrix.
+    ///
+    /// `shape` is only called if the shape hasn't been tessellated for this scale yet.
+    pub fn handle<'a>(
+        &self,
+        renderer: &mut dyn RenderBackend,
+        bitmap_source: &dyn BitmapSource,
+        matrix: &Matrix,
+        shape: impl FnOnce() -> DistilledShape<'a>,
+    ) -> ShapeHandle {
+        let scale = StrokeScale::from_matrix(matrix);
+        let mut handles = self.handles.borrow_mut();
+        if let Some(&(_, handle)) = handles.iter().find(|(s, _)| *s == Some(scale)) {
+            return handle;
+        }
+
+        let shape = shape();
+        let line_styles = shape.scaled_line_styles(scale);
+        let shape = shape.with_line_styles(&line_styles);
+        let reusable = match handles.iter().position(|(s, _)| s.is_none()) {
+            Some(i) => Some(i),
+            None if handles.len() >= Self::MAX_SCALES => Some(0),
+            None => None,
+        };
+        let handle = if let Some(i) = reusable {
+            let (_, handle) = handles.remove(i);
+            renderer.replace_shape(shape, bitmap_source, handle);
+            handle
+        } else {
+            renderer.register_shape(shape, bitmap_source)
+        };
+        handles.push((Some(scale), handle));
+        handle
+    }
+
+    /// Marks the tessellated shapes as stale after the shape has changed.
+    pub fn invalidate(&self) {
+        for (scale, _) in self.handles.borrow_mut().iter_mut() {
+            *scale = None;
+        }
+    }
+}
+
+/// A shape in a `ShapeCache`, tessellated with its strokes scaled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ShapeCacheKey {
+    pub id: CharacterId,
+
+    /// The quantized ratio of a morph shape, which is 0 for other shapes.
+    pub ratio: u16,
+    pub scale: StrokeScale,
+}
+
+/// Shapes tessellated with their strokes scaled, shared between all instances of the
+/// characters of a movie.
+///
+/// Every instance of a shape that is rendered at the same `StrokeScale` reuses the same
+/// handle, so that a symbol with many instances, such as the particles of an effect, is only
+/// tessellated once for each scale. Once the cache is full, shapes that weren't rendered
+/// during the last frame are evicted, and their handles are reused for other shapes.
+#[derive(Debug, Default)]
+pub struct ShapeCache {
+    /// The handles of the shapes, and whether they were used since the last sweep.
+    entries: RefCell<FnvHashMap<ShapeCacheKey, (ShapeHandle, bool)>>,
+
+    /// The handles of evicted shapes, which can be replaced by new shapes.
+    free_handles: RefCell<Vec<ShapeHandle>>,
+}
+
+impl ShapeCache {
+    const MAX_ENTRIES: usize = 256;
+
+    /// The handle of the shape with its strokes scaled for the scale in `key`.
+    ///
+    /// `shape` is only called if the shape hasn't been tessellated for this scale yet.
+    pub fn handle<'a>(
+        &self,
+        renderer: &mut dyn RenderBackend,
+        bitmap_source: &dyn BitmapSource,
+        key: ShapeCacheKey,
+        shape: impl FnOnce() -> DistilledShape<'a>,
+    ) -> ShapeHandle {
+        let mut entries = self.entries.borrow_mut();
+        if let Some((handle, used)) = entries.get_mut(&key) {
+            *used = true;
+            return *handle;
+        }
+
+        let shape = shape();
+        let line_styles = shape.scaled_line_styles(key.scale);
+        let shape = shape.with_line_styles(&line_styles);
+        let handle = if let Some(handle) = self.free_handles.borrow_mut().pop() {
+            renderer.replace_shape(shape, bitmap_source, handle);
+            handle
+        } else {
+            renderer.register_shape(shape, bitmap_source)
+        };
+        entries.insert(key, (handle, true));
+        handle
+    }
+
+    /// Evicts the shapes that weren't used since the last sweep if the cache is full.
+    ///
+    /// This is called before every frame is rendered, so that the handle of a shape is never
+    /// replaced during a frame that has already drawn it.
+    pub fn sweep(&self) {
+        let mut entries = self.entries.borrow_mut();
+        if entries.len() > Self::MAX_ENTRIES {
+            let mut free_handles = self.free_handles.borrow_mut();
+            entries.retain(|_, &mut (handle, used)| {
+                if !used {
+                    free_handles.push(handle);
+                }
+                used
+            });
+        }
+        for (_, used) in entries.values_mut() {
+            *used = false;
+        }
+    }
+}
+
+/// How the points of a shape are moved when its parent is scaled with a 9-slice grid
+/// (`scale9Grid`).
+///
+/// After the parent's scale is applied, the corners of the grid keep their size, the edges
+/// are only stretched along the grid, and the center is stretched to fill the rest of the
+/// parent's bounds. Like in Flash, only the points of the shape are moved, so edges that
+/// cross the grid are distorted. Strokes keep the width they have at the parent's original
+/// scale.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NineSlice {
+    x: NineSliceAxis,
+    y: NineSliceAxis,
+
+    /// The transform from the space of the shape into the space of the grid.
+    to_grid: Matrix,
+
+    /// The scale of the widths of strokes.
+    stroke_scale: f64,
+}
+
+impl NineSlice {
+    /// The 9-slice scaling of the given grid, for a parent with the given bounds and matrix,
+    /// all in the space of the parent.
+    ///
+    /// Returns `None` if the parent isn't scaled, so the shape doesn't need to change.
+    pub fn new(
+        grid: &BoundingBox,
+        bounds: &BoundingBox,
+        matrix: &Matrix,
+        to_grid: Matrix,
+    ) -> Option<Self> {
+        let (scale_x, scale_y) = matrix_scales(matrix);
+        let is_scaled = |scale: f64| (scale - 1.0).abs() > 1e-6;
+        if !grid.valid
+            || !bounds.valid
+            || !(scale_x > 0.0 && scale_y > 0.0)
+            || !(is_scaled(scale_x) || is_scaled(scale_y))
+        {
+            return None;
+        }
+        let range = |min: Twips, max: Twips| (f64::from(min.get()), f64::from(max.get()));
+        Some(Self {
+            x: NineSliceAxis::new(
+                range(bounds.x_min, bounds.x_max),
+                range(grid.x_min, grid.x_max),
+                scale_x,
+            ),
+            y: NineSliceAxis::new(
+                range(bounds.y_min, bounds.y_max),
+                range(grid.y_min, grid.y_max),
+                scale_y,
+            ),
+            to_grid,
+            stroke_scale: 1.0 / average_scale(scale_x, scale_y),
+        })
+    }
+
+    /// The styles of the strokes of the shape, with widths that don't scale with the parent.
+    fn line_styles(&self, shape: &DistilledShape) -> Vec<LineStyle> {
+        shape
+            .paths
+            .iter()
+            .filter_map(|path| match path {
+                DrawPath::Stroke { style, .. } => Some(LineStyle {
+                    width: Twips::new(
+                        (f64::from(style.width.get()) * self.stroke_scale)
+                            .round()
+                            .max(1.0) as i32,
+                    ),
+                    ..(*style).clone()
+                }),
+                DrawPath::Fill { .. } => None,
+            })
+            .collect()
+    }
+
+    /// Moves the points of the shape.
+    fn map_points(&self, shape: &mut DistilledShape) {
+        let mut from_grid = self.to_grid;
+        from_grid.invert();
+        let transform = |matrix: &Matrix, (x, y): Vector| {
+            let (a, b, c, d) = (
+                f64::from(matrix.a),
+                f64::from(matrix.b),
+                f64::from(matrix.c),
+                f64::from(matrix.d),
+            );
+            (
+                a * x + c * y + f64::from(matrix.tx.get()),
+                b * x + d * y + f64::from(matrix.ty.get()),
+            )
+        };
+        let map = |x: &mut Twips, y: &mut Twips| {
+            let (grid_x, grid_y) = transform(&self.to_grid, to_vector((*x, *y)));
+            let mapped = (self.x.map(grid_x), self.y.map(grid_y));
+            let (new_x, new_y) = transform(&from_grid, mapped);
+            *x = Twips::new(new_x.round() as i32);
+            *y = Twips::new(new_y.round() as i32);
+        };
+        for path in &mut shape.paths {
+            let commands = match path {
+                DrawPath::Stroke { commands, .. } => commands,
+                DrawPath::Fill { commands, .. } => commands,
+            };
+            for command in commands {
+                match command {
+                    DrawCommand::MoveTo { x, y } | DrawCommand::LineTo { x, y } => map(x, y),
+                    DrawCommand::CurveTo { x1, y1, x2, y2 } => {
+                        map(x1, y1);
+                        map(x2, y2);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The `scale9Grid` of a display object, with the bounds and matrix of the display object that
+/// the 9-slice scaling of its shapes depends on.
+///
+/// This is computed once when a display object with a grid is rendered, and shared by the
+/// shapes of all of its children.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScalingGrid {
+    grid: BoundingBox,
+    bounds: BoundingBox,
+    matrix: Matrix,
+}
+
+impl ScalingGrid {
+    /// The given grid of a display object with the given bounds and matrix, all in the space
+    /// of the display object.
+    pub fn new(grid: BoundingBox, bounds: BoundingBox, matrix: Matrix) -> Self {
+        Self {
+            grid,
+            bounds,
+            matrix,
+        }
+    }
+
+    /// The 9-slice scaling of a shape, where `to_grid` transforms the shape into the space of
+    /// the grid.
+    pub fn nine_slice(&self, to_grid: Matrix) -> Option<NineSlice> {
+        NineSlice::new(&self.grid, &self.bounds, &self.matrix, to_grid)
+    }
+}
+
+/// The 9-slice scaling along one axis of the grid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct NineSliceAxis {
+    bounds_min: f64,
+    grid_min: f64,
+    grid_max: f64,
+
+    /// The scale of the corners and the center, which cancel out the scale of the parent.
+    corner_scale: f64,
+    center_scale: f64,
+}
+
+impl NineSliceAxis {
+    fn new(
+        (bounds_min, bounds_max): (f64, f64),
+        (grid_min, grid_max): (f64, f64),
+        scale: f64,
+    ) -> Self {
+        let grid_min = grid_min.clamp(bounds_min, bounds_max);
+        let grid_max = grid_max.clamp(grid_min, bounds_max);
+        let corners = (grid_min - bounds_min) + (bounds_max - grid_max);
+        let center = grid_max - grid_min;
+
+        // The size of the bounds of the parent once it is scaled.
+        let size = (bounds_max - bounds_min) * scale;
+        let (corner_scale, center_scale) = if size >= corners {
+            let center_scale = if center > 0.0 {
+                (size - corners) / center / scale
+            } else {
+                0.0
+            };
+            (1.0 / scale, center_scale)
+        } else {
+            // The corners don't fit, so they shrink and the center disappears.
+            (size / corners / scale, 0.0)
+        };
+        Self {
+            bounds_min,
+            grid_min,
+            grid_max,
+            corner_scale,
+            center_scale,
+        }
+    }
+
+    fn map(&self, value: f64) -> f64 {
+        let start = value.min(self.grid_min) - self.bounds_min;
+        let center = value.clamp(self.grid_min, self.grid_max) - self.grid_min;
+        let end = value.max(self.grid_max) - self.grid_max;
+        self.bounds_min + (start + end) * self.corner_scale + center * self.center_scale
+    }
+}
+
+/// The handle of a shape tessellated for the 9-slice grid of its parent.
+#[derive(Clone, Debug, Default)]
+pub struct NineSliceCache {
+    /// The handle with the 9-slice scaling it was tessellated for, which is `None` if the
+    /// shape is stale.
+    handle: RefCell<Option<(Option<NineSlice>, ShapeHandle)>>,
+}
+
+impl NineSliceCache {
+    /// The handle of the shape with its points moved for the given 9-slice scaling.
+    ///
+    /// `shape` is only called if the shape hasn't been tessellated for this scaling yet.
+    pub fn handle<'a>(
+        &self,
+        renderer: &mut dyn RenderBackend,
+        bitmap_source: &dyn BitmapSource,
+        nine_slice: &NineSlice,
+        shape: impl FnOnce() -> DistilledShape<'a>,
+    ) -> ShapeHandle {
+        let mut cached = self.handle.borrow_mut();
+        if let Some((Some(cached_nine_slice), handle)) = &*cached {
+            if cached_nine_slice == nine_slice {
+                return *handle;
+            }
+        }
+
+        let shape = shape();
+        let line_styles = nine_slice.line_styles(&shape);
+        let mut shape = shape.with_line_styles(&line_styles);
+        nine_slice.map_points(&mut shape);
+        let handle = if let Some((_, handle)) = *cached {
+            renderer.replace_shape(shape, bitmap_source, handle);
+            handle
+        } else {
+            renderer.register_shape(shape, bitmap_source)
+        };
+        *cached = Some((Some(*nine_slice), handle));
+        handle
+    }
+
+    /// Marks the tessellated shape as stale after the shape has changed.
+    pub fn invalidate(&self) {
+        if let Some((nine_slice, _)) = &mut *self.handle.borrow_mut() {
+            *nine_slice = None;
+        }
+    }
+}
+
 /// `DrawCommands` trace the outline of a path.
 /// Fills follow the even-odd fill rule, with opposite winding for holes.
 #[derive(Debug, PartialEq, Clone)]
@@ -714,6 +1316,236 @@ mod tests {
         }];
         assert_eq!(commands, expected);
     }
+
+    /// Two overlapping squares drawn in the same direction.
+    fn overlapping_squares() -> Vec<ShapeRecord> {
+        let square = |x: f64, y: f64| {
+            vec![
+                ShapeRecord::StyleChange(Box::new(swf::StyleChangeData {
+                    move_to: Some((Twips::from_pixels(x), Twips::from_pixels(y))),
+                    fill_style_0: None,
+                    fill_style_1: Some(1),
+                    line_style: None,
+                    new_styles: None,
+                })),
+                ShapeRecord::StraightEdge {
+                    delta_x: Twips::from_pixels(100.0),
+                    delta_y: Twips::from_pixels(0.0),
+                },
+                ShapeRecord::StraightEdge {
+                    delta_x: Twips::from_pixels(0.0),
+                    delta_y: Twips::from_pixels(100.0),
+                },
+                ShapeRecord::StraightEdge {
+                    delta_x: Twips::from_pixels(-100.0),
+                    delta_y: Twips::from_pixels(0.0),
+                },
+                ShapeRecord::StraightEdge {
+                    delta_x: Twips::from_pixels(0.0),
+                    delta_y: Twips::from_pixels(-100.0),
+                },
+            ]
+        };
+        let mut records = square(0.0, 0.0);
+        records.extend(square(50.0, 50.0));
+        records
+    }
+
+    /// The overlap of two squares is a hole with even-odd winding, but filled with non-zero winding.
+    #[test]
+    fn hit_test_winding_rule() {
+        let point = |x: f64, y: f64| (Twips::from_pixels(x), Twips::from_pixels(y));
+        let matrix = Matrix::default();
+
+        let mut shape = build_shape(overlapping_squares());
+        assert!(shape_hit_test(&shape, point(25.0, 25.0), &matrix));
+        assert!(!shape_hit_test(&shape, point(75.0, 75.0), &matrix));
+        assert!(!shape_hit_test(&shape, point(175.0, 25.0), &matrix));
+
+        shape.has_fill_winding_rule = true;
+        assert!(shape_hit_test(&shape, point(25.0, 25.0), &matrix));
+        assert!(shape_hit_test(&shape, point(75.0, 75.0), &matrix));
+        assert!(!shape_hit_test(&shape, point(175.0, 25.0), &matrix));
+    }
+
+    fn line_style(width: f64) -> LineStyle {
+        LineStyle {
+            allow_scale_x: true,
+            allow_scale_y: true,
+            ..LineStyle::new_v1(Twips::from_pixels(width), swf::Color::from_rgb(0, 255))
+        }
+    }
+
+    fn commands(points: &[(f64, f64)]) -> Vec<DrawCommand> {
+        points
+            .iter()
+            .enumerate()
+            .map(|(i, &(x, y))| {
+                let (x, y) = (Twips::from_pixels(x), Twips::from_pixels(y));
+                if i == 0 {
+                    DrawCommand::MoveTo { x, y }
+                } else {
+                    DrawCommand::LineTo { x, y }
+                }
+            })
+            .collect()
+    }
+
+    /// The ends of an open stroke have caps of the line style.
+    #[test]
+    fn hit_test_stroke_caps() {
+        let point = |x: f64, y: f64| (Twips::from_pixels(x), Twips::from_pixels(y));
+        let matrix = Matrix::default();
+        let line = commands(&[(0.0, 0.0), (100.0, 0.0)]);
+        let hit_test = |cap, (x, y)| {
+            let style = LineStyle {
+                start_cap: cap,
+                end_cap: cap,
+                ..line_style(10.0)
+            };
+            draw_command_stroke_hit_test(&line, &style, point(x, y), &matrix)
+        };
+
+        assert!(hit_test(swf::LineCapStyle::None, (50.0, 4.0)));
+        assert!(!hit_test(swf::LineCapStyle::None, (103.0, 0.0)));
+        assert!(hit_test(swf::LineCapStyle::Round, (103.0, 0.0)));
+        assert!(!hit_test(swf::LineCapStyle::Round, (104.0, 4.0)));
+        assert!(hit_test(swf::LineCapStyle::Square, (104.0, 4.0)));
+        assert!(hit_test(swf::LineCapStyle::Square, (-4.0, -4.0)));
+        assert!(!hit_test(swf::LineCapStyle::Square, (106.0, 0.0)));
+    }
+
+    /// The outside corner between two edges has the join of the line style.
+    #[test]
+    fn hit_test_stroke_joins() {
+        let point = (Twips::from_pixels(104.0), Twips::from_pixels(-4.0));
+        let matrix = Matrix::default();
+        let corner = commands(&[(0.0, 0.0), (100.0, 0.0), (100.0, 100.0)]);
+        let hit_test = |join_style| {
+            let style = LineStyle {
+                join_style,
+                ..line_style(10.0)
+            };
+            draw_command_stroke_hit_test(&corner, &style, point, &matrix)
+        };
+
+        assert!(!hit_test(swf::LineJoinStyle::Round));
+        assert!(!hit_test(swf::LineJoinStyle::Bevel));
+        assert!(hit_test(swf::LineJoinStyle::Miter(swf::Fixed8::from_f32(
+            3.0
+        ))));
+        // The miter is clipped 1 half width from the corner.
+        assert!(!hit_test(swf::LineJoinStyle::Miter(swf::Fixed8::ONE)));
+    }
+
+    /// The corners of a 9-slice grid keep their size, and shrink only if they don't fit.
+    #[test]
+    fn nine_slice_axis() {
+        let axis = NineSliceAxis::new((0.0, 100.0), (10.0, 90.0), 2.0);
+        assert_eq!(axis.map(0.0), 0.0);
+        assert_eq!(axis.map(10.0), 5.0);
+        assert_eq!(axis.map(50.0), 50.0);
+        assert_eq!(axis.map(90.0), 95.0);
+        assert_eq!(axis.map(100.0), 100.0);
+
+        let axis = NineSliceAxis::new((0.0, 100.0), (10.0, 90.0), 0.1);
+        assert_eq!(axis.map(5.0), 25.0);
+        assert_eq!(axis.map(10.0), 50.0);
+        assert_eq!(axis.map(90.0), 50.0);
+        assert_eq!(axis.map(100.0), 100.0);
+    }
+
+    /// The shapes of the children of a display object with a grid are moved in the space of
+    /// the grid, and only if the display object is scaled.
+    #[test]
+    fn scaling_grid_nine_slice() {
+        let rect = |min: f64, max: f64| BoundingBox {
+            x_min: Twips::from_pixels(min),
+            y_min: Twips::from_pixels(min),
+            x_max: Twips::from_pixels(max),
+            y_max: Twips::from_pixels(max),
+            valid: true,
+        };
+        let to_grid = Matrix::translate(Twips::from_pixels(50.0), Twips::ZERO);
+
+        let grid = ScalingGrid::new(rect(10.0, 90.0), rect(0.0, 100.0), Matrix::IDENTITY);
+        assert_eq!(grid.nine_slice(to_grid), None);
+
+        let grid = ScalingGrid::new(rect(10.0, 90.0), rect(0.0, 100.0), Matrix::scale(2.0, 1.0));
+        let nine_slice = grid.nine_slice(to_grid).unwrap();
+        let style = FillStyle::Color(swf::Color::from_rgb(0, 255));
+        let mut shape = DistilledShape {
+            paths: vec![DrawPath::Fill {
+                style: &style,
+                commands: commands(&[(0.0, 0.0), (40.0, 0.0), (-50.0, 100.0)]),
+            }],
+            shape_bounds: BoundingBox::default(),
+            edge_bounds: BoundingBox::default(),
+            id: 0,
+        };
+        nine_slice.map_points(&mut shape);
+        match &shape.paths[0] {
+            DrawPath::Fill {
+                commands: mapped, ..
+            } => assert_eq!(
+                mapped,
+                &commands(&[(0.0, 0.0), (45.0, 0.0), (-50.0, 100.0)])
+            ),
+            DrawPath::Stroke { .. } => unreachable!(),
+        }
+    }
+
+    /// Strokes are at least 1 pixel wide, and may not scale with the shape.
+    #[test]
+    fn hit_test_stroke_width() {
+        let point = |y: i32| (Twips::new(500), Twips::new(y));
+        let line = commands(&[(0.0, 0.0), (100.0, 0.0)]);
+
+        // The shape is scaled up by 4x, so the point is 20 twips from the line on screen.
+        let local_matrix = Matrix::scale(0.25, 0.25);
+        assert!(draw_command_stroke_hit_test(
+            &line,
+            &line_style(1.0),
+            point(5),
+            &local_matrix
+        ));
+        let non_scaling = LineStyle {
+            allow_scale_x: false,
+            allow_scale_y: false,
+            ..line_style(1.0)
+        };
+        assert!(!draw_command_stroke_hit_test(
+            &line,
+            &non_scaling,
+            point(5),
+            &local_matrix
+        ));
+        assert!(draw_command_stroke_hit_test(
+            &line,
+            &non_scaling,
+            point(2),
+            &local_matrix
+        ));
+
+        // The shape is scaled down by 2x, but a hairline is still 1 pixel wide on screen.
+        let local_matrix = Matrix::scale(2.0, 2.0);
+        let hairline = LineStyle {
+            width: Twips::new(1),
+            ..line_style(1.0)
+        };
+        assert!(draw_command_stroke_hit_test(
+            &line,
+            &hairline,
+            point(15),
+            &local_matrix
+        ));
+        assert!(!draw_command_stroke_hit_test(
+            &line,
+            &hairline,
+            point(25),
+            &local_matrix
+        ));
+    }
 }
 
 /* SHAPEFLAG HITTEST (point-in-contour)
@@ -733,10 +1565,15 @@ mod tests {
  * We ignore any edges with fills on both sides (interior edges).
  *
  * If the final winding number is odd, then the point is inside the shape (for default even-odd winding).
+ * Shapes with the non-zero winding rule (DefineShape4) instead contain the point if the winding number is non-zero.
  *
- * For strokes, we calculate the distance to the line segment or curve and compare it to the stroke width.
- * Note that Flash renders with a minimum stroke width of 1px (20 twips) that we must account for.
- * TODO: We currently don't consider non-round endcaps or joins, or stroke scaling flags.
+ * For strokes, we follow each sub-path of the stroke (see `StrokeHitTest`):
+ *  the point is inside the body of an edge if it is within half of the stroke width of the edge,
+ *  measured perpendicular to the edge.
+ *  the vertices between edges are covered by the join of the line style.
+ *  the ends of open sub-paths are covered by the caps of the line style; closed sub-paths are joined instead.
+ * The width of the stroke accounts for the stroke scaling flags, pixel hinting, and the minimum width of
+ * 1px (20 twips) that Flash renders strokes with.
  */
 
 /// Test whether the given point in object space is contained within the contour of the given shape.
@@ -754,17 +1591,36 @@ pub fn shape_hit_test(
     let mut has_fill_style0: bool = false;
     let mut has_fill_style1: bool = false;
 
-    let min_width = stroke_minimum_width(local_matrix);
-    let mut stroke_width = None;
+    let mut stroke: Option<StrokeHitTest> = None;
     let mut line_styles = &shape.styles.line_styles;
 
+    let is_inside = |winding: i32| {
+        if shape.has_fill_winding_rule {
+            winding != 0
+        } else {
+            winding & 0b1 != 0
+        }
+    };
+
     for record in &shape.shape {
         match record {
             swf::ShapeRecord::StyleChange(style_change) => {
+                // A new line style or a move ends the current sub-path of the stroke.
+                let ends_sub_path = style_change.new_styles.is_some()
+                    || style_change.line_style.is_some()
+                    || style_change.move_to.is_some();
+                if ends_sub_path {
+                    if let Some(stroke) = &mut stroke {
+                        if stroke.finish() {
+                            return true;
+                        }
+                    }
+                }
+
                 // New styles indicates a new layer;
                 // Check if the point is within the current layer, then reset winding.
                 if let Some(new_styles) = &style_change.new_styles {
-                    if winding & 0b1 != 0 {
+                    if is_inside(winding) {
                         return true;
                     }
                     line_styles = &new_styles.line_styles;
@@ -783,18 +1639,18 @@ pub fn shape_hit_test(
                     has_fill_style1 = i > 0;
                 }
                 if let Some(i) = style_change.line_style {
-                    stroke_width = if i > 0 {
-                        // Flash renders strokes with a 1px minimum width.
-                        if let Some(line_style) = line_styles.get(i as usize - 1) {
-                            let width = line_style.width.get() as f64;
-                            let scaled_width = 0.5 * width.max(min_width);
-                            Some((scaled_width, scaled_width * scaled_width))
-                        } else {
-                            None
-                        }
-                    } else {
-                        None
-                    };
+                    stroke = i
+                        .checked_sub(1)
+                        .and_then(|i| line_styles.get(i as usize))
+                        .map(|line_style| {
+                            let width = hit_test_stroke_width(line_style, local_matrix);
+                            StrokeHitTest::new(line_style, width, (point_x, point_y))
+                        });
+                }
+                if ends_sub_path {
+                    if let Some(stroke) = &mut stroke {
+                        stroke.move_to((x, y));
+                    }
                 }
             }
             swf::ShapeRecord::StraightEdge { delta_x, delta_y } => {
@@ -809,10 +1665,8 @@ pub fn shape_hit_test(
                     winding += winding_number_line((point_x, point_y), (x1, y1), (x, y));
                 }
 
-                if let Some(width) = stroke_width {
-                    if hit_test_stroke((point_x, point_y), (x, y), (x1, y1), width) {
-                        return true;
-                    }
+                if let Some(stroke) = &mut stroke {
+                    stroke.line_to((x1, y1));
                 }
                 x = x1;
                 y = y1;
@@ -839,11 +1693,8 @@ pub fn shape_hit_test(
                     winding += winding_number_curve((point_x, point_y), (x2, y2), (x1, y1), (x, y));
                 }
 
-                if let Some(width) = stroke_width {
-                    if hit_test_stroke_curve((point_x, point_y), (x, y), (x1, y1), (x2, y2), width)
-                    {
-                        return true;
-                    }
+                if let Some(stroke) = &mut stroke {
+                    stroke.curve_to((x1, y1), (x2, y2));
                 }
 
                 x = x2;
@@ -851,7 +1702,12 @@ pub fn shape_hit_test(
             }
         }
     }
-    winding & 0b1 != 0
+    if let Some(stroke) = &mut stroke {
+        if stroke.finish() {
+            return true;
+        }
+    }
+    is_inside(winding)
 }
 
 /// Test whether the given point is contained within the paths specified by the draw commands.
@@ -886,151 +1742,262 @@ pub fn draw_command_fill_hit_test(commands: &[DrawCommand], test_point: (Twips,
 }
 
 /// Test whether the given point is contained within the strokes specified by the draw commands.
-/// local_matrix is used to calculate the minimum stroke width.
+/// local_matrix is used to calculate the width of the stroke.
 pub fn draw_command_stroke_hit_test(
     commands: &[DrawCommand],
-    stroke_width: Twips,
-    (point_x, point_y): (Twips, Twips),
+    line_style: &LineStyle,
+    point: (Twips, Twips),
     local_matrix: &Matrix,
 ) -> bool {
-    let stroke_min_width = stroke_minimum_width(local_matrix);
-    let stroke_width = 0.5 * f64::max(stroke_width.get().into(), stroke_min_width);
-    let stroke_widths = (stroke_width, stroke_width * stroke_width);
-    let mut x = Twips::default();
-    let mut y = Twips::default();
+    let width = hit_test_stroke_width(line_style, local_matrix);
+    let mut stroke = StrokeHitTest::new(line_style, width, point);
     for command in commands {
         match *command {
-            DrawCommand::MoveTo { x: x1, y: y1 } => {
-                x = x1;
-                y = y1;
-            }
-            DrawCommand::LineTo { x: x1, y: y1 } => {
-                if hit_test_stroke((point_x, point_y), (x, y), (x1, y1), stroke_widths) {
-                    return true;
-                }
-                x = x1;
-                y = y1;
-            }
-            DrawCommand::CurveTo { x1, y1, x2, y2 } => {
-                if hit_test_stroke_curve(
-                    (point_x, point_y),
-                    (x, y),
-                    (x1, y1),
-                    (x2, y2),
-                    stroke_widths,
-                ) {
+            DrawCommand::MoveTo { x, y } => {
+                if stroke.finish() {
                     return true;
                 }
-                x = x2;
-                y = y2;
+                stroke.move_to((x, y));
             }
+            DrawCommand::LineTo { x, y } => stroke.line_to((x, y)),
+            DrawCommand::CurveTo { x1, y1, x2, y2 } => stroke.curve_to((x1, y1), (x2, y2)),
         }
     }
+    stroke.finish()
+}
 
-    false
+type Vector = (f64, f64);
+
+fn to_vector((x, y): (Twips, Twips)) -> Vector {
+    (x.get().into(), y.get().into())
 }
 
-/// Given a matrix, calculates the scale for stroke widths.
-/// TODO: Verify the actual behavior; I think it's more like the average between scaleX and scaleY.
-/// Does not yet support vertical/horizontal stroke scaling flags.
-/// This might be better to add as a method to Matrix.
-fn stroke_minimum_width(matrix: &Matrix) -> f64 {
-    let sx = (matrix.a * matrix.a + matrix.b * matrix.b).sqrt();
-    let sy = (matrix.c * matrix.c + matrix.d * matrix.d).sqrt();
-    let scale: f64 = sx.max(sy).into();
-    20.0 * scale
+fn sub(a: Vector, b: Vector) -> Vector {
+    (a.0 - b.0, a.1 - b.1)
 }
 
-/// Returns whether the given point is inside the stroked line segment.
-/// `width_sq` should be the squared width of the stroke.
-fn hit_test_stroke(
-    (point_x, point_y): (Twips, Twips),
-    (x0, y0): (Twips, Twips),
-    (x1, y1): (Twips, Twips),
-    (stroke_width, stroke_width_sq): (f64, f64),
-) -> bool {
-    let px = point_x.get() as f64;
-    let py = point_y.get() as f64;
-    let x0 = x0.get() as f64;
-    let y0 = y0.get() as f64;
-    let x1 = x1.get() as f64;
-    let y1 = y1.get() as f64;
+fn dot(a: Vector, b: Vector) -> f64 {
+    a.0 * b.0 + a.1 * b.1
+}
 
-    // Early exit: out of bounds
-    let x_min = x0.min(x1);
-    let x_max = x0.max(x1);
-    if px < x_min - stroke_width || px > x_max + stroke_width {
-        return false;
+fn cross(a: Vector, b: Vector) -> f64 {
+    a.0 * b.1 - a.1 * b.0
+}
+
+/// The vector scaled to a length of 1, or `None` if it has no length.
+fn normalize(v: Vector) -> Option<Vector> {
+    let length = v.0.hypot(v.1);
+    (length > 0.0).then(|| (v.0 / length, v.1 / length))
+}
+
+/// Whether the point is inside of the convex polygon with the given corners, in either winding order.
+fn is_in_convex_polygon(point: Vector, corners: &[Vector]) -> bool {
+    let (mut has_left, mut has_right) = (false, false);
+    for (i, &a) in corners.iter().enumerate() {
+        let b = corners[(i + 1) % corners.len()];
+        let side = cross(sub(b, a), sub(point, a));
+        has_left |= side > 0.0;
+        has_right |= side < 0.0;
     }
-    let y_min = y0.min(y1);
-    let y_max = y0.max(y1);
-    if py < y_min - stroke_width || py > y_max + stroke_width {
-        return false;
+    !(has_left && has_right)
+}
+
+/// Tests whether a point is inside of a stroke, following the path of the stroke one edge at
+/// a time. The stroke has the caps and joins of its line style.
+struct StrokeHitTest<'a> {
+    style: &'a LineStyle,
+    point: Vector,
+
+    /// Half of the width of the stroke.
+    radius: f64,
+
+    /// The start of the current sub-path.
+    start: Vector,
+
+    /// The direction that the current sub-path starts in, or `None` if it has no edges yet.
+    start_direction: Option<Vector>,
+
+    /// The end of the last edge.
+    cursor: Vector,
+
+    /// The direction at the end of the last edge, or `None` if the sub-path has no edges yet.
+    direction: Option<Vector>,
+
+    is_hit: bool,
+}
+
+impl<'a> StrokeHitTest<'a> {
+    /// Tests the given point against a stroke of the given style and width in twips.
+    fn new(style: &'a LineStyle, width: f64, point: (Twips, Twips)) -> Self {
+        Self {
+            style,
+            point: to_vector(point),
+            radius: width / 2.0,
+            start: (0.0, 0.0),
+            start_direction: None,
+            cursor: (0.0, 0.0),
+            direction: None,
+            is_hit: false,
+        }
     }
 
-    // AB is the segment from (x0, y0) to (x1, y1) and P is (point_x, point_y).
-    //  P
-    //   .
-    //    .
-    //     A----->B
-    // If AP dot AB is <= 0.0, then PA is pointing away from AB, so A is the closest point.
-    let abx = x1 - x0;
-    let aby = y1 - y0;
-    let apx = px - x0;
-    let apy = py - y0;
-    let dot_a = abx * apx + aby * apy;
-    let dist = if dot_a <= 0.0 {
-        apx * apx + apy * apy
-    } else {
-        // If BP dot AB is >= 0.0, then BP is pointing away from BA, so B is the closest point.
-        let bpx = px - x1;
-        let bpy = py - y1;
-        let dot_b = abx * bpx + aby * bpy;
-        if dot_b >= 0.0 {
-            bpx * bpx + bpy * bpy
-        } else {
-            // Otherwise, the closest point will be within the interval of the segment.
-            // Project the point onto the segment.
-            let len = abx * abx + aby * aby;
-            let ex = apx - dot_a * abx / len;
-            let ey = apy - dot_a * aby / len;
-            ex * ex + ey * ey
+    /// Starts a new sub-path. `finish` should be called first to finish the current sub-path.
+    fn move_to(&mut self, point: (Twips, Twips)) {
+        self.start = to_vector(point);
+        self.cursor = self.start;
+        self.start_direction = None;
+        self.direction = None;
+    }
+
+    fn line_to(&mut self, end: (Twips, Twips)) {
+        let end = to_vector(end);
+        let edge = sub(end, self.cursor);
+        let direction = match normalize(edge) {
+            Some(direction) => direction,
+            None => return,
+        };
+        self.begin_edge(direction);
+
+        if !self.is_hit {
+            let offset = sub(self.point, self.cursor);
+            let along = dot(offset, direction);
+            self.is_hit = along >= 0.0
+                && along <= dot(edge, direction)
+                && cross(direction, offset).abs() <= self.radius;
         }
-    };
 
-    dist <= stroke_width_sq
+        self.cursor = end;
+        self.direction = Some(direction);
+    }
+
+    fn curve_to(&mut self, control: (Twips, Twips), anchor: (Twips, Twips)) {
+        let (control, anchor) = (to_vector(control), to_vector(anchor));
+        // The tangents at the ends of the curve, which are the direction of the chord when the
+        // control point is on an end.
+        let chord = normalize(sub(anchor, self.cursor));
+        let start_direction = normalize(sub(control, self.cursor)).or(chord);
+        let end_direction = normalize(sub(anchor, control)).or(chord);
+        let (start_direction, end_direction) = match (start_direction, end_direction) {
+            (Some(start_direction), Some(end_direction)) => (start_direction, end_direction),
+            _ => return,
+        };
+        self.begin_edge(start_direction);
+
+        if !self.is_hit {
+            self.is_hit =
+                hit_test_curve_body(self.point, self.cursor, control, anchor, self.radius);
+        }
+
+        self.cursor = anchor;
+        self.direction = Some(end_direction);
+    }
+
+    /// Finishes the current sub-path with caps, or a join if it is closed, and returns whether
+    /// the point is inside of any part of the stroke so far.
+    fn finish(&mut self) -> bool {
+        if let (Some(start_direction), Some(direction)) = (self.start_direction, self.direction) {
+            if self.cursor == self.start {
+                self.test_join(self.start, direction, start_direction);
+            } else {
+                self.test_cap(
+                    self.start,
+                    (-start_direction.0, -start_direction.1),
+                    self.style.start_cap,
+                );
+                self.test_cap(self.cursor, direction, self.style.end_cap);
+            }
+        }
+        self.start_direction = None;
+        self.direction = None;
+        self.is_hit
+    }
+
+    /// Joins the previous edge to an edge starting in the given direction.
+    fn begin_edge(&mut self, direction: Vector) {
+        match self.direction {
+            Some(previous) => self.test_join(self.cursor, previous, direction),
+            None => self.start_direction = Some(direction),
+        }
+    }
+
+    /// Tests the join at a vertex between an edge ending in direction `a` and an edge
+    /// starting in direction `b`.
+    fn test_join(&mut self, vertex: Vector, a: Vector, b: Vector) {
+        if self.is_hit {
+            return;
+        }
+        let offset = sub(self.point, vertex);
+        let radius = self.radius;
+        if self.style.join_style == swf::LineJoinStyle::Round {
+            self.is_hit = dot(offset, offset) <= radius * radius;
+            return;
+        }
+
+        // The join fills the gap on the outside of the turn between the two edges.
+        let side = if cross(a, b) > 0.0 { -radius } else { radius };
+        let outer_a = (-a.1 * side, a.0 * side);
+        let outer_b = (-b.1 * side, b.0 * side);
+        let bevel = [(0.0, 0.0), outer_a, outer_b];
+        let miter = match self.style.join_style {
+            swf::LineJoinStyle::Miter(limit) => {
+                normalize((outer_a.0 + outer_b.0, outer_a.1 + outer_b.1))
+                    .filter(|bisector| dot(*bisector, outer_a) > 0.0)
+                    .map(|bisector| (bisector, f64::from(limit.to_f32())))
+            }
+            _ => None,
+        };
+        self.is_hit = if let Some((bisector, limit)) = miter {
+            // The tip of the miter, where the outer sides of the edges meet. The miter is clipped
+            // at the miter limit, which is the distance from the vertex in half stroke widths.
+            let tip_distance = radius * radius / dot(bisector, outer_a);
+            let tip = (bisector.0 * tip_distance, bisector.1 * tip_distance);
+            is_in_convex_polygon(offset, &[(0.0, 0.0), outer_a, tip, outer_b])
+                && dot(offset, bisector) <= limit * radius
+                || is_in_convex_polygon(offset, &bevel)
+        } else {
+            is_in_convex_polygon(offset, &bevel)
+        };
+    }
+
+    /// Tests the cap at the end of a sub-path, which leaves the path in the given direction.
+    fn test_cap(&mut self, end: Vector, direction: Vector, cap: swf::LineCapStyle) {
+        if self.is_hit {
+            return;
+        }
+        let offset = sub(self.point, end);
+        let radius = self.radius;
+        self.is_hit = match cap {
+            swf::LineCapStyle::None => false,
+            swf::LineCapStyle::Round => dot(offset, offset) <= radius * radius,
+            swf::LineCapStyle::Square => {
+                let along = dot(offset, direction);
+                along >= 0.0 && along <= radius && cross(direction, offset).abs() <= radius
+            }
+        };
+    }
 }
 
-/// Returns whether the given point is inside the stroked bezier curve.
-/// `width_sq` should be the squared width of the stroke.
-fn hit_test_stroke_curve(
-    (point_x, point_y): (Twips, Twips),
-    (x0, y0): (Twips, Twips),
-    (x1, y1): (Twips, Twips),
-    (x2, y2): (Twips, Twips),
-    (stroke_width, stroke_width_sq): (f64, f64),
+/// Returns whether the given point is inside the body of a stroked bezier curve, which is the
+/// part of the stroke that is perpendicular to the curve, excluding the caps or joins at its ends.
+fn hit_test_curve_body(
+    (px, py): Vector,
+    (x0, y0): Vector,
+    (x1, y1): Vector,
+    (x2, y2): Vector,
+    radius: f64,
 ) -> bool {
-    let px = point_x.get() as f64;
-    let py = point_y.get() as f64;
-    let x0 = x0.get() as f64;
-    let y0 = y0.get() as f64;
-    let x1 = x1.get() as f64;
-    let y1 = y1.get() as f64;
-    let x2 = x2.get() as f64;
-    let y2 = y2.get() as f64;
-
     // Early exit: out of bounds
     // TODO: Since this involves an expensive cubic, probably wortwhile to calculate the tight bounds for the curve:
     // https://www.iquilezles.org/www/articles/bezierbbox/bezierbbox.htm
     let x_min = x0.min(x1).min(x2);
     let x_max = x0.max(x1).max(x2);
-    if px < x_min - stroke_width || px > x_max + stroke_width {
+    if px < x_min - radius || px > x_max + radius {
         return false;
     }
 
     let y_min = y0.min(y1).min(y2);
     let y_max = y0.max(y1).max(y2);
-    if py < y_min - stroke_width || py > y_max + stroke_width {
+    if py < y_min - radius || py > y_max + radius {
         return false;
     }
 
@@ -1040,8 +2007,8 @@ fn hit_test_stroke_curve(
     // Dot product to determine when we are perpendicular to the tangent.
     // (point - C(t)) . C'(t) = 0
     // The result is a cubic polynomial that we can solve for.
-    // After solving this polynomial, we choose the t with [0, 1.0] that gives us the minimum distance
-    // (also considering the endcaps).
+    // After solving this polynomial, we choose the t with [0, 1.0] that gives us the minimum distance.
+    // The ends of the curve are covered by caps or joins instead.
     // via http://blog.gludion.com/2009/08/distance-to-quadratic-bezier-curve.html
 
     let ax = x1 - x0;
@@ -1066,18 +2033,9 @@ fn hit_test_stroke_curve(
         dx * dx + dy * dy
     };
 
-    // Test end-caps
-    let mut dist = distance_to_curve(0.0);
-    dist = dist.min(distance_to_curve(1.0));
-
-    // Test roots.
-    for t in solve_cubic(a, b, c, d) {
-        if t >= 0.0 && t <= 1.0 {
-            dist = dist.min(distance_to_curve(t));
-        }
-    }
-
-    dist <= stroke_width_sq
+    solve_cubic(a, b, c, d)
+        .into_iter()
+        .any(|t| (0.0..=1.0).contains(&t) && distance_to_curve(t) <= radius * radius)
 }
 
 /// Calculates the winding number for a line segment relative to the given point.
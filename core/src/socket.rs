@@ -0,0 +1,187 @@
+//! Socket connections opened by `XMLSocket`.
+
+use crate::avm1::Object as Avm1Object;
+use crate::backend::navigator::{SocketEvent, SocketHandle};
+use crate::context::{ActionType, UpdateContext};
+use crate::streams::net_connection;
+use crate::string::AvmString;
+use gc_arena::Collect;
+
+/// A connection belonging to an AVM1 `XMLSocket` object.
+#[derive(Collect)]
+#[collect(no_drop)]
+struct XmlSocket<'gc> {
+    #[collect(require_static)]
+    handle: SocketHandle,
+
+    /// The `XMLSocket` that receives events for this connection.
+    object: Avm1Object<'gc>,
+
+    /// Received data that hasn't been terminated by a null byte yet.
+    buffer: Vec<u8>,
+}
+
+/// Manager of open `XMLSocket` connections.
+#[derive(Collect, Default)]
+#[collect(no_drop)]
+pub struct Sockets<'gc> {
+    sockets: Vec<XmlSocket<'gc>>,
+}
+
+impl<'gc> Sockets<'gc> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start connecting `object` to the given host and port, closing any
+    /// connection it already has.
+    ///
+    /// Returns `false` if the connection couldn't be started at all.
+    pub fn connect(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        object: Avm1Object<'gc>,
+        host: &str,
+        port: u16,
+    ) -> bool {
+        Self::close(context, object);
+
+        match context.navigator.connect_socket(host, port) {
+            Some(handle) => {
+                context.sockets.sockets.push(XmlSocket {
+                    handle,
+                    object,
+                    buffer: Vec::new(),
+                });
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Send a message over the connection of `object`.
+    ///
+    /// As in Flash Player, each message is terminated by a null byte.
+    pub fn send(context: &mut UpdateContext<'_, 'gc, '_>, object: Avm1Object<'gc>, message: &[u8]) {
+        if let Some(socket) = context.sockets.find(object) {
+            let mut data = message.to_vec();
+            data.push(0);
+            context.navigator.send_socket(socket.handle, data);
+        }
+    }
+
+    /// Close the connection of `object`, if it has one.
+    pub fn close(context: &mut UpdateContext<'_, 'gc, '_>, object: Avm1Object<'gc>) {
+        if let Some(index) = context
+            .sockets
+            .sockets
+            .iter()
+            .position(|socket| Avm1Object::ptr_eq(socket.object, object))
+        {
+            let socket = context.sockets.sockets.remove(index);
+            context.navigator.close_socket(socket.handle);
+        }
+    }
+
+    fn find(&self, object: Avm1Object<'gc>) -> Option<&XmlSocket<'gc>> {
+        self.sockets
+            .iter()
+            .find(|socket| Avm1Object::ptr_eq(socket.object, object))
+    }
+
+    /// Deliver the events that happened on open sockets to their `XMLSocket` objects.
+    pub fn update(context: &mut UpdateContext<'_, 'gc, '_>) {
+        for event in context.navigator.take_socket_events() {
+            let handle = match event {
+                SocketEvent::Connected(handle, _)
+                | SocketEvent::Data(handle, _)
+                | SocketEvent::Closed(handle) => handle,
+            };
+            let index = match context
+                .sockets
+                .sockets
+                .iter()
+                .position(|socket| socket.handle == handle)
+            {
+                Some(index) => index,
+                None => {
+                    // Sockets of `NetConnection`s are handled with their streams.
+                    if net_connection::owns_socket(context, handle) {
+                        context.stream_manager.push_socket_event(event);
+                    }
+                    // Otherwise, the socket has been closed by content.
+                    continue;
+                }
+            };
+            let object = context.sockets.sockets[index].object;
+
+            match event {
+                SocketEvent::Connected(_, success) => {
+                    if !success {
+                        context.sockets.sockets.remove(index);
+                    }
+                    Self::queue_method(context, object, "onConnect", vec![success.into()]);
+                }
+                SocketEvent::Data(_, data) => {
+                    let messages =
+                        split_messages(&mut context.sockets.sockets[index].buffer, &data);
+                    for message in messages {
+                        let message = AvmString::new_utf8(
+                            context.gc_context,
+                            String::from_utf8_lossy(&message),
+                        );
+                        Self::queue_method(context, object, "onData", vec![message.into()]);
+                    }
+                }
+                SocketEvent::Closed(_) => {
+                    context.sockets.sockets.remove(index);
+                    Self::queue_method(context, object, "onClose", vec![]);
+                }
+            }
+        }
+    }
+
+    fn queue_method(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        object: Avm1Object<'gc>,
+        name: &'static str,
+        args: Vec<crate::avm1::Value<'gc>>,
+    ) {
+        context.action_queue.queue_actions(
+            context.stage.root_clip(),
+            ActionType::Method { object, name, args },
+            false,
+        );
+    }
+}
+
+/// Append received `data` to `buffer`, and return the messages that are now
+/// complete.
+///
+/// Messages are terminated by a null byte, which is not included.
+fn split_messages(buffer: &mut Vec<u8>, data: &[u8]) -> Vec<Vec<u8>> {
+    buffer.extend_from_slice(data);
+
+    let mut messages = Vec::new();
+    while let Some(end) = buffer.iter().position(|&byte| byte == 0) {
+        let rest = buffer.split_off(end + 1);
+        buffer.pop();
+        messages.push(std::mem::replace(buffer, rest));
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_framing() {
+        let mut buffer = Vec::new();
+        assert!(split_messages(&mut buffer, b"<a/").is_empty());
+        assert_eq!(
+            split_messages(&mut buffer, b">\0<b/>\0\0<c"),
+            vec![b"<a/>".to_vec(), b"<b/>".to_vec(), vec![]]
+        );
+        assert_eq!(buffer, b"<c");
+    }
+}
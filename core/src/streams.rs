@@ -0,0 +1,1258 @@
+//! Playback of external media files, as done by `NetStream`.
+
+use crate::avm1::activation::{Activation as Avm1Activation, ActivationIdentifier};
+use crate::avm1::globals::shared_object::deserialize_value;
+use crate::avm1::property::Attribute;
+use crate::avm1::{Object as Avm1Object, ScriptObject, TObject};
+use crate::backend::audio::decoders::aac::AudioSpecificConfig;
+use crate::backend::audio::{SoundHandle, SoundInstanceHandle};
+use crate::backend::navigator::{RequestOptions, SocketEvent};
+use crate::backend::render::BitmapInfo;
+use crate::backend::video::{EncodedFrame, VideoStreamHandle, VideoStreamStats};
+use crate::context::{ActionType, UpdateContext};
+use flash_lso::types::{Element, Value as AmfValue};
+use gc_arena::{Collect, GcCell, MutationContext};
+use std::collections::BTreeSet;
+use swf::{AudioCompression, SoundEvent, SoundFormat, SoundInfo, VideoCodec, VideoDeblocking};
+
+pub mod flv;
+pub mod mp4;
+pub mod net_connection;
+pub mod rtmp;
+
+/// A stream of audio and video that is played back independently from the
+/// timeline, such as an externally loaded FLV file.
+#[derive(Clone, Copy, Debug, Collect)]
+#[collect(no_drop)]
+pub struct NetStream<'gc>(GcCell<'gc, NetStreamData<'gc>>);
+
+#[derive(Debug, Collect)]
+#[collect(no_drop)]
+pub struct NetStreamData<'gc> {
+    /// The AVM1 object that receives `onStatus` calls.
+    avm_object: Option<Avm1Object<'gc>>,
+
+    /// How many seconds of media must be buffered before playback starts.
+    buffer_time: f64,
+
+    /// Whether the playhead is waiting for the buffer to fill up.
+    #[collect(require_static)]
+    buffer_state: BufferState,
+
+    /// Whether the media file has been loaded and parsed yet.
+    loaded: bool,
+
+    /// Whether the media file has been loaded in its entirety.
+    load_complete: bool,
+
+    /// Whether the playhead is advancing.
+    playing: bool,
+
+    /// The position of the playhead, in milliseconds.
+    time: f64,
+
+    /// The timestamp up to which media has been loaded, in milliseconds.
+    buffered_until: f64,
+
+    /// The video frames of the stream, in decoding order.
+    #[collect(require_static)]
+    video_frames: Vec<StreamFrame>,
+
+    /// The decoder stream of the video frames.
+    #[collect(require_static)]
+    video_stream: Option<VideoStreamHandle>,
+
+    /// The indices of the video frames that can be decoded on their own.
+    #[collect(require_static)]
+    keyframes: BTreeSet<u32>,
+
+    /// The index of the next frame in `video_frames` to decode.
+    next_frame: usize,
+
+    /// The last decoded video frame.
+    #[collect(require_static)]
+    decoded_frame: Option<BitmapInfo>,
+
+    /// The number of video frames decoded per second, as measured over the
+    /// last second of playback.
+    current_fps: f64,
+
+    /// The playhead position and the number of frames decoded by the video
+    /// backend when the current second of playback began.
+    fps_sample_start: (f64, u32),
+
+    /// All audio of the stream, registered as one sound.
+    #[collect(require_static)]
+    sound: Option<SoundHandle>,
+
+    /// The playing instance of `sound`.
+    #[collect(require_static)]
+    sound_instance: Option<SoundInstanceHandle>,
+
+    /// Whether media is received as it is played, instead of being loaded
+    /// as a whole file.
+    live: bool,
+
+    /// Audio of a live stream that hasn't been decoded yet.
+    #[collect(require_static)]
+    live_audio: Option<LiveAudio>,
+
+    /// The dynamic sound that plays the audio of a live stream.
+    #[collect(require_static)]
+    live_sound_instance: Option<SoundInstanceHandle>,
+
+    /// The format of the AAC audio of a live stream, once it is known.
+    #[collect(require_static)]
+    live_aac_config: Option<AudioSpecificConfig>,
+}
+
+impl<'gc> NetStreamData<'gc> {
+    /// Forget about the current media file, keeping only the settings of
+    /// the stream.
+    fn reset(&mut self) -> Self {
+        let settings = Self {
+            avm_object: self.avm_object,
+            buffer_time: self.buffer_time,
+            ..Self::default()
+        };
+        std::mem::replace(self, settings)
+    }
+
+    /// Decode all video frames up to the position of the playhead.
+    fn decode_due_frames(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        stream: VideoStreamHandle,
+    ) {
+        while let Some(frame) = self.video_frames.get(self.next_frame) {
+            if f64::from(frame.timestamp) > self.time {
+                break;
+            }
+
+            let encoded_frame = EncodedFrame {
+                codec: frame.codec,
+                data: &frame.data,
+                frame_id: self.next_frame as u32,
+            };
+            match context
+                .video
+                .decode_video_stream_frame(stream, encoded_frame, context.renderer)
+            {
+                Ok(bitmap) => {
+                    self.decoded_frame = Some(bitmap);
+                    *context.needs_render = true;
+                }
+                Err(e) => log::error!("Got error when decoding NetStream video frame: {}", e),
+            }
+            // Frames of live streams are never seeked back to.
+            if self.live {
+                self.video_frames[self.next_frame].data = Vec::new();
+            }
+            self.next_frame += 1;
+        }
+    }
+
+    /// Update `current_fps` once another second of playback has passed.
+    fn measure_fps(&mut self, stats: VideoStreamStats) {
+        let (start_time, start_frames) = self.fps_sample_start;
+        let elapsed = self.time - start_time;
+        if elapsed >= 1000.0 {
+            let frames = stats.decoded_frames.saturating_sub(start_frames);
+            self.current_fps = f64::from(frames) * 1000.0 / elapsed;
+            self.fps_sample_start = (self.time, stats.decoded_frames);
+        }
+    }
+
+    /// Account for a packet of a live stream that starts at `timestamp`
+    /// milliseconds.
+    fn receive_live_packet(&mut self, timestamp: u32) {
+        // Servers don't necessarily start the timestamps of a stream at zero.
+        if self.time == 0.0 && self.buffered_until == 0.0 {
+            self.time = timestamp.into();
+        }
+        self.buffered_until = self.buffered_until.max(timestamp.into());
+    }
+
+    /// Decode the collected audio of a live stream, and queue it for playback.
+    fn play_live_audio(&mut self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let LiveAudio {
+            format,
+            data: packets,
+            start,
+            end,
+        } = match self.live_audio.take() {
+            Some(live_audio) => live_audio,
+            None => return,
+        };
+
+        // MP3 sounds start with the number of samples to skip.
+        let mut data = match format.compression {
+            AudioCompression::Mp3 => vec![0, 0],
+            _ => vec![],
+        };
+        data.extend_from_slice(&packets);
+        let sound = swf::Sound {
+            id: 0,
+            num_samples: num_samples(&format, &data, end - start),
+            format,
+            data: &data,
+        };
+        let samples = match context
+            .audio
+            .register_sound(&sound)
+            .and_then(|sound| context.audio.decode_sound(sound))
+        {
+            Ok(samples) => samples,
+            Err(e) => {
+                log::error!("Got error when decoding live NetStream audio: {}", e);
+                return;
+            }
+        };
+
+        let instance = match self.live_sound_instance {
+            Some(instance) => instance,
+            None => match context.audio.start_dynamic_sound() {
+                Ok(instance) => {
+                    self.live_sound_instance = Some(instance);
+                    instance
+                }
+                Err(e) => {
+                    log::error!("Got error when playing live NetStream audio: {}", e);
+                    return;
+                }
+            },
+        };
+        context.audio.queue_dynamic_samples(instance, &samples);
+    }
+}
+
+impl<'gc> Default for NetStreamData<'gc> {
+    fn default() -> Self {
+        Self {
+            avm_object: None,
+            buffer_time: 0.1,
+            buffer_state: BufferState::Filling,
+            loaded: false,
+            load_complete: false,
+            playing: false,
+            time: 0.0,
+            buffered_until: 0.0,
+            video_frames: Vec::new(),
+            video_stream: None,
+            keyframes: BTreeSet::new(),
+            next_frame: 0,
+            decoded_frame: None,
+            current_fps: 0.0,
+            fps_sample_start: (0.0, 0),
+            sound: None,
+            sound_instance: None,
+            live: false,
+            live_audio: None,
+            live_sound_instance: None,
+            live_aac_config: None,
+        }
+    }
+}
+
+/// Packets of live audio, collected until there are enough to be decoded
+/// together.
+#[derive(Debug)]
+struct LiveAudio {
+    format: SoundFormat,
+    data: Vec<u8>,
+
+    /// The timestamp of the first packet, in milliseconds.
+    start: u32,
+
+    /// The timestamp of the last packet, in milliseconds.
+    end: u32,
+}
+
+impl LiveAudio {
+    /// How much audio is collected before it is decoded, in milliseconds.
+    const DECODE_INTERVAL: u32 = 250;
+}
+
+/// Whether a stream has buffered enough media to play.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum BufferState {
+    /// Playback is paused until `buffer_time` seconds of media are
+    /// available, or the media file has been loaded completely.
+    Filling,
+
+    /// Playback continues until the buffer runs dry.
+    Full,
+}
+
+/// A single encoded video frame of a stream.
+#[derive(Debug)]
+struct StreamFrame {
+    /// The time at which this frame is shown, in milliseconds.
+    timestamp: u32,
+
+    codec: VideoCodec,
+
+    /// Whether the container marks this frame as a keyframe.
+    is_keyframe: bool,
+
+    data: Vec<u8>,
+}
+
+/// The demuxed contents of a media file.
+#[derive(Default)]
+struct Media {
+    video_frames: Vec<StreamFrame>,
+
+    /// The size of the video, if it has to be known before decoding.
+    video_size: Option<(u16, u16)>,
+
+    /// The format and concatenated packets of all audio.
+    audio: Option<(SoundFormat, Vec<u8>)>,
+
+    /// The timestamp of the last audio packet, in milliseconds.
+    audio_end: u32,
+
+    /// The timestamp of the last packet of any kind, in milliseconds.
+    duration: u32,
+
+    /// The argument of the `onMetaData` call of the file.
+    metadata: Option<AmfValue>,
+
+    /// The format of the AAC audio of an FLV file, once it is known.
+    aac_config: Option<AudioSpecificConfig>,
+}
+
+impl Media {
+    fn from_flv(data: &[u8]) -> Result<Self, flv::Error> {
+        let (header, mut reader) = flv::Reader::new(data)?;
+        log::debug!("Loading FLV version {} into NetStream", header.version);
+
+        let mut media = Self::default();
+        while let Some(tag) = reader.next_tag()? {
+            media.duration = media.duration.max(tag.timestamp);
+            match tag.data {
+                flv::TagData::Video(video) => {
+                    if video.frame_type == flv::FrameType::Command {
+                        continue;
+                    }
+                    if media.video_size.is_none() {
+                        media.video_size = video.vp6_frame_size();
+                    }
+                    media.video_frames.push(StreamFrame {
+                        timestamp: tag.timestamp,
+                        codec: video.codec,
+                        is_keyframe: matches!(
+                            video.frame_type,
+                            flv::FrameType::Keyframe | flv::FrameType::GeneratedKeyframe
+                        ),
+                        data: video.data.to_vec(),
+                    });
+                }
+                flv::TagData::Audio(packet) if packet.format == flv::AudioFormat::Aac => {
+                    if let Some((format, frame)) =
+                        read_aac_packet(&mut media.aac_config, packet.data)
+                    {
+                        media.push_audio(format, &frame, tag.timestamp);
+                    }
+                }
+                flv::TagData::Audio(packet) => match packet.sound_format() {
+                    Some(format) => media.push_audio(format, packet.data, tag.timestamp),
+                    None => log::warn!("Unsupported FLV audio format {:?}", packet.format),
+                },
+                flv::TagData::Script(data) => match flv::ScriptCall::read(data) {
+                    Ok(call) if call.name == "onMetaData" => {
+                        media.metadata = call.arguments.into_iter().next();
+                    }
+                    Ok(_) => {}
+                    Err(e) => log::warn!("Got error when reading FLV script data: {}", e),
+                },
+            }
+        }
+
+        // Streams that need to know their size upfront usually say so in
+        // their metadata, too.
+        if media.video_size.is_none() {
+            let width = media.metadata_number("width");
+            let height = media.metadata_number("height");
+            if let (Some(width), Some(height)) = (width, height) {
+                media.video_size = Some((width as u16, height as u16));
+            }
+        }
+
+        Ok(media)
+    }
+
+    fn from_mp4(data: &[u8]) -> Result<Self, mp4::Error> {
+        let mut media = Self::default();
+        for track in mp4::read_tracks(data)? {
+            match &track.kind {
+                mp4::TrackKind::Avc {
+                    width,
+                    height,
+                    configuration,
+                } if media.video_frames.is_empty() => {
+                    media.video_size = Some((*width, *height));
+
+                    // H.264 frames are passed on as FLV `AVCVIDEOPACKET`s,
+                    // starting with the decoder configuration.
+                    let mut sequence_header = vec![0, 0, 0, 0];
+                    sequence_header.extend_from_slice(configuration);
+                    media.video_frames.push(StreamFrame {
+                        timestamp: 0,
+                        codec: VideoCodec::H264,
+                        is_keyframe: true,
+                        data: sequence_header,
+                    });
+
+                    for sample in &track.samples {
+                        let timestamp = track.timestamp(sample);
+                        let composition_offset = track.composition_offset(sample).to_be_bytes();
+                        let mut packet = vec![1];
+                        packet.extend_from_slice(&composition_offset[1..]);
+                        packet.extend_from_slice(&data[sample.offset..sample.offset + sample.size]);
+                        media.duration = media.duration.max(timestamp);
+                        media.video_frames.push(StreamFrame {
+                            timestamp,
+                            codec: VideoCodec::H264,
+                            is_keyframe: sample.is_keyframe,
+                            data: packet,
+                        });
+                    }
+                }
+                mp4::TrackKind::Audio {
+                    format: [b'm', b'p', b'4', b'a'],
+                    configuration,
+                    ..
+                } if media.audio.is_none() => {
+                    let config = match AudioSpecificConfig::parse(configuration) {
+                        Ok(config) => config,
+                        Err(e) => {
+                            log::warn!("Unsupported MP4 AAC audio: {}", e);
+                            continue;
+                        }
+                    };
+                    let mut frame = Vec::new();
+                    for sample in &track.samples {
+                        let timestamp = track.timestamp(sample);
+                        media.duration = media.duration.max(timestamp);
+                        frame.clear();
+                        match config.write_adts_frame(
+                            &data[sample.offset..sample.offset + sample.size],
+                            &mut frame,
+                        ) {
+                            Ok(()) => media.push_audio(config.sound_format(), &frame, timestamp),
+                            Err(e) => log::warn!("Skipping MP4 AAC frame: {}", e),
+                        }
+                    }
+                }
+                mp4::TrackKind::Audio {
+                    format: [b'.', b'm', b'p', b'3'],
+                    sample_rate,
+                    channels,
+                    ..
+                } if media.audio.is_none() => {
+                    let format = SoundFormat {
+                        compression: AudioCompression::Mp3,
+                        sample_rate: *sample_rate as u16,
+                        is_stereo: *channels > 1,
+                        is_16_bit: true,
+                    };
+                    for sample in &track.samples {
+                        let timestamp = track.timestamp(sample);
+                        media.duration = media.duration.max(timestamp);
+                        media.push_audio(
+                            format.clone(),
+                            &data[sample.offset..sample.offset + sample.size],
+                            timestamp,
+                        );
+                    }
+                }
+                mp4::TrackKind::Audio { format, .. } => log::warn!(
+                    "Unsupported MP4 audio format {}",
+                    String::from_utf8_lossy(format)
+                ),
+                _ => {}
+            }
+        }
+
+        // MP4 files have no script data, so their metadata is made up from
+        // the properties of their tracks instead.
+        let mut metadata = vec![Element::new(
+            "duration",
+            AmfValue::Number(f64::from(media.duration) / 1000.0),
+        )];
+        if let Some((width, height)) = media.video_size {
+            metadata.push(Element::new("width", AmfValue::Number(width.into())));
+            metadata.push(Element::new("height", AmfValue::Number(height.into())));
+        }
+        media.metadata = Some(AmfValue::Object(metadata, None));
+
+        Ok(media)
+    }
+
+    /// Look up a numeric property of the metadata of this media file.
+    fn metadata_number(&self, name: &str) -> Option<f64> {
+        let elements = match self.metadata.as_ref()? {
+            AmfValue::ECMAArray(_, elements, _) | AmfValue::Object(elements, _) => elements,
+            _ => return None,
+        };
+        elements
+            .iter()
+            .find(|element| element.name == name)
+            .and_then(|element| match element.value() {
+                AmfValue::Number(number) => Some(*number),
+                _ => None,
+            })
+    }
+
+    /// Append a packet to the audio of this media file.
+    fn push_audio(&mut self, format: SoundFormat, data: &[u8], timestamp: u32) {
+        let (stream_format, audio_data) = self.audio.get_or_insert_with(|| {
+            // MP3 sounds start with the number of samples to skip.
+            let prefix: &[u8] = match format.compression {
+                AudioCompression::Mp3 => &[0, 0],
+                _ => &[],
+            };
+            (format.clone(), prefix.to_vec())
+        });
+        if *stream_format != format {
+            log::warn!("Audio format changed mid-stream; ignoring packet");
+            return;
+        }
+        audio_data.extend_from_slice(data);
+        self.audio_end = timestamp;
+    }
+}
+
+impl<'gc> NetStream<'gc> {
+    pub fn new(gc_context: MutationContext<'gc, '_>) -> Self {
+        Self(GcCell::allocate(gc_context, NetStreamData::default()))
+    }
+
+    pub fn ptr_eq(a: Self, b: Self) -> bool {
+        GcCell::ptr_eq(a.0, b.0)
+    }
+
+    /// Set the AVM1 object that receives status events of this stream.
+    pub fn set_avm_object(self, gc_context: MutationContext<'gc, '_>, object: Avm1Object<'gc>) {
+        self.0.write(gc_context).avm_object = Some(object);
+    }
+
+    /// The AVM1 object that receives status events of this stream.
+    pub fn avm_object(self) -> Option<Avm1Object<'gc>> {
+        self.0.read().avm_object
+    }
+
+    /// How many seconds of media must be buffered before playback starts.
+    pub fn buffer_time(self) -> f64 {
+        self.0.read().buffer_time
+    }
+
+    pub fn set_buffer_time(self, gc_context: MutationContext<'gc, '_>, buffer_time: f64) {
+        self.0.write(gc_context).buffer_time = buffer_time.max(0.0);
+    }
+
+    /// How many seconds of media are buffered ahead of the playhead.
+    pub fn buffer_length(self) -> f64 {
+        let read = self.0.read();
+        (read.buffered_until - read.time).max(0.0) / 1000.0
+    }
+
+    /// Start loading and playing the media file at the given URL.
+    pub fn play(self, context: &mut UpdateContext<'_, 'gc, '_>, url: &str) {
+        self.close(context);
+        self.0.write(context.gc_context).playing = true;
+        self.trigger_status(context, "NetStream.Play.Start", "status");
+
+        let fetch = context.navigator.fetch(url, RequestOptions::get());
+        let process =
+            context
+                .load_manager
+                .load_net_stream(context.player.clone().unwrap(), self, fetch);
+        context.navigator.spawn_future(process);
+        context.stream_manager.activate(self);
+    }
+
+    /// Stop playback, and forget about the current media file.
+    pub fn close(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let old = self.0.write(context.gc_context).reset();
+        if let Some(stream) = old.video_stream {
+            context.video.free_video_stream(stream);
+        }
+        if let Some(instance) = old.sound_instance {
+            context.stop_sound(instance);
+        }
+        if let Some(instance) = old.live_sound_instance {
+            context.audio.stop_sound(instance);
+        }
+        context.stream_manager.deactivate(self);
+    }
+
+    /// Start playing media that is sent by a server as it plays, instead of
+    /// being loaded as a whole file.
+    ///
+    /// Media is then added with `append_live_video` and `append_live_audio`.
+    pub fn start_live(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        self.close(context);
+        let mut write = self.0.write(context.gc_context);
+        write.loaded = true;
+        write.playing = true;
+        write.live = true;
+        drop(write);
+
+        context.stream_manager.activate(self);
+    }
+
+    /// Add a video packet of a live stream, laid out like the body of an FLV
+    /// video tag.
+    pub fn append_live_video(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        timestamp: u32,
+        data: &[u8],
+    ) {
+        let video = match flv::read_video_tag(data) {
+            Ok(video) => video,
+            Err(e) => {
+                log::warn!("Got error when reading live NetStream video: {}", e);
+                return;
+            }
+        };
+        if video.frame_type == flv::FrameType::Command {
+            return;
+        }
+
+        let mut write = self.0.write(context.gc_context);
+        if !write.live {
+            return;
+        }
+        write.receive_live_packet(timestamp);
+
+        let stream = match write.video_stream {
+            Some(stream) => stream,
+            // The number of frames of a live stream isn't known upfront.
+            None => match context.video.register_video_stream(
+                0,
+                video.vp6_frame_size().unwrap_or_default(),
+                video.codec,
+                VideoDeblocking::UseVideoPacketValue,
+            ) {
+                Ok(stream) => {
+                    write.video_stream = Some(stream);
+                    stream
+                }
+                Err(e) => {
+                    log::error!("Got error when registering live NetStream video: {}", e);
+                    return;
+                }
+            },
+        };
+
+        let frame_id = write.video_frames.len() as u32;
+        let is_keyframe = matches!(
+            video.frame_type,
+            flv::FrameType::Keyframe | flv::FrameType::GeneratedKeyframe
+        );
+        let dependency = context.video.preload_video_stream_frame(
+            stream,
+            EncodedFrame {
+                codec: video.codec,
+                data: video.data,
+                frame_id,
+            },
+        );
+        match dependency {
+            Ok(dependency) if dependency.is_keyframe() || is_keyframe => {
+                write.keyframes.insert(frame_id);
+            }
+            Ok(_) => {}
+            Err(e) => log::error!(
+                "Got error when preloading live NetStream video frame: {}",
+                e
+            ),
+        }
+        write.video_frames.push(StreamFrame {
+            timestamp,
+            codec: video.codec,
+            is_keyframe,
+            data: video.data.to_vec(),
+        });
+    }
+
+    /// Add an audio packet of a live stream, laid out like the body of an FLV
+    /// audio tag.
+    pub fn append_live_audio(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        timestamp: u32,
+        data: &[u8],
+    ) {
+        let packet = match flv::read_audio_tag(data) {
+            Ok(packet) => packet,
+            Err(e) => {
+                log::warn!("Got error when reading live NetStream audio: {}", e);
+                return;
+            }
+        };
+        let mut write = self.0.write(context.gc_context);
+        let (format, data) = if packet.format == flv::AudioFormat::Aac {
+            // The format of the stream has to be remembered even while paused.
+            match read_aac_packet(&mut write.live_aac_config, packet.data) {
+                Some(audio) => audio,
+                None => return,
+            }
+        } else {
+            match packet.sound_format() {
+                Some(format) => (format, packet.data.to_vec()),
+                None => {
+                    log::warn!("Unsupported live audio format {:?}", packet.format);
+                    return;
+                }
+            }
+        };
+
+        if !write.live || !write.playing {
+            return;
+        }
+        write.receive_live_packet(timestamp);
+
+        let live_audio = write.live_audio.get_or_insert_with(|| LiveAudio {
+            format: format.clone(),
+            data: Vec::new(),
+            start: timestamp,
+            end: timestamp,
+        });
+        if live_audio.format != format {
+            log::warn!("Audio format changed mid-stream; ignoring packet");
+            return;
+        }
+        live_audio.data.extend_from_slice(&data);
+        live_audio.end = timestamp;
+
+        if live_audio.end.saturating_sub(live_audio.start) >= LiveAudio::DECODE_INTERVAL {
+            write.play_live_audio(context);
+        }
+    }
+
+    /// Report that the server has stopped sending media to a live stream.
+    pub fn end_live(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let mut write = self.0.write(context.gc_context);
+        if !write.live {
+            return;
+        }
+        write.load_complete = true;
+        write.play_live_audio(context);
+        if let Some(instance) = write.live_sound_instance {
+            context.audio.end_dynamic_sound(instance);
+        }
+    }
+
+    /// Whether the playhead is advancing, or will once the media is loaded.
+    pub fn is_playing(self) -> bool {
+        self.0.read().playing
+    }
+
+    /// Stop advancing the playhead, keeping the current video frame on screen.
+    pub fn pause(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let mut write = self.0.write(context.gc_context);
+        if !write.playing {
+            return;
+        }
+        write.playing = false;
+        let sound_instance = write.sound_instance.take();
+        let live_sound_instance = write.live_sound_instance.take();
+        write.live_audio = None;
+        drop(write);
+
+        if let Some(instance) = sound_instance {
+            context.stop_sound(instance);
+        }
+        if let Some(instance) = live_sound_instance {
+            context.audio.stop_sound(instance);
+        }
+        context.stream_manager.deactivate(self);
+        self.trigger_status(context, "NetStream.Pause.Notify", "status");
+    }
+
+    /// Continue advancing the playhead after a call to `pause`.
+    pub fn resume(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let mut write = self.0.write(context.gc_context);
+        if write.playing {
+            return;
+        }
+        write.playing = true;
+        // Audio is restarted from the current position once the buffer is full.
+        write.buffer_state = BufferState::Filling;
+        drop(write);
+
+        context.stream_manager.activate(self);
+        self.trigger_status(context, "NetStream.Unpause.Notify", "status");
+    }
+
+    /// The position of the playhead, in seconds.
+    pub fn time(self) -> f64 {
+        self.0.read().time / 1000.0
+    }
+
+    /// The number of video frames being shown per second, which is zero
+    /// while playback is paused or buffering.
+    pub fn current_fps(self) -> f64 {
+        let read = self.0.read();
+        if read.playing && read.buffer_state == BufferState::Full {
+            read.current_fps
+        } else {
+            0.0
+        }
+    }
+
+    /// The video frame at the current position of the playhead.
+    pub fn decoded_frame(self) -> Option<BitmapInfo> {
+        self.0.read().decoded_frame
+    }
+
+    /// Demux a fully loaded FLV or MP4 file, and start playing it.
+    pub fn load_buffer(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        data: &[u8],
+    ) -> Result<(), crate::loader::Error> {
+        let Media {
+            video_frames,
+            video_size,
+            audio,
+            audio_end,
+            duration,
+            metadata,
+        } = if data.starts_with(b"FLV") {
+            Media::from_flv(data)?
+        } else {
+            Media::from_mp4(data)?
+        };
+
+        let video_stream = match video_frames.first() {
+            Some(first) => match context.video.register_video_stream(
+                video_frames.len() as u32,
+                video_size.unwrap_or_default(),
+                first.codec,
+                VideoDeblocking::UseVideoPacketValue,
+            ) {
+                Ok(stream) => Some(stream),
+                Err(e) => {
+                    log::error!("Got error when registering NetStream video: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let mut keyframes = BTreeSet::new();
+        if let Some(stream) = video_stream {
+            for (frame_id, frame) in video_frames.iter().enumerate() {
+                let dependency = context.video.preload_video_stream_frame(
+                    stream,
+                    EncodedFrame {
+                        codec: frame.codec,
+                        data: &frame.data,
+                        frame_id: frame_id as u32,
+                    },
+                );
+                match dependency {
+                    // The container knows about keyframes that the decoder
+                    // can't tell apart from other frames, and vice versa.
+                    Ok(dependency) if dependency.is_keyframe() || frame.is_keyframe => {
+                        keyframes.insert(frame_id as u32);
+                    }
+                    Ok(_) => {}
+                    Err(e) => log::error!("Got error when preloading NetStream video frame: {}", e),
+                }
+            }
+        }
+
+        let sound = audio.and_then(|(format, data)| {
+            let num_samples = num_samples(&format, &data, audio_end);
+            let sound = swf::Sound {
+                id: 0,
+                format,
+                num_samples,
+                data: &data,
+            };
+            context
+                .audio
+                .register_sound(&sound)
+                .map_err(|e| log::error!("Got error when registering NetStream audio: {}", e))
+                .ok()
+        });
+
+        let mut write = self.0.write(context.gc_context);
+        write.loaded = true;
+        write.load_complete = true;
+        write.buffer_state = BufferState::Filling;
+        write.time = 0.0;
+        write.buffered_until = duration.into();
+        write.video_frames = video_frames;
+        write.video_stream = video_stream;
+        write.keyframes = keyframes;
+        write.next_frame = 0;
+        write.sound = sound;
+        drop(write);
+
+        if let Some(metadata) = metadata {
+            self.trigger_metadata(context, &metadata);
+        }
+
+        // Show the first frame right away.
+        self.tick(context, 0.0);
+
+        Ok(())
+    }
+
+    /// Report that the media file could not be loaded.
+    pub fn load_failed(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        self.close(context);
+        self.trigger_status(context, "NetStream.Play.StreamNotFound", "error");
+    }
+
+    /// Advance the playhead by `dt` milliseconds, decoding every video frame
+    /// that became due.
+    ///
+    /// The playhead only moves while the buffer is full. Running out of
+    /// buffered media pauses playback until enough has been loaded again,
+    /// or stops it for good once the whole file has been played.
+    pub fn tick(self, context: &mut UpdateContext<'_, 'gc, '_>, dt: f64) {
+        let mut write = self.0.write(context.gc_context);
+        if !write.loaded || !write.playing {
+            return;
+        }
+
+        if write.buffer_state == BufferState::Filling {
+            let buffer_length = write.buffered_until - write.time;
+            if buffer_length < write.buffer_time * 1000.0 && !write.load_complete {
+                return;
+            }
+
+            write.buffer_state = BufferState::Full;
+            let sound = write.sound.filter(|_| write.sound_instance.is_none());
+            let time = write.time;
+            drop(write);
+
+            self.trigger_status(context, "NetStream.Buffer.Full", "status");
+            if let Some(sound) = sound {
+                let settings = SoundInfo {
+                    event: SoundEvent::Event,
+                    // Sample positions are always given at 44.1 kHz.
+                    in_sample: Some((time * 44.1) as u32).filter(|sample| *sample > 0),
+                    out_sample: None,
+                    num_loops: 1,
+                    envelope: None,
+                };
+                let instance = context.start_sound(sound, &settings, None, None);
+                self.0.write(context.gc_context).sound_instance = instance;
+            }
+
+            write = self.0.write(context.gc_context);
+        }
+
+        write.time = (write.time + dt).min(write.buffered_until);
+
+        if let Some(stream) = write.video_stream {
+            write.decode_due_frames(context, stream);
+            write.measure_fps(context.video.video_stream_stats(stream));
+        }
+
+        if write.time < write.buffered_until {
+            return;
+        }
+
+        write.buffer_state = BufferState::Filling;
+        let load_complete = write.load_complete;
+        let live = write.live;
+        if load_complete {
+            write.playing = false;
+        }
+        drop(write);
+
+        if load_complete {
+            context.stream_manager.deactivate(self);
+            // Servers report the end of live streams themselves.
+            if !live {
+                self.trigger_status(context, "NetStream.Play.Stop", "status");
+                self.trigger_status(context, "NetStream.Buffer.Flush", "status");
+            }
+        }
+        self.trigger_status(context, "NetStream.Buffer.Empty", "status");
+    }
+
+    /// Move the playhead to the last keyframe at or before `time` seconds.
+    pub fn seek(self, context: &mut UpdateContext<'_, 'gc, '_>, time: f64) {
+        let mut write = self.0.write(context.gc_context);
+        if !write.loaded {
+            return;
+        }
+
+        let target = time * 1000.0;
+        let keyframe = write
+            .keyframes
+            .iter()
+            .copied()
+            .take_while(|frame_id| {
+                f64::from(write.video_frames[*frame_id as usize].timestamp) <= target
+            })
+            .last()
+            .unwrap_or(0);
+
+        write.time = target.clamp(0.0, write.buffered_until);
+        if let Some(stream) = write.video_stream {
+            if let Err(e) = context.video.seek_video_stream(stream, keyframe) {
+                log::error!("Got error when seeking NetStream video: {}", e);
+            }
+            write.time = write
+                .video_frames
+                .get(keyframe as usize)
+                .map_or(0.0, |frame| frame.timestamp.into());
+            write.next_frame = keyframe as usize;
+            write.decode_due_frames(context, stream);
+            // Frames decoded to catch up to the new position aren't counted.
+            let decoded_frames = context.video.video_stream_stats(stream).decoded_frames;
+            write.fps_sample_start = (write.time, decoded_frames);
+        }
+
+        // Audio is restarted from the new position once the buffer is full.
+        let sound_instance = write.sound_instance.take();
+        write.buffer_state = BufferState::Filling;
+        if write.playing {
+            context.stream_manager.activate(self);
+        }
+        drop(write);
+
+        if let Some(instance) = sound_instance {
+            context.stop_sound(instance);
+        }
+        self.trigger_status(context, "NetStream.Seek.Notify", "status");
+    }
+
+    /// Queue a call to the `onMetaData` handler of this stream.
+    fn trigger_metadata(self, context: &mut UpdateContext<'_, 'gc, '_>, metadata: &AmfValue) {
+        self.queue_amf_call(context, "onMetaData", metadata);
+    }
+
+    /// Queue a call to the `onStatus` handler of this stream, with an info
+    /// object sent by a server.
+    pub fn trigger_status_info(self, context: &mut UpdateContext<'_, 'gc, '_>, info: &AmfValue) {
+        self.queue_amf_call(context, "onStatus", info);
+    }
+
+    fn queue_amf_call(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        name: &'static str,
+        argument: &AmfValue,
+    ) {
+        let object = match self.0.read().avm_object {
+            Some(object) => object,
+            None => return,
+        };
+
+        let mut activation = Avm1Activation::from_stub(
+            context.reborrow(),
+            ActivationIdentifier::root("[NetStream AMF call]"),
+        );
+        let argument = deserialize_value(&mut activation, argument);
+        drop(activation);
+
+        context.action_queue.queue_actions(
+            context.stage.root_clip(),
+            ActionType::Method {
+                object,
+                name,
+                args: vec![argument],
+            },
+            false,
+        );
+    }
+
+    /// Queue a call to the `onStatus` handler of this stream.
+    fn trigger_status(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        code: &'static str,
+        level: &'static str,
+    ) {
+        let object = match self.0.read().avm_object {
+            Some(object) => object,
+            None => return,
+        };
+
+        let info = ScriptObject::object(context.gc_context, Some(context.avm1.prototypes().object));
+        info.define_value(context.gc_context, "code", code.into(), Attribute::empty());
+        info.define_value(
+            context.gc_context,
+            "level",
+            level.into(),
+            Attribute::empty(),
+        );
+
+        context.action_queue.queue_actions(
+            context.stage.root_clip(),
+            ActionType::Method {
+                object,
+                name: "onStatus",
+                args: vec![info.into()],
+            },
+            false,
+        );
+    }
+}
+
+/// Read the body of an FLV audio packet of AAC audio.
+///
+/// The packet either configures the format of the stream, which is stored in
+/// `config`, or contains a frame. Frames are returned as ADTS frames, which
+/// are ready to be played back.
+fn read_aac_packet(
+    config: &mut Option<AudioSpecificConfig>,
+    data: &[u8],
+) -> Option<(SoundFormat, Vec<u8>)> {
+    match data.split_first() {
+        Some((0, sequence_header)) => {
+            match AudioSpecificConfig::parse(sequence_header) {
+                Ok(new_config) => *config = Some(new_config),
+                Err(e) => log::warn!("Unsupported AAC audio: {}", e),
+            }
+            None
+        }
+        Some((1, frame)) => {
+            let config = (*config)?;
+            let mut adts_frame = Vec::with_capacity(frame.len() + 7);
+            match config.write_adts_frame(frame, &mut adts_frame) {
+                Ok(()) => Some((config.sound_format(), adts_frame)),
+                Err(e) => {
+                    log::warn!("Skipping AAC frame: {}", e);
+                    None
+                }
+            }
+        }
+        _ => {
+            log::warn!("Invalid AAC audio packet");
+            None
+        }
+    }
+}
+
+/// Estimate the number of samples in a sound made of all audio packets of a
+/// stream, the last of which starts at `end` milliseconds.
+fn num_samples(format: &SoundFormat, data: &[u8], end: u32) -> u32 {
+    match format.compression {
+        AudioCompression::Uncompressed | AudioCompression::UncompressedUnknownEndian => {
+            let channels = if format.is_stereo { 2 } else { 1 };
+            let sample_size = if format.is_16_bit { 2 } else { 1 };
+            (data.len() / (channels * sample_size)) as u32
+        }
+        _ => (u64::from(end) * u64::from(format.sample_rate) / 1000) as u32,
+    }
+}
+
+/// Keeps track of the streams that are currently playing.
+#[derive(Default, Collect)]
+#[collect(no_drop)]
+pub struct StreamManager<'gc> {
+    active_streams: Vec<NetStream<'gc>>,
+
+    /// Connections of `NetConnection` objects to RTMP servers.
+    connections: Vec<net_connection::RtmpConnection<'gc>>,
+
+    /// Events of the sockets of `connections` that haven't been handled yet.
+    #[collect(require_static)]
+    socket_events: Vec<SocketEvent>,
+}
+
+impl<'gc> StreamManager<'gc> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start ticking a stream.
+    pub fn activate(&mut self, stream: NetStream<'gc>) {
+        if !self
+            .active_streams
+            .iter()
+            .any(|other| NetStream::ptr_eq(*other, stream))
+        {
+            self.active_streams.push(stream);
+        }
+    }
+
+    /// Stop ticking a stream.
+    pub fn deactivate(&mut self, stream: NetStream<'gc>) {
+        self.active_streams
+            .retain(|other| !NetStream::ptr_eq(*other, stream));
+    }
+
+    /// Queue an event of a socket that belongs to an RTMP connection.
+    pub fn push_socket_event(&mut self, event: SocketEvent) {
+        self.socket_events.push(event);
+    }
+
+    /// Exchange data with RTMP servers, and advance all active streams by
+    /// `dt` milliseconds.
+    pub fn update_streams(context: &mut UpdateContext<'_, 'gc, '_>, dt: f64) {
+        net_connection::update(context, dt);
+        for stream in context.stream_manager.active_streams.clone() {
+            stream.tick(context, dt);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm1::test_utils::with_avm;
+
+    /// The body of an FLV video tag of an H.263 keyframe. The null video
+    /// backend never decodes it.
+    const KEYFRAME: &[u8] = &[0x12, 0];
+
+    fn buffer_state(stream: NetStream<'_>) -> BufferState {
+        stream.0.read().buffer_state
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn live_stream_buffer_underrun() {
+        with_avm(8, |activation, _root| {
+            let context = &mut activation.context;
+            let stream = NetStream::new(context.gc_context);
+            stream.set_buffer_time(context.gc_context, 1.0);
+            stream.start_live(context);
+            stream.append_live_video(context, 0, KEYFRAME);
+            stream.append_live_video(context, 1500, KEYFRAME);
+
+            stream.tick(context, 100.0);
+            assert_eq!(buffer_state(stream), BufferState::Full);
+            assert_eq!(stream.time(), 0.1);
+
+            // Running out of media before the stream ends waits for more.
+            stream.tick(context, 2000.0);
+            assert_eq!(buffer_state(stream), BufferState::Filling);
+            assert_eq!(stream.time(), 1.5);
+            assert!(stream.is_playing());
+
+            stream.append_live_video(context, 2000, KEYFRAME);
+            stream.tick(context, 100.0);
+            assert_eq!(buffer_state(stream), BufferState::Filling);
+            assert_eq!(stream.time(), 1.5);
+
+            // Playback continues once `bufferTime` seconds are buffered again.
+            stream.append_live_video(context, 2600, KEYFRAME);
+            stream.tick(context, 100.0);
+            assert_eq!(buffer_state(stream), BufferState::Full);
+            assert_eq!(stream.time(), 1.6);
+
+            // Once the stream has ended, the rest of the buffer is played.
+            stream.end_live(context);
+            stream.tick(context, 2000.0);
+            assert_eq!(stream.time(), 2.6);
+            assert!(!stream.is_playing());
+            Ok(())
+        });
+    }
+}
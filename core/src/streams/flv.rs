@@ -0,0 +1,492 @@
+//! FLV container demuxing.
+//!
+//! An FLV file is a short header followed by a sequence of tags, each of
+//! which carries a timestamped packet of audio, video or script data. The
+//! payloads of audio and video tags mostly match what SWF files embed in
+//! their `SoundStreamBlock` and `VideoFrame` tags, so they can be handed to
+//! the audio and video backends as they are.
+
+use flash_lso::amf0::read::AMF0Decoder;
+use flash_lso::types::Value as AmfValue;
+use swf::{AudioCompression, SoundFormat, VideoCodec};
+use thiserror::Error;
+
+/// The size of the header of each tag, in bytes.
+const TAG_HEADER_SIZE: usize = 11;
+
+/// The size of the `PreviousTagSize` field following each tag, in bytes.
+const PREVIOUS_TAG_SIZE_SIZE: usize = 4;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Not an FLV file")]
+    InvalidSignature,
+
+    #[error("FLV header is truncated")]
+    TruncatedHeader,
+
+    #[error("Encrypted FLV tags are not supported")]
+    Encrypted,
+
+    #[error("Invalid FLV audio format {0}")]
+    InvalidAudioFormat(u8),
+
+    #[error("Invalid FLV video frame type {0}")]
+    InvalidFrameType(u8),
+
+    #[error("Invalid FLV video codec {0}")]
+    InvalidVideoCodec(u8),
+
+    #[error("FLV tag payload is empty")]
+    EmptyPayload,
+
+    #[error("Invalid FLV script data")]
+    InvalidScriptData,
+}
+
+/// The header of an FLV file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Header {
+    pub version: u8,
+    pub has_audio: bool,
+    pub has_video: bool,
+}
+
+/// A timestamped tag of an FLV file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tag<'a> {
+    /// The time at which this tag's data should be presented, in
+    /// milliseconds.
+    pub timestamp: u32,
+
+    pub data: TagData<'a>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TagData<'a> {
+    Audio(AudioTag<'a>),
+    Video(VideoTag<'a>),
+
+    /// An AMF0-encoded script call, such as `onMetaData`.
+    Script(&'a [u8]),
+}
+
+/// A call made by a script tag, such as `onMetaData`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScriptCall {
+    pub name: String,
+    pub arguments: Vec<AmfValue>,
+}
+
+impl ScriptCall {
+    /// Decode the AMF0 values of a script tag: the name of the called
+    /// function, followed by its arguments.
+    pub fn read(data: &[u8]) -> Result<Self, Error> {
+        let mut decoder = AMF0Decoder::default();
+        let (mut data, name) = decoder
+            .parse_single_element(data)
+            .map_err(|_| Error::InvalidScriptData)?;
+        let name = match name {
+            AmfValue::String(name) => name,
+            _ => return Err(Error::InvalidScriptData),
+        };
+
+        let mut arguments = Vec::new();
+        while !data.is_empty() {
+            let (rest, argument) = decoder
+                .parse_single_element(data)
+                .map_err(|_| Error::InvalidScriptData)?;
+            arguments.push(argument);
+            data = rest;
+        }
+
+        Ok(Self { name, arguments })
+    }
+}
+
+/// The audio formats that FLV files can declare.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AudioFormat {
+    LinearPcmPlatformEndian,
+    Adpcm,
+    Mp3,
+    LinearPcmLittleEndian,
+    Nellymoser16Khz,
+    Nellymoser8Khz,
+    Nellymoser,
+    G711ALaw,
+    G711MuLaw,
+    Aac,
+    Speex,
+    Mp38Khz,
+    DeviceSpecific,
+}
+
+impl AudioFormat {
+    fn from_u8(n: u8) -> Option<Self> {
+        Some(match n {
+            0 => Self::LinearPcmPlatformEndian,
+            1 => Self::Adpcm,
+            2 => Self::Mp3,
+            3 => Self::LinearPcmLittleEndian,
+            4 => Self::Nellymoser16Khz,
+            5 => Self::Nellymoser8Khz,
+            6 => Self::Nellymoser,
+            7 => Self::G711ALaw,
+            8 => Self::G711MuLaw,
+            10 => Self::Aac,
+            11 => Self::Speex,
+            14 => Self::Mp38Khz,
+            15 => Self::DeviceSpecific,
+            _ => return None,
+        })
+    }
+
+    /// The SWF equivalent of this format, if there is one.
+    pub fn compression(self) -> Option<AudioCompression> {
+        Some(match self {
+            Self::LinearPcmPlatformEndian => AudioCompression::UncompressedUnknownEndian,
+            Self::Adpcm => AudioCompression::Adpcm,
+            Self::Mp3 | Self::Mp38Khz => AudioCompression::Mp3,
+            Self::LinearPcmLittleEndian => AudioCompression::Uncompressed,
+            Self::Nellymoser16Khz => AudioCompression::Nellymoser16Khz,
+            Self::Nellymoser8Khz => AudioCompression::Nellymoser8Khz,
+            Self::Nellymoser => AudioCompression::Nellymoser,
+            Self::Speex => AudioCompression::Speex,
+            Self::G711ALaw | Self::G711MuLaw | Self::Aac | Self::DeviceSpecific => return None,
+        })
+    }
+}
+
+/// A packet of audio data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AudioTag<'a> {
+    pub format: AudioFormat,
+    pub sample_rate: u16,
+    pub is_stereo: bool,
+    pub is_16_bit: bool,
+
+    /// The encoded audio data.
+    ///
+    /// For AAC, this still starts with the `AACPacketType` byte.
+    pub data: &'a [u8],
+}
+
+impl<'a> AudioTag<'a> {
+    /// The format of this packet as a SWF sound format, if the audio
+    /// backends are able to play it.
+    pub fn sound_format(&self) -> Option<SoundFormat> {
+        Some(SoundFormat {
+            compression: self.format.compression()?,
+            sample_rate: self.sample_rate,
+            is_stereo: self.is_stereo,
+            is_16_bit: self.is_16_bit,
+        })
+    }
+}
+
+/// The type of a video frame.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FrameType {
+    Keyframe,
+    Interframe,
+    DisposableInterframe,
+    GeneratedKeyframe,
+
+    /// A video info or command frame, which contains no picture.
+    Command,
+}
+
+/// A packet of video data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VideoTag<'a> {
+    pub frame_type: FrameType,
+    pub codec: VideoCodec,
+
+    /// How many pixels to crop from the right and bottom edges of VP6
+    /// frames, which are always encoded in whole macroblocks.
+    pub crop: (u8, u8),
+
+    /// The encoded frame, laid out the same way as in a SWF `VideoFrame`
+    /// tag.
+    pub data: &'a [u8],
+}
+
+impl<'a> VideoTag<'a> {
+    /// Determine the displayed size of a VP6 keyframe from its header.
+    ///
+    /// Other codecs either carry their size in every frame, or don't need
+    /// to know it in advance, so this returns `None` for them.
+    pub fn vp6_frame_size(&self) -> Option<(u16, u16)> {
+        let header = match self.codec {
+            VideoCodec::Vp6 => self.data,
+            // VP6 frames with alpha start with the offset of the alpha data.
+            VideoCodec::Vp6WithAlpha => self.data.get(3..)?,
+            _ => return None,
+        };
+
+        let is_keyframe = header.first()? & 0b1000_0000 == 0;
+        if !is_keyframe {
+            return None;
+        }
+
+        let is_multistream = header[0] & 0b1 != 0;
+        let profile = (header.get(1)? >> 1) & 0b11;
+        let dimensions = if is_multistream || profile == 0 {
+            header.get(4..6)?
+        } else {
+            header.get(2..4)?
+        };
+
+        let width = u16::from(dimensions[1]) * 16;
+        let height = u16::from(dimensions[0]) * 16;
+        Some((
+            width.saturating_sub(self.crop.0.into()),
+            height.saturating_sub(self.crop.1.into()),
+        ))
+    }
+}
+
+/// A reader over the tags of an FLV file.
+pub struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Parse the header of an FLV file, and prepare to read its tags.
+    pub fn new(data: &'a [u8]) -> Result<(Header, Self), Error> {
+        if data.len() >= 3 && &data[..3] != b"FLV" {
+            return Err(Error::InvalidSignature);
+        }
+        if data.len() < 9 {
+            return Err(Error::TruncatedHeader);
+        }
+
+        let header = Header {
+            version: data[3],
+            has_audio: data[4] & 0b100 != 0,
+            has_video: data[4] & 0b1 != 0,
+        };
+        let data_offset = u32::from_be_bytes([data[5], data[6], data[7], data[8]]) as usize;
+
+        // The first tag is preceded by a `PreviousTagSize` of zero.
+        let reader = Self {
+            data,
+            position: data_offset.max(9) + PREVIOUS_TAG_SIZE_SIZE,
+        };
+        Ok((header, reader))
+    }
+
+    /// Read the next tag of the file.
+    ///
+    /// Returns `None` once there is no complete tag left to read. Tags of
+    /// unknown types are skipped.
+    pub fn next_tag(&mut self) -> Result<Option<Tag<'a>>, Error> {
+        loop {
+            let header = match self
+                .data
+                .get(self.position..self.position + TAG_HEADER_SIZE)
+            {
+                Some(header) => header,
+                None => return Ok(None),
+            };
+
+            let tag_type = header[0];
+            let size = read_u24(&header[1..4]) as usize;
+            let timestamp = read_u24(&header[4..7]) | (u32::from(header[7]) << 24);
+
+            let start = self.position + TAG_HEADER_SIZE;
+            let payload = match self.data.get(start..start + size) {
+                Some(payload) => payload,
+                None => return Ok(None),
+            };
+            self.position = start + size + PREVIOUS_TAG_SIZE_SIZE;
+
+            if tag_type & 0b10_0000 != 0 {
+                return Err(Error::Encrypted);
+            }
+
+            let data = match tag_type & 0b1_1111 {
+                8 => TagData::Audio(read_audio_tag(payload)?),
+                9 => TagData::Video(read_video_tag(payload)?),
+                18 => TagData::Script(payload),
+                _ => continue,
+            };
+
+            return Ok(Some(Tag { timestamp, data }));
+        }
+    }
+}
+
+fn read_u24(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]])
+}
+
+pub fn read_audio_tag(payload: &[u8]) -> Result<AudioTag<'_>, Error> {
+    let (&flags, data) = payload.split_first().ok_or(Error::EmptyPayload)?;
+
+    let format = AudioFormat::from_u8(flags >> 4).ok_or(Error::InvalidAudioFormat(flags >> 4))?;
+    let sample_rate = match format {
+        // These formats ignore the sample rate field.
+        AudioFormat::Nellymoser16Khz => 16000,
+        AudioFormat::Nellymoser8Khz | AudioFormat::Mp38Khz => 8000,
+        AudioFormat::Speex => 16000,
+        _ => match (flags >> 2) & 0b11 {
+            0 => 5512,
+            1 => 11025,
+            2 => 22050,
+            _ => 44100,
+        },
+    };
+
+    Ok(AudioTag {
+        format,
+        sample_rate,
+        is_16_bit: flags & 0b10 != 0,
+        is_stereo: flags & 0b1 != 0,
+        data,
+    })
+}
+
+pub fn read_video_tag(payload: &[u8]) -> Result<VideoTag<'_>, Error> {
+    let (&flags, mut data) = payload.split_first().ok_or(Error::EmptyPayload)?;
+
+    let frame_type = match flags >> 4 {
+        1 => FrameType::Keyframe,
+        2 => FrameType::Interframe,
+        3 => FrameType::DisposableInterframe,
+        4 => FrameType::GeneratedKeyframe,
+        5 => FrameType::Command,
+        other => return Err(Error::InvalidFrameType(other)),
+    };
+    let codec =
+        VideoCodec::from_u8(flags & 0b1111).ok_or(Error::InvalidVideoCodec(flags & 0b1111))?;
+
+    let mut crop = (0, 0);
+    if matches!(codec, VideoCodec::Vp6 | VideoCodec::Vp6WithAlpha)
+        && frame_type != FrameType::Command
+    {
+        let (&adjustment, rest) = data.split_first().ok_or(Error::EmptyPayload)?;
+        crop = (adjustment >> 4, adjustment & 0b1111);
+        data = rest;
+    }
+
+    Ok(VideoTag {
+        frame_type,
+        codec,
+        crop,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: [u8; 13] = [b'F', b'L', b'V', 1, 0b101, 0, 0, 0, 9, 0, 0, 0, 0];
+
+    fn file(tags: &[(u8, u32, &[u8])]) -> Vec<u8> {
+        let mut data = HEADER.to_vec();
+        for (tag_type, timestamp, payload) in tags {
+            let size = payload.len() as u32;
+            data.push(*tag_type);
+            data.extend_from_slice(&size.to_be_bytes()[1..]);
+            data.extend_from_slice(&timestamp.to_be_bytes()[1..]);
+            data.push((timestamp >> 24) as u8);
+            data.extend_from_slice(&[0, 0, 0]);
+            data.extend_from_slice(payload);
+            data.extend_from_slice(&(size + TAG_HEADER_SIZE as u32).to_be_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn read_header() {
+        let (header, _) = Reader::new(&HEADER).unwrap();
+        assert_eq!(
+            header,
+            Header {
+                version: 1,
+                has_audio: true,
+                has_video: true,
+            }
+        );
+        assert!(matches!(
+            Reader::new(b"FWS\x0A\0\0\0\0\0"),
+            Err(Error::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn read_tags() {
+        let data = file(&[
+            (8, 0, &[0x2F, 0xAA]),
+            (9, 0x0100_0040, &[0x14, 0x21, 0xBB]),
+            (18, 40, &[0x02]),
+        ]);
+        let (_, mut reader) = Reader::new(&data).unwrap();
+
+        let tag = reader.next_tag().unwrap().unwrap();
+        assert_eq!(tag.timestamp, 0);
+        match tag.data {
+            TagData::Audio(audio) => {
+                assert_eq!(audio.format, AudioFormat::Mp3);
+                assert_eq!(audio.sample_rate, 44100);
+                assert!(audio.is_stereo && audio.is_16_bit);
+                assert_eq!(audio.data, [0xAA]);
+            }
+            _ => panic!("Expected an audio tag"),
+        }
+
+        let tag = reader.next_tag().unwrap().unwrap();
+        assert_eq!(tag.timestamp, 0x0100_0040);
+        assert_eq!(
+            tag.data,
+            TagData::Video(VideoTag {
+                frame_type: FrameType::Keyframe,
+                codec: VideoCodec::Vp6,
+                crop: (2, 1),
+                data: &[0xBB],
+            })
+        );
+
+        let tag = reader.next_tag().unwrap().unwrap();
+        assert_eq!(tag.data, TagData::Script(&[0x02]));
+
+        assert_eq!(reader.next_tag().unwrap(), None);
+    }
+
+    #[test]
+    fn truncated_tag() {
+        let mut data = file(&[(9, 0, &[0x12, 0x00, 0x00])]);
+        data.truncate(data.len() - 5);
+        let (_, mut reader) = Reader::new(&data).unwrap();
+        assert_eq!(reader.next_tag().unwrap(), None);
+    }
+
+    #[test]
+    fn read_script_call() {
+        let mut data = vec![0x02, 0, 10];
+        data.extend_from_slice(b"onMetaData");
+        data.push(0x00);
+        data.extend_from_slice(&2.5f64.to_be_bytes());
+
+        let call = ScriptCall::read(&data).unwrap();
+        assert_eq!(call.name, "onMetaData");
+        assert_eq!(call.arguments, [AmfValue::Number(2.5)]);
+        assert!(ScriptCall::read(&[0x00, 0, 0, 0, 0, 0, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn vp6_size() {
+        let tag = VideoTag {
+            frame_type: FrameType::Keyframe,
+            codec: VideoCodec::Vp6,
+            crop: (8, 4),
+            // Intra frame with profile 3, 10 rows and 20 columns.
+            data: &[0x00, 0x06 | 0x40, 10, 20, 10, 20],
+        };
+        assert_eq!(tag.vp6_frame_size(), Some((312, 156)));
+    }
+}
@@ -0,0 +1,603 @@
+//! MP4 (and F4V) container demuxing.
+//!
+//! Only the sample tables in the `moov` box are parsed. They describe where
+//! each sample of each track is located in the file, when it is presented,
+//! and whether it is a keyframe; the samples themselves are read straight
+//! out of the file data.
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Not an MP4 file")]
+    InvalidSignature,
+
+    #[error("MP4 box is truncated")]
+    Truncated,
+
+    #[error("MP4 file has no {0} box")]
+    MissingBox(&'static str),
+
+    #[error("MP4 sample lies outside of the file")]
+    InvalidSample,
+}
+
+/// A track of an MP4 file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Track {
+    /// The number of time units per second in the timestamps of this track.
+    pub timescale: u32,
+
+    pub kind: TrackKind,
+    pub samples: Vec<Sample>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TrackKind {
+    /// An H.264 video track.
+    Avc {
+        width: u16,
+        height: u16,
+
+        /// The `AVCDecoderConfigurationRecord` of the track.
+        configuration: Vec<u8>,
+    },
+
+    /// An audio track.
+    Audio {
+        /// The four-character code of the audio format, such as `mp4a`.
+        format: [u8; 4],
+        sample_rate: u32,
+        channels: u16,
+
+        /// The decoder specific info of the track, which is the
+        /// `AudioSpecificConfig` of AAC audio. Empty if there is none.
+        configuration: Vec<u8>,
+    },
+
+    /// Any other kind of track, such as hint tracks or unsupported codecs.
+    Other,
+}
+
+/// The location and timing of a single sample of a track.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Sample {
+    /// The position of the sample in the file, in bytes.
+    pub offset: usize,
+    pub size: usize,
+
+    /// The time at which this sample is decoded, in time units of its
+    /// track.
+    pub decode_time: u64,
+
+    /// How much later than `decode_time` this sample is presented, in time
+    /// units of its track.
+    pub composition_offset: i32,
+
+    pub is_keyframe: bool,
+}
+
+impl Track {
+    /// The decoding time of a sample, in milliseconds.
+    ///
+    /// Like the timestamps of FLV tags, these are in decoding order.
+    pub fn timestamp(&self, sample: &Sample) -> u32 {
+        let time = sample.decode_time.saturating_mul(1000) / u64::from(self.timescale.max(1));
+        u32::try_from(time).unwrap_or(u32::MAX)
+    }
+
+    /// The composition offset of a sample, in milliseconds.
+    pub fn composition_offset(&self, sample: &Sample) -> i32 {
+        (i64::from(sample.composition_offset) * 1000 / i64::from(self.timescale.max(1))) as i32
+    }
+}
+
+/// Parse the tracks of an MP4 file.
+pub fn read_tracks(data: &[u8]) -> Result<Vec<Track>, Error> {
+    if data.get(4..8) != Some(&b"ftyp"[..]) {
+        return Err(Error::InvalidSignature);
+    }
+
+    let moov = find_box(data, b"moov")?.ok_or(Error::MissingBox("moov"))?;
+    let mut tracks = Vec::new();
+    for entry in boxes(moov) {
+        let (name, trak) = entry?;
+        if &name == b"trak" {
+            let track = read_track(trak, data.len())?;
+            for sample in &track.samples {
+                if sample
+                    .offset
+                    .checked_add(sample.size)
+                    .and_then(|end| data.get(sample.offset..end))
+                    .is_none()
+                {
+                    return Err(Error::InvalidSample);
+                }
+            }
+            tracks.push(track);
+        }
+    }
+
+    Ok(tracks)
+}
+
+/// Iterate over the boxes in `data`, yielding their names and contents.
+fn boxes(mut data: &[u8]) -> impl Iterator<Item = Result<([u8; 4], &[u8]), Error>> {
+    std::iter::from_fn(move || {
+        if data.is_empty() {
+            return None;
+        }
+
+        let current = data;
+        let result = (|| {
+            let mut reader = ByteReader(current);
+            let size = reader.read_u32()?;
+            let name = reader.read_fourcc()?;
+            let (header_size, size) = match size {
+                // The box extends to the end of its parent.
+                0 => (8, current.len()),
+                // The size doesn't fit into 32 bits.
+                1 => (
+                    16,
+                    usize::try_from(reader.read_u64()?).map_err(|_| Error::Truncated)?,
+                ),
+                size => (8, size as usize),
+            };
+            let contents = current.get(header_size..size).ok_or(Error::Truncated)?;
+            Ok((name, contents, size))
+        })();
+
+        let result = result.map(|(name, contents, size)| {
+            data = &current[size..];
+            (name, contents)
+        });
+        if result.is_err() {
+            data = &[];
+        }
+        Some(result)
+    })
+}
+
+/// Find the first box called `name` in `data`.
+fn find_box<'a>(data: &'a [u8], name: &[u8; 4]) -> Result<Option<&'a [u8]>, Error> {
+    for entry in boxes(data) {
+        let (box_name, contents) = entry?;
+        if &box_name == name {
+            return Ok(Some(contents));
+        }
+    }
+    Ok(None)
+}
+
+/// Find a box nested within others, such as `mdia/minf/stbl`.
+fn find_path<'a>(data: &'a [u8], path: &[&[u8; 4]]) -> Result<Option<&'a [u8]>, Error> {
+    let mut data = data;
+    for name in path {
+        data = match find_box(data, name)? {
+            Some(contents) => contents,
+            None => return Ok(None),
+        };
+    }
+    Ok(Some(data))
+}
+
+/// Read a track of a file that is `file_len` bytes long.
+fn read_track(trak: &[u8], file_len: usize) -> Result<Track, Error> {
+    let mdhd = find_path(trak, &[b"mdia", b"mdhd"])?.ok_or(Error::MissingBox("mdhd"))?;
+    let mut reader = ByteReader(mdhd);
+    let version = reader.read_u8()?;
+    // Flags, then the creation and modification times.
+    reader.skip(if version == 1 { 3 + 16 } else { 3 + 8 })?;
+    let timescale = reader.read_u32()?;
+
+    let stbl = find_path(trak, &[b"mdia", b"minf", b"stbl"])?.ok_or(Error::MissingBox("stbl"))?;
+    let kind = match find_box(stbl, b"stsd")? {
+        Some(stsd) => read_sample_description(stsd)?,
+        None => TrackKind::Other,
+    };
+    let samples = read_samples(stbl, file_len)?;
+
+    Ok(Track {
+        timescale,
+        kind,
+        samples,
+    })
+}
+
+/// Determine the kind of a track from the first entry of its `stsd` box.
+fn read_sample_description(stsd: &[u8]) -> Result<TrackKind, Error> {
+    // Version and flags, then the number of entries.
+    let entries = stsd.get(8..).ok_or(Error::Truncated)?;
+    let (format, entry) = match boxes(entries).next() {
+        Some(entry) => entry?,
+        None => return Ok(TrackKind::Other),
+    };
+
+    // Every sample entry starts with 6 reserved bytes and a data reference index.
+    let mut reader = ByteReader(entry);
+    reader.skip(8)?;
+
+    match &format {
+        b"avc1" => {
+            // Pre-defined and reserved fields.
+            reader.skip(16)?;
+            let width = reader.read_u16()?;
+            let height = reader.read_u16()?;
+            // Resolution, frame count, compressor name, depth and a
+            // pre-defined field.
+            reader.skip(50)?;
+            let configuration = find_box(reader.0, b"avcC")?
+                .ok_or(Error::MissingBox("avcC"))?
+                .to_vec();
+            Ok(TrackKind::Avc {
+                width,
+                height,
+                configuration,
+            })
+        }
+        b"mp4a" | b".mp3" => {
+            // Later versions of QuickTime sound descriptions add more fields.
+            let version = reader.read_u16()?;
+            // Revision level and vendor.
+            reader.skip(6)?;
+            let channels = reader.read_u16()?;
+            // Sample size, pre-defined and reserved fields.
+            reader.skip(6)?;
+            // A 16.16 fixed point number.
+            let sample_rate = reader.read_u32()? >> 16;
+            reader.skip(match version {
+                1 => 16,
+                2 => 36,
+                _ => 0,
+            })?;
+            let configuration = match find_box(reader.0, b"esds")? {
+                Some(esds) => read_decoder_specific_info(esds)?,
+                None => Vec::new(),
+            };
+            Ok(TrackKind::Audio {
+                format,
+                sample_rate,
+                channels,
+                configuration,
+            })
+        }
+        _ => Ok(TrackKind::Other),
+    }
+}
+
+/// Read the decoder specific info from the elementary stream descriptor in
+/// an `esds` box.
+fn read_decoder_specific_info(esds: &[u8]) -> Result<Vec<u8>, Error> {
+    const ES_DESCRIPTOR: u8 = 0x03;
+    const DECODER_CONFIG_DESCRIPTOR: u8 = 0x04;
+    const DECODER_SPECIFIC_INFO: u8 = 0x05;
+
+    let mut reader = ByteReader(esds);
+    // Version and flags.
+    reader.skip(4)?;
+    let es_descriptor = match reader.find_descriptor(ES_DESCRIPTOR)? {
+        Some(descriptor) => descriptor,
+        None => return Ok(Vec::new()),
+    };
+
+    let mut reader = ByteReader(es_descriptor);
+    // The elementary stream ID.
+    reader.skip(2)?;
+    let flags = reader.read_u8()?;
+    if flags & 0b1000_0000 != 0 {
+        // The ID of the stream this one depends on.
+        reader.skip(2)?;
+    }
+    if flags & 0b0100_0000 != 0 {
+        let url_len = reader.read_u8()?;
+        reader.skip(url_len.into())?;
+    }
+    if flags & 0b0010_0000 != 0 {
+        // The ID of the stream with the object clock reference.
+        reader.skip(2)?;
+    }
+    let decoder_config = match reader.find_descriptor(DECODER_CONFIG_DESCRIPTOR)? {
+        Some(descriptor) => descriptor,
+        None => return Ok(Vec::new()),
+    };
+
+    let mut reader = ByteReader(decoder_config);
+    // Object type, stream type, buffer size and bitrates.
+    reader.skip(13)?;
+    Ok(reader
+        .find_descriptor(DECODER_SPECIFIC_INFO)?
+        .map(|info| info.to_vec())
+        .unwrap_or_default())
+}
+
+/// Expand the sample table of a track into a list of samples.
+///
+/// Samples have to lie within a file of `file_len` bytes.
+fn read_samples(stbl: &[u8], file_len: usize) -> Result<Vec<Sample>, Error> {
+    let stsz = find_box(stbl, b"stsz")?.ok_or(Error::MissingBox("stsz"))?;
+    let mut reader = ByteReader(stsz);
+    reader.skip(4)?;
+    let uniform_size = reader.read_u32()?;
+    let sample_count = reader.read_u32()?;
+    match uniform_size {
+        0 if sample_count as usize > reader.0.len() / 4 => return Err(Error::Truncated),
+        // Every sample takes up some space in the file.
+        size if u64::from(size) * u64::from(sample_count) > file_len as u64 => {
+            return Err(Error::InvalidSample)
+        }
+        _ => (),
+    }
+    let sizes = (0..sample_count)
+        .map(|_| match uniform_size {
+            0 => reader.read_u32(),
+            size => Ok(size),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let chunk_offsets = if let Some(stco) = find_box(stbl, b"stco")? {
+        read_table(stco, 4, |reader| Ok(u64::from(reader.read_u32()?)))?
+    } else if let Some(co64) = find_box(stbl, b"co64")? {
+        read_table(co64, 8, |reader| reader.read_u64())?
+    } else {
+        return Err(Error::MissingBox("stco"));
+    };
+
+    // Runs of chunks with the same number of samples, by their first chunk.
+    let stsc = find_box(stbl, b"stsc")?.ok_or(Error::MissingBox("stsc"))?;
+    let chunk_runs = read_table(stsc, 12, |reader| {
+        let first_chunk = reader.read_u32()?;
+        let samples_per_chunk = reader.read_u32()?;
+        reader.skip(4)?;
+        Ok((first_chunk, samples_per_chunk))
+    })?;
+
+    let stts = find_box(stbl, b"stts")?.ok_or(Error::MissingBox("stts"))?;
+    let durations = read_table(stts, 8, |reader| {
+        Ok((reader.read_u32()?, reader.read_u32()?))
+    })?;
+
+    let composition_offsets = match find_box(stbl, b"ctts")? {
+        Some(ctts) => read_table(ctts, 8, |reader| {
+            Ok((reader.read_u32()?, reader.read_u32()? as i32))
+        })?,
+        None => Vec::new(),
+    };
+
+    // Without a sync sample table, every sample is a keyframe.
+    let keyframes = match find_box(stbl, b"stss")? {
+        Some(stss) => Some(read_table(stss, 4, |reader| reader.read_u32())?),
+        None => None,
+    };
+
+    let mut samples = Vec::with_capacity(sizes.len());
+    let mut sizes = sizes.into_iter();
+    for (index, chunk_offset) in chunk_offsets.into_iter().enumerate() {
+        let chunk = index as u32 + 1;
+        let samples_per_chunk = chunk_runs
+            .iter()
+            .rev()
+            .find(|(first_chunk, _)| *first_chunk <= chunk)
+            .map_or(0, |(_, samples_per_chunk)| *samples_per_chunk);
+
+        let mut offset = usize::try_from(chunk_offset).map_err(|_| Error::InvalidSample)?;
+        for size in sizes.by_ref().take(samples_per_chunk as usize) {
+            samples.push(Sample {
+                offset,
+                size: size as usize,
+                decode_time: 0,
+                composition_offset: 0,
+                is_keyframe: keyframes.is_none(),
+            });
+            offset = offset
+                .checked_add(size as usize)
+                .ok_or(Error::InvalidSample)?;
+        }
+    }
+
+    let mut decode_time = 0;
+    let mut deltas = durations
+        .iter()
+        .flat_map(|(count, delta)| std::iter::repeat(*delta).take(*count as usize));
+    for sample in &mut samples {
+        sample.decode_time = decode_time;
+        decode_time = decode_time.saturating_add(u64::from(deltas.next().unwrap_or_default()));
+    }
+
+    let mut offsets = composition_offsets
+        .iter()
+        .flat_map(|(count, offset)| std::iter::repeat(*offset).take(*count as usize));
+    for sample in &mut samples {
+        sample.composition_offset = offsets.next().unwrap_or_default();
+    }
+
+    for number in keyframes.into_iter().flatten() {
+        if let Some(sample) = (number as usize)
+            .checked_sub(1)
+            .and_then(|index| samples.get_mut(index))
+        {
+            sample.is_keyframe = true;
+        }
+    }
+
+    Ok(samples)
+}
+
+/// Read the entries of a table box, which are preceded by a version, flags
+/// and the number of entries. Each entry is `entry_size` bytes long.
+fn read_table<T>(
+    data: &[u8],
+    entry_size: usize,
+    mut read_entry: impl FnMut(&mut ByteReader) -> Result<T, Error>,
+) -> Result<Vec<T>, Error> {
+    let mut reader = ByteReader(data);
+    reader.skip(4)?;
+    let count = reader.read_u32()?;
+    if count as usize > reader.0.len() / entry_size {
+        return Err(Error::Truncated);
+    }
+    (0..count).map(|_| read_entry(&mut reader)).collect()
+}
+
+struct ByteReader<'a>(&'a [u8]);
+
+impl<'a> ByteReader<'a> {
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.0.len() < len {
+            return Err(Error::Truncated);
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> Result<(), Error> {
+        self.read_bytes(len).map(|_| ())
+    }
+
+    fn read_u8(&mut self) -> Result<u8, Error> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> Result<u16, Error> {
+        let bytes = self.read_bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_u32(&mut self) -> Result<u32, Error> {
+        let bytes = self.read_bytes(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, Error> {
+        Ok((u64::from(self.read_u32()?) << 32) | u64::from(self.read_u32()?))
+    }
+
+    fn read_fourcc(&mut self) -> Result<[u8; 4], Error> {
+        let bytes = self.read_bytes(4)?;
+        Ok([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+
+    /// Read an MPEG-4 descriptor, returning its tag and contents.
+    fn read_descriptor(&mut self) -> Result<(u8, &'a [u8]), Error> {
+        let tag = self.read_u8()?;
+        // The size is stored in up to four bytes of seven bits each.
+        let mut size = 0;
+        for _ in 0..4 {
+            let byte = self.read_u8()?;
+            size = (size << 7) | usize::from(byte & 0b111_1111);
+            if byte & 0b1000_0000 == 0 {
+                break;
+            }
+        }
+        Ok((tag, self.read_bytes(size)?))
+    }
+
+    /// Skip over descriptors until one with the given tag is found.
+    fn find_descriptor(&mut self, tag: u8) -> Result<Option<&'a [u8]>, Error> {
+        while !self.0.is_empty() {
+            let (descriptor_tag, contents) = self.read_descriptor()?;
+            if descriptor_tag == tag {
+                return Ok(Some(contents));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mp4_box(name: &[u8; 4], contents: &[u8]) -> Vec<u8> {
+        let mut data = (contents.len() as u32 + 8).to_be_bytes().to_vec();
+        data.extend_from_slice(name);
+        data.extend_from_slice(contents);
+        data
+    }
+
+    /// Encode a table box with the given number of entries.
+    fn table(count: u32, words: &[u32]) -> Vec<u8> {
+        let mut data = vec![0, 0, 0, 0];
+        data.extend_from_slice(&count.to_be_bytes());
+        for word in words {
+            data.extend_from_slice(&word.to_be_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn expand_sample_table() {
+        // Five samples in two chunks, of three and two samples.
+        let stbl = [
+            mp4_box(b"stsz", &table(0, &[5, 10, 20, 30, 40, 50])),
+            mp4_box(b"stco", &table(2, &[1000, 2000])),
+            mp4_box(b"stsc", &table(2, &[1, 3, 1, 2, 2, 1])),
+            mp4_box(b"stts", &table(1, &[5, 100])),
+            mp4_box(b"stss", &table(2, &[1, 4])),
+        ]
+        .concat();
+
+        let samples = read_samples(&stbl, 4096).unwrap();
+        let offsets: Vec<_> = samples.iter().map(|sample| sample.offset).collect();
+        assert_eq!(offsets, [1000, 1010, 1030, 2000, 2040]);
+        let times: Vec<_> = samples.iter().map(|sample| sample.decode_time).collect();
+        assert_eq!(times, [0, 100, 200, 300, 400]);
+        let keyframes: Vec<_> = samples.iter().map(|sample| sample.is_keyframe).collect();
+        assert_eq!(keyframes, [true, false, false, true, false]);
+    }
+
+    #[test]
+    fn reject_oversized_tables() {
+        // A sample count far larger than the table.
+        let stbl = [
+            mp4_box(b"stsz", &table(0, &[0xFFFF_FFFF, 10])),
+            mp4_box(b"stco", &table(1, &[0])),
+            mp4_box(b"stsc", &table(1, &[1, 1, 1])),
+            mp4_box(b"stts", &table(1, &[1, 100])),
+        ]
+        .concat();
+        assert!(matches!(read_samples(&stbl, 4096), Err(Error::Truncated)));
+
+        // Uniformly sized samples that can't fit into the file.
+        let stbl = [
+            mp4_box(b"stsz", &table(100, &[0xFFFF_FFFF])),
+            mp4_box(b"stco", &table(1, &[0])),
+            mp4_box(b"stsc", &table(1, &[1, 1, 1])),
+            mp4_box(b"stts", &table(1, &[1, 100])),
+        ]
+        .concat();
+        assert!(matches!(
+            read_samples(&stbl, 4096),
+            Err(Error::InvalidSample)
+        ));
+
+        // A chunk offset that overflows once sample sizes are added to it.
+        let stbl = [
+            mp4_box(b"stsz", &table(0, &[2, 0xFFFF_FFFF, 0xFFFF_FFFF])),
+            mp4_box(b"co64", &table(1, &[0xFFFF_FFFF, 0xFFFF_FFFF])),
+            mp4_box(b"stsc", &table(1, &[1, 2, 1])),
+            mp4_box(b"stts", &table(1, &[2, 100])),
+        ]
+        .concat();
+        assert!(read_samples(&stbl, 4096).is_err());
+    }
+
+    #[test]
+    fn read_aac_configuration() {
+        // An ES descriptor, with a decoder config descriptor holding a two
+        // byte AudioSpecificConfig.
+        let mut esds = vec![0, 0, 0, 0];
+        esds.extend_from_slice(&[0x03, 0x80, 0x80, 0x80, 22, 0, 1, 0]);
+        esds.extend_from_slice(&[0x04, 17, 0x40, 0x15, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        esds.extend_from_slice(&[0x05, 2, 0x12, 0x10]);
+        assert_eq!(read_decoder_specific_info(&esds).unwrap(), [0x12, 0x10]);
+    }
+
+    #[test]
+    fn reject_non_mp4() {
+        assert!(matches!(
+            read_tracks(b"FLV\x01\x05\0\0\0\x09"),
+            Err(Error::InvalidSignature)
+        ));
+    }
+}
@@ -0,0 +1,728 @@
+//! Connections of AVM1 `NetConnection` objects to RTMP servers, and the
+//! `NetStream`s that play media from them.
+
+use super::rtmp::{status_info, Client, Event, Protocol, RtmpUrl};
+use super::NetStream;
+use crate::avm1::activation::{Activation, ActivationIdentifier};
+use crate::avm1::globals::netconnection::set_connected;
+use crate::avm1::globals::shared_object::deserialize_value;
+use crate::avm1::{Object as Avm1Object, TObject, Value as Avm1Value};
+use crate::backend::navigator::{NavigatorBackend, RequestOptions, SocketEvent, SocketHandle};
+use crate::context::{ActionType, UpdateContext};
+use crate::loader::Error;
+use crate::string::AvmString;
+use flash_lso::types::Value as AmfValue;
+use gc_arena::Collect;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// The content type of RTMPT requests.
+const TUNNEL_CONTENT_TYPE: &str = "application/x-fcs";
+
+/// The longest time to wait between two RTMPT polls, in milliseconds.
+const MAX_IDLE_DELAY: f64 = 500.0;
+
+/// A connection belonging to an AVM1 `NetConnection` object.
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct RtmpConnection<'gc> {
+    /// The `NetConnection` that receives status events of this connection.
+    object: Avm1Object<'gc>,
+
+    #[collect(require_static)]
+    client: Client,
+
+    #[collect(require_static)]
+    transport: Transport,
+
+    /// Streams that are waiting for the server to create a message stream.
+    pending_streams: Vec<PendingStream<'gc>>,
+
+    /// Streams with a message stream on the server, and its ID.
+    streams: Vec<(u32, NetStream<'gc>)>,
+
+    /// The ID of the next `createStream` request.
+    next_request: u32,
+}
+
+#[derive(Collect)]
+#[collect(no_drop)]
+struct PendingStream<'gc> {
+    request: u32,
+    stream: NetStream<'gc>,
+
+    /// The name of the stream to play once the message stream exists.
+    #[collect(require_static)]
+    play: Option<String>,
+}
+
+/// How the bytes of a connection get to the server and back.
+enum Transport {
+    /// A plain TCP connection, for `rtmp` URLs.
+    Socket {
+        handle: SocketHandle,
+
+        /// Whether the socket has connected yet.
+        connected: bool,
+    },
+
+    /// HTTP requests to the server, for `rtmpt` and `rtmpts` URLs.
+    Tunnel {
+        /// The URL that the tunnel commands are appended to.
+        base_url: String,
+
+        /// The session ID handed out by the server, once it has been opened.
+        session: Option<String>,
+
+        /// The sequence number of the next request.
+        sequence: u32,
+
+        /// The result of the request that is in flight, if there is one.
+        response: Option<ResponseSlot>,
+
+        /// How long to wait before polling the server, in milliseconds.
+        idle_delay: f64,
+
+        /// The time since the last request, in milliseconds.
+        idle_time: f64,
+    },
+}
+
+/// Where a spawned tunnel request leaves its response.
+type ResponseSlot = Rc<RefCell<Option<Result<Vec<u8>, Error>>>>;
+
+/// Something that happened on the transport of a connection.
+enum Incoming {
+    Data(Vec<u8>),
+    Failed,
+}
+
+/// Start connecting `object` to the RTMP server at `url`, closing any
+/// connection it already has.
+///
+/// `arguments` are passed on to the application along with the `connect`
+/// command. Returns `false` if the connection couldn't be started at all.
+pub fn connect<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Avm1Object<'gc>,
+    url: &str,
+    arguments: &[AmfValue],
+) -> bool {
+    close(context, object);
+
+    let rtmp_url = match RtmpUrl::parse(url) {
+        Ok(rtmp_url) => rtmp_url,
+        Err(e) => {
+            log::warn!("NetConnection.connect: {}", e);
+            return false;
+        }
+    };
+
+    let transport = match rtmp_url.protocol {
+        Protocol::Rtmp => match context
+            .navigator
+            .connect_socket(&rtmp_url.host, rtmp_url.port)
+        {
+            Some(handle) => Transport::Socket {
+                handle,
+                connected: false,
+            },
+            None => return false,
+        },
+        Protocol::Rtmps => {
+            log::warn!("NetConnection.connect: rtmps connections are not supported");
+            return false;
+        }
+        Protocol::Rtmpt | Protocol::Rtmpts => {
+            let scheme = if rtmp_url.protocol == Protocol::Rtmpt {
+                "http"
+            } else {
+                "https"
+            };
+            Transport::Tunnel {
+                base_url: format!("{}://{}:{}", scheme, rtmp_url.host, rtmp_url.port),
+                session: None,
+                sequence: 0,
+                response: None,
+                idle_delay: 0.0,
+                idle_time: 0.0,
+            }
+        }
+    };
+
+    let swf_url = context.swf.url().map(str::to_string);
+    let client = Client::new(&rtmp_url, swf_url.as_deref(), None, arguments);
+    context.stream_manager.connections.push(RtmpConnection {
+        object,
+        client,
+        transport,
+        pending_streams: Vec::new(),
+        streams: Vec::new(),
+        next_request: 1,
+    });
+    true
+}
+
+/// Close the connection of `object`, if it has one.
+pub fn close<'gc>(context: &mut UpdateContext<'_, 'gc, '_>, object: Avm1Object<'gc>) {
+    if let Some(index) = find_connection(context, object) {
+        let connection = context.stream_manager.connections.remove(index);
+        connection.close_transport(context.navigator);
+        trigger_status(
+            context,
+            object,
+            &status_info("NetConnection.Connect.Closed", "status"),
+        );
+    }
+}
+
+/// Ask the server of the connection of `object` for a message stream that
+/// `stream` plays from.
+///
+/// Returns `false` if `object` isn't connected to a server.
+pub fn attach_stream<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Avm1Object<'gc>,
+    stream: NetStream<'gc>,
+) -> bool {
+    let index = match find_connection(context, object) {
+        Some(index) => index,
+        None => return false,
+    };
+
+    let connection = &mut context.stream_manager.connections[index];
+    let request = connection.next_request;
+    connection.next_request += 1;
+    connection.client.create_stream(request);
+    connection.pending_streams.push(PendingStream {
+        request,
+        stream,
+        play: None,
+    });
+    connection.send_output(context.navigator);
+    true
+}
+
+/// Start playing the stream `name` of the server that `stream` is attached to.
+///
+/// Returns `false` if `stream` isn't attached to a server.
+pub fn play<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    stream: NetStream<'gc>,
+    name: &str,
+) -> bool {
+    let index = match find_stream(context, stream) {
+        Some(index) => index,
+        None => return false,
+    };
+
+    stream.start_live(context);
+    let buffer_time = (stream.buffer_time() * 1000.0) as u32;
+    let connection = &mut context.stream_manager.connections[index];
+    if let Some(stream_id) = connection.stream_id(stream) {
+        // A start of -2 plays a live stream if there is one, and a recorded
+        // one otherwise.
+        connection.client.play(stream_id, name, -2.0, buffer_time);
+        connection.send_output(context.navigator);
+    } else if let Some(pending) = connection
+        .pending_streams
+        .iter_mut()
+        .find(|pending| NetStream::ptr_eq(pending.stream, stream))
+    {
+        pending.play = Some(name.to_string());
+    }
+    true
+}
+
+/// Pause or resume a stream on its server.
+///
+/// Returns `false` if `stream` isn't attached to a server.
+pub fn pause<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    stream: NetStream<'gc>,
+    paused: bool,
+) -> bool {
+    with_stream_id(context, stream, |client, stream_id| {
+        client.pause(stream_id, paused, stream.time() * 1000.0)
+    })
+}
+
+/// Continue a stream from `time` seconds on its server.
+///
+/// Returns `false` if `stream` isn't attached to a server.
+pub fn seek<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    stream: NetStream<'gc>,
+    time: f64,
+) -> bool {
+    let sent = with_stream_id(context, stream, |client, stream_id| {
+        client.seek(stream_id, time * 1000.0)
+    });
+    if sent {
+        // The server sends the stream again, starting at the new position.
+        stream.start_live(context);
+    }
+    sent
+}
+
+/// Have the server delete the message stream of `stream`.
+pub fn close_stream<'gc>(context: &mut UpdateContext<'_, 'gc, '_>, stream: NetStream<'gc>) {
+    with_stream_id(context, stream, |client, stream_id| {
+        client.delete_stream(stream_id)
+    });
+    for connection in &mut context.stream_manager.connections {
+        connection
+            .pending_streams
+            .retain(|pending| !NetStream::ptr_eq(pending.stream, stream));
+        connection
+            .streams
+            .retain(|(_, other)| !NetStream::ptr_eq(*other, stream));
+    }
+}
+
+/// Whether `handle` is the socket of a connection.
+pub fn owns_socket(context: &UpdateContext<'_, '_, '_>, handle: SocketHandle) -> bool {
+    context
+        .stream_manager
+        .connections
+        .iter()
+        .any(|connection| connection.socket_handle() == Some(handle))
+}
+
+/// Exchange data with the servers of all connections, and deliver what they
+/// sent to `NetConnection` and `NetStream` objects.
+pub fn update<'gc>(context: &mut UpdateContext<'_, 'gc, '_>, dt: f64) {
+    let mut incoming = Vec::new();
+
+    for event in std::mem::take(&mut context.stream_manager.socket_events) {
+        let handle = match event {
+            SocketEvent::Connected(handle, _)
+            | SocketEvent::Data(handle, _)
+            | SocketEvent::Closed(handle) => handle,
+        };
+        let connection = match context
+            .stream_manager
+            .connections
+            .iter_mut()
+            .find(|connection| connection.socket_handle() == Some(handle))
+        {
+            Some(connection) => connection,
+            // The connection has been closed by content.
+            None => continue,
+        };
+
+        match event {
+            SocketEvent::Connected(_, true) => {
+                if let Transport::Socket { connected, .. } = &mut connection.transport {
+                    *connected = true;
+                }
+                connection.send_output(context.navigator);
+            }
+            SocketEvent::Connected(_, false) | SocketEvent::Closed(_) => {
+                incoming.push((connection.object, Incoming::Failed))
+            }
+            SocketEvent::Data(_, data) => incoming.push((connection.object, Incoming::Data(data))),
+        }
+    }
+
+    for index in 0..context.stream_manager.connections.len() {
+        let connection = &mut context.stream_manager.connections[index];
+        let object = connection.object;
+        if let Some(result) = connection.poll_tunnel(dt) {
+            incoming.push((object, result));
+        }
+        connection.send_tunnel_request(context.navigator);
+    }
+
+    for (object, incoming) in incoming {
+        let index = match find_connection(context, object) {
+            Some(index) => index,
+            None => continue,
+        };
+        let connection = &mut context.stream_manager.connections[index];
+
+        let events = match incoming {
+            Incoming::Data(data) => match connection.client.receive(&data) {
+                Ok(events) => {
+                    connection.send_output(context.navigator);
+                    events
+                }
+                Err(e) => {
+                    log::warn!("Got error from RTMP server: {}", e);
+                    disconnect(context, index);
+                    continue;
+                }
+            },
+            Incoming::Failed => {
+                disconnect(context, index);
+                continue;
+            }
+        };
+
+        for event in events {
+            handle_event(context, object, event);
+        }
+    }
+}
+
+/// Drop a connection whose transport failed, and tell its `NetConnection`.
+fn disconnect(context: &mut UpdateContext<'_, '_, '_>, index: usize) {
+    let connection = context.stream_manager.connections.remove(index);
+    let object = connection.object;
+    let (code, level) = if connection.client.is_connected() {
+        ("NetConnection.Connect.Closed", "status")
+    } else {
+        ("NetConnection.Connect.Failed", "error")
+    };
+    connection.close_transport(context.navigator);
+    set_connected(context.gc_context, object, false);
+    trigger_status(context, object, &status_info(code, level));
+}
+
+fn handle_event<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Avm1Object<'gc>,
+    event: Event,
+) {
+    let index = match find_connection(context, object) {
+        Some(index) => index,
+        None => return,
+    };
+    let connection = &mut context.stream_manager.connections[index];
+
+    match event {
+        Event::Status { stream_id: 0, info } => {
+            let connected = connection.client.is_connected();
+            if !connected {
+                let connection = context.stream_manager.connections.remove(index);
+                connection.close_transport(context.navigator);
+            }
+            set_connected(context.gc_context, object, connected);
+            trigger_status(context, object, &info);
+        }
+        Event::Status { stream_id, info } => {
+            if let Some(stream) = connection.stream(stream_id) {
+                stream.trigger_status_info(context, &info);
+                if matches!(
+                    status_code(&info),
+                    Some("NetStream.Play.Stop" | "NetStream.Play.UnpublishNotify")
+                ) {
+                    stream.end_live(context);
+                }
+            }
+        }
+        Event::StreamCreated { request, stream_id } => {
+            let position = connection
+                .pending_streams
+                .iter()
+                .position(|pending| pending.request == request);
+            if let Some(position) = position {
+                let pending = connection.pending_streams.remove(position);
+                connection.streams.push((stream_id, pending.stream));
+                if let Some(name) = pending.play {
+                    let buffer_time = (pending.stream.buffer_time() * 1000.0) as u32;
+                    connection.client.play(stream_id, &name, -2.0, buffer_time);
+                    connection.send_output(context.navigator);
+                }
+            }
+        }
+        Event::Call {
+            stream_id,
+            name,
+            arguments,
+        } => {
+            // Calls like `|RtmpSampleAccess` are meant for the player itself.
+            if name.starts_with('|') {
+                return;
+            }
+            let target = if stream_id == 0 {
+                Some(object)
+            } else {
+                connection
+                    .stream(stream_id)
+                    .and_then(|stream| stream.avm_object())
+            };
+            if let Some(target) = target {
+                call_method(context, target, &name, &arguments);
+            }
+        }
+        Event::Audio {
+            stream_id,
+            timestamp,
+            data,
+        } => {
+            if let Some(stream) = connection.stream(stream_id) {
+                stream.append_live_audio(context, timestamp, &data);
+            }
+        }
+        Event::Video {
+            stream_id,
+            timestamp,
+            data,
+        } => {
+            if let Some(stream) = connection.stream(stream_id) {
+                stream.append_live_video(context, timestamp, &data);
+            }
+        }
+    }
+}
+
+/// Queue a call to the `onStatus` handler of a `NetConnection`.
+fn trigger_status<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Avm1Object<'gc>,
+    info: &AmfValue,
+) {
+    let mut activation = Activation::from_stub(
+        context.reborrow(),
+        ActivationIdentifier::root("[NetConnection onStatus]"),
+    );
+    let info = deserialize_value(&mut activation, info);
+    drop(activation);
+
+    context.action_queue.queue_actions(
+        context.stage.root_clip(),
+        ActionType::Method {
+            object,
+            name: "onStatus",
+            args: vec![info],
+        },
+        false,
+    );
+}
+
+/// Call a method that the server invoked on a `NetConnection` or `NetStream`.
+fn call_method<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    target: Avm1Object<'gc>,
+    name: &str,
+    arguments: &[AmfValue],
+) {
+    let version = context.swf.version();
+    let globals = context.avm1.global_object_cell();
+    let level0 = context.stage.root_clip();
+    let mut activation = Activation::from_nothing(
+        context.reborrow(),
+        ActivationIdentifier::root("[NetConnection call]"),
+        version,
+        globals,
+        level0,
+    );
+
+    let args: Vec<Avm1Value<'gc>> = arguments
+        .iter()
+        .map(|argument| deserialize_value(&mut activation, argument))
+        .collect();
+    let name = AvmString::new_utf8(activation.context.gc_context, name);
+    let _ = target.call_method(name, &args, &mut activation);
+    crate::player::Player::run_actions(&mut activation.context);
+}
+
+/// The `code` of an info object.
+fn status_code(info: &AmfValue) -> Option<&str> {
+    let elements = match info {
+        AmfValue::Object(elements, _) | AmfValue::ECMAArray(_, elements, _) => elements,
+        _ => return None,
+    };
+    elements
+        .iter()
+        .find(|element| element.name == "code")
+        .and_then(|element| match element.value() {
+            AmfValue::String(code) => Some(code.as_str()),
+            _ => None,
+        })
+}
+
+fn find_connection<'gc>(
+    context: &UpdateContext<'_, 'gc, '_>,
+    object: Avm1Object<'gc>,
+) -> Option<usize> {
+    context
+        .stream_manager
+        .connections
+        .iter()
+        .position(|connection| Avm1Object::ptr_eq(connection.object, object))
+}
+
+/// Find the connection that `stream` is attached to.
+fn find_stream<'gc>(context: &UpdateContext<'_, 'gc, '_>, stream: NetStream<'gc>) -> Option<usize> {
+    context
+        .stream_manager
+        .connections
+        .iter()
+        .position(|connection| {
+            connection.stream_id(stream).is_some()
+                || connection
+                    .pending_streams
+                    .iter()
+                    .any(|pending| NetStream::ptr_eq(pending.stream, stream))
+        })
+}
+
+/// Send a command for the message stream of `stream`, if it has one.
+fn with_stream_id<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    stream: NetStream<'gc>,
+    f: impl FnOnce(&mut Client, u32),
+) -> bool {
+    let index = match find_stream(context, stream) {
+        Some(index) => index,
+        None => return false,
+    };
+    let connection = &mut context.stream_manager.connections[index];
+    if let Some(stream_id) = connection.stream_id(stream) {
+        f(&mut connection.client, stream_id);
+        connection.send_output(context.navigator);
+    }
+    true
+}
+
+impl<'gc> RtmpConnection<'gc> {
+    fn stream(&self, stream_id: u32) -> Option<NetStream<'gc>> {
+        self.streams
+            .iter()
+            .find(|(id, _)| *id == stream_id)
+            .map(|(_, stream)| *stream)
+    }
+
+    fn stream_id(&self, stream: NetStream<'gc>) -> Option<u32> {
+        self.streams
+            .iter()
+            .find(|(_, other)| NetStream::ptr_eq(*other, stream))
+            .map(|(id, _)| *id)
+    }
+
+    fn socket_handle(&self) -> Option<SocketHandle> {
+        match self.transport {
+            Transport::Socket { handle, .. } => Some(handle),
+            Transport::Tunnel { .. } => None,
+        }
+    }
+
+    /// Send the output of the client over a socket transport.
+    ///
+    /// Tunnels send it along with their next request instead.
+    fn send_output(&mut self, navigator: &mut dyn NavigatorBackend) {
+        if let Transport::Socket {
+            handle,
+            connected: true,
+        } = self.transport
+        {
+            let output = self.client.take_output();
+            if !output.is_empty() {
+                navigator.send_socket(handle, output);
+            }
+        }
+    }
+
+    fn close_transport(self, navigator: &mut dyn NavigatorBackend) {
+        match self.transport {
+            Transport::Socket { handle, .. } => navigator.close_socket(handle),
+            Transport::Tunnel {
+                base_url,
+                session: Some(session),
+                ..
+            } => {
+                let url = format!("{}/close/{}", base_url, session);
+                let fetch = navigator.fetch(
+                    &url,
+                    RequestOptions::post(Some((vec![0], TUNNEL_CONTENT_TYPE.to_string()))),
+                );
+                navigator.spawn_future(Box::pin(async move {
+                    let _ = fetch.await;
+                    Ok(())
+                }));
+            }
+            Transport::Tunnel { .. } => {}
+        }
+    }
+
+    /// Take the response of the tunnel request in flight, once it has arrived.
+    fn poll_tunnel(&mut self, dt: f64) -> Option<Incoming> {
+        let (session, response, idle_delay, idle_time) = match &mut self.transport {
+            Transport::Tunnel {
+                session,
+                response,
+                idle_delay,
+                idle_time,
+                ..
+            } => (session, response, idle_delay, idle_time),
+            Transport::Socket { .. } => return None,
+        };
+        *idle_time += dt;
+
+        let result = response.as_ref()?.borrow_mut().take()?;
+        *response = None;
+        let body = match result {
+            Ok(body) => body,
+            Err(e) => {
+                log::warn!("Got error from RTMPT server: {}", e);
+                return Some(Incoming::Failed);
+            }
+        };
+
+        if session.is_none() {
+            // The response to `open` is the ID of the session.
+            *session = Some(String::from_utf8_lossy(&body).trim().to_string());
+            return None;
+        }
+
+        // Every other response starts with how long to wait before polling
+        // again, in units of about 20 milliseconds.
+        let (&interval, data) = body.split_first()?;
+        *idle_delay = if data.is_empty() {
+            (f64::from(interval) * 20.0).min(MAX_IDLE_DELAY)
+        } else {
+            0.0
+        };
+        *idle_time = 0.0;
+        Some(Incoming::Data(data.to_vec()))
+    }
+
+    /// Start the next request of a tunnel transport, if it is due.
+    fn send_tunnel_request(&mut self, navigator: &mut dyn NavigatorBackend) {
+        let (base_url, session, sequence, response, idle_delay, idle_time) =
+            match &mut self.transport {
+                Transport::Tunnel {
+                    base_url,
+                    session,
+                    sequence,
+                    response,
+                    idle_delay,
+                    idle_time,
+                } => (base_url, session, sequence, response, idle_delay, idle_time),
+                Transport::Socket { .. } => return,
+            };
+        if response.is_some() {
+            return;
+        }
+
+        let (url, body) = match session {
+            None if *sequence == 0 => (format!("{}/open/1", base_url), vec![0]),
+            None => return,
+            Some(session) => {
+                let output = self.client.take_output();
+                if output.is_empty() && *idle_time < *idle_delay {
+                    return;
+                }
+                let command = if output.is_empty() { "idle" } else { "send" };
+                let url = format!("{}/{}/{}/{}", base_url, command, session, sequence);
+                (url, if output.is_empty() { vec![0] } else { output })
+            }
+        };
+        *sequence += 1;
+        *idle_time = 0.0;
+
+        let slot = ResponseSlot::default();
+        *response = Some(slot.clone());
+        let fetch = navigator.fetch(
+            &url,
+            RequestOptions::post(Some((body, TUNNEL_CONTENT_TYPE.to_string()))),
+        );
+        navigator.spawn_future(Box::pin(async move {
+            let result = fetch.await;
+            *slot.borrow_mut() = Some(result);
+            Ok(())
+        }));
+    }
+}
@@ -0,0 +1,809 @@
+//! Client side of the Real Time Messaging Protocol, used by `NetConnection`s
+//! to stream from media servers such as Red5 or Wowza.
+//!
+//! `Client` only implements the protocol. Bytes received from the server are
+//! fed to it, and it produces the bytes to send back, as well as the events
+//! that `NetConnection` and `NetStream` objects have to hear about. Moving
+//! those bytes around is left to the transport of the connection.
+
+mod amf0;
+mod chunk;
+
+use chunk::{Decoder, Encoder, Message};
+use flash_lso::amf0::read::AMF0Decoder;
+use flash_lso::types::{Element, Value as AmfValue};
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// The size of the random handshake packets, C1/S1 and C2/S2.
+const HANDSHAKE_SIZE: usize = 1536;
+
+/// The only version of the protocol that is in use.
+const RTMP_VERSION: u8 = 3;
+
+/// The chunk size used for sent messages.
+const CHUNK_SIZE: u32 = 4096;
+
+/// The acknowledgement window announced to the server, in bytes.
+const WINDOW_ACK_SIZE: u32 = 2_500_000;
+
+// Message type IDs.
+const SET_CHUNK_SIZE: u8 = 1;
+const ABORT: u8 = 2;
+const ACKNOWLEDGEMENT: u8 = 3;
+const USER_CONTROL: u8 = 4;
+const WINDOW_ACKNOWLEDGEMENT_SIZE: u8 = 5;
+const SET_PEER_BANDWIDTH: u8 = 6;
+const AUDIO: u8 = 8;
+const VIDEO: u8 = 9;
+const DATA_AMF3: u8 = 15;
+const COMMAND_AMF3: u8 = 17;
+const DATA_AMF0: u8 = 18;
+const COMMAND_AMF0: u8 = 20;
+const AGGREGATE: u8 = 22;
+
+// User control event types.
+const SET_BUFFER_LENGTH: u16 = 3;
+const PING_REQUEST: u16 = 6;
+const PING_RESPONSE: u16 = 7;
+
+/// The chunk stream of protocol control messages.
+const CONTROL_CHUNK_STREAM: u32 = 2;
+
+/// The chunk stream of command messages.
+const COMMAND_CHUNK_STREAM: u32 = 3;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Invalid RTMP URL")]
+    InvalidUrl,
+
+    #[error("Unsupported RTMP version {0}")]
+    UnsupportedVersion(u8),
+
+    #[error("Invalid RTMP chunk size {0}")]
+    InvalidChunkSize(usize),
+
+    #[error("RTMP chunk stream {0} was continued before it was started")]
+    UnknownChunkStream(u32),
+
+    #[error("Invalid RTMP command message")]
+    InvalidCommand,
+}
+
+/// How the connection to the server is made.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Protocol {
+    /// RTMP over TCP.
+    Rtmp,
+
+    /// RTMP over TLS.
+    Rtmps,
+
+    /// RTMP tunneled through HTTP requests.
+    Rtmpt,
+
+    /// RTMP tunneled through HTTPS requests.
+    Rtmpts,
+}
+
+impl Protocol {
+    fn default_port(self) -> u16 {
+        match self {
+            Self::Rtmp => 1935,
+            Self::Rtmps | Self::Rtmpts => 443,
+            Self::Rtmpt => 80,
+        }
+    }
+}
+
+/// A parsed `rtmp://host[:port]/app[/instance]` URL.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RtmpUrl {
+    pub protocol: Protocol,
+    pub host: String,
+    pub port: u16,
+
+    /// The application to connect to, including its instance and query.
+    pub app: String,
+
+    /// The URL as it is reported to the server.
+    pub tc_url: String,
+}
+
+impl RtmpUrl {
+    pub fn parse(url: &str) -> Result<Self, Error> {
+        let parsed = url::Url::parse(url).map_err(|_| Error::InvalidUrl)?;
+        let protocol = match parsed.scheme() {
+            "rtmp" => Protocol::Rtmp,
+            "rtmps" => Protocol::Rtmps,
+            "rtmpt" => Protocol::Rtmpt,
+            "rtmpts" => Protocol::Rtmpts,
+            _ => return Err(Error::InvalidUrl),
+        };
+        let host = parsed.host_str().ok_or(Error::InvalidUrl)?.to_string();
+        let port = parsed.port().unwrap_or_else(|| protocol.default_port());
+
+        let mut app = parsed.path().trim_matches('/').to_string();
+        if let Some(query) = parsed.query() {
+            app.push('?');
+            app.push_str(query);
+        }
+
+        Ok(Self {
+            protocol,
+            host,
+            port,
+            app,
+            tc_url: url.to_string(),
+        })
+    }
+}
+
+/// Something that happened on a connection.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    /// A status event of the connection (stream 0) or of a message stream,
+    /// with an info object that carries its `code` and `level`.
+    Status { stream_id: u32, info: AmfValue },
+
+    /// A stream requested with `Client::create_stream` is ready.
+    StreamCreated { request: u32, stream_id: u32 },
+
+    /// The server called a method on the connection or a stream, such as
+    /// `onMetaData`.
+    Call {
+        stream_id: u32,
+        name: String,
+        arguments: Vec<AmfValue>,
+    },
+
+    /// An audio packet, laid out like the body of an FLV audio tag.
+    Audio {
+        stream_id: u32,
+        timestamp: u32,
+        data: Vec<u8>,
+    },
+
+    /// A video packet, laid out like the body of an FLV video tag.
+    Video {
+        stream_id: u32,
+        timestamp: u32,
+        data: Vec<u8>,
+    },
+}
+
+/// The outcome of a command that is waiting for a response.
+enum Transaction {
+    Connect,
+    CreateStream(u32),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum State {
+    /// Waiting for S0, S1 and S2.
+    Handshake,
+
+    /// Waiting for the response to the `connect` command.
+    Connecting,
+
+    Connected,
+
+    /// The server rejected the connection, or closed it.
+    Closed,
+}
+
+/// The protocol state of a connection to an RTMP server.
+pub struct Client {
+    state: State,
+
+    /// Received data that hasn't been processed yet.
+    input: Vec<u8>,
+
+    /// Data waiting to be sent to the server.
+    output: Vec<u8>,
+
+    decoder: Decoder,
+    encoder: Encoder,
+
+    /// The `connect` command, sent once the handshake is done.
+    connect_command: Option<Message>,
+
+    /// Commands sent before the connection was established.
+    queued: Vec<Message>,
+
+    transactions: HashMap<u32, Transaction>,
+    next_transaction_id: u32,
+
+    /// The number of bytes received, which is acknowledged periodically.
+    bytes_received: u32,
+    last_acknowledgement: u32,
+    window_ack_size: Option<u32>,
+}
+
+impl Client {
+    /// Start connecting to the application of `url`, passing `arguments`
+    /// along with the `connect` command.
+    pub fn new(
+        url: &RtmpUrl,
+        swf_url: Option<&str>,
+        page_url: Option<&str>,
+        arguments: &[AmfValue],
+    ) -> Self {
+        let mut command_object = vec![
+            Element::new("app", AmfValue::String(url.app.clone())),
+            Element::new("flashVer", AmfValue::String("WIN 32,0,0,0".to_string())),
+            Element::new("tcUrl", AmfValue::String(url.tc_url.clone())),
+            Element::new("fpad", AmfValue::Bool(false)),
+            Element::new("capabilities", AmfValue::Number(15.0)),
+            Element::new("audioCodecs", AmfValue::Number(3575.0)),
+            Element::new("videoCodecs", AmfValue::Number(252.0)),
+            Element::new("videoFunction", AmfValue::Number(1.0)),
+            Element::new("objectEncoding", AmfValue::Number(0.0)),
+        ];
+        if let Some(swf_url) = swf_url {
+            command_object.push(Element::new(
+                "swfUrl",
+                AmfValue::String(swf_url.to_string()),
+            ));
+        }
+        if let Some(page_url) = page_url {
+            command_object.push(Element::new(
+                "pageUrl",
+                AmfValue::String(page_url.to_string()),
+            ));
+        }
+
+        let mut client = Self {
+            state: State::Handshake,
+            input: Vec::new(),
+            output: Vec::new(),
+            decoder: Decoder::new(),
+            encoder: Encoder::new(),
+            connect_command: None,
+            queued: Vec::new(),
+            transactions: HashMap::new(),
+            next_transaction_id: 1,
+            bytes_received: 0,
+            last_acknowledgement: 0,
+            window_ack_size: None,
+        };
+
+        let transaction_id = client.transaction(Transaction::Connect);
+        let mut arguments_with_object = vec![AmfValue::Object(command_object, None)];
+        arguments_with_object.extend_from_slice(arguments);
+        client.connect_command = Some(command(
+            0,
+            "connect",
+            transaction_id,
+            &arguments_with_object,
+        ));
+
+        // C0 and C1: the version, followed by a timestamp, four zero bytes
+        // and random data that the server echoes back.
+        client.output.push(RTMP_VERSION);
+        client.output.extend_from_slice(&[0; 8]);
+        let mut seed: u32 = 0x2545_F491;
+        client.output.extend((8..HANDSHAKE_SIZE).map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed as u8
+        }));
+
+        client
+    }
+
+    /// Whether the server has accepted the connection.
+    pub fn is_connected(&self) -> bool {
+        self.state == State::Connected
+    }
+
+    /// Take the data that has to be sent to the server.
+    pub fn take_output(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.output)
+    }
+
+    /// Process data received from the server.
+    pub fn receive(&mut self, data: &[u8]) -> Result<Vec<Event>, Error> {
+        self.input.extend_from_slice(data);
+        self.bytes_received = self.bytes_received.wrapping_add(data.len() as u32);
+
+        if self.state == State::Handshake {
+            // S0, S1 and S2.
+            if self.input.len() < 1 + 2 * HANDSHAKE_SIZE {
+                return Ok(vec![]);
+            }
+            if self.input[0] != RTMP_VERSION {
+                return Err(Error::UnsupportedVersion(self.input[0]));
+            }
+
+            // C2 echoes S1.
+            let s1 = self.input[1..1 + HANDSHAKE_SIZE].to_vec();
+            self.output.extend_from_slice(&s1);
+            self.input.drain(..1 + 2 * HANDSHAKE_SIZE);
+            self.state = State::Connecting;
+
+            self.send_control(SET_CHUNK_SIZE, CHUNK_SIZE.to_be_bytes().to_vec());
+            self.encoder.set_chunk_size(CHUNK_SIZE as usize);
+            self.send_control(
+                WINDOW_ACKNOWLEDGEMENT_SIZE,
+                WINDOW_ACK_SIZE.to_be_bytes().to_vec(),
+            );
+            if let Some(connect_command) = self.connect_command.take() {
+                self.encoder
+                    .encode(COMMAND_CHUNK_STREAM, &connect_command, &mut self.output);
+            }
+        }
+
+        let mut events = Vec::new();
+        while let Some(message) = self.decoder.decode(&mut self.input)? {
+            self.handle_message(message, &mut events)?;
+        }
+
+        if let Some(window_ack_size) = self.window_ack_size {
+            if self.bytes_received.wrapping_sub(self.last_acknowledgement) >= window_ack_size {
+                self.last_acknowledgement = self.bytes_received;
+                self.send_control(ACKNOWLEDGEMENT, self.bytes_received.to_be_bytes().to_vec());
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// Ask the server to create a message stream. `request` is reported back
+    /// in `Event::StreamCreated`.
+    pub fn create_stream(&mut self, request: u32) {
+        let transaction_id = self.transaction(Transaction::CreateStream(request));
+        self.send_command(command(
+            0,
+            "createStream",
+            transaction_id,
+            &[AmfValue::Null],
+        ));
+    }
+
+    /// Start playing the stream `name` on a message stream, from `start`
+    /// seconds into a recorded stream, or live if `start` is -1 or -2.
+    pub fn play(&mut self, stream_id: u32, name: &str, start: f64, buffer_time: u32) {
+        self.send_command(command(
+            stream_id,
+            "play",
+            0,
+            &[
+                AmfValue::Null,
+                AmfValue::String(name.to_string()),
+                AmfValue::Number(start),
+            ],
+        ));
+        self.set_buffer_length(stream_id, buffer_time);
+    }
+
+    /// Pause or resume a message stream at `time` milliseconds.
+    pub fn pause(&mut self, stream_id: u32, paused: bool, time: f64) {
+        self.send_command(command(
+            stream_id,
+            "pause",
+            0,
+            &[
+                AmfValue::Null,
+                AmfValue::Bool(paused),
+                AmfValue::Number(time),
+            ],
+        ));
+    }
+
+    /// Continue a message stream from `time` milliseconds.
+    pub fn seek(&mut self, stream_id: u32, time: f64) {
+        self.send_command(command(
+            stream_id,
+            "seek",
+            0,
+            &[AmfValue::Null, AmfValue::Number(time)],
+        ));
+    }
+
+    /// Stop a message stream, and have the server delete it.
+    pub fn delete_stream(&mut self, stream_id: u32) {
+        self.send_command(command(stream_id, "closeStream", 0, &[AmfValue::Null]));
+        self.send_command(command(
+            0,
+            "deleteStream",
+            0,
+            &[AmfValue::Null, AmfValue::Number(stream_id.into())],
+        ));
+    }
+
+    /// Tell the server how many milliseconds of a stream are buffered.
+    pub fn set_buffer_length(&mut self, stream_id: u32, buffer_time: u32) {
+        let mut payload = SET_BUFFER_LENGTH.to_be_bytes().to_vec();
+        payload.extend_from_slice(&stream_id.to_be_bytes());
+        payload.extend_from_slice(&buffer_time.to_be_bytes());
+        self.send_control(USER_CONTROL, payload);
+    }
+
+    fn transaction(&mut self, transaction: Transaction) -> u32 {
+        let transaction_id = self.next_transaction_id;
+        self.next_transaction_id += 1;
+        self.transactions.insert(transaction_id, transaction);
+        transaction_id
+    }
+
+    fn send_control(&mut self, type_id: u8, payload: Vec<u8>) {
+        let message = Message {
+            timestamp: 0,
+            type_id,
+            stream_id: 0,
+            payload,
+        };
+        self.encoder
+            .encode(CONTROL_CHUNK_STREAM, &message, &mut self.output);
+    }
+
+    /// Send a command, or queue it until the connection is established.
+    fn send_command(&mut self, message: Message) {
+        match self.state {
+            State::Connected => {
+                self.encoder
+                    .encode(COMMAND_CHUNK_STREAM, &message, &mut self.output)
+            }
+            State::Handshake | State::Connecting => self.queued.push(message),
+            State::Closed => {}
+        }
+    }
+
+    fn handle_message(&mut self, message: Message, events: &mut Vec<Event>) -> Result<(), Error> {
+        let payload = &message.payload;
+        match message.type_id {
+            SET_CHUNK_SIZE => {
+                let size = read_u32(payload)? & 0x7FFF_FFFF;
+                self.decoder.set_chunk_size(size as usize)?;
+            }
+            ABORT => self.decoder.abort(read_u32(payload)?),
+            WINDOW_ACKNOWLEDGEMENT_SIZE => self.window_ack_size = Some(read_u32(payload)?),
+            SET_PEER_BANDWIDTH => {
+                let size = read_u32(payload)?;
+                self.send_control(WINDOW_ACKNOWLEDGEMENT_SIZE, size.to_be_bytes().to_vec());
+            }
+            USER_CONTROL => {
+                let event_type = payload.get(..2).ok_or(Error::InvalidCommand)?;
+                if u16::from_be_bytes([event_type[0], event_type[1]]) == PING_REQUEST {
+                    let mut response = PING_RESPONSE.to_be_bytes().to_vec();
+                    response.extend_from_slice(&payload[2..]);
+                    self.send_control(USER_CONTROL, response);
+                }
+            }
+            AUDIO | VIDEO if !payload.is_empty() => {
+                let (stream_id, timestamp, data) =
+                    (message.stream_id, message.timestamp, message.payload);
+                events.push(if message.type_id == AUDIO {
+                    Event::Audio {
+                        stream_id,
+                        timestamp,
+                        data,
+                    }
+                } else {
+                    Event::Video {
+                        stream_id,
+                        timestamp,
+                        data,
+                    }
+                });
+            }
+            DATA_AMF0 | DATA_AMF3 => {
+                let payload = strip_amf3_marker(message.type_id == DATA_AMF3, payload);
+                let mut values = read_values(payload)?.into_iter();
+                let mut name = match values.next() {
+                    Some(AmfValue::String(name)) => name,
+                    _ => return Err(Error::InvalidCommand),
+                };
+                // Metadata set by a publisher is wrapped in another call.
+                if name == "@setDataFrame" {
+                    name = match values.next() {
+                        Some(AmfValue::String(name)) => name,
+                        _ => return Err(Error::InvalidCommand),
+                    };
+                }
+                events.push(Event::Call {
+                    stream_id: message.stream_id,
+                    name,
+                    arguments: values.collect(),
+                });
+            }
+            COMMAND_AMF0 | COMMAND_AMF3 => {
+                let payload = strip_amf3_marker(message.type_id == COMMAND_AMF3, payload);
+                self.handle_command(message.stream_id, read_values(payload)?, events);
+            }
+            AGGREGATE => {
+                for message in split_aggregate(&message) {
+                    self.handle_message(message, events)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_command(&mut self, stream_id: u32, values: Vec<AmfValue>, events: &mut Vec<Event>) {
+        let mut values = values.into_iter();
+        let name = match values.next() {
+            Some(AmfValue::String(name)) => name,
+            _ => return,
+        };
+        let transaction_id = match values.next() {
+            Some(AmfValue::Number(id)) => id as u32,
+            _ => 0,
+        };
+        // Skip the command object.
+        let _ = values.next();
+        let info = values.next();
+
+        match name.as_str() {
+            "_result" | "_error" => {
+                let success = name == "_result";
+                match self.transactions.remove(&transaction_id) {
+                    Some(Transaction::Connect) => {
+                        self.state = if success {
+                            State::Connected
+                        } else {
+                            State::Closed
+                        };
+                        let info = info.unwrap_or_else(|| {
+                            status_info("NetConnection.Connect.Rejected", "error")
+                        });
+                        events.push(Event::Status { stream_id: 0, info });
+
+                        for message in std::mem::take(&mut self.queued) {
+                            self.send_command(message);
+                        }
+                    }
+                    Some(Transaction::CreateStream(request)) => match info {
+                        Some(AmfValue::Number(stream_id)) if success => {
+                            events.push(Event::StreamCreated {
+                                request,
+                                stream_id: stream_id as u32,
+                            })
+                        }
+                        _ => log::warn!("RTMP server failed to create a stream"),
+                    },
+                    None => {}
+                }
+            }
+            "onStatus" => {
+                if let Some(info) = info {
+                    events.push(Event::Status { stream_id, info });
+                }
+            }
+            "close" => {
+                self.state = State::Closed;
+                events.push(Event::Status {
+                    stream_id: 0,
+                    info: status_info("NetConnection.Connect.Closed", "status"),
+                });
+            }
+            _ => events.push(Event::Call {
+                stream_id,
+                name,
+                arguments: info.into_iter().chain(values).collect(),
+            }),
+        }
+    }
+}
+
+/// Build an AMF0 command message.
+fn command(stream_id: u32, name: &str, transaction_id: u32, arguments: &[AmfValue]) -> Message {
+    let mut payload = Vec::new();
+    amf0::write_string(&mut payload, name);
+    amf0::write_number(&mut payload, transaction_id.into());
+    for argument in arguments {
+        amf0::write_value(&mut payload, argument);
+    }
+    Message {
+        timestamp: 0,
+        type_id: COMMAND_AMF0,
+        stream_id,
+        payload,
+    }
+}
+
+/// An info object, as passed to `onStatus`.
+pub fn status_info(code: &str, level: &str) -> AmfValue {
+    AmfValue::Object(
+        vec![
+            Element::new("code", AmfValue::String(code.to_string())),
+            Element::new("level", AmfValue::String(level.to_string())),
+        ],
+        None,
+    )
+}
+
+/// AMF3 messages start with a marker byte, followed by AMF0 values that
+/// may switch to AMF3 for individual values.
+fn strip_amf3_marker(is_amf3: bool, payload: &[u8]) -> &[u8] {
+    match payload.split_first() {
+        Some((0, rest)) if is_amf3 => rest,
+        _ => payload,
+    }
+}
+
+fn read_values(mut data: &[u8]) -> Result<Vec<AmfValue>, Error> {
+    let mut decoder = AMF0Decoder::default();
+    let mut values = Vec::new();
+    while !data.is_empty() {
+        let (rest, value) = decoder
+            .parse_single_element(data)
+            .map_err(|_| Error::InvalidCommand)?;
+        values.push(value);
+        data = rest;
+    }
+    Ok(values)
+}
+
+fn read_u32(payload: &[u8]) -> Result<u32, Error> {
+    match payload.get(..4) {
+        Some(bytes) => Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+        None => Err(Error::InvalidCommand),
+    }
+}
+
+/// Split an aggregate message into the audio, video and data messages it
+/// carries as FLV tags.
+fn split_aggregate(message: &Message) -> Vec<Message> {
+    let mut messages = Vec::new();
+    let mut data = &message.payload[..];
+    let mut first_timestamp = None;
+    while data.len() >= 11 {
+        let type_id = data[0];
+        let size = u32::from_be_bytes([0, data[1], data[2], data[3]]) as usize;
+        let timestamp = u32::from_be_bytes([data[7], data[4], data[5], data[6]]);
+        let payload = match data.get(11..11 + size) {
+            Some(payload) => payload,
+            None => break,
+        };
+
+        // Timestamps are relative to the timestamp of the aggregate message.
+        let first_timestamp = *first_timestamp.get_or_insert(timestamp);
+        messages.push(Message {
+            timestamp: message
+                .timestamp
+                .wrapping_add(timestamp.wrapping_sub(first_timestamp)),
+            type_id,
+            stream_id: message.stream_id,
+            payload: payload.to_vec(),
+        });
+
+        // Each tag is followed by its size.
+        data = data.get(11 + size + 4..).unwrap_or_default();
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Play the server side of the handshake.
+    fn handshake(client: &mut Client) {
+        let c0c1 = client.take_output();
+        assert_eq!(c0c1.len(), 1 + HANDSHAKE_SIZE);
+        assert_eq!(c0c1[0], RTMP_VERSION);
+
+        let mut s0s1s2 = vec![RTMP_VERSION];
+        s0s1s2.extend(std::iter::repeat(7).take(HANDSHAKE_SIZE));
+        s0s1s2.extend_from_slice(&c0c1[1..]);
+        assert_eq!(client.receive(&s0s1s2).unwrap(), vec![]);
+    }
+
+    fn server_command(
+        stream_id: u32,
+        name: &str,
+        transaction_id: u32,
+        args: &[AmfValue],
+    ) -> Vec<u8> {
+        let mut data = Vec::new();
+        Encoder::new().encode(
+            3,
+            &command(stream_id, name, transaction_id, args),
+            &mut data,
+        );
+        data
+    }
+
+    #[test]
+    fn parse_url() {
+        let url = RtmpUrl::parse("rtmp://example.com/vod/instance?token=1").unwrap();
+        assert_eq!(url.protocol, Protocol::Rtmp);
+        assert_eq!(url.host, "example.com");
+        assert_eq!(url.port, 1935);
+        assert_eq!(url.app, "vod/instance?token=1");
+
+        let url = RtmpUrl::parse("rtmpt://example.com:8080/live").unwrap();
+        assert_eq!((url.protocol, url.port), (Protocol::Rtmpt, 8080));
+        assert!(RtmpUrl::parse("http://example.com/live").is_err());
+    }
+
+    #[test]
+    fn connect_and_play() {
+        let url = RtmpUrl::parse("rtmp://example.com/vod").unwrap();
+        let mut client = Client::new(&url, None, None, &[]);
+        handshake(&mut client);
+
+        // C2 echoes S1, followed by the `connect` command.
+        let output = client.take_output();
+        assert!(output[..HANDSHAKE_SIZE].iter().all(|byte| *byte == 7));
+        let mut decoder = Decoder::new();
+        let mut sent = output[HANDSHAKE_SIZE..].to_vec();
+        let mut messages = std::iter::from_fn(|| decoder.decode(&mut sent).unwrap());
+        assert_eq!(messages.next().unwrap().type_id, SET_CHUNK_SIZE);
+        decoder.set_chunk_size(CHUNK_SIZE as usize).unwrap();
+        let mut messages = std::iter::from_fn(|| decoder.decode(&mut sent).unwrap());
+        assert_eq!(
+            messages.next().unwrap().type_id,
+            WINDOW_ACKNOWLEDGEMENT_SIZE
+        );
+        let connect = messages.next().unwrap();
+        let values = read_values(&connect.payload).unwrap();
+        assert_eq!(values[0], AmfValue::String("connect".to_string()));
+
+        // Commands wait for the connection to be accepted.
+        client.create_stream(5);
+        assert!(client.take_output().is_empty());
+
+        let info = status_info("NetConnection.Connect.Success", "status");
+        let events = client
+            .receive(&server_command(
+                0,
+                "_result",
+                1,
+                &[AmfValue::Null, info.clone()],
+            ))
+            .unwrap();
+        assert_eq!(events, vec![Event::Status { stream_id: 0, info }]);
+        assert!(client.is_connected());
+        assert!(!client.take_output().is_empty());
+
+        let events = client
+            .receive(&server_command(
+                0,
+                "_result",
+                2,
+                &[AmfValue::Null, AmfValue::Number(1.0)],
+            ))
+            .unwrap();
+        assert_eq!(
+            events,
+            vec![Event::StreamCreated {
+                request: 5,
+                stream_id: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn aggregate() {
+        let mut payload = Vec::new();
+        for (timestamp, data) in [(100u32, 0xAA), (140, 0xBB)] {
+            payload.extend_from_slice(&[VIDEO, 0, 0, 1]);
+            payload.extend_from_slice(&timestamp.to_be_bytes()[1..]);
+            payload.extend_from_slice(&[0, 0, 0, 0, data]);
+            payload.extend_from_slice(&12u32.to_be_bytes());
+        }
+        let message = Message {
+            timestamp: 1000,
+            type_id: AGGREGATE,
+            stream_id: 1,
+            payload,
+        };
+
+        let messages = split_aggregate(&message);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            (messages[0].timestamp, &messages[0].payload[..]),
+            (1000, &[0xAA][..])
+        );
+        assert_eq!(
+            (messages[1].timestamp, &messages[1].payload[..]),
+            (1040, &[0xBB][..])
+        );
+    }
+}
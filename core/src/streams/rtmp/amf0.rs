@@ -0,0 +1,112 @@
+//! AMF0 encoding of the values sent in RTMP command messages.
+//!
+//! Values are decoded with `flash_lso`, but its writer only produces whole
+//! LSO files.
+
+use flash_lso::types::Value as AmfValue;
+
+const NUMBER: u8 = 0x00;
+const BOOLEAN: u8 = 0x01;
+const STRING: u8 = 0x02;
+const OBJECT: u8 = 0x03;
+const NULL: u8 = 0x05;
+const UNDEFINED: u8 = 0x06;
+const ECMA_ARRAY: u8 = 0x08;
+const OBJECT_END: u8 = 0x09;
+const DATE: u8 = 0x0B;
+const LONG_STRING: u8 = 0x0C;
+const XML_DOCUMENT: u8 = 0x0F;
+
+/// Append the AMF0 encoding of `value` to `out`.
+///
+/// Values that have no AMF0 representation are written as `undefined`.
+pub fn write_value(out: &mut Vec<u8>, value: &AmfValue) {
+    match value {
+        AmfValue::Number(number) => write_number(out, *number),
+        AmfValue::Integer(integer) => write_number(out, (*integer).into()),
+        AmfValue::Bool(boolean) => {
+            out.push(BOOLEAN);
+            out.push((*boolean).into());
+        }
+        AmfValue::String(string) => write_string(out, string),
+        AmfValue::Null => out.push(NULL),
+        AmfValue::Object(elements, _) => {
+            out.push(OBJECT);
+            write_properties(out, elements);
+        }
+        AmfValue::ECMAArray(_, elements, length) => {
+            out.push(ECMA_ARRAY);
+            out.extend_from_slice(&length.to_be_bytes());
+            write_properties(out, elements);
+        }
+        AmfValue::Date(time, _) => {
+            out.push(DATE);
+            out.extend_from_slice(&time.to_be_bytes());
+            // The time zone is reserved, and always zero.
+            out.extend_from_slice(&[0, 0]);
+        }
+        AmfValue::XML(content, _) => {
+            out.push(XML_DOCUMENT);
+            out.extend_from_slice(&(content.len() as u32).to_be_bytes());
+            out.extend_from_slice(content.as_bytes());
+        }
+        _ => out.push(UNDEFINED),
+    }
+}
+
+pub fn write_number(out: &mut Vec<u8>, number: f64) {
+    out.push(NUMBER);
+    out.extend_from_slice(&number.to_be_bytes());
+}
+
+pub fn write_string(out: &mut Vec<u8>, string: &str) {
+    match u16::try_from(string.len()) {
+        Ok(len) => {
+            out.push(STRING);
+            out.extend_from_slice(&len.to_be_bytes());
+        }
+        Err(_) => {
+            out.push(LONG_STRING);
+            out.extend_from_slice(&(string.len() as u32).to_be_bytes());
+        }
+    }
+    out.extend_from_slice(string.as_bytes());
+}
+
+/// Write the properties of an object, followed by the end marker.
+fn write_properties(out: &mut Vec<u8>, elements: &[flash_lso::types::Element]) {
+    for element in elements {
+        // Property names are always short strings, without a type marker.
+        let name = &element.name.as_bytes()[..element.name.len().min(u16::MAX.into())];
+        out.extend_from_slice(&(name.len() as u16).to_be_bytes());
+        out.extend_from_slice(name);
+        write_value(out, element.value());
+    }
+    out.extend_from_slice(&[0, 0, OBJECT_END]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flash_lso::amf0::read::AMF0Decoder;
+    use flash_lso::types::Element;
+
+    #[test]
+    fn round_trip() {
+        let value = AmfValue::Object(
+            vec![
+                Element::new("app", AmfValue::String("live".to_string())),
+                Element::new("fpad", AmfValue::Bool(false)),
+                Element::new("audioCodecs", AmfValue::Number(3575.0)),
+                Element::new("objectEncoding", AmfValue::Null),
+            ],
+            None,
+        );
+        let mut data = Vec::new();
+        write_value(&mut data, &value);
+
+        let (rest, decoded) = AMF0Decoder::default().parse_single_element(&data).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(decoded, value);
+    }
+}
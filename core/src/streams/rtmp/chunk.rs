@@ -0,0 +1,406 @@
+//! The chunk stream layer of RTMP.
+//!
+//! Messages are split into chunks of a negotiated maximum size, so that the
+//! messages of several chunk streams can be interleaved on one connection.
+//! Chunk headers only repeat the fields of a message header that changed
+//! since the previous chunk of the same chunk stream.
+
+use super::Error;
+use std::collections::HashMap;
+
+/// The chunk size that both sides use until told otherwise.
+pub const DEFAULT_CHUNK_SIZE: usize = 128;
+
+/// The largest chunk size that may be negotiated.
+const MAX_CHUNK_SIZE: usize = 0x7FFF_FFFF;
+
+/// Timestamps at or above this value are sent in an extended field.
+const EXTENDED_TIMESTAMP: u32 = 0xFF_FFFF;
+
+/// A complete message, reassembled from its chunks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Message {
+    /// The timestamp of the message, in milliseconds.
+    pub timestamp: u32,
+
+    pub type_id: u8,
+
+    /// The message stream that this message belongs to. Stream 0 is used
+    /// for control messages and commands of the connection itself.
+    pub stream_id: u32,
+
+    pub payload: Vec<u8>,
+}
+
+/// The header fields of the last chunk of a chunk stream.
+#[derive(Default)]
+struct ChunkStream {
+    timestamp: u32,
+    timestamp_delta: u32,
+    length: usize,
+    type_id: u8,
+    stream_id: u32,
+    has_extended_timestamp: bool,
+
+    /// The payload of a message whose chunks haven't all arrived yet.
+    payload: Vec<u8>,
+}
+
+/// Reassembles received chunks into messages.
+pub struct Decoder {
+    chunk_size: usize,
+    chunk_streams: HashMap<u32, ChunkStream>,
+}
+
+impl Decoder {
+    pub fn new() -> Self {
+        Self {
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            chunk_streams: HashMap::new(),
+        }
+    }
+
+    /// Change the size of the chunks that the peer sends.
+    pub fn set_chunk_size(&mut self, chunk_size: usize) -> Result<(), Error> {
+        if !(1..=MAX_CHUNK_SIZE).contains(&chunk_size) {
+            return Err(Error::InvalidChunkSize(chunk_size));
+        }
+        self.chunk_size = chunk_size;
+        Ok(())
+    }
+
+    /// Discard the partially received message of a chunk stream.
+    pub fn abort(&mut self, chunk_stream_id: u32) {
+        if let Some(chunk_stream) = self.chunk_streams.get_mut(&chunk_stream_id) {
+            chunk_stream.payload.clear();
+        }
+    }
+
+    /// Read chunks from the start of `data` until a message is complete.
+    ///
+    /// Consumed chunks are removed from `data`. Returns `None` once `data`
+    /// doesn't hold a complete chunk anymore.
+    pub fn decode(&mut self, data: &mut Vec<u8>) -> Result<Option<Message>, Error> {
+        loop {
+            let chunk = match self.read_chunk_header(data)? {
+                Some(chunk) => chunk,
+                None => return Ok(None),
+            };
+
+            let chunk_stream = self.chunk_streams.entry(chunk.chunk_stream_id).or_default();
+            let starts_message = chunk_stream.payload.is_empty();
+            match chunk.format {
+                0 => {
+                    chunk_stream.timestamp = chunk.timestamp;
+                    chunk_stream.timestamp_delta = chunk.timestamp;
+                }
+                1 | 2 => {
+                    chunk_stream.timestamp_delta = chunk.timestamp;
+                    chunk_stream.timestamp = chunk_stream.timestamp.wrapping_add(chunk.timestamp);
+                }
+                _ if starts_message => {
+                    chunk_stream.timestamp = chunk_stream
+                        .timestamp
+                        .wrapping_add(chunk_stream.timestamp_delta);
+                }
+                _ => {}
+            }
+            if let Some(length) = chunk.length {
+                chunk_stream.length = length;
+            }
+            if let Some(type_id) = chunk.type_id {
+                chunk_stream.type_id = type_id;
+            }
+            if let Some(stream_id) = chunk.stream_id {
+                chunk_stream.stream_id = stream_id;
+            }
+            chunk_stream.has_extended_timestamp = chunk.has_extended_timestamp;
+
+            chunk_stream
+                .payload
+                .extend_from_slice(&data[chunk.header_length..chunk.total_length]);
+            data.drain(..chunk.total_length);
+
+            if chunk_stream.payload.len() >= chunk_stream.length {
+                return Ok(Some(Message {
+                    timestamp: chunk_stream.timestamp,
+                    type_id: chunk_stream.type_id,
+                    stream_id: chunk_stream.stream_id,
+                    payload: std::mem::take(&mut chunk_stream.payload),
+                }));
+            }
+        }
+    }
+
+    /// Parse the header of the chunk at the start of `data`, if the whole
+    /// chunk has been received.
+    fn read_chunk_header(&self, data: &[u8]) -> Result<Option<ChunkHeader>, Error> {
+        let first = match data.first() {
+            Some(first) => *first,
+            None => return Ok(None),
+        };
+        let format = first >> 6;
+        let (chunk_stream_id, mut position) = match first & 0b11_1111 {
+            0 => match data.get(1) {
+                Some(id) => (64 + u32::from(*id), 2),
+                None => return Ok(None),
+            },
+            1 => match data.get(1..3) {
+                Some(id) => (64 + u32::from(id[0]) + (u32::from(id[1]) << 8), 3),
+                None => return Ok(None),
+            },
+            id => (u32::from(id), 1),
+        };
+
+        let previous = self.chunk_streams.get(&chunk_stream_id);
+        if format != 0 && previous.is_none() {
+            return Err(Error::UnknownChunkStream(chunk_stream_id));
+        }
+
+        let header_size = [11, 7, 3, 0][usize::from(format)];
+        let header = match data.get(position..position + header_size) {
+            Some(header) => header,
+            None => return Ok(None),
+        };
+        position += header_size;
+
+        let mut chunk = ChunkHeader {
+            format,
+            chunk_stream_id,
+            timestamp: 0,
+            length: None,
+            type_id: None,
+            stream_id: None,
+            has_extended_timestamp: false,
+            header_length: 0,
+            total_length: 0,
+        };
+        if format <= 2 {
+            chunk.timestamp = read_u24(&header[0..3]);
+            chunk.has_extended_timestamp = chunk.timestamp == EXTENDED_TIMESTAMP;
+        } else if let Some(previous) = previous {
+            chunk.has_extended_timestamp = previous.has_extended_timestamp;
+        }
+        if format <= 1 {
+            chunk.length = Some(read_u24(&header[3..6]) as usize);
+            chunk.type_id = Some(header[6]);
+        }
+        if format == 0 {
+            chunk.stream_id = Some(u32::from_le_bytes([
+                header[7], header[8], header[9], header[10],
+            ]));
+        }
+
+        if chunk.has_extended_timestamp {
+            let extended = match data.get(position..position + 4) {
+                Some(extended) => extended,
+                None => return Ok(None),
+            };
+            let extended = u32::from_be_bytes([extended[0], extended[1], extended[2], extended[3]]);
+            // Continuation chunks repeat the timestamp they extend.
+            if format <= 2 {
+                chunk.timestamp = extended;
+            }
+            position += 4;
+        }
+
+        let (length, received) = match previous {
+            Some(previous) if !previous.payload.is_empty() => (
+                chunk.length.unwrap_or(previous.length),
+                previous.payload.len(),
+            ),
+            Some(previous) => (chunk.length.unwrap_or(previous.length), 0),
+            None => (chunk.length.unwrap_or_default(), 0),
+        };
+        let chunk_length = length.saturating_sub(received).min(self.chunk_size);
+        if data.len() < position + chunk_length {
+            return Ok(None);
+        }
+
+        chunk.header_length = position;
+        chunk.total_length = position + chunk_length;
+        Ok(Some(chunk))
+    }
+}
+
+impl Default for Decoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The parsed header of a single chunk.
+struct ChunkHeader {
+    format: u8,
+    chunk_stream_id: u32,
+
+    /// The absolute timestamp for format 0, or the delta for formats 1 and 2.
+    timestamp: u32,
+
+    length: Option<usize>,
+    type_id: Option<u8>,
+    stream_id: Option<u32>,
+    has_extended_timestamp: bool,
+
+    /// The size of the header, including the extended timestamp.
+    header_length: usize,
+
+    /// The size of the header and the data of the chunk.
+    total_length: usize,
+}
+
+/// Splits outgoing messages into chunks.
+pub struct Encoder {
+    chunk_size: usize,
+}
+
+impl Encoder {
+    pub fn new() -> Self {
+        Self {
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    /// Change the size of the chunks that are sent.
+    ///
+    /// The peer has to be told about the new size with a Set Chunk Size
+    /// message first.
+    pub fn set_chunk_size(&mut self, chunk_size: usize) {
+        self.chunk_size = chunk_size.clamp(1, MAX_CHUNK_SIZE);
+    }
+
+    /// Append the chunks of `message` to `out`.
+    ///
+    /// Every message starts with a full header, so that no state has to be
+    /// tracked for the chunk streams that are sent on.
+    pub fn encode(&self, chunk_stream_id: u32, message: &Message, out: &mut Vec<u8>) {
+        let has_extended_timestamp = message.timestamp >= EXTENDED_TIMESTAMP;
+        let timestamp = message.timestamp.min(EXTENDED_TIMESTAMP);
+
+        write_basic_header(out, 0, chunk_stream_id);
+        out.extend_from_slice(&timestamp.to_be_bytes()[1..]);
+        out.extend_from_slice(&(message.payload.len() as u32).to_be_bytes()[1..]);
+        out.push(message.type_id);
+        out.extend_from_slice(&message.stream_id.to_le_bytes());
+        if has_extended_timestamp {
+            out.extend_from_slice(&message.timestamp.to_be_bytes());
+        }
+
+        for (i, chunk) in message.payload.chunks(self.chunk_size).enumerate() {
+            if i > 0 {
+                write_basic_header(out, 3, chunk_stream_id);
+                if has_extended_timestamp {
+                    out.extend_from_slice(&message.timestamp.to_be_bytes());
+                }
+            }
+            out.extend_from_slice(chunk);
+        }
+    }
+}
+
+impl Default for Encoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn write_basic_header(out: &mut Vec<u8>, format: u8, chunk_stream_id: u32) {
+    let format = format << 6;
+    match chunk_stream_id {
+        0..=63 => out.push(format | chunk_stream_id as u8),
+        64..=319 => out.extend_from_slice(&[format, (chunk_stream_id - 64) as u8]),
+        _ => {
+            let id = (chunk_stream_id - 64) as u16;
+            out.push(format | 1);
+            out.extend_from_slice(&id.to_le_bytes());
+        }
+    }
+}
+
+fn read_u24(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(timestamp: u32, len: usize) -> Message {
+        Message {
+            timestamp,
+            type_id: 9,
+            stream_id: 1,
+            payload: (0..len).map(|i| i as u8).collect(),
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let encoder = Encoder::new();
+        let mut decoder = Decoder::new();
+        let messages = [message(0, 300), message(0x0100_0000, 200), message(40, 0)];
+
+        let mut data = Vec::new();
+        for message in &messages {
+            encoder.encode(6, message, &mut data);
+        }
+        encoder.encode(400, &messages[0], &mut data);
+
+        for message in &messages {
+            assert_eq!(decoder.decode(&mut data).unwrap().as_ref(), Some(message));
+        }
+        assert_eq!(
+            decoder.decode(&mut data).unwrap().as_ref(),
+            Some(&messages[0])
+        );
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn partial_chunks() {
+        let mut data = Vec::new();
+        Encoder::new().encode(3, &message(0, 200), &mut data);
+        let mut decoder = Decoder::new();
+
+        let mut received = data[..150].to_vec();
+        assert_eq!(decoder.decode(&mut received).unwrap(), None);
+        // The complete first chunk has been consumed.
+        assert_eq!(received.len(), 150 - 12 - 128);
+
+        received.extend_from_slice(&data[150..]);
+        assert_eq!(
+            decoder.decode(&mut received).unwrap(),
+            Some(message(0, 200))
+        );
+    }
+
+    #[test]
+    fn compressed_headers() {
+        let mut data = vec![
+            // Format 0: timestamp 1000, length 2, type 8, stream 1.
+            0x04, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x02, 0x08, 0x01, 0x00, 0x00, 0x00, 0xAA, 0xBB,
+            // Format 2: delta 20.
+            0x84, 0x00, 0x00, 0x14, 0xCC, 0xDD, // Format 3: the same delta again.
+            0xC4, 0xEE, 0xFF,
+        ];
+        let mut decoder = Decoder::new();
+
+        let timestamps: Vec<_> = std::iter::from_fn(|| decoder.decode(&mut data).unwrap())
+            .map(|message| (message.timestamp, message.payload))
+            .collect();
+        assert_eq!(
+            timestamps,
+            [
+                (1000, vec![0xAA, 0xBB]),
+                (1020, vec![0xCC, 0xDD]),
+                (1040, vec![0xEE, 0xFF])
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_chunk_stream() {
+        let mut decoder = Decoder::new();
+        assert!(decoder.decode(&mut vec![0xC5]).is_err());
+    }
+}
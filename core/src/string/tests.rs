@@ -209,3 +209,26 @@ fn str_patterns() {
     test_pattern(wide, bstr!(b"aa"), &[(2, 4), (6, 8)], None);
     test_pattern(wide, wstr!('↓''a'), &[(1, 3), (5, 7)], None);
 }
+
+#[test]
+fn word_boundaries() {
+    use super::utils::{next_word_boundary, prev_word_boundary, word_range_at};
+
+    let s = bstr!(b"hello, big  world");
+    assert_eq!(next_word_boundary(s, 0), 5);
+    assert_eq!(next_word_boundary(s, 5), 7);
+    assert_eq!(next_word_boundary(s, 7), 12);
+    assert_eq!(next_word_boundary(s, 12), 17);
+    assert_eq!(next_word_boundary(s, 17), 17);
+
+    assert_eq!(prev_word_boundary(s, 17), 12);
+    assert_eq!(prev_word_boundary(s, 12), 7);
+    assert_eq!(prev_word_boundary(s, 7), 5);
+    assert_eq!(prev_word_boundary(s, 5), 0);
+    assert_eq!(prev_word_boundary(s, 0), 0);
+
+    assert_eq!(word_range_at(s, 2), 0..5);
+    assert_eq!(word_range_at(s, 5), 5..6);
+    assert_eq!(word_range_at(s, 10), 10..12);
+    assert_eq!(word_range_at(s, 17), 17..17);
+}
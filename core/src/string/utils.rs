@@ -40,6 +40,78 @@ pub fn next_char_boundary(slice: &super::WStr, pos: usize) -> usize {
     }
 }
 
+/// Returns `true` if the given utf16 code unit is part of a word when moving
+/// the caret or selecting text by word.
+fn is_word_unit(c: u16) -> bool {
+    // Surrogates are parts of non-BMP characters, which are mostly letters.
+    (0xD800..=0xDFFF).contains(&c)
+        || char::from_u32(c.into()).map_or(false, |c| c.is_alphanumeric() || c == '_')
+}
+
+/// Gets the position that moving the caret back by a word from `pos` goes
+/// to, which is the start of the previous word.
+pub fn prev_word_boundary(slice: &super::WStr, mut pos: usize) -> usize {
+    pos = pos.min(slice.len());
+    while pos > 0 && swf_is_whitespace(slice.at(pos - 1)) {
+        pos -= 1;
+    }
+    if pos > 0 && !is_word_unit(slice.at(pos - 1)) {
+        return prev_char_boundary(slice, pos);
+    }
+    while pos > 0 && is_word_unit(slice.at(pos - 1)) {
+        pos -= 1;
+    }
+    pos
+}
+
+/// Gets the position that moving the caret forward by a word from `pos`
+/// goes to, which is the start of the next word.
+pub fn next_word_boundary(slice: &super::WStr, mut pos: usize) -> usize {
+    let len = slice.len();
+    if pos >= len {
+        return len;
+    }
+    if is_word_unit(slice.at(pos)) {
+        while pos < len && is_word_unit(slice.at(pos)) {
+            pos += 1;
+        }
+    } else if !swf_is_whitespace(slice.at(pos)) {
+        pos = next_char_boundary(slice, pos);
+    }
+    while pos < len && swf_is_whitespace(slice.at(pos)) {
+        pos += 1;
+    }
+    pos
+}
+
+/// Gets the range of the word at `pos`, which is selected by double-clicking.
+///
+/// A run of whitespace counts as a word, and any other character that isn't
+/// part of a word is a word by itself.
+pub fn word_range_at(slice: &super::WStr, pos: usize) -> std::ops::Range<usize> {
+    let len = slice.len();
+    if pos >= len {
+        return len..len;
+    }
+    let c = slice.at(pos);
+    let same_kind: fn(u16) -> bool = if is_word_unit(c) {
+        is_word_unit
+    } else if swf_is_whitespace(c) {
+        swf_is_whitespace
+    } else {
+        return pos..next_char_boundary(slice, pos);
+    };
+    let mut start = pos;
+    while start > 0 && same_kind(slice.at(start - 1)) {
+        start -= 1;
+    }
+    let mut end = pos;
+    while end < len && same_kind(slice.at(end)) {
+        end += 1;
+    }
+    start..end
+}
+
 /// Returns `true` if the given utf16 code unit is an whitespace
 /// according to the Flash Player.
 #[inline]
@@ -1,58 +1,106 @@
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use ruffle_core::backend::audio::{
-    swf, AudioBackend, AudioMixer, SoundHandle, SoundInstanceHandle, SoundTransform,
+    swf, AudioBackend, AudioMixer, Resampling, SoundHandle, SoundInstanceHandle, SoundTransform,
 };
 use ruffle_core::impl_audio_mixer_backend;
+use std::sync::{Arc, Mutex};
 
 pub struct CpalAudioBackend {
     #[allow(dead_code)]
     device: cpal::Device,
-    #[allow(dead_code)]
     config: cpal::StreamConfig,
     stream: cpal::Stream,
     mixer: AudioMixer,
+    capture: Option<Capture>,
+
+    /// Whether the output stream is playing, so that a new output device resumes in the same state.
+    is_playing: bool,
+}
+
+/// Audio that is being captured from an input device.
+struct Capture {
+    #[allow(dead_code)]
+    stream: cpal::Stream,
+    samples: Arc<Mutex<Vec<i16>>>,
+}
+
+/// Converts input from a capture device to mono samples at the sample rate
+/// that was requested by the player.
+struct CaptureResampler {
+    num_channels: usize,
+
+    /// The number of output samples per input sample frame.
+    step: f64,
+
+    /// The fractional number of output samples that are due.
+    phase: f64,
+
+    /// The largest number of samples to keep if they aren't collected, one
+    /// second's worth.
+    max_samples: usize,
+
+    samples: Arc<Mutex<Vec<i16>>>,
+}
+
+impl CaptureResampler {
+    fn push<T: cpal::Sample>(&mut self, buffer: &[T]) {
+        let mut samples = self.samples.lock().unwrap();
+        for frame in buffer.chunks_exact(self.num_channels) {
+            // Downmix to mono.
+            let sample = frame
+                .iter()
+                .map(|sample| i32::from(sample.to_i16()))
+                .sum::<i32>()
+                / self.num_channels as i32;
+
+            self.phase += self.step;
+            while self.phase >= 1.0 {
+                samples.push(sample as i16);
+                self.phase -= 1.0;
+            }
+        }
+
+        if samples.len() > self.max_samples {
+            let excess = samples.len() - self.max_samples;
+            samples.drain(..excess);
+        }
+    }
 }
 
 type Error = Box<dyn std::error::Error>;
 
+/// The interpolation used to resample sounds to the sample rate of the audio device.
+#[derive(Copy, Clone, PartialEq, Debug, clap::ArgEnum)]
+pub enum AudioResampling {
+    Linear,
+    Cubic,
+    Sinc,
+}
+
+impl From<AudioResampling> for Resampling {
+    fn from(resampling: AudioResampling) -> Self {
+        match resampling {
+            AudioResampling::Linear => Resampling::Linear,
+            AudioResampling::Cubic => Resampling::Cubic,
+            AudioResampling::Sinc => Resampling::Sinc,
+        }
+    }
+}
+
 impl CpalAudioBackend {
-    pub fn new() -> Result<Self, Error> {
+    pub fn new(resampling: Resampling) -> Result<Self, Error> {
         // Create CPAL audio device.
         let host = cpal::default_host();
         let device = host
             .default_output_device()
             .ok_or("No audio devices available")?;
 
-        // Create audio stream for device.
         let config = device.default_output_config()?;
-        let sample_format = config.sample_format();
-        let config = cpal::StreamConfig::from(config);
-        let mixer = AudioMixer::new(config.channels.try_into()?, config.sample_rate.0);
+        let mut mixer = AudioMixer::new(config.channels().try_into()?, config.sample_rate().0);
+        mixer.set_resampling(resampling);
 
         // Start the audio stream.
-        let stream = {
-            let mixer = mixer.proxy();
-            let error_handler = move |err| log::error!("Audio stream error: {}", err);
-
-            match sample_format {
-                cpal::SampleFormat::F32 => device.build_output_stream(
-                    &config,
-                    move |buffer, _| mixer.mix::<f32>(buffer),
-                    error_handler,
-                ),
-                cpal::SampleFormat::I16 => device.build_output_stream(
-                    &config,
-                    move |buffer, _| mixer.mix::<i16>(buffer),
-                    error_handler,
-                ),
-                cpal::SampleFormat::U16 => device.build_output_stream(
-                    &config,
-                    move |buffer, _| mixer.mix::<u16>(buffer),
-                    error_handler,
-                ),
-            }?
-        };
-
+        let (config, stream) = Self::build_output_stream(&device, &mut mixer)?;
         stream.play()?;
 
         Ok(Self {
@@ -60,18 +108,157 @@ impl CpalAudioBackend {
             config,
             stream,
             mixer,
+            capture: None,
+            is_playing: true,
         })
     }
+
+    /// Creates an audio stream on the given device that plays the output of the mixer.
+    ///
+    /// The mixer is switched to the output format of the device.
+    fn build_output_stream(
+        device: &cpal::Device,
+        mixer: &mut AudioMixer,
+    ) -> Result<(cpal::StreamConfig, cpal::Stream), Error> {
+        let config = device.default_output_config()?;
+        let sample_format = config.sample_format();
+        let config = cpal::StreamConfig::from(config);
+        mixer.set_output_format(config.channels.try_into()?, config.sample_rate.0);
+
+        let mixer = mixer.proxy();
+        let error_handler = move |err| log::error!("Audio stream error: {}", err);
+
+        let stream = match sample_format {
+            cpal::SampleFormat::F32 => device.build_output_stream(
+                &config,
+                move |buffer, _| mixer.mix::<f32>(buffer),
+                error_handler,
+            ),
+            cpal::SampleFormat::I16 => device.build_output_stream(
+                &config,
+                move |buffer, _| mixer.mix::<i16>(buffer),
+                error_handler,
+            ),
+            cpal::SampleFormat::U16 => device.build_output_stream(
+                &config,
+                move |buffer, _| mixer.mix::<u16>(buffer),
+                error_handler,
+            ),
+        }?;
+        Ok((config, stream))
+    }
 }
 
 impl AudioBackend for CpalAudioBackend {
     impl_audio_mixer_backend!(mixer);
 
     fn play(&mut self) {
+        self.is_playing = true;
         self.stream.play().expect("Error trying to resume CPAL audio stream. This feature may not be supported by your audio device.");
     }
 
     fn pause(&mut self) {
+        self.is_playing = false;
         self.stream.pause().expect("Error trying to pause CPAL audio stream. This feature may not be supported by your audio device.");
     }
+
+    fn available_output_devices(&self) -> Vec<String> {
+        let host = cpal::default_host();
+        match host.output_devices() {
+            Ok(devices) => devices.filter_map(|device| device.name().ok()).collect(),
+            Err(e) => {
+                log::error!("Unable to list audio devices: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    fn set_output_device(&mut self, name: &str) -> Result<(), Error> {
+        let host = cpal::default_host();
+        let device = host
+            .output_devices()?
+            .find(|device| device.name().map_or(false, |n| n == name))
+            .ok_or_else(|| format!("No audio device named {}", name))?;
+
+        // The sound instances are shared with the old stream, so playing sounds carry on
+        // from where they were.
+        let output = (|| -> Result<_, Error> {
+            let (config, stream) = Self::build_output_stream(&device, &mut self.mixer)?;
+            if self.is_playing {
+                stream.play()?;
+            }
+            Ok((config, stream))
+        })();
+        let (config, stream) = match output {
+            Ok(output) => output,
+            Err(e) => {
+                // Keep mixing for the current device.
+                self.mixer
+                    .set_output_format(self.config.channels as u8, self.config.sample_rate.0);
+                return Err(e);
+            }
+        };
+
+        self.device = device;
+        self.config = config;
+        self.stream = stream;
+        Ok(())
+    }
+
+    fn start_capture(&mut self, sample_rate: u32) -> Result<(), Error> {
+        self.stop_capture();
+
+        let host = cpal::default_host();
+        let device = host
+            .default_input_device()
+            .ok_or("No audio input devices available")?;
+
+        let config = device.default_input_config()?;
+        let sample_format = config.sample_format();
+        let config = cpal::StreamConfig::from(config);
+
+        let samples = Arc::new(Mutex::new(Vec::new()));
+        let mut resampler = CaptureResampler {
+            num_channels: config.channels.into(),
+            step: f64::from(sample_rate) / f64::from(config.sample_rate.0),
+            phase: 0.0,
+            max_samples: sample_rate as usize,
+            samples: Arc::clone(&samples),
+        };
+        let error_handler = move |err| log::error!("Audio capture error: {}", err);
+
+        let stream = match sample_format {
+            cpal::SampleFormat::F32 => device.build_input_stream(
+                &config,
+                move |buffer: &[f32], _| resampler.push(buffer),
+                error_handler,
+            ),
+            cpal::SampleFormat::I16 => device.build_input_stream(
+                &config,
+                move |buffer: &[i16], _| resampler.push(buffer),
+                error_handler,
+            ),
+            cpal::SampleFormat::U16 => device.build_input_stream(
+                &config,
+                move |buffer: &[u16], _| resampler.push(buffer),
+                error_handler,
+            ),
+        }?;
+
+        stream.play()?;
+
+        self.capture = Some(Capture { stream, samples });
+        Ok(())
+    }
+
+    fn stop_capture(&mut self) {
+        self.capture = None;
+    }
+
+    fn take_captured_samples(&mut self) -> Vec<i16> {
+        match &self.capture {
+            Some(capture) => std::mem::take(&mut *capture.samples.lock().unwrap()),
+            None => Vec::new(),
+        }
+    }
 }
@@ -0,0 +1,22 @@
+use ruffle_core::backend::render::{Bitmap, BitmapFormat};
+use std::error::Error;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+/// Save a bitmap rendered by the player, such as a printed page, as a PNG file.
+pub fn save_png(path: &Path, bitmap: Bitmap) -> Result<(), Box<dyn Error>> {
+    let (color_type, data) = match bitmap.data {
+        BitmapFormat::Rgb(data) => (png::ColorType::Rgb, data),
+        BitmapFormat::Rgba(data) => (png::ColorType::Rgba, data),
+    };
+    let mut encoder = png::Encoder::new(
+        BufWriter::new(File::create(path)?),
+        bitmap.width,
+        bitmap.height,
+    );
+    encoder.set_color(color_type);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(&data)?;
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use ruffle_core::backend::capabilities::{
+    CapabilitiesProvider, Language, OperatingSystem, PlayerType, SystemCapabilities,
+};
+use std::rc::Rc;
+use winit::window::Window;
+
+pub struct DesktopCapabilitiesProvider {
+    window: Rc<Window>,
+}
+
+impl DesktopCapabilitiesProvider {
+    pub fn new(window: Rc<Window>) -> Self {
+        Self { window }
+    }
+}
+
+impl CapabilitiesProvider for DesktopCapabilitiesProvider {
+    fn screen_resolution(&self) -> (u32, u32) {
+        self.window
+            .current_monitor()
+            .map(|monitor| {
+                let size = monitor.size();
+                (size.width, size.height)
+            })
+            .unwrap_or((0, 0))
+    }
+
+    fn screen_dpi(&self) -> f32 {
+        let scale_factor = self
+            .window
+            .current_monitor()
+            .map(|monitor| monitor.scale_factor())
+            .unwrap_or(1.0);
+        (72.0 * scale_factor) as f32
+    }
+
+    fn language(&self) -> Language {
+        // Locales look like `en_US.UTF-8`.
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|locale| !locale.is_empty())
+            .map(|locale| {
+                let code = locale.split('.').next().unwrap_or_default();
+                Language::from_language_code(code)
+            })
+            .unwrap_or(Language::English)
+    }
+
+    fn os(&self) -> OperatingSystem {
+        if cfg!(target_os = "windows") {
+            OperatingSystem::WindowsUnknown
+        } else if cfg!(target_os = "macos") {
+            OperatingSystem::MacOs
+        } else {
+            OperatingSystem::Linux
+        }
+    }
+
+    fn player_type(&self) -> PlayerType {
+        PlayerType::StandAlone
+    }
+
+    fn capabilities(&self) -> SystemCapabilities {
+        SystemCapabilities::AUDIO
+            | SystemCapabilities::MP3
+            | SystemCapabilities::STREAMING_AUDIO
+            | SystemCapabilities::LOCAL_FILE_READ
+    }
+}
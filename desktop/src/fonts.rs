@@ -0,0 +1,59 @@
+use ruffle_core::backend::font::{FontBackend, FontFile};
+use std::collections::HashMap;
+
+/// Provides the fonts installed on the system, as found by `fontdb`.
+pub struct DesktopFontBackend {
+    database: fontdb::Database,
+
+    /// The font files loaded so far, or `None` for ones that couldn't be read.
+    files: HashMap<fontdb::ID, Option<FontFile>>,
+}
+
+impl DesktopFontBackend {
+    pub fn new() -> Self {
+        let mut database = fontdb::Database::new();
+        database.load_system_fonts();
+        Self {
+            database,
+            files: HashMap::new(),
+        }
+    }
+}
+
+impl FontBackend for DesktopFontBackend {
+    fn font_names(&self) -> Vec<String> {
+        self.database
+            .faces()
+            .iter()
+            .map(|face| face.family.clone())
+            .collect()
+    }
+
+    fn load_font(&mut self, family: &str, is_bold: bool, is_italic: bool) -> Option<FontFile> {
+        let id = self.database.query(&fontdb::Query {
+            families: &[fontdb::Family::Name(family)],
+            weight: if is_bold {
+                fontdb::Weight::BOLD
+            } else {
+                fontdb::Weight::NORMAL
+            },
+            style: if is_italic {
+                fontdb::Style::Italic
+            } else {
+                fontdb::Style::Normal
+            },
+            ..Default::default()
+        })?;
+
+        let database = &self.database;
+        self.files
+            .entry(id)
+            .or_insert_with(|| {
+                database.with_face_data(id, |data, index| FontFile {
+                    data: data.into(),
+                    index,
+                })
+            })
+            .clone()
+    }
+}
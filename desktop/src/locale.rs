@@ -1,5 +1,5 @@
-use ruffle_core::backend::locale::LocaleBackend;
-use ruffle_core::chrono::{DateTime, FixedOffset, Local, Offset, Utc};
+use ruffle_core::backend::locale::{LocalTimezone, LocaleBackend};
+use ruffle_core::chrono::{DateTime, Local, Utc};
 
 pub struct DesktopLocaleBackend();
 
@@ -14,7 +14,7 @@ impl LocaleBackend for DesktopLocaleBackend {
         Utc::now()
     }
 
-    fn get_timezone(&self) -> FixedOffset {
-        Local::now().offset().fix()
+    fn get_timezone(&self) -> LocalTimezone {
+        LocalTimezone::new(Local)
     }
 }
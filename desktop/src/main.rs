@@ -6,8 +6,11 @@
 #![windows_subsystem = "windows"]
 
 mod audio;
+mod bitmap;
+mod capabilities;
 mod custom_event;
 mod executor;
+mod fonts;
 mod locale;
 mod navigator;
 mod storage;
@@ -74,6 +77,15 @@ struct Opt {
     #[clap(long, short, default_value = "high", arg_enum)]
     power: PowerPreference,
 
+    /// Interpolation used to resample sounds to the output sample rate.
+    /// Cubic and sinc reduce aliasing on low sample rate sounds, at a higher CPU cost.
+    #[clap(long, default_value = "linear", arg_enum)]
+    audio_resampling: audio::AudioResampling,
+
+    /// Name of the audio output device to play sound on, instead of the system default.
+    #[clap(long)]
+    audio_device: Option<String>,
+
     /// Width of window in pixels.
     #[clap(long, display_order = 1)]
     width: Option<f64>,
@@ -95,6 +107,15 @@ struct Opt {
     #[clap(long, takes_value = false)]
     upgrade_to_https: bool,
 
+    /// A host that the movie may open sockets to, besides the host it was loaded from.
+    /// This can be repeated multiple times, for example --socket-allow a.com --socket-allow b.com.
+    #[clap(
+        long = "socket-allow",
+        number_of_values = 1,
+        multiple_occurrences = true
+    )]
+    socket_allowed_hosts: Vec<String>,
+
     #[clap(long, takes_value = false)]
     timedemo: bool,
 
@@ -258,13 +279,21 @@ impl App {
             opt.power.into(),
             trace_path(&opt),
         )?);
-        let audio: Box<dyn AudioBackend> = match audio::CpalAudioBackend::new() {
-            Ok(audio) => Box::new(audio),
-            Err(e) => {
-                log::error!("Unable to create audio device: {}", e);
-                Box::new(NullAudioBackend::new())
-            }
-        };
+        let audio: Box<dyn AudioBackend> =
+            match audio::CpalAudioBackend::new(opt.audio_resampling.into()) {
+                Ok(mut audio) => {
+                    if let Some(name) = &opt.audio_device {
+                        if let Err(e) = audio.set_output_device(name) {
+                            log::error!("Unable to use audio device {}: {}", name, e);
+                        }
+                    }
+                    Box::new(audio)
+                }
+                Err(e) => {
+                    log::error!("Unable to create audio device: {}", e);
+                    Box::new(NullAudioBackend::new())
+                }
+            };
         let (executor, channel) = GlutinAsyncExecutor::new(event_loop.create_proxy());
         let navigator = Box::new(navigator::ExternalNavigatorBackend::new(
             movie.as_ref().unwrap().1.clone(), // TODO: Get rid of this parameter.
@@ -272,10 +301,11 @@ impl App {
             event_loop.create_proxy(),
             opt.proxy.clone(),
             opt.upgrade_to_https,
+            opt.socket_allowed_hosts.clone(),
         ));
         let storage = Box::new(storage::DiskStorageBackend::new());
         let locale = Box::new(locale::DesktopLocaleBackend::new());
-        let video = Box::new(video::SoftwareVideoBackend::new());
+        let video = Box::new(video::SoftwareVideoBackend::with_decode_ahead(8));
         let log = Box::new(log_backend::NullLogBackend::new());
         let ui = Box::new(ui::DesktopUiBackend::new(window.clone()));
         let player = Player::new(renderer, audio, navigator, storage, locale, video, log, ui)?;
@@ -285,6 +315,10 @@ impl App {
         {
             let mut player_lock = player.lock().unwrap();
             player_lock.set_warn_on_unsupported_content(!opt.dont_warn_on_unsupported_content);
+            player_lock.set_capabilities_provider(Box::new(
+                capabilities::DesktopCapabilitiesProvider::new(window.clone()),
+            ));
+            player_lock.set_font_backend(Box::new(fonts::DesktopFontBackend::new()));
             if let Some(movie) = &movie {
                 player_lock.set_root_movie(movie.to_owned());
                 player_lock.set_is_playing(true); // Desktop player will auto-play.
@@ -366,6 +400,25 @@ impl App {
                             } if fullscreen_down => {
                                 fullscreen_down = false;
                             }
+                            WindowEvent::KeyboardInput {
+                                input:
+                                    KeyboardInput {
+                                        state: ElementState::Pressed,
+                                        virtual_keycode: Some(VirtualKeyCode::S),
+                                        modifiers,
+                                        ..
+                                    },
+                                ..
+                            } if modifiers.ctrl() && modifiers.shift() => {
+                                // Take screenshot
+                                let screenshot = player.lock().unwrap().capture_frame();
+                                if let Some(screenshot) = screenshot {
+                                    ui::save_screenshot(screenshot);
+                                } else {
+                                    log::error!("Couldn't capture a screenshot of the stage");
+                                }
+                                return;
+                            }
                             WindowEvent::KeyboardInput {
                                 input:
                                     KeyboardInput {
@@ -463,6 +516,26 @@ impl App {
                                     window.request_redraw();
                                 }
                             }
+                            WindowEvent::MouseInput {
+                                button: MouseButton::Right,
+                                state: ElementState::Pressed,
+                                ..
+                            } => {
+                                let mut player_lock = player.lock().unwrap();
+                                player_lock.show_context_menu();
+                                let choice = player_lock
+                                    .ui_mut()
+                                    .downcast_mut::<ui::DesktopUiBackend>()
+                                    .unwrap()
+                                    .take_context_menu_choice();
+                                if let Some(index) = choice {
+                                    player_lock.run_context_menu_callback(index);
+                                }
+                                player_lock.clear_custom_menu_items();
+                                if player_lock.needs_render() {
+                                    window.request_redraw();
+                                }
+                            }
                             WindowEvent::MouseWheel { delta, .. } => {
                                 use ruffle_core::events::MouseWheelDelta;
                                 let mut player_lock = player.lock().unwrap();
@@ -1,16 +1,23 @@
 //! Navigator backend for web
 
 use crate::custom_event::RuffleEvent;
-use isahc::{config::RedirectPolicy, prelude::*, AsyncReadResponseExt, HttpClient, Request};
+use futures_lite::AsyncReadExt;
+use generational_arena::Arena;
+use isahc::{config::RedirectPolicy, prelude::*, HttpClient, Request};
 use ruffle_core::backend::navigator::{
-    NavigationMethod, NavigatorBackend, OwnedFuture, RequestOptions,
+    FetchProgress, NavigationMethod, NavigatorBackend, OwnedFuture, RequestOptions, SocketEvent,
+    SocketHandle,
 };
 use ruffle_core::indexmap::IndexMap;
 use ruffle_core::loader::Error;
 use std::borrow::Cow;
 use std::fs;
+use std::io::{Read, Write};
+use std::net::{Shutdown, TcpStream};
 use std::rc::Rc;
-use std::sync::mpsc::Sender;
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
 use std::time::{Duration, Instant};
 use url::Url;
 use winit::event_loop::EventLoopProxy;
@@ -34,6 +41,24 @@ pub struct ExternalNavigatorBackend {
     client: Option<Rc<HttpClient>>,
 
     upgrade_to_https: bool,
+
+    /// Hosts that sockets may connect to besides the host the movie was loaded from.
+    socket_allowed_hosts: Vec<String>,
+
+    /// Open `XMLSocket` connections.
+    sockets: Arena<Socket>,
+
+    /// Events received by the socket threads, waiting to be taken by the player.
+    socket_events: Arc<Mutex<Vec<SocketEvent>>>,
+}
+
+/// A TCP connection, which is serviced by its own threads.
+struct Socket {
+    /// Sink for data to be written to the connection.
+    sender: Sender<Vec<u8>>,
+
+    /// The stream, once connected. Used to shut the connection down.
+    stream: Arc<Mutex<Option<TcpStream>>>,
 }
 
 impl ExternalNavigatorBackend {
@@ -45,6 +70,7 @@ impl ExternalNavigatorBackend {
         event_loop: EventLoopProxy<RuffleEvent>,
         proxy: Option<Url>,
         upgrade_to_https: bool,
+        socket_allowed_hosts: Vec<String>,
     ) -> Self {
         let proxy = proxy.and_then(|url| url.as_str().parse().ok());
         let builder = HttpClient::builder()
@@ -60,6 +86,9 @@ impl ExternalNavigatorBackend {
             movie_url,
             start_time: Instant::now(),
             upgrade_to_https,
+            socket_allowed_hosts,
+            sockets: Arena::new(),
+            socket_events: Arc::new(Mutex::new(Vec::new())),
         }
     }
 }
@@ -112,6 +141,15 @@ impl NavigatorBackend for ExternalNavigatorBackend {
     }
 
     fn fetch(&self, url: &str, options: RequestOptions) -> OwnedFuture<Vec<u8>, Error> {
+        self.fetch_with_progress(url, options, FetchProgress::new())
+    }
+
+    fn fetch_with_progress(
+        &self,
+        url: &str,
+        options: RequestOptions,
+        progress: FetchProgress,
+    ) -> OwnedFuture<Vec<u8>, Error> {
         // TODO: honor sandbox type (local-with-filesystem, local-with-network, remote, ...)
         let full_url = match self.movie_url.clone().join(url) {
             Ok(url) => url,
@@ -133,12 +171,19 @@ impl NavigatorBackend for ExternalNavigatorBackend {
             _ => Box::pin(async move {
                 let client = client.ok_or(Error::NetworkUnavailable)?;
 
-                let request = match options.method() {
+                let mut request = match options.method() {
                     NavigationMethod::Get => Request::get(processed_url.to_string()),
                     NavigationMethod::Post => Request::post(processed_url.to_string()),
                 };
 
-                let (body_data, _) = options.body().clone().unwrap_or_default();
+                let (body_data, mime) = options.body().clone().unwrap_or_default();
+                if !mime.is_empty() {
+                    request = request.header("Content-Type", mime);
+                }
+                for (name, value) in options.headers() {
+                    request = request.header(name.as_str(), value.as_str());
+                }
+
                 let body = request
                     .body(body_data)
                     .map_err(|e| Error::FetchError(e.to_string()))?;
@@ -149,17 +194,27 @@ impl NavigatorBackend for ExternalNavigatorBackend {
                     .map_err(|e| Error::FetchError(e.to_string()))?;
 
                 if !response.status().is_success() {
-                    return Err(Error::FetchError(format!(
-                        "HTTP status is not ok, got {}",
-                        response.status()
-                    )));
+                    return Err(Error::HttpNotOk(response.status().as_u16()));
+                }
+
+                let body = response.body_mut();
+                if let Some(len) = body.len() {
+                    progress.set_total(len as usize);
                 }
 
                 let mut buffer = vec![];
-                response
-                    .copy_to(&mut buffer)
-                    .await
-                    .map_err(|e| Error::FetchError(e.to_string()))?;
+                let mut chunk = [0; 16384];
+                loop {
+                    let read = body
+                        .read(&mut chunk)
+                        .await
+                        .map_err(|e| Error::FetchError(e.to_string()))?;
+                    if read == 0 {
+                        break;
+                    }
+                    buffer.extend_from_slice(&chunk[..read]);
+                    progress.add_loaded(read);
+                }
                 Ok(buffer)
             }),
         }
@@ -194,4 +249,122 @@ impl NavigatorBackend for ExternalNavigatorBackend {
         }
         url
     }
+
+    fn connect_socket(&mut self, host: &str, port: u16) -> Option<SocketHandle> {
+        // Without a socket policy file, Flash only lets movies connect back
+        // to the host they were loaded from. Local movies count as `localhost`.
+        let origin = self.movie_url.host_str().unwrap_or("localhost");
+        let allowed = host.eq_ignore_ascii_case(origin)
+            || self
+                .socket_allowed_hosts
+                .iter()
+                .any(|allowed| host.eq_ignore_ascii_case(allowed));
+        if !allowed {
+            log::warn!(
+                "Refusing to connect to {}:{}, which isn't the movie's host. Use --socket-allow to allow it.",
+                host,
+                port
+            );
+            return None;
+        }
+
+        let (sender, receiver) = channel::<Vec<u8>>();
+        let stream_slot = Arc::new(Mutex::new(None));
+        let handle = self.sockets.insert(Socket {
+            sender,
+            stream: stream_slot.clone(),
+        });
+
+        let host = host.to_string();
+        let events = self.socket_events.clone();
+        thread::spawn(move || {
+            let mut stream = match TcpStream::connect((host.as_str(), port)) {
+                Ok(stream) => stream,
+                Err(e) => {
+                    log::warn!("Couldn't connect to {}:{}: {}", host, port, e);
+                    events
+                        .lock()
+                        .unwrap()
+                        .push(SocketEvent::Connected(handle, false));
+                    return;
+                }
+            };
+
+            let mut reader = match (stream.try_clone(), stream.try_clone()) {
+                (Ok(reader), Ok(shutdown)) => {
+                    *stream_slot.lock().unwrap() = Some(shutdown);
+                    reader
+                }
+                _ => {
+                    events
+                        .lock()
+                        .unwrap()
+                        .push(SocketEvent::Connected(handle, false));
+                    return;
+                }
+            };
+            events
+                .lock()
+                .unwrap()
+                .push(SocketEvent::Connected(handle, true));
+
+            let reader_events = events.clone();
+            thread::spawn(move || {
+                let mut buffer = [0; 4096];
+                loop {
+                    match reader.read(&mut buffer) {
+                        Ok(0) | Err(_) => {
+                            reader_events
+                                .lock()
+                                .unwrap()
+                                .push(SocketEvent::Closed(handle));
+                            break;
+                        }
+                        Ok(len) => reader_events
+                            .lock()
+                            .unwrap()
+                            .push(SocketEvent::Data(handle, buffer[..len].to_vec())),
+                    }
+                }
+            });
+
+            // Write until the socket is closed, which drops the sender.
+            for data in receiver {
+                if stream.write_all(&data).is_err() {
+                    break;
+                }
+            }
+            let _ = stream.shutdown(Shutdown::Both);
+        });
+
+        Some(handle)
+    }
+
+    fn send_socket(&mut self, handle: SocketHandle, data: Vec<u8>) {
+        if let Some(socket) = self.sockets.get(handle) {
+            let _ = socket.sender.send(data);
+        }
+    }
+
+    fn close_socket(&mut self, handle: SocketHandle) {
+        if let Some(socket) = self.sockets.remove(handle) {
+            if let Some(stream) = socket.stream.lock().unwrap().take() {
+                let _ = stream.shutdown(Shutdown::Both);
+            }
+        }
+    }
+
+    fn take_socket_events(&mut self) -> Vec<SocketEvent> {
+        let mut events = std::mem::take(&mut *self.socket_events.lock().unwrap());
+        // Sockets that are no longer open are forgotten.
+        events.retain(|event| match event {
+            SocketEvent::Connected(handle, true) | SocketEvent::Data(handle, _) => {
+                self.sockets.contains(*handle)
+            }
+            SocketEvent::Connected(handle, false) | SocketEvent::Closed(handle) => {
+                self.sockets.remove(*handle).is_some()
+            }
+        });
+        events
+    }
 }
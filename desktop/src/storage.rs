@@ -4,6 +4,11 @@ use std::fs::File;
 use std::io::Write;
 use std::path::{Component, Path, PathBuf};
 
+/// The number of bytes that the shared objects of a domain may take up in
+/// total. This is the largest limit that can be picked in the settings of
+/// Flash Player, short of an unlimited one.
+const DOMAIN_SIZE_LIMIT: usize = 10 * 1024 * 1024;
+
 pub struct DiskStorageBackend {
     base_path: PathBuf,
     shared_objects_path: PathBuf,
@@ -43,6 +48,26 @@ impl DiskStorageBackend {
         // Remove this code eventually.
         self.base_path.join(name.replacen("/#", "/", 1))
     }
+
+    /// The total size of the files in `path` and its subdirectories, except
+    /// for `excluded`.
+    fn dir_size(path: &Path, excluded: &Path) -> usize {
+        let entries = match fs::read_dir(path) {
+            Ok(entries) => entries,
+            Err(_) => return 0,
+        };
+        entries
+            .filter_map(Result::ok)
+            .map(|entry| {
+                let path = entry.path();
+                match entry.metadata() {
+                    Ok(metadata) if metadata.is_dir() => Self::dir_size(&path, excluded),
+                    Ok(metadata) if path != excluded => metadata.len() as usize,
+                    _ => 0,
+                }
+            })
+            .sum()
+    }
 }
 
 impl StorageBackend for DiskStorageBackend {
@@ -107,4 +132,15 @@ impl StorageBackend for DiskStorageBackend {
         }
         let _ = fs::remove_file(path);
     }
+
+    fn size_limit(&self, name: &str) -> Option<usize> {
+        let path = self.get_shared_object_path(name);
+        if !Self::is_path_allowed(&path) {
+            return Some(0);
+        }
+        // Names start with the domain of the movie that the object belongs to.
+        let domain = name.split('/').next().unwrap_or_default();
+        let used = Self::dir_size(&self.shared_objects_path.join(domain), &path);
+        Some(DOMAIN_SIZE_LIMIT.saturating_sub(used))
+    }
 }
@@ -1,19 +1,29 @@
+use crate::bitmap::save_png;
 use clipboard::{ClipboardContext, ClipboardProvider};
+use ruffle_core::backend::render::Bitmap;
 use ruffle_core::backend::ui::{Error, MouseCursor, UiBackend};
 use ruffle_core::events::{KeyCode, PlayerEvent};
+use ruffle_core::ContextMenuItem;
 use std::collections::HashSet;
+use std::path::PathBuf;
 use std::rc::Rc;
-use tinyfiledialogs::{message_box_ok, MessageBoxIcon};
+use tinyfiledialogs::{
+    input_box, message_box_ok, message_box_yes_no, save_file_dialog_with_filter, MessageBoxIcon,
+    YesNo,
+};
+use winit::dpi::PhysicalPosition;
 use winit::event::{ElementState, ModifiersState, VirtualKeyCode, WindowEvent};
 use winit::window::{Fullscreen, Window};
 
 pub struct DesktopUiBackend {
     window: Rc<Window>,
     keys_down: HashSet<KeyCode>,
+    keys_toggled: HashSet<KeyCode>,
     cursor_visible: bool,
     last_key: KeyCode,
     last_char: Option<char>,
     clipboard: ClipboardContext,
+    context_menu_choice: Option<usize>,
 }
 
 impl DesktopUiBackend {
@@ -21,13 +31,20 @@ impl DesktopUiBackend {
         Self {
             window,
             keys_down: HashSet::new(),
+            keys_toggled: HashSet::new(),
             cursor_visible: true,
             last_key: KeyCode::Unknown,
             last_char: None,
             clipboard: ClipboardProvider::new().unwrap(),
+            context_menu_choice: None,
         }
     }
 
+    /// The index of the context menu item picked by the user, if any.
+    pub fn take_context_menu_choice(&mut self) -> Option<usize> {
+        self.context_menu_choice.take()
+    }
+
     /// Process an input event, and return an event that should be forward to the player, if any.
     pub fn handle_event(&mut self, event: WindowEvent) -> Option<PlayerEvent> {
         // Allow KeyboardInput.modifiers (ModifiersChanged event not functional yet).
@@ -36,8 +53,28 @@ impl DesktopUiBackend {
             if let Some(key) = input.virtual_keycode {
                 let key_code = winit_to_ruffle_key_code(key);
                 self.last_key = key_code;
-                self.last_char =
-                    winit_key_to_char(key, input.modifiers.contains(ModifiersState::SHIFT));
+                if input.state == ElementState::Pressed
+                    && !self.keys_down.contains(&key_code)
+                    && matches!(
+                        key_code,
+                        KeyCode::CapsLock | KeyCode::NumLock | KeyCode::ScrollLock
+                    )
+                {
+                    // winit can't tell us the state of the lock keys, so they're
+                    // assumed to start off.
+                    if !self.keys_toggled.remove(&key_code) {
+                        self.keys_toggled.insert(key_code);
+                    }
+                }
+
+                // Caps Lock only affects letters.
+                let mut is_shift_down = input.modifiers.contains(ModifiersState::SHIFT);
+                if (KeyCode::A as u8..=KeyCode::Z as u8).contains(&(key_code as u8))
+                    && self.keys_toggled.contains(&KeyCode::CapsLock)
+                {
+                    is_shift_down = !is_shift_down;
+                }
+                self.last_char = winit_key_to_char(key, is_shift_down);
                 match input.state {
                     ElementState::Pressed => {
                         self.keys_down.insert(key_code);
@@ -72,6 +109,10 @@ impl UiBackend for DesktopUiBackend {
         self.keys_down.contains(&key)
     }
 
+    fn is_key_toggled(&self, key: KeyCode) -> bool {
+        self.keys_toggled.contains(&key)
+    }
+
     fn last_key_code(&self) -> KeyCode {
         self.last_key
     }
@@ -100,10 +141,18 @@ impl UiBackend for DesktopUiBackend {
         self.window.set_cursor_icon(icon);
     }
 
+    fn clipboard_content(&mut self) -> String {
+        self.clipboard.get_contents().unwrap_or_default()
+    }
+
     fn set_clipboard_content(&mut self, content: String) {
         self.clipboard.set_contents(content).unwrap();
     }
 
+    fn set_ime_position(&mut self, x: f64, y: f64) {
+        self.window.set_ime_position(PhysicalPosition::new(x, y));
+    }
+
     fn set_fullscreen(&mut self, is_full: bool) -> Result<(), Error> {
         self.window.set_fullscreen(if is_full {
             Some(Fullscreen::Borderless(None))
@@ -129,11 +178,94 @@ impl UiBackend for DesktopUiBackend {
         );
     }
 
+    fn display_context_menu(&mut self, items: &[ContextMenuItem]) {
+        // tinyfiledialogs has no menu widget, so the items are listed by
+        // number and the user types the one to pick.
+        let mut message = String::new();
+        for (i, item) in items.iter().enumerate() {
+            if item.separator_before && !message.is_empty() {
+                message.push_str("----------\n");
+            }
+            let check = if item.checked { "[x] " } else { "" };
+            let state = if item.enabled { "" } else { " (disabled)" };
+            message.push_str(&format!("{}. {}{}{}\n", i + 1, check, item.caption, state));
+        }
+
+        self.context_menu_choice = input_box("Ruffle - Context menu", &message, "")
+            .and_then(|choice| choice.trim().parse::<usize>().ok())
+            .and_then(|choice| choice.checked_sub(1))
+            .filter(|&index| items.get(index).map_or(false, |item| item.enabled));
+    }
+
+    fn request_microphone_access(&mut self) -> bool {
+        message_box_yes_no(
+            "Ruffle - Microphone access",
+            "This content wants to use your microphone. Allow it?",
+            MessageBoxIcon::Question,
+            YesNo::No,
+        ) == YesNo::Yes
+    }
+
+    fn print(&mut self, pages: Vec<Bitmap>) {
+        // There's no portable way to reach the system's print dialog, so the
+        // pages are saved as images to be printed from any image viewer.
+        let path = match save_file_dialog_with_filter(
+            "Ruffle - Print to PNG",
+            "page.png",
+            &["*.png"],
+            "PNG images",
+        ) {
+            Some(path) => PathBuf::from(path),
+            None => return,
+        };
+
+        for (i, page) in pages.into_iter().enumerate() {
+            let path = if i == 0 {
+                path.clone()
+            } else {
+                // Number the following pages: `page.png`, `page-2.png`, ...
+                let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+                path.with_file_name(format!("{}-{}.png", stem, i + 1))
+            };
+            if let Err(e) = save_png(&path, page) {
+                log::error!("Couldn't save printed page to {}: {}", path.display(), e);
+                message_box_ok(
+                    "Ruffle - Print failed",
+                    &format!("Couldn't save printed page to {}", path.display()),
+                    MessageBoxIcon::Error,
+                );
+                return;
+            }
+        }
+    }
+
     fn message(&self, message: &str) {
         message_box_ok("Ruffle", message, MessageBoxIcon::Info)
     }
 }
 
+/// Asks where to save a screenshot of the stage, and saves it as a PNG.
+pub fn save_screenshot(screenshot: Bitmap) {
+    let path = match save_file_dialog_with_filter(
+        "Ruffle - Save screenshot",
+        "screenshot.png",
+        &["*.png"],
+        "PNG images",
+    ) {
+        Some(path) => PathBuf::from(path),
+        None => return,
+    };
+
+    if let Err(e) = save_png(&path, screenshot) {
+        log::error!("Couldn't save screenshot to {}: {}", path.display(), e);
+        message_box_ok(
+            "Ruffle - Screenshot failed",
+            &format!("Couldn't save screenshot to {}", path.display()),
+            MessageBoxIcon::Error,
+        );
+    }
+}
+
 /// Convert a winit `VirtualKeyCode` into a Ruffle `KeyCode`.
 /// Return `KeyCode::Unknown` if there is no matching Flash key code.
 fn winit_to_ruffle_key_code(key_code: VirtualKeyCode) -> KeyCode {
@@ -220,6 +352,7 @@ fn winit_to_ruffle_key_code(key_code: VirtualKeyCode) -> KeyCode {
         VirtualKeyCode::Insert => KeyCode::Insert,
         VirtualKeyCode::Delete => KeyCode::Delete,
         VirtualKeyCode::Pause => KeyCode::Pause,
+        VirtualKeyCode::Numlock => KeyCode::NumLock,
         VirtualKeyCode::Scroll => KeyCode::ScrollLock,
         VirtualKeyCode::F1 => KeyCode::F1,
         VirtualKeyCode::F2 => KeyCode::F2,
@@ -246,16 +379,26 @@ fn winit_key_to_char(key_code: VirtualKeyCode, is_shift_down: bool) -> Option<ch
     // for international layouts.
     Some(match (key_code, is_shift_down) {
         (VirtualKeyCode::Space, _) => ' ',
-        (VirtualKeyCode::Key0, _) => '0',
-        (VirtualKeyCode::Key1, _) => '1',
-        (VirtualKeyCode::Key2, _) => '2',
-        (VirtualKeyCode::Key3, _) => '3',
-        (VirtualKeyCode::Key4, _) => '4',
-        (VirtualKeyCode::Key5, _) => '5',
-        (VirtualKeyCode::Key6, _) => '6',
-        (VirtualKeyCode::Key7, _) => '7',
-        (VirtualKeyCode::Key8, _) => '8',
-        (VirtualKeyCode::Key9, _) => '9',
+        (VirtualKeyCode::Key0, false) => '0',
+        (VirtualKeyCode::Key0, true) => ')',
+        (VirtualKeyCode::Key1, false) => '1',
+        (VirtualKeyCode::Key1, true) => '!',
+        (VirtualKeyCode::Key2, false) => '2',
+        (VirtualKeyCode::Key2, true) => '@',
+        (VirtualKeyCode::Key3, false) => '3',
+        (VirtualKeyCode::Key3, true) => '#',
+        (VirtualKeyCode::Key4, false) => '4',
+        (VirtualKeyCode::Key4, true) => '$',
+        (VirtualKeyCode::Key5, false) => '5',
+        (VirtualKeyCode::Key5, true) => '%',
+        (VirtualKeyCode::Key6, false) => '6',
+        (VirtualKeyCode::Key6, true) => '^',
+        (VirtualKeyCode::Key7, false) => '7',
+        (VirtualKeyCode::Key7, true) => '&',
+        (VirtualKeyCode::Key8, false) => '8',
+        (VirtualKeyCode::Key8, true) => '*',
+        (VirtualKeyCode::Key9, false) => '9',
+        (VirtualKeyCode::Key9, true) => '(',
         (VirtualKeyCode::A, false) => 'a',
         (VirtualKeyCode::A, true) => 'A',
         (VirtualKeyCode::B, false) => 'b',
@@ -1,20 +1,26 @@
 use clap::Parser;
 use image::RgbaImage;
 use indicatif::{ProgressBar, ProgressStyle};
+use ruffle_core::backend::audio::decoders::{self, Decoder};
+use ruffle_core::backend::audio::encoders::write_wav;
 use ruffle_core::backend::audio::NullAudioBackend;
 use ruffle_core::backend::locale::NullLocaleBackend;
 use ruffle_core::backend::log::NullLogBackend;
 use ruffle_core::backend::navigator::NullNavigatorBackend;
+use ruffle_core::backend::render::RenderBackend;
 use ruffle_core::backend::storage::MemoryStorageBackend;
 use ruffle_core::backend::ui::NullUiBackend;
 use ruffle_core::backend::video::SoftwareVideoBackend;
-use ruffle_core::tag_utils::SwfMovie;
+use ruffle_core::swf::TagCode;
+use ruffle_core::tag_utils::{decode_tags, SwfMovie, SwfSlice};
 use ruffle_core::Player;
+use ruffle_render_software::SoftwareRenderBackend;
 use ruffle_render_wgpu::clap::{GraphicsBackend, PowerPreference};
 use ruffle_render_wgpu::target::TextureTarget;
 use ruffle_render_wgpu::{wgpu, Descriptors, WgpuRenderBackend};
 use std::error::Error;
-use std::fs::create_dir_all;
+use std::fs::{create_dir_all, File};
+use std::io::BufWriter;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use walkdir::{DirEntry, WalkDir};
@@ -61,6 +67,10 @@ struct Opt {
     #[clap(short, long)]
     silent: bool,
 
+    /// Export the sound stream of the main timeline to a WAV file, instead of capturing frames
+    #[clap(long = "export-audio")]
+    export_audio: bool,
+
     #[clap(flatten)]
     size: SizeOpt,
 
@@ -80,20 +90,34 @@ struct Opt {
     #[clap(long, short, case_insensitive = true, default_value = "high", arg_enum)]
     power: PowerPreference,
 
+    /// Render on the CPU instead of the GPU. This is slower, but needs no
+    /// graphics device and gives the same images on every machine.
+    #[clap(long)]
+    software: bool,
+
     /// Location to store a wgpu trace output
     #[clap(long, parse(from_os_str))]
     #[cfg(feature = "render_trace")]
     trace_path: Option<PathBuf>,
 }
 
+/// The render backend that frames are captured with.
+enum Renderer {
+    /// Render on the GPU, with the device of these descriptors.
+    Wgpu(Descriptors),
+
+    /// Render on the CPU.
+    Software,
+}
+
 fn take_screenshot(
-    descriptors: Descriptors,
+    renderer: Renderer,
     swf_path: &Path,
     frames: u32,
     skipframes: u32,
     progress: &Option<ProgressBar>,
     size: SizeOpt,
-) -> Result<(Descriptors, Vec<RgbaImage>), Box<dyn std::error::Error>> {
+) -> Result<(Renderer, Vec<RgbaImage>), Box<dyn std::error::Error>> {
     let movie = SwfMovie::from_path(&swf_path, None)?;
 
     let width = size
@@ -108,9 +132,15 @@ fn take_screenshot(
         .unwrap_or_else(|| movie.height().to_pixels());
     let height = (height * size.scale).round() as u32;
 
-    let target = TextureTarget::new(&descriptors.device, (width, height));
+    let render_backend: Box<dyn RenderBackend> = match renderer {
+        Renderer::Wgpu(descriptors) => {
+            let target = TextureTarget::new(&descriptors.device, (width, height));
+            Box::new(WgpuRenderBackend::new(descriptors, target)?)
+        }
+        Renderer::Software => Box::new(SoftwareRenderBackend::new(width, height)),
+    };
     let player = Player::new(
-        Box::new(WgpuRenderBackend::new(descriptors, target)?),
+        render_backend,
         Box::new(NullAudioBackend::new()),
         Box::new(NullNavigatorBackend::new()),
         Box::new(MemoryStorageBackend::default()),
@@ -141,12 +171,18 @@ fn take_screenshot(
         if i >= skipframes {
             player.lock().unwrap().render();
             let mut player = player.lock().unwrap();
-            let renderer = player
-                .renderer_mut()
-                .downcast_mut::<WgpuRenderBackend<TextureTarget>>()
-                .unwrap();
-            let target = renderer.target();
-            if let Some(image) = target.capture(renderer.device()) {
+            let renderer = player.renderer_mut();
+            let image = if let Some(renderer) =
+                renderer.downcast_mut::<WgpuRenderBackend<TextureTarget>>()
+            {
+                renderer.target().capture(renderer.device())
+            } else if let Some(renderer) = renderer.downcast_mut::<SoftwareRenderBackend>() {
+                let frame = renderer.capture_frame();
+                RgbaImage::from_raw(frame.width, frame.height, frame.into_rgba())
+            } else {
+                None
+            };
+            if let Some(image) = image {
                 result.push(image);
             } else {
                 return Err(format!("Unable to capture frame {} of {:?}", i, swf_path).into());
@@ -158,16 +194,159 @@ fn take_screenshot(
         }
     }
 
-    let descriptors = Arc::try_unwrap(player)
+    let renderer = match Arc::try_unwrap(player)
         .ok()
         .unwrap()
         .into_inner()?
         .destroy()
         .downcast::<WgpuRenderBackend<TextureTarget>>()
-        .ok()
-        .unwrap()
-        .descriptors();
-    Ok((descriptors, result))
+    {
+        Ok(renderer) => Renderer::Wgpu(renderer.descriptors()),
+        Err(_) => Renderer::Software,
+    };
+    Ok((renderer, result))
+}
+
+/// The decoded sound stream of a movie's main timeline.
+struct TimelineAudio {
+    frames: Vec<[i16; 2]>,
+    num_channels: u16,
+    sample_rate: u32,
+}
+
+fn decode_timeline_audio(swf_path: &Path) -> Result<Option<TimelineAudio>, Box<dyn Error>> {
+    let movie = Arc::new(SwfMovie::from_path(&swf_path, None)?);
+    let slice = SwfSlice::from(movie);
+
+    let mut stream_head = None;
+    let mut reader = slice.read_from(0);
+    decode_tags(
+        &mut reader,
+        |reader, tag_code, _tag_len| {
+            if matches!(
+                tag_code,
+                TagCode::SoundStreamHead | TagCode::SoundStreamHead2
+            ) && stream_head.is_none()
+            {
+                stream_head = Some(reader.read_sound_stream_head()?);
+            }
+            Ok(())
+        },
+        TagCode::End,
+    )?;
+
+    let stream_head = match stream_head {
+        Some(stream_head) => stream_head,
+        None => return Ok(None),
+    };
+    let decoder = decoders::make_stream_decoder(&stream_head.stream_format, slice)?;
+    let num_channels = decoder.num_channels().into();
+    let sample_rate = decoder.sample_rate().into();
+    Ok(Some(TimelineAudio {
+        frames: decoder.collect(),
+        num_channels,
+        sample_rate,
+    }))
+}
+
+fn save_timeline_audio(audio: &TimelineAudio, path: &Path) -> Result<(), Box<dyn Error>> {
+    let file = BufWriter::new(File::create(path)?);
+    write_wav(file, &audio.frames, audio.num_channels, audio.sample_rate)?;
+    Ok(())
+}
+
+fn export_single_swf_audio(opt: &Opt) -> Result<(), Box<dyn Error>> {
+    let output = opt.output_path.clone().unwrap_or_else(|| {
+        let mut result = PathBuf::new();
+        result.set_file_name(opt.swf.file_stem().unwrap());
+        result.set_extension("wav");
+        result
+    });
+
+    let message = if let Some(audio) = decode_timeline_audio(&opt.swf)? {
+        save_timeline_audio(&audio, &output)?;
+        format!(
+            "Saved audio of {} to {}",
+            opt.swf.to_string_lossy(),
+            output.to_string_lossy()
+        )
+    } else {
+        format!("{} has no timeline audio", opt.swf.to_string_lossy())
+    };
+    println!("{}", message);
+
+    Ok(())
+}
+
+fn export_multiple_swfs_audio(opt: &Opt) -> Result<(), Box<dyn Error>> {
+    let output = opt.output_path.clone().unwrap();
+    let files = find_files(&opt.swf, !opt.silent);
+
+    let progress = if !opt.silent {
+        let progress = ProgressBar::new(files.len() as u64);
+        progress.set_style(
+            ProgressStyle::default_bar()
+                .template(
+                    "[{elapsed_precise}] {bar:40.cyan/blue} [{eta_precise}] {pos:>7}/{len:7} {msg}",
+                )
+                .progress_chars("##-"),
+        );
+        Some(progress)
+    } else {
+        None
+    };
+
+    let mut num_exported = 0;
+    for file in &files {
+        if let Some(progress) = &progress {
+            progress.set_message(
+                file.path()
+                    .file_stem()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned(),
+            );
+        }
+
+        match decode_timeline_audio(file.path()) {
+            Ok(Some(audio)) => {
+                let mut relative_path = file
+                    .path()
+                    .strip_prefix(&opt.swf)
+                    .unwrap_or_else(|_| file.path())
+                    .to_path_buf();
+                relative_path.set_extension("wav");
+                let mut destination: PathBuf = (&output).into();
+                destination.push(relative_path);
+                if let Some(parent) = destination.parent() {
+                    let _ = create_dir_all(parent);
+                }
+                save_timeline_audio(&audio, &destination)?;
+                num_exported += 1;
+            }
+            Ok(None) => (),
+            Err(e) => log::error!("Unable to export audio of {:?}: {}", file.path(), e),
+        }
+
+        if let Some(progress) = &progress {
+            progress.inc(1);
+        }
+    }
+
+    let message = format!(
+        "Saved audio of {} of {} files to {}",
+        num_exported,
+        files.len(),
+        output.to_string_lossy()
+    );
+
+    if let Some(progress) = progress {
+        progress.finish_with_message(message);
+    } else {
+        println!("{}", message);
+    }
+
+    Ok(())
 }
 
 fn find_files(root: &Path, with_progress: bool) -> Vec<DirEntry> {
@@ -200,7 +379,7 @@ fn find_files(root: &Path, with_progress: bool) -> Vec<DirEntry> {
     results
 }
 
-fn capture_single_swf(descriptors: Descriptors, opt: &Opt) -> Result<(), Box<dyn Error>> {
+fn capture_single_swf(renderer: Renderer, opt: &Opt) -> Result<(), Box<dyn Error>> {
     let output = opt.output_path.clone().unwrap_or_else(|| {
         let mut result = PathBuf::new();
         result.set_file_name(opt.swf.file_stem().unwrap());
@@ -229,7 +408,7 @@ fn capture_single_swf(descriptors: Descriptors, opt: &Opt) -> Result<(), Box<dyn
     };
 
     let (_, frames) = take_screenshot(
-        descriptors,
+        renderer,
         &opt.swf,
         opt.frames,
         opt.skipframes,
@@ -276,7 +455,7 @@ fn capture_single_swf(descriptors: Descriptors, opt: &Opt) -> Result<(), Box<dyn
 }
 
 #[allow(unknown_lints, clippy::branches_sharing_code)]
-fn capture_multiple_swfs(mut descriptors: Descriptors, opt: &Opt) -> Result<(), Box<dyn Error>> {
+fn capture_multiple_swfs(mut renderer: Renderer, opt: &Opt) -> Result<(), Box<dyn Error>> {
     let output = opt.output_path.clone().unwrap();
     let files = find_files(&opt.swf, !opt.silent);
 
@@ -295,15 +474,15 @@ fn capture_multiple_swfs(mut descriptors: Descriptors, opt: &Opt) -> Result<(),
     };
 
     for file in &files {
-        let (new_descriptors, frames) = take_screenshot(
-            descriptors,
+        let (new_renderer, frames) = take_screenshot(
+            renderer,
             file.path(),
             opt.frames,
             opt.skipframes,
             &progress,
             opt.size,
         )?;
-        descriptors = new_descriptors;
+        renderer = new_renderer;
 
         if let Some(progress) = &progress {
             progress.set_message(
@@ -383,20 +562,37 @@ fn trace_path(_opt: &Opt) -> Option<&Path> {
 
 fn main() -> Result<(), Box<dyn Error>> {
     let opt: Opt = Opt::parse();
-    let instance = wgpu::Instance::new(opt.graphics.into());
-    let descriptors =
-        futures::executor::block_on(WgpuRenderBackend::<TextureTarget>::build_descriptors(
-            opt.graphics.into(),
-            instance,
-            None,
-            opt.power.into(),
-            trace_path(&opt),
-        ))?;
+
+    if opt.export_audio {
+        // Audio is decoded directly from the SWF, so no graphics device is needed.
+        return if opt.swf.is_file() {
+            export_single_swf_audio(&opt)
+        } else if opt.output_path.is_some() {
+            export_multiple_swfs_audio(&opt)
+        } else {
+            Err("Output directory is required when exporting multiple files.".into())
+        };
+    }
+
+    let renderer = if opt.software {
+        Renderer::Software
+    } else {
+        let instance = wgpu::Instance::new(opt.graphics.into());
+        let descriptors =
+            futures::executor::block_on(WgpuRenderBackend::<TextureTarget>::build_descriptors(
+                opt.graphics.into(),
+                instance,
+                None,
+                opt.power.into(),
+                trace_path(&opt),
+            ))?;
+        Renderer::Wgpu(descriptors)
+    };
 
     if opt.swf.is_file() {
-        capture_single_swf(descriptors, &opt)?;
+        capture_single_swf(renderer, &opt)?;
     } else if opt.output_path.is_some() {
-        capture_multiple_swfs(descriptors, &opt)?;
+        capture_multiple_swfs(renderer, &opt)?;
     } else {
         return Err("Output directory is required when exporting multiple files.".into());
     }
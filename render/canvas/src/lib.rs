@@ -1,4 +1,5 @@
 use ruffle_core::backend::render::{
+    check_rgba_len,
     swf::{self, CharacterId, GradientInterpolation, GradientSpread},
     Bitmap, BitmapFormat, BitmapHandle, BitmapInfo, BitmapSource, Color, JpegTagFormat,
     NullBitmapSource, RenderBackend, ShapeHandle, Transform,
@@ -6,6 +7,7 @@ use ruffle_core::backend::render::{
 use ruffle_core::color_transform::ColorTransform;
 use ruffle_core::matrix::Matrix;
 use ruffle_core::shape_utils::{DistilledShape, DrawCommand};
+use ruffle_core::StageQuality;
 use ruffle_web_common::JsResult;
 use wasm_bindgen::{JsCast, JsValue};
 use web_sys::{
@@ -29,6 +31,35 @@ pub struct WebCanvasRenderBackend {
     use_color_transform_hack: bool,
     pixelated_property_value: &'static str,
     deactivating_mask: bool,
+
+    /// The blend modes of the layers currently being rendered to, innermost last.
+    blend_modes: Vec<swf::BlendMode>,
+
+    /// Bitmaps are never smoothed at low quality.
+    quality: StageQuality,
+}
+
+/// The canvas composite operation that blends a layer with the given blend mode.
+///
+/// `Invert` and `Subtract` have no equivalent operation, and are handled by
+/// `pop_blend_mode` instead.
+fn composite_operation(blend_mode: swf::BlendMode) -> &'static str {
+    match blend_mode {
+        swf::BlendMode::Normal
+        | swf::BlendMode::Layer
+        | swf::BlendMode::Invert
+        | swf::BlendMode::Subtract => "source-over",
+        swf::BlendMode::Multiply => "multiply",
+        swf::BlendMode::Screen => "screen",
+        swf::BlendMode::Lighten => "lighten",
+        swf::BlendMode::Darken => "darken",
+        swf::BlendMode::Difference => "difference",
+        swf::BlendMode::Add => "lighter",
+        swf::BlendMode::Alpha => "destination-in",
+        swf::BlendMode::Erase => "destination-out",
+        swf::BlendMode::Overlay => "overlay",
+        swf::BlendMode::HardLight => "hard-light",
+    }
 }
 
 /// Canvas-drawable shape data extracted from an SWF file.
@@ -211,6 +242,8 @@ impl WebCanvasRenderBackend {
             viewport_height: 0,
             use_color_transform_hack: is_firefox,
             deactivating_mask: false,
+            blend_modes: vec![],
+            quality: StageQuality::High,
 
             // For rendering non-smoothed bitmaps.
             // crisp-edges works in Firefox, pixelated works in Chrome (and others)?
@@ -302,6 +335,37 @@ impl WebCanvasRenderBackend {
         }
     }
 
+    /// Draws a layer rendered by a blended clip onto the current render
+    /// target with the given composite operation.
+    fn composite_layer(&mut self, layer: &HtmlCanvasElement, operation: &str) {
+        self.context
+            .set_global_composite_operation(operation)
+            .warn_on_error();
+        self.context
+            .draw_image_with_html_canvas_element(layer, 0.0, 0.0)
+            .warn_on_error();
+        self.context
+            .set_global_composite_operation("source-over")
+            .warn_on_error();
+    }
+
+    /// Inverts the colors of the current render target.
+    fn invert_render_target(&mut self) {
+        self.context
+            .set_global_composite_operation("difference")
+            .warn_on_error();
+        self.context.set_fill_style(&"#fff".into());
+        self.context.fill_rect(
+            0.0,
+            0.0,
+            self.viewport_width.into(),
+            self.viewport_height.into(),
+        );
+        self.context
+            .set_global_composite_operation("source-over")
+            .warn_on_error();
+    }
+
     #[allow(clippy::float_cmp)]
     #[inline]
     fn set_transform(&mut self, matrix: &Matrix) {
@@ -415,6 +479,13 @@ impl RenderBackend for WebCanvasRenderBackend {
         self.viewport_height = height;
     }
 
+    fn set_quality(&mut self, quality: StageQuality) {
+        // Canvas paths are always anti-aliased, so only bitmap smoothing can follow the quality.
+        self.quality = quality;
+        self.context
+            .set_image_smoothing_enabled(quality != StageQuality::Low);
+    }
+
     fn register_shape(
         &mut self,
         shape: DistilledShape,
@@ -538,23 +609,28 @@ impl RenderBackend for WebCanvasRenderBackend {
             .fill_rect(0.0, 0.0, width.into(), height.into());
 
         self.deactivating_mask = false;
+        self.blend_modes.clear();
     }
 
     fn end_frame(&mut self) {
         // Noop
     }
 
-    fn render_bitmap(&mut self, bitmap: BitmapHandle, transform: &Transform, _smoothing: bool) {
+    fn render_bitmap(&mut self, bitmap: BitmapHandle, transform: &Transform, smoothing: bool) {
         if self.deactivating_mask {
             return;
         }
 
+        let quality_smoothing = self.quality != StageQuality::Low;
         self.set_transform(&transform.matrix);
         self.set_color_filter(transform);
         if let Some(bitmap) = self.bitmaps.get(bitmap.0) {
+            self.context
+                .set_image_smoothing_enabled(smoothing && quality_smoothing);
             let _ = self
                 .context
                 .draw_image_with_html_image_element(&bitmap.image, 0.0, 0.0);
+            self.context.set_image_smoothing_enabled(quality_smoothing);
         }
         self.clear_color_filter();
     }
@@ -702,6 +778,48 @@ impl RenderBackend for WebCanvasRenderBackend {
             .unwrap();
     }
 
+    fn push_blend_mode(&mut self, blend_mode: swf::BlendMode) {
+        // The blended clip is rendered to a render target of its own,
+        // which is composited onto the one below it when it is popped.
+        self.blend_modes.push(blend_mode);
+        self.push_render_target();
+    }
+    fn pop_blend_mode(&mut self) {
+        let blend_mode = self.blend_modes.pop().unwrap_or(swf::BlendMode::Normal);
+        let (layer_canvas, layer_context) = self.pop_render_target();
+        layer_context.reset_transform().warn_on_error();
+        self.context.reset_transform().warn_on_error();
+
+        let width = f64::from(self.viewport_width);
+        let height = f64::from(self.viewport_height);
+        match blend_mode {
+            swf::BlendMode::Invert => {
+                // Fill the clip with white, keeping its alpha, and take the
+                // difference with it, which inverts the colors below.
+                layer_context
+                    .set_global_composite_operation("source-in")
+                    .warn_on_error();
+                layer_context.set_fill_style(&"#fff".into());
+                layer_context.fill_rect(0.0, 0.0, width, height);
+                layer_context
+                    .set_global_composite_operation("source-over")
+                    .warn_on_error();
+                self.composite_layer(&layer_canvas, "difference");
+            }
+            swf::BlendMode::Subtract => {
+                // Canvas has no subtractive blending, but `b - s` is the same
+                // as `1 - ((1 - b) + s)`: invert what's below, add the clip,
+                // and invert the result again.
+                self.invert_render_target();
+                self.composite_layer(&layer_canvas, "lighter");
+                self.invert_render_target();
+            }
+            blend_mode => {
+                self.composite_layer(&layer_canvas, composite_operation(blend_mode));
+            }
+        }
+    }
+
     fn get_bitmap_pixels(&mut self, bitmap: BitmapHandle) -> Option<Bitmap> {
         let window = web_sys::window().unwrap();
         let document = window.document().unwrap();
@@ -741,6 +859,47 @@ impl RenderBackend for WebCanvasRenderBackend {
         }
     }
 
+    fn render_offscreen(
+        &mut self,
+        width: u32,
+        height: u32,
+        clear: Color,
+        draw: &mut dyn FnMut(&mut dyn RenderBackend),
+    ) -> Option<Bitmap> {
+        let viewport = (self.viewport_width, self.viewport_height);
+        self.viewport_width = width;
+        self.viewport_height = height;
+        self.push_render_target();
+
+        // This may be called while rendering the main frame, such as for a
+        // clip cached as a bitmap inside a mask, so its state is restored
+        // afterwards.
+        let deactivating_mask = self.deactivating_mask;
+        let blend_modes = std::mem::take(&mut self.blend_modes);
+
+        self.begin_frame(clear);
+        draw(self);
+        self.end_frame();
+
+        self.deactivating_mask = deactivating_mask;
+        self.blend_modes = blend_modes;
+        self.context.reset_transform().unwrap();
+        let pixels = self
+            .context
+            .get_image_data(0.0, 0.0, width.into(), height.into())
+            .ok();
+
+        self.pop_render_target();
+        self.viewport_width = viewport.0;
+        self.viewport_height = viewport.1;
+
+        pixels.map(|pixels| Bitmap {
+            width,
+            height,
+            data: BitmapFormat::Rgba(pixels.data().to_vec()),
+        })
+    }
+
     fn register_bitmap_raw(
         &mut self,
         width: u32,
@@ -761,12 +920,14 @@ impl RenderBackend for WebCanvasRenderBackend {
         handle: BitmapHandle,
         width: u32,
         height: u32,
-        rgba: Vec<u8>,
+        rgba: &[u8],
     ) -> Result<BitmapHandle, Error> {
+        check_rgba_len(width, height, rgba)?;
+
         let png = Self::bitmap_to_png_data_uri(Bitmap {
             width,
             height,
-            data: BitmapFormat::Rgba(rgba),
+            data: BitmapFormat::Rgba(rgba.to_vec()),
         })?;
 
         let image = HtmlImageElement::new().unwrap();
@@ -837,275 +998,276 @@ fn swf_shape_to_svg(
     let mut num_defs = 0;
     let mut has_linear_rgb_gradient = false;
 
-    let mut svg_paths = Vec::with_capacity(shape.paths.len());
-    for path in shape.paths {
-        match path {
-            DrawPath::Fill { style, commands } => {
-                let mut svg_path = SvgPath::new();
+    // Converts a fill style to an SVG paint, adding the gradient or pattern
+    // that it refers to to the definitions. Strokes with a fill style use
+    // this as well.
+    let mut fill_style_to_paint = |style: &FillStyle, mut svg_path: SvgPath| {
+        let fill = match style {
+            FillStyle::Color(Color { r, g, b, a }) => {
+                format!("rgba({},{},{},{})", r, g, b, f32::from(*a) / 255.0)
+            }
+            FillStyle::LinearGradient(gradient) => {
+                let shift = Matrix {
+                    a: 32768.0 / width,
+                    d: 32768.0 / height,
+                    tx: swf::Twips::new(-16384),
+                    ty: swf::Twips::new(-16384),
+                    ..Default::default()
+                };
+                let gradient_matrix = Matrix::from(gradient.matrix) * shift;
 
-                let fill = match style {
-                    FillStyle::Color(Color { r, g, b, a }) => {
-                        format!("rgba({},{},{},{})", r, g, b, f32::from(*a) / 255.0)
-                    }
-                    FillStyle::LinearGradient(gradient) => {
-                        let shift = Matrix {
-                            a: 32768.0 / width,
-                            d: 32768.0 / height,
-                            tx: swf::Twips::new(-16384),
-                            ty: swf::Twips::new(-16384),
-                            ..Default::default()
-                        };
-                        let gradient_matrix = Matrix::from(gradient.matrix) * shift;
-
-                        let mut svg_gradient = LinearGradient::new()
-                            .set("id", format!("f{}", num_defs))
-                            .set("gradientUnits", "userSpaceOnUse")
-                            .set(
-                                "gradientTransform",
-                                format!(
-                                    "matrix({} {} {} {} {} {})",
-                                    gradient_matrix.a,
-                                    gradient_matrix.b,
-                                    gradient_matrix.c,
-                                    gradient_matrix.d,
-                                    gradient_matrix.tx.get(),
-                                    gradient_matrix.ty.get()
-                                ),
-                            );
-                        svg_gradient = match gradient.spread {
-                            GradientSpread::Pad => svg_gradient, // default
-                            GradientSpread::Reflect => svg_gradient.set("spreadMethod", "reflect"),
-                            GradientSpread::Repeat => svg_gradient.set("spreadMethod", "repeat"),
-                        };
-                        if gradient.interpolation == GradientInterpolation::LinearRgb {
-                            has_linear_rgb_gradient = true;
-                            svg_path = svg_path.set("filter", "url('#_linearrgb')");
-                        }
-                        for record in &gradient.records {
-                            let color =
-                                if gradient.interpolation == GradientInterpolation::LinearRgb {
-                                    srgb_to_linear(record.color.clone())
-                                } else {
-                                    record.color.clone()
-                                };
-                            let stop = Stop::new()
-                                .set("offset", format!("{}%", f32::from(record.ratio) / 2.55))
-                                .set(
-                                    "stop-color",
-                                    format!(
-                                        "rgba({},{},{},{})",
-                                        color.r,
-                                        color.g,
-                                        color.b,
-                                        f32::from(color.a) / 255.0
-                                    ),
-                                );
-                            svg_gradient = svg_gradient.add(stop);
-                        }
-                        defs = defs.add(svg_gradient);
+                let mut svg_gradient = LinearGradient::new()
+                    .set("id", format!("f{}", num_defs))
+                    .set("gradientUnits", "userSpaceOnUse")
+                    .set(
+                        "gradientTransform",
+                        format!(
+                            "matrix({} {} {} {} {} {})",
+                            gradient_matrix.a,
+                            gradient_matrix.b,
+                            gradient_matrix.c,
+                            gradient_matrix.d,
+                            gradient_matrix.tx.get(),
+                            gradient_matrix.ty.get()
+                        ),
+                    );
+                svg_gradient = match gradient.spread {
+                    GradientSpread::Pad => svg_gradient, // default
+                    GradientSpread::Reflect => svg_gradient.set("spreadMethod", "reflect"),
+                    GradientSpread::Repeat => svg_gradient.set("spreadMethod", "repeat"),
+                };
+                if gradient.interpolation == GradientInterpolation::LinearRgb {
+                    has_linear_rgb_gradient = true;
+                    svg_path = svg_path.set("filter", "url('#_linearrgb')");
+                }
+                for record in &gradient.records {
+                    let color = if gradient.interpolation == GradientInterpolation::LinearRgb {
+                        srgb_to_linear(record.color.clone())
+                    } else {
+                        record.color.clone()
+                    };
+                    let stop = Stop::new()
+                        .set("offset", format!("{}%", f32::from(record.ratio) / 2.55))
+                        .set(
+                            "stop-color",
+                            format!(
+                                "rgba({},{},{},{})",
+                                color.r,
+                                color.g,
+                                color.b,
+                                f32::from(color.a) / 255.0
+                            ),
+                        );
+                    svg_gradient = svg_gradient.add(stop);
+                }
+                defs = defs.add(svg_gradient);
 
-                        let fill_id = format!("url(#f{})", num_defs);
-                        num_defs += 1;
-                        fill_id
-                    }
-                    FillStyle::RadialGradient(gradient) => {
-                        let shift = Matrix {
-                            a: 32768.0,
-                            d: 32768.0,
-                            ..Default::default()
-                        };
-                        let gradient_matrix = Matrix::from(gradient.matrix) * shift;
-
-                        let mut svg_gradient = RadialGradient::new()
-                            .set("id", format!("f{}", num_defs))
-                            .set("gradientUnits", "userSpaceOnUse")
-                            .set("cx", "0")
-                            .set("cy", "0")
-                            .set("r", "0.5")
-                            .set(
-                                "gradientTransform",
-                                format!(
-                                    "matrix({} {} {} {} {} {})",
-                                    gradient_matrix.a,
-                                    gradient_matrix.b,
-                                    gradient_matrix.c,
-                                    gradient_matrix.d,
-                                    gradient_matrix.tx.get(),
-                                    gradient_matrix.ty.get()
-                                ),
-                            );
-                        svg_gradient = match gradient.spread {
-                            GradientSpread::Pad => svg_gradient, // default
-                            GradientSpread::Reflect => svg_gradient.set("spreadMethod", "reflect"),
-                            GradientSpread::Repeat => svg_gradient.set("spreadMethod", "repeat"),
-                        };
-                        if gradient.interpolation == GradientInterpolation::LinearRgb {
-                            has_linear_rgb_gradient = true;
-                            svg_path = svg_path.set("filter", "url('#_linearrgb')");
-                        }
-                        for record in &gradient.records {
-                            let color =
-                                if gradient.interpolation == GradientInterpolation::LinearRgb {
-                                    srgb_to_linear(record.color.clone())
-                                } else {
-                                    record.color.clone()
-                                };
-                            let stop = Stop::new()
-                                .set("offset", format!("{}%", f32::from(record.ratio) / 2.55))
-                                .set(
-                                    "stop-color",
-                                    format!(
-                                        "rgba({},{},{},{})",
-                                        color.r,
-                                        color.g,
-                                        color.b,
-                                        f32::from(color.a) / 255.0
-                                    ),
-                                );
-                            svg_gradient = svg_gradient.add(stop);
-                        }
-                        defs = defs.add(svg_gradient);
+                let fill_id = format!("url(#f{})", num_defs);
+                num_defs += 1;
+                fill_id
+            }
+            FillStyle::RadialGradient(gradient) => {
+                let shift = Matrix {
+                    a: 32768.0,
+                    d: 32768.0,
+                    ..Default::default()
+                };
+                let gradient_matrix = Matrix::from(gradient.matrix) * shift;
+
+                let mut svg_gradient = RadialGradient::new()
+                    .set("id", format!("f{}", num_defs))
+                    .set("gradientUnits", "userSpaceOnUse")
+                    .set("cx", "0")
+                    .set("cy", "0")
+                    .set("r", "0.5")
+                    .set(
+                        "gradientTransform",
+                        format!(
+                            "matrix({} {} {} {} {} {})",
+                            gradient_matrix.a,
+                            gradient_matrix.b,
+                            gradient_matrix.c,
+                            gradient_matrix.d,
+                            gradient_matrix.tx.get(),
+                            gradient_matrix.ty.get()
+                        ),
+                    );
+                svg_gradient = match gradient.spread {
+                    GradientSpread::Pad => svg_gradient, // default
+                    GradientSpread::Reflect => svg_gradient.set("spreadMethod", "reflect"),
+                    GradientSpread::Repeat => svg_gradient.set("spreadMethod", "repeat"),
+                };
+                if gradient.interpolation == GradientInterpolation::LinearRgb {
+                    has_linear_rgb_gradient = true;
+                    svg_path = svg_path.set("filter", "url('#_linearrgb')");
+                }
+                for record in &gradient.records {
+                    let color = if gradient.interpolation == GradientInterpolation::LinearRgb {
+                        srgb_to_linear(record.color.clone())
+                    } else {
+                        record.color.clone()
+                    };
+                    let stop = Stop::new()
+                        .set("offset", format!("{}%", f32::from(record.ratio) / 2.55))
+                        .set(
+                            "stop-color",
+                            format!(
+                                "rgba({},{},{},{})",
+                                color.r,
+                                color.g,
+                                color.b,
+                                f32::from(color.a) / 255.0
+                            ),
+                        );
+                    svg_gradient = svg_gradient.add(stop);
+                }
+                defs = defs.add(svg_gradient);
 
-                        let fill_id = format!("url(#f{})", num_defs);
-                        num_defs += 1;
-                        fill_id
-                    }
-                    FillStyle::FocalGradient {
-                        gradient,
-                        focal_point,
-                    } => {
-                        let shift = Matrix {
-                            a: 32768.0,
-                            d: 32768.0,
-                            ..Default::default()
-                        };
-                        let gradient_matrix = Matrix::from(gradient.matrix) * shift;
-
-                        let mut svg_gradient = RadialGradient::new()
-                            .set("id", format!("f{}", num_defs))
-                            .set("fx", focal_point.to_f32() / 2.0)
-                            .set("gradientUnits", "userSpaceOnUse")
-                            .set("cx", "0")
-                            .set("cy", "0")
-                            .set("r", "0.5")
-                            .set(
-                                "gradientTransform",
-                                format!(
-                                    "matrix({} {} {} {} {} {})",
-                                    gradient_matrix.a,
-                                    gradient_matrix.b,
-                                    gradient_matrix.c,
-                                    gradient_matrix.d,
-                                    gradient_matrix.tx.get(),
-                                    gradient_matrix.ty.get()
-                                ),
-                            );
-                        svg_gradient = match gradient.spread {
-                            GradientSpread::Pad => svg_gradient, // default
-                            GradientSpread::Reflect => svg_gradient.set("spreadMethod", "reflect"),
-                            GradientSpread::Repeat => svg_gradient.set("spreadMethod", "repeat"),
-                        };
-                        if gradient.interpolation == GradientInterpolation::LinearRgb {
-                            has_linear_rgb_gradient = true;
-                            svg_path = svg_path.set("filter", "url('#_linearrgb')");
-                        }
-                        for record in &gradient.records {
-                            let color =
-                                if gradient.interpolation == GradientInterpolation::LinearRgb {
-                                    srgb_to_linear(record.color.clone())
-                                } else {
-                                    record.color.clone()
-                                };
-                            let stop = Stop::new()
-                                .set("offset", format!("{}%", f32::from(record.ratio) / 2.55))
-                                .set(
-                                    "stop-color",
-                                    format!(
-                                        "rgba({},{},{},{})",
-                                        color.r,
-                                        color.g,
-                                        color.b,
-                                        f32::from(color.a) / 255.0
-                                    ),
-                                );
-                            svg_gradient = svg_gradient.add(stop);
+                let fill_id = format!("url(#f{})", num_defs);
+                num_defs += 1;
+                fill_id
+            }
+            FillStyle::FocalGradient {
+                gradient,
+                focal_point,
+            } => {
+                let shift = Matrix {
+                    a: 32768.0,
+                    d: 32768.0,
+                    ..Default::default()
+                };
+                let gradient_matrix = Matrix::from(gradient.matrix) * shift;
+
+                let mut svg_gradient = RadialGradient::new()
+                    .set("id", format!("f{}", num_defs))
+                    // Flash keeps the focal point just inside of the edge.
+                    .set("fx", focal_point.to_f32().clamp(-0.98, 0.98) / 2.0)
+                    .set("gradientUnits", "userSpaceOnUse")
+                    .set("cx", "0")
+                    .set("cy", "0")
+                    .set("r", "0.5")
+                    .set(
+                        "gradientTransform",
+                        format!(
+                            "matrix({} {} {} {} {} {})",
+                            gradient_matrix.a,
+                            gradient_matrix.b,
+                            gradient_matrix.c,
+                            gradient_matrix.d,
+                            gradient_matrix.tx.get(),
+                            gradient_matrix.ty.get()
+                        ),
+                    );
+                svg_gradient = match gradient.spread {
+                    GradientSpread::Pad => svg_gradient, // default
+                    GradientSpread::Reflect => svg_gradient.set("spreadMethod", "reflect"),
+                    GradientSpread::Repeat => svg_gradient.set("spreadMethod", "repeat"),
+                };
+                if gradient.interpolation == GradientInterpolation::LinearRgb {
+                    has_linear_rgb_gradient = true;
+                    svg_path = svg_path.set("filter", "url('#_linearrgb')");
+                }
+                for record in &gradient.records {
+                    let color = if gradient.interpolation == GradientInterpolation::LinearRgb {
+                        srgb_to_linear(record.color.clone())
+                    } else {
+                        record.color.clone()
+                    };
+                    let stop = Stop::new()
+                        .set("offset", format!("{}%", f32::from(record.ratio) / 2.55))
+                        .set(
+                            "stop-color",
+                            format!(
+                                "rgba({},{},{},{})",
+                                color.r,
+                                color.g,
+                                color.b,
+                                f32::from(color.a) / 255.0
+                            ),
+                        );
+                    svg_gradient = svg_gradient.add(stop);
+                }
+                defs = defs.add(svg_gradient);
+
+                let fill_id = format!("url(#f{})", num_defs);
+                num_defs += 1;
+                fill_id
+            }
+            FillStyle::Bitmap {
+                id,
+                matrix,
+                is_smoothed,
+                is_repeating,
+            } => {
+                if let Some(bitmap) = bitmap_source
+                    .bitmap(*id)
+                    .and_then(|bitmap| bitmaps.get(bitmap.handle.0))
+                {
+                    if !bitmap_defs.contains(id) {
+                        let mut image = Image::new()
+                            .set("width", bitmap.width)
+                            .set("height", bitmap.height)
+                            .set("xlink:href", bitmap.data.as_str());
+
+                        if !*is_smoothed {
+                            image = image.set("image-rendering", pixelated_property_value);
                         }
-                        defs = defs.add(svg_gradient);
 
-                        let fill_id = format!("url(#f{})", num_defs);
-                        num_defs += 1;
-                        fill_id
-                    }
-                    FillStyle::Bitmap {
-                        id,
-                        matrix,
-                        is_smoothed,
-                        is_repeating,
-                    } => {
-                        if let Some(bitmap) = bitmap_source
-                            .bitmap(*id)
-                            .and_then(|bitmap| bitmaps.get(bitmap.handle.0))
-                        {
-                            if !bitmap_defs.contains(id) {
-                                let mut image = Image::new()
-                                    .set("width", bitmap.width)
-                                    .set("height", bitmap.height)
-                                    .set("xlink:href", bitmap.data.as_str());
-
-                                if !*is_smoothed {
-                                    image = image.set("image-rendering", pixelated_property_value);
-                                }
-
-                                let mut bitmap_pattern = Pattern::new()
-                                    .set("id", format!("b{}", id))
-                                    .set("patternUnits", "userSpaceOnUse");
-
-                                if !*is_repeating {
-                                    bitmap_pattern = bitmap_pattern
-                                        .set("width", bitmap.width)
-                                        .set("height", bitmap.height);
-                                } else {
-                                    bitmap_pattern = bitmap_pattern
-                                        .set("width", bitmap.width)
-                                        .set("height", bitmap.height)
-                                        .set(
-                                            "viewBox",
-                                            format!("0 0 {} {}", bitmap.width, bitmap.height),
-                                        );
-                                }
-
-                                bitmap_pattern = bitmap_pattern.add(image);
-
-                                defs = defs.add(bitmap_pattern);
-                                bitmap_defs.insert(*id);
-                            }
+                        let mut bitmap_pattern = Pattern::new()
+                            .set("id", format!("b{}", id))
+                            .set("patternUnits", "userSpaceOnUse");
+
+                        if !*is_repeating {
+                            bitmap_pattern = bitmap_pattern
+                                .set("width", bitmap.width)
+                                .set("height", bitmap.height);
                         } else {
-                            log::error!("Couldn't fill shape with unknown bitmap {}", id);
+                            bitmap_pattern = bitmap_pattern
+                                .set("width", bitmap.width)
+                                .set("height", bitmap.height)
+                                .set("viewBox", format!("0 0 {} {}", bitmap.width, bitmap.height));
                         }
 
-                        let svg_pattern = Pattern::new()
-                            .set("id", format!("f{}", num_defs))
-                            .set("xlink:href", format!("#b{}", id))
-                            .set(
-                                "patternTransform",
-                                format!(
-                                    "matrix({} {} {} {} {} {})",
-                                    matrix.a,
-                                    matrix.b,
-                                    matrix.c,
-                                    matrix.d,
-                                    matrix.tx.get(),
-                                    matrix.ty.get()
-                                ),
-                            );
-
-                        defs = defs.add(svg_pattern);
-
-                        let fill_id = format!("url(#f{})", num_defs);
-                        num_defs += 1;
-                        fill_id
+                        bitmap_pattern = bitmap_pattern.add(image);
+
+                        defs = defs.add(bitmap_pattern);
+                        bitmap_defs.insert(*id);
                     }
-                };
+                } else {
+                    log::error!("Couldn't fill shape with unknown bitmap {}", id);
+                }
+
+                let svg_pattern = Pattern::new()
+                    .set("id", format!("f{}", num_defs))
+                    .set("xlink:href", format!("#b{}", id))
+                    .set(
+                        "patternTransform",
+                        format!(
+                            "matrix({} {} {} {} {} {})",
+                            matrix.a,
+                            matrix.b,
+                            matrix.c,
+                            matrix.d,
+                            matrix.tx.get(),
+                            matrix.ty.get()
+                        ),
+                    );
+
+                defs = defs.add(svg_pattern);
+
+                let fill_id = format!("url(#f{})", num_defs);
+                num_defs += 1;
+                fill_id
+            }
+        };
+        (fill, svg_path)
+    };
+
+    let mut svg_paths = Vec::with_capacity(shape.paths.len());
+    for path in shape.paths {
+        match path {
+            DrawPath::Fill { style, commands } => {
+                let (fill, mut svg_path) = fill_style_to_paint(style, SvgPath::new());
 
                 let mut data = Data::new();
                 for command in commands {
@@ -1129,24 +1291,23 @@ fn swf_shape_to_svg(
                 commands,
                 is_closed,
             } => {
-                // Flash always renders strokes with a minimum width of 1 pixel (20 twips).
-                // Additionally, many SWFs use the "hairline" stroke setting, which sets the stroke's width
-                // to 1 twip. Because of the minimum, this will effectively make the stroke nearly-always render
-                // as 1 pixel wide.
-                // SVG doesn't have a minimum and can render strokes at fractional widths, so these hairline
-                // strokes end up rendering very faintly if we use the actual width of 1 twip.
-                // Therefore, we clamp the stroke width to 1 pixel (20 twips). This won't be 100% accurate
-                // if the shape is scaled, but it looks much closer to the Flash Player.
-                let stroke_width = std::cmp::max(style.width.get(), 20);
-                let mut svg_path = SvgPath::new()
-                    .set("fill", "none")
-                    .set(
-                        "stroke",
+                // Flash always renders strokes with a minimum width of 1 pixel (20 twips) on screen.
+                // Display objects account for this by scaling the widths of line styles for the scale
+                // that the shape is rendered at, so only avoid degenerate strokes here.
+                let stroke_width = std::cmp::max(style.width.get(), 1);
+                let (stroke, svg_path) = match &style.fill_style {
+                    Some(fill_style) => fill_style_to_paint(fill_style, SvgPath::new()),
+                    None => (
                         format!(
                             "rgba({},{},{},{})",
                             style.color.r, style.color.g, style.color.b, style.color.a
                         ),
-                    )
+                        SvgPath::new(),
+                    ),
+                };
+                let mut svg_path = svg_path
+                    .set("fill", "none")
+                    .set("stroke", stroke)
                     .set("stroke-width", stroke_width)
                     .set(
                         "stroke-linecap",
@@ -1395,15 +1556,15 @@ fn swf_shape_to_canvas_commands(
                 commands,
                 is_closed,
             } => {
-                // Flash always renders strokes with a minimum width of 1 pixel (20 twips).
-                // Additionally, many SWFs use the "hairline" stroke setting, which sets the stroke's width
-                // to 1 twip. Because of the minimum, this will effectively make the stroke nearly-always render
-                // as 1 pixel wide.
-                // SVG doesn't have a minimum and can render strokes at fractional widths, so these hairline
-                // strokes end up rendering very faintly if we use the actual width of 1 twip.
-                // Therefore, we clamp the stroke width to 1 pixel (20 twips). This won't be 100% accurate
-                // if the shape is scaled, but it looks much closer to the Flash Player.
-                let line_width = std::cmp::max(style.width.get(), 20);
+                // Strokes with a gradient or bitmap are drawn as SVG, like gradient fills.
+                if style.fill_style.is_some() {
+                    return None;
+                }
+
+                // Flash always renders strokes with a minimum width of 1 pixel (20 twips) on screen.
+                // Display objects account for this by scaling the widths of line styles for the scale
+                // that the shape is rendered at, so only avoid degenerate strokes here.
+                let line_width = std::cmp::max(style.width.get(), 1);
                 let stroke_style = CanvasColor(
                     format!(
                         "rgba({},{},{},{})",
@@ -1437,7 +1598,7 @@ fn swf_shape_to_canvas_commands(
                     stroke_style,
                     line_cap: line_cap.to_string(),
                     line_join: line_join.to_string(),
-                    miter_limit: miter_limit as f64 / 20.0,
+                    miter_limit: miter_limit as f64,
                 });
             }
         }
@@ -205,15 +205,63 @@ impl ShapeTessellator {
                     commands,
                     is_closed,
                 } => {
-                    let mut buffers_builder = BuffersBuilder::new(
-                        &mut lyon_mesh,
-                        RuffleVertexCtor {
-                            color: style.color.clone(),
-                        },
-                    );
+                    // Strokes with a gradient or bitmap are drawn in their own draw call, like fills.
+                    let draw = match &style.fill_style {
+                        Some(swf::FillStyle::LinearGradient(gradient)) => {
+                            Some(DrawType::Gradient(swf_gradient_to_uniforms(
+                                GradientType::Linear,
+                                gradient,
+                                swf::Fixed8::ZERO,
+                            )))
+                        }
+                        Some(swf::FillStyle::RadialGradient(gradient)) => {
+                            Some(DrawType::Gradient(swf_gradient_to_uniforms(
+                                GradientType::Radial,
+                                gradient,
+                                swf::Fixed8::ZERO,
+                            )))
+                        }
+                        Some(swf::FillStyle::FocalGradient {
+                            gradient,
+                            focal_point,
+                        }) => Some(DrawType::Gradient(swf_gradient_to_uniforms(
+                            GradientType::Focal,
+                            gradient,
+                            *focal_point,
+                        ))),
+                        Some(swf::FillStyle::Bitmap {
+                            id,
+                            matrix,
+                            is_smoothed,
+                            is_repeating,
+                        }) => bitmap_source.bitmap(*id).map(|bitmap| {
+                            DrawType::Bitmap(Bitmap {
+                                matrix: swf_bitmap_to_gl_matrix(
+                                    (*matrix).into(),
+                                    bitmap.width.into(),
+                                    bitmap.height.into(),
+                                ),
+                                bitmap: bitmap.handle,
+                                is_smoothed: *is_smoothed,
+                                is_repeating: *is_repeating,
+                            })
+                        }),
+                        _ => None,
+                    };
+
+                    let color = if draw.is_some() {
+                        flush_draw(DrawType::Color, &mut mesh, &mut lyon_mesh);
+                        swf::Color::from_rgb(0xffffff, 255)
+                    } else {
+                        style.color.clone()
+                    };
+                    let mut buffers_builder =
+                        BuffersBuilder::new(&mut lyon_mesh, RuffleVertexCtor { color });
 
-                    // TODO(Herschel): 0 width indicates "hairline".
-                    let width = (style.width.to_pixels() as f32).max(1.0);
+                    // Flash renders strokes at least 1 pixel wide on screen. Display objects
+                    // account for this by scaling the widths of line styles for the scale that
+                    // the shape is rendered at, so only avoid degenerate strokes here.
+                    let width = (style.width.get().max(1) as f32) / 20.0;
 
                     let mut options = StrokeOptions::default()
                         .with_line_width(width)
@@ -253,6 +301,10 @@ impl ShapeTessellator {
                         log::error!("Tessellation failure: {:?}", e);
                         continue;
                     }
+
+                    if let Some(draw) = draw {
+                        flush_draw(draw, &mut mesh, &mut lyon_mesh);
+                    }
                 }
             }
         }
@@ -301,7 +353,10 @@ pub struct Gradient {
     pub colors: Vec<[f32; 4]>,
     pub num_colors: usize,
     pub repeat_mode: swf::GradientSpread,
-    pub focal_point: swf::Fixed8,
+
+    /// The focal point of a focal gradient, from -1 at the left edge of the
+    /// gradient to 1 at its right edge.
+    pub focal_point: f32,
     pub interpolation: swf::GradientInterpolation,
 }
 
@@ -417,6 +472,10 @@ fn ruffle_path_to_lyon_path(commands: Vec<DrawCommand>, is_closed: bool) -> Path
 
 const MAX_GRADIENT_COLORS: usize = 15;
 
+/// Flash keeps the focal point of a focal gradient just inside of its edge,
+/// where the gradient would otherwise degenerate.
+const MAX_FOCAL_POINT: f32 = 0.98;
+
 /// Converts a gradient to the uniforms used by the shader.
 fn swf_gradient_to_uniforms(
     gradient_type: GradientType,
@@ -451,7 +510,9 @@ fn swf_gradient_to_uniforms(
         colors,
         num_colors,
         repeat_mode: gradient.spread,
-        focal_point,
+        focal_point: focal_point
+            .to_f32()
+            .clamp(-MAX_FOCAL_POINT, MAX_FOCAL_POINT),
         interpolation: gradient.interpolation,
     }
 }
@@ -0,0 +1,789 @@
+//! A render backend that rasterizes everything on the CPU.
+//!
+//! Shapes are tessellated the same way as in the GPU backends, and their
+//! triangles are rasterized with up to 16 samples per pixel, depending on
+//! the stage quality. Since it doesn't
+//! need a GPU or a browser, this backend can render headlessly, and its
+//! output is the same on every machine.
+
+use ruffle_core::backend::render::{
+    self, Bitmap, BitmapFormat, BitmapHandle, BitmapInfo, BitmapSource, Color, RenderBackend,
+    ShapeHandle, Transform, ViewportRect,
+};
+use ruffle_core::bitmap::bitmap_data;
+use ruffle_core::matrix::Matrix;
+use ruffle_core::shape_utils::DistilledShape;
+use ruffle_core::swf;
+use ruffle_core::StageQuality;
+use ruffle_render_common_tess::{
+    Bitmap as TessBitmap, DrawType as TessDrawType, Gradient as TessGradient, ShapeTessellator,
+};
+
+mod paint;
+mod rasterizer;
+
+use crate::paint::{Affine, Paint, Texture};
+use crate::rasterizer::{Coverage, MAX_SAMPLES};
+
+type Error = Box<dyn std::error::Error>;
+
+pub struct SoftwareRenderBackend {
+    target: Target,
+    tessellator: ShapeTessellator,
+    meshes: Vec<Mesh>,
+    textures: Vec<Texture>,
+
+    /// The number of anti-aliasing samples along each axis of a pixel.
+    samples_per_axis: u32,
+}
+
+struct Mesh {
+    draws: Vec<Draw>,
+}
+
+/// Triangles of a shape that are filled with the same paint.
+struct Draw {
+    fill: Fill,
+
+    /// The corners of the triangles, in pixels in the shape's coordinate space.
+    triangles: Vec<[(f32, f32); 3]>,
+}
+
+enum Fill {
+    Color(swf::Color),
+    Gradient(TessGradient),
+    Bitmap(TessBitmap),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MaskState {
+    NoMask,
+    DrawMask,
+    DrawMaskedContent,
+    ClearMask,
+}
+
+/// The pixels being rendered to, along with the masks and blend layers
+/// that apply to them.
+struct Target {
+    width: u32,
+    height: u32,
+
+    /// The pixels of the innermost layer, in pre-multiplied RGBA.
+    pixels: Vec<[u8; 4]>,
+
+    /// The samples covered by the shape currently being drawn.
+    coverage: Coverage,
+
+    /// The stack of masks of the innermost layer, holding the coverage of
+    /// every pixel. Once activated, a mask is intersected with the masks
+    /// below it.
+    masks: Vec<Vec<u8>>,
+    mask_state: MaskState,
+
+    /// The layers that blended display objects are being rendered to.
+    layers: Vec<BlendLayer>,
+
+    /// The rectangles that the current frame is limited to, or `None` if it
+    /// covers the whole target.
+    damage: Option<Vec<ViewportRect>>,
+}
+
+/// The state of a render target below a blend layer, which is restored when
+/// the layer is composited onto it.
+struct BlendLayer {
+    blend_mode: swf::BlendMode,
+    pixels: Vec<[u8; 4]>,
+    masks: Vec<Vec<u8>>,
+    mask_state: MaskState,
+}
+
+impl SoftwareRenderBackend {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            target: Target::new(width, height, MAX_SAMPLES),
+            tessellator: ShapeTessellator::new(),
+            meshes: Vec::new(),
+            textures: Vec::new(),
+            samples_per_axis: MAX_SAMPLES,
+        }
+    }
+
+    /// The last rendered frame, in un-multiplied RGBA.
+    pub fn capture_frame(&self) -> Bitmap {
+        self.target.capture()
+    }
+
+    fn register_shape_internal(
+        &mut self,
+        shape: DistilledShape,
+        bitmap_source: &dyn BitmapSource,
+    ) -> Mesh {
+        let lyon_mesh = self.tessellator.tessellate_shape(shape, bitmap_source);
+
+        let mut draws: Vec<Draw> = Vec::with_capacity(lyon_mesh.len());
+        for draw in lyon_mesh {
+            let vertices = &draw.vertices;
+            let triangles = draw.indices.chunks_exact(3).map(move |triangle| {
+                let [a, b, c] =
+                    [triangle[0], triangle[1], triangle[2]].map(|i| &vertices[i as usize]);
+                ([(a.x, a.y), (b.x, b.y), (c.x, c.y)], &a.color)
+            });
+            match draw.draw_type {
+                TessDrawType::Color => {
+                    // Solid fills of different colors are tessellated into one
+                    // draw, so they are split up by their vertex colors.
+                    for (triangle, color) in triangles {
+                        match draws.last_mut() {
+                            Some(Draw {
+                                fill: Fill::Color(last_color),
+                                triangles,
+                            }) if last_color == color => triangles.push(triangle),
+                            _ => draws.push(Draw {
+                                fill: Fill::Color(color.clone()),
+                                triangles: vec![triangle],
+                            }),
+                        }
+                    }
+                }
+                TessDrawType::Gradient(gradient) => draws.push(Draw {
+                    fill: Fill::Gradient(gradient),
+                    triangles: triangles.map(|(triangle, _)| triangle).collect(),
+                }),
+                TessDrawType::Bitmap(bitmap) => draws.push(Draw {
+                    fill: Fill::Bitmap(bitmap),
+                    triangles: triangles.map(|(triangle, _)| triangle).collect(),
+                }),
+            }
+        }
+
+        Mesh { draws }
+    }
+
+    fn register_bitmap(&mut self, bitmap: Bitmap) -> BitmapInfo {
+        let handle = BitmapHandle(self.textures.len());
+        let width = bitmap.width;
+        let height = bitmap.height;
+        self.textures.push(bitmap_to_texture(bitmap));
+        BitmapInfo {
+            handle,
+            width: width as u16,
+            height: height as u16,
+        }
+    }
+}
+
+impl RenderBackend for SoftwareRenderBackend {
+    fn set_viewport_dimensions(&mut self, width: u32, height: u32) {
+        self.target = Target::new(width, height, self.samples_per_axis);
+    }
+
+    fn register_shape(
+        &mut self,
+        shape: DistilledShape,
+        bitmap_source: &dyn BitmapSource,
+    ) -> ShapeHandle {
+        let handle = ShapeHandle(self.meshes.len());
+        let mesh = self.register_shape_internal(shape, bitmap_source);
+        self.meshes.push(mesh);
+        handle
+    }
+
+    fn replace_shape(
+        &mut self,
+        shape: DistilledShape,
+        bitmap_source: &dyn BitmapSource,
+        handle: ShapeHandle,
+    ) {
+        let mesh = self.register_shape_internal(shape, bitmap_source);
+        self.meshes[handle.0] = mesh;
+    }
+
+    fn register_glyph_shape(&mut self, glyph: &swf::Glyph) -> ShapeHandle {
+        let shape = ruffle_core::shape_utils::swf_glyph_to_shape(glyph);
+        let handle = ShapeHandle(self.meshes.len());
+        let mesh = self.register_shape_internal((&shape).into(), &render::NullBitmapSource);
+        self.meshes.push(mesh);
+        handle
+    }
+
+    fn register_bitmap_jpeg(
+        &mut self,
+        data: &[u8],
+        jpeg_tables: Option<&[u8]>,
+    ) -> Result<BitmapInfo, Error> {
+        let data = render::glue_tables_to_jpeg(data, jpeg_tables);
+        self.register_bitmap_jpeg_2(&data[..])
+    }
+
+    fn register_bitmap_jpeg_2(&mut self, data: &[u8]) -> Result<BitmapInfo, Error> {
+        let bitmap = render::decode_define_bits_jpeg(data, None)?;
+        Ok(self.register_bitmap(bitmap))
+    }
+
+    fn register_bitmap_jpeg_3_or_4(
+        &mut self,
+        jpeg_data: &[u8],
+        alpha_data: &[u8],
+    ) -> Result<BitmapInfo, Error> {
+        let bitmap = render::decode_define_bits_jpeg(jpeg_data, Some(alpha_data))?;
+        Ok(self.register_bitmap(bitmap))
+    }
+
+    fn register_bitmap_png(
+        &mut self,
+        swf_tag: &swf::DefineBitsLossless,
+    ) -> Result<BitmapInfo, Error> {
+        let bitmap = render::decode_define_bits_lossless(swf_tag)?;
+        Ok(self.register_bitmap(bitmap))
+    }
+
+    fn set_quality(&mut self, quality: StageQuality) {
+        let samples_per_axis = quality.sample_count().min(MAX_SAMPLES);
+        if samples_per_axis != self.samples_per_axis {
+            self.samples_per_axis = samples_per_axis;
+            self.target.coverage =
+                Coverage::new(self.target.width, self.target.height, samples_per_axis);
+        }
+    }
+
+    fn begin_frame(&mut self, clear: Color) {
+        self.target.clear(&clear, None);
+    }
+
+    fn supports_partial_frames(&self) -> bool {
+        true
+    }
+
+    fn begin_partial_frame(&mut self, clear: Color, damage: &[ViewportRect]) {
+        self.target.clear(&clear, Some(damage));
+    }
+
+    fn end_frame(&mut self) {
+        // Noop
+    }
+
+    fn render_bitmap(&mut self, bitmap: BitmapHandle, transform: &Transform, smoothing: bool) {
+        let texture = match self.textures.get(bitmap.0) {
+            Some(texture) => texture,
+            None => return,
+        };
+        let world = Affine::from(&transform.matrix);
+        let to_uv = match world.inverse() {
+            Some(inverse) => {
+                Affine::scale(1.0 / texture.width as f32, 1.0 / texture.height as f32) * inverse
+            }
+            None => return,
+        };
+
+        let (width, height) = (texture.width as f32, texture.height as f32);
+        self.target.fill_quad(world, width, height);
+        self.target.paint(&Paint::Bitmap {
+            texture,
+            to_uv,
+            color_transform: &transform.color_transform,
+            repeating: false,
+            smoothed: smoothing,
+        });
+    }
+
+    fn render_shape(&mut self, shape: ShapeHandle, transform: &Transform) {
+        let mesh = match self.meshes.get(shape.0) {
+            Some(mesh) => mesh,
+            None => return,
+        };
+        let world = Affine::from(&transform.matrix);
+        let inverse = match world.inverse() {
+            Some(inverse) => inverse,
+            None => return,
+        };
+
+        for draw in &mesh.draws {
+            let paint = match &draw.fill {
+                Fill::Color(color) => Paint::Color(paint::transform_color(
+                    [
+                        f32::from(color.r) / 255.0,
+                        f32::from(color.g) / 255.0,
+                        f32::from(color.b) / 255.0,
+                        f32::from(color.a) / 255.0,
+                    ],
+                    &transform.color_transform,
+                )),
+                Fill::Gradient(gradient) => Paint::Gradient {
+                    gradient,
+                    to_uv: Affine::from_columns(&gradient.matrix) * inverse,
+                    color_transform: &transform.color_transform,
+                },
+                Fill::Bitmap(bitmap) => match self.textures.get(bitmap.bitmap.0) {
+                    Some(texture) => Paint::Bitmap {
+                        texture,
+                        to_uv: Affine::from_columns(&bitmap.matrix) * inverse,
+                        color_transform: &transform.color_transform,
+                        repeating: bitmap.is_repeating,
+                        smoothed: bitmap.is_smoothed,
+                    },
+                    None => continue,
+                },
+            };
+
+            for triangle in &draw.triangles {
+                self.target
+                    .fill_triangle(triangle.map(|(x, y)| world.apply(x, y)));
+            }
+            self.target.paint(&paint);
+        }
+    }
+
+    fn draw_rect(&mut self, color: Color, matrix: &Matrix) {
+        let alpha = f32::from(color.a) / 255.0;
+        let color = [
+            (f32::from(color.r) * alpha).round() as u8,
+            (f32::from(color.g) * alpha).round() as u8,
+            (f32::from(color.b) * alpha).round() as u8,
+            color.a,
+        ];
+        self.target.fill_quad(Affine::from(matrix), 1.0, 1.0);
+        self.target.paint(&Paint::Color(color));
+    }
+
+    fn push_mask(&mut self) {
+        self.target.push_mask();
+    }
+
+    fn activate_mask(&mut self) {
+        self.target.activate_mask();
+    }
+
+    fn deactivate_mask(&mut self) {
+        self.target.mask_state = MaskState::ClearMask;
+    }
+
+    fn pop_mask(&mut self) {
+        self.target.pop_mask();
+    }
+
+    fn push_blend_mode(&mut self, blend_mode: swf::BlendMode) {
+        self.target.push_layer(blend_mode);
+    }
+
+    fn pop_blend_mode(&mut self) {
+        self.target.pop_layer();
+    }
+
+    fn get_bitmap_pixels(&mut self, bitmap: BitmapHandle) -> Option<Bitmap> {
+        let texture = self.textures.get(bitmap.0)?;
+        Some(Bitmap {
+            width: texture.width,
+            height: texture.height,
+            data: BitmapFormat::Rgba(texture.pixels.iter().flatten().copied().collect()),
+        })
+    }
+
+    fn register_bitmap_raw(
+        &mut self,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> Result<BitmapHandle, Error> {
+        Ok(self
+            .register_bitmap(Bitmap {
+                width,
+                height,
+                data: BitmapFormat::Rgba(rgba),
+            })
+            .handle)
+    }
+
+    fn update_texture(
+        &mut self,
+        handle: BitmapHandle,
+        width: u32,
+        height: u32,
+        rgba: &[u8],
+    ) -> Result<BitmapHandle, Error> {
+        render::check_rgba_len(width, height, rgba)?;
+
+        let texture = self
+            .textures
+            .get_mut(handle.0)
+            .ok_or("Unknown bitmap handle")?;
+        *texture = bitmap_to_texture(Bitmap {
+            width,
+            height,
+            data: BitmapFormat::Rgba(rgba.to_vec()),
+        });
+        Ok(handle)
+    }
+
+    fn render_offscreen(
+        &mut self,
+        width: u32,
+        height: u32,
+        clear: Color,
+        draw: &mut dyn FnMut(&mut dyn RenderBackend),
+    ) -> Option<Bitmap> {
+        let target = std::mem::replace(
+            &mut self.target,
+            Target::new(width, height, self.samples_per_axis),
+        );
+        self.begin_frame(clear);
+        draw(self);
+        self.end_frame();
+        let offscreen = std::mem::replace(&mut self.target, target);
+        Some(offscreen.capture())
+    }
+}
+
+impl Target {
+    fn new(width: u32, height: u32, samples_per_axis: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![[0; 4]; width as usize * height as usize],
+            coverage: Coverage::new(width, height, samples_per_axis),
+            masks: Vec::new(),
+            mask_state: MaskState::NoMask,
+            layers: Vec::new(),
+            damage: None,
+        }
+    }
+
+    /// Whether the given pixel is inside of the area that the current frame
+    /// is limited to.
+    fn is_damaged(damage: &Option<Vec<ViewportRect>>, x: u32, y: u32) -> bool {
+        match damage {
+            Some(damage) => damage.iter().any(|rect| rect.contains(x, y)),
+            None => true,
+        }
+    }
+
+    /// Clears the target to start a new frame, which is limited to the given
+    /// rectangles if there are any.
+    fn clear(&mut self, color: &Color, damage: Option<&[ViewportRect]>) {
+        if let Some(layer) = self.layers.drain(..).next() {
+            self.pixels = layer.pixels;
+        }
+        self.masks.clear();
+        self.mask_state = MaskState::NoMask;
+
+        let alpha = u16::from(color.a);
+        let multiply = |channel: u8| ((u16::from(channel) * alpha + 127) / 255) as u8;
+        let color = [
+            multiply(color.r),
+            multiply(color.g),
+            multiply(color.b),
+            color.a,
+        ];
+        self.damage = damage.map(|damage| damage.to_vec());
+        match &self.damage {
+            Some(damage) => {
+                for rect in damage {
+                    let x_max = rect.x_max.min(self.width);
+                    for y in rect.y_min..rect.y_max.min(self.height) {
+                        let row = (y * self.width) as usize;
+                        let start = row + rect.x_min.min(x_max) as usize;
+                        self.pixels[start..row + x_max as usize].fill(color);
+                    }
+                }
+            }
+            None => self.pixels.fill(color),
+        }
+    }
+
+    fn capture(&self) -> Bitmap {
+        let mut rgba: Vec<u8> = self.pixels.iter().flatten().copied().collect();
+        render::unmultiply_alpha_rgba(&mut rgba);
+        Bitmap {
+            width: self.width,
+            height: self.height,
+            data: BitmapFormat::Rgba(rgba),
+        }
+    }
+
+    fn fill_triangle(&mut self, points: [(f32, f32); 3]) {
+        if let Some(damage) = &self.damage {
+            // Skip triangles that are entirely outside of the frame.
+            let x_min = points.iter().fold(f32::INFINITY, |x, p| x.min(p.0));
+            let x_max = points.iter().fold(f32::NEG_INFINITY, |x, p| x.max(p.0));
+            let y_min = points.iter().fold(f32::INFINITY, |y, p| y.min(p.1));
+            let y_max = points.iter().fold(f32::NEG_INFINITY, |y, p| y.max(p.1));
+            let is_outside = |rect: &ViewportRect| {
+                x_max < rect.x_min as f32
+                    || x_min > rect.x_max as f32
+                    || y_max < rect.y_min as f32
+                    || y_min > rect.y_max as f32
+            };
+            if damage.iter().all(is_outside) {
+                return;
+            }
+        }
+        self.coverage.fill_triangle(points);
+    }
+
+    /// Covers the rectangle from the origin to `(width, height)`,
+    /// transformed by `matrix`.
+    fn fill_quad(&mut self, matrix: Affine, width: f32, height: f32) {
+        let corners = [(0.0, 0.0), (width, 0.0), (width, height), (0.0, height)]
+            .map(|(x, y)| matrix.apply(x, y));
+        self.fill_triangle([corners[0], corners[1], corners[2]]);
+        self.fill_triangle([corners[0], corners[2], corners[3]]);
+    }
+
+    /// Paints the covered pixels, and clears the coverage.
+    fn paint(&mut self, paint: &Paint) {
+        let width = self.width;
+        let pixels = &mut self.pixels;
+        match self.mask_state {
+            MaskState::ClearMask => self.coverage.drain(|_, _, _| ()),
+            MaskState::DrawMask => {
+                // Masks only use the shape of what is drawn to them.
+                if let Some(mask) = self.masks.last_mut() {
+                    self.coverage.drain(|x, y, coverage| {
+                        let value = &mut mask[(y * width + x) as usize];
+                        *value = union(*value, coverage);
+                    });
+                } else {
+                    self.coverage.drain(|_, _, _| ());
+                }
+            }
+            MaskState::NoMask | MaskState::DrawMaskedContent => {
+                let mask = match self.mask_state {
+                    MaskState::DrawMaskedContent => self.masks.last(),
+                    _ => None,
+                };
+                let damage = &self.damage;
+                self.coverage.drain(|x, y, coverage| {
+                    if !Self::is_damaged(damage, x, y) {
+                        return;
+                    }
+                    let i = (y * width + x) as usize;
+                    let coverage = match mask {
+                        Some(mask) => multiply(coverage, mask[i]),
+                        None => coverage,
+                    };
+                    if coverage == 0 {
+                        return;
+                    }
+                    let color = paint.color_at(x as f32 + 0.5, y as f32 + 0.5);
+                    pixels[i] = blend_normal(pixels[i], color.map(|c| multiply(c, coverage)));
+                });
+            }
+        }
+    }
+
+    fn push_mask(&mut self) {
+        self.masks
+            .push(vec![0; self.width as usize * self.height as usize]);
+        self.mask_state = MaskState::DrawMask;
+    }
+
+    fn activate_mask(&mut self) {
+        let len = self.masks.len();
+        if len >= 2 {
+            let (below, top) = self.masks.split_at_mut(len - 1);
+            for (value, below) in top[0].iter_mut().zip(&below[len - 2]) {
+                *value = multiply(*value, *below);
+            }
+        }
+        self.mask_state = MaskState::DrawMaskedContent;
+    }
+
+    fn pop_mask(&mut self) {
+        self.masks.pop();
+        self.mask_state = if self.masks.is_empty() {
+            MaskState::NoMask
+        } else {
+            MaskState::DrawMaskedContent
+        };
+    }
+
+    fn push_layer(&mut self, blend_mode: swf::BlendMode) {
+        let layer = vec![[0; 4]; self.pixels.len()];
+        self.layers.push(BlendLayer {
+            blend_mode,
+            pixels: std::mem::replace(&mut self.pixels, layer),
+            masks: std::mem::take(&mut self.masks),
+            mask_state: self.mask_state,
+        });
+        self.mask_state = MaskState::NoMask;
+    }
+
+    fn pop_layer(&mut self) {
+        let layer = match self.layers.pop() {
+            Some(layer) => layer,
+            None => {
+                log::error!("Blend layer stack underflow");
+                return;
+            }
+        };
+        let contents = std::mem::replace(&mut self.pixels, layer.pixels);
+        self.masks = layer.masks;
+        self.mask_state = layer.mask_state;
+
+        let mask = match self.mask_state {
+            MaskState::DrawMaskedContent => self.masks.last(),
+            MaskState::ClearMask => return,
+            _ => None,
+        };
+        let width = self.width as usize;
+        for (i, (dest, source)) in self.pixels.iter_mut().zip(contents).enumerate() {
+            if !Self::is_damaged(&self.damage, (i % width) as u32, (i / width) as u32) {
+                continue;
+            }
+            let source = match mask {
+                Some(mask) => source.map(|c| multiply(c, mask[i])),
+                None => source,
+            };
+            if source[3] == 0 && layer.blend_mode != swf::BlendMode::Alpha {
+                continue;
+            }
+            *dest = blend(*dest, source, layer.blend_mode);
+        }
+    }
+}
+
+/// Converts a decoded bitmap to a texture in pre-multiplied RGBA.
+fn bitmap_to_texture(bitmap: Bitmap) -> Texture {
+    let pixels = match bitmap.data {
+        BitmapFormat::Rgb(rgb) => rgb
+            .chunks_exact(3)
+            .map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        BitmapFormat::Rgba(rgba) => rgba
+            .chunks_exact(4)
+            .map(|p| [p[0], p[1], p[2], p[3]])
+            .collect(),
+    };
+    Texture {
+        width: bitmap.width,
+        height: bitmap.height,
+        pixels,
+    }
+}
+
+/// Composites a pre-multiplied color onto another with the given blend mode.
+fn blend(dest: [u8; 4], source: [u8; 4], blend_mode: swf::BlendMode) -> [u8; 4] {
+    match blend_mode {
+        swf::BlendMode::Normal | swf::BlendMode::Layer => blend_normal(dest, source),
+        _ => {
+            let [r, g, b, a] = source;
+            let source = bitmap_data::Color::argb(a, r, g, b);
+            let [r, g, b, a] = dest;
+            let dest = bitmap_data::Color::argb(a, r, g, b);
+            let color = bitmap_data::blend(dest, source, blend_mode);
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        }
+    }
+}
+
+/// Composites a pre-multiplied color over another.
+fn blend_normal(dest: [u8; 4], source: [u8; 4]) -> [u8; 4] {
+    let inverse_alpha = 255 - source[3];
+    [
+        source[0].saturating_add(multiply(dest[0], inverse_alpha)),
+        source[1].saturating_add(multiply(dest[1], inverse_alpha)),
+        source[2].saturating_add(multiply(dest[2], inverse_alpha)),
+        source[3].saturating_add(multiply(dest[3], inverse_alpha)),
+    ]
+}
+
+/// Multiplies two values, where 255 is 1.
+fn multiply(a: u8, b: u8) -> u8 {
+    ((u16::from(a) * u16::from(b) + 127) / 255) as u8
+}
+
+/// The coverage of the union of two areas with the given coverage.
+fn union(a: u8, b: u8) -> u8 {
+    a.saturating_add(multiply(b, 255 - a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pixel(backend: &SoftwareRenderBackend, x: u32, y: u32) -> [u8; 4] {
+        let frame = backend.capture_frame();
+        let i = ((y * frame.width + x) * 4) as usize;
+        match frame.data {
+            BitmapFormat::Rgba(rgba) => [rgba[i], rgba[i + 1], rgba[i + 2], rgba[i + 3]],
+            BitmapFormat::Rgb(_) => unreachable!(),
+        }
+    }
+
+    fn rect(x: f64, y: f64, width: f64, height: f64) -> Matrix {
+        Matrix {
+            a: width as f32,
+            d: height as f32,
+            tx: swf::Twips::from_pixels(x),
+            ty: swf::Twips::from_pixels(y),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn draw_rect() {
+        let mut backend = SoftwareRenderBackend::new(4, 4);
+        backend.begin_frame(Color::from_rgb(0xffffff, 255));
+        backend.draw_rect(Color::from_rgb(0xff0000, 255), &rect(1.0, 1.0, 2.0, 2.0));
+        backend.end_frame();
+
+        assert_eq!(pixel(&backend, 0, 0), [255, 255, 255, 255]);
+        assert_eq!(pixel(&backend, 1, 1), [255, 0, 0, 255]);
+        assert_eq!(pixel(&backend, 2, 2), [255, 0, 0, 255]);
+        assert_eq!(pixel(&backend, 3, 3), [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn masked_rect() {
+        let mut backend = SoftwareRenderBackend::new(4, 4);
+        backend.begin_frame(Color::from_rgb(0xffffff, 255));
+        backend.push_mask();
+        backend.draw_rect(Color::from_rgb(0, 255), &rect(0.0, 0.0, 2.0, 4.0));
+        backend.activate_mask();
+        backend.draw_rect(Color::from_rgb(0x0000ff, 255), &rect(0.0, 0.0, 4.0, 4.0));
+        backend.deactivate_mask();
+        backend.draw_rect(Color::from_rgb(0, 255), &rect(0.0, 0.0, 2.0, 4.0));
+        backend.pop_mask();
+        backend.end_frame();
+
+        assert_eq!(pixel(&backend, 1, 1), [0, 0, 255, 255]);
+        assert_eq!(pixel(&backend, 2, 1), [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn blend_layer() {
+        let mut backend = SoftwareRenderBackend::new(2, 2);
+        backend.begin_frame(Color::from_rgb(0x808080, 255));
+        backend.push_blend_mode(swf::BlendMode::Multiply);
+        backend.draw_rect(Color::from_rgb(0x808080, 255), &rect(0.0, 0.0, 1.0, 2.0));
+        backend.pop_blend_mode();
+        backend.end_frame();
+
+        assert_eq!(pixel(&backend, 0, 0), [64, 64, 64, 255]);
+        assert_eq!(pixel(&backend, 1, 0), [128, 128, 128, 255]);
+    }
+
+    #[test]
+    fn partial_frame() {
+        let mut backend = SoftwareRenderBackend::new(4, 4);
+        backend.begin_frame(Color::from_rgb(0xffffff, 255));
+        backend.draw_rect(Color::from_rgb(0xff0000, 255), &rect(0.0, 0.0, 4.0, 4.0));
+        backend.end_frame();
+
+        let damage = ViewportRect {
+            x_min: 1,
+            y_min: 1,
+            x_max: 3,
+            y_max: 2,
+        };
+        backend.begin_partial_frame(Color::from_rgb(0xffffff, 255), &[damage]);
+        backend.draw_rect(Color::from_rgb(0x0000ff, 255), &rect(2.0, 0.0, 2.0, 4.0));
+        backend.end_frame();
+
+        assert_eq!(pixel(&backend, 0, 1), [255, 0, 0, 255]);
+        assert_eq!(pixel(&backend, 1, 1), [255, 255, 255, 255]);
+        assert_eq!(pixel(&backend, 2, 1), [0, 0, 255, 255]);
+        assert_eq!(pixel(&backend, 3, 1), [255, 0, 0, 255]);
+        assert_eq!(pixel(&backend, 2, 2), [255, 0, 0, 255]);
+    }
+}
@@ -0,0 +1,361 @@
+//! The colors of solid, gradient and bitmap fills at each pixel.
+
+use ruffle_core::color_transform::ColorTransform;
+use ruffle_core::matrix::Matrix;
+use ruffle_core::swf;
+use ruffle_render_common_tess::{Gradient, GradientType};
+
+/// An affine transform of points in pixels.
+#[derive(Clone, Copy, Debug)]
+pub struct Affine {
+    a: f32,
+    b: f32,
+    c: f32,
+    d: f32,
+    tx: f32,
+    ty: f32,
+}
+
+impl Affine {
+    pub fn scale(x: f32, y: f32) -> Self {
+        Self {
+            a: x,
+            b: 0.0,
+            c: 0.0,
+            d: y,
+            tx: 0.0,
+            ty: 0.0,
+        }
+    }
+
+    /// Converts the column-major matrices that the tessellator produces
+    /// for gradients and bitmaps.
+    pub fn from_columns(m: &[[f32; 3]; 3]) -> Self {
+        Self {
+            a: m[0][0],
+            b: m[0][1],
+            c: m[1][0],
+            d: m[1][1],
+            tx: m[2][0],
+            ty: m[2][1],
+        }
+    }
+
+    pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            self.a * x + self.c * y + self.tx,
+            self.b * x + self.d * y + self.ty,
+        )
+    }
+
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.a * self.d - self.b * self.c;
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        Some(Self {
+            a: self.d / det,
+            b: -self.b / det,
+            c: -self.c / det,
+            d: self.a / det,
+            tx: (self.c * self.ty - self.d * self.tx) / det,
+            ty: (self.b * self.tx - self.a * self.ty) / det,
+        })
+    }
+}
+
+impl From<&Matrix> for Affine {
+    fn from(matrix: &Matrix) -> Self {
+        Self {
+            a: matrix.a,
+            b: matrix.b,
+            c: matrix.c,
+            d: matrix.d,
+            tx: matrix.tx.to_pixels() as f32,
+            ty: matrix.ty.to_pixels() as f32,
+        }
+    }
+}
+
+impl std::ops::Mul for Affine {
+    type Output = Self;
+
+    /// Combines two transforms, applying `rhs` first.
+    fn mul(self, rhs: Self) -> Self {
+        Self {
+            a: self.a * rhs.a + self.c * rhs.b,
+            b: self.b * rhs.a + self.d * rhs.b,
+            c: self.a * rhs.c + self.c * rhs.d,
+            d: self.b * rhs.c + self.d * rhs.d,
+            tx: self.a * rhs.tx + self.c * rhs.ty + self.tx,
+            ty: self.b * rhs.tx + self.d * rhs.ty + self.ty,
+        }
+    }
+}
+
+/// An image in pre-multiplied RGBA.
+pub struct Texture {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<[u8; 4]>,
+}
+
+impl Texture {
+    fn texel(&self, x: i32, y: i32, repeating: bool) -> [f32; 4] {
+        let (width, height) = (self.width as i32, self.height as i32);
+        let (x, y) = if repeating {
+            (x.rem_euclid(width), y.rem_euclid(height))
+        } else {
+            (x.clamp(0, width - 1), y.clamp(0, height - 1))
+        };
+        let texel = self.pixels[(y * width + x) as usize];
+        texel.map(|channel| f32::from(channel) / 255.0)
+    }
+
+    /// Samples the texture at the given texture coordinates, which are 0 to
+    /// 1 from edge to edge of the image.
+    fn sample(&self, u: f32, v: f32, repeating: bool, smoothed: bool) -> [f32; 4] {
+        if self.width == 0 || self.height == 0 {
+            return [0.0; 4];
+        }
+        let x = u * self.width as f32;
+        let y = v * self.height as f32;
+        if !smoothed {
+            return self.texel(x.floor() as i32, y.floor() as i32, repeating);
+        }
+
+        // Bilinear filtering between the centers of the nearest texels.
+        let (x, y) = (x - 0.5, y - 0.5);
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i32, y0 as i32);
+        let top = lerp(
+            self.texel(x0, y0, repeating),
+            self.texel(x0 + 1, y0, repeating),
+            fx,
+        );
+        let bottom = lerp(
+            self.texel(x0, y0 + 1, repeating),
+            self.texel(x0 + 1, y0 + 1, repeating),
+            fx,
+        );
+        lerp(top, bottom, fy)
+    }
+}
+
+/// How the pixels of a shape are colored.
+pub enum Paint<'a> {
+    /// A solid color in pre-multiplied RGBA.
+    Color([u8; 4]),
+
+    Gradient {
+        gradient: &'a Gradient,
+
+        /// Transforms pixels to gradient coordinates.
+        to_uv: Affine,
+        color_transform: &'a ColorTransform,
+    },
+
+    Bitmap {
+        texture: &'a Texture,
+
+        /// Transforms pixels to texture coordinates.
+        to_uv: Affine,
+        color_transform: &'a ColorTransform,
+        repeating: bool,
+        smoothed: bool,
+    },
+}
+
+impl Paint<'_> {
+    /// The color of the paint at the given point in pixels, in
+    /// pre-multiplied RGBA.
+    pub fn color_at(&self, x: f32, y: f32) -> [u8; 4] {
+        match self {
+            Paint::Color(color) => *color,
+            Paint::Gradient {
+                gradient,
+                to_uv,
+                color_transform,
+            } => {
+                let (u, v) = to_uv.apply(x, y);
+                transform_color(gradient_color(gradient, u, v), color_transform)
+            }
+            Paint::Bitmap {
+                texture,
+                to_uv,
+                color_transform,
+                repeating,
+                smoothed,
+            } => {
+                let (u, v) = to_uv.apply(x, y);
+                let color = texture.sample(u, v, *repeating, *smoothed);
+                if color_transform.is_identity() {
+                    return color.map(to_u8);
+                }
+                // The texture is pre-multiplied, but the color transform
+                // applies to the un-multiplied color.
+                if color[3] <= 0.0 {
+                    return [0; 4];
+                }
+                let alpha = color[3];
+                transform_color(
+                    [color[0] / alpha, color[1] / alpha, color[2] / alpha, alpha],
+                    color_transform,
+                )
+            }
+        }
+    }
+}
+
+/// Applies a color transform to an un-multiplied color, returning it
+/// pre-multiplied.
+pub fn transform_color(color: [f32; 4], color_transform: &ColorTransform) -> [u8; 4] {
+    let mult = color_transform.mult_rgba_normalized();
+    let add = color_transform.add_rgba_normalized();
+    let channel = |i: usize| (color[i] * mult[i] + add[i]).clamp(0.0, 1.0);
+    let alpha = channel(3);
+    [
+        to_u8(channel(0) * alpha),
+        to_u8(channel(1) * alpha),
+        to_u8(channel(2) * alpha),
+        to_u8(alpha),
+    ]
+}
+
+/// The un-multiplied color of a gradient at the given gradient coordinates.
+fn gradient_color(gradient: &Gradient, u: f32, v: f32) -> [f32; 4] {
+    let num_colors = gradient.num_colors;
+    if num_colors == 0 {
+        return [0.0; 4];
+    }
+    let ratios = &gradient.ratios[..num_colors];
+    let colors = &gradient.colors[..num_colors];
+
+    // The position in the gradient, where the bounds of the ratios are 0 to 1.
+    let t = match gradient.gradient_type {
+        GradientType::Linear => u,
+        GradientType::Radial => (u * 2.0 - 1.0).hypot(v * 2.0 - 1.0),
+        GradientType::Focal => {
+            let focal_point = gradient.focal_point;
+            let (x, y) = (focal_point - (u * 2.0 - 1.0), -(v * 2.0 - 1.0));
+            let length = x.hypot(y);
+            let (dx, dy) = (x / length, y / length);
+            length / ((1.0 - focal_point * focal_point * dy * dy).sqrt() + focal_point * dx)
+        }
+    };
+    let t = match gradient.repeat_mode {
+        swf::GradientSpread::Pad => t.clamp(0.0, 1.0),
+        swf::GradientSpread::Repeat => t.rem_euclid(1.0),
+        swf::GradientSpread::Reflect => {
+            let t = t.abs();
+            if t as i32 % 2 == 0 {
+                t.fract()
+            } else {
+                1.0 - t.fract()
+            }
+        }
+    };
+    let t = if t.is_nan() { 0.0 } else { t };
+
+    // Interpolate between the two colors around the position. Positions
+    // outside of the ratios are the first or last color, which also keeps a
+    // hard stop between colors with the same ratio at either end.
+    let last = num_colors - 1;
+    let color = if t <= ratios[0] {
+        colors[0]
+    } else if t >= ratios[last] {
+        colors[last]
+    } else {
+        let j = ratios.iter().position(|&ratio| t < ratio).unwrap_or(last);
+        let i = j - 1;
+        lerp(
+            colors[i],
+            colors[j],
+            (t - ratios[i]) / (ratios[j] - ratios[i]),
+        )
+    };
+
+    if gradient.interpolation == swf::GradientInterpolation::LinearRgb {
+        linear_to_srgb(color)
+    } else {
+        color
+    }
+}
+
+/// Converts an RGBA color from linear color space to sRGB space.
+fn linear_to_srgb(color: [f32; 4]) -> [f32; 4] {
+    fn to_srgb_channel(n: f32) -> f32 {
+        if n <= 0.0031308 {
+            n * 12.92
+        } else {
+            1.055 * n.powf(1.0 / 2.4) - 0.055
+        }
+    }
+    [
+        to_srgb_channel(color[0]),
+        to_srgb_channel(color[1]),
+        to_srgb_channel(color[2]),
+        color[3],
+    ]
+}
+
+fn lerp(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    [
+        a[0] + (b[0] - a[0]) * t,
+        a[1] + (b[1] - a[1]) * t,
+        a[2] + (b[2] - a[2]) * t,
+        a[3] + (b[3] - a[3]) * t,
+    ]
+}
+
+fn to_u8(value: f32) -> u8 {
+    (value * 255.0).round().clamp(0.0, 255.0) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient(repeat_mode: swf::GradientSpread, ratios: Vec<f32>) -> Gradient {
+        let colors = vec![
+            [0.0, 0.0, 0.0, 1.0],
+            [1.0, 1.0, 1.0, 1.0],
+            [1.0, 0.0, 0.0, 1.0],
+        ];
+        Gradient {
+            matrix: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+            gradient_type: GradientType::Linear,
+            num_colors: ratios.len(),
+            colors: colors[..ratios.len()].to_vec(),
+            ratios,
+            repeat_mode,
+            focal_point: 0.0,
+            interpolation: swf::GradientInterpolation::Rgb,
+        }
+    }
+
+    #[test]
+    fn spread_modes() {
+        let pad = gradient(swf::GradientSpread::Pad, vec![0.0, 1.0]);
+        assert_eq!(gradient_color(&pad, 1.25, 0.0), [1.0, 1.0, 1.0, 1.0]);
+        assert_eq!(gradient_color(&pad, -0.5, 0.0), [0.0, 0.0, 0.0, 1.0]);
+
+        let repeat = gradient(swf::GradientSpread::Repeat, vec![0.0, 1.0]);
+        assert_eq!(gradient_color(&repeat, 1.25, 0.0), [0.25, 0.25, 0.25, 1.0]);
+
+        let reflect = gradient(swf::GradientSpread::Reflect, vec![0.0, 1.0]);
+        assert_eq!(gradient_color(&reflect, 1.25, 0.0), [0.75, 0.75, 0.75, 1.0]);
+        assert_eq!(
+            gradient_color(&reflect, -0.25, 0.0),
+            [0.25, 0.25, 0.25, 1.0]
+        );
+    }
+
+    #[test]
+    fn hard_stop() {
+        let gradient = gradient(swf::GradientSpread::Pad, vec![0.0, 0.5, 0.5]);
+        assert_eq!(gradient_color(&gradient, 0.25, 0.0), [0.5, 0.5, 0.5, 1.0]);
+        assert_eq!(gradient_color(&gradient, 0.75, 0.0), [1.0, 0.0, 0.0, 1.0]);
+    }
+}
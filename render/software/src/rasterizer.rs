@@ -0,0 +1,189 @@
+//! Scanline rasterization of triangles into anti-aliased coverage masks.
+
+/// The largest number of samples along each axis of a pixel.
+pub const MAX_SAMPLES: u32 = 4;
+
+/// The area of a render target covered by a set of triangles.
+///
+/// Every pixel has up to 4x4 samples, each of which is either covered or not.
+/// A sample covered by several triangles is only counted once, so the
+/// triangles of a tessellated shape can be rasterized one after another
+/// without seams or overlaps where they meet.
+pub struct Coverage {
+    width: u32,
+    height: u32,
+
+    /// The number of samples along each axis of a pixel.
+    samples_per_axis: u32,
+    samples: Vec<u16>,
+
+    /// The pixels that may have covered samples, as
+    /// `(x_min, y_min, x_max, y_max)` with exclusive maximums.
+    bounds: Option<(u32, u32, u32, u32)>,
+}
+
+impl Coverage {
+    /// Creates the coverage of a target with the given number of samples
+    /// along each axis of a pixel, which is clamped to `MAX_SAMPLES`.
+    pub fn new(width: u32, height: u32, samples_per_axis: u32) -> Self {
+        Self {
+            width,
+            height,
+            samples_per_axis: samples_per_axis.clamp(1, MAX_SAMPLES),
+            samples: vec![0; width as usize * height as usize],
+            bounds: None,
+        }
+    }
+
+    /// Marks the samples inside of the given triangle as covered.
+    ///
+    /// The corners of the triangle are in pixels. Samples on an edge of the
+    /// triangle are inside of it.
+    pub fn fill_triangle(&mut self, points: [(f32, f32); 3]) {
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+        let samples_per_axis = self.samples_per_axis;
+        let samples_wide = (self.width * samples_per_axis) as f32;
+        let samples_high = (self.height * samples_per_axis) as f32;
+        let scale = samples_per_axis as f32;
+
+        let y_min = points.iter().fold(f32::INFINITY, |y, p| y.min(p.1));
+        let y_max = points.iter().fold(f32::NEG_INFINITY, |y, p| y.max(p.1));
+        if !(y_min.is_finite() && y_max.is_finite()) {
+            return;
+        }
+        // The sample rows whose centers are between the top and bottom of the triangle.
+        let row_start = (y_min * scale - 0.5).ceil().max(0.0);
+        let row_end = (y_max * scale - 0.5).floor().min(samples_high - 1.0);
+        if row_start > row_end {
+            return;
+        }
+
+        let edges = [
+            (points[0], points[1]),
+            (points[1], points[2]),
+            (points[2], points[0]),
+        ];
+        let (mut x_min_pixel, mut x_max_pixel) = (u32::MAX, 0);
+        for row in row_start as u32..=row_end as u32 {
+            let y = (row as f32 + 0.5) / scale;
+
+            // Find where the row crosses the edges of the triangle.
+            let mut left = f32::INFINITY;
+            let mut right = f32::NEG_INFINITY;
+            for &((x0, y0), (x1, y1)) in &edges {
+                if y0 == y1 || y < y0.min(y1) || y > y0.max(y1) {
+                    continue;
+                }
+                let x = x0 + (y - y0) * (x1 - x0) / (y1 - y0);
+                left = left.min(x);
+                right = right.max(x);
+            }
+            if left > right {
+                continue;
+            }
+
+            let column_start = (left * scale - 0.5).ceil().max(0.0);
+            let column_end = (right * scale - 0.5).floor().min(samples_wide - 1.0);
+            if column_start > column_end {
+                continue;
+            }
+            let (column_start, column_end) = (column_start as u32, column_end as u32);
+
+            let row_offset = (row / samples_per_axis * self.width) as usize;
+            let row_bits = (row % samples_per_axis) * samples_per_axis;
+            for column in column_start..=column_end {
+                let bit = row_bits + column % samples_per_axis;
+                self.samples[row_offset + (column / samples_per_axis) as usize] |= 1 << bit;
+            }
+            x_min_pixel = x_min_pixel.min(column_start / samples_per_axis);
+            x_max_pixel = x_max_pixel.max(column_end / samples_per_axis + 1);
+        }
+
+        if x_min_pixel < x_max_pixel {
+            let y_min_pixel = row_start as u32 / samples_per_axis;
+            let y_max_pixel = row_end as u32 / samples_per_axis + 1;
+            self.bounds = Some(match self.bounds {
+                Some((x_min, y_min, x_max, y_max)) => (
+                    x_min.min(x_min_pixel),
+                    y_min.min(y_min_pixel),
+                    x_max.max(x_max_pixel),
+                    y_max.max(y_max_pixel),
+                ),
+                None => (x_min_pixel, y_min_pixel, x_max_pixel, y_max_pixel),
+            });
+        }
+    }
+
+    /// Calls `f` with the position and coverage of every pixel with any
+    /// covered samples, and then clears all samples.
+    ///
+    /// The coverage ranges from 0 for no samples to 255 for all samples.
+    pub fn drain(&mut self, mut f: impl FnMut(u32, u32, u8)) {
+        let (x_min, y_min, x_max, y_max) = match self.bounds.take() {
+            Some(bounds) => bounds,
+            None => return,
+        };
+        let total = self.samples_per_axis * self.samples_per_axis;
+        for y in y_min..y_max {
+            let row_offset = (y * self.width) as usize;
+            for x in x_min..x_max {
+                let samples = &mut self.samples[row_offset + x as usize];
+                if *samples != 0 {
+                    let covered = samples.count_ones();
+                    *samples = 0;
+                    f(x, y, ((covered * 255 + total / 2) / total) as u8);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(coverage: &mut Coverage) -> Vec<(u32, u32, u8)> {
+        let mut pixels = vec![];
+        coverage.drain(|x, y, c| pixels.push((x, y, c)));
+        pixels
+    }
+
+    #[test]
+    fn pixel_aligned_rect() {
+        let mut coverage = Coverage::new(4, 4, MAX_SAMPLES);
+        coverage.fill_triangle([(1.0, 1.0), (3.0, 1.0), (3.0, 2.0)]);
+        coverage.fill_triangle([(1.0, 1.0), (3.0, 2.0), (1.0, 2.0)]);
+        assert_eq!(collect(&mut coverage), vec![(1, 1, 255), (2, 1, 255)]);
+        assert_eq!(collect(&mut coverage), vec![]);
+    }
+
+    #[test]
+    fn half_covered_pixel() {
+        let mut coverage = Coverage::new(2, 2, MAX_SAMPLES);
+        coverage.fill_triangle([(0.0, 0.0), (0.5, 0.0), (0.5, 1.0)]);
+        coverage.fill_triangle([(0.0, 0.0), (0.5, 1.0), (0.0, 1.0)]);
+        assert_eq!(collect(&mut coverage), vec![(0, 0, 128)]);
+    }
+
+    #[test]
+    fn clipped_to_target() {
+        let mut coverage = Coverage::new(2, 2, MAX_SAMPLES);
+        coverage.fill_triangle([(-10.0, -10.0), (13.0, -10.0), (-10.0, 13.0)]);
+        assert_eq!(
+            collect(&mut coverage),
+            vec![(0, 0, 255), (1, 0, 255), (0, 1, 255), (1, 1, 159)]
+        );
+    }
+
+    #[test]
+    fn single_sample() {
+        let mut coverage = Coverage::new(2, 2, 1);
+        coverage.fill_triangle([(0.0, 0.0), (2.0, 0.0), (0.0, 2.0)]);
+        assert_eq!(
+            collect(&mut coverage),
+            vec![(0, 0, 255), (1, 0, 255), (0, 1, 255)]
+        );
+    }
+}
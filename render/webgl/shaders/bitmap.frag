@@ -13,11 +13,14 @@ uniform vec4 add_color;
 uniform mat3 u_matrix;
 
 uniform sampler2D u_texture;
+uniform int u_repeat;
 
 varying vec2 frag_uv;
 
 void main() {
-    vec4 color = texture2D(u_texture, frag_uv);
+    // WebGL1 can't repeat non-power-of-2 textures, so repeating is done here instead.
+    vec2 uv = u_repeat != 0 ? fract(frag_uv) : frag_uv;
+    vec4 color = texture2D(u_texture, uv);
 
     // Unmultiply alpha before apply color transform.
     if( color.a > 0.0 ) {
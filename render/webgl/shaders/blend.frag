@@ -0,0 +1,85 @@
+#version 100
+
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+    precision highp float;
+#else
+    precision mediump float;
+#endif
+
+uniform mat4 view_matrix;
+uniform mat4 world_matrix;
+uniform vec4 mult_color;
+uniform vec4 add_color;
+uniform mat3 u_matrix;
+
+// The layer that the blended clip was rendered to.
+uniform sampler2D u_texture;
+// Everything that was rendered below the blended clip.
+uniform sampler2D u_backdrop;
+// The SWF value of the blend mode.
+uniform int u_blend_mode;
+
+varying vec2 frag_uv;
+
+vec3 hard_light(vec3 top, vec3 bottom) {
+    return mix(2.0 * top * bottom, 1.0 - 2.0 * (1.0 - top) * (1.0 - bottom), step(0.5, top));
+}
+
+void main() {
+    // Both textures have premultiplied alpha.
+    vec4 src = texture2D(u_texture, frag_uv);
+    vec4 dst = texture2D(u_backdrop, frag_uv);
+
+    if (u_blend_mode == 11) {
+        // Alpha
+        gl_FragColor = dst * src.a;
+        return;
+    } else if (u_blend_mode == 12) {
+        // Erase
+        gl_FragColor = dst * (1.0 - src.a);
+        return;
+    }
+
+    vec3 s = src.a > 0.0 ? src.rgb / src.a : vec3(0.0);
+    vec3 d = dst.a > 0.0 ? dst.rgb / dst.a : vec3(0.0);
+    vec3 mixed;
+    if (u_blend_mode == 3) {
+        // Multiply
+        mixed = s * d;
+    } else if (u_blend_mode == 4) {
+        // Screen
+        mixed = s + d - s * d;
+    } else if (u_blend_mode == 5) {
+        // Lighten
+        mixed = max(s, d);
+    } else if (u_blend_mode == 6) {
+        // Darken
+        mixed = min(s, d);
+    } else if (u_blend_mode == 7) {
+        // Difference
+        mixed = abs(s - d);
+    } else if (u_blend_mode == 8) {
+        // Add
+        mixed = min(s + d, 1.0);
+    } else if (u_blend_mode == 9) {
+        // Subtract
+        mixed = max(d - s, 0.0);
+    } else if (u_blend_mode == 10) {
+        // Invert
+        mixed = 1.0 - d;
+    } else if (u_blend_mode == 13) {
+        // Overlay
+        mixed = hard_light(d, s);
+    } else if (u_blend_mode == 14) {
+        // Hard light
+        mixed = hard_light(s, d);
+    } else {
+        // Normal and layer
+        mixed = s;
+    }
+
+    gl_FragColor = vec4(
+        src.rgb * (1.0 - dst.a) + dst.rgb * (1.0 - src.a) + src.a * dst.a * mixed,
+        src.a + dst.a * (1.0 - src.a)
+    );
+}
@@ -0,0 +1,34 @@
+#version 100
+
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+    precision highp float;
+#else
+    precision mediump float;
+#endif
+
+// The pre-multiplied image being blurred.
+uniform sampler2D u_texture;
+// One pixel in the direction of this pass, in texture coordinates.
+uniform vec2 u_blur_step;
+// The number of pixels averaged before each pixel.
+uniform int u_blur_left;
+// The number of pixels averaged, at most 255.
+uniform int u_blur_size;
+
+varying vec2 frag_uv;
+
+void main() {
+    vec4 sum = vec4(0.0);
+    // Loops need a constant bound in GLSL ES 1.0.
+    for (int i = 0; i < 255; i++) {
+        if (i >= u_blur_size) {
+            break;
+        }
+        vec2 uv = frag_uv + u_blur_step * float(i - u_blur_left);
+        // Pixels outside of the image are transparent.
+        if (all(greaterThanEqual(uv, vec2(0.0))) && all(lessThanEqual(uv, vec2(1.0)))) {
+            sum += texture2D(u_texture, uv);
+        }
+    }
+    gl_FragColor = sum / float(u_blur_size);
+}
@@ -0,0 +1,25 @@
+#version 100
+
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+    precision highp float;
+#else
+    precision mediump float;
+#endif
+
+// The pre-multiplied image being filtered.
+uniform sampler2D u_texture;
+// The 4x4 part of the color matrix, applied to un-multiplied colors.
+uniform mat4 u_color_matrix;
+// The offsets in the last column of the color matrix, in 0.0 to 1.0.
+uniform vec4 u_color_offset;
+
+varying vec2 frag_uv;
+
+void main() {
+    vec4 color = texture2D(u_texture, frag_uv);
+    if (color.a > 0.0) {
+        color = vec4(color.rgb / color.a, color.a);
+    }
+    color = clamp(u_color_matrix * color + u_color_offset, 0.0, 1.0);
+    gl_FragColor = vec4(color.rgb * color.a, color.a);
+}
@@ -45,8 +45,13 @@ void main() {
         vec2 uv = frag_uv * 2.0 - 1.0;
         vec2 d = vec2(u_focal_point, 0.0) - uv;
         float l = length(d);
-        d /= l;
-        t = l / (sqrt(1.0 -  u_focal_point*u_focal_point*d.y*d.y) + u_focal_point*d.x);
+        if( l > 0.0 ) {
+            d /= l;
+            t = l / (sqrt(1.0 -  u_focal_point*u_focal_point*d.y*d.y) + u_focal_point*d.x);
+        } else {
+            // The focal point itself.
+            t = 0.0;
+        }
     }
     if( u_repeat_mode == 0 )
     {
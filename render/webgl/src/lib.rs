@@ -1,10 +1,12 @@
 use bytemuck::{Pod, Zeroable};
 use ruffle_core::backend::render::{
-    Bitmap, BitmapFormat, BitmapHandle, BitmapInfo, BitmapSource, Color, NullBitmapSource,
-    RenderBackend, ShapeHandle, Transform,
+    check_rgba_len, Bitmap, BitmapFormat, BitmapHandle, BitmapInfo, BitmapSource, Color,
+    NullBitmapSource, RenderBackend, ShapeHandle, Transform,
 };
+use ruffle_core::bitmap::filters::{apply_filters_accelerated, BitmapFilter};
 use ruffle_core::shape_utils::DistilledShape;
 use ruffle_core::swf;
+use ruffle_core::StageQuality;
 use ruffle_render_common_tess::{
     Gradient as TessGradient, GradientType, ShapeTessellator, Vertex as TessVertex,
 };
@@ -24,6 +26,9 @@ const COLOR_FRAGMENT_GLSL: &str = include_str!("../shaders/color.frag");
 const TEXTURE_VERTEX_GLSL: &str = include_str!("../shaders/texture.vert");
 const GRADIENT_FRAGMENT_GLSL: &str = include_str!("../shaders/gradient.frag");
 const BITMAP_FRAGMENT_GLSL: &str = include_str!("../shaders/bitmap.frag");
+const BLEND_FRAGMENT_GLSL: &str = include_str!("../shaders/blend.frag");
+const BLUR_FRAGMENT_GLSL: &str = include_str!("../shaders/blur.frag");
+const COLOR_MATRIX_FRAGMENT_GLSL: &str = include_str!("../shaders/color_matrix.frag");
 const NUM_VERTEX_ATTRIBUTES: u32 = 2;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -69,9 +74,16 @@ pub struct WebGlRenderBackend {
     msaa_buffers: Option<MsaaBuffers>,
     msaa_sample_count: u32,
 
+    /// The most MSAA samples that the device supports, which the stage
+    /// quality may lower `msaa_sample_count` from.
+    max_msaa_sample_count: u32,
+
     color_program: ShaderProgram,
     bitmap_program: ShaderProgram,
     gradient_program: ShaderProgram,
+    blend_program: ShaderProgram,
+    blur_program: ShaderProgram,
+    color_matrix_program: ShaderProgram,
 
     shape_tessellator: ShapeTessellator,
 
@@ -80,11 +92,21 @@ pub struct WebGlRenderBackend {
 
     color_quad_shape: ShapeHandle,
     bitmap_quad_shape: ShapeHandle,
+    blend_quad_shape: ShapeHandle,
+    blur_quad_shape: ShapeHandle,
+    color_matrix_quad_shape: ShapeHandle,
 
     mask_state: MaskState,
     num_masks: u32,
     mask_state_dirty: bool,
 
+    // The layers that blended clips are currently being rendered to, innermost last.
+    blend_layers: Vec<BlendLayer>,
+    // Unused framebuffers for blend layers, kept to be reused.
+    layer_buffers: Vec<LayerBuffers>,
+    // The texture that the backdrop of a blend layer is copied to when it can't be resolved from MSAA.
+    backdrop_texture: Option<WebGlTexture>,
+
     active_program: *const ShaderProgram,
     blend_func: (u32, u32),
     mult_color: Option<[f32; 4]>,
@@ -198,13 +220,28 @@ impl WebGlRenderBackend {
         let bitmap_fragment = Self::compile_shader(&gl, Gl::FRAGMENT_SHADER, BITMAP_FRAGMENT_GLSL)?;
         let gradient_fragment =
             Self::compile_shader(&gl, Gl::FRAGMENT_SHADER, GRADIENT_FRAGMENT_GLSL)?;
+        let blend_fragment = Self::compile_shader(&gl, Gl::FRAGMENT_SHADER, BLEND_FRAGMENT_GLSL)?;
+        let blur_fragment = Self::compile_shader(&gl, Gl::FRAGMENT_SHADER, BLUR_FRAGMENT_GLSL)?;
+        let color_matrix_fragment =
+            Self::compile_shader(&gl, Gl::FRAGMENT_SHADER, COLOR_MATRIX_FRAGMENT_GLSL)?;
 
         let color_program = ShaderProgram::new(&gl, &color_vertex, &color_fragment)?;
         let bitmap_program = ShaderProgram::new(&gl, &texture_vertex, &bitmap_fragment)?;
         let gradient_program = ShaderProgram::new(&gl, &texture_vertex, &gradient_fragment)?;
+        let blend_program = ShaderProgram::new(&gl, &texture_vertex, &blend_fragment)?;
+        let blur_program = ShaderProgram::new(&gl, &texture_vertex, &blur_fragment)?;
+        let color_matrix_program =
+            ShaderProgram::new(&gl, &texture_vertex, &color_matrix_fragment)?;
 
+        // Alpha is always blended as if premultiplied, so that blend layers
+        // end up with premultiplied colors.
         gl.enable(Gl::BLEND);
-        gl.blend_func(Gl::SRC_ALPHA, Gl::ONE_MINUS_SRC_ALPHA);
+        gl.blend_func_separate(
+            Gl::SRC_ALPHA,
+            Gl::ONE_MINUS_SRC_ALPHA,
+            Gl::ONE,
+            Gl::ONE_MINUS_SRC_ALPHA,
+        );
 
         // Necessary to load RGB textures (alignment defaults to 4).
         gl.pixel_storei(Gl::UNPACK_ALIGNMENT, 1);
@@ -216,16 +253,23 @@ impl WebGlRenderBackend {
 
             msaa_buffers: None,
             msaa_sample_count,
+            max_msaa_sample_count: msaa_sample_count,
 
             color_program,
             gradient_program,
             bitmap_program,
+            blend_program,
+            blur_program,
+            color_matrix_program,
 
             shape_tessellator: ShapeTessellator::new(),
 
             meshes: vec![],
             color_quad_shape: ShapeHandle(0),
             bitmap_quad_shape: ShapeHandle(1),
+            blend_quad_shape: ShapeHandle(2),
+            blur_quad_shape: ShapeHandle(3),
+            color_matrix_quad_shape: ShapeHandle(4),
             textures: vec![],
             renderbuffer_width: 1,
             renderbuffer_height: 1,
@@ -237,6 +281,10 @@ impl WebGlRenderBackend {
             num_masks: 0,
             mask_state_dirty: true,
 
+            blend_layers: vec![],
+            layer_buffers: vec![],
+            backdrop_texture: None,
+
             active_program: std::ptr::null(),
             blend_func: (Gl::SRC_ALPHA, Gl::ONE_MINUS_SRC_ALPHA),
             mult_color: None,
@@ -248,6 +296,12 @@ impl WebGlRenderBackend {
         renderer.meshes.push(color_quad_mesh);
         let bitmap_quad_mesh = renderer.build_quad_mesh(&renderer.bitmap_program)?;
         renderer.meshes.push(bitmap_quad_mesh);
+        let blend_quad_mesh = renderer.build_quad_mesh(&renderer.blend_program)?;
+        renderer.meshes.push(blend_quad_mesh);
+        let blur_quad_mesh = renderer.build_quad_mesh(&renderer.blur_program)?;
+        renderer.meshes.push(blur_quad_mesh);
+        let color_matrix_quad_mesh = renderer.build_quad_mesh(&renderer.color_matrix_program)?;
+        renderer.meshes.push(color_matrix_quad_mesh);
         renderer.set_viewport_dimensions(1, 1);
 
         Ok(renderer)
@@ -358,16 +412,8 @@ impl WebGlRenderBackend {
     }
 
     fn build_msaa_buffers(&mut self, width: i32, height: i32) -> Result<(), Error> {
-        if self.gl2.is_none() || self.msaa_sample_count <= 1 {
-            self.gl.bind_framebuffer(Gl::FRAMEBUFFER, None);
-            self.gl.bind_renderbuffer(Gl::RENDERBUFFER, None);
-            return Ok(());
-        }
-
-        let gl = self.gl2.as_ref().unwrap();
-
         // Delete previous buffers, if they exist.
-        if let Some(msaa_buffers) = self.msaa_buffers.take() {
+        if let (Some(gl), Some(msaa_buffers)) = (&self.gl2, self.msaa_buffers.take()) {
             gl.delete_renderbuffer(Some(&msaa_buffers.color_renderbuffer));
             gl.delete_renderbuffer(Some(&msaa_buffers.stencil_renderbuffer));
             gl.delete_framebuffer(Some(&msaa_buffers.render_framebuffer));
@@ -375,6 +421,14 @@ impl WebGlRenderBackend {
             gl.delete_texture(Some(&msaa_buffers.framebuffer_texture));
         }
 
+        if self.gl2.is_none() || self.msaa_sample_count <= 1 {
+            self.gl.bind_framebuffer(Gl::FRAMEBUFFER, None);
+            self.gl.bind_renderbuffer(Gl::RENDERBUFFER, None);
+            return Ok(());
+        }
+
+        let gl = self.gl2.as_ref().unwrap();
+
         // Create frame and render buffers.
         let render_framebuffer = gl
             .create_framebuffer()
@@ -652,6 +706,539 @@ impl WebGlRenderBackend {
         }
     }
 
+    /// Creates a framebuffer of the size of the renderbuffers for a blend layer.
+    fn create_layer_buffers(&self) -> Result<LayerBuffers, Error> {
+        let gl = &self.gl;
+        let (width, height) = (self.renderbuffer_width, self.renderbuffer_height);
+
+        let texture = gl.create_texture().ok_or("Unable to create texture")?;
+        gl.bind_texture(Gl::TEXTURE_2D, Some(&texture));
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MAG_FILTER, Gl::NEAREST as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MIN_FILTER, Gl::NEAREST as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_WRAP_S, Gl::CLAMP_TO_EDGE as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_WRAP_T, Gl::CLAMP_TO_EDGE as i32);
+        gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+            Gl::TEXTURE_2D,
+            0,
+            Gl::RGBA as i32,
+            width,
+            height,
+            0,
+            Gl::RGBA,
+            Gl::UNSIGNED_BYTE,
+            None,
+        )
+        .into_js_result()?;
+        gl.bind_texture(Gl::TEXTURE_2D, None);
+
+        let framebuffer = gl
+            .create_framebuffer()
+            .ok_or("Unable to create framebuffer")?;
+        let stencil_renderbuffer = gl
+            .create_renderbuffer()
+            .ok_or("Unable to create renderbuffer")?;
+
+        let (msaa_color_renderbuffer, resolve_framebuffer) = match &self.gl2 {
+            Some(gl2) if self.msaa_buffers.is_some() => {
+                let color_renderbuffer = gl2
+                    .create_renderbuffer()
+                    .ok_or("Unable to create renderbuffer")?;
+                gl2.bind_renderbuffer(Gl2::RENDERBUFFER, Some(&color_renderbuffer));
+                gl2.renderbuffer_storage_multisample(
+                    Gl2::RENDERBUFFER,
+                    self.msaa_sample_count as i32,
+                    Gl2::RGBA8,
+                    width,
+                    height,
+                );
+                gl2.check_error("renderbuffer_storage_multisample (layer color)")?;
+
+                gl2.bind_renderbuffer(Gl2::RENDERBUFFER, Some(&stencil_renderbuffer));
+                gl2.renderbuffer_storage_multisample(
+                    Gl2::RENDERBUFFER,
+                    self.msaa_sample_count as i32,
+                    Gl2::STENCIL_INDEX8,
+                    width,
+                    height,
+                );
+                gl2.check_error("renderbuffer_storage_multisample (layer stencil)")?;
+
+                gl2.bind_framebuffer(Gl2::FRAMEBUFFER, Some(&framebuffer));
+                gl2.framebuffer_renderbuffer(
+                    Gl2::FRAMEBUFFER,
+                    Gl2::COLOR_ATTACHMENT0,
+                    Gl2::RENDERBUFFER,
+                    Some(&color_renderbuffer),
+                );
+
+                let resolve_framebuffer = gl2
+                    .create_framebuffer()
+                    .ok_or("Unable to create framebuffer")?;
+                gl2.bind_framebuffer(Gl2::FRAMEBUFFER, Some(&resolve_framebuffer));
+                gl2.framebuffer_texture_2d(
+                    Gl2::FRAMEBUFFER,
+                    Gl2::COLOR_ATTACHMENT0,
+                    Gl2::TEXTURE_2D,
+                    Some(&texture),
+                    0,
+                );
+                (Some(color_renderbuffer), Some(resolve_framebuffer))
+            }
+            _ => {
+                gl.bind_renderbuffer(Gl::RENDERBUFFER, Some(&stencil_renderbuffer));
+                gl.renderbuffer_storage(Gl::RENDERBUFFER, Gl::STENCIL_INDEX8, width, height);
+                gl.check_error("renderbuffer_storage (layer stencil)")?;
+
+                gl.bind_framebuffer(Gl::FRAMEBUFFER, Some(&framebuffer));
+                gl.framebuffer_texture_2d(
+                    Gl::FRAMEBUFFER,
+                    Gl::COLOR_ATTACHMENT0,
+                    Gl::TEXTURE_2D,
+                    Some(&texture),
+                    0,
+                );
+                (None, None)
+            }
+        };
+
+        gl.bind_framebuffer(Gl::FRAMEBUFFER, Some(&framebuffer));
+        gl.framebuffer_renderbuffer(
+            Gl::FRAMEBUFFER,
+            Gl::STENCIL_ATTACHMENT,
+            Gl::RENDERBUFFER,
+            Some(&stencil_renderbuffer),
+        );
+        gl.bind_renderbuffer(Gl::RENDERBUFFER, None);
+
+        Ok(LayerBuffers {
+            framebuffer,
+            stencil_renderbuffer,
+            texture,
+            msaa_color_renderbuffer,
+            resolve_framebuffer,
+        })
+    }
+
+    /// The framebuffer that is currently being rendered to, or `None` for the canvas.
+    fn render_target(&self) -> Option<WebGlFramebuffer> {
+        let layer = self
+            .blend_layers
+            .iter()
+            .rev()
+            .find_map(|layer| layer.buffers.as_ref());
+        match (layer, &self.msaa_buffers) {
+            (Some(layer), _) => Some(layer.framebuffer.clone()),
+            (None, Some(msaa_buffers)) => Some(msaa_buffers.render_framebuffer.clone()),
+            (None, None) => None,
+        }
+    }
+
+    /// Copies what has been rendered to the current render target to a
+    /// texture, which blend layers are composited with.
+    ///
+    /// Leaves the current render target bound.
+    fn backdrop_texture(&mut self) -> Result<WebGlTexture, Error> {
+        let layer = self
+            .blend_layers
+            .iter()
+            .rev()
+            .find_map(|layer| layer.buffers.as_ref());
+
+        // Render targets with MSAA are resolved to a texture of their own.
+        let resolved = match (layer, &self.msaa_buffers) {
+            (Some(layer), _) => layer
+                .resolve_framebuffer
+                .clone()
+                .map(|framebuffer| (framebuffer, layer.texture.clone())),
+            (None, Some(msaa_buffers)) => Some((
+                msaa_buffers.color_framebuffer.clone(),
+                msaa_buffers.framebuffer_texture.clone(),
+            )),
+            (None, None) => None,
+        };
+        let format = if layer.is_some() { Gl::RGBA } else { Gl::RGB };
+
+        let render_target = self.render_target();
+        if let (Some(gl2), Some((resolve_framebuffer, texture))) = (&self.gl2, resolved) {
+            gl2.bind_framebuffer(Gl2::READ_FRAMEBUFFER, render_target.as_ref());
+            gl2.bind_framebuffer(Gl2::DRAW_FRAMEBUFFER, Some(&resolve_framebuffer));
+            gl2.blit_framebuffer(
+                0,
+                0,
+                self.renderbuffer_width,
+                self.renderbuffer_height,
+                0,
+                0,
+                self.renderbuffer_width,
+                self.renderbuffer_height,
+                Gl2::COLOR_BUFFER_BIT,
+                Gl2::NEAREST,
+            );
+            gl2.bind_framebuffer(Gl2::FRAMEBUFFER, render_target.as_ref());
+            return Ok(texture);
+        }
+
+        let texture = match &self.backdrop_texture {
+            Some(texture) => texture.clone(),
+            None => {
+                let texture = self.gl.create_texture().ok_or("Unable to create texture")?;
+                self.backdrop_texture = Some(texture.clone());
+                texture
+            }
+        };
+        let gl = &self.gl;
+        gl.bind_framebuffer(Gl::FRAMEBUFFER, render_target.as_ref());
+        gl.bind_texture(Gl::TEXTURE_2D, Some(&texture));
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MAG_FILTER, Gl::NEAREST as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MIN_FILTER, Gl::NEAREST as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_WRAP_S, Gl::CLAMP_TO_EDGE as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_WRAP_T, Gl::CLAMP_TO_EDGE as i32);
+        gl.copy_tex_image_2d(
+            Gl::TEXTURE_2D,
+            0,
+            format,
+            0,
+            0,
+            self.renderbuffer_width,
+            self.renderbuffer_height,
+            0,
+        );
+        gl.bind_texture(Gl::TEXTURE_2D, None);
+        Ok(texture)
+    }
+
+    /// Reads the pre-multiplied RGBA pixels of a layer, top row first.
+    fn read_layer_pixels(&self, layer: &LayerBuffers, width: i32, height: i32) -> Vec<u8> {
+        self.gl.disable(Gl::STENCIL_TEST);
+        self.gl.color_mask(true, true, true, true);
+
+        // Resolve the MSAA of the layer, if we're using it (WebGL2).
+        let framebuffer = match (&self.gl2, &layer.resolve_framebuffer) {
+            (Some(gl2), Some(resolve_framebuffer)) => {
+                gl2.bind_framebuffer(Gl2::READ_FRAMEBUFFER, Some(&layer.framebuffer));
+                gl2.bind_framebuffer(Gl2::DRAW_FRAMEBUFFER, Some(resolve_framebuffer));
+                gl2.blit_framebuffer(
+                    0,
+                    0,
+                    width,
+                    height,
+                    0,
+                    0,
+                    width,
+                    height,
+                    Gl2::COLOR_BUFFER_BIT,
+                    Gl2::NEAREST,
+                );
+                resolve_framebuffer
+            }
+            _ => &layer.framebuffer,
+        };
+
+        let row_len = width as usize * 4;
+        let mut pixels = vec![0; row_len * height as usize];
+        self.gl.bind_framebuffer(Gl::FRAMEBUFFER, Some(framebuffer));
+        self.gl
+            .read_pixels_with_opt_u8_array(
+                0,
+                0,
+                width,
+                height,
+                Gl::RGBA,
+                Gl::UNSIGNED_BYTE,
+                Some(&mut pixels),
+            )
+            .warn_on_error();
+
+        // GL reads the bottom row first.
+        pixels
+            .chunks_exact(row_len)
+            .rev()
+            .flatten()
+            .copied()
+            .collect()
+    }
+
+    /// Composites a blend layer onto the current render target.
+    fn composite_layer(
+        &mut self,
+        layer: &LayerBuffers,
+        blend_mode: swf::BlendMode,
+    ) -> Result<(), Error> {
+        // Drawing to the render target below is affected by masks, but
+        // copying it is not.
+        self.gl.disable(Gl::STENCIL_TEST);
+        self.gl.color_mask(true, true, true, true);
+        self.mask_state_dirty = true;
+
+        // Resolve the MSAA of the layer, if we're using it (WebGL2).
+        if let (Some(gl2), Some(resolve_framebuffer)) = (&self.gl2, &layer.resolve_framebuffer) {
+            gl2.bind_framebuffer(Gl2::READ_FRAMEBUFFER, Some(&layer.framebuffer));
+            gl2.bind_framebuffer(Gl2::DRAW_FRAMEBUFFER, Some(resolve_framebuffer));
+            gl2.blit_framebuffer(
+                0,
+                0,
+                self.renderbuffer_width,
+                self.renderbuffer_height,
+                0,
+                0,
+                self.renderbuffer_width,
+                self.renderbuffer_height,
+                Gl2::COLOR_BUFFER_BIT,
+                Gl2::NEAREST,
+            );
+        }
+
+        let backdrop = self.backdrop_texture()?;
+        self.set_stencil_state();
+
+        // The blend shader does the blending itself.
+        self.gl.disable(Gl::BLEND);
+
+        let program = &self.blend_program;
+        self.gl.use_program(Some(&program.program));
+        self.active_program = std::ptr::null();
+
+        // Fill the render target.
+        program.uniform_matrix4fv(
+            &self.gl,
+            ShaderUniform::WorldMatrix,
+            &[
+                [2.0, 0.0, 0.0, 0.0],
+                [0.0, 2.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [-1.0, -1.0, 0.0, 1.0],
+            ],
+        );
+        program.uniform_matrix4fv(
+            &self.gl,
+            ShaderUniform::ViewMatrix,
+            &[
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        );
+        program.uniform_matrix3fv(
+            &self.gl,
+            ShaderUniform::TextureMatrix,
+            &[[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+        );
+
+        self.gl.active_texture(Gl::TEXTURE1);
+        self.gl.bind_texture(Gl::TEXTURE_2D, Some(&backdrop));
+        program.uniform1i(&self.gl, ShaderUniform::BlendBackdrop, 1);
+        self.gl.active_texture(Gl::TEXTURE0);
+        self.gl.bind_texture(Gl::TEXTURE_2D, Some(&layer.texture));
+        program.uniform1i(&self.gl, ShaderUniform::BitmapTexture, 0);
+        program.uniform1i(&self.gl, ShaderUniform::BlendMode, blend_mode as i32);
+
+        let quad = &self.meshes[self.blend_quad_shape.0];
+        self.bind_vertex_array(Some(&quad.draws[0].vao));
+        self.gl.draw_elements_with_i32(
+            Gl::TRIANGLES,
+            quad.draws[0].num_indices,
+            Gl::UNSIGNED_INT,
+            0,
+        );
+
+        self.gl.enable(Gl::BLEND);
+        Ok(())
+    }
+
+    /// Runs a chain of blur and color matrix filters on an image in
+    /// pre-multiplied RGBA, and reads back the filtered image.
+    ///
+    /// This may be called while a frame is being rendered, which is left as it was.
+    fn run_filters(
+        &mut self,
+        filters: &[BitmapFilter],
+        width: u32,
+        height: u32,
+        pixels: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        // Each pass reads the texture that the previous pass drew to.
+        let mut source = self.create_filter_buffers(width, height, Some(pixels))?;
+        let mut target = self.create_filter_buffers(width, height, None)?;
+        self.gl.disable(Gl::STENCIL_TEST);
+        self.gl.disable(Gl::BLEND);
+        self.gl.color_mask(true, true, true, true);
+        self.gl.viewport(0, 0, width as i32, height as i32);
+
+        let mut result: Result<(), Error> = Ok(());
+        for filter in filters {
+            match filter {
+                BitmapFilter::Blur(blur) => {
+                    let (size_x, size_y) = blur.box_size();
+                    for _ in 0..blur.passes() {
+                        for (size, step) in [
+                            (size_x, [1.0 / width as f32, 0.0]),
+                            (size_y, [0.0, 1.0 / height as f32]),
+                        ] {
+                            if size > 1 {
+                                let program = &self.blur_program;
+                                self.gl.use_program(Some(&program.program));
+                                program.uniform2fv(&self.gl, ShaderUniform::BlurStep, &step);
+                                program.uniform1i(
+                                    &self.gl,
+                                    ShaderUniform::BlurLeft,
+                                    (size as i32 - 1) / 2,
+                                );
+                                program.uniform1i(&self.gl, ShaderUniform::BlurSize, size as i32);
+                                self.draw_filter_pass(
+                                    program,
+                                    self.blur_quad_shape,
+                                    &source,
+                                    &target,
+                                );
+                                std::mem::swap(&mut source, &mut target);
+                            }
+                        }
+                    }
+                }
+                BitmapFilter::ColorMatrix(color_matrix) => {
+                    let (matrix, offset) = color_matrix.normalized();
+                    let program = &self.color_matrix_program;
+                    self.gl.use_program(Some(&program.program));
+                    program.uniform_matrix4fv(&self.gl, ShaderUniform::ColorMatrix, &matrix);
+                    program.uniform4fv(&self.gl, ShaderUniform::ColorOffset, &offset);
+                    self.draw_filter_pass(program, self.color_matrix_quad_shape, &source, &target);
+                    std::mem::swap(&mut source, &mut target);
+                }
+                _ => {
+                    result = Err("Filter has no shader".into());
+                    break;
+                }
+            }
+        }
+
+        let mut output = vec![0; width as usize * height as usize * 4];
+        if result.is_ok() {
+            self.gl.bind_framebuffer(Gl::FRAMEBUFFER, Some(&source.1));
+            result = self
+                .gl
+                .read_pixels_with_opt_u8_array(
+                    0,
+                    0,
+                    width as i32,
+                    height as i32,
+                    Gl::RGBA,
+                    Gl::UNSIGNED_BYTE,
+                    Some(&mut output),
+                )
+                .into_js_result()
+                .map_err(Error::from);
+        }
+
+        for (texture, framebuffer) in [source, target] {
+            self.gl.delete_framebuffer(Some(&framebuffer));
+            self.gl.delete_texture(Some(&texture));
+        }
+        self.gl.enable(Gl::BLEND);
+        self.mask_state_dirty = true;
+        self.active_program = std::ptr::null();
+        self.gl
+            .bind_framebuffer(Gl::FRAMEBUFFER, self.render_target().as_ref());
+        self.gl
+            .viewport(0, 0, self.renderbuffer_width, self.renderbuffer_height);
+        result.map(|()| output)
+    }
+
+    /// Creates a texture for a filter pass to read from or draw to, and a
+    /// framebuffer drawing to it.
+    fn create_filter_buffers(
+        &self,
+        width: u32,
+        height: u32,
+        pixels: Option<&[u8]>,
+    ) -> Result<(WebGlTexture, WebGlFramebuffer), Error> {
+        let gl = &self.gl;
+        let texture = gl.create_texture().ok_or("Unable to create texture")?;
+        gl.bind_texture(Gl::TEXTURE_2D, Some(&texture));
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MAG_FILTER, Gl::NEAREST as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MIN_FILTER, Gl::NEAREST as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_WRAP_S, Gl::CLAMP_TO_EDGE as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_WRAP_T, Gl::CLAMP_TO_EDGE as i32);
+        gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+            Gl::TEXTURE_2D,
+            0,
+            Gl::RGBA as i32,
+            width as i32,
+            height as i32,
+            0,
+            Gl::RGBA,
+            Gl::UNSIGNED_BYTE,
+            pixels,
+        )
+        .into_js_result()?;
+        gl.bind_texture(Gl::TEXTURE_2D, None);
+
+        let framebuffer = gl
+            .create_framebuffer()
+            .ok_or("Unable to create framebuffer")?;
+        gl.bind_framebuffer(Gl::FRAMEBUFFER, Some(&framebuffer));
+        gl.framebuffer_texture_2d(
+            Gl::FRAMEBUFFER,
+            Gl::COLOR_ATTACHMENT0,
+            Gl::TEXTURE_2D,
+            Some(&texture),
+            0,
+        );
+        Ok((texture, framebuffer))
+    }
+
+    /// Draws one filter pass with a program that is in use, reading
+    /// `source` and filling `target`.
+    fn draw_filter_pass(
+        &self,
+        program: &ShaderProgram,
+        quad_shape: ShapeHandle,
+        source: &(WebGlTexture, WebGlFramebuffer),
+        target: &(WebGlTexture, WebGlFramebuffer),
+    ) {
+        // The quad covers the target, and the image isn't flipped.
+        program.uniform_matrix4fv(
+            &self.gl,
+            ShaderUniform::WorldMatrix,
+            &[
+                [2.0, 0.0, 0.0, 0.0],
+                [0.0, 2.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [-1.0, -1.0, 0.0, 1.0],
+            ],
+        );
+        program.uniform_matrix4fv(
+            &self.gl,
+            ShaderUniform::ViewMatrix,
+            &[
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        );
+        program.uniform_matrix3fv(
+            &self.gl,
+            ShaderUniform::TextureMatrix,
+            &[[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+        );
+
+        self.gl.bind_framebuffer(Gl::FRAMEBUFFER, Some(&target.1));
+        self.gl.active_texture(Gl::TEXTURE0);
+        self.gl.bind_texture(Gl::TEXTURE_2D, Some(&source.0));
+        program.uniform1i(&self.gl, ShaderUniform::BitmapTexture, 0);
+
+        let quad = &self.meshes[quad_shape.0];
+        self.bind_vertex_array(Some(&quad.draws[0].vao));
+        self.gl.draw_elements_with_i32(
+            Gl::TRIANGLES,
+            quad.draws[0].num_indices,
+            Gl::UNSIGNED_INT,
+            0,
+        );
+        self.bind_vertex_array(None);
+    }
+
     fn register_bitmap(&mut self, bitmap: Bitmap) -> Result<BitmapInfo, Error> {
         let texture = self.gl.create_texture().unwrap();
         self.gl.bind_texture(Gl::TEXTURE_2D, Some(&texture));
@@ -728,11 +1315,31 @@ impl RenderBackend for WebGlRenderBackend {
         self.renderbuffer_height = self.view_height.clamp(1, self.gl.drawing_buffer_height());
 
         // Recreate framebuffers with the new size.
-        let _ = self.build_msaa_buffers(self.renderbuffer_width, self.renderbuffer_height);
+        if let Err(e) = self.build_msaa_buffers(self.renderbuffer_width, self.renderbuffer_height) {
+            log::error!("Unable to rebuild MSAA buffers: {}", e);
+        }
+        for layer in self.layer_buffers.drain(..) {
+            layer.delete(&self.gl);
+        }
         self.gl
             .viewport(0, 0, self.renderbuffer_width, self.renderbuffer_height);
     }
 
+    fn set_quality(&mut self, quality: StageQuality) {
+        let msaa_sample_count = quality.sample_count().min(self.max_msaa_sample_count);
+        if msaa_sample_count != self.msaa_sample_count {
+            self.msaa_sample_count = msaa_sample_count;
+            if let Err(e) =
+                self.build_msaa_buffers(self.renderbuffer_width, self.renderbuffer_height)
+            {
+                log::error!("Unable to rebuild MSAA buffers: {}", e);
+            }
+            for layer in self.layer_buffers.drain(..) {
+                layer.delete(&self.gl);
+            }
+        }
+    }
+
     fn register_shape(
         &mut self,
         shape: DistilledShape,
@@ -799,6 +1406,9 @@ impl RenderBackend for WebGlRenderBackend {
         self.mask_state = MaskState::NoMask;
         self.num_masks = 0;
         self.mask_state_dirty = true;
+        for layer in self.blend_layers.drain(..) {
+            self.layer_buffers.extend(layer.buffers);
+        }
 
         self.mult_color = None;
         self.add_color = None;
@@ -897,6 +1507,7 @@ impl RenderBackend for WebGlRenderBackend {
             self.gl
                 .bind_texture(Gl2::TEXTURE_2D, Some(&msaa_buffers.framebuffer_texture));
             program.uniform1i(&self.gl, ShaderUniform::BitmapTexture, 0);
+            program.uniform1i(&self.gl, ShaderUniform::BitmapRepeat, 0);
 
             // Render the quad.
             let quad = &self.meshes[self.bitmap_quad_shape.0];
@@ -967,7 +1578,12 @@ impl RenderBackend for WebGlRenderBackend {
                 self.add_color = None;
 
                 if (src_blend, dst_blend) != self.blend_func {
-                    self.gl.blend_func(src_blend, dst_blend);
+                    self.gl.blend_func_separate(
+                        src_blend,
+                        dst_blend,
+                        Gl::ONE,
+                        Gl::ONE_MINUS_SRC_ALPHA,
+                    );
                     self.blend_func = (src_blend, dst_blend);
                 }
             }
@@ -988,6 +1604,7 @@ impl RenderBackend for WebGlRenderBackend {
             self.gl.active_texture(Gl::TEXTURE0);
             self.gl.bind_texture(Gl::TEXTURE_2D, Some(texture));
             program.uniform1i(&self.gl, ShaderUniform::BitmapTexture, 0);
+            program.uniform1i(&self.gl, ShaderUniform::BitmapRepeat, 0);
 
             // Set texture parameters.
             let filter = if smoothing {
@@ -1058,7 +1675,12 @@ impl RenderBackend for WebGlRenderBackend {
                 self.add_color = None;
 
                 if (src_blend, dst_blend) != self.blend_func {
-                    self.gl.blend_func(src_blend, dst_blend);
+                    self.gl.blend_func_separate(
+                        src_blend,
+                        dst_blend,
+                        Gl::ONE,
+                        Gl::ONE_MINUS_SRC_ALPHA,
+                    );
                     self.blend_func = (src_blend, dst_blend);
                 }
             }
@@ -1143,12 +1765,18 @@ impl RenderBackend for WebGlRenderBackend {
                         .tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MAG_FILTER, filter);
                     self.gl
                         .tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MIN_FILTER, filter);
-                    // On WebGL1, you are unable to change the wrapping parameter of non-power-of-2 textures.
+                    // On WebGL1, you are unable to change the wrapping parameter of non-power-of-2 textures,
+                    // so the shader repeats the texture coordinates instead.
                     let wrap = if self.gl2.is_some() && bitmap.is_repeating {
                         Gl::REPEAT as i32
                     } else {
                         Gl::CLAMP_TO_EDGE as i32
                     };
+                    program.uniform1i(
+                        &self.gl,
+                        ShaderUniform::BitmapRepeat,
+                        (self.gl2.is_none() && bitmap.is_repeating) as i32,
+                    );
                     self.gl
                         .tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_WRAP_S, wrap);
                     self.gl
@@ -1202,7 +1830,8 @@ impl RenderBackend for WebGlRenderBackend {
             self.add_color = None;
 
             if (src_blend, dst_blend) != self.blend_func {
-                self.gl.blend_func(src_blend, dst_blend);
+                self.gl
+                    .blend_func_separate(src_blend, dst_blend, Gl::ONE, Gl::ONE_MINUS_SRC_ALPHA);
                 self.blend_func = (src_blend, dst_blend);
             }
         };
@@ -1263,6 +1892,171 @@ impl RenderBackend for WebGlRenderBackend {
         self.mask_state_dirty = true;
     }
 
+    fn push_blend_mode(&mut self, blend_mode: swf::BlendMode) {
+        let buffers = match self.layer_buffers.pop() {
+            Some(buffers) => Some(buffers),
+            None => self
+                .create_layer_buffers()
+                .map_err(|e| log::error!("Unable to create blend layer: {}", e))
+                .ok(),
+        };
+
+        if let Some(buffers) = &buffers {
+            self.gl
+                .bind_framebuffer(Gl::FRAMEBUFFER, Some(&buffers.framebuffer));
+        }
+        self.blend_layers.push(BlendLayer {
+            blend_mode,
+            buffers,
+            mask_state: self.mask_state,
+            num_masks: self.num_masks,
+        });
+
+        // The layer starts out transparent and unmasked.
+        if self.blend_layers.last().unwrap().buffers.is_some() {
+            self.mask_state = MaskState::NoMask;
+            self.num_masks = 0;
+            self.mask_state_dirty = true;
+            self.set_stencil_state();
+            self.gl.clear_color(0.0, 0.0, 0.0, 0.0);
+            self.gl.stencil_mask(0xff);
+            self.gl.clear(Gl::COLOR_BUFFER_BIT | Gl::STENCIL_BUFFER_BIT);
+        }
+    }
+
+    fn pop_blend_mode(&mut self) {
+        let layer = match self.blend_layers.pop() {
+            Some(layer) => layer,
+            None => {
+                log::error!("Blend layer stack underflow");
+                return;
+            }
+        };
+
+        self.mask_state = layer.mask_state;
+        self.num_masks = layer.num_masks;
+        self.mask_state_dirty = true;
+
+        if let Some(buffers) = layer.buffers {
+            if let Err(e) = self.composite_layer(&buffers, layer.blend_mode) {
+                log::error!("Unable to composite blend layer: {}", e);
+            }
+            self.layer_buffers.push(buffers);
+        }
+    }
+
+    fn render_offscreen(
+        &mut self,
+        width: u32,
+        height: u32,
+        clear: Color,
+        draw: &mut dyn FnMut(&mut dyn RenderBackend),
+    ) -> Option<Bitmap> {
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        // This may be called while rendering the main frame, such as for a
+        // clip cached as a bitmap inside a mask, so its state is restored
+        // afterwards. The offscreen image is rendered to a blend layer of
+        // its own size, which blended clips inside of it are composited onto.
+        let view = (self.view_width, self.view_height);
+        let renderbuffer = (self.renderbuffer_width, self.renderbuffer_height);
+        let mask_state = (self.mask_state, self.num_masks);
+        let blend_layers = std::mem::take(&mut self.blend_layers);
+        let layer_buffers = std::mem::take(&mut self.layer_buffers);
+
+        self.view_width = width as i32;
+        self.view_height = height as i32;
+        self.renderbuffer_width = width as i32;
+        self.renderbuffer_height = height as i32;
+        self.build_matrices();
+
+        let pixels = match self.create_layer_buffers() {
+            Ok(buffers) => {
+                self.gl
+                    .bind_framebuffer(Gl::FRAMEBUFFER, Some(&buffers.framebuffer));
+                self.blend_layers.push(BlendLayer {
+                    blend_mode: swf::BlendMode::Normal,
+                    buffers: Some(buffers),
+                    mask_state: MaskState::NoMask,
+                    num_masks: 0,
+                });
+                self.mask_state = MaskState::NoMask;
+                self.num_masks = 0;
+                self.mask_state_dirty = true;
+                self.mult_color = None;
+                self.add_color = None;
+                self.gl.viewport(0, 0, width as i32, height as i32);
+
+                // The layer is pre-multiplied.
+                let alpha = clear.a as f32 / 255.0;
+                self.set_stencil_state();
+                self.gl.clear_color(
+                    clear.r as f32 / 255.0 * alpha,
+                    clear.g as f32 / 255.0 * alpha,
+                    clear.b as f32 / 255.0 * alpha,
+                    alpha,
+                );
+                self.gl.stencil_mask(0xff);
+                self.gl.clear(Gl::COLOR_BUFFER_BIT | Gl::STENCIL_BUFFER_BIT);
+
+                draw(self);
+
+                // Blend layers left pushed by `draw` are discarded below.
+                let buffers = if self.blend_layers.is_empty() {
+                    None
+                } else {
+                    self.blend_layers.remove(0).buffers
+                };
+                let pixels = buffers
+                    .as_ref()
+                    .map(|buffers| self.read_layer_pixels(buffers, width as i32, height as i32));
+                if let Some(buffers) = buffers {
+                    buffers.delete(&self.gl);
+                }
+                pixels
+            }
+            Err(e) => {
+                log::error!("Unable to create offscreen framebuffer: {}", e);
+                None
+            }
+        };
+
+        for layer in self.blend_layers.drain(..) {
+            if let Some(buffers) = layer.buffers {
+                buffers.delete(&self.gl);
+            }
+        }
+        for buffers in std::mem::replace(&mut self.layer_buffers, layer_buffers) {
+            buffers.delete(&self.gl);
+        }
+        self.blend_layers = blend_layers;
+        self.mask_state = mask_state.0;
+        self.num_masks = mask_state.1;
+        self.mask_state_dirty = true;
+        self.mult_color = None;
+        self.add_color = None;
+        self.view_width = view.0;
+        self.view_height = view.1;
+        self.renderbuffer_width = renderbuffer.0;
+        self.renderbuffer_height = renderbuffer.1;
+        self.build_matrices();
+        self.gl
+            .bind_framebuffer(Gl::FRAMEBUFFER, self.render_target().as_ref());
+        self.gl
+            .viewport(0, 0, self.renderbuffer_width, self.renderbuffer_height);
+
+        pixels.map(|mut pixels| {
+            ruffle_core::backend::render::unmultiply_alpha_rgba(&mut pixels);
+            Bitmap {
+                width,
+                height,
+                data: BitmapFormat::Rgba(pixels),
+            }
+        })
+    }
+
     fn get_bitmap_pixels(&mut self, bitmap: BitmapHandle) -> Option<Bitmap> {
         self.bitmap_registry.get(&bitmap).cloned()
     }
@@ -1282,20 +2076,47 @@ impl RenderBackend for WebGlRenderBackend {
             .handle)
     }
 
+    fn apply_filters(
+        &mut self,
+        filters: &[BitmapFilter],
+        width: u32,
+        height: u32,
+        rgba: &[u8],
+    ) -> Vec<u8> {
+        apply_filters_accelerated(
+            filters,
+            width,
+            height,
+            rgba,
+            |filter| matches!(filter, BitmapFilter::Blur(_) | BitmapFilter::ColorMatrix(_)),
+            |filters, pixels| match self.run_filters(filters, width, height, pixels) {
+                Ok(pixels) => Some(pixels),
+                Err(e) => {
+                    log::error!("Unable to run filters: {}", e);
+                    None
+                }
+            },
+        )
+    }
+
     fn update_texture(
         &mut self,
         handle: BitmapHandle,
         width: u32,
         height: u32,
-        rgba: Vec<u8>,
+        rgba: &[u8],
     ) -> Result<BitmapHandle, Error> {
-        let texture = if let Some(texture) = self.textures.get(handle.0) {
+        check_rgba_len(width, height, rgba)?;
+
+        let texture = if let Some(texture) = self.textures.get_mut(handle.0) {
             texture
         } else {
             return Err("update_texture: Bitmap is not regsitered".into());
         };
 
         self.gl.bind_texture(Gl::TEXTURE_2D, Some(&texture.texture));
+        texture.width = width;
+        texture.height = height;
 
         self.gl
             .tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
@@ -1307,7 +2128,7 @@ impl RenderBackend for WebGlRenderBackend {
                 0,
                 Gl::RGBA,
                 Gl::UNSIGNED_BYTE,
-                Some(&rgba),
+                Some(rgba),
             )
             .into_js_result()?;
 
@@ -1340,7 +2161,9 @@ impl From<TessGradient> for Gradient {
         ratios[..gradient.num_colors].copy_from_slice(&gradient.ratios[..gradient.num_colors]);
         colors[..gradient.num_colors].copy_from_slice(&gradient.colors[..gradient.num_colors]);
 
-        for i in gradient.num_colors..MAX_GRADIENT_COLORS {
+        // Pad with the last color, which the shader expects. A gradient
+        // without any colors is transparent.
+        for i in gradient.num_colors.max(1)..MAX_GRADIENT_COLORS {
             ratios[i] = ratios[i - 1];
             colors[i] = colors[i - 1];
         }
@@ -1360,7 +2183,7 @@ impl From<TessGradient> for Gradient {
                 swf::GradientSpread::Repeat => 1,
                 swf::GradientSpread::Reflect => 2,
             },
-            focal_point: gradient.focal_point.to_f32(),
+            focal_point: gradient.focal_point,
             interpolation: gradient.interpolation,
         }
     }
@@ -1393,6 +2216,42 @@ enum DrawType {
     Bitmap(BitmapDraw),
 }
 
+/// A layer that a blended clip is rendered to, before it is composited onto
+/// the render target below it.
+struct BlendLayer {
+    blend_mode: swf::BlendMode,
+
+    /// The framebuffer of the layer, or `None` if it couldn't be created, in
+    /// which case the clip is rendered to the render target below instead.
+    buffers: Option<LayerBuffers>,
+
+    /// The masking state of the render target below, restored when the
+    /// layer is popped.
+    mask_state: MaskState,
+    num_masks: u32,
+}
+
+struct LayerBuffers {
+    framebuffer: WebGlFramebuffer,
+    stencil_renderbuffer: WebGlRenderbuffer,
+    texture: WebGlTexture,
+
+    // With MSAA (WebGL2), the layer is rendered to a multisampled color
+    // renderbuffer, and resolved to the texture through a second framebuffer.
+    msaa_color_renderbuffer: Option<WebGlRenderbuffer>,
+    resolve_framebuffer: Option<WebGlFramebuffer>,
+}
+
+impl LayerBuffers {
+    fn delete(&self, gl: &Gl) {
+        gl.delete_framebuffer(Some(&self.framebuffer));
+        gl.delete_renderbuffer(Some(&self.stencil_renderbuffer));
+        gl.delete_texture(Some(&self.texture));
+        gl.delete_renderbuffer(self.msaa_color_renderbuffer.as_ref());
+        gl.delete_framebuffer(self.resolve_framebuffer.as_ref());
+    }
+}
+
 struct MsaaBuffers {
     color_renderbuffer: WebGlRenderbuffer,
     stencil_renderbuffer: WebGlRenderbuffer,
@@ -1413,7 +2272,7 @@ struct ShaderProgram {
 }
 
 // These should match the uniform names in the shaders.
-const NUM_UNIFORMS: usize = 13;
+const NUM_UNIFORMS: usize = 21;
 const UNIFORM_NAMES: [&str; NUM_UNIFORMS] = [
     "world_matrix",
     "view_matrix",
@@ -1428,6 +2287,14 @@ const UNIFORM_NAMES: [&str; NUM_UNIFORMS] = [
     "u_focal_point",
     "u_interpolation",
     "u_texture",
+    "u_repeat",
+    "u_backdrop",
+    "u_blend_mode",
+    "u_blur_step",
+    "u_blur_left",
+    "u_blur_size",
+    "u_color_matrix",
+    "u_color_offset",
 ];
 
 enum ShaderUniform {
@@ -1444,6 +2311,14 @@ enum ShaderUniform {
     GradientFocalPoint,
     GradientInterpolation,
     BitmapTexture,
+    BitmapRepeat,
+    BlendBackdrop,
+    BlendMode,
+    BlurStep,
+    BlurLeft,
+    BlurSize,
+    ColorMatrix,
+    ColorOffset,
 }
 
 impl ShaderProgram {
@@ -1509,6 +2384,10 @@ impl ShaderProgram {
         gl.uniform1i(self.uniforms[uniform as usize].as_ref(), value);
     }
 
+    fn uniform2fv(&self, gl: &Gl, uniform: ShaderUniform, values: &[f32]) {
+        gl.uniform2fv_with_f32_array(self.uniforms[uniform as usize].as_ref(), values);
+    }
+
     fn uniform4fv(&self, gl: &Gl, uniform: ShaderUniform, values: &[f32]) {
         gl.uniform4fv_with_f32_array(self.uniforms[uniform as usize].as_ref(), values);
     }
@@ -0,0 +1,107 @@
+/// Shader used for compositing a blend layer onto the render target below it.
+
+[[block]]
+struct Blend {
+    /// The SWF value of the blend mode.
+    mode: i32;
+};
+
+struct VertexOutput {
+    [[builtin(position)]] position: vec4<f32>;
+    [[location(0)]] uv: vec2<f32>;
+};
+
+[[group(2), binding(0)]]
+var<uniform> blend: Blend;
+/// The layer that the blended clip was rendered to.
+[[group(2), binding(1)]]
+var layer_texture: texture_2d<f32>;
+/// Everything that was rendered below the blended clip.
+[[group(2), binding(2)]]
+var backdrop_texture: texture_2d<f32>;
+[[group(3), binding(0)]]
+var texture_sampler: sampler;
+
+[[stage(vertex)]]
+fn main_vertex(in: VertexInput) -> VertexOutput {
+    let pos = globals.view_matrix * transforms.world_matrix * vec4<f32>(in.position.x, in.position.y, 0.0, 1.0);
+    return VertexOutput(pos, in.position);
+}
+
+fn hard_light(top: vec3<f32>, bottom: vec3<f32>) -> vec3<f32> {
+    let multiplied = 2.0 * top * bottom;
+    let screened = vec3<f32>(1.0) - 2.0 * (vec3<f32>(1.0) - top) * (vec3<f32>(1.0) - bottom);
+    return mix(multiplied, screened, step(vec3<f32>(0.5), top));
+}
+
+[[stage(fragment)]]
+fn main_fragment(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    // Both textures have premultiplied alpha.
+    let src = input(textureSample(layer_texture, texture_sampler, in.uv));
+    let dst = input(textureSample(backdrop_texture, texture_sampler, in.uv));
+
+    if( blend.mode == 11 ) {
+        // Alpha
+        return output(dst * src.a);
+    }
+    if( blend.mode == 12 ) {
+        // Erase
+        return output(dst * (1.0 - src.a));
+    }
+
+    var s: vec3<f32> = vec3<f32>(0.0);
+    if( src.a > 0.0 ) {
+        s = src.rgb / src.a;
+    }
+    var d: vec3<f32> = vec3<f32>(0.0);
+    if( dst.a > 0.0 ) {
+        d = dst.rgb / dst.a;
+    }
+
+    // Only one of these applies, and normal and layer keep the source color.
+    var mixed: vec3<f32> = s;
+    if( blend.mode == 3 ) {
+        // Multiply
+        mixed = s * d;
+    }
+    if( blend.mode == 4 ) {
+        // Screen
+        mixed = s + d - s * d;
+    }
+    if( blend.mode == 5 ) {
+        // Lighten
+        mixed = max(s, d);
+    }
+    if( blend.mode == 6 ) {
+        // Darken
+        mixed = min(s, d);
+    }
+    if( blend.mode == 7 ) {
+        // Difference
+        mixed = abs(s - d);
+    }
+    if( blend.mode == 8 ) {
+        // Add
+        mixed = min(s + d, vec3<f32>(1.0));
+    }
+    if( blend.mode == 9 ) {
+        // Subtract
+        mixed = max(d - s, vec3<f32>(0.0));
+    }
+    if( blend.mode == 10 ) {
+        // Invert
+        mixed = vec3<f32>(1.0) - d;
+    }
+    if( blend.mode == 13 ) {
+        // Overlay
+        mixed = hard_light(d, s);
+    }
+    if( blend.mode == 14 ) {
+        // Hard light
+        mixed = hard_light(s, d);
+    }
+
+    let rgb = src.rgb * (1.0 - dst.a) + dst.rgb * (1.0 - src.a) + src.a * dst.a * mixed;
+    let out = vec4<f32>(rgb, src.a + dst.a * (1.0 - src.a));
+    return output(out);
+}
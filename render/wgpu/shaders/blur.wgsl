@@ -0,0 +1,24 @@
+/// Shader used for one pass of a box blur, in one direction.
+
+[[block]]
+struct Blur {
+    /// The direction of this pass, one pixel long.
+    direction: vec2<i32>;
+
+    /// The number of pixels averaged before and after each pixel.
+    left: i32;
+    right: i32;
+};
+
+[[group(0), binding(0)]]
+var<uniform> blur: Blur;
+
+[[stage(fragment)]]
+fn main_fragment([[builtin(position)]] position: vec4<f32>) -> [[location(0)]] vec4<f32> {
+    let center = vec2<i32>(position.xy);
+    var sum: vec4<f32> = vec4<f32>(0.0);
+    for (var i: i32 = -blur.left; i <= blur.right; i = i + 1) {
+        sum = sum + source_pixel(center + blur.direction * i);
+    }
+    return sum / f32(blur.left + blur.right + 1);
+}
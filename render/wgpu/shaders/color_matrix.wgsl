@@ -0,0 +1,23 @@
+/// Shader used for the color matrix filter.
+
+[[block]]
+struct ColorMatrix {
+    /// The 4x4 part of the matrix, applied to un-multiplied colors.
+    matrix: mat4x4<f32>;
+
+    /// The offsets in the last column of the matrix, in `0.0..=1.0`.
+    offset: vec4<f32>;
+};
+
+[[group(0), binding(0)]]
+var<uniform> color_matrix: ColorMatrix;
+
+[[stage(fragment)]]
+fn main_fragment([[builtin(position)]] position: vec4<f32>) -> [[location(0)]] vec4<f32> {
+    var color: vec4<f32> = source_pixel(vec2<i32>(position.xy));
+    if( color.a > 0.0 ) {
+        color = vec4<f32>(color.rgb / color.a, color.a);
+    }
+    color = clamp(color_matrix.matrix * color + color_matrix.offset, vec4<f32>(0.0), vec4<f32>(1.0));
+    return vec4<f32>(color.rgb * color.a, color.a);
+}
@@ -0,0 +1,23 @@
+/// Common WGSL shared among the filter shaders.
+/// Ruffle prepends this file onto every filter shader at runtime.
+///
+/// Filters draw a single triangle covering their target, and read the
+/// pre-multiplied source image by pixel. Pixels outside of the source image
+/// are transparent.
+
+[[group(0), binding(1)]]
+var source: texture_2d<f32>;
+
+[[stage(vertex)]]
+fn main_vertex([[builtin(vertex_index)]] index: u32) -> [[builtin(position)]] vec4<f32> {
+    let corner = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    return vec4<f32>(corner * 2.0 - 1.0, 0.0, 1.0);
+}
+
+fn source_pixel(position: vec2<i32>) -> vec4<f32> {
+    let size = textureDimensions(source);
+    if( any(position < vec2<i32>(0)) || any(position >= size) ) {
+        return vec4<f32>(0.0);
+    }
+    return textureLoad(source, position, 0);
+}
@@ -53,8 +53,13 @@ fn main_fragment(in: VertexOutput) -> [[location(0)]] vec4<f32> {
             let uv = in.uv * 2.0 - 1.0;
             var d: vec2<f32> = vec2<f32>(gradient.focal_point, 0.0) - uv;
             let l = length(d);
-            d = d / l;
-            t = l / (sqrt(1.0 - gradient.focal_point * gradient.focal_point * d.y * d.y) + gradient.focal_point * d.x);
+            if( l > 0.0 ) {
+                d = d / l;
+                t = l / (sqrt(1.0 - gradient.focal_point * gradient.focal_point * d.y * d.y) + gradient.focal_point * d.x);
+            } else {
+                // The focal point itself.
+                t = 0.0;
+            }
             break;
         }
     }
@@ -87,18 +92,24 @@ fn main_fragment(in: VertexOutput) -> [[location(0)]] vec4<f32> {
             break;
         }
     }
-    t = clamp(t, gradient.ratios[0], gradient.ratios[last]);
 
-    // Find the two gradient colors bordering our position.
-    var j: u32;
-    for( j = 1u; t > gradient.ratios[j]; j = j + 1u) {
-        // Noop
+    // Find the two gradient colors bordering our position, and lerp between them.
+    // Positions outside of the ratios are the first or last color, which also
+    // keeps a hard stop between colors with the same ratio at either end.
+    var color: vec4<f32>;
+    if( t <= gradient.ratios[0] ) {
+        color = gradient.colors[0];
+    } else if( t >= gradient.ratios[last] ) {
+        color = gradient.colors[last];
+    } else {
+        var j: u32;
+        for( j = 1u; t >= gradient.ratios[j]; j = j + 1u) {
+            // Noop
+        }
+        let i = j - 1u;
+        let a = (t - gradient.ratios[i]) / (gradient.ratios[j] - gradient.ratios[i]);
+        color = mix(gradient.colors[i], gradient.colors[j], a);
     }
-    let i = j - 1u;
-
-    // Lerp between the two colors.
-    let a = (t - gradient.ratios[i]) / (gradient.ratios[j] - gradient.ratios[i]);
-    var color: vec4<f32> = mix(gradient.colors[i], gradient.colors[j], a);
     if( gradient.interpolation != 0 ) {
         color = linear_to_srgb(color);
     }
@@ -1,3 +1,7 @@
 fn output(srgb: vec4<f32>) -> vec4<f32> {
     return srgb_to_linear(srgb);
 }
+
+fn input(linear: vec4<f32>) -> vec4<f32> {
+    return linear_to_srgb(linear);
+}
@@ -1,3 +1,7 @@
 fn output(srgb: vec4<f32>) -> vec4<f32> {
     return srgb;
 }
+
+fn input(srgb: vec4<f32>) -> vec4<f32> {
+    return srgb;
+}
@@ -0,0 +1,349 @@
+//! Shaders for running bitmap filters on the GPU.
+
+use crate::utils::{create_buffer_with_data, BufferDimensions};
+use bytemuck::{Pod, Zeroable};
+use ruffle_core::bitmap::filters::{BitmapFilter, BlurFilter, ColorMatrixFilter};
+use std::num::NonZeroU32;
+
+/// The uniforms of one pass of a box blur.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct BlurUniform {
+    direction: [i32; 2],
+    left: i32,
+    right: i32,
+}
+
+impl BlurUniform {
+    /// A pass averaging `size` pixels in a direction, matching the CPU blur.
+    fn new(direction: [i32; 2], size: usize) -> Self {
+        let left = (size as i32 - 1) / 2;
+        Self {
+            direction,
+            left,
+            right: size as i32 - 1 - left,
+        }
+    }
+
+    /// The passes of a blur filter, in the order that they are run.
+    fn passes(blur: &BlurFilter) -> Vec<Self> {
+        let (size_x, size_y) = blur.box_size();
+        let mut passes = Vec::new();
+        for _ in 0..blur.passes() {
+            if size_x > 1 {
+                passes.push(Self::new([1, 0], size_x));
+            }
+            if size_y > 1 {
+                passes.push(Self::new([0, 1], size_y));
+            }
+        }
+        passes
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct ColorMatrixUniform {
+    matrix: [[f32; 4]; 4],
+    offset: [f32; 4],
+}
+
+impl From<&ColorMatrixFilter> for ColorMatrixUniform {
+    fn from(filter: &ColorMatrixFilter) -> Self {
+        let (matrix, offset) = filter.normalized();
+        Self { matrix, offset }
+    }
+}
+
+#[derive(Debug)]
+pub struct FilterPipelines {
+    layout: wgpu::BindGroupLayout,
+    blur: wgpu::RenderPipeline,
+    color_matrix: wgpu::RenderPipeline,
+}
+
+impl FilterPipelines {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let layout_label = create_debug_label!("Filter bind group layout");
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+            ],
+            label: layout_label.as_deref(),
+        });
+
+        let pipeline_layout_label = create_debug_label!("Filter pipeline layout");
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: pipeline_layout_label.as_deref(),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+
+        let blur = create_filter_pipeline(
+            device,
+            &pipeline_layout,
+            "blur",
+            include_str!("../shaders/blur.wgsl"),
+        );
+        let color_matrix = create_filter_pipeline(
+            device,
+            &pipeline_layout,
+            "color matrix",
+            include_str!("../shaders/color_matrix.wgsl"),
+        );
+
+        Self {
+            layout,
+            blur,
+            color_matrix,
+        }
+    }
+
+    /// Whether a filter can be run by `apply`.
+    pub fn supports(filter: &BitmapFilter) -> bool {
+        matches!(filter, BitmapFilter::Blur(_) | BitmapFilter::ColorMatrix(_))
+    }
+
+    /// Runs a chain of supported filters on an image in pre-multiplied RGBA,
+    /// and reads back the filtered image.
+    pub fn apply(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        filters: &[BitmapFilter],
+        width: u32,
+        height: u32,
+        pixels: &[u8],
+    ) -> Option<Vec<u8>> {
+        let extent = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let create_texture = || {
+            let label = create_debug_label!("Filter texture");
+            device.create_texture(&wgpu::TextureDescriptor {
+                label: label.as_deref(),
+                size: extent,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING
+                    | wgpu::TextureUsages::RENDER_ATTACHMENT
+                    | wgpu::TextureUsages::COPY_SRC
+                    | wgpu::TextureUsages::COPY_DST,
+            })
+        };
+        let mut source = create_texture();
+        let mut target = create_texture();
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &source,
+                mip_level: 0,
+                origin: Default::default(),
+                aspect: wgpu::TextureAspect::All,
+            },
+            pixels,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: NonZeroU32::new(4 * width),
+                rows_per_image: None,
+            },
+            extent,
+        );
+
+        let label = create_debug_label!("Filter encoder");
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: label.as_deref(),
+        });
+        for filter in filters {
+            // Each pass reads the output of the previous one.
+            let passes: Vec<(&wgpu::RenderPipeline, Vec<u8>)> = match filter {
+                BitmapFilter::Blur(blur) => BlurUniform::passes(blur)
+                    .iter()
+                    .map(|pass| (&self.blur, bytemuck::bytes_of(pass).to_vec()))
+                    .collect(),
+                BitmapFilter::ColorMatrix(color_matrix) => vec![(
+                    &self.color_matrix,
+                    bytemuck::bytes_of(&ColorMatrixUniform::from(color_matrix)).to_vec(),
+                )],
+                _ => return None,
+            };
+            for (pipeline, uniform) in passes {
+                self.draw(device, &mut encoder, pipeline, &uniform, &source, &target);
+                std::mem::swap(&mut source, &mut target);
+            }
+        }
+
+        let dimensions = BufferDimensions::new(width as usize, height as usize);
+        let buffer_label = create_debug_label!("Filter buffer");
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: buffer_label.as_deref(),
+            size: dimensions.padded_bytes_per_row.get() as u64 * height as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture: &source,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(dimensions.padded_bytes_per_row),
+                    rows_per_image: None,
+                },
+            },
+            extent,
+        );
+        queue.submit(Some(encoder.finish()));
+        read_buffer(device, &buffer, &dimensions)
+    }
+
+    /// Draws one filter pass from `source` to the whole of `target`.
+    fn draw(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        pipeline: &wgpu::RenderPipeline,
+        uniform: &[u8],
+        source: &wgpu::Texture,
+        target: &wgpu::Texture,
+    ) {
+        let uniform_buffer = create_buffer_with_data(
+            device,
+            uniform,
+            wgpu::BufferUsages::UNIFORM,
+            create_debug_label!("Filter uniform buffer"),
+        );
+        let source_view = source.create_view(&Default::default());
+        let target_view = target.create_view(&Default::default());
+        let bind_group_label = create_debug_label!("Filter bind group");
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&source_view),
+                },
+            ],
+            label: bind_group_label.as_deref(),
+        });
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[wgpu::RenderPassColorAttachment {
+                view: &target_view,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: true,
+                },
+                resolve_target: None,
+            }],
+            depth_stencil_attachment: None,
+            label: create_debug_label!("Filter pass").as_deref(),
+        });
+        render_pass.set_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+/// Reads back the pixels of a filtered image after it has been submitted.
+#[cfg(not(target_family = "wasm"))]
+fn read_buffer(
+    device: &wgpu::Device,
+    buffer: &wgpu::Buffer,
+    dimensions: &BufferDimensions,
+) -> Option<Vec<u8>> {
+    let buffer_future = buffer.slice(..).map_async(wgpu::MapMode::Read);
+    device.poll(wgpu::Maintain::Wait);
+    if let Err(e) = futures::executor::block_on(buffer_future) {
+        log::error!("Unknown error reading filter buffer: {:?}", e);
+        return None;
+    }
+    let map = buffer.slice(..).get_mapped_range();
+    let mut output = Vec::with_capacity(dimensions.height * dimensions.unpadded_bytes_per_row);
+    for row in map.chunks(dimensions.padded_bytes_per_row.get() as usize) {
+        output.extend_from_slice(&row[..dimensions.unpadded_bytes_per_row]);
+    }
+    drop(map);
+    buffer.unmap();
+    Some(output)
+}
+
+/// Buffers can't be read back synchronously on the web.
+#[cfg(target_family = "wasm")]
+fn read_buffer(
+    _device: &wgpu::Device,
+    _buffer: &wgpu::Buffer,
+    _dimensions: &BufferDimensions,
+) -> Option<Vec<u8>> {
+    log::warn!("Filters can't be read back on the web");
+    None
+}
+
+fn create_filter_pipeline(
+    device: &wgpu::Device,
+    pipeline_layout: &wgpu::PipelineLayout,
+    name: &'static str,
+    src: &'static str,
+) -> wgpu::RenderPipeline {
+    const FILTER_SRC: &str = include_str!("../shaders/filter.wgsl");
+
+    let shader_label = create_debug_label!("Shader {} filter", name);
+    let shader = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+        label: shader_label.as_deref(),
+        source: wgpu::ShaderSource::Wgsl([FILTER_SRC, src].concat().into()),
+    });
+
+    let pipeline_label = create_debug_label!("{} filter pipeline", name);
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: pipeline_label.as_deref(),
+        layout: Some(pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "main_vertex",
+            buffers: &[],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "main_fragment",
+            targets: &[wgpu::ColorTargetState {
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                blend: None,
+                write_mask: wgpu::ColorWrites::ALL,
+            }],
+        }),
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+    })
+}
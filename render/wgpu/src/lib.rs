@@ -2,15 +2,17 @@ use ruffle_core::backend::render::{
     Bitmap, BitmapFormat, BitmapHandle, BitmapInfo, BitmapSource, Color, RenderBackend,
     ShapeHandle, Transform,
 };
+use ruffle_core::bitmap::filters::{apply_filters_accelerated, BitmapFilter};
 use ruffle_core::shape_utils::DistilledShape;
 use ruffle_core::swf;
+use ruffle_core::StageQuality;
 use std::{borrow::Cow, num::NonZeroU32};
 
 use bytemuck::{Pod, Zeroable};
 
 use crate::pipelines::Pipelines;
 use crate::target::{RenderTarget, RenderTargetFrame, SwapChainTarget};
-use crate::utils::{create_buffer_with_data, format_list, get_backend_names};
+use crate::utils::{create_buffer_with_data, format_list, get_backend_names, BufferDimensions};
 use enum_map::Enum;
 use ruffle_core::color_transform::ColorTransform;
 use ruffle_render_common_tess::{
@@ -24,6 +26,7 @@ type Error = Box<dyn std::error::Error>;
 mod utils;
 
 mod bitmaps;
+mod filters;
 mod globals;
 mod pipelines;
 pub mod target;
@@ -33,6 +36,7 @@ mod uniform_buffer;
 pub mod clap;
 
 use crate::bitmaps::BitmapSamplers;
+use crate::filters::FilterPipelines;
 use crate::globals::Globals;
 use crate::uniform_buffer::UniformBuffer;
 use std::collections::HashMap;
@@ -48,6 +52,7 @@ pub struct Descriptors {
     globals: Globals,
     uniform_buffers: UniformBuffer<Transforms>,
     pipelines: Pipelines,
+    filter_pipelines: FilterPipelines,
     bitmap_samplers: BitmapSamplers,
     msaa_sample_count: u32,
 }
@@ -91,6 +96,7 @@ impl Descriptors {
             globals.layout(),
             uniform_buffers.layout(),
         )?;
+        let filter_pipelines = FilterPipelines::new(&device);
 
         Ok(Self {
             device,
@@ -101,10 +107,25 @@ impl Descriptors {
             globals,
             uniform_buffers,
             pipelines,
+            filter_pipelines,
             bitmap_samplers,
             msaa_sample_count,
         })
     }
+
+    /// Recreates the pipelines to render with a different MSAA sample count.
+    fn set_msaa_sample_count(&mut self, msaa_sample_count: u32) -> Result<(), Error> {
+        self.pipelines = Pipelines::new(
+            &self.device,
+            self.surface_format,
+            msaa_sample_count,
+            self.bitmap_samplers.layout(),
+            self.globals.layout(),
+            self.uniform_buffers.layout(),
+        )?;
+        self.msaa_sample_count = msaa_sample_count;
+        Ok(())
+    }
 }
 
 pub struct WgpuRenderBackend<T: RenderTarget> {
@@ -122,11 +143,30 @@ pub struct WgpuRenderBackend<T: RenderTarget> {
     quad_ibo: wgpu::Buffer,
     quad_tex_transforms: wgpu::Buffer,
     bitmap_registry: HashMap<BitmapHandle, Bitmap>,
+
+    /// The layers that blended display objects are currently being rendered
+    /// to, innermost last.
+    blend_layers: Vec<BlendLayer>,
+
+    /// Unused blend layer textures, kept to be reused.
+    layer_textures: Vec<LayerTextures>,
+
+    /// The texture that what was rendered below a blend layer is copied to,
+    /// to blend the layer with.
+    backdrop: Option<(wgpu::Texture, wgpu::TextureView)>,
+
+    /// The bind groups used to composite blend layers this frame, which must
+    /// live until the frame is submitted.
+    blend_bind_groups: Vec<wgpu::BindGroup>,
+
+    /// The texture that an offscreen image is being rendered to instead of
+    /// the frame, if any.
+    offscreen: Option<OffscreenTarget>,
 }
 
 #[allow(dead_code)]
 struct Frame<'a, T: RenderTarget> {
-    frame_data: Box<(wgpu::CommandEncoder, T::Frame, wgpu::CommandEncoder)>,
+    frame_data: FrameData<T>,
 
     // TODO: This is a self-reference to the above, so we
     // use some unsafe to cast the lifetime away. We know this
@@ -144,6 +184,14 @@ impl<'a, T: RenderTarget> Frame<'static, T> {
     }
 }
 
+/// The draw encoder, output and uniform encoder of a frame. There is no
+/// output while rendering offscreen.
+type FrameData<T> = Box<(
+    wgpu::CommandEncoder,
+    Option<<T as RenderTarget>::Frame>,
+    wgpu::CommandEncoder,
+)>;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
 pub enum MaskState {
     NoMask,
@@ -159,6 +207,51 @@ struct Transforms {
     color_adjustments: ColorAdjustments,
 }
 
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct BlendUniforms {
+    mode: i32,
+    _padding: [i32; 3],
+}
+
+/// A layer that a blended display object is rendered to, before it is
+/// composited onto the render target below it.
+#[derive(Debug)]
+struct BlendLayer {
+    blend_mode: swf::BlendMode,
+
+    /// The textures of the layer, or `None` if the object is rendered
+    /// directly to the render target below instead.
+    textures: Option<LayerTextures>,
+
+    /// The masking state of the render target below, restored when the
+    /// layer is popped.
+    mask_state: MaskState,
+    num_masks: u32,
+}
+
+#[derive(Debug)]
+struct LayerTextures {
+    /// The multisampled buffers that the layer is rendered to.
+    color_view: wgpu::TextureView,
+    depth_view: wgpu::TextureView,
+
+    /// The texture that the color buffer is resolved to.
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+}
+
+/// An image being rendered by `render_offscreen`.
+#[derive(Debug)]
+struct OffscreenTarget {
+    /// Rendered to like a blend layer, with the size of the image.
+    textures: LayerTextures,
+
+    /// The buffer that the image is copied to, to be read back.
+    buffer: wgpu::Buffer,
+    buffer_dimensions: BufferDimensions,
+}
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Pod, Zeroable)]
 struct TextureTransforms {
@@ -221,6 +314,13 @@ impl From<TessGradient> for GradientUniforms {
         ratios[..gradient.num_colors].copy_from_slice(&gradient.ratios[..gradient.num_colors]);
         colors[..gradient.num_colors].copy_from_slice(&gradient.colors[..gradient.num_colors]);
 
+        // Pad with the last color, so that the shader never reads past it. A
+        // gradient without any colors is transparent.
+        for i in gradient.num_colors.max(1)..16 {
+            ratios[i] = ratios[i - 1];
+            colors[i] = colors[i - 1];
+        }
+
         Self {
             colors,
             ratios,
@@ -229,14 +329,14 @@ impl From<TessGradient> for GradientUniforms {
                 GradientType::Radial => 1,
                 GradientType::Focal => 2,
             },
-            num_colors: gradient.num_colors as u32,
+            num_colors: gradient.num_colors.max(1) as u32,
             repeat_mode: match gradient.repeat_mode {
                 swf::GradientSpread::Pad => 0,
                 swf::GradientSpread::Repeat => 1,
                 swf::GradientSpread::Reflect => 2,
             },
             interpolation: (gradient.interpolation == swf::GradientInterpolation::LinearRgb) as i32,
-            focal_point: gradient.focal_point.to_f32(),
+            focal_point: gradient.focal_point,
         }
     }
 }
@@ -410,6 +510,12 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
             quad_ibo,
             quad_tex_transforms,
             bitmap_registry: HashMap::new(),
+
+            blend_layers: Vec::new(),
+            layer_textures: Vec::new(),
+            backdrop: None,
+            blend_bind_groups: Vec::new(),
+            offscreen: None,
         })
     }
 
@@ -658,6 +764,22 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
     }
 
     fn register_bitmap(&mut self, bitmap: Bitmap, debug_str: &str) -> BitmapInfo {
+        let handle = BitmapHandle(self.textures.len());
+        let width = bitmap.width;
+        let height = bitmap.height;
+        let texture = self.create_texture(&bitmap, handle, debug_str);
+        self.bitmap_registry.insert(handle, bitmap);
+        self.textures.push(texture);
+
+        BitmapInfo {
+            handle,
+            width: width as u16,
+            height: height as u16,
+        }
+    }
+
+    /// Create a texture and its bind group holding the given bitmap.
+    fn create_texture(&self, bitmap: &Bitmap, handle: BitmapHandle, debug_str: &str) -> Texture {
         let extent = wgpu::Extent3d {
             width: bitmap.width,
             height: bitmap.height,
@@ -710,10 +832,6 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
             extent,
         );
 
-        let handle = BitmapHandle(self.textures.len());
-        let width = bitmap.width;
-        let height = bitmap.height;
-
         // Make bind group for bitmap quad.
         let texture_view = texture.create_view(&Default::default());
         let bind_group = self
@@ -740,18 +858,11 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
                 label: create_debug_label!("Bitmap {} bind group", handle.0).as_deref(),
             });
 
-        self.bitmap_registry.insert(handle, bitmap);
-        self.textures.push(Texture {
-            width,
-            height,
+        Texture {
+            width: bitmap.width,
+            height: bitmap.height,
             texture,
             bind_group,
-        });
-
-        BitmapInfo {
-            handle,
-            width: width as u16,
-            height: height as u16,
         }
     }
 
@@ -762,16 +873,121 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
     pub fn device(&self) -> &wgpu::Device {
         &self.descriptors.device
     }
-}
 
-impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
-    fn set_viewport_dimensions(&mut self, width: u32, height: u32) {
-        // Avoid panics from creating 0-sized framebuffers.
-        let width = std::cmp::max(width, 1);
-        let height = std::cmp::max(height, 1);
+    /// The size of what is currently being rendered.
+    fn viewport_size(&self) -> (u32, u32) {
+        match &self.offscreen {
+            Some(offscreen) => (
+                offscreen.buffer_dimensions.width as u32,
+                offscreen.buffer_dimensions.height as u32,
+            ),
+            None => (self.target.width(), self.target.height()),
+        }
+    }
 
-        self.target.resize(&self.descriptors.device, width, height);
+    /// Creates the encoders of a new frame, and uploads the globals for the
+    /// current viewport.
+    fn create_frame_data(&mut self, frame_output: Option<T::Frame>) -> FrameData<T> {
+        let label = create_debug_label!("Draw encoder");
+        let draw_encoder =
+            self.descriptors
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: label.as_deref(),
+                });
+        let uniform_encoder_label = create_debug_label!("Uniform upload command encoder");
+        let uniform_encoder =
+            self.descriptors
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: uniform_encoder_label.as_deref(),
+                });
+        let mut frame_data = Box::new((draw_encoder, frame_output, uniform_encoder));
+
+        self.descriptors
+            .globals
+            .update_uniform(&self.descriptors.device, &mut frame_data.0);
+        frame_data
+    }
+
+    /// Begins a render pass of the current frame, which draws to the
+    /// innermost blend layer, or to the offscreen image or frame buffer if
+    /// there is none.
+    ///
+    /// If `clear` is given, the render target and its stencil buffer are
+    /// cleared first. Otherwise, the render pass draws on top of what was
+    /// rendered before it.
+    fn begin_render_pass(&mut self, mut frame_data: FrameData<T>, clear: Option<wgpu::Color>) {
+        let (draw_encoder, frame_output, _) = &mut *frame_data;
+
+        let layer = self
+            .blend_layers
+            .iter()
+            .rev()
+            .find_map(|layer| layer.textures.as_ref())
+            .or_else(|| self.offscreen.as_ref().map(|offscreen| &offscreen.textures));
+        let msaa = self.descriptors.msaa_sample_count >= 2;
+        let (color_view, resolve_target, depth_view) = match (layer, frame_output) {
+            (Some(layer), _) if msaa => (&layer.color_view, Some(&layer.view), &layer.depth_view),
+            (Some(layer), _) => (&layer.view, None, &layer.depth_view),
+            (None, Some(frame_output)) if msaa => (
+                &self.frame_buffer_view,
+                Some(frame_output.view()),
+                &self.depth_texture_view,
+            ),
+            (None, Some(frame_output)) => (frame_output.view(), None, &self.depth_texture_view),
+            (None, None) => {
+                log::error!("No render target to begin a render pass with");
+                return;
+            }
+        };
+
+        let (color_load, depth_load, stencil_load) = match clear {
+            Some(color) => (
+                wgpu::LoadOp::Clear(color),
+                wgpu::LoadOp::Clear(0.0),
+                wgpu::LoadOp::Clear(0),
+            ),
+            None => (wgpu::LoadOp::Load, wgpu::LoadOp::Load, wgpu::LoadOp::Load),
+        };
+
+        let render_pass = draw_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[wgpu::RenderPassColorAttachment {
+                view: color_view,
+                ops: wgpu::Operations {
+                    load: color_load,
+                    store: true,
+                },
+                resolve_target,
+            }],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: depth_load,
+                    store: true,
+                }),
+                stencil_ops: Some(wgpu::Operations {
+                    load: stencil_load,
+                    store: true,
+                }),
+            }),
+            label: None,
+        });
 
+        // Since RenderPass holds a reference to the CommandEncoder, we cast the lifetime
+        // away to allow for the self-referencing struct. draw_encoder is boxed so its
+        // address should remain stable.
+        self.current_frame = Some(Frame {
+            render_pass: unsafe {
+                std::mem::transmute::<_, wgpu::RenderPass<'static>>(render_pass)
+            },
+            frame_data,
+        });
+    }
+
+    /// Creates the multisampled frame buffer and depth texture, and drops the
+    /// blend layer textures that must match them.
+    fn create_frame_buffers(&mut self, width: u32, height: u32) {
         let label = create_debug_label!("Framebuffer texture");
         let frame_buffer = self
             .descriptors
@@ -810,6 +1026,304 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
             });
         self.depth_texture_view = depth_texture.create_view(&Default::default());
         self.descriptors.globals.set_resolution(width, height);
+
+        // Blend layers are the size of the viewport.
+        self.layer_textures.clear();
+        self.backdrop = None;
+    }
+
+    /// Creates a texture to render to.
+    fn create_render_texture(
+        &self,
+        label: Option<String>,
+        (width, height): (u32, u32),
+        sample_count: u32,
+        format: wgpu::TextureFormat,
+        usage: wgpu::TextureUsages,
+    ) -> wgpu::Texture {
+        self.descriptors
+            .device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: label.as_deref(),
+                size: wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage,
+            })
+    }
+
+    fn create_layer_textures(&self, size: (u32, u32)) -> LayerTextures {
+        let color_texture = self.create_render_texture(
+            create_debug_label!("Blend layer color texture"),
+            size,
+            self.descriptors.msaa_sample_count,
+            self.target.format(),
+            wgpu::TextureUsages::RENDER_ATTACHMENT,
+        );
+        let depth_texture = self.create_render_texture(
+            create_debug_label!("Blend layer depth texture"),
+            size,
+            self.descriptors.msaa_sample_count,
+            wgpu::TextureFormat::Depth24PlusStencil8,
+            wgpu::TextureUsages::RENDER_ATTACHMENT,
+        );
+        let texture = self.create_render_texture(
+            create_debug_label!("Blend layer texture"),
+            size,
+            1,
+            self.target.format(),
+            wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC,
+        );
+
+        LayerTextures {
+            color_view: color_texture.create_view(&Default::default()),
+            depth_view: depth_texture.create_view(&Default::default()),
+            view: texture.create_view(&Default::default()),
+            texture,
+        }
+    }
+
+    /// Copies what was rendered below the innermost blend layer to the
+    /// backdrop texture.
+    fn copy_backdrop(&mut self, draw_encoder: &mut wgpu::CommandEncoder) {
+        if self.backdrop.is_none() {
+            let texture = self.create_render_texture(
+                create_debug_label!("Blend backdrop texture"),
+                self.viewport_size(),
+                1,
+                self.target.format(),
+                wgpu::TextureUsages::RENDER_ATTACHMENT
+                    | wgpu::TextureUsages::TEXTURE_BINDING
+                    | wgpu::TextureUsages::COPY_DST,
+            );
+            let view = texture.create_view(&Default::default());
+            self.backdrop = Some((texture, view));
+        }
+        let (backdrop_texture, backdrop_view) = self.backdrop.as_ref().unwrap();
+
+        let parent = self
+            .blend_layers
+            .iter()
+            .rev()
+            .find_map(|layer| layer.textures.as_ref())
+            .or_else(|| self.offscreen.as_ref().map(|offscreen| &offscreen.textures));
+        let (width, height) = self.viewport_size();
+        if let Some(parent) = parent {
+            // The parent layer was resolved to its texture when its render
+            // pass ended.
+            draw_encoder.copy_texture_to_texture(
+                wgpu::ImageCopyTexture {
+                    texture: &parent.texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                wgpu::ImageCopyTexture {
+                    texture: backdrop_texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+            );
+        } else {
+            // The frame buffer is resolved to the surface, which can't be
+            // read from, so resolve it to the backdrop as well.
+            drop(draw_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                color_attachments: &[wgpu::RenderPassColorAttachment {
+                    view: &self.frame_buffer_view,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: true,
+                    },
+                    resolve_target: Some(backdrop_view),
+                }],
+                depth_stencil_attachment: None,
+                label: create_debug_label!("Blend backdrop resolve").as_deref(),
+            }));
+        }
+    }
+
+    /// Reads back the RGBA pixels of an offscreen image after it has been
+    /// submitted.
+    #[cfg(not(target_family = "wasm"))]
+    fn read_offscreen(&self, offscreen: &OffscreenTarget) -> Option<Vec<u8>> {
+        let buffer_future = offscreen.buffer.slice(..).map_async(wgpu::MapMode::Read);
+        self.descriptors.device.poll(wgpu::Maintain::Wait);
+        if let Err(e) = futures::executor::block_on(buffer_future) {
+            log::error!("Unknown error reading offscreen buffer: {:?}", e);
+            return None;
+        }
+
+        let dimensions = &offscreen.buffer_dimensions;
+        let map = offscreen.buffer.slice(..).get_mapped_range();
+        let mut pixels = Vec::with_capacity(dimensions.height * dimensions.unpadded_bytes_per_row);
+        for row in map.chunks(dimensions.padded_bytes_per_row.get() as usize) {
+            pixels.extend_from_slice(&row[..dimensions.unpadded_bytes_per_row]);
+        }
+        drop(map);
+        offscreen.buffer.unmap();
+
+        if matches!(
+            self.target.format(),
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+        ) {
+            pixels
+                .chunks_exact_mut(4)
+                .for_each(|pixel| pixel.swap(0, 2));
+        }
+        Some(pixels)
+    }
+
+    /// Buffers can't be read back synchronously on the web.
+    #[cfg(target_family = "wasm")]
+    fn read_offscreen(&self, _offscreen: &OffscreenTarget) -> Option<Vec<u8>> {
+        log::warn!("Offscreen rendering isn't supported on the web");
+        None
+    }
+
+    /// Composites a blend layer onto the current render target, blending it
+    /// with the backdrop texture.
+    fn draw_blend_layer(&mut self, layer: &LayerTextures, blend_mode: swf::BlendMode) {
+        let frame = if let Some(frame) = &mut self.current_frame {
+            frame.get()
+        } else {
+            return;
+        };
+        let backdrop_view = if let Some((_, view)) = &self.backdrop {
+            view
+        } else {
+            return;
+        };
+
+        let uniforms = create_buffer_with_data(
+            &self.descriptors.device,
+            bytemuck::cast_slice(&[BlendUniforms {
+                mode: blend_mode as i32,
+                _padding: [0; 3],
+            }]),
+            wgpu::BufferUsages::UNIFORM,
+            create_debug_label!("Blend uniforms"),
+        );
+        let bind_group =
+            self.descriptors
+                .device
+                .create_bind_group(&wgpu::BindGroupDescriptor {
+                    layout: &self.descriptors.pipelines.blend_layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                                buffer: &uniforms,
+                                offset: 0,
+                                size: wgpu::BufferSize::new(
+                                    std::mem::size_of::<BlendUniforms>() as u64
+                                ),
+                            }),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: wgpu::BindingResource::TextureView(&layer.view),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 2,
+                            resource: wgpu::BindingResource::TextureView(backdrop_view),
+                        },
+                    ],
+                    label: create_debug_label!("Blend layer bind group").as_deref(),
+                });
+        self.blend_bind_groups.push(bind_group);
+        let bind_group = self.blend_bind_groups.last().unwrap();
+
+        // Cover the whole viewport.
+        let (width, height) = self.viewport_size();
+        let world_matrix = [
+            [width as f32, 0.0, 0.0, 0.0],
+            [0.0, height as f32, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+
+        frame.render_pass.set_pipeline(
+            self.descriptors
+                .pipelines
+                .blend_pipelines
+                .pipeline_for(self.mask_state),
+        );
+        frame
+            .render_pass
+            .set_bind_group(0, self.descriptors.globals.bind_group(), &[]);
+
+        self.descriptors.uniform_buffers.write_uniforms(
+            &self.descriptors.device,
+            &mut frame.frame_data.2,
+            &mut frame.render_pass,
+            1,
+            &Transforms {
+                world_matrix,
+                color_adjustments: ColorAdjustments::from(ColorTransform::default()),
+            },
+        );
+
+        frame.render_pass.set_bind_group(2, bind_group, &[]);
+        frame.render_pass.set_bind_group(
+            3,
+            self.descriptors
+                .bitmap_samplers
+                .get_bind_group(false, false),
+            &[],
+        );
+        frame
+            .render_pass
+            .set_vertex_buffer(0, self.quad_vbo.slice(..));
+        frame
+            .render_pass
+            .set_index_buffer(self.quad_ibo.slice(..), wgpu::IndexFormat::Uint32);
+
+        if self.mask_state == MaskState::DrawMaskedContent {
+            debug_assert!(self.num_masks > 0);
+            frame.render_pass.set_stencil_reference(self.num_masks);
+        }
+
+        frame.render_pass.draw_indexed(0..6, 0, 0..1);
+    }
+}
+
+impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
+    fn set_viewport_dimensions(&mut self, width: u32, height: u32) {
+        // Avoid panics from creating 0-sized framebuffers.
+        let width = std::cmp::max(width, 1);
+        let height = std::cmp::max(height, 1);
+
+        self.target.resize(&self.descriptors.device, width, height);
+        self.create_frame_buffers(width, height);
+    }
+
+    fn set_quality(&mut self, quality: StageQuality) {
+        // wgpu only guarantees support for 1 or 4 samples, so any anti-aliasing uses 4.
+        let msaa_sample_count = if quality.sample_count() >= 2 { 4 } else { 1 };
+        if msaa_sample_count == self.descriptors.msaa_sample_count {
+            return;
+        }
+
+        if let Err(e) = self.descriptors.set_msaa_sample_count(msaa_sample_count) {
+            log::error!("Couldn't change the MSAA sample count: {}", e);
+            return;
+        }
+        // The frame buffers are multisampled to match the pipelines.
+        self.create_frame_buffers(self.target.width(), self.target.height());
     }
 
     fn register_shape(
@@ -880,6 +1394,10 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
         self.mask_state = MaskState::NoMask;
         self.num_masks = 0;
         self.descriptors.uniform_buffers.reset();
+        for layer in self.blend_layers.drain(..) {
+            self.layer_textures.extend(layer.textures);
+        }
+        self.blend_bind_groups.clear();
 
         let frame_output = match self.target.get_next_texture() {
             Ok(frame) => frame,
@@ -895,69 +1413,16 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
             }
         };
 
-        let label = create_debug_label!("Draw encoder");
-        let draw_encoder =
-            self.descriptors
-                .device
-                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                    label: label.as_deref(),
-                });
-        let uniform_encoder_label = create_debug_label!("Uniform upload command encoder");
-        let uniform_encoder =
-            self.descriptors
-                .device
-                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                    label: uniform_encoder_label.as_deref(),
-                });
-        let mut frame_data = Box::new((draw_encoder, frame_output, uniform_encoder));
-
-        self.descriptors
-            .globals
-            .update_uniform(&self.descriptors.device, &mut frame_data.0);
-
-        let (color_view, resolve_target) = if self.descriptors.msaa_sample_count >= 2 {
-            (&self.frame_buffer_view, Some(frame_data.1.view()))
-        } else {
-            (frame_data.1.view(), None)
-        };
-
-        let render_pass = frame_data.0.begin_render_pass(&wgpu::RenderPassDescriptor {
-            color_attachments: &[wgpu::RenderPassColorAttachment {
-                view: color_view,
-                ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(wgpu::Color {
-                        r: f64::from(clear.r) / 255.0,
-                        g: f64::from(clear.g) / 255.0,
-                        b: f64::from(clear.b) / 255.0,
-                        a: f64::from(clear.a) / 255.0,
-                    }),
-                    store: true,
-                },
-                resolve_target,
-            }],
-            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-                view: &self.depth_texture_view,
-                depth_ops: Some(wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(0.0),
-                    store: true,
-                }),
-                stencil_ops: Some(wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(0),
-                    store: true,
-                }),
-            }),
-            label: None,
-        });
-
-        // Since RenderPass holds a reference to the CommandEncoder, we cast the lifetime
-        // away to allow for the self-referencing struct. draw_encoder is boxed so its
-        // address should remain stable.
-        self.current_frame = Some(Frame {
-            render_pass: unsafe {
-                std::mem::transmute::<_, wgpu::RenderPass<'static>>(render_pass)
-            },
+        let frame_data = self.create_frame_data(Some(frame_output));
+        self.begin_render_pass(
             frame_data,
-        });
+            Some(wgpu::Color {
+                r: f64::from(clear.r) / 255.0,
+                g: f64::from(clear.g) / 255.0,
+                b: f64::from(clear.b) / 255.0,
+                a: f64::from(clear.a) / 255.0,
+            }),
+        );
     }
 
     fn render_bitmap(&mut self, bitmap: BitmapHandle, transform: &Transform, smoothing: bool) {
@@ -1224,14 +1689,15 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
             // Finalize render pass.
             drop(frame.render_pass);
             self.descriptors.uniform_buffers.finish();
-            let draw_encoder = frame.frame_data.0;
-            let uniform_encoder = frame.frame_data.2;
-            self.target.submit(
-                &self.descriptors.device,
-                &self.descriptors.queue,
-                vec![uniform_encoder.finish(), draw_encoder.finish()],
-                frame.frame_data.1,
-            );
+            let (draw_encoder, frame_output, uniform_encoder) = *frame.frame_data;
+            if let Some(frame_output) = frame_output {
+                self.target.submit(
+                    &self.descriptors.device,
+                    &self.descriptors.queue,
+                    vec![uniform_encoder.finish(), draw_encoder.finish()],
+                    frame_output,
+                );
+            }
         }
     }
 
@@ -1263,6 +1729,230 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
         };
     }
 
+    fn push_blend_mode(&mut self, blend_mode: swf::BlendMode) {
+        // Blending needs to read back what was rendered below the layer,
+        // which is only possible from the multisampled frame buffer, as the
+        // surface can't be read from. Masks only draw to the stencil buffer,
+        // so they are never blended.
+        let can_blend = self.descriptors.msaa_sample_count >= 2
+            && self.current_frame.is_some()
+            && matches!(
+                self.mask_state,
+                MaskState::NoMask | MaskState::DrawMaskedContent
+            );
+        let textures = if can_blend {
+            Some(
+                self.layer_textures
+                    .pop()
+                    .unwrap_or_else(|| self.create_layer_textures(self.viewport_size())),
+            )
+        } else {
+            None
+        };
+
+        let has_textures = textures.is_some();
+        self.blend_layers.push(BlendLayer {
+            blend_mode,
+            textures,
+            mask_state: self.mask_state,
+            num_masks: self.num_masks,
+        });
+
+        if has_textures {
+            // The layer starts out transparent and unmasked.
+            self.mask_state = MaskState::NoMask;
+            self.num_masks = 0;
+            if let Some(frame) = self.current_frame.take() {
+                drop(frame.render_pass);
+                self.begin_render_pass(frame.frame_data, Some(wgpu::Color::TRANSPARENT));
+            }
+        }
+    }
+
+    fn pop_blend_mode(&mut self) {
+        let layer = if let Some(layer) = self.blend_layers.pop() {
+            layer
+        } else {
+            log::error!("Blend layer stack underflow");
+            return;
+        };
+
+        self.mask_state = layer.mask_state;
+        self.num_masks = layer.num_masks;
+
+        let textures = if let Some(textures) = layer.textures {
+            textures
+        } else {
+            return;
+        };
+
+        if let Some(frame) = self.current_frame.take() {
+            // Ending the render pass of the layer resolves it to its texture.
+            drop(frame.render_pass);
+            let mut frame_data = frame.frame_data;
+            self.copy_backdrop(&mut frame_data.0);
+            self.begin_render_pass(frame_data, None);
+            self.draw_blend_layer(&textures, layer.blend_mode);
+        }
+        self.layer_textures.push(textures);
+    }
+
+    fn render_offscreen(
+        &mut self,
+        width: u32,
+        height: u32,
+        clear: Color,
+        draw: &mut dyn FnMut(&mut dyn RenderBackend),
+    ) -> Option<Bitmap> {
+        if width == 0 || height == 0 {
+            return None;
+        }
+        // Textures larger than the device allows can't be created, so the
+        // image is cut down to the largest size that can be.
+        let max_size = self.descriptors.limits.max_texture_dimension_2d;
+        let width = width.min(max_size);
+        let height = height.min(max_size);
+
+        // This may be called while rendering a frame or another offscreen
+        // image, such as for a clip cached as a bitmap inside a mask. What
+        // was drawn so far is submitted, as the globals and blend textures
+        // are shared, and the frame is continued afterwards.
+        let frame_output = self.current_frame.take().map(|frame| {
+            drop(frame.render_pass);
+            self.descriptors.uniform_buffers.finish();
+            let (draw_encoder, frame_output, uniform_encoder) = *frame.frame_data;
+            self.descriptors
+                .queue
+                .submit(vec![uniform_encoder.finish(), draw_encoder.finish()]);
+            frame_output
+        });
+        let mask_state = (self.mask_state, self.num_masks);
+        let blend_layers = std::mem::take(&mut self.blend_layers);
+        let layer_textures = std::mem::take(&mut self.layer_textures);
+        let backdrop = self.backdrop.take();
+        let parent_offscreen = self.offscreen.take();
+
+        let buffer_dimensions = BufferDimensions::new(width as usize, height as usize);
+        let buffer_label = create_debug_label!("Offscreen buffer");
+        let buffer = self
+            .descriptors
+            .device
+            .create_buffer(&wgpu::BufferDescriptor {
+                label: buffer_label.as_deref(),
+                size: buffer_dimensions.padded_bytes_per_row.get() as u64 * height as u64,
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            });
+        self.offscreen = Some(OffscreenTarget {
+            textures: self.create_layer_textures((width, height)),
+            buffer,
+            buffer_dimensions,
+        });
+        self.mask_state = MaskState::NoMask;
+        self.num_masks = 0;
+        self.descriptors.globals.set_resolution(width, height);
+
+        // The image is pre-multiplied.
+        let alpha = f64::from(clear.a) / 255.0;
+        let frame_data = self.create_frame_data(None);
+        self.begin_render_pass(
+            frame_data,
+            Some(wgpu::Color {
+                r: f64::from(clear.r) / 255.0 * alpha,
+                g: f64::from(clear.g) / 255.0 * alpha,
+                b: f64::from(clear.b) / 255.0 * alpha,
+                a: alpha,
+            }),
+        );
+        draw(self);
+
+        // Blend layers left pushed by `draw` are discarded.
+        self.blend_layers.clear();
+        let offscreen = self.offscreen.take().unwrap();
+        if let Some(frame) = self.current_frame.take() {
+            // Ending the render pass resolves the image to its texture.
+            drop(frame.render_pass);
+            self.descriptors.uniform_buffers.finish();
+            let (mut draw_encoder, _, uniform_encoder) = *frame.frame_data;
+            draw_encoder.copy_texture_to_buffer(
+                wgpu::ImageCopyTexture {
+                    texture: &offscreen.textures.texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                wgpu::ImageCopyBuffer {
+                    buffer: &offscreen.buffer,
+                    layout: wgpu::ImageDataLayout {
+                        offset: 0,
+                        bytes_per_row: Some(offscreen.buffer_dimensions.padded_bytes_per_row),
+                        rows_per_image: None,
+                    },
+                },
+                wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+            );
+            self.descriptors
+                .queue
+                .submit(vec![uniform_encoder.finish(), draw_encoder.finish()]);
+        }
+        let pixels = self.read_offscreen(&offscreen);
+
+        self.mask_state = mask_state.0;
+        self.num_masks = mask_state.1;
+        self.blend_layers = blend_layers;
+        self.layer_textures = layer_textures;
+        self.backdrop = backdrop;
+        self.offscreen = parent_offscreen;
+        let (viewport_width, viewport_height) = self.viewport_size();
+        self.descriptors
+            .globals
+            .set_resolution(viewport_width, viewport_height);
+        if let Some(frame_output) = frame_output {
+            let frame_data = self.create_frame_data(Some(frame_output));
+            self.begin_render_pass(frame_data, None);
+        }
+
+        pixels.map(|mut pixels| {
+            ruffle_core::backend::render::unmultiply_alpha_rgba(&mut pixels);
+            Bitmap {
+                width,
+                height,
+                data: BitmapFormat::Rgba(pixels),
+            }
+        })
+    }
+
+    fn apply_filters(
+        &mut self,
+        filters: &[BitmapFilter],
+        width: u32,
+        height: u32,
+        rgba: &[u8],
+    ) -> Vec<u8> {
+        let descriptors = &self.descriptors;
+        apply_filters_accelerated(
+            filters,
+            width,
+            height,
+            rgba,
+            FilterPipelines::supports,
+            |filters, pixels| {
+                descriptors.filter_pipelines.apply(
+                    &descriptors.device,
+                    &descriptors.queue,
+                    filters,
+                    width,
+                    height,
+                    pixels,
+                )
+            },
+        )
+    }
+
     fn get_bitmap_pixels(&mut self, bitmap: BitmapHandle) -> Option<Bitmap> {
         self.bitmap_registry.get(&bitmap).cloned()
     }
@@ -1290,14 +1980,29 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
         handle: BitmapHandle,
         width: u32,
         height: u32,
-        rgba: Vec<u8>,
+        rgba: &[u8],
     ) -> Result<BitmapHandle, Error> {
+        ruffle_core::backend::render::check_rgba_len(width, height, rgba)?;
+
         let texture = if let Some(texture) = self.textures.get(handle.0) {
-            &texture.texture
+            texture
         } else {
             return Err("update_texture: Bitmap not registered".into());
         };
 
+        // A texture can't be resized, so a new one replaces it under the same handle.
+        if texture.width != width || texture.height != height {
+            let bitmap = Bitmap {
+                width,
+                height,
+                data: BitmapFormat::Rgba(rgba.to_vec()),
+            };
+            self.textures[handle.0] = self.create_texture(&bitmap, handle, "RAW");
+            self.bitmap_registry.insert(handle, bitmap);
+            return Ok(handle);
+        }
+        let texture = &texture.texture;
+
         let extent = wgpu::Extent3d {
             width,
             height,
@@ -1311,7 +2016,7 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
                 origin: Default::default(),
                 aspect: wgpu::TextureAspect::All,
             },
-            &rgba,
+            rgba,
             wgpu::ImageDataLayout {
                 offset: 0,
                 bytes_per_row: NonZeroU32::new(4 * extent.width),
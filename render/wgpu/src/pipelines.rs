@@ -16,6 +16,9 @@ pub struct Pipelines {
 
     pub gradient_pipelines: ShapePipeline,
     pub gradient_layout: wgpu::BindGroupLayout,
+
+    pub blend_pipelines: ShapePipeline,
+    pub blend_layout: wgpu::BindGroupLayout,
 }
 
 impl ShapePipeline {
@@ -55,6 +58,13 @@ impl Pipelines {
             output_srgb,
         );
 
+        let blend_shader = create_shader(
+            device,
+            "blend",
+            include_str!("../shaders/blend.wgsl"),
+            output_srgb,
+        );
+
         let vertex_buffers_description = [wgpu::VertexBufferLayout {
             array_stride: std::mem::size_of::<Vertex>() as u64,
             step_mode: wgpu::VertexStepMode::Vertex,
@@ -153,12 +163,63 @@ impl Pipelines {
             &gradient_bind_layout,
         );
 
+        let blend_bind_layout_label = create_debug_label!("Blend layer bind group layout");
+        let blend_bind_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+            ],
+            label: blend_bind_layout_label.as_deref(),
+        });
+
+        let blend_pipelines = create_blend_pipelines(
+            device,
+            surface_format,
+            &blend_shader,
+            msaa_sample_count,
+            &vertex_buffers_description,
+            sampler_layout,
+            globals_layout,
+            dynamic_uniforms_layout,
+            &blend_bind_layout,
+        );
+
         Ok(Self {
             color_pipelines,
             bitmap_pipelines,
             bitmap_layout: bitmap_bind_layout,
             gradient_pipelines,
             gradient_layout: gradient_bind_layout,
+            blend_pipelines,
+            blend_layout: blend_bind_layout,
         })
     }
 }
@@ -166,7 +227,9 @@ impl Pipelines {
 /// Builds a `wgpu::ShaderModule` the given WGSL source in `src`.
 ///
 /// The source is prepended with common code in `common.wgsl` and sRGB/linear conversions in
-/// `output_srgb.wgsl`/`output_linear.wgsl`, simulating a `#include` preprocessor. We could
+/// `output_srgb.wgsl`/`output_linear.wgsl`, simulating a `#include` preprocessor. The latter
+/// define `output`, which converts colors to the surface format, and `input`, which converts
+/// colors sampled from render targets back. We could
 /// possibly does this as an offline build step instead.
 fn create_shader(
     device: &wgpu::Device,
@@ -279,7 +342,7 @@ fn create_color_pipelines(
                             operation: wgpu::BlendOperation::Add,
                         },
                         alpha: wgpu::BlendComponent {
-                            src_factor: wgpu::BlendFactor::SrcAlpha,
+                            src_factor: wgpu::BlendFactor::One,
                             dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
                             operation: wgpu::BlendOperation::Add,
 
@@ -315,7 +378,7 @@ fn create_color_pipelines(
                             operation: wgpu::BlendOperation::Add,
                         },
                         alpha: wgpu::BlendComponent {
-                            src_factor: wgpu::BlendFactor::SrcAlpha,
+                            src_factor: wgpu::BlendFactor::One,
                             dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
                             operation: wgpu::BlendOperation::Add,
                         },
@@ -350,7 +413,7 @@ fn create_color_pipelines(
                             operation: wgpu::BlendOperation::Add,
                         },
                         alpha: wgpu::BlendComponent {
-                            src_factor: wgpu::BlendFactor::SrcAlpha,
+                            src_factor: wgpu::BlendFactor::One,
                             dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
                             operation: wgpu::BlendOperation::Add,
                         },
@@ -385,7 +448,7 @@ fn create_color_pipelines(
                             operation: wgpu::BlendOperation::Add,
                         },
                         alpha: wgpu::BlendComponent {
-                            src_factor: wgpu::BlendFactor::SrcAlpha,
+                            src_factor: wgpu::BlendFactor::One,
                             dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
                             operation: wgpu::BlendOperation::Add,
                         },
@@ -449,7 +512,7 @@ fn create_bitmap_pipeline(
                             operation: wgpu::BlendOperation::Add,
                         },
                         alpha: wgpu::BlendComponent {
-                            src_factor: wgpu::BlendFactor::SrcAlpha,
+                            src_factor: wgpu::BlendFactor::One,
                             dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
                             operation: wgpu::BlendOperation::Add,
                         },
@@ -484,7 +547,7 @@ fn create_bitmap_pipeline(
                             operation: wgpu::BlendOperation::Add,
                         },
                         alpha: wgpu::BlendComponent {
-                            src_factor: wgpu::BlendFactor::SrcAlpha,
+                            src_factor: wgpu::BlendFactor::One,
                             dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
                             operation: wgpu::BlendOperation::Add,
                         },
@@ -519,7 +582,7 @@ fn create_bitmap_pipeline(
                             operation: wgpu::BlendOperation::Add,
                         },
                         alpha: wgpu::BlendComponent {
-                            src_factor: wgpu::BlendFactor::SrcAlpha,
+                            src_factor: wgpu::BlendFactor::One,
                             dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
                             operation: wgpu::BlendOperation::Add,
                         },
@@ -554,7 +617,7 @@ fn create_bitmap_pipeline(
                             operation: wgpu::BlendOperation::Add,
                         },
                         alpha: wgpu::BlendComponent {
-                            src_factor: wgpu::BlendFactor::SrcAlpha,
+                            src_factor: wgpu::BlendFactor::One,
                             dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
                             operation: wgpu::BlendOperation::Add,
                         },
@@ -616,7 +679,7 @@ fn create_gradient_pipeline(
                             operation: wgpu::BlendOperation::Add,
                         },
                         alpha: wgpu::BlendComponent {
-                            src_factor: wgpu::BlendFactor::SrcAlpha,
+                            src_factor: wgpu::BlendFactor::One,
                             dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
                             operation: wgpu::BlendOperation::Add,
                         },
@@ -651,7 +714,7 @@ fn create_gradient_pipeline(
                             operation: wgpu::BlendOperation::Add,
                         },
                         alpha: wgpu::BlendComponent {
-                            src_factor: wgpu::BlendFactor::SrcAlpha,
+                            src_factor: wgpu::BlendFactor::One,
                             dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
                             operation: wgpu::BlendOperation::Add,
                         },
@@ -687,7 +750,7 @@ fn create_gradient_pipeline(
                             operation: wgpu::BlendOperation::Add,
                         },
                         alpha: wgpu::BlendComponent {
-                            src_factor: wgpu::BlendFactor::SrcAlpha,
+                            src_factor: wgpu::BlendFactor::One,
                             dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
                             operation: wgpu::BlendOperation::Add,
                         },
@@ -722,7 +785,7 @@ fn create_gradient_pipeline(
                             operation: wgpu::BlendOperation::Add,
                         },
                         alpha: wgpu::BlendComponent {
-                            src_factor: wgpu::BlendFactor::SrcAlpha,
+                            src_factor: wgpu::BlendFactor::One,
                             dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
                             operation: wgpu::BlendOperation::Add,
                         },
@@ -738,6 +801,63 @@ fn create_gradient_pipeline(
     ShapePipeline { mask_pipelines }
 }
 
+/// Creates the pipelines that composite blend layers.
+///
+/// The blend shader blends the layer with the backdrop itself, so the
+/// result replaces what's in the render target.
+#[allow(clippy::too_many_arguments)]
+fn create_blend_pipelines(
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    shader: &wgpu::ShaderModule,
+    msaa_sample_count: u32,
+    vertex_buffers_layout: &[wgpu::VertexBufferLayout<'_>],
+    sampler_layout: &wgpu::BindGroupLayout,
+    globals_layout: &wgpu::BindGroupLayout,
+    dynamic_uniforms_layout: &wgpu::BindGroupLayout,
+    blend_bind_layout: &wgpu::BindGroupLayout,
+) -> ShapePipeline {
+    let pipeline_layout_label = create_debug_label!("Blend pipeline layout");
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: pipeline_layout_label.as_deref(),
+        bind_group_layouts: &[
+            globals_layout,
+            dynamic_uniforms_layout,
+            blend_bind_layout,
+            sampler_layout,
+        ],
+        push_constant_ranges: &[],
+    });
+
+    let mask_pipelines = enum_map! {
+        mask_state => {
+            let (stencil, write_mask) = mask_render_state(mask_state);
+            device.create_render_pipeline(&create_pipeline_descriptor(
+                create_debug_label!("Blend pipeline {:?}", mask_state).as_deref(),
+                shader,
+                shader,
+                &pipeline_layout,
+                Some(wgpu::DepthStencilState {
+                    format: wgpu::TextureFormat::Depth24PlusStencil8,
+                    depth_write_enabled: false,
+                    depth_compare: wgpu::CompareFunction::Always,
+                    stencil,
+                    bias: Default::default(),
+                }),
+                &[wgpu::ColorTargetState {
+                    format,
+                    blend: None,
+                    write_mask,
+                }],
+                vertex_buffers_layout,
+                msaa_sample_count,
+            ))
+        }
+    };
+
+    ShapePipeline { mask_pipelines }
+}
+
 fn mask_render_state(state: MaskState) -> (wgpu::StencilState, wgpu::ColorWrites) {
     let (stencil_state, color_write) = match state {
         MaskState::NoMask => (
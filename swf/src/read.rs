@@ -1166,7 +1166,7 @@ impl<'a> Reader<'a> {
         })
     }
 
-    fn read_define_font_align_zones(&mut self) -> Result<Tag<'a>> {
+    pub fn read_define_font_align_zones(&mut self) -> Result<Tag<'a>> {
         let id = self.read_character_id()?;
         let thickness = FontThickness::from_u8(self.read_u8()? >> 6)
             .ok_or_else(|| Error::invalid_data("Invalid font thickness type."))?;
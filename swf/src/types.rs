@@ -744,6 +744,54 @@ impl BlendMode {
     }
 }
 
+impl std::fmt::Display for BlendMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            BlendMode::Normal => "normal",
+            BlendMode::Layer => "layer",
+            BlendMode::Multiply => "multiply",
+            BlendMode::Screen => "screen",
+            BlendMode::Lighten => "lighten",
+            BlendMode::Darken => "darken",
+            BlendMode::Difference => "difference",
+            BlendMode::Add => "add",
+            BlendMode::Subtract => "subtract",
+            BlendMode::Invert => "invert",
+            BlendMode::Alpha => "alpha",
+            BlendMode::Erase => "erase",
+            BlendMode::Overlay => "overlay",
+            BlendMode::HardLight => "hardlight",
+        };
+        f.write_str(name)
+    }
+}
+
+impl std::str::FromStr for BlendMode {
+    type Err = ();
+
+    /// Parses the ActionScript name of a blend mode, such as `"multiply"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let blend_mode = match s {
+            "normal" => BlendMode::Normal,
+            "layer" => BlendMode::Layer,
+            "multiply" => BlendMode::Multiply,
+            "screen" => BlendMode::Screen,
+            "lighten" => BlendMode::Lighten,
+            "darken" => BlendMode::Darken,
+            "difference" => BlendMode::Difference,
+            "add" => BlendMode::Add,
+            "subtract" => BlendMode::Subtract,
+            "invert" => BlendMode::Invert,
+            "alpha" => BlendMode::Alpha,
+            "erase" => BlendMode::Erase,
+            "overlay" => BlendMode::Overlay,
+            "hardlight" => BlendMode::HardLight,
+            _ => return Err(()),
+        };
+        Ok(blend_mode)
+    }
+}
+
 /// An clip action (a.k.a. clip event) placed on a MovieClip instance.
 /// Created in the Flash IDE using `onClipEvent` or `on` blocks.
 ///
@@ -1151,6 +1199,7 @@ pub enum AudioCompression {
     Nellymoser16Khz = 4,
     Nellymoser8Khz = 5,
     Nellymoser = 6,
+    Aac = 10,
     Speex = 11,
 }
 
@@ -1498,11 +1547,17 @@ impl VideoDeblocking {
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive)]
 pub enum VideoCodec {
+    /// Standalone JPEG images, which are only written by a few obscure
+    /// encoders.
+    Jpeg = 1,
     H263 = 2,
     ScreenVideo = 3,
     Vp6 = 4,
     Vp6WithAlpha = 5,
     ScreenVideoV2 = 6,
+
+    /// H.264, which is only found in FLV files and never in SWF video streams.
+    H264 = 7,
 }
 
 impl VideoCodec {
@@ -1562,3 +1617,19 @@ pub struct NameCharacter<'a> {
     pub id: CharacterId,
     pub name: &'a SwfStr,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blend_mode_names() {
+        for n in 1..=14 {
+            let blend_mode = BlendMode::from_u8(n).unwrap();
+            assert_eq!(blend_mode.to_string().parse(), Ok(blend_mode));
+        }
+        assert_eq!("hardlight".parse(), Ok(BlendMode::HardLight));
+        assert_eq!("HardLight".parse::<BlendMode>(), Err(()));
+        assert_eq!("".parse::<BlendMode>(), Err(()));
+    }
+}
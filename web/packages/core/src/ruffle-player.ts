@@ -218,6 +218,14 @@ export class RufflePlayer extends HTMLElement {
         this.addEventListener("contextmenu", this.showContextMenu.bind(this));
         this.addEventListener("pointerdown", this.pointerDown.bind(this));
         window.addEventListener("click", this.hideContextMenu.bind(this));
+        document.addEventListener(
+            "fullscreenchange",
+            this.fullscreenChanged.bind(this)
+        );
+        document.addEventListener(
+            "webkitfullscreenchange",
+            this.fullscreenChanged.bind(this)
+        );
 
         this.instance = null;
         this.options = null;
@@ -677,6 +685,14 @@ export class RufflePlayer extends HTMLElement {
         }
     }
 
+    /**
+     * Informs the player of fullscreen changes that it didn't request,
+     * such as the user pressing Escape.
+     */
+    private fullscreenChanged(): void {
+        this.instance?.fullscreen_changed(this.isFullscreen);
+    }
+
     private pointerDown(event: PointerEvent): void {
         // Give option to disable context menu when touch support is being used
         // to avoid a long press triggering the context menu. (#1972)
@@ -1347,6 +1363,35 @@ export class RufflePlayer extends HTMLElement {
         };
     }
 
+    /**
+     * Prints the pages of a `PrintJob` through the browser's print dialog.
+     *
+     * @param pages The pages to print, as image URLs.
+     */
+    private printPages(pages: string[]): void {
+        if (pages.length === 0) {
+            return;
+        }
+        const frame = document.createElement("iframe");
+        frame.style.display = "none";
+        document.body.appendChild(frame);
+        const frameDocument = frame.contentDocument!;
+        const style = frameDocument.createElement("style");
+        style.textContent =
+            "body { margin: 0; } img { display: block; max-width: 100%; page-break-after: always; }";
+        frameDocument.head.appendChild(style);
+        const images = pages.map((url) => {
+            const image = frameDocument.createElement("img");
+            image.src = url;
+            frameDocument.body.appendChild(image);
+            return image.decode().catch(() => undefined);
+        });
+        Promise.all(images).then(() => {
+            frame.contentWindow!.print();
+            document.body.removeChild(frame);
+        });
+    }
+
     protected debugPlayerInfo(): string {
         return `Allows script access: ${
             this.options?.allowScriptAccess ?? false
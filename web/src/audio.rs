@@ -8,11 +8,22 @@ use ruffle_core::backend::audio::{
 use ruffle_web_common::JsResult;
 use std::cell::{Cell, RefCell};
 use std::rc::Rc;
+use std::sync::Arc;
 use wasm_bindgen::{closure::Closure, prelude::*, JsCast};
-use web_sys::{AudioContext, GainNode};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{AnalyserNode, AudioContext, GainNode};
 
 pub struct WebAudioBackend {
     context: AudioContext,
+
+    /// The node that all sounds are played through, before they reach the
+    /// destination of `context`.
+    output: GainNode,
+
+    /// The nodes that record the left and right channels of `output`, for
+    /// `get_recent_output`.
+    output_analysers: [AnalyserNode; 2],
+
     sounds: Arena<Sound>,
     left_samples: Vec<f32>,
     right_samples: Vec<f32>,
@@ -21,11 +32,13 @@ pub struct WebAudioBackend {
     min_sample_rate: u16,
     preload_stream_data: FnvHashMap<PreloadStreamHandle, StreamData>,
     next_stream_id: u32,
+    capture: Option<Rc<RefCell<Capture>>>,
 }
 
 thread_local! {
     static SOUND_INSTANCES: RefCell<Arena<SoundInstance>> = RefCell::new(Arena::new());
     static NUM_SOUNDS_LOADING: Cell<u32> = Cell::new(0);
+    static COMPLETED_SOUNDS: RefCell<Vec<SoundInstanceHandle>> = RefCell::new(Vec::new());
 }
 
 #[derive(Clone)]
@@ -75,6 +88,11 @@ struct Sound {
 
     /// The length of the sound data as encoded in the SWF.
     size: u32,
+
+    /// The fully decoded audio, resampled to 44.1kHz stereo.
+    ///
+    /// This is filled in the first time the sound is decoded by `decode_sound`.
+    decoded: Option<Arc<[[i16; 2]]>>,
 }
 
 type Decoder = Box<dyn Iterator<Item = [i16; 2]>>;
@@ -87,9 +105,6 @@ struct SoundInstance {
     #[allow(dead_code)]
     handle: Option<SoundHandle>,
 
-    /// Format of the sound.
-    format: swf::SoundFormat,
-
     /// On web, sounds can be played via different methods:
     /// either decoded on the fly with Decoder, or pre-decoded
     /// and played with and AudioBufferSourceNode.
@@ -110,6 +125,10 @@ struct SoundInstance {
     /// The number of times the sound data will loop.
     /// `1` means the sound plays once.
     num_loops: u16,
+
+    /// The transform to apply to the samples of a `Decoder` sound.
+    /// `AudioBuffer` sounds are transformed by gain nodes instead.
+    transform: SoundTransform,
 }
 
 /// The Drop impl ensures that the sound is stopped and remove from the audio context,
@@ -153,7 +172,12 @@ struct AudioBufferInstance {
 
 impl AudioBufferInstance {
     #[allow(clippy::float_cmp)]
-    fn set_transform(&mut self, context: &AudioContext, transform: &SoundTransform) {
+    fn set_transform(
+        &mut self,
+        context: &AudioContext,
+        output: &web_sys::AudioNode,
+        transform: &SoundTransform,
+    ) {
         let is_full_transform = transform.left_to_right != 0.0
             || transform.right_to_left != 0.0
             || transform.left_to_left != transform.right_to_right;
@@ -162,14 +186,14 @@ impl AudioBufferInstance {
         match &self.sound_transform_nodes {
             SoundTransformNodes::None => {
                 if is_full_transform {
-                    let _ = self.create_full_transform(context);
+                    let _ = self.create_full_transform(context, output);
                 } else if transform.left_to_left != 1.0 || transform.right_to_right != 1.0 {
-                    let _ = self.create_volume_transform(context);
+                    let _ = self.create_volume_transform(context, output);
                 }
             }
             SoundTransformNodes::Volume { .. } => {
                 if is_full_transform {
-                    let _ = self.create_full_transform(context);
+                    let _ = self.create_full_transform(context, output);
                 }
             }
             SoundTransformNodes::Transform { .. } => (),
@@ -201,6 +225,7 @@ impl AudioBufferInstance {
     fn create_volume_transform(
         &mut self,
         context: &AudioContext,
+        output: &web_sys::AudioNode,
     ) -> Result<(), Box<dyn std::error::Error>> {
         // Create the gain node to control the volume.
         let gain = context.create_gain().into_js_result()?;
@@ -213,8 +238,7 @@ impl AudioBufferInstance {
             .connect_with_audio_node(&gain)
             .into_js_result()?;
 
-        gain.connect_with_audio_node(&context.destination())
-            .warn_on_error();
+        gain.connect_with_audio_node(output).warn_on_error();
 
         self.node = gain.clone().into();
         self.sound_transform_nodes = SoundTransformNodes::Volume { gain };
@@ -226,6 +250,7 @@ impl AudioBufferInstance {
     fn create_full_transform(
         &mut self,
         context: &AudioContext,
+        output: &web_sys::AudioNode,
     ) -> Result<(), Box<dyn std::error::Error>> {
         // Split the left and right channels.
         let splitter = context
@@ -282,9 +307,7 @@ impl AudioBufferInstance {
             .connect_with_audio_node_and_output_and_input(&merger, 0, 1)
             .into_js_result()?;
 
-        merger
-            .connect_with_audio_node(&context.destination())
-            .warn_on_error();
+        merger.connect_with_audio_node(output).warn_on_error();
 
         self.node = merger;
         self.envelope_is_stereo = true;
@@ -317,8 +340,44 @@ enum SoundTransformNodes {
     },
 }
 
+/// Audio that is being captured from the user's microphone.
+#[derive(Default)]
+struct Capture {
+    /// The captured mono samples that have yet to be collected.
+    samples: Rc<RefCell<Vec<i16>>>,
+
+    /// Whether this capture was stopped before the user granted access to
+    /// the microphone.
+    stopped: bool,
+
+    nodes: Option<CaptureNodes>,
+}
+
+/// The nodes that feed the microphone input to a `ScriptProcessorNode`.
+struct CaptureNodes {
+    stream: web_sys::MediaStream,
+    source: web_sys::MediaStreamAudioSourceNode,
+    processor: web_sys::ScriptProcessorNode,
+    _closure: Closure<dyn FnMut(web_sys::AudioProcessingEvent)>,
+}
+
+impl Drop for CaptureNodes {
+    fn drop(&mut self) {
+        self.processor.set_onaudioprocess(None);
+        let _ = self.source.disconnect();
+        let _ = self.processor.disconnect();
+        for track in self.stream.get_tracks().iter() {
+            track.unchecked_into::<web_sys::MediaStreamTrack>().stop();
+        }
+    }
+}
+
 type Error = Box<dyn std::error::Error>;
 
+/// The number of sample frames of the output that are recorded for
+/// `get_recent_output`.
+const OUTPUT_ANALYSER_SIZE: u32 = 2048;
+
 impl WebAudioBackend {
     pub fn new() -> Result<Self, Error> {
         let context = AudioContext::new().map_err(|_| "Unable to create AudioContext")?;
@@ -334,8 +393,34 @@ impl WebAudioBackend {
         }
         log::info!("Minimum audio buffer sample rate: {}", min_sample_rate);
 
+        // Sounds are mixed in `output`, which is split up into its channels
+        // so that each can be recorded by an analyser.
+        let output = context.create_gain().into_js_result()?;
+        // Mono sounds are played on both channels.
+        output.set_channel_count(2);
+        output.set_channel_count_mode(web_sys::ChannelCountMode::Explicit);
+        output
+            .connect_with_audio_node(&context.destination())
+            .into_js_result()?;
+        let splitter = context
+            .create_channel_splitter_with_number_of_outputs(2)
+            .into_js_result()?;
+        output.connect_with_audio_node(&splitter).into_js_result()?;
+        let output_analysers = [
+            context.create_analyser().into_js_result()?,
+            context.create_analyser().into_js_result()?,
+        ];
+        for (channel, analyser) in output_analysers.iter().enumerate() {
+            analyser.set_fft_size(OUTPUT_ANALYSER_SIZE);
+            splitter
+                .connect_with_audio_node_and_output(analyser, channel as u32)
+                .into_js_result()?;
+        }
+
         Ok(Self {
             context,
+            output,
+            output_analysers,
             sounds: Arena::new(),
             preload_stream_data: FnvHashMap::default(),
             next_stream_id: 0,
@@ -344,9 +429,65 @@ impl WebAudioBackend {
             output_time: 0.0,
             frame_rate: 1.0,
             min_sample_rate,
+            capture: None,
         })
     }
 
+    /// Feeds the microphone input to `capture` once the user grants access to it.
+    fn connect_capture(
+        context: &AudioContext,
+        stream: web_sys::MediaStream,
+        capture: &mut Capture,
+        sample_rate: u32,
+    ) -> Result<(), JsValue> {
+        let source = context.create_media_stream_source(&stream)?;
+        let processor = context.create_script_processor_with_buffer_size_and_number_of_input_channels_and_number_of_output_channels(4096, 1, 1)?;
+
+        let samples = Rc::clone(&capture.samples);
+        let step = f64::from(sample_rate) / f64::from(context.sample_rate());
+        let max_samples = sample_rate as usize;
+        let mut phase = 0.0;
+        let closure = Closure::wrap(Box::new(move |event: web_sys::AudioProcessingEvent| {
+            let input = match event
+                .input_buffer()
+                .and_then(|buffer| buffer.get_channel_data(0))
+            {
+                Ok(input) => input,
+                Err(_) => return,
+            };
+
+            // Resample to the requested rate.
+            let mut samples = samples.borrow_mut();
+            for sample in input {
+                phase += step;
+                while phase >= 1.0 {
+                    samples.push((sample.clamp(-1.0, 1.0) * 32767.0) as i16);
+                    phase -= 1.0;
+                }
+            }
+
+            // Drop old samples if they aren't being collected.
+            if samples.len() > max_samples {
+                let excess = samples.len() - max_samples;
+                samples.drain(..excess);
+            }
+        }) as Box<dyn FnMut(web_sys::AudioProcessingEvent)>);
+        processor.set_onaudioprocess(Some(closure.as_ref().unchecked_ref()));
+
+        // The processor only runs while it is connected to the output, but it
+        // outputs silence.
+        source.connect_with_audio_node(&processor)?;
+        processor.connect_with_audio_node(&context.destination())?;
+
+        capture.nodes = Some(CaptureNodes {
+            stream,
+            source,
+            processor,
+            _closure: closure,
+        });
+        Ok(())
+    }
+
     /// Returns the JavaScript AudioContext.
     pub fn audio_context(&self) -> &AudioContext {
         &self.context
@@ -430,17 +571,16 @@ impl WebAudioBackend {
                     }
                 };
 
-                node.connect_with_audio_node(&self.context.destination())
-                    .warn_on_error();
+                node.connect_with_audio_node(&self.output).warn_on_error();
 
                 // Create the sound instance and add it to the active instances list.
                 let instance = SoundInstance {
                     handle: Some(handle),
-                    format: sound.format.clone(),
                     start_time: self.context.current_time(),
                     loop_start,
                     loop_end,
                     num_loops,
+                    transform: SoundTransform::default(),
                     instance_type: SoundInstanceType::AudioBuffer(AudioBufferInstance {
                         envelope_node: node.clone(),
                         envelope_is_stereo: is_stereo,
@@ -458,7 +598,10 @@ impl WebAudioBackend {
                 let ended_handler = move || {
                     SOUND_INSTANCES.with(|instances| {
                         let mut instances = instances.borrow_mut();
-                        instances.remove(instance_handle)
+                        if instances.remove(instance_handle).is_some() {
+                            COMPLETED_SOUNDS
+                                .with(|completed| completed.borrow_mut().push(instance_handle));
+                        }
                     });
                 };
                 let closure = Closure::once_into_js(Box::new(ended_handler) as Box<dyn FnMut()>);
@@ -497,17 +640,17 @@ impl WebAudioBackend {
 
                 let instance = SoundInstance {
                     handle: Some(handle),
-                    format: sound.format.clone(),
                     start_time: self.context.current_time(),
                     loop_start: 0.0,
                     loop_end: std::f64::MAX,
                     num_loops: 1,
+                    transform: SoundTransform::default(),
                     instance_type: SoundInstanceType::Decoder(decoder),
                 };
                 SOUND_INSTANCES.with(|instances| {
                     let mut instances = instances.borrow_mut();
                     let instance_handle = instances.insert(instance);
-                    let script_processor_node = self.context.create_script_processor_with_buffer_size_and_number_of_input_channels_and_number_of_output_channels(4096, 0, if sound.format.is_stereo { 2 } else { 1 }).unwrap();
+                    let script_processor_node = self.context.create_script_processor_with_buffer_size_and_number_of_input_channels_and_number_of_output_channels(4096, 0, 2).unwrap();
                     let script_node = script_processor_node.clone();
                     let closure = Closure::wrap(Box::new(move |event| {
                             SOUND_INSTANCES.with(|instances| {
@@ -516,6 +659,7 @@ impl WebAudioBackend {
                                 let complete = WebAudioBackend::update_script_processor(instance, event);
                                 if complete {
                                     instances.remove(instance_handle);
+                                    COMPLETED_SOUNDS.with(|completed| completed.borrow_mut().push(instance_handle));
                                     script_node.disconnect().unwrap();
                                 }
                             })
@@ -612,7 +756,11 @@ impl WebAudioBackend {
         num_sample_frames: u32,
         adpcm_block_offsets: Option<&[usize]>,
     ) -> Result<AudioBufferPtr, Error> {
-        if format.compression == AudioCompression::Mp3 {
+        // Browsers can decode MP3 files and ADTS AAC streams themselves.
+        if matches!(
+            format.compression,
+            AudioCompression::Mp3 | AudioCompression::Aac
+        ) {
             return Ok(self.decompress_mp3_to_audio_buffer(format, audio_data, num_sample_frames));
         }
 
@@ -788,31 +936,22 @@ impl WebAudioBackend {
         let mut complete = false;
         let mut left_samples = vec![];
         let mut right_samples = vec![];
+        let transform = instance.transform.clone();
         if let SoundInstanceType::Decoder(ref mut decoder) = &mut instance.instance_type {
             let output_buffer = event.output_buffer().unwrap();
             let num_frames = output_buffer.length() as usize;
 
             for _ in 0..num_frames {
                 if let Some(frame) = decoder.next() {
-                    let (l, r) = (frame[0], frame[1]);
-                    left_samples.push(f32::from(l) / 32767.0);
-                    if instance.format.is_stereo {
-                        right_samples.push(f32::from(r) / 32767.0);
-                    }
+                    let (l, r) = (f32::from(frame[0]) / 32767.0, f32::from(frame[1]) / 32767.0);
+                    left_samples.push(l * transform.left_to_left + r * transform.right_to_left);
+                    right_samples.push(l * transform.left_to_right + r * transform.right_to_right);
                 } else {
                     complete = true;
                     break;
                 }
             }
-            copy_to_audio_buffer(
-                &output_buffer,
-                Some(&left_samples),
-                if instance.format.is_stereo {
-                    Some(&right_samples)
-                } else {
-                    None
-                },
-            );
+            copy_to_audio_buffer(&output_buffer, Some(&left_samples), Some(&right_samples));
         }
 
         complete
@@ -845,6 +984,7 @@ impl AudioBackend for WebAudioBackend {
             skip_sample_frames,
             stream_segments: vec![],
             size: data.len() as u32,
+            decoded: None,
         };
         Ok(self.sounds.insert(sound))
     }
@@ -948,6 +1088,7 @@ impl AudioBackend for WebAudioBackend {
                         skip_sample_frames: stream.skip_sample_frames,
                         stream_segments: stream.stream_segments,
                         size: stream.audio_data.len() as u32,
+                        decoded: None,
                     });
                     return Some(handle);
                 }
@@ -1069,6 +1210,20 @@ impl AudioBackend for WebAudioBackend {
         })
     }
 
+    fn get_stream_sync_position(&self, instance: SoundInstanceHandle) -> Option<f64> {
+        // Audio doesn't advance while the context is suspended, e.g. before the user has
+        // interacted with the page, so the timeline shouldn't wait for it.
+        if self.context.state() != web_sys::AudioContextState::Running {
+            return None;
+        }
+        // The output timestamp already accounts for the latency of the audio device.
+        self.get_sound_position(instance)
+    }
+
+    fn take_completed_sounds(&mut self) -> Vec<SoundInstanceHandle> {
+        COMPLETED_SOUNDS.with(|completed| std::mem::take(&mut *completed.borrow_mut()))
+    }
+
     fn get_sound_duration(&self, sound: SoundHandle) -> Option<f64> {
         if let Some(sound) = self.sounds.get(sound) {
             // AS duration does not subtract `skip_sample_frames`.
@@ -1089,22 +1244,147 @@ impl AudioBackend for WebAudioBackend {
         self.sounds.get(sound).map(|s| &s.format)
     }
 
+    fn decode_sound(&mut self, sound: SoundHandle) -> Result<Arc<[[i16; 2]]>, Error> {
+        // MP3 sounds are decoded asynchronously by the browser, so their
+        // buffers can't be read until that has finished.
+        let is_loading_complete = self.is_loading_complete();
+        let sound = self.sounds.get_mut(sound).ok_or("Invalid sound")?;
+        if let Some(decoded) = &sound.decoded {
+            return Ok(Arc::clone(decoded));
+        }
+
+        let buffer = match &sound.source {
+            SoundSource::AudioBuffer(buffer) if is_loading_complete => buffer.borrow(),
+            SoundSource::AudioBuffer(_) => return Err("Sound is still being decoded".into()),
+            SoundSource::Decoder(_) => return Err("Sound is not decoded to an AudioBuffer".into()),
+        };
+
+        let read_channel = |channel| {
+            buffer
+                .get_channel_data(channel)
+                .map_err(|e| format!("Failed to read AudioBuffer: {:?}", e))
+        };
+        let left = read_channel(0)?;
+        let right = if buffer.number_of_channels() > 1 {
+            read_channel(1)?
+        } else {
+            left.clone()
+        };
+
+        // The buffer may have been decoded at a different sample rate than
+        // the sound format (see `decompress_to_audio_buffer`).
+        let buffer_sample_rate = buffer.sample_rate() as u16;
+        let rate_scale = f64::from(buffer_sample_rate) / f64::from(sound.format.sample_rate);
+        let skip = (f64::from(sound.skip_sample_frames) * rate_scale) as usize;
+        let len = (f64::from(sound.num_sample_frames) * rate_scale) as usize;
+        let to_i16 = |sample: f32| (sample.clamp(-1.0, 1.0) * 32767.0) as i16;
+        let frames = left
+            .iter()
+            .zip(right.iter())
+            .skip(skip)
+            .take(len)
+            .map(|(&l, &r)| [to_i16(l), to_i16(r)]);
+
+        let decoded: Arc<[[i16; 2]]> = if frames.len() < 2 {
+            frames.collect()
+        } else {
+            resample(frames, buffer_sample_rate, 44100).collect()
+        };
+        drop(buffer);
+        sound.decoded = Some(Arc::clone(&decoded));
+        Ok(decoded)
+    }
+
     fn set_sound_transform(&mut self, instance: SoundInstanceHandle, transform: SoundTransform) {
         SOUND_INSTANCES.with(|instances| {
             let mut instances = instances.borrow_mut();
             if let Some(instance) = instances.get_mut(instance) {
                 if let SoundInstanceType::AudioBuffer(sound) = &mut instance.instance_type {
-                    sound.set_transform(&self.context, &transform);
+                    sound.set_transform(&self.context, &self.output, &transform);
                 }
+                instance.transform = transform;
             }
         })
     }
 
+    fn get_recent_output(&self, num_sample_frames: usize) -> Vec<[f32; 2]> {
+        // The output is recorded at the sample rate of the audio context.
+        let len = OUTPUT_ANALYSER_SIZE as usize;
+        let mut left = vec![0.0; len];
+        let mut right = vec![0.0; len];
+        self.output_analysers[0].get_float_time_domain_data(&mut left);
+        self.output_analysers[1].get_float_time_domain_data(&mut right);
+        let start = len.saturating_sub(num_sample_frames);
+        left[start..]
+            .iter()
+            .zip(&right[start..])
+            .map(|(left, right)| [*left, *right])
+            .collect()
+    }
+
     fn tick(&mut self) {
         // Update the output timestamp.
         // We do this once per frame to avoid spamming it in `get_sound_position`.
         self.output_time = get_audio_output_timestamp(&self.context);
     }
+
+    fn start_capture(&mut self, sample_rate: u32) -> Result<(), Error> {
+        self.stop_capture();
+
+        let media_devices = web_sys::window()
+            .ok_or("Expected window")?
+            .navigator()
+            .media_devices()
+            .map_err(|_| "Audio capture is unavailable")?;
+        let mut constraints = web_sys::MediaStreamConstraints::new();
+        constraints.audio(&JsValue::TRUE);
+        let user_media = media_devices
+            .get_user_media_with_constraints(&constraints)
+            .map_err(|_| "Unable to request access to the microphone")?;
+
+        let capture = Rc::new(RefCell::new(Capture::default()));
+        self.capture = Some(Rc::clone(&capture));
+
+        // Asks the user for permission to use the microphone.
+        let context = self.context.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let stream = match JsFuture::from(user_media).await {
+                Ok(stream) => stream.unchecked_into::<web_sys::MediaStream>(),
+                Err(_) => {
+                    log::warn!("Access to the microphone was denied");
+                    return;
+                }
+            };
+
+            let mut capture = capture.borrow_mut();
+            if capture.stopped {
+                for track in stream.get_tracks().iter() {
+                    track.unchecked_into::<web_sys::MediaStreamTrack>().stop();
+                }
+            } else if let Err(e) =
+                Self::connect_capture(&context, stream, &mut capture, sample_rate)
+            {
+                log::error!("Unable to capture audio from the microphone: {:?}", e);
+            }
+        });
+
+        Ok(())
+    }
+
+    fn stop_capture(&mut self) {
+        if let Some(capture) = self.capture.take() {
+            let mut capture = capture.borrow_mut();
+            capture.stopped = true;
+            capture.nodes = None;
+        }
+    }
+
+    fn take_captured_samples(&mut self) -> Vec<i16> {
+        match &self.capture {
+            Some(capture) => std::mem::take(&mut *capture.borrow().samples.borrow_mut()),
+            None => Vec::new(),
+        }
+    }
 }
 
 #[wasm_bindgen(raw_module = "./ruffle-imports.js")]
@@ -0,0 +1,68 @@
+use ruffle_core::backend::capabilities::{
+    CapabilitiesProvider, Language, OperatingSystem, PlayerType, SystemCapabilities,
+};
+
+pub struct WebCapabilitiesProvider();
+
+impl WebCapabilitiesProvider {
+    pub fn new() -> Self {
+        Self()
+    }
+}
+
+impl CapabilitiesProvider for WebCapabilitiesProvider {
+    fn screen_resolution(&self) -> (u32, u32) {
+        let window = match web_sys::window() {
+            Some(window) => window,
+            None => return (0, 0),
+        };
+        // The screen size is in CSS pixels, but Flash reports device pixels.
+        let scale_factor = window.device_pixel_ratio();
+        window
+            .screen()
+            .ok()
+            .and_then(|screen| Some((screen.width().ok()?, screen.height().ok()?)))
+            .map(|(width, height)| {
+                (
+                    (f64::from(width) * scale_factor) as u32,
+                    (f64::from(height) * scale_factor) as u32,
+                )
+            })
+            .unwrap_or((0, 0))
+    }
+
+    fn screen_dpi(&self) -> f32 {
+        let scale_factor = web_sys::window()
+            .map(|window| window.device_pixel_ratio())
+            .unwrap_or(1.0);
+        (72.0 * scale_factor) as f32
+    }
+
+    fn language(&self) -> Language {
+        web_sys::window()
+            .and_then(|window| window.navigator().language())
+            .map(|language| Language::from_language_code(&language))
+            .unwrap_or(Language::English)
+    }
+
+    fn os(&self) -> OperatingSystem {
+        let platform = web_sys::window()
+            .and_then(|window| window.navigator().platform().ok())
+            .unwrap_or_default();
+        if platform.starts_with("Win") {
+            OperatingSystem::WindowsUnknown
+        } else if platform.starts_with("Mac") {
+            OperatingSystem::MacOs
+        } else {
+            OperatingSystem::Linux
+        }
+    }
+
+    fn player_type(&self) -> PlayerType {
+        PlayerType::PlugIn
+    }
+
+    fn capabilities(&self) -> SystemCapabilities {
+        SystemCapabilities::AUDIO | SystemCapabilities::MP3 | SystemCapabilities::STREAMING_AUDIO
+    }
+}
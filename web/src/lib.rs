@@ -6,6 +6,7 @@
 
 //! Ruffle web frontend.
 mod audio;
+mod capabilities;
 mod locale;
 mod log_adapter;
 mod navigator;
@@ -38,8 +39,8 @@ use std::time::Duration;
 use std::{cell::RefCell, error::Error, num::NonZeroI32};
 use wasm_bindgen::{prelude::*, JsCast, JsValue};
 use web_sys::{
-    AddEventListenerOptions, Element, Event, EventTarget, HtmlCanvasElement, HtmlElement,
-    KeyboardEvent, PointerEvent, WheelEvent, Window,
+    AddEventListenerOptions, CompositionEvent, Element, Event, EventTarget, HtmlCanvasElement,
+    HtmlElement, KeyboardEvent, PointerEvent, WheelEvent, Window,
 };
 
 static RUFFLE_GLOBAL_PANIC: Once = Once::new();
@@ -75,6 +76,7 @@ struct RuffleInstance {
     mouse_wheel_callback: Option<Closure<dyn FnMut(WheelEvent)>>,
     key_down_callback: Option<Closure<dyn FnMut(KeyboardEvent)>>,
     key_up_callback: Option<Closure<dyn FnMut(KeyboardEvent)>>,
+    composition_end_callback: Option<Closure<dyn FnMut(CompositionEvent)>>,
     unload_callback: Option<Closure<dyn FnMut(Event)>>,
     has_focus: bool,
     trace_observer: Arc<RefCell<JsValue>>,
@@ -113,6 +115,9 @@ extern "C" {
     #[wasm_bindgen(method, js_name = "displayMessage")]
     fn display_message(this: &JavascriptPlayer, message: &str);
 
+    #[wasm_bindgen(method, js_name = "printPages")]
+    fn print_pages(this: &JavascriptPlayer, pages: Array);
+
     #[wasm_bindgen(method, getter, js_name = "isFullscreen")]
     fn is_fullscreen(this: &JavascriptPlayer) -> bool;
 
@@ -299,6 +304,10 @@ impl Ruffle {
         let _ = self.with_core_mut(|core| core.set_fullscreen(is_fullscreen));
     }
 
+    pub fn fullscreen_changed(&mut self, is_fullscreen: bool) {
+        let _ = self.with_core_mut(|core| core.fullscreen_changed(is_fullscreen));
+    }
+
     pub fn clear_custom_menu_items(&mut self) {
         let _ = self.with_core_mut(Player::clear_custom_menu_items);
     }
@@ -396,6 +405,16 @@ impl Ruffle {
                     .warn_on_error();
                 instance.key_up_callback = None;
             }
+            if let Some(composition_end_callback) = &instance.composition_end_callback {
+                instance
+                    .window
+                    .remove_event_listener_with_callback(
+                        "compositionend",
+                        composition_end_callback.as_ref().unchecked_ref(),
+                    )
+                    .warn_on_error();
+                instance.composition_end_callback = None;
+            }
             if let Some(unload_callback) = &instance.unload_callback {
                 instance
                     .window
@@ -508,6 +527,7 @@ impl Ruffle {
             }
             core.set_letterbox(config.letterbox);
             core.set_warn_on_unsupported_content(config.warn_on_unsupported_content);
+            core.set_capabilities_provider(Box::new(capabilities::WebCapabilitiesProvider::new()));
             core.set_max_execution_duration(config.max_execution_duration);
             core.set_show_menu(config.show_menu);
             core.set_stage_align(config.salign.as_deref().unwrap_or(""));
@@ -539,6 +559,7 @@ impl Ruffle {
             mouse_wheel_callback: None,
             key_down_callback: None,
             key_up_callback: None,
+            composition_end_callback: None,
             unload_callback: None,
             timestamp: None,
             has_focus: false,
@@ -724,7 +745,8 @@ impl Ruffle {
             // Create keydown event handler.
             let key_down_callback = Closure::wrap(Box::new(move |js_event: KeyboardEvent| {
                 let _ = ruffle.with_instance(|instance| {
-                    if instance.has_focus {
+                    // Keys pressed while composing text belong to the input method editor.
+                    if instance.has_focus && !js_event.is_composing() {
                         let _ = instance.with_core_mut(|core| {
                             let ui = if let Some(ui) =
                                 core.ui_mut().downcast_mut::<ui::WebUiBackend>()
@@ -789,6 +811,28 @@ impl Ruffle {
                 .warn_on_error();
             instance.key_up_callback = Some(key_up_callback);
 
+            // Create compositionend event handler, for text composed with an input method editor.
+            let composition_end_callback =
+                Closure::wrap(Box::new(move |js_event: CompositionEvent| {
+                    let _ = ruffle.with_instance(|instance| {
+                        if instance.has_focus {
+                            if let Some(text) = js_event.data().filter(|text| !text.is_empty()) {
+                                let _ = instance.with_core_mut(|core| {
+                                    core.handle_event(PlayerEvent::ImeCommit { text });
+                                });
+                            }
+                        }
+                    });
+                }) as Box<dyn FnMut(CompositionEvent)>);
+
+            window
+                .add_event_listener_with_callback(
+                    "compositionend",
+                    composition_end_callback.as_ref().unchecked_ref(),
+                )
+                .warn_on_error();
+            instance.composition_end_callback = Some(composition_end_callback);
+
             let unload_callback = Closure::wrap(Box::new(move |_| {
                 let _ = ruffle.with_core_mut(|core| {
                     core.flush_shared_objects();
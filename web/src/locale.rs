@@ -1,5 +1,5 @@
-use chrono::{DateTime, FixedOffset, Local, Offset, Utc};
-use ruffle_core::backend::locale::LocaleBackend;
+use chrono::{DateTime, Local, Utc};
+use ruffle_core::backend::locale::{LocalTimezone, LocaleBackend};
 
 pub struct WebLocaleBackend();
 
@@ -14,7 +14,7 @@ impl LocaleBackend for WebLocaleBackend {
         Utc::now()
     }
 
-    fn get_timezone(&self) -> FixedOffset {
-        Local::now().offset().fix()
+    fn get_timezone(&self) -> LocalTimezone {
+        LocalTimezone::new(Local)
     }
 }
@@ -1,17 +1,22 @@
 //! Navigator backend for web
-use js_sys::{Array, ArrayBuffer, Uint8Array};
+use generational_arena::Arena;
+use js_sys::{Array, ArrayBuffer, Function, Promise, Reflect, Uint8Array};
 use ruffle_core::backend::navigator::{
-    url_from_relative_url, NavigationMethod, NavigatorBackend, OwnedFuture, RequestOptions,
+    url_from_relative_url, FetchProgress, NavigationMethod, NavigatorBackend, OwnedFuture,
+    RequestOptions, SocketEvent, SocketHandle,
 };
 use ruffle_core::indexmap::IndexMap;
 use ruffle_core::loader::Error;
 use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
 use std::time::Duration;
 use url::Url;
-use wasm_bindgen::JsCast;
+use wasm_bindgen::{closure::Closure, JsCast};
 use wasm_bindgen_futures::{spawn_local, JsFuture};
 use web_sys::{
-    window, Blob, BlobPropertyBag, Document, Performance, Request, RequestInit, Response,
+    window, BinaryType, Blob, BlobPropertyBag, Document, MessageEvent, Performance, Request,
+    RequestInit, Response, WebSocket,
 };
 
 pub struct WebNavigatorBackend {
@@ -20,6 +25,24 @@ pub struct WebNavigatorBackend {
     allow_script_access: bool,
     upgrade_to_https: bool,
     base_url: Option<String>,
+
+    /// Open `XMLSocket` connections.
+    sockets: Arena<SocketConnection>,
+
+    /// Events received by the sockets, waiting to be taken by the player.
+    socket_events: Rc<RefCell<Vec<SocketEvent>>>,
+}
+
+/// An `XMLSocket` connection.
+///
+/// Browsers can't open raw TCP connections, so this connects with a WebSocket
+/// to the same host and port instead. The server (or a proxy in front of it)
+/// has to accept WebSockets, and pass the binary frames through unchanged.
+struct SocketConnection {
+    socket: WebSocket,
+    _on_open: Closure<dyn FnMut()>,
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+    _on_close: Closure<dyn FnMut()>,
 }
 
 impl WebNavigatorBackend {
@@ -66,6 +89,8 @@ impl WebNavigatorBackend {
             allow_script_access,
             upgrade_to_https,
             base_url,
+            sockets: Arena::new(),
+            socket_events: Rc::new(RefCell::new(Vec::new())),
         }
     }
 
@@ -171,6 +196,15 @@ impl NavigatorBackend for WebNavigatorBackend {
     }
 
     fn fetch(&self, url: &str, options: RequestOptions) -> OwnedFuture<Vec<u8>, Error> {
+        self.fetch_with_progress(url, options, FetchProgress::new())
+    }
+
+    fn fetch_with_progress(
+        &self,
+        url: &str,
+        options: RequestOptions,
+        progress: FetchProgress,
+    ) -> OwnedFuture<Vec<u8>, Error> {
         let url = if let Ok(parsed_url) = Url::parse(url) {
             self.pre_process_url(parsed_url).to_string()
         } else {
@@ -211,6 +245,11 @@ impl NavigatorBackend for WebNavigatorBackend {
             let request = Request::new_with_str_and_init(&url, &init)
                 .map_err(|_| Error::FetchError(format!("Unable to create request for {}", url)))?;
 
+            for (name, value) in options.headers() {
+                // Browsers refuse to send some headers; skip them rather than failing the request.
+                let _ = request.headers().set(name, value);
+            }
+
             let window = web_sys::window().unwrap();
             let fetchval = JsFuture::from(window.fetch_with_request(&request)).await;
             if fetchval.is_err() {
@@ -223,23 +262,52 @@ impl NavigatorBackend for WebNavigatorBackend {
             let resp: Response = fetchval.unwrap().dyn_into().unwrap();
 
             if !resp.ok() {
-                return Err(Error::FetchError(format!(
-                    "HTTP status is not ok, got {}",
-                    resp.status_text()
-                )));
+                return Err(Error::HttpNotOk(resp.status()));
             }
 
-            let data: ArrayBuffer = JsFuture::from(resp.array_buffer().unwrap())
-                .await
-                .map_err(|_| {
-                    Error::FetchError("Could not allocate array buffer for response".to_string())
-                })?
-                .dyn_into()
-                .unwrap();
+            if let Some(len) = resp
+                .headers()
+                .get("Content-Length")
+                .ok()
+                .flatten()
+                .and_then(|len| len.parse().ok())
+            {
+                progress.set_total(len);
+            }
+
+            let body = match resp.body() {
+                Some(body) => body,
+                None => return Ok(vec![]),
+            };
+
+            // Read the body a chunk at a time, so that the progress of the
+            // download can be reported.
+            let read_error = |_| Error::FetchError("Could not read response body".to_string());
+            let reader = body.get_reader();
+            let read: Function = Reflect::get(&reader, &"read".into())
+                .map_err(read_error)?
+                .unchecked_into();
+
+            let mut rust_array = vec![];
+            loop {
+                let read_promise: Promise =
+                    read.call0(&reader).map_err(read_error)?.unchecked_into();
+                let result = JsFuture::from(read_promise).await.map_err(read_error)?;
+                if Reflect::get(&result, &"done".into())
+                    .map_err(read_error)?
+                    .is_truthy()
+                {
+                    break;
+                }
 
-            let jsarray = Uint8Array::new(&data);
-            let mut rust_array = vec![0; jsarray.length() as usize];
-            jsarray.copy_to(&mut rust_array);
+                let chunk: Uint8Array = Reflect::get(&result, &"value".into())
+                    .map_err(read_error)?
+                    .unchecked_into();
+                let start = rust_array.len();
+                rust_array.resize(start + chunk.length() as usize, 0);
+                chunk.copy_to(&mut rust_array[start..]);
+                progress.add_loaded(chunk.length() as usize);
+            }
 
             Ok(rust_array)
         })
@@ -272,4 +340,96 @@ impl NavigatorBackend for WebNavigatorBackend {
         }
         url
     }
+
+    fn connect_socket(&mut self, host: &str, port: u16) -> Option<SocketHandle> {
+        let secure = window()
+            .and_then(|window| window.location().protocol().ok())
+            .map_or(false, |protocol| protocol == "https:");
+        let url = format!("{}://{}:{}", if secure { "wss" } else { "ws" }, host, port);
+        let socket = match WebSocket::new(&url) {
+            Ok(socket) => socket,
+            Err(e) => {
+                log::warn!("Couldn't open WebSocket to {}: {:?}", url, e);
+                return None;
+            }
+        };
+        socket.set_binary_type(BinaryType::Arraybuffer);
+
+        let events = self.socket_events.clone();
+        Some(self.sockets.insert_with(|handle| {
+            let opened = Rc::new(Cell::new(false));
+
+            let on_open = {
+                let events = events.clone();
+                let opened = opened.clone();
+                Closure::wrap(Box::new(move || {
+                    opened.set(true);
+                    events
+                        .borrow_mut()
+                        .push(SocketEvent::Connected(handle, true));
+                }) as Box<dyn FnMut()>)
+            };
+
+            let on_message = {
+                let events = events.clone();
+                Closure::wrap(Box::new(move |event: MessageEvent| {
+                    let data = event.data();
+                    let data = if let Some(text) = data.as_string() {
+                        text.into_bytes()
+                    } else {
+                        Uint8Array::new(&data).to_vec()
+                    };
+                    events.borrow_mut().push(SocketEvent::Data(handle, data));
+                }) as Box<dyn FnMut(MessageEvent)>)
+            };
+
+            // Errors always close the socket, so they don't need their own handler.
+            let on_close = Closure::wrap(Box::new(move || {
+                let event = if opened.get() {
+                    SocketEvent::Closed(handle)
+                } else {
+                    SocketEvent::Connected(handle, false)
+                };
+                events.borrow_mut().push(event);
+            }) as Box<dyn FnMut()>);
+
+            socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+            socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+            socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+
+            SocketConnection {
+                socket,
+                _on_open: on_open,
+                _on_message: on_message,
+                _on_close: on_close,
+            }
+        }))
+    }
+
+    fn send_socket(&mut self, handle: SocketHandle, data: Vec<u8>) {
+        if let Some(connection) = self.sockets.get(handle) {
+            if let Err(e) = connection.socket.send_with_u8_array(&data) {
+                log::warn!("Couldn't send data over WebSocket: {:?}", e);
+            }
+        }
+    }
+
+    fn close_socket(&mut self, handle: SocketHandle) {
+        if let Some(connection) = self.sockets.remove(handle) {
+            connection.socket.set_onopen(None);
+            connection.socket.set_onmessage(None);
+            connection.socket.set_onclose(None);
+            let _ = connection.socket.close();
+        }
+    }
+
+    fn take_socket_events(&mut self) -> Vec<SocketEvent> {
+        let events = std::mem::take(&mut *self.socket_events.borrow_mut());
+        for event in &events {
+            if let SocketEvent::Connected(handle, false) | SocketEvent::Closed(handle) = event {
+                self.sockets.remove(*handle);
+            }
+        }
+        events
+    }
 }
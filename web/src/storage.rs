@@ -1,6 +1,9 @@
 use ruffle_core::backend::storage::StorageBackend;
 use web_sys::Storage;
 
+/// The number of characters that browsers let a site keep in local storage.
+const LOCAL_STORAGE_QUOTA: usize = 5 * 1024 * 1024;
+
 pub struct LocalStorageBackend {
     storage: Storage,
 }
@@ -29,4 +32,19 @@ impl StorageBackend for LocalStorageBackend {
     fn remove_key(&mut self, name: &str) {
         let _ = self.storage.delete(name);
     }
+
+    fn size_limit(&self, name: &str) -> Option<usize> {
+        // Local storage is shared with everything else on the page.
+        let mut used = name.len();
+        for i in 0..self.storage.length().ok()? {
+            if let Ok(Some(key)) = self.storage.key(i) {
+                if key != name {
+                    let value = self.storage.get(&key).ok().flatten().unwrap_or_default();
+                    used += key.len() + value.len();
+                }
+            }
+        }
+        // Values are stored as base64, which takes 4 characters for every 3 bytes.
+        Some(LOCAL_STORAGE_QUOTA.saturating_sub(used) / 4 * 3)
+    }
 }
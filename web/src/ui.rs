@@ -1,9 +1,12 @@
 use super::JavascriptPlayer;
+use js_sys::Array;
+use ruffle_core::backend::render::{Bitmap, BitmapFormat};
 use ruffle_core::backend::ui::{Error, MouseCursor, UiBackend};
 use ruffle_core::events::KeyCode;
 use ruffle_web_common::JsResult;
 use std::collections::HashSet;
-use web_sys::{HtmlCanvasElement, KeyboardEvent};
+use wasm_bindgen::{Clamped, JsCast};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, ImageData, KeyboardEvent};
 
 #[derive(Debug)]
 struct FullScreenError {
@@ -27,10 +30,15 @@ pub struct WebUiBackend {
     js_player: JavascriptPlayer,
     canvas: HtmlCanvasElement,
     keys_down: HashSet<KeyCode>,
+    keys_toggled: HashSet<KeyCode>,
     cursor_visible: bool,
     cursor: MouseCursor,
     last_key: KeyCode,
     last_char: Option<char>,
+
+    /// The text copied from text fields. The system clipboard can't be read
+    /// synchronously, so copying and pasting only works within the player.
+    clipboard: String,
 }
 
 impl WebUiBackend {
@@ -39,10 +47,12 @@ impl WebUiBackend {
             js_player,
             canvas: canvas.clone(),
             keys_down: HashSet::new(),
+            keys_toggled: HashSet::new(),
             cursor_visible: true,
             cursor: MouseCursor::Arrow,
             last_key: KeyCode::Unknown,
             last_char: None,
+            clipboard: String::new(),
         }
     }
 
@@ -52,6 +62,7 @@ impl WebUiBackend {
         self.last_key = key_code;
         self.keys_down.insert(key_code);
         self.last_char = web_key_to_codepoint(&event.key());
+        self.update_keys_toggled(event);
     }
 
     /// Register a key release for a given code string.
@@ -60,6 +71,22 @@ impl WebUiBackend {
         self.last_key = key_code;
         self.keys_down.remove(&key_code);
         self.last_char = web_key_to_codepoint(&event.key());
+        self.update_keys_toggled(event);
+    }
+
+    /// Read the state of the lock keys from a keyboard event.
+    fn update_keys_toggled(&mut self, event: &KeyboardEvent) {
+        for (key_code, modifier) in [
+            (KeyCode::CapsLock, "CapsLock"),
+            (KeyCode::NumLock, "NumLock"),
+            (KeyCode::ScrollLock, "ScrollLock"),
+        ] {
+            if event.get_modifier_state(modifier) {
+                self.keys_toggled.insert(key_code);
+            } else {
+                self.keys_toggled.remove(&key_code);
+            }
+        }
     }
 
     fn update_mouse_cursor(&self) {
@@ -85,6 +112,10 @@ impl UiBackend for WebUiBackend {
         self.keys_down.contains(&key)
     }
 
+    fn is_key_toggled(&self, key: KeyCode) -> bool {
+        self.keys_toggled.contains(&key)
+    }
+
     fn last_key_code(&self) -> KeyCode {
         self.last_key
     }
@@ -107,8 +138,16 @@ impl UiBackend for WebUiBackend {
         self.update_mouse_cursor();
     }
 
-    fn set_clipboard_content(&mut self, _content: String) {
-        log::warn!("set clipboard not implemented");
+    fn clipboard_content(&mut self) -> String {
+        self.clipboard.clone()
+    }
+
+    fn set_clipboard_content(&mut self, content: String) {
+        self.clipboard = content;
+    }
+
+    fn set_ime_position(&mut self, _x: f64, _y: f64) {
+        // The browser places the composition itself.
     }
 
     fn set_fullscreen(&mut self, is_full: bool) -> Result<(), Error> {
@@ -130,6 +169,24 @@ impl UiBackend for WebUiBackend {
         self.js_player.display_root_movie_download_failed_message()
     }
 
+    fn request_microphone_access(&mut self) -> bool {
+        // The browser asks the user itself once the capture starts.
+        true
+    }
+
+    fn print(&mut self, pages: Vec<Bitmap>) {
+        let urls = Array::new();
+        for page in pages {
+            match bitmap_to_data_url(page) {
+                Some(url) => {
+                    urls.push(&url.into());
+                }
+                None => log::warn!("Couldn't convert a printed page to an image"),
+            }
+        }
+        self.js_player.print_pages(urls);
+    }
+
     fn message(&self, message: &str) {
         self.js_player.display_message(message);
     }
@@ -221,6 +278,7 @@ fn web_to_ruffle_key_code(key_code: &str) -> KeyCode {
         "Insert" => KeyCode::Insert,
         "Delete" => KeyCode::Delete,
         "Pause" => KeyCode::Pause,
+        "NumLock" => KeyCode::NumLock,
         "ScrollLock" => KeyCode::ScrollLock,
         "F1" => KeyCode::F1,
         "F2" => KeyCode::F2,
@@ -259,3 +317,25 @@ fn web_key_to_codepoint(key: &str) -> Option<char> {
         }
     }
 }
+
+/// Encode a bitmap as a PNG data URL, by drawing it to a temporary canvas.
+fn bitmap_to_data_url(bitmap: Bitmap) -> Option<String> {
+    let (width, height) = (bitmap.width, bitmap.height);
+    let mut rgba = match bitmap.data {
+        BitmapFormat::Rgba(rgba) => rgba,
+        BitmapFormat::Rgb(rgb) => rgb
+            .chunks_exact(3)
+            .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+            .collect(),
+    };
+
+    let document = web_sys::window()?.document()?;
+    let canvas: HtmlCanvasElement = document.create_element("canvas").ok()?.dyn_into().ok()?;
+    canvas.set_width(width);
+    canvas.set_height(height);
+    let context: CanvasRenderingContext2d = canvas.get_context("2d").ok()??.dyn_into().ok()?;
+    let image_data =
+        ImageData::new_with_u8_clamped_array_and_sh(Clamped(&mut rgba), width, height).ok()?;
+    context.put_image_data(&image_data, 0.0, 0.0).ok()?;
+    canvas.to_data_url().ok()
+}